@@ -10,7 +10,6 @@ anyos_std::entry!(main);
 // ── Constants ────────────────────────────────────────────────────────────────
 
 const DEFAULT_RETENTION_DAYS: u32 = 3;
-const POLL_INTERVAL_MS: u32 = 500;
 const MAX_PREVIEW_LEN: usize = 120;
 const HISTORY_FILE: &str = ".clipboard_history.json";
 const NUM_COLS: usize = 3;
@@ -268,9 +267,33 @@ fn update_status(s: &AppState) {
     s.retention_label.set_text(&retention);
 }
 
-// ── Clipboard polling ────────────────────────────────────────────────────────
+// ── Clipboard monitoring ─────────────────────────────────────────────────────
 
-fn poll_clipboard() {
+/// Add clips the compositor recorded while we were not running (e.g. before
+/// login autostart finished). Only text clips not already listed are taken.
+fn import_compositor_history(entries: &mut Vec<ClipEntry>) {
+    let mut clips = Vec::new();
+    let mut index = 0;
+    while let Some(clip) = anyui::clipboard_history(index, 4096) {
+        clips.push(clip);
+        index += 1;
+    }
+    // Oldest first, so the newest clip ends up at the front.
+    for clip in clips.iter().rev() {
+        if clip.format != 0 { continue; }
+        let text = match core::str::from_utf8(&clip.data) {
+            Ok(t) if !t.trim().is_empty() => t,
+            _ => continue,
+        };
+        if entries.iter().any(|e| e.text == text) { continue; }
+        entries.insert(0, ClipEntry {
+            text: String::from(text),
+            time: now_string(),
+        });
+    }
+}
+
+fn on_clipboard_changed() {
     let s = app();
     if s.paused { return; }
 
@@ -578,6 +601,7 @@ fn main() {
     // Load history
     let history_path = get_history_path();
     let (mut entries, retention_days) = load_history(&history_path);
+    import_compositor_history(&mut entries);
 
     // Get current clipboard content as baseline
     let mut clip_buf = [0u8; 4096];
@@ -803,9 +827,9 @@ fn main() {
     // Keyboard shortcuts
     win.on_key_down(|ke| { handle_key(ke); });
 
-    // ── Start clipboard polling timer ──
-    anyui::set_timer(POLL_INTERVAL_MS, || {
-        poll_clipboard();
+    // ── Follow clipboard changes (broadcast by the compositor) ──
+    anyui::on_clipboard_changed(|_| {
+        on_clipboard_changed();
     });
 
     // ── Run event loop ──
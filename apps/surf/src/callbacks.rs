@@ -1,13 +1,19 @@
 // Copyright (c) 2024-2026 Christian Moeller
 // SPDX-License-Identifier: MIT
 
-//! libanyui C-ABI callbacks and form-encoding utilities for the Surf browser.
+//! libanyui C-ABI callbacks and the form file picker for the Surf browser.
 //!
-//! These callbacks are registered with `WebView::set_link_callback` and
-//! `WebView::set_submit_callback` and are invoked by the UI toolkit when the
-//! user interacts with rendered page controls.
+//! These callbacks are registered with `WebView::set_link_callback`,
+//! `WebView::set_selection_callback`, `WebView::set_submit_callback` and
+//! `WebView::set_form_change_callback`, and are invoked by the UI toolkit
+//! when the user interacts with rendered page controls.  `on_navigation` is registered with
+//! `WebView::set_navigation_callback` and keeps the browser chrome in sync
+//! with the WebView's navigation controller; `on_metadata`
+//! (`WebView::set_metadata_callback`) does the same for titles, favicons and
+//! theme colours.
 
 use alloc::string::String;
+use libanyui_client::Widget;
 
 // ═══════════════════════════════════════════════════════════
 // Link click callback
@@ -16,14 +22,29 @@ use alloc::string::String;
 /// Called by libanyui when the user clicks on the page canvas or a rendered control.
 ///
 /// Resolves the link URL relative to the page's base URL and navigates to it.
-/// Also handles canvas-based submit button hits (since the canvas only has one callback).
+/// Also handles canvas-based submit button hits and media controls (since the
+/// canvas only has one callback).
 pub(crate) extern "C" fn on_link_click(ctrl_id: u32, _event_type: u32, _userdata: u64) {
     let st = crate::state();
+
+    // Media controls bar (play / pause, seek); playback is polled by the tick timer.
+    if st.tabs[st.active_tab].webview.media_click(ctrl_id) {
+        crate::ensure_anim_timer();
+        return;
+    }
+
     let tab = &st.tabs[st.active_tab];
 
     // Try link hit first.
     if let Some(link_url) = tab.webview.link_url_for(ctrl_id) {
-        let resolved = if link_url.starts_with("file://") {
+        // Same-document fragment link: scroll to the target, no navigation.
+        if link_url.starts_with('#') {
+            let fragment = String::from(&link_url[1..]);
+            st.tabs[st.active_tab].webview.scroll_to_fragment(&fragment, true);
+            crate::ensure_anim_timer();
+            return;
+        }
+        let resolved = if link_url.starts_with("file://") || link_url.starts_with("about:") {
             String::from(link_url)
         } else if let Some(ref base) = tab.current_url {
             let resolved_url = crate::http::resolve_url(base, link_url);
@@ -41,82 +62,187 @@ pub(crate) extern "C" fn on_link_click(ctrl_id: u32, _event_type: u32, _userdata
     }
 }
 
+// ═══════════════════════════════════════════════════════════
+// Scroll callback
+// ═══════════════════════════════════════════════════════════
+
+/// Called by libanyui when a tab's ScrollView offset changes (mouse wheel or
+/// scrollbar).  The WebView replays wheel jumps as a smooth scroll, driven
+/// by the animation timer.
+pub(crate) fn on_page_scroll(scroll_view_id: u32, offset: u32) {
+    let st = crate::state();
+    if let Some(tab) = st.tabs.iter_mut().find(|t| t.webview.scroll_view().id() == scroll_view_id) {
+        tab.webview.scroll_changed(offset);
+    }
+    crate::ensure_anim_timer();
+}
+
+// ═══════════════════════════════════════════════════════════
+// Text selection callback
+// ═══════════════════════════════════════════════════════════
+
+/// Called by libanyui for mouse down/move/up and double-click on the page
+/// canvas.  Forwards the event to the active tab's text selection.
+pub(crate) extern "C" fn on_page_mouse(ctrl_id: u32, event_type: u32, _userdata: u64) {
+    let st = crate::state();
+    let tab = &mut st.tabs[st.active_tab];
+    tab.webview.handle_selection_event(ctrl_id, event_type);
+}
+
 // ═══════════════════════════════════════════════════════════
 // Form submit callback
 // ═══════════════════════════════════════════════════════════
 
 /// Called by libanyui when the user clicks a form submit button.
 ///
-/// Collects form fields, URL-encodes them, resolves the action URL, and
-/// navigates with either GET (query string) or POST (request body).
+/// The WebView validates and encodes the form; this navigates to the
+/// submission URL with either GET or POST (encoded body).  A form that
+/// fails validation shows the first error in the status bar.
 pub(crate) extern "C" fn on_form_submit(ctrl_id: u32, _event_type: u32, _userdata: u64) {
     let st = crate::state();
-    let tab = &st.tabs[st.active_tab];
+    let tab = &mut st.tabs[st.active_tab];
 
     if !tab.webview.is_submit_button(ctrl_id) {
         return;
     }
 
-    let (action, method) = match tab.webview.form_action_for(ctrl_id) {
-        Some(am) => am,
-        None => return,
+    let submission = match tab.webview.submit_form_for(ctrl_id) {
+        Ok(s) => s,
+        Err(libwebview::FormError::Invalid(fields)) => {
+            if let Some(first) = fields.first() {
+                tab.status_text = String::from(first.reason.message());
+                crate::ui::update_status();
+            }
+            return;
+        }
+        Err(libwebview::FormError::NoForm) => return,
     };
 
-    // Collect and URL-encode form data.
-    let data = tab.webview.collect_form_data(ctrl_id);
-    let mut encoded = String::new();
-    for (i, (name, value)) in data.iter().enumerate() {
-        if i > 0 {
-            encoded.push('&');
-        }
-        url_encode_into(&mut encoded, name);
-        encoded.push('=');
-        url_encode_into(&mut encoded, value);
+    if submission.method == "POST" {
+        crate::tab::navigate_post(&submission.url, &submission.body, &submission.content_type);
+    } else {
+        crate::tab::navigate(&submission.url);
     }
+}
 
-    // Resolve the action URL relative to the current page.
-    let resolved_action = if let Some(ref base) = tab.current_url {
-        let action_url = crate::http::resolve_url(base, &action);
-        crate::ui::format_url(&action_url)
-    } else {
-        action
-    };
+// ═══════════════════════════════════════════════════════════
+// Form control change callback
+// ═══════════════════════════════════════════════════════════
 
-    if method == "POST" {
-        crate::tab::navigate_post(&resolved_action, &encoded);
-    } else {
-        // GET: append form data as a query string.
-        let mut url = resolved_action;
-        if !encoded.is_empty() {
-            url.push(if url.contains('?') { '&' } else { '?' });
-            url.push_str(&encoded);
+/// Called by libanyui when a checkbox, radio button, select or textarea on
+/// the page changes.  Lets the active tab's WebView apply radio-group
+/// exclusivity and textarea growth.
+pub(crate) extern "C" fn on_form_change(ctrl_id: u32, _event_type: u32, _userdata: u64) {
+    let st = crate::state();
+    let tab = &mut st.tabs[st.active_tab];
+    tab.webview.handle_form_change(ctrl_id);
+}
+
+// ═══════════════════════════════════════════════════════════
+// Navigation callback
+// ═══════════════════════════════════════════════════════════
+
+/// Called by libwebview when a navigation starts, commits, finishes or fails.
+///
+/// Updates the status bar, URL field, window title and tab labels.  Failures
+/// are reported by the caller, which knows the error message.
+pub(crate) extern "C" fn on_navigation(_nav_id: u32, event: u32, _userdata: u64) {
+    use libwebview::navigation::{NAV_STARTED, NAV_COMMITTED, NAV_FINISHED};
+
+    let st = crate::state();
+    let tab = &mut st.tabs[st.active_tab];
+    match event {
+        NAV_STARTED => {
+            let mut msg = String::from("Loading: ");
+            if let Some(p) = tab.webview.navigation().pending() {
+                msg.push_str(&p.url);
+            }
+            tab.status_text = msg;
+            crate::ui::update_status();
         }
-        crate::tab::navigate(&url);
+        NAV_COMMITTED => {
+            if let Some(entry) = tab.webview.navigation().current() {
+                tab.url_text = entry.url.clone();
+                tab.page_title = entry.title.clone();
+            }
+            let url_for_field = tab.url_text.clone();
+            st.url_field.set_text(&url_for_field);
+            crate::ui::update_title();
+            crate::ui::update_tab_labels();
+        }
+        NAV_FINISHED => {
+            tab.status_text = String::from("Done");
+            crate::ui::update_status();
+        }
+        _ => {}
     }
 }
 
 // ═══════════════════════════════════════════════════════════
-// URL encoding
+// Metadata callback
 // ═══════════════════════════════════════════════════════════
 
-/// Percent-encode `s` and append the result to `out`.
+/// Called by libwebview when a page's title, favicon or theme colour changes.
 ///
-/// Follows RFC 3986 unreserved characters (A-Z, a-z, 0-9, `-`, `_`, `.`, `~`).
-/// Spaces are encoded as `+` for `application/x-www-form-urlencoded`.
-pub(crate) fn url_encode_into(out: &mut String, s: &str) {
-    for b in s.bytes() {
-        match b {
-            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
-                out.push(b as char);
-            }
-            b' ' => out.push('+'),
-            _ => {
-                out.push('%');
-                let hi = b >> 4;
-                let lo = b & 0xF;
-                out.push(if hi < 10 { (b'0' + hi) as char } else { (b'A' + hi - 10) as char });
-                out.push(if lo < 10 { (b'0' + lo) as char } else { (b'A' + lo - 10) as char });
+/// The callback does not say which tab it came from, so every tab's title is
+/// re-read; the favicon and theme colour shown are the active tab's.
+pub(crate) extern "C" fn on_metadata(kind: u32, _arg: u32, _userdata: u64) {
+    use libwebview::metadata::{META_TITLE, META_ICON, META_THEME_COLOR};
+
+    let st = crate::state();
+    match kind {
+        META_TITLE => {
+            for tab in st.tabs.iter_mut() {
+                tab.page_title = tab.webview.get_title().unwrap_or_default();
             }
+            crate::ui::update_title();
+            crate::ui::update_tab_labels();
         }
+        META_ICON | META_THEME_COLOR => crate::ui::update_page_meta(),
+        _ => {}
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+// File picker
+// ═══════════════════════════════════════════════════════════
+
+/// `<input type="file">` chooser: the system Open File dialog.  Reads the
+/// chosen file into memory; one file per pick.
+pub(crate) struct DialogFilePicker;
+
+impl libwebview::FilePicker for DialogFilePicker {
+    fn pick(&mut self, _accept: &str, _multiple: bool) -> Option<alloc::vec::Vec<libwebview::PickedFile>> {
+        let path = libanyui_client::FileDialog::open_file()?;
+        let data = match anyos_std::fs::read_to_vec(&path) {
+            Ok(d) => d,
+            Err(_) => {
+                let st = crate::state();
+                let mut msg = String::from("Cannot read file: ");
+                msg.push_str(&path);
+                st.tabs[st.active_tab].status_text = msg;
+                crate::ui::update_status();
+                return None;
+            }
+        };
+        let name = String::from(path.rsplit('/').next().unwrap_or(&path));
+        let content_type = String::from(mime_for(&name));
+        Some(alloc::vec![libwebview::PickedFile { name, content_type, data }])
     }
 }
+
+/// MIME type for an upload, by file extension (empty when unknown).
+fn mime_for(name: &str) -> &'static str {
+    let ext = match name.rfind('.') {
+        Some(i) => &name[i + 1..],
+        None => return "",
+    };
+    let table: &[(&str, &str)] = &[
+        ("txt", "text/plain"), ("html", "text/html"), ("htm", "text/html"),
+        ("css", "text/css"), ("js", "text/javascript"), ("json", "application/json"),
+        ("png", "image/png"), ("jpg", "image/jpeg"), ("jpeg", "image/jpeg"),
+        ("gif", "image/gif"), ("bmp", "image/bmp"), ("svg", "image/svg+xml"),
+        ("pdf", "application/pdf"), ("zip", "application/zip"), ("gz", "application/gzip"),
+    ];
+    table.iter().find(|(e, _)| e.eq_ignore_ascii_case(ext)).map_or("", |(_, m)| m)
+}
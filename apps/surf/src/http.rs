@@ -45,141 +45,8 @@ pub enum FetchError {
 // Cookie jar
 // ---------------------------------------------------------------------------
 
-#[derive(Clone)]
-pub struct Cookie {
-    pub domain: String,
-    pub path: String,
-    pub name: String,
-    pub value: String,
-    pub secure: bool,
-    pub http_only: bool,
-}
-
-#[derive(Clone)]
-pub struct CookieJar {
-    pub cookies: Vec<Cookie>,
-}
-
-impl CookieJar {
-    pub fn new() -> Self {
-        CookieJar { cookies: Vec::new() }
-    }
-
-    /// Parse and store cookies from Set-Cookie headers.
-    pub fn store_from_headers(&mut self, headers: &str, request_host: &str, request_path: &str) {
-        for line in headers.split('\n') {
-            let line = line.trim_end_matches('\r');
-            if !starts_with_ignore_case(line, "set-cookie") { continue; }
-            let rest = &line["set-cookie".len()..];
-            if !rest.starts_with(':') { continue; }
-            let val = rest[1..].trim_start();
-            self.parse_set_cookie(val, request_host, request_path);
-        }
-    }
-
-    fn parse_set_cookie(&mut self, header: &str, request_host: &str, request_path: &str) {
-        // Format: name=value; Path=/; Domain=.example.com; Secure; HttpOnly
-        let mut parts = header.splitn(2, ';');
-        let name_value = match parts.next() {
-            Some(nv) => nv.trim(),
-            None => return,
-        };
-        let (name, value) = match name_value.find('=') {
-            Some(eq) => (name_value[..eq].trim(), name_value[eq + 1..].trim()),
-            None => return,
-        };
-        if name.is_empty() { return; }
-
-        let mut domain = String::from(request_host);
-        let mut path = String::from(request_path);
-        // Trim path to directory
-        if let Some(slash) = path.rfind('/') {
-            path.truncate(slash + 1);
-        }
-        let mut secure = false;
-        let mut http_only = false;
-
-        // Parse attributes
-        if let Some(attrs) = parts.next() {
-            for attr in attrs.split(';') {
-                let attr = attr.trim();
-                if starts_with_ignore_case(attr, "domain=") {
-                    let d = attr[7..].trim().trim_start_matches('.');
-                    if !d.is_empty() {
-                        domain = String::from(d);
-                    }
-                } else if starts_with_ignore_case(attr, "path=") {
-                    let p = attr[5..].trim();
-                    if !p.is_empty() {
-                        path = String::from(p);
-                    }
-                } else if eq_ignore_case(attr, "secure") {
-                    secure = true;
-                } else if eq_ignore_case(attr, "httponly") {
-                    http_only = true;
-                }
-                // Max-Age, Expires not handled (session cookies only)
-            }
-        }
-
-        // Remove existing cookie with same name+domain+path
-        self.cookies.retain(|c| {
-            !(c.name == name && c.domain == domain && c.path == path)
-        });
-
-        self.cookies.push(Cookie {
-            domain: domain.to_ascii_lowercase(),
-            path,
-            name: String::from(name),
-            value: String::from(value),
-            secure,
-            http_only,
-        });
-    }
-
-    /// Build the Cookie header value for a request.
-    pub fn cookie_header(&self, host: &str, path: &str, is_secure: bool) -> Option<String> {
-        let host_lower = host.to_ascii_lowercase();
-        let mut pairs: Vec<(&str, &str)> = Vec::new();
-
-        for c in &self.cookies {
-            // Domain match: host ends with cookie domain
-            if !domain_matches(&host_lower, &c.domain) { continue; }
-            // Path match: request path starts with cookie path
-            if !path.starts_with(c.path.as_str()) { continue; }
-            // Secure check
-            if c.secure && !is_secure { continue; }
-            pairs.push((&c.name, &c.value));
-        }
-
-        if pairs.is_empty() { return None; }
-
-        let mut s = String::new();
-        for (i, (name, value)) in pairs.iter().enumerate() {
-            if i > 0 { s.push_str("; "); }
-            s.push_str(name);
-            s.push('=');
-            s.push_str(value);
-        }
-        Some(s)
-    }
-}
-
-fn domain_matches(host: &str, domain: &str) -> bool {
-    if host == domain { return true; }
-    if host.ends_with(domain) {
-        let prefix_len = host.len() - domain.len();
-        if prefix_len > 0 && host.as_bytes()[prefix_len - 1] == b'.' {
-            return true;
-        }
-    }
-    false
-}
-
-fn eq_ignore_case(a: &str, b: &str) -> bool {
-    if a.len() != b.len() { return false; }
-    starts_with_ignore_case(a, b)
-}
+/// The cookie jar lives in libwebview so scripts and the browser share it.
+pub use libwebview::cookies::CookieJar;
 
 // Helper for to_ascii_lowercase without std
 trait AsciiLowerStr {
@@ -540,7 +407,20 @@ pub fn resolve_url(base: &Url, relative: &str) -> Url {
 // ---------------------------------------------------------------------------
 
 pub fn fetch(url: &Url, cookies: &mut CookieJar, pool: &mut ConnPool) -> Result<Response, FetchError> {
+    fetch_with_headers(url, "", cookies, pool)
+}
+
+/// GET `url`, adding `extra_headers` (complete `Name: value\r\n` lines) to
+/// the first request — e.g. `If-None-Match` for cache revalidation.  They
+/// are not repeated after a redirect, since they describe the original URL.
+pub fn fetch_with_headers(
+    url: &Url,
+    extra_headers: &str,
+    cookies: &mut CookieJar,
+    pool: &mut ConnPool,
+) -> Result<Response, FetchError> {
     let mut current = clone_url(url);
+    let mut extra_headers = extra_headers;
 
     for _redirect_n in 0..MAX_REDIRECTS {
         let is_https = current.scheme == "https";
@@ -557,7 +437,7 @@ pub fn fetch(url: &Url, cookies: &mut CookieJar, pool: &mut ConnPool) -> Result<
         };
 
         // 2. Build and send GET request.
-        let request = build_request(&current, cookies);
+        let request = build_request_with_method(&current, "GET", None, extra_headers, cookies);
         let mut send_ok = send_data(sock, request.as_bytes(), is_https);
 
         // Retry on stale pooled connection.
@@ -611,11 +491,22 @@ pub fn fetch(url: &Url, cookies: &mut CookieJar, pool: &mut ConnPool) -> Result<
             close_conn(sock, is_https);
             if let Some(location) = find_header_value(header_str, "location") {
                 current = resolve_url(&current, location);
+                extra_headers = "";
                 continue;
             }
             return Ok(Response { status, headers, body: Vec::new(), final_url: Some(clone_url(&current)) });
         }
 
+        // 304 / 204 never carry a body; the connection stays usable.
+        if status == 304 || status == 204 {
+            if response_says_close(header_str) {
+                close_conn(sock, is_https);
+            } else {
+                pool.put(current.host.clone(), current.port, sock, is_https);
+            }
+            return Ok(Response { status, headers, body: Vec::new(), final_url: Some(clone_url(&current)) });
+        }
+
         // 6. Read body (chunked or content-length or until close).
         let is_chunked = find_header_value(header_str, "transfer-encoding")
             .map(|v| v.contains("chunked"))
@@ -660,8 +551,14 @@ pub fn fetch(url: &Url, cookies: &mut CookieJar, pool: &mut ConnPool) -> Result<
     Err(FetchError::TooManyRedirects)
 }
 
-/// Fetch a URL using POST with a form-urlencoded body.
-pub fn fetch_post(url: &Url, body: &str, cookies: &mut CookieJar, pool: &mut ConnPool) -> Result<Response, FetchError> {
+/// Fetch a URL using POST with the given body and `Content-Type`.
+pub fn fetch_post(
+    url: &Url,
+    body: &[u8],
+    content_type: &str,
+    cookies: &mut CookieJar,
+    pool: &mut ConnPool,
+) -> Result<Response, FetchError> {
     let mut current = clone_url(url);
 
     for redirect_n in 0..MAX_REDIRECTS {
@@ -680,18 +577,18 @@ pub fn fetch_post(url: &Url, body: &str, cookies: &mut CookieJar, pool: &mut Con
 
         // Use POST on first request, but follow redirects as GET.
         let request = if redirect_n == 0 {
-            build_post_request(&current, body, cookies)
+            build_post_request(&current, body, content_type, cookies)
         } else {
-            build_request(&current, cookies)
+            build_request(&current, cookies).into_bytes()
         };
 
-        let mut send_ok = send_data(sock, request.as_bytes(), is_https);
+        let mut send_ok = send_data(sock, &request, is_https);
 
         // Retry on stale pooled connection.
         if !send_ok && from_pool {
             close_conn(sock, is_https);
             sock = connect_fresh(pool, &current.host, current.port, is_https)?;
-            send_ok = send_data(sock, request.as_bytes(), is_https);
+            send_ok = send_data(sock, &request, is_https);
         }
         if !send_ok {
             close_conn(sock, is_https);
@@ -1024,14 +921,25 @@ fn parse_content_length(headers: &str) -> Option<u32> {
 }
 
 fn build_request(url: &Url, cookies: &CookieJar) -> String {
-    build_request_with_method(url, "GET", None, cookies)
+    build_request_with_method(url, "GET", None, "", cookies)
 }
 
-fn build_post_request(url: &Url, body: &str, cookies: &CookieJar) -> String {
-    build_request_with_method(url, "POST", Some(body), cookies)
+/// POST request head followed by the (possibly binary) body.
+fn build_post_request(url: &Url, body: &[u8], content_type: &str, cookies: &CookieJar) -> Vec<u8> {
+    let mut req = build_request_with_method(url, "POST", Some((content_type, body.len())), "", cookies).into_bytes();
+    req.extend_from_slice(body);
+    req
 }
 
-fn build_request_with_method(url: &Url, method: &str, body: Option<&str>, cookies: &CookieJar) -> String {
+/// Request head; `body` is the `(Content-Type, length)` of a body sent
+/// after it.
+fn build_request_with_method(
+    url: &Url,
+    method: &str,
+    body: Option<(&str, usize)>,
+    extra_headers: &str,
+    cookies: &CookieJar,
+) -> String {
     let mut req = String::new();
     req.push_str(method);
     req.push(' ');
@@ -1047,10 +955,11 @@ fn build_request_with_method(url: &Url, method: &str, body: Option<&str>, cookie
     req.push_str("\r\nAccept-Encoding: gzip, deflate");
     req.push_str("\r\nConnection: keep-alive");
 
-    if let Some(body) = body {
-        req.push_str("\r\nContent-Type: application/x-www-form-urlencoded");
+    if let Some((content_type, len)) = body {
+        req.push_str("\r\nContent-Type: ");
+        req.push_str(content_type);
         req.push_str("\r\nContent-Length: ");
-        push_u32(&mut req, body.len() as u32);
+        push_u32(&mut req, len as u32);
     }
 
     // Append cookies
@@ -1060,12 +969,13 @@ fn build_request_with_method(url: &Url, method: &str, body: Option<&str>, cookie
         req.push_str(&cookie_val);
     }
 
-    req.push_str("\r\n\r\n");
-
-    if let Some(body) = body {
-        req.push_str(body);
+    let extra = extra_headers.trim_end_matches(['\r', '\n'].as_ref());
+    if !extra.is_empty() {
+        req.push_str("\r\n");
+        req.push_str(extra);
     }
 
+    req.push_str("\r\n\r\n");
     req
 }
 
@@ -59,6 +59,8 @@ struct AppState {
     btn_forward: ui_lib::Button,
     btn_reload: ui_lib::Button,
     url_field: ui_lib::TextField,
+    /// Favicon of the active tab, left of the URL field.
+    favicon_view: ui_lib::ImageView,
     /// DevTools toggle button (right of URL field).
     btn_devtools: ui_lib::Button,
     /// Floating popup menu that appears below the DevTools button.
@@ -77,6 +79,10 @@ struct AppState {
     tabs: Vec<tab::TabState>,
     active_tab: usize,
     cookies: http::CookieJar,
+    /// Profile directory holding persistent cookies and localStorage.
+    profile_dir: String,
+    /// Stylesheet / image cache shared by all tabs (disk tier in `<profile>/cache`).
+    resource_cache: libwebview::ResourceCache,
     /// Pending CSS fetch queue: (tab_index, href_attr, resolved_url).
     css_queue: Vec<(usize, String, http::Url)>,
     /// Timer ID for the async CSS fetch loop (0 = not running).
@@ -206,6 +212,9 @@ pub(crate) fn start_anim_timer() {
     st.anim_timer = ui_lib::set_timer(16, || {
         let st = state();
         let changed = st.tabs[st.active_tab].webview.tick(16);
+        // Timer callbacks may have issued XHR / fetch requests or set cookies.
+        submit_pending_http(st.active_tab);
+        store_script_cookies(st.active_tab);
         if changed {
             unsafe { IDLE_TICKS = 0; }
         } else {
@@ -281,16 +290,34 @@ fn process_fetched_results(results: Vec<net_worker::FetchResult>) {
             net_worker::FetchResult::NavError { error_msg, generation } => {
                 handle_nav_error(error_msg, generation);
             }
-            net_worker::FetchResult::CssDone { tab_index, href, body, headers, generation } => {
-                if handle_css_done(tab_index, href, body, headers, generation) {
+            net_worker::FetchResult::CssDone { tab_index, href, key, status, body, headers, generation } => {
+                if handle_css_done(tab_index, href, key, status, body, headers, generation) {
                     mark_relayout_dirty(tab_index);
                 }
             }
-            net_worker::FetchResult::ImageDone { tab_index, src, body, headers, generation } => {
-                if handle_image_done(tab_index, src, body, headers, generation) {
+            net_worker::FetchResult::ImageDone { tab_index, src, key, status, body, headers, generation } => {
+                if handle_image_done(tab_index, src, key, status, body, headers, generation) {
                     mark_relayout_dirty(tab_index);
                 }
             }
+            net_worker::FetchResult::FontDone { tab_index, src, key, status, body, headers, generation } => {
+                if handle_font_done(tab_index, src, key, status, body, headers, generation) {
+                    mark_relayout_dirty(tab_index);
+                }
+            }
+            net_worker::FetchResult::FaviconDone { tab_index, src, key, status, body, headers, generation } => {
+                handle_favicon_done(tab_index, src, key, status, body, headers, generation);
+            }
+            net_worker::FetchResult::ScriptDone { tab_index, id, response, cookies, generation } => {
+                handle_script_done(tab_index, id, response, cookies, generation);
+            }
+            net_worker::FetchResult::ScriptError { tab_index, id, generation } => {
+                let st = state();
+                if tab_index < st.tabs.len() && st.tabs[tab_index].nav_generation == generation {
+                    st.tabs[tab_index].webview.http_failed(id);
+                    submit_pending_http(tab_index);
+                }
+            }
         }
     }
 }
@@ -321,6 +348,14 @@ fn flush_relayout() {
             st.relayout_dirty[tab_idx] = false;
             if tab_idx < st.tabs.len() {
                 st.tabs[tab_idx].webview.relayout();
+                // Inline `<style>` blocks changed by scripts may add web fonts.
+                if resources::queue_fonts(tab_idx) {
+                    mark_relayout_dirty(tab_idx);
+                }
+                // The new styles may have started CSS transitions.
+                if st.tabs[tab_idx].webview.is_animating() {
+                    ensure_anim_timer();
+                }
             }
         }
     }
@@ -340,8 +375,8 @@ fn flush_relayout() {
     }
 }
 
-/// Handle a completed navigation fetch: decode body, render HTML, update
-/// history, queue external resources.
+/// Handle a completed navigation fetch: decode body, commit the navigation
+/// to the WebView, queue external resources.
 fn handle_nav_done(
     response: http::Response,
     original_url: http::Url,
@@ -361,6 +396,7 @@ fn handle_nav_done(
 
     // HTTP error check.
     if response.status < 200 || response.status >= 400 {
+        st.tabs[tab_idx].webview.fail_navigation();
         let mut msg = String::from("HTTP error ");
         ui::push_u32(&mut msg, response.status as u32);
         st.tabs[tab_idx].status_text = msg;
@@ -375,73 +411,66 @@ fn handle_nav_done(
     let body_text = resources::decode_http_body(&response.body, &response.headers);
 
     // Determine base URL (post-redirect URL takes precedence).
+    let redirected = response.final_url.is_some();
     let base_url = response.final_url.unwrap_or(original_url);
-    let url_str = ui::format_url(&base_url);
+    if redirected {
+        let url_str = ui::format_url(&base_url);
+        st.tabs[tab_idx].webview.navigation_redirected(&url_str);
+    }
+    st.tabs[tab_idx].current_url = Some(base_url.clone());
 
     // Clear stylesheets from the previous page.
     st.tabs[tab_idx].webview.clear_stylesheets();
 
-    // Set URL and cookies on the JS runtime before rendering.
-    st.tabs[tab_idx].webview.set_url(&url_str);
+    // Set cookies on the JS runtime before rendering.
+    // HttpOnly cookies are not visible to scripts.
     let is_secure = base_url.scheme == "https";
-    if let Some(cookie_hdr) = st.cookies.cookie_header(&base_url.host, &base_url.path, is_secure) {
-        st.tabs[tab_idx].webview.js_runtime().set_cookies(&cookie_hdr);
-    } else {
-        st.tabs[tab_idx].webview.js_runtime().set_cookies("");
-    }
+    let script_cookies = st.cookies.script_cookies(&base_url.host, &base_url.path, is_secure);
+    st.tabs[tab_idx].webview.js_runtime().set_cookies(&script_cookies);
 
-    // Parse and render the HTML document.
-    st.tabs[tab_idx].webview.set_html(&body_text);
+    // Parse and render the HTML document and commit it to the session
+    // history.  The navigation callback updates the chrome.
+    st.tabs[tab_idx].webview.commit_navigation(&body_text);
 
     // Flush JS console output to serial log.
     for line in st.tabs[tab_idx].webview.js_console() {
         anyos_std::println!("[surf-js] {}", line);
     }
-
-    // Extract page title.
-    let title = st.tabs[tab_idx].webview.get_title()
-        .unwrap_or_else(String::new);
-
-    // Update navigation history — only push if URL differs from current position.
-    let at_same = if !st.tabs[tab_idx].history.is_empty()
-        && st.tabs[tab_idx].history_pos < st.tabs[tab_idx].history.len()
-    {
-        st.tabs[tab_idx].history[st.tabs[tab_idx].history_pos] == url_str
-    } else {
-        false
-    };
-
-    if !at_same {
-        // Truncate any forward history.
-        if !st.tabs[tab_idx].history.is_empty() {
-            let pos = st.tabs[tab_idx].history_pos;
-            st.tabs[tab_idx].history.truncate(pos + 1);
-        }
-        st.tabs[tab_idx].history.push(url_str.clone());
-        st.tabs[tab_idx].history_pos = st.tabs[tab_idx].history.len() - 1;
-    }
-
-    st.tabs[tab_idx].page_title = title;
-    st.tabs[tab_idx].url_text = url_str;
-    st.tabs[tab_idx].current_url = Some(base_url.clone());
-    st.tabs[tab_idx].status_text = String::from("Done");
-
-    // Update chrome UI.
-    let url_for_field = st.tabs[tab_idx].url_text.clone();
-    st.url_field.set_text(&url_for_field);
-    ui::update_title();
-    ui::update_status();
-    ui::update_tab_labels();
     ui::update_devtools();
 
+    // Store cookies written by page scripts via `document.cookie`.
+    store_script_cookies(tab_idx);
+
     // Connect any WebSockets that JS requested during set_html().
     connect_pending_ws(tab_idx);
 
+    // Send any XHR / fetch requests issued by page scripts.
+    submit_pending_http(tab_idx);
+
     // Queue external CSS and images for async fetch via the worker thread.
+    // Resources fresh in the resource cache are applied right away instead.
     if let Some(dom) = st.tabs[tab_idx].webview.dom() {
-        resources::queue_stylesheets(dom, &base_url, tab_idx);
-        resources::queue_images(dom, &base_url, tab_idx);
+        let cached_css = resources::queue_stylesheets(dom, &base_url, tab_idx);
+        let cached_images = resources::queue_images(dom, &base_url, tab_idx);
+        let mut applied = false;
+        for (url, key) in &cached_css {
+            applied |= resources::apply_cached_stylesheet(tab_idx, url, key);
+        }
+        for (src, key) in &cached_images {
+            applied |= resources::apply_cached_image(tab_idx, src, key);
+        }
+        // Web fonts of inline `<style>` blocks and cached stylesheets.
+        applied |= resources::queue_fonts(tab_idx);
+        if applied {
+            mark_relayout_dirty(tab_idx);
+        }
     }
+    // The document is complete, so `/favicon.ico` is a candidate too.
+    resources::queue_favicon(tab_idx);
+    st.tabs[tab_idx].bypass_resource_cache = false;
+
+    // The document is in place; sub-resources arrive via relayouts.
+    st.tabs[tab_idx].webview.finish_navigation();
 
     // Restart animation/scroll tick timer (may have been stopped while idle).
     ensure_anim_timer();
@@ -454,17 +483,21 @@ fn handle_nav_error(error_msg: &'static str, generation: u32) {
     if st.tabs[tab_idx].nav_generation != generation {
         return;
     }
+    st.tabs[tab_idx].webview.fail_navigation();
     st.tabs[tab_idx].status_text = String::from(error_msg);
     ui::update_status();
 }
 
-/// Handle a completed CSS stylesheet fetch: apply the stylesheet.
+/// Handle a completed CSS stylesheet fetch: store it in the resource cache
+/// and apply it.  A `304 Not Modified` applies the cached copy.
 ///
 /// Returns `true` if the stylesheet was applied and a relayout is needed.
 /// The caller batches relayouts to avoid redundant work.
 fn handle_css_done(
     tab_index: usize,
     href: String,
+    key: String,
+    status: u16,
     body: Vec<u8>,
     headers: String,
     generation: u32,
@@ -473,19 +506,111 @@ fn handle_css_done(
     if tab_index >= st.tabs.len() { return false; }
     if st.tabs[tab_index].nav_generation != generation { return false; }
 
+    // Base URL of the sheet's `@font-face` sources.
+    let sheet_url = match st.tabs[tab_index].current_url {
+        Some(ref base) => ui::format_url(&http::resolve_url(base, &href)),
+        None => href.clone(),
+    };
+
+    if status == 304 {
+        if st.resource_cache.not_modified(&key, &headers) {
+            anyos_std::println!("[surf] CSS not modified: {}", href);
+            let applied = resources::apply_cached_stylesheet(tab_index, &sheet_url, &key);
+            return resources::queue_fonts(tab_index) || applied;
+        }
+        anyos_std::println!("[surf] CSS 304 for evicted cache entry: {}", href);
+        return false;
+    }
+    if status >= 200 && status < 300 {
+        st.resource_cache.store(&key, &headers, &body);
+    }
+
     let css_text = resources::decode_http_body(&body, &headers);
-    st.tabs[tab_index].webview.add_stylesheet(&css_text);
+    st.tabs[tab_index].webview.add_stylesheet_from(&css_text, &sheet_url);
     anyos_std::println!("[surf] applied CSS: {}", href);
+    resources::queue_fonts(tab_index);
     true
 }
 
-/// Handle a completed image fetch: decode SVG or raster and add to cache.
+/// Handle a completed web font fetch: store it in the resource cache and
+/// register it with the page.  A failed fetch lets the page fall back to
+/// the face's next source.
+///
+/// Returns `true` if the font was added and a relayout is needed.
+fn handle_font_done(
+    tab_index: usize,
+    src: String,
+    key: String,
+    status: u16,
+    body: Vec<u8>,
+    headers: String,
+    generation: u32,
+) -> bool {
+    let st = state();
+    if tab_index >= st.tabs.len() { return false; }
+    if st.tabs[tab_index].nav_generation != generation { return false; }
+
+    let added = if status == 304 && st.resource_cache.not_modified(&key, &headers) {
+        resources::apply_cached_font(tab_index, &src, &key)
+    } else if status >= 200 && status < 300 {
+        st.resource_cache.store(&key, &headers, &body);
+        st.tabs[tab_index].webview.add_font(&src, &body)
+    } else {
+        anyos_std::println!("[surf] font fetch failed ({}): {}", status, src);
+        st.tabs[tab_index].webview.font_failed(&src);
+        false
+    };
+    if added {
+        anyos_std::println!("[surf] applied font: {}", src);
+    }
+    resources::queue_fonts(tab_index) || added
+}
+
+/// Handle a completed favicon fetch: store it in the resource cache and
+/// hand it to the page, which decodes it and updates the chrome through
+/// the metadata callback.  A failure moves on to the next candidate.
+fn handle_favicon_done(
+    tab_index: usize,
+    src: String,
+    key: String,
+    status: u16,
+    body: Vec<u8>,
+    headers: String,
+    generation: u32,
+) {
+    let st = state();
+    if tab_index >= st.tabs.len() { return; }
+    if st.tabs[tab_index].nav_generation != generation { return; }
+
+    let added = if status == 304 && st.resource_cache.not_modified(&key, &headers) {
+        resources::apply_cached_favicon(tab_index, &src, &key)
+    } else if status >= 200 && status < 300 {
+        let added = st.tabs[tab_index].webview.add_favicon(&src, &body);
+        if added {
+            st.resource_cache.store(&key, &headers, &body);
+        }
+        added
+    } else {
+        st.tabs[tab_index].webview.favicon_failed(&src);
+        false
+    };
+    if !added {
+        anyos_std::println!("[surf] favicon unavailable ({}): {}", status, src);
+        resources::queue_favicon(tab_index);
+    }
+}
+
+/// Handle a completed image fetch: decode SVG or raster and add it to the
+/// page; the body and decoded pixels go into the resource cache.  A
+/// `304 Not Modified` reuses the cached image.
 ///
 /// Returns `true` if the image was decoded and a relayout is needed.
 /// The caller batches relayouts to avoid redundant work.
 fn handle_image_done(
     tab_index: usize,
     src: String,
+    key: String,
+    status: u16,
     body: Vec<u8>,
     headers: String,
     generation: u32,
@@ -494,33 +619,149 @@ fn handle_image_done(
     if tab_index >= st.tabs.len() { return false; }
     if st.tabs[tab_index].nav_generation != generation { return false; }
 
-    if resources::is_svg(&src, &headers) {
-        resources::decode_svg_no_relayout(&body, &src, tab_index);
-    } else {
-        resources::decode_raster_no_relayout(&body, &src, tab_index);
+    if status == 304 {
+        return st.resource_cache.not_modified(&key, &headers)
+            && resources::apply_cached_image(tab_index, &src, &key);
+    }
+    let cached = status >= 200 && status < 300
+        && st.resource_cache.store(&key, &headers, &body);
+
+    match resources::decode_image(&body, &src, &headers) {
+        Some((pixels, w, h)) => {
+            if cached {
+                st.resource_cache.set_image(&key, pixels.clone(), w, h);
+            }
+            st.tabs[tab_index].webview.add_image(&src, pixels, w, h);
+            true
+        }
+        None => false,
     }
-    true
+}
+
+/// Handle a completed script (XHR / fetch) request: hand the response to the
+/// page's JS runtime on the UI thread.
+fn handle_script_done(
+    tab_index: usize,
+    id: u64,
+    response: http::Response,
+    worker_cookies: http::CookieJar,
+    generation: u32,
+) {
+    let st = state();
+    if tab_index >= st.tabs.len() { return; }
+    if st.tabs[tab_index].nav_generation != generation { return; }
+
+    merge_cookies(worker_cookies);
+
+    let mut headers = Vec::new();
+    for line in response.headers.split('\n') {
+        let line = line.trim_end_matches('\r');
+        if let Some(colon) = line.find(':') {
+            headers.push((String::from(&line[..colon]), String::from(line[colon + 1..].trim_start())));
+        }
+    }
+    let resp = libwebview::js::HttpResponse {
+        id,
+        status: response.status,
+        status_text: String::new(),
+        body: resources::decode_http_body(&response.body, &response.headers),
+        headers,
+    };
+    st.tabs[tab_index].webview.http_response(&resp);
+
+    // Response callbacks commonly chain further requests.
+    submit_pending_http(tab_index);
+    store_script_cookies(tab_index);
+    ensure_anim_timer();
+}
+
+/// Drain XHR / fetch requests queued by the scripts of `tab_idx` and submit
+/// them to the network worker.  Responses come back through the net poll
+/// timer and are delivered on the UI thread by `handle_script_done`.
+pub(crate) fn submit_pending_http(tab_idx: usize) {
+    let st = state();
+    let requests = st.tabs[tab_idx].webview.js_runtime().take_pending_http_requests();
+    if requests.is_empty() {
+        return;
+    }
+    let generation = st.tabs[tab_idx].nav_generation;
+    for req in requests {
+        let url = match http::parse_url(&req.url) {
+            Ok(u) => u,
+            Err(_) => {
+                st.tabs[tab_idx].webview.http_failed(req.id);
+                continue;
+            }
+        };
+        net_worker::submit(net_worker::FetchRequest::Script {
+            tab_index: tab_idx,
+            id: req.id,
+            method: req.method,
+            url,
+            body: req.body.unwrap_or_default(),
+            cookies: st.cookies.clone(),
+            generation,
+        });
+    }
+    ensure_net_poll_timer();
 }
 
 /// Merge cookies returned by the worker thread into the main cookie jar.
 ///
 /// Worker-side cookies take precedence (they represent the most recent
-/// Set-Cookie headers from the server).
+/// Set-Cookie headers from the server).  Persistent cookies are written back
+/// to the profile directory when anything changed.
 fn merge_cookies(worker_jar: http::CookieJar) {
     let st = state();
+    let mut changed = false;
     for cookie in worker_jar.cookies {
         // Replace existing cookie with same name+domain+path, or add new.
         let existing = st.cookies.cookies.iter_mut().find(|c| {
             c.name == cookie.name && c.domain == cookie.domain && c.path == cookie.path
         });
         if let Some(existing) = existing {
-            existing.value = cookie.value;
-            existing.secure = cookie.secure;
-            existing.http_only = cookie.http_only;
+            if existing.value != cookie.value || existing.expires != cookie.expires {
+                changed = true;
+            }
+            *existing = cookie;
         } else {
+            changed = true;
             st.cookies.cookies.push(cookie);
         }
     }
+    if changed {
+        st.cookies.purge_expired();
+        st.cookies.save(&st.profile_dir);
+    }
+}
+
+/// Store the `document.cookie` writes made by the scripts of `tab_idx`.
+pub(crate) fn store_script_cookies(tab_idx: usize) {
+    let st = state();
+    let writes = st.tabs[tab_idx].webview.take_cookie_writes();
+    if writes.is_empty() {
+        return;
+    }
+    let url = match st.tabs[tab_idx].current_url {
+        Some(ref u) => u.clone(),
+        None => return,
+    };
+    for value in &writes {
+        st.cookies.set_cookie(value, &url.host, &url.path, true);
+    }
+    st.cookies.save(&st.profile_dir);
+}
+
+/// Profile directory for persistent browser data: `$HOME/.surf`, or
+/// `/tmp/.surf` when no home directory is set.
+fn profile_dir() -> String {
+    let mut home_buf = [0u8; 256];
+    let len = anyos_std::env::get("HOME", &mut home_buf);
+    if len == u32::MAX || len == 0 {
+        return String::from("/tmp/.surf");
+    }
+    let home = core::str::from_utf8(&home_buf[..len as usize]).unwrap_or("/tmp");
+    anyos_std::format!("{}/.surf", home)
 }
 
 // ═══════════════════════════════════════════════════════════
@@ -570,10 +811,18 @@ fn main() {
     btn_reload.set_size(32, 28);
     toolbar.add(&btn_reload);
 
+    // Favicon of the active tab (blank until the page provides one).
+    let favicon_view = ui_lib::ImageView::new(
+        libwebview::metadata::FAVICON_SIZE,
+        libwebview::metadata::FAVICON_SIZE,
+    );
+    favicon_view.set_position(116, 12);
+    toolbar.add(&favicon_view);
+
     // URL field — shortened by 84 px to make room for the DevTools button.
     let url_field = ui_lib::TextField::new();
-    url_field.set_position(116, 6);
-    url_field.set_size(666, 28);
+    url_field.set_position(138, 6);
+    url_field.set_size(644, 28);
     url_field.set_placeholder("Enter URL...");
     toolbar.add(&url_field);
 
@@ -587,7 +836,7 @@ fn main() {
     // The menu View is added to the window with an absolute position; it is
     // normally hidden and popped into view when the DevTools button is clicked.
     let devtools_menu = ui_lib::View::new();
-    devtools_menu.set_size(180, 80);
+    devtools_menu.set_size(180, 120);
     devtools_menu.set_color(0xFF3A3A3C);
     devtools_menu.set_visible(false);
     win.add(&devtools_menu);
@@ -608,6 +857,14 @@ fn main() {
     menu_item_clear.set_font_size(14);
     devtools_menu.add(&menu_item_clear);
 
+    let menu_item_cache = ui_lib::Label::new("  Resource Cache");
+    menu_item_cache.set_position(0, 80);
+    menu_item_cache.set_size(180, 38);
+    menu_item_cache.set_color(0xFF3A3A3C);
+    menu_item_cache.set_text_color(0xFFE5E5EA);
+    menu_item_cache.set_font_size(14);
+    devtools_menu.add(&menu_item_cache);
+
     // ── Tab bar (DOCK_TOP, 30 px) ────────────────────────────────────────────
     let tab_bar_view = ui_lib::TabBar::new("New Tab");
     tab_bar_view.set_dock(ui_lib::DOCK_TOP);
@@ -646,12 +903,19 @@ fn main() {
     win.add(&content_view);
 
     // ── Initial tab ──────────────────────────────────────────────────────────
+    let profile = profile_dir();
     let mut initial_tab = tab::TabState::new();
+    initial_tab.webview.set_profile_dir(&profile);
     initial_tab.webview.set_link_callback(callbacks::on_link_click, 0);
+    initial_tab.webview.set_selection_callback(callbacks::on_page_mouse, 0);
     initial_tab.webview.set_submit_callback(callbacks::on_form_submit, 0);
+    initial_tab.webview.set_form_change_callback(callbacks::on_form_change, 0);
+    initial_tab.webview.set_file_picker(alloc::boxed::Box::new(callbacks::DialogFilePicker));
+    initial_tab.webview.set_navigation_callback(callbacks::on_navigation, 0);
+    initial_tab.webview.set_metadata_callback(callbacks::on_metadata, 0);
     content_view.add(initial_tab.webview.scroll_view());
     initial_tab.webview.scroll_view().set_dock(ui_lib::DOCK_FILL);
-    initial_tab.webview.scroll_view().on_scroll(|_| { ensure_anim_timer(); });
+    initial_tab.webview.scroll_view().on_scroll(|e| crate::callbacks::on_page_scroll(e.id, e.offset));
 
     unsafe {
         STATE = Some(AppState {
@@ -661,6 +925,7 @@ fn main() {
             btn_forward,
             btn_reload,
             url_field,
+            favicon_view,
             btn_devtools,
             devtools_menu,
             tab_bar_view,
@@ -672,7 +937,13 @@ fn main() {
             devtools_menu_visible: false,
             tabs: vec![initial_tab],
             active_tab: 0,
-            cookies: http::CookieJar { cookies: Vec::new() },
+            cookies: http::CookieJar::load(&profile),
+            resource_cache: {
+                let mut cache = libwebview::ResourceCache::new();
+                cache.set_disk_dir(&anyos_std::format!("{}/cache", profile));
+                cache
+            },
+            profile_dir: profile,
             css_queue: Vec::new(),
             css_timer: 0,
             image_queue: Vec::new(),
@@ -694,7 +965,7 @@ fn main() {
     let st = state();
     st.btn_back.on_click(|_| { tab::go_back(); });
     st.btn_forward.on_click(|_| { tab::go_forward(); });
-    st.btn_reload.on_click(|_| { tab::reload(); });
+    st.btn_reload.on_click(|_| { tab::reload(false); });
 
     // DevTools button: show/hide the popup menu.
     btn_devtools.on_click(|_| {
@@ -722,6 +993,12 @@ fn main() {
         st.devtools_menu.set_visible(false);
         ui::clear_devtools();
     });
+    menu_item_cache.on_click(|_| {
+        let st = state();
+        st.devtools_menu_visible = false;
+        st.devtools_menu.set_visible(false);
+        tab::navigate("about:cache");
+    });
 
     // URL field: navigate on Enter.
     st.url_field.on_submit(|e| {
@@ -757,8 +1034,19 @@ fn main() {
         } else if ctrl && key == b'L' as u32 {
             let st = state();
             st.url_field.focus();
+        } else if ctrl && shift && key == b'R' as u32 {
+            // Ctrl+Shift+R — hard reload, bypassing cached images.
+            tab::reload(true);
         } else if ctrl && key == b'R' as u32 {
-            tab::reload();
+            tab::reload(false);
+        } else if ctrl && key == b'C' as u32 {
+            let st = state();
+            st.tabs[st.active_tab].webview.copy_selection();
+        } else if ctrl && key == b'A' as u32 {
+            let st = state();
+            st.tabs[st.active_tab].webview.select_all();
+        } else if ctrl && key == b'P' as u32 {
+            ui::save_as_pdf();
         } else if ctrl && shift && key == b'J' as u32 {
             // Ctrl+Shift+J — toggle DevTools console (Chrome shortcut).
             ui::toggle_devtools();
@@ -787,6 +1075,13 @@ fn main() {
         }
     });
 
+    // Pages follow the system theme (prefers-color-scheme).
+    ui_lib::theme::on_theme_changed(|_| {
+        for t in state().tabs.iter_mut() {
+            t.webview.refresh_color_scheme();
+        }
+    });
+
     // Start the CSS animation tick timer.
     start_anim_timer();
 
@@ -29,7 +29,9 @@ pub(crate) enum FetchRequest {
     /// Full page navigation (POST): fetch HTML with form body.
     NavigatePost {
         url: Url,
-        body: String,
+        body: Vec<u8>,
+        /// `Content-Type` of `body`.
+        content_type: String,
         cookies: CookieJar,
         generation: u32,
     },
@@ -38,6 +40,8 @@ pub(crate) enum FetchRequest {
         tab_index: usize,
         href: String,
         url: Url,
+        /// Conditional-request header lines for a stale cache entry (or empty).
+        validators: String,
         generation: u32,
     },
     /// External image fetch.
@@ -45,6 +49,39 @@ pub(crate) enum FetchRequest {
         tab_index: usize,
         src: String,
         url: Url,
+        /// Conditional-request header lines for a stale cache entry (or empty).
+        validators: String,
+        generation: u32,
+    },
+    /// Web font (`@font-face` source) fetch.
+    Font {
+        tab_index: usize,
+        /// Absolute URL as the WebView requested it.
+        src: String,
+        url: Url,
+        /// Conditional-request header lines for a stale cache entry (or empty).
+        validators: String,
+        generation: u32,
+    },
+    /// Favicon fetch (`WebView::take_favicon_request()`).
+    Favicon {
+        tab_index: usize,
+        /// Absolute URL as the WebView requested it.
+        src: String,
+        url: Url,
+        /// Conditional-request header lines for a stale cache entry (or empty).
+        validators: String,
+        generation: u32,
+    },
+    /// Page-initiated XMLHttpRequest / fetch().
+    Script {
+        tab_index: usize,
+        /// libwebview request ID, echoed back in the result.
+        id: u64,
+        method: String,
+        url: Url,
+        body: String,
+        cookies: CookieJar,
         generation: u32,
     },
 }
@@ -63,22 +100,66 @@ pub(crate) enum FetchResult {
         error_msg: &'static str,
         generation: u32,
     },
-    /// CSS fetch completed successfully.
+    /// CSS fetch completed successfully (`status` 304: use the cached copy).
     CssDone {
         tab_index: usize,
         href: String,
+        /// Resource cache key (see `cache_key()`).
+        key: String,
+        status: u16,
         body: Vec<u8>,
         headers: String,
         generation: u32,
     },
-    /// Image fetch completed successfully.
+    /// Image fetch completed (`status` 304: use the cached copy).
     ImageDone {
         tab_index: usize,
         src: String,
+        /// Resource cache key (see `cache_key()`).
+        key: String,
+        status: u16,
+        body: Vec<u8>,
+        headers: String,
+        generation: u32,
+    },
+    /// Font fetch completed (`status` 304: use the cached copy; 0: the
+    /// request failed at the network level).
+    FontDone {
+        tab_index: usize,
+        src: String,
+        /// Resource cache key (see `cache_key()`).
+        key: String,
+        status: u16,
         body: Vec<u8>,
         headers: String,
         generation: u32,
     },
+    /// Favicon fetch completed (`status` 304: use the cached copy; 0: the
+    /// request failed at the network level).
+    FaviconDone {
+        tab_index: usize,
+        src: String,
+        /// Resource cache key (see `cache_key()`).
+        key: String,
+        status: u16,
+        body: Vec<u8>,
+        headers: String,
+        generation: u32,
+    },
+    /// Script request completed (any HTTP status).
+    ScriptDone {
+        tab_index: usize,
+        id: u64,
+        response: http::Response,
+        cookies: CookieJar,
+        generation: u32,
+    },
+    /// Script request failed at the network level.
+    ScriptError {
+        tab_index: usize,
+        id: u64,
+        generation: u32,
+    },
 }
 
 // ═══════════════════════════════════════════════════════════
@@ -203,7 +284,10 @@ pub(crate) fn new_generation() -> u32 {
             q.retain(|r| match r {
                 FetchRequest::Navigate { .. } | FetchRequest::NavigatePost { .. } => true,
                 FetchRequest::Css { generation, .. }
-                | FetchRequest::Image { generation, .. } => *generation == gen,
+                | FetchRequest::Image { generation, .. }
+                | FetchRequest::Font { generation, .. }
+                | FetchRequest::Favicon { generation, .. }
+                | FetchRequest::Script { generation, .. } => *generation == gen,
             });
         }
     }
@@ -215,7 +299,11 @@ pub(crate) fn new_generation() -> u32 {
             q.retain(|r| match r {
                 FetchResult::NavDone { .. } | FetchResult::NavError { .. } => true,
                 FetchResult::CssDone { generation, .. }
-                | FetchResult::ImageDone { generation, .. } => *generation == gen,
+                | FetchResult::ImageDone { generation, .. }
+                | FetchResult::FontDone { generation, .. }
+                | FetchResult::FaviconDone { generation, .. }
+                | FetchResult::ScriptDone { generation, .. }
+                | FetchResult::ScriptError { generation, .. } => *generation == gen,
             });
         }
     }
@@ -228,57 +316,6 @@ pub(crate) fn current_generation() -> u32 {
     GENERATION.load(Ordering::Relaxed)
 }
 
-// ═══════════════════════════════════════════════════════════
-// Sub-resource cache
-// ═══════════════════════════════════════════════════════════
-
-/// Maximum number of cached CSS/image responses.
-const MAX_CACHE_ENTRIES: usize = 128;
-
-/// A cached HTTP response for a sub-resource (CSS or image).
-struct CacheEntry {
-    /// Cache key: fully-qualified URL string.
-    url_key: String,
-    /// Raw response body.
-    body: Vec<u8>,
-    /// Response headers.
-    headers: String,
-}
-
-/// Per-worker sub-resource cache for CSS and images.
-///
-/// Avoids re-fetching the same stylesheet or image across page loads.
-/// Uses a simple FIFO eviction when the cache is full.
-struct SubResourceCache {
-    entries: Vec<CacheEntry>,
-}
-
-impl SubResourceCache {
-    fn new() -> Self {
-        SubResourceCache { entries: Vec::new() }
-    }
-
-    /// Look up a cached response by URL.
-    fn get(&self, url_key: &str) -> Option<(&[u8], &str)> {
-        self.entries.iter()
-            .find(|e| e.url_key == url_key)
-            .map(|e| (e.body.as_slice(), e.headers.as_str()))
-    }
-
-    /// Store a response in the cache, evicting the oldest entry if full.
-    fn put(&mut self, url_key: String, body: Vec<u8>, headers: String) {
-        if self.entries.len() >= MAX_CACHE_ENTRIES {
-            self.entries.remove(0);
-        }
-        self.entries.push(CacheEntry { url_key, body, headers });
-    }
-
-    /// Clear all entries (called on navigation to a new page).
-    fn _clear(&mut self) {
-        self.entries.clear();
-    }
-}
-
 // ═══════════════════════════════════════════════════════════
 // Worker thread
 // ═══════════════════════════════════════════════════════════
@@ -289,7 +326,6 @@ impl SubResourceCache {
 /// `submit()` call will respawn the thread via `ensure_worker()`.
 fn worker_entry() {
     let mut pool = ConnPool::new();
-    let mut cache = SubResourceCache::new();
     let mut idle_count: u32 = 0;
 
     loop {
@@ -298,7 +334,7 @@ fn worker_entry() {
         match req {
             Some(request) => {
                 idle_count = 0;
-                process_request(request, &mut pool, &mut cache);
+                process_request(request, &mut pool);
             }
             None => {
                 idle_count += 1;
@@ -342,8 +378,8 @@ fn enqueue_result(result: FetchResult) {
     release(&RESULT_LOCK);
 }
 
-/// Format a URL as a cache key string.
-fn cache_key(url: &http::Url) -> String {
+/// Format a URL as a resource cache key string.
+pub(crate) fn cache_key(url: &http::Url) -> String {
     let mut key = String::new();
     key.push_str(&url.scheme);
     key.push_str("://");
@@ -360,7 +396,7 @@ fn cache_key(url: &http::Url) -> String {
 }
 
 /// Process a single fetch request.
-fn process_request(req: FetchRequest, pool: &mut ConnPool, cache: &mut SubResourceCache) {
+fn process_request(req: FetchRequest, pool: &mut ConnPool) {
     let current_gen = GENERATION.load(Ordering::Relaxed);
 
     match req {
@@ -386,11 +422,11 @@ fn process_request(req: FetchRequest, pool: &mut ConnPool, cache: &mut SubResour
             }
         }
 
-        FetchRequest::NavigatePost { url, body, mut cookies, generation } => {
+        FetchRequest::NavigatePost { url, body, content_type, mut cookies, generation } => {
             anyos_std::println!("[surf-net] navigate POST: {}://{}{}",
                 url.scheme, url.host, url.path);
 
-            match http::fetch_post(&url, &body, &mut cookies, pool) {
+            match http::fetch_post(&url, &body, &content_type, &mut cookies, pool) {
                 Ok(response) => {
                     enqueue_result(FetchResult::NavDone {
                         response,
@@ -408,35 +444,20 @@ fn process_request(req: FetchRequest, pool: &mut ConnPool, cache: &mut SubResour
             }
         }
 
-        FetchRequest::Css { tab_index, href, url, generation } => {
+        FetchRequest::Css { tab_index, href, url, validators, generation } => {
             if generation != current_gen {
                 return;
             }
 
-            let key = cache_key(&url);
-
-            // Check sub-resource cache first.
-            if let Some((body, headers)) = cache.get(&key) {
-                anyos_std::println!("[surf-net] CSS cache hit: {}", href);
-                enqueue_result(FetchResult::CssDone {
-                    tab_index,
-                    href,
-                    body: body.to_vec(),
-                    headers: String::from(headers),
-                    generation,
-                });
-                return;
-            }
-
             anyos_std::println!("[surf-net] fetching CSS: {}", href);
             let mut css_cookies = CookieJar::new();
-            match http::fetch(&url, &mut css_cookies, pool) {
+            match http::fetch_with_headers(&url, &validators, &mut css_cookies, pool) {
                 Ok(resp) if resp.status >= 200 && resp.status < 400 => {
-                    // Cache the response for future requests.
-                    cache.put(key, resp.body.clone(), resp.headers.clone());
                     enqueue_result(FetchResult::CssDone {
                         tab_index,
                         href,
+                        key: cache_key(&url),
+                        status: resp.status,
                         body: resp.body,
                         headers: resp.headers,
                         generation,
@@ -448,38 +469,112 @@ fn process_request(req: FetchRequest, pool: &mut ConnPool, cache: &mut SubResour
             }
         }
 
-        FetchRequest::Image { tab_index, src, url, generation } => {
+        FetchRequest::Image { tab_index, src, url, validators, generation } => {
             if generation != current_gen {
                 return;
             }
 
-            let key = cache_key(&url);
+            match http::fetch_with_headers(&url, &validators, &mut CookieJar::new(), pool) {
+                Ok(resp) => {
+                    enqueue_result(FetchResult::ImageDone {
+                        tab_index,
+                        src,
+                        key: cache_key(&url),
+                        status: resp.status,
+                        body: resp.body,
+                        headers: resp.headers,
+                        generation,
+                    });
+                }
+                _ => {}
+            }
+        }
 
-            // Check sub-resource cache first.
-            if let Some((body, headers)) = cache.get(&key) {
-                anyos_std::println!("[surf-net] image cache hit: {}", src);
-                enqueue_result(FetchResult::ImageDone {
-                    tab_index,
-                    src,
-                    body: body.to_vec(),
-                    headers: String::from(headers),
-                    generation,
-                });
+        FetchRequest::Font { tab_index, src, url, validators, generation } => {
+            if generation != current_gen {
                 return;
             }
 
-            match http::fetch(&url, &mut CookieJar::new(), pool) {
+            anyos_std::println!("[surf-net] fetching font: {}", src);
+            let key = cache_key(&url);
+            match http::fetch_with_headers(&url, &validators, &mut CookieJar::new(), pool) {
                 Ok(resp) => {
-                    cache.put(key, resp.body.clone(), resp.headers.clone());
-                    enqueue_result(FetchResult::ImageDone {
+                    enqueue_result(FetchResult::FontDone {
                         tab_index,
                         src,
+                        key,
+                        status: resp.status,
                         body: resp.body,
                         headers: resp.headers,
                         generation,
                     });
                 }
-                _ => {}
+                _ => {
+                    // Report the failure so the page can try the face's next source.
+                    enqueue_result(FetchResult::FontDone {
+                        tab_index,
+                        src,
+                        key,
+                        status: 0,
+                        body: Vec::new(),
+                        headers: String::new(),
+                        generation,
+                    });
+                }
+            }
+        }
+
+        FetchRequest::Favicon { tab_index, src, url, validators, generation } => {
+            if generation != current_gen {
+                return;
+            }
+
+            let key = cache_key(&url);
+            let (status, body, headers) =
+                match http::fetch_with_headers(&url, &validators, &mut CookieJar::new(), pool) {
+                    Ok(resp) => (resp.status, resp.body, resp.headers),
+                    // Report the failure so the page can try its next icon.
+                    Err(_) => (0, Vec::new(), String::new()),
+                };
+            enqueue_result(FetchResult::FaviconDone {
+                tab_index,
+                src,
+                key,
+                status,
+                body,
+                headers,
+                generation,
+            });
+        }
+
+        FetchRequest::Script { tab_index, id, method, url, body, mut cookies, generation } => {
+            if generation != current_gen {
+                return;
+            }
+
+            anyos_std::println!("[surf-net] script {}: {}://{}{}",
+                method, url.scheme, url.host, url.path);
+
+            // The HTTP client only speaks GET and form POST; other verbs
+            // are sent as POST so the body still reaches the server.
+            let result = if method == "GET" || method == "HEAD" {
+                http::fetch(&url, &mut cookies, pool)
+            } else {
+                http::fetch_post(&url, body.as_bytes(), "application/x-www-form-urlencoded", &mut cookies, pool)
+            };
+            match result {
+                Ok(response) => {
+                    enqueue_result(FetchResult::ScriptDone {
+                        tab_index,
+                        id,
+                        response,
+                        cookies,
+                        generation,
+                    });
+                }
+                Err(_) => {
+                    enqueue_result(FetchResult::ScriptError { tab_index, id, generation });
+                }
             }
         }
     }
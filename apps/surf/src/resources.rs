@@ -7,7 +7,10 @@
 //! - HTTP response body decoding (charset detection, Latin-1 → UTF-8)
 //! - External CSS stylesheet discovery and submission to the network worker
 //! - External image discovery and submission to the network worker
+//! - Web font (`@font-face`) requests of the WebView, via the network worker
+//! - Favicon requests of the WebView, via the network worker
 //! - SVG rasterisation and raster image decoding (called from result handlers)
+//! - Serving fresh stylesheets / images from the shared resource cache
 
 use alloc::string::String;
 use alloc::vec::Vec;
@@ -110,13 +113,19 @@ fn latin1_to_utf8(bytes: &[u8]) -> String {
 /// The page is rendered immediately with only the built-in user-agent CSS;
 /// each external stylesheet is applied and the layout refreshed as it arrives,
 /// giving a progressive-rendering effect without blocking the UI thread.
+///
+/// Stylesheets fresh in the resource cache are not fetched; their URLs and
+/// cache keys are returned for the caller to apply with
+/// `apply_cached_stylesheet()` once the DOM is no longer borrowed.  Stale
+/// entries are fetched with conditional-request headers.
 pub(crate) fn queue_stylesheets(
     dom: &libwebview::dom::Dom,
     base_url: &crate::http::Url,
     tab_index: usize,
-) {
+) -> Vec<(String, String)> {
     let generation = crate::net_worker::current_generation();
     let mut count = 0u32;
+    let mut cached = Vec::new();
 
     for (i, node) in dom.nodes.iter().enumerate() {
         if let libwebview::dom::NodeType::Element {
@@ -131,10 +140,18 @@ pub(crate) fn queue_stylesheets(
             if let Some(href) = dom.attr(i, "href") {
                 if !href.is_empty() {
                     let css_url = crate::http::resolve_url(base_url, href);
+                    let validators = match cache_lookup(&css_url, tab_index) {
+                        Ok(key) => {
+                            cached.push((crate::ui::format_url(&css_url), key));
+                            continue;
+                        }
+                        Err(v) => v,
+                    };
                     crate::net_worker::submit(crate::net_worker::FetchRequest::Css {
                         tab_index,
                         href: String::from(href),
                         url: css_url,
+                        validators,
                         generation,
                     });
                     count += 1;
@@ -147,6 +164,7 @@ pub(crate) fn queue_stylesheets(
         anyos_std::println!("[surf] submitted {} stylesheet(s) to worker", count);
         crate::ensure_net_poll_timer();
     }
+    cached
 }
 
 // ═══════════════════════════════════════════════════════════
@@ -155,13 +173,17 @@ pub(crate) fn queue_stylesheets(
 
 /// Scan the DOM for `<img src="…">` tags and submit them to the background
 /// network worker for async fetching.
+///
+/// Like `queue_stylesheets()`, returns `(src, cache key)` for images that
+/// are fresh in the resource cache, to be added with `apply_cached_image()`.
 pub(crate) fn queue_images(
     dom: &libwebview::dom::Dom,
     base_url: &crate::http::Url,
     tab_index: usize,
-) {
+) -> Vec<(String, String)> {
     let generation = crate::net_worker::current_generation();
     let mut count = 0u32;
+    let mut cached = Vec::new();
 
     for (i, node) in dom.nodes.iter().enumerate() {
         if let libwebview::dom::NodeType::Element {
@@ -174,10 +196,18 @@ pub(crate) fn queue_images(
                     continue;
                 }
                 let img_url = crate::http::resolve_url(base_url, src);
+                let validators = match cache_lookup(&img_url, tab_index) {
+                    Ok(key) => {
+                        cached.push((String::from(src), key));
+                        continue;
+                    }
+                    Err(v) => v,
+                };
                 crate::net_worker::submit(crate::net_worker::FetchRequest::Image {
                     tab_index,
                     src: String::from(src),
                     url: img_url,
+                    validators,
                     generation,
                 });
                 count += 1;
@@ -189,6 +219,118 @@ pub(crate) fn queue_images(
         anyos_std::println!("[surf] submitted {} image(s) to worker", count);
         crate::ensure_net_poll_timer();
     }
+    cached
+}
+
+// ═══════════════════════════════════════════════════════════
+// Web fonts — submits the WebView's font requests to the network worker
+// ═══════════════════════════════════════════════════════════
+
+/// Fetch the web font files `tab_index`'s page is waiting for (see
+/// `WebView::take_font_requests()`).  Fonts fresh in the resource cache are
+/// added right away.
+///
+/// Returns `true` if a cached font was added and a relayout is needed.
+pub(crate) fn queue_fonts(tab_index: usize) -> bool {
+    let st = crate::state();
+    if tab_index >= st.tabs.len() {
+        return false;
+    }
+    let generation = crate::net_worker::current_generation();
+    let mut count = 0u32;
+    let mut applied = false;
+
+    // A failed source can queue the face's next one.
+    let mut requests = st.tabs[tab_index].webview.take_font_requests();
+    while !requests.is_empty() {
+        for src in requests {
+            let url = match crate::http::parse_url(&src) {
+                Ok(u) => u,
+                Err(_) => {
+                    st.tabs[tab_index].webview.font_failed(&src);
+                    continue;
+                }
+            };
+            let validators = match cache_lookup(&url, tab_index) {
+                Ok(key) => {
+                    applied |= apply_cached_font(tab_index, &src, &key);
+                    continue;
+                }
+                Err(v) => v,
+            };
+            crate::net_worker::submit(crate::net_worker::FetchRequest::Font {
+                tab_index,
+                src,
+                url,
+                validators,
+                generation,
+            });
+            count += 1;
+        }
+        requests = st.tabs[tab_index].webview.take_font_requests();
+    }
+
+    if count > 0 {
+        anyos_std::println!("[surf] submitted {} font(s) to worker", count);
+        crate::ensure_net_poll_timer();
+    }
+    applied
+}
+
+// ═══════════════════════════════════════════════════════════
+// Favicons — submits the WebView's favicon requests to the network worker
+// ═══════════════════════════════════════════════════════════
+
+/// Fetch the next favicon candidate of `tab_index`'s page (see
+/// `WebView::take_favicon_request()`).  An icon fresh in the resource
+/// cache is added right away; if it fails to decode, the next candidate
+/// is tried.
+pub(crate) fn queue_favicon(tab_index: usize) {
+    let st = crate::state();
+    if tab_index >= st.tabs.len() {
+        return;
+    }
+    while let Some(src) = st.tabs[tab_index].webview.take_favicon_request() {
+        let url = match crate::http::parse_url(&src) {
+            Ok(u) => u,
+            Err(_) => {
+                st.tabs[tab_index].webview.favicon_failed(&src);
+                continue;
+            }
+        };
+        let validators = match cache_lookup(&url, tab_index) {
+            Ok(key) => {
+                apply_cached_favicon(tab_index, &src, &key);
+                continue;
+            }
+            Err(v) => v,
+        };
+        crate::net_worker::submit(crate::net_worker::FetchRequest::Favicon {
+            tab_index,
+            src,
+            url,
+            validators,
+            generation: crate::net_worker::current_generation(),
+        });
+        crate::ensure_net_poll_timer();
+        return;
+    }
+}
+
+/// Look `url` up in the resource cache: `Ok(key)` if fresh, otherwise
+/// `Err(validators)` with the conditional-request headers to fetch it with.
+/// A hard reload of `tab_index` always fetches unconditionally.
+fn cache_lookup(url: &crate::http::Url, tab_index: usize) -> Result<String, String> {
+    let st = crate::state();
+    if st.tabs[tab_index].bypass_resource_cache {
+        return Err(String::new());
+    }
+    let key = crate::net_worker::cache_key(url);
+    match st.resource_cache.lookup(&key) {
+        libwebview::cache::Lookup::Fresh => Ok(key),
+        libwebview::cache::Lookup::Stale(validators) => Err(validators),
+        libwebview::cache::Lookup::Miss => Err(String::new()),
+    }
 }
 
 // ═══════════════════════════════════════════════════════════
@@ -214,53 +356,30 @@ pub(crate) fn is_svg(src: &str, headers: &str) -> bool {
     false
 }
 
-/// Decode a raster image (PNG, JPEG, BMP, GIF) and add the result to the
-/// image cache of `tab_idx`, then relayout.
-pub(crate) fn decode_raster(data: &[u8], src: &str, tab_idx: usize) {
-    decode_raster_no_relayout(data, src, tab_idx);
-    let st = crate::state();
-    if tab_idx < st.tabs.len() {
-        st.tabs[tab_idx].webview.relayout();
+/// Decode a fetched image — SVG or raster (PNG, JPEG, BMP, GIF) — to ARGB
+/// pixels.  Returns `(pixels, width, height)`.
+pub(crate) fn decode_image(data: &[u8], src: &str, headers: &str) -> Option<(Vec<u32>, u32, u32)> {
+    if is_svg(src, headers) {
+        decode_svg(data)
+    } else {
+        decode_raster(data)
     }
 }
 
-/// Decode a raster image without triggering a relayout.
-///
-/// Used by the batch result processor which does a single relayout at the end.
-pub(crate) fn decode_raster_no_relayout(data: &[u8], src: &str, tab_idx: usize) {
-    let info = match libimage_client::probe(data) {
-        Some(i) => i,
-        None => return,
-    };
+fn decode_raster(data: &[u8]) -> Option<(Vec<u32>, u32, u32)> {
+    let info = libimage_client::probe(data)?;
     let w = info.width;
     let h = info.height;
     if w == 0 || h == 0 || w > 4096 || h > 4096 {
-        return;
+        return None;
     }
     let mut pixels = vec![0u32; (w * h) as usize];
     let mut scratch = vec![0u8; info.scratch_needed as usize];
-    if libimage_client::decode(data, &mut pixels, &mut scratch).is_ok() {
-        let st = crate::state();
-        if tab_idx < st.tabs.len() {
-            st.tabs[tab_idx].webview.add_image(src, pixels, w, h);
-        }
-    }
+    libimage_client::decode(data, &mut pixels, &mut scratch).ok()?;
+    Some((pixels, w, h))
 }
 
-/// Rasterise an SVG document and add the result to the image cache of
-/// `tab_idx`, then relayout.
-pub(crate) fn decode_svg(data: &[u8], src: &str, tab_idx: usize) {
-    decode_svg_no_relayout(data, src, tab_idx);
-    let st = crate::state();
-    if tab_idx < st.tabs.len() {
-        st.tabs[tab_idx].webview.relayout();
-    }
-}
-
-/// Rasterise an SVG document without triggering a relayout.
-///
-/// Used by the batch result processor which does a single relayout at the end.
-pub(crate) fn decode_svg_no_relayout(data: &[u8], src: &str, tab_idx: usize) {
+fn decode_svg(data: &[u8]) -> Option<(Vec<u32>, u32, u32)> {
     let (rw, rh) = match libsvg_client::probe(data) {
         Some((w, h)) => {
             let w = (w as u32).max(1).min(4096);
@@ -272,9 +391,88 @@ pub(crate) fn decode_svg_no_relayout(data: &[u8], src: &str, tab_idx: usize) {
 
     let mut pixels = vec![0u32; (rw * rh) as usize];
     if libsvg_client::render_to_size(data, &mut pixels, rw, rh, 0x00000000) {
-        let st = crate::state();
-        if tab_idx < st.tabs.len() {
-            st.tabs[tab_idx].webview.add_image(src, pixels, rw, rh);
+        Some((pixels, rw, rh))
+    } else {
+        None
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+// Resource cache hits (called instead of a fetch)
+// ═══════════════════════════════════════════════════════════
+
+/// Apply the cached stylesheet `key`, loaded from `url`, to `tab_idx`
+/// without relayout.  Returns `false` if it is no longer cached.
+pub(crate) fn apply_cached_stylesheet(tab_idx: usize, url: &str, key: &str) -> bool {
+    let st = crate::state();
+    if tab_idx >= st.tabs.len() {
+        return false;
+    }
+    let css_text = match st.resource_cache.get(key) {
+        Some((body, headers)) => decode_http_body(body, headers),
+        None => return false,
+    };
+    st.tabs[tab_idx].webview.add_stylesheet_from(&css_text, url);
+    true
+}
+
+/// Add the cached font `key` to `tab_idx` as `src` without relayout.
+/// A font no longer cached counts as a failed fetch.  Returns `true` if
+/// the page can use it.
+pub(crate) fn apply_cached_font(tab_idx: usize, src: &str, key: &str) -> bool {
+    let st = crate::state();
+    if tab_idx >= st.tabs.len() {
+        return false;
+    }
+    match st.resource_cache.get(key) {
+        Some((body, _)) => st.tabs[tab_idx].webview.add_font(src, body),
+        None => {
+            st.tabs[tab_idx].webview.font_failed(src);
+            false
+        }
+    }
+}
+
+/// Add the cached favicon `key` to `tab_idx` as `src`.  An icon no longer
+/// cached counts as a failed fetch.  Returns `true` if it decoded.
+pub(crate) fn apply_cached_favicon(tab_idx: usize, src: &str, key: &str) -> bool {
+    let st = crate::state();
+    if tab_idx >= st.tabs.len() {
+        return false;
+    }
+    match st.resource_cache.get(key) {
+        Some((body, _)) => st.tabs[tab_idx].webview.add_favicon(src, body),
+        None => {
+            st.tabs[tab_idx].webview.favicon_failed(src);
+            false
+        }
+    }
+}
+
+/// Add the cached image `key` to `tab_idx` as `src` without relayout,
+/// decoding (and caching the pixels) if only the body is cached so far.
+/// Returns `false` if it is no longer cached or fails to decode.
+pub(crate) fn apply_cached_image(tab_idx: usize, src: &str, key: &str) -> bool {
+    let st = crate::state();
+    if tab_idx >= st.tabs.len() {
+        return false;
+    }
+    if let Some(img) = st.resource_cache.image(key) {
+        let pixels = img.pixels.clone();
+        let (w, h) = (img.width, img.height);
+        st.tabs[tab_idx].webview.add_image(src, pixels, w, h);
+        return true;
+    }
+    let (body, headers) = match st.resource_cache.get(key) {
+        Some((body, headers)) => (body.to_vec(), String::from(headers)),
+        None => return false,
+    };
+    match decode_image(&body, src, &headers) {
+        Some((pixels, w, h)) => {
+            st.resource_cache.set_image(key, pixels.clone(), w, h);
+            st.tabs[tab_idx].webview.add_image(src, pixels, w, h);
+            true
         }
+        None => false,
     }
 }
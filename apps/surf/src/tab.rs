@@ -4,13 +4,13 @@
 //! Per-tab state and navigation logic for the Surf browser.
 //!
 //! `TabState` holds everything associated with a single browser tab:
-//! the `WebView`, URL/history, and page title.  The navigation functions
-//! (`navigate`, `navigate_post`, `go_back`, `go_forward`, `reload`) submit
-//! fetch requests to the background network worker and return immediately,
-//! keeping the UI thread responsive.
+//! the `WebView`, current URL, and page title.  Session history lives in the
+//! WebView's navigation controller; the navigation functions (`navigate`,
+//! `navigate_post`, `go_back`, `go_forward`, `reload`) start a navigation
+//! there and submit fetch requests to the background network worker,
+//! returning immediately to keep the UI thread responsive.
 
 use alloc::string::String;
-use alloc::vec::Vec;
 use libanyui_client as ui;
 use ui::Widget;
 
@@ -28,15 +28,14 @@ pub(crate) struct TabState {
     pub(crate) current_url: Option<crate::http::Url>,
     /// `<title>` extracted from the last loaded page.
     pub(crate) page_title: String,
-    /// Navigation history stack (fully-qualified URL strings).
-    pub(crate) history: Vec<String>,
-    /// Current position within `history` (0 = oldest entry).
-    pub(crate) history_pos: usize,
     /// Short status string shown in the status bar.
     pub(crate) status_text: String,
     /// Generation counter for the current navigation.
     /// Used to discard stale fetch results from the worker thread.
     pub(crate) nav_generation: u32,
+    /// Set by a hard reload: fetch the next page's stylesheets and images
+    /// even if they are fresh in the resource cache.
+    pub(crate) bypass_resource_cache: bool,
 }
 
 impl TabState {
@@ -47,21 +46,20 @@ impl TabState {
             url_text: String::new(),
             current_url: None,
             page_title: String::new(),
-            history: Vec::new(),
-            history_pos: 0,
             status_text: String::from("Ready"),
             nav_generation: 0,
+            bypass_resource_cache: false,
         }
     }
 
     /// Returns `true` when the history has an earlier entry to go back to.
     pub(crate) fn can_go_back(&self) -> bool {
-        self.history_pos > 0
+        self.webview.navigation().can_go_back()
     }
 
     /// Returns `true` when the history has a later entry to go forward to.
     pub(crate) fn can_go_forward(&self) -> bool {
-        self.history_pos + 1 < self.history.len()
+        self.webview.navigation().can_go_forward()
     }
 
     /// Short text used as the tab-bar label for this tab.
@@ -90,15 +88,30 @@ pub(crate) fn navigate(url_str: &str) {
     let st = crate::state();
     anyos_std::println!("[surf] navigating to: {}", url_str);
 
+    let tab = &mut st.tabs[st.active_tab];
+    let referrer = if tab.url_text.is_empty() { None } else { Some(tab.url_text.clone()) };
+    tab.webview.load_url(url_str, referrer.as_deref());
+    fetch(url_str);
+}
+
+/// Fetch `url_str` for the navigation pending in the active tab's WebView.
+fn fetch(url_str: &str) {
+    let st = crate::state();
+
     // Handle file:// URLs locally — no network needed.
     if url_str.starts_with("file://") {
         navigate_file(&url_str[7..]);
         return;
     }
+    if url_str.starts_with("about:cache") {
+        show_cache_page(url_str.ends_with("?clear"));
+        return;
+    }
 
     let url = match crate::http::parse_url(url_str) {
         Ok(u) => u,
         Err(_) => {
+            st.tabs[st.active_tab].webview.fail_navigation();
             st.tabs[st.active_tab].status_text = String::from("Invalid URL");
             crate::ui::update_status();
             return;
@@ -112,12 +125,6 @@ pub(crate) fn navigate(url_str: &str) {
     let generation = crate::net_worker::new_generation();
     st.tabs[st.active_tab].nav_generation = generation;
 
-    // Update UI to show loading state.
-    let mut loading_msg = String::from("Loading: ");
-    loading_msg.push_str(url_str);
-    st.tabs[st.active_tab].status_text = loading_msg;
-    crate::ui::update_status();
-
     // Clone cookies for the worker thread.
     let cookies = st.cookies.clone();
 
@@ -130,11 +137,12 @@ pub(crate) fn navigate(url_str: &str) {
     crate::ensure_net_poll_timer();
 }
 
-/// Navigate the active tab using a form POST request.
+/// Navigate the active tab using a form POST request with the given body
+/// and `Content-Type`.
 ///
 /// Submits the fetch to the background network worker and returns
 /// immediately, just like `navigate()`.
-pub(crate) fn navigate_post(url_str: &str, body: &str) {
+pub(crate) fn navigate_post(url_str: &str, body: &[u8], content_type: &str) {
     let st = crate::state();
 
     let url = match crate::http::parse_url(url_str) {
@@ -146,6 +154,10 @@ pub(crate) fn navigate_post(url_str: &str, body: &str) {
         }
     };
 
+    let tab = &mut st.tabs[st.active_tab];
+    let referrer = if tab.url_text.is_empty() { None } else { Some(tab.url_text.clone()) };
+    tab.webview.load_url(url_str, referrer.as_deref());
+
     cancel_pending_resources();
 
     let generation = crate::net_worker::new_generation();
@@ -158,7 +170,8 @@ pub(crate) fn navigate_post(url_str: &str, body: &str) {
 
     crate::net_worker::submit(crate::net_worker::FetchRequest::NavigatePost {
         url,
-        body: String::from(body),
+        body: body.to_vec(),
+        content_type: String::from(content_type),
         cookies,
         generation,
     });
@@ -182,6 +195,7 @@ fn navigate_file(path: &str) {
     let body = match anyos_std::fs::read_to_vec(path) {
         Ok(data) => data,
         Err(_) => {
+            st.tabs[tab_idx].webview.fail_navigation();
             let mut msg = String::from("File not found: ");
             msg.push_str(path);
             st.tabs[tab_idx].status_text = msg;
@@ -193,10 +207,6 @@ fn navigate_file(path: &str) {
     // Convert body to string (UTF-8 or Latin-1 fallback).
     let html = crate::resources::decode_http_body(&body, "");
 
-    // Build a file:// URL for display and history.
-    let mut url_str = String::from("file://");
-    url_str.push_str(path);
-
     // Build a pseudo-Url for base URL resolution (relative links).
     let base_url = crate::http::Url {
         scheme: String::from("file"),
@@ -204,48 +214,53 @@ fn navigate_file(path: &str) {
         port: 0,
         path: String::from(path),
     };
+    st.tabs[tab_idx].current_url = Some(base_url);
 
-    // Clear previous page state.
+    // Clear previous page state, then render and commit to history.
+    // The navigation callback updates the chrome.
     st.tabs[tab_idx].webview.clear_stylesheets();
-    st.tabs[tab_idx].webview.set_url(&url_str);
-
-    // Render the HTML.
-    st.tabs[tab_idx].webview.set_html(&html);
-
-    // Extract page title.
-    let title = st.tabs[tab_idx].webview.get_title()
-        .unwrap_or_else(String::new);
-
-    // Update history.
-    let at_same = if !st.tabs[tab_idx].history.is_empty()
-        && st.tabs[tab_idx].history_pos < st.tabs[tab_idx].history.len()
-    {
-        st.tabs[tab_idx].history[st.tabs[tab_idx].history_pos] == url_str
-    } else {
-        false
-    };
-    if !at_same {
-        if !st.tabs[tab_idx].history.is_empty() {
-            let pos = st.tabs[tab_idx].history_pos;
-            st.tabs[tab_idx].history.truncate(pos + 1);
-        }
-        st.tabs[tab_idx].history.push(url_str.clone());
-        st.tabs[tab_idx].history_pos = st.tabs[tab_idx].history.len() - 1;
-    }
+    st.tabs[tab_idx].webview.commit_navigation(&html);
+    st.tabs[tab_idx].webview.finish_navigation();
 
-    st.tabs[tab_idx].page_title = title;
-    st.tabs[tab_idx].url_text = url_str;
-    st.tabs[tab_idx].current_url = Some(base_url);
-    st.tabs[tab_idx].status_text = String::from("Done");
+    anyos_std::println!("[surf] loaded local file: {}", path);
+}
 
-    // Update chrome UI.
-    let url_for_field = st.tabs[tab_idx].url_text.clone();
-    st.url_field.set_text(&url_for_field);
-    crate::ui::update_title();
-    crate::ui::update_status();
-    crate::ui::update_tab_labels();
+/// Render the `about:cache` page: resource cache statistics and a link to
+/// `about:cache?clear`, which empties the cache (memory and disk) first.
+fn show_cache_page(clear: bool) {
+    let st = crate::state();
+    let tab_idx = st.active_tab;
 
-    anyos_std::println!("[surf] loaded local file: {}", path);
+    cancel_pending_resources();
+    if clear {
+        st.resource_cache.clear();
+    }
+
+    let s = st.resource_cache.stats();
+    let kib = |b: usize| (b + 1023) / 1024;
+    let html = anyos_std::format!(
+        "<html><head><title>Cache</title></head><body>\
+         <h1>Resource cache</h1>{}\
+         <table>\
+         <tr><td>Entries</td><td>{}</td></tr>\
+         <tr><td>Decoded images</td><td>{}</td></tr>\
+         <tr><td>Memory used</td><td>{} KiB of {} KiB</td></tr>\
+         <tr><td>Hits</td><td>{}</td></tr>\
+         <tr><td>Misses</td><td>{}</td></tr>\
+         <tr><td>Revalidated (304)</td><td>{}</td></tr>\
+         <tr><td>Loaded from disk</td><td>{}</td></tr>\
+         </table>\
+         <p><a href=\"about:cache?clear\">Clear cache</a></p>\
+         </body></html>",
+        if clear { "<p>The cache has been cleared.</p>" } else { "" },
+        s.entries, s.images, kib(s.bytes), kib(s.budget),
+        s.hits, s.misses, s.revalidated, s.disk_loads,
+    );
+
+    st.tabs[tab_idx].current_url = None;
+    st.tabs[tab_idx].webview.clear_stylesheets();
+    st.tabs[tab_idx].webview.commit_navigation(&html);
+    st.tabs[tab_idx].webview.finish_navigation();
 }
 
 /// Cancel any stale CSS/image fetches from the old timer-based system
@@ -267,32 +282,37 @@ fn cancel_pending_resources() {
 /// Navigate the active tab one step back in its history.
 pub(crate) fn go_back() {
     let st = crate::state();
-    let tab = &st.tabs[st.active_tab];
-    if tab.can_go_back() {
-        let new_pos = tab.history_pos - 1;
-        let url = tab.history[new_pos].clone();
-        st.tabs[st.active_tab].history_pos = new_pos;
-        navigate(&url);
+    if st.tabs[st.active_tab].webview.go_back().is_some() {
+        fetch_pending();
     }
 }
 
 /// Navigate the active tab one step forward in its history.
 pub(crate) fn go_forward() {
     let st = crate::state();
-    let tab = &st.tabs[st.active_tab];
-    if tab.can_go_forward() {
-        let new_pos = tab.history_pos + 1;
-        let url = tab.history[new_pos].clone();
-        st.tabs[st.active_tab].history_pos = new_pos;
-        navigate(&url);
+    if st.tabs[st.active_tab].webview.go_forward().is_some() {
+        fetch_pending();
     }
 }
 
 /// Reload the current page in the active tab.
-pub(crate) fn reload() {
+///
+/// With `bypass_cache` the WebView drops its decoded images and the
+/// resource cache is skipped, so every resource is fetched again.
+pub(crate) fn reload(bypass_cache: bool) {
     let st = crate::state();
-    let url = st.tabs[st.active_tab].url_text.clone();
-    if !url.is_empty() {
-        navigate(&url);
+    st.tabs[st.active_tab].bypass_resource_cache = bypass_cache;
+    if st.tabs[st.active_tab].webview.reload(bypass_cache).is_some() {
+        fetch_pending();
     }
 }
+
+/// Fetch the URL of the active tab's pending navigation.
+fn fetch_pending() {
+    let st = crate::state();
+    let url = match st.tabs[st.active_tab].webview.navigation().pending() {
+        Some(p) => p.url.clone(),
+        None => return,
+    };
+    fetch(&url);
+}
@@ -4,7 +4,7 @@
 //! UI chrome helpers and tab management for the Surf browser.
 //!
 //! Contains the stateless helper functions that update window/tab-bar chrome
-//! (`update_title`, `update_status`, `update_tab_labels`) as well as URL
+//! (`update_title`, `update_status`, `update_tab_labels`, `update_page_meta`) as well as URL
 //! formatting utilities and the tab lifecycle functions (`add_tab`,
 //! `close_tab`, `switch_tab`).
 
@@ -57,6 +57,25 @@ pub(crate) fn update_title() {
     }
 }
 
+/// Toolbar background when the page sets no `theme-color`.
+const TOOLBAR_COLOR: u32 = 0xFF2A2A2C;
+
+/// Show the active tab's favicon next to the URL field and tint the toolbar
+/// with its `theme-color`.
+pub(crate) fn update_page_meta() {
+    let st = crate::state();
+    let webview = &st.tabs[st.active_tab].webview;
+    match webview.favicon() {
+        Some(icon) => st.favicon_view.set_pixels(&icon.pixels, icon.width, icon.height),
+        None => {
+            let size = libwebview::metadata::FAVICON_SIZE;
+            let blank = alloc::vec![0u32; (size * size) as usize];
+            st.favicon_view.set_pixels(&blank, size, size);
+        }
+    }
+    st.toolbar.set_color(webview.theme_color().unwrap_or(TOOLBAR_COLOR));
+}
+
 /// Update the status bar text from the active tab's `status_text`.
 pub(crate) fn update_status() {
     let st = crate::state();
@@ -93,11 +112,17 @@ pub(crate) fn update_tab_labels() {
 pub(crate) fn add_tab() {
     let st = crate::state();
     let mut tab = crate::tab::TabState::new();
+    tab.webview.set_profile_dir(&st.profile_dir);
     tab.webview.set_link_callback(crate::callbacks::on_link_click, 0);
+    tab.webview.set_selection_callback(crate::callbacks::on_page_mouse, 0);
     tab.webview.set_submit_callback(crate::callbacks::on_form_submit, 0);
+    tab.webview.set_form_change_callback(crate::callbacks::on_form_change, 0);
+    tab.webview.set_file_picker(alloc::boxed::Box::new(crate::callbacks::DialogFilePicker));
+    tab.webview.set_navigation_callback(crate::callbacks::on_navigation, 0);
+    tab.webview.set_metadata_callback(crate::callbacks::on_metadata, 0);
     st.content_view.add(tab.webview.scroll_view());
     tab.webview.scroll_view().set_dock(ui::DOCK_FILL);
-    tab.webview.scroll_view().on_scroll(|_| { crate::ensure_anim_timer(); });
+    tab.webview.scroll_view().on_scroll(|e| crate::callbacks::on_page_scroll(e.id, e.offset));
 
     // Hide all existing tabs while we add the new one.
     for t in &st.tabs {
@@ -109,6 +134,7 @@ pub(crate) fn add_tab() {
     st.url_field.set_text("");
     update_title();
     update_tab_labels();
+    update_page_meta();
 }
 
 /// Close the tab at `idx`.
@@ -183,4 +209,38 @@ pub(crate) fn switch_tab(idx: usize) {
     update_title();
     update_status();
     update_tab_labels();
+    update_page_meta();
+}
+
+// ═══════════════════════════════════════════════════════════
+// Print / Save as PDF
+// ═══════════════════════════════════════════════════════════
+
+/// A4 page size in CSS pixels (96 dpi).
+const PAGE_W: u32 = 794;
+const PAGE_H: u32 = 1123;
+
+/// Ask for a file name and save the active tab's page as an A4 PDF.
+pub(crate) fn save_as_pdf() {
+    let st = crate::state();
+    let mut default = if st.tabs[st.active_tab].page_title.is_empty() {
+        String::from("page")
+    } else {
+        st.tabs[st.active_tab].page_title.replace('/', "-")
+    };
+    default.push_str(".pdf");
+    let path = match ui::FileDialog::save_file(&default) {
+        Some(p) => p,
+        None => return,
+    };
+    let pdf = st.tabs[st.active_tab].webview.export_pdf(PAGE_W, PAGE_H);
+    let tab = &mut st.tabs[st.active_tab];
+    tab.status_text = if anyos_std::fs::write_bytes(&path, &pdf).is_ok() {
+        let mut s = String::from("Saved ");
+        s.push_str(&path);
+        s
+    } else {
+        String::from("Could not save PDF")
+    };
+    update_status();
 }
@@ -148,6 +148,10 @@ struct VmEntry {
     shm_ptr: *const u8,
     /// Cached instruction count (read from SHM header).
     instruction_count: u64,
+    /// Guest OS detected on the disk by vmd (empty until reported).
+    guest_os: String,
+    /// RAM recommended for the detected guest, in MiB (0 = no hint).
+    guest_ram_mb: u32,
 }
 
 /// Labels displaying real-time VM information.
@@ -565,6 +569,8 @@ fn load_all_vms() -> (Vec<VmEntry>, SidebarLayout) {
                 shm_id: 0,
                 shm_ptr: core::ptr::null(),
                 instruction_count: 0,
+                guest_os: String::new(),
+                guest_ram_mb: 0,
             });
             loaded.push(String::from(uuid));
         }
@@ -611,6 +617,8 @@ fn load_all_vms() -> (Vec<VmEntry>, SidebarLayout) {
                             shm_id: 0,
                             shm_ptr: core::ptr::null(),
                             instruction_count: 0,
+                            guest_os: String::new(),
+                            guest_ram_mb: 0,
                         });
                     }
                 }
@@ -845,10 +853,18 @@ fn update_info_labels() {
     // RAM and instruction count.
     let mut buf = [0u8; 32];
     let s = fmt_label_val(&mut buf, "RAM: ", entry.config.ram_mb, " MB");
-    a.info.ram_label.set_text(s);
+    if entry.guest_ram_mb > entry.config.ram_mb {
+        a.info.ram_label.set_text(&format!("{} ({} MB recommended)", s, entry.guest_ram_mb));
+    } else {
+        a.info.ram_label.set_text(s);
+    }
 
     if entry.state == VmState::Running || entry.instruction_count > 0 {
-        a.info.mode_label.set_text("Mode: x86 (vmd)");
+        if entry.guest_os.is_empty() {
+            a.info.mode_label.set_text("Mode: x86 (vmd)");
+        } else {
+            a.info.mode_label.set_text(&format!("Mode: x86 (vmd), {}", entry.guest_os));
+        }
 
         let mut ibuf = [0u8; 40];
         let s = fmt_label_u64(&mut ibuf, "Instructions: ", entry.instruction_count);
@@ -930,6 +946,8 @@ fn start_selected_vm() {
             if line.starts_with("created") {
                 parse_created_response(entry, line);
                 got_created = true;
+            } else if line.starts_with("guest 0 ") {
+                parse_guest_response(entry, line);
             }
         }
         if got_created {
@@ -969,6 +987,16 @@ fn parse_created_response(entry: &mut VmEntry, resp: &str) {
     }
 }
 
+/// Parse a "guest 0 <os_code> <ram_mb> <name>" report of the OS vmd
+/// detected on the disk.
+fn parse_guest_response(entry: &mut VmEntry, resp: &str) {
+    let parts: Vec<&str> = resp.splitn(5, ' ').collect();
+    if parts.len() == 5 {
+        entry.guest_ram_mb = parse_u32_simple(parts[3]);
+        entry.guest_os = String::from(parts[4]);
+    }
+}
+
 /// Simple decimal parser for no_std.
 fn parse_u32_simple(s: &str) -> u32 {
     let mut val: u32 = 0;
@@ -1649,6 +1677,8 @@ fn create_new_vm() {
         shm_id: 0,
         shm_ptr: core::ptr::null(),
         instruction_count: 0,
+        guest_os: String::new(),
+        guest_ram_mb: 0,
     });
 
     a.selected_vm = a.vms.len() - 1;
@@ -1703,12 +1733,22 @@ fn vm_tick() {
                     a.status_label.set_text_color(0xFF999999);
                 } else if line.starts_with("state 0 stopped") {
                     entry.state = VmState::Stopped;
+                } else if line.starts_with("state 0 shutdown") {
+                    entry.state = VmState::Stopped;
+                    a.status_label.set_text("VM powered off");
+                    a.status_label.set_text_color(0xFF999999);
+                } else if line.starts_with("state 0 reset") {
+                    a.status_label.set_text("VM restarted by guest");
+                    a.status_label.set_text_color(0xFF999999);
                 } else if line.starts_with("state 0 error") || line.starts_with("error 0") {
                     entry.state = VmState::Stopped;
                     let detail = if line.len() > 8 { &line[8..] } else { "error" };
                     a.status_label.set_text(detail);
                     a.status_label.set_text_color(0xFFFF4040);
                     anyos_std::println!("vmmanager: {}", detail);
+                } else if line.starts_with("guest 0 ") {
+                    parse_guest_response(entry, line);
+                    update_info_labels();
                 } else if line.starts_with("serial 0 ") {
                     let text = &line[9..];
                     anyos_std::print!("{}", text);
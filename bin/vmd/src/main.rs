@@ -29,7 +29,7 @@ use alloc::string::String;
 use alloc::vec::Vec;
 use anyos_std::fs;
 use anyos_std::ipc;
-use libcorevm_client::{ExitReason, VmHandle};
+use libcorevm_client::{ExitReason, Machine, VmHandle};
 
 anyos_std::entry!(main);
 
@@ -46,12 +46,9 @@ const SEABIOS_PATH: &str = "/System/shared/corevm/bios/seabios.bin";
 /// Path to the VGA BIOS (SeaVGABIOS/stdvga) ROM image.
 const VGABIOS_PATH: &str = "/System/shared/corevm/bios/vgabios.bin";
 
-/// Instructions to run per execution batch before checking IPC.
-/// Higher = more throughput, lower = more responsive to commands.
-const BATCH_SIZE: u64 = 5_000_000;
-
-/// PIT ticks to advance per batch (approximate real PIT rate).
-const PIT_TICKS_PER_BATCH: u32 = 4;
+/// Virtual time to run per execution batch before checking IPC, in
+/// microseconds. libcorevm sizes the instruction batches and ticks the PIT.
+const SLICE_US: u64 = 10_000;
 
 /// SHM state constants (written to offset 16).
 const STATE_STOPPED: u32 = 0;
@@ -265,6 +262,8 @@ struct VmConfigInfo {
     name: String,
     ram_mb: u32,
     disk_image: String,
+    /// Per-VM overlay on top of `disk_image` (empty = use the disk directly).
+    disk_overlay: String,
     iso_image: String,
 }
 
@@ -308,6 +307,7 @@ fn read_vm_config(uuid: &str) -> Option<VmConfigInfo> {
     let mut name = String::new();
     let mut ram_mb: u32 = 64;
     let mut disk_image = String::new();
+    let mut disk_overlay = String::new();
     let mut iso_image = String::new();
 
     for line in text.split('\n') {
@@ -324,6 +324,8 @@ fn read_vm_config(uuid: &str) -> Option<VmConfigInfo> {
             }
         } else if let Some(val) = line.strip_prefix("disk=") {
             disk_image = String::from(val);
+        } else if let Some(val) = line.strip_prefix("overlay=") {
+            disk_overlay = String::from(val);
         } else if let Some(val) = line.strip_prefix("iso=") {
             iso_image = String::from(val);
         }
@@ -337,6 +339,7 @@ fn read_vm_config(uuid: &str) -> Option<VmConfigInfo> {
         name,
         ram_mb,
         disk_image,
+        disk_overlay,
         iso_image,
     })
 }
@@ -378,9 +381,11 @@ fn cmd_create(uuid: &str) {
         }
     };
 
-    // Set up standard PC devices.
-    handle.setup_standard_devices();
-    handle.setup_ide();
+    // Standard PC devices plus an IDE controller; the disk is attached below.
+    if let Err(code) = handle.configure(&Machine::new().standard().ide(None)) {
+        send_status(&format!("error 0 machine configuration rejected ({})", code));
+        return;
+    }
 
     // Create shared memory for VGA framebuffer.
     let shm_id = ipc::shm_create(SHM_SIZE);
@@ -409,16 +414,30 @@ fn cmd_create(uuid: &str) {
     send_status(&format!("created 0 {}", shm_id));
     anyos_std::println!("[vmd] VM '{}' created ({} MiB RAM, shm={})", config.name, config.ram_mb, shm_id);
 
-    // Attach disk image if configured.
+    // Attach disk image if configured (read and written in place, not loaded).
+    // With an overlay, the VM starts from a fresh overlay over the shared
+    // disk image, which itself is never written.
     if !config.disk_image.is_empty() {
-        let data = read_file(&config.disk_image);
-        if !data.is_empty() {
-            if let Some(ref inst) = d.vm {
-                inst.handle.ide_attach_disk(&data);
-            }
-            anyos_std::println!("[vmd] attached disk: {} ({} bytes)", config.disk_image, data.len());
+        let disk = if config.disk_overlay.is_empty() {
+            Some(&config.disk_image)
         } else {
-            send_status(&format!("error 0 failed to read disk image: {}", config.disk_image));
+            match libcorevm_client::create_disk_overlay(&config.disk_overlay, &config.disk_image) {
+                Ok(()) => Some(&config.disk_overlay),
+                Err(e) => {
+                    // Never fall back to writing the shared image.
+                    send_status(&format!("error 0 failed to create disk overlay: {} ({})", config.disk_overlay, e));
+                    None
+                }
+            }
+        };
+        if let (Some(disk), Some(inst)) = (disk, d.vm.as_ref()) {
+            match inst.handle.ide_open_disk(disk, true) {
+                Ok(()) => {
+                    anyos_std::println!("[vmd] attached disk: {}", disk);
+                    report_guest_os(inst, config.ram_mb);
+                }
+                Err(e) => send_status(&format!("error 0 failed to open disk image: {} ({})", disk, e)),
+            }
         }
     }
 
@@ -434,6 +453,16 @@ fn cmd_create(uuid: &str) {
     }
 }
 
+/// Detect the OS on the attached disk and report it to vmmanager as
+/// `guest 0 <os_code> <recommended_ram_mb> <name>`.
+fn report_guest_os(inst: &VmInstance, ram_mb: u32) {
+    let os = inst.handle.probe_disk();
+    let profile = os.profile();
+    anyos_std::println!("[vmd] detected guest: {} ({} MiB RAM recommended, {} configured)",
+        os.name(), profile.ram_mb, ram_mb);
+    send_status(&format!("guest 0 {} {} {}", os as u32, profile.ram_mb, os.name()));
+}
+
 /// Handle `start` command — load BIOS and begin execution.
 fn cmd_start() {
     let d = daemon();
@@ -453,23 +482,29 @@ fn cmd_start() {
             inst.handle.load_binary(load_addr, &bios_data);
             inst.handle.set_rip(0xFFF0);
             anyos_std::println!("[vmd] loaded SeaBIOS ({} bytes at 0x{:X})", bios_data.len(), load_addr);
+        } else if inst.handle.load_default_bios() {
+            // No SeaBIOS image — fall back to the built-in BIOS, which also
+            // provides INT 10h itself, so no VGA BIOS is needed.
+            anyos_std::println!("[vmd] SeaBIOS not found at {}, using built-in BIOS", SEABIOS_PATH);
         } else {
-            send_status("error 0 SeaBIOS not found");
-            anyos_std::println!("[vmd] ERROR: SeaBIOS not found at {}", SEABIOS_PATH);
+            send_status("error 0 no BIOS available");
+            anyos_std::println!("[vmd] ERROR: SeaBIOS not found at {} and built-in BIOS failed", SEABIOS_PATH);
             return;
         }
 
-        // Load VGA BIOS: provide it both as a fw_cfg file for SeaBIOS's
-        // modern path AND at 0xC0000 in RAM for the legacy ROM scan path.
-        let vgabios_data = read_file(VGABIOS_PATH);
-        if !vgabios_data.is_empty() {
-            // fw_cfg file entry — SeaBIOS loads VGA ROMs via "vgaroms/" prefix.
-            inst.handle.fw_cfg_add_file("vgaroms/vgabios-stdvga.bin", &vgabios_data);
-            // Also place directly in RAM at 0xC0000 (legacy fallback).
-            inst.handle.load_binary(0xC0000, &vgabios_data);
-            anyos_std::println!("[vmd] loaded VGA BIOS ({} bytes, fw_cfg + 0xC0000)", vgabios_data.len());
-        } else {
-            anyos_std::println!("[vmd] WARNING: VGA BIOS not found at {}", VGABIOS_PATH);
+        if !bios_data.is_empty() {
+            // Load VGA BIOS: provide it both as a fw_cfg file for SeaBIOS's
+            // modern path AND at 0xC0000 in RAM for the legacy ROM scan path.
+            let vgabios_data = read_file(VGABIOS_PATH);
+            if !vgabios_data.is_empty() {
+                // fw_cfg file entry — SeaBIOS loads VGA ROMs via "vgaroms/" prefix.
+                inst.handle.fw_cfg_add_file("vgaroms/vgabios-stdvga.bin", &vgabios_data);
+                // Also place directly in RAM at 0xC0000 (legacy fallback).
+                inst.handle.load_binary(0xC0000, &vgabios_data);
+                anyos_std::println!("[vmd] loaded VGA BIOS ({} bytes, fw_cfg + 0xC0000)", vgabios_data.len());
+            } else {
+                anyos_std::println!("[vmd] WARNING: VGA BIOS not found at {}", VGABIOS_PATH);
+            }
         }
 
         // Log MMIO diagnostic info before starting execution.
@@ -495,6 +530,9 @@ fn cmd_stop() {
         }
         inst.handle.request_stop();
         inst.running = false;
+        if !inst.handle.ide_flush() {
+            anyos_std::println!("[vmd] VM '{}': could not write back the disk image", inst.name);
+        }
         update_shm_state(inst, STATE_STOPPED);
         send_status("state 0 stopped");
         anyos_std::println!("[vmd] VM '{}' stopped", inst.name);
@@ -593,25 +631,13 @@ fn run_vm_batch() -> bool {
         _ => return false,
     };
 
-    // Advance PIT and deliver timer interrupts.
-    for _ in 0..PIT_TICKS_PER_BATCH {
-        if inst.handle.pit_tick() {
-            inst.handle.pic_raise_irq(0);
-        }
-    }
-
-    // Execute instructions.
-    let exit = inst.handle.run(BATCH_SIZE);
+    // Execute one slice; timer interrupts are delivered by libcorevm.
+    let (exit, _advanced) = inst.handle.run_for_micros(SLICE_US);
 
     match exit {
         ExitReason::Halted => {
-            // HLT pauses until the next interrupt — deliver a PIT tick
-            // and resume. This is critical: SeaBIOS uses HLT during POST
-            // to wait for timer events. Sleep briefly to avoid busy-spinning.
-            anyos_std::process::sleep(1);
-            if inst.handle.pit_tick() {
-                inst.handle.pic_raise_irq(0);
-            }
+            // HLT with no timer running: only host input can wake the guest.
+            // libcorevm already slept out the slice, so just resume.
             // Drain serial and debug port output (SeaBIOS debug messages).
             let serial_out = inst.handle.serial_take_output_vec();
             if !serial_out.is_empty() {
@@ -657,6 +683,48 @@ fn run_vm_batch() -> bool {
         ExitReason::Breakpoint => {
             // Continue running after breakpoint.
         }
+        ExitReason::Watchpoint => {
+            // A watch changed value — pause so it can be inspected.
+            inst.running = false;
+            update_shm_state(inst, STATE_STOPPED);
+            update_shm_framebuffer(inst);
+            if let Some(hit) = inst.handle.watch_hit() {
+                anyos_std::println!("[vmd] watch {} changed 0x{:X} -> 0x{:X} at RIP=0x{:X}",
+                    hit.id, hit.old, hit.new, hit.rip);
+            }
+            send_status("state 0 watchpoint");
+            return false;
+        }
+        ExitReason::Shutdown => {
+            // Guest powered off via ACPI — treat like a stop.
+            inst.running = false;
+            update_shm_state(inst, STATE_STOPPED);
+            update_shm_framebuffer(inst);
+            send_status("state 0 shutdown");
+            anyos_std::println!("[vmd] VM '{}' powered off by guest", inst.name);
+            return false;
+        }
+        ExitReason::Reset => {
+            // Guest reboot — restart at the reset vector. RAM (including the
+            // shadowed BIOS) is kept, just like a warm reset on real hardware.
+            inst.handle.reset();
+            inst.handle.set_rip(0xFFF0);
+            send_status("state 0 reset");
+            anyos_std::println!("[vmd] VM '{}' reset by guest", inst.name);
+        }
+        ExitReason::GuestExit => {
+            // Only reachable with a TEST_EXIT port configured; stop like a power-off.
+            inst.running = false;
+            update_shm_state(inst, STATE_STOPPED);
+            update_shm_framebuffer(inst);
+            let status = inst.handle.test_exit_status().unwrap_or(0);
+            send_status(&format!("state 0 exit {}", status));
+            anyos_std::println!("[vmd] VM '{}' exited with status {}", inst.name, status);
+            return false;
+        }
+        ExitReason::IoRequest => {
+            // vmd emulates every device and never enables I/O exits.
+        }
     }
 
     // Drain serial output and forward to vmmanager.
@@ -676,6 +744,14 @@ fn run_vm_batch() -> bool {
         }
     }
 
+    // Drain guest log lines (Bochs debug console, port 0xE9).
+    let mut line = [0u8; 256];
+    while let Some((_, n)) = inst.handle.debugcon_take_line(&mut line) {
+        if let Ok(text) = core::str::from_utf8(&line[..n]) {
+            anyos_std::println!("[guest] {}", text);
+        }
+    }
+
     // Update shared memory framebuffer.
     update_shm_framebuffer(inst);
 
@@ -769,11 +845,10 @@ fn main() {
         // Run VM execution batch if active.
         let vm_active = run_vm_batch();
 
-        // Sleep briefly to avoid 100% CPU usage.
+        // Sleep briefly to avoid 100% CPU usage. A running VM sleeps and
+        // yields inside its slice, so the guest clock keeps up with the host.
         if !vm_active {
             anyos_std::process::sleep(10);
-        } else {
-            anyos_std::process::sleep(1);
         }
     }
 }
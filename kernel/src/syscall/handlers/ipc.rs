@@ -202,6 +202,58 @@ pub fn sys_evt_chan_wait(chan_id: u32, sub_id: u32, timeout_ms: u32) -> u32 {
     }
 }
 
+/// Maximum number of subscriptions `sys_evt_chan_wait_any` waits on.
+const MAX_WAIT_ANY: usize = 16;
+
+/// Block until any of several channel subscriptions has an event, or timeout.
+///
+/// ebx=ptr to `count` (chan_id, sub_id) u32 pairs, ecx=count (max 16),
+/// edx=timeout_ms. Lets a UI thread wait on its compositor channel and
+/// application channels at once. Returns 1 if events are available on any
+/// subscription, 0 on timeout/spurious wake.
+pub fn sys_evt_chan_wait_any(pairs_ptr: u32, count: u32, timeout_ms: u32) -> u32 {
+    let count = (count as usize).min(MAX_WAIT_ANY);
+    if pairs_ptr == 0 || count == 0 || !is_valid_user_ptr(pairs_ptr as u64, (count * 8) as u64) {
+        return 0;
+    }
+    let words = unsafe { core::slice::from_raw_parts(pairs_ptr as *const u32, count * 2) };
+    let mut pairs = [(0u32, 0u32); MAX_WAIT_ANY];
+    for i in 0..count {
+        pairs[i] = (words[i * 2], words[i * 2 + 1]);
+    }
+    let pairs = &pairs[..count];
+    let any_events = || pairs.iter().any(|&(c, s)| event_bus::channel_has_events(c, s));
+
+    if any_events() {
+        return 1;
+    }
+
+    // Register as waiter on every subscription; an emit to any of them wakes us.
+    // Registration fails for unknown subscriptions and for ones that received
+    // an event since the check above.
+    let tid = crate::task::scheduler::current_tid();
+    for &(c, s) in pairs {
+        if !event_bus::channel_register_waiter(c, s, tid) && event_bus::channel_has_events(c, s) {
+            for &(c, s) in pairs {
+                event_bus::channel_unregister_waiter(c, s);
+            }
+            return 1;
+        }
+    }
+
+    let effective_ms = if timeout_ms == u32::MAX { 60_000 } else { timeout_ms };
+    let pit_hz = crate::arch::hal::timer_frequency_hz() as u32;
+    let ticks = (effective_ms as u64 * pit_hz as u64 / 1000) as u32;
+    let ticks = if ticks == 0 { 1 } else { ticks };
+    let now = crate::arch::hal::timer_current_ticks();
+    crate::task::scheduler::sleep_until(now.wrapping_add(ticks));
+
+    for &(c, s) in pairs {
+        event_bus::channel_unregister_waiter(c, s);
+    }
+    if any_events() { 1 } else { 0 }
+}
+
 /// Wake the compositor's management thread if it is blocked.
 ///
 /// Uses a two-tier approach for IRQ safety:
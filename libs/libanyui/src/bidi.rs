@@ -0,0 +1,531 @@
+//! Bidirectional text — Unicode BiDi algorithm (UAX #9) and Arabic shaping.
+//!
+//! Text is stored in logical (typing) order. Before a line is drawn it is
+//! resolved into embedding levels and reordered into visual order:
+//!
+//! 1. The paragraph direction is taken from the first strong character
+//!    (P2/P3), falling back to the window direction for neutral-only text.
+//! 2. Weak and neutral types are resolved with rules W1–W7 and N1–N2, then
+//!    implicit levels with I1–I2.
+//! 3. Trailing whitespace and tabs return to the paragraph level (L1), runs
+//!    are reversed from the highest level down (L2), and brackets in
+//!    right-to-left runs are mirrored (L4).
+//!
+//! Explicit embedding, override and isolate controls are not interpreted;
+//! LRM/RLM act as strong marks. Combining marks and emoji sequences stay
+//! attached to their base character, and Arabic letters are replaced by
+//! their contextual presentation forms so they connect. Both steps map one
+//! character to one character, so every caret position in the display maps
+//! straight back to a logical byte offset — that is what [`BidiLine`] provides to the text
+//! controls for drawing, hit testing and visual caret movement.
+//!
+//! A window in right-to-left mode (`anyui_set_window_rtl`) also mirrors its
+//! dock and horizontal stack layouts; the event loop publishes that mode with
+//! [`set_window_rtl`] while it dispatches, lays out and renders the window.
+
+use alloc::vec::Vec;
+use crate::cluster::{decode, next_boundary, prev_boundary};
+
+/// Right-to-left mode of the window being processed.
+static mut WINDOW_RTL: bool = false;
+
+/// Set the direction of the window whose events, layout or rendering are
+/// being processed. The event loop resets it to left-to-right afterwards.
+pub fn set_window_rtl(rtl: bool) {
+    unsafe { WINDOW_RTL = rtl; }
+}
+
+/// Whether the window being processed is in right-to-left mode.
+#[inline(always)]
+pub fn window_rtl() -> bool {
+    unsafe { WINDOW_RTL }
+}
+
+// ── Character classification ───────────────────────────────────────
+
+/// Bidi character types (UAX #9, table 4) that the implicit rules use.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Class {
+    L, R, AL, EN, ES, ET, AN, CS, NSM, BN, S, WS, ON,
+}
+
+fn class_of(c: char) -> Class {
+    use Class::*;
+    let u = c as u32;
+    match u {
+        0x30..=0x39 | 0xB2 | 0xB3 | 0xB9 | 0x6F0..=0x6F9 | 0x2070 | 0x2074..=0x2079
+            | 0x2080..=0x2089 | 0xFF10..=0xFF19 => EN,
+        0x2B | 0x2D | 0x207A | 0x207B | 0x208A | 0x208B | 0x2212 | 0xFE62 | 0xFE63
+            | 0xFF0B | 0xFF0D => ES,
+        0x23..=0x25 | 0xA2..=0xA5 | 0xB0 | 0xB1 | 0x609 | 0x60A | 0x66A | 0x2030..=0x2034
+            | 0x20A0..=0x20CF | 0xFE5F | 0xFE69 | 0xFE6A | 0xFF03..=0xFF05 => ET,
+        0x2C | 0x2E | 0x2F | 0x3A | 0xA0 | 0x60C | 0x202F | 0x2044 | 0xFE50 | 0xFE52
+            | 0xFE55 | 0xFF0C | 0xFF0E | 0xFF0F | 0xFF1A => CS,
+        0x09 | 0x0B | 0x1F => S,
+        0x20 | 0x0C | 0x2000..=0x200A | 0x2028 | 0x205F | 0x3000 => WS,
+        0x00..=0x08 | 0x0E..=0x1B | 0x7F..=0x9F | 0xAD | 0x200B..=0x200D | 0x2060..=0x206F
+            | 0xFEFF => BN,
+        0x200E => L,
+        0x200F => R,
+        0x300..=0x36F | 0x483..=0x489 | 0x591..=0x5BD | 0x5BF | 0x5C1 | 0x5C2 | 0x5C4
+            | 0x5C5 | 0x5C7 | 0x610..=0x61A | 0x64B..=0x65F | 0x670 | 0x6D6..=0x6DC
+            | 0x6DF..=0x6E4 | 0x6E7 | 0x6E8 | 0x6EA..=0x6ED | 0x20D0..=0x20FF
+            | 0xFE00..=0xFE0F | 0xFE20..=0xFE2F => NSM,
+        0x600..=0x605 | 0x660..=0x669 | 0x66B | 0x66C | 0x6DD | 0x8E2 => AN,
+        0x590..=0x5FF | 0x7C0..=0x85F | 0xFB1D..=0xFB4F | 0x10800..=0x10FFF => R,
+        0x600..=0x7BF | 0x860..=0x8FF | 0xFB50..=0xFDFF | 0xFE70..=0xFEFE => AL,
+        0x21 | 0x22 | 0x26..=0x2A | 0x3B..=0x40 | 0x5B..=0x60 | 0x7B..=0x7E | 0xA1
+            | 0xA6..=0xA9 | 0xAB | 0xAC | 0xAE | 0xAF | 0xB4 | 0xB6..=0xB8 | 0xBB..=0xBF
+            | 0xD7 | 0xF7 | 0x2010..=0x2027 | 0x2035..=0x2043 | 0x2045..=0x205E
+            | 0x2100..=0x2101 | 0x2190..=0x2BFF | 0x3001..=0x3003 | 0xFE30..=0xFE4F => ON,
+        _ => L,
+    }
+}
+
+/// Mirrored glyph for characters drawn in a right-to-left run (L4).
+fn mirror(c: char) -> Option<char> {
+    Some(match c {
+        '(' => ')', ')' => '(',
+        '[' => ']', ']' => '[',
+        '{' => '}', '}' => '{',
+        '<' => '>', '>' => '<',
+        '«' => '»', '»' => '«',
+        '‹' => '›', '›' => '‹',
+        _ => return None,
+    })
+}
+
+// ── Arabic shaping ─────────────────────────────────────────────────
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Joining {
+    /// Does not join (or not Arabic).
+    None,
+    /// Joins only to the preceding letter (alef, dal, reh, waw, …).
+    Right,
+    /// Joins on both sides.
+    Dual,
+    /// Tatweel / ZWJ: joins on both sides but has no forms itself.
+    Causing,
+}
+
+/// Isolated presentation form (0 = none) and joining type of U+0621..U+064A.
+/// The final, initial and medial forms follow the isolated one.
+const ARABIC_FORMS: [(u16, u8); 42] = [
+    (0xFE80, 0), (0xFE81, 1), (0xFE83, 1), (0xFE85, 1), (0xFE87, 1), (0xFE89, 2),
+    (0xFE8D, 1), (0xFE8F, 2), (0xFE93, 1), (0xFE95, 2), (0xFE99, 2), (0xFE9D, 2),
+    (0xFEA1, 2), (0xFEA5, 2), (0xFEA9, 1), (0xFEAB, 1), (0xFEAD, 1), (0xFEAF, 1),
+    (0xFEB1, 2), (0xFEB5, 2), (0xFEB9, 2), (0xFEBD, 2), (0xFEC1, 2), (0xFEC5, 2),
+    (0xFEC9, 2), (0xFECD, 2), (0, 2), (0, 2), (0, 2), (0, 2), (0, 2), (0, 3),
+    (0xFED1, 2), (0xFED5, 2), (0xFED9, 2), (0xFEDD, 2), (0xFEE1, 2), (0xFEE5, 2),
+    (0xFEE9, 2), (0xFEED, 1), (0xFEEF, 1), (0xFEF1, 2),
+];
+
+/// Persian/Urdu letters outside the basic block: (char, isolated form, joining).
+const ARABIC_EXTRA_FORMS: [(u16, u16, u8); 6] = [
+    (0x067E, 0xFB56, 2), (0x0686, 0xFB7A, 2), (0x0698, 0xFB8A, 1),
+    (0x06A9, 0xFB8E, 2), (0x06AF, 0xFB92, 2), (0x06CC, 0xFBFC, 2),
+];
+
+fn arabic_forms(c: char) -> (u32, Joining) {
+    let u = c as u32;
+    let (iso, j) = if (0x621..=0x64A).contains(&u) {
+        let (iso, j) = ARABIC_FORMS[(u - 0x621) as usize];
+        (iso as u32, j)
+    } else if let Some(&(_, iso, j)) = ARABIC_EXTRA_FORMS.iter().find(|e| e.0 as u32 == u) {
+        (iso as u32, j)
+    } else if u == 0x200D {
+        (0, 3)
+    } else {
+        (0, 0)
+    };
+    let joining = match j {
+        1 => Joining::Right,
+        2 => Joining::Dual,
+        3 => Joining::Causing,
+        _ => Joining::None,
+    };
+    (iso, joining)
+}
+
+// ── Resolution ─────────────────────────────────────────────────────
+
+/// A base character plus its combining marks, in logical order.
+#[derive(Clone, Copy)]
+struct Unit {
+    /// Byte range in the logical text.
+    start: usize,
+    len: usize,
+    /// Bytes of the base character (marks follow it).
+    base_len: usize,
+    ch: char,
+    class: Class,
+    level: u8,
+}
+
+/// One unit per character cluster (see [`crate::cluster`]), so combining
+/// marks and emoji sequences move with their base character.
+fn units_of(text: &[u8]) -> Vec<Unit> {
+    let mut units: Vec<Unit> = Vec::new();
+    let mut i = 0;
+    while i < text.len() {
+        let end = next_boundary(text, i);
+        let (ch, n) = decode(text, i);
+        units.push(Unit { start: i, len: end - i, base_len: n, ch, class: class_of(ch), level: 0 });
+        i = end;
+    }
+    units
+}
+
+/// Whether `text` contains right-to-left or Arabic-number characters and
+/// therefore needs reordering even in a left-to-right paragraph.
+pub fn has_rtl(text: &[u8]) -> bool {
+    if text.iter().all(|&b| b < 0x80) {
+        return false;
+    }
+    let mut i = 0;
+    while i < text.len() {
+        let (ch, n) = decode(text, i);
+        if matches!(class_of(ch), Class::R | Class::AL | Class::AN) {
+            return true;
+        }
+        i += n;
+    }
+    false
+}
+
+/// Resolve the levels of `units` in a paragraph of level `para`.
+fn resolve(units: &mut [Unit], para: u8) {
+    use Class::*;
+    let n = units.len();
+    let e = if para & 1 == 1 { R } else { L };
+    let mut t: Vec<Class> = units.iter().map(|u| u.class).collect();
+
+    // W1: a leading mark takes the start-of-sequence type.
+    for c in t.iter_mut() {
+        if *c == NSM { *c = e; }
+    }
+    // W2/W3: European numbers after Arabic letters become Arabic numbers.
+    let mut last_strong = e;
+    for c in t.iter_mut() {
+        match *c {
+            L | R | AL => last_strong = *c,
+            EN if last_strong == AL => *c = AN,
+            _ => {}
+        }
+    }
+    for c in t.iter_mut() {
+        if *c == AL { *c = R; }
+    }
+    // W4: single separators between numbers of the same kind.
+    for i in 1..n.saturating_sub(1) {
+        let (a, b) = (t[i - 1], t[i + 1]);
+        if a == b && ((t[i] == ES && a == EN) || (t[i] == CS && (a == EN || a == AN))) {
+            t[i] = a;
+        }
+    }
+    // W5: terminators adjacent to European numbers.
+    let mut i = 0;
+    while i < n {
+        if t[i] != ET {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < n && t[i] == ET { i += 1; }
+        if (start > 0 && t[start - 1] == EN) || (i < n && t[i] == EN) {
+            for c in &mut t[start..i] { *c = EN; }
+        }
+    }
+    // W6: remaining separators and terminators are neutral.
+    for c in t.iter_mut() {
+        if matches!(*c, ES | ET | CS) { *c = ON; }
+    }
+    // W7: European numbers in a left-to-right context.
+    let mut last_strong = e;
+    for c in t.iter_mut() {
+        match *c {
+            L | R => last_strong = *c,
+            EN if last_strong == L => *c = L,
+            _ => {}
+        }
+    }
+    // N1/N2: neutrals between equal directions take it, else the paragraph's.
+    let strong_dir = |c: Class| if c == L { L } else { R };
+    let mut i = 0;
+    while i < n {
+        if !matches!(t[i], WS | ON | S | BN) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < n && matches!(t[i], WS | ON | S | BN) { i += 1; }
+        let before = if start == 0 { e } else { strong_dir(t[start - 1]) };
+        let after = if i == n { e } else { strong_dir(t[i]) };
+        let dir = if before == after { before } else { e };
+        for c in &mut t[start..i] { *c = dir; }
+    }
+    // I1/I2: implicit levels.
+    for (u, &c) in units.iter_mut().zip(t.iter()) {
+        u.level = match (para & 1, c) {
+            (0, R) => para + 1,
+            (0, AN) | (0, EN) => para + 2,
+            (1, L) | (1, EN) | (1, AN) => para + 1,
+            _ => para,
+        };
+    }
+    // L1: tabs, and whitespace before tabs or at the end of the line.
+    let mut trailing = true;
+    for u in units.iter_mut().rev() {
+        match u.class {
+            S => {
+                u.level = para;
+                trailing = true;
+            }
+            WS | BN if trailing => u.level = para,
+            _ => trailing = false,
+        }
+    }
+}
+
+/// Visual order (L2) of units with the given levels.
+fn visual_order(units: &[Unit]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..units.len()).collect();
+    let max = units.iter().map(|u| u.level).max().unwrap_or(0);
+    let min_odd = units.iter().map(|u| u.level).filter(|l| l & 1 == 1).min().unwrap_or(max + 1);
+    let mut level = max;
+    while level >= min_odd && level > 0 {
+        let mut i = 0;
+        while i < order.len() {
+            if units[order[i]].level < level {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < order.len() && units[order[i]].level >= level { i += 1; }
+            order[start..i].reverse();
+        }
+        level -= 1;
+    }
+    order
+}
+
+/// Display character for unit `i`: its contextual Arabic form, mirrored in
+/// right-to-left runs, or the character itself.
+fn display_char(units: &[Unit], i: usize) -> char {
+    let u = &units[i];
+    let (iso, joining) = arabic_forms(u.ch);
+    if iso != 0 {
+        let prev = if i > 0 { arabic_forms(units[i - 1].ch).1 } else { Joining::None };
+        let next = if i + 1 < units.len() { arabic_forms(units[i + 1].ch).1 } else { Joining::None };
+        let joins_prev = matches!(prev, Joining::Dual | Joining::Causing);
+        let joins_next = joining == Joining::Dual
+            && matches!(next, Joining::Right | Joining::Dual | Joining::Causing);
+        let form = match (joining, joins_prev, joins_next) {
+            (Joining::Dual, true, true) => 3,
+            (Joining::Dual, false, true) => 2,
+            (Joining::Dual | Joining::Right, true, _) => 1,
+            _ => 0,
+        };
+        return char::from_u32(iso + form).unwrap_or(u.ch);
+    }
+    if u.level & 1 == 1 {
+        if let Some(m) = mirror(u.ch) {
+            return m;
+        }
+    }
+    u.ch
+}
+
+// ── Display line ───────────────────────────────────────────────────
+
+/// A unit placed on the display line.
+#[derive(Clone, Copy)]
+struct Glyph {
+    start: usize,
+    len: usize,
+    level: u8,
+    /// Left and right edge in pixels from the start of the line.
+    left: i32,
+    right: i32,
+}
+
+/// One line of text prepared for display: reordered, shaped and measured.
+///
+/// Positions passed in and returned are logical byte offsets; x coordinates
+/// are pixels from the left edge of the drawn text.
+pub struct BidiLine {
+    /// Bytes to draw, in visual (left-to-right) order.
+    visual: Vec<u8>,
+    /// Units in logical order (empty for plain left-to-right text).
+    glyphs: Vec<Glyph>,
+    /// Length of the logical text in bytes.
+    len: usize,
+    rtl: bool,
+    font_id: u16,
+    size: u16,
+}
+
+impl BidiLine {
+    /// Prepare `text` for drawing with the given font. Text without strong
+    /// characters takes the direction of the current window.
+    pub fn new(text: &[u8], font_id: u16, size: u16) -> BidiLine {
+        let mut units = units_of(text);
+        let first_strong = units.iter().find_map(|u| match u.class {
+            Class::L => Some(false),
+            Class::R | Class::AL => Some(true),
+            _ => None,
+        });
+        let rtl = first_strong.unwrap_or_else(window_rtl);
+        if !rtl && !has_rtl(text) {
+            return BidiLine { visual: text.to_vec(), glyphs: Vec::new(), len: text.len(), rtl, font_id, size };
+        }
+
+        resolve(&mut units, rtl as u8);
+        let order = visual_order(&units);
+        let mut visual = Vec::with_capacity(text.len());
+        let mut glyphs: Vec<Glyph> = units.iter()
+            .map(|u| Glyph { start: u.start, len: u.len, level: u.level, left: 0, right: 0 })
+            .collect();
+        let mut x = 0;
+        for &i in &order {
+            let u = &units[i];
+            let ch = display_char(&units, i);
+            if ch == u.ch {
+                visual.extend_from_slice(&text[u.start..u.start + u.len]);
+            } else {
+                let mut buf = [0u8; 4];
+                visual.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+                visual.extend_from_slice(&text[u.start + u.base_len..u.start + u.len]);
+            }
+            let right = measure(&visual, font_id, size) as i32;
+            glyphs[i].left = x;
+            glyphs[i].right = right;
+            x = right;
+        }
+        BidiLine { visual, glyphs, len: text.len(), rtl, font_id, size }
+    }
+
+    /// Bytes to pass to the text renderer.
+    pub fn visual(&self) -> &[u8] {
+        &self.visual
+    }
+
+    /// Whether the paragraph runs right to left.
+    pub fn is_rtl(&self) -> bool {
+        self.rtl
+    }
+
+    /// Width of the whole line in pixels.
+    pub fn width(&self) -> i32 {
+        match self.glyphs.iter().map(|g| g.right).max() {
+            Some(w) => w,
+            None => measure(&self.visual, self.font_id, self.size) as i32,
+        }
+    }
+
+    /// Unit index ending at logical offset `pos` (rounded down), if any.
+    fn glyph_before(&self, pos: usize) -> Option<usize> {
+        self.glyphs.iter().rposition(|g| g.start < pos)
+    }
+
+    /// X of the caret at logical offset `pos`: the trailing edge of the
+    /// character before it, or the leading edge of the first character.
+    pub fn caret_x(&self, pos: usize) -> i32 {
+        if self.glyphs.is_empty() {
+            let pos = pos.min(self.visual.len());
+            return measure(&self.visual[..pos], self.font_id, self.size) as i32;
+        }
+        match self.glyph_before(pos) {
+            Some(i) => {
+                let g = &self.glyphs[i];
+                if g.level & 1 == 1 { g.left } else { g.right }
+            }
+            None => {
+                let g = &self.glyphs[0];
+                if g.level & 1 == 1 { g.right } else { g.left }
+            }
+        }
+    }
+
+    /// Logical offset of the caret position closest to `x`.
+    pub fn hit_test(&self, x: i32) -> usize {
+        if self.glyphs.is_empty() {
+            let mut prev_w = 0;
+            let mut pos = 0;
+            while pos < self.visual.len() {
+                let next = next_boundary(&self.visual, pos);
+                let w = measure(&self.visual[..next], self.font_id, self.size) as i32;
+                if x < w {
+                    return if x < (prev_w + w) / 2 { pos } else { next };
+                }
+                prev_w = w;
+                pos = next;
+            }
+            return self.visual.len();
+        }
+        let hit = self.glyphs.iter()
+            .find(|g| x >= g.left && x < g.right)
+            .or_else(|| {
+                // Outside the text: the glyph at that visual edge.
+                if x < 0 {
+                    self.glyphs.iter().min_by_key(|g| g.left)
+                } else {
+                    self.glyphs.iter().max_by_key(|g| g.right)
+                }
+            });
+        match hit {
+            Some(g) => {
+                let left_half = x < (g.left + g.right) / 2;
+                if left_half == (g.level & 1 == 0) { g.start } else { g.start + g.len }
+            }
+            None => 0,
+        }
+    }
+
+    /// Visual x ranges covered by the logical byte range `start..end`
+    /// (several for a selection that crosses direction changes).
+    pub fn spans(&self, start: usize, end: usize) -> Vec<(i32, i32)> {
+        if self.glyphs.is_empty() {
+            let s = self.caret_x(start);
+            let e = self.caret_x(end);
+            return if e > s { alloc::vec![(s, e)] } else { Vec::new() };
+        }
+        let mut covered: Vec<(i32, i32)> = self.glyphs.iter()
+            .filter(|g| g.start >= start && g.start + g.len <= end && g.len > 0)
+            .map(|g| (g.left, g.right))
+            .collect();
+        covered.sort_unstable();
+        let mut merged: Vec<(i32, i32)> = Vec::new();
+        for (l, r) in covered {
+            match merged.last_mut() {
+                Some(last) if l <= last.1 => last.1 = last.1.max(r),
+                _ => merged.push((l, r)),
+            }
+        }
+        merged
+    }
+
+    /// Caret offset one step to the left (`right` = false) or right of
+    /// `pos` on screen. Returns `pos` at the visual edge of the line.
+    pub fn move_visual(&self, pos: usize, right: bool) -> usize {
+        if self.glyphs.is_empty() {
+            return if right { next_boundary(&self.visual, pos) } else { prev_boundary(&self.visual, pos) };
+        }
+        let cur = self.caret_x(pos);
+        let mut best: Option<(i32, usize)> = None;
+        let candidates = core::iter::once(0).chain(self.glyphs.iter().map(|g| g.start + g.len));
+        for b in candidates {
+            let x = self.caret_x(b);
+            let dist = if right { x - cur } else { cur - x };
+            if dist > 0 && best.map_or(true, |(d, _)| dist < d) {
+                best = Some((dist, b));
+            }
+        }
+        best.map_or(pos.min(self.len), |(_, b)| b)
+    }
+}
+
+fn measure(text: &[u8], font_id: u16, size: u16) -> u32 {
+    crate::draw::measure_text_ex(text, font_id, size).0
+}
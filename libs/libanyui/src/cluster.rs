@@ -0,0 +1,126 @@
+//! Character clusters — what the caret steps over and Backspace/Delete
+//! remove as one character.
+//!
+//! A cluster is a base character together with everything that only
+//! modifies it: combining marks, variation selectors, emoji skin-tone
+//! modifiers, keycap and tag characters, and characters glued on with a
+//! zero-width joiner (ZWJ family and profession emoji). Two regional
+//! indicators form one flag, and CR LF is a single line break. This follows
+//! the extended grapheme cluster rules of UAX #29 for the cases the text
+//! controls meet in practice; Hangul syllable composition and Indic
+//! conjuncts are not modelled.
+//!
+//! Text is UTF-8; invalid bytes are clusters of their own so they can still
+//! be deleted.
+
+/// Decode one UTF-8 character at `i`. Invalid bytes decode as U+FFFD with a
+/// length of one, so the original bytes are kept for display.
+pub fn decode(text: &[u8], i: usize) -> (char, usize) {
+    let b = text[i];
+    let n = match b {
+        0x00..=0x7F => return (b as char, 1),
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => return ('\u{FFFD}', 1),
+    };
+    match text.get(i..i + n).and_then(|s| core::str::from_utf8(s).ok()) {
+        Some(s) => (s.chars().next().unwrap_or('\u{FFFD}'), n),
+        None => ('\u{FFFD}', 1),
+    }
+}
+
+const ZWJ: char = '\u{200D}';
+
+/// Characters that attach to the preceding one.
+fn is_extend(c: char) -> bool {
+    matches!(c as u32,
+        0x300..=0x36F | 0x483..=0x489 | 0x591..=0x5BD | 0x5BF | 0x5C1 | 0x5C2 | 0x5C4
+        | 0x5C5 | 0x5C7 | 0x610..=0x61A | 0x64B..=0x65F | 0x670 | 0x6D6..=0x6DC
+        | 0x6DF..=0x6E4 | 0x6E7 | 0x6E8 | 0x6EA..=0x6ED | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF
+        | 0x200C | 0x200D | 0x20D0..=0x20FF | 0xFE00..=0xFE0F | 0xFE20..=0xFE2F
+        | 0x1F3FB..=0x1F3FF | 0xE0020..=0xE007F | 0xE0100..=0xE01EF)
+}
+
+/// Pictographic characters a ZWJ can join (UAX #29 GB11).
+fn is_pictographic(c: char) -> bool {
+    matches!(c as u32,
+        0xA9 | 0xAE | 0x203C | 0x2049 | 0x2122 | 0x2139 | 0x2194..=0x21AA
+        | 0x2300..=0x23FF | 0x24C2 | 0x25AA..=0x25FE | 0x2600..=0x27BF | 0x2934 | 0x2935
+        | 0x2B05..=0x2B55 | 0x3030 | 0x303D | 0x3297 | 0x3299 | 0x1F000..=0x1FAFF)
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c as u32, 0x1F1E6..=0x1F1FF)
+}
+
+/// End of the cluster starting at byte offset `pos`.
+pub fn next_boundary(text: &[u8], pos: usize) -> usize {
+    if pos >= text.len() {
+        return text.len();
+    }
+    let (base, n) = decode(text, pos);
+    let mut end = pos + n;
+    if base == '\r' {
+        return if text.get(end) == Some(&b'\n') { end + 1 } else { end };
+    }
+    if base == '\n' {
+        return end;
+    }
+    if is_regional_indicator(base) {
+        if end < text.len() {
+            let (c, m) = decode(text, end);
+            if is_regional_indicator(c) {
+                end += m;
+            }
+        }
+        return end;
+    }
+    let mut prev = base;
+    while end < text.len() {
+        let (c, m) = decode(text, end);
+        if !(is_extend(c) || (prev == ZWJ && is_pictographic(c))) {
+            break;
+        }
+        end += m;
+        prev = c;
+    }
+    end
+}
+
+/// Start of the cluster that ends at byte offset `pos`.
+pub fn prev_boundary(text: &[u8], pos: usize) -> usize {
+    let pos = pos.min(text.len());
+    if pos == 0 {
+        return 0;
+    }
+    // Clusters are short: step back to a character that always starts a
+    // cluster, then walk forward to the one ending at `pos`.
+    let mut start = pos;
+    let mut steps = 0;
+    while start > 0 && steps < 64 {
+        start -= 1;
+        while start > 0 && text[start] & 0xC0 == 0x80 {
+            start -= 1;
+        }
+        steps += 1;
+        let (c, _) = decode(text, start);
+        if c == '\n' {
+            if start > 0 && text[start - 1] == b'\r' {
+                start -= 1;
+            }
+            break;
+        }
+        if !is_extend(c) && !is_regional_indicator(c) && !is_pictographic(c) {
+            break;
+        }
+    }
+    let mut i = start;
+    loop {
+        let next = next_boundary(text, i);
+        if next >= pos {
+            return i;
+        }
+        i = next;
+    }
+}
@@ -106,6 +106,12 @@ struct LibcompositorExports {
     get_window_position: extern "C" fn(channel_id: u32, sub_id: u32, window_id: u32, out_x: *mut i32, out_y: *mut i32) -> u32,
 
     minimize_window: extern "C" fn(channel_id: u32, window_id: u32),
+
+    get_clipboard_history: extern "C" fn(
+        channel_id: u32, sub_id: u32, index: u32,
+        out_ptr: *mut u8, out_cap: u32,
+        out_format: *mut u32, out_seq: *mut u32,
+    ) -> u32,
 }
 
 fn exports() -> &'static LibcompositorExports {
@@ -124,6 +130,11 @@ pub const EVT_WINDOW_CLOSE: u32 = 0x3007;
 pub const EVT_MOUSE_MOVE: u32 = 0x300A;
 pub const EVT_FRAME_ACK: u32 = 0x300B;
 pub const EVT_FOCUS_LOST: u32 = 0x300C;
+pub const EVT_WINDOW_SNAPPED: u32 = 0x300D;
+/// Touch contact: arg3 = phase | contact_id << 8 (see `pointer`).
+pub const EVT_TOUCH: u32 = 0x300E;
+/// Pen/tablet sample: arg3 = packed phase, buttons, pressure, tilt (see `pointer`).
+pub const EVT_PEN: u32 = 0x300F;
 
 // ── High-level wrappers ──────────────────────────────────────────────
 
@@ -244,6 +255,16 @@ pub fn screen_size() -> (u32, u32) {
     (w, h)
 }
 
+/// Physical bounds `(x, y, w, h)` of each monitor, primary first.
+///
+/// The compositor currently drives a single framebuffer, so this is one
+/// entry covering the screen; callers go through the list so additional
+/// outputs need no API changes.
+pub fn monitors() -> alloc::vec::Vec<(i32, i32, u32, u32)> {
+    let (w, h) = screen_size();
+    alloc::vec![(0, 0, w, h)]
+}
+
 /// Show a notification banner via the compositor.
 pub fn show_notification(
     channel_id: u32,
@@ -296,6 +317,30 @@ pub fn clipboard_get() -> Option<alloc::vec::Vec<u8>> {
     Some(buf[..actual].to_vec())
 }
 
+/// Clipboard sequence number from the shared uisys page (written by the
+/// compositor on every clipboard change).
+pub fn clipboard_seq() -> u32 {
+    unsafe { core::ptr::read_volatile(0x0400_001C as *const u32) }
+}
+
+/// Read clipboard history entry `index` into `buf`.
+/// Returns (bytes written, format, sequence number); 0 bytes if unavailable.
+pub fn clipboard_history(index: u32, buf: &mut [u8]) -> (u32, u32, u32) {
+    let st = crate::state();
+    let mut format: u32 = 0;
+    let mut seq: u32 = 0;
+    let len = (exports().get_clipboard_history)(
+        st.channel_id,
+        st.sub_id,
+        index,
+        buf.as_mut_ptr(),
+        buf.len() as u32,
+        &mut format,
+        &mut seq,
+    );
+    (len, format, seq)
+}
+
 // ── Surface helpers ──────────────────────────────────────────────────
 
 /// Fill a rectangle on a window's SHM surface.
@@ -42,9 +42,54 @@ pub const EVENT_MOUSE_DOWN: u32 = 14;
 pub const EVENT_MOUSE_UP: u32 = 15;
 pub const EVENT_MOUSE_MOVE: u32 = 16;
 pub const EVENT_SUBMIT: u32 = 17;
-
-/// Number of callback slots (EVENT_CLICK=1 .. EVENT_SUBMIT=17, index 0 unused).
-const NUM_CALLBACK_SLOTS: usize = 18;
+/// Window was snapped to (or released from) a screen region.
+pub const EVENT_SNAP: u32 = 18;
+/// "Ignore" was chosen from a misspelled word's popup.
+pub const EVENT_SPELL_IGNORE: u32 = 19;
+/// "Add to Dictionary" was chosen from a misspelled word's popup.
+pub const EVENT_SPELL_ADD: u32 = 20;
+/// StackPanel: a child was dragged to a new position (see `anyui_stackpanel_get_last_reorder`).
+pub const EVENT_REORDER: u32 = 21;
+/// TextField: an edit is about to be committed; the handler may veto it
+/// with `anyui_textfield_reject_edit`. Fired synchronously, before EVENT_CHANGE.
+pub const EVENT_VALIDATE: u32 = 22;
+/// A touch contact went down on the control (see `anyui_get_pointer_info`).
+pub const EVENT_TOUCH_DOWN: u32 = 23;
+/// A touch contact that went down on the control moved.
+pub const EVENT_TOUCH_MOVE: u32 = 24;
+/// A touch contact that went down on the control was lifted.
+pub const EVENT_TOUCH_UP: u32 = 25;
+/// Two-finger pinch over the control; the pointer info's `scale` is the
+/// contact distance relative to the start of the gesture.
+pub const EVENT_PINCH: u32 = 26;
+/// An asynchronous operation on the control failed (e.g. an ImageView file
+/// load; see `anyui_imageview_get_load_error`).
+pub const EVENT_ERROR: u32 = 27;
+/// The control was pressed and held (see [`crate::gesture`] and
+/// `anyui_get_gesture_info`).
+pub const EVENT_LONG_PRESS: u32 = 28;
+/// A swipe ended over the control; the gesture info has its direction.
+pub const EVENT_SWIPE: u32 = 29;
+/// The control was tapped twice in quick succession.
+pub const EVENT_DOUBLE_TAP: u32 = 30;
+/// The user changed a container's selection (see [`crate::selection`]).
+pub const EVENT_SELECTION_CHANGED: u32 = 31;
+/// Help was requested for a control or a descendant (see [`crate::help`]).
+pub const EVENT_HELP: u32 = 32;
+
+/// Number of callback slots (EVENT_CLICK=1 .. EVENT_HELP=32, index 0 unused).
+const NUM_CALLBACK_SLOTS: usize = 33;
+
+// ── Cursor shapes (values match the compositor's CMD_SET_CURSOR) ─────
+
+/// Inherit from the parent (or the control's built-in cursor).
+pub const CURSOR_DEFAULT: u32 = 0;
+pub const CURSOR_ARROW: u32 = 1;
+pub const CURSOR_IBEAM: u32 = 2;
+pub const CURSOR_HAND: u32 = 3;
+pub const CURSOR_RESIZE_H: u32 = 4;
+pub const CURSOR_RESIZE_V: u32 = 5;
+pub const CURSOR_BUSY: u32 = 6;
 
 // ── Key codes (must match compositor's encode_scancode output) ───────
 
@@ -61,6 +106,7 @@ pub const KEY_HOME: u32      = 0x121;
 pub const KEY_END: u32       = 0x122;
 pub const KEY_PAGE_UP: u32   = 0x123;
 pub const KEY_PAGE_DOWN: u32 = 0x124;
+pub const KEY_F1: u32        = 0x140;
 
 // Keyboard modifier flags (bitmask in event[4])
 pub const MOD_SHIFT: u32 = 1;
@@ -210,6 +256,12 @@ pub enum ControlKind {
     TreeView = 40,
     RadioGroup = 41,
     DropDown = 42,
+    Toast = 43,
+    MarkdownView = 44,
+    CircularProgress = 45,
+    ActivitySpinner = 46,
+    FormLayout = 47,
+    NativeSurface = 48,
 }
 
 impl ControlKind {
@@ -258,6 +310,12 @@ impl ControlKind {
             40 => Self::TreeView,
             41 => Self::RadioGroup,
             42 => Self::DropDown,
+            43 => Self::Toast,
+            44 => Self::MarkdownView,
+            45 => Self::CircularProgress,
+            46 => Self::ActivitySpinner,
+            47 => Self::FormLayout,
+            48 => Self::NativeSurface,
             _ => Self::View,
         }
     }
@@ -272,6 +330,8 @@ impl ControlKind {
             Self::Checkbox | Self::RadioButton => (20, 20),
             Self::Slider => (200, 20),
             Self::ProgressBar => (200, 8),
+            Self::CircularProgress => (48, 48),
+            Self::ActivitySpinner => (20, 20),
             Self::Stepper => (94, 28),
             Self::SegmentedControl => (200, 28),
             Self::Divider => (200, 1),
@@ -280,7 +340,7 @@ impl ControlKind {
             Self::TextArea => (300, 150),
             Self::IconButton | Self::ColorWell => (32, 32),
             Self::Tooltip => (150, 24),
-            Self::Canvas => (200, 200),
+            Self::Canvas | Self::NativeSurface => (200, 200),
             Self::Expander => (200, 32),
             Self::DropDown => (200, 32),
             Self::Toolbar => (0, 36),
@@ -314,6 +374,58 @@ pub struct CallbackSlot {
     pub userdata: u64,
 }
 
+/// Owner-draw callback: `(control_id, info, userdata)`.
+///
+/// Called after the control's own rendering (and before its children are
+/// drawn) with the visible part of the control in the back buffer. The
+/// callback may read control properties but must not create, destroy or
+/// modify controls.
+pub type CustomDrawCallback = extern "C" fn(ControlId, *const CustomDrawInfo, u64);
+
+/// `CustomDrawInfo::flags`: the pointer is over the control.
+pub const DRAW_HOVERED: u32 = 1;
+/// `CustomDrawInfo::flags`: the control is held down by the mouse.
+pub const DRAW_PRESSED: u32 = 2;
+/// `CustomDrawInfo::flags`: the control has keyboard focus.
+pub const DRAW_FOCUSED: u32 = 4;
+/// `CustomDrawInfo::flags`: the control is disabled.
+pub const DRAW_DISABLED: u32 = 8;
+
+/// Drawing target handed to a [`CustomDrawCallback`]. All sizes and offsets
+/// are physical pixels.
+#[repr(C)]
+pub struct CustomDrawInfo {
+    /// First visible pixel of the control (ARGB, row-major).
+    pub pixels: *mut u32,
+    /// Size of the visible region starting at `pixels`.
+    pub width: u32,
+    pub height: u32,
+    /// Pixels per back-buffer row.
+    pub stride: u32,
+    /// Position of `pixels[0]` within the control; non-zero when the control
+    /// is partly scrolled or clipped away.
+    pub offset_x: u32,
+    pub offset_y: u32,
+    /// Full size of the control.
+    pub control_width: u32,
+    pub control_height: u32,
+    /// UI scale in percent (logical → physical pixels).
+    pub scale: u32,
+    /// `DRAW_*` flags.
+    pub flags: u32,
+    /// The control's state value (checked, selected index, …).
+    pub state: u32,
+    /// Active theme palette.
+    pub colors: *const crate::theme::ThemeColors,
+}
+
+/// Registered owner-draw callback with its userdata.
+#[derive(Clone, Copy)]
+pub struct CustomDraw {
+    pub cb: CustomDrawCallback,
+    pub userdata: u64,
+}
+
 /// Shared state for all controls (composition pattern for "base class" fields).
 pub struct ControlBase {
     pub id: ControlId,
@@ -336,6 +448,9 @@ pub struct ControlBase {
 
     /// Whether this control needs to be redrawn.
     pub dirty: bool,
+    /// Part of the control (local coordinates) to redraw while `dirty` is
+    /// set; `None` means the whole control.  See `mark_dirty_rect()`.
+    pub dirty_rect: Option<(i32, i32, u32, u32)>,
 
     /// Whether the mouse cursor is currently over this control.
     pub hovered: bool,
@@ -360,11 +475,60 @@ pub struct ControlBase {
     /// Tooltip text to show on hover (empty = no tooltip).
     pub tooltip_text: Vec<u8>,
 
+    /// Context help topic (empty = inherit the parent's, see [`crate::help`]).
+    pub help_topic: Vec<u8>,
+
+    /// App-defined user data and the destructor called with it when the
+    /// control is removed (`anyui_set_tag`, see [`crate::tag`]).
+    pub tag: u64,
+    pub tag_destructor: Option<crate::tag::TagDestructor>,
+
+    /// Badge drawn over one corner of the control (`anyui_set_badge`).
+    pub badge: Option<crate::controls::badge::BadgeOverlay>,
+
+    /// Validators and error state (`anyui_validate_*`, see [`crate::validation`]).
+    pub validation: Option<Box<crate::validation::Validation>>,
+
+    /// String-table key of the control's text (`anyui_set_text_key`, see
+    /// [`crate::i18n`]); re-resolved when the language changes.
+    pub text_key: Option<Box<crate::i18n::TextKey>>,
+
     /// Tab focus order index. Controls with lower tab_index get focus first.
     /// 0 means "use insertion order" (default). Cascaded: parent tab_index
     /// is used as the primary sort key, child tab_index as secondary.
     pub tab_index: u32,
 
+    /// Stacking order among siblings. Higher values render on top and are
+    /// hit-tested first; equal values keep insertion order. Independent of
+    /// layout (dock) order and tab order.
+    pub z_index: i32,
+
+    /// Wheel steps this control (or a descendant) cannot use chain on to
+    /// the nearest scrollable ancestor. When false the control keeps them,
+    /// so the page behind it does not move (`anyui_set_scroll_chaining`).
+    pub scroll_chaining: bool,
+
+    /// Cursor shape over this control (`CURSOR_*`; CURSOR_DEFAULT inherits).
+    pub cursor: u32,
+
+    /// Owner-draw callback run after the control renders (`anyui_set_custom_draw`).
+    pub custom_draw: Option<CustomDraw>,
+
+    /// Virtualization mode: children are realized on demand by an item
+    /// provider (`anyui_set_virtualized`, see [`crate::virtualize`]).
+    pub virtual_items: Option<Box<crate::virtualize::Virtualization>>,
+
+    /// Long-press, swipe and double-tap recognizers (`anyui_gesture_*`, see
+    /// [`crate::gesture`]).
+    pub gestures: Option<Box<crate::gesture::Recognizers>>,
+
+    /// Selected children of a View or FlowPanel (`anyui_set_selection_mode`,
+    /// see [`crate::selection`]).
+    pub selection: Option<Box<crate::selection::Selection>>,
+
+    /// Class names and cascade bookkeeping for styles (see [`crate::style`]).
+    pub style: crate::style::StyleState,
+
     /// Callback table indexed by event type (EVENT_CLICK=1 .. EVENT_MOUSE_MOVE=16).
     /// Index 0 is unused. Each slot has its own userdata.
     callbacks: [Option<CallbackSlot>; NUM_CALLBACK_SLOTS],
@@ -372,6 +536,7 @@ pub struct ControlBase {
 
 impl ControlBase {
     pub fn new(id: ControlId, parent: ControlId, x: i32, y: i32, w: u32, h: u32) -> Self {
+        crate::mark_needs_restyle();
         Self {
             id,
             parent,
@@ -388,6 +553,7 @@ impl ControlBase {
             color: 0,
             state: 0,
             dirty: true,
+            dirty_rect: None,
             hovered: false,
             focused: false,
             disabled: false,
@@ -401,7 +567,21 @@ impl ControlBase {
             max_h: 0,
             context_menu: None,
             tooltip_text: Vec::new(),
+            help_topic: Vec::new(),
+            tag: 0,
+            tag_destructor: None,
+            badge: None,
+            validation: None,
+            text_key: None,
             tab_index: 0,
+            z_index: 0,
+            scroll_chaining: true,
+            cursor: CURSOR_DEFAULT,
+            custom_draw: None,
+            virtual_items: None,
+            gestures: None,
+            selection: None,
+            style: crate::style::StyleState::new(),
             callbacks: [None; NUM_CALLBACK_SLOTS],
         }
     }
@@ -410,9 +590,31 @@ impl ControlBase {
     /// Prefer this over setting `dirty = true` directly — it enables the event
     /// loop to skip O(n) dirty scans on idle frames.
     pub fn mark_dirty(&mut self) {
+        self.dirty_rect = None;
+        if !self.dirty {
+            self.dirty = true;
+            crate::mark_needs_repaint();
+        }
+    }
+
+    /// Mark only part of this control (local coordinates) as needing a
+    /// repaint, so the event loop re-composites just that region.  Repeated
+    /// calls before the next frame accumulate; a pending full `mark_dirty()`
+    /// is never narrowed.
+    pub fn mark_dirty_rect(&mut self, x: i32, y: i32, w: u32, h: u32) {
+        if w == 0 || h == 0 {
+            return;
+        }
         if !self.dirty {
             self.dirty = true;
+            self.dirty_rect = Some((x, y, w, h));
             crate::mark_needs_repaint();
+        } else if let Some((dx, dy, dw, dh)) = self.dirty_rect {
+            let x0 = dx.min(x);
+            let y0 = dy.min(y);
+            let x1 = (dx + dw as i32).max(x + w as i32);
+            let y1 = (dy + dh as i32).max(y + h as i32);
+            self.dirty_rect = Some((x0, y0, (x1 - x0) as u32, (y1 - y0) as u32));
         }
     }
 
@@ -529,7 +731,9 @@ impl EventResponse {
 /// - DoubleClick — two clicks within 400ms
 /// - Focus / Blur — keyboard focus changes
 /// - KeyDown — keyboard input to focused control
-/// - Scroll — mouse wheel
+/// - Scroll — mouse wheel (or two-finger touch scroll)
+/// - TouchDown / TouchMove / TouchUp, Pinch — touch contacts and gestures
+/// - LongPress / Swipe / DoubleTap — for controls with gesture recognizers
 ///
 /// Each control overrides the virtual methods relevant to its behavior.
 /// Default implementations do nothing (return IGNORED).
@@ -588,6 +792,28 @@ pub trait Control {
         None
     }
 
+    /// Auto-size: fit the height to content whose lowest child ends at
+    /// `content_bottom` (child y + h + bottom margin). Override for
+    /// containers that draw chrome around their children.
+    fn fit_content_height(&mut self, content_bottom: i32) {
+        let pad = self.base().padding;
+        let w = self.base().w;
+        self.set_size(w, (content_bottom + pad.bottom).max(0) as u32);
+    }
+
+    /// Distance from the top edge to the baseline of the first line of
+    /// text, used to line up labels with their fields (FormLayout).
+    /// `None` for controls without text.
+    fn baseline(&self) -> Option<i32> { None }
+
+    /// Bytes of pixel data this control holds (canvas buffers, decoded
+    /// images, pre-rendered icons). Counted by `anyui_get_memory_usage`.
+    fn pixel_bytes(&self) -> usize { 0 }
+
+    /// Drop caches that can be rebuilt on demand. Returns bytes freed.
+    /// Called by `anyui_trim_memory` and on memory pressure in low-memory mode.
+    fn trim_caches(&mut self) -> usize { 0 }
+
     // ── Virtual event handlers (override in subclasses) ──────────────
 
     /// Called when mouse cursor enters this control's bounds.
@@ -623,6 +849,19 @@ pub trait Control {
     /// Returns `None` (default) when no scrollbar is present.
     fn scrollbar_hit_x(&self) -> Option<i32> { None }
 
+    /// Built-in cursor shape used when none was set with `anyui_set_cursor`
+    /// (e.g. CURSOR_IBEAM for text inputs). CURSOR_DEFAULT inherits.
+    fn default_cursor(&self) -> u32 { CURSOR_DEFAULT }
+
+    /// Misspelled word at local (x, y), for controls with spell checking
+    /// enabled. The control remembers the word's position so a later
+    /// `replace_spell_word` can substitute a suggestion. Default: `None`.
+    fn spell_word_at(&mut self, _local_x: i32, _local_y: i32) -> Option<Vec<u8>> { None }
+
+    /// Replace the word found by the last `spell_word_at`. Returns true if
+    /// the text changed.
+    fn replace_spell_word(&mut self, _replacement: &[u8]) -> bool { false }
+
     /// Called when mouse is clicked (down + up on same control).
     /// This is a higher-level event synthesized by the event loop.
     fn handle_click(&mut self, _local_x: i32, _local_y: i32, _button: u32) -> EventResponse {
@@ -646,11 +885,20 @@ pub trait Control {
         EventResponse::IGNORED
     }
 
-    /// Called when mouse wheel scrolls over this control.
+    /// Called when mouse wheel scrolls over this control, one step at a time
+    /// (`delta` is +1 or -1). A scrollable at its limit in that direction
+    /// returns IGNORED so the step chains to its scrollable ancestor.
     fn handle_scroll(&mut self, _delta: i32) -> EventResponse {
         EventResponse::IGNORED
     }
 
+    /// Called with every pointer sample (mouse, pen or touch) for this
+    /// control, before the mouse handlers it may also produce. Carries
+    /// pressure, tilt and the touch contact id; `ev.x/y` are local.
+    fn handle_pointer(&mut self, _ev: &crate::pointer::PointerEvent) -> EventResponse {
+        EventResponse::IGNORED
+    }
+
     /// Called when this control receives keyboard focus.
     fn handle_focus(&mut self) {
         self.base_mut().focused = true;
@@ -781,11 +1029,63 @@ pub trait Control {
 
 // ── Tree utilities ──────────────────────────────────────────────────
 
+/// `children` sorted bottom-to-top by z_index (stable, so equal z_index keeps
+/// insertion order).  Rendering walks this forwards, hit-testing backwards.
+pub fn paint_order(controls: &[Box<dyn Control>], children: &[ControlId]) -> Vec<ControlId> {
+    let mut keyed: Vec<(i32, ControlId)> = children.iter()
+        .map(|&cid| (find_idx(controls, cid).map_or(0, |i| controls[i].base().z_index), cid))
+        .collect();
+    if keyed.iter().any(|&(z, _)| z != 0) {
+        keyed.sort_by_key(|&(z, _)| z);
+    }
+    keyed.into_iter().map(|(_, cid)| cid).collect()
+}
+
+/// Invoke control `id`'s callback for `event_type` right away.
+///
+/// For events raised outside event dispatch — e.g. from a control's own
+/// timer callback, which already runs in the deferred-callback phase.
+pub fn invoke_event_callback(id: ControlId, event_type: u32) {
+    let slot = crate::state().controls.iter()
+        .find(|c| c.id() == id)
+        .and_then(|c| c.get_event_callback(event_type));
+    if let Some(slot) = slot {
+        (slot.cb)(id, event_type, slot.userdata);
+    }
+}
+
 /// Find a control by ID. Returns index in the slice.
 pub fn find_idx(controls: &[Box<dyn Control>], id: ControlId) -> Option<usize> {
     controls.iter().position(|c| c.id() == id)
 }
 
+/// Effective cursor shape over control `id`: its own or built-in cursor,
+/// else the nearest ancestor's, else CURSOR_ARROW.
+pub fn resolve_cursor(controls: &[Box<dyn Control>], id: ControlId) -> u32 {
+    let mut cur = id;
+    loop {
+        let Some(idx) = find_idx(controls, cur) else { return CURSOR_ARROW };
+        let c = &controls[idx];
+        let shape = if c.base().cursor != CURSOR_DEFAULT { c.base().cursor } else { c.default_cursor() };
+        if shape != CURSOR_DEFAULT { return shape; }
+        let parent = c.parent_id();
+        if parent == 0 || parent == cur { return CURSOR_ARROW; }
+        cur = parent;
+    }
+}
+
+/// Whether `id` is `ancestor` or one of its descendants.
+pub fn is_within(controls: &[Box<dyn Control>], id: ControlId, ancestor: ControlId) -> bool {
+    let mut cur = id;
+    loop {
+        if cur == ancestor { return true; }
+        let Some(idx) = find_idx(controls, cur) else { return false };
+        let parent = controls[idx].parent_id();
+        if parent == 0 || parent == cur { return false; }
+        cur = parent;
+    }
+}
+
 /// Hit-test: find the deepest visible interactive control under (px, py).
 /// Coordinates are in window-local space.
 pub fn hit_test(
@@ -829,8 +1129,8 @@ pub fn hit_test(
     if controls[idx].kind() == ControlKind::Expander && b.state == 0 {
         // Collapsed — no children are clickable
     } else {
-        // Check children in reverse order (topmost first)
-        let children: Vec<ControlId> = b.children.to_vec();
+        // Check children in reverse paint order (topmost first)
+        let children = paint_order(controls, &b.children);
         for &child_id in children.iter().rev() {
             if let Some(hit) = hit_test(controls, child_id, px, py, abs_x, child_abs_y) {
                 return Some(hit);
@@ -889,7 +1189,7 @@ pub fn hit_test_any(
     if controls[idx].kind() == ControlKind::Expander && b.state == 0 {
         // Collapsed — skip children
     } else {
-        let children: Vec<ControlId> = b.children.to_vec();
+        let children = paint_order(controls, &b.children);
         for &child_id in children.iter().rev() {
             if let Some(hit) = hit_test_any(controls, child_id, px, py, abs_x, child_abs_y) {
                 return Some(hit);
@@ -0,0 +1,98 @@
+use crate::control::{Control, ControlBase, ControlKind, ControlId};
+
+/// Repaint interval of the rotation (~30 fps).
+const ANIM_INTERVAL_MS: u32 = 33;
+/// Duration of one full rotation.
+const ROTATION_MS: u32 = 1000;
+/// Length of the rotating arc (tenths of a degree).
+const ARC_SWEEP: u32 = 1000;
+
+/// Indeterminate busy indicator: an arc rotating around a faint track,
+/// repainted by a timer while running. Spins from creation; a stopped
+/// spinner draws nothing. `base.color` overrides the arc color
+/// (0 = theme accent).
+pub struct ActivitySpinner {
+    pub(crate) base: ControlBase,
+    /// Ring width in logical pixels (0 = proportional to the size).
+    pub(crate) thickness: u32,
+    /// Animation timer while running (0 = stopped).
+    pub(crate) timer_id: u32,
+}
+
+impl ActivitySpinner {
+    pub fn new(base: ControlBase) -> Self {
+        let mut s = Self { base, thickness: 0, timer_id: 0 };
+        s.set_running(true);
+        s
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.timer_id != 0
+    }
+
+    /// Start or stop the rotation timer.
+    pub fn set_running(&mut self, running: bool) {
+        if running == self.is_running() {
+            return;
+        }
+        let st = crate::state();
+        if running {
+            self.timer_id = st.timers.set_timer(ANIM_INTERVAL_MS, spinner_tick, self.base.id as u64);
+        } else {
+            st.timers.kill_timer(self.timer_id);
+            self.timer_id = 0;
+        }
+        self.base.mark_dirty();
+    }
+}
+
+impl Control for ActivitySpinner {
+    fn base(&self) -> &ControlBase { &self.base }
+    fn base_mut(&mut self) -> &mut ControlBase { &mut self.base }
+    fn kind(&self) -> ControlKind { ControlKind::ActivitySpinner }
+
+    fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
+        if !self.is_running() {
+            return;
+        }
+        let b = self.base();
+        let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
+        let tc = crate::theme::colors();
+
+        let d = p.w.min(p.h);
+        let x = p.x + ((p.w - d) / 2) as i32;
+        let y = p.y + ((p.h - d) / 2) as i32;
+        let t = super::circular_progress::ring_thickness(self.thickness, d);
+        let color = if b.color != 0 { b.color } else { tc.accent };
+
+        crate::draw::draw_arc(surface, x, y, d, t, 0, crate::draw::ARC_TURN, crate::theme::with_alpha(color, 48));
+        let phase = crate::syscall::uptime_ms() % ROTATION_MS;
+        let start = crate::draw::ARC_TURN * phase / ROTATION_MS;
+        crate::draw::draw_arc(surface, x, y, d, t, start, ARC_SWEEP, color);
+    }
+}
+
+/// Animation tick (userdata = control ID). Stops itself once the control
+/// is gone or has been stopped; hidden spinners skip the repaint.
+extern "C" fn spinner_tick(timer_id: ControlId, _event_type: u32, userdata: u64) {
+    let st = crate::state();
+    let id = userdata as ControlId;
+    let running = match st.controls.iter_mut().find(|c| c.id() == id) {
+        Some(ctrl) if ctrl.kind() == ControlKind::ActivitySpinner => {
+            let raw: *mut dyn Control = &mut **ctrl;
+            let sp = unsafe { &mut *(raw as *mut ActivitySpinner) };
+            if sp.timer_id == timer_id {
+                if sp.base.visible {
+                    sp.base.mark_dirty();
+                }
+                true
+            } else {
+                false
+            }
+        }
+        _ => false,
+    };
+    if !running {
+        st.timers.kill_timer(timer_id);
+    }
+}
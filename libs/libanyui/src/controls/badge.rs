@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 use crate::control::{Control, ControlBase, TextControlBase, ControlKind};
 
 pub struct Badge {
@@ -23,3 +24,68 @@ impl Control for Badge {
         crate::draw::fill_rounded_rect(surface, p.x, p.y, p.w, p.h, p.h / 2, bg);
     }
 }
+
+// ── Badge overlay (attached to another control) ─────────────────────
+
+/// Corner of the host control a badge overlay is anchored to.
+pub const CORNER_TOP_RIGHT: u32 = 0;
+pub const CORNER_TOP_LEFT: u32 = 1;
+pub const CORNER_BOTTOM_RIGHT: u32 = 2;
+pub const CORNER_BOTTOM_LEFT: u32 = 3;
+
+/// Overlay height and font size in logical pixels.
+const OVERLAY_H: u32 = 16;
+const OVERLAY_FONT_SIZE: u16 = 10;
+/// Horizontal text padding inside the pill.
+const OVERLAY_PAD: u32 = 5;
+
+/// A small pill with text drawn over a corner of any control
+/// (`anyui_set_badge`). Rendered after the control's children, inside the
+/// control's bounds, so it repaints whenever the control does.
+pub struct BadgeOverlay {
+    pub text: Vec<u8>,
+    /// Pill color (0 = theme badge red).
+    pub color: u32,
+    /// One of the `CORNER_*` constants.
+    pub corner: u32,
+}
+
+impl BadgeOverlay {
+    /// Overlay text for a numeric count (counts above 99 show as "99+").
+    pub fn count_text(count: u32) -> Vec<u8> {
+        if count > 99 {
+            return Vec::from(&b"99+"[..]);
+        }
+        let mut text = Vec::new();
+        if count >= 10 {
+            text.push(b'0' + (count / 10) as u8);
+        }
+        text.push(b'0' + (count % 10) as u8);
+        text
+    }
+
+    /// Draw over the host control at logical position `(x, y)`, size `w`×`h`.
+    pub fn render(&self, surface: &crate::draw::Surface, x: i32, y: i32, w: u32, h: u32) {
+        let tc = crate::theme::colors();
+        let fs = crate::draw::scale_font(OVERLAY_FONT_SIZE);
+        let (tw, th) = crate::draw::text_size_at(&self.text, fs);
+        let ph = crate::theme::scale(OVERLAY_H);
+        let pw = (tw + crate::theme::scale(OVERLAY_PAD) * 2).max(ph);
+
+        let p = crate::draw::scale_bounds(0, 0, x, y, w, h);
+        let (right, bottom) = match self.corner {
+            CORNER_TOP_LEFT => (false, false),
+            CORNER_BOTTOM_RIGHT => (true, true),
+            CORNER_BOTTOM_LEFT => (false, true),
+            _ => (true, false),
+        };
+        let bx = if right { (p.x + p.w as i32 - pw as i32).max(p.x) } else { p.x };
+        let by = if bottom { (p.y + p.h as i32 - ph as i32).max(p.y) } else { p.y };
+
+        let bg = if self.color != 0 { self.color } else { tc.badge_red };
+        crate::draw::fill_rounded_rect(surface, bx, by, pw, ph, ph / 2, bg);
+        let tx = bx + (pw as i32 - tw as i32) / 2;
+        let ty = by + (ph as i32 - th as i32) / 2;
+        crate::draw::draw_text_sized(surface, tx, ty, 0xFFFFFFFF, &self.text, fs);
+    }
+}
@@ -16,6 +16,11 @@ impl Control for Button {
     fn text_base_mut(&mut self) -> Option<&mut crate::control::TextControlBase> { Some(&mut self.text_base) }
     fn kind(&self) -> ControlKind { ControlKind::Button }
 
+    fn baseline(&self) -> Option<i32> {
+        let fs = self.text_base.text_style.font_size;
+        Some((self.text_base.base.h as i32 - fs as i32) / 2 + crate::draw::text_ascent(fs))
+    }
+
     fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
         let b = &self.text_base.base;
         let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
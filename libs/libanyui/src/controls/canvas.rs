@@ -5,11 +5,18 @@
 //! The buffer is blitted to the window's SHM surface during rendering.
 //!
 //! When `interactive` is true, mouse move events are tracked and fire
-//! EVENT_CHANGE callbacks, enabling drag-to-draw behavior.
+//! EVENT_CHANGE callbacks, enabling drag-to-draw behavior. Pen pressure
+//! and tilt of the last sample are kept in `last_pointer`.
+//!
+//! `invalidate_rect` repaints only part of the canvas. With double buffering
+//! enabled, drawing goes to `pixels` (the back buffer) while the window shows
+//! a separate front copy; `end_paint` / `invalidate_rect` copy the changed
+//! region to the front, so a clear-and-redraw never appears half done.
 
 use alloc::vec;
 use alloc::vec::Vec;
 use crate::control::{Control, ControlBase, ControlKind, EventResponse};
+use crate::pointer::PointerEvent;
 
 pub struct Canvas {
     pub(crate) base: ControlBase,
@@ -21,6 +28,16 @@ pub struct Canvas {
     pub mouse_button: u32,
     /// When true, handle_mouse_move fires EVENT_CHANGE for drag-drawing.
     pub interactive: bool,
+    /// Last pointer sample over the canvas (local coordinates).
+    pub last_pointer: PointerEvent,
+    /// Displayed copy of `pixels` while double buffering is on (empty otherwise).
+    front: Vec<u32>,
+    /// Inside `begin_paint()` / `end_paint()`.
+    painting: bool,
+    /// Region invalidated since `begin_paint()`; `None` with `paint_full`
+    /// unset means nothing was invalidated explicitly.
+    paint_rect: Option<(i32, i32, u32, u32)>,
+    paint_full: bool,
 }
 
 impl Canvas {
@@ -33,7 +50,119 @@ impl Canvas {
             last_mouse_y: 0,
             mouse_button: 0,
             interactive: false,
+            last_pointer: PointerEvent::default(),
+            front: Vec::new(),
+            painting: false,
+            paint_rect: None,
+            paint_full: false,
+        }
+    }
+
+    // ── Buffering and invalidation ───────────────────────────────────
+
+    pub fn double_buffered(&self) -> bool {
+        !self.front.is_empty()
+    }
+
+    /// Enable or disable the front buffer.
+    pub fn set_double_buffered(&mut self, enabled: bool) {
+        if enabled == self.double_buffered() {
+            return;
+        }
+        self.front = if enabled { self.pixels.clone() } else { Vec::new() };
+        self.base.mark_dirty();
+    }
+
+    /// Start an atomic update: nothing drawn becomes visible until `end_paint()`.
+    pub fn begin_paint(&mut self) {
+        self.painting = true;
+        self.paint_rect = None;
+        self.paint_full = false;
+    }
+
+    /// Finish an atomic update and show it: the regions passed to
+    /// `invalidate_rect()` since `begin_paint()`, or the whole canvas if
+    /// there were none.
+    pub fn end_paint(&mut self) {
+        if !self.painting {
+            return;
+        }
+        self.painting = false;
+        match self.paint_rect.take() {
+            Some((x, y, w, h)) if !self.paint_full => self.present(Some((x, y, w, h))),
+            _ => self.present(None),
         }
+        self.paint_full = false;
+    }
+
+    /// Show the pixels in `(x, y, w, h)` (canvas coordinates). Inside a
+    /// paint the region is remembered for `end_paint()`.
+    pub fn invalidate_rect(&mut self, x: i32, y: i32, w: u32, h: u32) {
+        let r = match self.clip(x, y, w, h) {
+            Some(r) => r,
+            None => return,
+        };
+        if self.painting {
+            self.paint_rect = Some(match self.paint_rect {
+                Some((px, py, pw, ph)) => {
+                    let x0 = px.min(r.0);
+                    let y0 = py.min(r.1);
+                    let x1 = (px + pw as i32).max(r.0 + r.2 as i32);
+                    let y1 = (py + ph as i32).max(r.1 + r.3 as i32);
+                    (x0, y0, (x1 - x0) as u32, (y1 - y0) as u32)
+                }
+                None => r,
+            });
+        } else {
+            self.present(Some(r));
+        }
+    }
+
+    /// The whole canvas changed (clear, bulk copy): show it, or defer to
+    /// `end_paint()` inside a paint.
+    pub fn invalidate_all(&mut self) {
+        if self.painting {
+            self.paint_full = true;
+        } else {
+            self.present(None);
+        }
+    }
+
+    /// Copy `rect` (or everything) to the front buffer and repaint it.
+    fn present(&mut self, rect: Option<(i32, i32, u32, u32)>) {
+        let stride = self.stride() as usize;
+        match rect {
+            Some((x, y, w, h)) => {
+                if !self.front.is_empty() {
+                    for row in y as usize..(y as usize + h as usize) {
+                        let start = row * stride + x as usize;
+                        let end = start + w as usize;
+                        if end <= self.pixels.len() && end <= self.front.len() {
+                            self.front[start..end].copy_from_slice(&self.pixels[start..end]);
+                        }
+                    }
+                }
+                self.base.mark_dirty_rect(x, y, w, h);
+            }
+            None => {
+                if !self.front.is_empty() {
+                    self.front.copy_from_slice(&self.pixels);
+                }
+                self.base.mark_dirty();
+            }
+        }
+    }
+
+    /// Clip a rectangle to the canvas bounds.
+    fn clip(&self, x: i32, y: i32, w: u32, h: u32) -> Option<(i32, i32, u32, u32)> {
+        let x0 = x.max(0);
+        let y0 = y.max(0);
+        let x1 = (x + w as i32).min(self.stride() as i32);
+        let y1 = (y + h as i32).min(self.height() as i32);
+        if x1 <= x0 || y1 <= y0 {
+            return None;
+        }
+        Some((x0, y0, (x1 - x0) as u32, (y1 - y0) as u32))
     }
 
     // ── Drawing primitives ───────────────────────────────────────────
@@ -339,7 +468,7 @@ impl Canvas {
     pub fn copy_pixels_from(&mut self, src: &[u32]) {
         let len = src.len().min(self.pixels.len());
         self.pixels[..len].copy_from_slice(&src[..len]);
-        self.base.mark_dirty();
+        self.invalidate_all();
     }
 
     /// Copy canvas pixel data into a destination slice. Returns count copied.
@@ -367,6 +496,10 @@ impl Control for Canvas {
     fn base_mut(&mut self) -> &mut ControlBase { &mut self.base }
     fn kind(&self) -> ControlKind { ControlKind::Canvas }
 
+    fn pixel_bytes(&self) -> usize {
+        (self.pixels.capacity() + self.front.capacity()) * 4
+    }
+
     fn set_size(&mut self, w: u32, h: u32) {
         let b = self.base_mut();
         if b.w != w || b.h != h {
@@ -377,21 +510,25 @@ impl Control for Canvas {
             let expected = (w * h) as usize;
             if expected > 0 && self.pixels.len() != expected {
                 self.pixels.resize(expected, 0xFF000000);
+                if !self.front.is_empty() {
+                    self.front.clone_from(&self.pixels);
+                }
             }
         }
     }
 
     fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
         if self.pixels.is_empty() { return; }
+        let pixels = if self.front.is_empty() { &self.pixels } else { &self.front };
         let b = self.base();
         let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
         // Canvas pixel buffer is in logical resolution. At 1x the sizes
         // match and we do a fast 1:1 copy. At higher DPI we nearest-neighbor
         // upscale from logical (b.w × b.h) to physical (p.w × p.h).
         if b.w == p.w && b.h == p.h {
-            crate::draw::blit_buffer(surface, p.x, p.y, b.w, b.h, &self.pixels);
+            crate::draw::blit_buffer(surface, p.x, p.y, b.w, b.h, pixels);
         } else {
-            crate::draw::blit_buffer_scaled(surface, p.x, p.y, p.w, p.h, b.w, b.h, &self.pixels);
+            crate::draw::blit_buffer_scaled(surface, p.x, p.y, p.w, p.h, b.w, b.h, pixels);
         }
     }
 
@@ -427,4 +564,9 @@ impl Control for Canvas {
     fn handle_click(&mut self, _lx: i32, _ly: i32, _button: u32) -> EventResponse {
         EventResponse::CLICK
     }
+
+    fn handle_pointer(&mut self, ev: &PointerEvent) -> EventResponse {
+        self.last_pointer = *ev;
+        EventResponse::IGNORED
+    }
 }
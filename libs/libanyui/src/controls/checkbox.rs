@@ -15,6 +15,10 @@ impl Control for Checkbox {
     fn text_base_mut(&mut self) -> Option<&mut crate::control::TextControlBase> { Some(&mut self.text_base) }
     fn kind(&self) -> ControlKind { ControlKind::Checkbox }
 
+    fn baseline(&self) -> Option<i32> {
+        Some(2 + crate::draw::text_ascent(self.text_base.text_style.font_size))
+    }
+
     fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
         let b = &self.text_base.base;
         let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
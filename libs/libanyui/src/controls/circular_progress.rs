@@ -0,0 +1,70 @@
+use crate::control::{Control, ControlBase, ControlKind};
+
+/// Controls smaller than this (logical px) omit the percentage text.
+const MIN_TEXT_SIZE: u32 = 32;
+
+/// Determinate progress ring: `state` (0-100) is drawn as a clockwise arc
+/// starting at 12 o'clock, with the percentage in the middle.
+/// `base.color` overrides the fill color (0 = theme accent).
+pub struct CircularProgress {
+    pub(crate) base: ControlBase,
+    /// Ring width in logical pixels (0 = proportional to the size).
+    pub(crate) thickness: u32,
+    /// Draw the percentage text (only when the ring is large enough).
+    pub(crate) show_text: bool,
+    /// Track color (0 = theme control background).
+    pub(crate) track_color: u32,
+}
+
+impl CircularProgress {
+    pub fn new(base: ControlBase) -> Self {
+        Self { base, thickness: 0, show_text: true, track_color: 0 }
+    }
+}
+
+/// Physical ring width for a ring of diameter `d` (physical px).
+pub(crate) fn ring_thickness(logical: u32, d: u32) -> u32 {
+    if logical > 0 { crate::theme::scale(logical) } else { (d / 10).max(2) }
+}
+
+impl Control for CircularProgress {
+    fn base(&self) -> &ControlBase { &self.base }
+    fn base_mut(&mut self) -> &mut ControlBase { &mut self.base }
+    fn kind(&self) -> ControlKind { ControlKind::CircularProgress }
+
+    fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
+        let b = self.base();
+        let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
+        let tc = crate::theme::colors();
+
+        // Square ring centered in the bounds
+        let d = p.w.min(p.h);
+        let x = p.x + ((p.w - d) / 2) as i32;
+        let y = p.y + ((p.h - d) / 2) as i32;
+        let t = ring_thickness(self.thickness, d);
+
+        let track = if self.track_color != 0 { self.track_color } else { tc.control_bg };
+        crate::draw::draw_arc(surface, x, y, d, t, 0, crate::draw::ARC_TURN, track);
+
+        let val = b.state.min(100);
+        let fill = if b.color != 0 { b.color } else { tc.accent };
+        crate::draw::draw_arc(surface, x, y, d, t, 0, crate::draw::ARC_TURN * val / 100, fill);
+
+        if self.show_text && b.w.min(b.h) >= MIN_TEXT_SIZE {
+            let mut buf = [0u8; 4];
+            let n = if val >= 100 { 3 } else if val >= 10 { 2 } else { 1 };
+            let mut v = val;
+            for i in (0..n).rev() {
+                buf[i] = b'0' + (v % 10) as u8;
+                v /= 10;
+            }
+            buf[n] = b'%';
+            let text = &buf[..n + 1];
+            let size = crate::draw::scale_font((b.w.min(b.h) / 4).clamp(9, 24) as u16);
+            let (tw, th) = crate::draw::text_size_at(text, size);
+            let tx = x + (d as i32 - tw as i32) / 2;
+            let ty = y + (d as i32 - th as i32) / 2;
+            crate::draw::draw_text_sized(surface, tx, ty, tc.text, text, size);
+        }
+    }
+}
@@ -44,6 +44,13 @@ impl ContextMenu {
         self.text_base.base.h = total_h.max(MENU_PAD * 2) as u32;
     }
 
+    /// Highlight item `index` (u32::MAX = none), e.g. for keyboard navigation
+    /// driven by the control that owns the popup.
+    pub(crate) fn set_hovered_item(&mut self, index: u32) {
+        self.hovered_item = index;
+        self.text_base.base.mark_dirty();
+    }
+
     /// Map a local Y coordinate to an item index, returning None for dividers or out-of-bounds.
     fn item_at_y(&self, ly: i32) -> Option<u32> {
         let items: alloc::vec::Vec<&[u8]> = self.text_base.text.split(|&b| b == b'|').collect();
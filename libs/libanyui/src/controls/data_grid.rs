@@ -200,15 +200,7 @@ impl DataGrid {
         self.row_count = 0;
         let col_count = self.columns.len().max(1);
         for row_data in data.split(|&b| b == 0x1E) {
-            let cells: Vec<&[u8]> = row_data.split(|&b| b == 0x1F).collect();
-            for (ci, cell) in cells.iter().enumerate() {
-                if ci >= col_count { break; }
-                self.cell_data.push(cell.to_vec());
-            }
-            // Pad with empty cells if row has fewer columns
-            for _ in cells.len()..col_count {
-                self.cell_data.push(Vec::new());
-            }
+            self.cell_data.extend(decode_row(row_data, col_count));
             self.row_count += 1;
         }
         self.clamp_scroll();
@@ -245,6 +237,77 @@ impl DataGrid {
         }
     }
 
+    /// Insert a row at `row` (clamped to the row count). `data` holds the
+    /// cells separated by 0x1F, as in one row of `set_data_from_encoded`.
+    pub fn insert_row(&mut self, row: usize, data: &[u8]) {
+        let row = row.min(self.row_count);
+        let col_count = self.columns.len().max(1);
+        let at = row * col_count;
+        let cells = decode_row(data, col_count);
+        self.cell_data.splice(at..at, cells);
+        if self.cell_colors.len() > at {
+            self.cell_colors.splice(at..at, core::iter::repeat(0).take(col_count));
+        }
+        if self.cell_bg_colors.len() > at {
+            self.cell_bg_colors.splice(at..at, core::iter::repeat(0).take(col_count));
+        }
+        if self.char_color_offsets.len() > at {
+            self.char_color_offsets.splice(at..at, core::iter::repeat(u32::MAX).take(col_count));
+        }
+        if self.cell_icons.len() > at {
+            self.cell_icons.splice(at..at, (0..col_count).map(|_| None));
+        }
+        if self.minimap_colors.len() > row {
+            self.minimap_colors.insert(row, 0);
+        }
+        self.row_count += 1;
+        self.shift_selection(row, true);
+        self.ensure_selection_bits();
+        self.rebuild_sort();
+        self.base.mark_dirty();
+    }
+
+    /// Remove row `row`; rows below it move up by one.
+    pub fn remove_row(&mut self, row: usize) {
+        if row >= self.row_count { return; }
+        let col_count = self.columns.len().max(1);
+        let range = row * col_count..(row + 1) * col_count;
+        self.cell_data.drain(range.clone());
+        for v in [&mut self.cell_colors, &mut self.cell_bg_colors, &mut self.char_color_offsets] {
+            if v.len() >= range.end {
+                v.drain(range.clone());
+            }
+        }
+        if self.cell_icons.len() >= range.end {
+            self.cell_icons.drain(range);
+        }
+        if row < self.minimap_colors.len() {
+            self.minimap_colors.remove(row);
+        }
+        self.shift_selection(row, false);
+        self.row_count -= 1;
+        self.ensure_selection_bits();
+        if self.hovered_row.map_or(false, |h| h >= self.row_count) {
+            self.hovered_row = None;
+        }
+        self.clamp_scroll();
+        self.rebuild_sort();
+        self.base.mark_dirty();
+    }
+
+    /// Replace all cells of row `row` (0x1F-separated, see `insert_row`).
+    pub fn set_row(&mut self, row: usize, data: &[u8]) {
+        if row >= self.row_count { return; }
+        let col_count = self.columns.len().max(1);
+        let at = row * col_count;
+        let cells = decode_row(data, col_count);
+        if self.cell_data[at..at + col_count] != cells[..] {
+            self.cell_data.splice(at..at + col_count, cells);
+            self.rebuild_sort();
+            self.base.mark_dirty();
+        }
+    }
+
     pub fn get_cell(&self, row: usize, col: usize) -> &[u8] {
         let col_count = self.columns.len().max(1);
         let idx = row * col_count + col;
@@ -361,6 +424,33 @@ impl DataGrid {
         }
     }
 
+    /// Move selection bits and the anchor for a row inserted at (or removed
+    /// from) `row`.
+    fn shift_selection(&mut self, row: usize, inserted: bool) {
+        let old_count = if inserted { self.row_count - 1 } else { self.row_count };
+        let selected: Vec<usize> = (0..old_count).filter(|&r| self.is_row_selected(r)).collect();
+        self.selected_rows.fill(0);
+        self.ensure_selection_bits();
+        for r in selected {
+            let new_r = if r < row {
+                r
+            } else if inserted {
+                r + 1
+            } else if r == row {
+                continue;
+            } else {
+                r - 1
+            };
+            self.set_row_selected(new_r, true);
+        }
+        self.anchor_row = match self.anchor_row {
+            Some(a) if a < row => Some(a),
+            Some(a) if inserted => Some(a + 1),
+            Some(a) if a > row => Some(a - 1),
+            _ => None,
+        };
+    }
+
     pub(crate) fn clear_selection(&mut self) {
         self.selected_rows.fill(0);
     }
@@ -603,7 +693,9 @@ impl Control for DataGrid {
                                 } else {
                                     default_color
                                 };
-                                let mut span_end = span_start + 1;
+                                // Spans break only between clusters, so an emoji
+                                // is never split across two draw calls.
+                                let mut span_end = crate::cluster::next_boundary(text, span_start);
                                 while span_end < text_len {
                                     let next_idx = base_off + span_end;
                                     let next_color = if next_idx < self.char_colors.len() && self.char_colors[next_idx] != 0 {
@@ -612,7 +704,7 @@ impl Control for DataGrid {
                                         default_color
                                     };
                                     if next_color != span_color { break; }
-                                    span_end += 1;
+                                    span_end = crate::cluster::next_boundary(text, span_end);
                                 }
                                 let span = &text[span_start..span_end];
                                 crate::draw::draw_text_sized(&cell_clip, cx, text_y, span_color, span, fs);
@@ -920,7 +1012,11 @@ impl Control for DataGrid {
         let content_h = self.row_count as i32 * self.row_height as i32;
         let viewport_h = self.base.h as i32 - self.header_height as i32;
         let max_scroll = (content_h - viewport_h).max(0);
-        self.scroll_y = (self.scroll_y - delta * 20).max(0).min(max_scroll);
+        let y = (self.scroll_y - delta * 20).max(0).min(max_scroll);
+        if y == self.scroll_y {
+            return EventResponse::IGNORED;
+        }
+        self.scroll_y = y;
         self.base.mark_dirty();
         EventResponse::CONSUMED
     }
@@ -1012,6 +1108,17 @@ fn parse_u32(s: &[u8]) -> Option<u32> {
 /// gets `is_number=false` and sorts after all numbers. Handles optional
 /// leading whitespace, negative sign, and decimal point. Trailing suffixes
 /// (e.g. "KB", "%") are ignored.
+/// Split one encoded row (cells separated by 0x1F) into exactly `col_count`
+/// cells, padding with empty cells if the row has fewer columns.
+fn decode_row(data: &[u8], col_count: usize) -> Vec<Vec<u8>> {
+    let mut cells: Vec<Vec<u8>> = data.split(|&b| b == 0x1F)
+        .take(col_count)
+        .map(|c| c.to_vec())
+        .collect();
+    cells.resize(col_count, Vec::new());
+    cells
+}
+
 fn parse_sort_key(s: &[u8]) -> (bool, i64, i64) {
     let mut i = 0;
     // Skip leading whitespace
@@ -50,6 +50,11 @@ impl Control for DropDown {
     fn text_base_mut(&mut self) -> Option<&mut TextControlBase> { Some(&mut self.text_base) }
     fn kind(&self) -> ControlKind { ControlKind::DropDown }
 
+    fn baseline(&self) -> Option<i32> {
+        let fs = if self.text_base.text_style.font_size > 0 { self.text_base.text_style.font_size } else { 13 };
+        Some((self.text_base.base.h as i32 - fs as i32) / 2 + crate::draw::text_ascent(fs))
+    }
+
     fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
         let b = &self.text_base.base;
         let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
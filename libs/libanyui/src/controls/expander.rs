@@ -1,22 +1,84 @@
+//! Expander — collapsible section with a clickable header.
+//!
+//! Expanding and collapsing animate the height between the header and the
+//! expanded height. `base.state` tracks whether the body (children) is laid
+//! out and drawn, which stays set until a collapse animation has finished;
+//! the expanded flag reported to apps is `expanded`.
+
+use alloc::boxed::Box;
 use alloc::vec::Vec;
-use crate::control::{Control, ControlBase, TextControlBase, ControlKind, ChildLayout, EventResponse};
+use crate::control::{Control, ControlBase, ControlId, TextControlBase, ControlKind, ChildLayout, EventResponse};
 
 pub const HEADER_HEIGHT: u32 = 32;
+/// Repaint interval of the expand/collapse animation.
+const ANIM_INTERVAL_MS: u32 = 16;
 
 pub struct Expander {
     pub(crate) text_base: TextControlBase,
     /// Height when expanded (saved on collapse, restored on expand).
     expanded_height: u32,
+    /// Expanded (target) state; `base.state` follows once the animation ends.
+    expanded: bool,
+    /// Height animation timer (0 = none).
+    timer_id: u32,
 }
 
 impl Expander {
     pub fn new(text_base: TextControlBase) -> Self {
         // Default to expanded (state = 1)
         let h = text_base.base.h;
-        let mut e = Self { text_base, expanded_height: h };
+        let mut e = Self { text_base, expanded_height: h, expanded: true, timer_id: 0 };
         e.text_base.base.state = 1;
         e
     }
+
+    /// Whether the height is being animated.
+    pub fn is_animating(&self) -> bool {
+        self.timer_id != 0
+    }
+
+    /// Expand or collapse, animating the height.
+    fn set_expanded(&mut self, expanded: bool) {
+        if self.expanded == expanded {
+            return;
+        }
+        if !expanded && !self.is_animating() {
+            // Collapsing: save the current height to restore later.
+            self.expanded_height = self.text_base.base.h;
+        }
+        self.expanded = expanded;
+        // The body is shown for the whole animation, clipped to the height.
+        self.text_base.base.state = 1;
+        if self.timer_id == 0 {
+            self.timer_id = crate::state().timers.set_timer(ANIM_INTERVAL_MS, expander_tick, self.text_base.base.id as u64);
+        }
+        self.text_base.base.mark_dirty();
+        crate::mark_needs_layout();
+    }
+
+    fn target_height(&self) -> u32 {
+        if self.expanded { self.expanded_height.max(HEADER_HEIGHT) } else { HEADER_HEIGHT }
+    }
+
+    /// Advance the height animation by one frame. Returns false once the
+    /// target height has been reached.
+    fn step(&mut self) -> bool {
+        let target = self.target_height() as i32;
+        let h = self.text_base.base.h as i32;
+        // Cover a third of the remaining distance per frame (ease-out).
+        let diff = target - h;
+        let step = if diff.abs() <= 2 { diff } else { diff / 3 };
+        self.text_base.base.h = (h + step) as u32;
+        self.text_base.base.mark_dirty();
+        crate::mark_needs_layout();
+        if self.text_base.base.h as i32 != target {
+            return true;
+        }
+        if !self.expanded {
+            self.text_base.base.state = 0;
+        }
+        false
+    }
 }
 
 impl Control for Expander {
@@ -31,7 +93,8 @@ impl Control for Expander {
         let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
         let (x, y, w) = (p.x, p.y, p.w);
         let tc = crate::theme::colors();
-        let expanded = b.state != 0;
+        let expanded = self.expanded;
+        let body_shown = b.state != 0;
         let hdr_h = crate::theme::scale(HEADER_HEIGHT);
 
         // Header background
@@ -64,7 +127,7 @@ impl Control for Expander {
         }
 
         // Border
-        if expanded {
+        if body_shown {
             crate::draw::fill_rect(surface, x, y + hdr_h as i32 - 1, w, 1, tc.card_border);
         } else {
             crate::draw::draw_border(surface, x, y, w, hdr_h, tc.card_border);
@@ -72,35 +135,45 @@ impl Control for Expander {
     }
 
     fn set_size(&mut self, w: u32, h: u32) {
+        let animating = self.is_animating();
         let b = &mut self.text_base.base;
         if b.w != w || b.h != h {
             b.w = w;
             b.h = h;
             b.mark_dirty();
             // Track expanded height when in expanded state
-            if b.state != 0 {
+            if b.state != 0 && !animating {
                 self.expanded_height = h;
             }
         }
     }
 
+    /// The expanded flag, not whether the body is still visible.
+    fn state_val(&self) -> u32 {
+        self.expanded as u32
+    }
+
+    fn set_state(&mut self, s: u32) {
+        self.set_expanded(s != 0);
+    }
+
+    /// Auto-size: the body sits below the header. While collapsed or
+    /// animating, only the expanded height is updated.
+    fn fit_content_height(&mut self, content_bottom: i32) {
+        let full = HEADER_HEIGHT + (content_bottom + self.text_base.base.padding.bottom).max(0) as u32;
+        if self.expanded && !self.is_animating() {
+            let w = self.text_base.base.w;
+            self.set_size(w, full);
+        } else {
+            self.expanded_height = full;
+        }
+    }
+
     fn is_interactive(&self) -> bool { true }
 
     fn handle_click(&mut self, _lx: i32, ly: i32, _button: u32) -> EventResponse {
         if ly < HEADER_HEIGHT as i32 {
-            let expanded = self.text_base.base.state != 0;
-            if expanded {
-                // Collapsing: save current height, shrink to header only
-                self.expanded_height = self.text_base.base.h;
-                self.text_base.base.h = HEADER_HEIGHT;
-                self.text_base.base.state = 0;
-            } else {
-                // Expanding: restore saved height
-                self.text_base.base.h = self.expanded_height;
-                self.text_base.base.state = 1;
-            }
-            self.text_base.base.mark_dirty();
-            crate::mark_needs_layout();
+            self.set_expanded(!self.expanded);
             EventResponse::CHANGED
         } else {
             EventResponse::IGNORED
@@ -118,3 +191,43 @@ impl Control for Expander {
         None
     }
 }
+
+fn as_expander(ctrl: &mut Box<dyn Control>) -> Option<&mut Expander> {
+    if ctrl.kind() == ControlKind::Expander {
+        let raw: *mut dyn Control = &mut **ctrl;
+        Some(unsafe { &mut *(raw as *mut Expander) })
+    } else {
+        None
+    }
+}
+
+/// Whether the children of this control must be clipped to its bounds
+/// (an Expander in the middle of an expand/collapse animation).
+pub fn clips_children(ctrl: &Box<dyn Control>) -> bool {
+    if ctrl.kind() == ControlKind::Expander {
+        let raw: *const dyn Control = &**ctrl;
+        unsafe { &*(raw as *const Expander) }.is_animating()
+    } else {
+        false
+    }
+}
+
+/// Expand/collapse animation tick (userdata = control ID). Stops itself
+/// once the target height is reached.
+extern "C" fn expander_tick(timer_id: ControlId, _event_type: u32, userdata: u64) {
+    let st = crate::state();
+    let id = userdata as ControlId;
+    let animating = match st.controls.iter_mut().find(|c| c.id() == id).and_then(|c| as_expander(c)) {
+        Some(e) if e.timer_id == timer_id => {
+            let more = e.step();
+            if !more {
+                e.timer_id = 0;
+            }
+            more
+        }
+        _ => false,
+    };
+    if !animating {
+        st.timers.kill_timer(timer_id);
+    }
+}
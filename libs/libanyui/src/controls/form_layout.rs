@@ -0,0 +1,143 @@
+//! FormLayout — two-column container for label/field pairs.
+//!
+//! Children are taken in pairs: even children are labels (left column), odd
+//! children are their fields (right column). The label column is as wide as
+//! the widest label unless a fixed width is set; fields get the remaining
+//! width and keep their own height. Within a row the label is moved so its
+//! text baseline lines up with the field's (centred when the field has no
+//! text). The height always fits the rows, so forms nest inside GroupBoxes
+//! and Expanders without manual sizing.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use crate::control::{Control, ControlBase, ControlKind, ChildLayout, find_idx};
+
+/// Default gap between the label and field columns.
+const DEFAULT_COLUMN_GAP: u32 = 12;
+/// Default gap between rows.
+const DEFAULT_ROW_GAP: u32 = 8;
+
+pub struct FormLayout {
+    pub(crate) base: ControlBase,
+    /// Fixed label column width (0 = fit the widest label).
+    pub label_width: u32,
+    pub column_gap: u32,
+    pub row_gap: u32,
+}
+
+impl FormLayout {
+    pub fn new(base: ControlBase) -> Self {
+        Self { base, label_width: 0, column_gap: DEFAULT_COLUMN_GAP, row_gap: DEFAULT_ROW_GAP }
+    }
+
+    /// Natural width of a label (text extent plus horizontal padding and
+    /// margins), falling back to its current width for non-text controls.
+    fn natural_width(ctrl: &dyn Control) -> i32 {
+        let b = ctrl.base();
+        let w = match ctrl.text_base() {
+            Some(tb) => {
+                let longest = tb.text.split(|&c| c == b'\n')
+                    .map(|line| crate::draw::measure_text_ex(line, tb.text_style.font_id, tb.text_style.font_size).0)
+                    .max()
+                    .unwrap_or(0);
+                longest as i32 + b.padding.left + b.padding.right
+            }
+            None => b.w as i32,
+        };
+        w + b.margin.left + b.margin.right
+    }
+}
+
+impl Control for FormLayout {
+    fn base(&self) -> &ControlBase { &self.base }
+    fn base_mut(&mut self) -> &mut ControlBase { &mut self.base }
+    fn kind(&self) -> ControlKind { ControlKind::FormLayout }
+
+    fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
+        if self.base.color != 0 {
+            let b = self.base();
+            let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
+            crate::draw::fill_rect(surface, p.x, p.y, p.w, p.h, b.color);
+        }
+    }
+
+    fn layout_children(&self, controls: &[Box<dyn Control>]) -> Option<Vec<ChildLayout>> {
+        let pad = &self.base.padding;
+        let avail_w = self.base.w as i32 - pad.left - pad.right;
+
+        // (label, field) index pairs; rows whose field is hidden are skipped.
+        let children = &self.base.children;
+        let mut rows: Vec<(Option<usize>, usize)> = Vec::new();
+        for pair in children.chunks(2) {
+            let label = find_idx(controls, pair[0]).filter(|&i| controls[i].base().visible);
+            let field = match pair.get(1).and_then(|&id| find_idx(controls, id)) {
+                Some(i) if controls[i].base().visible => i,
+                _ => continue,
+            };
+            rows.push((label, field));
+        }
+
+        let label_col = if self.label_width > 0 {
+            self.label_width as i32
+        } else {
+            rows.iter()
+                .filter_map(|&(l, _)| l)
+                .map(|l| Self::natural_width(&*controls[l]))
+                .max()
+                .unwrap_or(0)
+                .min(avail_w / 2)
+        };
+        let field_x = pad.left + label_col + self.column_gap as i32;
+        let field_col = (avail_w - label_col - self.column_gap as i32).max(0);
+
+        let mut result = Vec::new();
+        let mut y = pad.top;
+        for &(label, field) in &rows {
+            let fb = controls[field].base();
+            let fm = fb.margin;
+            let field_h = fb.h as i32;
+            let field_top = fm.top;
+
+            // Label offset within the row: align baselines, else centre.
+            let (label_top, label_extent) = match label {
+                Some(l) => {
+                    let lb = controls[l].base();
+                    let lm = lb.margin;
+                    let lh = lb.h as i32;
+                    let top = match (controls[l].baseline(), controls[field].baseline()) {
+                        (Some(lbase), Some(fbase)) => field_top + fbase - lbase,
+                        _ => field_top + (field_h - lh) / 2,
+                    };
+                    (top, lh + lm.bottom)
+                }
+                None => (0, 0),
+            };
+            // A label reaching above the row pushes the whole row down.
+            let shift = (-label_top).max(0);
+            let row_h = (field_top + field_h + fm.bottom).max(label_top + label_extent) + shift;
+
+            if let Some(l) = label {
+                let lm = controls[l].base().margin;
+                let lw = (label_col - lm.left - lm.right).max(0) as u32;
+                result.push(ChildLayout {
+                    id: controls[l].id(),
+                    x: pad.left + lm.left,
+                    y: y + label_top + shift,
+                    w: Some(lw),
+                    h: None,
+                });
+            }
+            let fw = (field_col - fm.left - fm.right).max(0) as u32;
+            result.push(ChildLayout {
+                id: controls[field].id(),
+                x: field_x + fm.left,
+                y: y + field_top + shift,
+                w: Some(fw),
+                h: None,
+            });
+
+            y += row_h + self.row_gap as i32;
+        }
+        Some(result)
+    }
+}
@@ -1,5 +1,9 @@
 use crate::control::{Control, ControlBase, TextControlBase, ControlKind};
 
+/// Space kept below the lowest child when auto-sized, so content clears the
+/// bottom border.
+const CONTENT_BOTTOM_INSET: i32 = 10;
+
 pub struct GroupBox {
     pub(crate) text_base: TextControlBase,
 }
@@ -15,6 +19,13 @@ impl Control for GroupBox {
     fn text_base_mut(&mut self) -> Option<&mut crate::control::TextControlBase> { Some(&mut self.text_base) }
     fn kind(&self) -> ControlKind { ControlKind::GroupBox }
 
+    fn fit_content_height(&mut self, content_bottom: i32) {
+        let b = &self.text_base.base;
+        let h = (content_bottom + b.padding.bottom + CONTENT_BOTTOM_INSET).max(0) as u32;
+        let w = b.w;
+        self.set_size(w, h);
+    }
+
     fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
         let b = &self.text_base.base;
         let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
@@ -60,6 +60,8 @@ impl Control for IconButton {
     fn text_base_mut(&mut self) -> Option<&mut crate::control::TextControlBase> { Some(&mut self.text_base) }
     fn kind(&self) -> ControlKind { ControlKind::IconButton }
 
+    fn pixel_bytes(&self) -> usize { self.icon_pixels.capacity() * 4 }
+
     fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
         let b = &self.text_base.base;
         let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
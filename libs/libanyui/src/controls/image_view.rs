@@ -2,9 +2,21 @@
 //!
 //! The client-side library handles image file I/O and decoding via libimage.
 //! The server (this DLL) stores and blits the pre-decoded pixel buffer.
+//! `anyui_imageview_load_file` instead decodes on the image loader's worker
+//! thread (see `image_loader`); until the pixels arrive the placeholder is
+//! drawn with a spinner.
 
 use alloc::vec::Vec;
-use crate::control::{Control, ControlBase, ControlKind};
+use crate::control::{Control, ControlBase, ControlKind, ControlId};
+
+/// Spinner repaint interval while loading (~30 fps).
+const SPINNER_INTERVAL_MS: u32 = 33;
+/// Duration of one spinner rotation.
+const SPINNER_ROTATION_MS: u32 = 1000;
+/// Length of the spinner arc (tenths of a degree).
+const SPINNER_SWEEP: u32 = 1000;
+/// Largest spinner diameter in logical pixels.
+const SPINNER_MAX: u32 = 32;
 
 /// Scale mode for ImageView.
 pub const SCALE_NONE: u32 = 0;    // original size, top-left aligned
@@ -21,6 +33,15 @@ pub struct ImageView {
     pub(crate) img_h: u32,
     /// Scale mode: 0=None, 1=Fit, 2=Fill, 3=Stretch.
     pub(crate) scale_mode: u32,
+    /// A file load is in flight.
+    pub(crate) loading: bool,
+    /// Bumped by every load and pixel change; a load result only applies
+    /// if it still matches.
+    pub(crate) load_generation: u32,
+    /// `LOAD_ERR_*` code of the last failed load (0 = none).
+    pub(crate) load_error: u32,
+    /// Spinner repaint timer while loading (0 = none).
+    spinner_timer: u32,
 }
 
 impl ImageView {
@@ -31,15 +52,20 @@ impl ImageView {
             img_w: 0,
             img_h: 0,
             scale_mode: SCALE_FIT,
+            loading: false,
+            load_generation: 0,
+            load_error: 0,
+            spinner_timer: 0,
         }
     }
 
-    /// Set pixel data from decoded ARGB buffer.
+    /// Set pixel data from decoded ARGB buffer. Cancels a pending load.
     pub fn set_pixels(&mut self, data: &[u32], w: u32, h: u32) {
         let expected = (w as usize) * (h as usize);
         if data.len() < expected {
             return;
         }
+        self.end_load();
         self.pixels.clear();
         self.pixels.extend_from_slice(&data[..expected]);
         self.img_w = w;
@@ -47,13 +73,45 @@ impl ImageView {
         self.base.mark_dirty();
     }
 
-    /// Clear pixel data.
+    /// Clear pixel data. Cancels a pending load.
     pub fn clear(&mut self) {
+        self.end_load();
         self.pixels.clear();
         self.img_w = 0;
         self.img_h = 0;
         self.base.mark_dirty();
     }
+
+    /// Start loading an image file on the loader thread: drops the current
+    /// image and shows the spinner. Returns false if the load could not be
+    /// queued (the view is left empty).
+    pub fn load_file(&mut self, path: &[u8]) -> bool {
+        self.clear();
+        self.load_error = 0;
+        if !crate::image_loader::submit(self.base.id, self.load_generation, path) {
+            return false;
+        }
+        self.loading = true;
+        self.spinner_timer = crate::state().timers.set_timer(SPINNER_INTERVAL_MS, spinner_tick, self.base.id as u64);
+        true
+    }
+
+    /// Record a failed load; the placeholder stays.
+    pub(crate) fn fail_load(&mut self, error: u32) {
+        self.end_load();
+        self.load_error = error;
+        self.base.mark_dirty();
+    }
+
+    /// Leave the loading state; an outstanding result no longer applies.
+    fn end_load(&mut self) {
+        self.load_generation = self.load_generation.wrapping_add(1);
+        if self.loading {
+            self.loading = false;
+            crate::state().timers.kill_timer(self.spinner_timer);
+            self.spinner_timer = 0;
+        }
+    }
 }
 
 impl Control for ImageView {
@@ -61,6 +119,16 @@ impl Control for ImageView {
     fn base_mut(&mut self) -> &mut ControlBase { &mut self.base }
     fn kind(&self) -> ControlKind { ControlKind::ImageView }
 
+    fn pixel_bytes(&self) -> usize { self.pixels.capacity() * 4 }
+
+    /// `set_pixels` reuses the buffer, so a smaller image keeps the old
+    /// allocation until trimmed.
+    fn trim_caches(&mut self) -> usize {
+        let before = self.pixels.capacity();
+        self.pixels.shrink_to_fit();
+        (before - self.pixels.capacity()) * 4
+    }
+
     fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
         let b = self.base();
         let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
@@ -68,7 +136,20 @@ impl Control for ImageView {
 
         if self.pixels.is_empty() || self.img_w == 0 || self.img_h == 0 {
             // No image loaded — draw placeholder
-            crate::draw::fill_rect(surface, x, y, cw, ch, crate::theme::colors().placeholder_bg);
+            let tc = crate::theme::colors();
+            crate::draw::fill_rect(surface, x, y, cw, ch, tc.placeholder_bg);
+            if self.loading {
+                let d = (cw.min(ch) / 2).min(crate::theme::scale(SPINNER_MAX));
+                if d > 0 {
+                    let sx = x + ((cw - d) / 2) as i32;
+                    let sy = y + ((ch - d) / 2) as i32;
+                    let t = super::circular_progress::ring_thickness(0, d);
+                    crate::draw::draw_arc(surface, sx, sy, d, t, 0, crate::draw::ARC_TURN, crate::theme::with_alpha(tc.accent, 48));
+                    let phase = crate::syscall::uptime_ms() % SPINNER_ROTATION_MS;
+                    let start = crate::draw::ARC_TURN * phase / SPINNER_ROTATION_MS;
+                    crate::draw::draw_arc(surface, sx, sy, d, t, start, SPINNER_SWEEP, tc.accent);
+                }
+            }
             return;
         }
 
@@ -99,6 +180,31 @@ impl Control for ImageView {
     }
 }
 
+/// Spinner repaint tick (userdata = control ID). Stops itself once the
+/// control is gone or no longer loading.
+extern "C" fn spinner_tick(timer_id: ControlId, _event_type: u32, userdata: u64) {
+    let st = crate::state();
+    let id = userdata as ControlId;
+    let loading = match st.controls.iter_mut().find(|c| c.id() == id) {
+        Some(ctrl) if ctrl.kind() == ControlKind::ImageView => {
+            let raw: *mut dyn Control = &mut **ctrl;
+            let iv = unsafe { &mut *(raw as *mut ImageView) };
+            if iv.loading && iv.spinner_timer == timer_id {
+                if iv.base.visible {
+                    iv.base.mark_dirty();
+                }
+                true
+            } else {
+                false
+            }
+        }
+        _ => false,
+    };
+    if !loading {
+        st.timers.kill_timer(timer_id);
+    }
+}
+
 /// Calculate destination rect for "fit" mode (scale to fit, preserve aspect).
 fn fit_rect(src_w: u32, src_h: u32, dst_w: u32, dst_h: u32) -> (i32, i32, u32, u32) {
     if src_w == 0 || src_h == 0 || dst_w == 0 || dst_h == 0 {
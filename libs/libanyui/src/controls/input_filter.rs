@@ -0,0 +1,173 @@
+//! Input filtering for TextField — numeric/decimal filters, masks, max length.
+//!
+//! Mask patterns use `#` for a digit, `A` for a letter and `*` for any letter
+//! or digit; every other mask character is a literal that is inserted
+//! automatically ("##:##", "###-###"). A masked field stores the formatted
+//! text; edits are applied to the raw (slot) characters and re-formatted.
+
+use alloc::vec::Vec;
+
+pub const FILTER_NONE: u32 = 0;
+/// Digits only.
+pub const FILTER_NUMERIC: u32 = 1;
+/// Optional leading '-', digits and at most one decimal separator.
+pub const FILTER_DECIMAL: u32 = 2;
+
+/// Filter settings of one TextField.
+pub struct InputFilter {
+    pub mode: u32,
+    /// Decimal separator for FILTER_DECIMAL (b'.' or b',').
+    pub decimal_sep: u8,
+    /// Mask pattern (empty = no mask).
+    pub mask: Vec<u8>,
+    /// Maximum text length in bytes (0 = unlimited).
+    pub max_length: u32,
+}
+
+impl InputFilter {
+    pub const fn new() -> Self {
+        Self { mode: FILTER_NONE, decimal_sep: b'.', mask: Vec::new(), max_length: 0 }
+    }
+
+    /// Replace `text[start..end]` with `ins`. Returns the new text and cursor
+    /// position, or None if the result is not acceptable input.
+    pub fn apply(&self, text: &[u8], start: usize, end: usize, ins: &[u8]) -> Option<(Vec<u8>, usize)> {
+        let ins = self.normalize(ins);
+        if !self.mask.is_empty() {
+            return self.apply_masked(text, start, end, &ins);
+        }
+        let mut out = Vec::with_capacity(text.len() + ins.len());
+        out.extend_from_slice(&text[..start]);
+        out.extend_from_slice(&ins);
+        out.extend_from_slice(&text[end..]);
+        if !self.accepts(&out) {
+            return None;
+        }
+        Some((out, start + ins.len()))
+    }
+
+    /// Map typed decimal separators ('.' or ',') to the configured one.
+    fn normalize(&self, ins: &[u8]) -> Vec<u8> {
+        if self.mode == FILTER_DECIMAL {
+            ins.iter().map(|&b| if b == b'.' || b == b',' { self.decimal_sep } else { b }).collect()
+        } else {
+            ins.to_vec()
+        }
+    }
+
+    /// Whether a complete (unmasked) text passes the filter and max length.
+    fn accepts(&self, text: &[u8]) -> bool {
+        if self.max_length != 0 && text.len() > self.max_length as usize {
+            return false;
+        }
+        match self.mode {
+            FILTER_NUMERIC => text.iter().all(|b| b.is_ascii_digit()),
+            FILTER_DECIMAL => {
+                let body = text.strip_prefix(b"-").unwrap_or(text);
+                body.iter().all(|&b| b.is_ascii_digit() || b == self.decimal_sep)
+                    && body.iter().filter(|&&b| b == self.decimal_sep).count() <= 1
+            }
+            _ => true,
+        }
+    }
+
+    fn apply_masked(&self, text: &[u8], start: usize, end: usize, ins: &[u8]) -> Option<(Vec<u8>, usize)> {
+        let raw = self.raw_chars(text);
+        let raw_start = self.slots_before(text, start);
+        let raw_end = self.slots_before(text, end).max(raw_start);
+        // Typed literals ("12:30") are skipped; the mask inserts them itself.
+        let typed: Vec<u8> = ins.iter().copied().filter(|&b| !self.is_literal(b)).collect();
+        let mut new_raw = Vec::with_capacity(raw.len() + typed.len());
+        new_raw.extend_from_slice(&raw[..raw_start]);
+        new_raw.extend_from_slice(&typed);
+        new_raw.extend_from_slice(&raw[raw_end.min(raw.len())..]);
+        let out = self.format(&new_raw)?;
+        if self.max_length != 0 && out.len() > self.max_length as usize {
+            return None;
+        }
+        let cursor = self.pos_after_slots(&out, raw_start + typed.len());
+        Some((out, cursor))
+    }
+
+    /// Place raw characters into the mask slots, inserting the literals in
+    /// front of each filled slot. None if a character does not fit its slot.
+    fn format(&self, raw: &[u8]) -> Option<Vec<u8>> {
+        let mut out = Vec::with_capacity(self.mask.len());
+        let mut chars = raw.iter().peekable();
+        let mut literals = Vec::new();
+        for &m in &self.mask {
+            if chars.peek().is_none() {
+                break;
+            }
+            if is_slot(m) {
+                let c = *chars.next().unwrap();
+                if !slot_matches(m, c) {
+                    return None;
+                }
+                out.append(&mut literals);
+                out.push(c);
+            } else {
+                literals.push(m);
+            }
+        }
+        if chars.peek().is_some() {
+            return None;
+        }
+        Some(out)
+    }
+
+    /// The user-entered characters of a formatted text.
+    fn raw_chars(&self, text: &[u8]) -> Vec<u8> {
+        text.iter().zip(self.mask.iter()).filter(|&(_, &m)| is_slot(m)).map(|(&c, _)| c).collect()
+    }
+
+    /// Number of slot characters in `text[..pos]`.
+    pub fn slots_before(&self, text: &[u8], pos: usize) -> usize {
+        self.mask.iter().take(pos.min(text.len())).filter(|&&m| is_slot(m)).count()
+    }
+
+    /// Text position just after the `n`-th filled slot.
+    fn pos_after_slots(&self, text: &[u8], n: usize) -> usize {
+        if n == 0 {
+            return 0;
+        }
+        let mut seen = 0;
+        for (i, &m) in self.mask.iter().enumerate().take(text.len()) {
+            if is_slot(m) {
+                seen += 1;
+                if seen == n {
+                    return i + 1;
+                }
+            }
+        }
+        text.len()
+    }
+
+    /// Whether `b` appears in the mask as a literal.
+    fn is_literal(&self, b: u8) -> bool {
+        self.mask.iter().any(|&m| !is_slot(m) && m == b)
+    }
+
+    /// Whether `pos` in a masked text holds a literal.
+    pub fn is_literal_at(&self, pos: usize) -> bool {
+        self.mask.get(pos).map_or(false, |&m| !is_slot(m))
+    }
+
+    /// A masked field is complete when every slot is filled.
+    pub fn is_complete(&self, text: &[u8]) -> bool {
+        self.mask.is_empty() || self.raw_chars(text).len() == self.mask.iter().filter(|&&m| is_slot(m)).count()
+    }
+}
+
+fn is_slot(m: u8) -> bool {
+    m == b'#' || m == b'A' || m == b'*'
+}
+
+fn slot_matches(m: u8, c: u8) -> bool {
+    match m {
+        b'#' => c.is_ascii_digit(),
+        b'A' => c.is_ascii_alphabetic(),
+        b'*' => c.is_ascii_alphanumeric(),
+        _ => false,
+    }
+}
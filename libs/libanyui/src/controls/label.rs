@@ -15,6 +15,11 @@ impl Control for Label {
     fn text_base_mut(&mut self) -> Option<&mut crate::control::TextControlBase> { Some(&mut self.text_base) }
     fn kind(&self) -> ControlKind { ControlKind::Label }
 
+    fn baseline(&self) -> Option<i32> {
+        let b = &self.text_base.base;
+        Some(b.padding.top + crate::draw::text_ascent(self.text_base.text_style.font_size))
+    }
+
     fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
         let b = &self.text_base.base;
         let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
@@ -44,24 +49,24 @@ impl Control for Label {
         let text_w = w as i32 - pad_left - pad_right;
         let mut line_y = y + pad_top;
         let line_h = fs as i32 + crate::theme::scale_i32(2);
+        // Left alignment follows the reading direction of the window.
+        let align = if align == 0 && crate::bidi::window_rtl() { 2 } else { align };
         let mut start = 0;
         loop {
             let end = text[start..].iter().position(|&b| b == b'\n').map(|p| start + p).unwrap_or(text.len());
-            let line = &text[start..end];
+            let line = crate::bidi::BidiLine::new(&text[start..end], fid, fs);
 
             let tx = if align == 1 {
                 // Center
-                let (tw, _) = crate::draw::measure_text_ex(line, fid, fs);
-                text_x + (text_w - tw as i32) / 2
+                text_x + (text_w - line.width()) / 2
             } else if align == 2 {
                 // Right
-                let (tw, _) = crate::draw::measure_text_ex(line, fid, fs);
-                text_x + text_w - tw as i32
+                text_x + text_w - line.width()
             } else {
                 text_x
             };
 
-            crate::draw::draw_text_ex(surface, tx, line_y, text_color, line, fid, fs);
+            crate::draw::draw_text_ex(surface, tx, line_y, text_color, line.visual(), fid, fs);
             line_y += line_h;
 
             if end >= text.len() { break; }
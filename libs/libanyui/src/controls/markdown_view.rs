@@ -0,0 +1,915 @@
+//! MarkdownView — read-only rich text rendered from a markdown subset.
+//!
+//! Intended for settings pages, about boxes and changelog dialogs that need
+//! formatted text without pulling in the web view.  Supported syntax:
+//!
+//! - ATX headings (`#` … `######`), paragraphs, `---` rules, `>` quotes
+//! - `-`/`*`/`+` bullet and `1.` ordered lists (nested by indentation)
+//! - fenced code blocks (```), `` `inline code` ``
+//! - `**bold**`, `*italic*`, `***both***` (also with `_`)
+//! - `[links](url)` and `![images](src)`
+//!
+//! The markdown source lives in the TextControlBase text; it is parsed into
+//! blocks on `set_text` and laid out into positioned runs whenever the text
+//! or the control width changes.  Rendering just walks the cached runs.
+//!
+//! Images are not loaded here: the document only records their sources and
+//! the client injects decoded pixels with `anyui_markdown_set_image` (the
+//! same split as ImageView).  Until then a placeholder with the alt text is
+//! drawn.  Injected pixels are kept across `set_text` so re-setting the
+//! document does not require reloading images.
+//!
+//! Clicking a link stores its URL (`anyui_markdown_get_link`) and fires
+//! EVENT_CLICK; clicks elsewhere are ignored.
+
+use alloc::vec::Vec;
+use crate::control::{Control, ControlBase, TextControlBase, ControlKind, EventResponse};
+
+const PAD: i32 = 12;
+const LIST_INDENT: i32 = 20;
+const QUOTE_INDENT: i32 = 14;
+const CODE_PAD: i32 = 8;
+/// Extra size added to the body font size for h1..h6.
+const HEADING_GROW: [u16; 6] = [10, 6, 4, 2, 1, 0];
+/// Placeholder size for images whose pixels have not been injected yet.
+const PLACEHOLDER_H: i32 = 24;
+
+// Text roles — mapped to theme colors at render time so theme switches apply.
+const ROLE_TEXT: u8 = 0;
+const ROLE_HEADING: u8 = 1;
+const ROLE_CODE: u8 = 2;
+const ROLE_LINK: u8 = 3;
+const ROLE_QUOTE: u8 = 4;
+// Fill roles.
+const FILL_CODE_BG: u8 = 0;
+const FILL_RULE: u8 = 1;
+const FILL_BULLET: u8 = 2;
+const FILL_QUOTE_BAR: u8 = 3;
+
+#[derive(Clone, Copy, PartialEq, Default)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    code: bool,
+}
+
+/// Inline run of uniformly styled text (or an image reference).
+struct Span {
+    text: Vec<u8>,
+    style: Style,
+    link: Option<u16>,
+    image: Option<u16>,
+}
+
+enum Block {
+    Heading(u8, Vec<Span>),
+    Paragraph(Vec<Span>),
+    /// List item: nesting level, marker (empty = bullet), content.
+    Item(u8, Vec<u8>, Vec<Span>),
+    Quote(Vec<Span>),
+    Code(Vec<Vec<u8>>),
+    Rule,
+}
+
+/// Positioned layout element, in logical content coordinates.
+enum Item {
+    Text { x: i32, y: i32, w: i32, h: i32, text: Vec<u8>, font: u16, size: u16, role: u8, link: Option<u16> },
+    Fill { x: i32, y: i32, w: i32, h: i32, role: u8 },
+    Image { x: i32, y: i32, w: i32, h: i32, img: u16 },
+}
+
+impl Item {
+    fn set_y(&mut self, ny: i32) {
+        match self {
+            Item::Text { y, .. } | Item::Fill { y, .. } | Item::Image { y, .. } => *y = ny,
+        }
+    }
+
+    fn height(&self) -> i32 {
+        match self {
+            Item::Text { h, .. } | Item::Fill { h, .. } | Item::Image { h, .. } => *h,
+        }
+    }
+}
+
+/// An image referenced by the document, with pixels once injected.
+pub(crate) struct MdImage {
+    pub(crate) src: Vec<u8>,
+    alt: Vec<u8>,
+    pixels: Vec<u32>,
+    img_w: u32,
+    img_h: u32,
+    /// Referenced by the current document (injected images are retained
+    /// even when a new document no longer uses them).
+    used: bool,
+}
+
+pub struct MarkdownView {
+    pub(crate) text_base: TextControlBase,
+    blocks: Vec<Block>,
+    links: Vec<Vec<u8>>,
+    pub(crate) images: Vec<MdImage>,
+    items: Vec<Item>,
+    content_h: i32,
+    /// Width the cached layout was computed for (`u32::MAX` = stale).
+    layout_w: u32,
+    scroll_y: i32,
+    hovered_link: Option<u16>,
+    /// URL of the most recently clicked link.
+    pub(crate) clicked_link: Vec<u8>,
+}
+
+impl MarkdownView {
+    pub fn new(text_base: TextControlBase) -> Self {
+        let mut mv = Self {
+            text_base,
+            blocks: Vec::new(),
+            links: Vec::new(),
+            images: Vec::new(),
+            items: Vec::new(),
+            content_h: 0,
+            layout_w: 0,
+            scroll_y: 0,
+            hovered_link: None,
+            clicked_link: Vec::new(),
+        };
+        mv.parse();
+        mv
+    }
+
+    /// Store decoded ARGB pixels for image `src`. Returns true if the current
+    /// document references it.
+    pub fn set_image(&mut self, src: &[u8], data: &[u32], w: u32, h: u32) -> bool {
+        let expected = (w as usize) * (h as usize);
+        if data.len() < expected {
+            return false;
+        }
+        let idx = match self.images.iter().position(|im| im.src.as_slice() == src) {
+            Some(i) => i,
+            None => {
+                self.images.push(MdImage {
+                    src: src.to_vec(), alt: Vec::new(), pixels: Vec::new(),
+                    img_w: 0, img_h: 0, used: false,
+                });
+                self.images.len() - 1
+            }
+        };
+        let im = &mut self.images[idx];
+        im.pixels.clear();
+        im.pixels.extend_from_slice(&data[..expected]);
+        im.img_w = w;
+        im.img_h = h;
+        if im.used {
+            self.relayout();
+        }
+        self.images[idx].used
+    }
+
+    /// Sources of the images referenced by the current document.
+    pub fn image_sources(&self) -> impl Iterator<Item = &[u8]> {
+        self.images.iter().filter(|im| im.used).map(|im| im.src.as_slice())
+    }
+
+    fn body_size(&self) -> u16 {
+        self.text_base.text_style.font_size.max(8)
+    }
+
+    fn max_scroll(&self) -> i32 {
+        (self.content_h - self.text_base.base.h as i32).max(0)
+    }
+
+    fn relayout(&mut self) {
+        self.layout_w = u32::MAX;
+        self.layout();
+        self.text_base.base.mark_dirty();
+    }
+
+    // ── Parsing ──────────────────────────────────────────────────────
+
+    fn parse(&mut self) {
+        self.blocks.clear();
+        self.links.clear();
+        self.hovered_link = None;
+        self.images.retain(|im| !im.pixels.is_empty());
+        for im in self.images.iter_mut() {
+            im.used = false;
+        }
+
+        let src = core::mem::take(&mut self.text_base.text);
+        let mut para: Vec<u8> = Vec::new();
+        // Pending paragraph-like block the accumulated text belongs to.
+        let mut pending: Option<Block> = None;
+        let mut lines = src.split(|&b| b == b'\n');
+
+        while let Some(raw) = lines.next() {
+            let line = raw.strip_suffix(b"\r").unwrap_or(raw);
+            let (indent, body) = split_indent(line);
+
+            if body.is_empty() {
+                self.flush(&mut pending, &mut para);
+                continue;
+            }
+
+            if body.starts_with(b"```") {
+                self.flush(&mut pending, &mut para);
+                let mut code = Vec::new();
+                for raw in lines.by_ref() {
+                    let l = raw.strip_suffix(b"\r").unwrap_or(raw);
+                    if split_indent(l).1.starts_with(b"```") {
+                        break;
+                    }
+                    code.push(expand_tabs(l));
+                }
+                self.blocks.push(Block::Code(code));
+                continue;
+            }
+
+            if let Some((level, rest)) = heading(body) {
+                self.flush(&mut pending, &mut para);
+                let spans = self.inline(rest);
+                self.blocks.push(Block::Heading(level, spans));
+                continue;
+            }
+
+            if is_rule(body) {
+                self.flush(&mut pending, &mut para);
+                self.blocks.push(Block::Rule);
+                continue;
+            }
+
+            if let Some((marker, rest)) = list_marker(body) {
+                self.flush(&mut pending, &mut para);
+                pending = Some(Block::Item((indent / 2).min(8) as u8, marker, Vec::new()));
+                para.extend_from_slice(rest);
+                continue;
+            }
+
+            if let Some(rest) = body.strip_prefix(b">") {
+                let rest = rest.strip_prefix(b" ").unwrap_or(rest);
+                if !matches!(pending, Some(Block::Quote(_))) {
+                    self.flush(&mut pending, &mut para);
+                    pending = Some(Block::Quote(Vec::new()));
+                }
+                append_line(&mut para, rest);
+                continue;
+            }
+
+            // Plain text continues the open paragraph, quote or (lazily)
+            // list item; otherwise it starts a new paragraph.
+            if pending.is_none() {
+                pending = Some(Block::Paragraph(Vec::new()));
+            }
+            append_line(&mut para, body);
+        }
+        self.flush(&mut pending, &mut para);
+        self.text_base.text = src;
+        self.relayout();
+    }
+
+    /// Finish the pending paragraph-like block with the accumulated text.
+    fn flush(&mut self, pending: &mut Option<Block>, para: &mut Vec<u8>) {
+        if let Some(block) = pending.take() {
+            let spans = self.inline(para);
+            self.blocks.push(match block {
+                Block::Item(level, marker, _) => Block::Item(level, marker, spans),
+                Block::Quote(_) => Block::Quote(spans),
+                _ => Block::Paragraph(spans),
+            });
+        }
+        para.clear();
+    }
+
+    fn inline(&mut self, text: &[u8]) -> Vec<Span> {
+        let mut out = Vec::new();
+        self.inline_into(text, Style::default(), None, &mut out);
+        out
+    }
+
+    fn inline_into(&mut self, s: &[u8], mut style: Style, link: Option<u16>, out: &mut Vec<Span>) {
+        let mut cur: Vec<u8> = Vec::new();
+        let mut i = 0;
+        while i < s.len() {
+            let c = s[i];
+
+            if c == b'\\' && i + 1 < s.len() && s[i + 1].is_ascii_punctuation() {
+                cur.push(s[i + 1]);
+                i += 2;
+                continue;
+            }
+
+            if c == b'`' {
+                if let Some(end) = find(&s[i + 1..], b"`") {
+                    push_span(out, &mut cur, style, link);
+                    let code = Style { code: true, ..style };
+                    out.push(Span { text: s[i + 1..i + 1 + end].to_vec(), style: code, link, image: None });
+                    i += end + 2;
+                    continue;
+                }
+            }
+
+            if c == b'!' && s.get(i + 1) == Some(&b'[') {
+                if let Some((alt, target, next)) = bracket_link(s, i + 1) {
+                    push_span(out, &mut cur, style, link);
+                    let img = self.image_ref(target, alt);
+                    out.push(Span { text: alt.to_vec(), style, link, image: Some(img) });
+                    i = next;
+                    continue;
+                }
+            }
+
+            if c == b'[' && link.is_none() {
+                if let Some((label, target, next)) = bracket_link(s, i) {
+                    push_span(out, &mut cur, style, link);
+                    let l = self.links.len() as u16;
+                    self.links.push(target.to_vec());
+                    self.inline_into(label, style, Some(l), out);
+                    i = next;
+                    continue;
+                }
+            }
+
+            if c == b'*' || c == b'_' {
+                let run = s[i..].iter().take_while(|&&b| b == c).count().min(3);
+                let prev = if i > 0 { s[i - 1] } else { b' ' };
+                let next = s.get(i + run).copied().unwrap_or(b' ');
+                // `_` never emphasises inside words (snake_case identifiers).
+                let intraword = prev.is_ascii_alphanumeric() && next.is_ascii_alphanumeric();
+                if !(c == b'_' && intraword) {
+                    let marker = &s[i..i + run];
+                    let closing = match run {
+                        1 => style.italic,
+                        2 => style.bold,
+                        _ => style.bold && style.italic,
+                    };
+                    // Open only if a matching closer follows.
+                    if closing || (!next.is_ascii_whitespace() && find(&s[i + run..], marker).is_some()) {
+                        push_span(out, &mut cur, style, link);
+                        if run != 1 { style.bold = !closing; }
+                        if run != 2 { style.italic = !closing; }
+                        i += run;
+                        continue;
+                    }
+                }
+            }
+
+            cur.push(c);
+            i += 1;
+        }
+        push_span(out, &mut cur, style, link);
+    }
+
+    fn image_ref(&mut self, src: &[u8], alt: &[u8]) -> u16 {
+        if let Some(i) = self.images.iter().position(|im| im.src.as_slice() == src) {
+            let im = &mut self.images[i];
+            im.used = true;
+            im.alt.clear();
+            im.alt.extend_from_slice(alt);
+            return i as u16;
+        }
+        self.images.push(MdImage {
+            src: src.to_vec(), alt: alt.to_vec(), pixels: Vec::new(),
+            img_w: 0, img_h: 0, used: true,
+        });
+        (self.images.len() - 1) as u16
+    }
+
+    // ── Layout ───────────────────────────────────────────────────────
+
+    fn layout(&mut self) {
+        let w = self.text_base.base.w;
+        if w == self.layout_w {
+            return;
+        }
+        self.layout_w = w;
+        let mut items = Vec::new();
+        let right = (w as i32 - PAD).max(PAD + 1);
+        let body = self.body_size();
+        let gap = body as i32 * 2 / 3;
+        let mut y = PAD;
+
+        for (bi, block) in self.blocks.iter().enumerate() {
+            if bi > 0 {
+                y += gap;
+            }
+            match block {
+                Block::Heading(level, spans) => {
+                    let size = body + HEADING_GROW[(*level as usize - 1).min(5)];
+                    if bi > 0 {
+                        y += gap / 2;
+                    }
+                    self.layout_spans(spans, PAD, right, size, true, ROLE_HEADING, &mut y, &mut items);
+                    if *level <= 2 {
+                        y += 4;
+                        items.push(Item::Fill { x: PAD, y, w: right - PAD, h: 1, role: FILL_RULE });
+                        y += 1;
+                    }
+                }
+                Block::Paragraph(spans) => {
+                    self.layout_spans(spans, PAD, right, body, false, ROLE_TEXT, &mut y, &mut items);
+                }
+                Block::Item(level, marker, spans) => {
+                    // Consecutive list items are packed tighter than paragraphs.
+                    if bi > 0 && matches!(self.blocks[bi - 1], Block::Item(..)) {
+                        y -= gap * 2 / 3;
+                    }
+                    let left = PAD + (*level as i32 + 1) * LIST_INDENT;
+                    let line_h = line_height(body);
+                    if marker.is_empty() {
+                        let d = (body as i32 / 3).max(4);
+                        items.push(Item::Fill {
+                            x: left - LIST_INDENT / 2 - d / 2, y: y + (line_h - d) / 2,
+                            w: d, h: d, role: FILL_BULLET,
+                        });
+                    } else {
+                        let (mw, _) = crate::draw::measure_text_ex(marker, 0, body);
+                        items.push(Item::Text {
+                            x: left - 4 - mw as i32, y: y + (line_h - body as i32) / 2,
+                            w: mw as i32, h: body as i32, text: marker.clone(),
+                            font: 0, size: body, role: ROLE_TEXT, link: None,
+                        });
+                    }
+                    self.layout_spans(spans, left, right, body, false, ROLE_TEXT, &mut y, &mut items);
+                }
+                Block::Quote(spans) => {
+                    let y0 = y;
+                    self.layout_spans(spans, PAD + QUOTE_INDENT, right, body, false, ROLE_QUOTE, &mut y, &mut items);
+                    items.push(Item::Fill { x: PAD, y: y0, w: 3, h: y - y0, role: FILL_QUOTE_BAR });
+                }
+                Block::Code(lines) => {
+                    let size = body.saturating_sub(1).max(8);
+                    let line_h = line_height(size);
+                    let h = lines.len() as i32 * line_h + CODE_PAD * 2;
+                    items.push(Item::Fill { x: PAD, y, w: right - PAD, h, role: FILL_CODE_BG });
+                    let mut ly = y + CODE_PAD;
+                    for l in lines {
+                        if !l.is_empty() {
+                            let (lw, _) = crate::draw::measure_text_ex(l, 4, size);
+                            items.push(Item::Text {
+                                x: PAD + CODE_PAD, y: ly + (line_h - size as i32) / 2,
+                                w: lw as i32, h: size as i32, text: l.clone(),
+                                font: 4, size, role: ROLE_TEXT, link: None,
+                            });
+                        }
+                        ly += line_h;
+                    }
+                    y += h;
+                }
+                Block::Rule => {
+                    y += gap / 2;
+                    items.push(Item::Fill { x: PAD, y, w: right - PAD, h: 1, role: FILL_RULE });
+                    y += 1 + gap / 2;
+                }
+            }
+        }
+
+        self.items = items;
+        self.content_h = y + PAD;
+        self.scroll_y = self.scroll_y.clamp(0, self.max_scroll());
+    }
+
+    /// Word-wrap `spans` between `left` and `right`, appending positioned
+    /// items and advancing `y` past the last line.
+    fn layout_spans(
+        &self, spans: &[Span], left: i32, right: i32, size: u16, heading: bool,
+        role: u8, y: &mut i32, out: &mut Vec<Item>,
+    ) {
+        let mut line: Vec<Item> = Vec::new();
+        let mut x = left;
+        let text_h = line_height(size);
+        let mut line_h = text_h;
+
+        for span in spans {
+            if let Some(img) = span.image {
+                let (w, h) = self.image_box(img, right - left, size);
+                if x > left && x + w > right {
+                    flush_line(&mut line, y, line_h, out);
+                    x = left;
+                    line_h = text_h;
+                }
+                line.push(Item::Image { x, y: 0, w, h, img });
+                line_h = line_h.max(h);
+                x += w;
+                continue;
+            }
+
+            let st = span.style;
+            let font = if st.code {
+                4
+            } else if (st.bold || heading) && st.italic {
+                3
+            } else if st.bold || heading {
+                1
+            } else if st.italic {
+                3
+            } else {
+                0
+            };
+            let item_role = if span.link.is_some() {
+                ROLE_LINK
+            } else if st.code {
+                ROLE_CODE
+            } else {
+                role
+            };
+
+            for word in words(&span.text) {
+                let (ww, _) = crate::draw::measure_text_ex(word, font, size);
+                let ww = ww as i32;
+                if x > left && x + ww > right {
+                    flush_line(&mut line, y, line_h, out);
+                    x = left;
+                    line_h = text_h;
+                    if word.iter().all(|&b| b == b' ') {
+                        continue;
+                    }
+                }
+                // Extend the previous run when the style carries over.
+                if let Some(Item::Text { x: px, w: pw, text, font: pf, role: pr, link: pl, .. }) = line.last_mut() {
+                    if *pf == font && *pr == item_role && *pl == span.link && *px + *pw == x {
+                        text.extend_from_slice(word);
+                        *pw += ww;
+                        x += ww;
+                        continue;
+                    }
+                }
+                line.push(Item::Text {
+                    x, y: 0, w: ww, h: size as i32, text: word.to_vec(),
+                    font, size, role: item_role, link: span.link,
+                });
+                x += ww;
+            }
+        }
+        if line.is_empty() {
+            *y += text_h;
+        } else {
+            flush_line(&mut line, y, line_h, out);
+        }
+    }
+
+    /// Display size of image `img`: scaled down to `max_w`, or a placeholder
+    /// sized for the alt text while no pixels are available.
+    fn image_box(&self, img: u16, max_w: i32, size: u16) -> (i32, i32) {
+        let im = &self.images[img as usize];
+        if im.img_w == 0 || im.img_h == 0 {
+            let (tw, _) = crate::draw::measure_text_ex(&im.alt, 0, size);
+            return ((tw as i32 + 16).clamp(PLACEHOLDER_H, max_w.max(PLACEHOLDER_H)), PLACEHOLDER_H);
+        }
+        let max_w = max_w.max(1);
+        let (w, h) = (im.img_w as i32, im.img_h as i32);
+        if w <= max_w {
+            (w, h)
+        } else {
+            (max_w, (h as i64 * max_w as i64 / w as i64).max(1) as i32)
+        }
+    }
+
+    fn link_at(&self, lx: i32, ly: i32) -> Option<u16> {
+        let cy = ly + self.scroll_y;
+        self.items.iter().find_map(|it| match it {
+            Item::Text { x, y, w, h, link: Some(l), .. }
+                if lx >= *x && lx < x + w && cy >= y - 2 && cy < y + h + 2 => Some(*l),
+            _ => None,
+        })
+    }
+}
+
+impl Control for MarkdownView {
+    fn base(&self) -> &ControlBase { &self.text_base.base }
+    fn base_mut(&mut self) -> &mut ControlBase { &mut self.text_base.base }
+    fn text_base(&self) -> Option<&TextControlBase> { Some(&self.text_base) }
+    fn text_base_mut(&mut self) -> Option<&mut TextControlBase> { Some(&mut self.text_base) }
+    fn kind(&self) -> ControlKind { ControlKind::MarkdownView }
+
+    fn pixel_bytes(&self) -> usize {
+        self.images.iter().map(|im| im.pixels.capacity() * 4).sum()
+    }
+
+    /// Release images the current document no longer references; their
+    /// entries go on the next parse.
+    fn trim_caches(&mut self) -> usize {
+        let mut freed = 0;
+        for im in self.images.iter_mut().filter(|im| !im.used) {
+            freed += im.pixels.capacity() * 4;
+            im.pixels = Vec::new();
+            im.img_w = 0;
+            im.img_h = 0;
+        }
+        freed
+    }
+
+    fn is_interactive(&self) -> bool { true }
+    fn accepts_focus(&self) -> bool { false }
+
+    fn set_text(&mut self, t: &[u8]) {
+        if self.text_base.text.as_slice() != t {
+            self.text_base.set_text(t);
+            self.scroll_y = 0;
+            self.parse();
+        }
+    }
+
+    fn set_font_size(&mut self, size: u16) {
+        if self.text_base.text_style.font_size != size {
+            self.text_base.text_style.font_size = size;
+            self.relayout();
+        }
+    }
+
+    fn set_size(&mut self, w: u32, h: u32) {
+        let b = self.base_mut();
+        if b.w != w || b.h != h {
+            if !b.dirty {
+                b.prev_w = b.w;
+                b.prev_h = b.h;
+            }
+            b.w = w;
+            b.h = h;
+            b.mark_dirty();
+            self.layout();
+            self.scroll_y = self.scroll_y.clamp(0, self.max_scroll());
+        }
+    }
+
+    fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
+        let b = &self.text_base.base;
+        let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
+        let (x, y, w, h) = (p.x, p.y, p.w, p.h);
+        let tc = crate::theme::colors();
+        let text_color = self.text_base.effective_text_color();
+        let sc = crate::theme::scale_i32;
+        let top = y - sc(self.scroll_y);
+
+        let clipped = surface.with_clip(x, y, w, h);
+        let s = &clipped;
+        for it in &self.items {
+            match it {
+                Item::Fill { x: ix, y: iy, w: iw, h: ih, role } => {
+                    let (fx, fy) = (x + sc(*ix), top + sc(*iy));
+                    let (fw, fh) = (sc(*iw).max(1) as u32, sc(*ih).max(1) as u32);
+                    if fy >= y + h as i32 || fy + fh as i32 <= y { continue; }
+                    match *role {
+                        FILL_CODE_BG => crate::draw::fill_rounded_rect(s, fx, fy, fw, fh, crate::theme::scale(4), tc.editor_bg),
+                        FILL_BULLET => crate::draw::fill_rounded_rect(s, fx, fy, fw, fh, fw / 2, text_color),
+                        FILL_QUOTE_BAR => crate::draw::fill_rect(s, fx, fy, fw, fh, tc.accent),
+                        _ => crate::draw::fill_rect(s, fx, fy, fw, fh, tc.separator),
+                    }
+                }
+                Item::Text { x: ix, y: iy, w: iw, h: ih, text, font, size, role, link } => {
+                    let (tx, ty) = (x + sc(*ix), top + sc(*iy));
+                    let th = sc(*ih);
+                    if ty >= y + h as i32 || ty + th <= y { continue; }
+                    if *role == ROLE_CODE {
+                        crate::draw::fill_rounded_rect(s, tx - sc(2), ty - sc(1), (sc(*iw) + sc(4)) as u32,
+                            (th + sc(2)) as u32, crate::theme::scale(3), tc.editor_bg);
+                    }
+                    let color = match *role {
+                        ROLE_LINK => tc.accent,
+                        ROLE_QUOTE => tc.text_secondary,
+                        _ => text_color,
+                    };
+                    crate::draw::draw_text_ex(s, tx, ty, color, text, *font, crate::draw::scale_font(*size));
+                    if link.is_some() && *link == self.hovered_link {
+                        crate::draw::fill_rect(s, tx, ty + th, sc(*iw) as u32, crate::theme::scale(1), color);
+                    }
+                }
+                Item::Image { x: ix, y: iy, w: iw, h: ih, img } => {
+                    let (ix, iy) = (x + sc(*ix), top + sc(*iy));
+                    let (iw, ih) = (sc(*iw) as u32, sc(*ih) as u32);
+                    if iy >= y + h as i32 || iy + ih as i32 <= y { continue; }
+                    let im = &self.images[*img as usize];
+                    if im.pixels.is_empty() {
+                        crate::draw::fill_rect(s, ix, iy, iw, ih, tc.placeholder_bg);
+                        crate::draw::draw_border(s, ix, iy, iw, ih, tc.card_border);
+                        let fs = crate::draw::scale_font(self.body_size());
+                        let (_, th) = crate::draw::measure_text_ex(&im.alt, 0, fs);
+                        crate::draw::draw_text_ex(s, ix + sc(8), iy + (ih as i32 - th as i32) / 2,
+                            tc.text_secondary, &im.alt, 0, fs);
+                    } else if iw == im.img_w && ih == im.img_h {
+                        crate::draw::blit_buffer(s, ix, iy, iw, ih, &im.pixels);
+                    } else {
+                        crate::draw::blit_buffer_scaled(s, ix, iy, iw, ih, im.img_w, im.img_h, &im.pixels);
+                    }
+                }
+            }
+        }
+
+        // Vertical scrollbar
+        let s_content_h = sc(self.content_h);
+        if s_content_h > h as i32 && h > 0 {
+            let bar_w = crate::theme::scale(6);
+            let track_x = x + w as i32 - bar_w as i32 - 2;
+            let max_scroll = (s_content_h - h as i32).max(1) as u32;
+            let min_thumb = crate::theme::scale(20);
+            let thumb_h = ((h * h) / s_content_h as u32).max(min_thumb).min(h);
+            let thumb_y = y + (sc(self.scroll_y) as u32 * (h - thumb_h) / max_scroll) as i32;
+            crate::draw::fill_rounded_rect(surface, track_x, thumb_y, bar_w, thumb_h, bar_w / 2, tc.scrollbar);
+        }
+    }
+
+    fn handle_mouse_move(&mut self, lx: i32, ly: i32) -> EventResponse {
+        let link = self.link_at(lx, ly);
+        if link != self.hovered_link {
+            self.hovered_link = link;
+            return EventResponse::CONSUMED;
+        }
+        EventResponse::IGNORED
+    }
+
+    fn handle_mouse_leave(&mut self) {
+        self.text_base.base.hovered = false;
+        self.hovered_link = None;
+        self.text_base.base.mark_dirty();
+    }
+
+    fn handle_click(&mut self, lx: i32, ly: i32, button: u32) -> EventResponse {
+        if button & 1 == 0 {
+            return EventResponse::IGNORED;
+        }
+        match self.link_at(lx, ly) {
+            Some(l) => {
+                self.clicked_link.clear();
+                self.clicked_link.extend_from_slice(&self.links[l as usize]);
+                EventResponse::CLICK
+            }
+            None => EventResponse::IGNORED,
+        }
+    }
+
+    fn handle_scroll(&mut self, delta: i32) -> EventResponse {
+        let max_scroll = self.max_scroll();
+        let step = line_height(self.body_size()) * 3;
+        let y = (self.scroll_y - delta * step).clamp(0, max_scroll);
+        // Let an enclosing ScrollView handle documents that fit, and steps
+        // past either end.
+        if y == self.scroll_y {
+            return EventResponse::IGNORED;
+        }
+        self.scroll_y = y;
+        self.text_base.base.mark_dirty();
+        EventResponse::CONSUMED
+    }
+}
+
+// ── Helpers ──────────────────────────────────────────────────────────
+
+fn line_height(size: u16) -> i32 {
+    size as i32 * 4 / 3 + 2
+}
+
+/// Position the pending `line` at `y`, bottom-aligning its items so inline
+/// images push the text down, then advance `y` by `line_h`.
+fn flush_line(line: &mut Vec<Item>, y: &mut i32, line_h: i32, out: &mut Vec<Item>) {
+    for mut it in line.drain(..) {
+        let ih = it.height();
+        let off = match it {
+            // Centre text within its own line box, then bottom-align the box.
+            Item::Text { size, .. } => line_h - line_height(size) + (line_height(size) - ih) / 2,
+            _ => line_h - ih,
+        };
+        it.set_y(*y + off);
+        out.push(it);
+    }
+    *y += line_h;
+}
+
+fn push_span(out: &mut Vec<Span>, cur: &mut Vec<u8>, style: Style, link: Option<u16>) {
+    if !cur.is_empty() {
+        out.push(Span { text: core::mem::take(cur), style, link, image: None });
+    }
+}
+
+/// Split text into wrap units: each word together with the spaces after it.
+fn words(text: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut rest = text;
+    core::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let mut i = 0;
+        while i < rest.len() && rest[i] != b' ' { i += 1; }
+        while i < rest.len() && rest[i] == b' ' { i += 1; }
+        let (w, r) = rest.split_at(i);
+        rest = r;
+        Some(w)
+    })
+}
+
+fn find(hay: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() || hay.len() < needle.len() {
+        return None;
+    }
+    (0..=hay.len() - needle.len()).find(|&i| &hay[i..i + needle.len()] == needle)
+}
+
+/// Parse `[label](target)` starting at the `[` at `s[open]`.
+/// Returns (label, target, index after the closing parenthesis).
+fn bracket_link(s: &[u8], open: usize) -> Option<(&[u8], &[u8], usize)> {
+    let mut depth = 0;
+    let mut close = None;
+    for (i, &b) in s.iter().enumerate().skip(open) {
+        match b {
+            b'[' => depth += 1,
+            b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(i);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let close = close?;
+    if s.get(close + 1) != Some(&b'(') {
+        return None;
+    }
+    let end = close + 2 + s[close + 2..].iter().position(|&b| b == b')')?;
+    let mut target = trim(&s[close + 2..end]);
+    // Drop an optional "title" after the URL.
+    if let Some(sp) = target.iter().position(|&b| b == b' ') {
+        target = &target[..sp];
+    }
+    Some((&s[open + 1..close], target, end + 1))
+}
+
+fn trim(s: &[u8]) -> &[u8] {
+    let start = s.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(s.len());
+    let end = s.iter().rposition(|b| !b.is_ascii_whitespace()).map_or(start, |e| e + 1);
+    &s[start..end]
+}
+
+/// Leading indentation in columns (tab = 4) and the remaining text.
+fn split_indent(line: &[u8]) -> (usize, &[u8]) {
+    let mut cols = 0;
+    for (i, &b) in line.iter().enumerate() {
+        match b {
+            b' ' => cols += 1,
+            b'\t' => cols += 4,
+            _ => return (cols, &line[i..]),
+        }
+    }
+    (cols, &[])
+}
+
+fn expand_tabs(line: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(line.len());
+    for &b in line {
+        if b == b'\t' {
+            let n = 4 - out.len() % 4;
+            out.extend(core::iter::repeat(b' ').take(n));
+        } else {
+            out.push(b);
+        }
+    }
+    out
+}
+
+fn append_line(para: &mut Vec<u8>, line: &[u8]) {
+    if !para.is_empty() {
+        para.push(b' ');
+    }
+    para.extend_from_slice(trim(line));
+}
+
+fn heading(body: &[u8]) -> Option<(u8, &[u8])> {
+    let level = body.iter().take_while(|&&b| b == b'#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &body[level..];
+    if !rest.is_empty() && rest[0] != b' ' {
+        return None;
+    }
+    let mut rest = trim(rest);
+    // Optional closing hashes: "## Title ##".
+    while let Some(r) = rest.strip_suffix(b"#") {
+        rest = r;
+    }
+    Some((level as u8, trim(rest)))
+}
+
+fn is_rule(body: &[u8]) -> bool {
+    let c = body[0];
+    if c != b'-' && c != b'*' && c != b'_' {
+        return false;
+    }
+    let n = body.iter().filter(|&&b| b == c).count();
+    n >= 3 && body.iter().all(|&b| b == c || b == b' ')
+}
+
+/// List item marker: returns (marker text, item content). The marker is
+/// empty for bullets and e.g. "3." for ordered items.
+fn list_marker(body: &[u8]) -> Option<(Vec<u8>, &[u8])> {
+    if body.len() >= 2 && matches!(body[0], b'-' | b'*' | b'+') && body[1] == b' ' {
+        return Some((Vec::new(), &body[2..]));
+    }
+    let digits = body.iter().take_while(|b| b.is_ascii_digit()).count();
+    if digits > 0 && digits <= 9 && body.len() > digits + 1
+        && matches!(body[digits], b'.' | b')') && body[digits + 1] == b' '
+    {
+        let mut marker = body[..digits].to_vec();
+        marker.push(b'.');
+        return Some((marker, &body[digits + 2..]));
+    }
+    None
+}
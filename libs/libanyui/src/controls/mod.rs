@@ -11,6 +11,7 @@ pub mod view;
 pub mod label;
 pub mod button;
 pub mod textfield;
+pub mod input_filter;
 pub mod toggle;
 pub mod checkbox;
 pub mod slider;
@@ -49,6 +50,12 @@ pub mod text_editor;
 pub mod tree_view;
 pub mod radio_group;
 pub mod dropdown;
+pub mod toast;
+pub mod markdown_view;
+pub mod circular_progress;
+pub mod activity_spinner;
+pub mod form_layout;
+pub mod native_surface;
 
 /// Factory: create a concrete control based on `kind`.
 ///
@@ -75,6 +82,8 @@ pub fn create_control(
         ControlKind::View => Box::new(view::View::new(base)),
         ControlKind::Slider => Box::new(slider::Slider::new(base)),
         ControlKind::ProgressBar => Box::new(progress_bar::ProgressBar::new(base)),
+        ControlKind::CircularProgress => Box::new(circular_progress::CircularProgress::new(base)),
+        ControlKind::ActivitySpinner => Box::new(activity_spinner::ActivitySpinner::new(base)),
         ControlKind::TableView => Box::new(table_view::TableView::new(base)),
         ControlKind::ScrollView => Box::new(scroll_view::ScrollView::new(base)),
         ControlKind::Sidebar => Box::new(sidebar::Sidebar::new(base)),
@@ -88,7 +97,9 @@ pub fn create_control(
         ControlKind::StackPanel => Box::new(stack_panel::StackPanel::new(base)),
         ControlKind::FlowPanel => Box::new(flow_panel::FlowPanel::new(base)),
         ControlKind::TableLayout => Box::new(table_layout::TableLayout::new(base)),
+        ControlKind::FormLayout => Box::new(form_layout::FormLayout::new(base)),
         ControlKind::Canvas => Box::new(canvas::Canvas::new(base)),
+        ControlKind::NativeSurface => Box::new(native_surface::NativeSurface::new(base)),
         ControlKind::DataGrid => Box::new(data_grid::DataGrid::new(base)),
         ControlKind::TextEditor => Box::new(text_editor::TextEditor::new(base)),
         ControlKind::TreeView => Box::new(tree_view::TreeView::new(base)),
@@ -118,5 +129,7 @@ pub fn create_control(
         ControlKind::Badge => Box::new(badge::Badge::new(TextControlBase::new(base).with_text(text))),
         ControlKind::Tag => Box::new(tag::Tag::new(TextControlBase::new(base).with_text(text))),
         ControlKind::StatusIndicator => Box::new(status_indicator::StatusIndicator::new(TextControlBase::new(base).with_text(text))),
+        ControlKind::Toast => Box::new(toast::Toast::new(TextControlBase::new(base).with_text(text))),
+        ControlKind::MarkdownView => Box::new(markdown_view::MarkdownView::new(TextControlBase::new(base).with_text(text))),
     }
 }
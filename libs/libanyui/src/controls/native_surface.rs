@@ -0,0 +1,145 @@
+//! NativeSurface — an app-owned pixel buffer composited by the compositor.
+//!
+//! Unlike a Canvas, whose pixels are copied into the window on every
+//! render, a NativeSurface hands its buffer to the compositor as a
+//! sub-surface of the window (CMD_ATTACH_SURFACE). The app renders into the
+//! buffer (libgl, corevm VGA output, ...) and calls `present`; the frame
+//! reaches the screen without touching the window's own surface.
+//!
+//! The control takes part in layout like any other. After every render of
+//! its window the event loop sends the sub-surface's position and visible
+//! part (clipped by ScrollViews and Expanders) to the compositor when they
+//! changed. The buffer is drawn at the control's top-left corner in
+//! physical pixels; where it does not cover the control, the control's
+//! background color shows.
+
+use crate::control::{Control, ControlBase, ControlKind};
+use crate::syscall;
+
+pub struct NativeSurface {
+    pub(crate) base: ControlBase,
+    /// SHM region of the buffer (0 = no buffer allocated yet).
+    pub shm_id: u32,
+    /// Buffer mapping in this process.
+    pub pixels: *mut u32,
+    /// Buffer size in pixels.
+    pub buf_w: u32,
+    pub buf_h: u32,
+    /// Compositor channel the sub-surface was attached on.
+    pub channel_id: u32,
+    /// Compositor window the sub-surface is attached to.
+    pub window_id: u32,
+    /// Last placement sent to the compositor: buffer origin and visible
+    /// part (x, y, w, h) in physical content coordinates. `None` = not sent.
+    pub placed: Option<(i32, i32, i32, i32, u32, u32)>,
+}
+
+impl NativeSurface {
+    pub fn new(base: ControlBase) -> Self {
+        Self {
+            base,
+            shm_id: 0,
+            pixels: core::ptr::null_mut(),
+            buf_w: 0,
+            buf_h: 0,
+            channel_id: 0,
+            window_id: 0,
+            placed: None,
+        }
+    }
+
+    /// Allocate a `w`×`h` buffer and attach it to compositor window
+    /// `window_id`, replacing any previous buffer. Returns false if the
+    /// SHM region could not be created.
+    pub fn alloc(&mut self, channel_id: u32, window_id: u32, w: u32, h: u32) -> bool {
+        self.release();
+        let shm_id = syscall::shm_create(w * h * 4);
+        if shm_id == 0 {
+            return false;
+        }
+        let addr = syscall::shm_map(shm_id);
+        if addr == 0 {
+            syscall::shm_destroy(shm_id);
+            return false;
+        }
+        let cmd: [u32; 5] = [0x101E, window_id, shm_id, (w << 16) | h, 0]; // CMD_ATTACH_SURFACE
+        syscall::evt_chan_emit(channel_id, &cmd);
+        self.shm_id = shm_id;
+        self.pixels = addr as *mut u32;
+        self.buf_w = w;
+        self.buf_h = h;
+        self.channel_id = channel_id;
+        self.window_id = window_id;
+        self.placed = None;
+        self.base.mark_dirty();
+        true
+    }
+
+    /// Detach and free the buffer.
+    pub fn release(&mut self) {
+        if self.shm_id == 0 {
+            return;
+        }
+        let cmd: [u32; 5] = [0x1025, self.shm_id, 0, 0, 0]; // CMD_DETACH_SURFACE
+        syscall::evt_chan_emit(self.channel_id, &cmd);
+        syscall::shm_unmap(self.shm_id);
+        syscall::shm_destroy(self.shm_id);
+        self.shm_id = 0;
+        self.pixels = core::ptr::null_mut();
+        self.buf_w = 0;
+        self.buf_h = 0;
+        self.placed = None;
+    }
+
+    /// Tell the compositor a new frame is in the buffer.
+    pub fn present(&self) {
+        if self.shm_id != 0 {
+            let cmd: [u32; 5] = [0x1024, self.shm_id, 0, 0, 0]; // CMD_PRESENT_SURFACE
+            syscall::evt_chan_emit(self.channel_id, &cmd);
+        }
+    }
+
+    /// Move the sub-surface to buffer origin (x, y) with visible part
+    /// `clip` (physical content coordinates; `None` hides it). Only sends
+    /// a command when the placement changed.
+    pub fn place(&mut self, x: i32, y: i32, clip: Option<(i32, i32, u32, u32)>) {
+        if self.shm_id == 0 {
+            return;
+        }
+        let (cx, cy, cw, ch) = clip.unwrap_or((0, 0, 0, 0));
+        let placement = (x, y, cx, cy, cw, ch);
+        if self.placed == Some(placement) {
+            return;
+        }
+        self.placed = Some(placement);
+        let cmd: [u32; 5] = [
+            0x101F, // CMD_PLACE_SURFACE
+            self.shm_id,
+            ((x as u32) << 16) | (y as u32 & 0xFFFF),
+            ((cx as u32) << 16) | (cy as u32 & 0xFFFF),
+            (cw.min(0xFFFF) << 16) | ch.min(0xFFFF),
+        ];
+        syscall::evt_chan_emit(self.channel_id, &cmd);
+    }
+}
+
+impl Drop for NativeSurface {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+impl Control for NativeSurface {
+    fn base(&self) -> &ControlBase { &self.base }
+    fn base_mut(&mut self) -> &mut ControlBase { &mut self.base }
+    fn kind(&self) -> ControlKind { ControlKind::NativeSurface }
+
+    fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
+        // The buffer itself is composited above the window; draw only the
+        // background it may not cover.
+        let b = &self.base;
+        let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
+        let color = if b.color != 0 { b.color } else { 0xFF000000 };
+        crate::draw::fill_rect(surface, p.x, p.y, p.w, p.h, color);
+    }
+}
@@ -1,11 +1,89 @@
-use crate::control::{Control, ControlBase, ControlKind};
+use crate::control::{Control, ControlBase, ControlKind, ControlId};
+
+/// Determinate fill in the accent color (default).
+pub const MODE_NORMAL: u32 = 0;
+/// Unknown duration: a segment sweeps across the track, driven by a timer.
+pub const MODE_INDETERMINATE: u32 = 1;
+/// Progress halted: fill drawn in the warning color.
+pub const MODE_PAUSED: u32 = 2;
+/// Operation failed: fill drawn in the destructive color.
+pub const MODE_ERROR: u32 = 3;
+
+/// Repaint interval of the indeterminate animation (~30 fps).
+const ANIM_INTERVAL_MS: u32 = 33;
+/// Duration of one sweep across the track.
+const SWEEP_MS: u32 = 1500;
+/// Gap between segments in segmented style.
+const SEGMENT_GAP: u32 = 2;
 
 pub struct ProgressBar {
     pub(crate) base: ControlBase,
+    pub(crate) mode: u32,
+    /// Secondary (buffered) value, 0..100, drawn behind the main fill.
+    pub(crate) buffer: u32,
+    /// Number of blocks in segmented style (0 or 1 = continuous).
+    pub(crate) segments: u32,
+    /// Animation timer while in indeterminate mode (0 = none).
+    pub(crate) timer_id: u32,
 }
 
 impl ProgressBar {
-    pub fn new(base: ControlBase) -> Self { Self { base } }
+    pub fn new(base: ControlBase) -> Self {
+        Self { base, mode: MODE_NORMAL, buffer: 0, segments: 0, timer_id: 0 }
+    }
+
+    /// Switch display mode, starting or stopping the animation timer.
+    pub fn set_mode(&mut self, mode: u32) {
+        let mode = if mode > MODE_ERROR { MODE_NORMAL } else { mode };
+        if mode == self.mode {
+            return;
+        }
+        self.mode = mode;
+        let st = crate::state();
+        if mode == MODE_INDETERMINATE {
+            if self.timer_id == 0 {
+                self.timer_id = st.timers.set_timer(ANIM_INTERVAL_MS, progressbar_tick, self.base.id as u64);
+            }
+        } else if self.timer_id != 0 {
+            st.timers.kill_timer(self.timer_id);
+            self.timer_id = 0;
+        }
+        self.base.mark_dirty();
+    }
+
+    /// Fill color for the current mode.
+    fn fill_color(&self) -> u32 {
+        let tc = crate::theme::colors();
+        match self.mode {
+            MODE_PAUSED => tc.warning,
+            MODE_ERROR => tc.destructive,
+            _ => tc.accent,
+        }
+    }
+
+    /// Draw a filled span `[x0, x1)` of the track, split into blocks in
+    /// segmented style.
+    fn draw_span(&self, surface: &crate::draw::Surface, x: i32, y: i32, w: u32, h: u32, x0: i32, x1: i32, color: u32) {
+        if x1 <= x0 {
+            return;
+        }
+        let r = h / 2;
+        if self.segments < 2 {
+            crate::draw::fill_rounded_rect(surface, x0, y, (x1 - x0) as u32, h, r, color);
+            crate::draw::draw_top_highlight(surface, x0, y, (x1 - x0) as u32, r, crate::theme::lighten(color, 20));
+            return;
+        }
+        let n = self.segments;
+        for i in 0..n {
+            let sx = x + (w as u64 * i as u64 / n as u64) as i32;
+            let ex = x + (w as u64 * (i + 1) as u64 / n as u64) as i32 - if i + 1 < n { SEGMENT_GAP as i32 } else { 0 };
+            let (s, e) = (sx.max(x0), ex.min(x1));
+            if e > s {
+                crate::draw::fill_rect(surface, s, y, (e - s) as u32, h, color);
+                crate::draw::fill_rect(surface, s, y, (e - s) as u32, 1, crate::theme::lighten(color, 20));
+            }
+        }
+    }
 }
 
 impl Control for ProgressBar {
@@ -24,13 +102,51 @@ impl Control for ProgressBar {
         crate::draw::fill_rounded_rect(surface, x, y, w, h, r, tc.control_bg);
         crate::draw::draw_top_highlight(surface, x, y, w, r, crate::theme::darken(tc.control_bg, 8));
 
-        // Filled portion with accent
+        let color = self.fill_color();
+
+        if self.mode == MODE_INDETERMINATE {
+            // Marquee: a third-width segment sweeping left to right,
+            // entering and leaving through the track ends.
+            let seg = (w / 3).max(h);
+            let travel = w + seg;
+            let phase = crate::syscall::uptime_ms() % SWEEP_MS;
+            let start = x - seg as i32 + (travel as u64 * phase as u64 / SWEEP_MS as u64) as i32;
+            let x0 = start.max(x);
+            let x1 = (start + seg as i32).min(x + w as i32);
+            self.draw_span(surface, x, y, w, h, x0, x1, color);
+            return;
+        }
+
+        // Buffered portion behind the main fill
+        let buffer_w = (w as u64 * self.buffer.min(100) as u64 / 100) as i32;
+        self.draw_span(surface, x, y, w, h, x, x + buffer_w, crate::theme::darken(tc.control_bg, 24));
+
+        // Filled portion
         let val = b.state.min(100);
-        let fill_w = (w as u64 * val as u64 / 100) as u32;
-        if fill_w > 0 {
-            crate::draw::fill_rounded_rect(surface, x, y, fill_w, h, r, tc.accent);
-            // Subtle highlight on the filled portion
-            crate::draw::draw_top_highlight(surface, x, y, fill_w, r, crate::theme::lighten(tc.accent, 20));
+        let fill_w = (w as u64 * val as u64 / 100) as i32;
+        self.draw_span(surface, x, y, w, h, x, x + fill_w, color);
+    }
+}
+
+/// Animation tick for indeterminate progress bars (userdata = control ID).
+/// Stops itself once the control is gone or has left indeterminate mode.
+extern "C" fn progressbar_tick(timer_id: ControlId, _event_type: u32, userdata: u64) {
+    let st = crate::state();
+    let id = userdata as ControlId;
+    let animating = match st.controls.iter_mut().find(|c| c.id() == id) {
+        Some(ctrl) if ctrl.kind() == ControlKind::ProgressBar => {
+            let raw: *mut dyn Control = &mut **ctrl;
+            let pb = unsafe { &mut *(raw as *mut ProgressBar) };
+            if pb.mode == MODE_INDETERMINATE && pb.timer_id == timer_id {
+                pb.base.mark_dirty();
+                true
+            } else {
+                false
+            }
         }
+        _ => false,
+    };
+    if !animating {
+        st.timers.kill_timer(timer_id);
     }
 }
@@ -1,7 +1,31 @@
-use crate::control::{Control, ControlBase, ControlKind, EventResponse};
+//! ScrollView — vertically scrolling container.
+//!
+//! Content scrolls with the mouse wheel, by dragging the scrollbar thumb, and
+//! by dragging the content itself (touch-style). Releasing a content drag
+//! while it is still moving lets the content glide on and slow down
+//! (kinetic scrolling). `anyui_scrollview_set_offset` scrolls from code,
+//! optionally gliding to the target.
+//!
+//! The scrollbar is either always visible (track and thumb, the default) or
+//! an overlay: a thin thumb over the content that appears while scrolling
+//! and fades out shortly after.
+//!
+//! Offset changes made by the user or by an animation fire EVENT_SCROLL;
+//! `anyui_scrollview_get_scroll_info` reports the new offset, the change
+//! since the previous EVENT_SCROLL and the largest possible offset, so
+//! infinite lists can load more rows as the end comes into view.
+//!
+//! `anyui_ensure_visible` scrolls every ScrollView enclosing a control just
+//! far enough to show it, keeping the ScrollView's top and bottom padding
+//! clear; Tab navigation does the same for the newly focused control.
+
+use alloc::boxed::Box;
+use crate::control::{Control, ControlBase, ControlKind, ControlId, EventResponse};
 
 /// Scrollbar track width in pixels.
 const BAR_W: u32 = 10;
+/// Overlay scrollbar thumb width in pixels.
+const OVERLAY_BAR_W: u32 = 6;
 /// Padding around scrollbar edges.
 const BAR_PAD: i32 = 2;
 /// Minimum thumb height in pixels.
@@ -9,20 +33,105 @@ const MIN_THUMB: i32 = 20;
 /// Corner radius for the rounded thumb.
 const THUMB_RADIUS: u32 = 4;
 
+/// Scrollbar style: track and thumb always shown while content overflows.
+pub const SCROLLBAR_ALWAYS: u32 = 0;
+/// Scrollbar style: thin thumb over the content, shown while scrolling.
+pub const SCROLLBAR_OVERLAY: u32 = 1;
+
+/// Repaint interval of the fling/glide/fade animation.
+const ANIM_INTERVAL_MS: u32 = 16;
+/// Pointer travel before a press on the content turns into a drag.
+const PAN_THRESHOLD: i32 = 4;
+/// Velocity kept per frame while flinging (1.0 = no friction).
+const FLING_FRICTION: f32 = 0.95;
+/// Fling velocities below this (px per frame) stop the fling.
+const MIN_FLING_VELOCITY: f32 = 0.5;
+/// A drag released after resting this long does not fling.
+const FLING_REST_MS: u32 = 60;
+/// Overlay scrollbar: time fully visible after the last scroll.
+const OVERLAY_HOLD_MS: u32 = 800;
+/// Overlay scrollbar: fade-out duration.
+const OVERLAY_FADE_MS: u32 = 300;
+
+/// Content drag in progress.
+struct Pan {
+    /// Pointer Y at press.
+    start_y: i32,
+    /// Pointer Y at the previous move.
+    last_y: i32,
+    last_ms: u32,
+    /// Smoothed scroll velocity in px per frame (positive = scrolling down).
+    velocity: f32,
+    /// Passed `PAN_THRESHOLD`; content follows the pointer.
+    active: bool,
+}
+
+/// Animation driven by the timer.
+#[derive(Clone, Copy, PartialEq)]
+enum Motion {
+    None,
+    /// Kinetic scroll with the given velocity (px per frame).
+    Fling(f32),
+    /// Ease towards a target offset.
+    Glide(i32),
+}
+
 pub struct ScrollView {
     pub(crate) base: ControlBase,
     pub(crate) scroll_y: i32,
     /// Total content height (computed from children bounds).
     pub(crate) content_height: u32,
+    /// SCROLLBAR_ALWAYS or SCROLLBAR_OVERLAY.
+    pub scrollbar_style: u32,
+    /// Dragging the content scrolls it.
+    pub kinetic: bool,
     /// True while the user is dragging the scrollbar thumb.
     dragging_thumb: bool,
     /// Mouse-Y offset from thumb top when drag started.
     drag_anchor: i32,
+    pan: Option<Pan>,
+    motion: Motion,
+    /// Sub-pixel offset while flinging.
+    fling_pos: f32,
+    /// Animation timer (0 = none).
+    timer_id: u32,
+    /// Uptime of the last offset change (overlay scrollbar visibility).
+    last_scroll_ms: u32,
+    /// Offset reported by the previous EVENT_SCROLL.
+    reported_y: i32,
+    /// Offset change reported by the last EVENT_SCROLL.
+    pub(crate) last_delta: i32,
+    /// Offset changed since the last EVENT_SCROLL.
+    scroll_pending: bool,
 }
 
 impl ScrollView {
     pub fn new(base: ControlBase) -> Self {
-        Self { base, scroll_y: 0, content_height: 0, dragging_thumb: false, drag_anchor: 0 }
+        Self {
+            base,
+            scroll_y: 0,
+            content_height: 0,
+            scrollbar_style: SCROLLBAR_ALWAYS,
+            kinetic: true,
+            dragging_thumb: false,
+            drag_anchor: 0,
+            pan: None,
+            motion: Motion::None,
+            fling_pos: 0.0,
+            timer_id: 0,
+            last_scroll_ms: 0,
+            reported_y: 0,
+            last_delta: 0,
+            scroll_pending: false,
+        }
+    }
+
+    pub fn max_scroll(&self) -> i32 {
+        if self.content_height > self.base.h {
+            (self.content_height - self.base.h) as i32
+        } else {
+            0
+        }
     }
 
     /// Returns (track_h, thumb_h, max_scroll) if the scrollbar is visible.
@@ -38,6 +147,32 @@ impl ScrollView {
         Some((track_h, thumb_h, max_scroll))
     }
 
+    /// Width of the scrollbar for the current style.
+    fn bar_w(&self) -> u32 {
+        if self.scrollbar_style == SCROLLBAR_OVERLAY { OVERLAY_BAR_W } else { BAR_W }
+    }
+
+    /// Overlay thumb opacity (0–255) at the current time.
+    fn overlay_alpha(&self) -> u32 {
+        if self.dragging_thumb || self.pan.as_ref().map_or(false, |p| p.active) {
+            return 255;
+        }
+        let idle = crate::syscall::uptime_ms().saturating_sub(self.last_scroll_ms);
+        if self.last_scroll_ms == 0 || idle >= OVERLAY_HOLD_MS + OVERLAY_FADE_MS {
+            0
+        } else if idle <= OVERLAY_HOLD_MS {
+            255
+        } else {
+            255 * (OVERLAY_HOLD_MS + OVERLAY_FADE_MS - idle) / OVERLAY_FADE_MS
+        }
+    }
+
+    /// Whether the scrollbar can currently be grabbed.
+    fn bar_visible(&self) -> bool {
+        self.scrollbar_metrics().is_some()
+            && (self.scrollbar_style != SCROLLBAR_OVERLAY || self.overlay_alpha() > 0)
+    }
+
     /// Y position of thumb top, relative to this control's top.
     fn thumb_y(&self, track_h: i32, thumb_h: i32, max_scroll: i32) -> i32 {
         let frac = if max_scroll > 0 {
@@ -56,8 +191,93 @@ impl ScrollView {
         } else {
             0
         };
-        self.scroll_y = new_scroll.max(0).min(max_scroll);
-        self.base.state = self.scroll_y as u32;
+        self.set_scroll(new_scroll);
+    }
+
+    /// Move to offset `y` (clamped). Returns `false` if it did not change.
+    fn set_scroll(&mut self, y: i32) -> bool {
+        let y = y.max(0).min(self.max_scroll());
+        self.last_scroll_ms = crate::syscall::uptime_ms();
+        if self.scrollbar_style == SCROLLBAR_OVERLAY {
+            // Keep the overlay thumb on screen until it has faded out.
+            self.start_timer();
+            self.base.mark_dirty();
+        }
+        if y == self.scroll_y {
+            return false;
+        }
+        self.scroll_y = y;
+        self.base.state = y as u32;
+        self.scroll_pending = true;
+        self.base.mark_dirty();
+        true
+    }
+
+    /// Scroll to `offset` from code, either at once or gliding there.
+    pub fn scroll_to(&mut self, offset: u32, animated: bool) {
+        let target = offset.min(i32::MAX as u32) as i32;
+        if animated {
+            self.motion = Motion::Glide(target.max(0).min(self.max_scroll()));
+            self.start_timer();
+        } else {
+            self.motion = Motion::None;
+            self.set_scroll(target);
+            // Not a user scroll: no EVENT_SCROLL, and the next one reports
+            // its change from here.
+            self.scroll_pending = false;
+            self.reported_y = self.scroll_y;
+        }
+    }
+
+    fn start_timer(&mut self) {
+        if self.timer_id == 0 {
+            self.timer_id = crate::state().timers.set_timer(ANIM_INTERVAL_MS, scrollview_tick, self.base.id as u64);
+        }
+    }
+
+    /// Advance the fling/glide and overlay fade by one frame. Returns
+    /// `false` once there is nothing left to animate.
+    fn step(&mut self) -> bool {
+        match self.motion {
+            Motion::Fling(v) => {
+                let v = v * FLING_FRICTION;
+                self.fling_pos += v;
+                let y = self.fling_pos as i32;
+                let moved = self.set_scroll(y);
+                let at_edge = y <= 0 || y >= self.max_scroll();
+                self.motion = if (!moved && at_edge) || v.abs() < MIN_FLING_VELOCITY {
+                    Motion::None
+                } else {
+                    Motion::Fling(v)
+                };
+            }
+            Motion::Glide(target) => {
+                let remaining = target - self.scroll_y;
+                let step = remaining / 4;
+                let step = if step == 0 { remaining.signum() } else { step };
+                self.set_scroll(self.scroll_y + step);
+                if self.scroll_y == target || step == 0 {
+                    self.motion = Motion::None;
+                }
+            }
+            Motion::None => {}
+        }
+        if self.scrollbar_style == SCROLLBAR_OVERLAY {
+            self.base.mark_dirty();
+        }
+        self.motion != Motion::None
+            || (self.scrollbar_style == SCROLLBAR_OVERLAY && self.overlay_alpha() > 0)
+    }
+
+    /// Take the pending offset change, updating the reported delta.
+    fn take_scrolled(&mut self) -> bool {
+        if !self.scroll_pending {
+            return false;
+        }
+        self.scroll_pending = false;
+        self.last_delta = self.scroll_y - self.reported_y;
+        self.reported_y = self.scroll_y;
+        true
     }
 }
 
@@ -73,40 +293,70 @@ impl Control for ScrollView {
 
         if let Some((track_h, thumb_h, max_scroll)) = self.scrollbar_metrics() {
             let tc = crate::theme::colors();
-            let bar_w = crate::theme::scale(BAR_W);
+            let overlay = self.scrollbar_style == SCROLLBAR_OVERLAY;
+            let alpha = if overlay { self.overlay_alpha() } else { 255 };
+            if alpha == 0 {
+                return;
+            }
+            let bar_w = crate::theme::scale(self.bar_w());
             let bar_pad = crate::theme::scale_i32(BAR_PAD);
-            let thumb_r = crate::theme::scale(THUMB_RADIUS);
+            let thumb_r = crate::theme::scale(THUMB_RADIUS).min(bar_w / 2);
             let bar_x = x + w as i32 - bar_w as i32 - bar_pad;
 
-            // Track
-            let track_pad_h = if h > (bar_pad as u32 * 2) { h - bar_pad as u32 * 2 } else { 1 };
-            crate::draw::fill_rect(surface, bar_x, y + bar_pad, bar_w, track_pad_h, tc.scrollbar_track);
+            // Track (the overlay style draws the thumb alone)
+            if !overlay {
+                let track_pad_h = if h > (bar_pad as u32 * 2) { h - bar_pad as u32 * 2 } else { 1 };
+                crate::draw::fill_rect(surface, bar_x, y + bar_pad, bar_w, track_pad_h, tc.scrollbar_track);
+            }
 
             // Thumb — metrics are still in logical space so scale the thumb height for rendering
             let ty = self.thumb_y(track_h, thumb_h, max_scroll);
             let phys_ty = crate::theme::scale_i32(ty);
             let phys_thumb_h = crate::theme::scale(thumb_h as u32);
+            let color = if overlay {
+                crate::theme::with_alpha(tc.scrollbar, (tc.scrollbar >> 24) * alpha / 255)
+            } else {
+                tc.scrollbar
+            };
             crate::draw::fill_rounded_rect(
-                surface, bar_x, y + phys_ty, bar_w, phys_thumb_h, thumb_r, tc.scrollbar,
+                surface, bar_x, y + phys_ty, bar_w, phys_thumb_h, thumb_r, color,
             );
         }
     }
 
     fn is_interactive(&self) -> bool { true }
 
+    /// Programmatic scroll: keep `scroll_y` in sync with the state, otherwise
+    /// `update_scroll_bounds()` would revert it on the next layout pass.
+    fn set_state(&mut self, s: u32) {
+        let y = s.min(i32::MAX as u32) as i32;
+        self.motion = Motion::None;
+        self.reported_y = y;
+        if self.scroll_y != y || self.base.state != s {
+            self.scroll_y = y;
+            self.base.state = s;
+            self.base.mark_dirty();
+        }
+    }
+
     fn scrollbar_hit_x(&self) -> Option<i32> {
-        if self.scrollbar_metrics().is_some() {
+        if self.bar_visible() {
             // Hit area extends 2px left of the visible track for easier targeting.
-            Some(self.base.w as i32 - BAR_W as i32 - BAR_PAD - 2)
+            Some(self.base.w as i32 - self.bar_w() as i32 - BAR_PAD - 2)
         } else {
             None
         }
     }
 
-    fn handle_mouse_down(&mut self, local_x: i32, local_y: i32, _button: u32) -> EventResponse {
+    fn handle_mouse_down(&mut self, local_x: i32, local_y: i32, button: u32) -> EventResponse {
+        // Any press stops a running fling or glide.
+        self.motion = Motion::None;
+        if button & 0x02 != 0 {
+            return EventResponse::IGNORED;
+        }
         if let Some((track_h, thumb_h, max_scroll)) = self.scrollbar_metrics() {
-            let hit_x = self.base.w as i32 - BAR_W as i32 - BAR_PAD - 2;
-            if local_x >= hit_x {
+            let hit_x = self.base.w as i32 - self.bar_w() as i32 - BAR_PAD - 2;
+            if self.bar_visible() && local_x >= hit_x {
                 let ty = self.thumb_y(track_h, thumb_h, max_scroll);
                 if local_y >= ty && local_y < ty + thumb_h {
                     // Click on thumb — start drag, remember offset within thumb.
@@ -121,20 +371,54 @@ impl Control for ScrollView {
                 }
                 return EventResponse::CHANGED;
             }
+            if self.kinetic {
+                self.pan = Some(Pan {
+                    start_y: local_y,
+                    last_y: local_y,
+                    last_ms: crate::syscall::uptime_ms(),
+                    velocity: 0.0,
+                    active: false,
+                });
+                return EventResponse::CONSUMED;
+            }
         }
         EventResponse::IGNORED
     }
 
     fn handle_mouse_move(&mut self, _local_x: i32, local_y: i32) -> EventResponse {
-        if !self.dragging_thumb {
+        if self.dragging_thumb {
+            if let Some((track_h, thumb_h, max_scroll)) = self.scrollbar_metrics() {
+                let new_top = local_y - self.drag_anchor - BAR_PAD;
+                self.set_scroll_from_thumb(new_top, track_h, thumb_h, max_scroll);
+                return EventResponse::CHANGED;
+            }
             return EventResponse::IGNORED;
         }
-        if let Some((track_h, thumb_h, max_scroll)) = self.scrollbar_metrics() {
-            let new_top = local_y - self.drag_anchor - BAR_PAD;
-            self.set_scroll_from_thumb(new_top, track_h, thumb_h, max_scroll);
-            return EventResponse::CHANGED;
+        let dy = match self.pan.as_mut() {
+            Some(pan) => {
+                if !pan.active && (local_y - pan.start_y).abs() < PAN_THRESHOLD {
+                    return EventResponse::IGNORED;
+                }
+                pan.active = true;
+                let now = crate::syscall::uptime_ms();
+                let dy = local_y - pan.last_y;
+                let dt = now.saturating_sub(pan.last_ms);
+                if dt > 0 {
+                    // Pointer down = content down = offset up, hence the minus.
+                    let sample = -dy as f32 * ANIM_INTERVAL_MS as f32 / dt as f32;
+                    pan.velocity = pan.velocity * 0.4 + sample * 0.6;
+                    pan.last_ms = now;
+                }
+                pan.last_y = local_y;
+                dy
+            }
+            None => return EventResponse::IGNORED,
+        };
+        if self.set_scroll(self.scroll_y - dy) {
+            EventResponse::CHANGED
+        } else {
+            EventResponse::CONSUMED
         }
-        EventResponse::IGNORED
     }
 
     fn handle_mouse_up(&mut self, _local_x: i32, _local_y: i32, _button: u32) -> EventResponse {
@@ -142,21 +426,112 @@ impl Control for ScrollView {
             self.dragging_thumb = false;
             return EventResponse::CONSUMED;
         }
+        if let Some(pan) = self.pan.take() {
+            let resting = crate::syscall::uptime_ms().saturating_sub(pan.last_ms) >= FLING_REST_MS;
+            if pan.active && !resting && pan.velocity.abs() >= MIN_FLING_VELOCITY {
+                self.fling_pos = self.scroll_y as f32;
+                self.motion = Motion::Fling(pan.velocity);
+                self.start_timer();
+            }
+            return EventResponse::CONSUMED;
+        }
         EventResponse::IGNORED
     }
 
     fn handle_scroll(&mut self, delta: i32) -> EventResponse {
-        let max_scroll = if self.content_height > self.base.h {
-            (self.content_height - self.base.h) as i32
-        } else {
-            0
-        };
-        self.scroll_y = (self.scroll_y - delta * 20).max(0).min(max_scroll);
-        self.base.state = self.scroll_y as u32;
+        self.motion = Motion::None;
+        if !self.set_scroll(self.scroll_y - delta * 20) {
+            return EventResponse::IGNORED;
+        }
         EventResponse::CHANGED
     }
 }
 
+fn as_scroll_view(ctrl: &mut Box<dyn Control>) -> Option<&mut ScrollView> {
+    if ctrl.kind() == ControlKind::ScrollView {
+        let raw: *mut dyn Control = &mut **ctrl;
+        Some(unsafe { &mut *(raw as *mut ScrollView) })
+    } else {
+        None
+    }
+}
+
+/// Take the offset change of the ScrollView since its last EVENT_SCROLL
+/// (called from the event loop after pointer input). Returns `true` if
+/// EVENT_SCROLL should fire.
+pub fn take_scrolled(ctrl: &mut Box<dyn Control>) -> bool {
+    match as_scroll_view(ctrl) {
+        Some(sv) => sv.take_scrolled(),
+        None => false,
+    }
+}
+
+/// Fling/glide/overlay-fade tick (userdata = control ID). Fires EVENT_SCROLL
+/// for each frame that moved the content and stops itself when idle.
+extern "C" fn scrollview_tick(timer_id: ControlId, _event_type: u32, userdata: u64) {
+    let st = crate::state();
+    let id = userdata as ControlId;
+    let mut scrolled = false;
+    let animating = match st.controls.iter_mut().find(|c| c.id() == id).and_then(|c| as_scroll_view(c)) {
+        Some(sv) if sv.timer_id == timer_id => {
+            let more = sv.step();
+            if !more {
+                sv.timer_id = 0;
+            }
+            scrolled = sv.take_scrolled();
+            more
+        }
+        _ => false,
+    };
+    if !animating {
+        st.timers.kill_timer(timer_id);
+    }
+    if scrolled {
+        crate::control::invoke_event_callback(id, crate::control::EVENT_SCROLL);
+    }
+}
+
+/// Scroll the ScrollViews enclosing control `id`, innermost first, just far
+/// enough to show it. A control taller than the viewport is aligned to the
+/// top. Offsets jump (no animation, no EVENT_SCROLL). Returns `true` if any
+/// offset changed. Call after layout, so content heights are current.
+pub fn ensure_visible(controls: &mut [Box<dyn Control>], id: ControlId) -> bool {
+    let Some(mut cur) = crate::control::find_idx(controls, id) else { return false };
+    // Vertical extent of the control in the coordinates of the current parent.
+    let mut top = controls[cur].base().y;
+    let h = controls[cur].base().h as i32;
+    let mut moved = false;
+    loop {
+        let parent = controls[cur].parent_id();
+        if parent == 0 || parent == controls[cur].id() {
+            break;
+        }
+        let Some(pi) = crate::control::find_idx(controls, parent) else { break };
+        if let Some(sv) = as_scroll_view(&mut controls[pi]) {
+            let pad = sv.base.padding;
+            let view_h = sv.base.h as i32;
+            let (lo, hi) = (top - pad.top, top + h + pad.bottom);
+            let mut y = sv.scroll_y;
+            if lo < y || hi - lo > view_h {
+                y = lo;
+            } else if hi > y + view_h {
+                y = hi - view_h;
+            }
+            if y != sv.scroll_y {
+                let before = sv.scroll_y;
+                sv.scroll_to(y.max(0) as u32, false);
+                moved |= sv.scroll_y != before;
+            }
+            top -= sv.scroll_y;
+        } else if controls[pi].kind() == ControlKind::Expander && controls[pi].base().state != 0 {
+            top += crate::controls::expander::HEADER_HEIGHT as i32;
+        }
+        top += controls[pi].base().y;
+        cur = pi;
+    }
+    moved
+}
+
 /// Update content_height for all ScrollViews (called from event_loop after layout).
 pub fn update_scroll_bounds(controls: &mut [alloc::boxed::Box<dyn Control>]) {
     for i in 0..controls.len() {
@@ -175,11 +550,12 @@ pub fn update_scroll_bounds(controls: &mut [alloc::boxed::Box<dyn Control>]) {
             let raw: *mut dyn Control = &mut *controls[i];
             let sv = unsafe { &mut *(raw as *mut ScrollView) };
             sv.content_height = max_bottom.max(0) as u32;
-            let max_scroll = if sv.content_height > sv.base.h {
-                (sv.content_height - sv.base.h) as i32
-            } else { 0 };
+            let max_scroll = sv.max_scroll();
             sv.scroll_y = sv.scroll_y.min(max_scroll).max(0);
             sv.base.state = sv.scroll_y as u32;
+            if let Motion::Glide(target) = sv.motion {
+                sv.motion = Motion::Glide(target.min(max_scroll));
+            }
         }
     }
 }
@@ -1,5 +1,17 @@
+//! SearchField — pill-shaped text input with search conveniences.
+//!
+//! Text changes can be debounced: with `debounce_ms > 0` each edit re-arms a
+//! one-shot event-loop timer and EVENT_CHANGE fires only once typing pauses.
+//! The field keeps an app-supplied suggestion list and an automatic history
+//! of submitted queries (shown while the text is empty).  The event loop
+//! presents the active list in a popup window (reusing the ContextMenu popup
+//! infrastructure); Up/Down/Enter/Escape navigate it from the field.
+
 use alloc::vec::Vec;
-use crate::control::{Control, ControlBase, TextControlBase, ControlKind, EventResponse};
+use crate::control::{Control, ControlBase, ControlId, TextControlBase, ControlKind, EventResponse};
+
+/// Maximum number of remembered queries.
+const MAX_HISTORY: usize = 10;
 
 pub struct SearchField {
     pub(crate) text_base: TextControlBase,
@@ -11,6 +23,21 @@ pub struct SearchField {
     sel_anchor: usize,
     /// Whether a mouse drag selection is in progress.
     dragging: bool,
+    /// Delay before EVENT_CHANGE fires after an edit (0 = immediately).
+    pub(crate) debounce_ms: u32,
+    /// Pending debounce timer (0 = none).
+    debounce_timer: u32,
+    /// Suggestions supplied by the app for the current text.
+    pub(crate) suggestions: Vec<Vec<u8>>,
+    /// Recently submitted queries, newest first.
+    pub(crate) history: Vec<Vec<u8>>,
+    /// Highlighted entry of the suggestion list (-1 = none).
+    pub(crate) suggest_index: i32,
+    /// List hidden by Escape or a submit until the text changes again.
+    list_dismissed: bool,
+    /// Set when the suggestion popup must be opened, updated or closed;
+    /// the event loop reconciles it and clears the flag.
+    pub(crate) popup_dirty: bool,
 }
 
 impl SearchField {
@@ -22,7 +49,98 @@ impl SearchField {
             scroll_x: 0,
             sel_anchor: 0,
             dragging: false,
+            debounce_ms: 0,
+            debounce_timer: 0,
+            suggestions: Vec::new(),
+            history: Vec::new(),
+            suggest_index: -1,
+            list_dismissed: false,
+            popup_dirty: false,
+        }
+    }
+
+    /// Replace the suggestion list (pipe-separated; empty entries dropped).
+    pub fn set_suggestions(&mut self, data: &[u8]) {
+        self.suggestions = data.split(|&b| b == b'|')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_vec())
+            .collect();
+        self.suggest_index = -1;
+        self.list_dismissed = false;
+        self.popup_dirty = true;
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.suggest_index = -1;
+        self.popup_dirty = true;
+    }
+
+    /// Entries offered below the field: the history while the text is
+    /// empty, the app's suggestions otherwise.
+    pub(crate) fn list_items(&self) -> &[Vec<u8>] {
+        if self.text_base.text.is_empty() { &self.history } else { &self.suggestions }
+    }
+
+    fn list_active(&self) -> bool {
+        !self.list_dismissed && !self.list_items().is_empty()
+    }
+
+    /// Pipe-separated popup menu text, or `None` if no list should show.
+    pub(crate) fn popup_items(&self) -> Option<Vec<u8>> {
+        if !self.focused || !self.list_active() { return None; }
+        let mut out = Vec::new();
+        for (i, item) in self.list_items().iter().enumerate() {
+            if i > 0 { out.push(b'|'); }
+            out.extend(item.iter().map(|&b| if b == b'|' { b' ' } else { b }));
         }
+        Some(out)
+    }
+
+    /// Take list entry `index` as the query: replaces the text, records it
+    /// in the history and closes the list.  Returns false if out of range.
+    pub(crate) fn accept_item(&mut self, index: usize) -> bool {
+        let item = match self.list_items().get(index) {
+            Some(item) => item.clone(),
+            None => return false,
+        };
+        self.text_base.text = item;
+        self.cursor_pos = self.text_base.text.len();
+        self.sel_anchor = self.cursor_pos;
+        self.ensure_cursor_visible();
+        self.cancel_debounce();
+        self.remember_query();
+        self.list_dismissed = true;
+        self.popup_dirty = true;
+        self.text_base.base.mark_dirty();
+        true
+    }
+
+    /// Move the current text to the front of the history.
+    fn remember_query(&mut self) {
+        let text = &self.text_base.text;
+        if text.is_empty() { return; }
+        self.history.retain(|h| h != text);
+        self.history.insert(0, text.clone());
+        self.history.truncate(MAX_HISTORY);
+    }
+
+    /// (Re)start the debounce timer; the change event fires when it elapses.
+    fn arm_debounce(&mut self) {
+        let timers = &mut crate::state().timers;
+        if self.debounce_timer != 0 {
+            timers.kill_timer(self.debounce_timer);
+        }
+        self.debounce_timer = timers.set_timer(self.debounce_ms, debounce_tick, self.text_base.base.id as u64);
+    }
+
+    /// Drop a pending debounce timer.  Returns true if a change event was
+    /// still outstanding.
+    fn cancel_debounce(&mut self) -> bool {
+        if self.debounce_timer == 0 { return false; }
+        crate::state().timers.kill_timer(self.debounce_timer);
+        self.debounce_timer = 0;
+        true
     }
 
     const TEXT_LEFT: i32 = 26; // After search icon.
@@ -94,6 +212,118 @@ impl SearchField {
         while i < len && !is_word_char(text[i]) { i += 1; }
         i
     }
+
+    /// Editing and cursor keys; the trait handler adds list navigation.
+    fn edit_key(&mut self, keycode: u32, char_code: u32, modifiers: u32) -> EventResponse {
+        use crate::control::*;
+        let shift = modifiers & MOD_SHIFT != 0;
+        let ctrl = modifiers & MOD_CTRL != 0;
+
+        if ctrl && (char_code == b'a' as u32 || char_code == b'A' as u32) {
+            self.sel_anchor = 0;
+            self.cursor_pos = self.text_base.text.len();
+            self.ensure_cursor_visible();
+            return EventResponse::CONSUMED;
+        }
+        if ctrl && (char_code == b'c' as u32 || char_code == b'C' as u32) {
+            if self.has_selection() {
+                crate::compositor::clipboard_set(self.selected_bytes());
+            }
+            return EventResponse::CONSUMED;
+        }
+        if ctrl && (char_code == b'x' as u32 || char_code == b'X' as u32) {
+            if self.has_selection() {
+                let bytes = self.selected_bytes().to_vec();
+                crate::compositor::clipboard_set(&bytes);
+                self.delete_selection();
+                self.ensure_cursor_visible();
+                return EventResponse::CHANGED;
+            }
+            return EventResponse::CONSUMED;
+        }
+        if ctrl && (char_code == b'v' as u32 || char_code == b'V' as u32) {
+            if let Some(clip) = crate::compositor::clipboard_get() {
+                let filtered: Vec<u8> = clip.into_iter().filter(|&b| b >= 0x20 && b < 0x7F).collect();
+                if !filtered.is_empty() {
+                    self.delete_selection();
+                    let pos = self.cursor_pos.min(self.text_base.text.len());
+                    for (i, &b) in filtered.iter().enumerate() {
+                        self.text_base.text.insert(pos + i, b);
+                    }
+                    self.cursor_pos = pos + filtered.len();
+                    self.sel_anchor = self.cursor_pos;
+                    self.ensure_cursor_visible();
+                    return EventResponse::CHANGED;
+                }
+            }
+            return EventResponse::CONSUMED;
+        }
+
+        if keycode == KEY_ENTER { return EventResponse::SUBMIT; }
+
+        if char_code >= 0x20 && char_code < 0x7F && !ctrl {
+            self.delete_selection();
+            let pos = self.cursor_pos.min(self.text_base.text.len());
+            self.text_base.text.insert(pos, char_code as u8);
+            self.cursor_pos = pos + 1;
+            self.sel_anchor = self.cursor_pos;
+            self.ensure_cursor_visible();
+            return EventResponse::CHANGED;
+        }
+
+        if keycode == KEY_BACKSPACE {
+            if self.has_selection() { self.delete_selection(); self.ensure_cursor_visible(); return EventResponse::CHANGED; }
+            if self.cursor_pos > 0 && !self.text_base.text.is_empty() {
+                self.cursor_pos -= 1;
+                self.text_base.text.remove(self.cursor_pos);
+                self.sel_anchor = self.cursor_pos;
+                self.ensure_cursor_visible();
+                return EventResponse::CHANGED;
+            }
+            return EventResponse::CONSUMED;
+        }
+        if keycode == KEY_DELETE {
+            if self.has_selection() { self.delete_selection(); self.ensure_cursor_visible(); return EventResponse::CHANGED; }
+            if self.cursor_pos < self.text_base.text.len() {
+                self.text_base.text.remove(self.cursor_pos);
+                self.sel_anchor = self.cursor_pos;
+                self.ensure_cursor_visible();
+                return EventResponse::CHANGED;
+            }
+            return EventResponse::CONSUMED;
+        }
+
+        if keycode == KEY_LEFT {
+            if ctrl { self.cursor_pos = self.word_left(self.cursor_pos); }
+            else if !shift && self.has_selection() { let (s, _) = self.selection_range(); self.cursor_pos = s; }
+            else if self.cursor_pos > 0 { self.cursor_pos -= 1; }
+            if !shift { self.sel_anchor = self.cursor_pos; }
+            self.ensure_cursor_visible();
+            return EventResponse::CONSUMED;
+        }
+        if keycode == KEY_RIGHT {
+            if ctrl { self.cursor_pos = self.word_right(self.cursor_pos); }
+            else if !shift && self.has_selection() { let (_, e) = self.selection_range(); self.cursor_pos = e; }
+            else if self.cursor_pos < self.text_base.text.len() { self.cursor_pos += 1; }
+            if !shift { self.sel_anchor = self.cursor_pos; }
+            self.ensure_cursor_visible();
+            return EventResponse::CONSUMED;
+        }
+        if keycode == KEY_HOME {
+            self.cursor_pos = 0;
+            if !shift { self.sel_anchor = 0; }
+            self.ensure_cursor_visible();
+            return EventResponse::CONSUMED;
+        }
+        if keycode == KEY_END {
+            self.cursor_pos = self.text_base.text.len();
+            if !shift { self.sel_anchor = self.cursor_pos; }
+            self.ensure_cursor_visible();
+            return EventResponse::CONSUMED;
+        }
+
+        EventResponse::IGNORED
+    }
 }
 
 fn is_word_char(b: u8) -> bool {
@@ -107,6 +337,10 @@ impl Control for SearchField {
     fn text_base_mut(&mut self) -> Option<&mut crate::control::TextControlBase> { Some(&mut self.text_base) }
     fn kind(&self) -> ControlKind { ControlKind::SearchField }
 
+    fn baseline(&self) -> Option<i32> {
+        Some(6 + crate::draw::text_ascent(self.text_base.text_style.font_size))
+    }
+
     fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
         let b = &self.text_base.base;
         let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
@@ -185,6 +419,7 @@ impl Control for SearchField {
     }
 
     fn is_interactive(&self) -> bool { !self.text_base.base.disabled }
+    fn default_cursor(&self) -> u32 { crate::control::CURSOR_IBEAM }
     fn accepts_focus(&self) -> bool { !self.text_base.base.disabled }
 
     fn handle_mouse_down(&mut self, lx: i32, _ly: i32, _button: u32) -> EventResponse {
@@ -239,118 +474,60 @@ impl Control for SearchField {
     }
 
     fn handle_key_down(&mut self, keycode: u32, char_code: u32, modifiers: u32) -> EventResponse {
-        use crate::control::*;
-        let shift = modifiers & MOD_SHIFT != 0;
-        let ctrl = modifiers & MOD_CTRL != 0;
-
-        if ctrl && (char_code == b'a' as u32 || char_code == b'A' as u32) {
-            self.sel_anchor = 0;
-            self.cursor_pos = self.text_base.text.len();
-            self.ensure_cursor_visible();
-            return EventResponse::CONSUMED;
-        }
-        if ctrl && (char_code == b'c' as u32 || char_code == b'C' as u32) {
-            if self.has_selection() {
-                crate::compositor::clipboard_set(self.selected_bytes());
-            }
-            return EventResponse::CONSUMED;
-        }
-        if ctrl && (char_code == b'x' as u32 || char_code == b'X' as u32) {
-            if self.has_selection() {
-                let bytes = self.selected_bytes().to_vec();
-                crate::compositor::clipboard_set(&bytes);
-                self.delete_selection();
-                self.ensure_cursor_visible();
-                return EventResponse::CHANGED;
-            }
-            return EventResponse::CONSUMED;
-        }
-        if ctrl && (char_code == b'v' as u32 || char_code == b'V' as u32) {
-            if let Some(clip) = crate::compositor::clipboard_get() {
-                let filtered: Vec<u8> = clip.into_iter().filter(|&b| b >= 0x20 && b < 0x7F).collect();
-                if !filtered.is_empty() {
-                    self.delete_selection();
-                    let pos = self.cursor_pos.min(self.text_base.text.len());
-                    for (i, &b) in filtered.iter().enumerate() {
-                        self.text_base.text.insert(pos + i, b);
-                    }
-                    self.cursor_pos = pos + filtered.len();
-                    self.sel_anchor = self.cursor_pos;
-                    self.ensure_cursor_visible();
-                    return EventResponse::CHANGED;
+        use crate::control::{KEY_UP, KEY_DOWN, KEY_ENTER, KEY_ESCAPE};
+
+        // Suggestion list navigation
+        if self.list_active() {
+            let n = self.list_items().len() as i32;
+            match keycode {
+                KEY_DOWN => {
+                    self.suggest_index = (self.suggest_index + 1).min(n - 1);
+                    self.popup_dirty = true;
+                    return EventResponse::CONSUMED;
                 }
+                KEY_UP => {
+                    self.suggest_index = (self.suggest_index - 1).max(-1);
+                    self.popup_dirty = true;
+                    return EventResponse::CONSUMED;
+                }
+                KEY_ESCAPE => {
+                    self.list_dismissed = true;
+                    self.suggest_index = -1;
+                    self.popup_dirty = true;
+                    return EventResponse::CONSUMED;
+                }
+                KEY_ENTER if self.suggest_index >= 0 => {
+                    self.accept_item(self.suggest_index as usize);
+                    return EventResponse { consumed: true, fire_click: false, fire_change: true, fire_submit: true };
+                }
+                _ => {}
             }
-            return EventResponse::CONSUMED;
         }
 
-        if keycode == KEY_ENTER { return EventResponse::SUBMIT; }
-
-        if char_code >= 0x20 && char_code < 0x7F && !ctrl {
-            self.delete_selection();
-            let pos = self.cursor_pos.min(self.text_base.text.len());
-            self.text_base.text.insert(pos, char_code as u8);
-            self.cursor_pos = pos + 1;
-            self.sel_anchor = self.cursor_pos;
-            self.ensure_cursor_visible();
-            return EventResponse::CHANGED;
-        }
-
-        if keycode == KEY_BACKSPACE {
-            if self.has_selection() { self.delete_selection(); self.ensure_cursor_visible(); return EventResponse::CHANGED; }
-            if self.cursor_pos > 0 && !self.text_base.text.is_empty() {
-                self.cursor_pos -= 1;
-                self.text_base.text.remove(self.cursor_pos);
-                self.sel_anchor = self.cursor_pos;
-                self.ensure_cursor_visible();
-                return EventResponse::CHANGED;
+        let mut resp = self.edit_key(keycode, char_code, modifiers);
+        if resp.fire_change {
+            self.suggest_index = -1;
+            self.list_dismissed = false;
+            self.popup_dirty = true;
+            if self.debounce_ms > 0 {
+                self.arm_debounce();
+                resp.fire_change = false;
             }
-            return EventResponse::CONSUMED;
         }
-        if keycode == KEY_DELETE {
-            if self.has_selection() { self.delete_selection(); self.ensure_cursor_visible(); return EventResponse::CHANGED; }
-            if self.cursor_pos < self.text_base.text.len() {
-                self.text_base.text.remove(self.cursor_pos);
-                self.sel_anchor = self.cursor_pos;
-                self.ensure_cursor_visible();
-                return EventResponse::CHANGED;
-            }
-            return EventResponse::CONSUMED;
+        if resp.fire_submit {
+            // Deliver a still-pending change before the submit.
+            resp.fire_change = self.cancel_debounce();
+            self.remember_query();
+            self.list_dismissed = true;
+            self.popup_dirty = true;
         }
-
-        if keycode == KEY_LEFT {
-            if ctrl { self.cursor_pos = self.word_left(self.cursor_pos); }
-            else if !shift && self.has_selection() { let (s, _) = self.selection_range(); self.cursor_pos = s; }
-            else if self.cursor_pos > 0 { self.cursor_pos -= 1; }
-            if !shift { self.sel_anchor = self.cursor_pos; }
-            self.ensure_cursor_visible();
-            return EventResponse::CONSUMED;
-        }
-        if keycode == KEY_RIGHT {
-            if ctrl { self.cursor_pos = self.word_right(self.cursor_pos); }
-            else if !shift && self.has_selection() { let (_, e) = self.selection_range(); self.cursor_pos = e; }
-            else if self.cursor_pos < self.text_base.text.len() { self.cursor_pos += 1; }
-            if !shift { self.sel_anchor = self.cursor_pos; }
-            self.ensure_cursor_visible();
-            return EventResponse::CONSUMED;
-        }
-        if keycode == KEY_HOME {
-            self.cursor_pos = 0;
-            if !shift { self.sel_anchor = 0; }
-            self.ensure_cursor_visible();
-            return EventResponse::CONSUMED;
-        }
-        if keycode == KEY_END {
-            self.cursor_pos = self.text_base.text.len();
-            if !shift { self.sel_anchor = self.cursor_pos; }
-            self.ensure_cursor_visible();
-            return EventResponse::CONSUMED;
-        }
-
-        EventResponse::IGNORED
+        resp
     }
 
     fn handle_focus(&mut self) {
         self.focused = true;
+        self.list_dismissed = false;
+        self.popup_dirty = true;
         self.text_base.base.focused = true;
         self.text_base.base.mark_dirty();
         self.sel_anchor = 0;
@@ -363,6 +540,24 @@ impl Control for SearchField {
         self.text_base.base.focused = false;
         self.dragging = false;
         self.sel_anchor = self.cursor_pos;
+        self.suggest_index = -1;
+        self.popup_dirty = true;
         self.text_base.base.mark_dirty();
     }
 }
+
+/// Debounce timer callback (userdata = field ID): one-shot, fires the
+/// field's EVENT_CHANGE callback once typing has paused.
+extern "C" fn debounce_tick(timer_id: ControlId, _event_type: u32, userdata: u64) {
+    let st = crate::state();
+    st.timers.kill_timer(timer_id);
+    let id = userdata as ControlId;
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if ctrl.kind() != ControlKind::SearchField { return; }
+        let raw: *mut dyn Control = &mut **ctrl;
+        let sf = unsafe { &mut *(raw as *mut SearchField) };
+        if sf.debounce_timer != timer_id { return; }
+        sf.debounce_timer = 0;
+        crate::control::invoke_event_callback(id, crate::control::EVENT_CHANGE);
+    }
+}
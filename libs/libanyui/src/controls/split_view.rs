@@ -75,6 +75,13 @@ impl Control for SplitView {
     }
 
     fn is_interactive(&self) -> bool { true }
+    fn default_cursor(&self) -> u32 {
+        // Only the divider is exposed; the panes cover the rest.
+        match self.orientation {
+            Orientation::Horizontal => crate::control::CURSOR_RESIZE_H,
+            Orientation::Vertical => crate::control::CURSOR_RESIZE_V,
+        }
+    }
 
     fn layout_children(&self, _controls: &[Box<dyn Control>]) -> Option<Vec<ChildLayout>> {
         let children = &self.base.children;
@@ -1,17 +1,242 @@
 //! StackPanel — layout container that stacks children vertically or horizontally.
+//!
+//! A reorderable StackPanel reserves a handle strip at the leading edge of
+//! each row (left for vertical stacks, top for horizontal ones). Pressing a
+//! handle lifts that child; while dragging, the other children slide aside to
+//! open a gap at the drop position. Releasing commits the new order and fires
+//! EVENT_REORDER with the old/new child indices (`anyui_stackpanel_get_last_reorder`).
+//!
+//! Horizontal stacks in a right-to-left window run from right to left. Slot
+//! positions are kept in logical (leading-edge) coordinates and only mirrored
+//! when children are placed, grips drawn, or pointer positions read.
 
 use alloc::boxed::Box;
 use alloc::vec::Vec;
-use crate::control::{Control, ControlBase, ControlKind, ChildLayout, Orientation, find_idx};
+use crate::control::{Control, ControlBase, ControlKind, ControlId, ChildLayout, EventResponse, Orientation, find_idx};
+
+/// Default handle strip size (logical px).
+const DEFAULT_HANDLE_SIZE: u32 = 20;
+/// Repaint interval of the slide animation.
+const ANIM_INTERVAL_MS: u32 = 16;
+
+/// A child being dragged by its handle.
+struct Drag {
+    id: ControlId,
+    /// Visible index the drag started from.
+    from: usize,
+    /// Visible index the child would be dropped at.
+    to: usize,
+    /// Pointer offset from the child's slot start (main axis).
+    grab: i32,
+    /// Pointer position (main axis, panel-local).
+    pointer: i32,
+}
 
 pub struct StackPanel {
     pub(crate) base: ControlBase,
     pub orientation: Orientation,
+    /// Children can be dragged to new positions by their handle strip.
+    pub reorderable: bool,
+    /// Size of the handle strip across the stacking axis (logical px).
+    pub handle_size: u32,
+    /// (child, main-axis extent incl. margins) of each visible child, in
+    /// order, from the last layout pass.
+    pub(crate) slots: Vec<(ControlId, i32)>,
+    drag: Option<Drag>,
+    /// Main-axis offsets of children still sliding into place.
+    offsets: Vec<(ControlId, i32)>,
+    /// Slide animation timer (0 = none).
+    timer_id: u32,
+    /// (old, new) child indices of the last drag reorder.
+    pub last_reorder: (u32, u32),
+    /// A drag reorder completed and EVENT_REORDER has not been fired yet.
+    reorder_pending: bool,
 }
 
 impl StackPanel {
     pub fn new(base: ControlBase) -> Self {
-        Self { base, orientation: Orientation::Vertical }
+        Self {
+            base,
+            orientation: Orientation::Vertical,
+            reorderable: false,
+            handle_size: DEFAULT_HANDLE_SIZE,
+            slots: Vec::new(),
+            drag: None,
+            offsets: Vec::new(),
+            timer_id: 0,
+            last_reorder: (0, 0),
+            reorder_pending: false,
+        }
+    }
+
+    fn vertical(&self) -> bool {
+        self.orientation == Orientation::Vertical
+    }
+
+    /// Horizontal stack in a right-to-left window: the main axis runs
+    /// from the right edge.
+    fn mirrored(&self) -> bool {
+        !self.vertical() && crate::bidi::window_rtl()
+    }
+
+    /// Panel-local pointer position along the main axis, in logical
+    /// (leading-edge) coordinates.
+    fn main_coord(&self, local_x: i32, local_y: i32) -> i32 {
+        if self.vertical() {
+            local_y
+        } else if self.mirrored() {
+            self.base.w as i32 - local_x
+        } else {
+            local_x
+        }
+    }
+
+    /// Handle strip reserved before each child (0 when not reorderable).
+    fn handle_extent(&self) -> i32 {
+        if self.reorderable { self.handle_size as i32 } else { 0 }
+    }
+
+    /// Main-axis start of the first slot.
+    fn main_start(&self) -> i32 {
+        if self.vertical() { self.base.padding.top } else { self.base.padding.left }
+    }
+
+    /// Visible children in display order: the natural order, or with the
+    /// dragged child moved to its drop index.
+    fn display_order(&self, slots: &[(ControlId, i32)]) -> Vec<(ControlId, i32)> {
+        let mut order: Vec<(ControlId, i32)> = slots.to_vec();
+        if let Some(d) = &self.drag {
+            if let Some(pos) = order.iter().position(|&(id, _)| id == d.id) {
+                let item = order.remove(pos);
+                order.insert(d.to.min(order.len()), item);
+            }
+        }
+        order
+    }
+
+    /// Main-axis slot start of every visible child as currently displayed
+    /// (drag gap, dragged child under the pointer, slide offsets applied).
+    fn positions(&self, slots: &[(ControlId, i32)]) -> Vec<(ControlId, i32)> {
+        let start = self.main_start();
+        let total: i32 = slots.iter().map(|&(_, e)| e).sum();
+        let mut cursor = start;
+        let mut out = Vec::with_capacity(slots.len());
+        for (id, extent) in self.display_order(slots) {
+            let mut pos = cursor;
+            match &self.drag {
+                Some(d) if d.id == id => {
+                    pos = (d.pointer - d.grab).clamp(start, (start + total - extent).max(start));
+                }
+                _ => {
+                    if let Some(&(_, off)) = self.offsets.iter().find(|&&(oid, _)| oid == id) {
+                        pos += off;
+                    }
+                }
+            }
+            out.push((id, pos));
+            cursor += extent;
+        }
+        out
+    }
+
+    /// Start sliding children from their current positions to the ones
+    /// produced by `update` (which changes the order or drag state).
+    fn animate(&mut self, update: impl FnOnce(&mut Self)) {
+        let slots = self.slots.clone();
+        let before = self.positions(&slots);
+        update(self);
+        let after = self.positions(&self.slots.clone());
+        for (id, new_pos) in after {
+            let old_pos = match before.iter().find(|&&(bid, _)| bid == id) {
+                Some(&(_, p)) => p,
+                None => continue,
+            };
+            if self.drag.as_ref().map_or(false, |d| d.id == id) || old_pos == new_pos {
+                continue;
+            }
+            // `before` already includes any running offset; restart from there.
+            let base_pos = new_pos - self.offsets.iter().find(|&&(oid, _)| oid == id).map_or(0, |&(_, o)| o);
+            let off = old_pos - base_pos;
+            self.offsets.retain(|&(oid, _)| oid != id);
+            if off != 0 {
+                self.offsets.push((id, off));
+            }
+        }
+        if !self.offsets.is_empty() && self.timer_id == 0 {
+            self.timer_id = crate::state().timers.set_timer(ANIM_INTERVAL_MS, stackpanel_tick, self.base.id as u64);
+        }
+        crate::mark_needs_layout();
+        self.base.mark_dirty();
+    }
+
+    /// Visible index of the slot whose span contains main-axis position `pos`.
+    fn slot_at(&self, pos: i32) -> Option<usize> {
+        let mut cursor = self.main_start();
+        for (i, &(_, extent)) in self.slots.iter().enumerate() {
+            if pos >= cursor && pos < cursor + extent {
+                return Some(i);
+            }
+            cursor += extent;
+        }
+        None
+    }
+
+    /// Drop index for the dragged child: the number of other children whose
+    /// center lies before the dragged child's center.
+    fn drop_index(&self, d: &Drag) -> usize {
+        let extent = self.slots.get(d.from).map_or(0, |&(_, e)| e);
+        let center = d.pointer - d.grab + extent / 2;
+        let mut cursor = self.main_start();
+        let mut index = 0;
+        for &(id, e) in &self.slots {
+            if id != d.id && cursor + e / 2 < center {
+                index += 1;
+            }
+            cursor += e;
+        }
+        index
+    }
+
+    /// Move child `from` to index `to` in the children list (both clamped),
+    /// sliding the visible children into their new places.
+    /// Returns false if either index is out of range.
+    pub fn move_child(&mut self, from: usize, to: usize) -> bool {
+        let n = self.base.children.len();
+        if from >= n || to >= n {
+            return false;
+        }
+        if from != to {
+            self.animate(|sp| {
+                let id = sp.base.children.remove(from);
+                sp.base.children.insert(to, id);
+                // Keep slot order in step so the animation targets are right.
+                let order = &sp.base.children;
+                sp.slots.sort_by_key(|&(sid, _)| order.iter().position(|&c| c == sid).unwrap_or(usize::MAX));
+            });
+        }
+        true
+    }
+
+    /// Draw a three-line grip centered in the handle strip of a slot.
+    fn draw_grip(&self, surface: &crate::draw::Surface, ax: i32, ay: i32, main_pos: i32, extent: i32, color: u32) {
+        let b = &self.base;
+        let hs = self.handle_size as i32;
+        let (cx, cy) = if self.vertical() {
+            (b.x + b.padding.left + hs / 2, b.y + main_pos + extent / 2)
+        } else if self.mirrored() {
+            (b.x + b.w as i32 - main_pos - extent / 2, b.y + b.padding.top + hs / 2)
+        } else {
+            (b.x + main_pos + extent / 2, b.y + b.padding.top + hs / 2)
+        };
+        for i in -1..=1 {
+            let (gx, gy, gw, gh) = if self.vertical() {
+                (cx - 5, cy + i * 4, 10, 2)
+            } else {
+                (cx + i * 4, cy - 5, 2, 10)
+            };
+            let p = crate::draw::scale_bounds(ax, ay, gx, gy, gw, gh);
+            crate::draw::fill_rect(surface, p.x, p.y, p.w.max(1), p.h.max(1), color);
+        }
     }
 }
 
@@ -20,6 +245,14 @@ impl Control for StackPanel {
     fn base_mut(&mut self) -> &mut ControlBase { &mut self.base }
     fn kind(&self) -> ControlKind { ControlKind::StackPanel }
 
+    fn is_interactive(&self) -> bool {
+        self.reorderable
+    }
+
+    fn accepts_focus(&self) -> bool {
+        false
+    }
+
     fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
         // StackPanel is transparent — only renders its background if color is set
         if self.base.color != 0 {
@@ -27,37 +260,208 @@ impl Control for StackPanel {
             let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
             crate::draw::fill_rect(surface, p.x, p.y, p.w, p.h, b.color);
         }
+        if self.reorderable {
+            let tc = crate::theme::colors();
+            for (id, pos) in self.positions(&self.slots) {
+                let extent = self.slots.iter().find(|&&(sid, _)| sid == id).map_or(0, |&(_, e)| e);
+                let dragging = self.drag.as_ref().map_or(false, |d| d.id == id);
+                let color = if dragging { tc.accent } else { tc.text_secondary };
+                self.draw_grip(surface, ax, ay, pos, extent, color);
+            }
+        }
+    }
+
+    fn handle_mouse_down(&mut self, local_x: i32, local_y: i32, button: u32) -> EventResponse {
+        if !self.reorderable || button & 0x02 != 0 || self.base.virtual_items.is_some() {
+            return EventResponse::IGNORED;
+        }
+        let main = self.main_coord(local_x, local_y);
+        let (cross, cross_start) = if self.vertical() {
+            (local_x, self.base.padding.left)
+        } else {
+            (local_y, self.base.padding.top)
+        };
+        if cross < cross_start || cross >= cross_start + self.handle_size as i32 {
+            return EventResponse::IGNORED;
+        }
+        let from = match self.slot_at(main) {
+            Some(i) => i,
+            None => return EventResponse::IGNORED,
+        };
+        let slot_start = self.main_start() + self.slots[..from].iter().map(|&(_, e)| e).sum::<i32>();
+        self.drag = Some(Drag { id: self.slots[from].0, from, to: from, grab: main - slot_start, pointer: main });
+        crate::mark_needs_layout();
+        self.base.mark_dirty();
+        EventResponse::CONSUMED
+    }
+
+    fn handle_mouse_move(&mut self, local_x: i32, local_y: i32) -> EventResponse {
+        let main = self.main_coord(local_x, local_y);
+        let to = match self.drag.as_mut() {
+            Some(d) => {
+                d.pointer = main;
+                d.to
+            }
+            None => return EventResponse::IGNORED,
+        };
+        let new_to = self.drop_index(self.drag.as_ref().unwrap());
+        if new_to != to {
+            self.animate(|sp| {
+                if let Some(d) = sp.drag.as_mut() {
+                    d.to = new_to;
+                }
+            });
+        } else {
+            crate::mark_needs_layout();
+            self.base.mark_dirty();
+        }
+        EventResponse::CONSUMED
+    }
+
+    fn handle_mouse_up(&mut self, _local_x: i32, _local_y: i32, _button: u32) -> EventResponse {
+        let (id, from, to) = match &self.drag {
+            Some(d) => (d.id, d.from, d.to),
+            None => return EventResponse::IGNORED,
+        };
+        // Slide the dropped child from under the pointer into its slot.
+        self.animate(|sp| {
+            sp.drag = None;
+            if from != to {
+                let mut order: Vec<ControlId> = sp.slots.iter().map(|&(sid, _)| sid).collect();
+                let moved = order.remove(from);
+                order.insert(to, moved);
+                sp.slots.sort_by_key(|&(sid, _)| order.iter().position(|&o| o == sid).unwrap_or(usize::MAX));
+            }
+        });
+        if from != to {
+            // Rebuild the children list: visible children take the new
+            // order, hidden ones keep their places.
+            let old_index = self.base.children.iter().position(|&c| c == id).unwrap_or(0);
+            let mut visible = self.slots.iter().map(|&(sid, _)| sid);
+            let new_children: Vec<ControlId> = self.base.children.iter()
+                .map(|&c| if self.slots.iter().any(|&(sid, _)| sid == c) { visible.next().unwrap_or(c) } else { c })
+                .collect();
+            self.base.children = new_children;
+            let new_index = self.base.children.iter().position(|&c| c == id).unwrap_or(0);
+            self.last_reorder = (old_index as u32, new_index as u32);
+            self.reorder_pending = true;
+        }
+        EventResponse::CONSUMED
     }
 
     fn layout_children(&self, controls: &[Box<dyn Control>]) -> Option<Vec<ChildLayout>> {
         let pad = &self.base.padding;
-        let mut cursor_x = pad.left;
-        let mut cursor_y = pad.top;
+        let handle = self.handle_extent();
+        let slots = child_slots(controls, &self.base.children, self.vertical());
+        let positions = self.positions(&slots);
+        let mirrored = self.mirrored();
         let mut result = Vec::new();
 
-        let children = &self.base.children;
-        for &child_id in children {
+        for (child_id, pos) in positions {
             let ci = match find_idx(controls, child_id) {
                 Some(i) => i,
                 None => continue,
             };
-            if !controls[ci].base().visible {
-                continue;
-            }
-
             let m = controls[ci].base().margin;
 
             match self.orientation {
                 Orientation::Vertical => {
-                    result.push(ChildLayout { id: child_id, x: cursor_x + m.left, y: cursor_y + m.top, w: None, h: None });
-                    cursor_y += controls[ci].base().h as i32 + m.top + m.bottom;
+                    result.push(ChildLayout { id: child_id, x: pad.left + handle + m.left, y: pos + m.top, w: None, h: None });
                 }
                 Orientation::Horizontal => {
-                    result.push(ChildLayout { id: child_id, x: cursor_x + m.left, y: cursor_y + m.top, w: None, h: None });
-                    cursor_x += controls[ci].base().w as i32 + m.left + m.right;
+                    let x = if mirrored {
+                        let extent = slots.iter().find(|&&(sid, _)| sid == child_id).map_or(0, |&(_, e)| e);
+                        self.base.w as i32 - pos - extent + m.right
+                    } else {
+                        pos + m.left
+                    };
+                    result.push(ChildLayout { id: child_id, x, y: pad.top + handle + m.top, w: None, h: None });
                 }
             }
         }
         Some(result)
     }
 }
+
+/// (child, main-axis extent incl. margins) of each visible child, in order.
+fn child_slots(controls: &[Box<dyn Control>], children: &[ControlId], vertical: bool) -> Vec<(ControlId, i32)> {
+    let mut slots = Vec::new();
+    for &child_id in children {
+        let ci = match find_idx(controls, child_id) {
+            Some(i) => i,
+            None => continue,
+        };
+        let b = controls[ci].base();
+        if !b.visible {
+            continue;
+        }
+        let extent = if vertical {
+            b.h as i32 + b.margin.top + b.margin.bottom
+        } else {
+            b.w as i32 + b.margin.left + b.margin.right
+        };
+        slots.push((child_id, extent));
+    }
+    slots
+}
+
+fn as_stack_panel(ctrl: &mut Box<dyn Control>) -> Option<&mut StackPanel> {
+    if ctrl.kind() == ControlKind::StackPanel {
+        let raw: *mut dyn Control = &mut **ctrl;
+        Some(unsafe { &mut *(raw as *mut StackPanel) })
+    } else {
+        None
+    }
+}
+
+/// True once after a handle drag on this StackPanel committed a new order
+/// (the event loop then fires EVENT_REORDER).
+pub fn take_reorder(ctrl: &mut Box<dyn Control>) -> bool {
+    match as_stack_panel(ctrl) {
+        Some(sp) => core::mem::replace(&mut sp.reorder_pending, false),
+        None => false,
+    }
+}
+
+/// Record the child slots of the StackPanel at `idx` after a layout pass,
+/// so mouse handling and rendering know where each row is.
+pub fn record_slots(controls: &mut [Box<dyn Control>], idx: usize) {
+    let (children, vertical) = match as_stack_panel(&mut controls[idx]) {
+        Some(sp) => (sp.base.children.clone(), sp.vertical()),
+        _ => return,
+    };
+    let slots = child_slots(controls, &children, vertical);
+    if let Some(sp) = as_stack_panel(&mut controls[idx]) {
+        // Keep the current display order while a drag is in progress.
+        if sp.drag.is_none() {
+            sp.slots = slots;
+        }
+    }
+}
+
+/// Slide animation tick (userdata = control ID). Shrinks the offsets by a
+/// third per frame and stops itself once everything has settled.
+extern "C" fn stackpanel_tick(timer_id: ControlId, _event_type: u32, userdata: u64) {
+    let st = crate::state();
+    let id = userdata as ControlId;
+    let animating = match st.controls.iter_mut().find(|c| c.id() == id).and_then(|c| as_stack_panel(c)) {
+        Some(sp) if sp.timer_id == timer_id => {
+            for (_, off) in sp.offsets.iter_mut() {
+                *off = *off * 2 / 3;
+            }
+            sp.offsets.retain(|&(_, off)| off != 0);
+            crate::mark_needs_layout();
+            sp.base.mark_dirty();
+            if sp.offsets.is_empty() {
+                sp.timer_id = 0;
+                false
+            } else {
+                true
+            }
+        }
+        _ => false,
+    };
+    if !animating {
+        st.timers.kill_timer(timer_id);
+    }
+}
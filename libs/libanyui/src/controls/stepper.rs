@@ -1,23 +1,211 @@
-use crate::control::{Control, ControlBase, TextControlBase, ControlKind, EventResponse};
-
-fn format_u32(mut v: u32) -> [u8; 10] {
-    let mut buf = [0u8; 10];
-    if v == 0 { buf[0] = b'0'; return buf; }
-    let mut i = 9;
-    while v > 0 { buf[i] = b'0' + (v % 10) as u8; v /= 10; i -= 1; }
-    let start = i + 1;
-    let len = 10 - start;
-    let mut out = [0u8; 10];
-    out[..len].copy_from_slice(&buf[start..]);
-    out
-}
+//! Stepper — numeric up/down control.
+//!
+//! `base.state` holds the value as an `i32` (two's complement in the `u32`
+//! state) in units of the last decimal place: with `decimals = 2` a state of
+//! 1234 displays as "12.34".  The value is kept within `[min, max]` and moved
+//! by `step` via the −/+ buttons, Up/Down keys, or direct keyboard entry in
+//! the middle area (committed with Enter, validated against the range).
+//! Holding a button repeats the step with acceleration, driven by an
+//! event-loop timer.
+
+use alloc::vec::Vec;
+use crate::control::{Control, ControlBase, ControlId, TextControlBase, ControlKind, EventResponse};
+
+/// Width of the −/+ button areas (logical pixels).
+const BUTTON_W: i32 = 28;
+/// Delay before press-and-hold starts repeating.
+const HOLD_DELAY_MS: u32 = 400;
+/// Interval between repeats while held.
+const HOLD_REPEAT_MS: u32 = 60;
+/// Maximum supported decimal places.
+const MAX_DECIMALS: u32 = 6;
 
 pub struct Stepper {
     pub(crate) text_base: TextControlBase,
+    pub(crate) min: i32,
+    pub(crate) max: i32,
+    pub(crate) step: u32,
+    pub(crate) decimals: u32,
+    pub(crate) prefix: Vec<u8>,
+    pub(crate) suffix: Vec<u8>,
+    /// Text being typed in the value area (`None` = not editing).
+    edit: Option<Vec<u8>>,
+    /// The edit text failed validation on the last commit attempt.
+    invalid: bool,
+    /// Press-and-hold repeat timer (0 = none) and its direction (−1/+1).
+    hold_timer: u32,
+    hold_dir: i32,
+    /// Number of repeats so far (drives acceleration).
+    hold_ticks: u32,
 }
 
 impl Stepper {
-    pub fn new(text_base: TextControlBase) -> Self { Self { text_base } }
+    pub fn new(text_base: TextControlBase) -> Self {
+        Self {
+            text_base,
+            min: 0,
+            max: i32::MAX,
+            step: 1,
+            decimals: 0,
+            prefix: Vec::new(),
+            suffix: Vec::new(),
+            edit: None,
+            invalid: false,
+            hold_timer: 0,
+            hold_dir: 0,
+            hold_ticks: 0,
+        }
+    }
+
+    pub fn value(&self) -> i32 {
+        self.text_base.base.state as i32
+    }
+
+    /// Set the value (clamped to the range).  Returns true if it changed.
+    pub fn set_value(&mut self, v: i32) -> bool {
+        let v = v.clamp(self.min, self.max);
+        if v == self.value() { return false; }
+        self.text_base.base.state = v as u32;
+        self.text_base.base.mark_dirty();
+        true
+    }
+
+    pub fn set_range(&mut self, min: i32, max: i32) {
+        let (min, max) = if min <= max { (min, max) } else { (max, min) };
+        self.min = min;
+        self.max = max;
+        let v = self.value();
+        self.set_value(v);
+        self.text_base.base.mark_dirty();
+    }
+
+    pub fn set_decimals(&mut self, places: u32) {
+        self.decimals = places.min(MAX_DECIMALS);
+        self.text_base.base.mark_dirty();
+    }
+
+    /// Move the value by `count` steps.  Returns true if it changed.
+    fn step_by(&mut self, count: i32) -> bool {
+        let delta = (self.step as i64) * (count as i64);
+        let v = (self.value() as i64 + delta).clamp(self.min as i64, self.max as i64);
+        self.set_value(v as i32)
+    }
+
+    /// Display text: prefix, formatted value, suffix.
+    fn display_text(&self) -> Vec<u8> {
+        let mut out = self.prefix.clone();
+        out.extend_from_slice(&format_value(self.value(), self.decimals));
+        out.extend_from_slice(&self.suffix);
+        out
+    }
+
+    // ── Direct entry ────────────────────────────────────────────────
+
+    fn begin_edit(&mut self) {
+        if self.edit.is_none() {
+            self.edit = Some(format_value(self.value(), self.decimals));
+            self.invalid = false;
+            self.text_base.base.mark_dirty();
+        }
+    }
+
+    /// Validate and apply the typed text.  Returns `Some(changed)` on
+    /// success; on failure the edit stays open and is flagged invalid.
+    fn commit_edit(&mut self) -> Option<bool> {
+        let text = self.edit.as_ref()?;
+        match parse_value(text, self.decimals).filter(|&v| v >= self.min && v <= self.max) {
+            Some(v) => {
+                self.edit = None;
+                self.invalid = false;
+                self.text_base.base.mark_dirty();
+                Some(self.set_value(v))
+            }
+            None => {
+                self.invalid = true;
+                self.text_base.base.mark_dirty();
+                None
+            }
+        }
+    }
+
+    fn cancel_edit(&mut self) {
+        if self.edit.take().is_some() {
+            self.invalid = false;
+            self.text_base.base.mark_dirty();
+        }
+    }
+
+    // ── Press-and-hold ──────────────────────────────────────────────
+
+    fn start_hold(&mut self, dir: i32) {
+        self.stop_hold();
+        self.hold_dir = dir;
+        self.hold_ticks = 0;
+        self.hold_timer = crate::state().timers.set_timer(HOLD_DELAY_MS, hold_tick, self.text_base.base.id as u64);
+    }
+
+    fn stop_hold(&mut self) {
+        if self.hold_timer != 0 {
+            crate::state().timers.kill_timer(self.hold_timer);
+            self.hold_timer = 0;
+        }
+        self.hold_dir = 0;
+    }
+
+    /// Steps per repeat: speeds up the longer a button is held.
+    fn hold_multiplier(&self) -> i32 {
+        match self.hold_ticks {
+            0..=9 => 1,
+            10..=24 => 5,
+            _ => 10,
+        }
+    }
+}
+
+/// Format `v` (in units of the last decimal place) with `decimals` places.
+fn format_value(v: i32, decimals: u32) -> Vec<u8> {
+    let mut digits = Vec::new();
+    let mut n = (v as i64).unsigned_abs();
+    loop {
+        digits.push(b'0' + (n % 10) as u8);
+        n /= 10;
+        if n == 0 && digits.len() > decimals as usize { break; }
+    }
+    let mut out = Vec::with_capacity(digits.len() + 2);
+    if v < 0 { out.push(b'-'); }
+    for (i, &d) in digits.iter().enumerate().rev() {
+        out.push(d);
+        if i == decimals as usize && i > 0 { out.push(b'.'); }
+    }
+    out
+}
+
+/// Parse typed text into units of the last decimal place.  Accepts an
+/// optional sign and at most `decimals` fractional digits.
+fn parse_value(text: &[u8], decimals: u32) -> Option<i32> {
+    let (neg, rest) = match text.first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    };
+    let (int_part, frac_part) = match rest.iter().position(|&b| b == b'.') {
+        Some(dot) => (&rest[..dot], &rest[dot + 1..]),
+        None => (rest, &rest[rest.len()..]),
+    };
+    if int_part.is_empty() && frac_part.is_empty() { return None; }
+    if frac_part.len() > decimals as usize { return None; }
+    let mut v: i64 = 0;
+    for &b in int_part.iter().chain(frac_part.iter()) {
+        if !b.is_ascii_digit() { return None; }
+        v = v * 10 + (b - b'0') as i64;
+        if v > i32::MAX as i64 + 1 { return None; }
+    }
+    for _ in frac_part.len()..decimals as usize {
+        v *= 10;
+    }
+    let v = if neg { -v } else { v };
+    if v < i32::MIN as i64 || v > i32::MAX as i64 { return None; }
+    Some(v as i32)
 }
 
 impl Control for Stepper {
@@ -27,6 +215,11 @@ impl Control for Stepper {
     fn text_base_mut(&mut self) -> Option<&mut crate::control::TextControlBase> { Some(&mut self.text_base) }
     fn kind(&self) -> ControlKind { ControlKind::Stepper }
 
+    fn set_state(&mut self, s: u32) {
+        self.cancel_edit();
+        self.set_value(s as i32);
+    }
+
     fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
         let b = &self.text_base.base;
         let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
@@ -42,46 +235,196 @@ impl Control for Stepper {
         crate::draw::fill_rounded_rect(surface, x, y, w, h, corner, bg);
         crate::draw::draw_top_highlight(surface, x, y, w, corner, crate::theme::lighten(bg, 10));
 
-        let text_color = if disabled { tc.text_disabled } else { tc.text };
+        let text_color = if disabled {
+            tc.text_disabled
+        } else if self.invalid {
+            tc.destructive
+        } else {
+            tc.text
+        };
         let btn_color = if disabled { tc.text_disabled } else { tc.text_secondary };
         let fs = crate::draw::scale_font(14);
         let y_pad = crate::theme::scale_i32(6);
 
-        // Minus button
-        crate::draw::draw_text_sized(surface, x + crate::theme::scale_i32(10), y + y_pad, btn_color, b"\xe2\x88\x92", fs);
+        // Minus button (dimmed at the lower bound)
+        let minus_color = if self.value() <= self.min { tc.text_disabled } else { btn_color };
+        crate::draw::draw_text_sized(surface, x + crate::theme::scale_i32(10), y + y_pad, minus_color, b"\xe2\x88\x92", fs);
 
-        // Value display
-        let val_text = format_u32(b.state);
+        // Value display (or the text being typed, with a cursor)
+        let val_text = match self.edit {
+            Some(ref e) => e.clone(),
+            None => self.display_text(),
+        };
         let (vw, _) = crate::draw::text_size_at(&val_text, fs);
         let cx = x + (w as i32 - vw as i32) / 2;
         crate::draw::draw_text_sized(surface, cx, y + y_pad, text_color, &val_text, fs);
+        if self.edit.is_some() && focused {
+            let cursor_pad = crate::theme::scale_i32(5);
+            let cursor_h = if h > (cursor_pad as u32 * 2) { h - cursor_pad as u32 * 2 } else { 1 };
+            crate::draw::fill_rect(surface, cx + vw as i32 + 1, y + cursor_pad, crate::theme::scale(1), cursor_h, tc.accent);
+        }
 
-        // Plus button
-        crate::draw::draw_text_sized(surface, x + w as i32 - crate::theme::scale_i32(18), y + y_pad, btn_color, b"+", fs);
+        // Plus button (dimmed at the upper bound)
+        let plus_color = if self.value() >= self.max { tc.text_disabled } else { btn_color };
+        crate::draw::draw_text_sized(surface, x + w as i32 - crate::theme::scale_i32(18), y + y_pad, plus_color, b"+", fs);
 
         // Separators
-        let sep_x_left = crate::theme::scale_i32(28);
-        let sep_x_right = crate::theme::scale_i32(29);
+        let sep_x_left = crate::theme::scale_i32(BUTTON_W);
+        let sep_x_right = crate::theme::scale_i32(BUTTON_W + 1);
         let sep_pad = crate::theme::scale_i32(4);
         let sep_h = if h > (sep_pad as u32 * 2) { h - sep_pad as u32 * 2 } else { 1 };
         crate::draw::fill_rect(surface, x + sep_x_left, y + sep_pad, 1, sep_h, tc.separator);
         crate::draw::fill_rect(surface, x + w as i32 - sep_x_right, y + sep_pad, 1, sep_h, tc.separator);
 
-        // Focus ring
+        // Focus ring (red while the typed value is invalid)
         if focused && !disabled {
-            crate::draw::draw_focus_ring(surface, x, y, w, h, corner, tc.accent);
+            let ring = if self.invalid { tc.destructive } else { tc.accent };
+            crate::draw::draw_focus_ring(surface, x, y, w, h, corner, ring);
         }
     }
 
     fn is_interactive(&self) -> bool { !self.text_base.base.disabled }
 
-    fn handle_click(&mut self, lx: i32, _ly: i32, _button: u32) -> EventResponse {
-        let half = self.text_base.base.w as i32 / 2;
-        if lx < half {
-            if self.text_base.base.state > 0 { self.text_base.base.state -= 1; }
+    fn handle_mouse_down(&mut self, lx: i32, _ly: i32, _button: u32) -> EventResponse {
+        let w = self.text_base.base.w as i32;
+        let dir = if lx < BUTTON_W {
+            -1
+        } else if lx >= w - BUTTON_W {
+            1
         } else {
-            self.text_base.base.state += 1;
+            // Value area: start typing
+            self.begin_edit();
+            return EventResponse::CONSUMED;
+        };
+        let mut changed = false;
+        if self.edit.is_some() {
+            // Take what was typed before stepping from it.
+            match self.commit_edit() {
+                Some(c) => changed = c,
+                None => self.cancel_edit(),
+            }
+        }
+        changed |= self.step_by(dir);
+        self.start_hold(dir);
+        if changed { EventResponse::CHANGED } else { EventResponse::CONSUMED }
+    }
+
+    fn handle_mouse_up(&mut self, _lx: i32, _ly: i32, _button: u32) -> EventResponse {
+        self.stop_hold();
+        EventResponse::CONSUMED
+    }
+
+    fn handle_click(&mut self, _lx: i32, _ly: i32, _button: u32) -> EventResponse {
+        // Stepping happens on mouse down (so holding can repeat).
+        EventResponse::CONSUMED
+    }
+
+    fn handle_key_down(&mut self, keycode: u32, char_code: u32, modifiers: u32) -> EventResponse {
+        use crate::control::*;
+        if modifiers & MOD_CTRL != 0 { return EventResponse::IGNORED; }
+
+        let steps = match keycode {
+            KEY_UP => Some(1),
+            KEY_DOWN => Some(-1),
+            KEY_PAGE_UP => Some(10),
+            KEY_PAGE_DOWN => Some(-10),
+            _ => None,
+        };
+        if let Some(n) = steps {
+            let mut changed = self.edit.is_some() && self.commit_edit() == Some(true);
+            if self.edit.is_some() { return EventResponse::CONSUMED; }
+            changed |= self.step_by(n);
+            return if changed { EventResponse::CHANGED } else { EventResponse::CONSUMED };
+        }
+        if keycode == KEY_HOME || keycode == KEY_END {
+            if self.edit.is_some() { return EventResponse::CONSUMED; }
+            let target = if keycode == KEY_HOME { self.min } else { self.max };
+            return if self.set_value(target) { EventResponse::CHANGED } else { EventResponse::CONSUMED };
+        }
+
+        match keycode {
+            KEY_ENTER => {
+                if self.edit.is_none() { return EventResponse::IGNORED; }
+                return match self.commit_edit() {
+                    Some(true) => EventResponse::CHANGED,
+                    _ => EventResponse::CONSUMED,
+                };
+            }
+            KEY_ESCAPE => {
+                if self.edit.is_none() { return EventResponse::IGNORED; }
+                self.cancel_edit();
+                return EventResponse::CONSUMED;
+            }
+            KEY_BACKSPACE => {
+                if let Some(ref mut e) = self.edit {
+                    e.pop();
+                    self.invalid = false;
+                    self.text_base.base.mark_dirty();
+                    return EventResponse::CONSUMED;
+                }
+                return EventResponse::IGNORED;
+            }
+            _ => {}
+        }
+
+        // Typing digits, sign or decimal point starts / extends an edit.
+        let c = char_code as u8;
+        let accepted = char_code < 0x80
+            && (c.is_ascii_digit() || c == b'-' || c == b'+' || (c == b'.' && self.decimals > 0));
+        if accepted {
+            if self.edit.is_none() {
+                self.edit = Some(Vec::new());
+            }
+            if let Some(ref mut e) = self.edit {
+                if e.len() < 16 { e.push(c); }
+            }
+            self.invalid = false;
+            self.text_base.base.mark_dirty();
+            return EventResponse::CONSUMED;
+        }
+        EventResponse::IGNORED
+    }
+
+    fn handle_blur(&mut self) {
+        self.stop_hold();
+        // Keep a valid typed value, drop an invalid one.
+        if self.edit.is_some() && self.commit_edit().is_none() {
+            self.cancel_edit();
         }
-        EventResponse::CHANGED
+        self.text_base.base.focused = false;
+        self.text_base.base.mark_dirty();
+    }
+}
+
+/// Press-and-hold timer callback (userdata = stepper ID).  The first tick
+/// ends the initial delay and re-arms at the repeat interval.
+extern "C" fn hold_tick(timer_id: ControlId, _event_type: u32, userdata: u64) {
+    let st = crate::state();
+    let id = userdata as ControlId;
+    let stepper = match st.controls.iter_mut().find(|c| c.id() == id) {
+        Some(ctrl) if ctrl.kind() == ControlKind::Stepper => {
+            let raw: *mut dyn Control = &mut **ctrl;
+            unsafe { &mut *(raw as *mut Stepper) }
+        }
+        _ => {
+            st.timers.kill_timer(timer_id);
+            return;
+        }
+    };
+    if stepper.hold_timer != timer_id || stepper.hold_dir == 0 {
+        st.timers.kill_timer(timer_id);
+        return;
+    }
+    if stepper.hold_ticks == 0 {
+        st.timers.kill_timer(timer_id);
+        stepper.hold_timer = st.timers.set_timer(HOLD_REPEAT_MS, hold_tick, userdata);
+    }
+    stepper.hold_ticks += 1;
+    let changed = stepper.step_by(stepper.hold_dir * stepper.hold_multiplier());
+    if !changed {
+        // Reached a bound: nothing more to repeat.
+        stepper.stop_hold();
+        return;
     }
+    crate::control::invoke_event_callback(id, crate::control::EVENT_CHANGE);
 }
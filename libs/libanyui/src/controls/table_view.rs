@@ -35,7 +35,11 @@ impl Control for TableView {
     }
 
     fn handle_scroll(&mut self, delta: i32) -> EventResponse {
-        self.scroll_y = (self.scroll_y + delta * 16).max(0);
+        let y = (self.scroll_y + delta * 16).max(0);
+        if y == self.scroll_y {
+            return EventResponse::IGNORED;
+        }
+        self.scroll_y = y;
         EventResponse::CONSUMED
     }
 }
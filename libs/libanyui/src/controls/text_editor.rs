@@ -177,6 +177,10 @@ pub struct TextEditor {
     highlighted_lines: Vec<LineHighlight>,
     /// When true, text cannot be edited (navigation and copy still work).
     pub(crate) read_only: bool,
+    /// Underline misspelled words (needs a registered spell checker).
+    pub(crate) spell_check: bool,
+    /// (row, start_col, end_col) of the word targeted by the spell-check popup.
+    spell_target: Option<(usize, usize, usize)>,
 }
 
 impl TextEditor {
@@ -204,6 +208,8 @@ impl TextEditor {
             redo_stack: Vec::new(),
             highlighted_lines: Vec::new(),
             read_only: false,
+            spell_check: false,
+            spell_target: None,
         }
     }
 
@@ -390,6 +396,48 @@ impl TextEditor {
         self.gutter_width = (digits + 1) as u32 * self.char_width + 8;
     }
 
+    /// Line `row` measured for display at `size`, if it contains non-ASCII
+    /// text (right-to-left script, emoji, accented letters) whose glyphs do
+    /// not fit the monospace grid. ASCII lines keep the grid.
+    fn bidi_line(&self, row: usize, size: u16) -> Option<crate::bidi::BidiLine> {
+        let line = self.lines.get(row)?;
+        if !line.is_ascii() {
+            Some(crate::bidi::BidiLine::new(line, self.font_id, size))
+        } else {
+            None
+        }
+    }
+
+    /// Move the cursor one character left or right on screen, wrapping to
+    /// the neighbouring line at the line's edge.
+    fn step_cursor(&mut self, right: bool) {
+        let col = self.cursor_col.min(self.lines[self.cursor_row].len());
+        let forward = match self.bidi_line(self.cursor_row, self.font_size) {
+            Some(line) => {
+                let next = line.move_visual(col, right);
+                if next != col {
+                    self.cursor_col = next;
+                    return;
+                }
+                right != line.is_rtl()
+            }
+            None => right,
+        };
+        if forward {
+            if col < self.lines[self.cursor_row].len() {
+                self.cursor_col = crate::cluster::next_boundary(&self.lines[self.cursor_row], col);
+            } else if self.cursor_row + 1 < self.lines.len() {
+                self.cursor_row += 1;
+                self.cursor_col = 0;
+            }
+        } else if col > 0 {
+            self.cursor_col = crate::cluster::prev_boundary(&self.lines[self.cursor_row], col);
+        } else if self.cursor_row > 0 {
+            self.cursor_row -= 1;
+            self.cursor_col = self.lines[self.cursor_row].len();
+        }
+    }
+
     fn ensure_cursor_visible(&mut self) {
         let cursor_y = (self.cursor_row as i32) * self.line_height as i32;
         let visible_h = self.base.h as i32 - 2;
@@ -399,7 +447,10 @@ impl TextEditor {
         if cursor_y + self.line_height as i32 > self.scroll_y + visible_h {
             self.scroll_y = cursor_y + self.line_height as i32 - visible_h;
         }
-        let cursor_x = (self.cursor_col as i32) * self.char_width as i32;
+        let cursor_x = match self.bidi_line(self.cursor_row, self.font_size) {
+            Some(line) => line.caret_x(self.cursor_col),
+            None => (self.cursor_col as i32) * self.char_width as i32,
+        };
         let text_area_w = self.base.w as i32 - self.gutter_width as i32 - 10;
         if cursor_x < self.scroll_x {
             self.scroll_x = cursor_x;
@@ -429,7 +480,10 @@ impl TextEditor {
         let row = ((ly - 1 + self.scroll_y) / self.line_height as i32).max(0) as usize;
         let row = row.min(self.lines.len().saturating_sub(1));
         let text_lx = lx - self.gutter_width as i32 - 1 + self.scroll_x;
-        let col = (text_lx / self.char_width as i32).max(0) as usize;
+        let col = match self.bidi_line(row, self.font_size) {
+            Some(line) => line.hit_test(text_lx),
+            None => (text_lx / self.char_width as i32).max(0) as usize,
+        };
         let col = col.min(self.lines[row].len());
         (row, col)
     }
@@ -580,6 +634,7 @@ impl Control for TextEditor {
 
         for row in visible_start..visible_end {
             let row_y = y + 1 + (row as i32) * s_line_h as i32 - s_scroll_y;
+            let bidi = self.bidi_line(row, s_font_size);
 
             // Per-line highlights (debugger breakpoints, current RIP, etc.)
             for hl in &self.highlighted_lines {
@@ -615,7 +670,18 @@ impl Control for TextEditor {
                         let line_len = self.lines[row].len();
                         let sel_start = if row == sr { sc.min(line_len) } else { 0 };
                         let sel_end = if row == er { ec.min(line_len) } else { line_len };
-                        if sel_start < sel_end || (row > sr && row < er) {
+                        if let (Some(line), true) = (&bidi, sel_start < sel_end) {
+                            for (l, r) in line.spans(sel_start, sel_end) {
+                                crate::draw::fill_rect(
+                                    &clipped,
+                                    text_x_base + l - s_scroll_x,
+                                    row_y,
+                                    (r - l) as u32,
+                                    s_line_h,
+                                    tc.editor_selection,
+                                );
+                            }
+                        } else if sel_start < sel_end || (row > sr && row < er) {
                             let sx = text_x_base + (sel_start as i32) * s_char_w as i32 - s_scroll_x;
                             let sel_chars = if sel_end > sel_start { sel_end - sel_start } else { 0 };
                             // For middle lines of multiline selection, extend to edge
@@ -665,14 +731,33 @@ impl Control for TextEditor {
 
             // Text content
             let line = &self.lines[row];
-            if !line.is_empty() {
+            let reordered = bidi.is_some() && crate::bidi::has_rtl(line);
+            if let (Some(ref bl), true) = (&bidi, reordered) {
+                // Mixed-direction lines are drawn reordered, without syntax colors.
+                if let Some(ref syn) = self.syntax {
+                    let (_, still_in) = tokenize_line(line, in_block_comment, syn);
+                    in_block_comment = still_in;
+                }
+                crate::draw::draw_text_ex(
+                    &clipped,
+                    text_x_base - s_scroll_x,
+                    row_y + s_text_pad,
+                    tc.text,
+                    bl.visual(),
+                    self.font_id,
+                    s_font_size,
+                );
+            } else if !line.is_empty() {
                 if let Some(ref syn) = self.syntax {
                     let (spans, still_in) = tokenize_line(line, in_block_comment, syn);
                     in_block_comment = still_in;
                     for span in &spans {
                         let text_slice = &line[span.start..span.end];
-                        let span_x = text_x_base + (span.start as i32) * s_char_w as i32
-                            - s_scroll_x;
+                        let col_x = match bidi {
+                            Some(ref bl) => bl.caret_x(span.start),
+                            None => (span.start as i32) * s_char_w as i32,
+                        };
+                        let span_x = text_x_base + col_x - s_scroll_x;
                         crate::draw::draw_text_ex(
                             &clipped,
                             span_x,
@@ -700,10 +785,31 @@ impl Control for TextEditor {
                 in_block_comment = still_in;
             }
 
+            // Squiggly underline below misspelled words
+            if self.spell_check && !self.read_only {
+                let squiggle_y = row_y + s_text_pad + s_font_size as i32 + crate::theme::scale_i32(1);
+                for (ws, we) in crate::spell::misspelled_ranges(line) {
+                    match bidi {
+                        Some(ref bl) => {
+                            for (l, r) in bl.spans(ws, we) {
+                                crate::draw::draw_squiggle(&clipped, text_x_base + l - s_scroll_x, squiggle_y, (r - l).max(1) as u32, tc.destructive);
+                            }
+                        }
+                        None => {
+                            let sx = text_x_base + (ws as i32) * s_char_w as i32 - s_scroll_x;
+                            crate::draw::draw_squiggle(&clipped, sx, squiggle_y, (we - ws) as u32 * s_char_w, tc.destructive);
+                        }
+                    }
+                }
+            }
+
             // Cursor
             if row == self.cursor_row && self.focused {
-                let cursor_x = text_x_base + (self.cursor_col as i32) * s_char_w as i32
-                    - s_scroll_x;
+                let col_x = match bidi {
+                    Some(ref bl) => bl.caret_x(self.cursor_col),
+                    None => (self.cursor_col as i32) * s_char_w as i32,
+                };
+                let cursor_x = text_x_base + col_x - s_scroll_x;
                 let cursor_w = crate::theme::scale(2);
                 crate::draw::fill_rect(
                     &clipped,
@@ -750,6 +856,36 @@ impl Control for TextEditor {
         }
     }
 
+    fn spell_word_at(&mut self, lx: i32, ly: i32) -> Option<Vec<u8>> {
+        if !self.spell_check || self.read_only {
+            return None;
+        }
+        let (row, col) = self.pixel_to_cursor(lx, ly);
+        let line = &self.lines[row];
+        let (start, end) = crate::spell::word_at(line, col)?;
+        let word = line[start..end].to_vec();
+        if !crate::spell::is_misspelled(&word) {
+            return None;
+        }
+        self.spell_target = Some((row, start, end));
+        Some(word)
+    }
+
+    fn replace_spell_word(&mut self, replacement: &[u8]) -> bool {
+        let (row, start, end) = match self.spell_target.take() {
+            Some(t) if t.0 < self.lines.len() && t.2 <= self.lines[t.0].len() => t,
+            _ => return false,
+        };
+        self.push_undo();
+        self.lines[row].splice(start..end, replacement.iter().copied());
+        self.cursor_row = row;
+        self.cursor_col = start + replacement.len();
+        self.selection = None;
+        self.ensure_cursor_visible();
+        self.base.mark_dirty();
+        true
+    }
+
     fn handle_mouse_down(&mut self, lx: i32, ly: i32, button: u32) -> EventResponse {
         if button & 1 != 0 {
             // Left button: start selection
@@ -898,22 +1034,8 @@ impl Control for TextEditor {
             }
             // Move cursor
             match keycode {
-                KEY_LEFT => {
-                    if self.cursor_col > 0 {
-                        self.cursor_col -= 1;
-                    } else if self.cursor_row > 0 {
-                        self.cursor_row -= 1;
-                        self.cursor_col = self.lines[self.cursor_row].len();
-                    }
-                }
-                KEY_RIGHT => {
-                    if self.cursor_col < self.lines[self.cursor_row].len() {
-                        self.cursor_col += 1;
-                    } else if self.cursor_row + 1 < self.lines.len() {
-                        self.cursor_row += 1;
-                        self.cursor_col = 0;
-                    }
-                }
+                KEY_LEFT => self.step_cursor(false),
+                KEY_RIGHT => self.step_cursor(true),
                 KEY_UP => {
                     if self.cursor_row > 0 {
                         self.cursor_row -= 1;
@@ -1017,8 +1139,9 @@ impl Control for TextEditor {
         if keycode == KEY_BACKSPACE {
             self.clamp_cursor();
             if self.cursor_col > 0 {
-                self.cursor_col -= 1;
-                self.lines[self.cursor_row].remove(self.cursor_col);
+                let start = crate::cluster::prev_boundary(&self.lines[self.cursor_row], self.cursor_col);
+                self.lines[self.cursor_row].drain(start..self.cursor_col);
+                self.cursor_col = start;
             } else if self.cursor_row > 0 {
                 let current_line = self.lines.remove(self.cursor_row);
                 self.cursor_row -= 1;
@@ -1034,7 +1157,8 @@ impl Control for TextEditor {
         if keycode == KEY_DELETE {
             self.clamp_cursor();
             if self.cursor_col < self.lines[self.cursor_row].len() {
-                self.lines[self.cursor_row].remove(self.cursor_col);
+                let end = crate::cluster::next_boundary(&self.lines[self.cursor_row], self.cursor_col);
+                self.lines[self.cursor_row].drain(self.cursor_col..end);
             } else if self.cursor_row + 1 < self.lines.len() {
                 let next_line = self.lines.remove(self.cursor_row + 1);
                 self.lines[self.cursor_row].extend_from_slice(&next_line);
@@ -1056,24 +1180,14 @@ impl Control for TextEditor {
         }
         // Left arrow
         if keycode == KEY_LEFT {
-            if self.cursor_col > 0 {
-                self.cursor_col -= 1;
-            } else if self.cursor_row > 0 {
-                self.cursor_row -= 1;
-                self.cursor_col = self.lines[self.cursor_row].len();
-            }
+            self.step_cursor(false);
             self.ensure_cursor_visible();
             self.base.mark_dirty();
             return EventResponse::CONSUMED;
         }
         // Right arrow
         if keycode == KEY_RIGHT {
-            if self.cursor_col < self.lines[self.cursor_row].len() {
-                self.cursor_col += 1;
-            } else if self.cursor_row + 1 < self.lines.len() {
-                self.cursor_row += 1;
-                self.cursor_col = 0;
-            }
+            self.step_cursor(true);
             self.ensure_cursor_visible();
             self.base.mark_dirty();
             return EventResponse::CONSUMED;
@@ -1137,8 +1251,11 @@ impl Control for TextEditor {
 
     fn handle_scroll(&mut self, delta: i32) -> EventResponse {
         let max_scroll = (self.content_height() - (self.base.h as i32 - 2)).max(0);
-        self.scroll_y =
-            (self.scroll_y - delta * self.line_height as i32).clamp(0, max_scroll);
+        let y = (self.scroll_y - delta * self.line_height as i32).clamp(0, max_scroll);
+        if y == self.scroll_y {
+            return EventResponse::IGNORED;
+        }
+        self.scroll_y = y;
         self.base.mark_dirty();
         EventResponse::CONSUMED
     }
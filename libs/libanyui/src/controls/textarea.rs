@@ -1,55 +1,269 @@
+//! TextArea — lightweight multi-line text input for forms.
+//!
+//! Wraps long lines at word boundaries, scrolls vertically with a draggable
+//! scrollbar, shows placeholder text while empty and can cap the text length.
+//! Enter inserts a newline; with `submit_on_ctrl_enter`, Ctrl+Enter fires
+//! EVENT_SUBMIT instead. For code editing use TextEditor.
+
+use alloc::vec::Vec;
 use crate::control::{Control, ControlBase, TextControlBase, ControlKind, EventResponse};
 
+/// Text inset from the left/right edge.
+const PAD_X: i32 = 8;
+/// Text inset from the top/bottom edge.
+const PAD_Y: i32 = 6;
+/// Scrollbar width.
+const BAR_W: i32 = 6;
+/// Gap between scrollbar and edge.
+const BAR_PAD: i32 = 2;
+/// Minimum thumb height.
+const MIN_THUMB_H: i32 = 20;
+
 pub struct TextArea {
     pub(crate) text_base: TextControlBase,
     pub(crate) cursor_pos: usize,
     pub(crate) focused: bool,
     pub(crate) scroll_y: i32,
+    /// Selection anchor (byte offset). If != cursor_pos, text is selected.
+    sel_anchor: usize,
+    /// Whether a mouse drag selection is in progress.
+    dragging: bool,
+    /// Mouse-Y offset within the thumb while dragging the scrollbar.
+    thumb_drag: Option<i32>,
+    /// Column (logical px) kept while moving up/down across short lines.
+    preferred_x: Option<i32>,
+
+    pub(crate) placeholder: Vec<u8>,
+    /// Maximum text length in bytes (0 = unlimited).
+    pub(crate) max_length: u32,
+    /// Ctrl+Enter fires EVENT_SUBMIT instead of inserting a newline.
+    pub(crate) submit_on_ctrl_enter: bool,
 }
 
 impl TextArea {
     pub fn new(text_base: TextControlBase) -> Self {
-        Self { text_base, cursor_pos: 0, focused: false, scroll_y: 0 }
+        Self {
+            text_base,
+            cursor_pos: 0,
+            focused: false,
+            scroll_y: 0,
+            sel_anchor: 0,
+            dragging: false,
+            thumb_drag: None,
+            preferred_x: None,
+            placeholder: Vec::new(),
+            max_length: 0,
+            submit_on_ctrl_enter: false,
+        }
     }
 
-    /// Count newlines in text to determine total line count.
-    fn line_count(&self) -> usize {
-        if self.text_base.text.is_empty() { return 1; }
-        let mut count = 1usize;
-        for &b in &self.text_base.text {
-            if b == b'\n' { count += 1; }
-        }
-        count
+    fn font_size(&self) -> u16 {
+        self.text_base.text_style.font_size
     }
 
     /// Approximate line height from font size.
     fn line_height(&self) -> i32 {
-        self.text_base.text_style.font_size as i32 + 4
+        self.font_size() as i32 + 4
     }
 
-    /// Total content height in pixels.
-    fn content_height(&self) -> i32 {
-        self.line_count() as i32 * self.line_height()
+    /// Width available to a line of text (the scrollbar lane is always
+    /// reserved so wrapping does not change when the scrollbar appears).
+    fn wrap_width(&self) -> i32 {
+        (self.text_base.base.w as i32 - PAD_X * 2 - BAR_W - BAR_PAD).max(1)
     }
 
-    /// Maximum scroll offset.
-    fn max_scroll(&self) -> i32 {
-        let ch = self.content_height();
-        let vh = self.text_base.base.h as i32 - 12; // 6px padding top+bottom
-        (ch - vh).max(0)
+    /// Visual lines as byte ranges `(start, end)`; `end` excludes the newline.
+    /// A line wrapped mid-paragraph ends where the next one starts.
+    fn visual_lines(&self) -> Vec<(usize, usize)> {
+        let text = &self.text_base.text;
+        let max_w = self.wrap_width();
+        let fs = self.font_size();
+        let mut lines = Vec::new();
+        let mut start = 0;
+        loop {
+            let end = text[start..].iter().position(|&b| b == b'\n').map_or(text.len(), |p| start + p);
+            wrap_paragraph(text, start, end, max_w, fs, &mut lines);
+            if end >= text.len() {
+                break;
+            }
+            start = end + 1;
+        }
+        lines
     }
 
-    /// Clamp scroll_y to valid range.
-    fn clamp_scroll(&mut self) {
-        self.scroll_y = self.scroll_y.clamp(0, self.max_scroll());
+    /// Total content height in pixels.
+    fn content_height(&self, line_count: usize) -> i32 {
+        line_count as i32 * self.line_height() + PAD_Y * 2
+    }
+
+    /// Maximum scroll offset.
+    fn max_scroll(&self, line_count: usize) -> i32 {
+        (self.content_height(line_count) - self.text_base.base.h as i32).max(0)
     }
 
     /// Auto-scroll to bottom (for output append use case).
     pub fn scroll_to_bottom(&mut self) {
-        self.scroll_y = self.max_scroll();
+        self.scroll_y = self.max_scroll(self.visual_lines().len());
+    }
+
+    /// Returns (track_h, thumb_h, max_scroll) if the scrollbar is visible.
+    fn scrollbar_metrics(&self, line_count: usize) -> Option<(i32, i32, i32)> {
+        let h = self.text_base.base.h as i32;
+        let content_h = self.content_height(line_count);
+        if content_h <= h {
+            return None;
+        }
+        let track_h = h - BAR_PAD * 2;
+        let thumb_h = ((h as i64 * track_h as i64) / content_h as i64).max(MIN_THUMB_H as i64).min(track_h as i64) as i32;
+        Some((track_h, thumb_h, content_h - h))
+    }
+
+    /// Y position of thumb top, relative to this control's top.
+    fn thumb_y(&self, track_h: i32, thumb_h: i32, max_scroll: i32) -> i32 {
+        let frac = if max_scroll > 0 {
+            (self.scroll_y as i64 * (track_h - thumb_h) as i64 / max_scroll as i64) as i32
+        } else { 0 };
+        BAR_PAD + frac.max(0).min(track_h - thumb_h)
+    }
+
+    fn selection_range(&self) -> (usize, usize) {
+        let len = self.text_base.text.len();
+        let a = self.sel_anchor.min(len);
+        let c = self.cursor_pos.min(len);
+        if a <= c { (a, c) } else { (c, a) }
+    }
+
+    fn has_selection(&self) -> bool {
+        self.cursor_pos != self.sel_anchor
+    }
+
+    /// Replace the selection with `ins`, truncated to the max length.
+    fn insert(&mut self, ins: &[u8]) -> EventResponse {
+        let (start, end) = self.selection_range();
+        let kept = self.text_base.text.len() - (end - start);
+        let room = if self.max_length == 0 { ins.len() } else { (self.max_length as usize).saturating_sub(kept) };
+        let ins = &ins[..ins.len().min(room)];
+        if ins.is_empty() && start == end {
+            return EventResponse::CONSUMED;
+        }
+        self.text_base.text.splice(start..end, ins.iter().copied());
+        self.cursor_pos = start + ins.len();
+        self.sel_anchor = self.cursor_pos;
+        self.preferred_x = None;
+        self.ensure_cursor_visible();
+        EventResponse::CHANGED
+    }
+
+    /// Delete `start..end` (or the selection, if any).
+    fn delete(&mut self, start: usize, end: usize) -> EventResponse {
+        if self.has_selection() {
+            return self.insert(&[]);
+        }
+        if start >= end {
+            return EventResponse::CONSUMED;
+        }
+        self.sel_anchor = start;
+        self.cursor_pos = end;
+        self.insert(&[])
+    }
+
+    /// Visual line containing byte position `pos`.
+    fn line_of(lines: &[(usize, usize)], pos: usize) -> usize {
+        lines.iter().rposition(|&(s, _)| s <= pos).unwrap_or(0)
+    }
+
+    /// Logical x offset of `pos` within its visual line.
+    fn x_of(&self, lines: &[(usize, usize)], pos: usize) -> i32 {
+        let (s, _) = lines[Self::line_of(lines, pos)];
+        crate::draw::text_width_n_at(&self.text_base.text[s..], pos - s, self.font_size()) as i32
+    }
+
+    /// Byte position nearest to text-space x on visual line `line`.
+    fn pos_on_line(&self, lines: &[(usize, usize)], line: usize, x: i32) -> usize {
+        let (s, e) = lines[line];
+        let pos = s + crate::draw::text_hit_test(&self.text_base.text[s..e], x, self.font_size());
+        // The end of a wrapped line is the start of the next one; stay put.
+        if pos == e && e > s && lines.get(line + 1).map_or(false, |&(ns, _)| ns == e) {
+            e - 1
+        } else {
+            pos
+        }
+    }
+
+    /// Byte position under a local point.
+    fn pos_at(&self, lx: i32, ly: i32) -> usize {
+        let lines = self.visual_lines();
+        let line = ((ly - PAD_Y + self.scroll_y).max(0) / self.line_height()) as usize;
+        self.pos_on_line(&lines, line.min(lines.len() - 1), lx - PAD_X)
+    }
+
+    /// Move the cursor `delta` visual lines up (negative) or down.
+    fn move_lines(&mut self, delta: i32) {
+        let lines = self.visual_lines();
+        let cur = Self::line_of(&lines, self.cursor_pos) as i32;
+        let x = match self.preferred_x {
+            Some(x) => x,
+            None => self.x_of(&lines, self.cursor_pos),
+        };
+        let target = cur + delta;
+        self.cursor_pos = if target < 0 {
+            0
+        } else if target as usize >= lines.len() {
+            self.text_base.text.len()
+        } else {
+            self.pos_on_line(&lines, target as usize, x)
+        };
+        self.preferred_x = Some(x);
+    }
+
+    fn ensure_cursor_visible(&mut self) {
+        let lines = self.visual_lines();
+        let lh = self.line_height();
+        let top = Self::line_of(&lines, self.cursor_pos.min(self.text_base.text.len())) as i32 * lh;
+        let view_h = self.text_base.base.h as i32 - PAD_Y * 2;
+        if top < self.scroll_y {
+            self.scroll_y = top;
+        } else if top + lh > self.scroll_y + view_h {
+            self.scroll_y = top + lh - view_h;
+        }
+        self.scroll_y = self.scroll_y.clamp(0, self.max_scroll(lines.len()));
+        self.text_base.base.mark_dirty();
+    }
+
+    fn page_lines(&self) -> i32 {
+        ((self.text_base.base.h as i32 - PAD_Y * 2) / self.line_height()).max(1)
+    }
+}
+
+/// Split `text[start..end]` into lines no wider than `max_w`, breaking after
+/// the last space that fits (or mid-word if a word is wider than a line).
+fn wrap_paragraph(text: &[u8], start: usize, end: usize, max_w: i32, fs: u16, out: &mut Vec<(usize, usize)>) {
+    let mut s = start;
+    loop {
+        let rest = &text[s..end];
+        if rest.is_empty() || crate::draw::text_width_n_at(rest, rest.len(), fs) as i32 <= max_w {
+            out.push((s, end));
+            return;
+        }
+        // Longest prefix that fits (at least one byte so wrapping progresses).
+        let (mut lo, mut hi) = (1, rest.len());
+        while lo < hi {
+            let mid = (lo + hi + 1) / 2;
+            if crate::draw::text_width_n_at(rest, mid, fs) as i32 <= max_w { lo = mid; } else { hi = mid - 1; }
+        }
+        let brk = match rest[..lo].iter().rposition(|&b| b == b' ') {
+            Some(sp) if sp > 0 => sp + 1,
+            _ => lo,
+        };
+        out.push((s, s + brk));
+        s += brk;
     }
 }
 
+fn is_word_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
 impl Control for TextArea {
     fn base(&self) -> &ControlBase { &self.text_base.base }
     fn base_mut(&mut self) -> &mut ControlBase { &mut self.text_base.base }
@@ -59,6 +273,8 @@ impl Control for TextArea {
 
     fn set_text(&mut self, t: &[u8]) {
         self.text_base.set_text(t);
+        self.cursor_pos = self.text_base.text.len();
+        self.sel_anchor = self.cursor_pos;
         self.scroll_to_bottom();
     }
 
@@ -66,145 +282,268 @@ impl Control for TextArea {
         let b = &self.text_base.base;
         let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
         let (x, y, w, h) = (p.x, p.y, p.w, p.h);
-        let bg = if b.color != 0 {
-            b.color
-        } else {
-            crate::theme::colors().input_bg
-        };
         let tc = crate::theme::colors();
+        let disabled = b.disabled;
+        let corner = crate::theme::input_corner();
 
-        // Background
-        crate::draw::fill_rect(surface, x, y, w, h, bg);
-
-        // Clip text to control bounds (physical)
-        let clipped = surface.with_clip(x, y, w, h);
-        let text_color = if self.text_base.text_style.text_color != 0 {
-            self.text_base.text_style.text_color
+        // Background and border, like TextField.
+        let bg = if b.color != 0 { b.color } else { tc.input_bg };
+        let bg = if disabled { crate::theme::darken(bg, 10) } else { bg };
+        crate::draw::fill_rounded_rect(surface, x, y, w, h, corner, bg);
+        let border_color = if self.focused {
+            tc.input_focus
+        } else if b.hovered && !disabled {
+            tc.accent
         } else {
-            tc.text
+            tc.input_border
         };
+        crate::draw::draw_rounded_border(surface, x, y, w, h, corner, border_color);
+        if self.focused && !disabled {
+            crate::draw::draw_focus_ring(surface, x, y, w, h, corner, tc.accent);
+        }
+
+        // Clip text to the inside of the border (physical)
+        let clipped = surface.with_clip(x + 1, y + 1, w.saturating_sub(2), h.saturating_sub(2));
+        let text_color = if disabled { tc.text_disabled } else { self.text_base.effective_text_color() };
 
         let font_id = self.text_base.text_style.font_id;
-        let font_size = crate::draw::scale_font(self.text_base.text_style.font_size);
-        let lh = font_size as i32 + crate::theme::scale_i32(4);
-        let pad_x = crate::theme::scale_i32(8);
-        let pad_y = crate::theme::scale_i32(6);
+        let font_size = crate::draw::scale_font(self.font_size());
+        let lh = crate::theme::scale_i32(self.line_height());
+        let pad_x = crate::theme::scale_i32(PAD_X);
+        let pad_y = crate::theme::scale_i32(PAD_Y);
         let scaled_scroll_y = crate::theme::scale_i32(self.scroll_y);
         let text = &self.text_base.text;
 
+        if text.is_empty() && !self.placeholder.is_empty() {
+            crate::draw::draw_text_ex(&clipped, x + pad_x, y + pad_y, tc.text_secondary, &self.placeholder, font_id, font_size);
+        }
+
         // Render visible lines only
-        if !text.is_empty() {
-            let viewport_h = h as i32 - pad_y * 2;
-            let first_vis = (scaled_scroll_y / lh).max(0) as usize;
-            let last_vis = ((scaled_scroll_y + viewport_h) / lh + 1) as usize;
-
-            let mut line_idx = 0usize;
-            let mut line_start = 0usize;
-
-            for i in 0..=text.len() {
-                let is_end = i == text.len() || text[i] == b'\n';
-                if is_end {
-                    if line_idx >= first_vis && line_idx <= last_vis {
-                        let line_y = y + pad_y + (line_idx as i32) * lh - scaled_scroll_y;
-                        let line_data = &text[line_start..i];
-                        if !line_data.is_empty() {
-                            crate::draw::draw_text_ex(
-                                &clipped, x + pad_x, line_y, text_color,
-                                line_data, font_id, font_size,
-                            );
-                        }
-                    }
-                    if line_idx > last_vis { break; }
-                    line_idx += 1;
-                    line_start = i + 1;
+        let lines = self.visual_lines();
+        let (sel_start, sel_end) = self.selection_range();
+        let show_sel = self.focused && sel_start != sel_end;
+        let first_vis = (scaled_scroll_y / lh).max(0) as usize;
+        let last_vis = ((scaled_scroll_y + h as i32) / lh + 1) as usize;
+        for (i, &(s, e)) in lines.iter().enumerate().skip(first_vis).take(last_vis + 1 - first_vis) {
+            let line_y = y + pad_y + i as i32 * lh - scaled_scroll_y;
+            let line = &text[s..e];
+            if show_sel && sel_start <= e && sel_end >= s {
+                let a = sel_start.max(s) - s;
+                let z = sel_end.min(e) - s;
+                let ax_px = crate::draw::text_width_n_at(line, a, font_size) as i32;
+                let mut zx_px = crate::draw::text_width_n_at(line, z, font_size) as i32;
+                // Selected newline: show a small block past the line end.
+                if sel_end > e && e < text.len() && text[e] == b'\n' {
+                    zx_px += crate::theme::scale_i32(4);
                 }
+                crate::draw::fill_rect(&clipped, x + pad_x + ax_px, line_y, (zx_px - ax_px).max(0) as u32, lh as u32, tc.accent & 0x60FFFFFF);
+            }
+            if !line.is_empty() {
+                crate::draw::draw_text_ex(&clipped, x + pad_x, line_y, text_color, line, font_id, font_size);
             }
         }
 
         // Cursor
         if self.focused {
             let cpos = self.cursor_pos.min(text.len());
-            let mut cur_line = 0usize;
-            let mut col_start = 0usize;
-            for i in 0..cpos {
-                if text[i] == b'\n' {
-                    cur_line += 1;
-                    col_start = i + 1;
-                }
-            }
-            let col_slice = &text[col_start..cpos];
-            let cx_offset = crate::draw::text_width_n_at(col_slice, col_slice.len(), font_size) as i32;
-            let cy = y + pad_y + (cur_line as i32) * lh - scaled_scroll_y;
+            let line = Self::line_of(&lines, cpos);
+            let (s, _) = lines[line];
+            let cx = x + pad_x + crate::draw::text_width_n_at(&text[s..], cpos - s, font_size) as i32;
+            let cy = y + pad_y + line as i32 * lh - scaled_scroll_y;
             let cursor_w = crate::theme::scale(2);
-            crate::draw::fill_rect(&clipped, x + pad_x + cx_offset, cy, cursor_w, font_size as u32, tc.accent);
-        }
-
-        // Scrollbar
-        let content_h = crate::theme::scale_i32(self.content_height());
-        let view_h = h as i32 - crate::theme::scale_i32(4);
-        if content_h > view_h && view_h > 4 {
-            let bar_w = crate::theme::scale(6);
-            let bar_x = x + w as i32 - bar_w as i32 - crate::theme::scale_i32(2);
-            let track_y = y + crate::theme::scale_i32(2);
-            let track_h = view_h;
-            crate::draw::fill_rect(&clipped, bar_x, track_y, bar_w, track_h as u32, tc.scrollbar_track);
-            let thumb_h = ((view_h as i64 * track_h as i64) / content_h as i64).max(crate::theme::scale_i32(20) as i64) as i32;
-            let max_scroll = crate::theme::scale_i32(self.max_scroll());
-            let scroll_frac = if max_scroll > 0 {
-                (scaled_scroll_y as i64 * (track_h - thumb_h) as i64 / max_scroll as i64) as i32
-            } else { 0 };
-            let thumb_y = track_y + scroll_frac.max(0).min(track_h - thumb_h);
+            crate::draw::fill_rect(&clipped, cx, cy, cursor_w, font_size as u32, tc.accent);
+        }
+
+        // Scrollbar — metrics are logical, scale for rendering
+        if let Some((track_h, thumb_h, max_scroll)) = self.scrollbar_metrics(lines.len()) {
+            let bar_w = crate::theme::scale(BAR_W as u32);
+            let bar_x = x + w as i32 - crate::theme::scale_i32(BAR_W + BAR_PAD);
+            let track_y = y + crate::theme::scale_i32(BAR_PAD);
+            crate::draw::fill_rect(&clipped, bar_x, track_y, bar_w, crate::theme::scale(track_h as u32), tc.scrollbar_track);
+            let thumb_y = y + crate::theme::scale_i32(self.thumb_y(track_h, thumb_h, max_scroll));
             let thumb_r = crate::theme::scale(3);
-            crate::draw::fill_rounded_rect(&clipped, bar_x, thumb_y, bar_w, thumb_h as u32, thumb_r, tc.scrollbar);
+            crate::draw::fill_rounded_rect(&clipped, bar_x, thumb_y, bar_w, crate::theme::scale(thumb_h as u32), thumb_r, tc.scrollbar);
         }
     }
 
-    fn is_interactive(&self) -> bool { true }
-    fn accepts_focus(&self) -> bool { true }
+    fn is_interactive(&self) -> bool { !self.text_base.base.disabled }
+    fn default_cursor(&self) -> u32 { crate::control::CURSOR_IBEAM }
+    fn accepts_focus(&self) -> bool { !self.text_base.base.disabled }
 
-    fn handle_click(&mut self, _lx: i32, _ly: i32, _button: u32) -> EventResponse {
-        self.cursor_pos = self.text_base.text.len();
+    fn handle_mouse_down(&mut self, lx: i32, ly: i32, _button: u32) -> EventResponse {
+        let line_count = self.visual_lines().len();
+        if let Some((track_h, thumb_h, max_scroll)) = self.scrollbar_metrics(line_count) {
+            if lx >= self.text_base.base.w as i32 - BAR_W - BAR_PAD * 2 {
+                let ty = self.thumb_y(track_h, thumb_h, max_scroll);
+                let anchor = if ly >= ty && ly < ty + thumb_h { ly - ty } else { thumb_h / 2 };
+                self.thumb_drag = Some(anchor);
+                return self.handle_mouse_move(lx, ly);
+            }
+        }
+        self.cursor_pos = self.pos_at(lx, ly);
+        self.sel_anchor = self.cursor_pos;
+        self.preferred_x = None;
+        self.dragging = true;
+        self.text_base.base.mark_dirty();
         EventResponse::CONSUMED
     }
 
-    fn handle_key_down(&mut self, keycode: u32, char_code: u32, _modifiers: u32) -> EventResponse {
-        if char_code >= 0x20 && char_code < 0x7F {
-            let ch = char_code as u8;
-            if self.cursor_pos > self.text_base.text.len() {
-                self.cursor_pos = self.text_base.text.len();
+    fn handle_mouse_move(&mut self, lx: i32, ly: i32) -> EventResponse {
+        if let Some(anchor) = self.thumb_drag {
+            if let Some((track_h, thumb_h, max_scroll)) = self.scrollbar_metrics(self.visual_lines().len()) {
+                let top = (ly - anchor - BAR_PAD).clamp(0, track_h - thumb_h);
+                self.scroll_y = if track_h > thumb_h {
+                    (top as i64 * max_scroll as i64 / (track_h - thumb_h) as i64) as i32
+                } else { 0 };
+                self.text_base.base.mark_dirty();
             }
-            self.text_base.text.insert(self.cursor_pos, ch);
-            self.cursor_pos += 1;
-            EventResponse::CHANGED
-        } else if keycode == crate::control::KEY_ENTER {
-            if self.cursor_pos > self.text_base.text.len() {
-                self.cursor_pos = self.text_base.text.len();
+            return EventResponse::CONSUMED;
+        }
+        if self.dragging {
+            self.cursor_pos = self.pos_at(lx, ly);
+            self.ensure_cursor_visible();
+            return EventResponse::CONSUMED;
+        }
+        EventResponse::IGNORED
+    }
+
+    fn handle_mouse_up(&mut self, _lx: i32, _ly: i32, _button: u32) -> EventResponse {
+        self.dragging = false;
+        self.thumb_drag = None;
+        EventResponse::CONSUMED
+    }
+
+    fn handle_double_click(&mut self, lx: i32, ly: i32, _button: u32) -> EventResponse {
+        // Select the word under the cursor.
+        let text = &self.text_base.text;
+        if text.is_empty() { return EventResponse::CONSUMED; }
+        let pos = self.pos_at(lx, ly).min(text.len() - 1);
+        let mut start = pos;
+        while start > 0 && is_word_char(text[start - 1]) { start -= 1; }
+        let mut end = pos;
+        while end < text.len() && is_word_char(text[end]) { end += 1; }
+        if start == end {
+            end = pos + 1;
+        }
+        self.sel_anchor = start;
+        self.cursor_pos = end;
+        self.ensure_cursor_visible();
+        EventResponse::CONSUMED
+    }
+
+    fn handle_key_down(&mut self, keycode: u32, char_code: u32, modifiers: u32) -> EventResponse {
+        use crate::control::*;
+        let shift = modifiers & MOD_SHIFT != 0;
+        let ctrl = modifiers & MOD_CTRL != 0;
+        let len = self.text_base.text.len();
+        self.cursor_pos = self.cursor_pos.min(len);
+        self.sel_anchor = self.sel_anchor.min(len);
+
+        if ctrl && (char_code == b'a' as u32 || char_code == b'A' as u32) {
+            self.sel_anchor = 0;
+            self.cursor_pos = len;
+            self.ensure_cursor_visible();
+            return EventResponse::CONSUMED;
+        }
+        if ctrl && (char_code == b'c' as u32 || char_code == b'C' as u32) {
+            if self.has_selection() {
+                let (s, e) = self.selection_range();
+                crate::compositor::clipboard_set(&self.text_base.text[s..e]);
             }
-            self.text_base.text.insert(self.cursor_pos, b'\n');
-            self.cursor_pos += 1;
-            EventResponse::CHANGED
-        } else if keycode == crate::control::KEY_BACKSPACE {
-            if self.cursor_pos > 0 && !self.text_base.text.is_empty() {
-                self.cursor_pos -= 1;
-                self.text_base.text.remove(self.cursor_pos);
-                EventResponse::CHANGED
-            } else {
-                EventResponse::CONSUMED
+            return EventResponse::CONSUMED;
+        }
+        if ctrl && (char_code == b'x' as u32 || char_code == b'X' as u32) {
+            if self.has_selection() {
+                let (s, e) = self.selection_range();
+                crate::compositor::clipboard_set(&self.text_base.text[s..e]);
+                return self.insert(&[]);
             }
-        } else if keycode == crate::control::KEY_LEFT {
-            if self.cursor_pos > 0 { self.cursor_pos -= 1; }
-            EventResponse::CONSUMED
-        } else if keycode == crate::control::KEY_RIGHT {
-            if self.cursor_pos < self.text_base.text.len() { self.cursor_pos += 1; }
-            EventResponse::CONSUMED
-        } else {
-            EventResponse::IGNORED
+            return EventResponse::CONSUMED;
+        }
+        if ctrl && (char_code == b'v' as u32 || char_code == b'V' as u32) {
+            if let Some(clip) = crate::compositor::clipboard_get() {
+                // Printable ASCII and newlines only.
+                let filtered: Vec<u8> = clip.into_iter()
+                    .filter(|&b| b == b'\n' || (b >= 0x20 && b < 0x7F))
+                    .collect();
+                if !filtered.is_empty() {
+                    return self.insert(&filtered);
+                }
+            }
+            return EventResponse::CONSUMED;
+        }
+
+        if keycode == KEY_ENTER {
+            if ctrl && self.submit_on_ctrl_enter {
+                return EventResponse::SUBMIT;
+            }
+            return self.insert(b"\n");
+        }
+
+        if char_code >= 0x20 && char_code < 0x7F && !ctrl {
+            return self.insert(&[char_code as u8]);
         }
+
+        if keycode == KEY_BACKSPACE {
+            let c = self.cursor_pos;
+            return self.delete(c.saturating_sub(1), c);
+        }
+        if keycode == KEY_DELETE {
+            let c = self.cursor_pos;
+            return self.delete(c, (c + 1).min(len));
+        }
+
+        let vertical = keycode == KEY_UP || keycode == KEY_DOWN
+            || keycode == KEY_PAGE_UP || keycode == KEY_PAGE_DOWN;
+        if !vertical {
+            self.preferred_x = None;
+        }
+        match keycode {
+            KEY_LEFT => {
+                if !shift && self.has_selection() {
+                    self.cursor_pos = self.selection_range().0;
+                } else if self.cursor_pos > 0 {
+                    self.cursor_pos -= 1;
+                }
+            }
+            KEY_RIGHT => {
+                if !shift && self.has_selection() {
+                    self.cursor_pos = self.selection_range().1;
+                } else if self.cursor_pos < len {
+                    self.cursor_pos += 1;
+                }
+            }
+            KEY_UP => self.move_lines(-1),
+            KEY_DOWN => self.move_lines(1),
+            KEY_PAGE_UP => self.move_lines(-self.page_lines()),
+            KEY_PAGE_DOWN => self.move_lines(self.page_lines()),
+            KEY_HOME | KEY_END => {
+                let lines = self.visual_lines();
+                let line = Self::line_of(&lines, self.cursor_pos);
+                self.cursor_pos = match (keycode == KEY_HOME, ctrl) {
+                    (true, true) => 0,
+                    (false, true) => len,
+                    (true, false) => lines[line].0,
+                    (false, false) => self.pos_on_line(&lines, line, i32::MAX),
+                };
+            }
+            _ => return EventResponse::IGNORED,
+        }
+        if !shift {
+            self.sel_anchor = self.cursor_pos;
+        }
+        self.ensure_cursor_visible();
+        EventResponse::CONSUMED
     }
 
     fn handle_scroll(&mut self, delta: i32) -> EventResponse {
         let lh = self.line_height();
-        self.scroll_y = (self.scroll_y - delta * lh).clamp(0, self.max_scroll());
+        let max = self.max_scroll(self.visual_lines().len());
+        let y = (self.scroll_y - delta * lh).clamp(0, max);
+        if y == self.scroll_y {
+            return EventResponse::IGNORED;
+        }
+        self.scroll_y = y;
         self.text_base.base.mark_dirty();
         EventResponse::CONSUMED
     }
@@ -214,11 +553,15 @@ impl Control for TextArea {
         self.text_base.base.focused = true;
         self.text_base.base.mark_dirty();
         self.cursor_pos = self.text_base.text.len();
+        self.sel_anchor = self.cursor_pos;
     }
 
     fn handle_blur(&mut self) {
         self.focused = false;
         self.text_base.base.focused = false;
+        self.dragging = false;
+        self.thumb_drag = None;
+        self.sel_anchor = self.cursor_pos;
         self.text_base.base.mark_dirty();
     }
 }
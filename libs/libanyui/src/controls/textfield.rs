@@ -1,5 +1,6 @@
 use alloc::vec::Vec;
 use crate::control::{Control, ControlBase, TextControlBase, ControlKind, EventResponse};
+use super::input_filter::InputFilter;
 
 pub struct TextField {
     pub(crate) text_base: TextControlBase,
@@ -23,6 +24,21 @@ pub struct TextField {
     sel_anchor: usize,
     /// Whether a mouse drag selection is in progress.
     dragging: bool,
+
+    /// Underline misspelled words (needs a registered spell checker).
+    pub(crate) spell_check: bool,
+    /// Byte range of the word targeted by the spell-check popup.
+    spell_target: Option<(usize, usize)>,
+
+    /// Numeric/decimal filter, mask and max length for typed input.
+    pub(crate) filter: InputFilter,
+    /// Input was rejected (by the filter or EVENT_VALIDATE) or flagged by
+    /// the app; drawn with a destructive border.
+    pub(crate) invalid: bool,
+    /// Text, cursor and anchor before the last edit, until it is validated.
+    pre_edit: Option<(Vec<u8>, usize, usize)>,
+    /// The EVENT_VALIDATE callback vetoed the pending edit.
+    pub(crate) edit_rejected: bool,
 }
 
 impl TextField {
@@ -40,7 +56,65 @@ impl TextField {
             scroll_x: 0,
             sel_anchor: 0,
             dragging: false,
+            spell_check: false,
+            spell_target: None,
+            filter: InputFilter::new(),
+            invalid: false,
+            pre_edit: None,
+            edit_rejected: false,
+        }
+    }
+
+    /// Replace `text[start..end]` with `ins`, subject to the input filter.
+    /// Rejected input leaves the text alone and flags the field invalid.
+    fn replace_range(&mut self, start: usize, end: usize, ins: &[u8]) -> EventResponse {
+        let len = self.text_base.text.len();
+        let (start, end) = (start.min(len), end.min(len));
+        match self.filter.apply(&self.text_base.text, start, end, ins) {
+            Some((text, cursor)) => {
+                self.text_base.text = text;
+                self.cursor_pos = cursor;
+                self.sel_anchor = cursor;
+                self.invalid = false;
+                self.ensure_cursor_visible();
+                EventResponse::CHANGED
+            }
+            None => {
+                self.invalid = true;
+                self.text_base.base.mark_dirty();
+                EventResponse::CONSUMED
+            }
+        }
+    }
+
+    /// Re-apply the filter to the current text (after the filter changed).
+    /// Text that does not fit is cleared.
+    pub(crate) fn refilter(&mut self) {
+        let old = core::mem::take(&mut self.text_base.text);
+        self.text_base.text = self.filter.apply(&[], 0, 0, &old).map(|(t, _)| t).unwrap_or_default();
+        self.cursor_pos = self.text_base.text.len();
+        self.sel_anchor = self.cursor_pos;
+        self.invalid = false;
+        self.ensure_cursor_visible();
+        self.text_base.base.mark_dirty();
+    }
+
+    /// Finish validating the pending edit. Restores the previous text if the
+    /// EVENT_VALIDATE callback rejected it; returns whether the edit stands.
+    pub(crate) fn finish_validate(&mut self) -> bool {
+        let before = self.pre_edit.take();
+        if !core::mem::replace(&mut self.edit_rejected, false) {
+            return true;
+        }
+        if let Some((text, cursor, anchor)) = before {
+            self.text_base.text = text;
+            self.cursor_pos = cursor;
+            self.sel_anchor = anchor;
         }
+        self.invalid = true;
+        self.ensure_cursor_visible();
+        self.text_base.base.mark_dirty();
+        false
     }
 
     pub(crate) fn select_all(&mut self) {
@@ -78,6 +152,17 @@ impl TextField {
         }
     }
 
+    /// The display text prepared for bidirectional rendering at `size`.
+    fn bidi_line(&self, size: u16) -> crate::bidi::BidiLine {
+        crate::bidi::BidiLine::new(&self.display_text(), 0, size)
+    }
+
+    /// Offset of the text within a text area `area_w` wide: right-to-left
+    /// text that fits is aligned to the right edge.
+    fn align_offset(line: &crate::bidi::BidiLine, area_w: i32) -> i32 {
+        if line.is_rtl() { (area_w - line.width()).max(0) } else { 0 }
+    }
+
     /// Returns (sel_start, sel_end) sorted.
     fn selection_range(&self) -> (usize, usize) {
         if self.cursor_pos <= self.sel_anchor {
@@ -91,17 +176,6 @@ impl TextField {
         self.cursor_pos != self.sel_anchor
     }
 
-    /// Delete selected text and collapse cursor.
-    fn delete_selection(&mut self) {
-        if !self.has_selection() { return; }
-        let (start, end) = self.selection_range();
-        let end = end.min(self.text_base.text.len());
-        let start = start.min(end);
-        self.text_base.text.drain(start..end);
-        self.cursor_pos = start;
-        self.sel_anchor = start;
-    }
-
     /// Get selected text as bytes.
     fn selected_bytes(&self) -> &[u8] {
         if !self.has_selection() { return &[]; }
@@ -113,11 +187,9 @@ impl TextField {
 
     /// Ensure cursor is visible by adjusting scroll_x.
     fn ensure_cursor_visible(&mut self) {
-        let fs = self.text_base.text_style.font_size;
-        let display = self.display_text();
-        let cursor = self.cursor_pos.min(display.len());
-        let cursor_px = crate::draw::text_width_n_at(&display, cursor, fs) as i32;
+        let line = self.bidi_line(self.text_base.text_style.font_size);
         let area_w = self.text_area_width();
+        let cursor_px = Self::align_offset(&line, area_w) + line.caret_x(self.cursor_pos);
 
         // Scroll right if cursor is past the visible area.
         if cursor_px - self.scroll_x > area_w {
@@ -133,10 +205,9 @@ impl TextField {
 
     /// Convert a local x coordinate (relative to control) to a byte position.
     fn x_to_pos(&self, local_x: i32) -> usize {
-        let fs = self.text_base.text_style.font_size;
-        let display = self.display_text();
-        let text_local_x = local_x - self.text_area_left() + self.scroll_x;
-        crate::draw::text_hit_test(&display, text_local_x, fs)
+        let line = self.bidi_line(self.text_base.text_style.font_size);
+        let offset = Self::align_offset(&line, self.text_area_width());
+        line.hit_test(local_x - self.text_area_left() + self.scroll_x - offset)
     }
 
     /// Find the start of the previous word boundary.
@@ -163,6 +234,150 @@ impl TextField {
         while i < len && !is_word_char(text[i]) { i += 1; }
         i
     }
+
+    /// Key handling proper; `handle_key_down` wraps it to snapshot edits.
+    fn edit_key(&mut self, keycode: u32, char_code: u32, modifiers: u32) -> EventResponse {
+        use crate::control::*;
+        let shift = modifiers & MOD_SHIFT != 0;
+        let ctrl = modifiers & MOD_CTRL != 0;
+
+        // Ctrl+A: select all.
+        if ctrl && (char_code == b'a' as u32 || char_code == b'A' as u32) {
+            self.sel_anchor = 0;
+            self.cursor_pos = self.text_base.text.len();
+            self.ensure_cursor_visible();
+            return EventResponse::CONSUMED;
+        }
+
+        // Ctrl+C: copy selection to clipboard.
+        if ctrl && (char_code == b'c' as u32 || char_code == b'C' as u32) {
+            if self.has_selection() {
+                let bytes = self.selected_bytes().to_vec();
+                crate::compositor::clipboard_set(&bytes);
+            }
+            return EventResponse::CONSUMED;
+        }
+
+        // Ctrl+X: cut selection.
+        if ctrl && (char_code == b'x' as u32 || char_code == b'X' as u32) {
+            if self.has_selection() {
+                let bytes = self.selected_bytes().to_vec();
+                crate::compositor::clipboard_set(&bytes);
+                let (start, end) = self.selection_range();
+                return self.replace_range(start, end, &[]);
+            }
+            return EventResponse::CONSUMED;
+        }
+
+        // Ctrl+V: paste from clipboard.
+        if ctrl && (char_code == b'v' as u32 || char_code == b'V' as u32) {
+            if let Some(clip) = crate::compositor::clipboard_get() {
+                // Drop control characters (line breaks, tabs); UTF-8 text
+                // such as emoji is kept.
+                let filtered: Vec<u8> = clip.into_iter().filter(|&b| b >= 0x20 && b != 0x7F).collect();
+                if !filtered.is_empty() {
+                    let (start, end) = self.selection_range();
+                    return self.replace_range(start, end, &filtered);
+                }
+            }
+            return EventResponse::CONSUMED;
+        }
+
+        // Printable character input.
+        if char_code >= 0x20 && char_code < 0x7F && !ctrl {
+            let (start, end) = self.selection_range();
+            return self.replace_range(start, end, &[char_code as u8]);
+        }
+
+        if keycode == KEY_BACKSPACE {
+            if self.has_selection() {
+                let (start, end) = self.selection_range();
+                return self.replace_range(start, end, &[]);
+            }
+            let end = self.cursor_pos.min(self.text_base.text.len());
+            if end > 0 {
+                // Masked fields delete the previous entered character,
+                // stepping over auto-inserted literals.
+                let mut start = crate::cluster::prev_boundary(&self.text_base.text, end);
+                while start > 0 && self.filter.is_literal_at(start) {
+                    start -= 1;
+                }
+                return self.replace_range(start, end, &[]);
+            }
+            return EventResponse::CONSUMED;
+        }
+
+        if keycode == KEY_DELETE {
+            if self.has_selection() {
+                let (start, end) = self.selection_range();
+                return self.replace_range(start, end, &[]);
+            }
+            let len = self.text_base.text.len();
+            if self.cursor_pos < len {
+                let mut end = crate::cluster::next_boundary(&self.text_base.text, self.cursor_pos);
+                while end < len && self.filter.is_literal_at(end - 1) {
+                    end += 1;
+                }
+                return self.replace_range(self.cursor_pos, end, &[]);
+            }
+            return EventResponse::CONSUMED;
+        }
+
+        if keycode == KEY_LEFT {
+            if ctrl {
+                // Word left.
+                self.cursor_pos = self.word_left(self.cursor_pos);
+            } else if !shift && self.has_selection() {
+                // Collapse selection to left edge.
+                let (start, _) = self.selection_range();
+                self.cursor_pos = start;
+            } else {
+                // One character to the left on screen.
+                let line = self.bidi_line(self.text_base.text_style.font_size);
+                self.cursor_pos = line.move_visual(self.cursor_pos, false);
+            }
+            if !shift { self.sel_anchor = self.cursor_pos; }
+            self.ensure_cursor_visible();
+            return EventResponse::CONSUMED;
+        }
+
+        if keycode == KEY_RIGHT {
+            if ctrl {
+                // Word right.
+                self.cursor_pos = self.word_right(self.cursor_pos);
+            } else if !shift && self.has_selection() {
+                // Collapse selection to right edge.
+                let (_, end) = self.selection_range();
+                self.cursor_pos = end;
+            } else {
+                let line = self.bidi_line(self.text_base.text_style.font_size);
+                self.cursor_pos = line.move_visual(self.cursor_pos, true);
+            }
+            if !shift { self.sel_anchor = self.cursor_pos; }
+            self.ensure_cursor_visible();
+            return EventResponse::CONSUMED;
+        }
+
+        if keycode == KEY_HOME {
+            self.cursor_pos = 0;
+            if !shift { self.sel_anchor = 0; }
+            self.ensure_cursor_visible();
+            return EventResponse::CONSUMED;
+        }
+
+        if keycode == KEY_END {
+            self.cursor_pos = self.text_base.text.len();
+            if !shift { self.sel_anchor = self.cursor_pos; }
+            self.ensure_cursor_visible();
+            return EventResponse::CONSUMED;
+        }
+
+        if keycode == KEY_ENTER {
+            return EventResponse::SUBMIT;
+        }
+
+        EventResponse::IGNORED
+    }
 }
 
 fn is_word_char(b: u8) -> bool {
@@ -176,6 +391,10 @@ impl Control for TextField {
     fn text_base_mut(&mut self) -> Option<&mut crate::control::TextControlBase> { Some(&mut self.text_base) }
     fn kind(&self) -> ControlKind { ControlKind::TextField }
 
+    fn baseline(&self) -> Option<i32> {
+        Some(6 + crate::draw::text_ascent(self.text_base.text_style.font_size))
+    }
+
     fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
         let b = &self.text_base.base;
         let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
@@ -195,7 +414,9 @@ impl Control for TextField {
         crate::draw::fill_rounded_rect(surface, x, y, w, h, corner, bg);
 
         // Border: focus (accent) > hover (lighter) > normal
-        let border_color = if self.focused {
+        let border_color = if self.invalid && !disabled {
+            tc.destructive
+        } else if self.focused {
             tc.input_focus
         } else if hovered && !disabled {
             tc.accent
@@ -206,7 +427,8 @@ impl Control for TextField {
 
         // Focus ring (2px glow around the field)
         if self.focused && !disabled {
-            crate::draw::draw_focus_ring(surface, x, y, w, h, corner, tc.accent);
+            let ring = if self.invalid { tc.destructive } else { tc.accent };
+            crate::draw::draw_focus_ring(surface, x, y, w, h, corner, ring);
         }
 
         // Prefix icon placeholder (scaled)
@@ -238,33 +460,41 @@ impl Control for TextField {
         let font_size = crate::draw::scale_font(self.text_base.text_style.font_size);
         let text_y = y + crate::theme::scale_i32(6);
         let scaled_scroll_x = crate::theme::scale_i32(self.scroll_x);
-        let text_x = x + text_left - scaled_scroll_x;
 
         if self.text_base.text.is_empty() && !self.placeholder.is_empty() {
-            crate::draw::draw_text_sized(&clipped, x + text_left, text_y, tc.text_secondary, &self.placeholder, font_size);
+            let placeholder = crate::bidi::BidiLine::new(&self.placeholder, 0, font_size);
+            let px = x + text_left + Self::align_offset(&placeholder, area_w as i32);
+            crate::draw::draw_text_sized(&clipped, px, text_y, tc.text_secondary, placeholder.visual(), font_size);
         } else {
-            let display = self.display_text();
+            let line = self.bidi_line(font_size);
+            let text_x = x + text_left - scaled_scroll_x + Self::align_offset(&line, area_w as i32);
 
-            // Draw selection highlight.
+            // Draw selection highlight (one span per direction run).
             if self.has_selection() && self.focused {
                 let (sel_start, sel_end) = self.selection_range();
-                let start_px = crate::draw::text_width_n_at(&display, sel_start.min(display.len()), font_size) as i32;
-                let end_px = crate::draw::text_width_n_at(&display, sel_end.min(display.len()), font_size) as i32;
-                let sel_x = text_x + start_px;
-                let sel_w = (end_px - start_px).max(0) as u32;
                 let sel_pad = crate::theme::scale_i32(3);
                 let sel_h = if h > (sel_pad as u32 * 2) { h - sel_pad as u32 * 2 } else { 1 };
-                crate::draw::fill_rect(&clipped, sel_x, y + sel_pad, sel_w, sel_h, tc.accent & 0x60FFFFFF);
+                for (sx, ex) in line.spans(sel_start, sel_end) {
+                    crate::draw::fill_rect(&clipped, text_x + sx, y + sel_pad, (ex - sx) as u32, sel_h, tc.accent & 0x60FFFFFF);
+                }
             }
 
             // Draw text.
-            crate::draw::draw_text_sized(&clipped, text_x, text_y, text_color, &display, font_size);
+            crate::draw::draw_text_sized(&clipped, text_x, text_y, text_color, line.visual(), font_size);
+
+            // Squiggly underline below misspelled words.
+            if self.spell_check && !self.password_mode {
+                let squiggle_y = text_y + font_size as i32 + crate::theme::scale_i32(1);
+                for (ws, we) in crate::spell::misspelled_ranges(&self.text_base.text) {
+                    for (sx, ex) in line.spans(ws, we) {
+                        crate::draw::draw_squiggle(&clipped, text_x + sx, squiggle_y, (ex - sx).max(1) as u32, tc.destructive);
+                    }
+                }
+            }
 
             // Cursor.
             if self.focused {
-                let cursor = self.cursor_pos.min(display.len());
-                let cursor_px = crate::draw::text_width_n_at(&display, cursor, font_size) as i32;
-                let cx = text_x + cursor_px;
+                let cx = text_x + line.caret_x(self.cursor_pos);
                 let cursor_pad = crate::theme::scale_i32(4);
                 let cursor_w = crate::theme::scale(2);
                 let cursor_h = if h > (cursor_pad as u32 * 2) { h - cursor_pad as u32 * 2 } else { 1 };
@@ -274,8 +504,36 @@ impl Control for TextField {
     }
 
     fn is_interactive(&self) -> bool { !self.text_base.base.disabled }
+    fn default_cursor(&self) -> u32 { crate::control::CURSOR_IBEAM }
     fn accepts_focus(&self) -> bool { !self.text_base.base.disabled }
 
+    fn spell_word_at(&mut self, lx: i32, _ly: i32) -> Option<Vec<u8>> {
+        if !self.spell_check || self.password_mode { return None; }
+        let text = &self.text_base.text;
+        let pos = self.x_to_pos(lx);
+        // The hit test returns the nearest caret position, which may sit
+        // just past the last letter of the word that was clicked.
+        let range = crate::spell::word_at(text, pos)
+            .or_else(|| pos.checked_sub(1).and_then(|p| crate::spell::word_at(text, p)))?;
+        let word = text[range.0..range.1].to_vec();
+        if !crate::spell::is_misspelled(&word) { return None; }
+        self.spell_target = Some(range);
+        Some(word)
+    }
+
+    fn replace_spell_word(&mut self, replacement: &[u8]) -> bool {
+        let (start, end) = match self.spell_target.take() {
+            Some(r) if r.1 <= self.text_base.text.len() => r,
+            _ => return false,
+        };
+        self.text_base.text.splice(start..end, replacement.iter().copied());
+        self.cursor_pos = start + replacement.len();
+        self.sel_anchor = self.cursor_pos;
+        self.ensure_cursor_visible();
+        self.text_base.base.mark_dirty();
+        true
+    }
+
     fn handle_mouse_down(&mut self, lx: i32, _ly: i32, _button: u32) -> EventResponse {
         let pos = self.x_to_pos(lx);
         self.cursor_pos = pos;
@@ -308,16 +566,16 @@ impl Control for TextField {
         let pos = self.x_to_pos(lx);
         let text = &self.text_base.text;
         if text.is_empty() { return EventResponse::CONSUMED; }
-        let pos = pos.min(text.len().saturating_sub(1));
+        let pos = if pos >= text.len() { crate::cluster::prev_boundary(text, text.len()) } else { pos };
         // Find word boundaries.
         let mut start = pos;
         while start > 0 && is_word_char(text[start - 1]) { start -= 1; }
         let mut end = pos;
         while end < text.len() && is_word_char(text[end]) { end += 1; }
-        // If we clicked on a non-word char, select that single char.
+        // If we clicked on a non-word char, select that single character.
         if start == end && pos < text.len() {
-            end = pos + 1;
             start = pos;
+            end = crate::cluster::next_boundary(text, pos);
         }
         self.sel_anchor = start;
         self.cursor_pos = end;
@@ -334,153 +592,13 @@ impl Control for TextField {
     }
 
     fn handle_key_down(&mut self, keycode: u32, char_code: u32, modifiers: u32) -> EventResponse {
-        use crate::control::*;
-        let shift = modifiers & MOD_SHIFT != 0;
-        let ctrl = modifiers & MOD_CTRL != 0;
-
-        // Ctrl+A: select all.
-        if ctrl && (char_code == b'a' as u32 || char_code == b'A' as u32) {
-            self.sel_anchor = 0;
-            self.cursor_pos = self.text_base.text.len();
-            self.ensure_cursor_visible();
-            return EventResponse::CONSUMED;
-        }
-
-        // Ctrl+C: copy selection to clipboard.
-        if ctrl && (char_code == b'c' as u32 || char_code == b'C' as u32) {
-            if self.has_selection() {
-                let bytes = self.selected_bytes().to_vec();
-                crate::compositor::clipboard_set(&bytes);
-            }
-            return EventResponse::CONSUMED;
-        }
-
-        // Ctrl+X: cut selection.
-        if ctrl && (char_code == b'x' as u32 || char_code == b'X' as u32) {
-            if self.has_selection() {
-                let bytes = self.selected_bytes().to_vec();
-                crate::compositor::clipboard_set(&bytes);
-                self.delete_selection();
-                self.ensure_cursor_visible();
-                return EventResponse::CHANGED;
-            }
-            return EventResponse::CONSUMED;
-        }
-
-        // Ctrl+V: paste from clipboard.
-        if ctrl && (char_code == b'v' as u32 || char_code == b'V' as u32) {
-            if let Some(clip) = crate::compositor::clipboard_get() {
-                // Filter to printable ASCII.
-                let filtered: Vec<u8> = clip.into_iter().filter(|&b| b >= 0x20 && b < 0x7F).collect();
-                if !filtered.is_empty() {
-                    self.delete_selection();
-                    let pos = self.cursor_pos.min(self.text_base.text.len());
-                    for (i, &b) in filtered.iter().enumerate() {
-                        self.text_base.text.insert(pos + i, b);
-                    }
-                    self.cursor_pos = pos + filtered.len();
-                    self.sel_anchor = self.cursor_pos;
-                    self.ensure_cursor_visible();
-                    return EventResponse::CHANGED;
-                }
-            }
-            return EventResponse::CONSUMED;
-        }
-
-        // Printable character input.
-        if char_code >= 0x20 && char_code < 0x7F && !ctrl {
-            let ch = char_code as u8;
-            self.delete_selection();
-            let pos = self.cursor_pos.min(self.text_base.text.len());
-            self.text_base.text.insert(pos, ch);
-            self.cursor_pos = pos + 1;
-            self.sel_anchor = self.cursor_pos;
-            self.ensure_cursor_visible();
-            return EventResponse::CHANGED;
-        }
-
-        if keycode == KEY_BACKSPACE {
-            if self.has_selection() {
-                self.delete_selection();
-                self.ensure_cursor_visible();
-                return EventResponse::CHANGED;
-            }
-            if self.cursor_pos > 0 && !self.text_base.text.is_empty() {
-                self.cursor_pos -= 1;
-                self.text_base.text.remove(self.cursor_pos);
-                self.sel_anchor = self.cursor_pos;
-                self.ensure_cursor_visible();
-                return EventResponse::CHANGED;
-            }
-            return EventResponse::CONSUMED;
-        }
-
-        if keycode == KEY_DELETE {
-            if self.has_selection() {
-                self.delete_selection();
-                self.ensure_cursor_visible();
-                return EventResponse::CHANGED;
-            }
-            if self.cursor_pos < self.text_base.text.len() {
-                self.text_base.text.remove(self.cursor_pos);
-                self.sel_anchor = self.cursor_pos;
-                self.ensure_cursor_visible();
-                return EventResponse::CHANGED;
-            }
-            return EventResponse::CONSUMED;
-        }
-
-        if keycode == KEY_LEFT {
-            if ctrl {
-                // Word left.
-                self.cursor_pos = self.word_left(self.cursor_pos);
-            } else if !shift && self.has_selection() {
-                // Collapse selection to left edge.
-                let (start, _) = self.selection_range();
-                self.cursor_pos = start;
-            } else if self.cursor_pos > 0 {
-                self.cursor_pos -= 1;
-            }
-            if !shift { self.sel_anchor = self.cursor_pos; }
-            self.ensure_cursor_visible();
-            return EventResponse::CONSUMED;
+        let before = (self.text_base.text.clone(), self.cursor_pos, self.sel_anchor);
+        let resp = self.edit_key(keycode, char_code, modifiers);
+        if resp.fire_change {
+            self.pre_edit = Some(before);
+            self.edit_rejected = false;
         }
-
-        if keycode == KEY_RIGHT {
-            if ctrl {
-                // Word right.
-                self.cursor_pos = self.word_right(self.cursor_pos);
-            } else if !shift && self.has_selection() {
-                // Collapse selection to right edge.
-                let (_, end) = self.selection_range();
-                self.cursor_pos = end;
-            } else if self.cursor_pos < self.text_base.text.len() {
-                self.cursor_pos += 1;
-            }
-            if !shift { self.sel_anchor = self.cursor_pos; }
-            self.ensure_cursor_visible();
-            return EventResponse::CONSUMED;
-        }
-
-        if keycode == KEY_HOME {
-            self.cursor_pos = 0;
-            if !shift { self.sel_anchor = 0; }
-            self.ensure_cursor_visible();
-            return EventResponse::CONSUMED;
-        }
-
-        if keycode == KEY_END {
-            self.cursor_pos = self.text_base.text.len();
-            if !shift { self.sel_anchor = self.cursor_pos; }
-            self.ensure_cursor_visible();
-            return EventResponse::CONSUMED;
-        }
-
-        if keycode == KEY_ENTER {
-            return EventResponse::SUBMIT;
-        }
-
-        EventResponse::IGNORED
+        resp
     }
 
     fn handle_focus(&mut self) {
@@ -499,6 +617,24 @@ impl Control for TextField {
         self.dragging = false;
         // Collapse selection on blur.
         self.sel_anchor = self.cursor_pos;
+        // A partially filled mask is invalid input.
+        if !self.text_base.text.is_empty() && !self.filter.is_complete(&self.text_base.text) {
+            self.invalid = true;
+        }
         self.text_base.base.mark_dirty();
     }
 }
+
+fn as_textfield(ctrl: &mut alloc::boxed::Box<dyn Control>) -> Option<&mut TextField> {
+    if ctrl.kind() == ControlKind::TextField {
+        let raw: *mut dyn Control = &mut **ctrl;
+        Some(unsafe { &mut *(raw as *mut TextField) })
+    } else {
+        None
+    }
+}
+
+/// Finish validating the TextField's pending edit (see `TextField::finish_validate`).
+pub fn finish_validate(ctrl: &mut alloc::boxed::Box<dyn Control>) -> bool {
+    as_textfield(ctrl).map_or(true, |tf| tf.finish_validate())
+}
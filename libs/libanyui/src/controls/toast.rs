@@ -0,0 +1,257 @@
+//! Toast — transient in-window notification ("File saved — Undo").
+//!
+//! Toasts are created by `anyui_show_toast` as children of a window and are
+//! positioned by the framework, not by layout: they stack upward from the
+//! bottom centre of the window, newest lowest.  A shared event-loop timer
+//! (`AnyuiState::toast_timer`) counts down each toast's timeout (paused while
+//! hovered), eases toasts toward their stack slot — sliding in from below
+//! and back out when dismissed — and removes them once off-screen.  The
+//! timer is killed again when no toasts remain.
+//!
+//! Clicking the action text fires the toast's EVENT_CLICK callback; any click
+//! dismisses the toast.
+
+use alloc::vec::Vec;
+use crate::control::{Control, ControlBase, ControlId, TextControlBase, ControlKind, EventResponse};
+
+const TOAST_H: u32 = 40;
+/// Distance from the window's bottom edge / between stacked toasts.
+const MARGIN: i32 = 16;
+const GAP: i32 = 8;
+const PAD_X: i32 = 16;
+const MIN_W: u32 = 200;
+/// Timeout used when the caller passes 0.
+pub const DEFAULT_TIMEOUT_MS: u32 = 4000;
+/// Maximum number of toasts on screen per window; older ones are retired.
+const MAX_VISIBLE: usize = 4;
+/// Animation tick interval (~60 fps).
+const TICK_MS: u32 = 16;
+const FONT_SIZE: u16 = 13;
+
+pub struct Toast {
+    pub(crate) text_base: TextControlBase,
+    /// Action label drawn at the right edge (empty = no action).
+    pub(crate) action: Vec<u8>,
+    /// Time left before auto-dismiss.
+    remaining_ms: u32,
+    /// Sliding out; removed once below the window.
+    pub(crate) exiting: bool,
+    /// Last tick timestamp, for countdown deltas.
+    last_ms: u32,
+}
+
+impl Toast {
+    pub fn new(text_base: TextControlBase) -> Self {
+        Self {
+            text_base,
+            action: Vec::new(),
+            remaining_ms: DEFAULT_TIMEOUT_MS,
+            exiting: false,
+            last_ms: crate::syscall::uptime_ms(),
+        }
+    }
+
+    fn action_width(&self) -> i32 {
+        if self.action.is_empty() { return 0; }
+        let (aw, _) = crate::draw::text_size_at(&self.action, FONT_SIZE);
+        aw as i32 + PAD_X
+    }
+
+    /// Natural width for the message and action, capped to `max_w`.
+    fn preferred_width(&self, max_w: u32) -> u32 {
+        let (tw, _) = crate::draw::text_size_at(&self.text_base.text, FONT_SIZE);
+        let w = tw + (PAD_X * 2 + self.action_width()) as u32;
+        w.max(MIN_W).min(max_w.max(1))
+    }
+}
+
+impl Control for Toast {
+    fn base(&self) -> &ControlBase { &self.text_base.base }
+    fn base_mut(&mut self) -> &mut ControlBase { &mut self.text_base.base }
+    fn text_base(&self) -> Option<&TextControlBase> { Some(&self.text_base) }
+    fn text_base_mut(&mut self) -> Option<&mut TextControlBase> { Some(&mut self.text_base) }
+    fn kind(&self) -> ControlKind { ControlKind::Toast }
+
+    fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
+        let b = &self.text_base.base;
+        let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
+        let (x, y, w, h) = (p.x, p.y, p.w, p.h);
+        let tc = crate::theme::colors();
+        let corner = crate::theme::scale(8);
+
+        // Inverted colors so the toast stands out from window content.
+        crate::draw::draw_shadow_rounded_rect(
+            surface, x, y, w, h, corner as i32,
+            0, crate::theme::scale_i32(2), crate::theme::scale_i32(8), 50,
+        );
+        crate::draw::fill_rounded_rect(surface, x, y, w, h, corner, tc.text);
+
+        let fs = crate::draw::scale_font(FONT_SIZE);
+        let (_, th) = crate::draw::text_size_at(b"Ag", fs);
+        let ty = y + (h as i32 - th as i32) / 2;
+        let pad = crate::theme::scale_i32(PAD_X);
+        let action_w = crate::theme::scale_i32(self.action_width());
+        let clipped = surface.with_clip(x, y, (w as i32 - action_w).max(0) as u32, h);
+        crate::draw::draw_text_sized(&clipped, x + pad, ty, tc.window_bg, &self.text_base.text, fs);
+
+        if !self.action.is_empty() {
+            let (aw, _) = crate::draw::text_size_at(&self.action, fs);
+            let action_color = if b.hovered { crate::theme::lighten(tc.accent, 15) } else { tc.accent };
+            crate::draw::draw_text_sized(surface, x + w as i32 - pad - aw as i32, ty, action_color, &self.action, fs);
+        }
+    }
+
+    fn is_interactive(&self) -> bool { true }
+    fn accepts_focus(&self) -> bool { false }
+
+    fn handle_click(&mut self, lx: i32, _ly: i32, _button: u32) -> EventResponse {
+        if self.exiting { return EventResponse::CONSUMED; }
+        self.exiting = true;
+        let on_action = !self.action.is_empty()
+            && lx >= self.text_base.base.w as i32 - self.action_width() - PAD_X / 2;
+        if on_action { EventResponse::CLICK } else { EventResponse::CONSUMED }
+    }
+}
+
+fn as_toast(ctrl: &mut alloc::boxed::Box<dyn Control>) -> Option<&mut Toast> {
+    if ctrl.kind() == ControlKind::Toast {
+        let raw: *mut dyn Control = &mut **ctrl;
+        Some(unsafe { &mut *(raw as *mut Toast) })
+    } else {
+        None
+    }
+}
+
+/// Create a toast in window `win` and start the animation timer.
+/// Returns the toast's control ID, or 0 if `win` is not a window.
+pub fn show(
+    win: ControlId,
+    text: &[u8],
+    action: &[u8],
+    timeout_ms: u32,
+    cb: crate::control::Callback,
+    userdata: u64,
+) -> ControlId {
+    let st = crate::state();
+    let (win_w, win_h) = match st.controls.iter().find(|c| c.id() == win) {
+        Some(c) if c.kind() == ControlKind::Window => (c.base().w, c.base().h),
+        _ => return 0,
+    };
+
+    let id = st.next_id;
+    st.next_id += 1;
+    let mut ctrl = crate::controls::create_control(ControlKind::Toast, id, win, 0, win_h as i32, 0, TOAST_H, text);
+    if let Some(t) = as_toast(&mut ctrl) {
+        t.action = action.to_vec();
+        t.remaining_ms = if timeout_ms == 0 { DEFAULT_TIMEOUT_MS } else { timeout_ms };
+        let w = t.preferred_width(win_w.saturating_sub(MARGIN as u32 * 2));
+        t.text_base.base.w = w;
+        t.text_base.base.x = (win_w as i32 - w as i32) / 2;
+    }
+    ctrl.base_mut().set_callback(crate::control::EVENT_CLICK, cb, userdata);
+    st.controls.push(ctrl);
+    if let Some(p) = st.controls.iter_mut().find(|c| c.id() == win) {
+        p.add_child(id);
+    }
+
+    // Too many on screen: retire the oldest.
+    let mut live: Vec<ControlId> = window_toasts(st, win);
+    live.retain(|&t| {
+        st.controls.iter_mut().find(|c| c.id() == t).and_then(as_toast).map_or(false, |t| !t.exiting)
+    });
+    if live.len() > MAX_VISIBLE {
+        for &old in &live[..live.len() - MAX_VISIBLE] {
+            dismiss(old);
+        }
+    }
+
+    if st.toast_timer == 0 {
+        st.toast_timer = st.timers.set_timer(TICK_MS, toast_tick, 0);
+    }
+    id
+}
+
+/// Start the slide-out of toast `id`.
+pub fn dismiss(id: ControlId) {
+    let st = crate::state();
+    if let Some(t) = st.controls.iter_mut().find(|c| c.id() == id).and_then(as_toast) {
+        t.exiting = true;
+    }
+}
+
+/// Toast children of `win`, oldest first.
+fn window_toasts(st: &crate::AnyuiState, win: ControlId) -> Vec<ControlId> {
+    let children = match st.controls.iter().find(|c| c.id() == win) {
+        Some(w) => w.children().to_vec(),
+        None => return Vec::new(),
+    };
+    children.into_iter()
+        .filter(|&cid| st.controls.iter().any(|c| c.id() == cid && c.kind() == ControlKind::Toast))
+        .collect()
+}
+
+/// Animation timer: count down, ease toward stack slots, remove finished toasts.
+extern "C" fn toast_tick(timer_id: ControlId, _event_type: u32, _userdata: u64) {
+    let st = crate::state();
+    let now = crate::syscall::uptime_ms();
+    let mut finished: Vec<ControlId> = Vec::new();
+    let mut any = false;
+
+    for wi in 0..st.windows.len() {
+        let win = st.windows[wi];
+        let toasts = window_toasts(st, win);
+        if toasts.is_empty() { continue; }
+        any = true;
+        let (win_w, win_h) = match st.controls.iter().find(|c| c.id() == win) {
+            Some(c) => (c.base().w as i32, c.base().h as i32),
+            None => continue,
+        };
+
+        // Slots are assigned newest-first from the bottom up.
+        let mut slot = 0;
+        for &tid in toasts.iter().rev() {
+            let t = match st.controls.iter_mut().find(|c| c.id() == tid).and_then(as_toast) {
+                Some(t) => t,
+                None => continue,
+            };
+
+            let dt = now.wrapping_sub(t.last_ms);
+            t.last_ms = now;
+            if !t.exiting && !t.text_base.base.hovered {
+                t.remaining_ms = t.remaining_ms.saturating_sub(dt);
+                if t.remaining_ms == 0 { t.exiting = true; }
+            }
+
+            let target_y = if t.exiting {
+                win_h + GAP
+            } else {
+                let y = win_h - MARGIN - TOAST_H as i32 - slot * (TOAST_H as i32 + GAP);
+                slot += 1;
+                y
+            };
+
+            let b = &mut t.text_base.base;
+            if t.exiting && b.y >= win_h && b.prev_y >= win_h {
+                // Off-screen and the last frame has been rendered.
+                finished.push(tid);
+                continue;
+            }
+            let dy = target_y - b.y;
+            let step = if dy.abs() <= 2 { dy } else { dy / 4 + dy.signum() * 2 };
+            let x = (win_w - b.w as i32) / 2;
+            if step != 0 || b.x != x {
+                b.y += step;
+                b.x = x;
+                b.mark_dirty();
+            }
+        }
+    }
+
+    for tid in finished {
+        crate::anyui_remove(tid);
+    }
+    if !any {
+        st.timers.kill_timer(timer_id);
+        st.toast_timer = 0;
+    }
+}
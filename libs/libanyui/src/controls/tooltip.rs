@@ -34,7 +34,7 @@ impl Control for Tooltip {
 
         if !self.text_base.text.is_empty() {
             let fs = crate::draw::scale_font(self.text_base.text_style.font_size);
-            crate::draw::draw_text_sized(surface, x + crate::theme::scale_i32(8), y + crate::theme::scale_i32(4), tc.text, &self.text_base.text, fs);
+            crate::draw::draw_text_sized(surface, x + crate::theme::scale_i32(8), y + crate::theme::scale_i32(4), self.text_base.effective_text_color(), &self.text_base.text, fs);
         }
     }
 }
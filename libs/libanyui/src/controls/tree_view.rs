@@ -298,6 +298,10 @@ impl Control for TreeView {
     fn base_mut(&mut self) -> &mut ControlBase { &mut self.base }
     fn kind(&self) -> ControlKind { ControlKind::TreeView }
 
+    fn pixel_bytes(&self) -> usize {
+        self.nodes.iter().map(|n| n.icon_pixels.capacity() * 4).sum()
+    }
+
     fn render(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
         let b = self.base();
         let p = crate::draw::scale_bounds(ax, ay, b.x, b.y, b.w, b.h);
@@ -586,7 +590,11 @@ impl Control for TreeView {
         let content_h = self.content_height() as i32;
         let visible_h = self.base.h.saturating_sub(2) as i32;
         let max_scroll = (content_h - visible_h).max(0);
-        self.scroll_y = (self.scroll_y - delta * 20).max(0).min(max_scroll);
+        let y = (self.scroll_y - delta * 20).max(0).min(max_scroll);
+        if y == self.scroll_y {
+            return EventResponse::IGNORED;
+        }
+        self.scroll_y = y;
         self.base.mark_dirty();
         EventResponse::CONSUMED
     }
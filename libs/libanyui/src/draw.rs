@@ -401,6 +401,12 @@ pub fn text_size_at(text: &[u8], size: u16) -> (u32, u32) {
     measure_text_ex(text, DEFAULT_FONT_ID, size)
 }
 
+/// Approximate ascent of text drawn at `size`: the baseline lies this far
+/// below the y passed to the `draw_text*` functions.
+pub fn text_ascent(size: u16) -> i32 {
+    size as i32 * 4 / 5
+}
+
 /// Measure text width of the first `n` bytes using the default system font.
 pub fn text_width_n(text: &[u8], n: usize) -> u32 {
     text_width_n_at(text, n, DEFAULT_FONT_SIZE)
@@ -465,6 +471,92 @@ pub fn draw_focus_ring(s: &Surface, x: i32, y: i32, w: u32, h: u32, r: u32, colo
     }
 }
 
+/// Draw a 1px zigzag underline of width `w` whose crests sit at `y`
+/// (spell-check "misspelled word" marker). Cost: one fill_rect per column.
+pub fn draw_squiggle(s: &Surface, x: i32, y: i32, w: u32, color: u32) {
+    let period = crate::theme::scale_i32(4).max(2);
+    let amp = period / 2;
+    for dx in 0..w as i32 {
+        let phase = dx % period;
+        let dy = if phase < amp { phase } else { period - phase };
+        fill_rect(s, x + dx, y + dy, 1, 1, color);
+    }
+}
+
+// ── Arcs ────────────────────────────────────────────────────────────
+
+/// Full turn in arc angle units (tenths of a degree).
+pub const ARC_TURN: u32 = 3600;
+
+/// Draw an anti-aliased ring segment inside the square box `(x, y, d, d)`.
+///
+/// The ring's outer edge touches the box and it is `thickness` pixels wide.
+/// Angles are in tenths of a degree, measured clockwise from 12 o'clock;
+/// `sweep >= ARC_TURN` draws the full ring. Cost: one blend per pixel of the
+/// bounding box.
+pub fn draw_arc(s: &Surface, x: i32, y: i32, d: u32, thickness: u32, start: u32, sweep: u32, color: u32) {
+    if d == 0 || thickness == 0 || sweep == 0 { return; }
+    let clip_x0 = s.clip_x.max(0).max(x);
+    let clip_y0 = s.clip_y.max(0).max(y);
+    let clip_x1 = (s.clip_x + s.clip_w as i32).min(s.width as i32).min(x + d as i32);
+    let clip_y1 = (s.clip_y + s.clip_h as i32).min(s.height as i32).min(y + d as i32);
+    // Distances in 1/16 px; pixel centers and the ring center in half pixels.
+    let r_out = (d as i32) * 8;
+    let r_in = r_out - (thickness.min(d / 2) as i32) * 16;
+    let c2x = 2 * x + d as i32;
+    let c2y = 2 * y + d as i32;
+    let start = start % ARC_TURN;
+    let src_a = (color >> 24) & 0xFF;
+    for py in clip_y0..clip_y1 {
+        let dy = 2 * py + 1 - c2y;
+        for px in clip_x0..clip_x1 {
+            let dx = 2 * px + 1 - c2x;
+            let dist = isqrt_u64((dx * dx + dy * dy) as u64 * 64) as i32;
+            let outer = (r_out - dist + 8).clamp(0, 16);
+            let inner = (dist - r_in + 8).clamp(0, 16);
+            let cov = outer.min(inner) as u32;
+            if cov == 0 { continue; }
+            if sweep < ARC_TURN && (arc_angle(dx, dy) + ARC_TURN - start) % ARC_TURN >= sweep {
+                continue;
+            }
+            let alpha = src_a * cov / 16;
+            let idx = py as usize * s.width as usize + px as usize;
+            unsafe { *s.pixels.add(idx) = blend_over(color, alpha, *s.pixels.add(idx)); }
+        }
+    }
+}
+
+/// Clockwise angle from 12 o'clock of the vector `(dx, dy)` (y down), in
+/// tenths of a degree. Uses a quadratic atan approximation (error < 0.3°).
+fn arc_angle(dx: i32, dy: i32) -> u32 {
+    let (ax, ay) = (dx.unsigned_abs(), dy.unsigned_abs());
+    if ax == 0 && ay == 0 { return 0; }
+    // atan(z) ≈ z·π/4 + 0.273·z·(1 − z) for z in [0, 1], z in 1/1024 units.
+    let atan = |num: u32, den: u32| -> u32 {
+        let z = (num as u64 * 1024 / den as u64) as u32;
+        (z * 450 + z * (1024 - z) / 1024 * 156) / 1024
+    };
+    // Angle away from the vertical axis, 0..900.
+    let off = if ax <= ay { atan(ax, ay) } else { 900 - atan(ay, ax) };
+    match (dx >= 0, dy < 0) {
+        (true, true) => off,
+        (true, false) => 1800 - off,
+        (false, false) => 1800 + off,
+        (false, true) => (3600 - off) % 3600,
+    }
+}
+
+/// Blend an RGB color at `alpha` (0-255) over an opaque destination pixel.
+#[inline(always)]
+fn blend_over(color: u32, alpha: u32, dst: u32) -> u32 {
+    if alpha >= 255 { return color | 0xFF000000; }
+    let inv = 255 - alpha;
+    let r = ((color >> 16) & 0xFF) * alpha + ((dst >> 16) & 0xFF) * inv;
+    let g = ((color >> 8) & 0xFF) * alpha + ((dst >> 8) & 0xFF) * inv;
+    let b = (color & 0xFF) * alpha + (dst & 0xFF) * inv;
+    0xFF000000 | ((r / 255) << 16) | ((g / 255) << 8) | (b / 255)
+}
+
 // ── Shadow rendering ───────────────────────────────────────────────
 
 /// Integer square root (Newton's method).
@@ -481,7 +573,7 @@ fn isqrt_u32(n: u32) -> u32 {
 
 /// Integer square root for u64.
 #[inline]
-fn isqrt_u64(n: u64) -> u64 {
+pub(crate) fn isqrt_u64(n: u64) -> u64 {
     if n == 0 { return 0; }
     let mut x = 1u64 << ((64 - n.leading_zeros() + 1) / 2);
     loop {
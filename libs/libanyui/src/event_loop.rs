@@ -13,14 +13,19 @@
 //! 3. **MOUSE_UP**: If pressed control is still under cursor → dispatch handle_mouse_up,
 //!    then handle_click. Check for double-click.
 //! 4. **KEY_DOWN**: Dispatch to focused control via handle_key_down.
-//! 5. **SCROLL**: Dispatch to control under cursor via handle_scroll.
+//! 5. **SCROLL**: Dispatch to control under cursor via handle_scroll, step by
+//!    step; steps a scrollable at its limit cannot use chain to its ancestors.
 //! 6. **WINDOW_CLOSE**: Fire close callback, queue window for removal.
 //! 7. **WINDOW_RESIZE**: Update window size, fire resize callback.
+//! 8. **PEN / TOUCH**: Deliver the sample to `handle_pointer` (touch also fires
+//!    TouchDown/Move/Up and two-finger gestures), then dispatch the mouse
+//!    event it stands for. See [`crate::pointer`].
 
 use alloc::boxed::Box;
 use alloc::vec::Vec;
 use crate::compositor;
 use crate::control::{self, ControlId, ControlKind, Control, Callback};
+use crate::pointer::{self, PointerEvent};
 
 /// Double-click threshold in milliseconds (standard: 400ms).
 const DOUBLE_CLICK_MS: u32 = 400;
@@ -31,11 +36,18 @@ struct PendingCallback {
     event_type: u32,
     cb: Callback,
     userdata: u64,
+    /// Pointer sample that caused the callback (window coordinates).
+    pointer: Option<PointerEvent>,
+    /// Gesture that caused the callback (window coordinates).
+    gesture: Option<crate::gesture::GestureInfo>,
+    /// Source control of an EVENT_HELP callback.
+    help: Option<ControlId>,
 }
 
 /// Run the event loop. Blocks until all windows are closed or quit is requested.
 /// Event-driven: blocks on `evt_chan_wait` until the compositor delivers an event
 /// or the next timer fires. VSync back-pressure uses a shorter timeout.
+/// With event sources registered, `evt_chan_wait_any` also wakes on their channels.
 pub fn run() {
     loop {
         if run_once() == 0 {
@@ -58,14 +70,28 @@ pub fn run() {
             }
         }
 
+        // A held press wakes the loop when its long-press is due
+        if let Some(due) = st.gestures.next_deadline() {
+            min_wait = min_wait.min(due);
+        }
+
         // VSync back-pressure: poll faster when a frame is pending ACK
-        if st.comp_windows.iter().any(|cw| cw.frame_presented) {
+        if st.comp_windows.iter().any(|cw| cw.frame_presented && cw.present_mode != crate::PRESENT_IMMEDIATE) {
             min_wait = min_wait.min(8);
         }
 
         if min_wait > 0 {
-            // Block until compositor sends event OR timer timeout
-            crate::syscall::evt_chan_wait(st.channel_id, st.sub_id, min_wait);
+            // Block until compositor (or an event source) sends an event OR timer timeout
+            if st.event_sources.sources.is_empty() {
+                crate::syscall::evt_chan_wait(st.channel_id, st.sub_id, min_wait);
+            } else {
+                let mut subs: Vec<[u32; 2]> = Vec::with_capacity(st.event_sources.sources.len() + 1);
+                subs.push([st.channel_id, st.sub_id]);
+                for s in &st.event_sources.sources {
+                    subs.push([s.channel_id, s.sub_id]);
+                }
+                crate::syscall::evt_chan_wait_any(&subs, min_wait);
+            }
         }
     }
 }
@@ -87,6 +113,14 @@ pub fn run_once() -> u32 {
     // ── Phase 0: Drain marshal queue (cross-thread commands) ───────
     crate::marshal::drain(st);
 
+    // ── Phase 0.1: Apply finished ImageView file loads ──────────────
+    for (id, event_type) in crate::image_loader::drain(st) {
+        fire_event_callback(&st.controls, id, event_type, &mut pending_cbs);
+    }
+
+    // ── Phase 0.2: Dispatch application event sources ──────────────
+    crate::event_source::dispatch();
+
     // ── Phase 0.5: Fire elapsed timers ──────────────────────────────
     {
         let now = crate::syscall::uptime_ms();
@@ -97,6 +131,9 @@ pub fn run_once() -> u32 {
                     event_type: 0,
                     cb: slot.callback,
                     userdata: slot.userdata,
+                    pointer: None,
+                    gesture: None,
+                    help: None,
                 });
                 slot.last_fired_ms = now;
             }
@@ -122,9 +159,14 @@ pub fn run_once() -> u32 {
     {
         let popup_wid = st.popup.as_ref().map(|p| p.window_id);
         if let Some(popup_window_id) = popup_wid {
+            set_popup_zoom(st);
             for ev in all_events.iter_mut() {
                 // Handle events for the popup window
                 if ev[0] >= 0x3000 && ev[1] == popup_window_id {
+                    // Menus only understand the mouse.
+                    if ev[0] == compositor::EVT_PEN || ev[0] == compositor::EVT_TOUCH {
+                        *ev = st.touch.emulate(ev, st.last_modifiers).unwrap_or([0; 5]);
+                    }
                     match ev[0] {
                         compositor::EVT_MOUSE_MOVE => {
                             // Physical pixels from compositor — convert to logical
@@ -182,6 +224,9 @@ pub fn run_once() -> u32 {
                             if let Some(menu_id) = st.pressed.take() {
                                 let margin = st.popup.as_ref().map(|p| p.margin).unwrap_or(0);
                                 let owner_dd = st.popup.as_ref().and_then(|p| p.owner_dropdown);
+                                let owner_sf = st.popup.as_ref().and_then(|p| p.owner_search);
+                                let owner_spell = st.popup.as_ref()
+                                    .and_then(|p| p.owner_spell.map(|id| (id, p.spell_word.clone())));
                                 if let Some(idx) = control::find_idx(&st.controls, menu_id) {
                                     let (ax, ay) = (st.controls[idx].base().x, st.controls[idx].base().y);
                                     let local_x = mx - margin - ax;
@@ -198,6 +243,23 @@ pub fn run_once() -> u32 {
                                                 st.controls[dd_idx].base_mut().mark_dirty();
                                             }
                                             fire_event_callback(&st.controls, dd_id, control::EVENT_CHANGE, &mut pending_cbs);
+                                        } else if let Some(sf_id) = owner_sf {
+                                            // Suggestion popup: the chosen entry becomes the query
+                                            let selected_idx = st.controls[idx].base().state as usize;
+                                            dismiss_popup(st);
+                                            let accepted = control::find_idx(&st.controls, sf_id)
+                                                .and_then(|i| as_search_field(&mut st.controls[i]))
+                                                .map_or(false, |sf| sf.accept_item(selected_idx));
+                                            if accepted {
+                                                fire_event_callback(&st.controls, sf_id, control::EVENT_CHANGE, &mut pending_cbs);
+                                                fire_event_callback(&st.controls, sf_id, control::EVENT_SUBMIT, &mut pending_cbs);
+                                            }
+                                        } else if let Some((owner_id, word)) = owner_spell {
+                                            // Spell-check menu: replace, ignore or add the word
+                                            let selected_idx = st.controls[idx].base().state as usize;
+                                            let items = st.controls[idx].text().to_vec();
+                                            dismiss_popup(st);
+                                            apply_spell_choice(st, owner_id, &word, &items, selected_idx, &mut pending_cbs);
                                         } else {
                                             // Normal context menu
                                             dismiss_popup(st);
@@ -213,6 +275,17 @@ pub fn run_once() -> u32 {
                             // Another window gained focus → dismiss popup
                             dismiss_popup(st);
                         }
+                        compositor::EVT_KEY_DOWN | compositor::EVT_KEY_UP
+                            if st.popup.as_ref().map_or(false, |p| p.owner_search.is_some()) =>
+                        {
+                            // Suggestion popups never take the keyboard: typing and
+                            // list navigation keep going to the SearchField.
+                            let owner_win = st.popup.as_ref().map_or(0, |p| p.owner_win_idx);
+                            if let Some(cw) = st.comp_windows.get(owner_win) {
+                                ev[1] = cw.window_id;
+                                continue;
+                            }
+                        }
                         compositor::EVT_KEY_DOWN => {
                             let keycode = ev[2];
                             if keycode == control::KEY_ESCAPE {
@@ -229,12 +302,14 @@ pub fn run_once() -> u32 {
             // Check if any MOUSE_DOWN for a non-popup window while popup is active → dismiss
             if st.popup.is_some() {
                 for ev in all_events.iter_mut() {
-                    if ev[0] == compositor::EVT_MOUSE_DOWN && ev[1] != popup_window_id {
+                    let press = ev[0] == compositor::EVT_MOUSE_DOWN || pointer::is_down_event(ev);
+                    if press && ev[1] != popup_window_id {
                         dismiss_popup(st);
                         break;
                     }
                 }
             }
+            crate::theme::set_window_zoom(100);
         }
     }
 
@@ -242,13 +317,22 @@ pub fn run_once() -> u32 {
     for ev in all_events.iter() {
         if ev[0] == 0 { continue; }
         match ev[0] {
-            // EVT_THEME_CHANGED (0x0050): mark all windows dirty so every
-            // control re-renders with the new theme palette.
+            // EVT_THEME_CHANGED (0x0050): reload the palettes (picking up
+            // edited .conf files and keeping app overrides), mark all windows
+            // dirty so every control re-renders, and notify the app.
             0x0050 => {
-                for &win_id in &st.windows {
-                    if let Some(idx) = crate::control::find_idx(&st.controls, win_id) {
-                        mark_tree_dirty(&mut st.controls, idx);
-                    }
+                crate::theme::reload();
+                mark_all_windows_dirty(st);
+                if let Some((cb, ud)) = st.on_theme_changed {
+                    pending_cbs.push(PendingCallback {
+                        id: crate::theme::get_theme(),
+                        event_type: 0x0050,
+                        cb,
+                        userdata: ud,
+                        pointer: None,
+                        gesture: None,
+                        help: None,
+                    });
                 }
             }
             // EVT_FONT_SMOOTHING_CHANGED (0x0051): mark all windows dirty
@@ -266,8 +350,10 @@ pub fn run_once() -> u32 {
             0x0052 => {
                 crate::theme::refresh_scale_cache();
                 for cw in st.comp_windows.iter_mut() {
+                    crate::theme::set_window_zoom(cw.effective_zoom());
                     let phys_w = crate::theme::scale(cw.logical_width);
                     let phys_h = crate::theme::scale(cw.logical_height);
+                    crate::theme::set_window_zoom(100);
                     if phys_w != cw.width || phys_h != cw.height {
                         if let Some((new_shm_id, new_surface)) = crate::compositor::resize_shm(
                             st.channel_id,
@@ -281,8 +367,10 @@ pub fn run_once() -> u32 {
                         }
                         cw.width = phys_w;
                         cw.height = phys_h;
-                        let new_count = (phys_w as usize) * (phys_h as usize);
-                        cw.back_buffer.resize(new_count, 0);
+                        if !cw.back_buffer.is_empty() {
+                            let new_count = (phys_w as usize) * (phys_h as usize);
+                            cw.back_buffer.resize(new_count, 0);
+                        }
                     }
                 }
                 for &win_id in &st.windows {
@@ -292,6 +380,75 @@ pub fn run_once() -> u32 {
                 }
                 st.needs_layout = true;
             }
+            // EVT_ACCESSIBILITY_CHANGED (0x0054): high contrast and/or the
+            // system UI zoom changed. Re-evaluate the palette, re-zoom windows
+            // that follow the system zoom, and notify the app like a theme
+            // change (custom-drawn content must pick up the new palette).
+            0x0054 => {
+                crate::theme::refresh_accessibility_cache();
+                for wi in 0..st.comp_windows.len() {
+                    apply_window_zoom(st, wi);
+                }
+                mark_all_windows_dirty(st);
+                if let Some((cb, ud)) = st.on_theme_changed {
+                    pending_cbs.push(PendingCallback {
+                        id: crate::theme::get_theme(),
+                        event_type: 0x0050,
+                        cb,
+                        userdata: ud,
+                        pointer: None,
+                        gesture: None,
+                        help: None,
+                    });
+                }
+            }
+            0x0055 => {
+                // EVT_CLIPBOARD_CHANGED: ev[1] = clipboard_seq
+                if let Some((cb, ud)) = st.on_clipboard_changed {
+                    pending_cbs.push(PendingCallback {
+                        id: ev[1],
+                        event_type: 0x0055,
+                        cb,
+                        userdata: ud,
+                        pointer: None,
+                        gesture: None,
+                        help: None,
+                    });
+                }
+            }
+            0x0056 => {
+                // EVT_MEMORY_PRESSURE: ev[1] = new level, ev[2] = old level,
+                // ev[3] = free KiB. Low-memory mode trims before notifying.
+                st.memory.pressure = ev[1];
+                if st.memory.low_memory && ev[1] >= crate::memory::PRESSURE_LOW {
+                    crate::memory::trim(st);
+                }
+                if let Some((cb, ud)) = st.memory.on_pressure {
+                    pending_cbs.push(PendingCallback {
+                        id: ev[1],
+                        event_type: crate::memory::EVT_MEMORY_PRESSURE,
+                        cb,
+                        userdata: ud,
+                        pointer: None,
+                        gesture: None,
+                        help: None,
+                    });
+                }
+            }
+            0x0053 => {
+                // EVT_MONITORS_CHANGED: ev[1] = monitor_count
+                if let Some((cb, ud)) = st.on_monitors_changed {
+                    pending_cbs.push(PendingCallback {
+                        id: ev[1],
+                        event_type: 0x0053,
+                        cb,
+                        userdata: ud,
+                        pointer: None,
+                        gesture: None,
+                        help: None,
+                    });
+                }
+            }
             0x0060 => {
                 // EVT_WINDOW_OPENED: ev[1] = app_tid
                 if let Some((cb, ud)) = st.on_window_opened {
@@ -300,6 +457,9 @@ pub fn run_once() -> u32 {
                         event_type: 0x0060,
                         cb,
                         userdata: ud,
+                        pointer: None,
+                        gesture: None,
+                        help: None,
                     });
                 }
             }
@@ -311,6 +471,23 @@ pub fn run_once() -> u32 {
                         event_type: 0x0061,
                         cb,
                         userdata: ud,
+                        pointer: None,
+                        gesture: None,
+                        help: None,
+                    });
+                }
+            }
+            0x0062 => {
+                // EVT_DOCK_BADGE: ev[1] = app_tid, ev[2] = count
+                if let Some((cb, ud)) = st.on_dock_badge {
+                    pending_cbs.push(PendingCallback {
+                        id: ev[1],
+                        event_type: ev[2],
+                        cb,
+                        userdata: ud,
+                        pointer: None,
+                        gesture: None,
+                        help: None,
                     });
                 }
             }
@@ -323,18 +500,42 @@ pub fn run_once() -> u32 {
         if wi >= st.windows.len() { break; }
         let win_id = st.windows[wi];
         let comp_window_id = st.comp_windows[wi].window_id;
+        // Coordinates from the compositor are unscaled with this window's zoom.
+        crate::theme::set_window_zoom(st.comp_windows[wi].effective_zoom());
+        crate::bidi::set_window_rtl(st.comp_windows[wi].rtl);
 
         // Process events that belong to this window
         // Buffer layout: [event_type, window_id, arg1, arg2, arg3]
-        for ev in all_events.iter() {
+        for raw in all_events.iter() {
             // Skip consumed popup events
-            if ev[0] == 0 { continue; }
+            if raw[0] == 0 { continue; }
             // Window-specific events (0x3000+): filter by window_id
-            if ev[0] >= 0x3000 && ev[1] != comp_window_id { continue; }
+            if raw[0] >= 0x3000 && raw[1] != comp_window_id { continue; }
             // Broadcast events (<0x1000): only process on first window
-            if ev[0] < 0x1000 && wi > 0 { continue; }
+            if raw[0] < 0x1000 && wi > 0 { continue; }
             // Skip unknown range
-            if ev[0] >= 0x1000 && ev[0] < 0x3000 { continue; }
+            if raw[0] >= 0x1000 && raw[0] < 0x3000 { continue; }
+
+            // Pen and touch samples go to the pointer API first, then down
+            // the mouse path as the mouse event they stand for (if any).
+            let cb_start = pending_cbs.len();
+            let emulated;
+            let ev = match raw[0] {
+                compositor::EVT_PEN | compositor::EVT_TOUCH => {
+                    match dispatch_pointer(st, win_id, raw, &mut pending_cbs) {
+                        Some(mouse) => {
+                            emulated = mouse;
+                            &emulated
+                        }
+                        None => continue,
+                    }
+                }
+                compositor::EVT_MOUSE_MOVE | compositor::EVT_MOUSE_DOWN | compositor::EVT_MOUSE_UP => {
+                    track_mouse(st, raw);
+                    raw
+                }
+                _ => raw,
+            };
 
             match ev[0] {
                 compositor::EVT_WINDOW_CLOSE => {
@@ -349,7 +550,8 @@ pub fn run_once() -> u32 {
                     let my = crate::theme::unscale(ev[3] as i32);
 
                     // Update hover tracking (MouseEnter / MouseLeave)
-                    let new_hover = control::hit_test_any(&st.controls, win_id, mx, my, 0, 0);
+                    let new_hover = control::hit_test_any(&st.controls, win_id, mx, my, 0, 0)
+                        .filter(|&id| in_focus_scope(st, win_id, id));
                     let old_hover = st.hovered;
 
                     if new_hover != old_hover {
@@ -389,8 +591,8 @@ pub fn run_once() -> u32 {
                                 let text = st.controls[idx2].base().tooltip_text.clone();
                                 let (ax, ay) = control::abs_position(&st.controls, new_id);
                                 let ctrl_h = st.controls[idx2].base().h;
-                                // Estimate tooltip width: ~8px per char + 16px padding
-                                let tip_w = (text.len() as u32 * 8 + 16).max(40);
+                                // Measured text width (emoji included) + 16px padding
+                                let tip_w = (crate::draw::text_size_at(&text, 14).0 + 16).max(40);
 
                                 // Lazily create the tooltip or reuse existing one
                                 let tip_id = if let Some(tid) = st.active_tooltip {
@@ -444,6 +646,10 @@ pub fn run_once() -> u32 {
                     }
 
                     // If a control is pressed, dispatch mouse_move for drag
+                    st.gestures.motion(mx, my);
+                    if let Some(c) = st.selecting.motion(&mut st.controls, mx, my) {
+                        fire_event_callback(&st.controls, c, control::EVENT_SELECTION_CHANGED, &mut pending_cbs);
+                    }
                     if let Some(pressed_id) = st.pressed {
                         if let Some(idx) = control::find_idx(&st.controls, pressed_id) {
                             let (ax, ay) = control::abs_position(&st.controls, pressed_id);
@@ -456,12 +662,31 @@ pub fn run_once() -> u32 {
                                 if resp.fire_change {
                                     fire_event_callback(&st.controls, pressed_id, control::EVENT_CHANGE, &mut pending_cbs);
                                 }
+                                // ScrollView: thumb or content drag moved the offset
+                                if crate::controls::scroll_view::take_scrolled(&mut st.controls[idx]) {
+                                    fire_event_callback(&st.controls, pressed_id, control::EVENT_SCROLL, &mut pending_cbs);
+                                }
                                 if resp.fire_click {
                                     fire_event_callback(&st.controls, pressed_id, control::EVENT_CLICK, &mut pending_cbs);
                                 }
                             }
                         }
                     }
+
+                    update_cursor(st, wi);
+                }
+
+                compositor::EVT_MOUSE_DOWN if st.help.whats_this => {
+                    // "What's this?": the press asks for help on the deepest
+                    // control under the pointer and does nothing else.
+                    let mx = crate::theme::unscale(ev[2] as i32);
+                    let my = crate::theme::unscale(ev[3] as i32);
+                    st.help.whats_this = false;
+                    let source = control::hit_test_any(&st.controls, win_id, mx, my, 0, 0)
+                        .filter(|&id| in_focus_scope(st, win_id, id));
+                    if let Some(source) = source {
+                        fire_help_callback(&st.controls, source, &mut pending_cbs);
+                    }
                 }
 
                 compositor::EVT_MOUSE_DOWN => {
@@ -472,7 +697,8 @@ pub fn run_once() -> u32 {
                     let button = ev[4] & 0xFF;
                     st.last_modifiers = (ev[4] >> 8) & 0xFF;
 
-                    let hit_id = control::hit_test(&st.controls, win_id, mx, my, 0, 0);
+                    let hit_id = control::hit_test(&st.controls, win_id, mx, my, 0, 0)
+                        .filter(|&id| in_focus_scope(st, win_id, id));
 
                     // Update focus
                     if let Some(new_focus) = hit_id {
@@ -506,6 +732,19 @@ pub fn run_once() -> u32 {
 
                     st.pressed = hit_id;
                     st.pressed_button = button;
+                    if let Some(id) = hit_id.filter(|_| button & 0x01 != 0) {
+                        st.gestures.down(&st.controls, id, mx, my);
+                    }
+                    // Selection items need not be interactive (an icon and
+                    // a label in a View), so look for the deepest control.
+                    let select_hit = control::hit_test_any(&st.controls, win_id, mx, my, 0, 0)
+                        .filter(|&id| in_focus_scope(st, win_id, id));
+                    if let Some(id) = select_hit {
+                        let modifiers = st.last_modifiers;
+                        if let Some(c) = st.selecting.down(&mut st.controls, id, mx, my, button, modifiers) {
+                            fire_event_callback(&st.controls, c, control::EVENT_SELECTION_CHANGED, &mut pending_cbs);
+                        }
+                    }
 
                     if let Some(target_id) = hit_id {
                         if let Some(idx) = control::find_idx(&st.controls, target_id) {
@@ -520,6 +759,10 @@ pub fn run_once() -> u32 {
                             if resp.fire_change {
                                 fire_event_callback(&st.controls, target_id, control::EVENT_CHANGE, &mut pending_cbs);
                             }
+                            // ScrollView: a click on the track jumped the offset
+                            if crate::controls::scroll_view::take_scrolled(&mut st.controls[idx]) {
+                                fire_event_callback(&st.controls, target_id, control::EVENT_SCROLL, &mut pending_cbs);
+                            }
                             if resp.fire_click {
                                 fire_event_callback(&st.controls, target_id, control::EVENT_CLICK, &mut pending_cbs);
                             }
@@ -536,6 +779,14 @@ pub fn run_once() -> u32 {
                     st.last_modifiers = (ev[4] >> 8) & 0xFF;
 
                     let pressed_id = st.pressed.take();
+                    // A drag keeps its cursor until release.
+                    update_cursor(st, wi);
+
+                    // A long-press already answered this press: no click.
+                    let (gesture, long_pressed) = st.gestures.up(mx, my);
+                    if let Some(c) = st.selecting.up(&mut st.controls) {
+                        fire_event_callback(&st.controls, c, control::EVENT_SELECTION_CHANGED, &mut pending_cbs);
+                    }
 
                     if let Some(target_id) = pressed_id {
                         if let Some(idx) = control::find_idx(&st.controls, target_id) {
@@ -551,88 +802,31 @@ pub fn run_once() -> u32 {
                                 fire_event_callback(&st.controls, target_id, control::EVENT_CHANGE, &mut pending_cbs);
                             }
 
+                            // StackPanel: a handle drag committed a new child order
+                            if crate::controls::stack_panel::take_reorder(&mut st.controls[idx]) {
+                                fire_event_callback(&st.controls, target_id, control::EVENT_REORDER, &mut pending_cbs);
+                            }
+
                             // Check if mouse is still over the pressed control → Click
-                            let still_over = is_point_in_control(&st.controls, target_id, mx, my);
+                            let still_over = !long_pressed
+                                && is_point_in_control(&st.controls, target_id, mx, my);
 
                             if still_over {
                                 if st.pressed_button & 0x02 != 0 {
                                     // Right-click → fire EVENT_CONTEXT_MENU
                                     fire_event_callback(&st.controls, target_id, control::EVENT_CONTEXT_MENU, &mut pending_cbs);
 
-                                    // If control has a context menu, show it as a popup window
+                                    // A misspelled word under the pointer gets the spell-check
+                                    // menu; otherwise show the control's own context menu.
                                     if let Some(idx2) = control::find_idx(&st.controls, target_id) {
-                                        if let Some(menu_id) = st.controls[idx2].base().context_menu {
-                                            if let Some(mi) = control::find_idx(&st.controls, menu_id) {
+                                        if let Some(word) = st.controls[idx2].spell_word_at(local_x, local_y) {
+                                            dismiss_popup(st);
+                                            open_spell_menu(st, word, target_id, wi, mx, my);
+                                        } else if let Some(menu_id) = st.controls[idx2].base().context_menu {
+                                            if control::find_idx(&st.controls, menu_id).is_some() {
                                                 // Dismiss any existing popup first
                                                 dismiss_popup(st);
-
-                                                // Get menu dimensions (logical)
-                                                let menu_w = st.controls[mi].base().w;
-                                                let menu_h = st.controls[mi].base().h;
-
-                                                // Shadow margin (logical pixels)
-                                                let margin: i32 = 16;
-                                                let popup_w = menu_w + (margin as u32) * 2;
-                                                let popup_h = menu_h + (margin as u32) * 2;
-
-                                                // Physical popup dimensions for SHM surface
-                                                let phys_popup_w = crate::theme::scale(popup_w);
-                                                let phys_popup_h = crate::theme::scale(popup_h);
-
-                                                // Get parent window's content-area screen position (physical)
-                                                let (content_x, content_y) = compositor::get_window_position(
-                                                    st.channel_id, st.sub_id, comp_window_id,
-                                                );
-
-                                                // Calculate popup screen position (physical coords).
-                                                // mx/my are logical — scale to physical for screen placement.
-                                                let phys_mx = crate::theme::scale_i32(mx);
-                                                let phys_my = crate::theme::scale_i32(my);
-                                                let phys_margin = crate::theme::scale_i32(margin);
-                                                let mut popup_x = content_x + phys_mx - phys_margin;
-                                                let mut popup_y = content_y + phys_my - phys_margin;
-
-                                                // Clamp to screen bounds (physical)
-                                                let (scr_w, scr_h) = compositor::screen_size();
-                                                if popup_x + phys_popup_w as i32 > scr_w as i32 {
-                                                    popup_x = scr_w as i32 - phys_popup_w as i32;
-                                                }
-                                                if popup_y + phys_popup_h as i32 > scr_h as i32 {
-                                                    popup_y = scr_h as i32 - phys_popup_h as i32;
-                                                }
-                                                if popup_x < 0 { popup_x = 0; }
-                                                if popup_y < 0 { popup_y = 0; }
-
-                                                // Create popup compositor window (borderless, always-on-top, immovable)
-                                                // Flags: BORDERLESS=0x01 | NOT_RESIZABLE=0x02 | ALWAYS_ON_TOP=0x04 | NO_MOVE=0x100
-                                                let popup_flags: u32 = 0x01 | 0x02 | 0x04 | 0x100;
-                                                if let Some((popup_win_id, shm_id, surface)) = compositor::create_window(
-                                                    st.channel_id, st.sub_id,
-                                                    popup_x, popup_y,
-                                                    phys_popup_w, phys_popup_h,
-                                                    popup_flags,
-                                                ) {
-                                                    // Position menu at origin for clean popup rendering
-                                                    st.controls[mi].set_position(0, 0);
-                                                    // Menu stays invisible in parent (rendered directly in popup)
-                                                    st.controls[mi].base_mut().visible = false;
-
-                                                    // Back buffer at physical dimensions.
-                                                    let back_buffer = alloc::vec![0u32; (phys_popup_w * phys_popup_h) as usize];
-                                                    st.popup = Some(crate::PopupInfo {
-                                                        window_id: popup_win_id,
-                                                        shm_id,
-                                                        surface,
-                                                        width: phys_popup_w,
-                                                        height: phys_popup_h,
-                                                        back_buffer,
-                                                        menu_id,
-                                                        owner_win_idx: wi,
-                                                        margin,  // logical — used for hit-testing and render offset
-                                                        dirty: true,
-                                                        owner_dropdown: None,
-                                                    });
-                                                }
+                                                open_menu_at(st, menu_id, wi, mx, my, None);
                                             }
                                         }
                                     }
@@ -650,91 +844,10 @@ pub fn run_once() -> u32 {
                                             if dd.open {
                                                 dd.open = false; // clear immediately; popup takes over
 
-                                                // Gather items text and DropDown dimensions
+                                                // Dismiss any existing popup, then show the items below the DropDown
                                                 let items_text: alloc::vec::Vec<u8> = dd.text_base.text.clone();
-                                                let dd_w = dd.text_base.base.w;
-                                                let dd_h = dd.text_base.base.h;
-                                                let dd_abs = control::abs_position(&st.controls, target_id);
-
-                                                // Dismiss any existing popup
                                                 dismiss_popup(st);
-
-                                                // Create a temporary ContextMenu control
-                                                let menu_id = st.next_id;
-                                                st.next_id += 1;
-                                                let menu_ctrl = crate::controls::create_control(
-                                                    ControlKind::ContextMenu, menu_id, 0, 0, 0, 0, 0, &items_text,
-                                                );
-                                                st.controls.push(menu_ctrl);
-
-                                                // Force menu width to match DropDown width (min)
-                                                if let Some(mi) = control::find_idx(&st.controls, menu_id) {
-                                                    let menu_w = st.controls[mi].base().w.max(dd_w);
-                                                    st.controls[mi].base_mut().w = menu_w;
-                                                    let menu_h = st.controls[mi].base().h;
-
-                                                    // Shadow margin (logical pixels)
-                                                    let margin: i32 = 16;
-                                                    let popup_w = menu_w + (margin as u32) * 2;
-                                                    let popup_h = menu_h + (margin as u32) * 2;
-
-                                                    // Physical popup dimensions for SHM surface
-                                                    let phys_popup_w = crate::theme::scale(popup_w);
-                                                    let phys_popup_h = crate::theme::scale(popup_h);
-
-                                                    // Position popup below the DropDown (physical coords).
-                                                    // dd_abs is logical — scale for compositor screen placement.
-                                                    let (content_x, content_y) = compositor::get_window_position(
-                                                        st.channel_id, st.sub_id, comp_window_id,
-                                                    );
-                                                    let phys_dd_x = crate::theme::scale_i32(dd_abs.0);
-                                                    let phys_dd_y = crate::theme::scale_i32(dd_abs.1);
-                                                    let phys_dd_h = crate::theme::scale(dd_h);
-                                                    let phys_margin = crate::theme::scale_i32(margin);
-                                                    let phys_menu_h = crate::theme::scale(menu_h);
-                                                    let mut popup_x = content_x + phys_dd_x - phys_margin;
-                                                    let mut popup_y = content_y + phys_dd_y + phys_dd_h as i32 - phys_margin;
-
-                                                    // Clamp to screen bounds (physical)
-                                                    let (scr_w, scr_h) = compositor::screen_size();
-                                                    if popup_x + phys_popup_w as i32 > scr_w as i32 {
-                                                        popup_x = scr_w as i32 - phys_popup_w as i32;
-                                                    }
-                                                    if popup_y + phys_popup_h as i32 > scr_h as i32 {
-                                                        // Open upward if no room below
-                                                        popup_y = content_y + phys_dd_y - phys_menu_h as i32 - phys_margin;
-                                                    }
-                                                    if popup_x < 0 { popup_x = 0; }
-                                                    if popup_y < 0 { popup_y = 0; }
-
-                                                    // Create popup compositor window (physical dimensions)
-                                                    let popup_flags: u32 = 0x01 | 0x02 | 0x04 | 0x100;
-                                                    if let Some((popup_win_id, shm_id, surface)) = compositor::create_window(
-                                                        st.channel_id, st.sub_id,
-                                                        popup_x, popup_y,
-                                                        phys_popup_w, phys_popup_h,
-                                                        popup_flags,
-                                                    ) {
-                                                        st.controls[mi].set_position(0, 0);
-                                                        st.controls[mi].base_mut().visible = false;
-
-                                                        // Back buffer at physical dimensions.
-                                                        let back_buffer = alloc::vec![0u32; (phys_popup_w * phys_popup_h) as usize];
-                                                        st.popup = Some(crate::PopupInfo {
-                                                            window_id: popup_win_id,
-                                                            shm_id,
-                                                            surface,
-                                                            width: phys_popup_w,
-                                                            height: phys_popup_h,
-                                                            back_buffer,
-                                                            menu_id,
-                                                            owner_win_idx: wi,
-                                                            margin,  // logical — used for hit-testing and render offset
-                                                            dirty: true,
-                                                            owner_dropdown: Some(target_id),
-                                                        });
-                                                    }
-                                                }
+                                                open_anchored_menu(st, &items_text, target_id, wi, Some(target_id), None);
                                             }
                                         }
 
@@ -796,6 +909,25 @@ pub fn run_once() -> u32 {
                             }
                         }
                     }
+
+                    if let Some(g) = gesture {
+                        fire_gesture_callback(&st.controls, g, &mut pending_cbs);
+                    }
+                }
+
+                compositor::EVT_KEY_DOWN if st.help.whats_this && ev[2] == control::KEY_ESCAPE => {
+                    st.help.whats_this = false;
+                }
+
+                compositor::EVT_KEY_DOWN if ev[2] == control::KEY_F1 => {
+                    // F1: help on the focused control, else on the window.
+                    st.last_keycode = ev[2];
+                    st.last_char_code = ev[3];
+                    st.last_modifiers = ev[4];
+                    let source = st.focused
+                        .filter(|&f| in_focus_scope(st, win_id, f))
+                        .unwrap_or(win_id);
+                    fire_help_callback(&st.controls, source, &mut pending_cbs);
                 }
 
                 compositor::EVT_KEY_DOWN => {
@@ -811,10 +943,14 @@ pub fn run_once() -> u32 {
 
                     let mut handled = false;
 
-                    if let Some(focus_id) = st.focused {
+                    // A control left focused outside the active scope gets no keys.
+                    if let Some(focus_id) = st.focused.filter(|&f| in_focus_scope(st, win_id, f)) {
                         if let Some(idx) = control::find_idx(&st.controls, focus_id) {
-                            let resp = st.controls[idx].handle_key_down(keycode, char_code, modifiers);
+                            let mut resp = st.controls[idx].handle_key_down(keycode, char_code, modifiers);
                             st.controls[idx].base_mut().mark_dirty();
+                            if resp.fire_change && !validate_edit(st, focus_id) {
+                                resp.fire_change = false;
+                            }
 
                             if resp.consumed {
                                 handled = true;
@@ -849,26 +985,7 @@ pub fn run_once() -> u32 {
 
                     // Dispatch to hovered control, bubbling up to ScrollView if needed
                     if let Some(target_id) = st.hovered {
-                        let mut cur = target_id;
-                        loop {
-                            if let Some(idx) = control::find_idx(&st.controls, cur) {
-                                let resp = st.controls[idx].handle_scroll(dz);
-                                if resp.consumed {
-                                    st.controls[idx].base_mut().mark_dirty();
-                                    fire_event_callback(&st.controls, cur, control::EVENT_SCROLL, &mut pending_cbs);
-                                    if resp.fire_change {
-                                        fire_event_callback(&st.controls, cur, control::EVENT_CHANGE, &mut pending_cbs);
-                                    }
-                                    break;
-                                }
-                                // Bubble up to parent
-                                let parent = st.controls[idx].parent_id();
-                                if parent == 0 || parent == cur { break; }
-                                cur = parent;
-                            } else {
-                                break;
-                            }
-                        }
+                        dispatch_scroll(st, target_id, dz, &mut pending_cbs);
                     }
                 }
 
@@ -896,9 +1013,12 @@ pub fn run_once() -> u32 {
                         cw.height = phys_h;
                         cw.logical_width = logical_w;
                         cw.logical_height = logical_h;
-                        // Resize back buffer at physical dimensions.
-                        let new_count = (phys_w as usize) * (phys_h as usize);
-                        cw.back_buffer.resize(new_count, 0);
+                        // Resize back buffer at physical dimensions (a released
+                        // one is reallocated by the next render).
+                        if !cw.back_buffer.is_empty() {
+                            let new_count = (phys_w as usize) * (phys_h as usize);
+                            cw.back_buffer.resize(new_count, 0);
+                        }
 
                     }
                     if let Some(idx) = control::find_idx(&st.controls, win_id) {
@@ -909,21 +1029,49 @@ pub fn run_once() -> u32 {
                     st.needs_layout = true;
                 }
 
+                compositor::EVT_WINDOW_SNAPPED => {
+                    // arg1=region, arg2/arg3=content size (follows EVT_RESIZE).
+                    if wi < st.comp_windows.len() {
+                        st.comp_windows[wi].snap_region = ev[2];
+                    }
+                    fire_event_callback(&st.controls, win_id, control::EVENT_SNAP, &mut pending_cbs);
+                }
+
                 compositor::EVT_FRAME_ACK => {
                     // VSync callback: compositor has composited our frame to screen.
                     // Clear back-pressure so we can present the next frame.
                     if wi < st.comp_windows.len() {
+                        if st.comp_windows[wi].frame_presented {
+                            st.frame_stats.present_wait_ms = crate::syscall::uptime_ms()
+                                .wrapping_sub(st.comp_windows[wi].last_present_ms);
+                        }
                         st.comp_windows[wi].frame_presented = false;
                     }
                 }
 
                 _ => {}
             }
+
+            // Mouse callbacks see the sample (mouse or pen/touch) behind them.
+            if matches!(ev[0], compositor::EVT_MOUSE_MOVE | compositor::EVT_MOUSE_DOWN | compositor::EVT_MOUSE_UP) {
+                let sample = st.pointer;
+                for pcb in &mut pending_cbs[cb_start..] {
+                    pcb.pointer.get_or_insert(sample);
+                }
+            }
         }
     }
+    crate::theme::set_window_zoom(100);
+    crate::bidi::set_window_rtl(false);
+
+    // ── Phase 1.5: Long-press whose hold time ran out ───────────────
+    if let Some(g) = st.gestures.poll() {
+        fire_gesture_callback(&st.controls, g, &mut pending_cbs);
+    }
 
     // ── Phase 2: Close windows ──────────────────────────────────────
     let channel_id = st.channel_id;
+    let mut tag_drops = Vec::new();
     for win_id in &windows_to_close {
         if let Some(wi) = st.windows.iter().position(|&w| w == *win_id) {
             let cw = &st.comp_windows[wi];
@@ -932,13 +1080,29 @@ pub fn run_once() -> u32 {
             st.windows.remove(wi);
         }
         clear_tracking_for(st, *win_id);
-        remove_subtree(&mut st.controls, *win_id);
+        tag_drops.extend(remove_subtree(&mut st.controls, *win_id));
     }
 
     // ── Phase 3: Invoke callbacks (no borrows held) ────────────────
     for pcb in pending_cbs {
+        if let Some(sample) = pcb.pointer {
+            // Pointer info reads local to the control being notified.
+            let st = crate::state();
+            let (ax, ay) = control::abs_position(&st.controls, pcb.id);
+            st.pointer = sample.relative_to(ax, ay);
+        }
+        if let Some(info) = pcb.gesture {
+            let st = crate::state();
+            let (ax, ay) = control::abs_position(&st.controls, pcb.id);
+            st.gesture = crate::gesture::GestureInfo { x: info.x - ax, y: info.y - ay, ..info };
+        }
+        if let Some(source) = pcb.help {
+            crate::state().help.source = source;
+        }
         (pcb.cb)(pcb.id, pcb.event_type, pcb.userdata);
     }
+    // Tag destructors of controls in closed windows.
+    crate::tag::run_destructors(tag_drops);
 
     // Re-acquire state (callbacks may have modified it)
     let st = crate::state();
@@ -946,19 +1110,54 @@ pub fn run_once() -> u32 {
         return 0;
     }
 
+    // ── Phase 3.3: Validate controls that lost focus or were edited ──
+    // Custom validators are application callbacks: no borrows held.
+    crate::validation::revalidate();
+    let st = crate::state();
+
+    st.frame_stats.begin();
+
+    // ── Phase 3.4: Realize items of virtualized containers ──────────
+    // Scrolling since the last frame may have brought new items into view.
+    // Item providers are application callbacks: no borrows held.
+    crate::virtualize::realize_all();
+    let st = crate::state();
+
+    // ── Phase 3.45: Apply styles to new controls / after style changes ─
+    if crate::style::resolve_all(st) {
+        st.needs_layout = true;
+    }
+
     // ── Phase 3.5: Layout (skipped when no layout-affecting changes) ──
     if st.needs_layout {
-        for wi in 0..st.windows.len() {
-            let win_id = st.windows[wi];
-            crate::layout::perform_layout(&mut st.controls, win_id);
+        let layout_start = crate::syscall::uptime_ms();
+        layout_windows(st);
+
+        // Layout may have moved or resized virtualized containers; realize
+        // what is visible now and lay out once more so it shows this frame.
+        if crate::virtualize::realize_all() {
+            crate::style::resolve_all(crate::state());
+            layout_windows(crate::state());
         }
+        let st = crate::state();
+        st.frame_stats.current.layout_ms = crate::syscall::uptime_ms().wrapping_sub(layout_start);
+    }
+    let st = crate::state();
 
-        // Phase 3.6: Update scroll bounds (only after layout)
-        crate::controls::scroll_view::update_scroll_bounds(&mut st.controls);
-
-        st.needs_layout = false;
+    // ── Phase 3.6: Scroll a control into view (Tab focus, ensure_visible) ─
+    // After layout, so newly created controls have their final position.
+    if let Some(id) = st.ensure_visible.take() {
+        crate::controls::scroll_view::ensure_visible(&mut st.controls, id);
     }
 
+    // ── Phase 3.65: SearchField suggestion popup ────────────────────
+    // Runs after layout so the popup is anchored at the field's final position.
+    sync_search_popup(st);
+    crate::theme::set_window_zoom(100);
+
+    // ── Phase 3.66: Validation message bubbles ──────────────────────
+    crate::validation::place_messages(st);
+
     // ── Phase 3.7: Compute per-window dirty flags + dirty rects ─────
     // Push-based: only scan when mark_dirty() was called since last render.
     // On idle frames (no events, no timers), this entire phase is skipped.
@@ -977,19 +1176,32 @@ pub fn run_once() -> u32 {
         st.needs_repaint = false;
     }
 
+    // ── Phase 3.8: Low-memory mode — hidden windows keep no back buffer ──
+    if st.memory.low_memory {
+        crate::memory::release_hidden_buffers(st);
+    }
+
     // ── Phase 4: Render dirty windows (with VSync back-pressure) ───
     // Incremental rendering: only re-render controls that intersect the dirty
     // rect, copy only the dirty region to SHM, and tell the compositor which
     // rect changed. For typical interactions (hover, click, typing) this is
     // 50-500x faster than a full-window redraw.
     let channel_id = st.channel_id;
+    let mut rendered = false;
     for wi in 0..st.windows.len() {
         let win_id = st.windows[wi];
+        let mode = st.comp_windows[wi].present_mode;
+
+        // Manual mode: hold the frame back until the application presents it.
+        if mode == crate::PRESENT_MANUAL && !st.comp_windows[wi].present_requested {
+            continue;
+        }
 
         // Back-pressure: skip if previous frame hasn't been composited yet.
         // This prevents overwriting SHM while compositor is reading it.
         // Safety timeout after 64ms (~4 frames) to avoid hangs if ACK is lost.
-        if st.comp_windows[wi].frame_presented {
+        // Immediate mode skips the wait and accepts possible tearing.
+        if mode != crate::PRESENT_IMMEDIATE && st.comp_windows[wi].frame_presented {
             let now = crate::syscall::uptime_ms();
             if now.wrapping_sub(st.comp_windows[wi].last_present_ms) < 64 {
                 continue;
@@ -997,11 +1209,30 @@ pub fn run_once() -> u32 {
             st.comp_windows[wi].frame_presented = false;
         }
 
+        // A manual present with nothing changed has nothing to send.
+        st.comp_windows[wi].present_requested = false;
+
         // Skip rendering if no control in this window tree is dirty (O(1) check)
         if !st.comp_windows[wi].dirty {
             continue;
         }
 
+        // The back buffer was released while the window was hidden
+        // (anyui_trim_memory / low-memory mode): reallocate and redraw in full.
+        if st.comp_windows[wi].back_buffer.is_empty() {
+            if !crate::memory::window_visible(st, win_id) {
+                // Nothing to draw into; showing the window redraws it in full.
+                clear_dirty(&mut st.controls, win_id);
+                st.comp_windows[wi].dirty = false;
+                continue;
+            }
+            let cw = &mut st.comp_windows[wi];
+            cw.back_buffer = alloc::vec![0u32; (cw.width as usize) * (cw.height as usize)];
+            cw.dirty_rect = None;
+        }
+        crate::theme::set_window_zoom(st.comp_windows[wi].effective_zoom());
+        crate::bidi::set_window_rtl(st.comp_windows[wi].rtl);
+
         let surface_ptr = st.comp_windows[wi].surface;
         let sw = st.comp_windows[wi].width;
         let sh = st.comp_windows[wi].height;
@@ -1010,6 +1241,7 @@ pub fn run_once() -> u32 {
         let dirty_rect = st.comp_windows[wi].dirty_rect;
         let logical_w = st.comp_windows[wi].logical_width;
         let logical_h = st.comp_windows[wi].logical_height;
+        let render_start = crate::syscall::uptime_ms();
 
         // Clamp dirty rect in logical space (for render_tree intersection tests)
         let logical_dr = dirty_rect.map(|(dx, dy, dw, dh)| {
@@ -1051,7 +1283,27 @@ pub fn run_once() -> u32 {
         // Render control tree — only controls intersecting the LOGICAL dirty rect
         // are drawn. The surface's physical clip rect ensures drawing ops outside
         // the dirty region are discarded at the pixel level.
-        render_tree(&st.controls, win_id, &surf, 0, 0, logical_dr);
+        let mut drawn = 0u32;
+        render_tree(&st.controls, win_id, &surf, 0, 0, logical_dr, &mut drawn);
+        if st.frame_stats.overlay {
+            st.frame_stats.draw_overlay(&full_surf);
+        }
+        let dirty_area = match physical_dr {
+            Some((_, _, dw, dh)) => dw * dh,
+            None => sw * sh,
+        };
+
+        // Stats overlay: drawn on top of the control tree outside the clip, so
+        // extend the copied/presented region to cover the overlay box.
+        let physical_dr = match physical_dr {
+            Some(dr) if st.frame_stats.overlay => {
+                let (ox, oy, ow, oh) = st.frame_stats.overlay_rect();
+                let ow = ow.min(sw.saturating_sub(ox as u32));
+                let oh = oh.min(sh.saturating_sub(oy as u32));
+                Some(union_rect(Some(dr), ox, oy, ow, oh))
+            }
+            other => other,
+        };
 
         // Copy back buffer → SHM: either the dirty region or the full buffer.
         // Uses PHYSICAL dirty rect for pixel-level copy offsets.
@@ -1079,6 +1331,13 @@ pub fn run_once() -> u32 {
 
         // Clear dirty flags + reset prev_x/y/w/h after rendering
         clear_dirty(&mut st.controls, win_id);
+        place_native_surfaces(st, wi);
+
+        let fs = &mut st.frame_stats.current;
+        fs.render_ms += crate::syscall::uptime_ms().wrapping_sub(render_start);
+        fs.controls_drawn += drawn;
+        fs.dirty_area += dirty_area;
+        rendered = true;
 
         // Clear the CompWindow dirty state — without this, cw.dirty stays true
         // forever (Phase 3.7 only resets it when needs_repaint is true, but no
@@ -1100,6 +1359,8 @@ pub fn run_once() -> u32 {
         st.comp_windows[wi].frame_presented = true;
         st.comp_windows[wi].last_present_ms = crate::syscall::uptime_ms();
     }
+    crate::theme::set_window_zoom(100);
+    crate::bidi::set_window_rtl(false);
 
     // ── Phase 4.1: Render popup (if active and dirty) ──────────────
     // Popup rendering is separate from regular windows because the popup
@@ -1116,6 +1377,7 @@ pub fn run_once() -> u32 {
     };
 
     if let Some((menu_id, margin, pw, ph, surface, popup_win_id, shm_id)) = popup_render_info {
+        set_popup_zoom(st);
         // Clear dirty flag and back buffer
         if let Some(ref mut p) = st.popup {
             p.dirty = false;
@@ -1137,6 +1399,12 @@ pub fn run_once() -> u32 {
 
         // Present the popup
         compositor::present(st.channel_id, popup_win_id, shm_id);
+        crate::theme::set_window_zoom(100);
+    }
+
+    // ── Phase 5: Publish frame stats, fire the over-budget callback ──
+    if let Some((cb, frame_ms, frame, userdata)) = st.frame_stats.finish(rendered) {
+        cb(frame_ms, frame, userdata);
     }
 
     1
@@ -1144,6 +1412,21 @@ pub fn run_once() -> u32 {
 
 // ── Helper functions ────────────────────────────────────────────────
 
+/// Lay out every window and update the ScrollView bounds.
+fn layout_windows(st: &mut crate::AnyuiState) {
+    for wi in 0..st.windows.len() {
+        let win_id = st.windows[wi];
+        crate::bidi::set_window_rtl(st.comp_windows[wi].rtl);
+        crate::layout::perform_layout(&mut st.controls, win_id);
+    }
+    crate::bidi::set_window_rtl(false);
+
+    // Update scroll bounds (only after layout)
+    crate::controls::scroll_view::update_scroll_bounds(&mut st.controls);
+
+    st.needs_layout = false;
+}
+
 fn fire_event_callback(
     controls: &[Box<dyn Control>],
     id: ControlId,
@@ -1157,11 +1440,266 @@ fn fire_event_callback(
                 event_type,
                 cb: slot.cb,
                 userdata: slot.userdata,
+                pointer: None,
+                gesture: None,
+                help: None,
             });
         }
     }
 }
 
+/// Queue a callback that reads `sample` through `anyui_get_pointer_info`.
+fn fire_pointer_callback(
+    controls: &[Box<dyn Control>],
+    id: ControlId,
+    event_type: u32,
+    sample: &PointerEvent,
+    pending: &mut Vec<PendingCallback>,
+) {
+    let start = pending.len();
+    fire_event_callback(controls, id, event_type, pending);
+    if let Some(pcb) = pending.get_mut(start) {
+        pcb.pointer = Some(*sample);
+    }
+}
+
+/// Queue the callback for a recognized gesture; it reads the gesture
+/// through `anyui_get_gesture_info`.
+fn fire_gesture_callback(
+    controls: &[Box<dyn Control>],
+    g: crate::gesture::Recognized,
+    pending: &mut Vec<PendingCallback>,
+) {
+    let start = pending.len();
+    fire_event_callback(controls, g.target, g.event_type, pending);
+    if let Some(pcb) = pending.get_mut(start) {
+        pcb.gesture = Some(g.info);
+    }
+}
+
+/// Queue EVENT_HELP for `source` on the nearest control with a handler;
+/// the callback reads `source` through `anyui_get_help_source`.
+fn fire_help_callback(
+    controls: &[Box<dyn Control>],
+    source: ControlId,
+    pending: &mut Vec<PendingCallback>,
+) {
+    let Some(target) = crate::help::handler(controls, source) else { return };
+    let start = pending.len();
+    fire_event_callback(controls, target, control::EVENT_HELP, pending);
+    if let Some(pcb) = pending.get_mut(start) {
+        pcb.help = Some(source);
+    }
+}
+
+/// Scroll by `dz` wheel steps over `target`, one step at a time. Each step
+/// goes to the innermost control that can still move in that direction: a
+/// scrollable at its limit lets the step chain up to the nearest scrollable
+/// ancestor, unless a control on the way has scroll chaining turned off.
+/// EVENT_SCROLL (and EVENT_CHANGE if asked for) fire once per control that
+/// moved.
+fn dispatch_scroll(
+    st: &mut crate::AnyuiState,
+    target: ControlId,
+    dz: i32,
+    pending: &mut Vec<PendingCallback>,
+) {
+    // (control, fire_change) for every control that moved.
+    let mut moved: Vec<(ControlId, bool)> = Vec::new();
+    for _ in 0..dz.unsigned_abs() {
+        let mut taken = false;
+        let mut cur = target;
+        while let Some(idx) = control::find_idx(&st.controls, cur) {
+            let resp = st.controls[idx].handle_scroll(dz.signum());
+            if resp.consumed {
+                st.controls[idx].base_mut().mark_dirty();
+                // Refresh the ScrollView's reported offset delta
+                crate::controls::scroll_view::take_scrolled(&mut st.controls[idx]);
+                match moved.iter_mut().find(|m| m.0 == cur) {
+                    Some(m) => m.1 |= resp.fire_change,
+                    None => moved.push((cur, resp.fire_change)),
+                }
+                taken = true;
+                break;
+            }
+            if !st.controls[idx].base().scroll_chaining { break; }
+            // Chain to parent
+            let parent = st.controls[idx].parent_id();
+            if parent == 0 || parent == cur { break; }
+            cur = parent;
+        }
+        // Nothing along the chain can move further this way.
+        if !taken { break; }
+    }
+    for (id, change) in moved {
+        fire_event_callback(&st.controls, id, control::EVENT_SCROLL, pending);
+        if change {
+            fire_event_callback(&st.controls, id, control::EVENT_CHANGE, pending);
+        }
+    }
+}
+
+// ── Pointer input (pen, touch) ─────────────────────────────────────
+
+/// Pass the current pointer sample to `target`'s `handle_pointer`.
+fn deliver_pointer(st: &mut crate::AnyuiState, target: Option<ControlId>) {
+    let id = match target {
+        Some(id) => id,
+        None => return,
+    };
+    if let Some(idx) = control::find_idx(&st.controls, id) {
+        let (ax, ay) = control::abs_position(&st.controls, id);
+        let local = st.pointer.relative_to(ax, ay);
+        if st.controls[idx].handle_pointer(&local).consumed {
+            st.controls[idx].base_mut().mark_dirty();
+        }
+    }
+}
+
+/// Record a mouse event as the current pointer sample.
+fn track_mouse(st: &mut crate::AnyuiState, ev: &[u32; 5]) {
+    let mx = crate::theme::unscale(ev[2] as i32);
+    let my = crate::theme::unscale(ev[3] as i32);
+    let (phase, buttons) = match ev[0] {
+        compositor::EVT_MOUSE_DOWN => (pointer::PHASE_DOWN, ev[4] & 0xFF),
+        compositor::EVT_MOUSE_UP => (pointer::PHASE_UP, 0),
+        _ => (pointer::PHASE_MOVE, if st.pressed.is_some() { st.pressed_button } else { 0 }),
+    };
+    st.pointer = PointerEvent::mouse(phase, mx, my, buttons);
+    deliver_pointer(st, st.pressed.or(st.hovered));
+}
+
+/// Handle an EVT_PEN or EVT_TOUCH event. Returns the mouse event it
+/// stands for, to be dispatched next.
+fn dispatch_pointer(
+    st: &mut crate::AnyuiState,
+    win_id: ControlId,
+    ev: &[u32; 5],
+    pending: &mut Vec<PendingCallback>,
+) -> Option<[u32; 5]> {
+    // arg1=local_x, arg2=local_y (physical), arg3=packed sample.
+    let x = crate::theme::unscale(ev[2] as i32);
+    let y = crate::theme::unscale(ev[3] as i32);
+    if ev[0] == compositor::EVT_PEN {
+        st.pointer = PointerEvent::pen(x, y, ev[4]);
+        let target = st.pressed.or_else(|| {
+            control::hit_test(&st.controls, win_id, x, y, 0, 0)
+                .filter(|&id| in_focus_scope(st, win_id, id))
+        });
+        deliver_pointer(st, target);
+    } else {
+        st.pointer = PointerEvent::touch(x, y, ev[4]);
+        dispatch_touch(st, win_id, pending);
+    }
+    st.touch.emulate(ev, st.last_modifiers)
+}
+
+/// Track the touch contact in `st.pointer`, fire its EVENT_TOUCH_* on the
+/// control it went down on and drive two-finger gestures.
+fn dispatch_touch(st: &mut crate::AnyuiState, win_id: ControlId, pending: &mut Vec<PendingCallback>) {
+    let sample = st.pointer;
+    match sample.phase {
+        pointer::PHASE_DOWN => {
+            let target = control::hit_test(&st.controls, win_id, sample.x, sample.y, 0, 0)
+                .filter(|&id| in_focus_scope(st, win_id, id));
+            st.touch.contacts.retain(|c| c.id != sample.contact);
+            st.touch.contacts.push(pointer::Contact { id: sample.contact, target, x: sample.x, y: sample.y });
+            touch_event(st, target, control::EVENT_TOUCH_DOWN, pending);
+
+            if st.touch.contacts.len() == 2 {
+                cancel_touch_press(st, pending);
+                if let Some((mx, my)) = st.touch.midpoint() {
+                    let target = control::hit_test_any(&st.controls, win_id, mx, my, 0, 0)
+                        .filter(|&id| in_focus_scope(st, win_id, id))
+                        .or(st.touch.contacts[0].target);
+                    if let Some(target) = target {
+                        st.touch.begin_gesture(target);
+                    }
+                }
+            }
+        }
+        pointer::PHASE_UP => {
+            if let Some(i) = st.touch.contacts.iter().position(|c| c.id == sample.contact) {
+                let contact = st.touch.contacts.remove(i);
+                touch_event(st, contact.target, control::EVENT_TOUCH_UP, pending);
+            }
+            st.touch.end_gesture();
+        }
+        _ => {
+            let i = match st.touch.contacts.iter().position(|c| c.id == sample.contact) {
+                Some(i) => i,
+                None => return,
+            };
+            let contact = &mut st.touch.contacts[i];
+            contact.x = sample.x;
+            contact.y = sample.y;
+            let target = contact.target;
+            touch_event(st, target, control::EVENT_TOUCH_MOVE, pending);
+
+            if i >= 2 {
+                return;
+            }
+            if let Some(step) = st.touch.update_gesture() {
+                if let Some(scale) = step.scale {
+                    let pinch = PointerEvent {
+                        kind: pointer::POINTER_TOUCH,
+                        phase: pointer::PHASE_MOVE,
+                        x: step.mid_x,
+                        y: step.mid_y,
+                        pressure: pointer::PRESSURE_MAX,
+                        scale,
+                        ..PointerEvent::default()
+                    };
+                    // The nearest control that listens for pinches gets it.
+                    let mut cur = step.target;
+                    while let Some(idx) = control::find_idx(&st.controls, cur) {
+                        if st.controls[idx].get_event_callback(control::EVENT_PINCH).is_some() {
+                            fire_pointer_callback(&st.controls, cur, control::EVENT_PINCH, &pinch, pending);
+                            break;
+                        }
+                        let parent = st.controls[idx].parent_id();
+                        if parent == 0 || parent == cur { break; }
+                        cur = parent;
+                    }
+                }
+                if step.scroll != 0 {
+                    dispatch_scroll(st, step.target, step.scroll, pending);
+                }
+            }
+        }
+    }
+}
+
+/// Deliver the current touch sample to `target` and fire `event_type` on it.
+fn touch_event(
+    st: &mut crate::AnyuiState,
+    target: Option<ControlId>,
+    event_type: u32,
+    pending: &mut Vec<PendingCallback>,
+) {
+    deliver_pointer(st, target);
+    if let Some(id) = target {
+        fire_pointer_callback(&st.controls, id, event_type, &st.pointer, pending);
+    }
+}
+
+/// A second contact turned the touch into a gesture: release the control
+/// pressed by the first one without clicking it.
+fn cancel_touch_press(st: &mut crate::AnyuiState, pending: &mut Vec<PendingCallback>) {
+    let id = match st.pressed.take() {
+        Some(id) => id,
+        None => return,
+    };
+    if let Some(idx) = control::find_idx(&st.controls, id) {
+        let (ax, ay) = control::abs_position(&st.controls, id);
+        let first = &st.touch.contacts[0];
+        let (lx, ly) = (first.x - ax, first.y - ay);
+        st.controls[idx].handle_mouse_up(lx, ly, st.pressed_button);
+        st.controls[idx].base_mut().mark_dirty();
+        fire_event_callback(&st.controls, id, control::EVENT_MOUSE_UP, pending);
+    }
+}
+
 /// Build a cascaded tab sort key for a control: (parent_tab_index, own_tab_index, insertion_order).
 /// This ensures controls are grouped by parent tab_index first, then sorted within the group.
 fn tab_sort_key(controls: &[Box<dyn control::Control>], id: ControlId, insertion_idx: usize) -> (u32, u32, usize) {
@@ -1177,21 +1715,51 @@ fn tab_sort_key(controls: &[Box<dyn control::Control>], id: ControlId, insertion
     (parent_tab, own, insertion_idx)
 }
 
+/// Run a TextField's EVENT_VALIDATE callback right away (not deferred like
+/// other callbacks) so it can veto the edit just made. Returns false if the
+/// edit was rejected and rolled back.
+fn validate_edit(st: &mut crate::AnyuiState, id: ControlId) -> bool {
+    let slot = match control::find_idx(&st.controls, id) {
+        Some(idx) if st.controls[idx].kind() == ControlKind::TextField => {
+            st.controls[idx].get_event_callback(control::EVENT_VALIDATE)
+        }
+        _ => return true,
+    };
+    if let Some(slot) = slot {
+        (slot.cb)(id, control::EVENT_VALIDATE, slot.userdata);
+    }
+    // Re-acquire state (the callback may have modified it)
+    let st = crate::state();
+    match control::find_idx(&st.controls, id) {
+        Some(idx) => crate::controls::textfield::finish_validate(&mut st.controls[idx]),
+        None => false,
+    }
+}
+
 /// Cycle keyboard focus to the next focusable control within the window.
 /// Controls are ordered by cascaded tab_index (parent tab_index, own tab_index, insertion order).
-fn cycle_focus(
-    st: &mut crate::AnyuiState,
-    win_id: ControlId,
-    pending: &mut Vec<PendingCallback>,
-) {
-    // Collect all focusable controls that belong to this window (with insertion index for stable sort)
+/// Innermost focus scope root inside window `win_id`, if any.
+fn active_focus_scope(st: &crate::AnyuiState, win_id: ControlId) -> Option<ControlId> {
+    st.focus_scopes.iter().rev()
+        .map(|&(root, _)| root)
+        .find(|&root| control::is_within(&st.controls, root, win_id))
+}
+
+/// Whether `id` may receive input in `win_id` under the active focus scope.
+fn in_focus_scope(st: &crate::AnyuiState, win_id: ControlId, id: ControlId) -> bool {
+    active_focus_scope(st, win_id).map_or(true, |root| control::is_within(&st.controls, id, root))
+}
+
+/// Visible focusable controls under `root`, in tab order.
+pub(crate) fn focus_order(st: &crate::AnyuiState, root: ControlId) -> Vec<ControlId> {
+    // Collect all focusable controls under root (with insertion index for stable sort)
     let mut focusable: Vec<(ControlId, usize)> = Vec::new();
     for (ins_idx, c) in st.controls.iter().enumerate() {
-        if !c.accepts_focus() || c.id() == win_id || !c.base().visible { continue; }
-        // Check that this control belongs to the window
+        if !c.accepts_focus() || c.id() == root || !c.base().visible { continue; }
+        // Check that this control belongs to root
         let mut cur = c.parent_id();
         let belongs = loop {
-            if cur == win_id { break true; }
+            if cur == root { break true; }
             if cur == 0 { break false; }
             match control::find_idx(&st.controls, cur) {
                 Some(idx) => {
@@ -1205,8 +1773,6 @@ fn cycle_focus(
         if belongs { focusable.push((c.id(), ins_idx)); }
     }
 
-    if focusable.is_empty() { return; }
-
     // Sort by cascaded tab_index
     focusable.sort_by(|a, b| {
         let ka = tab_sort_key(&st.controls, a.0, a.1);
@@ -1214,7 +1780,17 @@ fn cycle_focus(
         ka.cmp(&kb)
     });
 
-    let ids: Vec<ControlId> = focusable.iter().map(|f| f.0).collect();
+    focusable.iter().map(|f| f.0).collect()
+}
+
+fn cycle_focus(
+    st: &mut crate::AnyuiState,
+    win_id: ControlId,
+    pending: &mut Vec<PendingCallback>,
+) {
+    let root = active_focus_scope(st, win_id).unwrap_or(win_id);
+    let ids = focus_order(st, root);
+    if ids.is_empty() { return; }
 
     // Find current focused index
     let cur_idx = st.focused
@@ -1238,6 +1814,7 @@ fn cycle_focus(
         st.controls[idx].handle_focus();
         st.controls[idx].base_mut().mark_dirty();
         st.focused = Some(next_id);
+        st.ensure_visible = Some(next_id);
         fire_event_callback(&st.controls, next_id, control::EVENT_FOCUS, pending);
     }
 }
@@ -1259,9 +1836,13 @@ fn is_point_in_control(
 
 
 fn clear_tracking_for(st: &mut crate::AnyuiState, id: ControlId) {
+    st.focus_scopes.retain(|&(root, _)| root != id);
     if st.focused == Some(id) { st.focused = None; }
     if st.pressed == Some(id) { st.pressed = None; }
     if st.hovered == Some(id) { st.hovered = None; }
+    st.touch.forget(id);
+    st.gestures.forget(id);
+    st.selecting.forget(id);
 
     if let Some(ctrl) = st.controls.iter().find(|c| c.id() == id) {
         let children: Vec<ControlId> = ctrl.children().to_vec();
@@ -1273,6 +1854,385 @@ fn clear_tracking_for(st: &mut crate::AnyuiState, id: ControlId) {
 
 // ── Popup dismiss ──────────────────────────────────────────────────
 
+fn as_search_field(ctrl: &mut Box<dyn Control>) -> Option<&mut crate::controls::searchfield::SearchField> {
+    if ctrl.kind() == ControlKind::SearchField {
+        let raw: *mut dyn Control = &mut **ctrl;
+        Some(unsafe { &mut *(raw as *mut crate::controls::searchfield::SearchField) })
+    } else {
+        None
+    }
+}
+
+/// Send the cursor of the pressed (else hovered) control to the compositor
+/// if it differs from the one last sent for window `wi`.
+fn update_cursor(st: &mut crate::AnyuiState, wi: usize) {
+    let shape = st.pressed.or(st.hovered)
+        .map_or(control::CURSOR_ARROW, |id| control::resolve_cursor(&st.controls, id));
+    if let Some(cw) = st.comp_windows.get_mut(wi) {
+        if cw.cursor != shape {
+            cw.cursor = shape;
+            let cmd: [u32; 5] = [0x101A, cw.window_id, shape, 0, 0]; // CMD_SET_CURSOR
+            crate::syscall::evt_chan_emit(st.channel_id, &cmd);
+        }
+    }
+}
+
+/// Re-evaluate the cursor after control `id` changed its cursor shape.
+pub(crate) fn refresh_cursor_for(st: &mut crate::AnyuiState, id: ControlId) {
+    if let Some(wi) = window_index_of(st, id) {
+        update_cursor(st, wi);
+    }
+}
+
+/// Index into st.windows of the window containing control `id`.
+pub(crate) fn window_index_of(st: &crate::AnyuiState, id: ControlId) -> Option<usize> {
+    let mut cur = id;
+    loop {
+        let idx = control::find_idx(&st.controls, cur)?;
+        let parent = st.controls[idx].parent_id();
+        if parent == 0 || parent == cur {
+            return st.windows.iter().position(|&w| w == cur);
+        }
+        cur = parent;
+    }
+}
+
+/// Open, update or close the suggestion popup of the focused SearchField.
+/// The field flags changes via `popup_dirty`; a popup whose field lost focus
+/// is closed unconditionally.
+fn sync_search_popup(st: &mut crate::AnyuiState) {
+    if let Some(owner) = st.popup.as_ref().and_then(|p| p.owner_search) {
+        if st.focused != Some(owner) {
+            dismiss_popup(st);
+        }
+    }
+
+    let focus_id = match st.focused {
+        Some(id) => id,
+        None => return,
+    };
+    let (items, hover) = match control::find_idx(&st.controls, focus_id)
+        .and_then(|i| as_search_field(&mut st.controls[i]))
+    {
+        Some(sf) if sf.popup_dirty => {
+            sf.popup_dirty = false;
+            (sf.popup_items(), sf.suggest_index)
+        }
+        _ => return,
+    };
+
+    let ours = st.popup.as_ref().map_or(false, |p| p.owner_search == Some(focus_id));
+    let items = match items {
+        Some(items) => items,
+        None => {
+            if ours { dismiss_popup(st); }
+            return;
+        }
+    };
+
+    // Reuse the open popup if it already shows the same entries.
+    let current_menu = st.popup.as_ref().filter(|_| ours).map(|p| p.menu_id);
+    let same = current_menu
+        .and_then(|mid| control::find_idx(&st.controls, mid))
+        .and_then(|mi| st.controls[mi].text_base().map(|tb| tb.text == items))
+        .unwrap_or(false);
+    let menu_id = if same {
+        current_menu
+    } else if ours || st.popup.is_none() {
+        dismiss_popup(st);
+        match window_index_of(st, focus_id) {
+            Some(wi) => open_anchored_menu(st, &items, focus_id, wi, None, Some(focus_id)),
+            None => None,
+        }
+    } else {
+        // Another popup (context menu, DropDown) is showing.
+        None
+    };
+
+    if let Some(mid) = menu_id {
+        if let Some(mi) = control::find_idx(&st.controls, mid) {
+            if st.controls[mi].kind() == ControlKind::ContextMenu {
+                let raw: *mut dyn Control = &mut *st.controls[mi];
+                let menu = unsafe { &mut *(raw as *mut crate::controls::context_menu::ContextMenu) };
+                menu.set_hovered_item(if hover >= 0 { hover as u32 } else { u32::MAX });
+            }
+        }
+        if let Some(ref mut p) = st.popup {
+            p.dirty = true;
+        }
+    }
+}
+
+/// Open a popup window showing `items` (pipe-separated) as a menu directly
+/// below control `anchor_id` of window `wi`, or above it if there is no room.
+/// The menu is a temporary ContextMenu control, removed by `dismiss_popup`.
+/// Returns the menu control ID.
+fn open_anchored_menu(
+    st: &mut crate::AnyuiState,
+    items: &[u8],
+    anchor_id: ControlId,
+    wi: usize,
+    owner_dropdown: Option<ControlId>,
+    owner_search: Option<ControlId>,
+) -> Option<ControlId> {
+    let anchor_idx = control::find_idx(&st.controls, anchor_id)?;
+    let anchor_w = st.controls[anchor_idx].base().w;
+    let anchor_h = st.controls[anchor_idx].base().h;
+    let anchor_abs = control::abs_position(&st.controls, anchor_id);
+    let comp_window_id = st.comp_windows.get(wi)?.window_id;
+    // The popup is sized and positioned with the owner window's zoom.
+    crate::theme::set_window_zoom(st.comp_windows[wi].effective_zoom());
+
+    // Create a temporary ContextMenu control
+    let menu_id = st.next_id;
+    st.next_id += 1;
+    let menu_ctrl = crate::controls::create_control(
+        ControlKind::ContextMenu, menu_id, 0, 0, 0, 0, 0, items,
+    );
+    st.controls.push(menu_ctrl);
+
+    // Force menu width to match the anchor width (min)
+    let mi = control::find_idx(&st.controls, menu_id)?;
+    let menu_w = st.controls[mi].base().w.max(anchor_w);
+    st.controls[mi].base_mut().w = menu_w;
+    let menu_h = st.controls[mi].base().h;
+
+    // Shadow margin (logical pixels)
+    let margin: i32 = 16;
+    let popup_w = menu_w + (margin as u32) * 2;
+    let popup_h = menu_h + (margin as u32) * 2;
+
+    // Physical popup dimensions for SHM surface
+    let phys_popup_w = crate::theme::scale(popup_w);
+    let phys_popup_h = crate::theme::scale(popup_h);
+
+    // Position popup below the anchor (physical coords).
+    // anchor_abs is logical — scale for compositor screen placement.
+    let (content_x, content_y) = compositor::get_window_position(
+        st.channel_id, st.sub_id, comp_window_id,
+    );
+    let phys_anchor_x = crate::theme::scale_i32(anchor_abs.0);
+    let phys_anchor_y = crate::theme::scale_i32(anchor_abs.1);
+    let phys_anchor_h = crate::theme::scale(anchor_h);
+    let phys_margin = crate::theme::scale_i32(margin);
+    let phys_menu_h = crate::theme::scale(menu_h);
+    let mut popup_x = content_x + phys_anchor_x - phys_margin;
+    let mut popup_y = content_y + phys_anchor_y + phys_anchor_h as i32 - phys_margin;
+
+    // Clamp to screen bounds (physical)
+    let (scr_w, scr_h) = compositor::screen_size();
+    if popup_x + phys_popup_w as i32 > scr_w as i32 {
+        popup_x = scr_w as i32 - phys_popup_w as i32;
+    }
+    if popup_y + phys_popup_h as i32 > scr_h as i32 {
+        // Open upward if no room below
+        popup_y = content_y + phys_anchor_y - phys_menu_h as i32 - phys_margin;
+    }
+    if popup_x < 0 { popup_x = 0; }
+    if popup_y < 0 { popup_y = 0; }
+
+    // Create popup compositor window (physical dimensions)
+    let popup_flags: u32 = 0x01 | 0x02 | 0x04 | 0x100;
+    match compositor::create_window(
+        st.channel_id, st.sub_id,
+        popup_x, popup_y,
+        phys_popup_w, phys_popup_h,
+        popup_flags,
+    ) {
+        Some((popup_win_id, shm_id, surface)) => {
+            st.controls[mi].set_position(0, 0);
+            st.controls[mi].base_mut().visible = false;
+
+            // Back buffer at physical dimensions.
+            let back_buffer = alloc::vec![0u32; (phys_popup_w * phys_popup_h) as usize];
+            st.popup = Some(crate::PopupInfo {
+                window_id: popup_win_id,
+                shm_id,
+                surface,
+                width: phys_popup_w,
+                height: phys_popup_h,
+                back_buffer,
+                menu_id,
+                owner_win_idx: wi,
+                margin,  // logical — used for hit-testing and render offset
+                dirty: true,
+                owner_dropdown,
+                owner_search,
+                owner_spell: None,
+                spell_word: Vec::new(),
+            });
+            Some(menu_id)
+        }
+        None => {
+            st.controls.retain(|c| c.id() != menu_id);
+            None
+        }
+    }
+}
+
+/// Open a popup window showing the existing ContextMenu control `menu_id`
+/// at logical point (mx, my) of window `wi`, clamped to the screen.
+/// `owner_spell` is set for spell-check menus (control ID + misspelled word).
+/// Returns false if the compositor window could not be created.
+fn open_menu_at(
+    st: &mut crate::AnyuiState,
+    menu_id: ControlId,
+    wi: usize,
+    mx: i32,
+    my: i32,
+    owner_spell: Option<(ControlId, Vec<u8>)>,
+) -> bool {
+    let mi = match control::find_idx(&st.controls, menu_id) {
+        Some(i) => i,
+        None => return false,
+    };
+    let comp_window_id = match st.comp_windows.get(wi) {
+        Some(cw) => cw.window_id,
+        None => return false,
+    };
+    // The popup is sized and positioned with the owner window's zoom.
+    crate::theme::set_window_zoom(st.comp_windows[wi].effective_zoom());
+
+    // Get menu dimensions (logical)
+    let menu_w = st.controls[mi].base().w;
+    let menu_h = st.controls[mi].base().h;
+
+    // Shadow margin (logical pixels)
+    let margin: i32 = 16;
+    let popup_w = menu_w + (margin as u32) * 2;
+    let popup_h = menu_h + (margin as u32) * 2;
+
+    // Physical popup dimensions for SHM surface
+    let phys_popup_w = crate::theme::scale(popup_w);
+    let phys_popup_h = crate::theme::scale(popup_h);
+
+    // Get parent window's content-area screen position (physical)
+    let (content_x, content_y) = compositor::get_window_position(
+        st.channel_id, st.sub_id, comp_window_id,
+    );
+
+    // Calculate popup screen position (physical coords).
+    // mx/my are logical — scale to physical for screen placement.
+    let phys_mx = crate::theme::scale_i32(mx);
+    let phys_my = crate::theme::scale_i32(my);
+    let phys_margin = crate::theme::scale_i32(margin);
+    let mut popup_x = content_x + phys_mx - phys_margin;
+    let mut popup_y = content_y + phys_my - phys_margin;
+
+    // Clamp to screen bounds (physical)
+    let (scr_w, scr_h) = compositor::screen_size();
+    if popup_x + phys_popup_w as i32 > scr_w as i32 {
+        popup_x = scr_w as i32 - phys_popup_w as i32;
+    }
+    if popup_y + phys_popup_h as i32 > scr_h as i32 {
+        popup_y = scr_h as i32 - phys_popup_h as i32;
+    }
+    if popup_x < 0 { popup_x = 0; }
+    if popup_y < 0 { popup_y = 0; }
+
+    // Create popup compositor window (borderless, always-on-top, immovable)
+    // Flags: BORDERLESS=0x01 | NOT_RESIZABLE=0x02 | ALWAYS_ON_TOP=0x04 | NO_MOVE=0x100
+    let popup_flags: u32 = 0x01 | 0x02 | 0x04 | 0x100;
+    match compositor::create_window(
+        st.channel_id, st.sub_id,
+        popup_x, popup_y,
+        phys_popup_w, phys_popup_h,
+        popup_flags,
+    ) {
+        Some((popup_win_id, shm_id, surface)) => {
+            // Position menu at origin for clean popup rendering
+            st.controls[mi].set_position(0, 0);
+            // Menu stays invisible in parent (rendered directly in popup)
+            st.controls[mi].base_mut().visible = false;
+
+            // Back buffer at physical dimensions.
+            let back_buffer = alloc::vec![0u32; (phys_popup_w * phys_popup_h) as usize];
+            let (owner_spell, spell_word) = match owner_spell {
+                Some((id, word)) => (Some(id), word),
+                None => (None, Vec::new()),
+            };
+            st.popup = Some(crate::PopupInfo {
+                window_id: popup_win_id,
+                shm_id,
+                surface,
+                width: phys_popup_w,
+                height: phys_popup_h,
+                back_buffer,
+                menu_id,
+                owner_win_idx: wi,
+                margin,  // logical — used for hit-testing and render offset
+                dirty: true,
+                owner_dropdown: None,
+                owner_search: None,
+                owner_spell,
+                spell_word,
+            });
+            true
+        }
+        None => false,
+    }
+}
+
+/// Open the spell-check menu for `word` (a misspelled word in text control
+/// `owner_id`) at logical point (mx, my). The menu is a temporary
+/// ContextMenu control, removed by `dismiss_popup`.
+fn open_spell_menu(
+    st: &mut crate::AnyuiState,
+    word: Vec<u8>,
+    owner_id: ControlId,
+    wi: usize,
+    mx: i32,
+    my: i32,
+) {
+    let items = crate::spell::menu_items(&crate::spell::suggestions(&word));
+    let menu_id = st.next_id;
+    st.next_id += 1;
+    let menu_ctrl = crate::controls::create_control(
+        ControlKind::ContextMenu, menu_id, 0, 0, 0, 0, 0, &items,
+    );
+    st.controls.push(menu_ctrl);
+    if !open_menu_at(st, menu_id, wi, mx, my, Some((owner_id, word))) {
+        st.controls.retain(|c| c.id() != menu_id);
+    }
+}
+
+/// Apply the spell-check menu entry `index` chosen for `word` in control
+/// `owner_id`: replace the word with a suggestion, or ignore it / add it
+/// to the dictionary and fire the matching event.
+fn apply_spell_choice(
+    st: &mut crate::AnyuiState,
+    owner_id: ControlId,
+    word: &[u8],
+    items: &[u8],
+    index: usize,
+    pending_cbs: &mut Vec<PendingCallback>,
+) {
+    let entries: Vec<&[u8]> = items.split(|&b| b == b'|').collect();
+    let n = entries.len();
+    // Layout: suggestions..., "-", Ignore, Add to Dictionary
+    if n < 3 || index >= n {
+        return;
+    }
+    if index >= n - 2 {
+        crate::spell::ignore(word);
+        let event = if index == n - 2 { control::EVENT_SPELL_IGNORE } else { control::EVENT_SPELL_ADD };
+        fire_event_callback(&st.controls, owner_id, event, pending_cbs);
+        // The word is accepted everywhere now — drop stale underlines.
+        for c in st.controls.iter_mut() {
+            if matches!(c.kind(), ControlKind::TextField | ControlKind::TextEditor) {
+                c.base_mut().mark_dirty();
+            }
+        }
+    } else if entries[index] != crate::spell::NO_SUGGESTIONS {
+        let replacement = entries[index].to_vec();
+        let changed = control::find_idx(&st.controls, owner_id)
+            .map_or(false, |i| st.controls[i].replace_spell_word(&replacement));
+        if changed {
+            fire_event_callback(&st.controls, owner_id, control::EVENT_CHANGE, pending_cbs);
+        }
+    }
+}
+
 /// Dismiss the active context menu popup window.
 /// Destroys the compositor window and clears the popup state.
 fn dismiss_popup(st: &mut crate::AnyuiState) {
@@ -1285,7 +2245,9 @@ fn dismiss_popup(st: &mut crate::AnyuiState) {
                 dd.open = false;
                 dd.text_base.base.mark_dirty();
             }
-            // Remove the temporary ContextMenu control we created
+        }
+        // Remove the temporary ContextMenu control we created
+        if popup.owner_dropdown.is_some() || popup.owner_search.is_some() || popup.owner_spell.is_some() {
             st.controls.retain(|c| c.id() != popup.menu_id);
         }
         compositor::destroy_window(st.channel_id, popup.window_id, popup.shm_id);
@@ -1303,6 +2265,7 @@ fn clear_dirty(controls: &mut [Box<dyn Control>], id: ControlId) {
     };
     let b = controls[idx].base_mut();
     b.dirty = false;
+    b.dirty_rect = None;
     b.prev_x = b.x;
     b.prev_y = b.y;
     b.prev_w = b.w;
@@ -1377,8 +2340,11 @@ fn collect_dirty_rects(
             return; // No need to recurse — full render
         }
 
-        // Union current bounds with dirty rect
-        cw.dirty_rect = Some(union_rect(cw.dirty_rect, abs_x, abs_y, b.w, b.h));
+        // Union current bounds (or just the invalidated part) with dirty rect
+        cw.dirty_rect = Some(match b.dirty_rect {
+            Some((rx, ry, rw, rh)) => union_rect(cw.dirty_rect, abs_x + rx, abs_y + ry, rw, rh),
+            None => union_rect(cw.dirty_rect, abs_x, abs_y, b.w, b.h),
+        });
 
         // If position or size changed, also union the old bounds to repaint the vacated area.
         if b.prev_x != b.x || b.prev_y != b.y || b.prev_w != b.w || b.prev_h != b.h {
@@ -1415,6 +2381,7 @@ fn render_tree(
     parent_abs_x: i32,
     parent_abs_y: i32,
     dirty_rect: Option<(i32, i32, u32, u32)>,
+    drawn: &mut u32,
 ) {
     let idx = match control::find_idx(controls, id) {
         Some(i) => i,
@@ -1443,12 +2410,18 @@ fn render_tree(
     // so it isn't painted over by content.
     if controls[idx].kind() != ControlKind::ScrollView {
         controls[idx].render(surface, parent_abs_x, parent_abs_y);
+        run_custom_draw(&*controls[idx], surface, parent_abs_x, parent_abs_y);
     }
+    // Selected items are highlighted behind their own rendering.
+    if let Some(sel) = &controls[idx].base().selection {
+        sel.render_items(controls, surface, abs_x, abs_y);
+    }
+    *drawn += 1;
 
     let child_abs_x = abs_x;
     let child_abs_y = abs_y;
 
-    let children: Vec<u32> = controls[idx].children().to_vec();
+    let children = control::paint_order(controls, controls[idx].children());
     // Skip children if this is a collapsed Expander
     if controls[idx].kind() == ControlKind::Expander && controls[idx].base().state == 0 {
         return;
@@ -1471,11 +2444,19 @@ fn render_tree(
                 Some((sv_y, sv_h as i32)),
             )
         }
-        ControlKind::Expander => (
-            child_abs_y + crate::controls::expander::HEADER_HEIGHT as i32,
-            *surface,
-            None,
-        ),
+        ControlKind::Expander => {
+            let body_y = child_abs_y + crate::controls::expander::HEADER_HEIGHT as i32;
+            // Mid-animation the body is taller than the expander — clip it.
+            let child_surface = if crate::controls::expander::clips_children(&controls[idx]) {
+                let b = controls[idx].base();
+                let body_h = b.h.saturating_sub(crate::controls::expander::HEADER_HEIGHT);
+                let p = crate::draw::scale_bounds(0, 0, parent_abs_x + b.x, body_y, b.w, body_h);
+                surface.with_clip(p.x, p.y, p.w, p.h)
+            } else {
+                *surface
+            };
+            (body_y, child_surface, None)
+        }
         _ => (child_abs_y, *surface, None),
     };
     for &cid in &children {
@@ -1492,19 +2473,205 @@ fn render_tree(
                 }
             }
         }
-        render_tree(controls, cid, &child_surface, child_abs_x, child_abs_y, dirty_rect);
+        render_tree(controls, cid, &child_surface, child_abs_x, child_abs_y, dirty_rect, drawn);
     }
 
     // ScrollView: render scrollbar AFTER children so it isn't painted over.
     if controls[idx].kind() == ControlKind::ScrollView {
         controls[idx].render(surface, parent_abs_x, parent_abs_y);
+        run_custom_draw(&*controls[idx], surface, parent_abs_x, parent_abs_y);
+    }
+
+    // The rubber band goes over the items it selects.
+    if let Some(sel) = &controls[idx].base().selection {
+        sel.render_band(surface, abs_x, abs_y);
+    }
+
+    // Badge overlay goes on top of the control and its children.
+    if let Some(badge) = &controls[idx].base().badge {
+        badge.render(surface, abs_x, abs_y, cw, ch);
+    }
+    if let Some(v) = &controls[idx].base().validation {
+        v.render(surface, abs_x, abs_y, cw, ch);
+    }
+}
+
+/// Send the position and visible part of every NativeSurface of window
+/// `wi` to the compositor (see [`crate::controls::native_surface`]).
+fn place_native_surfaces(st: &mut crate::AnyuiState, wi: usize) {
+    let comp_window_id = st.comp_windows[wi].window_id;
+    for i in 0..st.controls.len() {
+        if st.controls[i].kind() != ControlKind::NativeSurface {
+            continue;
+        }
+        let id = st.controls[i].id();
+        let (ax, ay) = control::abs_position(&st.controls, id);
+        let origin = crate::draw::scale_bounds(0, 0, ax, ay, 0, 0);
+        let clip = visible_rect(&st.controls, id).map(|(x, y, w, h)| {
+            let p = crate::draw::scale_bounds(0, 0, x, y, w, h);
+            (p.x, p.y, p.w, p.h)
+        });
+        let raw: *mut dyn Control = &mut *st.controls[i];
+        let ns = unsafe { &mut *(raw as *mut crate::controls::native_surface::NativeSurface) };
+        if ns.window_id == comp_window_id {
+            ns.place(origin.x, origin.y, clip);
+        }
+    }
+}
+
+/// Visible part of control `id` in logical window coordinates: its bounds
+/// clipped by enclosing ScrollViews and animating Expanders. `None` if it
+/// or an ancestor is hidden, inside a collapsed Expander or scrolled out
+/// of view.
+pub(crate) fn visible_rect(controls: &[Box<dyn Control>], id: ControlId) -> Option<(i32, i32, u32, u32)> {
+    let mut cur = control::find_idx(controls, id)?;
+    let (ax, ay) = control::abs_position(controls, id);
+    let (w, h) = controls[cur].size();
+    let (mut x0, mut y0) = (ax, ay);
+    let (mut x1, mut y1) = (ax + w as i32, ay + h as i32);
+    loop {
+        if !controls[cur].visible() {
+            return None;
+        }
+        let parent = controls[cur].parent_id();
+        if parent == 0 || parent == controls[cur].id() {
+            break;
+        }
+        let pi = control::find_idx(controls, parent)?;
+        let p = &controls[pi];
+        let (px, py) = control::abs_position(controls, parent);
+        let (pw, ph) = p.size();
+        let clip = match p.kind() {
+            ControlKind::ScrollView => Some((py, ph)),
+            ControlKind::Expander if p.base().state == 0 => return None,
+            ControlKind::Expander if crate::controls::expander::clips_children(p) => {
+                let header = crate::controls::expander::HEADER_HEIGHT;
+                Some((py + header as i32, ph.saturating_sub(header)))
+            }
+            _ => None,
+        };
+        if let Some((top, height)) = clip {
+            x0 = x0.max(px);
+            x1 = x1.min(px + pw as i32);
+            y0 = y0.max(top);
+            y1 = y1.min(top + height as i32);
+        }
+        cur = pi;
+    }
+    if x1 > x0 && y1 > y0 {
+        Some((x0, y0, (x1 - x0) as u32, (y1 - y0) as u32))
+    } else {
+        None
+    }
+}
+
+/// Call the control's owner-draw callback, if any, with the visible part of
+/// its bounds (clipped to `surface`).
+fn run_custom_draw(ctrl: &dyn Control, surface: &crate::draw::Surface, parent_abs_x: i32, parent_abs_y: i32) {
+    let b = ctrl.base();
+    let Some(cd) = b.custom_draw else { return };
+    let p = crate::draw::scale_bounds(parent_abs_x, parent_abs_y, b.x, b.y, b.w, b.h);
+    let x0 = p.x.max(surface.clip_x).max(0);
+    let y0 = p.y.max(surface.clip_y).max(0);
+    let x1 = (p.x + p.w as i32)
+        .min(surface.clip_x + surface.clip_w as i32)
+        .min(surface.width as i32);
+    let y1 = (p.y + p.h as i32)
+        .min(surface.clip_y + surface.clip_h as i32)
+        .min(surface.height as i32);
+    if x1 <= x0 || y1 <= y0 {
+        return;
+    }
+    let mut flags = 0;
+    if b.hovered { flags |= control::DRAW_HOVERED; }
+    if crate::state().pressed == Some(b.id) { flags |= control::DRAW_PRESSED; }
+    if b.focused { flags |= control::DRAW_FOCUSED; }
+    if b.disabled { flags |= control::DRAW_DISABLED; }
+    let info = control::CustomDrawInfo {
+        pixels: unsafe { surface.pixels.add(y0 as usize * surface.width as usize + x0 as usize) },
+        width: (x1 - x0) as u32,
+        height: (y1 - y0) as u32,
+        stride: surface.width,
+        offset_x: (x0 - p.x) as u32,
+        offset_y: (y0 - p.y) as u32,
+        control_width: p.w,
+        control_height: p.h,
+        scale: crate::theme::scale_factor(),
+        flags,
+        state: ctrl.state_val(),
+        colors: crate::theme::colors_ptr(),
+    };
+    (cd.cb)(b.id, &info, cd.userdata);
+}
+
+/// Render the subtree rooted at `id` into a fresh buffer at the control's
+/// own size (physical pixels), independent of any window. Pixels the subtree
+/// does not paint stay transparent (0). Returns `(width, height, pixels)`,
+/// or `None` if the control doesn't exist or has an empty size.
+pub(crate) fn render_offscreen(controls: &[Box<dyn Control>], id: ControlId) -> Option<(u32, u32, Vec<u32>)> {
+    let idx = control::find_idx(controls, id)?;
+    let (x, y) = controls[idx].position();
+    let (w, h) = controls[idx].size();
+    let pw = crate::theme::scale(w);
+    let ph = crate::theme::scale(h);
+    if pw == 0 || ph == 0 {
+        return None;
     }
+    let mut pixels = alloc::vec![0u32; (pw as usize) * (ph as usize)];
+    let surf = crate::draw::Surface::new(pixels.as_mut_ptr(), pw, ph);
+    // Offset by the control's own position so its top-left lands at (0, 0).
+    let mut drawn = 0u32;
+    render_tree(controls, id, &surf, -x, -y, None, &mut drawn);
+    Some((pw, ph, pixels))
 }
 
 // ── Theme-change repaint helper ─────────────────────────────────────
 
+/// Mark every window's control tree dirty (palette or font changes).
+/// Apply the popup owner's zoom so the menu is rendered and hit-tested at
+/// the same scale it was opened with.
+fn set_popup_zoom(st: &crate::AnyuiState) {
+    let zoom = st.popup.as_ref()
+        .and_then(|p| st.comp_windows.get(p.owner_win_idx))
+        .map_or(100, |cw| cw.effective_zoom());
+    crate::theme::set_window_zoom(zoom);
+}
+
+/// Re-derive window `wi`'s logical size after its zoom changed.
+///
+/// The on-screen (physical) size stays the same; the control tree gets the
+/// smaller logical size that fits at the new zoom and is laid out again.
+pub(crate) fn apply_window_zoom(st: &mut crate::AnyuiState, wi: usize) {
+    let Some(cw) = st.comp_windows.get_mut(wi) else { return };
+    crate::theme::set_window_zoom(cw.effective_zoom());
+    let logical_w = crate::theme::unscale_u32(cw.width);
+    let logical_h = crate::theme::unscale_u32(cw.height);
+    crate::theme::set_window_zoom(100);
+    if logical_w == cw.logical_width && logical_h == cw.logical_height {
+        return;
+    }
+    cw.logical_width = logical_w;
+    cw.logical_height = logical_h;
+    cw.dirty = true;
+    cw.dirty_rect = None;
+    let win_id = st.windows[wi];
+    if let Some(idx) = control::find_idx(&st.controls, win_id) {
+        st.controls[idx].set_size(logical_w, logical_h);
+        mark_tree_dirty(&mut st.controls, idx);
+    }
+    st.needs_layout = true;
+}
+
+pub(crate) fn mark_all_windows_dirty(st: &mut crate::AnyuiState) {
+    for &win_id in &st.windows {
+        if let Some(idx) = crate::control::find_idx(&st.controls, win_id) {
+            mark_tree_dirty(&mut st.controls, idx);
+        }
+    }
+}
+
 /// Recursively mark a control and all its descendants as dirty.
-fn mark_tree_dirty(controls: &mut [Box<dyn Control>], idx: usize) {
+pub(crate) fn mark_tree_dirty(controls: &mut [Box<dyn Control>], idx: usize) {
     controls[idx].base_mut().mark_dirty();
     let children: Vec<u32> = controls[idx].children().to_vec();
     for &cid in &children {
@@ -1516,7 +2683,12 @@ fn mark_tree_dirty(controls: &mut [Box<dyn Control>], idx: usize) {
 
 // ── Subtree removal ─────────────────────────────────────────────────
 
-fn remove_subtree(controls: &mut Vec<Box<dyn Control>>, id: ControlId) {
+/// Remove control `id` and its descendants. Returns their tag destructor
+/// calls, to be run once no state borrow is held.
+fn remove_subtree(
+    controls: &mut Vec<Box<dyn Control>>,
+    id: ControlId,
+) -> Vec<(crate::tag::TagDestructor, ControlId, u64)> {
     let mut to_remove = Vec::new();
     collect_descendants(controls, id, &mut to_remove);
     to_remove.push(id);
@@ -1528,7 +2700,9 @@ fn remove_subtree(controls: &mut Vec<Box<dyn Control>>, id: ControlId) {
         }
     }
 
+    let drops = crate::tag::take_destructors(controls, &to_remove);
     controls.retain(|c| !to_remove.contains(&c.id()));
+    drops
 }
 
 fn collect_descendants(
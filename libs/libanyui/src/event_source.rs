@@ -0,0 +1,125 @@
+//! Event sources — application event channels serviced by the UI event loop.
+//!
+//! An app that also listens on IPC / network channels registers each channel
+//! with `anyui_add_event_source()`. The event loop then blocks on the
+//! compositor channel and all source channels at once (`evt_chan_wait_any`),
+//! and dispatches every event that arrives on a source to its callback on the
+//! UI thread, instead of the app busy-polling between `run_once()` calls.
+//!
+//! The callback receives `(source_id, event_type, userdata)`, where
+//! `event_type` is the first event word; the full five-word event is
+//! available during the callback via `anyui_event_source_data()`.
+//!
+//! `anyui_wakeup()` may be called from any thread: it posts an empty event to
+//! the app's own compositor subscription, which ends the current wait.
+//!
+//! # Usage (via client API)
+//! ```ignore
+//! let id = ui::add_event_source(chan, |ev| { /* runs on UI thread */ });
+//! ui::wakeup(); // from a worker thread
+//! ui::remove_event_source(id);
+//! ```
+
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU32, Ordering};
+use crate::control::Callback;
+
+/// Maximum number of event sources (the kernel waits on at most 16
+/// subscriptions, one of which is the compositor channel).
+pub const MAX_SOURCES: usize = 15;
+
+/// Events dispatched per source per frame, so a flooding channel cannot
+/// starve input handling and rendering.
+const MAX_EVENTS_PER_FRAME: usize = 64;
+
+/// One registered channel subscription.
+pub struct EventSource {
+    pub id: u32,
+    pub channel_id: u32,
+    pub sub_id: u32,
+    pub callback: Callback,
+    pub userdata: u64,
+}
+
+/// Event source storage, owned by AnyuiState.
+pub struct EventSourceState {
+    pub sources: Vec<EventSource>,
+    next_id: u32,
+    /// Event currently being dispatched (valid during a source callback).
+    pub current: [u32; 5],
+}
+
+impl EventSourceState {
+    pub fn new() -> Self {
+        Self {
+            sources: Vec::new(),
+            next_id: 1,
+            current: [0; 5],
+        }
+    }
+
+    /// Subscribe to `channel_id` and register the callback.
+    /// Returns the source ID (>0), or 0 if the source limit is reached.
+    pub fn add(&mut self, channel_id: u32, cb: Callback, userdata: u64) -> u32 {
+        if self.sources.len() >= MAX_SOURCES {
+            return 0;
+        }
+        let sub_id = crate::syscall::evt_chan_subscribe(channel_id, 0);
+        let id = self.next_id;
+        self.next_id += 1;
+        self.sources.push(EventSource { id, channel_id, sub_id, callback: cb, userdata });
+        id
+    }
+
+    /// Unsubscribe and remove a source by ID. No-op if not found.
+    pub fn remove(&mut self, source_id: u32) {
+        if let Some(pos) = self.sources.iter().position(|s| s.id == source_id) {
+            let s = self.sources.remove(pos);
+            crate::syscall::evt_chan_unsubscribe(s.channel_id, s.sub_id);
+        }
+    }
+}
+
+/// Drain pending events from all sources and invoke their callbacks.
+/// Called from `run_once()`; callbacks may add or remove sources.
+pub fn dispatch() {
+    let mut events: Vec<(u32, Callback, u64, [u32; 5])> = Vec::new();
+    for s in &crate::state().event_sources.sources {
+        let mut ev = [0u32; 5];
+        let mut n = 0;
+        while n < MAX_EVENTS_PER_FRAME && crate::syscall::evt_chan_poll(s.channel_id, s.sub_id, &mut ev) {
+            events.push((s.id, s.callback, s.userdata, ev));
+            ev = [0u32; 5];
+            n += 1;
+        }
+    }
+    for (id, cb, userdata, ev) in events {
+        // Skip sources removed by an earlier callback in this batch.
+        if !crate::state().event_sources.sources.iter().any(|s| s.id == id) {
+            continue;
+        }
+        crate::state().event_sources.current = ev;
+        cb(id, ev[0], userdata);
+    }
+}
+
+// ── Cross-thread wakeup ──────────────────────────────────────────────
+
+static WAKE_CHANNEL: AtomicU32 = AtomicU32::new(0);
+static WAKE_SUB: AtomicU32 = AtomicU32::new(0);
+
+/// Record the subscription `wakeup()` posts to (the compositor channel).
+pub fn set_wake_target(channel_id: u32, sub_id: u32) {
+    WAKE_SUB.store(sub_id, Ordering::Relaxed);
+    WAKE_CHANNEL.store(channel_id, Ordering::Release);
+}
+
+/// End the event loop's current wait. Safe to call from any thread.
+/// The posted event has type 0, which the dispatcher ignores.
+pub fn wakeup() {
+    let channel_id = WAKE_CHANNEL.load(Ordering::Acquire);
+    if channel_id != 0 {
+        let ev = [0u32; 5];
+        crate::syscall::evt_chan_emit_to(channel_id, WAKE_SUB.load(Ordering::Relaxed), &ev);
+    }
+}
@@ -0,0 +1,185 @@
+//! Frame statistics — per-frame timing and render counters for diagnosing jank.
+//!
+//! `run_once()` measures every frame that renders at least one window:
+//! layout time, render time (control tree + SHM copy), how long the previous
+//! present waited for the compositor's frame ACK, the dirty area and the
+//! number of controls drawn. The latest frame is returned by
+//! `anyui_get_frame_stats()`.
+//!
+//! A frame budget callback fires after any frame whose layout + render time
+//! exceeds the budget, receiving `(frame_ms, frame_number, userdata)`.
+//! The optional overlay draws the previous frame's numbers into the top-left
+//! corner of each window.
+//!
+//! Times come from `uptime_ms()` and therefore have millisecond resolution.
+//!
+//! # Usage (via client API)
+//! ```ignore
+//! ui::set_stats_overlay(true);
+//! ui::on_frame_over_budget(16, |ms, frame| { /* frame took `ms` */ });
+//! let s = ui::frame_stats();
+//! ```
+
+use crate::control::Callback;
+
+/// Number of u32 words written by `anyui_get_frame_stats()`.
+pub const STATS_WORDS: usize = 8;
+
+/// Overlay box size in logical pixels.
+const OVERLAY_W: u32 = 168;
+const OVERLAY_H: u32 = 62;
+const OVERLAY_FONT_SIZE: u16 = 11;
+
+/// Statistics for one rendered frame.
+#[derive(Clone, Copy, Default)]
+pub struct FrameStats {
+    /// Frame counter (1 = first rendered frame).
+    pub frame: u32,
+    /// Time spent in layout (0 when layout was skipped).
+    pub layout_ms: u32,
+    /// Time spent rendering control trees and copying to SHM.
+    pub render_ms: u32,
+    /// Present → frame ACK latency of the most recently acknowledged frame.
+    pub present_wait_ms: u32,
+    /// Physical pixels repainted, summed over all rendered windows.
+    pub dirty_area: u32,
+    /// Controls whose render() was called.
+    pub controls_drawn: u32,
+    /// Layout + render time (compared against the budget).
+    pub frame_ms: u32,
+    /// Frames that exceeded the budget since the budget was set.
+    pub over_budget: u32,
+}
+
+impl FrameStats {
+    /// Flatten in the word order documented for `anyui_get_frame_stats()`.
+    pub fn to_words(&self) -> [u32; STATS_WORDS] {
+        [
+            self.frame, self.layout_ms, self.render_ms, self.present_wait_ms,
+            self.dirty_area, self.controls_drawn, self.frame_ms, self.over_budget,
+        ]
+    }
+}
+
+/// Frame statistics storage, owned by AnyuiState.
+pub struct FrameStatsState {
+    /// Most recently completed frame.
+    pub last: FrameStats,
+    /// Frame currently being measured by `run_once()`.
+    pub current: FrameStats,
+    /// Latest present → ACK latency, carried into the next frame.
+    pub present_wait_ms: u32,
+    pub overlay: bool,
+    /// Budget in milliseconds (0 = no budget).
+    pub budget_ms: u32,
+    pub on_over_budget: Option<(Callback, u64)>,
+    frames: u32,
+    over_budget: u32,
+}
+
+impl FrameStatsState {
+    pub fn new() -> Self {
+        Self {
+            last: FrameStats::default(),
+            current: FrameStats::default(),
+            present_wait_ms: 0,
+            overlay: false,
+            budget_ms: 0,
+            on_over_budget: None,
+            frames: 0,
+            over_budget: 0,
+        }
+    }
+
+    /// Start measuring a new frame.
+    pub fn begin(&mut self) {
+        self.current = FrameStats::default();
+    }
+
+    /// Set or clear the frame budget and its callback; resets the over-budget count.
+    pub fn set_budget(&mut self, budget_ms: u32, cb: Option<(Callback, u64)>) {
+        self.budget_ms = budget_ms;
+        self.on_over_budget = cb;
+        self.over_budget = 0;
+    }
+
+    /// Publish the current frame if anything was rendered.
+    /// Returns the budget callback to invoke when the frame was over budget.
+    pub fn finish(&mut self, rendered: bool) -> Option<(Callback, u32, u32, u64)> {
+        if !rendered {
+            return None;
+        }
+        self.frames = self.frames.wrapping_add(1);
+        let mut s = self.current;
+        s.frame = self.frames;
+        s.present_wait_ms = self.present_wait_ms;
+        s.frame_ms = s.layout_ms + s.render_ms;
+        let over = self.budget_ms > 0 && s.frame_ms > self.budget_ms;
+        if over {
+            self.over_budget += 1;
+        }
+        s.over_budget = self.over_budget;
+        self.last = s;
+        match self.on_over_budget {
+            Some((cb, userdata)) if over => Some((cb, s.frame_ms, s.frame, userdata)),
+            _ => None,
+        }
+    }
+
+    /// Physical rect covered by the overlay (top-left corner of the window).
+    pub fn overlay_rect(&self) -> (i32, i32, u32, u32) {
+        let m = crate::theme::scale(4) as i32;
+        (m, m, crate::theme::scale(OVERLAY_W), crate::theme::scale(OVERLAY_H))
+    }
+
+    /// Draw the overlay with the last frame's numbers onto a window surface.
+    pub fn draw_overlay(&self, surf: &crate::draw::Surface) {
+        let (x, y, w, h) = self.overlay_rect();
+        let s = &self.last;
+        let color = if self.budget_ms > 0 && s.frame_ms > self.budget_ms { 0xFFFF6060 } else { 0xFFE0E0E0 };
+        crate::draw::fill_rect(surf, x, y, w, h, 0xFF202020);
+
+        let size = crate::theme::scale(OVERLAY_FONT_SIZE as u32) as u16;
+        let line_h = crate::theme::scale(14) as i32;
+        let left = x + crate::theme::scale(6) as i32;
+        let right = x + (w / 2) as i32 + crate::theme::scale(6) as i32;
+        let top = y + crate::theme::scale(4) as i32;
+        let items: [(i32, i32, &[u8], u32, &[u8]); 6] = [
+            (left, 0, b"frame ", s.frame_ms, b" ms"),
+            (left, 1, b"layout ", s.layout_ms, b" ms"),
+            (left, 2, b"render ", s.render_ms, b" ms"),
+            (left, 3, b"present ", s.present_wait_ms, b" ms"),
+            (right, 0, b"", s.controls_drawn, b" ctl"),
+            (right, 1, b"", s.dirty_area / 1000, b"k px"),
+        ];
+        let mut buf = [0u8; 32];
+        for (tx, line, label, val, unit) in items {
+            let mut n = push(&mut buf, 0, label);
+            n += write_u32(&mut buf[n..], val);
+            n = push(&mut buf, n, unit);
+            crate::draw::draw_text_sized(surf, tx, top + line * line_h, color, &buf[..n], size);
+        }
+    }
+}
+
+fn push(buf: &mut [u8], pos: usize, s: &[u8]) -> usize {
+    let end = (pos + s.len()).min(buf.len());
+    buf[pos..end].copy_from_slice(&s[..end - pos]);
+    end
+}
+
+fn write_u32(buf: &mut [u8], val: u32) -> usize {
+    let mut tmp = [0u8; 10];
+    let mut n = val;
+    let mut len = 0;
+    loop {
+        tmp[len] = b'0' + (n % 10) as u8;
+        n /= 10;
+        len += 1;
+        if n == 0 { break; }
+    }
+    for i in 0..len {
+        buf[i] = tmp[len - 1 - i];
+    }
+    len
+}
@@ -0,0 +1,334 @@
+//! Gesture recognizers: long-press, swipe and double-tap on any control.
+//!
+//! A control turns recognizers on with `anyui_gesture_*`. They watch the
+//! left-button presses of the mouse path, so they work with the mouse, the
+//! pen and the first touch contact alike (see [`crate::pointer`]). A press
+//! belongs to the nearest control, the pressed one or an ancestor, with the
+//! recognizer enabled; each recognizer picks its control independently, so
+//! a list can take swipes while its rows take long presses.
+//!
+//! - Long-press: EVENT_LONG_PRESS once the pointer has stayed within
+//!   [`TAP_SLOP`] of the press point for the configured delay. The release
+//!   that ends the press does not click.
+//! - Swipe: EVENT_SWIPE on release when the travel along an enabled axis is
+//!   at least [`SWIPE_MIN_DISTANCE`] and the release speed reaches the
+//!   configured threshold.
+//! - Double-tap: EVENT_DOUBLE_TAP on the second of two short, still presses
+//!   close together within the configured interval.
+//!
+//! Callbacks read the parameters with `anyui_get_gesture_info`. Two-finger
+//! pinches are not a recognizer here: touch delivers them as EVENT_PINCH.
+
+use crate::control::{self, Control, ControlId};
+use alloc::boxed::Box;
+
+/// Gesture kinds (`GestureInfo::kind`).
+pub const GESTURE_LONG_PRESS: u32 = 1;
+pub const GESTURE_SWIPE: u32 = 2;
+pub const GESTURE_DOUBLE_TAP: u32 = 3;
+
+/// Swipe axes for `anyui_gesture_swipe`.
+pub const SWIPE_HORIZONTAL: u32 = 0x01;
+pub const SWIPE_VERTICAL: u32 = 0x02;
+
+/// Swipe directions (`GestureInfo::direction`).
+pub const SWIPE_LEFT: u32 = 1;
+pub const SWIPE_RIGHT: u32 = 2;
+pub const SWIPE_UP: u32 = 3;
+pub const SWIPE_DOWN: u32 = 4;
+
+/// Travel (logical pixels) a press may make and still count as held still.
+pub const TAP_SLOP: i32 = 8;
+/// Shortest travel along the axis that can be a swipe (logical pixels).
+pub const SWIPE_MIN_DISTANCE: i32 = 30;
+/// Window over which the release speed of a swipe is measured.
+const VELOCITY_WINDOW_MS: u32 = 100;
+
+/// Recognizers enabled on one control (`ControlBase::gestures`).
+#[derive(Clone, Copy, Default)]
+pub struct Recognizers {
+    /// Hold time before EVENT_LONG_PRESS, in ms (0 = off).
+    pub long_press_ms: u32,
+    /// SWIPE_HORIZONTAL | SWIPE_VERTICAL (0 = off).
+    pub swipe_axes: u32,
+    /// Minimum release speed of a swipe, logical pixels per second.
+    pub swipe_velocity: u32,
+    /// Longest gap between the taps of a double-tap, in ms (0 = off).
+    pub double_tap_ms: u32,
+}
+
+impl Recognizers {
+    fn is_empty(&self) -> bool {
+        self.long_press_ms == 0 && self.swipe_axes == 0 && self.double_tap_ms == 0
+    }
+}
+
+/// Parameters of the gesture behind the current callback.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct GestureInfo {
+    /// GESTURE_LONG_PRESS, GESTURE_SWIPE or GESTURE_DOUBLE_TAP.
+    pub kind: u32,
+    /// Press point, local to the notified control (logical pixels).
+    pub x: i32,
+    pub y: i32,
+    /// Swipe: SWIPE_LEFT / RIGHT / UP / DOWN. 0 otherwise.
+    pub direction: u32,
+    /// Swipe: travel along the swipe axis (logical pixels). 0 otherwise.
+    pub distance: u32,
+    /// Swipe: release speed along the axis (logical pixels per second).
+    pub velocity: u32,
+    /// Time from the press to the gesture (double-tap: between the taps), ms.
+    pub duration_ms: u32,
+}
+
+/// Change one control's recognizers, dropping the box when none are left.
+pub fn update(ctrl: &mut dyn Control, f: impl FnOnce(&mut Recognizers)) {
+    let b = ctrl.base_mut();
+    let mut r = b.gestures.as_deref().copied().unwrap_or_default();
+    f(&mut r);
+    b.gestures = if r.is_empty() { None } else { Some(Box::new(r)) };
+}
+
+/// Nearest control from `id` up whose recognizers pass `enabled`.
+fn resolve(
+    controls: &[Box<dyn Control>],
+    id: ControlId,
+    enabled: impl Fn(&Recognizers) -> bool,
+) -> Option<(ControlId, Recognizers)> {
+    let mut cur = id;
+    loop {
+        let idx = control::find_idx(controls, cur)?;
+        let b = controls[idx].base();
+        if let Some(r) = b.gestures.as_deref().filter(|r| enabled(r)) {
+            return Some((cur, *r));
+        }
+        if b.parent == 0 || b.parent == cur {
+            return None;
+        }
+        cur = b.parent;
+    }
+}
+
+/// A left-button press being watched.
+struct Press {
+    /// (control, hold time) for the long-press recognizer.
+    long_press: Option<(ControlId, u32)>,
+    /// (control, axes, minimum speed) for the swipe recognizer.
+    swipe: Option<(ControlId, u32, u32)>,
+    /// (control, interval) for the double-tap recognizer.
+    double_tap: Option<(ControlId, u32)>,
+    /// Press point and time (window position, logical pixels).
+    x: i32,
+    y: i32,
+    ms: u32,
+    /// Sample the release speed is measured from.
+    anchor: (i32, i32, u32),
+    /// Latest sample.
+    last: (i32, i32, u32),
+    /// Moved beyond TAP_SLOP: no long-press or tap any more.
+    moved: bool,
+    long_fired: bool,
+}
+
+/// A tap that may become the first half of a double-tap.
+struct Tap {
+    target: ControlId,
+    x: i32,
+    y: i32,
+    ms: u32,
+}
+
+/// A recognized gesture, ready to be dispatched.
+pub(crate) struct Recognized {
+    pub target: ControlId,
+    pub event_type: u32,
+    /// Coordinates still in window space.
+    pub info: GestureInfo,
+}
+
+pub(crate) struct GestureState {
+    press: Option<Press>,
+    last_tap: Option<Tap>,
+}
+
+impl GestureState {
+    pub const fn new() -> Self {
+        Self { press: None, last_tap: None }
+    }
+
+    /// A left-button press went down on `hit` at window position (x, y).
+    pub fn down(&mut self, controls: &[Box<dyn Control>], hit: ControlId, x: i32, y: i32) {
+        let long_press = resolve(controls, hit, |r| r.long_press_ms != 0)
+            .map(|(id, r)| (id, r.long_press_ms));
+        let swipe = resolve(controls, hit, |r| r.swipe_axes != 0)
+            .map(|(id, r)| (id, r.swipe_axes, r.swipe_velocity));
+        let double_tap = resolve(controls, hit, |r| r.double_tap_ms != 0)
+            .map(|(id, r)| (id, r.double_tap_ms));
+        if long_press.is_none() && swipe.is_none() && double_tap.is_none() {
+            self.press = None;
+            self.last_tap = None;
+            return;
+        }
+        let now = crate::syscall::uptime_ms();
+        self.press = Some(Press {
+            long_press,
+            swipe,
+            double_tap,
+            x,
+            y,
+            ms: now,
+            anchor: (x, y, now),
+            last: (x, y, now),
+            moved: false,
+            long_fired: false,
+        });
+    }
+
+    /// The pointer moved to window position (x, y) during a press.
+    pub fn motion(&mut self, x: i32, y: i32) {
+        let Some(p) = self.press.as_mut() else { return };
+        let now = crate::syscall::uptime_ms();
+        if now.wrapping_sub(p.anchor.2) > VELOCITY_WINDOW_MS {
+            p.anchor = p.last;
+        }
+        p.last = (x, y, now);
+        if (x - p.x).abs() > TAP_SLOP || (y - p.y).abs() > TAP_SLOP {
+            p.moved = true;
+        }
+    }
+
+    /// A long-press whose hold time has run out.
+    pub fn poll(&mut self) -> Option<Recognized> {
+        let p = self.press.as_mut()?;
+        let (target, delay) = p.long_press?;
+        let held = crate::syscall::uptime_ms().wrapping_sub(p.ms);
+        if p.moved || p.long_fired || held < delay {
+            return None;
+        }
+        p.long_fired = true;
+        Some(Recognized {
+            target,
+            event_type: control::EVENT_LONG_PRESS,
+            info: GestureInfo {
+                kind: GESTURE_LONG_PRESS,
+                x: p.x,
+                y: p.y,
+                duration_ms: held,
+                ..GestureInfo::default()
+            },
+        })
+    }
+
+    /// Milliseconds until a pending long-press fires, if one is armed.
+    pub fn next_deadline(&self) -> Option<u32> {
+        let p = self.press.as_ref()?;
+        let (_, delay) = p.long_press?;
+        if p.moved || p.long_fired {
+            return None;
+        }
+        Some(delay.saturating_sub(crate::syscall::uptime_ms().wrapping_sub(p.ms)))
+    }
+
+    /// The press ended at window position (x, y). Returns the gesture it
+    /// completed, if any, and whether a long-press consumed it.
+    pub fn up(&mut self, x: i32, y: i32) -> (Option<Recognized>, bool) {
+        self.motion(x, y);
+        let Some(p) = self.press.take() else { return (None, false) };
+        if p.long_fired {
+            self.last_tap = None;
+            return (None, true);
+        }
+        let now = p.last.2;
+        if let Some((target, axes, min_velocity)) = p.swipe {
+            if let Some(r) = swipe(&p, target, axes, min_velocity) {
+                self.last_tap = None;
+                return (Some(r), false);
+            }
+        }
+        if p.moved {
+            self.last_tap = None;
+            return (None, false);
+        }
+        let Some((target, interval)) = p.double_tap else { return (None, false) };
+        if let Some(tap) = self.last_tap.take() {
+            let gap = now.wrapping_sub(tap.ms);
+            if tap.target == target
+                && gap <= interval
+                && (p.x - tap.x).abs() <= TAP_SLOP * 2
+                && (p.y - tap.y).abs() <= TAP_SLOP * 2
+            {
+                let info = GestureInfo {
+                    kind: GESTURE_DOUBLE_TAP,
+                    x: p.x,
+                    y: p.y,
+                    duration_ms: gap,
+                    ..GestureInfo::default()
+                };
+                return (Some(Recognized { target, event_type: control::EVENT_DOUBLE_TAP, info }), false);
+            }
+        }
+        self.last_tap = Some(Tap { target, x: p.x, y: p.y, ms: now });
+        (None, false)
+    }
+
+    /// Drop all tracking that refers to `id` (control destroyed).
+    pub fn forget(&mut self, id: ControlId) {
+        if let Some(p) = self.press.as_mut() {
+            if p.long_press.map_or(false, |(t, _)| t == id) { p.long_press = None; }
+            if p.swipe.map_or(false, |(t, _, _)| t == id) { p.swipe = None; }
+            if p.double_tap.map_or(false, |(t, _)| t == id) { p.double_tap = None; }
+        }
+        if self.last_tap.as_ref().map_or(false, |t| t.target == id) {
+            self.last_tap = None;
+        }
+    }
+}
+
+/// Classify a finished press as a swipe along one of `axes`, released at
+/// `min_velocity` or faster.
+fn swipe(p: &Press, target: ControlId, axes: u32, min_velocity: u32) -> Option<Recognized> {
+    let (x, y, now) = p.last;
+    let dx = x - p.x;
+    let dy = y - p.y;
+    let horizontal = dx.abs() >= dy.abs();
+    let axis = if horizontal { SWIPE_HORIZONTAL } else { SWIPE_VERTICAL };
+    if axes & axis == 0 {
+        return None;
+    }
+    let distance = if horizontal { dx } else { dy };
+    if distance.abs() < SWIPE_MIN_DISTANCE {
+        return None;
+    }
+
+    // Release speed: travel along the axis since the anchor sample.
+    let (ax, ay, ams) = p.anchor;
+    let dt = now.wrapping_sub(ams).max(1);
+    let travel = if horizontal { x - ax } else { y - ay };
+    // Moving back against the swipe direction is no release speed.
+    let travel = if travel.signum() == distance.signum() { travel.unsigned_abs() } else { 0 };
+    let velocity = travel * 1000 / dt;
+
+    if velocity < min_velocity {
+        return None;
+    }
+    let direction = match (horizontal, distance > 0) {
+        (true, false) => SWIPE_LEFT,
+        (true, true) => SWIPE_RIGHT,
+        (false, false) => SWIPE_UP,
+        (false, true) => SWIPE_DOWN,
+    };
+    Some(Recognized {
+        target,
+        event_type: control::EVENT_SWIPE,
+        info: GestureInfo {
+            kind: GESTURE_SWIPE,
+            x: p.x,
+            y: p.y,
+            direction,
+            distance: distance.unsigned_abs(),
+            velocity,
+            duration_ms: now.wrapping_sub(p.ms),
+        },
+    })
+}
@@ -0,0 +1,73 @@
+//! Context help — per-control help topics, F1 routing and "What's this?" mode.
+//!
+//! A control gets a topic string with `anyui_set_help_topic`. Help is
+//! requested for a *source* control in two ways:
+//!
+//! - F1 in a window asks for help on the focused control (the window itself
+//!   when nothing is focused). F1 never reaches the focused control.
+//! - In "What's this?" mode (`anyui_set_whats_this`) the next press in any
+//!   window ends the mode and asks for help on the deepest control under the
+//!   pointer, labels and other non-interactive controls included, instead of
+//!   clicking it. Escape ends the mode without a request.
+//!
+//! EVENT_HELP goes to the nearest control with an EVENT_HELP callback, the
+//! source or an ancestor, so one handler on the window serves every control.
+//! The callback reads the source with `anyui_get_help_source` and its topic
+//! with `anyui_get_help_topic`: the source's own topic, else the nearest
+//! ancestor's (empty if no control on the way has one).
+//!
+//! # Usage (via client API)
+//! ```ignore
+//! save_btn.set_help_topic("files/saving");
+//! win.on_help(|_source, topic| show_help_page(topic));
+//! help_button.on_click(|_| ui::set_whats_this(true));
+//! ```
+
+use alloc::boxed::Box;
+
+use crate::control::{self, Control, ControlId};
+
+/// Help request storage, owned by AnyuiState.
+pub struct HelpState {
+    /// The next press requests help instead of clicking.
+    pub whats_this: bool,
+    /// Source control of the current EVENT_HELP callback.
+    pub source: ControlId,
+}
+
+impl HelpState {
+    pub fn new() -> Self {
+        Self { whats_this: false, source: 0 }
+    }
+}
+
+/// Effective help topic of control `id`: its own, else the nearest
+/// ancestor's, else empty.
+pub fn topic(controls: &[Box<dyn Control>], id: ControlId) -> &[u8] {
+    let mut cur = id;
+    loop {
+        let Some(idx) = control::find_idx(controls, cur) else { return &[] };
+        let c = &controls[idx];
+        if !c.base().help_topic.is_empty() {
+            return &c.base().help_topic;
+        }
+        let parent = c.parent_id();
+        if parent == 0 || parent == cur { return &[]; }
+        cur = parent;
+    }
+}
+
+/// Nearest control, `id` or an ancestor, with an EVENT_HELP callback.
+pub fn handler(controls: &[Box<dyn Control>], id: ControlId) -> Option<ControlId> {
+    let mut cur = id;
+    loop {
+        let idx = control::find_idx(controls, cur)?;
+        let c = &controls[idx];
+        if c.get_event_callback(control::EVENT_HELP).is_some() {
+            return Some(cur);
+        }
+        let parent = c.parent_id();
+        if parent == 0 || parent == cur { return None; }
+        cur = parent;
+    }
+}
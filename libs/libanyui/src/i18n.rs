@@ -0,0 +1,362 @@
+//! Localization — string tables, keyed control texts and locale formatting.
+//!
+//! The application loads one or more `.lang` files per language
+//! (`anyui_lang_load`) and picks the active language with
+//! `anyui_set_language`. A `.lang` file holds `key = value` lines; blank
+//! lines and lines starting with `#` are skipped, and values may use `\n`,
+//! `\t` and `\\` escapes. Keys starting with `@` set the locale's number
+//! and date conventions instead of strings:
+//!
+//! ```text
+//! @decimal = ,
+//! @group = .
+//! @date = dd.MM.yyyy
+//! @time = HH:mm
+//! ```
+//!
+//! A control given its text with `anyui_set_text_key` remembers the key and
+//! its arguments; `{0}`, `{1}`, ... in the translation are replaced by the
+//! arguments. Switching the language re-resolves every keyed control, lays
+//! the windows out again and calls the `anyui_on_language_changed`
+//! callback, so an app can switch language without a restart. Setting a
+//! control's text directly (`anyui_set_text`) drops its key.
+//!
+//! A key missing from the active language is looked up in the fallback
+//! language (the first one loaded), and shows as the key itself if it is
+//! missing there too.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::control::{Callback, ControlId};
+
+/// Numeric tokens understood in `@date` / `@time` patterns:
+/// `yyyy yy MM M dd d HH H hh h mm ss a`; anything else is copied.
+const DEFAULT_DATE: &[u8] = b"yyyy-MM-dd";
+const DEFAULT_TIME: &[u8] = b"HH:mm";
+
+/// Number and date conventions of a language.
+#[derive(Clone)]
+pub struct Locale {
+    pub decimal: Vec<u8>,
+    /// Thousands separator (empty = no grouping).
+    pub group: Vec<u8>,
+    pub date: Vec<u8>,
+    pub time: Vec<u8>,
+}
+
+impl Locale {
+    fn new() -> Self {
+        Locale {
+            decimal: Vec::from(&b"."[..]),
+            group: Vec::from(&b","[..]),
+            date: Vec::from(DEFAULT_DATE),
+            time: Vec::from(DEFAULT_TIME),
+        }
+    }
+}
+
+/// Strings and locale of one language.
+pub struct Language {
+    pub code: Vec<u8>,
+    pub strings: BTreeMap<Vec<u8>, Vec<u8>>,
+    pub locale: Locale,
+}
+
+/// The key a control's text comes from (`ControlBase::text_key`).
+pub struct TextKey {
+    pub key: Vec<u8>,
+    pub args: Vec<Vec<u8>>,
+}
+
+/// Loaded languages and the active one (`AnyuiState::i18n`).
+pub struct I18nState {
+    /// Loaded languages; the first is the fallback.
+    pub languages: Vec<Language>,
+    /// Index into `languages` of the active language.
+    pub active: Option<usize>,
+    pub on_language_changed: Option<(Callback, u64)>,
+}
+
+impl I18nState {
+    pub fn new() -> Self {
+        I18nState { languages: Vec::new(), active: None, on_language_changed: None }
+    }
+
+    fn find(&self, code: &[u8]) -> Option<usize> {
+        self.languages.iter().position(|l| l.code == code)
+    }
+
+    /// Translation of `key` (active language, then fallback), if any.
+    pub fn lookup(&self, key: &[u8]) -> Option<&[u8]> {
+        let active = self.active.and_then(|i| self.languages[i].strings.get(key));
+        active.or_else(|| self.languages.first().and_then(|l| l.strings.get(key))).map(|v| &v[..])
+    }
+
+    /// Locale of the active language (defaults if none is active).
+    pub fn locale(&self) -> Locale {
+        match self.active {
+            Some(i) => self.languages[i].locale.clone(),
+            None => Locale::new(),
+        }
+    }
+
+    /// Translate `key` and substitute `{n}` with `args[n]`.
+    pub fn resolve(&self, key: &[u8], args: &[Vec<u8>]) -> Vec<u8> {
+        let template = self.lookup(key).unwrap_or(key);
+        let mut out = Vec::with_capacity(template.len());
+        let mut i = 0;
+        while i < template.len() {
+            if template[i] == b'{' {
+                if let Some(end) = template[i + 1..].iter().position(|&b| b == b'}') {
+                    let digits = &template[i + 1..i + 1 + end];
+                    if let Some(n) = parse_index(digits) {
+                        if let Some(arg) = args.get(n) {
+                            out.extend_from_slice(arg);
+                        }
+                        i += end + 2;
+                        continue;
+                    }
+                }
+            }
+            out.push(template[i]);
+            i += 1;
+        }
+        out
+    }
+}
+
+fn parse_index(digits: &[u8]) -> Option<usize> {
+    if digits.is_empty() || digits.len() > 2 || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    Some(digits.iter().fold(0, |n, &d| n * 10 + (d - b'0') as usize))
+}
+
+// ── .lang files ──────────────────────────────────────────────────────
+
+fn read_file(path: &str) -> Option<Vec<u8>> {
+    use crate::syscall;
+    let fd = syscall::open(path, 0);
+    if fd == u32::MAX {
+        return None;
+    }
+    let size = syscall::file_size(fd) as usize;
+    let mut buf = alloc::vec![0u8; size];
+    let mut done = 0;
+    while done < size {
+        let n = syscall::read(fd, &mut buf[done..]);
+        if n == 0 || n == u32::MAX {
+            break;
+        }
+        done += n as usize;
+    }
+    syscall::close(fd);
+    buf.truncate(done);
+    Some(buf)
+}
+
+fn unescape(value: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(value.len());
+    let mut i = 0;
+    while i < value.len() {
+        if value[i] == b'\\' && i + 1 < value.len() {
+            out.push(match value[i + 1] {
+                b'n' => b'\n',
+                b't' => b'\t',
+                other => other,
+            });
+            i += 2;
+        } else {
+            out.push(value[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Parse `.lang` text into `lang`, overriding keys it already has.
+fn parse_into(text: &[u8], lang: &mut Language) {
+    for line in text.split(|&b| b == b'\n') {
+        let line = line.trim_ascii();
+        if line.is_empty() || line[0] == b'#' {
+            continue;
+        }
+        let Some(eq) = line.iter().position(|&b| b == b'=') else { continue };
+        let key = line[..eq].trim_ascii();
+        let value = unescape(line[eq + 1..].trim_ascii());
+        match key {
+            b"" => {}
+            b"@decimal" => lang.locale.decimal = value,
+            b"@group" => lang.locale.group = value,
+            b"@date" => lang.locale.date = value,
+            b"@time" => lang.locale.time = value,
+            _ => {
+                lang.strings.insert(key.to_vec(), value);
+            }
+        }
+    }
+}
+
+/// Load a `.lang` file into language `code`. Returns false if the file
+/// cannot be read.
+pub(crate) fn load(code: &[u8], path: &str) -> bool {
+    let Some(data) = read_file(path) else { return false };
+    let i18n = &mut crate::state().i18n;
+    let idx = match i18n.find(code) {
+        Some(i) => i,
+        None => {
+            i18n.languages.push(Language { code: code.to_vec(), strings: BTreeMap::new(), locale: Locale::new() });
+            i18n.languages.len() - 1
+        }
+    };
+    parse_into(&data, &mut i18n.languages[idx]);
+    // New strings for the active language show at once.
+    if i18n.active == Some(idx) || idx == 0 {
+        refresh_keyed_texts();
+    }
+    true
+}
+
+/// Make `code` the active language, update every keyed text and notify
+/// the app. Returns false if no file was loaded for `code`. Must be called
+/// with no framework state borrowed (it calls the app's callback).
+pub(crate) fn set_language(code: &[u8]) -> bool {
+    let i18n = &mut crate::state().i18n;
+    let Some(idx) = i18n.find(code) else { return false };
+    if i18n.active == Some(idx) {
+        return true;
+    }
+    i18n.active = Some(idx);
+    refresh_keyed_texts();
+    if let Some((cb, ud)) = crate::state().i18n.on_language_changed {
+        cb(0, 0, ud);
+    }
+    true
+}
+
+/// Give control `id` the translation of `key` and remember the key.
+pub(crate) fn set_text_key(id: ControlId, key: &[u8], args: Vec<Vec<u8>>) -> bool {
+    let st = crate::state();
+    let Some(idx) = crate::control::find_idx(&st.controls, id) else { return false };
+    let text = st.i18n.resolve(key, &args);
+    let ctrl = &mut st.controls[idx];
+    ctrl.set_text(&text);
+    ctrl.base_mut().text_key = Some(alloc::boxed::Box::new(TextKey { key: key.to_vec(), args }));
+    crate::mark_needs_layout();
+    true
+}
+
+/// Re-resolve the text of every keyed control and relayout.
+fn refresh_keyed_texts() {
+    let st = crate::state();
+    let mut changed = false;
+    for i in 0..st.controls.len() {
+        let text = match st.controls[i].base().text_key.as_deref() {
+            Some(k) => st.i18n.resolve(&k.key, &k.args),
+            None => continue,
+        };
+        if st.controls[i].text() != &text[..] {
+            st.controls[i].set_text(&text);
+            changed = true;
+        }
+    }
+    if changed {
+        crate::mark_needs_layout();
+    }
+}
+
+/// Split `\0`-separated arguments (an empty buffer means no arguments).
+pub(crate) fn split_args(packed: &[u8]) -> Vec<Vec<u8>> {
+    if packed.is_empty() {
+        return Vec::new();
+    }
+    packed.split(|&b| b == 0).map(|a| a.to_vec()).collect()
+}
+
+// ── Formatting ───────────────────────────────────────────────────────
+
+/// `value` with `decimals` fraction digits, the locale's decimal mark and
+/// thousands grouping.
+pub(crate) fn format_number(value: f64, decimals: u32) -> Vec<u8> {
+    let locale = crate::state().i18n.locale();
+    if !value.is_finite() {
+        return alloc::format!("{}", value).into_bytes();
+    }
+    let plain = alloc::format!("{:.*}", decimals.min(20) as usize, value);
+    let (sign, digits) = match plain.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", &plain[..]),
+    };
+    let (int, frac) = match digits.find('.') {
+        Some(dot) => (&digits[..dot], Some(&digits[dot + 1..])),
+        None => (digits, None),
+    };
+    let mut out = Vec::from(sign.as_bytes());
+    for (i, d) in int.bytes().enumerate() {
+        if i > 0 && (int.len() - i) % 3 == 0 {
+            out.extend_from_slice(&locale.group);
+        }
+        out.push(d);
+    }
+    if let Some(frac) = frac {
+        out.extend_from_slice(&locale.decimal);
+        out.extend_from_slice(frac.as_bytes());
+    }
+    out
+}
+
+/// Date / time fields for pattern formatting.
+#[derive(Default)]
+pub(crate) struct DateTime {
+    pub year: u32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+/// Format with the active locale's `@date` (`time` = false) or `@time` pattern.
+pub(crate) fn format_datetime(dt: &DateTime, time: bool) -> Vec<u8> {
+    let locale = crate::state().i18n.locale();
+    expand_pattern(if time { &locale.time } else { &locale.date }, dt)
+}
+
+fn expand_pattern(pattern: &[u8], dt: &DateTime) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < pattern.len() {
+        let c = pattern[i];
+        let run = pattern[i..].iter().take_while(|&&b| b == c).count();
+        let hour12 = if dt.hour % 12 == 0 { 12 } else { dt.hour % 12 };
+        let (value, width, used) = match (c, run) {
+            (b'y', n) if n >= 4 => (dt.year, 4, 4),
+            (b'y', n) if n >= 2 => (dt.year % 100, 2, 2),
+            (b'M', n) => (dt.month, n.min(2), n.min(2)),
+            (b'd', n) => (dt.day, n.min(2), n.min(2)),
+            (b'H', n) => (dt.hour, n.min(2), n.min(2)),
+            (b'h', n) => (hour12, n.min(2), n.min(2)),
+            (b'm', n) => (dt.minute, 2, n.min(2)),
+            (b's', n) => (dt.second, 2, n.min(2)),
+            (b'a', _) => {
+                out.extend_from_slice(if dt.hour < 12 { b"AM" } else { b"PM" });
+                i += 1;
+                continue;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+                continue;
+            }
+        };
+        push_padded(&mut out, value, width);
+        i += used;
+    }
+    out
+}
+
+fn push_padded(out: &mut Vec<u8>, value: u32, width: usize) {
+    let s = alloc::format!("{:0width$}", value, width = width);
+    out.extend_from_slice(s.as_bytes());
+}
@@ -0,0 +1,367 @@
+//! Image loader — reads and decodes ImageView files on a worker thread.
+//!
+//! `anyui_imageview_load_file()` queues a job and returns at once; the
+//! ImageView shows its placeholder with a spinner meanwhile. A single
+//! worker thread (started on the first load) reads the file and decodes it
+//! through libimage (BMP, PNG, JPEG, GIF, ICO). The decoded pixels are handed
+//! back through a result queue that the event loop drains at the start of
+//! each frame, so they are only ever applied on the UI thread.
+//!
+//! The DLL heap is not thread-safe, so the worker never allocates: jobs and
+//! results are fixed-size queue entries, and the file, scratch and pixel
+//! buffers are mapped with `mmap`. The UI thread copies the pixels into the
+//! ImageView and unmaps the buffer.
+//!
+//! A result is dropped if the control was destroyed, or if it was given new
+//! pixels, cleared or asked to load another file since (generation check).
+
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use crate::control::{Control, ControlId, ControlKind};
+use crate::controls::image_view::ImageView;
+
+/// Longest accepted file path in bytes.
+pub const MAX_PATH: usize = 256;
+
+/// Pending jobs / undelivered results per queue.
+const QUEUE_SIZE: usize = 32;
+
+/// Worker thread stack size.
+const WORKER_STACK: u32 = 64 * 1024;
+
+// ── Load error codes (`anyui_imageview_get_load_error`) ─────────────
+
+/// The file could not be opened or read.
+pub const LOAD_ERR_IO: u32 = 1;
+/// The file is not an image format libimage recognizes.
+pub const LOAD_ERR_FORMAT: u32 = 2;
+/// The image data is corrupt or uses an unsupported feature.
+pub const LOAD_ERR_DECODE: u32 = 3;
+/// The decode buffers could not be mapped.
+pub const LOAD_ERR_NO_MEMORY: u32 = 4;
+
+// ── libimage DLIB access (at fixed address 0x04100000) ──────────────
+
+const LIBIMAGE_BASE: usize = 0x0410_0000;
+
+#[repr(C)]
+struct ImageInfo {
+    width: u32,
+    height: u32,
+    format: u32,
+    scratch_needed: u32,
+}
+
+/// Partial mirror of libimage's export table — only the fields we need.
+#[repr(C)]
+struct LibimageExportsPartial {
+    _magic: [u8; 4],
+    _version: u32,
+    _num_exports: u32,
+    _pad: u32,
+    // offset 16: video
+    _video_probe: usize,
+    _video_decode_frame: usize,
+    // offset 32: still images
+    image_probe: extern "C" fn(*const u8, u32, *mut ImageInfo) -> i32,
+    image_decode: extern "C" fn(*const u8, u32, *mut u32, u32, *mut u8, u32) -> i32,
+}
+
+#[inline(always)]
+fn libimage() -> &'static LibimageExportsPartial {
+    unsafe { &*(LIBIMAGE_BASE as *const LibimageExportsPartial) }
+}
+
+// ── Queues ───────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy)]
+struct Job {
+    id: ControlId,
+    generation: u32,
+    path: [u8; MAX_PATH],
+    path_len: usize,
+}
+
+/// Outcome of a job. On success `pixels` is an `mmap` of `map_size` bytes
+/// holding `width * height` ARGB pixels.
+#[derive(Clone, Copy)]
+struct Loaded {
+    id: ControlId,
+    generation: u32,
+    pixels: u64,
+    map_size: u32,
+    width: u32,
+    height: u32,
+    /// 0 on success, else a `LOAD_ERR_*` code.
+    error: u32,
+}
+
+/// Spinlock-protected ring buffer (same scheme as the marshal queue).
+struct Ring<T: Copy> {
+    buf: [Option<T>; QUEUE_SIZE],
+    head: usize,
+    tail: usize,
+    lock: AtomicBool,
+}
+
+impl<T: Copy> Ring<T> {
+    const fn new() -> Self {
+        Self { buf: [None; QUEUE_SIZE], head: 0, tail: 0, lock: AtomicBool::new(false) }
+    }
+
+    fn push(&mut self, item: T) -> bool {
+        while self.lock.swap(true, Ordering::Acquire) {
+            core::hint::spin_loop();
+        }
+        let next = (self.head + 1) % QUEUE_SIZE;
+        let ok = next != self.tail;
+        if ok {
+            self.buf[self.head] = Some(item);
+            self.head = next;
+        }
+        self.lock.store(false, Ordering::Release);
+        ok
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        while self.lock.swap(true, Ordering::Acquire) {
+            core::hint::spin_loop();
+        }
+        let item = if self.tail == self.head {
+            None
+        } else {
+            let item = self.buf[self.tail].take();
+            self.tail = (self.tail + 1) % QUEUE_SIZE;
+            item
+        };
+        self.lock.store(false, Ordering::Release);
+        item
+    }
+}
+
+static mut JOBS: Ring<Job> = Ring::new();
+static mut RESULTS: Ring<Loaded> = Ring::new();
+
+/// Channel / subscription the worker sleeps on (0 = not started).
+static WORKER_CHANNEL: AtomicU32 = AtomicU32::new(0);
+static WORKER_SUB: AtomicU32 = AtomicU32::new(0);
+
+// ── UI thread side ──────────────────────────────────────────────────
+
+/// Queue `path` to be loaded into ImageView `id` as load `generation`.
+/// Starts the worker on first use. Returns false if the path is too long
+/// or not UTF-8, the queue is full or the worker cannot be started.
+pub fn submit(id: ControlId, generation: u32, path: &[u8]) -> bool {
+    if path.is_empty() || path.len() > MAX_PATH || core::str::from_utf8(path).is_err() {
+        return false;
+    }
+    if !ensure_worker() {
+        return false;
+    }
+    let mut job = Job { id, generation, path: [0; MAX_PATH], path_len: path.len() };
+    job.path[..path.len()].copy_from_slice(path);
+    if !unsafe { JOBS.push(job) } {
+        return false;
+    }
+    let ev = [0u32; 5];
+    crate::syscall::evt_chan_emit_to(WORKER_CHANNEL.load(Ordering::Acquire), WORKER_SUB.load(Ordering::Relaxed), &ev);
+    true
+}
+
+/// Create the worker's wake channel and start the worker thread.
+fn ensure_worker() -> bool {
+    if WORKER_CHANNEL.load(Ordering::Acquire) != 0 {
+        return true;
+    }
+    // Channel names are global: make it unique to this process.
+    let mut name = [0u8; 32];
+    let prefix = b"anyui-imgload-";
+    name[..prefix.len()].copy_from_slice(prefix);
+    let mut len = prefix.len();
+    let mut digits = [0u8; 10];
+    let mut n = crate::syscall::get_tid();
+    let mut nd = 0;
+    loop {
+        digits[nd] = b'0' + (n % 10) as u8;
+        nd += 1;
+        n /= 10;
+        if n == 0 { break; }
+    }
+    while nd > 0 {
+        nd -= 1;
+        name[len] = digits[nd];
+        len += 1;
+    }
+    let chan = crate::syscall::evt_chan_create(name.as_ptr(), len as u32);
+    if chan == 0 || chan == u32::MAX {
+        return false;
+    }
+    let sub = crate::syscall::evt_chan_subscribe(chan, 0);
+
+    let stack = crate::syscall::mmap(WORKER_STACK);
+    if stack == u64::MAX {
+        crate::syscall::evt_chan_unsubscribe(chan, sub);
+        return false;
+    }
+    WORKER_SUB.store(sub, Ordering::Relaxed);
+    WORKER_CHANNEL.store(chan, Ordering::Release);
+    if crate::syscall::thread_create(worker_main, stack + WORKER_STACK as u64 - 8, "anyui-imgload") == 0 {
+        WORKER_CHANNEL.store(0, Ordering::Release);
+        crate::syscall::evt_chan_unsubscribe(chan, sub);
+        crate::syscall::munmap(stack, WORKER_STACK);
+        return false;
+    }
+    true
+}
+
+/// Apply finished loads to their ImageViews. Called at the start of each
+/// `run_once()` frame. Returns `(control, event)` pairs to notify:
+/// EVENT_CHANGE for a completed load, EVENT_ERROR for a failed one.
+pub fn drain(st: &mut crate::AnyuiState) -> Vec<(ControlId, u32)> {
+    let mut fired = Vec::new();
+    while let Some(r) = unsafe { RESULTS.pop() } {
+        if let Some(iv) = image_view(st, r.id) {
+            if iv.loading && iv.load_generation == r.generation {
+                if r.error == 0 {
+                    let count = r.width as usize * r.height as usize;
+                    let px = unsafe { core::slice::from_raw_parts(r.pixels as *const u32, count) };
+                    iv.set_pixels(px, r.width, r.height);
+                    fired.push((r.id, crate::control::EVENT_CHANGE));
+                } else {
+                    iv.fail_load(r.error);
+                    fired.push((r.id, crate::control::EVENT_ERROR));
+                }
+            }
+        }
+        if r.pixels != 0 {
+            crate::syscall::munmap(r.pixels, r.map_size);
+        }
+    }
+    fired
+}
+
+fn image_view(st: &mut crate::AnyuiState, id: ControlId) -> Option<&mut ImageView> {
+    let ctrl = st.controls.iter_mut().find(|c| c.id() == id)?;
+    if ctrl.kind() != ControlKind::ImageView {
+        return None;
+    }
+    let raw: *mut dyn Control = &mut **ctrl;
+    Some(unsafe { &mut *(raw as *mut ImageView) })
+}
+
+// ── Worker thread ───────────────────────────────────────────────────
+
+fn worker_main() {
+    let chan = WORKER_CHANNEL.load(Ordering::Acquire);
+    let sub = WORKER_SUB.load(Ordering::Relaxed);
+    let mut ev = [0u32; 5];
+    loop {
+        // Consume wake events first: a job queued after this point
+        // leaves a fresh event behind, so the wait below returns.
+        while crate::syscall::evt_chan_poll(chan, sub, &mut ev) {}
+        while let Some(job) = unsafe { JOBS.pop() } {
+            let r = load(&job);
+            // The result queue only fills up if the UI thread stalls;
+            // wait for it rather than leak the pixel buffer.
+            while !unsafe { RESULTS.push(r) } {
+                crate::event_source::wakeup();
+                crate::syscall::sleep(10);
+            }
+            crate::event_source::wakeup();
+        }
+        crate::syscall::evt_chan_wait(chan, sub, u32::MAX);
+    }
+}
+
+/// Read and decode one file. Never allocates from the heap.
+fn load(job: &Job) -> Loaded {
+    let mut out = Loaded {
+        id: job.id,
+        generation: job.generation,
+        pixels: 0,
+        map_size: 0,
+        width: 0,
+        height: 0,
+        error: 0,
+    };
+    let path = match core::str::from_utf8(&job.path[..job.path_len]) {
+        Ok(p) => p,
+        Err(_) => {
+            out.error = LOAD_ERR_IO;
+            return out;
+        }
+    };
+
+    // Read the whole file.
+    let fd = crate::syscall::open(path, 0);
+    if fd == u32::MAX {
+        out.error = LOAD_ERR_IO;
+        return out;
+    }
+    let size = crate::syscall::file_size(fd);
+    let data = if size == 0 { u64::MAX } else { crate::syscall::mmap(size) };
+    if data == u64::MAX {
+        crate::syscall::close(fd);
+        out.error = if size == 0 { LOAD_ERR_IO } else { LOAD_ERR_NO_MEMORY };
+        return out;
+    }
+    let bytes = unsafe { core::slice::from_raw_parts_mut(data as *mut u8, size as usize) };
+    let mut done = 0usize;
+    while done < bytes.len() {
+        let n = crate::syscall::read(fd, &mut bytes[done..]);
+        if n == 0 || n == u32::MAX {
+            break;
+        }
+        done += n as usize;
+    }
+    crate::syscall::close(fd);
+    if done < bytes.len() {
+        crate::syscall::munmap(data, size);
+        out.error = LOAD_ERR_IO;
+        return out;
+    }
+
+    out.error = decode(data, size, &mut out);
+    crate::syscall::munmap(data, size);
+    out
+}
+
+/// Probe and decode the `size`-byte image at `data` into a new pixel
+/// mapping stored in `out`. Returns 0 or a `LOAD_ERR_*` code.
+fn decode(data: u64, size: u32, out: &mut Loaded) -> u32 {
+    let lib = libimage();
+    let mut info = ImageInfo { width: 0, height: 0, format: 0, scratch_needed: 0 };
+    if (lib.image_probe)(data as *const u8, size, &mut info) != 0 || info.width == 0 || info.height == 0 {
+        return LOAD_ERR_FORMAT;
+    }
+    let count = match info.width.checked_mul(info.height) {
+        Some(c) if c <= u32::MAX / 4 => c,
+        _ => return LOAD_ERR_NO_MEMORY,
+    };
+    let map_size = count * 4;
+    let pixels = crate::syscall::mmap(map_size);
+    if pixels == u64::MAX {
+        return LOAD_ERR_NO_MEMORY;
+    }
+    let scratch_size = info.scratch_needed.max(1);
+    let scratch = crate::syscall::mmap(scratch_size);
+    if scratch == u64::MAX {
+        crate::syscall::munmap(pixels, map_size);
+        return LOAD_ERR_NO_MEMORY;
+    }
+    let ret = (lib.image_decode)(
+        data as *const u8, size,
+        pixels as *mut u32, count,
+        scratch as *mut u8, scratch_size,
+    );
+    crate::syscall::munmap(scratch, scratch_size);
+    if ret != 0 {
+        crate::syscall::munmap(pixels, map_size);
+        return LOAD_ERR_DECODE;
+    }
+    out.pixels = pixels;
+    out.map_size = map_size;
+    out.width = info.width;
+    out.height = info.height;
+    0
+}
@@ -15,6 +15,10 @@
 //! 4. Recurse into all children
 //! 5. After recursion, auto-size controls compute their height from children,
 //!    then dock layout is re-run so subsequent siblings use the correct heights.
+//!
+//! In a right-to-left window docked children are mirrored across the parent's
+//! width, so Left docks end up on the right and padding and margins swap
+//! sides (see [`crate::bidi`]).
 
 use alloc::boxed::Box;
 use alloc::vec::Vec;
@@ -26,6 +30,7 @@ fn dock_layout(controls: &mut Vec<Box<dyn Control>>, parent_idx: usize, children
     let pad = controls[parent_idx].base().padding;
     let pw = controls[parent_idx].base().w;
     let ph = controls[parent_idx].base().h;
+    let rtl = crate::bidi::window_rtl();
 
     let mut area_left = pad.left;
     let mut area_top = pad.top;
@@ -45,65 +50,64 @@ fn dock_layout(controls: &mut Vec<Box<dyn Control>>, parent_idx: usize, children
         let dock = controls[ci].base().dock;
         let margin = controls[ci].base().margin;
 
-        match dock {
+        let (x, y, w, h) = match dock {
             DockStyle::Top => {
                 let ch = controls[ci].base().h;
                 let x = area_left + margin.left;
                 let y = area_top + margin.top;
                 let w = (area_right - area_left - margin.left - margin.right).max(0) as u32;
-                controls[ci].set_position(x, y);
-                controls[ci].set_size(w, ch);
                 area_top += ch as i32 + margin.top + margin.bottom;
+                (x, y, w, ch)
             }
             DockStyle::Bottom => {
                 let ch = controls[ci].base().h;
                 let x = area_left + margin.left;
                 let y = area_bottom - ch as i32 - margin.bottom;
                 let w = (area_right - area_left - margin.left - margin.right).max(0) as u32;
-                controls[ci].set_position(x, y);
-                controls[ci].set_size(w, ch);
                 area_bottom -= ch as i32 + margin.top + margin.bottom;
+                (x, y, w, ch)
             }
             DockStyle::Left => {
                 let cw = controls[ci].base().w;
                 let x = area_left + margin.left;
                 let y = area_top + margin.top;
                 let h = (area_bottom - area_top - margin.top - margin.bottom).max(0) as u32;
-                controls[ci].set_position(x, y);
-                controls[ci].set_size(cw, h);
                 area_left += cw as i32 + margin.left + margin.right;
+                (x, y, cw, h)
             }
             DockStyle::Right => {
                 let cw = controls[ci].base().w;
                 let x = area_right - cw as i32 - margin.right;
                 let y = area_top + margin.top;
                 let h = (area_bottom - area_top - margin.top - margin.bottom).max(0) as u32;
-                controls[ci].set_position(x, y);
-                controls[ci].set_size(cw, h);
                 area_right -= cw as i32 + margin.left + margin.right;
+                (x, y, cw, h)
             }
             DockStyle::Fill => {
                 let x = area_left + margin.left;
                 let y = area_top + margin.top;
                 let w = (area_right - area_left - margin.left - margin.right).max(0) as u32;
                 let h = (area_bottom - area_top - margin.top - margin.bottom).max(0) as u32;
-                controls[ci].set_position(x, y);
-                controls[ci].set_size(w, h);
+                (x, y, w, h)
             }
             DockStyle::None => {
                 // Manual positioning — leave x/y as-is
+                continue;
             }
-        }
+        };
+        // Right-to-left: mirror across the parent's width.
+        let x = if rtl { pw as i32 - x - w as i32 } else { x };
+        controls[ci].set_position(x, y);
+        controls[ci].set_size(w, h);
     }
 }
 
 /// Auto-size a control's height to fit its children.
 ///
-/// Scans all visible children and sets the control's height to the maximum
-/// bottom edge (child y + child h + child margin bottom) plus the control's
-/// own bottom padding.
+/// Scans all visible children for the maximum bottom edge (child y + child h
+/// + child margin bottom) and lets the control fit its height to it — by
+/// default that edge plus the control's own bottom padding.
 fn auto_size_height(controls: &mut Vec<Box<dyn Control>>, idx: usize, children: &[ControlId]) {
-    let pad = controls[idx].base().padding;
     let mut max_bottom = 0i32;
     for &child_id in children {
         if let Some(ci) = find_idx(controls, child_id) {
@@ -114,9 +118,7 @@ fn auto_size_height(controls: &mut Vec<Box<dyn Control>>, idx: usize, children:
             }
         }
     }
-    let content_h = (max_bottom + pad.bottom).max(0) as u32;
-    let w = controls[idx].base().w;
-    controls[idx].set_size(w, content_h);
+    controls[idx].fit_content_height(max_bottom);
 }
 
 /// Perform layout for a control and all its descendants.
@@ -126,13 +128,22 @@ pub fn perform_layout(controls: &mut Vec<Box<dyn Control>>, id: ControlId) {
         None => return,
     };
 
+    let virtualized = controls[idx].base().virtual_items.is_some();
     let children: Vec<ControlId> = controls[idx].base().children.to_vec();
     if children.is_empty() {
+        if virtualized {
+            crate::virtualize::fit_extent(controls, idx);
+        }
         return;
     }
 
     // Check if this control has a custom layout (StackPanel, FlowPanel, etc.)
-    let custom_layouts = controls[idx].layout_children(controls);
+    // Virtualized containers place their realized children by item index.
+    let custom_layouts = if virtualized {
+        Some(crate::virtualize::layout_children(controls, idx))
+    } else {
+        controls[idx].layout_children(controls)
+    };
     let used_standard_layout;
 
     if let Some(layouts) = custom_layouts {
@@ -177,13 +188,21 @@ pub fn perform_layout(controls: &mut Vec<Box<dyn Control>>, id: ControlId) {
         dock_layout(controls, idx, &children);
     }
 
-    // Auto-size this control's own height if needed (StackPanel always, or
-    // any control with auto_size = true).
+    // Auto-size this control's own height if needed (StackPanel and
+    // FormLayout always, or any control with auto_size = true).
     let idx = match find_idx(controls, id) {
         Some(i) => i,
         None => return,
     };
-    let should_auto_size = controls[idx].kind() == ControlKind::StackPanel
+    if virtualized {
+        // Sized to all items, not just the realized ones.
+        crate::virtualize::fit_extent(controls, idx);
+        return;
+    }
+    if controls[idx].kind() == ControlKind::StackPanel {
+        crate::controls::stack_panel::record_slots(controls, idx);
+    }
+    let should_auto_size = matches!(controls[idx].kind(), ControlKind::StackPanel | ControlKind::FormLayout)
         || controls[idx].base().auto_size;
     if should_auto_size {
         auto_size_height(controls, idx, &children);
@@ -59,17 +59,34 @@ impl core::fmt::Write for LogBuf {
     }
 }
 
+mod bidi;
+mod cluster;
 mod compositor;
 mod control;
 mod controls;
 pub mod draw;
 mod event_loop;
+mod event_source;
+mod frame_stats;
+mod image_loader;
 pub mod font_bitmap;
 mod layout;
 mod marshal;
+mod memory;
 pub mod syscall;
 mod timer;
+mod virtualize;
+mod pointer;
+mod gesture;
+mod selection;
+mod help;
+mod tag;
+mod style;
 mod dialogs;
+mod spell;
+mod pattern;
+mod validation;
+mod i18n;
 pub mod icons;
 pub mod theme;
 
@@ -79,6 +96,16 @@ use control::{Control, ControlId, ControlKind, Callback, DockStyle, Orientation}
 
 // ── Compositor window handle ─────────────────────────────────────────
 
+/// Present a window once the compositor has acknowledged its previous frame.
+pub const PRESENT_VSYNC: u32 = 0;
+/// Present a window as soon as it is rendered, without waiting for the
+/// previous frame's ACK. Lowest latency; the compositor may show a frame
+/// that is being overwritten (tearing).
+pub const PRESENT_IMMEDIATE: u32 = 1;
+/// Render and present a window only when the application calls
+/// `anyui_present()`. Changes accumulate in the dirty region meanwhile.
+pub const PRESENT_MANUAL: u32 = 2;
+
 /// Per-window compositor state (SHM surface + IDs).
 ///
 /// `width`/`height` are **physical** pixel dimensions (used for SHM surface
@@ -111,6 +138,26 @@ pub(crate) struct CompWindow {
     /// then a single memcpy to SHM before present() — the compositor never sees
     /// a half-rendered frame (no background flash, no partial content).
     pub back_buffer: Vec<u32>,
+    /// Snap region the compositor has tiled this window to (0 = floating).
+    pub snap_region: u32,
+    /// Cursor shape last sent to the compositor (`CURSOR_*`).
+    pub cursor: u32,
+    /// Per-window UI zoom in percent (0 = follow the system accessibility zoom).
+    pub zoom: u32,
+    /// Right-to-left mode: mirrored dock/stack layout and RTL default text
+    /// direction (see [`bidi`]).
+    pub rtl: bool,
+    /// How frames reach the compositor (`PRESENT_*`).
+    pub present_mode: u32,
+    /// Manual mode: `anyui_present()` was called and the frame is not out yet.
+    pub present_requested: bool,
+}
+
+impl CompWindow {
+    /// Zoom in effect for this window: its own, or the system UI zoom.
+    pub fn effective_zoom(&self) -> u32 {
+        if self.zoom != 0 { self.zoom } else { theme::system_zoom() }
+    }
 }
 
 // ── Context menu popup window ─────────────────────────────────────────
@@ -136,6 +183,14 @@ pub(crate) struct PopupInfo {
     /// If this popup was opened by a DropDown, its control ID.
     /// When the popup item is selected, the DropDown's state is updated.
     pub owner_dropdown: Option<ControlId>,
+    /// If this popup shows a SearchField's suggestions, its control ID.
+    /// Selecting an item replaces the field's text and submits it.
+    pub owner_search: Option<ControlId>,
+    /// If this popup is the spell-check menu of a text control, its control ID.
+    /// Items are the suggestions for `spell_word`, then Ignore / Add to Dictionary.
+    pub owner_spell: Option<ControlId>,
+    /// The misspelled word the spell-check menu was opened for.
+    pub spell_word: Vec<u8>,
 }
 
 // ── Global state (per-process, lives in .data/.bss of the .so) ───────
@@ -169,6 +224,9 @@ pub(crate) struct AnyuiState {
     pub click_count: u32,
     /// Which mouse button was pressed (for right-click detection).
     pub pressed_button: u32,
+    /// Focus scope stack: (root, focus before push). Keyboard navigation and
+    /// hit-testing in the root's window are confined to the topmost root.
+    pub focus_scopes: Vec<(ControlId, Option<ControlId>)>,
 
     // ── Tooltip ──────────────────────────────────────────────────────
     /// Framework-managed tooltip control ID (created lazily on first use).
@@ -180,6 +238,25 @@ pub(crate) struct AnyuiState {
 
     // ── Timers ───────────────────────────────────────────────────────
     pub timers: timer::TimerState,
+    /// Application event channels waited on by the event loop.
+    pub event_sources: event_source::EventSourceState,
+    /// Per-frame timing/render statistics, overlay and budget callback.
+    pub frame_stats: frame_stats::FrameStatsState,
+    /// Control to scroll into view after the next layout (see
+    /// `anyui_ensure_visible`).
+    pub ensure_visible: Option<ControlId>,
+    /// Memory pressure level, callback and low-memory mode (see [`memory`]).
+    pub memory: memory::MemoryState,
+    /// Animation timer shared by all in-window toasts (0 = not running).
+    pub toast_timer: u32,
+    /// Containers in virtualization mode (see [`virtualize`]).
+    pub virtualized: Vec<ControlId>,
+    /// Controls with validators (see [`validation`]).
+    pub validated: Vec<ControlId>,
+    /// Focused control as of the last validation pass (blur detection).
+    pub validation_focus: Option<ControlId>,
+    /// String tables and active language (see [`i18n`]).
+    pub i18n: i18n::I18nState,
 
     // ── Dirty tracking (push-based, avoids per-frame O(n) scans) ─────
     /// True when at least one control has been marked dirty since last render.
@@ -195,11 +272,38 @@ pub(crate) struct AnyuiState {
     /// Modifier flags from the most recent KEY_DOWN event.
     pub last_modifiers: u32,
 
+    // ── Pointer input (mouse, pen, touch) ────────────────────────────
+    /// Sample being dispatched; local to the control during its callbacks.
+    pub pointer: pointer::PointerEvent,
+    /// Touch contacts that are down and the two-finger gesture state.
+    pub touch: pointer::TouchState,
+    /// Press being watched by gesture recognizers.
+    pub gestures: gesture::GestureState,
+    /// Gesture behind the current callback (`anyui_get_gesture_info`).
+    pub gesture: gesture::GestureInfo,
+    /// Press driving a container's selection or rubber band.
+    pub selecting: selection::SelectState,
+    /// "What's this?" mode and the source of the current EVENT_HELP.
+    pub help: help::HelpState,
+
+    // ── Styles ───────────────────────────────────────────────────────
+    /// Named styles and their kind bindings (see [`style`]).
+    pub styles: style::StyleSheet,
+
     // ── Window lifecycle callbacks (for dock/system integration) ──────
     /// Callback for EVT_WINDOW_OPENED (0x0060). Called with (app_tid, 0x0060, userdata).
     pub on_window_opened: Option<(Callback, u64)>,
     /// Callback for EVT_WINDOW_CLOSED (0x0061). Called with (app_tid, 0x0061, userdata).
     pub on_window_closed: Option<(Callback, u64)>,
+    /// Callback for EVT_DOCK_BADGE (0x0062). Called with (app_tid, count, userdata).
+    pub on_dock_badge: Option<(Callback, u64)>,
+    /// Callback for EVT_THEME_CHANGED (0x0050). Called with (theme, 0x0050, userdata),
+    /// theme = 0 dark / 1 light, after the palettes have been reloaded.
+    pub on_theme_changed: Option<(Callback, u64)>,
+    /// Callback for EVT_MONITORS_CHANGED (0x0053). Called with (monitor_count, 0x0053, userdata).
+    pub on_monitors_changed: Option<(Callback, u64)>,
+    /// Callback for EVT_CLIPBOARD_CHANGED (0x0055). Called with (clipboard_seq, 0x0055, userdata).
+    pub on_clipboard_changed: Option<(Callback, u64)>,
 }
 
 /// Signal that at least one control needs repainting.
@@ -222,6 +326,16 @@ pub(crate) fn mark_needs_layout() {
     }
 }
 
+/// Signal that a control was created and may need styles applied.
+/// Called from `ControlBase::new()`.
+pub(crate) fn mark_needs_restyle() {
+    unsafe {
+        if let Some(ref mut st) = STATE {
+            st.styles.control_added();
+        }
+    }
+}
+
 static mut STATE: Option<AnyuiState> = None;
 
 pub(crate) fn state() -> &'static mut AnyuiState {
@@ -261,6 +375,7 @@ pub extern "C" fn anyui_init() -> u32 {
     // scale()/scale_i32() return correct values from the very first call
     // (before the event loop starts refreshing the cache every frame).
     theme::refresh_scale_cache();
+    theme::refresh_accessibility_cache();
 
     unsafe {
         STATE = Some(AnyuiState {
@@ -278,18 +393,40 @@ pub extern "C" fn anyui_init() -> u32 {
             last_click_tick: 0,
             click_count: 0,
             pressed_button: 0,
+            focus_scopes: Vec::new(),
             active_tooltip: None,
             popup: None,
             timers: timer::TimerState::new(),
+            event_sources: event_source::EventSourceState::new(),
+            frame_stats: frame_stats::FrameStatsState::new(),
+            ensure_visible: None,
+            memory: memory::MemoryState::new(),
+            toast_timer: 0,
+            virtualized: Vec::new(),
+            validated: Vec::new(),
+            validation_focus: None,
+            i18n: i18n::I18nState::new(),
             needs_repaint: true,
             needs_layout: true,
             last_keycode: 0,
             last_char_code: 0,
             last_modifiers: 0,
+            pointer: pointer::PointerEvent::default(),
+            touch: pointer::TouchState::new(),
+            gestures: gesture::GestureState::new(),
+            gesture: gesture::GestureInfo::default(),
+            selecting: selection::SelectState::new(),
+            help: help::HelpState::new(),
+            styles: style::StyleSheet::new(),
             on_window_opened: None,
             on_window_closed: None,
+            on_dock_badge: None,
+            on_theme_changed: None,
+            on_monitors_changed: None,
+            on_clipboard_changed: None,
         });
     }
+    event_source::set_wake_target(channel_id, sub_id);
     1
 }
 
@@ -305,6 +442,10 @@ pub extern "C" fn anyui_shutdown() {
     for cw in &st.comp_windows {
         compositor::destroy_window(channel_id, cw.window_id, cw.shm_id);
     }
+    for s in &st.event_sources.sources {
+        syscall::evt_chan_unsubscribe(s.channel_id, s.sub_id);
+    }
+    event_source::set_wake_target(0, 0);
     unsafe { STATE = None; }
 }
 
@@ -346,8 +487,11 @@ pub extern "C" fn anyui_create_window(
     // pass it through unscaled so the compositor detects it correctly.
     let phys_x = if x == -1 { -1 } else { crate::theme::scale_i32(x) };
     let phys_y = if y == -1 { -1 } else { crate::theme::scale_i32(y) };
+    // The system UI zoom enlarges new windows so the app keeps its logical size.
+    crate::theme::set_window_zoom(crate::theme::system_zoom());
     let phys_w = crate::theme::scale(w);
     let phys_h = crate::theme::scale(h);
+    crate::theme::set_window_zoom(100);
 
     // Create compositor window via DLL — physical pixel dimensions.
     // Set WIN_FLAG_DPI_AWARE (0x200) so the compositor knows this window
@@ -381,6 +525,12 @@ pub extern "C" fn anyui_create_window(
         dirty: true,
         dirty_rect: None,
         back_buffer: alloc::vec![0u32; pixel_count],
+        snap_region: 0,
+        cursor: control::CURSOR_ARROW,
+        zoom: 0,
+        rtl: false,
+        present_mode: PRESENT_VSYNC,
+        present_requested: false,
     });
     id
 }
@@ -485,6 +635,8 @@ pub extern "C" fn anyui_add_child(parent: ControlId, child: ControlId) {
 pub extern "C" fn anyui_set_text(id: ControlId, text: *const u8, len: u32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        // Explicit text replaces a localized one.
+        ctrl.base_mut().text_key = None;
         if !text.is_null() && len > 0 {
             let slice = unsafe { core::slice::from_raw_parts(text, len as usize) };
             ctrl.set_text(slice);
@@ -540,6 +692,7 @@ pub extern "C" fn anyui_set_visible(id: ControlId, visible: u32) {
 pub extern "C" fn anyui_set_color(id: ControlId, color: u32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        ctrl.base_mut().style.inline |= style::bit(style::PROP_COLOR);
         ctrl.set_color(color);
     }
 }
@@ -564,6 +717,7 @@ pub extern "C" fn anyui_get_state(id: ControlId) -> u32 {
 pub extern "C" fn anyui_set_padding(id: ControlId, left: i32, top: i32, right: i32, bottom: i32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        ctrl.base_mut().style.inline |= style::PADDING_BITS;
         ctrl.base_mut().padding = control::Padding { left, top, right, bottom };
         ctrl.base_mut().mark_dirty();
     }
@@ -574,6 +728,7 @@ pub extern "C" fn anyui_set_padding(id: ControlId, left: i32, top: i32, right: i
 pub extern "C" fn anyui_set_margin(id: ControlId, left: i32, top: i32, right: i32, bottom: i32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        ctrl.base_mut().style.inline |= style::MARGIN_BITS;
         ctrl.base_mut().margin = control::Margin { left, top, right, bottom };
         ctrl.base_mut().mark_dirty();
     }
@@ -617,6 +772,7 @@ pub extern "C" fn anyui_set_min_size(id: ControlId, min_w: u32, min_h: u32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
         let b = ctrl.base_mut();
+        b.style.inline |= style::bit(style::PROP_MIN_WIDTH) | style::bit(style::PROP_MIN_HEIGHT);
         b.min_w = min_w;
         b.min_h = min_h;
     }
@@ -638,6 +794,7 @@ pub extern "C" fn anyui_set_max_size(id: ControlId, max_w: u32, max_h: u32) {
 pub extern "C" fn anyui_set_font_size(id: ControlId, size: u32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        ctrl.base_mut().style.inline |= style::bit(style::PROP_FONT_SIZE);
         ctrl.set_font_size(size as u16);
     }
 }
@@ -653,6 +810,7 @@ pub extern "C" fn anyui_get_font_size(id: ControlId) -> u32 {
 pub extern "C" fn anyui_set_font(id: ControlId, font_id: u32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        ctrl.base_mut().style.inline |= style::bit(style::PROP_FONT_ID);
         if let Some(tb) = ctrl.text_base_mut() {
             tb.text_style.font_id = font_id as u16;
         }
@@ -663,12 +821,71 @@ pub extern "C" fn anyui_set_font(id: ControlId, font_id: u32) {
 pub extern "C" fn anyui_set_text_color(id: ControlId, color: u32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        ctrl.base_mut().style.inline |= style::bit(style::PROP_TEXT_COLOR);
         if let Some(tb) = ctrl.text_base_mut() {
             tb.text_style.text_color = color;
         }
     }
 }
 
+// ── Styles ───────────────────────────────────────────────────────────
+
+/// Create a named style, or get the id of the existing style with that
+/// name. Returns the style id (never 0), or 0 if `name` is empty.
+///
+/// A style applies to every control whose class list contains its name
+/// (`anyui_set_class`) and to the kinds it is bound to with
+/// `anyui_style_apply_kind`. See [`style`] for the cascade.
+#[no_mangle]
+pub extern "C" fn anyui_style_create(name: *const u8, len: u32) -> u32 {
+    if name.is_null() || len == 0 {
+        return 0;
+    }
+    let name = unsafe { core::slice::from_raw_parts(name, len as usize) };
+    state().styles.create(name)
+}
+
+/// Set a property (`PROP_*` in [`style`]) of a style. Colors are ARGB,
+/// paddings/margins signed logical pixels.
+#[no_mangle]
+pub extern "C" fn anyui_style_set(style_id: u32, prop: u32, value: u32) {
+    state().styles.set(style_id, prop, Some(value));
+}
+
+/// Remove a property from a style; controls get back the value they had
+/// before the style supplied it (unless another style supplies it).
+#[no_mangle]
+pub extern "C" fn anyui_style_unset(style_id: u32, prop: u32) {
+    state().styles.set(style_id, prop, None);
+}
+
+/// Apply `style_id` to every control of `kind` (`ControlKind` value);
+/// 0 removes the kind's style.
+#[no_mangle]
+pub extern "C" fn anyui_style_apply_kind(kind: u32, style_id: u32) {
+    state().styles.apply_kind(kind, style_id);
+}
+
+/// Set a control's class list: style names separated by spaces, applied
+/// in order after the kind style. An empty list removes all classes.
+#[no_mangle]
+pub extern "C" fn anyui_set_class(id: ControlId, name: *const u8, len: u32) {
+    let st = state();
+    let class = if !name.is_null() && len > 0 {
+        unsafe { core::slice::from_raw_parts(name, len as usize) }
+    } else {
+        &[]
+    };
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        let s = &mut ctrl.base_mut().style;
+        if s.class.as_slice() != class {
+            s.class.clear();
+            s.class.extend_from_slice(class);
+            st.styles.restyle(s);
+        }
+    }
+}
+
 // ── Layout orientation ───────────────────────────────────────────────
 
 #[no_mangle]
@@ -696,6 +913,209 @@ pub extern "C" fn anyui_set_orientation(id: ControlId, orientation: u32) {
     }
 }
 
+// ── StackPanel reordering ────────────────────────────────────────────
+
+fn with_stack_panel<R>(id: ControlId, f: impl FnOnce(&mut controls::stack_panel::StackPanel) -> R) -> Option<R> {
+    let st = state();
+    let ctrl = st.controls.iter_mut().find(|c| c.id() == id)?;
+    if ctrl.kind() != ControlKind::StackPanel {
+        return None;
+    }
+    let raw: *mut dyn Control = &mut **ctrl;
+    Some(f(unsafe { &mut *(raw as *mut controls::stack_panel::StackPanel) }))
+}
+
+/// Enable (1) or disable (0) dragging children to new positions by a
+/// handle strip at the leading edge of each row.
+#[no_mangle]
+pub extern "C" fn anyui_stackpanel_set_reorderable(id: ControlId, enabled: u32) {
+    with_stack_panel(id, |sp| {
+        sp.reorderable = enabled != 0;
+        sp.base.mark_dirty();
+    });
+    mark_needs_layout();
+}
+
+/// Set the width (vertical) or height (horizontal) of the drag handle strip.
+#[no_mangle]
+pub extern "C" fn anyui_stackpanel_set_handle_size(id: ControlId, size: u32) {
+    with_stack_panel(id, |sp| {
+        sp.handle_size = size;
+        sp.base.mark_dirty();
+    });
+    mark_needs_layout();
+}
+
+/// Move child `from` to index `to`, animating the other children aside.
+/// Does not fire EVENT_REORDER. Returns 1 on success, 0 if out of range.
+#[no_mangle]
+pub extern "C" fn anyui_stackpanel_move_child(id: ControlId, from: u32, to: u32) -> u32 {
+    with_stack_panel(id, |sp| sp.move_child(from as usize, to as usize)).unwrap_or(false) as u32
+}
+
+/// Old and new child index of the last drag reorder (valid in EVENT_REORDER).
+#[no_mangle]
+pub extern "C" fn anyui_stackpanel_get_last_reorder(id: ControlId, old_index: *mut u32, new_index: *mut u32) {
+    let (old, new) = with_stack_panel(id, |sp| sp.last_reorder).unwrap_or((0, 0));
+    unsafe {
+        if !old_index.is_null() { *old_index = old; }
+        if !new_index.is_null() { *new_index = new; }
+    }
+}
+
+// ── Virtualized containers ───────────────────────────────────────────
+
+/// Put a container into virtualization mode (see [`virtualize`]): it holds
+/// `count` items, each `item_height` along its stacking axis, and only the
+/// items near the visible area get a child, created or recycled by
+/// `provider(container, index, recycled, userdata)`. Existing children that
+/// are not provided items should be removed first. A null `provider` turns
+/// virtualization off; realized children stay and are laid out normally.
+/// Drag reordering of StackPanels is not available while virtualized.
+#[no_mangle]
+pub extern "C" fn anyui_set_virtualized(
+    id: ControlId,
+    item_height: u32,
+    count: u32,
+    provider: Option<virtualize::ItemProvider>,
+    userdata: u64,
+) {
+    let st = state();
+    let ctrl = match st.controls.iter_mut().find(|c| c.id() == id) {
+        Some(c) => c,
+        None => return,
+    };
+    let b = ctrl.base_mut();
+    match provider {
+        Some(cb) => {
+            match b.virtual_items.as_deref_mut() {
+                Some(v) => {
+                    v.item_height = item_height.max(1);
+                    v.count = count;
+                    v.provider = cb;
+                    v.userdata = userdata;
+                    v.refresh();
+                }
+                None => b.virtual_items = Some(Box::new(virtualize::Virtualization::new(item_height, count, cb, userdata))),
+            }
+            if !st.virtualized.contains(&id) {
+                st.virtualized.push(id);
+            }
+        }
+        None => {
+            // Spare children are no longer recycled; show them again so
+            // the application sees all of its children.
+            let spare = b.virtual_items.take().map(|v| v.spare).unwrap_or_default();
+            for c in spare {
+                if let Some(sc) = st.controls.iter_mut().find(|sc| sc.id() == c) {
+                    sc.base_mut().visible = true;
+                }
+            }
+            st.virtualized.retain(|&v| v != id);
+        }
+    }
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        ctrl.base_mut().mark_dirty();
+    }
+    mark_needs_layout();
+}
+
+/// Change the item count of a virtualized container. Items at or beyond
+/// the new count are hidden; new items are realized when they come into view.
+#[no_mangle]
+pub extern "C" fn anyui_virtual_set_count(id: ControlId, count: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(v) = ctrl.base_mut().virtual_items.as_deref_mut() {
+            v.count = count;
+            ctrl.base_mut().mark_dirty();
+            mark_needs_layout();
+        }
+    }
+}
+
+/// Rebind every realized item of a virtualized container on the next frame
+/// (the provider is called again for each, with its current child), e.g.
+/// after the underlying data changed.
+#[no_mangle]
+pub extern "C" fn anyui_virtual_refresh(id: ControlId) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(v) = ctrl.base_mut().virtual_items.as_deref_mut() {
+            v.refresh();
+            mark_needs_layout();
+        }
+    }
+}
+
+/// Child currently showing item `index` of a virtualized container, or 0
+/// if the item is not realized.
+#[no_mangle]
+pub extern "C" fn anyui_virtual_get_child(id: ControlId, index: u32) -> ControlId {
+    let st = state();
+    st.controls.iter()
+        .find(|c| c.id() == id)
+        .and_then(|c| c.base().virtual_items.as_deref())
+        .and_then(|v| v.child_for(index))
+        .unwrap_or(0)
+}
+
+// ── ScrollView ───────────────────────────────────────────────────────
+
+fn with_scroll_view<R>(id: ControlId, f: impl FnOnce(&mut controls::scroll_view::ScrollView) -> R) -> Option<R> {
+    let st = state();
+    let ctrl = st.controls.iter_mut().find(|c| c.id() == id)?;
+    if ctrl.kind() != ControlKind::ScrollView {
+        return None;
+    }
+    let raw: *mut dyn Control = &mut **ctrl;
+    Some(f(unsafe { &mut *(raw as *mut controls::scroll_view::ScrollView) }))
+}
+
+/// Scroll to `offset` (clamped to the content). With `animated` != 0 the
+/// content glides there and fires EVENT_SCROLL on the way; otherwise it
+/// jumps without an event.
+#[no_mangle]
+pub extern "C" fn anyui_scrollview_set_offset(id: ControlId, offset: u32, animated: u32) {
+    with_scroll_view(id, |sv| sv.scroll_to(offset, animated != 0));
+}
+
+/// Current vertical scroll offset in pixels.
+#[no_mangle]
+pub extern "C" fn anyui_scrollview_get_offset(id: ControlId) -> u32 {
+    with_scroll_view(id, |sv| sv.scroll_y.max(0) as u32).unwrap_or(0)
+}
+
+/// Scrollbar style: 0 = always visible (track and thumb), 1 = overlay thumb
+/// shown only while scrolling.
+#[no_mangle]
+pub extern "C" fn anyui_scrollview_set_scrollbar_style(id: ControlId, style: u32) {
+    with_scroll_view(id, |sv| {
+        sv.scrollbar_style = style.min(controls::scroll_view::SCROLLBAR_OVERLAY);
+        sv.base.mark_dirty();
+    });
+}
+
+/// Enable (1, default) or disable (0) scrolling by dragging the content,
+/// including the kinetic glide after release.
+#[no_mangle]
+pub extern "C" fn anyui_scrollview_set_kinetic(id: ControlId, enabled: u32) {
+    with_scroll_view(id, |sv| sv.kinetic = enabled != 0);
+}
+
+/// Offset, change since the previous EVENT_SCROLL, and largest possible
+/// offset (valid in EVENT_SCROLL).
+#[no_mangle]
+pub extern "C" fn anyui_scrollview_get_scroll_info(id: ControlId, offset: *mut u32, delta: *mut i32, max_offset: *mut u32) {
+    let (off, d, max) = with_scroll_view(id, |sv| (sv.scroll_y.max(0) as u32, sv.last_delta, sv.max_scroll() as u32))
+        .unwrap_or((0, 0, 0));
+    unsafe {
+        if !offset.is_null() { *offset = off; }
+        if !delta.is_null() { *delta = d; }
+        if !max_offset.is_null() { *max_offset = max; }
+    }
+}
+
 // ── TableLayout properties ───────────────────────────────────────────
 
 #[no_mangle]
@@ -744,6 +1164,42 @@ pub extern "C" fn anyui_set_column_widths(id: ControlId, widths: *const u32, len
     }
 }
 
+// ── FormLayout properties ────────────────────────────────────────────
+
+fn as_form_layout(ctrl: &mut alloc::boxed::Box<dyn Control>) -> Option<&mut controls::form_layout::FormLayout> {
+    if ctrl.kind() == ControlKind::FormLayout {
+        let raw: *mut dyn Control = &mut **ctrl;
+        Some(unsafe { &mut *(raw as *mut controls::form_layout::FormLayout) })
+    } else {
+        None
+    }
+}
+
+/// Set a fixed label column width for a FormLayout (0 = fit the widest label).
+#[no_mangle]
+pub extern "C" fn anyui_formlayout_set_label_width(id: ControlId, width: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(fl) = as_form_layout(ctrl) {
+            fl.label_width = width;
+        }
+    }
+    mark_needs_layout();
+}
+
+/// Set the gap between the label and field columns and between rows.
+#[no_mangle]
+pub extern "C" fn anyui_formlayout_set_spacing(id: ControlId, column_gap: u32, row_gap: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(fl) = as_form_layout(ctrl) {
+            fl.column_gap = column_gap;
+            fl.row_gap = row_gap;
+        }
+    }
+    mark_needs_layout();
+}
+
 // ── SplitView properties ─────────────────────────────────────────────
 
 /// Helper to downcast a control to SplitView.
@@ -875,169 +1331,462 @@ pub extern "C" fn anyui_textfield_select_all(id: ControlId) {
     }
 }
 
-// ── Canvas operations ────────────────────────────────────────────────
-
+/// Restrict typed input: 0 = any, 1 = digits only, 2 = decimal number.
 #[no_mangle]
-pub extern "C" fn anyui_canvas_set_pixel(id: ControlId, x: i32, y: i32, color: u32) {
+pub extern "C" fn anyui_textfield_set_filter(id: ControlId, mode: u32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        if ctrl.kind() == ControlKind::Canvas {
-            let raw: *mut dyn Control = &mut **ctrl;
-            let canvas = unsafe { &mut *(raw as *mut controls::canvas::Canvas) };
-            canvas.set_pixel(x, y, color);
+        if let Some(tf) = as_textfield(ctrl) {
+            tf.filter.mode = mode.min(controls::input_filter::FILTER_DECIMAL);
+            tf.refilter();
         }
     }
 }
 
+/// Decimal separator used by the decimal filter (e.g. b'.' or b',').
 #[no_mangle]
-pub extern "C" fn anyui_canvas_clear(id: ControlId, color: u32) {
+pub extern "C" fn anyui_textfield_set_decimal_separator(id: ControlId, sep: u32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        if ctrl.kind() == ControlKind::Canvas {
-            let raw: *mut dyn Control = &mut **ctrl;
-            let canvas = unsafe { &mut *(raw as *mut controls::canvas::Canvas) };
-            canvas.clear(color);
-            canvas.base.mark_dirty();
+        if let Some(tf) = as_textfield(ctrl) {
+            let sep = sep as u8;
+            if sep != tf.filter.decimal_sep {
+                let old = tf.filter.decimal_sep;
+                for b in tf.text_base.text.iter_mut() {
+                    if *b == old { *b = sep; }
+                }
+                tf.filter.decimal_sep = sep;
+                tf.text_base.base.mark_dirty();
+            }
         }
     }
 }
 
+/// Set an input mask: '#' digit, 'A' letter, '*' letter or digit, anything
+/// else is a literal inserted automatically. Empty clears the mask.
 #[no_mangle]
-pub extern "C" fn anyui_canvas_fill_rect(id: ControlId, x: i32, y: i32, w: u32, h: u32, color: u32) {
+pub extern "C" fn anyui_textfield_set_mask(id: ControlId, mask: *const u8, len: u32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        if ctrl.kind() == ControlKind::Canvas {
-            let raw: *mut dyn Control = &mut **ctrl;
-            let canvas = unsafe { &mut *(raw as *mut controls::canvas::Canvas) };
-            canvas.fill_rect(x, y, w, h, color);
+        if let Some(tf) = as_textfield(ctrl) {
+            tf.filter.mask.clear();
+            if !mask.is_null() && len > 0 {
+                tf.filter.mask.extend_from_slice(unsafe { core::slice::from_raw_parts(mask, len as usize) });
+            }
+            tf.refilter();
         }
     }
 }
 
+/// Maximum text length in bytes (0 = unlimited).
 #[no_mangle]
-pub extern "C" fn anyui_canvas_draw_line(id: ControlId, x0: i32, y0: i32, x1: i32, y1: i32, color: u32) {
+pub extern "C" fn anyui_textfield_set_max_length(id: ControlId, max_len: u32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        if ctrl.kind() == ControlKind::Canvas {
-            let raw: *mut dyn Control = &mut **ctrl;
-            let canvas = unsafe { &mut *(raw as *mut controls::canvas::Canvas) };
-            canvas.draw_line(x0, y0, x1, y1, color);
+        if let Some(tf) = as_textfield(ctrl) {
+            tf.filter.max_length = max_len;
+            tf.refilter();
         }
     }
 }
 
+/// Flag the field as invalid (red border) or clear the flag. The flag is
+/// also set by rejected input and cleared by the next accepted edit.
 #[no_mangle]
-pub extern "C" fn anyui_canvas_draw_rect(id: ControlId, x: i32, y: i32, w: u32, h: u32, color: u32, thickness: u32) {
+pub extern "C" fn anyui_textfield_set_invalid(id: ControlId, invalid: u32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        if ctrl.kind() == ControlKind::Canvas {
-            let raw: *mut dyn Control = &mut **ctrl;
-            let canvas = unsafe { &mut *(raw as *mut controls::canvas::Canvas) };
-            canvas.draw_rect(x, y, w, h, color, thickness);
+        if let Some(tf) = as_textfield(ctrl) {
+            tf.invalid = invalid != 0;
+            tf.text_base.base.mark_dirty();
         }
     }
 }
 
 #[no_mangle]
-pub extern "C" fn anyui_canvas_draw_circle(id: ControlId, cx: i32, cy: i32, radius: i32, color: u32) {
+pub extern "C" fn anyui_textfield_is_invalid(id: ControlId) -> u32 {
     let st = state();
-    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        if ctrl.kind() == ControlKind::Canvas {
-            let raw: *mut dyn Control = &mut **ctrl;
-            let canvas = unsafe { &mut *(raw as *mut controls::canvas::Canvas) };
-            canvas.draw_circle(cx, cy, radius, color);
-        }
+    match st.controls.iter_mut().find(|c| c.id() == id).and_then(|c| as_textfield(c)) {
+        Some(tf) => tf.invalid as u32,
+        None => 0,
     }
 }
 
+/// Veto the edit being validated. Only meaningful inside an EVENT_VALIDATE
+/// callback; the previous text is restored and no EVENT_CHANGE fires.
 #[no_mangle]
-pub extern "C" fn anyui_canvas_fill_circle(id: ControlId, cx: i32, cy: i32, radius: i32, color: u32) {
+pub extern "C" fn anyui_textfield_reject_edit(id: ControlId) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        if ctrl.kind() == ControlKind::Canvas {
-            let raw: *mut dyn Control = &mut **ctrl;
-            let canvas = unsafe { &mut *(raw as *mut controls::canvas::Canvas) };
-            canvas.fill_circle(cx, cy, radius, color);
+        if let Some(tf) = as_textfield(ctrl) {
+            tf.edit_rejected = true;
         }
     }
 }
 
+// ── TextArea properties ──────────────────────────────────────────────
+
+fn as_textarea(ctrl: &mut Box<dyn Control>) -> Option<&mut controls::textarea::TextArea> {
+    if ctrl.kind() == ControlKind::TextArea {
+        let raw: *mut dyn Control = &mut **ctrl;
+        Some(unsafe { &mut *(raw as *mut controls::textarea::TextArea) })
+    } else {
+        None
+    }
+}
+
 #[no_mangle]
-pub extern "C" fn anyui_canvas_get_buffer(id: ControlId) -> *mut u32 {
+pub extern "C" fn anyui_textarea_set_placeholder(id: ControlId, text: *const u8, len: u32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        if ctrl.kind() == ControlKind::Canvas {
-            let raw: *mut dyn Control = &mut **ctrl;
-            let canvas = unsafe { &mut *(raw as *mut controls::canvas::Canvas) };
-            return canvas.pixels.as_mut_ptr();
+        if let Some(ta) = as_textarea(ctrl) {
+            let new_text = if !text.is_null() && len > 0 {
+                unsafe { core::slice::from_raw_parts(text, len as usize) }
+            } else {
+                &[]
+            };
+            if ta.placeholder.as_slice() != new_text {
+                ta.placeholder.clear();
+                ta.placeholder.extend_from_slice(new_text);
+                ta.text_base.base.mark_dirty();
+            }
         }
     }
-    core::ptr::null_mut()
 }
 
+/// Maximum text length in bytes (0 = unlimited). Typing and pasting stop at
+/// the limit; text set programmatically is not truncated.
 #[no_mangle]
-pub extern "C" fn anyui_canvas_get_stride(id: ControlId) -> u32 {
+pub extern "C" fn anyui_textarea_set_max_length(id: ControlId, max_len: u32) {
     let st = state();
-    if let Some(ctrl) = st.controls.iter().find(|c| c.id() == id) {
-        if ctrl.kind() == ControlKind::Canvas {
-            return ctrl.base().w;
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(ta) = as_textarea(ctrl) {
+            ta.max_length = max_len;
         }
     }
-    0
 }
 
-/// Get the canvas height (in pixels).
+/// When enabled, Ctrl+Enter fires EVENT_SUBMIT instead of inserting a newline.
 #[no_mangle]
-pub extern "C" fn anyui_canvas_get_height(id: ControlId) -> u32 {
+pub extern "C" fn anyui_textarea_set_submit_on_ctrl_enter(id: ControlId, enabled: u32) {
     let st = state();
-    if let Some(ctrl) = st.controls.iter().find(|c| c.id() == id) {
-        if ctrl.kind() == ControlKind::Canvas {
-            return ctrl.base().h;
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(ta) = as_textarea(ctrl) {
+            ta.submit_on_ctrl_enter = enabled != 0;
         }
     }
-    0
 }
 
-// ── Canvas extensions (interactive, drawing primitives) ──────────────
+// ── SearchField properties ───────────────────────────────────────────
 
-fn as_canvas(ctrl: &mut Box<dyn Control>) -> Option<&mut controls::canvas::Canvas> {
-    if ctrl.kind() == ControlKind::Canvas {
+fn as_searchfield(ctrl: &mut Box<dyn Control>) -> Option<&mut controls::searchfield::SearchField> {
+    if ctrl.kind() == ControlKind::SearchField {
         let raw: *mut dyn Control = &mut **ctrl;
-        Some(unsafe { &mut *(raw as *mut controls::canvas::Canvas) })
-    } else {
-        None
-    }
-}
-
-fn as_canvas_ref(ctrl: &Box<dyn Control>) -> Option<&controls::canvas::Canvas> {
-    if ctrl.kind() == ControlKind::Canvas {
-        let raw: *const dyn Control = &**ctrl;
-        Some(unsafe { &*(raw as *const controls::canvas::Canvas) })
+        Some(unsafe { &mut *(raw as *mut controls::searchfield::SearchField) })
     } else {
         None
     }
 }
 
-/// Enable or disable interactive mode (mouse move fires EVENT_CHANGE).
+/// Delay EVENT_CHANGE until typing pauses for `ms` milliseconds (0 = off).
 #[no_mangle]
-pub extern "C" fn anyui_canvas_set_interactive(id: ControlId, enabled: u32) {
+pub extern "C" fn anyui_searchfield_set_debounce(id: ControlId, ms: u32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        if let Some(cv) = as_canvas(ctrl) {
-            cv.interactive = enabled != 0;
+        if let Some(sf) = as_searchfield(ctrl) {
+            sf.debounce_ms = ms;
         }
     }
 }
 
-/// Get last mouse position and button state. Returns via out pointers.
+/// Set the suggestions for the current text (pipe-separated, empty = none).
+/// They are shown in a popup below the field while it has focus.
 #[no_mangle]
-pub extern "C" fn anyui_canvas_get_mouse(id: ControlId, out_x: *mut i32, out_y: *mut i32, out_button: *mut u32) {
+pub extern "C" fn anyui_searchfield_set_suggestions(id: ControlId, data: *const u8, len: u32) {
     let st = state();
-    if let Some(ctrl) = st.controls.iter().find(|c| c.id() == id) {
-        if let Some(cv) = as_canvas_ref(ctrl) {
-            if !out_x.is_null() { unsafe { *out_x = cv.last_mouse_x; } }
-            if !out_y.is_null() { unsafe { *out_y = cv.last_mouse_y; } }
-            if !out_button.is_null() { unsafe { *out_button = cv.mouse_button; } }
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(sf) = as_searchfield(ctrl) {
+            let data = if !data.is_null() && len > 0 {
+                unsafe { core::slice::from_raw_parts(data, len as usize) }
+            } else {
+                &[]
+            };
+            sf.set_suggestions(data);
+        }
+    }
+}
+
+/// Copy the recent-query history (newest first, pipe-separated) into `buf`.
+/// Returns the number of bytes written.
+#[no_mangle]
+pub extern "C" fn anyui_searchfield_get_history(id: ControlId, buf: *mut u8, max_len: u32) -> u32 {
+    let st = state();
+    let sf = match st.controls.iter_mut().find(|c| c.id() == id).and_then(|c| as_searchfield(c)) {
+        Some(sf) => sf,
+        None => return 0,
+    };
+    let mut out: Vec<u8> = Vec::new();
+    for (i, h) in sf.history.iter().enumerate() {
+        if i > 0 { out.push(b'|'); }
+        out.extend_from_slice(h);
+    }
+    let copy_len = out.len().min(max_len as usize);
+    if !buf.is_null() && copy_len > 0 {
+        unsafe { core::ptr::copy_nonoverlapping(out.as_ptr(), buf, copy_len); }
+    }
+    copy_len as u32
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_searchfield_clear_history(id: ControlId) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(sf) = as_searchfield(ctrl) {
+            sf.clear_history();
+        }
+    }
+}
+
+// ── Stepper properties ───────────────────────────────────────────────
+
+fn as_stepper(ctrl: &mut Box<dyn Control>) -> Option<&mut controls::stepper::Stepper> {
+    if ctrl.kind() == ControlKind::Stepper {
+        let raw: *mut dyn Control = &mut **ctrl;
+        Some(unsafe { &mut *(raw as *mut controls::stepper::Stepper) })
+    } else {
+        None
+    }
+}
+
+/// Set the allowed value range (in units of the last decimal place).
+/// The current value is clamped into it.
+#[no_mangle]
+pub extern "C" fn anyui_stepper_set_range(id: ControlId, min: i32, max: i32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(sp) = as_stepper(ctrl) {
+            sp.set_range(min, max);
+        }
+    }
+}
+
+/// Set the increment applied per button press / arrow key (minimum 1).
+#[no_mangle]
+pub extern "C" fn anyui_stepper_set_step(id: ControlId, step: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(sp) = as_stepper(ctrl) {
+            sp.step = step.max(1);
+        }
+    }
+}
+
+/// Set the number of decimal places (0-6) used to display the value.
+#[no_mangle]
+pub extern "C" fn anyui_stepper_set_decimals(id: ControlId, places: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(sp) = as_stepper(ctrl) {
+            sp.set_decimals(places);
+        }
+    }
+}
+
+/// Set text shown before (`which` = 0) or after (`which` = 1) the value,
+/// e.g. "$" or " px".
+#[no_mangle]
+pub extern "C" fn anyui_stepper_set_affix(id: ControlId, which: u32, text: *const u8, len: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(sp) = as_stepper(ctrl) {
+            let text = if !text.is_null() && len > 0 {
+                unsafe { core::slice::from_raw_parts(text, len as usize) }
+            } else {
+                &[]
+            };
+            let target = if which == 0 { &mut sp.prefix } else { &mut sp.suffix };
+            target.clear();
+            target.extend_from_slice(text);
+            sp.text_base.base.mark_dirty();
+        }
+    }
+}
+
+// ── Canvas operations ────────────────────────────────────────────────
+
+#[no_mangle]
+pub extern "C" fn anyui_canvas_set_pixel(id: ControlId, x: i32, y: i32, color: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if ctrl.kind() == ControlKind::Canvas {
+            let raw: *mut dyn Control = &mut **ctrl;
+            let canvas = unsafe { &mut *(raw as *mut controls::canvas::Canvas) };
+            canvas.set_pixel(x, y, color);
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_canvas_clear(id: ControlId, color: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if ctrl.kind() == ControlKind::Canvas {
+            let raw: *mut dyn Control = &mut **ctrl;
+            let canvas = unsafe { &mut *(raw as *mut controls::canvas::Canvas) };
+            canvas.clear(color);
+            canvas.invalidate_all();
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_canvas_fill_rect(id: ControlId, x: i32, y: i32, w: u32, h: u32, color: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if ctrl.kind() == ControlKind::Canvas {
+            let raw: *mut dyn Control = &mut **ctrl;
+            let canvas = unsafe { &mut *(raw as *mut controls::canvas::Canvas) };
+            canvas.fill_rect(x, y, w, h, color);
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_canvas_draw_line(id: ControlId, x0: i32, y0: i32, x1: i32, y1: i32, color: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if ctrl.kind() == ControlKind::Canvas {
+            let raw: *mut dyn Control = &mut **ctrl;
+            let canvas = unsafe { &mut *(raw as *mut controls::canvas::Canvas) };
+            canvas.draw_line(x0, y0, x1, y1, color);
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_canvas_draw_rect(id: ControlId, x: i32, y: i32, w: u32, h: u32, color: u32, thickness: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if ctrl.kind() == ControlKind::Canvas {
+            let raw: *mut dyn Control = &mut **ctrl;
+            let canvas = unsafe { &mut *(raw as *mut controls::canvas::Canvas) };
+            canvas.draw_rect(x, y, w, h, color, thickness);
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_canvas_draw_circle(id: ControlId, cx: i32, cy: i32, radius: i32, color: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if ctrl.kind() == ControlKind::Canvas {
+            let raw: *mut dyn Control = &mut **ctrl;
+            let canvas = unsafe { &mut *(raw as *mut controls::canvas::Canvas) };
+            canvas.draw_circle(cx, cy, radius, color);
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_canvas_fill_circle(id: ControlId, cx: i32, cy: i32, radius: i32, color: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if ctrl.kind() == ControlKind::Canvas {
+            let raw: *mut dyn Control = &mut **ctrl;
+            let canvas = unsafe { &mut *(raw as *mut controls::canvas::Canvas) };
+            canvas.fill_circle(cx, cy, radius, color);
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_canvas_get_buffer(id: ControlId) -> *mut u32 {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if ctrl.kind() == ControlKind::Canvas {
+            let raw: *mut dyn Control = &mut **ctrl;
+            let canvas = unsafe { &mut *(raw as *mut controls::canvas::Canvas) };
+            return canvas.pixels.as_mut_ptr();
+        }
+    }
+    core::ptr::null_mut()
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_canvas_get_stride(id: ControlId) -> u32 {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter().find(|c| c.id() == id) {
+        if ctrl.kind() == ControlKind::Canvas {
+            return ctrl.base().w;
+        }
+    }
+    0
+}
+
+/// Get the canvas height (in pixels).
+#[no_mangle]
+pub extern "C" fn anyui_canvas_get_height(id: ControlId) -> u32 {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter().find(|c| c.id() == id) {
+        if ctrl.kind() == ControlKind::Canvas {
+            return ctrl.base().h;
+        }
+    }
+    0
+}
+
+// ── Canvas extensions (interactive, drawing primitives) ──────────────
+
+fn as_canvas(ctrl: &mut Box<dyn Control>) -> Option<&mut controls::canvas::Canvas> {
+    if ctrl.kind() == ControlKind::Canvas {
+        let raw: *mut dyn Control = &mut **ctrl;
+        Some(unsafe { &mut *(raw as *mut controls::canvas::Canvas) })
+    } else {
+        None
+    }
+}
+
+fn as_canvas_ref(ctrl: &Box<dyn Control>) -> Option<&controls::canvas::Canvas> {
+    if ctrl.kind() == ControlKind::Canvas {
+        let raw: *const dyn Control = &**ctrl;
+        Some(unsafe { &*(raw as *const controls::canvas::Canvas) })
+    } else {
+        None
+    }
+}
+
+/// Enable or disable interactive mode (mouse move fires EVENT_CHANGE).
+#[no_mangle]
+pub extern "C" fn anyui_canvas_set_interactive(id: ControlId, enabled: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(cv) = as_canvas(ctrl) {
+            cv.interactive = enabled != 0;
+        }
+    }
+}
+
+/// Get last mouse position and button state. Returns via out pointers.
+#[no_mangle]
+pub extern "C" fn anyui_canvas_get_mouse(id: ControlId, out_x: *mut i32, out_y: *mut i32, out_button: *mut u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter().find(|c| c.id() == id) {
+        if let Some(cv) = as_canvas_ref(ctrl) {
+            if !out_x.is_null() { unsafe { *out_x = cv.last_mouse_x; } }
+            if !out_y.is_null() { unsafe { *out_y = cv.last_mouse_y; } }
+            if !out_button.is_null() { unsafe { *out_button = cv.mouse_button; } }
+        }
+    }
+}
+
+/// Get the last pen/touch/mouse sample seen by the canvas (pressure, tilt,
+/// contact id; position local to the canvas). `out` points to a `PointerEvent`.
+#[no_mangle]
+pub extern "C" fn anyui_canvas_get_pointer(id: ControlId, out: *mut pointer::PointerEvent) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter().find(|c| c.id() == id) {
+        if let Some(cv) = as_canvas_ref(ctrl) {
+            if !out.is_null() { unsafe { *out = cv.last_pointer; } }
         }
     }
 }
@@ -1127,6 +1876,128 @@ pub extern "C" fn anyui_canvas_copy_to(id: ControlId, dst: *mut u32, len: u32) -
     0
 }
 
+/// Repaint only the region `(x, y, w, h)` of a canvas (canvas pixels)
+/// after drawing into it. Inside `begin_paint`/`end_paint` the region is
+/// collected and shown at `end_paint`.
+#[no_mangle]
+pub extern "C" fn anyui_canvas_invalidate_rect(id: ControlId, x: i32, y: i32, w: u32, h: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(cv) = as_canvas(ctrl) {
+            cv.invalidate_rect(x, y, w, h);
+        }
+    }
+}
+
+/// Enable (1) or disable (0) the canvas front buffer. While enabled the
+/// window shows the last presented frame, never a half-drawn one.
+#[no_mangle]
+pub extern "C" fn anyui_canvas_set_double_buffered(id: ControlId, enabled: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(cv) = as_canvas(ctrl) {
+            cv.set_double_buffered(enabled != 0);
+        }
+    }
+}
+
+/// Start an atomic canvas update; nothing drawn is shown until `end_paint`.
+#[no_mangle]
+pub extern "C" fn anyui_canvas_begin_paint(id: ControlId) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(cv) = as_canvas(ctrl) {
+            cv.begin_paint();
+        }
+    }
+}
+
+/// Finish an atomic canvas update and show the invalidated regions (the
+/// whole canvas if none were invalidated).
+#[no_mangle]
+pub extern "C" fn anyui_canvas_end_paint(id: ControlId) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(cv) = as_canvas(ctrl) {
+            cv.end_paint();
+        }
+    }
+}
+
+// ── NativeSurface ────────────────────────────────────────────────────
+
+fn as_native_surface(ctrl: &mut Box<dyn Control>) -> Option<&mut controls::native_surface::NativeSurface> {
+    if ctrl.kind() == ControlKind::NativeSurface {
+        let raw: *mut dyn Control = &mut **ctrl;
+        Some(unsafe { &mut *(raw as *mut controls::native_surface::NativeSurface) })
+    } else {
+        None
+    }
+}
+
+/// Allocate a `w`×`h` ARGB buffer for a NativeSurface and attach it to the
+/// compositor as a sub-surface of the control's window. Returns the buffer
+/// (valid until the next allocation or until the control is removed), or
+/// null if the control is not in a window yet or allocation failed.
+/// Calling it again with the same size returns the existing buffer.
+#[no_mangle]
+pub extern "C" fn anyui_native_surface_alloc(id: ControlId, w: u32, h: u32) -> *mut u32 {
+    let st = state();
+    if w == 0 || h == 0 || w > 0xFFFF || h > 0xFFFF {
+        return core::ptr::null_mut();
+    }
+    let Some(wi) = event_loop::window_index_of(st, id) else { return core::ptr::null_mut() };
+    let window_id = st.comp_windows[wi].window_id;
+    let channel_id = st.channel_id;
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(ns) = as_native_surface(ctrl) {
+            let same = ns.shm_id != 0 && ns.buf_w == w && ns.buf_h == h && ns.window_id == window_id;
+            if same || ns.alloc(channel_id, window_id, w, h) {
+                return ns.pixels;
+            }
+        }
+    }
+    core::ptr::null_mut()
+}
+
+/// Get a NativeSurface's buffer and its size (null / 0×0 if none).
+#[no_mangle]
+pub extern "C" fn anyui_native_surface_pixels(id: ControlId, out_w: *mut u32, out_h: *mut u32) -> *mut u32 {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(ns) = as_native_surface(ctrl) {
+            unsafe {
+                if !out_w.is_null() { *out_w = ns.buf_w; }
+                if !out_h.is_null() { *out_h = ns.buf_h; }
+            }
+            return ns.pixels;
+        }
+    }
+    core::ptr::null_mut()
+}
+
+/// Show the frame just rendered into a NativeSurface's buffer.
+#[no_mangle]
+pub extern "C" fn anyui_native_surface_present(id: ControlId) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(ns) = as_native_surface(ctrl) {
+            ns.present();
+        }
+    }
+}
+
+/// Free a NativeSurface's buffer and remove it from the compositor.
+#[no_mangle]
+pub extern "C" fn anyui_native_surface_release(id: ControlId) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(ns) = as_native_surface(ctrl) {
+            ns.release();
+        }
+    }
+}
+
 // ── ImageView ────────────────────────────────────────────────────────
 
 /// Set pixel data for an ImageView from a decoded ARGB buffer.
@@ -1146,70 +2017,314 @@ pub extern "C" fn anyui_imageview_set_pixels(id: ControlId, data: *const u32, w:
     }
 }
 
-/// Set the scale mode for an ImageView: 0=None, 1=Fit, 2=Fill, 3=Stretch.
+/// Set the scale mode for an ImageView: 0=None, 1=Fit, 2=Fill, 3=Stretch.
+#[no_mangle]
+pub extern "C" fn anyui_imageview_set_scale_mode(id: ControlId, mode: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if ctrl.kind() == ControlKind::ImageView {
+            let raw: *mut dyn Control = &mut **ctrl;
+            let iv = unsafe { &mut *(raw as *mut controls::image_view::ImageView) };
+            if iv.scale_mode != mode {
+                iv.scale_mode = mode;
+                iv.base.mark_dirty();
+            }
+        }
+    }
+}
+
+/// Get the original image dimensions. Returns via out pointers. Returns 1 on success.
+#[no_mangle]
+pub extern "C" fn anyui_imageview_get_image_size(id: ControlId, out_w: *mut u32, out_h: *mut u32) -> u32 {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter().find(|c| c.id() == id) {
+        if ctrl.kind() == ControlKind::ImageView {
+            let raw: *const dyn Control = &**ctrl;
+            let iv = unsafe { &*(raw as *const controls::image_view::ImageView) };
+            if !out_w.is_null() { unsafe { *out_w = iv.img_w; } }
+            if !out_h.is_null() { unsafe { *out_h = iv.img_h; } }
+            return 1;
+        }
+    }
+    0
+}
+
+/// Clear pixel data from an ImageView.
+#[no_mangle]
+pub extern "C" fn anyui_imageview_clear(id: ControlId) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if ctrl.kind() == ControlKind::ImageView {
+            let raw: *mut dyn Control = &mut **ctrl;
+            let iv = unsafe { &mut *(raw as *mut controls::image_view::ImageView) };
+            iv.clear();
+        }
+    }
+}
+
+/// Load an image file into an ImageView on the image loader thread.
+/// The view shows a spinner until the decoded pixels are applied on the UI
+/// thread, then fires EVENT_CHANGE; on failure it fires EVENT_ERROR (see
+/// `anyui_imageview_get_load_error`). Setting pixels, clearing or loading
+/// again cancels the pending load. Returns 1 if the load was queued.
+#[no_mangle]
+pub extern "C" fn anyui_imageview_load_file(id: ControlId, path: *const u8, path_len: u32) -> u32 {
+    if path.is_null() || path_len == 0 {
+        return 0;
+    }
+    let path = unsafe { core::slice::from_raw_parts(path, path_len as usize) };
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if ctrl.kind() == ControlKind::ImageView {
+            let raw: *mut dyn Control = &mut **ctrl;
+            let iv = unsafe { &mut *(raw as *mut controls::image_view::ImageView) };
+            return iv.load_file(path) as u32;
+        }
+    }
+    0
+}
+
+/// Error code of the ImageView's last failed file load: 0 = none, 1 = I/O,
+/// 2 = unrecognized format, 3 = decode error, 4 = out of memory.
+#[no_mangle]
+pub extern "C" fn anyui_imageview_get_load_error(id: ControlId) -> u32 {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter().find(|c| c.id() == id) {
+        if ctrl.kind() == ControlKind::ImageView {
+            let raw: *const dyn Control = &**ctrl;
+            let iv = unsafe { &*(raw as *const controls::image_view::ImageView) };
+            return iv.load_error;
+        }
+    }
+    0
+}
+
+// ── MarkdownView ─────────────────────────────────────────────────────
+
+fn markdown_view(st: &mut AnyuiState, id: ControlId) -> Option<&mut controls::markdown_view::MarkdownView> {
+    let ctrl = st.controls.iter_mut().find(|c| c.id() == id)?;
+    if ctrl.kind() != ControlKind::MarkdownView {
+        return None;
+    }
+    let raw: *mut dyn Control = &mut **ctrl;
+    Some(unsafe { &mut *(raw as *mut controls::markdown_view::MarkdownView) })
+}
+
+/// Provide decoded ARGB pixels for the image `src` referenced by a
+/// MarkdownView (`![alt](src)`). Returns 1 if the document uses it.
+#[no_mangle]
+pub extern "C" fn anyui_markdown_set_image(
+    id: ControlId, src: *const u8, src_len: u32, data: *const u32, w: u32, h: u32,
+) -> u32 {
+    let count = (w as usize) * (h as usize);
+    if src.is_null() || data.is_null() || count == 0 {
+        return 0;
+    }
+    let src = unsafe { core::slice::from_raw_parts(src, src_len as usize) };
+    let pixels = unsafe { core::slice::from_raw_parts(data, count) };
+    match markdown_view(state(), id) {
+        Some(mv) => mv.set_image(src, pixels, w, h) as u32,
+        None => 0,
+    }
+}
+
+/// Number of distinct images referenced by a MarkdownView's document.
+#[no_mangle]
+pub extern "C" fn anyui_markdown_image_count(id: ControlId) -> u32 {
+    markdown_view(state(), id).map_or(0, |mv| mv.image_sources().count() as u32)
+}
+
+/// Copy the source of image `index` into `buf`. Returns bytes written.
+#[no_mangle]
+pub extern "C" fn anyui_markdown_image_src(id: ControlId, index: u32, buf: *mut u8, max_len: u32) -> u32 {
+    if buf.is_null() {
+        return 0;
+    }
+    let mv = match markdown_view(state(), id) {
+        Some(mv) => mv,
+        None => return 0,
+    };
+    match mv.image_sources().nth(index as usize) {
+        Some(src) => {
+            let n = src.len().min(max_len as usize);
+            unsafe { core::ptr::copy_nonoverlapping(src.as_ptr(), buf, n); }
+            n as u32
+        }
+        None => 0,
+    }
+}
+
+/// Copy the URL of the last clicked link into `buf`. Returns bytes written.
+#[no_mangle]
+pub extern "C" fn anyui_markdown_get_link(id: ControlId, buf: *mut u8, max_len: u32) -> u32 {
+    if buf.is_null() {
+        return 0;
+    }
+    match markdown_view(state(), id) {
+        Some(mv) => {
+            let n = mv.clicked_link.len().min(max_len as usize);
+            unsafe { core::ptr::copy_nonoverlapping(mv.clicked_link.as_ptr(), buf, n); }
+            n as u32
+        }
+        None => 0,
+    }
+}
+
+// ── IconButton ───────────────────────────────────────────────────────
+
+/// Set pre-rendered icon pixel data for an IconButton.
+#[no_mangle]
+pub extern "C" fn anyui_iconbutton_set_pixels(id: ControlId, data: *const u32, w: u32, h: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if ctrl.kind() == ControlKind::IconButton {
+            let count = (w as usize) * (h as usize);
+            if !data.is_null() && count > 0 {
+                let slice = unsafe { core::slice::from_raw_parts(data, count) };
+                let raw: *mut dyn Control = &mut **ctrl;
+                let ib = unsafe { &mut *(raw as *mut controls::icon_button::IconButton) };
+                ib.set_icon_pixels(slice, w, h);
+            }
+        }
+    }
+}
+
+// ── ProgressBar ──────────────────────────────────────────────────────
+
+fn as_progress_bar(ctrl: &mut alloc::boxed::Box<dyn Control>) -> Option<&mut controls::progress_bar::ProgressBar> {
+    if ctrl.kind() == ControlKind::ProgressBar {
+        let raw: *mut dyn Control = &mut **ctrl;
+        Some(unsafe { &mut *(raw as *mut controls::progress_bar::ProgressBar) })
+    } else {
+        None
+    }
+}
+
+/// Set the display mode (0 = normal, 1 = indeterminate, 2 = paused, 3 = error).
+/// Indeterminate mode animates from the event loop until the mode changes.
+#[no_mangle]
+pub extern "C" fn anyui_progressbar_set_mode(id: ControlId, mode: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(pb) = as_progress_bar(ctrl) {
+            pb.set_mode(mode);
+        }
+    }
+}
+
+/// Get the display mode.
+#[no_mangle]
+pub extern "C" fn anyui_progressbar_get_mode(id: ControlId) -> u32 {
+    let st = state();
+    st.controls.iter_mut().find(|c| c.id() == id)
+        .and_then(|c| as_progress_bar(c))
+        .map_or(0, |pb| pb.mode)
+}
+
+/// Set the buffered (secondary) value, 0-100, drawn behind the main fill.
+#[no_mangle]
+pub extern "C" fn anyui_progressbar_set_buffer(id: ControlId, value: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(pb) = as_progress_bar(ctrl) {
+            pb.buffer = value.min(100);
+            pb.base.mark_dirty();
+        }
+    }
+}
+
+/// Split the bar into `count` blocks (0 or 1 = continuous fill).
+#[no_mangle]
+pub extern "C" fn anyui_progressbar_set_segments(id: ControlId, count: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(pb) = as_progress_bar(ctrl) {
+            pb.segments = count.min(100);
+            pb.base.mark_dirty();
+        }
+    }
+}
+
+// ── CircularProgress / ActivitySpinner ───────────────────────────────
+
+fn as_circular_progress(ctrl: &mut alloc::boxed::Box<dyn Control>) -> Option<&mut controls::circular_progress::CircularProgress> {
+    if ctrl.kind() == ControlKind::CircularProgress {
+        let raw: *mut dyn Control = &mut **ctrl;
+        Some(unsafe { &mut *(raw as *mut controls::circular_progress::CircularProgress) })
+    } else {
+        None
+    }
+}
+
+fn as_activity_spinner(ctrl: &mut alloc::boxed::Box<dyn Control>) -> Option<&mut controls::activity_spinner::ActivitySpinner> {
+    if ctrl.kind() == ControlKind::ActivitySpinner {
+        let raw: *mut dyn Control = &mut **ctrl;
+        Some(unsafe { &mut *(raw as *mut controls::activity_spinner::ActivitySpinner) })
+    } else {
+        None
+    }
+}
+
+/// Set the ring width in logical pixels (0 = proportional to the size).
+/// Applies to CircularProgress and ActivitySpinner.
 #[no_mangle]
-pub extern "C" fn anyui_imageview_set_scale_mode(id: ControlId, mode: u32) {
+pub extern "C" fn anyui_ring_set_thickness(id: ControlId, thickness: u32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        if ctrl.kind() == ControlKind::ImageView {
-            let raw: *mut dyn Control = &mut **ctrl;
-            let iv = unsafe { &mut *(raw as *mut controls::image_view::ImageView) };
-            if iv.scale_mode != mode {
-                iv.scale_mode = mode;
-                iv.base.mark_dirty();
-            }
+        if let Some(cp) = as_circular_progress(ctrl) {
+            cp.thickness = thickness.min(64);
+            cp.base.mark_dirty();
+        } else if let Some(sp) = as_activity_spinner(ctrl) {
+            sp.thickness = thickness.min(64);
+            sp.base.mark_dirty();
         }
     }
 }
 
-/// Get the original image dimensions. Returns via out pointers. Returns 1 on success.
+/// Show or hide the percentage text of a CircularProgress.
 #[no_mangle]
-pub extern "C" fn anyui_imageview_get_image_size(id: ControlId, out_w: *mut u32, out_h: *mut u32) -> u32 {
+pub extern "C" fn anyui_circularprogress_set_show_text(id: ControlId, show: u32) {
     let st = state();
-    if let Some(ctrl) = st.controls.iter().find(|c| c.id() == id) {
-        if ctrl.kind() == ControlKind::ImageView {
-            let raw: *const dyn Control = &**ctrl;
-            let iv = unsafe { &*(raw as *const controls::image_view::ImageView) };
-            if !out_w.is_null() { unsafe { *out_w = iv.img_w; } }
-            if !out_h.is_null() { unsafe { *out_h = iv.img_h; } }
-            return 1;
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(cp) = as_circular_progress(ctrl) {
+            cp.show_text = show != 0;
+            cp.base.mark_dirty();
         }
     }
-    0
 }
 
-/// Clear pixel data from an ImageView.
+/// Set the track (unfilled ring) color of a CircularProgress (0 = theme).
 #[no_mangle]
-pub extern "C" fn anyui_imageview_clear(id: ControlId) {
+pub extern "C" fn anyui_circularprogress_set_track_color(id: ControlId, color: u32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        if ctrl.kind() == ControlKind::ImageView {
-            let raw: *mut dyn Control = &mut **ctrl;
-            let iv = unsafe { &mut *(raw as *mut controls::image_view::ImageView) };
-            iv.clear();
+        if let Some(cp) = as_circular_progress(ctrl) {
+            cp.track_color = color;
+            cp.base.mark_dirty();
         }
     }
 }
 
-// ── IconButton ───────────────────────────────────────────────────────
-
-/// Set pre-rendered icon pixel data for an IconButton.
+/// Start (1) or stop (0) an ActivitySpinner. A stopped spinner is not drawn.
 #[no_mangle]
-pub extern "C" fn anyui_iconbutton_set_pixels(id: ControlId, data: *const u32, w: u32, h: u32) {
+pub extern "C" fn anyui_spinner_set_running(id: ControlId, running: u32) {
     let st = state();
     if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        if ctrl.kind() == ControlKind::IconButton {
-            let count = (w as usize) * (h as usize);
-            if !data.is_null() && count > 0 {
-                let slice = unsafe { core::slice::from_raw_parts(data, count) };
-                let raw: *mut dyn Control = &mut **ctrl;
-                let ib = unsafe { &mut *(raw as *mut controls::icon_button::IconButton) };
-                ib.set_icon_pixels(slice, w, h);
-            }
+        if let Some(sp) = as_activity_spinner(ctrl) {
+            sp.set_running(running != 0);
         }
     }
 }
 
+/// Returns 1 if the ActivitySpinner is running.
+#[no_mangle]
+pub extern "C" fn anyui_spinner_is_running(id: ControlId) -> u32 {
+    let st = state();
+    st.controls.iter_mut().find(|c| c.id() == id)
+        .and_then(|c| as_activity_spinner(c))
+        .map_or(0, |sp| sp.is_running() as u32)
+}
+
 // ── DataGrid ─────────────────────────────────────────────────────────
 
 fn as_data_grid(ctrl: &mut alloc::boxed::Box<dyn Control>) -> Option<&mut controls::data_grid::DataGrid> {
@@ -1306,6 +2421,46 @@ pub extern "C" fn anyui_datagrid_set_cell(id: ControlId, row: u32, col: u32, tex
     }
 }
 
+#[no_mangle]
+pub extern "C" fn anyui_datagrid_insert_row(id: ControlId, row: u32, data: *const u8, len: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(dg) = as_data_grid(ctrl) {
+            let slice = if !data.is_null() && len > 0 {
+                unsafe { core::slice::from_raw_parts(data, len as usize) }
+            } else {
+                &[]
+            };
+            dg.insert_row(row as usize, slice);
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_datagrid_set_row(id: ControlId, row: u32, data: *const u8, len: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(dg) = as_data_grid(ctrl) {
+            let slice = if !data.is_null() && len > 0 {
+                unsafe { core::slice::from_raw_parts(data, len as usize) }
+            } else {
+                &[]
+            };
+            dg.set_row(row as usize, slice);
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_datagrid_remove_row(id: ControlId, row: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(dg) = as_data_grid(ctrl) {
+            dg.remove_row(row as usize);
+        }
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn anyui_datagrid_get_cell(id: ControlId, row: u32, col: u32, buf: *mut u8, max_len: u32) -> u32 {
     let st = state();
@@ -2008,97 +3163,403 @@ pub extern "C" fn anyui_treeview_clear(id: ControlId) {
 }
 
 #[no_mangle]
-pub extern "C" fn anyui_treeview_get_node_count(id: ControlId) -> u32 {
-    let st = state();
-    if let Some(ctrl) = st.controls.iter().find(|c| c.id() == id) {
-        if let Some(tv) = as_tree_view_ref(ctrl) {
-            return tv.node_count() as u32;
-        }
-    }
-    0
+pub extern "C" fn anyui_treeview_get_node_count(id: ControlId) -> u32 {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter().find(|c| c.id() == id) {
+        if let Some(tv) = as_tree_view_ref(ctrl) {
+            return tv.node_count() as u32;
+        }
+    }
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_treeview_set_indent_width(id: ControlId, width: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(tv) = as_tree_view(ctrl) {
+            let w = width.max(8);
+            if tv.indent_width != w {
+                tv.indent_width = w;
+                tv.base.mark_dirty();
+            }
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_treeview_set_row_height(id: ControlId, height: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(tv) = as_tree_view(ctrl) {
+            let h = height.max(16);
+            if tv.row_height != h {
+                tv.row_height = h;
+                tv.base.mark_dirty();
+            }
+        }
+    }
+}
+
+// ── Spell checking ──────────────────────────────────────────────────
+
+/// Register the process-wide spell checker (null `check` removes it).
+///
+/// `check(word, len, userdata)` returns 1 if the word is spelled correctly.
+/// `suggest(word, len, out, out_cap, userdata)` (may be null) writes
+/// pipe-separated replacements into `out` and returns the byte count.
+/// Only TextFields / TextEditors with `anyui_set_spell_check` enabled are checked.
+#[no_mangle]
+pub extern "C" fn anyui_set_spell_checker(
+    check: Option<spell::CheckFn>,
+    suggest: Option<spell::SuggestFn>,
+    userdata: u64,
+) {
+    spell::set_checker(check, suggest, userdata);
+    let st = state();
+    for c in st.controls.iter_mut() {
+        if matches!(c.kind(), ControlKind::TextField | ControlKind::TextEditor) {
+            c.base_mut().mark_dirty();
+        }
+    }
+}
+
+/// Enable (1) or disable (0) spell checking on a TextField or TextEditor.
+#[no_mangle]
+pub extern "C" fn anyui_set_spell_check(id: ControlId, enabled: u32) {
+    let st = state();
+    let new_val = enabled != 0;
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        if let Some(tf) = as_textfield(ctrl) {
+            if tf.spell_check != new_val {
+                tf.spell_check = new_val;
+                tf.text_base.base.mark_dirty();
+            }
+        } else if let Some(te) = as_text_editor(ctrl) {
+            if te.spell_check != new_val {
+                te.spell_check = new_val;
+                te.base.mark_dirty();
+            }
+        }
+    }
+}
+
+/// Copy the word of the most recent EVENT_SPELL_IGNORE / EVENT_SPELL_ADD
+/// into `buf`. Returns the number of bytes written.
+#[no_mangle]
+pub extern "C" fn anyui_spell_get_word(buf: *mut u8, max_len: u32) -> u32 {
+    let w = spell::action_word();
+    let copy_len = w.len().min(max_len as usize);
+    if !buf.is_null() && copy_len > 0 {
+        unsafe {
+            core::ptr::copy_nonoverlapping(w.as_ptr(), buf, copy_len);
+        }
+    }
+    copy_len as u32
+}
+
+// ── Callbacks ────────────────────────────────────────────────────────
+
+/// Register a callback for a specific event type on a control.
+///
+/// Event types: EVENT_CLICK=1, EVENT_CHANGE=2, EVENT_KEY=3, EVENT_FOCUS=4,
+/// EVENT_BLUR=5, EVENT_CLOSE=6, EVENT_RESIZE=7, EVENT_SCROLL=8,
+/// EVENT_DRAG=9, EVENT_CONTEXT_MENU=10, EVENT_DOUBLE_CLICK=11,
+/// EVENT_MOUSE_ENTER=12, EVENT_MOUSE_LEAVE=13, EVENT_MOUSE_DOWN=14,
+/// EVENT_MOUSE_UP=15, EVENT_MOUSE_MOVE=16
+#[no_mangle]
+pub extern "C" fn anyui_on_event(id: ControlId, event_type: u32, cb: Callback, userdata: u64) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        ctrl.set_event_callback(event_type, cb, userdata);
+    }
+}
+
+// Convenience wrappers (backward compat)
+#[no_mangle]
+pub extern "C" fn anyui_on_click(id: ControlId, cb: Callback, userdata: u64) {
+    anyui_on_event(id, control::EVENT_CLICK, cb, userdata);
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_on_change(id: ControlId, cb: Callback, userdata: u64) {
+    anyui_on_event(id, control::EVENT_CHANGE, cb, userdata);
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_on_submit(id: ControlId, cb: Callback, userdata: u64) {
+    anyui_on_event(id, control::EVENT_SUBMIT, cb, userdata);
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_set_context_menu(id: ControlId, menu_id: ControlId) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        ctrl.base_mut().context_menu = Some(menu_id);
+    }
+}
+
+/// Set tooltip text for a control. Pass empty text (len=0) to remove.
+#[no_mangle]
+pub extern "C" fn anyui_set_tooltip(id: ControlId, text: *const u8, len: u32) {
+    let st = state();
+    let bytes = if len > 0 && !text.is_null() {
+        unsafe { core::slice::from_raw_parts(text, len as usize) }.to_vec()
+    } else {
+        Vec::new()
+    };
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        ctrl.base_mut().tooltip_text = bytes;
+    }
+}
+
+// ── Badge overlays ───────────────────────────────────────────────────
+
+fn set_badge_text(id: ControlId, text: Vec<u8>, color: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        let b = ctrl.base_mut();
+        b.badge = if text.is_empty() {
+            None
+        } else {
+            let corner = b.badge.as_ref().map_or(controls::badge::CORNER_TOP_RIGHT, |o| o.corner);
+            Some(controls::badge::BadgeOverlay { text, color, corner })
+        };
+        b.mark_dirty();
+    }
+}
+
+/// Attach a badge with `text` to a corner of any control (empty = remove).
+/// `color` is the pill color (0 = theme badge red).
+#[no_mangle]
+pub extern "C" fn anyui_set_badge(id: ControlId, text: *const u8, len: u32, color: u32) {
+    let bytes = if len > 0 && !text.is_null() {
+        unsafe { core::slice::from_raw_parts(text, len as usize) }.to_vec()
+    } else {
+        Vec::new()
+    };
+    set_badge_text(id, bytes, color);
+}
+
+/// Attach a numeric badge to a control (0 = remove, above 99 shows "99+").
+#[no_mangle]
+pub extern "C" fn anyui_set_badge_count(id: ControlId, count: u32, color: u32) {
+    let text = if count == 0 { Vec::new() } else { controls::badge::BadgeOverlay::count_text(count) };
+    set_badge_text(id, text, color);
+}
+
+/// Choose the corner a control's badge is anchored to
+/// (0 = top-right, 1 = top-left, 2 = bottom-right, 3 = bottom-left).
+#[no_mangle]
+pub extern "C" fn anyui_set_badge_corner(id: ControlId, corner: u32) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        let b = ctrl.base_mut();
+        if let Some(badge) = b.badge.as_mut() {
+            badge.corner = corner.min(controls::badge::CORNER_BOTTOM_LEFT);
+            b.mark_dirty();
+        }
+    }
+}
+
+// ── Form validation ─────────────────────────────────────────────────
+
+fn bytes_arg<'a>(ptr: *const u8, len: u32) -> &'a [u8] {
+    if len > 0 && !ptr.is_null() {
+        unsafe { core::slice::from_raw_parts(ptr, len as usize) }
+    } else {
+        &[]
+    }
+}
+
+/// Require a value: non-blank text for text controls, a non-zero state for
+/// the others. `msg` is the error shown when it is missing.
+/// Returns 0 if the control does not exist.
+#[no_mangle]
+pub extern "C" fn anyui_validate_required(id: ControlId, msg: *const u8, msg_len: u32) -> u32 {
+    validation::add_rule(id, validation::Check::Required, bytes_arg(msg, msg_len)) as u32
+}
+
+/// Require a number within `min..=max` (`.` or `,` as decimal separator;
+/// an empty value passes). Returns 0 if the control does not exist.
+#[no_mangle]
+pub extern "C" fn anyui_validate_range(id: ControlId, min: f64, max: f64, msg: *const u8, msg_len: u32) -> u32 {
+    validation::add_rule(id, validation::Check::Range { min, max }, bytes_arg(msg, msg_len)) as u32
+}
+
+/// Require the whole value to match a regular expression (an empty value
+/// passes). Returns 0 if the pattern is malformed or the control does not exist.
+#[no_mangle]
+pub extern "C" fn anyui_validate_pattern(
+    id: ControlId,
+    pattern: *const u8,
+    pattern_len: u32,
+    msg: *const u8,
+    msg_len: u32,
+) -> u32 {
+    let re = match core::str::from_utf8(bytes_arg(pattern, pattern_len)).ok().and_then(pattern::Regex::new) {
+        Some(re) => re,
+        None => return 0,
+    };
+    validation::add_rule(id, validation::Check::Pattern(re), bytes_arg(msg, msg_len)) as u32
+}
+
+/// Validate with an application callback: `cb(id, value, len, userdata)`
+/// returns 1 if the value is valid. Returns 0 if the control does not exist.
+#[no_mangle]
+pub extern "C" fn anyui_validate_custom(
+    id: ControlId,
+    cb: validation::ValidateFn,
+    userdata: u64,
+    msg: *const u8,
+    msg_len: u32,
+) -> u32 {
+    validation::add_rule(id, validation::Check::Custom { cb, userdata }, bytes_arg(msg, msg_len)) as u32
+}
+
+/// Remove all validators of a control and clear its error.
+#[no_mangle]
+pub extern "C" fn anyui_validate_clear(id: ControlId) {
+    validation::clear(id);
+}
+
+/// Validate one control now. Returns 1 if it is valid.
+#[no_mangle]
+pub extern "C" fn anyui_validate_control(id: ControlId) -> u32 {
+    validation::validate(id) as u32
+}
+
+/// Validate every enabled, visible control with validators under `root_id`
+/// (e.g. when a form is submitted) and show their errors. Returns the first
+/// invalid control in tree order, or 0 if all are valid.
+#[no_mangle]
+pub extern "C" fn anyui_form_validate(root_id: ControlId) -> ControlId {
+    validation::validate_tree(root_id)
 }
 
+/// Copy the current error message of a control into `buf`. Returns the
+/// number of bytes written (0 = valid or not validated yet).
 #[no_mangle]
-pub extern "C" fn anyui_treeview_set_indent_width(id: ControlId, width: u32) {
+pub extern "C" fn anyui_get_validation_error(id: ControlId, buf: *mut u8, max_len: u32) -> u32 {
     let st = state();
-    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        if let Some(tv) = as_tree_view(ctrl) {
-            let w = width.max(8);
-            if tv.indent_width != w {
-                tv.indent_width = w;
-                tv.base.mark_dirty();
-            }
+    let err = match st.controls.iter().find(|c| c.id() == id)
+        .and_then(|c| c.base().validation.as_ref())
+        .and_then(|v| v.error.as_ref())
+    {
+        Some(e) => e,
+        None => return 0,
+    };
+    let copy_len = err.len().min(max_len as usize);
+    if !buf.is_null() && copy_len > 0 {
+        unsafe {
+            core::ptr::copy_nonoverlapping(err.as_ptr(), buf, copy_len);
         }
     }
+    copy_len as u32
 }
 
+// ── Localization ────────────────────────────────────────────────────
+
+/// Load a `.lang` file (`key = value` lines) into the string table of
+/// language `lang` (e.g. `"de"`). Several files may be loaded per language;
+/// later keys win. The first language loaded is the fallback for missing
+/// keys. Returns 0 if the file cannot be read.
 #[no_mangle]
-pub extern "C" fn anyui_treeview_set_row_height(id: ControlId, height: u32) {
-    let st = state();
-    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        if let Some(tv) = as_tree_view(ctrl) {
-            let h = height.max(16);
-            if tv.row_height != h {
-                tv.row_height = h;
-                tv.base.mark_dirty();
-            }
-        }
-    }
+pub extern "C" fn anyui_lang_load(lang: *const u8, lang_len: u32, path: *const u8, path_len: u32) -> u32 {
+    let Ok(path) = core::str::from_utf8(bytes_arg(path, path_len)) else { return 0 };
+    i18n::load(bytes_arg(lang, lang_len), path) as u32
 }
 
-// ── Callbacks ────────────────────────────────────────────────────────
+/// Switch the active language: every control with a text key gets its new
+/// text, windows are laid out again and the language-changed callback
+/// runs. Returns 0 if no file was loaded for `lang`.
+#[no_mangle]
+pub extern "C" fn anyui_set_language(lang: *const u8, lang_len: u32) -> u32 {
+    i18n::set_language(bytes_arg(lang, lang_len)) as u32
+}
 
-/// Register a callback for a specific event type on a control.
-///
-/// Event types: EVENT_CLICK=1, EVENT_CHANGE=2, EVENT_KEY=3, EVENT_FOCUS=4,
-/// EVENT_BLUR=5, EVENT_CLOSE=6, EVENT_RESIZE=7, EVENT_SCROLL=8,
-/// EVENT_DRAG=9, EVENT_CONTEXT_MENU=10, EVENT_DOUBLE_CLICK=11,
-/// EVENT_MOUSE_ENTER=12, EVENT_MOUSE_LEAVE=13, EVENT_MOUSE_DOWN=14,
-/// EVENT_MOUSE_UP=15, EVENT_MOUSE_MOVE=16
+/// Copy the active language code into `buf`. Returns its length (0 = none).
 #[no_mangle]
-pub extern "C" fn anyui_on_event(id: ControlId, event_type: u32, cb: Callback, userdata: u64) {
-    let st = state();
-    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        ctrl.set_event_callback(event_type, cb, userdata);
-    }
+pub extern "C" fn anyui_get_language(buf: *mut u8, max_len: u32) -> u32 {
+    let i18n = &state().i18n;
+    let code = match i18n.active {
+        Some(i) => &i18n.languages[i].code[..],
+        None => &[][..],
+    };
+    copy_out(code, buf, max_len)
 }
 
-// Convenience wrappers (backward compat)
+/// Register a callback run after the language was switched, called with
+/// (0, 0, userdata). Keyed texts are already updated; apps use it for texts
+/// they build themselves.
 #[no_mangle]
-pub extern "C" fn anyui_on_click(id: ControlId, cb: Callback, userdata: u64) {
-    anyui_on_event(id, control::EVENT_CLICK, cb, userdata);
+pub extern "C" fn anyui_on_language_changed(cb: Callback, userdata: u64) {
+    state().i18n.on_language_changed = Some((cb, userdata));
 }
 
+/// Set a control's text from the string table and keep it in sync with
+/// the active language. `args` holds `\0`-separated arguments that replace
+/// `{0}`, `{1}`, ... in the translation (empty = none). A key without a
+/// translation is shown as is. Returns 0 if the control does not exist.
 #[no_mangle]
-pub extern "C" fn anyui_on_change(id: ControlId, cb: Callback, userdata: u64) {
-    anyui_on_event(id, control::EVENT_CHANGE, cb, userdata);
+pub extern "C" fn anyui_set_text_key(
+    id: ControlId,
+    key: *const u8,
+    key_len: u32,
+    args: *const u8,
+    args_len: u32,
+) -> u32 {
+    let args = i18n::split_args(bytes_arg(args, args_len));
+    i18n::set_text_key(id, bytes_arg(key, key_len), args) as u32
 }
 
+/// Translate `key` (with `\0`-separated `args` as in `anyui_set_text_key`)
+/// into `buf`. Returns the number of bytes written.
 #[no_mangle]
-pub extern "C" fn anyui_on_submit(id: ControlId, cb: Callback, userdata: u64) {
-    anyui_on_event(id, control::EVENT_SUBMIT, cb, userdata);
+pub extern "C" fn anyui_tr(
+    key: *const u8,
+    key_len: u32,
+    args: *const u8,
+    args_len: u32,
+    buf: *mut u8,
+    max_len: u32,
+) -> u32 {
+    let args = i18n::split_args(bytes_arg(args, args_len));
+    let text = state().i18n.resolve(bytes_arg(key, key_len), &args);
+    copy_out(&text, buf, max_len)
 }
 
+/// Format a number with `decimals` fraction digits and the active locale's
+/// decimal mark and thousands separator. Returns the bytes written.
 #[no_mangle]
-pub extern "C" fn anyui_set_context_menu(id: ControlId, menu_id: ControlId) {
-    let st = state();
-    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        ctrl.base_mut().context_menu = Some(menu_id);
-    }
+pub extern "C" fn anyui_format_number(value: f64, decimals: u32, buf: *mut u8, max_len: u32) -> u32 {
+    copy_out(&i18n::format_number(value, decimals), buf, max_len)
 }
 
-/// Set tooltip text for a control. Pass empty text (len=0) to remove.
+/// Format a date with the active locale's `@date` pattern. Returns the
+/// bytes written.
 #[no_mangle]
-pub extern "C" fn anyui_set_tooltip(id: ControlId, text: *const u8, len: u32) {
-    let st = state();
-    let bytes = if len > 0 && !text.is_null() {
-        unsafe { core::slice::from_raw_parts(text, len as usize) }.to_vec()
-    } else {
-        Vec::new()
-    };
-    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
-        ctrl.base_mut().tooltip_text = bytes;
+pub extern "C" fn anyui_format_date(year: u32, month: u32, day: u32, buf: *mut u8, max_len: u32) -> u32 {
+    let dt = i18n::DateTime { year, month, day, ..Default::default() };
+    copy_out(&i18n::format_datetime(&dt, false), buf, max_len)
+}
+
+/// Format a time of day with the active locale's `@time` pattern. Returns
+/// the bytes written.
+#[no_mangle]
+pub extern "C" fn anyui_format_time(hour: u32, minute: u32, second: u32, buf: *mut u8, max_len: u32) -> u32 {
+    let dt = i18n::DateTime { hour, minute, second, ..Default::default() };
+    copy_out(&i18n::format_datetime(&dt, true), buf, max_len)
+}
+
+fn copy_out(data: &[u8], buf: *mut u8, max_len: u32) -> u32 {
+    let copy_len = data.len().min(max_len as usize);
+    if !buf.is_null() && copy_len > 0 {
+        unsafe {
+            core::ptr::copy_nonoverlapping(data.as_ptr(), buf, copy_len);
+        }
     }
+    copy_len as u32
 }
 
 // ── MessageBox ───────────────────────────────────────────────────────
@@ -2218,6 +3679,8 @@ pub extern "C" fn anyui_message_box(
         b.set_event_callback(control::EVENT_CLICK, msgbox_ok_clicked, 0);
     }
 
+    anyui_push_focus_scope(overlay_id);
+
     // Mini event loop — block until dismissed
     unsafe { MSGBOX_DISMISSED = false; }
     while !unsafe { MSGBOX_DISMISSED } {
@@ -2228,6 +3691,7 @@ pub extern "C" fn anyui_message_box(
     }
 
     // Clean up — remove overlay and all descendants
+    anyui_pop_focus_scope();
     anyui_remove(overlay_id);
 }
 
@@ -2280,6 +3744,184 @@ pub extern "C" fn anyui_quit() {
     state().quit_requested = true;
 }
 
+/// Break the event loop out of its wait so it runs a frame promptly.
+/// Callable from any thread.
+#[no_mangle]
+pub extern "C" fn anyui_wakeup() {
+    event_source::wakeup();
+}
+
+// ── Event sources ────────────────────────────────────────────────────
+
+/// Service an event channel from the event loop: the loop's wait includes
+/// `channel_id`, and each event on it invokes `cb(source_id, event_type, userdata)`
+/// on the UI thread. Returns a source ID (>0), or 0 if too many sources exist.
+#[no_mangle]
+pub extern "C" fn anyui_add_event_source(
+    channel_id: u32,
+    cb: control::Callback,
+    userdata: u64,
+) -> u32 {
+    state().event_sources.add(channel_id, cb, userdata)
+}
+
+/// Remove an event source and unsubscribe from its channel.
+#[no_mangle]
+pub extern "C" fn anyui_remove_event_source(source_id: u32) {
+    state().event_sources.remove(source_id);
+}
+
+/// Copy the five words of the event being dispatched to a source callback
+/// into `buf`. Returns 5, or 0 if `buf` is null.
+#[no_mangle]
+pub extern "C" fn anyui_event_source_data(buf: *mut u32) -> u32 {
+    if buf.is_null() { return 0; }
+    let ev = state().event_sources.current;
+    unsafe { core::ptr::copy_nonoverlapping(ev.as_ptr(), buf, 5); }
+    5
+}
+
+// ── Screen capture ───────────────────────────────────────────────────
+
+/// Physical pixel size of what `anyui_capture_window` / `anyui_capture_control`
+/// would return for `id`: the window's back buffer for a window, the control's
+/// scaled size otherwise. Returns 1 on success, 0 if `id` doesn't exist.
+#[no_mangle]
+pub extern "C" fn anyui_capture_size(id: ControlId, out_w: *mut u32, out_h: *mut u32) -> u32 {
+    let st = state();
+    let (w, h) = if let Some(wi) = st.windows.iter().position(|&w| w == id) {
+        (st.comp_windows[wi].width, st.comp_windows[wi].height)
+    } else if let Some(ctrl) = st.controls.iter().find(|c| c.id() == id) {
+        let (w, h) = ctrl.size();
+        (crate::theme::scale(w), crate::theme::scale(h))
+    } else {
+        return 0;
+    };
+    if !out_w.is_null() { unsafe { *out_w = w; } }
+    if !out_h.is_null() { unsafe { *out_h = h; } }
+    1
+}
+
+/// Copy window `win_id`'s back buffer — the last rendered frame of its whole
+/// control tree, in physical ARGB pixels, row-major — into `buf`.
+/// Returns the pixel count of the capture (width × height, see
+/// `anyui_capture_size`); nothing is copied unless `len` is at least that.
+/// Returns 0 if `win_id` is not a window or is hidden with its back buffer
+/// released (see `anyui_trim_memory`).
+#[no_mangle]
+pub extern "C" fn anyui_capture_window(win_id: ControlId, buf: *mut u32, len: u32) -> u32 {
+    let st = state();
+    let Some(wi) = st.windows.iter().position(|&w| w == win_id) else { return 0 };
+    let pixels = &st.comp_windows[wi].back_buffer;
+    if !buf.is_null() && len as usize >= pixels.len() {
+        unsafe { core::ptr::copy_nonoverlapping(pixels.as_ptr(), buf, pixels.len()); }
+    }
+    pixels.len() as u32
+}
+
+/// Render control `id` and its children offscreen at the control's own size
+/// and copy the result (physical ARGB pixels, row-major, transparent where
+/// nothing was painted) into `buf`. The control need not be on screen; an
+/// invisible control captures as fully transparent.
+/// Returns the pixel count of the capture; nothing is copied unless `len` is
+/// at least that. Returns 0 if `id` doesn't exist or has an empty size.
+#[no_mangle]
+pub extern "C" fn anyui_capture_control(id: ControlId, buf: *mut u32, len: u32) -> u32 {
+    let st = state();
+    let Some((w, h)) = st.controls.iter().find(|c| c.id() == id).map(|c| c.size()) else { return 0 };
+    let count = crate::theme::scale(w) * crate::theme::scale(h);
+    if buf.is_null() || len < count {
+        return count;
+    }
+    match event_loop::render_offscreen(&st.controls, id) {
+        Some((_, _, pixels)) => {
+            unsafe { core::ptr::copy_nonoverlapping(pixels.as_ptr(), buf, pixels.len()); }
+            pixels.len() as u32
+        }
+        None => 0,
+    }
+}
+
+// ── Frame statistics ─────────────────────────────────────────────────
+
+/// Copy the last rendered frame's statistics into `buf` (up to `max_words` u32s).
+/// Word order: frame, layout_ms, render_ms, present_wait_ms, dirty_area (physical
+/// pixels), controls_drawn, frame_ms, over_budget. Returns the words written.
+#[no_mangle]
+pub extern "C" fn anyui_get_frame_stats(buf: *mut u32, max_words: u32) -> u32 {
+    if buf.is_null() { return 0; }
+    let words = state().frame_stats.last.to_words();
+    let n = (max_words as usize).min(frame_stats::STATS_WORDS);
+    unsafe { core::ptr::copy_nonoverlapping(words.as_ptr(), buf, n); }
+    n as u32
+}
+
+/// Show (1) or hide (0) the frame statistics overlay in every window.
+#[no_mangle]
+pub extern "C" fn anyui_set_stats_overlay(enabled: u32) {
+    let st = state();
+    let enabled = enabled != 0;
+    if st.frame_stats.overlay != enabled {
+        st.frame_stats.overlay = enabled;
+        // Full repaint: shows the overlay at once, or erases it.
+        event_loop::mark_all_windows_dirty(st);
+    }
+}
+
+/// Set a frame budget: `cb(frame_ms, frame_number, userdata)` fires after any
+/// frame whose layout + render time exceeds `budget_ms`. 0 removes the budget.
+#[no_mangle]
+pub extern "C" fn anyui_set_frame_budget(budget_ms: u32, cb: Callback, userdata: u64) {
+    let hook = if budget_ms > 0 { Some((cb, userdata)) } else { None };
+    state().frame_stats.set_budget(budget_ms, hook);
+}
+
+// ── Memory budget ────────────────────────────────────────────────────
+
+/// Copy the current UI memory usage into `buf` (up to `max_words` u32s).
+/// Word order: back_buffers, shm_surfaces, canvases, images (image views,
+/// markdown images, icons), total — all in bytes — then the last memory
+/// pressure level. Returns the words written.
+#[no_mangle]
+pub extern "C" fn anyui_get_memory_usage(buf: *mut u32, max_words: u32) -> u32 {
+    if buf.is_null() { return 0; }
+    let words = memory::usage(state());
+    let n = (max_words as usize).min(memory::USAGE_WORDS);
+    unsafe { core::ptr::copy_nonoverlapping(words.as_ptr(), buf, n); }
+    n as u32
+}
+
+/// Register a callback for system memory pressure changes.
+/// Receives (level, 0x0056, userdata); level is 0 = none, 1 = low, 2 = critical.
+#[no_mangle]
+pub extern "C" fn anyui_on_memory_pressure(cb: Callback, userdata: u64) {
+    state().memory.on_pressure = Some((cb, userdata));
+}
+
+/// Last memory pressure level broadcast by the compositor.
+#[no_mangle]
+pub extern "C" fn anyui_get_memory_pressure() -> u32 {
+    state().memory.pressure
+}
+
+/// Drop rebuildable caches (unused images, slack in image buffers) and the
+/// back buffers of hidden windows. Returns the bytes freed.
+#[no_mangle]
+pub extern "C" fn anyui_trim_memory() -> u32 {
+    memory::trim(state()).min(u32::MAX as usize) as u32
+}
+
+/// Enable (1) or disable (0) low-memory mode: hidden windows release their
+/// back buffers every frame, and memory pressure trims automatically.
+#[no_mangle]
+pub extern "C" fn anyui_set_low_memory_mode(enabled: u32) {
+    let st = state();
+    st.memory.low_memory = enabled != 0;
+    if st.memory.low_memory {
+        memory::release_hidden_buffers(st);
+    }
+}
+
 // ── Timers ───────────────────────────────────────────────────────────
 
 /// Register a periodic timer. Returns a timer ID (>0).
@@ -2311,11 +3953,29 @@ pub extern "C" fn anyui_remove(id: ControlId) {
     collect_descendants(st, id, &mut to_remove);
     to_remove.push(id);
 
+    // Validation message bubbles live under the window; they go too.
+    let bubbles: Vec<ControlId> = st.controls.iter()
+        .filter(|c| to_remove.contains(&c.id()))
+        .filter_map(|c| c.base().validation.as_ref().and_then(|v| v.message_id))
+        .collect();
+    for bid in bubbles {
+        if let Some(p) = st.controls.iter().find(|c| c.id() == bid).map(|c| c.parent_id()) {
+            if let Some(pc) = st.controls.iter_mut().find(|c| c.id() == p) {
+                pc.remove_child(bid);
+                pc.base_mut().mark_dirty();
+            }
+        }
+        to_remove.push(bid);
+    }
+
     // Clear tracking for removed controls
+    st.focus_scopes.retain(|&(root, _)| !to_remove.contains(&root));
     for &rid in &to_remove {
         if st.focused == Some(rid) { st.focused = None; }
         if st.pressed == Some(rid) { st.pressed = None; }
         if st.hovered == Some(rid) { st.hovered = None; }
+        st.touch.forget(rid);
+        st.gestures.forget(rid);
     }
 
     // Remove from parent's children
@@ -2326,7 +3986,9 @@ pub extern "C" fn anyui_remove(id: ControlId) {
         }
     }
 
+    let drops = tag::take_destructors(&mut st.controls, &to_remove);
     st.controls.retain(|c| !to_remove.contains(&c.id()));
+    tag::run_destructors(drops);
 }
 
 /// Remove a specific child from a parent container and destroy it.
@@ -2367,10 +4029,13 @@ pub extern "C" fn anyui_clear_children(parent: ControlId) {
     }
 
     // Clear tracking for removed controls
+    st.focus_scopes.retain(|&(root, _)| !to_remove.contains(&root));
     for &rid in &to_remove {
         if st.focused == Some(rid) { st.focused = None; }
         if st.pressed == Some(rid) { st.pressed = None; }
         if st.hovered == Some(rid) { st.hovered = None; }
+        st.touch.forget(rid);
+        st.gestures.forget(rid);
     }
 
     // Clear parent's children list
@@ -2379,7 +4044,9 @@ pub extern "C" fn anyui_clear_children(parent: ControlId) {
     }
 
     // Remove all collected controls
+    let drops = tag::take_destructors(&mut st.controls, &to_remove);
     st.controls.retain(|c| !to_remove.contains(&c.id()));
+    tag::run_destructors(drops);
 }
 
 /// Programmatically resize a window (SHM buffer, back buffer, control size).
@@ -2390,11 +4057,13 @@ pub extern "C" fn anyui_clear_children(parent: ControlId) {
 #[no_mangle]
 pub extern "C" fn anyui_resize_window(win_id: ControlId, new_w: u32, new_h: u32) {
     let st = state();
-    // Convert logical → physical for the compositor surface.
-    let phys_w = crate::theme::scale(new_w);
-    let phys_h = crate::theme::scale(new_h);
     if let Some(wi) = st.windows.iter().position(|&w| w == win_id) {
         let cw = &mut st.comp_windows[wi];
+        // Convert logical → physical for the compositor surface.
+        crate::theme::set_window_zoom(cw.effective_zoom());
+        let phys_w = crate::theme::scale(new_w);
+        let phys_h = crate::theme::scale(new_h);
+        crate::theme::set_window_zoom(100);
         if cw.logical_width == new_w && cw.logical_height == new_h {
             return;
         }
@@ -2408,8 +4077,11 @@ pub extern "C" fn anyui_resize_window(win_id: ControlId, new_w: u32, new_h: u32)
         cw.height = phys_h;
         cw.logical_width = new_w;
         cw.logical_height = new_h;
-        let new_count = (phys_w as usize) * (phys_h as usize);
-        cw.back_buffer.resize(new_count, 0);
+        // A released back buffer is reallocated by the next render.
+        if !cw.back_buffer.is_empty() {
+            let new_count = (phys_w as usize) * (phys_h as usize);
+            cw.back_buffer.resize(new_count, 0);
+        }
         cw.dirty = true;
         cw.dirty_rect = None; // full redraw
     }
@@ -2443,6 +4115,59 @@ pub extern "C" fn anyui_move_window(win_id: ControlId, x: i32, y: i32) {
     }
 }
 
+/// Tile a window to a screen region via the compositor (CMD_SNAP_WINDOW).
+/// region: 0 = restore, 1 = left half, 2 = right half, 3-6 = top-left,
+/// top-right, bottom-left, bottom-right quarter, 7 = maximized.
+/// The window receives EVENT_RESIZE and then EVENT_SNAP once applied.
+#[no_mangle]
+pub extern "C" fn anyui_snap_window(win_id: ControlId, region: u32) {
+    let st = state();
+    if let Some(wi) = st.windows.iter().position(|&w| w == win_id) {
+        let comp_win_id = st.comp_windows[wi].window_id;
+        let cmd: [u32; 5] = [0x1018, comp_win_id, region, 0, 0]; // CMD_SNAP_WINDOW
+        syscall::evt_chan_emit(st.channel_id, &cmd);
+    }
+}
+
+/// Current snap region of a window (0 = floating).
+#[no_mangle]
+pub extern "C" fn anyui_get_snap_region(win_id: ControlId) -> u32 {
+    let st = state();
+    match st.windows.iter().position(|&w| w == win_id) {
+        Some(wi) => st.comp_windows[wi].snap_region,
+        None => 0,
+    }
+}
+
+/// Make window `win_id` a child dialog of window `owner_id` (0 = detach).
+/// The compositor centers it over the owner, keeps it stacked above the
+/// owner and minimizes / restores the two together (CMD_SET_WINDOW_OWNER).
+/// Move the window afterwards to place it elsewhere.
+#[no_mangle]
+pub extern "C" fn anyui_set_window_owner(win_id: ControlId, owner_id: ControlId) {
+    let st = state();
+    let Some(wi) = st.windows.iter().position(|&w| w == win_id) else { return };
+    let owner_comp_id = match st.windows.iter().position(|&w| w == owner_id) {
+        Some(oi) => st.comp_windows[oi].window_id,
+        None if owner_id == 0 => 0,
+        None => return,
+    };
+    let cmd: [u32; 5] = [0x101C, st.comp_windows[wi].window_id, owner_comp_id, 0, 0]; // CMD_SET_WINDOW_OWNER
+    syscall::evt_chan_emit(st.channel_id, &cmd);
+}
+
+/// Set (`on` != 0) or clear the always-on-top flag of a window after
+/// creation (CMD_SET_ALWAYS_ON_TOP).
+#[no_mangle]
+pub extern "C" fn anyui_set_always_on_top(win_id: ControlId, on: u32) {
+    let st = state();
+    if let Some(wi) = st.windows.iter().position(|&w| w == win_id) {
+        let comp_win_id = st.comp_windows[wi].window_id;
+        let cmd: [u32; 5] = [0x101D, comp_win_id, (on != 0) as u32, 0, 0]; // CMD_SET_ALWAYS_ON_TOP
+        syscall::evt_chan_emit(st.channel_id, &cmd);
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn anyui_destroy_window(win_id: ControlId) {
     let st = state();
@@ -2483,6 +4208,48 @@ pub extern "C" fn anyui_set_blur_behind(id: ControlId, radius: u32) {
     }
 }
 
+// ── Cursor ──────────────────────────────────────────────────────────
+
+/// Set the cursor shape shown while the mouse is over a control and its
+/// children (`CURSOR_*`; CURSOR_DEFAULT restores the inherited/built-in one).
+#[no_mangle]
+pub extern "C" fn anyui_set_cursor(id: ControlId, cursor_kind: u32) {
+    let st = state();
+    if let Some(idx) = control::find_idx(&st.controls, id) {
+        st.controls[idx].base_mut().style.inline |= style::bit(style::PROP_CURSOR);
+        st.controls[idx].base_mut().cursor = cursor_kind;
+        event_loop::refresh_cursor_for(st, id);
+    }
+}
+
+// ── Owner draw ──────────────────────────────────────────────────────
+
+/// Set (or with a null `cb`, remove) a control's owner-draw callback.
+///
+/// After the control renders itself, `cb(id, info, userdata)` receives the
+/// visible part of the control in the back buffer together with its stride,
+/// state flags and the theme palette, and can paint over it — e.g. custom
+/// buttons or list rows. Children are drawn afterwards, on top.
+#[no_mangle]
+pub extern "C" fn anyui_set_custom_draw(id: ControlId, cb: Option<control::CustomDrawCallback>, userdata: u64) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        let b = ctrl.base_mut();
+        b.custom_draw = cb.map(|cb| control::CustomDraw { cb, userdata });
+        b.mark_dirty();
+    }
+}
+
+/// Repaint a control on the next frame, e.g. after the data an owner-draw
+/// callback paints from has changed.
+#[no_mangle]
+pub extern "C" fn anyui_invalidate(id: ControlId) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        ctrl.base_mut().mark_dirty();
+    }
+}
+
 // ── Focus management ────────────────────────────────────────────────
 
 /// Programmatically set keyboard focus to a control.
@@ -2497,25 +4264,154 @@ pub extern "C" fn anyui_set_focus(id: ControlId) {
             }
         }
     }
-    // Focus the new control
-    if let Some(idx) = control::find_idx(&st.controls, id) {
-        st.controls[idx].handle_focus();
-        st.focused = Some(id);
+    // Focus the new control
+    if let Some(idx) = control::find_idx(&st.controls, id) {
+        st.controls[idx].handle_focus();
+        st.focused = Some(id);
+    }
+}
+
+/// Scroll every ScrollView enclosing control `id` just far enough to show
+/// it, innermost first, keeping clear of each ScrollView's top and bottom
+/// padding. Runs after the next layout, so it also works for controls
+/// created in the same callback. Tab navigation does this automatically.
+#[no_mangle]
+pub extern "C" fn anyui_ensure_visible(id: ControlId) {
+    let st = state();
+    if control::find_idx(&st.controls, id).is_some() {
+        st.ensure_visible = Some(id);
+    }
+}
+
+/// Confine keyboard navigation and hit-testing of `root_id`'s window to the
+/// subtree under `root_id` (for app-built modal overlays). Focus moves to the
+/// first focusable control inside the scope. Scopes nest; undo with
+/// `anyui_pop_focus_scope`. Returns 0 if `root_id` does not exist.
+#[no_mangle]
+pub extern "C" fn anyui_push_focus_scope(root_id: ControlId) -> u32 {
+    let st = state();
+    if control::find_idx(&st.controls, root_id).is_none() { return 0; }
+    st.focus_scopes.push((root_id, st.focused));
+    let inside = st.focused.map_or(false, |f| control::is_within(&st.controls, f, root_id));
+    if !inside {
+        match event_loop::focus_order(st, root_id).first() {
+            Some(&first) => anyui_set_focus(first),
+            None => {
+                if let Some(old_id) = st.focused.take() {
+                    if let Some(idx) = control::find_idx(&st.controls, old_id) {
+                        st.controls[idx].handle_blur();
+                        st.controls[idx].base_mut().mark_dirty();
+                    }
+                }
+            }
+        }
+    }
+    1
+}
+
+/// Remove the topmost focus scope and restore the focus it replaced.
+#[no_mangle]
+pub extern "C" fn anyui_pop_focus_scope() {
+    let st = state();
+    if let Some((_, Some(prev_id))) = st.focus_scopes.pop() {
+        if control::find_idx(&st.controls, prev_id).is_some() {
+            anyui_set_focus(prev_id);
+        }
+    }
+}
+
+/// Set the tab focus order index for a control.
+/// Controls with lower tab_index receive focus first when Tab is pressed.
+/// The index is cascaded: parent tab_index sorts first, then child tab_index.
+/// Default is 0 (insertion order).
+#[no_mangle]
+pub extern "C" fn anyui_set_tab_index(id: ControlId, index: u32) {
+    let st = state();
+    if let Some(idx) = control::find_idx(&st.controls, id) {
+        st.controls[idx].base_mut().tab_index = index;
+    }
+}
+
+// ── Z-order ─────────────────────────────────────────────────────────
+
+/// Set a control's stacking order among its siblings. Higher values render
+/// on top and receive clicks first; equal values keep insertion order.
+/// Layout and tab order are unaffected. Default is 0.
+#[no_mangle]
+pub extern "C" fn anyui_set_z_index(id: ControlId, z: i32) {
+    let st = state();
+    if let Some(idx) = control::find_idx(&st.controls, id) {
+        let b = st.controls[idx].base_mut();
+        if b.z_index != z {
+            b.z_index = z;
+            b.mark_dirty();
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn anyui_get_z_index(id: ControlId) -> i32 {
+    let st = state();
+    control::find_idx(&st.controls, id).map_or(0, |i| st.controls[i].base().z_index)
+}
+
+/// Sibling z_index range (min, max) of `id`, excluding `id` itself.
+fn sibling_z_range(st: &AnyuiState, id: ControlId) -> Option<(i32, i32)> {
+    let parent = st.controls.iter().find(|c| c.id() == id)?.parent_id();
+    let siblings = st.controls.iter().find(|c| c.id() == parent)?.children();
+    siblings.iter()
+        .filter(|&&cid| cid != id)
+        .filter_map(|&cid| st.controls.iter().find(|c| c.id() == cid))
+        .map(|c| c.base().z_index)
+        .fold(None, |acc, z| match acc {
+            None => Some((z, z)),
+            Some((lo, hi)) => Some((lo.min(z), hi.max(z))),
+        })
+}
+
+/// Raise a control above all of its siblings.
+#[no_mangle]
+pub extern "C" fn anyui_bring_to_front(id: ControlId) {
+    let z = anyui_get_z_index(id);
+    if let Some((_, hi)) = sibling_z_range(state(), id) {
+        // Strictly above so it also beats later-inserted siblings of equal z.
+        if z <= hi {
+            anyui_set_z_index(id, hi.saturating_add(1));
+        }
+    }
+}
+
+/// Lower a control below all of its siblings.
+#[no_mangle]
+pub extern "C" fn anyui_send_to_back(id: ControlId) {
+    let z = anyui_get_z_index(id);
+    if let Some((lo, _)) = sibling_z_range(state(), id) {
+        if z >= lo {
+            anyui_set_z_index(id, lo.saturating_sub(1));
+        }
     }
 }
 
-/// Set the tab focus order index for a control.
-/// Controls with lower tab_index receive focus first when Tab is pressed.
-/// The index is cascaded: parent tab_index sorts first, then child tab_index.
-/// Default is 0 (insertion order).
+// ── Scroll chaining ─────────────────────────────────────────────────
+
+/// Set whether wheel steps that `id` and its descendants cannot use (a
+/// scrollable at its limit, or a control that does not scroll) chain on to
+/// the nearest scrollable ancestor. Off keeps them at `id`, e.g. for a
+/// list in a scrolling page that should not move the page. Default is on.
 #[no_mangle]
-pub extern "C" fn anyui_set_tab_index(id: ControlId, index: u32) {
+pub extern "C" fn anyui_set_scroll_chaining(id: ControlId, enabled: u32) {
     let st = state();
     if let Some(idx) = control::find_idx(&st.controls, id) {
-        st.controls[idx].base_mut().tab_index = index;
+        st.controls[idx].base_mut().scroll_chaining = enabled != 0;
     }
 }
 
+#[no_mangle]
+pub extern "C" fn anyui_get_scroll_chaining(id: ControlId) -> u32 {
+    let st = state();
+    control::find_idx(&st.controls, id).map_or(1, |i| st.controls[i].base().scroll_chaining as u32)
+}
+
 // ── Screen size ─────────────────────────────────────────────────────
 
 /// Get screen dimensions. Returns (width, height) via out pointers.
@@ -2529,6 +4425,97 @@ pub extern "C" fn anyui_screen_size(out_w: *mut u32, out_h: *mut u32) {
     if !out_h.is_null() { unsafe { *out_h = lh; } }
 }
 
+// ── Monitors ────────────────────────────────────────────────────────
+
+/// Logical bounds of monitor `index`, or None if out of range.
+fn monitor_rect(index: u32) -> Option<(i32, i32, u32, u32)> {
+    let (x, y, w, h) = *compositor::monitors().get(index as usize)?;
+    Some((
+        crate::theme::unscale(x),
+        crate::theme::unscale(y),
+        crate::theme::unscale_u32(w),
+        crate::theme::unscale_u32(h),
+    ))
+}
+
+/// Number of connected monitors (at least 1).
+#[no_mangle]
+pub extern "C" fn anyui_get_monitor_count() -> u32 {
+    (compositor::monitors().len() as u32).max(1)
+}
+
+/// Get the logical bounds of monitor `index` in desktop coordinates.
+/// Returns 1 on success, 0 if `index` is out of range.
+#[no_mangle]
+pub extern "C" fn anyui_get_monitor_rect(
+    index: u32,
+    out_x: *mut i32,
+    out_y: *mut i32,
+    out_w: *mut u32,
+    out_h: *mut u32,
+) -> u32 {
+    let Some((x, y, w, h)) = monitor_rect(index) else { return 0 };
+    if !out_x.is_null() { unsafe { *out_x = x; } }
+    if !out_y.is_null() { unsafe { *out_y = y; } }
+    if !out_w.is_null() { unsafe { *out_w = w; } }
+    if !out_h.is_null() { unsafe { *out_h = h; } }
+    1
+}
+
+/// Effective DPI of monitor `index` (96 × scale factor), 0 if out of range.
+#[no_mangle]
+pub extern "C" fn anyui_get_monitor_dpi(index: u32) -> u32 {
+    if index as usize >= compositor::monitors().len() {
+        return 0;
+    }
+    96 * crate::theme::scale_factor() / 100
+}
+
+/// Index of the monitor containing the centre of window `win_id`
+/// (0 if the window is unknown or off all monitors).
+#[no_mangle]
+pub extern "C" fn anyui_get_window_monitor(win_id: ControlId) -> u32 {
+    let st = state();
+    let Some(wi) = st.windows.iter().position(|&w| w == win_id) else { return 0 };
+    let cw = &st.comp_windows[wi];
+    let (px, py) = compositor::get_window_position(st.channel_id, st.sub_id, cw.window_id);
+    let cx = px + cw.width as i32 / 2;
+    let cy = py + cw.height as i32 / 2;
+    compositor::monitors().iter()
+        .position(|&(x, y, w, h)| cx >= x && cy >= y && cx < x + w as i32 && cy < y + h as i32)
+        .unwrap_or(0) as u32
+}
+
+/// Move window `win_id` onto monitor `index`. `x`/`y` are logical offsets
+/// from the monitor's top-left; -1 centres the window on that axis.
+/// Returns 1 on success, 0 if the window or monitor is unknown.
+#[no_mangle]
+pub extern "C" fn anyui_place_window_on_monitor(win_id: ControlId, index: u32, x: i32, y: i32) -> u32 {
+    let st = state();
+    let Some(wi) = st.windows.iter().position(|&w| w == win_id) else { return 0 };
+    let Some((mx, my, mw, mh)) = monitor_rect(index) else { return 0 };
+    let (ww, wh) = (st.comp_windows[wi].logical_width as i32, st.comp_windows[wi].logical_height as i32);
+    let wx = if x == -1 { mx + ((mw as i32 - ww) / 2).max(0) } else { mx + x };
+    let wy = if y == -1 { my + ((mh as i32 - wh) / 2).max(0) } else { my + y };
+    anyui_move_window(win_id, wx, wy);
+    1
+}
+
+/// Centre window `win_id` on the monitor that `owner_id` occupies
+/// (for dialogs and secondary windows).
+#[no_mangle]
+pub extern "C" fn anyui_center_on_owner_monitor(win_id: ControlId, owner_id: ControlId) -> u32 {
+    let monitor = anyui_get_window_monitor(owner_id);
+    anyui_place_window_on_monitor(win_id, monitor, -1, -1)
+}
+
+/// Register a callback for monitor configuration changes (resolution change,
+/// monitor added/removed). Callback receives (monitor_count, 0x0053, userdata).
+#[no_mangle]
+pub extern "C" fn anyui_on_monitors_changed(cb: Callback, userdata: u64) {
+    state().on_monitors_changed = Some((cb, userdata));
+}
+
 // ── Notifications ───────────────────────────────────────────────────
 
 /// Show a notification banner via the compositor.
@@ -2558,6 +4545,40 @@ pub extern "C" fn anyui_show_notification(
     compositor::show_notification(st.channel_id, title, message, icon_ptr, timeout_ms, 0);
 }
 
+/// Show a toast at the bottom of window `win` (stacked above earlier ones).
+///
+/// `action_ptr`/`action_len`: optional action label (e.g. "Undo"); clicking
+/// it invokes `cb(toast_id, EVENT_CLICK, userdata)`.
+/// `timeout_ms`: auto-dismiss timeout (0 = default 4s), paused while hovered.
+/// Returns the toast's control ID, or 0 if `win` is not a window.
+#[no_mangle]
+pub extern "C" fn anyui_show_toast(
+    win: ControlId,
+    text_ptr: *const u8, text_len: u32,
+    action_ptr: *const u8, action_len: u32,
+    timeout_ms: u32,
+    cb: control::Callback,
+    userdata: u64,
+) -> ControlId {
+    let text = if !text_ptr.is_null() && text_len > 0 {
+        unsafe { core::slice::from_raw_parts(text_ptr, text_len as usize) }
+    } else {
+        b""
+    };
+    let action = if !action_ptr.is_null() && action_len > 0 {
+        unsafe { core::slice::from_raw_parts(action_ptr, action_len as usize) }
+    } else {
+        b""
+    };
+    controls::toast::show(win, text, action, timeout_ms, cb, userdata)
+}
+
+/// Dismiss a toast early (slides out). No-op if it is already gone.
+#[no_mangle]
+pub extern "C" fn anyui_dismiss_toast(id: ControlId) {
+    controls::toast::dismiss(id);
+}
+
 // ── Theme ────────────────────────────────────────────────────────────
 
 /// Set the system theme.
@@ -2602,6 +4623,45 @@ pub extern "C" fn anyui_apply_accent_style(
     theme::apply_accent_style(dark_accent, dark_hover, light_accent, light_hover);
 }
 
+/// Override one palette color role (index of the `ThemeColors` field) for
+/// this process only, in both dark and light palettes, and repaint.
+/// Returns 1 on success, 0 if `role` is out of range.
+#[no_mangle]
+pub extern "C" fn anyui_theme_set_color(role: u32, argb: u32) -> u32 {
+    if !theme::set_color(role, argb) {
+        return 0;
+    }
+    event_loop::mark_all_windows_dirty(state());
+    1
+}
+
+/// Load a branded theme `.conf` as per-process overrides and repaint.
+/// Returns 1 on success, 0 if the file cannot be read.
+#[no_mangle]
+pub extern "C" fn anyui_theme_load_conf(path_ptr: *const u8, path_len: u32) -> u32 {
+    let path = unsafe { core::slice::from_raw_parts(path_ptr, path_len as usize) };
+    let Ok(path) = core::str::from_utf8(path) else { return 0 };
+    if !theme::load_custom_conf(path) {
+        return 0;
+    }
+    event_loop::mark_all_windows_dirty(state());
+    1
+}
+
+/// Drop all per-process color overrides and repaint with the system palette.
+#[no_mangle]
+pub extern "C" fn anyui_theme_reset_colors() {
+    theme::reset_colors();
+    event_loop::mark_all_windows_dirty(state());
+}
+
+/// Register a callback for system theme changes pushed by the compositor.
+/// Callback receives (theme, 0x0050, userdata), theme = 0 dark / 1 light.
+#[no_mangle]
+pub extern "C" fn anyui_on_theme_changed(cb: Callback, userdata: u64) {
+    state().on_theme_changed = Some((cb, userdata));
+}
+
 /// Set the font smoothing mode system-wide.
 ///
 /// Sends CMD_SET_FONT_SMOOTHING (0x1016) to the compositor, which writes
@@ -2651,6 +4711,147 @@ pub extern "C" fn anyui_get_scale_factor() -> u32 {
     if v >= 100 && v <= 300 { v } else { 100 }
 }
 
+// ── Accessibility ───────────────────────────────────────────────
+
+/// Set the system accessibility preferences (high contrast on/off and the
+/// default UI zoom, 100–200 in 25% steps).
+///
+/// Sends CMD_SET_ACCESSIBILITY to the compositor, which writes to the shared
+/// page, persists the setting and broadcasts the change to all apps.
+#[no_mangle]
+pub extern "C" fn anyui_set_accessibility(high_contrast: u32, zoom: u32) {
+    let channel_id = state().channel_id;
+    if channel_id != 0 {
+        let cmd: [u32; 5] = [0x101B, (high_contrast != 0) as u32, zoom, 0, 0]; // CMD_SET_ACCESSIBILITY
+        syscall::evt_chan_emit(channel_id, &cmd);
+    }
+}
+
+/// Get the system accessibility word from the shared uisys page.
+///
+/// Returns: bit 0 = high contrast, bits [31:16] = UI zoom percent (0 = 100).
+#[no_mangle]
+pub extern "C" fn anyui_get_accessibility() -> u32 {
+    theme::accessibility_flags()
+}
+
+/// Choose the high-contrast palette for this process only.
+///
+/// mode: 0 = follow the system setting, 1 = always off, 2 = always on.
+#[no_mangle]
+pub extern "C" fn anyui_set_high_contrast(mode: u32) {
+    let was = theme::is_high_contrast();
+    theme::set_high_contrast(mode);
+    if theme::is_high_contrast() != was {
+        event_loop::mark_all_windows_dirty(state());
+    }
+}
+
+/// Whether the high-contrast palette is in effect (1) or not (0).
+#[no_mangle]
+pub extern "C" fn anyui_is_high_contrast() -> u32 {
+    theme::is_high_contrast() as u32
+}
+
+/// Zoom a window's content to `percent` (100–200 in 25% steps; 0 = follow
+/// the system accessibility zoom). Fonts and layout scale up while the
+/// window keeps its on-screen size, so the control tree sees a smaller
+/// logical size and is laid out again.
+#[no_mangle]
+pub extern "C" fn anyui_set_window_zoom(win_id: ControlId, percent: u32) {
+    let st = state();
+    let Some(wi) = st.windows.iter().position(|&w| w == win_id) else { return };
+    st.comp_windows[wi].zoom = theme::normalize_zoom(percent);
+    event_loop::apply_window_zoom(st, wi);
+}
+
+/// Zoom in effect for a window in percent (its own or the system zoom),
+/// 0 if `win_id` is not a window.
+#[no_mangle]
+pub extern "C" fn anyui_get_window_zoom(win_id: ControlId) -> u32 {
+    let st = state();
+    match st.windows.iter().position(|&w| w == win_id) {
+        Some(wi) => st.comp_windows[wi].effective_zoom(),
+        None => 0,
+    }
+}
+
+// ── Right-to-left layout ─────────────────────────────────────────
+
+/// Switch a window between left-to-right (0) and right-to-left (1) mode.
+///
+/// In RTL mode docked children and horizontal StackPanels are mirrored
+/// (Left docks sit on the right, padding and margins swap sides, the first
+/// stack child is rightmost), labels align to the right by default, and
+/// text without strong characters runs right to left. Text with strong
+/// characters always follows the Unicode BiDi algorithm in either mode.
+#[no_mangle]
+pub extern "C" fn anyui_set_window_rtl(win_id: ControlId, rtl: u32) {
+    let st = state();
+    let Some(wi) = st.windows.iter().position(|&w| w == win_id) else { return };
+    if st.comp_windows[wi].rtl == (rtl != 0) {
+        return;
+    }
+    st.comp_windows[wi].rtl = rtl != 0;
+    if let Some(idx) = control::find_idx(&st.controls, win_id) {
+        event_loop::mark_tree_dirty(&mut st.controls, idx);
+    }
+    st.needs_layout = true;
+}
+
+/// Whether a window is in right-to-left mode (1) or not (0).
+#[no_mangle]
+pub extern "C" fn anyui_get_window_rtl(win_id: ControlId) -> u32 {
+    let st = state();
+    match st.windows.iter().position(|&w| w == win_id) {
+        Some(wi) => st.comp_windows[wi].rtl as u32,
+        None => 0,
+    }
+}
+
+// ── Present mode ─────────────────────────────────────────────────
+
+/// Choose how a window's frames reach the compositor: `PRESENT_VSYNC` (0,
+/// default) waits for the previous frame's ACK, `PRESENT_IMMEDIATE` (1)
+/// presents every rendered frame at once, `PRESENT_MANUAL` (2) presents only
+/// on `anyui_present()`. Unknown modes are ignored.
+#[no_mangle]
+pub extern "C" fn anyui_set_present_mode(win_id: ControlId, mode: u32) {
+    if mode > PRESENT_MANUAL {
+        return;
+    }
+    let st = state();
+    let Some(wi) = st.windows.iter().position(|&w| w == win_id) else { return };
+    let cw = &mut st.comp_windows[wi];
+    cw.present_mode = mode;
+    cw.present_requested = false;
+}
+
+/// Present mode of a window (`PRESENT_*`), `PRESENT_VSYNC` if `win_id` is
+/// not a window.
+#[no_mangle]
+pub extern "C" fn anyui_get_present_mode(win_id: ControlId) -> u32 {
+    let st = state();
+    match st.windows.iter().position(|&w| w == win_id) {
+        Some(wi) => st.comp_windows[wi].present_mode,
+        None => PRESENT_VSYNC,
+    }
+}
+
+/// Send a manual-mode window's pending changes to the compositor.
+///
+/// The frame is rendered at the end of the current event loop iteration,
+/// once the compositor has acknowledged the previous one. Several calls
+/// before that produce a single frame. No effect in the other modes.
+#[no_mangle]
+pub extern "C" fn anyui_present(win_id: ControlId) {
+    let st = state();
+    let Some(wi) = st.windows.iter().position(|&w| w == win_id) else { return };
+    if st.comp_windows[wi].present_mode == PRESENT_MANUAL {
+        st.comp_windows[wi].present_requested = true;
+    }
+}
+
 // ── Window title (post-creation) ─────────────────────────────────
 
 /// Set the title of a window after creation.
@@ -2687,6 +4888,192 @@ pub extern "C" fn anyui_get_key_info(
     if !out_modifiers.is_null() { unsafe { *out_modifiers = st.last_modifiers; } }
 }
 
+// ── Pointer event info ──────────────────────────────────────────
+
+/// Query the pointer sample (mouse, pen or touch) behind the current
+/// callback: kind, phase, contact id, position local to the control,
+/// pressure, tilt and buttons, plus the scale of an EVENT_PINCH.
+/// `out` points to a `PointerEvent` (10 × 32-bit fields).
+#[no_mangle]
+pub extern "C" fn anyui_get_pointer_info(out: *mut pointer::PointerEvent) {
+    if !out.is_null() {
+        unsafe { *out = state().pointer; }
+    }
+}
+
+// ── Gesture recognizers ─────────────────────────────────────────
+
+/// Fire EVENT_LONG_PRESS when the control (or a descendant without its own
+/// long-press recognizer) is pressed and held still for `delay_ms`.
+/// 0 removes the recognizer.
+#[no_mangle]
+pub extern "C" fn anyui_gesture_long_press(id: ControlId, delay_ms: u32) {
+    let st = state();
+    if let Some(idx) = control::find_idx(&st.controls, id) {
+        gesture::update(&mut *st.controls[idx], |r| r.long_press_ms = delay_ms);
+    }
+}
+
+/// Fire EVENT_SWIPE for presses that end moving along `axes` (1 =
+/// horizontal, 2 = vertical, 3 = both) at `min_velocity` logical pixels per
+/// second or faster. `axes` 0 removes the recognizer.
+#[no_mangle]
+pub extern "C" fn anyui_gesture_swipe(id: ControlId, axes: u32, min_velocity: u32) {
+    let st = state();
+    if let Some(idx) = control::find_idx(&st.controls, id) {
+        gesture::update(&mut *st.controls[idx], |r| {
+            r.swipe_axes = axes & (gesture::SWIPE_HORIZONTAL | gesture::SWIPE_VERTICAL);
+            r.swipe_velocity = min_velocity;
+        });
+    }
+}
+
+/// Fire EVENT_DOUBLE_TAP for two taps on the control at most `interval_ms`
+/// apart. 0 removes the recognizer.
+#[no_mangle]
+pub extern "C" fn anyui_gesture_double_tap(id: ControlId, interval_ms: u32) {
+    let st = state();
+    if let Some(idx) = control::find_idx(&st.controls, id) {
+        gesture::update(&mut *st.controls[idx], |r| r.double_tap_ms = interval_ms);
+    }
+}
+
+/// Query the gesture behind the current EVENT_LONG_PRESS, EVENT_SWIPE or
+/// EVENT_DOUBLE_TAP callback: kind, press point local to the control,
+/// swipe direction, distance and velocity, and duration.
+/// `out` points to a `GestureInfo` (7 × 32-bit fields).
+#[no_mangle]
+pub extern "C" fn anyui_get_gesture_info(out: *mut gesture::GestureInfo) {
+    if !out.is_null() {
+        unsafe { *out = state().gesture; }
+    }
+}
+
+// ── Container selection ─────────────────────────────────────────
+
+/// Let the user select children of a View or FlowPanel by clicking and
+/// rubber-band dragging (see [`selection`]). `mode`: 0 = off, 1 = single,
+/// 2 = multiple. Turning selection off clears it.
+/// Returns 1 on success, 0 if the control cannot select its children.
+#[no_mangle]
+pub extern "C" fn anyui_set_selection_mode(id: ControlId, mode: u32) -> u32 {
+    let st = state();
+    match control::find_idx(&st.controls, id) {
+        Some(idx) => selection::set_mode(&mut *st.controls[idx], mode) as u32,
+        None => 0,
+    }
+}
+
+/// Copy up to `max` selected child ids of a container to `out`, in child
+/// order. Returns the number of selected children.
+#[no_mangle]
+pub extern "C" fn anyui_get_selection(id: ControlId, out: *mut ControlId, max: u32) -> u32 {
+    let ids = selection::get(&state().controls, id);
+    let n = ids.len().min(max as usize);
+    if n > 0 && !out.is_null() {
+        unsafe { core::ptr::copy_nonoverlapping(ids.as_ptr(), out, n); }
+    }
+    ids.len() as u32
+}
+
+/// Replace a container's selection with the `count` ids at `ids`; ids that
+/// are not its children are ignored. Does not fire EVENT_SELECTION_CHANGED.
+/// Returns 1 on success, 0 if the container has selection turned off.
+#[no_mangle]
+pub extern "C" fn anyui_set_selection(id: ControlId, ids: *const ControlId, count: u32) -> u32 {
+    let ids = if ids.is_null() || count == 0 {
+        &[][..]
+    } else {
+        unsafe { core::slice::from_raw_parts(ids, count as usize) }
+    };
+    selection::set(&mut state().controls, id, ids) as u32
+}
+
+// ── Context help ────────────────────────────────────────────────────
+
+/// Set the context help topic of a control (see [`help`]). Pass empty text
+/// (len=0) to inherit the parent's topic again.
+#[no_mangle]
+pub extern "C" fn anyui_set_help_topic(id: ControlId, topic: *const u8, len: u32) {
+    let st = state();
+    let bytes = if len > 0 && !topic.is_null() {
+        unsafe { core::slice::from_raw_parts(topic, len as usize) }.to_vec()
+    } else {
+        Vec::new()
+    };
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        ctrl.base_mut().help_topic = bytes;
+    }
+}
+
+/// Copy the effective help topic of a control (its own, else the nearest
+/// ancestor's) into `buf`. Returns the number of bytes copied.
+#[no_mangle]
+pub extern "C" fn anyui_get_help_topic(id: ControlId, buf: *mut u8, max_len: u32) -> u32 {
+    let t = help::topic(&state().controls, id);
+    let copy_len = t.len().min(max_len as usize);
+    if !buf.is_null() && copy_len > 0 {
+        unsafe {
+            core::ptr::copy_nonoverlapping(t.as_ptr(), buf, copy_len);
+        }
+    }
+    copy_len as u32
+}
+
+/// The control help was requested for, during an EVENT_HELP callback.
+#[no_mangle]
+pub extern "C" fn anyui_get_help_source() -> ControlId {
+    state().help.source
+}
+
+/// Turn "What's this?" mode on (1) or off (0). While on, the next press
+/// fires EVENT_HELP for the control under the pointer instead of clicking.
+#[no_mangle]
+pub extern "C" fn anyui_set_whats_this(on: u32) {
+    state().help.whats_this = on != 0;
+}
+
+/// Whether "What's this?" mode is on (1) or off (0).
+#[no_mangle]
+pub extern "C" fn anyui_get_whats_this() -> u32 {
+    state().help.whats_this as u32
+}
+
+// ── User data ───────────────────────────────────────────────────────
+
+/// Store an app-defined value with a control (see [`tag`]). The previous
+/// tag is replaced without calling the destructor.
+#[no_mangle]
+pub extern "C" fn anyui_set_tag(id: ControlId, tag: u64) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        ctrl.base_mut().tag = tag;
+    }
+}
+
+/// The tag of a control (0 if none was set or `id` doesn't exist).
+#[no_mangle]
+pub extern "C" fn anyui_get_tag(id: ControlId) -> u64 {
+    state().controls.iter().find(|c| c.id() == id).map_or(0, |c| c.base().tag)
+}
+
+/// Set the function called with the control's id and tag when it is
+/// removed. Pass None to clear it.
+#[no_mangle]
+pub extern "C" fn anyui_set_tag_destructor(id: ControlId, cb: Option<tag::TagDestructor>) {
+    let st = state();
+    if let Some(ctrl) = st.controls.iter_mut().find(|c| c.id() == id) {
+        ctrl.base_mut().tag_destructor = cb;
+    }
+}
+
+/// The tag destructor of a control, None if it has none or `id` doesn't
+/// exist.
+#[no_mangle]
+pub extern "C" fn anyui_get_tag_destructor(id: ControlId) -> Option<tag::TagDestructor> {
+    state().controls.iter().find(|c| c.id() == id).and_then(|c| c.base().tag_destructor)
+}
+
 // ── Clipboard ───────────────────────────────────────────────────
 
 /// Copy text to the system clipboard.
@@ -2719,6 +5106,45 @@ pub extern "C" fn anyui_clipboard_get(out: *mut u8, capacity: u32) -> u32 {
     }
 }
 
+/// Register a callback for clipboard changes (any app set the clipboard).
+/// Called with (clipboard_seq, 0x0055, userdata).
+#[no_mangle]
+pub extern "C" fn anyui_on_clipboard_changed(cb: Callback, userdata: u64) {
+    state().on_clipboard_changed = Some((cb, userdata));
+}
+
+/// Current clipboard sequence number. It changes every time the clipboard
+/// is set, so comparing it to a saved value detects changes without IPC
+/// (read from the shared uisys page). 0 = not set since login.
+#[no_mangle]
+pub extern "C" fn anyui_clipboard_seq() -> u32 {
+    compositor::clipboard_seq()
+}
+
+/// Get entry `index` (0 = newest) of the compositor's clipboard history.
+/// Returns the number of bytes written to `out` (0 if there is no such
+/// entry, or the caller is not the clipboard manager or root). `out_format`
+/// and `out_seq` (may be null) receive the entry's format and sequence number.
+#[no_mangle]
+pub extern "C" fn anyui_clipboard_history(
+    index: u32,
+    out: *mut u8,
+    capacity: u32,
+    out_format: *mut u32,
+    out_seq: *mut u32,
+) -> u32 {
+    if out.is_null() || capacity == 0 {
+        return 0;
+    }
+    let buf = unsafe { core::slice::from_raw_parts_mut(out, capacity as usize) };
+    let (len, format, seq) = compositor::clipboard_history(index, buf);
+    unsafe {
+        if !out_format.is_null() { *out_format = format; }
+        if !out_seq.is_null() { *out_seq = seq; }
+    }
+    len
+}
+
 // ── Window size query ───────────────────────────────────────────
 
 /// Get the size of a control. Returns via out pointers.
@@ -2791,6 +5217,26 @@ pub extern "C" fn anyui_on_window_closed(cb: Callback, userdata: u64) {
     state().on_window_closed = Some((cb, userdata));
 }
 
+// ── Dock icon badge ─────────────────────────────────────────────────
+
+/// Show `count` as a badge on this app's dock icon (0 = remove).
+/// Sends CMD_SET_DOCK_BADGE (0x1019); the compositor relays it to the dock
+/// as EVT_DOCK_BADGE.
+#[no_mangle]
+pub extern "C" fn anyui_set_dock_badge(count: u32) {
+    let channel_id = state().channel_id;
+    if channel_id == 0 { return; }
+    let cmd: [u32; 5] = [0x1019, syscall::get_tid(), count, 0, 0]; // CMD_SET_DOCK_BADGE
+    syscall::evt_chan_emit(channel_id, &cmd);
+}
+
+/// Register a callback for EVT_DOCK_BADGE (0x0062), used by the dock.
+/// Callback receives (app_tid, count, userdata).
+#[no_mangle]
+pub extern "C" fn anyui_on_dock_badge(cb: Callback, userdata: u64) {
+    state().on_dock_badge = Some((cb, userdata));
+}
+
 // ── Focus by task ID ────────────────────────────────────────────────
 
 /// Send CMD_FOCUS_BY_TID to the compositor to bring a window to the front.
@@ -98,13 +98,15 @@ impl MarshalQueue {
 
 static mut QUEUE: MarshalQueue = MarshalQueue::new();
 
-/// Push a command to the marshal queue (thread-safe).
+/// Push a command to the marshal queue (thread-safe) and wake the event
+/// loop so it is applied promptly.
 fn marshal_push(cmd: UiCommand) {
     unsafe {
         QUEUE.acquire();
         let _ = QUEUE.push(cmd);
         QUEUE.release();
     }
+    crate::event_source::wakeup();
 }
 
 /// Drain all pending marshal commands and apply them to UI state.
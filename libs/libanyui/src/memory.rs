@@ -0,0 +1,119 @@
+//! Memory budget — UI memory accounting, pressure notifications and trimming.
+//!
+//! On small-memory configurations every window costs a back buffer plus an
+//! SHM surface of `width * height * 4` bytes, and canvases and images add
+//! their own pixel buffers. This module lets an application see where that
+//! memory goes and give some of it back:
+//!
+//! - `usage()` sums back buffers, SHM surfaces and control pixel data
+//!   (`Control::pixel_bytes`), returned by `anyui_get_memory_usage()`.
+//! - The compositor broadcasts `EVT_MEMORY_PRESSURE` (0x0056) when free
+//!   physical memory crosses a threshold; the event loop records the level
+//!   and fires the `anyui_on_memory_pressure()` callback with
+//!   `(level, 0x0056, userdata)`.
+//! - `trim()` calls `Control::trim_caches` on every control and releases
+//!   the back buffers of hidden windows. A released back buffer is
+//!   reallocated, and the window redrawn in full, when it is shown again.
+//! - In low-memory mode hidden windows never keep a back buffer and every
+//!   pressure broadcast at LOW or above trims before the app is notified.
+//!
+//! # Usage (via client API)
+//! ```ignore
+//! ui::set_low_memory_mode(true);
+//! ui::on_memory_pressure(|level| { if level >= ui::PRESSURE_LOW { cache.clear(); } });
+//! let u = ui::memory_usage();
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::control::{Callback, ControlKind};
+use crate::AnyuiState;
+
+/// Memory pressure broadcast by the compositor.
+pub const EVT_MEMORY_PRESSURE: u32 = 0x0056;
+
+pub const PRESSURE_NONE: u32 = 0;
+pub const PRESSURE_LOW: u32 = 1;
+pub const PRESSURE_CRITICAL: u32 = 2;
+
+/// Number of u32 words written by `anyui_get_memory_usage()`.
+pub const USAGE_WORDS: usize = 6;
+
+/// Memory budget storage, owned by AnyuiState.
+pub struct MemoryState {
+    /// Release hidden windows' back buffers eagerly and trim on pressure.
+    pub low_memory: bool,
+    /// Last level broadcast by the compositor (`PRESSURE_*`).
+    pub pressure: u32,
+    pub on_pressure: Option<(Callback, u64)>,
+}
+
+impl MemoryState {
+    pub fn new() -> Self {
+        Self { low_memory: false, pressure: PRESSURE_NONE, on_pressure: None }
+    }
+}
+
+/// Current UI memory usage in bytes, in the word order documented for
+/// `anyui_get_memory_usage()`: back buffers, SHM surfaces, canvases,
+/// images and icons, total, pressure level.
+pub fn usage(st: &AnyuiState) -> [u32; USAGE_WORDS] {
+    let mut back = 0usize;
+    let mut shm = 0usize;
+    for cw in st.comp_windows.iter() {
+        back += cw.back_buffer.capacity() * 4;
+        shm += (cw.width as usize) * (cw.height as usize) * 4;
+    }
+    if let Some(p) = &st.popup {
+        back += p.back_buffer.capacity() * 4;
+        shm += (p.width as usize) * (p.height as usize) * 4;
+    }
+    let mut canvases = 0usize;
+    let mut images = 0usize;
+    for ctrl in st.controls.iter() {
+        let n = ctrl.pixel_bytes();
+        if ctrl.kind() == ControlKind::Canvas {
+            canvases += n;
+        } else {
+            images += n;
+        }
+    }
+    let total = back + shm + canvases + images;
+    [
+        clamp(back), clamp(shm), clamp(canvases), clamp(images), clamp(total),
+        st.memory.pressure,
+    ]
+}
+
+fn clamp(n: usize) -> u32 {
+    n.min(u32::MAX as usize) as u32
+}
+
+/// Whether window `win_id` is shown.
+pub fn window_visible(st: &AnyuiState, win_id: u32) -> bool {
+    crate::control::find_idx(&st.controls, win_id)
+        .map_or(false, |idx| st.controls[idx].base().visible)
+}
+
+/// Release the back buffers of hidden windows. Returns bytes freed.
+pub fn release_hidden_buffers(st: &mut AnyuiState) -> usize {
+    let mut freed = 0;
+    for wi in 0..st.windows.len() {
+        if st.comp_windows[wi].back_buffer.capacity() == 0 || window_visible(st, st.windows[wi]) {
+            continue;
+        }
+        let cw = &mut st.comp_windows[wi];
+        freed += cw.back_buffer.capacity() * 4;
+        cw.back_buffer = Vec::new();
+    }
+    freed
+}
+
+/// Drop control caches and hidden windows' back buffers. Returns bytes freed.
+pub fn trim(st: &mut AnyuiState) -> usize {
+    let mut freed = 0;
+    for ctrl in st.controls.iter_mut() {
+        freed += ctrl.trim_caches();
+    }
+    freed + release_hidden_buffers(st)
+}
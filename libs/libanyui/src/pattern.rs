@@ -0,0 +1,344 @@
+//! Minimal regular expressions for pattern validators
+//! (`anyui_validate_pattern`, see [`crate::validation`]).
+//!
+//! Supports literals, `.`, character classes (`[a-z]`, `[^0-9]`), the
+//! escapes `\d \D \w \W \s \S`, groups (`(…)`, `(?:…)`), alternation,
+//! anchors and the quantifiers `* + ? {n} {n,} {n,m}` (greedy or lazy).
+//! Matching is backtracking with a step budget, so pathological patterns
+//! fail instead of hanging the UI thread.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cell::Cell;
+
+/// Maximum number of node visits per match attempt.
+const STEP_BUDGET: u32 = 200_000;
+
+#[derive(Clone, Copy)]
+enum ClassItem {
+    Range(char, char),
+    Digit(bool),
+    Word(bool),
+    Space(bool),
+}
+
+enum Node {
+    Char(char),
+    Any,
+    Class { items: Vec<ClassItem>, negated: bool },
+    Group(Vec<Vec<Node>>),
+    Repeat { node: Box<Node>, min: u32, max: Option<u32>, greedy: bool },
+    Start,
+    End,
+}
+
+/// A compiled pattern.
+pub struct Regex {
+    alts: Vec<Vec<Node>>,
+}
+
+impl Regex {
+    /// Compile `pattern`.  Returns `None` if the pattern is malformed.
+    pub fn new(pattern: &str) -> Option<Regex> {
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut p = Parser { chars: &chars, pos: 0 };
+        let alts = p.parse_alt()?;
+        if p.pos != chars.len() {
+            return None;
+        }
+        Some(Regex { alts })
+    }
+
+    /// True if the pattern matches the whole of `text`.
+    pub fn full_match(&self, text: &str) -> bool {
+        let input: Vec<char> = text.chars().collect();
+        let m = Matcher { input: &input, steps: Cell::new(0) };
+        let len = input.len();
+        m.match_alts(&self.alts, 0, &mut |p| p == len)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+struct Parser<'a> {
+    chars: &'a [char],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn parse_alt(&mut self) -> Option<Vec<Vec<Node>>> {
+        let mut alts = Vec::new();
+        loop {
+            alts.push(self.parse_seq()?);
+            if self.peek() == Some('|') {
+                self.pos += 1;
+            } else {
+                return Some(alts);
+            }
+        }
+    }
+
+    fn parse_seq(&mut self) -> Option<Vec<Node>> {
+        let mut seq = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.parse_atom()?;
+            seq.push(self.parse_quantifier(atom)?);
+        }
+        Some(seq)
+    }
+
+    fn parse_atom(&mut self) -> Option<Node> {
+        match self.next()? {
+            '(' => {
+                if self.peek() == Some('?') {
+                    self.pos += 1;
+                    if self.next()? != ':' {
+                        return None;
+                    }
+                }
+                let alts = self.parse_alt()?;
+                if self.next()? != ')' {
+                    return None;
+                }
+                Some(Node::Group(alts))
+            }
+            '[' => self.parse_class(),
+            '.' => Some(Node::Any),
+            '^' => Some(Node::Start),
+            '$' => Some(Node::End),
+            '\\' => {
+                let c = self.next()?;
+                Some(match class_escape(c) {
+                    Some(item) => Node::Class { items: alloc::vec![item], negated: false },
+                    None => Node::Char(char_escape(c)),
+                })
+            }
+            '*' | '+' | '?' => None,
+            c => Some(Node::Char(c)),
+        }
+    }
+
+    fn parse_class(&mut self) -> Option<Node> {
+        let mut items = Vec::new();
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.pos += 1;
+        }
+        let mut first = true;
+        loop {
+            let c = self.next()?;
+            if c == ']' && !first {
+                break;
+            }
+            first = false;
+            let lo = if c == '\\' {
+                let e = self.next()?;
+                if let Some(item) = class_escape(e) {
+                    items.push(item);
+                    continue;
+                }
+                char_escape(e)
+            } else {
+                c
+            };
+            // Range `a-z` (a trailing `-` is literal).
+            if self.peek() == Some('-') && self.chars.get(self.pos + 1).map_or(false, |&n| n != ']') {
+                self.pos += 1;
+                let mut hi = self.next()?;
+                if hi == '\\' {
+                    hi = char_escape(self.next()?);
+                }
+                if hi < lo {
+                    return None;
+                }
+                items.push(ClassItem::Range(lo, hi));
+            } else {
+                items.push(ClassItem::Range(lo, lo));
+            }
+        }
+        Some(Node::Class { items, negated })
+    }
+
+    fn parse_quantifier(&mut self, atom: Node) -> Option<Node> {
+        let (min, max) = match self.peek() {
+            Some('*') => { self.pos += 1; (0, None) }
+            Some('+') => { self.pos += 1; (1, None) }
+            Some('?') => { self.pos += 1; (0, Some(1)) }
+            Some('{') => match self.parse_braces() {
+                Some(b) => b,
+                None => return Some(atom),
+            },
+            _ => return Some(atom),
+        };
+        if matches!(atom, Node::Start | Node::End) {
+            return None;
+        }
+        let greedy = if self.peek() == Some('?') {
+            self.pos += 1;
+            false
+        } else {
+            true
+        };
+        Some(Node::Repeat { node: Box::new(atom), min, max, greedy })
+    }
+
+    /// Parse `{n}`, `{n,}` or `{n,m}`.  On anything else the position is
+    /// left unchanged and `{` is then taken as a literal.
+    fn parse_braces(&mut self) -> Option<(u32, Option<u32>)> {
+        let start = self.pos;
+        self.pos += 1;
+        let result = (|| {
+            let min = self.parse_number()?;
+            match self.next()? {
+                '}' => Some((min, Some(min))),
+                ',' => {
+                    if self.peek() == Some('}') {
+                        self.pos += 1;
+                        return Some((min, None));
+                    }
+                    let max = self.parse_number()?;
+                    if self.next()? != '}' || max < min {
+                        return None;
+                    }
+                    Some((min, Some(max)))
+                }
+                _ => None,
+            }
+        })();
+        if result.is_none() {
+            self.pos = start;
+        }
+        result
+    }
+
+    fn parse_number(&mut self) -> Option<u32> {
+        let start = self.pos;
+        let mut n: u32 = 0;
+        while let Some(d) = self.peek().and_then(|c| c.to_digit(10)) {
+            n = n.saturating_mul(10).saturating_add(d);
+            self.pos += 1;
+        }
+        if self.pos == start { None } else { Some(n) }
+    }
+}
+
+fn class_escape(c: char) -> Option<ClassItem> {
+    match c {
+        'd' => Some(ClassItem::Digit(true)),
+        'D' => Some(ClassItem::Digit(false)),
+        'w' => Some(ClassItem::Word(true)),
+        'W' => Some(ClassItem::Word(false)),
+        's' => Some(ClassItem::Space(true)),
+        'S' => Some(ClassItem::Space(false)),
+        _ => None,
+    }
+}
+
+fn char_escape(c: char) -> char {
+    match c {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        other => other,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Matcher
+// ---------------------------------------------------------------------------
+
+struct Matcher<'a> {
+    input: &'a [char],
+    steps: Cell<u32>,
+}
+
+impl<'a> Matcher<'a> {
+    fn tick(&self) -> bool {
+        let s = self.steps.get() + 1;
+        self.steps.set(s);
+        s <= STEP_BUDGET
+    }
+
+    fn match_alts(&self, alts: &[Vec<Node>], pos: usize, k: &mut dyn FnMut(usize) -> bool) -> bool {
+        alts.iter().any(|seq| self.match_seq(seq, pos, k))
+    }
+
+    fn match_seq(&self, seq: &[Node], pos: usize, k: &mut dyn FnMut(usize) -> bool) -> bool {
+        match seq.split_first() {
+            None => k(pos),
+            Some((node, rest)) => self.match_node(node, pos, &mut |p| self.match_seq(rest, p, k)),
+        }
+    }
+
+    fn match_node(&self, node: &Node, pos: usize, k: &mut dyn FnMut(usize) -> bool) -> bool {
+        if !self.tick() {
+            return false;
+        }
+        match node {
+            Node::Char(c) => self.input.get(pos) == Some(c) && k(pos + 1),
+            Node::Any => self.input.get(pos).map_or(false, |&c| c != '\n') && k(pos + 1),
+            Node::Class { items, negated } => match self.input.get(pos) {
+                Some(&c) => class_matches(items, c) != *negated && k(pos + 1),
+                None => false,
+            },
+            Node::Group(alts) => self.match_alts(alts, pos, k),
+            Node::Repeat { node, min, max, greedy } => {
+                self.match_repeat(node, *min, *max, *greedy, 0, pos, k)
+            }
+            Node::Start => pos == 0 && k(pos),
+            Node::End => pos == self.input.len() && k(pos),
+        }
+    }
+
+    fn match_repeat(
+        &self,
+        node: &Node,
+        min: u32,
+        max: Option<u32>,
+        greedy: bool,
+        count: u32,
+        pos: usize,
+        k: &mut dyn FnMut(usize) -> bool,
+    ) -> bool {
+        let can_stop = count >= min;
+        let can_more = max.map_or(true, |m| count < m);
+        let more = |k: &mut dyn FnMut(usize) -> bool| {
+            can_more && self.match_node(node, pos, &mut |p| {
+                // An empty iteration past the minimum cannot make progress.
+                if p == pos && can_stop {
+                    return false;
+                }
+                self.match_repeat(node, min, max, greedy, count + 1, p, k)
+            })
+        };
+        if greedy {
+            more(k) || (can_stop && k(pos))
+        } else {
+            (can_stop && k(pos)) || more(k)
+        }
+    }
+}
+
+fn class_matches(items: &[ClassItem], c: char) -> bool {
+    items.iter().any(|item| match *item {
+        ClassItem::Range(lo, hi) => c >= lo && c <= hi,
+        ClassItem::Digit(pos) => c.is_ascii_digit() == pos,
+        ClassItem::Word(pos) => (c.is_ascii_alphanumeric() || c == '_') == pos,
+        ClassItem::Space(pos) => c.is_whitespace() == pos,
+    })
+}
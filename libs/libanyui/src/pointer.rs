@@ -0,0 +1,284 @@
+//! Pointer input beyond the mouse: pen/tablet samples and multi-touch.
+//!
+//! The compositor reports pen samples as EVT_PEN and touch contacts as
+//! EVT_TOUCH, in physical window coordinates like mouse events. Every
+//! pointer sample (mouse included) becomes a [`PointerEvent`] that is passed
+//! to `Control::handle_pointer` of the control under it and can be read with
+//! `anyui_get_pointer_info` from the callbacks fired for it.
+//!
+//! Pen and touch also drive the regular mouse path so every control works
+//! with them: the pen acts as the left button (the right one while the
+//! barrel button is held), and the first touch contact acts as the left
+//! button until a second contact turns the touch into a gesture.
+//!
+//! Touch contacts fire EVENT_TOUCH_DOWN / MOVE / UP on the control they went
+//! down on. Two contacts synthesize gestures on the control under their
+//! midpoint: EVENT_PINCH as the distance between them changes, and scroll
+//! steps (like the mouse wheel) as the midpoint moves vertically.
+
+use alloc::vec::Vec;
+use crate::compositor;
+use crate::control::ControlId;
+
+// ── Pointer kinds and phases ─────────────────────────────────────────
+
+pub const POINTER_MOUSE: u32 = 0;
+pub const POINTER_PEN: u32 = 1;
+pub const POINTER_TOUCH: u32 = 2;
+
+pub const PHASE_DOWN: u32 = 0;
+pub const PHASE_MOVE: u32 = 1;
+pub const PHASE_UP: u32 = 2;
+/// Pen in range above the tablet without touching it.
+pub const PHASE_HOVER: u32 = 3;
+
+/// Full pressure. Pens report 0..=PRESSURE_MAX; mouse buttons and touch
+/// contacts report 0 or PRESSURE_MAX.
+pub const PRESSURE_MAX: u32 = 4095;
+
+/// Pen buttons (`PointerEvent::buttons` for POINTER_PEN).
+pub const PEN_BARREL: u32 = 0x01;
+pub const PEN_ERASER: u32 = 0x02;
+
+/// Flag in arg3 of a mouse event synthesized from pen or touch input.
+pub const EMULATED: u32 = 1 << 31;
+
+/// Vertical midpoint travel (logical pixels) per synthesized scroll step,
+/// the distance a ScrollView moves per wheel notch.
+const SCROLL_STEP: i32 = 20;
+
+/// One pointer sample.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct PointerEvent {
+    /// POINTER_MOUSE, POINTER_PEN or POINTER_TOUCH.
+    pub kind: u32,
+    /// PHASE_DOWN, PHASE_MOVE, PHASE_UP or PHASE_HOVER.
+    pub phase: u32,
+    /// Touch contact id (0 for mouse and pen).
+    pub contact: u32,
+    /// Position in logical pixels: window coordinates while the event loop
+    /// dispatches, local to the control in `handle_pointer` and callbacks.
+    pub x: i32,
+    pub y: i32,
+    /// 0..=PRESSURE_MAX.
+    pub pressure: u32,
+    /// Pen tilt in degrees (-90..=90), positive towards +x / +y.
+    pub tilt_x: i32,
+    pub tilt_y: i32,
+    /// Mouse buttons (0x01 left, 0x02 right) or PEN_* flags.
+    pub buttons: u32,
+    /// EVENT_PINCH: distance between the two contacts relative to the
+    /// start of the gesture, in 1/1000 (1000 = unchanged). 0 otherwise.
+    pub scale: u32,
+}
+
+impl PointerEvent {
+    pub fn mouse(phase: u32, x: i32, y: i32, buttons: u32) -> Self {
+        Self {
+            kind: POINTER_MOUSE,
+            phase,
+            x,
+            y,
+            pressure: if buttons != 0 { PRESSURE_MAX } else { 0 },
+            buttons,
+            ..Self::default()
+        }
+    }
+
+    /// Decode an EVT_PEN arg3 sampled at (x, y).
+    pub fn pen(x: i32, y: i32, packed: u32) -> Self {
+        Self {
+            kind: POINTER_PEN,
+            phase: packed & 0x03,
+            x,
+            y,
+            pressure: (packed >> 4) & 0xFFF,
+            tilt_x: (packed >> 16) as u8 as i8 as i32,
+            tilt_y: (packed >> 24) as u8 as i8 as i32,
+            buttons: (packed >> 2) & 0x03,
+            ..Self::default()
+        }
+    }
+
+    /// Decode an EVT_TOUCH arg3 sampled at (x, y).
+    pub fn touch(x: i32, y: i32, packed: u32) -> Self {
+        let phase = packed & 0x03;
+        Self {
+            kind: POINTER_TOUCH,
+            phase,
+            contact: (packed >> 8) & 0xFF,
+            x,
+            y,
+            pressure: if phase == PHASE_UP { 0 } else { PRESSURE_MAX },
+            ..Self::default()
+        }
+    }
+
+    /// The same sample relative to a control at window position (ax, ay).
+    pub fn relative_to(&self, ax: i32, ay: i32) -> Self {
+        Self { x: self.x - ax, y: self.y - ay, ..*self }
+    }
+}
+
+/// True for a pen or touch event that puts a pointer down.
+pub fn is_down_event(ev: &[u32; 5]) -> bool {
+    (ev[0] == compositor::EVT_PEN || ev[0] == compositor::EVT_TOUCH) && ev[4] & 0x03 == PHASE_DOWN
+}
+
+// ── Touch tracking and gestures ──────────────────────────────────────
+
+/// A touch contact that is down.
+pub(crate) struct Contact {
+    pub id: u32,
+    /// Control the contact went down on; receives all its events.
+    pub target: Option<ControlId>,
+    /// Window position (logical pixels).
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Two-finger gesture in progress.
+struct Gesture {
+    target: ControlId,
+    start_dist: i32,
+    last_dist: i32,
+    last_mid_y: i32,
+    /// Midpoint travel not yet turned into scroll steps.
+    scroll_acc: i32,
+}
+
+/// What a contact move did to the gesture.
+pub(crate) struct GestureStep {
+    pub target: ControlId,
+    /// Midpoint of the two contacts (window position).
+    pub mid_x: i32,
+    pub mid_y: i32,
+    /// New pinch scale (1/1000) if the distance changed.
+    pub scale: Option<u32>,
+    /// Scroll steps (positive = content follows the fingers down).
+    pub scroll: i32,
+}
+
+pub(crate) struct TouchState {
+    pub contacts: Vec<Contact>,
+    gesture: Option<Gesture>,
+    /// Contact driving the mouse path.
+    emulating: Option<u32>,
+    /// Contacts down as seen by `emulate` (popup windows included).
+    down: u32,
+}
+
+impl TouchState {
+    pub const fn new() -> Self {
+        Self { contacts: Vec::new(), gesture: None, emulating: None, down: 0 }
+    }
+
+    /// The mouse event a pen or touch event stands for, flagged EMULATED.
+    /// `modifiers` are the current keyboard modifiers.
+    pub fn emulate(&mut self, ev: &[u32; 5], modifiers: u32) -> Option<[u32; 5]> {
+        let phase = ev[4] & 0x03;
+        let mouse = |kind: u32, button: u32| {
+            Some([kind, ev[1], ev[2], ev[3], button | modifiers << 8 | EMULATED])
+        };
+        if ev[0] == compositor::EVT_PEN {
+            let button = if (ev[4] >> 2) & PEN_BARREL != 0 { 0x02 } else { 0x01 };
+            return match phase {
+                PHASE_DOWN => mouse(compositor::EVT_MOUSE_DOWN, button),
+                PHASE_UP => mouse(compositor::EVT_MOUSE_UP, button),
+                _ => mouse(compositor::EVT_MOUSE_MOVE, 0),
+            };
+        }
+        let contact = (ev[4] >> 8) & 0xFF;
+        match phase {
+            PHASE_DOWN => {
+                self.down += 1;
+                if self.down == 1 {
+                    self.emulating = Some(contact);
+                    mouse(compositor::EVT_MOUSE_DOWN, 0x01)
+                } else {
+                    // A second finger: the touch is a gesture, not a press.
+                    self.emulating = None;
+                    None
+                }
+            }
+            PHASE_UP => {
+                self.down = self.down.saturating_sub(1);
+                if self.emulating == Some(contact) {
+                    self.emulating = None;
+                    mouse(compositor::EVT_MOUSE_UP, 0x01)
+                } else {
+                    None
+                }
+            }
+            _ if self.emulating == Some(contact) => mouse(compositor::EVT_MOUSE_MOVE, 0),
+            _ => None,
+        }
+    }
+
+    /// Distance and midpoint of the first two contacts.
+    fn pair(&self) -> Option<(i32, i32, i32)> {
+        let (a, b) = match self.contacts.as_slice() {
+            [a, b, ..] => (a, b),
+            _ => return None,
+        };
+        let dx = (a.x - b.x) as i64;
+        let dy = (a.y - b.y) as i64;
+        let dist = crate::draw::isqrt_u64((dx * dx + dy * dy) as u64) as i32;
+        Some((dist, (a.x + b.x) / 2, (a.y + b.y) / 2))
+    }
+
+    /// Midpoint of the first two contacts, where a gesture would start.
+    pub fn midpoint(&self) -> Option<(i32, i32)> {
+        self.pair().map(|(_, mx, my)| (mx, my))
+    }
+
+    /// Start a two-finger gesture on `target`.
+    pub fn begin_gesture(&mut self, target: ControlId) {
+        if let Some((dist, _, mid_y)) = self.pair() {
+            self.gesture = Some(Gesture {
+                target,
+                start_dist: dist.max(1),
+                last_dist: dist,
+                last_mid_y: mid_y,
+                scroll_acc: 0,
+            });
+        }
+    }
+
+    /// Advance the gesture after one of its contacts moved.
+    pub fn update_gesture(&mut self) -> Option<GestureStep> {
+        let (dist, mid_x, mid_y) = self.pair()?;
+        let g = self.gesture.as_mut()?;
+        let scale = if dist != g.last_dist {
+            g.last_dist = dist;
+            Some((dist as i64 * 1000 / g.start_dist as i64) as u32)
+        } else {
+            None
+        };
+        g.scroll_acc += mid_y - g.last_mid_y;
+        g.last_mid_y = mid_y;
+        let scroll = g.scroll_acc / SCROLL_STEP;
+        g.scroll_acc -= scroll * SCROLL_STEP;
+        Some(GestureStep { target: g.target, mid_x, mid_y, scale, scroll })
+    }
+
+    /// Forget the gesture once fewer than two contacts are down.
+    pub fn end_gesture(&mut self) {
+        if self.contacts.len() < 2 {
+            self.gesture = None;
+        }
+    }
+
+    /// Drop all tracking that refers to `id` (control destroyed).
+    pub fn forget(&mut self, id: ControlId) {
+        for c in self.contacts.iter_mut() {
+            if c.target == Some(id) {
+                c.target = None;
+            }
+        }
+        if self.gesture.as_ref().map_or(false, |g| g.target == id) {
+            self.gesture = None;
+        }
+    }
+}
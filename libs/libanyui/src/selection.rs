@@ -0,0 +1,375 @@
+//! Multi-selection of the children of a View or FlowPanel.
+//!
+//! A container turns selection on with `anyui_set_selection_mode`. Its
+//! direct children are the selectable items, as the icons of a file-manager
+//! view are. Selection follows the left-button presses of the mouse path,
+//! like the gesture recognizers (see [`crate::gesture`]). The pressed
+//! control keeps its own events, so an icon still clicks and double-clicks.
+//!
+//! - Click on an item selects only that item. Ctrl-click toggles it and
+//!   Shift-click selects the range from the last clicked item, in child
+//!   order. Ctrl+Shift adds the range to the selection.
+//! - A press on an already selected item keeps the selection until release,
+//!   so the items can be dragged together. A release without a drag then
+//!   selects only that item.
+//! - A press on empty space clears the selection. Dragging from there draws
+//!   a rubber band that selects the items it touches. With Shift the band
+//!   adds to the selection, and with Ctrl it toggles the items it touches.
+//! - A right-click on an item outside the selection selects only that item,
+//!   so a context menu acts on what is under the pointer.
+//!
+//! EVENT_SELECTION_CHANGED fires on the container whenever the user changes
+//! the selection; `anyui_get_selection` reads the ids in child order.
+//! `SELECT_SINGLE` allows one item and no rubber band.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::control::{self, Control, ControlId, ControlKind, MOD_CTRL, MOD_SHIFT};
+
+/// Selection modes for `anyui_set_selection_mode`.
+pub const SELECT_NONE: u32 = 0;
+pub const SELECT_SINGLE: u32 = 1;
+pub const SELECT_MULTIPLE: u32 = 2;
+
+/// Travel (logical pixels) before a press on empty space becomes a band.
+const BAND_THRESHOLD: i32 = 3;
+/// Corner radius of the item highlight (logical pixels).
+const HIGHLIGHT_RADIUS: u32 = 6;
+
+/// Selection state of one container (`ControlBase::selection`).
+pub struct Selection {
+    pub mode: u32,
+    /// Selected children, in child order.
+    pub selected: Vec<ControlId>,
+    /// Item Shift-click ranges start from.
+    anchor: Option<ControlId>,
+    /// Rubber band (x, y, w, h) local to the container while it is dragged.
+    band: Option<(i32, i32, u32, u32)>,
+}
+
+impl Selection {
+    fn new(mode: u32) -> Self {
+        Self { mode, selected: Vec::new(), anchor: None, band: None }
+    }
+
+    pub fn is_selected(&self, id: ControlId) -> bool {
+        self.selected.contains(&id)
+    }
+
+    /// Draw the highlight behind the selected items. `ax`, `ay` are the
+    /// container's absolute position (logical pixels).
+    pub fn render_items(&self, controls: &[Box<dyn Control>], surface: &crate::draw::Surface, ax: i32, ay: i32) {
+        if self.selected.is_empty() {
+            return;
+        }
+        let tc = crate::theme::colors();
+        let fill = crate::theme::with_alpha(tc.selection, 64);
+        let border = crate::theme::with_alpha(tc.selection, 160);
+        let r = crate::theme::scale(HIGHLIGHT_RADIUS);
+        for &id in &self.selected {
+            let Some(ci) = control::find_idx(controls, id) else { continue };
+            let b = controls[ci].base();
+            if !b.visible {
+                continue;
+            }
+            let p = crate::draw::scale_bounds(ax, ay, b.x - 2, b.y - 2, b.w + 4, b.h + 4);
+            crate::draw::fill_rounded_rect(surface, p.x, p.y, p.w, p.h, r, fill);
+            crate::draw::draw_rounded_border(surface, p.x, p.y, p.w, p.h, r, border);
+        }
+    }
+
+    /// Draw the rubber band over the items.
+    pub fn render_band(&self, surface: &crate::draw::Surface, ax: i32, ay: i32) {
+        let Some((x, y, w, h)) = self.band else { return };
+        let tc = crate::theme::colors();
+        let p = crate::draw::scale_bounds(ax, ay, x, y, w, h);
+        crate::draw::fill_rect(surface, p.x, p.y, p.w, p.h, crate::theme::with_alpha(tc.selection, 48));
+        crate::draw::draw_border(surface, p.x, p.y, p.w, p.h, tc.selection);
+    }
+}
+
+/// Whether controls of `kind` can select their children.
+pub fn supports(kind: ControlKind) -> bool {
+    matches!(kind, ControlKind::View | ControlKind::FlowPanel)
+}
+
+/// Set a container's selection mode. Returns false if it cannot select.
+pub fn set_mode(ctrl: &mut dyn Control, mode: u32) -> bool {
+    if !supports(ctrl.kind()) || mode > SELECT_MULTIPLE {
+        return false;
+    }
+    let b = ctrl.base_mut();
+    if mode == SELECT_NONE {
+        b.selection = None;
+    } else if let Some(sel) = b.selection.as_mut() {
+        sel.mode = mode;
+        if mode == SELECT_SINGLE {
+            sel.selected.truncate(1);
+        }
+    } else {
+        b.selection = Some(Box::new(Selection::new(mode)));
+    }
+    b.mark_dirty();
+    true
+}
+
+/// Replace a container's selection with those of `ids` that are its
+/// children (the first one only in single mode). Returns false if the
+/// container has selection turned off.
+pub fn set(controls: &mut [Box<dyn Control>], container: ControlId, ids: &[ControlId]) -> bool {
+    let Some(idx) = control::find_idx(controls, container) else { return false };
+    let b = controls[idx].base_mut();
+    let children = b.children.clone();
+    let Some(sel) = b.selection.as_mut() else { return false };
+    let mut selected: Vec<ControlId> = children.into_iter().filter(|c| ids.contains(c)).collect();
+    if sel.mode == SELECT_SINGLE {
+        selected.truncate(1);
+    }
+    sel.anchor = selected.first().copied();
+    sel.selected = selected;
+    b.mark_dirty();
+    true
+}
+
+/// Selected children of `container`, in child order. Children removed since
+/// they were selected are left out.
+pub fn get(controls: &[Box<dyn Control>], container: ControlId) -> Vec<ControlId> {
+    let Some(idx) = control::find_idx(controls, container) else { return Vec::new() };
+    let b = controls[idx].base();
+    match &b.selection {
+        Some(sel) => sel.selected.iter().copied().filter(|id| b.children.contains(id)).collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Nearest container from `id` up with selection on, and the item (its
+/// child on the way to `id`) the press landed on, if any.
+fn resolve(controls: &[Box<dyn Control>], id: ControlId) -> Option<(ControlId, Option<ControlId>)> {
+    let mut cur = id;
+    let mut item = None;
+    loop {
+        let idx = control::find_idx(controls, cur)?;
+        let b = controls[idx].base();
+        if b.selection.is_some() {
+            return Some((cur, item));
+        }
+        if b.parent == 0 || b.parent == cur {
+            return None;
+        }
+        item = Some(cur);
+        cur = b.parent;
+    }
+}
+
+/// Run `f` on the selection of `container`; true if the selected set changed.
+fn update(
+    controls: &mut [Box<dyn Control>],
+    container: ControlId,
+    f: impl FnOnce(&mut Selection, &[ControlId]),
+) -> bool {
+    let Some(idx) = control::find_idx(controls, container) else { return false };
+    let b = controls[idx].base_mut();
+    let children = core::mem::take(&mut b.children);
+    let changed = match b.selection.as_mut() {
+        Some(sel) => {
+            let before = sel.selected.clone();
+            let had_band = sel.band.is_some();
+            f(sel, &children);
+            let changed = sel.selected != before;
+            if changed || had_band || sel.band.is_some() {
+                b.mark_dirty();
+            }
+            changed
+        }
+        None => false,
+    };
+    b.children = children;
+    changed
+}
+
+/// Selected items of `children` in child order: those `pick` accepts.
+fn collect(children: &[ControlId], mut pick: impl FnMut(ControlId) -> bool) -> Vec<ControlId> {
+    children.iter().copied().filter(|&c| pick(c)).collect()
+}
+
+/// A left-button press on a selecting container.
+struct Press {
+    container: ControlId,
+    /// Press point local to the container.
+    x: i32,
+    y: i32,
+    modifiers: u32,
+    /// Press on empty space: selection before the press, sorted, that a
+    /// Shift or Ctrl band combines with.
+    band_base: Option<Vec<ControlId>>,
+    /// Plain press on a selected item: select only it on a release without
+    /// a drag.
+    deferred: Option<ControlId>,
+    moved: bool,
+}
+
+pub(crate) struct SelectState {
+    press: Option<Press>,
+}
+
+impl SelectState {
+    pub const fn new() -> Self {
+        Self { press: None }
+    }
+
+    /// A press of `button` went down on `hit` at window position (x, y).
+    /// Returns the container whose selection changed.
+    pub fn down(
+        &mut self,
+        controls: &mut [Box<dyn Control>],
+        hit: ControlId,
+        x: i32,
+        y: i32,
+        button: u32,
+        modifiers: u32,
+    ) -> Option<ControlId> {
+        self.press = None;
+        let (container, item) = resolve(controls, hit)?;
+        let right = button & 0x01 == 0;
+        if right && button & 0x02 == 0 {
+            return None;
+        }
+        let ctrl = modifiers & MOD_CTRL != 0;
+        let shift = modifiers & MOD_SHIFT != 0;
+        let (ax, ay) = control::abs_position(controls, container);
+        let mut press = Press {
+            container,
+            x: x - ax,
+            y: y - ay,
+            modifiers,
+            band_base: None,
+            deferred: None,
+            moved: false,
+        };
+
+        let changed = update(controls, container, |sel, children| {
+            let multiple = sel.mode == SELECT_MULTIPLE;
+            match item {
+                Some(item) if right => {
+                    if !sel.is_selected(item) {
+                        sel.selected = alloc::vec![item];
+                        sel.anchor = Some(item);
+                    }
+                }
+                Some(item) if multiple && shift => {
+                    let from = sel.anchor
+                        .and_then(|a| children.iter().position(|&c| c == a))
+                        .unwrap_or(0);
+                    let to = children.iter().position(|&c| c == item).unwrap_or(from);
+                    let (lo, hi) = (from.min(to), from.max(to));
+                    let range = &children[lo..=hi];
+                    let keep = if ctrl { sel.selected.clone() } else { Vec::new() };
+                    sel.selected = collect(children, |c| range.contains(&c) || keep.contains(&c));
+                }
+                Some(item) if ctrl => {
+                    if sel.is_selected(item) {
+                        sel.selected.retain(|&c| c != item);
+                    } else if multiple {
+                        sel.selected = collect(children, |c| c == item || sel.selected.contains(&c));
+                    } else {
+                        sel.selected = alloc::vec![item];
+                    }
+                    sel.anchor = Some(item);
+                }
+                Some(item) => {
+                    if sel.is_selected(item) && sel.selected.len() > 1 {
+                        press.deferred = Some(item);
+                    } else {
+                        sel.selected = alloc::vec![item];
+                    }
+                    sel.anchor = Some(item);
+                }
+                None if right => {}
+                None => {
+                    if multiple && (ctrl || shift) {
+                        let mut base = sel.selected.clone();
+                        base.sort_unstable();
+                        press.band_base = Some(base);
+                    } else {
+                        sel.selected.clear();
+                        if multiple {
+                            press.band_base = Some(Vec::new());
+                        }
+                    }
+                }
+            }
+        });
+        if !right {
+            self.press = Some(press);
+        }
+        if changed { Some(container) } else { None }
+    }
+
+    /// The pointer moved to window position (x, y) during a press.
+    /// Returns the container whose selection changed.
+    pub fn motion(&mut self, controls: &mut [Box<dyn Control>], x: i32, y: i32) -> Option<ControlId> {
+        let p = self.press.as_mut()?;
+        let (ax, ay) = control::abs_position(controls, p.container);
+        let (lx, ly) = (x - ax, y - ay);
+        if !p.moved {
+            if (lx - p.x).abs() <= BAND_THRESHOLD && (ly - p.y).abs() <= BAND_THRESHOLD {
+                return None;
+            }
+            p.moved = true;
+        }
+        let base = p.band_base.as_ref()?;
+        let band = (
+            p.x.min(lx),
+            p.y.min(ly),
+            (lx - p.x).unsigned_abs(),
+            (ly - p.y).unsigned_abs(),
+        );
+        let toggle = p.modifiers & MOD_CTRL != 0;
+        let container = p.container;
+
+        // Item bounds are read before the selection is borrowed mutably.
+        let idx = control::find_idx(controls, container)?;
+        let touched: Vec<ControlId> = controls[idx].base().children.iter().copied()
+            .filter(|&c| {
+                control::find_idx(controls, c).map_or(false, |ci| {
+                    let b = controls[ci].base();
+                    b.visible && rects_touch(band, (b.x, b.y, b.w, b.h))
+                })
+            })
+            .collect();
+
+        let changed = update(controls, container, |sel, children| {
+            sel.band = Some(band);
+            sel.selected = collect(children, |c| {
+                let in_base = base.binary_search(&c).is_ok();
+                let in_band = touched.contains(&c);
+                if toggle { in_base != in_band } else { in_base || in_band }
+            });
+        });
+        if changed { Some(container) } else { None }
+    }
+
+    /// The press ended. Returns the container whose selection changed.
+    pub fn up(&mut self, controls: &mut [Box<dyn Control>]) -> Option<ControlId> {
+        let p = self.press.take()?;
+        let changed = update(controls, p.container, |sel, _| {
+            sel.band = None;
+            if let Some(item) = p.deferred.filter(|_| !p.moved) {
+                sel.selected = alloc::vec![item];
+            }
+        });
+        if changed { Some(p.container) } else { None }
+    }
+
+    /// Drop the press if it refers to `id` (control destroyed).
+    pub fn forget(&mut self, id: ControlId) {
+        if self.press.as_ref().map_or(false, |p| p.container == id || p.deferred == Some(id)) {
+            self.press = None;
+        }
+    }
+}
+
+fn rects_touch(a: (i32, i32, u32, u32), b: (i32, i32, u32, u32)) -> bool {
+    a.0 < b.0 + b.2 as i32 && b.0 < a.0 + a.2 as i32 && a.1 < b.1 + b.3 as i32 && b.1 < a.1 + a.3 as i32
+}
@@ -0,0 +1,195 @@
+//! Spell checking — pluggable word-check hook for TextField and TextEditor.
+//!
+//! The application (or a dictionary DLL it loads) registers a checker with
+//! `anyui_set_spell_checker`. Controls with spell checking enabled ask it
+//! about every word they render and draw a squiggly underline below the
+//! words it rejects. Right-clicking such a word opens a popup listing the
+//! checker's suggestions plus "Ignore" and "Add to Dictionary" entries.
+//!
+//! Ignored words are remembered for the lifetime of the process and shared
+//! by all controls. "Add to Dictionary" ignores the word as well and fires
+//! `EVENT_SPELL_ADD` so the application can persist it.
+
+use alloc::vec::Vec;
+
+/// Word check: `(word, len, userdata) -> 1` if the word is spelled correctly.
+pub type CheckFn = extern "C" fn(*const u8, u32, u64) -> u32;
+
+/// Suggestions: `(word, len, out, out_cap, userdata) -> bytes written`.
+/// Writes pipe-separated replacement candidates, best match first.
+pub type SuggestFn = extern "C" fn(*const u8, u32, *mut u8, u32, u64) -> u32;
+
+/// Maximum number of suggestions shown in the popup.
+pub const MAX_SUGGESTIONS: usize = 5;
+
+/// Popup entry label when the checker has no replacement candidates.
+pub const NO_SUGGESTIONS: &[u8] = b"(No suggestions)";
+pub const IGNORE_LABEL: &[u8] = b"Ignore";
+pub const ADD_LABEL: &[u8] = b"Add to Dictionary";
+
+struct SpellState {
+    check: Option<CheckFn>,
+    suggest: Option<SuggestFn>,
+    userdata: u64,
+    /// Words accepted for this session via Ignore / Add to Dictionary.
+    ignored: Vec<Vec<u8>>,
+    /// Word the most recent Ignore / Add to Dictionary action applied to.
+    action_word: Vec<u8>,
+}
+
+static mut SPELL: SpellState = SpellState {
+    check: None,
+    suggest: None,
+    userdata: 0,
+    ignored: Vec::new(),
+    action_word: Vec::new(),
+};
+
+fn spell() -> &'static mut SpellState {
+    unsafe { &mut *core::ptr::addr_of_mut!(SPELL) }
+}
+
+/// Install (or with `check == None`, remove) the word checker.
+pub fn set_checker(check: Option<CheckFn>, suggest: Option<SuggestFn>, userdata: u64) {
+    let sp = spell();
+    sp.check = check;
+    sp.suggest = suggest;
+    sp.userdata = userdata;
+}
+
+/// Whether a checker is registered.
+pub fn is_active() -> bool {
+    spell().check.is_some()
+}
+
+/// Returns true if the checker rejects `word` and it has not been ignored.
+pub fn is_misspelled(word: &[u8]) -> bool {
+    let sp = spell();
+    let check = match sp.check {
+        Some(f) => f,
+        None => return false,
+    };
+    if word.len() < 2 || sp.ignored.iter().any(|w| w.as_slice() == word) {
+        return false;
+    }
+    check(word.as_ptr(), word.len() as u32, sp.userdata) == 0
+}
+
+/// Query up to `MAX_SUGGESTIONS` replacements for `word`.
+pub fn suggestions(word: &[u8]) -> Vec<Vec<u8>> {
+    let sp = spell();
+    let suggest = match sp.suggest {
+        Some(f) => f,
+        None => return Vec::new(),
+    };
+    let mut buf = [0u8; 512];
+    let n = suggest(word.as_ptr(), word.len() as u32, buf.as_mut_ptr(), buf.len() as u32, sp.userdata);
+    let n = (n as usize).min(buf.len());
+    buf[..n]
+        .split(|&b| b == b'|')
+        .filter(|s| !s.is_empty() && *s != b"-")
+        .take(MAX_SUGGESTIONS)
+        .map(|s| s.to_vec())
+        .collect()
+}
+
+/// Accept `word` for the rest of the session and remember it as the
+/// subject of the current Ignore / Add to Dictionary event.
+pub fn ignore(word: &[u8]) {
+    let sp = spell();
+    if !sp.ignored.iter().any(|w| w.as_slice() == word) {
+        sp.ignored.push(word.to_vec());
+    }
+    sp.action_word.clear();
+    sp.action_word.extend_from_slice(word);
+}
+
+/// Word the last Ignore / Add to Dictionary action applied to.
+pub fn action_word() -> &'static [u8] {
+    &spell().action_word
+}
+
+/// Build the pipe-separated popup menu for `sugg`: suggestions (or a
+/// placeholder), a divider, then Ignore and Add to Dictionary.
+pub fn menu_items(sugg: &[Vec<u8>]) -> Vec<u8> {
+    let mut items = Vec::new();
+    if sugg.is_empty() {
+        items.extend_from_slice(NO_SUGGESTIONS);
+        items.push(b'|');
+    }
+    for s in sugg {
+        items.extend_from_slice(s);
+        items.push(b'|');
+    }
+    items.extend_from_slice(b"-|");
+    items.extend_from_slice(IGNORE_LABEL);
+    items.push(b'|');
+    items.extend_from_slice(ADD_LABEL);
+    items
+}
+
+fn is_letter(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b >= 0x80
+}
+
+fn is_token_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'\'' || b >= 0x80
+}
+
+/// Byte ranges of the words in `text` the checker rejects.
+///
+/// Tokens containing digits or underscores (identifiers, numbers) are never
+/// checked; apostrophes are kept inside words ("don't") but trimmed at the
+/// edges.
+pub fn misspelled_ranges(text: &[u8]) -> Vec<(usize, usize)> {
+    let mut out = Vec::new();
+    if !is_active() {
+        return out;
+    }
+    let mut i = 0;
+    while i < text.len() {
+        if !is_token_char(text[i]) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < text.len() && is_token_char(text[i]) {
+            i += 1;
+        }
+        if let Some((s, e)) = trim_word(text, start, i) {
+            if is_misspelled(&text[s..e]) {
+                out.push((s, e));
+            }
+        }
+    }
+    out
+}
+
+/// Byte range of the checkable word containing `pos`, if any.
+pub fn word_at(text: &[u8], pos: usize) -> Option<(usize, usize)> {
+    if pos >= text.len() || !is_token_char(text[pos]) {
+        return None;
+    }
+    let mut start = pos;
+    while start > 0 && is_token_char(text[start - 1]) {
+        start -= 1;
+    }
+    let mut end = pos;
+    while end < text.len() && is_token_char(text[end]) {
+        end += 1;
+    }
+    let (s, e) = trim_word(text, start, end)?;
+    if pos >= s && pos < e { Some((s, e)) } else { None }
+}
+
+/// Strip edge apostrophes from the token `text[start..end]`. Returns None
+/// for tokens that are not plain words.
+fn trim_word(text: &[u8], mut start: usize, mut end: usize) -> Option<(usize, usize)> {
+    while start < end && text[start] == b'\'' { start += 1; }
+    while end > start && text[end - 1] == b'\'' { end -= 1; }
+    let word = &text[start..end];
+    if word.is_empty() || !word.iter().all(|&b| is_letter(b) || b == b'\'') {
+        return None;
+    }
+    Some((start, end))
+}
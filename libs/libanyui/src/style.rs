@@ -0,0 +1,307 @@
+//! Styles — named property bags applied by control kind or class name.
+//!
+//! A style (`anyui_style_create`) holds values for any of the `PROP_*`
+//! properties below. It applies to a control in two ways:
+//!
+//! - **By kind** — `anyui_style_apply_kind` makes it the style of every
+//!   control of one `ControlKind` (e.g. all Buttons).
+//! - **By class** — a control whose class list (`anyui_set_class`, names
+//!   separated by spaces) contains the style's name.
+//!
+//! Values cascade: kind style, then the class styles in list order, then
+//! inline setters (`anyui_set_color`, `anyui_set_padding`, ...), each
+//! overriding the previous. A property set inline on a control is never
+//! touched by styles again.
+//!
+//! Styles are resolved once per frame, before layout, and only for controls
+//! that are out of date: changing a style or a kind binding restyles every
+//! control, creating a control or changing its class restyles just that one.
+//! When a style stops supplying a property, the control gets back the value
+//! it had before the style was applied.
+
+use alloc::vec::Vec;
+use crate::control::Control;
+
+// ── Properties ───────────────────────────────────────────────────────
+
+pub const PROP_COLOR: u32 = 0;
+pub const PROP_TEXT_COLOR: u32 = 1;
+pub const PROP_FONT_SIZE: u32 = 2;
+pub const PROP_FONT_ID: u32 = 3;
+pub const PROP_PADDING_LEFT: u32 = 4;
+pub const PROP_PADDING_TOP: u32 = 5;
+pub const PROP_PADDING_RIGHT: u32 = 6;
+pub const PROP_PADDING_BOTTOM: u32 = 7;
+pub const PROP_MARGIN_LEFT: u32 = 8;
+pub const PROP_MARGIN_TOP: u32 = 9;
+pub const PROP_MARGIN_RIGHT: u32 = 10;
+pub const PROP_MARGIN_BOTTOM: u32 = 11;
+pub const PROP_MIN_WIDTH: u32 = 12;
+pub const PROP_MIN_HEIGHT: u32 = 13;
+pub const PROP_CURSOR: u32 = 14;
+pub const PROP_COUNT: usize = 15;
+
+/// Bit of `prop` in property masks.
+pub const fn bit(prop: u32) -> u32 {
+    1 << prop
+}
+
+pub const PADDING_BITS: u32 = bit(PROP_PADDING_LEFT) | bit(PROP_PADDING_TOP)
+    | bit(PROP_PADDING_RIGHT) | bit(PROP_PADDING_BOTTOM);
+pub const MARGIN_BITS: u32 = bit(PROP_MARGIN_LEFT) | bit(PROP_MARGIN_TOP)
+    | bit(PROP_MARGIN_RIGHT) | bit(PROP_MARGIN_BOTTOM);
+
+/// Properties that affect layout rather than just appearance.
+const LAYOUT_BITS: u32 = PADDING_BITS | MARGIN_BITS | bit(PROP_FONT_SIZE) | bit(PROP_FONT_ID)
+    | bit(PROP_MIN_WIDTH) | bit(PROP_MIN_HEIGHT);
+
+// ── Style sheet ──────────────────────────────────────────────────────
+
+/// One named style.
+struct Style {
+    name: Vec<u8>,
+    /// Properties this style supplies.
+    mask: u32,
+    values: [u32; PROP_COUNT],
+}
+
+/// All styles of the process and their kind bindings.
+pub(crate) struct StyleSheet {
+    /// Style id N is `styles[N - 1]`.
+    styles: Vec<Style>,
+    /// (ControlKind, style id).
+    kinds: Vec<(u32, u32)>,
+    /// Bumped on every change that can affect any control; 0 until the
+    /// first style exists.
+    generation: u32,
+    /// Some control is out of date.
+    pending: bool,
+}
+
+impl StyleSheet {
+    pub const fn new() -> Self {
+        Self { styles: Vec::new(), kinds: Vec::new(), generation: 0, pending: false }
+    }
+
+    /// Style id for `name`, creating an empty style if there is none yet.
+    pub fn create(&mut self, name: &[u8]) -> u32 {
+        if let Some(i) = self.styles.iter().position(|s| s.name == name) {
+            return i as u32 + 1;
+        }
+        self.styles.push(Style { name: name.to_vec(), mask: 0, values: [0; PROP_COUNT] });
+        self.invalidate();
+        self.styles.len() as u32
+    }
+
+    /// Set (`Some`) or remove (`None`) one property of a style.
+    pub fn set(&mut self, style: u32, prop: u32, value: Option<u32>) -> bool {
+        let s = match self.styles.get_mut((style as usize).wrapping_sub(1)) {
+            Some(s) if (prop as usize) < PROP_COUNT => s,
+            _ => return false,
+        };
+        match value {
+            Some(v) => {
+                s.mask |= bit(prop);
+                s.values[prop as usize] = v;
+            }
+            None => s.mask &= !bit(prop),
+        }
+        self.invalidate();
+        true
+    }
+
+    /// Make `style` the style of every control of `kind` (0 = none).
+    pub fn apply_kind(&mut self, kind: u32, style: u32) {
+        self.kinds.retain(|&(k, _)| k != kind);
+        if style != 0 && (style as usize) <= self.styles.len() {
+            self.kinds.push((kind, style));
+        }
+        self.invalidate();
+    }
+
+    /// Restyle every control on the next frame.
+    fn invalidate(&mut self) {
+        self.generation = self.generation.wrapping_add(1).max(1);
+        self.pending = true;
+    }
+
+    /// Restyle controls created since the last frame (no-op while no style exists).
+    pub fn control_added(&mut self) {
+        if self.generation != 0 {
+            self.pending = true;
+        }
+    }
+
+    /// Restyle one control whose class changed.
+    pub fn restyle(&mut self, state: &mut StyleState) {
+        state.applied = 0;
+        self.control_added();
+    }
+
+    fn by_id(&self, id: u32) -> Option<&Style> {
+        self.styles.get((id as usize).wrapping_sub(1))
+    }
+
+    /// Cascade kind and class styles into (mask, values).
+    fn cascade(&self, kind: u32, class: &[u8]) -> (u32, [u32; PROP_COUNT]) {
+        let mut mask = 0;
+        let mut values = [0; PROP_COUNT];
+        let mut merge = |s: &Style| {
+            for p in 0..PROP_COUNT {
+                if s.mask & (1 << p) != 0 {
+                    values[p] = s.values[p];
+                }
+            }
+            mask |= s.mask;
+        };
+        if let Some(&(_, id)) = self.kinds.iter().find(|&&(k, _)| k == kind) {
+            if let Some(s) = self.by_id(id) {
+                merge(s);
+            }
+        }
+        for name in class.split(|&b| b == b' ').filter(|n| !n.is_empty()) {
+            if let Some(s) = self.styles.iter().find(|s| s.name == name) {
+                merge(s);
+            }
+        }
+        (mask, values)
+    }
+}
+
+// ── Per-control state ────────────────────────────────────────────────
+
+/// Styling state of one control (`ControlBase::style`).
+pub struct StyleState {
+    /// Class names, separated by spaces.
+    pub class: Vec<u8>,
+    /// Properties set through inline setters; styles leave them alone.
+    pub inline: u32,
+    /// Properties currently supplied by a style.
+    styled: u32,
+    /// Values the styled properties had before a style took them over.
+    saved: [u32; PROP_COUNT],
+    /// Style sheet generation this control was last resolved against.
+    applied: u32,
+}
+
+impl StyleState {
+    pub const fn new() -> Self {
+        Self { class: Vec::new(), inline: 0, styled: 0, saved: [0; PROP_COUNT], applied: 0 }
+    }
+}
+
+/// Resolve styles for every out-of-date control. Returns true if a
+/// layout-affecting property changed.
+pub(crate) fn resolve_all(st: &mut crate::AnyuiState) -> bool {
+    if !st.styles.pending {
+        return false;
+    }
+    st.styles.pending = false;
+    let sheet = &st.styles;
+    let mut relayout = false;
+    for ctrl in st.controls.iter_mut() {
+        if ctrl.base().style.applied != sheet.generation {
+            relayout |= resolve(ctrl.as_mut(), sheet);
+        }
+    }
+    relayout
+}
+
+fn resolve(ctrl: &mut dyn Control, sheet: &StyleSheet) -> bool {
+    let kind = ctrl.kind() as u32;
+    let (mask, values) = sheet.cascade(kind, &ctrl.base().style.class);
+    let mut changed = 0;
+    for p in 0..PROP_COUNT as u32 {
+        let b = bit(p);
+        let s = &mut ctrl.base_mut().style;
+        if s.inline & b != 0 {
+            s.styled &= !b;
+            continue;
+        }
+        let styled = s.styled & b != 0;
+        if mask & b != 0 {
+            if !styled {
+                let Some(orig) = read(ctrl, p) else { continue };
+                let s = &mut ctrl.base_mut().style;
+                s.saved[p as usize] = orig;
+                s.styled |= b;
+            }
+            if read(ctrl, p) != Some(values[p as usize]) {
+                write(ctrl, p, values[p as usize]);
+                changed |= b;
+            }
+        } else if styled {
+            let s = &mut ctrl.base_mut().style;
+            s.styled &= !b;
+            let orig = s.saved[p as usize];
+            write(ctrl, p, orig);
+            changed |= b;
+        }
+    }
+    let b = ctrl.base_mut();
+    b.style.applied = sheet.generation;
+    if changed != 0 {
+        b.mark_dirty();
+    }
+    changed & LAYOUT_BITS != 0
+}
+
+/// Current value of `prop`, or `None` if the control does not have it
+/// (text properties of non-text controls).
+fn read(ctrl: &dyn Control, prop: u32) -> Option<u32> {
+    let b = ctrl.base();
+    Some(match prop {
+        PROP_COLOR => b.color,
+        PROP_TEXT_COLOR => ctrl.text_base()?.text_style.text_color,
+        PROP_FONT_SIZE => {
+            ctrl.text_base()?;
+            ctrl.get_font_size() as u32
+        }
+        PROP_FONT_ID => ctrl.text_base()?.text_style.font_id as u32,
+        PROP_PADDING_LEFT => b.padding.left as u32,
+        PROP_PADDING_TOP => b.padding.top as u32,
+        PROP_PADDING_RIGHT => b.padding.right as u32,
+        PROP_PADDING_BOTTOM => b.padding.bottom as u32,
+        PROP_MARGIN_LEFT => b.margin.left as u32,
+        PROP_MARGIN_TOP => b.margin.top as u32,
+        PROP_MARGIN_RIGHT => b.margin.right as u32,
+        PROP_MARGIN_BOTTOM => b.margin.bottom as u32,
+        PROP_MIN_WIDTH => b.min_w,
+        PROP_MIN_HEIGHT => b.min_h,
+        PROP_CURSOR => b.cursor,
+        _ => return None,
+    })
+}
+
+fn write(ctrl: &mut dyn Control, prop: u32, v: u32) {
+    match prop {
+        PROP_COLOR => ctrl.set_color(v),
+        PROP_FONT_SIZE => ctrl.set_font_size(v as u16),
+        PROP_TEXT_COLOR | PROP_FONT_ID => {
+            if let Some(tb) = ctrl.text_base_mut() {
+                if prop == PROP_TEXT_COLOR {
+                    tb.text_style.text_color = v;
+                } else {
+                    tb.text_style.font_id = v as u16;
+                }
+            }
+        }
+        _ => {
+            let b = ctrl.base_mut();
+            match prop {
+                PROP_PADDING_LEFT => b.padding.left = v as i32,
+                PROP_PADDING_TOP => b.padding.top = v as i32,
+                PROP_PADDING_RIGHT => b.padding.right = v as i32,
+                PROP_PADDING_BOTTOM => b.padding.bottom = v as i32,
+                PROP_MARGIN_LEFT => b.margin.left = v as i32,
+                PROP_MARGIN_TOP => b.margin.top = v as i32,
+                PROP_MARGIN_RIGHT => b.margin.right = v as i32,
+                PROP_MARGIN_BOTTOM => b.margin.bottom = v as i32,
+                PROP_MIN_WIDTH => b.min_w = v,
+                PROP_MIN_HEIGHT => b.min_h = v,
+                PROP_CURSOR => b.cursor = v,
+                _ => {}
+            }
+        }
+    }
+}
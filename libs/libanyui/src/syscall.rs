@@ -2,8 +2,10 @@
 
 pub use libsyscall::{
     exit, yield_cpu, sleep, sbrk, mmap, munmap, uptime_ms,
-    dll_load, readdir, getcwd, write, open, read, close,
-    evt_chan_poll, evt_chan_wait, evt_chan_emit,
+    dll_load, get_tid, thread_create, readdir, getcwd, write, open, read, close, file_size,
+    evt_chan_create, evt_chan_poll, evt_chan_wait, evt_chan_emit,
+    evt_chan_subscribe, evt_chan_unsubscribe, evt_chan_emit_to, evt_chan_wait_any,
+    shm_create, shm_map, shm_unmap, shm_destroy,
 };
 
 /// Create a directory (accepts &[u8] path).
@@ -0,0 +1,49 @@
+//! Per-control user data — an app-defined 64-bit tag with an optional
+//! destructor.
+//!
+//! `anyui_set_tag` stores any value with a control (an index, a handle, a
+//! pointer to app state) so apps don't keep side tables keyed by ControlId.
+//! A destructor set with `anyui_set_tag_destructor` is called exactly once
+//! with the control's id and tag when the control is removed — directly,
+//! with its parent or window, or by `anyui_clear_children` — after the
+//! control is gone, so it may call back into anyui. Replacing the tag does
+//! not call it. Control ids are never reused, so a tag can't outlive its
+//! control and show up on a later one.
+//!
+//! # Usage (via client API)
+//! ```ignore
+//! row.set_user_data(Document { path, dirty: false });
+//! row.with_user_data(|doc: &mut Document| doc.dirty = true);
+//! ```
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::control::{Control, ControlId};
+
+/// Called with a removed control's id and tag.
+pub type TagDestructor = extern "C" fn(ControlId, u64);
+
+/// Take the pending destructor calls of the controls in `ids`, which are
+/// about to be removed. Run them with [`run_destructors`] once they are.
+pub fn take_destructors(
+    controls: &mut [Box<dyn Control>],
+    ids: &[ControlId],
+) -> Vec<(TagDestructor, ControlId, u64)> {
+    let mut out = Vec::new();
+    for c in controls.iter_mut().filter(|c| ids.contains(&c.id())) {
+        let id = c.id();
+        let b = c.base_mut();
+        if let Some(f) = b.tag_destructor.take() {
+            out.push((f, id, b.tag));
+        }
+    }
+    out
+}
+
+/// Call destructors taken by [`take_destructors`].
+pub fn run_destructors(calls: Vec<(TagDestructor, ControlId, u64)>) {
+    for (f, id, tag) in calls {
+        f(id, tag);
+    }
+}
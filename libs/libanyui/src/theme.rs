@@ -5,6 +5,14 @@
 //! time.  Falls back to built-in defaults if the files are missing.
 //!
 //! The theme value is stored locally and set via `set_theme()`.
+//!
+//! Apps may override individual palette roles (`set_color()`) or load a
+//! branded `.conf` of their own (`load_custom_conf()`).  Overrides are
+//! per-process and are re-applied whenever the palettes are reloaded.
+//!
+//! A high-contrast variant of each palette (`{dark,light}_hc.conf`) is used
+//! while the system accessibility flag is set, unless the app forces it on
+//! or off with `set_high_contrast()`.  Overrides do not apply to it.
 
 use alloc::vec::Vec;
 
@@ -55,9 +63,23 @@ pub struct ThemeColors {
     pub placeholder_bg: u32,
 }
 
+/// Number of color roles in `ThemeColors`.  A role is the index of its
+/// field (every field is a `u32`, and the struct is `repr(C)`).
+pub const ROLE_COUNT: usize = core::mem::size_of::<ThemeColors>() / 4;
+
+impl ThemeColors {
+    /// Mutable access to a color by role index.
+    fn role_mut(&mut self, role: usize) -> Option<&mut u32> {
+        if role >= ROLE_COUNT {
+            return None;
+        }
+        Some(unsafe { &mut *(self as *mut ThemeColors as *mut u32).add(role) })
+    }
+}
+
 // ── Built-in default palettes (overwritten by .conf at runtime) ─────────
 
-static mut DARK: ThemeColors = ThemeColors {
+const DEFAULT_DARK: ThemeColors = ThemeColors {
     window_bg:        0xFF1E1E1E,
     text:             0xFFE6E6E6,
     text_secondary:   0xFF969696,
@@ -96,7 +118,7 @@ static mut DARK: ThemeColors = ThemeColors {
     placeholder_bg:   0xFF2A2A2A,
 };
 
-static mut LIGHT: ThemeColors = ThemeColors {
+const DEFAULT_LIGHT: ThemeColors = ThemeColors {
     window_bg:        0xFFF5F5F7,
     text:             0xFF1D1D1F,
     text_secondary:   0xFF86868B,
@@ -135,6 +157,89 @@ static mut LIGHT: ThemeColors = ThemeColors {
     placeholder_bg:   0xFFE0E0E0,
 };
 
+const DEFAULT_HC_DARK: ThemeColors = ThemeColors {
+    window_bg:        0xFF000000,
+    text:             0xFFFFFFFF,
+    text_secondary:   0xFFE0E0E0,
+    text_disabled:    0xFFA0A0A0,
+    accent:           0xFF3FA9FF,
+    accent_hover:     0xFF7CC4FF,
+    destructive:      0xFFFF6A5F,
+    success:          0xFF3CF27A,
+    warning:          0xFFFFE100,
+    control_bg:       0xFF000000,
+    control_hover:    0xFF1F3A5C,
+    control_pressed:  0xFF3FA9FF,
+    input_bg:         0xFF000000,
+    input_border:     0xFFFFFFFF,
+    input_focus:      0xFFFFE100,
+    separator:        0xFFFFFFFF,
+    selection:        0xFF1A5FB4,
+    sidebar_bg:       0xFF000000,
+    card_bg:          0xFF000000,
+    card_border:      0xFFFFFFFF,
+    badge_red:        0xFFFF6A5F,
+    toggle_on:        0xFF3CF27A,
+    toggle_off:       0xFFA0A0A0,
+    toggle_thumb:     0xFFFFFFFF,
+    scrollbar:        0xFFFFFFFF,
+    scrollbar_track:  0xFF000000,
+    check_mark:       0xFF000000,
+    toolbar_bg:       0xFF000000,
+    tab_inactive_bg:  0xFF000000,
+    tab_hover_bg:     0xFF1F3A5C,
+    tab_border_active:0xFFFFE100,
+    editor_bg:        0xFF000000,
+    editor_line_hl:   0xFF202020,
+    editor_selection:  0xFF1A5FB4,
+    alt_row_bg:       0xFF141414,
+    placeholder_bg:   0xFF202020,
+};
+
+const DEFAULT_HC_LIGHT: ThemeColors = ThemeColors {
+    window_bg:        0xFFFFFFFF,
+    text:             0xFF000000,
+    text_secondary:   0xFF202020,
+    text_disabled:    0xFF606060,
+    accent:           0xFF0040C0,
+    accent_hover:     0xFF002C8A,
+    destructive:      0xFFB00000,
+    success:          0xFF006400,
+    warning:          0xFF8A4B00,
+    control_bg:       0xFFFFFFFF,
+    control_hover:    0xFFD6E4FF,
+    control_pressed:  0xFF0040C0,
+    input_bg:         0xFFFFFFFF,
+    input_border:     0xFF000000,
+    input_focus:      0xFF0040C0,
+    separator:        0xFF000000,
+    selection:        0xFF0040C0,
+    sidebar_bg:       0xFFFFFFFF,
+    card_bg:          0xFFFFFFFF,
+    card_border:      0xFF000000,
+    badge_red:        0xFFB00000,
+    toggle_on:        0xFF006400,
+    toggle_off:       0xFF606060,
+    toggle_thumb:     0xFFFFFFFF,
+    scrollbar:        0xFF000000,
+    scrollbar_track:  0xFFFFFFFF,
+    check_mark:       0xFFFFFFFF,
+    toolbar_bg:       0xFFFFFFFF,
+    tab_inactive_bg:  0xFFFFFFFF,
+    tab_hover_bg:     0xFFD6E4FF,
+    tab_border_active:0xFF0040C0,
+    editor_bg:        0xFFFFFFFF,
+    editor_line_hl:   0xFFEBEBEB,
+    editor_selection:  0xFFB3CCFF,
+    alt_row_bg:       0xFFF0F0F0,
+    placeholder_bg:   0xFFE0E0E0,
+};
+
+static mut DARK: ThemeColors = DEFAULT_DARK;
+static mut LIGHT: ThemeColors = DEFAULT_LIGHT;
+static mut HC_DARK: ThemeColors = DEFAULT_HC_DARK;
+static mut HC_LIGHT: ThemeColors = DEFAULT_HC_LIGHT;
+
 // ── Theme flag and accessors ────────────────────────────────────────────────
 
 /// Address of the theme flag in the shared uisys DLIB export page.
@@ -165,7 +270,14 @@ pub fn get_theme() -> u32 {
 #[inline(always)]
 pub fn colors() -> &'static ThemeColors {
     let t = unsafe { core::ptr::read_volatile(THEME_SHARED_ADDR) };
-    unsafe { if t == 0 { &DARK } else { &LIGHT } }
+    unsafe {
+        match (HIGH_CONTRAST, t == 0) {
+            (false, true) => &DARK,
+            (false, false) => &LIGHT,
+            (true, true) => &HC_DARK,
+            (true, false) => &HC_LIGHT,
+        }
+    }
 }
 
 /// Check if the current theme is light.
@@ -187,6 +299,8 @@ pub fn colors_ptr() -> *const ThemeColors {
 /// Paths for on-disk theme definitions.
 const DARK_CONF_PATH: &str = "/System/compositor/themes/dark.conf";
 const LIGHT_CONF_PATH: &str = "/System/compositor/themes/light.conf";
+const HC_DARK_CONF_PATH: &str = "/System/compositor/themes/dark_hc.conf";
+const HC_LIGHT_CONF_PATH: &str = "/System/compositor/themes/light_hc.conf";
 
 /// Path for the user's current accent style preference.
 const CURRENT_STYLE_PATH: &str = "/System/compositor/themes/current_style";
@@ -204,6 +318,12 @@ pub fn load_from_disk() {
     if let Some(data) = read_file(LIGHT_CONF_PATH) {
         unsafe { parse_conf_into(&data, &mut LIGHT); }
     }
+    if let Some(data) = read_file(HC_DARK_CONF_PATH) {
+        unsafe { parse_conf_into(&data, &mut HC_DARK); }
+    }
+    if let Some(data) = read_file(HC_LIGHT_CONF_PATH) {
+        unsafe { parse_conf_into(&data, &mut HC_LIGHT); }
+    }
     load_current_style();
 }
 
@@ -315,45 +435,142 @@ fn parse_conf_into(data: &[u8], tc: &mut ThemeColors) {
         let key = line[..eq].trim();
         let val_str = line[eq + 1..].trim();
         let Some(val) = parse_hex_color(val_str) else { continue };
+        // Unknown keys are silently skipped.
+        if let Some(slot) = role_for_key(key).and_then(|r| tc.role_mut(r)) {
+            *slot = val;
+        }
+    }
+}
 
-        match key {
-            "WINDOW_BG"        => tc.window_bg = val,
-            "TEXT"             => tc.text = val,
-            "TEXT_SECONDARY"   => tc.text_secondary = val,
-            "TEXT_DISABLED"    => tc.text_disabled = val,
-            "ACCENT"           => tc.accent = val,
-            "ACCENT_HOVER"     => tc.accent_hover = val,
-            "DESTRUCTIVE"      => tc.destructive = val,
-            "SUCCESS"          => tc.success = val,
-            "WARNING"          => tc.warning = val,
-            "CONTROL_BG"       => tc.control_bg = val,
-            "CONTROL_HOVER"    => tc.control_hover = val,
-            "CONTROL_PRESSED"  => tc.control_pressed = val,
-            "INPUT_BG"         => tc.input_bg = val,
-            "INPUT_BORDER"     => tc.input_border = val,
-            "INPUT_FOCUS"      => tc.input_focus = val,
-            "SEPARATOR"        => tc.separator = val,
-            "SELECTION"        => tc.selection = val,
-            "SIDEBAR_BG"       => tc.sidebar_bg = val,
-            "CARD_BG"          => tc.card_bg = val,
-            "CARD_BORDER"      => tc.card_border = val,
-            "BADGE_RED"        => tc.badge_red = val,
-            "TOGGLE_ON"        => tc.toggle_on = val,
-            "TOGGLE_OFF"       => tc.toggle_off = val,
-            "TOGGLE_THUMB"     => tc.toggle_thumb = val,
-            "SCROLLBAR"        => tc.scrollbar = val,
-            "SCROLLBAR_TRACK"  => tc.scrollbar_track = val,
-            "CHECK_MARK"       => tc.check_mark = val,
-            "TOOLBAR_BG"       => tc.toolbar_bg = val,
-            "TAB_INACTIVE_BG"  => tc.tab_inactive_bg = val,
-            "TAB_HOVER_BG"     => tc.tab_hover_bg = val,
-            "TAB_BORDER_ACTIVE"=> tc.tab_border_active = val,
-            "EDITOR_BG"        => tc.editor_bg = val,
-            "EDITOR_LINE_HL"   => tc.editor_line_hl = val,
-            "EDITOR_SELECTION"  => tc.editor_selection = val,
-            "ALT_ROW_BG"       => tc.alt_row_bg = val,
-            "PLACEHOLDER_BG"   => tc.placeholder_bg = val,
-            _ => {} // unknown key — silently skip
+/// Map a `.conf` key to its color role index.
+fn role_for_key(key: &str) -> Option<usize> {
+    let role = match key {
+        "WINDOW_BG"        => 0,
+        "TEXT"             => 1,
+        "TEXT_SECONDARY"   => 2,
+        "TEXT_DISABLED"    => 3,
+        "ACCENT"           => 4,
+        "ACCENT_HOVER"     => 5,
+        "DESTRUCTIVE"      => 6,
+        "SUCCESS"          => 7,
+        "WARNING"          => 8,
+        "CONTROL_BG"       => 9,
+        "CONTROL_HOVER"    => 10,
+        "CONTROL_PRESSED"  => 11,
+        "INPUT_BG"         => 12,
+        "INPUT_BORDER"     => 13,
+        "INPUT_FOCUS"      => 14,
+        "SEPARATOR"        => 15,
+        "SELECTION"        => 16,
+        "SIDEBAR_BG"       => 17,
+        "CARD_BG"          => 18,
+        "CARD_BORDER"      => 19,
+        "BADGE_RED"        => 20,
+        "TOGGLE_ON"        => 21,
+        "TOGGLE_OFF"       => 22,
+        "TOGGLE_THUMB"     => 23,
+        "SCROLLBAR"        => 24,
+        "SCROLLBAR_TRACK"  => 25,
+        "CHECK_MARK"       => 26,
+        "TOOLBAR_BG"       => 27,
+        "TAB_INACTIVE_BG"  => 28,
+        "TAB_HOVER_BG"     => 29,
+        "TAB_BORDER_ACTIVE"=> 30,
+        "EDITOR_BG"        => 31,
+        "EDITOR_LINE_HL"   => 32,
+        "EDITOR_SELECTION" => 33,
+        "ALT_ROW_BG"       => 34,
+        "PLACEHOLDER_BG"   => 35,
+        _ => return None,
+    };
+    Some(role)
+}
+
+// ── Per-process overrides ───────────────────────────────────────────────────
+
+/// App-specific color overrides, indexed `[palette][role]` (0 = dark,
+/// 1 = light).  `None` = use the system value.
+static mut OVERRIDES: [[Option<u32>; ROLE_COUNT]; 2] = [[None; ROLE_COUNT]; 2];
+
+/// Which palettes an override applies to.
+const PALETTE_DARK: u8 = 1;
+const PALETTE_LIGHT: u8 = 2;
+const PALETTE_BOTH: u8 = PALETTE_DARK | PALETTE_LIGHT;
+
+fn set_override(palettes: u8, role: usize, argb: u32) {
+    unsafe {
+        if palettes & PALETTE_DARK != 0 {
+            OVERRIDES[0][role] = Some(argb);
+            if let Some(slot) = DARK.role_mut(role) { *slot = argb; }
+        }
+        if palettes & PALETTE_LIGHT != 0 {
+            OVERRIDES[1][role] = Some(argb);
+            if let Some(slot) = LIGHT.role_mut(role) { *slot = argb; }
+        }
+    }
+}
+
+/// Override one color role in both palettes for this process.
+/// Returns `false` if `role` is out of range.
+pub fn set_color(role: u32, argb: u32) -> bool {
+    let role = role as usize;
+    if role >= ROLE_COUNT {
+        return false;
+    }
+    set_override(PALETTE_BOTH, role, argb);
+    true
+}
+
+/// Load a branded theme `.conf` as per-process overrides.
+///
+/// Keys before any section header apply to both palettes; keys after a
+/// `[dark]` or `[light]` header apply to that palette only.  Returns `false`
+/// if the file cannot be read.
+pub fn load_custom_conf(path: &str) -> bool {
+    let Some(data) = read_file(path) else { return false };
+    let Ok(text) = core::str::from_utf8(&data) else { return false };
+
+    let mut palettes = PALETTE_BOTH;
+    for line in text.split('\n') {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line {
+            "[dark]" => { palettes = PALETTE_DARK; continue; }
+            "[light]" => { palettes = PALETTE_LIGHT; continue; }
+            _ => {}
+        }
+        let Some(eq) = line.find('=') else { continue };
+        let Some(val) = parse_hex_color(line[eq + 1..].trim()) else { continue };
+        if let Some(role) = role_for_key(line[..eq].trim()) {
+            set_override(palettes, role, val);
+        }
+    }
+    true
+}
+
+/// Drop all per-process overrides and reload the system palettes.
+pub fn reset_colors() {
+    unsafe { OVERRIDES = [[None; ROLE_COUNT]; 2]; }
+    reload();
+}
+
+/// Rebuild both palettes from the built-in defaults, the system `.conf`
+/// files and the current accent style, then re-apply this process's
+/// overrides.  Called when the compositor broadcasts a theme change.
+pub fn reload() {
+    unsafe {
+        DARK = DEFAULT_DARK;
+        LIGHT = DEFAULT_LIGHT;
+        HC_DARK = DEFAULT_HC_DARK;
+        HC_LIGHT = DEFAULT_HC_LIGHT;
+    }
+    load_from_disk();
+    unsafe {
+        for role in 0..ROLE_COUNT {
+            if let (Some(v), Some(slot)) = (OVERRIDES[0][role], DARK.role_mut(role)) { *slot = v; }
+            if let (Some(v), Some(slot)) = (OVERRIDES[1][role], LIGHT.role_mut(role)) { *slot = v; }
         }
     }
 }
@@ -405,12 +622,90 @@ pub fn with_alpha(color: u32, alpha: u32) -> u32 {
     (color & 0x00FFFFFF) | ((alpha & 0xFF) << 24)
 }
 
+// ── Accessibility ─────────────────────────────────────────────────────
+
+/// Address of the `accessibility` field in the uisys.dlib shared page
+/// (offset 0x18): bit 0 = high contrast, bits [31:16] = UI zoom percent.
+const UISYS_ACCESSIBILITY_ADDR: *const u32 = 0x0400_0018 as *const u32;
+
+/// Accessibility flag bit: high-contrast palettes requested system-wide.
+pub const A11Y_HIGH_CONTRAST: u32 = 0x01;
+
+/// Per-process high-contrast modes for `set_high_contrast()`.
+pub const HC_FOLLOW_SYSTEM: u32 = 0;
+pub const HC_OFF: u32 = 1;
+pub const HC_ON: u32 = 2;
+
+/// Per-process high-contrast mode (one of the `HC_*` constants).
+static mut HIGH_CONTRAST_MODE: u32 = HC_FOLLOW_SYSTEM;
+
+/// Effective high-contrast state — refreshed via `refresh_accessibility_cache()`.
+static mut HIGH_CONTRAST: bool = false;
+
+/// Cached system UI zoom (percent) from the shared page.
+static mut SYSTEM_ZOOM: u32 = 100;
+
+/// Read the raw accessibility word from the shared DLIB page.
+pub fn accessibility_flags() -> u32 {
+    unsafe { core::ptr::read_volatile(UISYS_ACCESSIBILITY_ADDR) }
+}
+
+/// Refresh the cached high-contrast state and system zoom from the shared
+/// page.  Called at init and when the compositor broadcasts a change.
+pub fn refresh_accessibility_cache() {
+    let flags = accessibility_flags();
+    let zoom = flags >> 16;
+    unsafe {
+        SYSTEM_ZOOM = if zoom >= 100 && zoom <= 200 { zoom } else { 100 };
+        HIGH_CONTRAST = match HIGH_CONTRAST_MODE {
+            HC_OFF => false,
+            HC_ON => true,
+            _ => flags & A11Y_HIGH_CONTRAST != 0,
+        };
+    }
+}
+
+/// Force the high-contrast palette on or off for this process, or follow
+/// the system setting again (`HC_FOLLOW_SYSTEM`).
+pub fn set_high_contrast(mode: u32) {
+    unsafe { HIGH_CONTRAST_MODE = if mode <= HC_ON { mode } else { HC_FOLLOW_SYSTEM }; }
+    refresh_accessibility_cache();
+}
+
+/// Whether the high-contrast palette is currently in effect.
+#[inline(always)]
+pub fn is_high_contrast() -> bool {
+    unsafe { HIGH_CONTRAST }
+}
+
+/// System default UI zoom for windows that have no zoom of their own.
+pub fn system_zoom() -> u32 {
+    unsafe { SYSTEM_ZOOM }
+}
+
+/// Clamp a requested window zoom to 100–200% in steps of 25
+/// (0 = follow the system zoom).
+pub fn normalize_zoom(percent: u32) -> u32 {
+    if percent == 0 {
+        return 0;
+    }
+    ((percent.max(100).min(200) + 12) / 25) * 25
+}
+
 // ── DPI Scale Factor ──────────────────────────────────────────────────
 
 /// Address of the `scale` field in the uisys.dlib shared page (offset 0x14).
 const UISYS_SCALE_ADDR: *const u32 = 0x0400_0014 as *const u32;
 
-/// Cached scale factor — refreshed once per frame via `refresh_scale_cache()`.
+/// System DPI scale from the shared page (without window zoom).
+static mut SYSTEM_SCALE: u32 = 100;
+
+/// Zoom of the window currently being processed (percent, 100 = none).
+static mut WINDOW_ZOOM: u32 = 100;
+
+/// Cached effective scale factor (system scale × window zoom) — refreshed
+/// once per frame via `refresh_scale_cache()` and switched per window via
+/// `set_window_zoom()`.
 static mut CACHED_SCALE: u32 = 100;
 
 /// Refresh the cached scale factor from the shared DLIB page.
@@ -418,10 +713,27 @@ static mut CACHED_SCALE: u32 = 100;
 pub fn refresh_scale_cache() {
     unsafe {
         let v = core::ptr::read_volatile(UISYS_SCALE_ADDR);
-        CACHED_SCALE = if v >= 100 && v <= 300 { v } else { 100 };
+        SYSTEM_SCALE = if v >= 100 && v <= 300 { v } else { 100 };
+        CACHED_SCALE = SYSTEM_SCALE * WINDOW_ZOOM / 100;
     }
 }
 
+/// Make `scale()`/`unscale()` apply a window's zoom on top of the system
+/// scale.  The event loop sets this around each window's event dispatch,
+/// layout and rendering, and resets it to 100 afterwards.
+pub fn set_window_zoom(percent: u32) {
+    unsafe {
+        WINDOW_ZOOM = if percent >= 100 { percent } else { 100 };
+        CACHED_SCALE = SYSTEM_SCALE * WINDOW_ZOOM / 100;
+    }
+}
+
+/// System DPI scale factor, ignoring any window zoom.
+#[inline(always)]
+pub fn system_scale_factor() -> u32 {
+    unsafe { SYSTEM_SCALE }
+}
+
 /// Current DPI scale factor as percentage (100 = 1x, 200 = 2x, etc.).
 #[inline(always)]
 pub fn scale_factor() -> u32 {
@@ -0,0 +1,340 @@
+//! Form validation — validators attached to input controls.
+//!
+//! The application attaches validators to a control (`anyui_validate_required`,
+//! `anyui_validate_range`, `anyui_validate_pattern`, `anyui_validate_custom`),
+//! each with the message to show when it fails. They are checked in the
+//! order they were added; the message of the first failing one becomes the
+//! control's error.
+//!
+//! A control is validated when it loses focus, and every validated control
+//! under a root by `anyui_form_validate` (call it when the form is
+//! submitted), which returns the first invalid control for focusing. An
+//! invalid control is checked again whenever its value changes, so the
+//! error goes away as soon as the input is fixed.
+//!
+//! Invalid controls get a red outline, and their message is shown in a
+//! bubble just below them (a framework-managed Tooltip control, placed after
+//! every layout and hidden while the control is scrolled out of view).
+//!
+//! The value checked is the text of text controls (TextField, TextArea,
+//! SearchField, TextEditor, ...) and the decimal `state` of all others
+//! (Slider, Stepper, Checkbox, DropDown, ...). `required` rejects blank text
+//! or a zero state; range and pattern validators accept an empty value.
+
+use alloc::vec::Vec;
+
+use crate::control::{self, Control, ControlId, ControlKind};
+use crate::pattern::Regex;
+
+/// Custom validator: `(control, value, len, userdata) -> 1` if the value is valid.
+pub type ValidateFn = extern "C" fn(ControlId, *const u8, u32, u64) -> u32;
+
+/// Message bubble font size and height in logical pixels.
+const MESSAGE_FONT_SIZE: u16 = 12;
+const MESSAGE_H: u32 = 22;
+/// Gap between a control and its message bubble.
+const MESSAGE_GAP: i32 = 4;
+
+pub enum Check {
+    Required,
+    /// Numeric value within `min..=max` (`.` or `,` as decimal separator).
+    Range { min: f64, max: f64 },
+    /// The whole value matches.
+    Pattern(Regex),
+    Custom { cb: ValidateFn, userdata: u64 },
+}
+
+pub struct Rule {
+    pub check: Check,
+    pub message: Vec<u8>,
+}
+
+/// Validators and error state of one control (`ControlBase::validation`).
+pub struct Validation {
+    pub rules: Vec<Rule>,
+    /// Message of the first failing rule (`None` = valid or not checked yet).
+    pub error: Option<Vec<u8>>,
+    /// Value at the last check (an invalid control is re-checked when it changes).
+    pub checked: Vec<u8>,
+    /// Message bubble (Tooltip control), created on the first error.
+    pub message_id: Option<ControlId>,
+}
+
+impl Validation {
+    /// Red outline over an invalid control at logical position `(x, y)`.
+    pub fn render(&self, surface: &crate::draw::Surface, x: i32, y: i32, w: u32, h: u32) {
+        if self.error.is_none() || w < 4 || h < 4 {
+            return;
+        }
+        let p = crate::draw::scale_bounds(0, 0, x, y, w, h);
+        let color = crate::theme::colors().destructive;
+        let r = crate::theme::input_corner();
+        crate::draw::draw_rounded_border(surface, p.x, p.y, p.w, p.h, r, color);
+        crate::draw::draw_rounded_border(surface, p.x + 1, p.y + 1, p.w - 2, p.h - 2, r.saturating_sub(1), color);
+    }
+}
+
+/// Attach a validator to control `id`. Returns false if there is no such control.
+pub(crate) fn add_rule(id: ControlId, check: Check, message: &[u8]) -> bool {
+    let st = crate::state();
+    let idx = match control::find_idx(&st.controls, id) {
+        Some(i) => i,
+        None => return false,
+    };
+    let v = st.controls[idx].base_mut().validation.get_or_insert_with(|| {
+        alloc::boxed::Box::new(Validation { rules: Vec::new(), error: None, checked: Vec::new(), message_id: None })
+    });
+    v.rules.push(Rule { check, message: message.to_vec() });
+    if !st.validated.contains(&id) {
+        st.validated.push(id);
+    }
+    true
+}
+
+/// Remove every validator of control `id` and its error.
+pub(crate) fn clear(id: ControlId) {
+    let st = crate::state();
+    if let Some(idx) = control::find_idx(&st.controls, id) {
+        if let Some(v) = st.controls[idx].base_mut().validation.as_deref_mut() {
+            v.rules.clear();
+        }
+    }
+    set_error(id, None, Vec::new());
+}
+
+/// The value validators see, and whether it is text.
+fn value_of(ctrl: &alloc::boxed::Box<dyn Control>) -> (Vec<u8>, bool) {
+    if let Some(te) = crate::as_text_editor_ref(ctrl) {
+        return (te.get_text(), true);
+    }
+    if matches!(ctrl.kind(), ControlKind::TextField | ControlKind::TextArea | ControlKind::SearchField) {
+        return (ctrl.text().to_vec(), true);
+    }
+    let mut digits = Vec::new();
+    let mut n = ctrl.state_val();
+    loop {
+        digits.insert(0, b'0' + (n % 10) as u8);
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    (digits, false)
+}
+
+fn parse_number(value: &[u8]) -> Option<f64> {
+    let text = core::str::from_utf8(value).ok()?.trim();
+    let text: alloc::string::String = text.chars().map(|c| if c == ',' { '.' } else { c }).collect();
+    text.parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/// Outcome of a built-in check (custom validators pass here).
+fn check_builtin(check: &Check, value: &[u8], is_text: bool) -> bool {
+    let empty = value.iter().all(|b| b.is_ascii_whitespace());
+    match check {
+        Check::Required if is_text => !empty,
+        Check::Required => value != b"0",
+        Check::Range { .. } | Check::Pattern(_) if is_text && empty => true,
+        Check::Range { min, max } => parse_number(value).map_or(false, |v| v >= *min && v <= *max),
+        Check::Pattern(re) => core::str::from_utf8(value).map_or(false, |s| re.full_match(s)),
+        Check::Custom { .. } => true,
+    }
+}
+
+/// Check every validator of control `id` and show or clear its error.
+/// Returns true if the control is valid (or has no validators). Must be
+/// called with no framework state borrowed: custom validators are
+/// application callbacks.
+pub(crate) fn validate(id: ControlId) -> bool {
+    let (value, is_text) = {
+        let st = crate::state();
+        match control::find_idx(&st.controls, id) {
+            Some(idx) => value_of(&st.controls[idx]),
+            None => return true,
+        }
+    };
+    let mut error = None;
+    let mut i = 0;
+    loop {
+        // Looked up again for every rule: a custom validator may have
+        // changed or removed the control.
+        let st = crate::state();
+        let rule = match control::find_idx(&st.controls, id)
+            .and_then(|idx| st.controls[idx].base().validation.as_deref())
+            .and_then(|v| v.rules.get(i))
+        {
+            Some(r) => r,
+            None => break,
+        };
+        let message = rule.message.clone();
+        let ok = match rule.check {
+            Check::Custom { cb, userdata } => cb(id, value.as_ptr(), value.len() as u32, userdata) != 0,
+            ref check => check_builtin(check, &value, is_text),
+        };
+        if !ok {
+            error = Some(message);
+            break;
+        }
+        i += 1;
+    }
+    let valid = error.is_none();
+    set_error(id, error, value);
+    valid
+}
+
+fn set_error(id: ControlId, error: Option<Vec<u8>>, checked: Vec<u8>) {
+    let st = crate::state();
+    let idx = match control::find_idx(&st.controls, id) {
+        Some(i) => i,
+        None => return,
+    };
+    let b = st.controls[idx].base_mut();
+    let changed = match b.validation.as_deref_mut() {
+        Some(v) => {
+            v.checked = checked;
+            let changed = v.error != error;
+            v.error = error;
+            changed
+        }
+        None => false,
+    };
+    if changed {
+        b.mark_dirty();
+    }
+}
+
+/// Validate every enabled, visible control with validators under `root`
+/// (including `root`), in tree order. Returns the first invalid one, or 0.
+pub(crate) fn validate_tree(root: ControlId) -> ControlId {
+    let mut ids = Vec::new();
+    collect(&crate::state().controls, root, &mut ids);
+    let mut first = 0;
+    for id in ids {
+        if !validate(id) && first == 0 {
+            first = id;
+        }
+    }
+    first
+}
+
+fn collect(controls: &[alloc::boxed::Box<dyn Control>], id: ControlId, out: &mut Vec<ControlId>) {
+    let idx = match control::find_idx(controls, id) {
+        Some(i) => i,
+        None => return,
+    };
+    let b = controls[idx].base();
+    if b.validation.as_ref().map_or(false, |v| !v.rules.is_empty()) && b.visible && !b.disabled {
+        out.push(id);
+    }
+    for &child in controls[idx].children() {
+        collect(controls, child, out);
+    }
+}
+
+/// Validate the control that lost focus since the last call, and invalid
+/// controls whose value changed. Called once per frame with no framework
+/// state borrowed.
+pub(crate) fn revalidate() {
+    let st = crate::state();
+    if st.validated.is_empty() {
+        st.validation_focus = st.focused;
+        return;
+    }
+    let mut due = Vec::new();
+    let blurred = core::mem::replace(&mut st.validation_focus, st.focused);
+    if let Some(prev) = blurred.filter(|&p| st.focused != Some(p)) {
+        if st.validated.contains(&prev) {
+            due.push(prev);
+        }
+    }
+    for &id in &st.validated {
+        if due.contains(&id) {
+            continue;
+        }
+        if let Some(idx) = control::find_idx(&st.controls, id) {
+            let stale = match st.controls[idx].base().validation.as_deref() {
+                Some(v) => v.error.is_some() && value_of(&st.controls[idx]).0 != v.checked,
+                None => false,
+            };
+            if stale {
+                due.push(id);
+            }
+        }
+    }
+    for id in due {
+        validate(id);
+    }
+}
+
+/// Show, move or hide the message bubble of every validated control.
+/// Runs after layout so bubbles follow their controls.
+pub(crate) fn place_messages(st: &mut crate::AnyuiState) {
+    let controls = &st.controls;
+    st.validated.retain(|&id| control::find_idx(controls, id).is_some());
+    for n in 0..st.validated.len() {
+        let id = st.validated[n];
+        let idx = match control::find_idx(&st.controls, id) {
+            Some(i) => i,
+            None => continue,
+        };
+        let (error, bubble) = match st.controls[idx].base().validation.as_deref() {
+            Some(v) => (v.error.clone(), v.message_id),
+            None => continue,
+        };
+        let shown = crate::event_loop::visible_rect(&st.controls, id).is_some();
+        let (error, win_id) = match (error, crate::event_loop::window_index_of(st, id)) {
+            (Some(e), Some(wi)) if shown => (e, st.windows[wi]),
+            _ => {
+                if let Some(bi) = bubble.and_then(|b| control::find_idx(&st.controls, b)) {
+                    if st.controls[bi].base().visible {
+                        st.controls[bi].base_mut().visible = false;
+                        st.controls[bi].base_mut().mark_dirty();
+                    }
+                }
+                continue;
+            }
+        };
+
+        let bubble_id = match bubble.filter(|&b| control::find_idx(&st.controls, b).is_some()) {
+            Some(b) => b,
+            None => {
+                let bid = st.next_id;
+                st.next_id += 1;
+                let mut ctrl = crate::controls::create_control(
+                    ControlKind::Tooltip, bid, win_id, 0, 0, 0, MESSAGE_H, &[],
+                );
+                ctrl.base_mut().visible = false;
+                if let Some(tb) = ctrl.text_base_mut() {
+                    tb.text_style.font_size = MESSAGE_FONT_SIZE;
+                }
+                st.controls.push(ctrl);
+                if let Some(wi) = control::find_idx(&st.controls, win_id) {
+                    st.controls[wi].add_child(bid);
+                }
+                if let Some(v) = st.controls[idx].base_mut().validation.as_deref_mut() {
+                    v.message_id = Some(bid);
+                }
+                bid
+            }
+        };
+
+        let (ax, ay) = control::abs_position(&st.controls, id);
+        let ctrl_h = st.controls[idx].base().h;
+        let w = crate::draw::text_size_at(&error, MESSAGE_FONT_SIZE).0 + 16;
+        let bi = match control::find_idx(&st.controls, bubble_id) {
+            Some(i) => i,
+            None => continue,
+        };
+        let bubble = &mut st.controls[bi];
+        bubble.set_position(ax, ay + ctrl_h as i32 + MESSAGE_GAP);
+        if let Some(tb) = bubble.text_base_mut() {
+            tb.text_style.text_color = crate::theme::colors().destructive;
+            tb.set_text(&error);
+        }
+        bubble.set_size(w, MESSAGE_H);
+        if !bubble.base().visible {
+            bubble.base_mut().visible = true;
+            bubble.base_mut().mark_dirty();
+        }
+    }
+}
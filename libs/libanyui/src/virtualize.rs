@@ -0,0 +1,377 @@
+//! Virtualized containers — realize only the children that are on screen.
+//!
+//! A container with thousands of children lays out and draws all of them.
+//! In virtualization mode (`anyui_set_virtualized`) the container instead
+//! holds `count` logical items of a fixed extent and asks an item provider
+//! callback for a child control only for the items in (or just around) the
+//! visible area. Children that scroll out of view are hidden and handed
+//! back to the provider for the next item that scrolls in, so the number of
+//! live controls stays proportional to the viewport, not to `count`.
+//!
+//! # Item arrangement
+//!
+//! - **Vertical StackPanel** and any other container: one item per row,
+//!   `item_height` apart.
+//! - **Horizontal StackPanel**: one item per column, `item_height` being the
+//!   column width.
+//! - **FlowPanel**: a grid of `item_height` tall rows; the column count is
+//!   the panel width divided by the width of the realized children (plus
+//!   margins), which should therefore all be the same.
+//!
+//! The container sizes itself to the full virtual extent, so an enclosing
+//! ScrollView scrolls over all items. The visible area is the viewport of
+//! the nearest ScrollView ancestor (vertical arrangements), otherwise the
+//! part of the container inside its window.
+//!
+//! # Provider
+//!
+//! `provider(container, index, recycled, userdata) -> child` is called with
+//! no framework state borrowed, once per item that becomes visible. With
+//! `recycled` = 0 it creates a new child of `container` (`anyui_add_control`)
+//! set up for item `index`; otherwise it rebinds the hidden child `recycled`
+//! to item `index`. It returns the child to show (normally the recycled one)
+//! or 0 to leave the item empty.
+
+use alloc::vec::Vec;
+use crate::control::{Control, ControlId, ControlKind, ChildLayout, Orientation, find_idx};
+
+/// Item provider: `(container, index, recycled_child, userdata) -> child`.
+pub type ItemProvider = extern "C" fn(ControlId, u32, ControlId, u64) -> ControlId;
+
+/// Items realized beyond each edge of the visible area, so short scrolls
+/// do not show empty slots before the next frame.
+const OVERSCAN: u32 = 2;
+
+/// Upper bound on provider calls per container per frame; the rest of a
+/// large jump is realized on the following frames.
+const MAX_REALIZE_PER_FRAME: usize = 256;
+
+/// How a virtualized container arranges its items.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Arrangement {
+    Rows,
+    Columns,
+    Grid,
+}
+
+/// Virtualization state of one container (`ControlBase::virtual_items`).
+pub struct Virtualization {
+    /// Extent of one item along the stacking axis (row height, or column
+    /// width for horizontal stacks).
+    pub item_height: u32,
+    /// Number of logical items.
+    pub count: u32,
+    pub provider: ItemProvider,
+    pub userdata: u64,
+    /// Realized children as (item index, child), sorted by index.
+    pub realized: Vec<(u32, ControlId)>,
+    /// Hidden children waiting to be reused.
+    pub spare: Vec<ControlId>,
+    /// Grid cell width measured from the realized children (FlowPanel).
+    cell_w: u32,
+    /// All realized items must be rebound (data changed).
+    rebind: bool,
+}
+
+impl Virtualization {
+    pub fn new(item_height: u32, count: u32, provider: ItemProvider, userdata: u64) -> Self {
+        Self {
+            item_height: item_height.max(1),
+            count,
+            provider,
+            userdata,
+            realized: Vec::new(),
+            spare: Vec::new(),
+            cell_w: 0,
+            rebind: false,
+        }
+    }
+
+    /// Child showing item `index`, if realized.
+    pub fn child_for(&self, index: u32) -> Option<ControlId> {
+        self.realized.binary_search_by_key(&index, |&(i, _)| i).ok().map(|p| self.realized[p].1)
+    }
+
+    /// Rebind every realized item on the next frame.
+    pub fn refresh(&mut self) {
+        self.rebind = true;
+    }
+}
+
+fn arrangement(ctrl: &dyn Control) -> Arrangement {
+    match ctrl.kind() {
+        ControlKind::FlowPanel => Arrangement::Grid,
+        ControlKind::StackPanel => {
+            let sp = unsafe { &*(ctrl as *const dyn Control as *const crate::controls::stack_panel::StackPanel) };
+            if sp.orientation == Orientation::Horizontal { Arrangement::Columns } else { Arrangement::Rows }
+        }
+        _ => Arrangement::Rows,
+    }
+}
+
+/// Columns of a grid container (1 for the other arrangements).
+fn columns(ctrl: &dyn Control, v: &Virtualization) -> u32 {
+    if arrangement(ctrl) != Arrangement::Grid {
+        return 1;
+    }
+    let b = ctrl.base();
+    let inner = b.w as i32 - b.padding.left - b.padding.right;
+    let cell = if v.cell_w > 0 { v.cell_w } else { v.item_height };
+    (inner.max(0) as u32 / cell).max(1)
+}
+
+/// Visible span of the container at `idx` along its stacking axis, in
+/// container-local coordinates, from the last layout pass.
+fn visible_span(controls: &[alloc::boxed::Box<dyn Control>], idx: usize, vertical: bool) -> (i32, i32) {
+    let b = controls[idx].base();
+    let mut offset = if vertical { b.y } else { b.x };
+    let mut cur = b.parent;
+    let mut extent = if vertical { b.h } else { b.w } as i32;
+    let mut origin = 0;
+    while let Some(pi) = find_idx(controls, cur) {
+        let p = controls[pi].base();
+        if vertical && controls[pi].kind() == ControlKind::ScrollView {
+            // Children of a ScrollView are laid out in content coordinates;
+            // its state is the scroll offset.
+            origin = p.state as i32;
+            extent = p.h as i32;
+            break;
+        }
+        extent = if vertical { p.h } else { p.w } as i32;
+        if p.parent == 0 {
+            break;
+        }
+        offset += if vertical { p.y } else { p.x };
+        cur = p.parent;
+    }
+    (origin - offset, origin - offset + extent)
+}
+
+/// Item indices `[first, last)` that should be realized.
+fn wanted_range(controls: &[alloc::boxed::Box<dyn Control>], idx: usize, v: &Virtualization) -> (u32, u32) {
+    let ctrl = &*controls[idx];
+    let arr = arrangement(ctrl);
+    let vertical = arr != Arrangement::Columns;
+    let (start, end) = visible_span(controls, idx, vertical);
+    let pad = &ctrl.base().padding;
+    let lead = if vertical { pad.top } else { pad.left };
+    let item = v.item_height as i32;
+    let first_line = ((start - lead).max(0) / item) as u32;
+    let last_line = ((end - lead).max(0) as u32).div_ceil(item as u32);
+    let cols = columns(ctrl, v);
+    let first = first_line.saturating_sub(OVERSCAN).saturating_mul(cols);
+    let last = last_line.saturating_add(OVERSCAN).saturating_mul(cols);
+    (first.min(v.count), last.min(v.count))
+}
+
+fn virt_mut(ctrl: &mut alloc::boxed::Box<dyn Control>) -> Option<&mut Virtualization> {
+    ctrl.base_mut().virtual_items.as_deref_mut()
+}
+
+/// One pending provider call.
+struct Job {
+    container: ControlId,
+    index: u32,
+    recycled: ControlId,
+    provider: ItemProvider,
+    userdata: u64,
+}
+
+/// Bring the realized children of every virtualized container in line with
+/// its visible range, calling the item providers as needed. Must be called
+/// with no framework state borrowed. Returns true if any child was
+/// realized, hidden or rebound (layout is then marked as needed).
+pub(crate) fn realize_all() -> bool {
+    let st = crate::state();
+    if st.virtualized.is_empty() {
+        return false;
+    }
+    let mut jobs: Vec<Job> = Vec::new();
+    let mut changed = false;
+    let containers = st.virtualized.clone();
+    let mut alive = Vec::with_capacity(containers.len());
+
+    for id in containers {
+        let idx = match find_idx(&st.controls, id) {
+            Some(i) if st.controls[i].base().virtual_items.is_some() => i,
+            _ => continue,
+        };
+        alive.push(id);
+        let (first, last) = {
+            let v = st.controls[idx].base().virtual_items.as_deref().unwrap();
+            wanted_range(&st.controls, idx, v)
+        };
+        let (mut realized, mut spare, rebind, provider, userdata) = {
+            let v = virt_mut(&mut st.controls[idx]).unwrap();
+            (
+                core::mem::take(&mut v.realized),
+                core::mem::take(&mut v.spare),
+                core::mem::replace(&mut v.rebind, false),
+                v.provider,
+                v.userdata,
+            )
+        };
+        // Forget children the application destroyed.
+        realized.retain(|&(_, c)| find_idx(&st.controls, c).is_some());
+        spare.retain(|&c| find_idx(&st.controls, c).is_some());
+
+        let jobs_before = jobs.len();
+        let mut hidden = false;
+        let mut kept = Vec::with_capacity(realized.len());
+        for (index, child) in realized {
+            if index < first || index >= last {
+                // Left the range: hide and keep for reuse.
+                if let Some(ci) = find_idx(&st.controls, child) {
+                    st.controls[ci].base_mut().visible = false;
+                }
+                spare.push(child);
+                hidden = true;
+            } else if rebind {
+                // Hand the child back for the same item.
+                jobs.push(Job { container: id, index, recycled: child, provider, userdata });
+            } else {
+                kept.push((index, child));
+            }
+        }
+        let mut budget = MAX_REALIZE_PER_FRAME;
+        for index in first..last {
+            if budget == 0 {
+                break;
+            }
+            if kept.binary_search_by_key(&index, |&(i, _)| i).is_ok()
+                || jobs[jobs_before..].iter().any(|j| j.index == index)
+            {
+                continue;
+            }
+            let recycled = spare.pop().unwrap_or(0);
+            jobs.push(Job { container: id, index, recycled, provider, userdata });
+            budget -= 1;
+        }
+
+        let ctrl = &mut st.controls[idx];
+        if let Some(v) = virt_mut(ctrl) {
+            v.realized = kept;
+            v.spare = spare;
+        }
+        if hidden || jobs.len() > jobs_before {
+            ctrl.base_mut().mark_dirty();
+        }
+        changed |= hidden;
+    }
+    st.virtualized = alive;
+
+    // Provider calls: no borrows held, state is re-acquired after each.
+    let had_jobs = !jobs.is_empty();
+    for job in jobs {
+        let child = (job.provider)(job.container, job.index, job.recycled, job.userdata);
+        let st = crate::state();
+        if child != 0 {
+            if let Some(ci) = find_idx(&st.controls, child) {
+                let b = st.controls[ci].base_mut();
+                b.visible = true;
+                b.mark_dirty();
+            }
+        }
+        let ci = match find_idx(&st.controls, job.container) {
+            Some(i) => i,
+            None => continue,
+        };
+        if let Some(v) = virt_mut(&mut st.controls[ci]) {
+            if child != 0 && job.index < v.count {
+                if let Err(pos) = v.realized.binary_search_by_key(&job.index, |&(i, _)| i) {
+                    v.realized.insert(pos, (job.index, child));
+                }
+                v.spare.retain(|&c| c != child);
+            }
+            if job.recycled != 0 && job.recycled != child && !v.spare.contains(&job.recycled) {
+                v.spare.push(job.recycled);
+                if let Some(ri) = find_idx(&st.controls, job.recycled) {
+                    st.controls[ri].base_mut().visible = false;
+                }
+            }
+        }
+    }
+
+    if changed || had_jobs {
+        crate::mark_needs_layout();
+        return true;
+    }
+    false
+}
+
+/// Layout of a virtualized container: realized children placed by item
+/// index. Also measures the grid cell width of FlowPanels.
+pub(crate) fn layout_children(controls: &mut [alloc::boxed::Box<dyn Control>], idx: usize) -> Vec<ChildLayout> {
+    let arr = arrangement(&*controls[idx]);
+    let realized = match controls[idx].base().virtual_items.as_deref() {
+        Some(v) => v.realized.clone(),
+        None => return Vec::new(),
+    };
+    if arr == Arrangement::Grid {
+        let cell_w = realized.iter()
+            .filter_map(|&(_, c)| find_idx(controls, c))
+            .map(|ci| {
+                let b = controls[ci].base();
+                (b.w as i32 + b.margin.left + b.margin.right).max(1) as u32
+            })
+            .next();
+        if let (Some(w), Some(v)) = (cell_w, virt_mut(&mut controls[idx])) {
+            v.cell_w = w;
+        }
+    }
+
+    let ctrl = &*controls[idx];
+    let v = ctrl.base().virtual_items.as_deref().unwrap();
+    let b = ctrl.base();
+    let pad = b.padding;
+    let item = v.item_height as i32;
+    let cols = columns(ctrl, v);
+    let cell_w = if v.cell_w > 0 { v.cell_w } else { v.item_height } as i32;
+    let mirrored = arr == Arrangement::Columns && crate::bidi::window_rtl();
+    let mut result = Vec::with_capacity(realized.len());
+    for (index, child) in realized {
+        let ci = match find_idx(controls, child) {
+            Some(i) => i,
+            None => continue,
+        };
+        let m = controls[ci].base().margin;
+        let i = index as i32;
+        let (x, y) = match arr {
+            Arrangement::Rows => (pad.left + m.left, pad.top + i * item + m.top),
+            Arrangement::Columns if mirrored => {
+                (b.w as i32 - pad.left - (i + 1) * item + m.right, pad.top + m.top)
+            }
+            Arrangement::Columns => (pad.left + i * item + m.left, pad.top + m.top),
+            Arrangement::Grid => {
+                let (row, col) = (i / cols as i32, i % cols as i32);
+                (pad.left + col * cell_w + m.left, pad.top + row * item + m.top)
+            }
+        };
+        result.push(ChildLayout { id: child, x, y, w: None, h: None });
+    }
+    result
+}
+
+/// Size a virtualized container to the full extent of its items.
+pub(crate) fn fit_extent(controls: &mut [alloc::boxed::Box<dyn Control>], idx: usize) {
+    let ctrl = &*controls[idx];
+    let v = match ctrl.base().virtual_items.as_deref() {
+        Some(v) => v,
+        None => return,
+    };
+    let b = ctrl.base();
+    let (w, h) = match arrangement(ctrl) {
+        Arrangement::Columns => {
+            let extent = b.padding.left + (v.count as i32).saturating_mul(v.item_height as i32) + b.padding.right;
+            (extent.max(0) as u32, b.h)
+        }
+        arr => {
+            let lines = if arr == Arrangement::Grid { v.count.div_ceil(columns(ctrl, v)) } else { v.count };
+            let extent = b.padding.top + (lines as i32).saturating_mul(v.item_height as i32) + b.padding.bottom;
+            (b.w, extent.max(0) as u32)
+        }
+    };
+    if (w, h) != (b.w, b.h) {
+        controls[idx].set_size(w, h);
+    }
+}
@@ -0,0 +1,26 @@
+use crate::{Control, Widget, lib, KIND_ACTIVITY_SPINNER};
+
+leaf_control!(ActivitySpinner, KIND_ACTIVITY_SPINNER);
+
+impl ActivitySpinner {
+    /// Create a running spinner. `set_color` overrides the accent color.
+    pub fn new() -> Self {
+        let id = (lib().create_control)(KIND_ACTIVITY_SPINNER, core::ptr::null(), 0);
+        Self { ctrl: Control { id } }
+    }
+
+    /// Start or stop the animation. A stopped spinner is not drawn.
+    pub fn set_running(&self, running: bool) {
+        (lib().spinner_set_running)(self.ctrl.id, running as u32);
+    }
+
+    /// Whether the spinner is animating.
+    pub fn is_running(&self) -> bool {
+        (lib().spinner_is_running)(self.ctrl.id) != 0
+    }
+
+    /// Ring width in logical pixels (0 = proportional to the size).
+    pub fn set_thickness(&self, thickness: u32) {
+        (lib().ring_set_thickness)(self.ctrl.id, thickness);
+    }
+}
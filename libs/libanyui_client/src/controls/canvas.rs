@@ -100,6 +100,38 @@ impl Canvas {
         (lib().canvas_copy_to)(self.ctrl.id, dst.as_mut_ptr(), dst.len() as u32) as usize
     }
 
+    /// Repaint only `(x, y, w, h)` (canvas pixels) after drawing into it,
+    /// e.g. through `get_buffer()`. Inside `begin_paint()` / `end_paint()`
+    /// the region is shown at `end_paint()`.
+    pub fn invalidate_rect(&self, x: i32, y: i32, w: u32, h: u32) {
+        (lib().canvas_invalidate_rect)(self.ctrl.id, x, y, w, h);
+    }
+
+    /// Keep a front buffer: the window shows the last presented frame while
+    /// the canvas is being redrawn, so clear-and-redraw does not flicker.
+    pub fn set_double_buffered(&self, enabled: bool) {
+        (lib().canvas_set_double_buffered)(self.ctrl.id, enabled as u32);
+    }
+
+    /// Start an atomic update; nothing drawn is shown until `end_paint()`.
+    pub fn begin_paint(&self) {
+        (lib().canvas_begin_paint)(self.ctrl.id);
+    }
+
+    /// Show the update started by `begin_paint()`: the regions passed to
+    /// `invalidate_rect()` meanwhile, or the whole canvas if there were none.
+    pub fn end_paint(&self) {
+        (lib().canvas_end_paint)(self.ctrl.id);
+    }
+
+    /// Last pointer sample over the canvas: pen pressure and tilt, touch
+    /// contact id. Read it from the mouse callbacks while drawing.
+    pub fn pointer(&self) -> crate::PointerEvent {
+        let mut ev = crate::PointerEvent::default();
+        (lib().canvas_get_pointer)(self.ctrl.id, &mut ev);
+        ev
+    }
+
     /// Register callback for click events.
     pub fn on_click(&self, mut f: impl FnMut(&ClickEvent) + 'static) {
         let (thunk, ud) = events::register(move |id, _| f(&ClickEvent { id }));
@@ -0,0 +1,29 @@
+use crate::{Control, Widget, lib, KIND_CIRCULAR_PROGRESS};
+
+leaf_control!(CircularProgress, KIND_CIRCULAR_PROGRESS);
+
+impl CircularProgress {
+    /// Create a progress ring showing `value` (0-100). Set the value later
+    /// with `set_state`; `set_color` overrides the accent fill color.
+    pub fn new(value: u32) -> Self {
+        let id = (lib().create_control)(KIND_CIRCULAR_PROGRESS, core::ptr::null(), 0);
+        (lib().set_state)(id, value);
+        Self { ctrl: Control { id } }
+    }
+
+    /// Ring width in logical pixels (0 = proportional to the size).
+    pub fn set_thickness(&self, thickness: u32) {
+        (lib().ring_set_thickness)(self.ctrl.id, thickness);
+    }
+
+    /// Show or hide the percentage text. It is always omitted when the
+    /// control is smaller than 32×32.
+    pub fn set_show_text(&self, show: bool) {
+        (lib().circularprogress_set_show_text)(self.ctrl.id, show as u32);
+    }
+
+    /// Color of the unfilled part of the ring (0 = theme default).
+    pub fn set_track_color(&self, color: u32) {
+        (lib().circularprogress_set_track_color)(self.ctrl.id, color);
+    }
+}
@@ -23,6 +23,7 @@ pub const SORT_STRING: u8 = 0;
 pub const SORT_NUMERIC: u8 = 1;
 
 /// Builder for column definitions.
+#[derive(Clone)]
 pub struct ColumnDef {
     header: Vec<u8>,
     width: u32,
@@ -121,6 +122,33 @@ impl DataGrid {
         (lib().datagrid_set_cell)(self.ctrl.id, row, col, text.as_ptr(), text.len() as u32);
     }
 
+    /// Insert a row of cell strings before `row` (`row >= row_count()` appends).
+    /// Rows below it move down; their selection moves with them.
+    pub fn insert_row(&self, row: u32, cells: &[&str]) {
+        self.insert_row_raw(row, &encode_row(cells));
+    }
+
+    /// Insert a pre-encoded row (cells separated by 0x1F).
+    pub fn insert_row_raw(&self, row: u32, data: &[u8]) {
+        (lib().datagrid_insert_row)(self.ctrl.id, row, data.as_ptr(), data.len() as u32);
+    }
+
+    /// Replace all cells of an existing row.
+    pub fn set_row(&self, row: u32, cells: &[&str]) {
+        self.set_row_raw(row, &encode_row(cells));
+    }
+
+    /// Replace all cells of an existing row from a pre-encoded buffer
+    /// (cells separated by 0x1F).
+    pub fn set_row_raw(&self, row: u32, data: &[u8]) {
+        (lib().datagrid_set_row)(self.ctrl.id, row, data.as_ptr(), data.len() as u32);
+    }
+
+    /// Remove a row. Rows below it move up.
+    pub fn remove_row(&self, row: u32) {
+        (lib().datagrid_remove_row)(self.ctrl.id, row);
+    }
+
     /// Get a cell's text into a buffer. Returns the number of bytes written.
     pub fn get_cell(&self, row: u32, col: u32, buf: &mut [u8]) -> u32 {
         (lib().datagrid_get_cell)(self.ctrl.id, row, col, buf.as_mut_ptr(), buf.len() as u32)
@@ -272,6 +300,15 @@ impl DataGrid {
     }
 }
 
+fn encode_row(cells: &[&str]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for (ci, cell) in cells.iter().enumerate() {
+        if ci > 0 { buf.push(0x1F); }
+        buf.extend_from_slice(cell.as_bytes());
+    }
+    buf
+}
+
 fn write_u32_ascii(buf: &mut Vec<u8>, val: u32) {
     if val == 0 {
         buf.push(b'0');
@@ -0,0 +1,227 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::{Ref, RefCell};
+use crate::controls::datagrid::{DataGrid, ColumnDef, SELECTION_SINGLE, SELECTION_MULTI};
+use crate::{Widget, Control};
+
+/// A DataGrid column bound to a field of the row type `T`.
+pub struct GridColumn<T> {
+    def: ColumnDef,
+    bind: Box<dyn Fn(&T) -> String>,
+}
+
+impl<T> GridColumn<T> {
+    /// Create a column whose cell text is `bind(row)`.
+    pub fn new(header: &str, bind: impl Fn(&T) -> String + 'static) -> Self {
+        Self { def: ColumnDef::new(header), bind: Box::new(bind) }
+    }
+
+    pub fn width(mut self, w: u32) -> Self {
+        self.def = self.def.width(w);
+        self
+    }
+
+    pub fn align(mut self, a: u8) -> Self {
+        self.def = self.def.align(a);
+        self
+    }
+
+    /// Compare this column's values as numbers when sorting.
+    pub fn numeric(mut self) -> Self {
+        self.def = self.def.numeric();
+        self
+    }
+}
+
+/// Typed view over a DataGrid: holds the row objects and encodes them into
+/// cells through the column bindings.
+///
+/// Row indices are model indices (the order rows were added in), independent
+/// of how the grid is currently sorted. Single-row changes (`push`, `insert`,
+/// `update`, `remove`) only send that row to the grid.
+pub struct DataGridView<T> {
+    grid: DataGrid,
+    columns: Rc<Vec<GridColumn<T>>>,
+    rows: Rc<RefCell<Vec<T>>>,
+}
+
+impl<T: 'static> DataGridView<T> {
+    /// Create a grid with the given display size and columns.
+    pub fn new(w: u32, h: u32, columns: Vec<GridColumn<T>>) -> Self {
+        let grid = DataGrid::new(w, h);
+        let defs: Vec<ColumnDef> = columns.iter().map(|c| c.def.clone()).collect();
+        grid.set_columns(&defs);
+        Self { grid, columns: Rc::new(columns), rows: Rc::new(RefCell::new(Vec::new())) }
+    }
+
+    /// The underlying DataGrid (styling, sorting, scrolling).
+    pub fn grid(&self) -> &DataGrid {
+        &self.grid
+    }
+
+    /// Replace all rows.
+    pub fn set_rows(&self, rows: Vec<T>) {
+        let mut buf = Vec::new();
+        for (ri, row) in rows.iter().enumerate() {
+            if ri > 0 { buf.push(0x1E); }
+            self.encode(row, &mut buf);
+        }
+        *self.rows.borrow_mut() = rows;
+        if buf.is_empty() {
+            self.grid.set_row_count(0);
+        } else {
+            self.grid.set_data_raw(&buf);
+        }
+    }
+
+    /// All rows, in model order.
+    pub fn rows(&self) -> Ref<'_, Vec<T>> {
+        self.rows.borrow()
+    }
+
+    /// The row at `index`, if any.
+    pub fn row(&self, index: usize) -> Option<Ref<'_, T>> {
+        Ref::filter_map(self.rows.borrow(), |rows| rows.get(index)).ok()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.borrow().is_empty()
+    }
+
+    /// Append a row.
+    pub fn push(&self, row: T) {
+        let index = self.len();
+        self.insert(index, row);
+    }
+
+    /// Insert a row at `index` (clamped to the row count).
+    pub fn insert(&self, index: usize, row: T) {
+        let mut buf = Vec::new();
+        self.encode(&row, &mut buf);
+        let mut rows = self.rows.borrow_mut();
+        let index = index.min(rows.len());
+        rows.insert(index, row);
+        drop(rows);
+        self.grid.insert_row_raw(index as u32, &buf);
+    }
+
+    /// Modify the row at `index` in place and refresh its cells.
+    /// Returns `false` if `index` is out of range.
+    pub fn update(&self, index: usize, f: impl FnOnce(&mut T)) -> bool {
+        let mut buf = Vec::new();
+        {
+            let mut rows = self.rows.borrow_mut();
+            let row = match rows.get_mut(index) {
+                Some(r) => r,
+                None => return false,
+            };
+            f(row);
+            self.encode(row, &mut buf);
+        }
+        self.grid.set_row_raw(index as u32, &buf);
+        true
+    }
+
+    /// Replace the row at `index`, returning the old row.
+    pub fn replace(&self, index: usize, row: T) -> Option<T> {
+        let mut buf = Vec::new();
+        self.encode(&row, &mut buf);
+        let old = {
+            let mut rows = self.rows.borrow_mut();
+            let slot = rows.get_mut(index)?;
+            core::mem::replace(slot, row)
+        };
+        self.grid.set_row_raw(index as u32, &buf);
+        Some(old)
+    }
+
+    /// Remove and return the row at `index`.
+    pub fn remove(&self, index: usize) -> Option<T> {
+        let mut rows = self.rows.borrow_mut();
+        if index >= rows.len() {
+            return None;
+        }
+        let row = rows.remove(index);
+        drop(rows);
+        self.grid.remove_row(index as u32);
+        Some(row)
+    }
+
+    /// Index of the first row matching `pred`.
+    pub fn position(&self, pred: impl Fn(&T) -> bool) -> Option<usize> {
+        self.rows.borrow().iter().position(|r| pred(r))
+    }
+
+    /// Index of the selected row (single selection).
+    pub fn selected_index(&self) -> Option<usize> {
+        let sel = self.grid.selected_row();
+        if sel == u32::MAX || sel as usize >= self.len() { None } else { Some(sel as usize) }
+    }
+
+    /// The selected row (single selection).
+    pub fn selected(&self) -> Option<Ref<'_, T>> {
+        self.row(self.selected_index()?)
+    }
+
+    /// Indices of all selected rows (multi-selection), in model order.
+    pub fn selected_indices(&self) -> Vec<usize> {
+        (0..self.len()).filter(|&i| self.grid.is_row_selected(i as u32)).collect()
+    }
+
+    /// Select the row at `index`.
+    pub fn select(&self, index: usize) {
+        self.grid.set_selected_row(index as u32);
+    }
+
+    /// Enable multi-row selection.
+    pub fn set_multi_select(&self, multi: bool) {
+        self.grid.set_selection_mode(if multi { SELECTION_MULTI } else { SELECTION_SINGLE });
+    }
+
+    /// Register a callback for selection changes with the selected row
+    /// (`None` when the selection was cleared).
+    pub fn on_selection_changed(&self, mut f: impl FnMut(Option<&T>) + 'static) {
+        let rows = self.rows.clone();
+        self.grid.on_selection_changed(move |e| {
+            let rows = rows.borrow();
+            f(rows.get(e.index as usize));
+        });
+    }
+
+    /// Register a callback for submit (Enter or double-click) with the row.
+    pub fn on_submit(&self, mut f: impl FnMut(&T) + 'static) {
+        let rows = self.rows.clone();
+        self.grid.on_submit(move |e| {
+            if let Some(row) = rows.borrow().get(e.index as usize) {
+                f(row);
+            }
+        });
+    }
+
+    fn encode(&self, row: &T, buf: &mut Vec<u8>) {
+        for (ci, col) in self.columns.iter().enumerate() {
+            if ci > 0 { buf.push(0x1F); }
+            let text = (col.bind)(row);
+            // Separator bytes would split the cell.
+            buf.extend(text.bytes().filter(|&b| b != 0x1E && b != 0x1F));
+        }
+    }
+
+}
+
+impl<T> Widget for DataGridView<T> {
+    fn id(&self) -> u32 { self.grid.id() }
+}
+
+impl<T> core::ops::Deref for DataGridView<T> {
+    type Target = Control;
+    fn deref(&self) -> &Control {
+        &self.grid
+    }
+}
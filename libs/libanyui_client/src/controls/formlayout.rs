@@ -0,0 +1,38 @@
+use crate::{Container, Control, Widget, lib, KIND_FORM_LAYOUT};
+use crate::controls::Label;
+
+container_control!(FormLayout, KIND_FORM_LAYOUT);
+
+impl FormLayout {
+    /// Create an empty form. Children are taken in label/field pairs; the
+    /// height fits the rows automatically.
+    pub fn new() -> Self {
+        let id = (lib().create_control)(KIND_FORM_LAYOUT, core::ptr::null(), 0);
+        Self { container: Container { ctrl: Control { id } } }
+    }
+
+    /// Append a row with a text label and `field`. Returns the label so
+    /// its style can be adjusted.
+    pub fn add_row(&self, label: &str, field: &impl Widget) -> Label {
+        let lbl = Label::new(label);
+        self.add(&lbl);
+        self.add(field);
+        lbl
+    }
+
+    /// Append a row with a custom label control.
+    pub fn add_row_with(&self, label: &impl Widget, field: &impl Widget) {
+        self.add(label);
+        self.add(field);
+    }
+
+    /// Fix the label column width (0 = fit the widest label, the default).
+    pub fn set_label_width(&self, width: u32) {
+        (lib().formlayout_set_label_width)(self.container.ctrl.id, width);
+    }
+
+    /// Set the gap between the label and field columns and between rows.
+    pub fn set_spacing(&self, column_gap: u32, row_gap: u32) {
+        (lib().formlayout_set_spacing)(self.container.ctrl.id, column_gap, row_gap);
+    }
+}
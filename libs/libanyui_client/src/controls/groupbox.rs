@@ -3,6 +3,8 @@ use crate::{Container, Control, Widget, lib, KIND_GROUP_BOX};
 container_control!(GroupBox, KIND_GROUP_BOX);
 
 impl GroupBox {
+    /// Create a group box with the given title. Call `set_auto_size(true)`
+    /// to fit its height to the children instead of a fixed size.
     pub fn new(title: &str) -> Self {
         let id = (lib().create_control)(KIND_GROUP_BOX, title.as_ptr(), title.len() as u32);
         Self { container: Container { ctrl: Control { id } } }
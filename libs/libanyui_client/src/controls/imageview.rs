@@ -1,6 +1,7 @@
 use alloc::vec;
 use alloc::vec::Vec;
-use crate::{Control, Widget, lib, KIND_IMAGE_VIEW};
+use crate::{Control, Widget, lib, events, KIND_IMAGE_VIEW};
+use crate::events::EventArgs;
 
 /// Scale mode constants (must match server-side values).
 pub const SCALE_NONE: u32 = 0;
@@ -8,6 +9,12 @@ pub const SCALE_FIT: u32 = 1;
 pub const SCALE_FILL: u32 = 2;
 pub const SCALE_STRETCH: u32 = 3;
 
+/// File load error codes (`ImageView::load_error`).
+pub const LOAD_ERR_IO: u32 = 1;
+pub const LOAD_ERR_FORMAT: u32 = 2;
+pub const LOAD_ERR_DECODE: u32 = 3;
+pub const LOAD_ERR_NO_MEMORY: u32 = 4;
+
 leaf_control!(ImageView, KIND_IMAGE_VIEW);
 
 impl ImageView {
@@ -54,6 +61,25 @@ impl ImageView {
         }
     }
 
+    /// Load an image file without blocking: libanyui reads and decodes it on
+    /// a worker thread and shows a spinner meanwhile. Fires `on_loaded` once
+    /// the image is shown, or `on_error` if loading fails (see `load_error`).
+    /// Returns false if the load could not be started.
+    pub fn load_file(&self, path: &str) -> bool {
+        (lib().imageview_load_file)(self.ctrl.id, path.as_ptr(), path.len() as u32) != 0
+    }
+
+    /// Register a closure called when a `load_file` image has been applied.
+    pub fn on_loaded(&self, mut f: impl FnMut(&EventArgs) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| f(&EventArgs { id }));
+        (lib().on_change_fn)(self.ctrl.id, thunk, ud);
+    }
+
+    /// Error of the last failed `load_file` (LOAD_ERR_*), or 0.
+    pub fn load_error(&self) -> u32 {
+        (lib().imageview_get_load_error)(self.ctrl.id)
+    }
+
     /// Load an ICO file at a specific icon size.
     pub fn load_ico(&self, path: &str, preferred_size: u32) {
         if let Ok(data) = anyos_std::fs::read_to_vec(path) {
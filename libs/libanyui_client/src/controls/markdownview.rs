@@ -0,0 +1,78 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::{Control, Widget, lib, events, KIND_MARKDOWN_VIEW};
+use crate::events::LinkClickedEvent;
+
+leaf_control!(MarkdownView, KIND_MARKDOWN_VIEW);
+
+impl MarkdownView {
+    /// Create a MarkdownView showing `markdown`.
+    pub fn new(markdown: &str) -> Self {
+        let id = (lib().create_control)(KIND_MARKDOWN_VIEW, markdown.as_ptr(), markdown.len() as u32);
+        Self { ctrl: Control { id } }
+    }
+
+    /// Replace the displayed document. Images already provided are kept.
+    pub fn set_markdown(&self, markdown: &str) {
+        self.ctrl.set_text(markdown);
+    }
+
+    /// Sources of the images (`![alt](src)`) referenced by the document.
+    pub fn image_sources(&self) -> Vec<String> {
+        let count = (lib().markdown_image_count)(self.ctrl.id);
+        let mut out = Vec::with_capacity(count as usize);
+        let mut buf = [0u8; 512];
+        for i in 0..count {
+            let len = ((lib().markdown_image_src)(self.ctrl.id, i, buf.as_mut_ptr(), buf.len() as u32) as usize).min(buf.len());
+            out.push(String::from_utf8_lossy(&buf[..len]).into_owned());
+        }
+        out
+    }
+
+    /// Set decoded ARGB pixels for image `src`.
+    pub fn set_image(&self, src: &str, pixels: &[u32], w: u32, h: u32) {
+        if pixels.len() >= (w as usize) * (h as usize) {
+            (lib().markdown_set_image)(self.ctrl.id, src.as_ptr(), src.len() as u32, pixels.as_ptr(), w, h);
+        }
+    }
+
+    /// Decode image file data via libimage and set it for `src`.
+    pub fn set_image_bytes(&self, src: &str, data: &[u8]) {
+        if let Some(info) = libimage_client::probe(data) {
+            let pixel_count = (info.width as usize) * (info.height as usize);
+            let mut pixels = vec![0u32; pixel_count];
+            let mut scratch = vec![0u8; info.scratch_needed as usize];
+            if libimage_client::decode(data, &mut pixels, &mut scratch).is_ok() {
+                self.set_image(src, &pixels, info.width, info.height);
+            }
+        }
+    }
+
+    /// Load every referenced image from disk. Relative sources are resolved
+    /// against `base_dir`; URLs (anything containing "://") are skipped.
+    pub fn load_images(&self, base_dir: &str) {
+        for src in self.image_sources() {
+            if src.contains("://") {
+                continue;
+            }
+            let path = if src.starts_with('/') || base_dir.is_empty() {
+                src.clone()
+            } else {
+                let mut p = String::from(base_dir.trim_end_matches('/'));
+                p.push('/');
+                p.push_str(&src);
+                p
+            };
+            if let Ok(data) = anyos_std::fs::read_to_vec(&path) {
+                self.set_image_bytes(&src, &data);
+            }
+        }
+    }
+
+    /// Called when a link in the document is clicked.
+    pub fn on_link_clicked(&self, mut f: impl FnMut(&LinkClickedEvent) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| f(&LinkClickedEvent { id }));
+        (lib().on_click_fn)(self.ctrl.id, thunk, ud);
+    }
+}
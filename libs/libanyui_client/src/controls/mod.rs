@@ -22,10 +22,15 @@ mod badge;
 mod tag;
 mod imagebutton;
 mod canvas;
+mod nativesurface;
 mod datagrid;
+mod datagridview;
 mod texteditor;
 mod treeview;
 mod dropdown;
+mod markdownview;
+mod circularprogress;
+mod activityspinner;
 
 // ── Container controls (can have children) ──
 mod expander;
@@ -46,6 +51,7 @@ mod tableview;
 mod stackpanel;
 mod flowpanel;
 mod tablelayout;
+mod formlayout;
 mod radiogroup;
 
 // ── Static dialogs ──
@@ -60,12 +66,15 @@ pub use toggle::Toggle;
 pub use checkbox::Checkbox;
 pub use slider::Slider;
 pub use radiobutton::RadioButton;
-pub use progressbar::ProgressBar;
+pub use progressbar::{ProgressBar, PROGRESS_NORMAL, PROGRESS_INDETERMINATE, PROGRESS_PAUSED,
+    PROGRESS_ERROR};
+pub use circularprogress::CircularProgress;
+pub use activityspinner::ActivitySpinner;
 pub use stepper::Stepper;
 pub use segmented::SegmentedControl;
 pub use divider::Divider;
 pub use tooltip::Tooltip;
-pub use imageview::ImageView;
+pub use imageview::{ImageView, LOAD_ERR_IO, LOAD_ERR_FORMAT, LOAD_ERR_DECODE, LOAD_ERR_NO_MEMORY};
 pub use statusindicator::StatusIndicator;
 pub use colorwell::ColorWell;
 pub use searchfield::SearchField;
@@ -77,15 +86,19 @@ pub use badge::Badge;
 pub use imagebutton::ImageButton;
 pub use tag::Tag;
 pub use canvas::Canvas;
+pub use nativesurface::NativeSurface;
 pub use datagrid::{DataGrid, ColumnDef, ALIGN_LEFT, ALIGN_CENTER, ALIGN_RIGHT,
     SELECTION_SINGLE, SELECTION_MULTI, SORT_NONE, SORT_ASCENDING, SORT_DESCENDING,
     SORT_STRING, SORT_NUMERIC};
+pub use datagridview::{DataGridView, GridColumn};
 pub use texteditor::TextEditor;
 pub use treeview::{TreeView, STYLE_NORMAL, STYLE_BOLD};
 
 pub use expander::Expander;
 pub use window::{Window, WIN_FLAG_BORDERLESS, WIN_FLAG_NOT_RESIZABLE, WIN_FLAG_ALWAYS_ON_TOP,
-    WIN_FLAG_NO_CLOSE, WIN_FLAG_NO_MINIMIZE, WIN_FLAG_NO_MAXIMIZE, WIN_FLAG_SHADOW};
+    WIN_FLAG_NO_CLOSE, WIN_FLAG_NO_MINIMIZE, WIN_FLAG_NO_MAXIMIZE, WIN_FLAG_SHADOW,
+    SNAP_NONE, SNAP_LEFT, SNAP_RIGHT, SNAP_TOP_LEFT, SNAP_TOP_RIGHT, SNAP_BOTTOM_LEFT,
+    SNAP_BOTTOM_RIGHT, SNAP_MAXIMIZED, PRESENT_VSYNC, PRESENT_IMMEDIATE, PRESENT_MANUAL};
 pub use view::View;
 pub use card::Card;
 pub use groupbox::GroupBox;
@@ -101,8 +114,10 @@ pub use tableview::TableView;
 pub use stackpanel::StackPanel;
 pub use flowpanel::FlowPanel;
 pub use tablelayout::TableLayout;
+pub use formlayout::FormLayout;
 pub use radiogroup::RadioGroup;
 pub use dropdown::DropDown;
+pub use markdownview::MarkdownView;
 
 pub use messagebox::{MessageBox, MessageBoxType};
 pub use filedialog::FileDialog;
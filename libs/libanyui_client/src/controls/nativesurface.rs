@@ -0,0 +1,39 @@
+use crate::{Control, Widget, lib, KIND_NATIVE_SURFACE};
+
+leaf_control!(NativeSurface, KIND_NATIVE_SURFACE);
+
+impl NativeSurface {
+    /// Create a native surface control of `w`×`h` logical pixels. It has no
+    /// buffer until `alloc_buffer` is called (after adding it to a window).
+    pub fn new(w: u32, h: u32) -> Self {
+        let id = (lib().create_control)(KIND_NATIVE_SURFACE, core::ptr::null(), 0);
+        (lib().set_size)(id, w, h);
+        Self { ctrl: Control { id } }
+    }
+
+    /// Allocate a `w`×`h` ARGB buffer that the compositor shows at the
+    /// control's top-left corner, one buffer pixel per physical pixel.
+    /// Replaces any previous buffer; returns null if the control is not in
+    /// a window yet or allocation failed. Render into the buffer (libgl,
+    /// VM display, ...) and call `present` to show the frame.
+    pub fn alloc_buffer(&self, w: u32, h: u32) -> *mut u32 {
+        (lib().native_surface_alloc)(self.ctrl.id, w, h)
+    }
+
+    /// The current buffer and its size, if one is allocated.
+    pub fn buffer(&self) -> Option<(*mut u32, u32, u32)> {
+        let (mut w, mut h) = (0u32, 0u32);
+        let ptr = (lib().native_surface_pixels)(self.ctrl.id, &mut w, &mut h);
+        if ptr.is_null() { None } else { Some((ptr, w, h)) }
+    }
+
+    /// Show the frame just rendered into the buffer.
+    pub fn present(&self) {
+        (lib().native_surface_present)(self.ctrl.id);
+    }
+
+    /// Free the buffer (the control's background color shows again).
+    pub fn release_buffer(&self) {
+        (lib().native_surface_release)(self.ctrl.id);
+    }
+}
@@ -1,5 +1,11 @@
 use crate::{Control, Widget, lib, KIND_PROGRESS_BAR};
 
+/// Display mode constants (must match server-side values).
+pub const PROGRESS_NORMAL: u32 = 0;
+pub const PROGRESS_INDETERMINATE: u32 = 1;
+pub const PROGRESS_PAUSED: u32 = 2;
+pub const PROGRESS_ERROR: u32 = 3;
+
 leaf_control!(ProgressBar, KIND_PROGRESS_BAR);
 
 impl ProgressBar {
@@ -8,4 +14,26 @@ impl ProgressBar {
         (lib().set_state)(id, value);
         Self { ctrl: Control { id } }
     }
+
+    /// Set the display mode (`PROGRESS_NORMAL`, `PROGRESS_INDETERMINATE`,
+    /// `PROGRESS_PAUSED` or `PROGRESS_ERROR`). Indeterminate mode animates a
+    /// sweeping segment for operations of unknown length.
+    pub fn set_mode(&self, mode: u32) {
+        (lib().progressbar_set_mode)(self.ctrl.id, mode);
+    }
+
+    /// Get the current display mode.
+    pub fn mode(&self) -> u32 {
+        (lib().progressbar_get_mode)(self.ctrl.id)
+    }
+
+    /// Set the buffered (secondary) value, 0-100, shown behind the main fill.
+    pub fn set_buffer(&self, value: u32) {
+        (lib().progressbar_set_buffer)(self.ctrl.id, value);
+    }
+
+    /// Draw the bar as `count` separate blocks (0 or 1 = continuous).
+    pub fn set_segments(&self, count: u32) {
+        (lib().progressbar_set_segments)(self.ctrl.id, count);
+    }
 }
@@ -9,11 +9,38 @@ impl ScrollView {
         Self { container: Container { ctrl: Control { id } } }
     }
 
+    /// Scroll to `offset` pixels. An animated scroll glides there and fires
+    /// `on_scroll` on the way; otherwise it jumps without an event.
+    pub fn set_offset(&self, offset: u32, animated: bool) {
+        (lib().scrollview_set_offset)(self.container.ctrl.id, offset, animated as u32);
+    }
+
+    /// Current vertical scroll offset in pixels.
+    pub fn offset(&self) -> u32 {
+        (lib().scrollview_get_offset)(self.container.ctrl.id)
+    }
+
+    /// `SCROLLBAR_ALWAYS` (default) or `SCROLLBAR_OVERLAY`.
+    pub fn set_scrollbar_style(&self, style: u32) {
+        (lib().scrollview_set_scrollbar_style)(self.container.ctrl.id, style);
+    }
+
+    /// Allow scrolling by dragging the content, with a kinetic glide after
+    /// release (enabled by default).
+    pub fn set_kinetic(&self, enabled: bool) {
+        (lib().scrollview_set_kinetic)(self.container.ctrl.id, enabled as u32);
+    }
+
+    /// Called whenever the user, a kinetic glide or an animated `set_offset`
+    /// moves the content.
     pub fn on_scroll(&self, mut f: impl FnMut(&ScrollChangedEvent) + 'static) {
         let (thunk, ud) = events::register(move |id, _| {
-            let offset = Control::from_id(id).get_state();
-            f(&ScrollChangedEvent { id, offset });
+            let mut offset = 0u32;
+            let mut delta = 0i32;
+            let mut max_offset = 0u32;
+            (lib().scrollview_get_scroll_info)(id, &mut offset, &mut delta, &mut max_offset);
+            f(&ScrollChangedEvent { id, offset, delta, max_offset });
         });
-        (lib().on_change_fn)(self.container.ctrl.id, thunk, ud);
+        (lib().on_event_fn)(self.container.ctrl.id, crate::EVENT_SCROLL, thunk, ud);
     }
 }
@@ -1,3 +1,5 @@
+use alloc::string::String;
+use alloc::vec::Vec;
 use crate::{Control, Widget, lib, events, KIND_SEARCH_FIELD};
 use crate::events::{TextChangedEvent, SubmitEvent};
 
@@ -13,6 +15,41 @@ impl SearchField {
         (lib().textfield_set_placeholder)(self.ctrl.id, text.as_ptr(), text.len() as u32);
     }
 
+    /// Delay `on_text_changed` until typing pauses for `ms` milliseconds
+    /// (0 = fire on every keystroke).
+    pub fn set_debounce(&self, ms: u32) {
+        (lib().searchfield_set_debounce)(self.ctrl.id, ms);
+    }
+
+    /// Show `items` in a popup below the field (empty slice hides it).
+    /// Up/Down highlight an entry; Enter or a click makes it the query and
+    /// fires `on_text_changed` and `on_submit`.
+    pub fn set_suggestions(&self, items: &[&str]) {
+        let mut joined = Vec::new();
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 { joined.push(b'|'); }
+            joined.extend_from_slice(item.as_bytes());
+        }
+        (lib().searchfield_set_suggestions)(self.ctrl.id, joined.as_ptr(), joined.len() as u32);
+    }
+
+    /// Recently submitted queries, newest first. They are offered
+    /// automatically while the field is empty.
+    pub fn history(&self) -> Vec<String> {
+        let mut buf = [0u8; 4096];
+        let len = (lib().searchfield_get_history)(self.ctrl.id, buf.as_mut_ptr(), buf.len() as u32) as usize;
+        if len == 0 {
+            return Vec::new();
+        }
+        buf[..len].split(|&b| b == b'|')
+            .map(|s| String::from_utf8_lossy(s).into_owned())
+            .collect()
+    }
+
+    pub fn clear_history(&self) {
+        (lib().searchfield_clear_history)(self.ctrl.id);
+    }
+
     /// Called when text content changes.
     pub fn on_text_changed(&self, mut f: impl FnMut(&TextChangedEvent) + 'static) {
         let (thunk, ud) = events::register(move |id, _| f(&TextChangedEvent { id }));
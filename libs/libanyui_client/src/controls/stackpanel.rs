@@ -1,4 +1,5 @@
-use crate::{Container, Control, Widget, lib, KIND_STACK_PANEL, ORIENTATION_VERTICAL, ORIENTATION_HORIZONTAL};
+use crate::{Container, Control, Widget, lib, events, KIND_STACK_PANEL, ORIENTATION_VERTICAL, ORIENTATION_HORIZONTAL};
+use crate::events::ReorderEvent;
 
 container_control!(StackPanel, KIND_STACK_PANEL);
 
@@ -15,4 +16,32 @@ impl StackPanel {
     pub fn set_orientation(&self, orientation: u32) {
         (lib().set_orientation)(self.container.ctrl.id, orientation);
     }
+
+    /// Let the user drag children to new positions by a handle strip at the
+    /// leading edge of each row.
+    pub fn set_reorderable(&self, enabled: bool) {
+        (lib().stackpanel_set_reorderable)(self.container.ctrl.id, enabled as u32);
+    }
+
+    /// Width (vertical) or height (horizontal) of the drag handle strip.
+    pub fn set_handle_size(&self, size: u32) {
+        (lib().stackpanel_set_handle_size)(self.container.ctrl.id, size);
+    }
+
+    /// Move child `from` to index `to`, sliding the other children aside.
+    /// Does not fire `on_reorder`. Returns false if an index is out of range.
+    pub fn move_child(&self, from: u32, to: u32) -> bool {
+        (lib().stackpanel_move_child)(self.container.ctrl.id, from, to) != 0
+    }
+
+    /// Called when the user dropped a dragged child at a new position.
+    pub fn on_reorder(&self, mut f: impl FnMut(&ReorderEvent) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| {
+            let mut old_index = 0u32;
+            let mut new_index = 0u32;
+            (lib().stackpanel_get_last_reorder)(id, &mut old_index, &mut new_index);
+            f(&ReorderEvent { id, old_index, new_index });
+        });
+        (lib().on_event_fn)(self.container.ctrl.id, crate::EVENT_REORDER, thunk, ud);
+    }
 }
@@ -9,6 +9,43 @@ impl Stepper {
         Self { ctrl: Control { id } }
     }
 
+    /// Current value, in units of the last decimal place
+    /// (with 2 decimals, 1234 means 12.34).
+    pub fn value(&self) -> i32 {
+        self.get_state() as i32
+    }
+
+    /// Set the value (clamped to the range).
+    pub fn set_value(&self, value: i32) {
+        self.set_state(value as u32);
+    }
+
+    /// Set the allowed range (default 0..=i32::MAX).
+    pub fn set_range(&self, min: i32, max: i32) {
+        (lib().stepper_set_range)(self.ctrl.id, min, max);
+    }
+
+    /// Set the increment per click / arrow key (default 1).
+    /// Holding a button repeats it, accelerating the longer it is held.
+    pub fn set_step(&self, step: u32) {
+        (lib().stepper_set_step)(self.ctrl.id, step);
+    }
+
+    /// Display the value with `places` decimal places (0-6).
+    pub fn set_decimals(&self, places: u32) {
+        (lib().stepper_set_decimals)(self.ctrl.id, places);
+    }
+
+    /// Text shown before the value (e.g. "$").
+    pub fn set_prefix(&self, text: &str) {
+        (lib().stepper_set_affix)(self.ctrl.id, 0, text.as_ptr(), text.len() as u32);
+    }
+
+    /// Text shown after the value (e.g. " px").
+    pub fn set_suffix(&self, text: &str) {
+        (lib().stepper_set_affix)(self.ctrl.id, 1, text.as_ptr(), text.len() as u32);
+    }
+
     pub fn on_value_changed(&self, mut f: impl FnMut(&ValueChangedEvent) + 'static) {
         let (thunk, ud) = events::register(move |id, _| {
             let value = Control::from_id(id).get_state();
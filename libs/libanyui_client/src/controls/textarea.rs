@@ -1,5 +1,5 @@
 use crate::{Control, Widget, lib, events, KIND_TEXT_AREA};
-use crate::events::TextChangedEvent;
+use crate::events::{TextChangedEvent, SubmitEvent};
 
 leaf_control!(TextArea, KIND_TEXT_AREA);
 
@@ -9,6 +9,27 @@ impl TextArea {
         Self { ctrl: Control { id } }
     }
 
+    /// Text shown in secondary color while the area is empty.
+    pub fn set_placeholder(&self, text: &str) {
+        (lib().textarea_set_placeholder)(self.ctrl.id, text.as_ptr(), text.len() as u32);
+    }
+
+    /// Maximum text length in bytes (0 = unlimited).
+    pub fn set_max_length(&self, max_len: u32) {
+        (lib().textarea_set_max_length)(self.ctrl.id, max_len);
+    }
+
+    /// Let Ctrl+Enter fire `on_submit` instead of inserting a newline.
+    pub fn set_submit_on_ctrl_enter(&self, enabled: bool) {
+        (lib().textarea_set_submit_on_ctrl_enter)(self.ctrl.id, enabled as u32);
+    }
+
+    /// Called on Ctrl+Enter when `set_submit_on_ctrl_enter(true)`.
+    pub fn on_submit(&self, mut f: impl FnMut(&SubmitEvent) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| f(&SubmitEvent { id }));
+        (lib().on_submit_fn)(self.ctrl.id, thunk, ud);
+    }
+
     pub fn on_text_changed(&self, mut f: impl FnMut(&TextChangedEvent) + 'static) {
         let (thunk, ud) = events::register(move |id, _| f(&TextChangedEvent { id }));
         (lib().on_change_fn)(self.ctrl.id, thunk, ud);
@@ -11,6 +11,11 @@ impl TextEditor {
         Self { ctrl: Control { id } }
     }
 
+    /// Wrap an existing control ID as a TextEditor.
+    pub fn from_id(id: u32) -> Self {
+        Self { ctrl: Control { id } }
+    }
+
     /// Create a TextEditor and load text from a file.
     pub fn from_file(path: &str, w: u32, h: u32) -> Self {
         let te = Self::new(w, h);
@@ -137,6 +142,13 @@ impl TextEditor {
         (lib().texteditor_set_read_only)(self.ctrl.id, read_only as u32);
     }
 
+    /// Underline misspelled words (see `set_spell_checker`). Right-clicking
+    /// one offers the checker's suggestions plus Ignore / Add to Dictionary.
+    /// Read-only editors are never checked.
+    pub fn set_spell_check(&self, enabled: bool) {
+        (lib().set_spell_check)(self.ctrl.id, enabled as u32);
+    }
+
     /// Scroll the view so the given line is visible (centered if possible).
     pub fn ensure_line_visible(&self, line: u32) {
         (lib().texteditor_ensure_line_visible)(self.ctrl.id, line);
@@ -149,4 +161,21 @@ impl TextEditor {
         });
         (lib().on_change_fn)(self.ctrl.id, thunk, ud);
     }
+
+    /// Called when "Ignore" is chosen for a misspelled word.
+    pub fn on_spell_ignore(&self, mut f: impl FnMut(&crate::events::SpellEvent) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| {
+            f(&crate::events::SpellEvent { id });
+        });
+        (lib().on_event_fn)(self.ctrl.id, crate::EVENT_SPELL_IGNORE, thunk, ud);
+    }
+
+    /// Called when "Add to Dictionary" is chosen for a misspelled word;
+    /// the application should persist `SpellEvent::word()`.
+    pub fn on_spell_add(&self, mut f: impl FnMut(&crate::events::SpellEvent) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| {
+            f(&crate::events::SpellEvent { id });
+        });
+        (lib().on_event_fn)(self.ctrl.id, crate::EVENT_SPELL_ADD, thunk, ud);
+    }
 }
@@ -1,5 +1,5 @@
-use crate::{Control, Widget, lib, events, KIND_TEXTFIELD};
-use crate::events::{TextChangedEvent, SubmitEvent};
+use crate::{Control, Widget, lib, events, KIND_TEXTFIELD, EVENT_SPELL_IGNORE, EVENT_SPELL_ADD, EVENT_VALIDATE};
+use crate::events::{TextChangedEvent, SubmitEvent, SpellEvent, ValidateEvent};
 
 leaf_control!(TextField, KIND_TEXTFIELD);
 
@@ -29,6 +29,65 @@ impl TextField {
         (lib().textfield_set_password)(self.ctrl.id, enabled as u32);
     }
 
+    /// Restrict typed input to `FILTER_NUMERIC` (digits) or `FILTER_DECIMAL`
+    /// (optional '-', digits, one decimal separator). `FILTER_NONE` lifts it.
+    pub fn set_filter(&self, mode: u32) {
+        (lib().textfield_set_filter)(self.ctrl.id, mode);
+    }
+
+    /// Decimal separator for `FILTER_DECIMAL` (default '.'). Typing either
+    /// '.' or ',' enters this separator.
+    pub fn set_decimal_separator(&self, sep: char) {
+        (lib().textfield_set_decimal_separator)(self.ctrl.id, sep as u32);
+    }
+
+    /// Input mask: `#` digit, `A` letter, `*` letter or digit; other
+    /// characters are literals inserted automatically ("##:##"). Empty clears it.
+    pub fn set_mask(&self, mask: &str) {
+        (lib().textfield_set_mask)(self.ctrl.id, mask.as_ptr(), mask.len() as u32);
+    }
+
+    /// Maximum text length in bytes (0 = unlimited).
+    pub fn set_max_length(&self, max_len: u32) {
+        (lib().textfield_set_max_length)(self.ctrl.id, max_len);
+    }
+
+    /// Show (or clear) the invalid-input border. Rejected input sets it;
+    /// the next accepted edit clears it.
+    pub fn set_invalid(&self, invalid: bool) {
+        (lib().textfield_set_invalid)(self.ctrl.id, invalid as u32);
+    }
+
+    pub fn is_invalid(&self) -> bool {
+        (lib().textfield_is_invalid)(self.ctrl.id) != 0
+    }
+
+    /// Called after each edit, before `on_text_changed`; call
+    /// `ValidateEvent::reject()` to undo the edit.
+    pub fn on_validate(&self, mut f: impl FnMut(&ValidateEvent) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| f(&ValidateEvent { id }));
+        (lib().on_event_fn)(self.ctrl.id, EVENT_VALIDATE, thunk, ud);
+    }
+
+    /// Underline misspelled words (see `set_spell_checker`). Right-clicking
+    /// one offers the checker's suggestions plus Ignore / Add to Dictionary.
+    pub fn set_spell_check(&self, enabled: bool) {
+        (lib().set_spell_check)(self.ctrl.id, enabled as u32);
+    }
+
+    /// Called when "Ignore" is chosen for a misspelled word.
+    pub fn on_spell_ignore(&self, mut f: impl FnMut(&SpellEvent) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| f(&SpellEvent { id }));
+        (lib().on_event_fn)(self.ctrl.id, EVENT_SPELL_IGNORE, thunk, ud);
+    }
+
+    /// Called when "Add to Dictionary" is chosen for a misspelled word;
+    /// the application should persist `SpellEvent::word()`.
+    pub fn on_spell_add(&self, mut f: impl FnMut(&SpellEvent) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| f(&SpellEvent { id }));
+        (lib().on_event_fn)(self.ctrl.id, EVENT_SPELL_ADD, thunk, ud);
+    }
+
     pub fn on_text_changed(&self, mut f: impl FnMut(&TextChangedEvent) + 'static) {
         let (thunk, ud) = events::register(move |id, _| f(&TextChangedEvent { id }));
         (lib().on_change_fn)(self.ctrl.id, thunk, ud);
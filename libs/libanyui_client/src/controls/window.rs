@@ -1,4 +1,4 @@
-use crate::{Container, Control, Widget, lib, events, KIND_WINDOW, EVENT_CLOSE, EVENT_RESIZE, EVENT_KEY};
+use crate::{Container, Control, Widget, lib, events, KIND_WINDOW, EVENT_CLOSE, EVENT_RESIZE, EVENT_KEY, EVENT_SNAP};
 use crate::events::{EventArgs, ClickEvent};
 use crate::KeyEvent;
 
@@ -14,6 +14,26 @@ pub const WIN_FLAG_NO_MINIMIZE: u32 = 0x10;
 pub const WIN_FLAG_NO_MAXIMIZE: u32 = 0x20;
 pub const WIN_FLAG_SHADOW: u32 = 0x40;
 
+// ── Snap region constants ───────────────────────────────────────────
+
+pub const SNAP_NONE: u32 = 0;
+pub const SNAP_LEFT: u32 = 1;
+pub const SNAP_RIGHT: u32 = 2;
+pub const SNAP_TOP_LEFT: u32 = 3;
+pub const SNAP_TOP_RIGHT: u32 = 4;
+pub const SNAP_BOTTOM_LEFT: u32 = 5;
+pub const SNAP_BOTTOM_RIGHT: u32 = 6;
+pub const SNAP_MAXIMIZED: u32 = 7;
+
+// ── Present mode constants ──────────────────────────────────────────
+
+/// Wait for the compositor to show the previous frame (default).
+pub const PRESENT_VSYNC: u32 = 0;
+/// Present every frame at once; lowest latency, may tear.
+pub const PRESENT_IMMEDIATE: u32 = 1;
+/// Present only when `Window::present()` is called.
+pub const PRESENT_MANUAL: u32 = 2;
+
 impl Window {
     /// Create a new window at position (x, y) with default flags.
     /// x/y: pixel coordinates, or -1 for compositor auto-placement (CW_USEDEFAULT).
@@ -47,11 +67,46 @@ impl Window {
         (lib().move_window)(self.container.ctrl.id, x, y);
     }
 
+    /// Index of the monitor containing the window's centre.
+    pub fn monitor(&self) -> u32 {
+        (lib().get_window_monitor)(self.container.ctrl.id)
+    }
+
+    /// Move the window onto `monitor`. `x`/`y` are offsets from the monitor's
+    /// top-left corner; -1 centres on that axis. Returns false if the monitor
+    /// index is out of range.
+    pub fn place_on_monitor(&self, monitor: u32, x: i32, y: i32) -> bool {
+        (lib().place_window_on_monitor)(self.container.ctrl.id, monitor, x, y) != 0
+    }
+
+    /// Centre this window on the monitor `owner` occupies (for dialogs).
+    pub fn center_on_owner(&self, owner: &Window) -> bool {
+        (lib().center_on_owner_monitor)(self.container.ctrl.id, owner.container.ctrl.id) != 0
+    }
+
     /// Minimize the window (hide off-screen, restore via dock click).
     pub fn minimize(&self) {
         (lib().minimize_window)(self.container.ctrl.id);
     }
 
+    /// Make this window a child dialog of `owner`: it is centered over the
+    /// owner, always stays above it and is minimized / restored with it.
+    /// Call `move_to` afterwards to place it somewhere else.
+    pub fn set_owner(&self, owner: &Window) {
+        (lib().set_window_owner)(self.container.ctrl.id, owner.container.ctrl.id);
+    }
+
+    /// Detach the window from its owner.
+    pub fn clear_owner(&self) {
+        (lib().set_window_owner)(self.container.ctrl.id, 0);
+    }
+
+    /// Keep the window above all normal windows (same as creating it with
+    /// `WIN_FLAG_ALWAYS_ON_TOP`).
+    pub fn set_always_on_top(&self, on: bool) {
+        (lib().set_always_on_top)(self.container.ctrl.id, on as u32);
+    }
+
     /// Register a closure to be called when the window background is clicked.
     pub fn on_click(&self, mut f: impl FnMut(&ClickEvent) + 'static) {
         let (thunk, ud) = events::register(move |id, _| f(&ClickEvent { id }));
@@ -68,6 +123,68 @@ impl Window {
         (lib().on_event_fn)(self.container.ctrl.id, EVENT_RESIZE, thunk, ud);
     }
 
+    /// Tile the window to a screen region (`SNAP_LEFT`, `SNAP_TOP_RIGHT`, ...)
+    /// or restore its previous bounds with `SNAP_NONE`. Applied by the
+    /// compositor; `on_resize` and then `on_snap` fire once it takes effect.
+    pub fn snap(&self, region: u32) {
+        (lib().snap_window)(self.container.ctrl.id, region);
+    }
+
+    /// Current snap region (`SNAP_NONE` when floating).
+    pub fn snap_region(&self) -> u32 {
+        (lib().get_snap_region)(self.container.ctrl.id)
+    }
+
+    /// Called when the window is snapped — by `snap()` or by dragging it to
+    /// a screen edge — or released from a snap. The window already has its
+    /// new size; query `snap_region()` for the region.
+    pub fn on_snap(&self, mut f: impl FnMut(&EventArgs) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| f(&EventArgs { id }));
+        (lib().on_event_fn)(self.container.ctrl.id, EVENT_SNAP, thunk, ud);
+    }
+
+    /// Zoom the window's content to `percent` (125, 150, 200; 100 = none).
+    /// Fonts and layout grow while the window keeps its on-screen size.
+    /// Pass 0 to follow the system accessibility zoom again.
+    pub fn set_zoom(&self, percent: u32) {
+        (lib().set_window_zoom)(self.container.ctrl.id, percent);
+    }
+
+    /// Zoom currently in effect for this window, in percent.
+    pub fn zoom(&self) -> u32 {
+        (lib().get_window_zoom)(self.container.ctrl.id)
+    }
+
+    /// Switch the window to right-to-left layout: docked children and
+    /// horizontal stacks are mirrored and text without a strong direction
+    /// reads right to left.
+    pub fn set_rtl(&self, rtl: bool) {
+        (lib().set_window_rtl)(self.container.ctrl.id, rtl as u32);
+    }
+
+    /// Whether the window uses right-to-left layout.
+    pub fn is_rtl(&self) -> bool {
+        (lib().get_window_rtl)(self.container.ctrl.id) != 0
+    }
+
+    /// Choose how frames reach the screen (`PRESENT_VSYNC`,
+    /// `PRESENT_IMMEDIATE`, `PRESENT_MANUAL`).
+    pub fn set_present_mode(&self, mode: u32) {
+        (lib().set_present_mode)(self.container.ctrl.id, mode);
+    }
+
+    /// Current present mode (`PRESENT_*`).
+    pub fn present_mode(&self) -> u32 {
+        (lib().get_present_mode)(self.container.ctrl.id)
+    }
+
+    /// In `PRESENT_MANUAL` mode, show the changes made since the last
+    /// present. The frame goes out at the end of the current event loop
+    /// iteration.
+    pub fn present(&self) {
+        (lib().present)(self.container.ctrl.id);
+    }
+
     /// Register a typed key-down handler on this window.
     /// The closure receives a `KeyEvent` with keycode, char_code, and modifiers.
     /// This fires for unhandled key events that bubble up to the window.
@@ -0,0 +1,160 @@
+//! Owner draw — paint over a control's own rendering.
+//!
+//! After a control with a custom draw callback renders itself, the callback
+//! gets a [`CustomDrawInfo`] describing the visible part of the control in
+//! the window's back buffer and can paint over it (custom buttons, list
+//! rows, …). Children are drawn afterwards, on top.
+//!
+//! # Usage
+//! ```rust
+//! row.set_custom_draw(|d| {
+//!     let tc = d.colors();
+//!     let bg = if d.is_hovered() { tc.control_hover } else { tc.card_bg };
+//!     d.fill_rect(0, 0, d.control_width, d.control_height, bg);
+//!     d.fill_rect(0, 0, d.scale(4), d.control_height, tc.accent);
+//! });
+//! ```
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use crate::theme::ThemeColors;
+
+/// `CustomDrawInfo::flags`: the pointer is over the control.
+pub const DRAW_HOVERED: u32 = 1;
+/// `CustomDrawInfo::flags`: the control is held down by the mouse.
+pub const DRAW_PRESSED: u32 = 2;
+/// `CustomDrawInfo::flags`: the control has keyboard focus.
+pub const DRAW_FOCUSED: u32 = 4;
+/// `CustomDrawInfo::flags`: the control is disabled.
+pub const DRAW_DISABLED: u32 = 8;
+
+/// Raw owner-draw callback: extern "C" fn(control_id, info, userdata).
+pub type CustomDrawFn = extern "C" fn(u32, *const CustomDrawInfo, u64);
+
+/// Drawing target passed to owner-draw callbacks. All sizes and offsets are
+/// physical pixels.
+///
+/// Layout **must** match `CustomDrawInfo` in `libs/libanyui/src/control.rs`.
+#[repr(C)]
+pub struct CustomDrawInfo {
+    /// First visible pixel of the control (ARGB, row-major).
+    pub pixels: *mut u32,
+    /// Size of the visible region starting at `pixels`.
+    pub width: u32,
+    pub height: u32,
+    /// Pixels per back-buffer row.
+    pub stride: u32,
+    /// Position of `pixels[0]` within the control; non-zero when the control
+    /// is partly scrolled or clipped away.
+    pub offset_x: u32,
+    pub offset_y: u32,
+    /// Full size of the control.
+    pub control_width: u32,
+    pub control_height: u32,
+    /// UI scale in percent (logical → physical pixels).
+    pub scale: u32,
+    /// `DRAW_*` flags.
+    pub flags: u32,
+    /// The control's state value (checked, selected index, …).
+    pub state: u32,
+    colors: *const ThemeColors,
+}
+
+impl CustomDrawInfo {
+    /// The active theme palette.
+    pub fn colors(&self) -> &ThemeColors {
+        unsafe { &*self.colors }
+    }
+
+    pub fn is_hovered(&self) -> bool { self.flags & DRAW_HOVERED != 0 }
+    pub fn is_pressed(&self) -> bool { self.flags & DRAW_PRESSED != 0 }
+    pub fn is_focused(&self) -> bool { self.flags & DRAW_FOCUSED != 0 }
+    pub fn is_disabled(&self) -> bool { self.flags & DRAW_DISABLED != 0 }
+
+    /// Scale a logical pixel value to physical pixels.
+    pub fn scale(&self, logical: u32) -> u32 {
+        (logical * self.scale + 50) / 100
+    }
+
+    /// Fill a rectangle given in control coordinates (physical pixels),
+    /// clipped to the visible region. Colors with alpha below 255 are
+    /// blended over the existing pixels.
+    pub fn fill_rect(&self, x: i32, y: i32, w: u32, h: u32, color: u32) {
+        let x0 = (x - self.offset_x as i32).max(0);
+        let y0 = (y - self.offset_y as i32).max(0);
+        let x1 = (x + w as i32 - self.offset_x as i32).min(self.width as i32);
+        let y1 = (y + h as i32 - self.offset_y as i32).min(self.height as i32);
+        if x1 <= x0 || y1 <= y0 {
+            return;
+        }
+        for row in y0..y1 {
+            let line = unsafe { self.pixels.add(row as usize * self.stride as usize) };
+            for col in x0..x1 {
+                unsafe {
+                    let p = line.add(col as usize);
+                    *p = blend(*p, color);
+                }
+            }
+        }
+    }
+
+    /// Set one pixel given in control coordinates (ignored when clipped).
+    pub fn put_pixel(&self, x: i32, y: i32, color: u32) {
+        self.fill_rect(x, y, 1, 1, color);
+    }
+
+    /// Read one pixel given in control coordinates (0 when clipped).
+    pub fn pixel(&self, x: i32, y: i32) -> u32 {
+        let vx = x - self.offset_x as i32;
+        let vy = y - self.offset_y as i32;
+        if vx < 0 || vy < 0 || vx >= self.width as i32 || vy >= self.height as i32 {
+            return 0;
+        }
+        unsafe { *self.pixels.add(vy as usize * self.stride as usize + vx as usize) }
+    }
+}
+
+/// Source-over blend of ARGB `src` onto `dst`.
+fn blend(dst: u32, src: u32) -> u32 {
+    let a = src >> 24;
+    if a == 255 {
+        return src;
+    }
+    if a == 0 {
+        return dst;
+    }
+    let inv = 255 - a;
+    let ch = |shift: u32| (((src >> shift) & 0xFF) * a + ((dst >> shift) & 0xFF) * inv) / 255;
+    let da = (dst >> 24) & 0xFF;
+    let out_a = a + da * inv / 255;
+    (out_a << 24) | (ch(16) << 16) | (ch(8) << 8) | ch(0)
+}
+
+// ── Closure registry ─────────────────────────────────────────────────
+
+type DrawHandler = Box<dyn FnMut(&CustomDrawInfo)>;
+
+static mut DRAW_HANDLERS: Option<Vec<DrawHandler>> = None;
+
+fn handlers() -> &'static mut Vec<DrawHandler> {
+    unsafe { DRAW_HANDLERS.get_or_insert_with(Vec::new) }
+}
+
+/// FFI thunk: dispatches to the registered closure at `DRAW_HANDLERS[userdata]`.
+extern "C" fn draw_thunk(_id: u32, info: *const CustomDrawInfo, userdata: u64) {
+    if info.is_null() {
+        return;
+    }
+    let idx = userdata as usize;
+    let h = handlers();
+    if idx < h.len() {
+        h[idx](unsafe { &*info });
+    }
+}
+
+/// Register a closure, returning the (thunk, userdata) pair for FFI registration.
+pub(crate) fn register(f: impl FnMut(&CustomDrawInfo) + 'static) -> (CustomDrawFn, u64) {
+    let idx = handlers().len();
+    handlers().push(Box::new(f));
+    (draw_thunk, idx as u64)
+}
@@ -0,0 +1,14 @@
+/// Link clicked event — fired by MarkdownView when a link is clicked.
+pub struct LinkClickedEvent {
+    /// The control ID.
+    pub id: u32,
+}
+
+impl LinkClickedEvent {
+    /// The clicked link's target URL, as written in the markdown source.
+    pub fn url(&self) -> alloc::string::String {
+        let mut buf = [0u8; 512];
+        let len = ((crate::lib().markdown_get_link)(self.id, buf.as_mut_ptr(), buf.len() as u32) as usize).min(buf.len());
+        alloc::string::String::from_utf8_lossy(&buf[..len]).into_owned()
+    }
+}
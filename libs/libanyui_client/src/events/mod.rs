@@ -11,10 +11,16 @@ use alloc::vec::Vec;
 
 pub mod shared;
 mod color;
+mod link;
+mod reorder;
+mod validate;
 
 // Re-export all event types at the events:: level
 pub use shared::*;
 pub use color::ColorSelectedEvent;
+pub use link::LinkClickedEvent;
+pub use reorder::ReorderEvent;
+pub use validate::ValidateEvent;
 
 // ══════════════════════════════════════════════════════════════════════
 //  Closure Registry
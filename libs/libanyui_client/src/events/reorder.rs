@@ -0,0 +1,10 @@
+/// Reorder event — fired by a reorderable StackPanel when a child was dragged
+/// to a new position.
+pub struct ReorderEvent {
+    /// The StackPanel's control ID.
+    pub id: u32,
+    /// Child index the dragged control had before the drag.
+    pub old_index: u32,
+    /// Child index the dragged control has now.
+    pub new_index: u32,
+}
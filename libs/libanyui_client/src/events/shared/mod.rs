@@ -5,6 +5,7 @@ mod checked;
 mod common;
 mod scroll;
 mod selection;
+mod spell;
 mod submit;
 mod text;
 mod value;
@@ -14,6 +15,7 @@ pub use checked::CheckedChangedEvent;
 pub use common::EventArgs;
 pub use scroll::ScrollChangedEvent;
 pub use selection::SelectionChangedEvent;
+pub use spell::SpellEvent;
 pub use submit::SubmitEvent;
 pub use text::TextChangedEvent;
 pub use value::ValueChangedEvent;
@@ -4,4 +4,8 @@ pub struct ScrollChangedEvent {
     pub id: u32,
     /// The current scroll offset.
     pub offset: u32,
+    /// Change since the previous scroll event (positive = scrolled down).
+    pub delta: i32,
+    /// Largest possible offset; `offset == max_offset` at the end of the content.
+    pub max_offset: u32,
 }
@@ -0,0 +1,15 @@
+/// Spell event — fired by TextField, TextEditor when "Ignore" or
+/// "Add to Dictionary" is chosen for a misspelled word.
+pub struct SpellEvent {
+    /// The control ID containing the word.
+    pub id: u32,
+}
+
+impl SpellEvent {
+    /// The word the action applied to.
+    pub fn word(&self) -> alloc::string::String {
+        let mut buf = [0u8; 128];
+        let len = ((crate::lib().spell_get_word)(buf.as_mut_ptr(), buf.len() as u32) as usize).min(buf.len());
+        alloc::string::String::from_utf8_lossy(&buf[..len]).into_owned()
+    }
+}
@@ -0,0 +1,21 @@
+/// Validate event — fired by TextField before an edit is committed.
+pub struct ValidateEvent {
+    /// The TextField's control ID.
+    pub id: u32,
+}
+
+impl ValidateEvent {
+    /// The text as it would be after the edit.
+    pub fn text(&self) -> alloc::string::String {
+        let ctrl = crate::Control::from_id(self.id);
+        let mut buf = [0u8; 512];
+        let len = (ctrl.get_text(&mut buf) as usize).min(buf.len());
+        alloc::string::String::from_utf8_lossy(&buf[..len]).into_owned()
+    }
+
+    /// Veto the edit: the previous text is restored, the field is marked
+    /// invalid and no text-changed event fires.
+    pub fn reject(&self) {
+        (crate::lib().textfield_reject_edit)(self.id);
+    }
+}
@@ -0,0 +1,109 @@
+//! Localization — string tables, language switching and locale formatting.
+//!
+//! Translations live in `.lang` files of `key = value` lines (`#` starts a
+//! comment; `\n`, `\t` and `\\` are escapes). Keys starting with `@` set the
+//! number and date conventions of the language:
+//!
+//! ```text
+//! # de.lang
+//! @decimal = ,
+//! @group = .
+//! @date = dd.MM.yyyy
+//! @time = HH:mm
+//! file.open = Öffnen
+//! status.items = {0} Elemente
+//! ```
+//!
+//! Controls given a key with [`Control::set_text_key`] keep following the
+//! active language, so [`set_language`] switches a running app without a
+//! restart. Texts the app builds itself are refreshed in
+//! [`on_language_changed`]. A key missing from the active language falls
+//! back to the first language loaded, then to the key itself.
+//!
+//! # Usage
+//! ```rust
+//! i18n::lang_load("en", "/Apps/Notes/lang/en.lang");
+//! i18n::lang_load("de", "/Apps/Notes/lang/de.lang");
+//! i18n::set_language("de");
+//!
+//! open.set_text_key("file.open", &[]);
+//! status.set_text_key("status.items", &["12"]);
+//! i18n::on_language_changed(move || title.set_text(&i18n::tr("app.title", &[])));
+//! ```
+//!
+//! [`Control::set_text_key`]: crate::Control::set_text_key
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::lib;
+
+/// Load a `.lang` file into the string table of language `lang` (e.g.
+/// `"de"`). Returns false if the file cannot be read.
+pub fn lang_load(lang: &str, path: &str) -> bool {
+    (lib().lang_load)(lang.as_ptr(), lang.len() as u32, path.as_ptr(), path.len() as u32) != 0
+}
+
+/// Switch the active language and update every keyed text. Returns false
+/// if no file was loaded for `lang`.
+pub fn set_language(lang: &str) -> bool {
+    (lib().set_language)(lang.as_ptr(), lang.len() as u32) != 0
+}
+
+/// The active language code (empty if none was set).
+pub fn language() -> String {
+    let mut buf = [0u8; 32];
+    let len = (lib().get_language)(buf.as_mut_ptr(), buf.len() as u32) as usize;
+    String::from(core::str::from_utf8(&buf[..len]).unwrap_or(""))
+}
+
+/// Register a callback run after the language was switched (keyed texts
+/// are already updated).
+pub fn on_language_changed(mut f: impl FnMut() + 'static) {
+    let (thunk, ud) = crate::events::register(move |_, _| f());
+    (lib().on_language_changed)(thunk, ud);
+}
+
+/// Translate `key` in the active language, with `{0}`, `{1}`, ...
+/// replaced by `args`.
+pub fn tr(key: &str, args: &[&str]) -> String {
+    let packed = pack_args(args);
+    let mut buf = [0u8; 1024];
+    let len = (lib().tr)(
+        key.as_ptr(), key.len() as u32, packed.as_ptr(), packed.len() as u32, buf.as_mut_ptr(), buf.len() as u32,
+    ) as usize;
+    String::from_utf8_lossy(&buf[..len]).into_owned()
+}
+
+/// Format a number with `decimals` fraction digits in the active locale
+/// (e.g. `1.234,50` for German).
+pub fn format_number(value: f64, decimals: u32) -> String {
+    let mut buf = [0u8; 64];
+    let len = (lib().format_number)(value, decimals, buf.as_mut_ptr(), buf.len() as u32) as usize;
+    String::from_utf8_lossy(&buf[..len]).into_owned()
+}
+
+/// Format a date with the active locale's date pattern.
+pub fn format_date(year: u32, month: u32, day: u32) -> String {
+    let mut buf = [0u8; 64];
+    let len = (lib().format_date)(year, month, day, buf.as_mut_ptr(), buf.len() as u32) as usize;
+    String::from_utf8_lossy(&buf[..len]).into_owned()
+}
+
+/// Format a time of day with the active locale's time pattern.
+pub fn format_time(hour: u32, minute: u32, second: u32) -> String {
+    let mut buf = [0u8; 64];
+    let len = (lib().format_time)(hour, minute, second, buf.as_mut_ptr(), buf.len() as u32) as usize;
+    String::from_utf8_lossy(&buf[..len]).into_owned()
+}
+
+/// Join arguments with `\0` separators, as libanyui expects them.
+pub(crate) fn pack_args(args: &[&str]) -> Vec<u8> {
+    let mut packed = Vec::new();
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            packed.push(0);
+        }
+        packed.extend_from_slice(arg.as_bytes());
+    }
+    packed
+}
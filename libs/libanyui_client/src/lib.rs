@@ -38,6 +38,21 @@ pub use icon::{Icon, IconType};
 
 pub mod theme;
 
+pub mod custom_draw;
+pub use custom_draw::{CustomDrawInfo, CustomDrawFn};
+
+pub mod virtualize;
+pub use virtualize::ItemProviderFn;
+
+pub mod style;
+pub use style::Style;
+
+pub mod validation;
+pub use validation::{ValidateFn, form_validate};
+
+pub mod i18n;
+pub use i18n::tr;
+
 use dynlink::{DlHandle, dl_open, dl_sym};
 
 // ── Control kind constants (match libanyui's ControlKind enum) ───────
@@ -85,6 +100,12 @@ pub const KIND_TEXT_EDITOR: u32 = 39;
 pub const KIND_TREE_VIEW: u32 = 40;
 pub const KIND_RADIO_GROUP: u32 = 41;
 pub const KIND_DROP_DOWN: u32 = 42;
+pub const KIND_TOAST: u32 = 43;
+pub const KIND_MARKDOWN_VIEW: u32 = 44;
+pub const KIND_CIRCULAR_PROGRESS: u32 = 45;
+pub const KIND_ACTIVITY_SPINNER: u32 = 46;
+pub const KIND_FORM_LAYOUT: u32 = 47;
+pub const KIND_NATIVE_SURFACE: u32 = 48;
 
 // ── DockStyle constants ─────────────────────────────────────────────
 
@@ -100,6 +121,35 @@ pub const DOCK_FILL: u32 = 5;
 pub const ORIENTATION_VERTICAL: u32 = 0;
 pub const ORIENTATION_HORIZONTAL: u32 = 1;
 
+// ── ScrollView scrollbar style constants ────────────────────────────
+
+pub const SCROLLBAR_ALWAYS: u32 = 0;
+pub const SCROLLBAR_OVERLAY: u32 = 1;
+
+// ── Badge corner constants ──────────────────────────────────────────
+
+pub const BADGE_TOP_RIGHT: u32 = 0;
+pub const BADGE_TOP_LEFT: u32 = 1;
+pub const BADGE_BOTTOM_RIGHT: u32 = 2;
+pub const BADGE_BOTTOM_LEFT: u32 = 3;
+
+// ── TextField input filter constants ────────────────────────────────
+
+pub const FILTER_NONE: u32 = 0;
+pub const FILTER_NUMERIC: u32 = 1;
+pub const FILTER_DECIMAL: u32 = 2;
+
+// ── Cursor shape constants ──────────────────────────────────────────
+
+/// Inherit from the parent (text inputs and splitters have their own).
+pub const CURSOR_DEFAULT: u32 = 0;
+pub const CURSOR_ARROW: u32 = 1;
+pub const CURSOR_IBEAM: u32 = 2;
+pub const CURSOR_HAND: u32 = 3;
+pub const CURSOR_RESIZE_H: u32 = 4;
+pub const CURSOR_RESIZE_V: u32 = 5;
+pub const CURSOR_BUSY: u32 = 6;
+
 // ── Event type constants ────────────────────────────────────────────
 
 pub const EVENT_CLICK: u32 = 1;
@@ -119,10 +169,39 @@ pub const EVENT_MOUSE_DOWN: u32 = 14;
 pub const EVENT_MOUSE_UP: u32 = 15;
 pub const EVENT_MOUSE_MOVE: u32 = 16;
 pub const EVENT_SUBMIT: u32 = 17;
+pub const EVENT_SNAP: u32 = 18;
+pub const EVENT_SPELL_IGNORE: u32 = 19;
+pub const EVENT_SPELL_ADD: u32 = 20;
+pub const EVENT_REORDER: u32 = 21;
+pub const EVENT_VALIDATE: u32 = 22;
+pub const EVENT_TOUCH_DOWN: u32 = 23;
+pub const EVENT_TOUCH_MOVE: u32 = 24;
+pub const EVENT_TOUCH_UP: u32 = 25;
+pub const EVENT_PINCH: u32 = 26;
+pub const EVENT_ERROR: u32 = 27;
+pub const EVENT_LONG_PRESS: u32 = 28;
+pub const EVENT_SWIPE: u32 = 29;
+pub const EVENT_DOUBLE_TAP: u32 = 30;
+pub const EVENT_SELECTION_CHANGED: u32 = 31;
+pub const EVENT_HELP: u32 = 32;
+
+// Container selection modes (`Container::set_selection_mode`)
+pub const SELECT_NONE: u32 = 0;
+pub const SELECT_SINGLE: u32 = 1;
+pub const SELECT_MULTIPLE: u32 = 2;
 
 /// Callback type: extern "C" fn(control_id: u32, event_type: u32, userdata: u64)
 pub type Callback = extern "C" fn(u32, u32, u64);
 
+/// Tag destructor: extern "C" fn(control_id: u32, tag: u64), called when the control is removed.
+pub type TagDestructor = extern "C" fn(u32, u64);
+
+/// Spell checker word test: extern "C" fn(word, len, userdata) -> 1 if correct.
+pub type SpellCheckFn = extern "C" fn(*const u8, u32, u64) -> u32;
+/// Spell checker suggestions: extern "C" fn(word, len, out, out_cap, userdata)
+/// -> bytes of pipe-separated replacements written to `out`.
+pub type SpellSuggestFn = extern "C" fn(*const u8, u32, *mut u8, u32, u64) -> u32;
+
 // ══════════════════════════════════════════════════════════════════════
 //  Internal: cached function pointers from libanyui.so
 // ══════════════════════════════════════════════════════════════════════
@@ -158,6 +237,10 @@ struct AnyuiLib {
     resize_window: extern "C" fn(u32, u32, u32),
     move_window: extern "C" fn(u32, i32, i32),
     minimize_window: extern "C" fn(u32),
+    pub(crate) snap_window: extern "C" fn(u32, u32),
+    pub(crate) get_snap_region: extern "C" fn(u32) -> u32,
+    pub(crate) set_window_owner: extern "C" fn(u32, u32),
+    pub(crate) set_always_on_top: extern "C" fn(u32, u32),
     // Layout
     set_padding: extern "C" fn(u32, i32, i32, i32, i32),
     set_margin: extern "C" fn(u32, i32, i32, i32, i32),
@@ -176,6 +259,8 @@ struct AnyuiLib {
     set_columns: extern "C" fn(u32, u32),
     set_row_height: extern "C" fn(u32, u32),
     set_column_widths: extern "C" fn(u32, *const u32, u32),
+    formlayout_set_label_width: extern "C" fn(u32, u32),
+    formlayout_set_spacing: extern "C" fn(u32, u32, u32),
     // SplitView properties
     set_split_ratio: extern "C" fn(u32, u32),
     set_min_split: extern "C" fn(u32, u32),
@@ -201,12 +286,42 @@ struct AnyuiLib {
     canvas_get_pixel: extern "C" fn(u32, i32, i32) -> u32,
     canvas_copy_from: extern "C" fn(u32, *const u32, u32),
     canvas_copy_to: extern "C" fn(u32, *mut u32, u32) -> u32,
+    canvas_invalidate_rect: extern "C" fn(u32, i32, i32, u32, u32),
+    canvas_set_double_buffered: extern "C" fn(u32, u32),
+    canvas_begin_paint: extern "C" fn(u32),
+    canvas_end_paint: extern "C" fn(u32),
+    // NativeSurface
+    native_surface_alloc: extern "C" fn(u32, u32, u32) -> *mut u32,
+    native_surface_pixels: extern "C" fn(u32, *mut u32, *mut u32) -> *mut u32,
+    native_surface_present: extern "C" fn(u32),
+    native_surface_release: extern "C" fn(u32),
     // TextField-specific
     textfield_set_prefix: extern "C" fn(u32, u32),
     textfield_set_postfix: extern "C" fn(u32, u32),
     textfield_set_password: extern "C" fn(u32, u32),
     textfield_set_placeholder: extern "C" fn(u32, *const u8, u32),
     textfield_select_all: extern "C" fn(u32),
+    textfield_set_filter: extern "C" fn(u32, u32),
+    textfield_set_decimal_separator: extern "C" fn(u32, u32),
+    textfield_set_mask: extern "C" fn(u32, *const u8, u32),
+    textfield_set_max_length: extern "C" fn(u32, u32),
+    textfield_set_invalid: extern "C" fn(u32, u32),
+    textfield_is_invalid: extern "C" fn(u32) -> u32,
+    textfield_reject_edit: extern "C" fn(u32),
+    // TextArea
+    textarea_set_placeholder: extern "C" fn(u32, *const u8, u32),
+    textarea_set_max_length: extern "C" fn(u32, u32),
+    textarea_set_submit_on_ctrl_enter: extern "C" fn(u32, u32),
+    // Stepper
+    stepper_set_range: extern "C" fn(u32, i32, i32),
+    stepper_set_step: extern "C" fn(u32, u32),
+    stepper_set_decimals: extern "C" fn(u32, u32),
+    stepper_set_affix: extern "C" fn(u32, u32, *const u8, u32),
+    // SearchField
+    searchfield_set_debounce: extern "C" fn(u32, u32),
+    searchfield_set_suggestions: extern "C" fn(u32, *const u8, u32),
+    searchfield_get_history: extern "C" fn(u32, *mut u8, u32) -> u32,
+    searchfield_clear_history: extern "C" fn(u32),
     // Marshal (cross-thread)
     marshal_set_text: extern "C" fn(u32, *const u8, u32),
     marshal_set_color: extern "C" fn(u32, u32),
@@ -219,15 +334,106 @@ struct AnyuiLib {
     set_context_menu: extern "C" fn(u32, u32),
     // Tooltip
     set_tooltip: extern "C" fn(u32, *const u8, u32),
+    // Badge overlays
+    set_badge: extern "C" fn(u32, *const u8, u32, u32),
+    set_badge_count: extern "C" fn(u32, u32, u32),
+    set_badge_corner: extern "C" fn(u32, u32),
+    // Form validation
+    validate_required: extern "C" fn(u32, *const u8, u32) -> u32,
+    validate_range: extern "C" fn(u32, f64, f64, *const u8, u32) -> u32,
+    validate_pattern: extern "C" fn(u32, *const u8, u32, *const u8, u32) -> u32,
+    validate_custom: extern "C" fn(u32, ValidateFn, u64, *const u8, u32) -> u32,
+    validate_clear: extern "C" fn(u32),
+    validate_control: extern "C" fn(u32) -> u32,
+    form_validate: extern "C" fn(u32) -> u32,
+    get_validation_error: extern "C" fn(u32, *mut u8, u32) -> u32,
+    // Localization
+    pub(crate) lang_load: extern "C" fn(*const u8, u32, *const u8, u32) -> u32,
+    pub(crate) set_language: extern "C" fn(*const u8, u32) -> u32,
+    pub(crate) get_language: extern "C" fn(*mut u8, u32) -> u32,
+    pub(crate) on_language_changed: extern "C" fn(Callback, u64),
+    set_text_key: extern "C" fn(u32, *const u8, u32, *const u8, u32) -> u32,
+    pub(crate) tr: extern "C" fn(*const u8, u32, *const u8, u32, *mut u8, u32) -> u32,
+    pub(crate) format_number: extern "C" fn(f64, u32, *mut u8, u32) -> u32,
+    pub(crate) format_date: extern "C" fn(u32, u32, u32, *mut u8, u32) -> u32,
+    pub(crate) format_time: extern "C" fn(u32, u32, u32, *mut u8, u32) -> u32,
+    // Cursor
+    set_cursor: extern "C" fn(u32, u32),
+    // Styles
+    style_create: extern "C" fn(*const u8, u32) -> u32,
+    style_set: extern "C" fn(u32, u32, u32),
+    style_unset: extern "C" fn(u32, u32),
+    style_apply_kind: extern "C" fn(u32, u32),
+    set_class: extern "C" fn(u32, *const u8, u32),
+    // Owner draw
+    set_custom_draw: extern "C" fn(u32, Option<CustomDrawFn>, u64),
+    invalidate: extern "C" fn(u32),
+    // Virtualized containers
+    set_virtualized: extern "C" fn(u32, u32, u32, Option<ItemProviderFn>, u64),
+    virtual_set_count: extern "C" fn(u32, u32),
+    virtual_refresh: extern "C" fn(u32),
+    virtual_get_child: extern "C" fn(u32, u32) -> u32,
+    // Pointer (pen / touch) info
+    get_pointer_info: extern "C" fn(*mut PointerEvent),
+    canvas_get_pointer: extern "C" fn(u32, *mut PointerEvent),
+    // Gesture recognizers
+    gesture_long_press: extern "C" fn(u32, u32),
+    gesture_swipe: extern "C" fn(u32, u32, u32),
+    gesture_double_tap: extern "C" fn(u32, u32),
+    get_gesture_info: extern "C" fn(*mut GestureInfo),
+    // Container selection
+    set_selection_mode: extern "C" fn(u32, u32) -> u32,
+    get_selection: extern "C" fn(u32, *mut u32, u32) -> u32,
+    set_selection: extern "C" fn(u32, *const u32, u32) -> u32,
+    // Context help
+    set_help_topic: extern "C" fn(u32, *const u8, u32),
+    get_help_topic: extern "C" fn(u32, *mut u8, u32) -> u32,
+    get_help_source: extern "C" fn() -> u32,
+    set_whats_this: extern "C" fn(u32),
+    get_whats_this: extern "C" fn() -> u32,
+    // User data
+    set_tag: extern "C" fn(u32, u64),
+    get_tag: extern "C" fn(u32) -> u64,
+    set_tag_destructor: extern "C" fn(u32, Option<TagDestructor>),
+    get_tag_destructor: extern "C" fn(u32) -> Option<TagDestructor>,
     // MessageBox
     message_box: extern "C" fn(u32, *const u8, u32, *const u8, u32),
     // IconButton
     iconbutton_set_pixels: extern "C" fn(u32, *const u32, u32, u32),
+    // ProgressBar
+    progressbar_set_mode: extern "C" fn(u32, u32),
+    progressbar_get_mode: extern "C" fn(u32) -> u32,
+    progressbar_set_buffer: extern "C" fn(u32, u32),
+    progressbar_set_segments: extern "C" fn(u32, u32),
+    // CircularProgress / ActivitySpinner
+    ring_set_thickness: extern "C" fn(u32, u32),
+    circularprogress_set_show_text: extern "C" fn(u32, u32),
+    circularprogress_set_track_color: extern "C" fn(u32, u32),
+    spinner_set_running: extern "C" fn(u32, u32),
+    spinner_is_running: extern "C" fn(u32) -> u32,
+    // StackPanel reordering
+    stackpanel_set_reorderable: extern "C" fn(u32, u32),
+    stackpanel_set_handle_size: extern "C" fn(u32, u32),
+    stackpanel_move_child: extern "C" fn(u32, u32, u32) -> u32,
+    stackpanel_get_last_reorder: extern "C" fn(u32, *mut u32, *mut u32),
+    // ScrollView
+    scrollview_set_offset: extern "C" fn(u32, u32, u32),
+    scrollview_get_offset: extern "C" fn(u32) -> u32,
+    scrollview_set_scrollbar_style: extern "C" fn(u32, u32),
+    scrollview_set_kinetic: extern "C" fn(u32, u32),
+    scrollview_get_scroll_info: extern "C" fn(u32, *mut u32, *mut i32, *mut u32),
     // ImageView
     imageview_set_pixels: extern "C" fn(u32, *const u32, u32, u32),
     imageview_set_scale_mode: extern "C" fn(u32, u32),
     imageview_get_image_size: extern "C" fn(u32, *mut u32, *mut u32) -> u32,
     imageview_clear: extern "C" fn(u32),
+    imageview_load_file: extern "C" fn(u32, *const u8, u32) -> u32,
+    imageview_get_load_error: extern "C" fn(u32) -> u32,
+    // MarkdownView
+    markdown_set_image: extern "C" fn(u32, *const u8, u32, *const u32, u32, u32) -> u32,
+    markdown_image_count: extern "C" fn(u32) -> u32,
+    markdown_image_src: extern "C" fn(u32, u32, *mut u8, u32) -> u32,
+    markdown_get_link: extern "C" fn(u32, *mut u8, u32) -> u32,
     // DataGrid
     datagrid_set_columns: extern "C" fn(u32, *const u8, u32),
     datagrid_get_column_count: extern "C" fn(u32) -> u32,
@@ -236,6 +442,9 @@ struct AnyuiLib {
     datagrid_set_data: extern "C" fn(u32, *const u8, u32),
     datagrid_set_cell: extern "C" fn(u32, u32, u32, *const u8, u32),
     datagrid_get_cell: extern "C" fn(u32, u32, u32, *mut u8, u32) -> u32,
+    datagrid_insert_row: extern "C" fn(u32, u32, *const u8, u32),
+    datagrid_set_row: extern "C" fn(u32, u32, *const u8, u32),
+    datagrid_remove_row: extern "C" fn(u32, u32),
     datagrid_set_cell_colors: extern "C" fn(u32, *const u32, u32),
     datagrid_set_cell_bg_colors: extern "C" fn(u32, *const u32, u32),
     datagrid_set_row_count: extern "C" fn(u32, u32),
@@ -291,6 +500,23 @@ struct AnyuiLib {
     // Timer
     set_timer_fn: extern "C" fn(u32, Callback, u64) -> u32,
     kill_timer_fn: extern "C" fn(u32),
+    // Event sources
+    wakeup_fn: extern "C" fn(),
+    add_event_source_fn: extern "C" fn(u32, Callback, u64) -> u32,
+    remove_event_source_fn: extern "C" fn(u32),
+    event_source_data_fn: extern "C" fn(*mut u32) -> u32,
+    get_frame_stats_fn: extern "C" fn(*mut u32, u32) -> u32,
+    set_stats_overlay_fn: extern "C" fn(u32),
+    set_frame_budget_fn: extern "C" fn(u32, Callback, u64),
+    get_memory_usage_fn: extern "C" fn(*mut u32, u32) -> u32,
+    on_memory_pressure_fn: extern "C" fn(Callback, u64),
+    get_memory_pressure_fn: extern "C" fn() -> u32,
+    trim_memory_fn: extern "C" fn() -> u32,
+    set_low_memory_mode_fn: extern "C" fn(u32),
+    // Screen capture
+    capture_size_fn: extern "C" fn(u32, *mut u32, *mut u32) -> u32,
+    capture_window_fn: extern "C" fn(u32, *mut u32, u32) -> u32,
+    capture_control_fn: extern "C" fn(u32, *mut u32, u32) -> u32,
     // File dialogs
     open_folder_fn: extern "C" fn(*mut u8, u32) -> u32,
     open_file_fn: extern "C" fn(*mut u8, u32) -> u32,
@@ -301,21 +527,61 @@ struct AnyuiLib {
     // Focus management
     set_focus: extern "C" fn(u32),
     set_tab_index: extern "C" fn(u32, u32),
+    push_focus_scope: extern "C" fn(u32) -> u32,
+    ensure_visible: extern "C" fn(u32),
+    pop_focus_scope: extern "C" fn(),
+    set_z_index: extern "C" fn(u32, i32),
+    get_z_index: extern "C" fn(u32) -> i32,
+    set_scroll_chaining: extern "C" fn(u32, u32),
+    get_scroll_chaining: extern "C" fn(u32) -> u32,
+    bring_to_front: extern "C" fn(u32),
+    send_to_back: extern "C" fn(u32),
     // Screen size
     screen_size: extern "C" fn(*mut u32, *mut u32),
+    // Monitors
+    get_monitor_count: extern "C" fn() -> u32,
+    get_monitor_rect: extern "C" fn(u32, *mut i32, *mut i32, *mut u32, *mut u32) -> u32,
+    get_monitor_dpi: extern "C" fn(u32) -> u32,
+    pub(crate) get_window_monitor: extern "C" fn(u32) -> u32,
+    pub(crate) place_window_on_monitor: extern "C" fn(u32, u32, i32, i32) -> u32,
+    pub(crate) center_on_owner_monitor: extern "C" fn(u32, u32) -> u32,
+    on_monitors_changed: extern "C" fn(Callback, u64),
+    // Spell checking
+    set_spell_checker: extern "C" fn(Option<SpellCheckFn>, Option<SpellSuggestFn>, u64),
+    pub(crate) set_spell_check: extern "C" fn(u32, u32),
+    pub(crate) spell_get_word: extern "C" fn(*mut u8, u32) -> u32,
     // Notifications
     show_notification: extern "C" fn(*const u8, u32, *const u8, u32, *const u32, u32),
+    show_toast: extern "C" fn(u32, *const u8, u32, *const u8, u32, u32, Callback, u64) -> u32,
+    dismiss_toast: extern "C" fn(u32),
     // Theme
     pub(crate) set_theme: extern "C" fn(u32),
     pub(crate) get_theme: extern "C" fn() -> u32,
     pub(crate) get_theme_colors_ptr: extern "C" fn() -> *const u8,
     pub(crate) apply_accent_style: extern "C" fn(u32, u32, u32, u32),
+    pub(crate) theme_set_color: extern "C" fn(u32, u32) -> u32,
+    pub(crate) theme_load_conf: extern "C" fn(*const u8, u32) -> u32,
+    pub(crate) theme_reset_colors: extern "C" fn(),
+    pub(crate) on_theme_changed: extern "C" fn(Callback, u64),
     // Font smoothing
     pub(crate) set_font_smoothing: extern "C" fn(u32),
     pub(crate) get_font_smoothing: extern "C" fn() -> u32,
     // DPI scale factor
     pub(crate) set_scale_factor: extern "C" fn(u32),
     pub(crate) get_scale_factor: extern "C" fn() -> u32,
+    // Accessibility
+    pub(crate) set_accessibility: extern "C" fn(u32, u32),
+    pub(crate) get_accessibility: extern "C" fn() -> u32,
+    pub(crate) set_high_contrast: extern "C" fn(u32),
+    pub(crate) is_high_contrast: extern "C" fn() -> u32,
+    pub(crate) set_window_zoom: extern "C" fn(u32, u32),
+    pub(crate) get_window_zoom: extern "C" fn(u32) -> u32,
+    pub(crate) set_window_rtl: extern "C" fn(u32, u32),
+    pub(crate) get_window_rtl: extern "C" fn(u32) -> u32,
+    // Present mode
+    pub(crate) set_present_mode: extern "C" fn(u32, u32),
+    pub(crate) get_present_mode: extern "C" fn(u32) -> u32,
+    pub(crate) present: extern "C" fn(u32),
     // Window title
     set_title: extern "C" fn(u32, *const u8, u32),
     // Key event info
@@ -323,6 +589,9 @@ struct AnyuiLib {
     // Clipboard
     clipboard_set: extern "C" fn(*const u8, u32),
     clipboard_get: extern "C" fn(*mut u8, u32) -> u32,
+    on_clipboard_changed: extern "C" fn(Callback, u64),
+    clipboard_seq: extern "C" fn() -> u32,
+    clipboard_history: extern "C" fn(u32, *mut u8, u32, *mut u32, *mut u32) -> u32,
     // Size/Position query
     get_size: extern "C" fn(u32, *mut u32, *mut u32),
     get_position: extern "C" fn(u32, *mut i32, *mut i32),
@@ -336,6 +605,9 @@ struct AnyuiLib {
     // Window lifecycle callbacks
     on_window_opened_fn: extern "C" fn(Callback, u64),
     on_window_closed_fn: extern "C" fn(Callback, u64),
+    // Dock icon badge
+    set_dock_badge_fn: extern "C" fn(u32),
+    on_dock_badge_fn: extern "C" fn(Callback, u64),
     // Focus by task ID
     focus_by_tid_fn: extern "C" fn(u32),
 }
@@ -398,6 +670,10 @@ pub fn init() -> bool {
             resize_window: resolve(&handle, "anyui_resize_window"),
             move_window: resolve(&handle, "anyui_move_window"),
             minimize_window: resolve(&handle, "anyui_minimize_window"),
+            snap_window: resolve(&handle, "anyui_snap_window"),
+            get_snap_region: resolve(&handle, "anyui_get_snap_region"),
+            set_window_owner: resolve(&handle, "anyui_set_window_owner"),
+            set_always_on_top: resolve(&handle, "anyui_set_always_on_top"),
             // Layout
             set_padding: resolve(&handle, "anyui_set_padding"),
             set_margin: resolve(&handle, "anyui_set_margin"),
@@ -416,6 +692,8 @@ pub fn init() -> bool {
             set_columns: resolve(&handle, "anyui_set_columns"),
             set_row_height: resolve(&handle, "anyui_set_row_height"),
             set_column_widths: resolve(&handle, "anyui_set_column_widths"),
+            formlayout_set_label_width: resolve(&handle, "anyui_formlayout_set_label_width"),
+            formlayout_set_spacing: resolve(&handle, "anyui_formlayout_set_spacing"),
             // SplitView properties
             set_split_ratio: resolve(&handle, "anyui_set_split_ratio"),
             set_min_split: resolve(&handle, "anyui_set_min_split"),
@@ -441,12 +719,42 @@ pub fn init() -> bool {
             canvas_get_pixel: resolve(&handle, "anyui_canvas_get_pixel"),
             canvas_copy_from: resolve(&handle, "anyui_canvas_copy_from"),
             canvas_copy_to: resolve(&handle, "anyui_canvas_copy_to"),
+            canvas_invalidate_rect: resolve(&handle, "anyui_canvas_invalidate_rect"),
+            canvas_set_double_buffered: resolve(&handle, "anyui_canvas_set_double_buffered"),
+            canvas_begin_paint: resolve(&handle, "anyui_canvas_begin_paint"),
+            canvas_end_paint: resolve(&handle, "anyui_canvas_end_paint"),
+            // NativeSurface
+            native_surface_alloc: resolve(&handle, "anyui_native_surface_alloc"),
+            native_surface_pixels: resolve(&handle, "anyui_native_surface_pixels"),
+            native_surface_present: resolve(&handle, "anyui_native_surface_present"),
+            native_surface_release: resolve(&handle, "anyui_native_surface_release"),
             // TextField-specific
             textfield_set_prefix: resolve(&handle, "anyui_textfield_set_prefix"),
             textfield_set_postfix: resolve(&handle, "anyui_textfield_set_postfix"),
             textfield_set_password: resolve(&handle, "anyui_textfield_set_password"),
             textfield_set_placeholder: resolve(&handle, "anyui_textfield_set_placeholder"),
             textfield_select_all: resolve(&handle, "anyui_textfield_select_all"),
+            textfield_set_filter: resolve(&handle, "anyui_textfield_set_filter"),
+            textfield_set_decimal_separator: resolve(&handle, "anyui_textfield_set_decimal_separator"),
+            textfield_set_mask: resolve(&handle, "anyui_textfield_set_mask"),
+            textfield_set_max_length: resolve(&handle, "anyui_textfield_set_max_length"),
+            textfield_set_invalid: resolve(&handle, "anyui_textfield_set_invalid"),
+            textfield_is_invalid: resolve(&handle, "anyui_textfield_is_invalid"),
+            textfield_reject_edit: resolve(&handle, "anyui_textfield_reject_edit"),
+            // TextArea
+            textarea_set_placeholder: resolve(&handle, "anyui_textarea_set_placeholder"),
+            textarea_set_max_length: resolve(&handle, "anyui_textarea_set_max_length"),
+            textarea_set_submit_on_ctrl_enter: resolve(&handle, "anyui_textarea_set_submit_on_ctrl_enter"),
+            // Stepper
+            stepper_set_range: resolve(&handle, "anyui_stepper_set_range"),
+            stepper_set_step: resolve(&handle, "anyui_stepper_set_step"),
+            stepper_set_decimals: resolve(&handle, "anyui_stepper_set_decimals"),
+            stepper_set_affix: resolve(&handle, "anyui_stepper_set_affix"),
+            // SearchField
+            searchfield_set_debounce: resolve(&handle, "anyui_searchfield_set_debounce"),
+            searchfield_set_suggestions: resolve(&handle, "anyui_searchfield_set_suggestions"),
+            searchfield_get_history: resolve(&handle, "anyui_searchfield_get_history"),
+            searchfield_clear_history: resolve(&handle, "anyui_searchfield_clear_history"),
             // Marshal (cross-thread)
             marshal_set_text: resolve(&handle, "anyui_marshal_set_text"),
             marshal_set_color: resolve(&handle, "anyui_marshal_set_color"),
@@ -459,15 +767,93 @@ pub fn init() -> bool {
             set_context_menu: resolve(&handle, "anyui_set_context_menu"),
             // Tooltip
             set_tooltip: resolve(&handle, "anyui_set_tooltip"),
+            set_badge: resolve(&handle, "anyui_set_badge"),
+            set_badge_count: resolve(&handle, "anyui_set_badge_count"),
+            set_badge_corner: resolve(&handle, "anyui_set_badge_corner"),
+            validate_required: resolve(&handle, "anyui_validate_required"),
+            validate_range: resolve(&handle, "anyui_validate_range"),
+            validate_pattern: resolve(&handle, "anyui_validate_pattern"),
+            validate_custom: resolve(&handle, "anyui_validate_custom"),
+            validate_clear: resolve(&handle, "anyui_validate_clear"),
+            validate_control: resolve(&handle, "anyui_validate_control"),
+            form_validate: resolve(&handle, "anyui_form_validate"),
+            get_validation_error: resolve(&handle, "anyui_get_validation_error"),
+            lang_load: resolve(&handle, "anyui_lang_load"),
+            set_language: resolve(&handle, "anyui_set_language"),
+            get_language: resolve(&handle, "anyui_get_language"),
+            on_language_changed: resolve(&handle, "anyui_on_language_changed"),
+            set_text_key: resolve(&handle, "anyui_set_text_key"),
+            tr: resolve(&handle, "anyui_tr"),
+            format_number: resolve(&handle, "anyui_format_number"),
+            format_date: resolve(&handle, "anyui_format_date"),
+            format_time: resolve(&handle, "anyui_format_time"),
+            set_cursor: resolve(&handle, "anyui_set_cursor"),
+            style_create: resolve(&handle, "anyui_style_create"),
+            style_set: resolve(&handle, "anyui_style_set"),
+            style_unset: resolve(&handle, "anyui_style_unset"),
+            style_apply_kind: resolve(&handle, "anyui_style_apply_kind"),
+            set_class: resolve(&handle, "anyui_set_class"),
+            set_custom_draw: resolve(&handle, "anyui_set_custom_draw"),
+            invalidate: resolve(&handle, "anyui_invalidate"),
+            set_virtualized: resolve(&handle, "anyui_set_virtualized"),
+            virtual_set_count: resolve(&handle, "anyui_virtual_set_count"),
+            virtual_refresh: resolve(&handle, "anyui_virtual_refresh"),
+            virtual_get_child: resolve(&handle, "anyui_virtual_get_child"),
+            get_pointer_info: resolve(&handle, "anyui_get_pointer_info"),
+            canvas_get_pointer: resolve(&handle, "anyui_canvas_get_pointer"),
+            gesture_long_press: resolve(&handle, "anyui_gesture_long_press"),
+            gesture_swipe: resolve(&handle, "anyui_gesture_swipe"),
+            gesture_double_tap: resolve(&handle, "anyui_gesture_double_tap"),
+            get_gesture_info: resolve(&handle, "anyui_get_gesture_info"),
+            set_selection_mode: resolve(&handle, "anyui_set_selection_mode"),
+            get_selection: resolve(&handle, "anyui_get_selection"),
+            set_selection: resolve(&handle, "anyui_set_selection"),
+            set_help_topic: resolve(&handle, "anyui_set_help_topic"),
+            get_help_topic: resolve(&handle, "anyui_get_help_topic"),
+            get_help_source: resolve(&handle, "anyui_get_help_source"),
+            set_whats_this: resolve(&handle, "anyui_set_whats_this"),
+            get_whats_this: resolve(&handle, "anyui_get_whats_this"),
+            set_tag: resolve(&handle, "anyui_set_tag"),
+            get_tag: resolve(&handle, "anyui_get_tag"),
+            set_tag_destructor: resolve(&handle, "anyui_set_tag_destructor"),
+            get_tag_destructor: resolve(&handle, "anyui_get_tag_destructor"),
             // MessageBox
             message_box: resolve(&handle, "anyui_message_box"),
             // IconButton
             iconbutton_set_pixels: resolve(&handle, "anyui_iconbutton_set_pixels"),
+            // ProgressBar
+            progressbar_set_mode: resolve(&handle, "anyui_progressbar_set_mode"),
+            progressbar_get_mode: resolve(&handle, "anyui_progressbar_get_mode"),
+            progressbar_set_buffer: resolve(&handle, "anyui_progressbar_set_buffer"),
+            progressbar_set_segments: resolve(&handle, "anyui_progressbar_set_segments"),
+            // CircularProgress / ActivitySpinner
+            ring_set_thickness: resolve(&handle, "anyui_ring_set_thickness"),
+            circularprogress_set_show_text: resolve(&handle, "anyui_circularprogress_set_show_text"),
+            circularprogress_set_track_color: resolve(&handle, "anyui_circularprogress_set_track_color"),
+            spinner_set_running: resolve(&handle, "anyui_spinner_set_running"),
+            spinner_is_running: resolve(&handle, "anyui_spinner_is_running"),
+            // StackPanel reordering
+            stackpanel_set_reorderable: resolve(&handle, "anyui_stackpanel_set_reorderable"),
+            stackpanel_set_handle_size: resolve(&handle, "anyui_stackpanel_set_handle_size"),
+            stackpanel_move_child: resolve(&handle, "anyui_stackpanel_move_child"),
+            stackpanel_get_last_reorder: resolve(&handle, "anyui_stackpanel_get_last_reorder"),
+            scrollview_set_offset: resolve(&handle, "anyui_scrollview_set_offset"),
+            scrollview_get_offset: resolve(&handle, "anyui_scrollview_get_offset"),
+            scrollview_set_scrollbar_style: resolve(&handle, "anyui_scrollview_set_scrollbar_style"),
+            scrollview_set_kinetic: resolve(&handle, "anyui_scrollview_set_kinetic"),
+            scrollview_get_scroll_info: resolve(&handle, "anyui_scrollview_get_scroll_info"),
             // ImageView
             imageview_set_pixels: resolve(&handle, "anyui_imageview_set_pixels"),
             imageview_set_scale_mode: resolve(&handle, "anyui_imageview_set_scale_mode"),
             imageview_get_image_size: resolve(&handle, "anyui_imageview_get_image_size"),
             imageview_clear: resolve(&handle, "anyui_imageview_clear"),
+            imageview_load_file: resolve(&handle, "anyui_imageview_load_file"),
+            imageview_get_load_error: resolve(&handle, "anyui_imageview_get_load_error"),
+            // MarkdownView
+            markdown_set_image: resolve(&handle, "anyui_markdown_set_image"),
+            markdown_image_count: resolve(&handle, "anyui_markdown_image_count"),
+            markdown_image_src: resolve(&handle, "anyui_markdown_image_src"),
+            markdown_get_link: resolve(&handle, "anyui_markdown_get_link"),
             // DataGrid
             datagrid_set_columns: resolve(&handle, "anyui_datagrid_set_columns"),
             datagrid_get_column_count: resolve(&handle, "anyui_datagrid_get_column_count"),
@@ -476,6 +862,9 @@ pub fn init() -> bool {
             datagrid_set_data: resolve(&handle, "anyui_datagrid_set_data"),
             datagrid_set_cell: resolve(&handle, "anyui_datagrid_set_cell"),
             datagrid_get_cell: resolve(&handle, "anyui_datagrid_get_cell"),
+            datagrid_insert_row: resolve(&handle, "anyui_datagrid_insert_row"),
+            datagrid_set_row: resolve(&handle, "anyui_datagrid_set_row"),
+            datagrid_remove_row: resolve(&handle, "anyui_datagrid_remove_row"),
             datagrid_set_cell_colors: resolve(&handle, "anyui_datagrid_set_cell_colors"),
             datagrid_set_cell_bg_colors: resolve(&handle, "anyui_datagrid_set_cell_bg_colors"),
             datagrid_set_row_count: resolve(&handle, "anyui_datagrid_set_row_count"),
@@ -531,6 +920,21 @@ pub fn init() -> bool {
             // Timer
             set_timer_fn: resolve(&handle, "anyui_set_timer"),
             kill_timer_fn: resolve(&handle, "anyui_kill_timer"),
+            wakeup_fn: resolve(&handle, "anyui_wakeup"),
+            add_event_source_fn: resolve(&handle, "anyui_add_event_source"),
+            remove_event_source_fn: resolve(&handle, "anyui_remove_event_source"),
+            event_source_data_fn: resolve(&handle, "anyui_event_source_data"),
+            get_frame_stats_fn: resolve(&handle, "anyui_get_frame_stats"),
+            set_stats_overlay_fn: resolve(&handle, "anyui_set_stats_overlay"),
+            set_frame_budget_fn: resolve(&handle, "anyui_set_frame_budget"),
+            get_memory_usage_fn: resolve(&handle, "anyui_get_memory_usage"),
+            on_memory_pressure_fn: resolve(&handle, "anyui_on_memory_pressure"),
+            get_memory_pressure_fn: resolve(&handle, "anyui_get_memory_pressure"),
+            trim_memory_fn: resolve(&handle, "anyui_trim_memory"),
+            set_low_memory_mode_fn: resolve(&handle, "anyui_set_low_memory_mode"),
+            capture_size_fn: resolve(&handle, "anyui_capture_size"),
+            capture_window_fn: resolve(&handle, "anyui_capture_window"),
+            capture_control_fn: resolve(&handle, "anyui_capture_control"),
             // File dialogs
             open_folder_fn: resolve(&handle, "anyui_open_folder"),
             open_file_fn: resolve(&handle, "anyui_open_file"),
@@ -541,21 +945,60 @@ pub fn init() -> bool {
             // Focus management
             set_focus: resolve(&handle, "anyui_set_focus"),
             set_tab_index: resolve(&handle, "anyui_set_tab_index"),
+            push_focus_scope: resolve(&handle, "anyui_push_focus_scope"),
+            ensure_visible: resolve(&handle, "anyui_ensure_visible"),
+            pop_focus_scope: resolve(&handle, "anyui_pop_focus_scope"),
+            set_z_index: resolve(&handle, "anyui_set_z_index"),
+            get_z_index: resolve(&handle, "anyui_get_z_index"),
+            set_scroll_chaining: resolve(&handle, "anyui_set_scroll_chaining"),
+            get_scroll_chaining: resolve(&handle, "anyui_get_scroll_chaining"),
+            bring_to_front: resolve(&handle, "anyui_bring_to_front"),
+            send_to_back: resolve(&handle, "anyui_send_to_back"),
             // Screen size
             screen_size: resolve(&handle, "anyui_screen_size"),
+            // Monitors
+            get_monitor_count: resolve(&handle, "anyui_get_monitor_count"),
+            get_monitor_rect: resolve(&handle, "anyui_get_monitor_rect"),
+            get_monitor_dpi: resolve(&handle, "anyui_get_monitor_dpi"),
+            get_window_monitor: resolve(&handle, "anyui_get_window_monitor"),
+            place_window_on_monitor: resolve(&handle, "anyui_place_window_on_monitor"),
+            center_on_owner_monitor: resolve(&handle, "anyui_center_on_owner_monitor"),
+            on_monitors_changed: resolve(&handle, "anyui_on_monitors_changed"),
+            // Spell checking
+            set_spell_checker: resolve(&handle, "anyui_set_spell_checker"),
+            set_spell_check: resolve(&handle, "anyui_set_spell_check"),
+            spell_get_word: resolve(&handle, "anyui_spell_get_word"),
             // Notifications
             show_notification: resolve(&handle, "anyui_show_notification"),
+            show_toast: resolve(&handle, "anyui_show_toast"),
+            dismiss_toast: resolve(&handle, "anyui_dismiss_toast"),
             // Theme
             set_theme: resolve(&handle, "anyui_set_theme"),
             get_theme: resolve(&handle, "anyui_get_theme"),
             get_theme_colors_ptr: resolve(&handle, "anyui_get_theme_colors_ptr"),
             apply_accent_style: resolve(&handle, "anyui_apply_accent_style"),
+            theme_set_color: resolve(&handle, "anyui_theme_set_color"),
+            theme_load_conf: resolve(&handle, "anyui_theme_load_conf"),
+            theme_reset_colors: resolve(&handle, "anyui_theme_reset_colors"),
+            on_theme_changed: resolve(&handle, "anyui_on_theme_changed"),
             // Font smoothing
             set_font_smoothing: resolve(&handle, "anyui_set_font_smoothing"),
             get_font_smoothing: resolve(&handle, "anyui_get_font_smoothing"),
             // DPI scale factor
             set_scale_factor: resolve(&handle, "anyui_set_scale_factor"),
             get_scale_factor: resolve(&handle, "anyui_get_scale_factor"),
+            // Accessibility
+            set_accessibility: resolve(&handle, "anyui_set_accessibility"),
+            get_accessibility: resolve(&handle, "anyui_get_accessibility"),
+            set_high_contrast: resolve(&handle, "anyui_set_high_contrast"),
+            is_high_contrast: resolve(&handle, "anyui_is_high_contrast"),
+            set_window_zoom: resolve(&handle, "anyui_set_window_zoom"),
+            get_window_zoom: resolve(&handle, "anyui_get_window_zoom"),
+            set_window_rtl: resolve(&handle, "anyui_set_window_rtl"),
+            get_window_rtl: resolve(&handle, "anyui_get_window_rtl"),
+            set_present_mode: resolve(&handle, "anyui_set_present_mode"),
+            get_present_mode: resolve(&handle, "anyui_get_present_mode"),
+            present: resolve(&handle, "anyui_present"),
             // Window title
             set_title: resolve(&handle, "anyui_set_title"),
             // Key event info
@@ -563,6 +1006,9 @@ pub fn init() -> bool {
             // Clipboard
             clipboard_set: resolve(&handle, "anyui_clipboard_set"),
             clipboard_get: resolve(&handle, "anyui_clipboard_get"),
+            on_clipboard_changed: resolve(&handle, "anyui_on_clipboard_changed"),
+            clipboard_seq: resolve(&handle, "anyui_clipboard_seq"),
+            clipboard_history: resolve(&handle, "anyui_clipboard_history"),
             // Size/Position query
             get_size: resolve(&handle, "anyui_get_size"),
             get_position: resolve(&handle, "anyui_get_position"),
@@ -573,6 +1019,8 @@ pub fn init() -> bool {
             get_compositor_channel_fn: resolve(&handle, "anyui_get_compositor_channel"),
             on_window_opened_fn: resolve(&handle, "anyui_on_window_opened"),
             on_window_closed_fn: resolve(&handle, "anyui_on_window_closed"),
+            set_dock_badge_fn: resolve(&handle, "anyui_set_dock_badge"),
+            on_dock_badge_fn: resolve(&handle, "anyui_on_dock_badge"),
             focus_by_tid_fn: resolve(&handle, "anyui_focus_by_tid"),
             _handle: handle,
         };
@@ -630,6 +1078,18 @@ pub fn on_window_closed(mut f: impl FnMut(u32) + 'static) {
     (lib().on_window_closed_fn)(thunk, ud);
 }
 
+/// Show `count` as a badge on this app's dock icon (0 = remove).
+pub fn set_dock_badge(count: u32) {
+    (lib().set_dock_badge_fn)(count);
+}
+
+/// Register a callback for dock badge changes of any app (used by the dock).
+/// Callback receives (app_tid, count).
+pub fn on_dock_badge(mut f: impl FnMut(u32, u32) + 'static) {
+    let (thunk, ud) = events::register(move |id, count| f(id, count));
+    (lib().on_dock_badge_fn)(thunk, ud);
+}
+
 /// Focus the window belonging to a specific task ID via compositor IPC.
 pub fn focus_by_tid(tid: u32) {
     (lib().focus_by_tid_fn)(tid);
@@ -843,6 +1303,78 @@ impl Control {
         (lib().on_submit_fn)(self.id, cb, userdata);
     }
 
+    /// Register a closure for a touch contact going down on this control.
+    pub fn on_touch_down(&self, mut f: impl FnMut(u32, &PointerEvent) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| f(id, &get_pointer_info()));
+        self.on_event_raw(EVENT_TOUCH_DOWN, thunk, ud);
+    }
+
+    /// Register a closure for moves of a contact that went down on this control.
+    pub fn on_touch_move(&self, mut f: impl FnMut(u32, &PointerEvent) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| f(id, &get_pointer_info()));
+        self.on_event_raw(EVENT_TOUCH_MOVE, thunk, ud);
+    }
+
+    /// Register a closure for a contact that went down on this control being lifted.
+    pub fn on_touch_up(&self, mut f: impl FnMut(u32, &PointerEvent) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| f(id, &get_pointer_info()));
+        self.on_event_raw(EVENT_TOUCH_UP, thunk, ud);
+    }
+
+    /// Register a closure for two-finger pinches over this control.
+    /// `PointerEvent::scale` is the zoom factor since the gesture started (1000 = 1.0).
+    pub fn on_pinch(&self, mut f: impl FnMut(u32, &PointerEvent) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| f(id, &get_pointer_info()));
+        self.on_event_raw(EVENT_PINCH, thunk, ud);
+    }
+
+    // ── Gestures ──
+
+    /// Recognize press-and-hold on this control (and descendants without
+    /// their own long-press) after `delay_ms`; 0 turns it off. A long press
+    /// does not click.
+    pub fn set_long_press(&self, delay_ms: u32) {
+        (lib().gesture_long_press)(self.id, delay_ms);
+    }
+
+    /// Recognize swipes along `axes` (`SWIPE_HORIZONTAL`, `SWIPE_VERTICAL`)
+    /// released at `min_velocity` logical pixels per second or faster;
+    /// `axes` 0 turns it off.
+    pub fn set_swipe(&self, axes: u32, min_velocity: u32) {
+        (lib().gesture_swipe)(self.id, axes, min_velocity);
+    }
+
+    /// Recognize two taps at most `interval_ms` apart; 0 turns it off.
+    pub fn set_double_tap(&self, interval_ms: u32) {
+        (lib().gesture_double_tap)(self.id, interval_ms);
+    }
+
+    /// Register a closure for long presses (see `set_long_press`).
+    pub fn on_long_press(&self, mut f: impl FnMut(u32, &GestureInfo) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| f(id, &get_gesture_info()));
+        self.on_event_raw(EVENT_LONG_PRESS, thunk, ud);
+    }
+
+    /// Register a closure for swipes (see `set_swipe`); `GestureInfo::direction`
+    /// is one of the `SWIPE_LEFT` .. `SWIPE_DOWN` constants.
+    pub fn on_swipe(&self, mut f: impl FnMut(u32, &GestureInfo) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| f(id, &get_gesture_info()));
+        self.on_event_raw(EVENT_SWIPE, thunk, ud);
+    }
+
+    /// Register a closure for double taps (see `set_double_tap`).
+    pub fn on_double_tap(&self, mut f: impl FnMut(u32, &GestureInfo) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| f(id, &get_gesture_info()));
+        self.on_event_raw(EVENT_DOUBLE_TAP, thunk, ud);
+    }
+
+    /// Register a closure for failures of asynchronous work on this control
+    /// (e.g. `ImageView::load_file`).
+    pub fn on_error(&self, mut f: impl FnMut(u32) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| f(id));
+        self.on_event_raw(EVENT_ERROR, thunk, ud);
+    }
+
     // ── Context menu ──
 
     /// Attach a context menu to this control. Shown on right-click.
@@ -859,6 +1391,204 @@ impl Control {
         (lib().set_tooltip)(self.id, bytes.as_ptr(), bytes.len() as u32);
     }
 
+    // ── Context help ──
+
+    /// Set the help topic reported for this control and, unless they set
+    /// their own, its descendants. Pass empty string to inherit again.
+    pub fn set_help_topic(&self, topic: &str) {
+        let bytes = topic.as_bytes();
+        (lib().set_help_topic)(self.id, bytes.as_ptr(), bytes.len() as u32);
+    }
+
+    /// The effective help topic: this control's own, else the nearest
+    /// ancestor's (empty if none has one).
+    pub fn help_topic(&self) -> alloc::string::String {
+        let mut buf = [0u8; 256];
+        let len = (lib().get_help_topic)(self.id, buf.as_mut_ptr(), buf.len() as u32) as usize;
+        alloc::string::String::from(core::str::from_utf8(&buf[..len]).unwrap_or(""))
+    }
+
+    /// Called when help is requested for this control or a descendant
+    /// without a closer handler (F1 or "What's this?"), with the source
+    /// control and its effective topic.
+    pub fn on_help(&self, mut f: impl FnMut(u32, &str) + 'static) {
+        let (thunk, ud) = events::register(move |_, _| {
+            let source = (lib().get_help_source)();
+            f(source, &Control { id: source }.help_topic());
+        });
+        self.on_event_raw(EVENT_HELP, thunk, ud);
+    }
+
+    // ── User data ──
+
+    /// Store an app-defined value with this control. The previous tag is
+    /// replaced without calling the destructor.
+    pub fn set_tag(&self, tag: u64) {
+        (lib().set_tag)(self.id, tag);
+    }
+
+    /// The control's tag (0 if none was set).
+    pub fn tag(&self) -> u64 {
+        (lib().get_tag)(self.id)
+    }
+
+    /// Set the function called with the control's id and tag when it is
+    /// removed. Pass None to clear it.
+    pub fn set_tag_destructor(&self, f: Option<TagDestructor>) {
+        (lib().set_tag_destructor)(self.id, f);
+    }
+
+    /// Attach `value` to this control, replacing (and dropping) any value set
+    /// before. It is dropped when the control is removed. Uses the tag and
+    /// its destructor.
+    pub fn set_user_data<T: 'static>(&self, value: T) {
+        self.drop_user_data();
+        let data: UserData = alloc::boxed::Box::new(alloc::boxed::Box::new(value));
+        (lib().set_tag)(self.id, alloc::boxed::Box::into_raw(data) as u64);
+        (lib().set_tag_destructor)(self.id, Some(drop_user_data));
+    }
+
+    /// Run `f` on the value set with `set_user_data`, if it is a `T`.
+    /// `f` must not remove the control.
+    pub fn with_user_data<T: 'static, R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        if !self.has_user_data() {
+            return None;
+        }
+        let data = unsafe { &mut *((lib().get_tag)(self.id) as *mut alloc::boxed::Box<dyn core::any::Any>) };
+        data.downcast_mut::<T>().map(f)
+    }
+
+    /// Drop the value set with `set_user_data`, if any.
+    pub fn drop_user_data(&self) {
+        if self.has_user_data() {
+            (lib().set_tag_destructor)(self.id, None);
+            drop_user_data(self.id, (lib().get_tag)(self.id));
+            (lib().set_tag)(self.id, 0);
+        }
+    }
+
+    fn has_user_data(&self) -> bool {
+        let ours: TagDestructor = drop_user_data;
+        (lib().get_tag_destructor)(self.id).map(|f| f as usize) == Some(ours as usize)
+    }
+
+    // ── Badge ──
+
+    /// Attach a badge with `text` to a corner of this control.
+    /// `color` 0 = theme badge red. Pass empty string to remove.
+    pub fn set_badge(&self, text: &str, color: u32) {
+        let bytes = text.as_bytes();
+        (lib().set_badge)(self.id, bytes.as_ptr(), bytes.len() as u32, color);
+    }
+
+    /// Attach a numeric badge (0 = remove, above 99 shows "99+").
+    pub fn set_badge_count(&self, count: u32, color: u32) {
+        (lib().set_badge_count)(self.id, count, color);
+    }
+
+    /// Move the badge to another corner (`BADGE_TOP_RIGHT` etc.).
+    pub fn set_badge_corner(&self, corner: u32) {
+        (lib().set_badge_corner)(self.id, corner);
+    }
+
+    // ── Validation ──
+
+    /// Require a value (non-blank text, or a non-zero state); see [`validation`].
+    pub fn validate_required(&self, message: &str) {
+        (lib().validate_required)(self.id, message.as_ptr(), message.len() as u32);
+    }
+
+    /// Require a number within `min..=max` (an empty value passes).
+    pub fn validate_range(&self, min: f64, max: f64, message: &str) {
+        (lib().validate_range)(self.id, min, max, message.as_ptr(), message.len() as u32);
+    }
+
+    /// Require the whole value to match a regular expression (an empty
+    /// value passes). Returns false if the pattern is malformed.
+    pub fn validate_pattern(&self, pattern: &str, message: &str) -> bool {
+        (lib().validate_pattern)(
+            self.id, pattern.as_ptr(), pattern.len() as u32, message.as_ptr(), message.len() as u32,
+        ) != 0
+    }
+
+    /// Validate with a closure returning true for valid values.
+    pub fn validate_custom(&self, f: impl FnMut(&str) -> bool + 'static, message: &str) {
+        let (thunk, ud) = validation::register(f);
+        (lib().validate_custom)(self.id, thunk, ud, message.as_ptr(), message.len() as u32);
+    }
+
+    pub fn validate_custom_raw(&self, cb: ValidateFn, userdata: u64, message: &str) {
+        (lib().validate_custom)(self.id, cb, userdata, message.as_ptr(), message.len() as u32);
+    }
+
+    /// Remove all validators and the error.
+    pub fn clear_validators(&self) {
+        (lib().validate_clear)(self.id);
+    }
+
+    /// Validate now. Returns true if the value is valid.
+    pub fn validate(&self) -> bool {
+        (lib().validate_control)(self.id) != 0
+    }
+
+    /// The current error message (`None` = valid or not validated yet).
+    pub fn validation_error(&self) -> Option<alloc::string::String> {
+        let mut buf = [0u8; 256];
+        let len = (lib().get_validation_error)(self.id, buf.as_mut_ptr(), buf.len() as u32) as usize;
+        if len == 0 {
+            return None;
+        }
+        Some(alloc::string::String::from(core::str::from_utf8(&buf[..len]).unwrap_or("")))
+    }
+
+    // ── Localization ──
+
+    /// Show the translation of `key`, with `{0}`, `{1}`, ... replaced by
+    /// `args`. The text follows language switches; see [`i18n`].
+    pub fn set_text_key(&self, key: &str, args: &[&str]) {
+        let packed = i18n::pack_args(args);
+        (lib().set_text_key)(self.id, key.as_ptr(), key.len() as u32, packed.as_ptr(), packed.len() as u32);
+    }
+
+    // ── Cursor ──
+
+    /// Set the mouse cursor shown over this control and its children
+    /// (`CURSOR_HAND` etc.; `CURSOR_DEFAULT` inherits from the parent).
+    pub fn set_cursor(&self, cursor: u32) {
+        (lib().set_cursor)(self.id, cursor);
+    }
+
+    // ── Styles ──
+
+    /// Set the class list: [`Style`] names separated by spaces, applied
+    /// after the kind style and before this control's own setters.
+    pub fn set_class(&self, class: &str) {
+        (lib().set_class)(self.id, class.as_ptr(), class.len() as u32);
+    }
+
+    // ── Owner draw ──
+
+    /// Paint over this control after it renders itself; see [`custom_draw`].
+    pub fn set_custom_draw(&self, f: impl FnMut(&CustomDrawInfo) + 'static) {
+        let (thunk, ud) = custom_draw::register(f);
+        (lib().set_custom_draw)(self.id, Some(thunk), ud);
+    }
+
+    pub fn set_custom_draw_raw(&self, cb: CustomDrawFn, userdata: u64) {
+        (lib().set_custom_draw)(self.id, Some(cb), userdata);
+    }
+
+    /// Remove the owner-draw callback.
+    pub fn clear_custom_draw(&self) {
+        (lib().set_custom_draw)(self.id, None, 0);
+    }
+
+    /// Repaint this control on the next frame (e.g. after the data an
+    /// owner-draw callback paints from has changed).
+    pub fn invalidate(&self) {
+        (lib().invalidate)(self.id);
+    }
+
     // ── Focus ──
 
     /// Programmatically set keyboard focus to this control.
@@ -873,6 +1603,57 @@ impl Control {
         (lib().set_tab_index)(self.id, index);
     }
 
+    /// Trap keyboard focus and mouse input of this control's window inside
+    /// this control (e.g. a custom modal overlay) until [`pop_focus_scope`].
+    /// Focus moves to the first focusable control inside.
+    pub fn push_focus_scope(&self) -> bool {
+        (lib().push_focus_scope)(self.id) != 0
+    }
+
+    /// Scroll the enclosing ScrollViews so this control is shown. Takes
+    /// effect after the next layout; Tab navigation does this for the newly
+    /// focused control.
+    pub fn ensure_visible(&self) {
+        (lib().ensure_visible)(self.id);
+    }
+
+    // ── Z-order ──
+
+    /// Set the stacking order among siblings. Higher values draw on top and
+    /// receive clicks first; equal values keep insertion order. Layout and
+    /// tab order are unaffected. Default is 0.
+    pub fn set_z_index(&self, z: i32) {
+        (lib().set_z_index)(self.id, z);
+    }
+
+    pub fn z_index(&self) -> i32 {
+        (lib().get_z_index)(self.id)
+    }
+
+    /// Raise above all siblings (e.g. a floating panel that was clicked).
+    pub fn bring_to_front(&self) {
+        (lib().bring_to_front)(self.id);
+    }
+
+    /// Lower below all siblings.
+    pub fn send_to_back(&self) {
+        (lib().send_to_back)(self.id);
+    }
+
+    // ── Scrolling ──
+
+    /// Set whether wheel scrolling this control (or a descendant) cannot
+    /// use, e.g. at the end of its content, moves the nearest scrollable
+    /// ancestor instead. Turn off for a list inside a scrolling page that
+    /// should never move the page. Default is on.
+    pub fn set_scroll_chaining(&self, enabled: bool) {
+        (lib().set_scroll_chaining)(self.id, enabled as u32);
+    }
+
+    pub fn scroll_chaining(&self) -> bool {
+        (lib().get_scroll_chaining)(self.id) != 0
+    }
+
     // ── Removal ──
 
     pub fn remove(&self) {
@@ -917,6 +1698,86 @@ impl Container {
     pub fn clear(&self) {
         (lib().clear_children_fn)(self.ctrl.id);
     }
+
+    // ── Virtualization ──
+
+    /// Show `count` items of `item_height` (column width for horizontal
+    /// stacks), creating controls only for the visible ones; see
+    /// [`virtualize`]. `f(index, recycled)` binds a hidden control to item
+    /// `index`, or creates a new one (added to this container) when
+    /// `recycled` is `None`.
+    pub fn set_virtualized(
+        &self,
+        item_height: u32,
+        count: u32,
+        f: impl FnMut(u32, Option<Control>) -> Option<Control> + 'static,
+    ) {
+        let (thunk, ud) = virtualize::register(f);
+        (lib().set_virtualized)(self.ctrl.id, item_height, count, Some(thunk), ud);
+    }
+
+    pub fn set_virtualized_raw(&self, item_height: u32, count: u32, cb: ItemProviderFn, userdata: u64) {
+        (lib().set_virtualized)(self.ctrl.id, item_height, count, Some(cb), userdata);
+    }
+
+    /// Leave virtualization mode; the realized children stay.
+    pub fn clear_virtualized(&self) {
+        (lib().set_virtualized)(self.ctrl.id, 0, 0, None, 0);
+    }
+
+    /// Change the number of items of a virtualized container.
+    pub fn set_virtual_count(&self, count: u32) {
+        (lib().virtual_set_count)(self.ctrl.id, count);
+    }
+
+    /// Rebind all visible items (after the data behind them changed).
+    pub fn refresh_virtual(&self) {
+        (lib().virtual_refresh)(self.ctrl.id);
+    }
+
+    /// Control currently showing item `index`, if it is realized.
+    pub fn virtual_child(&self, index: u32) -> Option<Control> {
+        match (lib().virtual_get_child)(self.ctrl.id, index) {
+            0 => None,
+            id => Some(Control::from_id(id)),
+        }
+    }
+
+    // ── Selection (View, FlowPanel) ──
+
+    /// Let the user select children by click, Ctrl/Shift-click and
+    /// rubber-band drag (`SELECT_NONE`, `SELECT_SINGLE`, `SELECT_MULTIPLE`).
+    /// Returns false for containers that cannot select their children.
+    pub fn set_selection_mode(&self, mode: u32) -> bool {
+        (lib().set_selection_mode)(self.ctrl.id, mode) != 0
+    }
+
+    /// Ids of the selected children, in child order.
+    pub fn selection(&self) -> alloc::vec::Vec<u32> {
+        let n = (lib().get_selection)(self.ctrl.id, core::ptr::null_mut(), 0);
+        let mut ids = alloc::vec![0u32; n as usize];
+        let n = (lib().get_selection)(self.ctrl.id, ids.as_mut_ptr(), n);
+        ids.truncate(n as usize);
+        ids
+    }
+
+    /// Replace the selection (does not fire `on_selection_changed`).
+    pub fn set_selection(&self, ids: &[u32]) -> bool {
+        (lib().set_selection)(self.ctrl.id, ids.as_ptr(), ids.len() as u32) != 0
+    }
+
+    /// Clear the selection (does not fire `on_selection_changed`).
+    pub fn clear_selection(&self) {
+        (lib().set_selection)(self.ctrl.id, core::ptr::null(), 0);
+    }
+
+    /// Called when the user changed the selection, with the selected ids.
+    pub fn on_selection_changed(&self, mut f: impl FnMut(u32, &[u32]) + 'static) {
+        let (thunk, ud) = events::register(move |id, _| {
+            f(id, &Container { ctrl: Control { id } }.selection());
+        });
+        (lib().on_event_fn)(self.ctrl.id, EVENT_SELECTION_CHANGED, thunk, ud);
+    }
 }
 
 // ══════════════════════════════════════════════════════════════════════
@@ -1019,6 +1880,199 @@ pub fn kill_timer(timer_id: u32) {
     (lib().kill_timer_fn)(timer_id);
 }
 
+// ── Event sources ────────────────────────────────────────────────────
+
+/// Have the event loop wait on event channel `channel_id` too, calling `f`
+/// with each event (five words) on the UI thread as it arrives.
+/// Returns a source ID for `remove_event_source()`, or 0 if the limit
+/// (15 sources) is reached.
+pub fn add_event_source(channel_id: u32, mut f: impl FnMut(&[u32; 5]) + 'static) -> u32 {
+    let (thunk, ud) = events::register(move |_id, _event_type| {
+        let mut ev = [0u32; 5];
+        (lib().event_source_data_fn)(ev.as_mut_ptr());
+        f(&ev);
+    });
+    (lib().add_event_source_fn)(channel_id, thunk, ud)
+}
+
+/// Stop servicing an event source. No-op if the ID is invalid.
+pub fn remove_event_source(source_id: u32) {
+    (lib().remove_event_source_fn)(source_id);
+}
+
+/// Wake the event loop from its wait so it runs a frame now.
+/// Safe to call from any thread (e.g. after a worker queued results).
+pub fn wakeup() {
+    (lib().wakeup_fn)();
+}
+
+// ── Frame statistics ─────────────────────────────────────────────────
+
+/// Timing and render counters for the last rendered frame (times in ms).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameStats {
+    /// Frame counter (1 = first rendered frame).
+    pub frame: u32,
+    pub layout_ms: u32,
+    pub render_ms: u32,
+    /// Present → compositor frame ACK latency of the latest acknowledged frame.
+    pub present_wait_ms: u32,
+    /// Physical pixels repainted across all windows.
+    pub dirty_area: u32,
+    pub controls_drawn: u32,
+    /// Layout + render time.
+    pub frame_ms: u32,
+    /// Frames over budget since `on_frame_over_budget()` was called.
+    pub over_budget: u32,
+}
+
+/// Statistics for the most recently rendered frame.
+pub fn frame_stats() -> FrameStats {
+    let mut w = [0u32; 8];
+    (lib().get_frame_stats_fn)(w.as_mut_ptr(), w.len() as u32);
+    FrameStats {
+        frame: w[0],
+        layout_ms: w[1],
+        render_ms: w[2],
+        present_wait_ms: w[3],
+        dirty_area: w[4],
+        controls_drawn: w[5],
+        frame_ms: w[6],
+        over_budget: w[7],
+    }
+}
+
+/// Show or hide the frame statistics overlay in the top-left of every window.
+pub fn set_stats_overlay(enabled: bool) {
+    (lib().set_stats_overlay_fn)(enabled as u32);
+}
+
+/// Call `f(frame_ms, frame_number)` after every frame whose layout + render
+/// time exceeds `budget_ms`. Replaces any previous budget.
+pub fn on_frame_over_budget(budget_ms: u32, mut f: impl FnMut(u32, u32) + 'static) {
+    let (thunk, ud) = events::register(move |frame_ms, frame| {
+        f(frame_ms, frame);
+    });
+    (lib().set_frame_budget_fn)(budget_ms, thunk, ud);
+}
+
+/// Remove the frame budget set by `on_frame_over_budget()`.
+pub fn clear_frame_budget() {
+    extern "C" fn noop(_: u32, _: u32, _: u64) {}
+    (lib().set_frame_budget_fn)(0, noop, 0);
+}
+
+// ── Memory budget ────────────────────────────────────────────────────
+
+/// No memory pressure.
+pub const PRESSURE_NONE: u32 = 0;
+/// Free physical memory is low (below 15%).
+pub const PRESSURE_LOW: u32 = 1;
+/// Free physical memory is critical (below 5%).
+pub const PRESSURE_CRITICAL: u32 = 2;
+
+/// UI memory held by this process, in bytes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MemoryUsage {
+    /// Window back buffers (released for hidden windows by `trim_memory()`).
+    pub back_buffers: u32,
+    /// Shared-memory surfaces handed to the compositor.
+    pub surfaces: u32,
+    /// Canvas pixel buffers (including double-buffer front copies).
+    pub canvases: u32,
+    /// Image views, markdown images and pre-rendered icons.
+    pub images: u32,
+    pub total: u32,
+    /// Last memory pressure level (`PRESSURE_*`).
+    pub pressure: u32,
+}
+
+/// Current UI memory usage.
+pub fn memory_usage() -> MemoryUsage {
+    let mut w = [0u32; 6];
+    (lib().get_memory_usage_fn)(w.as_mut_ptr(), w.len() as u32);
+    MemoryUsage {
+        back_buffers: w[0],
+        surfaces: w[1],
+        canvases: w[2],
+        images: w[3],
+        total: w[4],
+        pressure: w[5],
+    }
+}
+
+/// Call `f(level)` when the system memory pressure level changes
+/// (`PRESSURE_*`). Replaces any previous callback.
+pub fn on_memory_pressure(mut f: impl FnMut(u32) + 'static) {
+    let (thunk, ud) = events::register(move |level, _| {
+        f(level);
+    });
+    (lib().on_memory_pressure_fn)(thunk, ud);
+}
+
+/// Last memory pressure level broadcast by the system (`PRESSURE_*`).
+pub fn memory_pressure() -> u32 {
+    (lib().get_memory_pressure_fn)()
+}
+
+/// Drop rebuildable caches and the back buffers of hidden windows.
+/// Returns the bytes freed.
+pub fn trim_memory() -> u32 {
+    (lib().trim_memory_fn)()
+}
+
+/// In low-memory mode hidden windows keep no back buffer and memory
+/// pressure trims automatically before `on_memory_pressure` fires.
+pub fn set_low_memory_mode(enabled: bool) {
+    (lib().set_low_memory_mode_fn)(enabled as u32);
+}
+
+// ── Context help API ────────────────────────────────────────────────
+
+/// Turn "What's this?" mode on or off. While on, the next click fires
+/// `on_help` for the control under the pointer instead of clicking it;
+/// Escape cancels.
+pub fn set_whats_this(on: bool) {
+    (lib().set_whats_this)(on as u32);
+}
+
+/// Whether "What's this?" mode is on.
+pub fn whats_this() -> bool {
+    (lib().get_whats_this)() != 0
+}
+
+// ── Screen capture API ──────────────────────────────────────────────
+
+/// Captured pixels: physical-resolution ARGB, row-major.
+pub struct Capture {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: alloc::vec::Vec<u32>,
+}
+
+fn capture_with(id: u32, f: extern "C" fn(u32, *mut u32, u32) -> u32) -> Option<Capture> {
+    let (mut w, mut h) = (0u32, 0u32);
+    if (lib().capture_size_fn)(id, &mut w, &mut h) == 0 || w == 0 || h == 0 {
+        return None;
+    }
+    let mut pixels = alloc::vec![0u32; (w * h) as usize];
+    if f(id, pixels.as_mut_ptr(), pixels.len() as u32) != w * h {
+        return None;
+    }
+    Some(Capture { width: w, height: h, pixels })
+}
+
+/// Copy of the last frame rendered for `window` (its whole control tree).
+pub fn capture_window(window: &impl Widget) -> Option<Capture> {
+    capture_with(window.id(), lib().capture_window_fn)
+}
+
+/// Render `control` and its children offscreen at the control's own size.
+/// Areas the control doesn't paint are transparent.
+pub fn capture_control(control: &impl Widget) -> Option<Capture> {
+    capture_with(control.id(), lib().capture_control_fn)
+}
+
 // ── Blur-behind API ─────────────────────────────────────────────────
 
 /// Enable or disable blur-behind on a window (frosted glass effect).
@@ -1027,6 +2081,14 @@ pub fn set_blur_behind(window: &impl Widget, radius: u32) {
     (lib().set_blur_behind)(window.id(), radius);
 }
 
+// ── Focus scope API ─────────────────────────────────────────────────
+
+/// Remove the innermost focus scope (see `Control::push_focus_scope`) and
+/// restore the focus it replaced.
+pub fn pop_focus_scope() {
+    (lib().pop_focus_scope)();
+}
+
 // ── Screen size API ─────────────────────────────────────────────────
 
 /// Get screen dimensions.
@@ -1037,6 +2099,114 @@ pub fn screen_size() -> (u32, u32) {
     (w, h)
 }
 
+// ── Monitor API ─────────────────────────────────────────────────────
+
+/// Number of connected monitors (at least 1). Monitor 0 is the primary.
+pub fn monitor_count() -> u32 {
+    (lib().get_monitor_count)()
+}
+
+/// Bounds `(x, y, w, h)` of a monitor in desktop coordinates, or None if
+/// `index` is out of range.
+pub fn monitor_rect(index: u32) -> Option<(i32, i32, u32, u32)> {
+    let (mut x, mut y, mut w, mut h) = (0i32, 0i32, 0u32, 0u32);
+    if (lib().get_monitor_rect)(index, &mut x, &mut y, &mut w, &mut h) != 0 {
+        Some((x, y, w, h))
+    } else {
+        None
+    }
+}
+
+/// Effective DPI of a monitor (96 at 100% scale), 0 if out of range.
+pub fn monitor_dpi(index: u32) -> u32 {
+    (lib().get_monitor_dpi)(index)
+}
+
+/// Register a callback for monitor configuration changes (resolution
+/// change, monitor added/removed). Receives the new monitor count.
+pub fn on_monitors_changed(mut f: impl FnMut(u32) + 'static) {
+    let (thunk, ud) = events::register(move |count, _| f(count));
+    (lib().on_monitors_changed)(thunk, ud);
+}
+
+// ── User data ───────────────────────────────────────────────────────
+
+/// Value behind the tag of a control with `set_user_data`.
+type UserData = alloc::boxed::Box<alloc::boxed::Box<dyn core::any::Any>>;
+
+/// Tag destructor of `set_user_data` values.
+extern "C" fn drop_user_data(_id: u32, tag: u64) {
+    if tag != 0 {
+        drop(unsafe { alloc::boxed::Box::from_raw(tag as *mut alloc::boxed::Box<dyn core::any::Any>) });
+    }
+}
+
+// ── Spell checking API ──────────────────────────────────────────────
+
+type SpellCheckHandler = alloc::boxed::Box<dyn FnMut(&str) -> bool>;
+type SpellSuggestHandler = alloc::boxed::Box<dyn FnMut(&str) -> alloc::vec::Vec<alloc::string::String>>;
+
+static mut SPELL_HANDLERS: Option<(SpellCheckHandler, SpellSuggestHandler)> = None;
+
+extern "C" fn spell_check_thunk(word: *const u8, len: u32, _ud: u64) -> u32 {
+    let word = unsafe { core::slice::from_raw_parts(word, len as usize) };
+    let word = match core::str::from_utf8(word) {
+        Ok(w) => w,
+        Err(_) => return 1,
+    };
+    match unsafe { SPELL_HANDLERS.as_mut() } {
+        Some((check, _)) => check(word) as u32,
+        None => 1,
+    }
+}
+
+extern "C" fn spell_suggest_thunk(word: *const u8, len: u32, out: *mut u8, cap: u32, _ud: u64) -> u32 {
+    let word = unsafe { core::slice::from_raw_parts(word, len as usize) };
+    let word = match core::str::from_utf8(word) {
+        Ok(w) => w,
+        Err(_) => return 0,
+    };
+    let list = match unsafe { SPELL_HANDLERS.as_mut() } {
+        Some((_, suggest)) => suggest(word),
+        None => return 0,
+    };
+    let out = unsafe { core::slice::from_raw_parts_mut(out, cap as usize) };
+    let mut n = 0usize;
+    for s in &list {
+        let sep = if n > 0 { 1 } else { 0 };
+        if n + sep + s.len() > out.len() { break; }
+        if sep == 1 { out[n] = b'|'; }
+        out[n + sep..n + sep + s.len()].copy_from_slice(s.as_bytes());
+        n += sep + s.len();
+    }
+    n as u32
+}
+
+/// Install the spell checker used by TextFields / TextEditors that have
+/// `set_spell_check(true)`. `check` returns true for correctly spelled
+/// words; `suggest` returns replacement candidates, best match first
+/// (only the first few are shown).
+pub fn set_spell_checker(
+    check: impl FnMut(&str) -> bool + 'static,
+    suggest: impl FnMut(&str) -> alloc::vec::Vec<alloc::string::String> + 'static,
+) {
+    unsafe { SPELL_HANDLERS = Some((alloc::boxed::Box::new(check), alloc::boxed::Box::new(suggest))); }
+    (lib().set_spell_checker)(Some(spell_check_thunk), Some(spell_suggest_thunk), 0);
+}
+
+/// Register a raw C spell checker, e.g. one exported by a dictionary DLL.
+/// `suggest` may be `None`.
+pub fn set_spell_checker_raw(check: SpellCheckFn, suggest: Option<SpellSuggestFn>, userdata: u64) {
+    unsafe { SPELL_HANDLERS = None; }
+    (lib().set_spell_checker)(Some(check), suggest, userdata);
+}
+
+/// Remove the spell checker; all underlines disappear.
+pub fn clear_spell_checker() {
+    unsafe { SPELL_HANDLERS = None; }
+    (lib().set_spell_checker)(None, None, 0);
+}
+
 // ── Notification API ─────────────────────────────────────────────────
 
 /// Show a notification banner via the compositor.
@@ -1057,6 +2227,38 @@ pub fn show_notification(title: &str, message: &str, icon: Option<&[u32; 256]>,
     );
 }
 
+/// Show a toast at the bottom of `window` that slides in, stacks above any
+/// toasts already shown, and dismisses itself after `timeout_ms`
+/// (0 = default 4s; the countdown pauses while hovered).
+///
+/// - `action`: optional action label (e.g. "Undo"); `on_action` fires when
+///   it is clicked. Clicking anywhere else on the toast just dismisses it.
+///
+/// Returns the toast's ID for `dismiss_toast()`.
+pub fn show_toast(
+    window: &impl Widget,
+    text: &str,
+    action: Option<&str>,
+    timeout_ms: u32,
+    mut on_action: impl FnMut() + 'static,
+) -> u32 {
+    let action = action.unwrap_or("");
+    let (thunk, ud) = events::register(move |_id, _event_type| {
+        on_action();
+    });
+    (lib().show_toast)(
+        window.id(),
+        text.as_ptr(), text.len() as u32,
+        action.as_ptr(), action.len() as u32,
+        timeout_ms, thunk, ud,
+    )
+}
+
+/// Slide out a toast before its timeout. No-op if it is already gone.
+pub fn dismiss_toast(toast_id: u32) {
+    (lib().dismiss_toast)(toast_id);
+}
+
 // ══════════════════════════════════════════════════════════════════════
 //  Key event API
 // ══════════════════════════════════════════════════════════════════════
@@ -1130,6 +2332,106 @@ pub fn get_modifiers() -> u32 {
     modifiers
 }
 
+// ── Pointer info (mouse, pen, touch) ────────────────────────────────
+
+pub const POINTER_MOUSE: u32 = 0;
+pub const POINTER_PEN: u32 = 1;
+pub const POINTER_TOUCH: u32 = 2;
+
+pub const PHASE_DOWN: u32 = 0;
+pub const PHASE_MOVE: u32 = 1;
+pub const PHASE_UP: u32 = 2;
+/// Pen in range above the tablet without touching it.
+pub const PHASE_HOVER: u32 = 3;
+
+/// Full pressure (pens report 0..=PRESSURE_MAX).
+pub const PRESSURE_MAX: u32 = 4095;
+
+// Pen buttons
+pub const PEN_BARREL: u32 = 0x01;
+pub const PEN_ERASER: u32 = 0x02;
+
+/// A pointer sample: mouse, pen or touch contact.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PointerEvent {
+    /// POINTER_MOUSE, POINTER_PEN or POINTER_TOUCH.
+    pub kind: u32,
+    /// PHASE_DOWN, PHASE_MOVE, PHASE_UP or PHASE_HOVER.
+    pub phase: u32,
+    /// Touch contact id (0 for mouse and pen).
+    pub contact: u32,
+    /// Position relative to the control.
+    pub x: i32,
+    pub y: i32,
+    /// 0..=PRESSURE_MAX.
+    pub pressure: u32,
+    /// Pen tilt in degrees (-90..=90).
+    pub tilt_x: i32,
+    pub tilt_y: i32,
+    /// Mouse buttons (1 = left, 2 = right) or PEN_* flags.
+    pub buttons: u32,
+    /// EVENT_PINCH: contact distance relative to the gesture start (1000 = unchanged).
+    pub scale: u32,
+}
+
+impl PointerEvent {
+    pub fn is_pen(&self) -> bool { self.kind == POINTER_PEN }
+    pub fn is_touch(&self) -> bool { self.kind == POINTER_TOUCH }
+    pub fn is_eraser(&self) -> bool { self.is_pen() && self.buttons & PEN_ERASER != 0 }
+}
+
+/// Query the pointer sample behind the current callback (mouse, touch and
+/// pinch events). The position is relative to the notified control.
+pub fn get_pointer_info() -> PointerEvent {
+    let mut ev = PointerEvent::default();
+    (lib().get_pointer_info)(&mut ev);
+    ev
+}
+
+// ── Gesture info ────────────────────────────────────────────────────
+
+pub const GESTURE_LONG_PRESS: u32 = 1;
+pub const GESTURE_SWIPE: u32 = 2;
+pub const GESTURE_DOUBLE_TAP: u32 = 3;
+
+// Swipe axes (`Control::set_swipe`)
+pub const SWIPE_HORIZONTAL: u32 = 0x01;
+pub const SWIPE_VERTICAL: u32 = 0x02;
+
+// Swipe directions (`GestureInfo::direction`)
+pub const SWIPE_LEFT: u32 = 1;
+pub const SWIPE_RIGHT: u32 = 2;
+pub const SWIPE_UP: u32 = 3;
+pub const SWIPE_DOWN: u32 = 4;
+
+/// A recognized long-press, swipe or double-tap.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GestureInfo {
+    /// GESTURE_LONG_PRESS, GESTURE_SWIPE or GESTURE_DOUBLE_TAP.
+    pub kind: u32,
+    /// Press point relative to the control.
+    pub x: i32,
+    pub y: i32,
+    /// Swipe: SWIPE_LEFT, SWIPE_RIGHT, SWIPE_UP or SWIPE_DOWN.
+    pub direction: u32,
+    /// Swipe: travel along the swipe axis in logical pixels.
+    pub distance: u32,
+    /// Swipe: release speed in logical pixels per second.
+    pub velocity: u32,
+    /// Press to gesture (double tap: between the taps), in ms.
+    pub duration_ms: u32,
+}
+
+/// Query the gesture behind the current long-press, swipe or double-tap
+/// callback.
+pub fn get_gesture_info() -> GestureInfo {
+    let mut info = GestureInfo::default();
+    (lib().get_gesture_info)(&mut info);
+    info
+}
+
 // ══════════════════════════════════════════════════════════════════════
 //  Clipboard API
 // ══════════════════════════════════════════════════════════════════════
@@ -1150,6 +2452,43 @@ pub fn clipboard_get(buf: &mut [u8]) -> u32 {
     (lib().clipboard_get)(buf.as_mut_ptr(), buf.len() as u32)
 }
 
+/// Register a callback for clipboard changes made by any app.
+/// Receives the new clipboard sequence number.
+pub fn on_clipboard_changed(mut f: impl FnMut(u32) + 'static) {
+    let (thunk, ud) = events::register(move |seq, _| f(seq));
+    (lib().on_clipboard_changed)(thunk, ud);
+}
+
+/// Current clipboard sequence number. It changes every time the clipboard
+/// is set, so comparing it with a saved value is a cheap change check.
+pub fn clipboard_seq() -> u32 {
+    (lib().clipboard_seq)()
+}
+
+/// An entry of the compositor's clipboard history.
+pub struct ClipboardEntry {
+    pub data: alloc::vec::Vec<u8>,
+    /// 0 = text/plain, 1 = text/uri-list.
+    pub format: u32,
+    /// Clipboard sequence number the entry was set with.
+    pub seq: u32,
+}
+
+/// Get clipboard history entry `index` (0 = newest), up to `max_len`
+/// bytes. Returns `None` past the end of the history, or if this app is
+/// not allowed to read it (only the clipboard manager and root are).
+pub fn clipboard_history(index: u32, max_len: usize) -> Option<ClipboardEntry> {
+    let mut data = alloc::vec![0u8; max_len];
+    let mut format = 0u32;
+    let mut seq = 0u32;
+    let len = (lib().clipboard_history)(index, data.as_mut_ptr(), max_len as u32, &mut format, &mut seq);
+    if len == 0 {
+        return None;
+    }
+    data.truncate(len as usize);
+    Some(ClipboardEntry { data, format, seq })
+}
+
 // ══════════════════════════════════════════════════════════════════════
 //  Theme API
 // ══════════════════════════════════════════════════════════════════════
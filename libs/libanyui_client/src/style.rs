@@ -0,0 +1,117 @@
+//! Styles — shared looks applied by control kind or class name.
+//!
+//! A [`Style`] is a named set of properties. Bind it to a control kind with
+//! [`Style::apply_to_kind`] or give controls its name as a class with
+//! [`Control::set_class`](crate::Control::set_class). Values cascade: kind
+//! style → class styles (in class-list order) → the control's own setters,
+//! so changing a style restyles every control using it in one call.
+//!
+//! # Usage
+//! ```rust
+//! use libanyui_client as ui;
+//!
+//! let primary = ui::Style::new("primary");
+//! primary.set_color(ui::theme::colors().accent);
+//! primary.set_text_color(0xFFFFFFFF);
+//! primary.set_padding(12, 6, 12, 6);
+//! ui::Style::new("button").set_font_size(13).apply_to_kind(ui::KIND_BUTTON);
+//!
+//! let ok = ui::Button::new("OK");
+//! ok.set_class("primary");
+//! ```
+
+use crate::lib;
+
+pub const PROP_COLOR: u32 = 0;
+pub const PROP_TEXT_COLOR: u32 = 1;
+pub const PROP_FONT_SIZE: u32 = 2;
+pub const PROP_FONT_ID: u32 = 3;
+pub const PROP_PADDING_LEFT: u32 = 4;
+pub const PROP_PADDING_TOP: u32 = 5;
+pub const PROP_PADDING_RIGHT: u32 = 6;
+pub const PROP_PADDING_BOTTOM: u32 = 7;
+pub const PROP_MARGIN_LEFT: u32 = 8;
+pub const PROP_MARGIN_TOP: u32 = 9;
+pub const PROP_MARGIN_RIGHT: u32 = 10;
+pub const PROP_MARGIN_BOTTOM: u32 = 11;
+pub const PROP_MIN_WIDTH: u32 = 12;
+pub const PROP_MIN_HEIGHT: u32 = 13;
+pub const PROP_CURSOR: u32 = 14;
+
+/// A named style. Creating a style with an existing name returns the same
+/// style, so any part of an app can reach it by name.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    id: u32,
+}
+
+impl Style {
+    pub fn new(name: &str) -> Self {
+        Self { id: (lib().style_create)(name.as_ptr(), name.len() as u32) }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Set a raw property (`PROP_*`).
+    pub fn set(&self, prop: u32, value: u32) -> &Self {
+        (lib().style_set)(self.id, prop, value);
+        self
+    }
+
+    /// Stop supplying a property.
+    pub fn unset(&self, prop: u32) -> &Self {
+        (lib().style_unset)(self.id, prop);
+        self
+    }
+
+    pub fn set_color(&self, color: u32) -> &Self {
+        self.set(PROP_COLOR, color)
+    }
+
+    pub fn set_text_color(&self, color: u32) -> &Self {
+        self.set(PROP_TEXT_COLOR, color)
+    }
+
+    pub fn set_font_size(&self, size: u32) -> &Self {
+        self.set(PROP_FONT_SIZE, size)
+    }
+
+    pub fn set_font(&self, font_id: u32) -> &Self {
+        self.set(PROP_FONT_ID, font_id)
+    }
+
+    pub fn set_padding(&self, left: i32, top: i32, right: i32, bottom: i32) -> &Self {
+        self.set(PROP_PADDING_LEFT, left as u32)
+            .set(PROP_PADDING_TOP, top as u32)
+            .set(PROP_PADDING_RIGHT, right as u32)
+            .set(PROP_PADDING_BOTTOM, bottom as u32)
+    }
+
+    pub fn set_margin(&self, left: i32, top: i32, right: i32, bottom: i32) -> &Self {
+        self.set(PROP_MARGIN_LEFT, left as u32)
+            .set(PROP_MARGIN_TOP, top as u32)
+            .set(PROP_MARGIN_RIGHT, right as u32)
+            .set(PROP_MARGIN_BOTTOM, bottom as u32)
+    }
+
+    pub fn set_min_size(&self, min_w: u32, min_h: u32) -> &Self {
+        self.set(PROP_MIN_WIDTH, min_w).set(PROP_MIN_HEIGHT, min_h)
+    }
+
+    pub fn set_cursor(&self, cursor: u32) -> &Self {
+        self.set(PROP_CURSOR, cursor)
+    }
+
+    /// Make this the style of every control of `kind` (`KIND_*`).
+    pub fn apply_to_kind(&self, kind: u32) -> &Self {
+        (lib().style_apply_kind)(kind, self.id);
+        self
+    }
+
+    /// Remove the style bound to `kind`, if any.
+    pub fn clear_kind(kind: u32) {
+        (lib().style_apply_kind)(kind, 0);
+    }
+}
@@ -57,6 +57,45 @@ pub struct ThemeColors {
     pub placeholder_bg: u32,
 }
 
+// ── Color roles (field index in `ThemeColors`) ───────────────────────
+
+pub const ROLE_WINDOW_BG: u32         = 0;
+pub const ROLE_TEXT: u32              = 1;
+pub const ROLE_TEXT_SECONDARY: u32    = 2;
+pub const ROLE_TEXT_DISABLED: u32     = 3;
+pub const ROLE_ACCENT: u32            = 4;
+pub const ROLE_ACCENT_HOVER: u32      = 5;
+pub const ROLE_DESTRUCTIVE: u32       = 6;
+pub const ROLE_SUCCESS: u32           = 7;
+pub const ROLE_WARNING: u32           = 8;
+pub const ROLE_CONTROL_BG: u32        = 9;
+pub const ROLE_CONTROL_HOVER: u32     = 10;
+pub const ROLE_CONTROL_PRESSED: u32   = 11;
+pub const ROLE_INPUT_BG: u32          = 12;
+pub const ROLE_INPUT_BORDER: u32      = 13;
+pub const ROLE_INPUT_FOCUS: u32       = 14;
+pub const ROLE_SEPARATOR: u32         = 15;
+pub const ROLE_SELECTION: u32         = 16;
+pub const ROLE_SIDEBAR_BG: u32        = 17;
+pub const ROLE_CARD_BG: u32           = 18;
+pub const ROLE_CARD_BORDER: u32       = 19;
+pub const ROLE_BADGE_RED: u32         = 20;
+pub const ROLE_TOGGLE_ON: u32         = 21;
+pub const ROLE_TOGGLE_OFF: u32        = 22;
+pub const ROLE_TOGGLE_THUMB: u32      = 23;
+pub const ROLE_SCROLLBAR: u32         = 24;
+pub const ROLE_SCROLLBAR_TRACK: u32   = 25;
+pub const ROLE_CHECK_MARK: u32        = 26;
+pub const ROLE_TOOLBAR_BG: u32        = 27;
+pub const ROLE_TAB_INACTIVE_BG: u32   = 28;
+pub const ROLE_TAB_HOVER_BG: u32      = 29;
+pub const ROLE_TAB_BORDER_ACTIVE: u32 = 30;
+pub const ROLE_EDITOR_BG: u32         = 31;
+pub const ROLE_EDITOR_LINE_HL: u32    = 32;
+pub const ROLE_EDITOR_SELECTION: u32  = 33;
+pub const ROLE_ALT_ROW_BG: u32        = 34;
+pub const ROLE_PLACEHOLDER_BG: u32    = 35;
+
 /// Fallback dark palette — used only when the server-side pointer is NULL
 /// (i.e. libanyui hasn't been initialised yet).
 const DARK: ThemeColors = ThemeColors {
@@ -126,6 +165,32 @@ pub fn apply_accent_style(dark_accent: u32, dark_hover: u32, light_accent: u32,
     (crate::lib().apply_accent_style)(dark_accent, dark_hover, light_accent, light_hover);
 }
 
+/// Override one color role (`ROLE_*`) for this app only, in both the dark
+/// and light palettes. All windows repaint. Returns false for an unknown role.
+pub fn set_color(role: u32, argb: u32) -> bool {
+    (crate::lib().theme_set_color)(role, argb) != 0
+}
+
+/// Load a branded theme `.conf` (`KEY=0xAARRGGBB` lines, same keys as the
+/// system themes) as app-only overrides. Keys after a `[dark]` or `[light]`
+/// line apply to that palette only. Returns false if the file can't be read.
+pub fn load_conf(path: &str) -> bool {
+    (crate::lib().theme_load_conf)(path.as_ptr(), path.len() as u32) != 0
+}
+
+/// Drop all app-only overrides and return to the system palette.
+pub fn reset_colors() {
+    (crate::lib().theme_reset_colors)();
+}
+
+/// Register a callback for system theme changes pushed by the compositor.
+/// Called with `true` for light, `false` for dark, after the palettes have
+/// been reloaded (app overrides are kept) and all windows marked for repaint.
+pub fn on_theme_changed(mut f: impl FnMut(bool) + 'static) {
+    let (thunk, ud) = crate::events::register(move |theme, _| f(theme != 0));
+    (crate::lib().on_theme_changed)(thunk, ud);
+}
+
 /// Set the font smoothing mode system-wide.
 ///
 /// Sends an IPC command to the compositor, which writes to the shared page
@@ -158,6 +223,52 @@ pub fn get_scale_factor() -> u32 {
     (crate::lib().get_scale_factor)()
 }
 
+// ── Accessibility ────────────────────────────────────────────────────
+
+/// Per-app high-contrast choice for [`set_high_contrast`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HighContrast {
+    /// Use the high-contrast palette while the system setting is on.
+    FollowSystem = 0,
+    /// Never use the high-contrast palette in this app.
+    Off = 1,
+    /// Always use the high-contrast palette in this app.
+    On = 2,
+}
+
+/// Set the system accessibility preferences: high-contrast palette and the
+/// default UI zoom for app windows (100–200 in 25% steps).
+///
+/// Sends an IPC command to the compositor, which writes to the shared page,
+/// persists the setting and notifies every app (`on_theme_changed` fires).
+pub fn set_accessibility(high_contrast: bool, zoom: u32) {
+    (crate::lib().set_accessibility)(high_contrast as u32, zoom);
+}
+
+/// Whether high contrast is enabled system-wide.
+pub fn system_high_contrast() -> bool {
+    (crate::lib().get_accessibility)() & 1 != 0
+}
+
+/// System default UI zoom in percent (100 = none).
+pub fn system_zoom() -> u32 {
+    match (crate::lib().get_accessibility)() >> 16 {
+        0 => 100,
+        z => z,
+    }
+}
+
+/// Override the system high-contrast setting for this app only.
+pub fn set_high_contrast(mode: HighContrast) {
+    (crate::lib().set_high_contrast)(mode as u32);
+}
+
+/// Whether the high-contrast palette is currently in effect for this app.
+/// [`colors`] already returns it; this is for custom-drawn content.
+pub fn is_high_contrast() -> bool {
+    (crate::lib().is_high_contrast)() != 0
+}
+
 // ── Color utility functions ──────────────────────────────────────────
 
 /// Darken a color by subtracting `amount` from each RGB channel.
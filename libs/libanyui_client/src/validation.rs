@@ -0,0 +1,75 @@
+//! Form validation — validators on input controls.
+//!
+//! Each validator carries the message shown when it fails; the first
+//! failing one (in the order they were added) sets the control's error.
+//! A control is validated when it loses focus, and [`form_validate`]
+//! checks every validated control under a root — call it when the form is
+//! submitted and focus the control it returns. Invalid controls get a red
+//! outline and their message in a bubble below them; the error clears as
+//! soon as the value is fixed.
+//!
+//! Text controls are validated by their text, all other controls by their
+//! state value (as decimal digits).
+//!
+//! # Usage
+//! ```rust
+//! name.validate_required("Please enter your name");
+//! age.validate_range(18.0, 130.0, "Age must be between 18 and 130");
+//! zip.validate_pattern("\\d{5}", "Five digits expected");
+//! user.validate_custom(|v| !taken(v), "This name is taken");
+//!
+//! save.on_click(move |_| {
+//!     match anyui::form_validate(&form) {
+//!         Some(bad) => bad.focus(),
+//!         None => submit(),
+//!     }
+//! });
+//! ```
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use crate::{Control, Widget, lib};
+
+/// Raw custom validator: extern "C" fn(control, value, len, userdata) -> 1 if valid.
+pub type ValidateFn = extern "C" fn(u32, *const u8, u32, u64) -> u32;
+
+/// Validate every enabled, visible control with validators under `root`
+/// and show their errors. Returns the first invalid control, if any.
+pub fn form_validate(root: &impl Widget) -> Option<Control> {
+    match (lib().form_validate)(root.id()) {
+        0 => None,
+        id => Some(Control::from_id(id)),
+    }
+}
+
+// ── Closure registry ─────────────────────────────────────────────────
+
+type ValidateHandler = Box<dyn FnMut(&str) -> bool>;
+
+static mut VALIDATE_HANDLERS: Option<Vec<ValidateHandler>> = None;
+
+fn handlers() -> &'static mut Vec<ValidateHandler> {
+    unsafe { VALIDATE_HANDLERS.get_or_insert_with(Vec::new) }
+}
+
+/// FFI thunk: dispatches to the registered closure at `VALIDATE_HANDLERS[userdata]`.
+/// Values that are not UTF-8 are invalid.
+extern "C" fn validate_thunk(_id: u32, value: *const u8, len: u32, userdata: u64) -> u32 {
+    let idx = userdata as usize;
+    let h = handlers();
+    if idx >= h.len() {
+        return 1;
+    }
+    let bytes = if len > 0 { unsafe { core::slice::from_raw_parts(value, len as usize) } } else { &[] };
+    match core::str::from_utf8(bytes) {
+        Ok(v) => h[idx](v) as u32,
+        Err(_) => 0,
+    }
+}
+
+/// Register a closure, returning the (thunk, userdata) pair for FFI registration.
+pub(crate) fn register(f: impl FnMut(&str) -> bool + 'static) -> (ValidateFn, u64) {
+    let idx = handlers().len();
+    handlers().push(Box::new(f));
+    (validate_thunk, idx as u64)
+}
@@ -0,0 +1,65 @@
+//! Virtualized containers — only the visible items get a control.
+//!
+//! A container in virtualization mode holds `count` items of a fixed
+//! extent (row height, or column width for horizontal stacks) and asks an
+//! item provider for a control only for the items on screen. Controls that
+//! scroll out of view are hidden and handed back to the provider to be
+//! rebound to the next item that scrolls in.
+//!
+//! # Usage
+//! ```rust
+//! let list = StackPanel::vertical();
+//! scroll.add(&list);
+//! list.set_virtualized(28, names.len() as u32, move |index, recycled| {
+//!     let row = recycled.unwrap_or_else(|| *Label::new(""));
+//!     row.set_text(&names[index as usize]);
+//!     Some(row)
+//! });
+//! ```
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use crate::{Control, Widget, lib};
+
+/// Raw item provider: extern "C" fn(container, index, recycled, userdata) -> child.
+pub type ItemProviderFn = extern "C" fn(u32, u32, u32, u64) -> u32;
+
+// ── Closure registry ─────────────────────────────────────────────────
+
+type ItemHandler = Box<dyn FnMut(u32, Option<Control>) -> Option<Control>>;
+
+static mut ITEM_HANDLERS: Option<Vec<ItemHandler>> = None;
+
+fn handlers() -> &'static mut Vec<ItemHandler> {
+    unsafe { ITEM_HANDLERS.get_or_insert_with(Vec::new) }
+}
+
+/// FFI thunk: dispatches to the registered closure at `ITEM_HANDLERS[userdata]`.
+/// A newly created control is attached to the container.
+extern "C" fn provider_thunk(container: u32, index: u32, recycled: u32, userdata: u64) -> u32 {
+    let idx = userdata as usize;
+    let h = handlers();
+    if idx >= h.len() {
+        return 0;
+    }
+    let recycled = if recycled != 0 { Some(Control::from_id(recycled)) } else { None };
+    let fresh = recycled.is_none();
+    match h[idx](index, recycled) {
+        Some(child) => {
+            if fresh {
+                (lib().add_child)(container, child.id());
+            }
+            child.id()
+        }
+        None => 0,
+    }
+}
+
+/// Register a closure, returning the (thunk, userdata) pair for FFI registration.
+pub(crate) fn register(
+    f: impl FnMut(u32, Option<Control>) -> Option<Control> + 'static,
+) -> (ItemProviderFn, u64) {
+    let idx = handlers().len();
+    handlers().push(Box::new(f));
+    (provider_thunk, idx as u64)
+}
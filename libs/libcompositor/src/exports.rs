@@ -23,6 +23,7 @@ const CMD_SET_WALLPAPER: u32 = 0x100F;
 const CMD_CREATE_VRAM_WINDOW: u32 = 0x1010;
 const CMD_SET_CLIPBOARD: u32 = 0x1011;
 const CMD_GET_CLIPBOARD: u32 = 0x1012;
+const CMD_GET_CLIPBOARD_HISTORY: u32 = 0x1026;
 const CMD_GET_WINDOW_POS: u32 = 0x1013;
 const CMD_MINIMIZE_WINDOW: u32 = 0x1015;
 const CMD_SHOW_NOTIFICATION: u32 = 0x1020;
@@ -32,8 +33,9 @@ const RESP_VRAM_WINDOW_CREATED: u32 = 0x2004;
 const RESP_VRAM_WINDOW_FAILED: u32 = 0x2005;
 const RESP_WINDOW_POS: u32 = 0x2006;
 const RESP_CLIPBOARD_DATA: u32 = 0x2010;
+const RESP_CLIPBOARD_HISTORY: u32 = 0x2011;
 
-const NUM_EXPORTS: u32 = 24;
+const NUM_EXPORTS: u32 = 25;
 
 #[repr(C)]
 pub struct LibcompositorExports {
@@ -175,6 +177,16 @@ pub struct LibcompositorExports {
 
     /// Minimize a window (move off-screen, save bounds for later restore).
     pub minimize_window: extern "C" fn(channel_id: u32, window_id: u32),
+
+    /// Get clipboard history entry `index` (0 = newest). Returns the byte
+    /// count (0 if there is no such entry or the caller may not read the
+    /// history). Writes up to out_cap bytes into out_ptr; out_format and
+    /// out_seq receive the entry's format and clipboard sequence number.
+    pub get_clipboard_history: extern "C" fn(
+        channel_id: u32, sub_id: u32, index: u32,
+        out_ptr: *mut u8, out_cap: u32,
+        out_format: *mut u32, out_seq: *mut u32,
+    ) -> u32,
 }
 
 #[link_section = ".exports"]
@@ -209,6 +221,7 @@ pub static LIBCOMPOSITOR_EXPORTS: LibcompositorExports = LibcompositorExports {
     dismiss_notification: export_dismiss_notification,
     get_window_position: export_get_window_position,
     minimize_window: export_minimize_window,
+    get_clipboard_history: export_get_clipboard_history,
 };
 
 // ── Export Implementations ───────────────────────────────────────────────────
@@ -820,3 +833,55 @@ extern "C" fn export_minimize_window(channel_id: u32, window_id: u32) {
     let cmd: [u32; 5] = [CMD_MINIMIZE_WINDOW, window_id, 0, 0, 0];
     syscall::evt_chan_emit(channel_id, &cmd);
 }
+
+extern "C" fn export_get_clipboard_history(
+    channel_id: u32,
+    sub_id: u32,
+    index: u32,
+    out_ptr: *mut u8,
+    out_cap: u32,
+    out_format: *mut u32,
+    out_seq: *mut u32,
+) -> u32 {
+    if out_ptr.is_null() || out_cap == 0 {
+        return 0;
+    }
+
+    let shm_id = syscall::shm_create(out_cap);
+    if shm_id == 0 {
+        return 0;
+    }
+    let shm_addr = syscall::shm_map(shm_id);
+    if shm_addr == 0 {
+        syscall::shm_destroy(shm_id);
+        return 0;
+    }
+
+    let tid = syscall::get_tid();
+    let cmd: [u32; 5] = [CMD_GET_CLIPBOARD_HISTORY, shm_id, out_cap, tid, index];
+    syscall::evt_chan_emit(channel_id, &cmd);
+
+    // Poll for RESP_CLIPBOARD_HISTORY (matched by our SHM id)
+    let mut response = [0u32; 5];
+    let mut result_len: u32 = 0;
+    'wait: for _ in 0..50 {
+        while syscall::evt_chan_poll(channel_id, sub_id, &mut response) {
+            if response[0] == RESP_CLIPBOARD_HISTORY && response[1] == shm_id {
+                result_len = response[2].min(out_cap);
+                unsafe {
+                    if !out_format.is_null() { *out_format = response[3]; }
+                    if !out_seq.is_null() { *out_seq = response[4]; }
+                    if result_len > 0 {
+                        core::ptr::copy_nonoverlapping(shm_addr as *const u8, out_ptr, result_len as usize);
+                    }
+                }
+                break 'wait;
+            }
+        }
+        syscall::sleep(5);
+    }
+
+    syscall::shm_unmap(shm_id);
+    syscall::shm_destroy(shm_id);
+    result_len
+}
@@ -0,0 +1,1095 @@
+//! Built-in minimal PC BIOS.
+//!
+//! An optional alternative to supplying a SeaBIOS image: enough firmware
+//! to boot DOS-era software and simple hobby operating systems from the
+//! IDE disk. Loaded via [`corevm_load_default_bios`](crate::corevm_load_default_bios).
+//!
+//! # How it works
+//!
+//! [`rom_image`] builds a 64 KiB ROM that is placed at 0xF0000. Each
+//! serviced interrupt vector points to a 16-byte stub in the ROM that
+//! executes `OUT 0xEE, AL` followed by `IRET`. The [`TrapPort`] device
+//! answers that write with [`VmError::BiosTrap`], the CPU loop exits with
+//! [`ExitReason::BiosTrap`](crate::cpu::ExitReason::BiosTrap), and
+//! `corevm_run` calls [`service`], which performs the function directly on
+//! the guest registers, memory and devices and then steps past the `OUT`.
+//! The stub's own `IRET` returns to the caller, so the guest sees an
+//! ordinary software interrupt. POST runs the same way from the reset
+//! vector, so a guest-initiated reset re-initializes the machine.
+//!
+//! # Services
+//!
+//! | Vector | Description |
+//! |--------|-------------|
+//! | 08h | Timer tick (BDA tick count, INT 1Ch chain) |
+//! | 09h | Keyboard IRQ (scancode set 1 → BDA key buffer) |
+//! | 10h | Video: text output, cursor, scrolling, mode set |
+//! | 11h | Equipment list |
+//! | 12h | Conventional memory size |
+//! | 13h | Disk services for drive 80h (CHS and EDD extensions) |
+//! | 15h | System services: E820/E801/88h memory map, A20 gate |
+//! | 16h | Keyboard services |
+//! | 18h/19h | Boot failure / bootstrap loader |
+//! | 1Ah | Tick count and RTC time/date |
+//!
+//! Hardware IRQs without a service get a stub that only sends EOI.
+//! All other vectors point to a bare `IRET`.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::cpu::Mode;
+use crate::devices::ide::Ide;
+use crate::devices::svga::{Svga, VgaMode};
+use crate::error::{Result, VmError};
+use crate::flags;
+use crate::io::IoHandler;
+use crate::memory::MemoryBus;
+use crate::registers::{GprIndex, SegReg};
+use crate::VmEngine;
+
+/// Guest physical address the ROM image is loaded at.
+pub const ROM_BASE: u64 = 0xF0000;
+/// Size of the ROM image in bytes.
+pub const ROM_SIZE: usize = 0x10000;
+/// I/O port the ROM stubs write to in order to trap into the host.
+pub const TRAP_PORT: u16 = 0xEE;
+
+/// Real-mode segment of the ROM.
+const ROM_SEG: u16 = 0xF000;
+/// ROM offset of the interrupt stub table (256 stubs).
+const STUB_BASE: u16 = 0x8000;
+/// Size of one interrupt stub in bytes.
+const STUB_SIZE: u16 = 16;
+/// ROM offset of the POST entry point (target of the reset vector jump).
+const POST_ENTRY: u16 = 0x9000;
+/// ROM offset of the diskette parameter table (INT 1Eh), at the
+/// address fixed by the original IBM AT BIOS.
+const DISKETTE_PARAMS: u16 = 0xEFC7;
+
+/// BIOS Data Area base address.
+const BDA: u64 = 0x400;
+/// Extended BIOS Data Area segment (639 KiB conventional memory).
+const EBDA_SEG: u16 = 0x9FC0;
+/// Conventional memory reported by INT 12h, in KiB.
+const BASE_MEM_KB: u16 = 639;
+/// BDA tick count at midnight (~18.2 Hz × 86400 s).
+const TICKS_PER_DAY: u32 = 0x1800B0;
+
+/// Emulated disk geometry for CHS addressing.
+const DISK_HEADS: u64 = 16;
+const DISK_SPT: u64 = 63;
+const SECTOR_SIZE: usize = 512;
+
+/// INT 13h status codes.
+const DISK_OK: u8 = 0x00;
+const DISK_BAD_COMMAND: u8 = 0x01;
+const DISK_SECTOR_NOT_FOUND: u8 = 0x04;
+const DISK_READ_ERROR: u8 = 0x10;
+const DISK_WRITE_FAULT: u8 = 0xCC;
+
+/// E820 address range types.
+const E820_RAM: u32 = 1;
+const E820_RESERVED: u32 = 2;
+
+/// Set 1 scancode → ASCII, unshifted (codes 0x00-0x39).
+const KEYMAP: &[u8; 58] =
+    b"\0\x1b1234567890-=\x08\tqwertyuiop[]\r\0asdfghjkl;'`\0\\zxcvbnm,./\0*\0 ";
+/// Set 1 scancode → ASCII, with Shift held.
+const KEYMAP_SHIFT: &[u8; 58] =
+    b"\0\x1b!@#$%^&*()_+\x08\tQWERTYUIOP{}\r\0ASDFGHJKL:\"~\0|ZXCVBNM<>?\0*\0 ";
+/// Keypad codes 0x47-0x53 with Num Lock on.
+const KEYMAP_KEYPAD: &[u8; 13] = b"789-456+1230.";
+
+/// Port that traps ROM stub execution into the host.
+///
+/// Only registered when the built-in BIOS is loaded. Every write fails
+/// with [`VmError::BiosTrap`] so the CPU loop hands control to [`service`].
+#[derive(Debug)]
+pub struct TrapPort;
+
+impl IoHandler for TrapPort {
+    fn read(&mut self, _port: u16, _size: u8) -> Result<u32> {
+        Ok(0xFF)
+    }
+
+    fn write(&mut self, _port: u16, _size: u8, _val: u32) -> Result<()> {
+        Err(VmError::BiosTrap)
+    }
+}
+
+/// What the CPU loop should do after a trap has been serviced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BiosAction {
+    /// The service completed; keep executing the guest.
+    Resume,
+    /// The service is blocked on input (INT 16h read with an empty
+    /// buffer). The guest stays on the trapping instruction and the run
+    /// should end as if halted, so the host idles before retrying.
+    Wait,
+}
+
+/// Build the 64 KiB ROM image.
+pub fn rom_image() -> Vec<u8> {
+    const TRAP: u8 = TRAP_PORT as u8;
+    let mut rom = vec![0u8; ROM_SIZE];
+
+    for vector in 0..=255u8 {
+        let stub: &[u8] = match vector {
+            // OUT; INT 1Ch; IRET — the host bumps the tick count first.
+            0x08 => &[0xE6, TRAP, 0xCD, 0x1C, 0xCF],
+            // STI first so timer IRQs keep running while a read waits for a key.
+            0x16 => &[0xFB, 0xE6, TRAP, 0xCF],
+            0x09 | 0x10..=0x13 | 0x15 | 0x18 | 0x19 | 0x1A => &[0xE6, TRAP, 0xCF],
+            // Remaining master PIC IRQs: PUSH AX; MOV AL,20h; OUT 20h,AL; POP AX; IRET.
+            0x0A..=0x0F => &[0x50, 0xB0, 0x20, 0xE6, 0x20, 0x58, 0xCF],
+            // Slave PIC IRQs: EOI to both controllers.
+            0x70..=0x77 => &[0x50, 0xB0, 0x20, 0xE6, 0xA0, 0xE6, 0x20, 0x58, 0xCF],
+            _ => &[0xCF],
+        };
+        let off = (STUB_BASE + vector as u16 * STUB_SIZE) as usize;
+        rom[off..off + stub.len()].copy_from_slice(stub);
+    }
+
+    // POST: OUT (host POST); INT 19h; if boot returns: STI; HLT; JMP $-1.
+    let post = [0xE6, TRAP, 0xCD, 0x19, 0xFB, 0xF4, 0xEB, 0xFD];
+    rom[POST_ENTRY as usize..POST_ENTRY as usize + post.len()].copy_from_slice(&post);
+
+    // 1.44 MB diskette parameter table.
+    let dpt = [0xAF, 0x02, 0x25, 0x02, 0x12, 0x1B, 0xFF, 0x6C, 0xF6, 0x0F, 0x08];
+    let off = DISKETTE_PARAMS as usize;
+    rom[off..off + dpt.len()].copy_from_slice(&dpt);
+
+    // Reset vector: JMP FAR F000:POST_ENTRY.
+    let entry = POST_ENTRY.to_le_bytes();
+    rom[0xFFF0..0xFFF5].copy_from_slice(&[0xEA, entry[0], entry[1], 0x00, 0xF0]);
+    // BIOS date and model byte (0xFC = PC/AT).
+    rom[0xFFF5..0xFFFD].copy_from_slice(b"01/01/26");
+    rom[0xFFFE] = 0xFC;
+
+    rom
+}
+
+/// Service a trap raised by a ROM stub.
+///
+/// `ide` and `svga` are the VM's disk controller and display adapter, if
+/// set up; the corresponding services fail gracefully without them.
+pub fn service(engine: &mut VmEngine, ide: Option<&mut Ide>, svga: Option<&mut Svga>) -> BiosAction {
+    let mut bios = Bios { engine, ide, svga };
+    let regs = &bios.engine.cpu.regs;
+    let ip = regs.rip as u16;
+    let in_rom = bios.engine.cpu.mode == Mode::RealMode
+        && regs.segment(SegReg::Cs).selector == ROM_SEG;
+
+    let outcome = if !in_rom {
+        // A guest wrote to the trap port on its own — treat it as a no-op.
+        Outcome::Done
+    } else if ip == POST_ENTRY {
+        bios.post();
+        Outcome::Done
+    } else if ip >= STUB_BASE && ip < STUB_BASE + 256 * STUB_SIZE {
+        bios.interrupt(((ip - STUB_BASE) / STUB_SIZE) as u8)
+    } else {
+        Outcome::Done
+    };
+
+    match outcome {
+        Outcome::Done => {
+            bios.skip_out();
+            BiosAction::Resume
+        }
+        Outcome::Jumped => BiosAction::Resume,
+        Outcome::Wait => BiosAction::Wait,
+    }
+}
+
+/// Result of a single BIOS service.
+enum Outcome {
+    /// Continue after the trapping `OUT`.
+    Done,
+    /// The service transferred control itself (bootstrap).
+    Jumped,
+    /// Retry the trapping `OUT` later.
+    Wait,
+}
+
+/// Borrowed VM state for the duration of one service call.
+struct Bios<'a> {
+    engine: &'a mut VmEngine,
+    ide: Option<&'a mut Ide>,
+    svga: Option<&'a mut Svga>,
+}
+
+impl Bios<'_> {
+    // ── Register and memory helpers ──
+
+    fn reg16(&self, reg: GprIndex) -> u16 {
+        self.engine.cpu.regs.read_gpr16(reg as u8)
+    }
+
+    fn set_reg16(&mut self, reg: GprIndex, val: u16) {
+        self.engine.cpu.regs.write_gpr16(reg as u8, val);
+    }
+
+    fn reg32(&self, reg: GprIndex) -> u32 {
+        self.engine.cpu.regs.read_gpr32(reg as u8)
+    }
+
+    fn set_reg32(&mut self, reg: GprIndex, val: u32) {
+        self.engine.cpu.regs.write_gpr32(reg as u8, val);
+    }
+
+    fn ah(&self) -> u8 {
+        (self.reg16(GprIndex::Rax) >> 8) as u8
+    }
+
+    fn al(&self) -> u8 {
+        self.reg16(GprIndex::Rax) as u8
+    }
+
+    fn set_ah(&mut self, val: u8) {
+        let ax = self.reg16(GprIndex::Rax);
+        self.set_reg16(GprIndex::Rax, (ax & 0x00FF) | ((val as u16) << 8));
+    }
+
+    fn set_al(&mut self, val: u8) {
+        let ax = self.reg16(GprIndex::Rax);
+        self.set_reg16(GprIndex::Rax, (ax & 0xFF00) | val as u16);
+    }
+
+    /// Linear address of `seg:off`.
+    fn linear(&self, seg: SegReg, off: u16) -> u64 {
+        self.engine.cpu.regs.segment(seg).base + off as u64
+    }
+
+    fn rd8(&self, addr: u64) -> u8 {
+        self.engine.memory.read_u8(addr).unwrap_or(0)
+    }
+
+    fn rd16(&self, addr: u64) -> u16 {
+        self.engine.memory.read_u16(addr).unwrap_or(0)
+    }
+
+    fn rd32(&self, addr: u64) -> u32 {
+        self.engine.memory.read_u32(addr).unwrap_or(0)
+    }
+
+    fn rd64(&self, addr: u64) -> u64 {
+        self.engine.memory.read_u64(addr).unwrap_or(0)
+    }
+
+    fn wr8(&mut self, addr: u64, val: u8) {
+        let _ = self.engine.memory.write_u8(addr, val);
+    }
+
+    fn wr16(&mut self, addr: u64, val: u16) {
+        let _ = self.engine.memory.write_u16(addr, val);
+    }
+
+    fn wr32(&mut self, addr: u64, val: u32) {
+        let _ = self.engine.memory.write_u32(addr, val);
+    }
+
+    fn wr64(&mut self, addr: u64, val: u64) {
+        let _ = self.engine.memory.write_u64(addr, val);
+    }
+
+    fn port_out(&mut self, port: u16, val: u8) {
+        let _ = self.engine.io.port_out(port, 1, val as u32);
+    }
+
+    fn port_in(&mut self, port: u16) -> u8 {
+        self.engine.io.port_in(port, 1).unwrap_or(0xFF) as u8
+    }
+
+    /// Set or clear `flag` in the FLAGS image the stub's `IRET` will restore.
+    fn set_return_flag(&mut self, flag: u64, on: bool) {
+        let sp = self.reg16(GprIndex::Rsp);
+        let addr = self.linear(SegReg::Ss, sp.wrapping_add(4));
+        let mut fl = self.rd16(addr);
+        if on {
+            fl |= flag as u16;
+        } else {
+            fl &= !(flag as u16);
+        }
+        self.wr16(addr, fl);
+    }
+
+    /// Advance IP past the trapping `OUT imm8` / `OUT DX` instruction.
+    fn skip_out(&mut self) {
+        let ip = self.engine.cpu.regs.rip as u16;
+        let mut len = 0u16;
+        loop {
+            let byte = self.rd8(self.linear(SegReg::Cs, ip.wrapping_add(len)));
+            len += 1;
+            match byte {
+                0x66 | 0x67 => continue,
+                0xE6 | 0xE7 => len += 1,
+                _ => {}
+            }
+            break;
+        }
+        self.engine.cpu.regs.rip = ip.wrapping_add(len) as u64;
+    }
+
+    fn eoi(&mut self) {
+        self.port_out(0x20, 0x20);
+    }
+
+    // ── Dispatch ──
+
+    fn interrupt(&mut self, vector: u8) -> Outcome {
+        match vector {
+            0x08 => self.int08(),
+            0x09 => {
+                self.poll_keyboard();
+                self.eoi();
+            }
+            0x10 => self.int10(),
+            0x11 => {
+                let equip = self.rd16(BDA + 0x10);
+                self.set_reg16(GprIndex::Rax, equip);
+            }
+            0x12 => self.set_reg16(GprIndex::Rax, self.rd16(BDA + 0x13)),
+            0x13 => self.int13(),
+            0x15 => self.int15(),
+            0x16 => return self.int16(),
+            0x18 => self.print("No bootable device.\r\n"),
+            0x19 => return self.boot(),
+            0x1A => self.int1a(),
+            _ => {}
+        }
+        Outcome::Done
+    }
+
+    // ── POST ──
+
+    /// Power-on self test: IVT, BDA, PIC/PIT, keyboard and video setup.
+    fn post(&mut self) {
+        // Interrupt vector table.
+        for vector in 0..256u64 {
+            let (seg, off) = match vector {
+                0x1E => (ROM_SEG, DISKETTE_PARAMS),
+                0x1D | 0x1F | 0x41 | 0x43 | 0x46 | 0x60..=0x67 => (0, 0),
+                _ => (ROM_SEG, STUB_BASE + vector as u16 * STUB_SIZE),
+            };
+            self.wr16(vector * 4, off);
+            self.wr16(vector * 4 + 2, seg);
+        }
+
+        // BIOS data area.
+        for addr in BDA..0x500 {
+            self.wr8(addr, 0);
+        }
+        for (i, &base) in [0x3F8u16, 0x2F8, 0x3E8, 0x2E8].iter().enumerate() {
+            self.wr16(BDA + i as u64 * 2, base);
+        }
+        self.wr16(BDA + 0x0E, EBDA_SEG);
+        // Equipment: 4 serial ports, 80x25 color, x87 present.
+        self.wr16(BDA + 0x10, (4 << 9) | (2 << 4) | 0x02);
+        self.wr16(BDA + 0x13, BASE_MEM_KB);
+        // Keyboard buffer 0x41E-0x43D, empty.
+        self.wr16(BDA + 0x1A, 0x1E);
+        self.wr16(BDA + 0x1C, 0x1E);
+        self.wr16(BDA + 0x80, 0x1E);
+        self.wr16(BDA + 0x82, 0x3E);
+        let disks = self.ide.as_ref().map_or(0, |ide| (ide.sector_count() > 0) as u8);
+        self.wr8(BDA + 0x75, disks);
+        // EBDA size in KiB.
+        let ebda = (EBDA_SEG as u64) << 4;
+        for addr in ebda..ebda + 0x400 {
+            self.wr8(addr, 0);
+        }
+        self.wr8(ebda, 1);
+
+        // PIC: remap to 08h/70h, cascade on IRQ2, enable timer, keyboard and cascade.
+        for &(port, val) in &[
+            (0x20, 0x11), (0x21, 0x08), (0x21, 0x04), (0x21, 0x01),
+            (0xA0, 0x11), (0xA1, 0x70), (0xA1, 0x02), (0xA1, 0x01),
+            (0x21, 0xF8), (0xA1, 0xFF),
+        ] {
+            self.port_out(port, val);
+        }
+        // PIT channel 0: mode 3, divisor 65536 (18.2 Hz).
+        self.port_out(0x43, 0x36);
+        self.port_out(0x40, 0);
+        self.port_out(0x40, 0);
+
+        // Keyboard: switch to scancode set 1 (what INT 09h/16h decode) and
+        // drop the acknowledgements.
+        self.port_out(0x60, 0xF0);
+        self.port_out(0x60, 0x01);
+        while self.port_in(0x64) & 0x01 != 0 {
+            self.port_in(0x60);
+        }
+
+        // Video: 80x25 text, cleared, cursor at the top left.
+        self.set_video_mode(0x03);
+        self.print("corevm built-in BIOS\r\n");
+
+        // Stack for the bootstrap loader.
+        self.engine.cpu.regs.load_segment_real(SegReg::Ss, 0);
+        self.set_reg16(GprIndex::Rsp, 0x7C00);
+    }
+
+    /// INT 19h — load the MBR of drive 80h to 0000:7C00 and jump to it.
+    fn boot(&mut self) -> Outcome {
+        let mut sector = [0u8; SECTOR_SIZE];
+        let loaded = match self.ide.as_mut() {
+            Some(ide) => ide.read_image(0, &mut sector),
+            None => false,
+        };
+        if !loaded || sector[510] != 0x55 || sector[511] != 0xAA {
+            self.print("No bootable disk found.\r\n");
+            return Outcome::Done;
+        }
+        let _ = self.engine.memory.write_bytes(0x7C00, &sector);
+
+        let regs = &mut self.engine.cpu.regs;
+        regs.load_segment_real(SegReg::Cs, 0);
+        regs.load_segment_real(SegReg::Ds, 0);
+        regs.load_segment_real(SegReg::Es, 0);
+        regs.rip = 0x7C00;
+        regs.rflags |= flags::IF;
+        regs.write_gpr16(GprIndex::Rdx as u8, 0x0080);
+        Outcome::Jumped
+    }
+
+    // ── INT 08h / 1Ah: timer ──
+
+    fn int08(&mut self) {
+        let mut ticks = self.rd32(BDA + 0x6C).wrapping_add(1);
+        if ticks >= TICKS_PER_DAY {
+            ticks = 0;
+            self.wr8(BDA + 0x70, 1);
+        }
+        self.wr32(BDA + 0x6C, ticks);
+        // Keys are not IRQ-driven on this controller; pick them up here so
+        // software reading the BDA buffer directly sees them.
+        self.poll_keyboard();
+        self.eoi();
+    }
+
+    fn int1a(&mut self) {
+        match self.ah() {
+            0x00 => {
+                let ticks = self.rd32(BDA + 0x6C);
+                self.set_reg16(GprIndex::Rcx, (ticks >> 16) as u16);
+                self.set_reg16(GprIndex::Rdx, ticks as u16);
+                let midnight = self.rd8(BDA + 0x70);
+                self.wr8(BDA + 0x70, 0);
+                self.set_al(midnight);
+            }
+            0x01 => {
+                let ticks = ((self.reg16(GprIndex::Rcx) as u32) << 16)
+                    | self.reg16(GprIndex::Rdx) as u32;
+                self.wr32(BDA + 0x6C, ticks);
+                self.wr8(BDA + 0x70, 0);
+            }
+            0x02 => {
+                let (hour, min, sec) = (self.cmos(0x04), self.cmos(0x02), self.cmos(0x00));
+                self.set_reg16(GprIndex::Rcx, ((hour as u16) << 8) | min as u16);
+                self.set_reg16(GprIndex::Rdx, (sec as u16) << 8);
+                self.set_return_flag(flags::CF, false);
+            }
+            0x04 => {
+                let (century, year) = (self.cmos(0x32), self.cmos(0x09));
+                let (month, day) = (self.cmos(0x08), self.cmos(0x07));
+                self.set_reg16(GprIndex::Rcx, ((century as u16) << 8) | year as u16);
+                self.set_reg16(GprIndex::Rdx, ((month as u16) << 8) | day as u16);
+                self.set_return_flag(flags::CF, false);
+            }
+            _ => self.set_return_flag(flags::CF, true),
+        }
+    }
+
+    fn cmos(&mut self, reg: u8) -> u8 {
+        self.port_out(0x70, reg);
+        self.port_in(0x71)
+    }
+
+    // ── INT 09h / 16h: keyboard ──
+
+    /// Drain pending scancodes from the PS/2 controller into the BDA.
+    fn poll_keyboard(&mut self) {
+        loop {
+            let status = self.port_in(0x64);
+            if status & 0x01 == 0 {
+                break;
+            }
+            let byte = self.port_in(0x60);
+            // Mouse bytes have no consumer without an INT 33h driver.
+            if status & 0x20 == 0 {
+                self.scancode(byte);
+            }
+        }
+    }
+
+    /// Decode one set 1 scancode byte, updating shift state and the key buffer.
+    fn scancode(&mut self, byte: u8) {
+        let kbd_status = self.rd8(BDA + 0x96);
+        if byte == 0xE0 {
+            self.wr8(BDA + 0x96, kbd_status | 0x02);
+            return;
+        }
+        let extended = kbd_status & 0x02 != 0;
+        self.wr8(BDA + 0x96, kbd_status & !0x02);
+
+        let release = byte & 0x80 != 0;
+        let code = byte & 0x7F;
+        let mut shift = self.rd8(BDA + 0x17);
+        let modifier = match code {
+            0x36 if !extended => 0x01,
+            0x2A if !extended => 0x02,
+            0x1D => 0x04,
+            0x38 => 0x08,
+            _ => 0,
+        };
+        if modifier != 0 {
+            if release {
+                shift &= !modifier;
+            } else {
+                shift |= modifier;
+            }
+            self.wr8(BDA + 0x17, shift);
+            return;
+        }
+        let toggle = match code {
+            0x46 => 0x10,
+            0x45 => 0x20,
+            0x3A => 0x40,
+            _ => 0,
+        };
+        if toggle != 0 {
+            if !release {
+                self.wr8(BDA + 0x17, shift ^ toggle);
+            }
+            return;
+        }
+        if release || (extended && code == 0x2A) {
+            return;
+        }
+
+        let shifted = shift & 0x03 != 0;
+        let ctrl = shift & 0x04 != 0;
+        let alt = shift & 0x08 != 0;
+        let mut ascii = match code {
+            0x1C | 0x35 if extended => KEYMAP[code as usize],
+            _ if extended => 0,
+            0x00..=0x39 if shifted => KEYMAP_SHIFT[code as usize],
+            0x00..=0x39 => KEYMAP[code as usize],
+            0x4A => b'-',
+            0x4E => b'+',
+            0x47..=0x53 if (shift & 0x20 != 0) != shifted => {
+                KEYMAP_KEYPAD[(code - 0x47) as usize]
+            }
+            _ => 0,
+        };
+        if ascii.is_ascii_alphabetic() && shift & 0x40 != 0 {
+            ascii ^= 0x20;
+        }
+        if alt {
+            ascii = 0;
+        } else if ctrl && ascii.is_ascii_alphabetic() {
+            ascii &= 0x1F;
+        }
+
+        let scan = match code {
+            0x57 => 0x85,
+            0x58 => 0x86,
+            _ => code,
+        };
+        let special = matches!(code, 0x3B..=0x44 | 0x47..=0x53 | 0x57 | 0x58);
+        if ascii != 0 || special || alt {
+            self.push_key(((scan as u16) << 8) | ascii as u16);
+        }
+    }
+
+    /// Append a keystroke to the BDA ring buffer. Returns false if full.
+    fn push_key(&mut self, key: u16) -> bool {
+        let (start, end) = (self.rd16(BDA + 0x80), self.rd16(BDA + 0x82));
+        let (head, tail) = (self.rd16(BDA + 0x1A), self.rd16(BDA + 0x1C));
+        let mut next = tail + 2;
+        if next >= end {
+            next = start;
+        }
+        if next == head {
+            return false;
+        }
+        self.wr16(BDA + tail as u64, key);
+        self.wr16(BDA + 0x1C, next);
+        true
+    }
+
+    /// Peek at the oldest keystroke in the BDA ring buffer.
+    fn peek_key(&self) -> Option<u16> {
+        let (head, tail) = (self.rd16(BDA + 0x1A), self.rd16(BDA + 0x1C));
+        if head == tail {
+            None
+        } else {
+            Some(self.rd16(BDA + head as u64))
+        }
+    }
+
+    /// Remove and return the oldest keystroke.
+    fn pop_key(&mut self) -> Option<u16> {
+        let key = self.peek_key()?;
+        let (start, end) = (self.rd16(BDA + 0x80), self.rd16(BDA + 0x82));
+        let mut head = self.rd16(BDA + 0x1A) + 2;
+        if head >= end {
+            head = start;
+        }
+        self.wr16(BDA + 0x1A, head);
+        Some(key)
+    }
+
+    fn int16(&mut self) -> Outcome {
+        self.poll_keyboard();
+        match self.ah() {
+            0x00 | 0x10 => match self.pop_key() {
+                Some(key) => self.set_reg16(GprIndex::Rax, key),
+                None => return Outcome::Wait,
+            },
+            0x01 | 0x11 => match self.peek_key() {
+                Some(key) => {
+                    self.set_reg16(GprIndex::Rax, key);
+                    self.set_return_flag(flags::ZF, false);
+                }
+                None => self.set_return_flag(flags::ZF, true),
+            },
+            0x02 => self.set_al(self.rd8(BDA + 0x17)),
+            0x12 => {
+                let ax = ((self.rd8(BDA + 0x18) as u16) << 8) | self.rd8(BDA + 0x17) as u16;
+                self.set_reg16(GprIndex::Rax, ax);
+            }
+            0x05 => {
+                let ok = self.push_key(self.reg16(GprIndex::Rcx));
+                self.set_al(!ok as u8);
+            }
+            _ => {}
+        }
+        Outcome::Done
+    }
+
+    // ── INT 10h: video ──
+
+    fn int10(&mut self) {
+        let bx = self.reg16(GprIndex::Rbx);
+        let cx = self.reg16(GprIndex::Rcx);
+        let dx = self.reg16(GprIndex::Rdx);
+        match self.ah() {
+            0x00 => self.set_video_mode(self.al()),
+            0x01 => {
+                self.wr16(BDA + 0x60, cx);
+                if let Some(svga) = self.svga.as_mut() {
+                    svga.crtc_regs[0x0A] = (cx >> 8) as u8;
+                    svga.crtc_regs[0x0B] = cx as u8;
+                }
+            }
+            0x02 => self.set_cursor((dx & 0xFF) as u8, (dx >> 8) as u8),
+            0x03 => {
+                let (col, row) = self.cursor();
+                self.set_reg16(GprIndex::Rdx, ((row as u16) << 8) | col as u16);
+                self.set_reg16(GprIndex::Rcx, self.rd16(BDA + 0x60));
+            }
+            0x05 => self.wr8(BDA + 0x62, self.al()),
+            0x06 | 0x07 => self.scroll(
+                self.ah() == 0x06,
+                self.al(),
+                (bx >> 8) as u8,
+                ((cx >> 8) as u8, cx as u8),
+                ((dx >> 8) as u8, dx as u8),
+            ),
+            0x08 => {
+                let (col, row) = self.cursor();
+                let cell = self.cell(col, row);
+                self.set_reg16(GprIndex::Rax, cell);
+            }
+            0x09 | 0x0A => {
+                let (col, row) = self.cursor();
+                let ch = self.al();
+                let attr = if self.ah() == 0x09 {
+                    bx as u8
+                } else {
+                    (self.cell(col, row) >> 8) as u8
+                };
+                let start = row as usize * 80 + col as usize;
+                for i in start..(start + cx as usize).min(80 * 25) {
+                    self.put_cell_index(i, ch, attr);
+                }
+            }
+            0x0E => self.teletype(self.al(), None),
+            0x0F => {
+                let cols = self.rd8(BDA + 0x4A);
+                let mode = self.rd8(BDA + 0x49);
+                self.set_reg16(GprIndex::Rax, ((cols as u16) << 8) | mode as u16);
+                let page = self.rd8(BDA + 0x62);
+                self.set_reg16(GprIndex::Rbx, (bx & 0x00FF) | ((page as u16) << 8));
+            }
+            0x12 if bx as u8 == 0x10 => {
+                // EGA/VGA info: color display, 256 KiB.
+                self.set_reg16(GprIndex::Rbx, 0x0003);
+                self.set_reg16(GprIndex::Rcx, 0x0009);
+            }
+            0x13 => self.write_string(),
+            0x1A if self.al() == 0x00 => {
+                self.set_al(0x1A);
+                self.set_reg16(GprIndex::Rbx, 0x0008);
+            }
+            0x4F => self.set_reg16(GprIndex::Rax, 0x014F),
+            _ => {}
+        }
+    }
+
+    fn set_video_mode(&mut self, al: u8) {
+        let mode = al & 0x7F;
+        let vga_mode = match mode {
+            0x12 => VgaMode::Graphics640x480x16,
+            0x13 => VgaMode::Graphics320x200x256,
+            _ => VgaMode::Text80x25,
+        };
+        let text = vga_mode == VgaMode::Text80x25;
+        if let Some(svga) = self.svga.as_mut() {
+            svga.set_mode(vga_mode);
+            if text && al & 0x80 == 0 {
+                svga.text_buffer.iter_mut().for_each(|cell| *cell = 0x0720);
+            }
+        }
+        self.wr8(BDA + 0x49, mode);
+        self.wr16(BDA + 0x4A, if mode == 0x13 { 40 } else { 80 });
+        self.wr16(BDA + 0x4C, 80 * 25 * 2);
+        self.wr16(BDA + 0x4E, 0);
+        self.wr16(BDA + 0x63, 0x3D4);
+        self.wr8(BDA + 0x84, 24);
+        self.wr16(BDA + 0x85, 16);
+        self.wr16(BDA + 0x60, 0x0607);
+        self.wr8(BDA + 0x62, 0);
+        self.set_cursor(0, 0);
+    }
+
+    fn cursor(&self) -> (u8, u8) {
+        let pos = self.rd16(BDA + 0x50);
+        (pos as u8, (pos >> 8) as u8)
+    }
+
+    fn set_cursor(&mut self, col: u8, row: u8) {
+        self.wr16(BDA + 0x50, ((row as u16) << 8) | col as u16);
+        if let Some(svga) = self.svga.as_mut() {
+            let pos = row as u16 * 80 + col as u16;
+            svga.crtc_regs[0x0E] = (pos >> 8) as u8;
+            svga.crtc_regs[0x0F] = pos as u8;
+        }
+    }
+
+    fn cell(&self, col: u8, row: u8) -> u16 {
+        let idx = row as usize * 80 + col as usize;
+        self.svga.as_ref().and_then(|svga| svga.text_buffer.get(idx).copied()).unwrap_or(0x0720)
+    }
+
+    fn put_cell_index(&mut self, idx: usize, ch: u8, attr: u8) {
+        if let Some(cell) = self.svga.as_mut().and_then(|svga| svga.text_buffer.get_mut(idx)) {
+            *cell = ((attr as u16) << 8) | ch as u16;
+        }
+    }
+
+    /// Scroll the window `top_left..=bottom_right` (row, col) by `lines`
+    /// (0 = clear), filling vacated rows with `attr`.
+    fn scroll(&mut self, up: bool, lines: u8, attr: u8, top_left: (u8, u8), bottom_right: (u8, u8)) {
+        let svga = match self.svga.as_mut() {
+            Some(svga) => svga,
+            None => return,
+        };
+        let (top, left) = (top_left.0 as usize, top_left.1 as usize);
+        let bottom = (bottom_right.0 as usize).min(24);
+        let right = (bottom_right.1 as usize).min(79);
+        if top > bottom || left > right {
+            return;
+        }
+        let height = bottom - top + 1;
+        let n = if lines == 0 || lines as usize > height { height } else { lines as usize };
+        let blank = ((attr as u16) << 8) | b' ' as u16;
+        let buf = &mut svga.text_buffer;
+        let mut copy_row = |dst: usize, src: Option<usize>| {
+            for col in left..=right {
+                buf[dst * 80 + col] = match src {
+                    Some(src) => buf[src * 80 + col],
+                    None => blank,
+                };
+            }
+        };
+        if up {
+            for row in top..=bottom {
+                copy_row(row, if row + n <= bottom { Some(row + n) } else { None });
+            }
+        } else {
+            for row in (top..=bottom).rev() {
+                copy_row(row, if row >= top + n { Some(row - n) } else { None });
+            }
+        }
+    }
+
+    /// Teletype output with cursor advance, wrapping and scrolling.
+    fn teletype(&mut self, ch: u8, attr: Option<u8>) {
+        let (mut col, mut row) = self.cursor();
+        match ch {
+            0x07 => {}
+            0x08 => col = col.saturating_sub(1),
+            b'\r' => col = 0,
+            b'\n' => row += 1,
+            _ => {
+                let attr = attr.unwrap_or_else(|| (self.cell(col, row) >> 8) as u8);
+                self.put_cell_index(row as usize * 80 + col as usize, ch, attr);
+                col += 1;
+                if col >= 80 {
+                    col = 0;
+                    row += 1;
+                }
+            }
+        }
+        if row >= 25 {
+            self.scroll(true, 1, 0x07, (0, 0), (24, 79));
+            row = 24;
+        }
+        self.set_cursor(col, row);
+    }
+
+    /// AH=13h — write string ES:BP (CX chars) at DH:DL.
+    fn write_string(&mut self) {
+        let mode = self.al();
+        let attr = self.reg16(GprIndex::Rbx) as u8;
+        let len = self.reg16(GprIndex::Rcx);
+        let dx = self.reg16(GprIndex::Rdx);
+        let bp = self.reg16(GprIndex::Rbp);
+        let saved = self.cursor();
+        self.set_cursor(dx as u8, (dx >> 8) as u8);
+        let step = if mode & 0x02 != 0 { 2 } else { 1 };
+        for i in 0..len {
+            let addr = self.linear(SegReg::Es, bp.wrapping_add(i * step));
+            let ch = self.rd8(addr);
+            let a = if step == 2 { self.rd8(addr + 1) } else { attr };
+            self.teletype(ch, Some(a));
+        }
+        if mode & 0x01 == 0 {
+            self.set_cursor(saved.0, saved.1);
+        }
+    }
+
+    /// Print a host string through the teletype path.
+    fn print(&mut self, s: &str) {
+        for &b in s.as_bytes() {
+            self.teletype(b, None);
+        }
+    }
+
+    // ── INT 13h: disk ──
+
+    /// Set AH and the BDA status byte to `status`; CF reflects failure.
+    fn disk_status(&mut self, status: u8) {
+        self.set_ah(status);
+        self.wr8(BDA + 0x74, status);
+        self.set_return_flag(flags::CF, status != DISK_OK);
+    }
+
+    /// (cylinders, total sectors) of drive 80h, if a disk is attached.
+    fn disk_geometry(&self) -> Option<(u64, u64)> {
+        let total = self.ide.as_ref()?.sector_count();
+        if total == 0 {
+            return None;
+        }
+        let cyls = (total / (DISK_HEADS * DISK_SPT)).clamp(1, 1024);
+        Some((cyls, total))
+    }
+
+    /// Move `count` sectors between the disk at `lba` and guest memory at `addr`.
+    fn transfer(&mut self, lba: u64, count: u16, addr: u64, write: bool) -> u8 {
+        let ide = match self.ide.as_mut() {
+            Some(ide) => ide,
+            None => return DISK_BAD_COMMAND,
+        };
+        if lba + count as u64 > ide.sector_count() {
+            return DISK_SECTOR_NOT_FOUND;
+        }
+        let mut sector = [0u8; SECTOR_SIZE];
+        for i in 0..count as u64 {
+            let at = addr + i * SECTOR_SIZE as u64;
+            if write {
+                let _ = self.engine.memory.read_bytes(at, &mut sector);
+                if !ide.write_image(lba + i, &sector) {
+                    return DISK_WRITE_FAULT;
+                }
+            } else {
+                if !ide.read_image(lba + i, &mut sector) {
+                    return DISK_READ_ERROR;
+                }
+                let _ = self.engine.memory.write_bytes(at, &sector);
+            }
+        }
+        DISK_OK
+    }
+
+    fn int13(&mut self) {
+        let dl = self.reg16(GprIndex::Rdx) as u8;
+        let (cyls, total) = match self.disk_geometry() {
+            Some(geometry) if dl == 0x80 => geometry,
+            _ => {
+                self.disk_status(DISK_BAD_COMMAND);
+                return;
+            }
+        };
+        match self.ah() {
+            0x00 | 0x04 | 0x0C | 0x0D | 0x10 | 0x11 | 0x14 => self.disk_status(DISK_OK),
+            0x01 => {
+                let last = self.rd8(BDA + 0x74);
+                self.disk_status(last);
+            }
+            0x02 | 0x03 => {
+                let write = self.ah() == 0x03;
+                let count = self.al() as u16;
+                let cx = self.reg16(GprIndex::Rcx);
+                let cyl = ((cx >> 8) | ((cx & 0xC0) << 2)) as u64;
+                let sector = (cx & 0x3F) as u64;
+                let head = (self.reg16(GprIndex::Rdx) >> 8) as u64;
+                if sector == 0 || sector > DISK_SPT || head >= DISK_HEADS {
+                    self.disk_status(DISK_SECTOR_NOT_FOUND);
+                    return;
+                }
+                let lba = (cyl * DISK_HEADS + head) * DISK_SPT + sector - 1;
+                let addr = self.linear(SegReg::Es, self.reg16(GprIndex::Rbx));
+                let status = self.transfer(lba, count, addr, write);
+                self.set_al(if status == DISK_OK { count as u8 } else { 0 });
+                self.disk_status(status);
+            }
+            0x08 => {
+                let max_cyl = (cyls - 1) as u16;
+                let cl = DISK_SPT as u16 | ((max_cyl >> 2) & 0xC0);
+                self.set_reg16(GprIndex::Rcx, ((max_cyl & 0xFF) << 8) | cl);
+                self.set_reg16(GprIndex::Rdx, (((DISK_HEADS - 1) as u16) << 8) | 1);
+                self.set_reg16(GprIndex::Rbx, 0);
+                self.set_al(0);
+                self.disk_status(DISK_OK);
+            }
+            0x15 => {
+                // Fixed disk present; CX:DX = sector count.
+                let sectors = total.min(u32::MAX as u64) as u32;
+                self.set_reg16(GprIndex::Rcx, (sectors >> 16) as u16);
+                self.set_reg16(GprIndex::Rdx, sectors as u16);
+                self.set_ah(0x03);
+                self.set_return_flag(flags::CF, false);
+            }
+            0x41 if self.reg16(GprIndex::Rbx) == 0x55AA => {
+                self.set_reg16(GprIndex::Rbx, 0xAA55);
+                self.set_reg16(GprIndex::Rcx, 0x0001);
+                self.set_ah(0x21);
+                self.set_return_flag(flags::CF, false);
+            }
+            0x42 | 0x43 => {
+                let write = self.ah() == 0x43;
+                let dap = self.linear(SegReg::Ds, self.reg16(GprIndex::Rsi));
+                let count = self.rd16(dap + 2);
+                let (off, seg) = (self.rd16(dap + 4), self.rd16(dap + 6));
+                let lba = self.rd64(dap + 8);
+                let addr = if off == 0xFFFF && seg == 0xFFFF && self.rd8(dap) >= 0x18 {
+                    self.rd64(dap + 0x10)
+                } else {
+                    ((seg as u64) << 4) + off as u64
+                };
+                let status = self.transfer(lba, count, addr, write);
+                if status != DISK_OK {
+                    self.wr16(dap + 2, 0);
+                }
+                self.disk_status(status);
+            }
+            0x48 => {
+                let buf = self.linear(SegReg::Ds, self.reg16(GprIndex::Rsi));
+                if self.rd16(buf) < 0x1A {
+                    self.disk_status(DISK_BAD_COMMAND);
+                    return;
+                }
+                self.wr16(buf, 0x1A);
+                self.wr16(buf + 2, 0x0002);
+                self.wr32(buf + 4, cyls as u32);
+                self.wr32(buf + 8, DISK_HEADS as u32);
+                self.wr32(buf + 12, DISK_SPT as u32);
+                self.wr64(buf + 16, total);
+                self.wr16(buf + 24, SECTOR_SIZE as u16);
+                self.disk_status(DISK_OK);
+            }
+            _ => self.disk_status(DISK_BAD_COMMAND),
+        }
+    }
+
+    // ── INT 15h: system services ──
+
+    fn int15(&mut self) {
+        let ram = self.engine.memory.ram().size() as u64;
+        let ext_kb = ram.saturating_sub(0x10_0000) / 1024;
+        match self.reg16(GprIndex::Rax) {
+            0xE820 if self.reg32(GprIndex::Rdx) == 0x534D_4150 => {
+                let mut map = [(0u64, 0u64, 0u32); 5];
+                let mut n = 0;
+                for entry in [
+                    (0, (BASE_MEM_KB as u64) * 1024, E820_RAM),
+                    ((BASE_MEM_KB as u64) * 1024, 0x400, E820_RESERVED),
+                    (ROM_BASE, ROM_SIZE as u64, E820_RESERVED),
+                    (0x10_0000, ram.saturating_sub(0x10_0000), E820_RAM),
+                    (0xFEC0_0000, 0x1000, E820_RESERVED),
+                ] {
+                    if entry.1 != 0 {
+                        map[n] = entry;
+                        n += 1;
+                    }
+                }
+                let index = self.reg32(GprIndex::Rbx) as usize;
+                if index >= n {
+                    self.set_return_flag(flags::CF, true);
+                    self.set_ah(0x86);
+                    return;
+                }
+                let (base, len, kind) = map[index];
+                let buf = self.linear(SegReg::Es, self.reg16(GprIndex::Rdi));
+                self.wr64(buf, base);
+                self.wr64(buf + 8, len);
+                self.wr32(buf + 16, kind);
+                self.set_reg32(GprIndex::Rax, 0x534D_4150);
+                self.set_reg32(GprIndex::Rcx, 20);
+                self.set_reg32(GprIndex::Rbx, if index + 1 < n { index as u32 + 1 } else { 0 });
+                self.set_return_flag(flags::CF, false);
+            }
+            0xE801 => {
+                let low = ext_kb.min(15 * 1024) as u16;
+                let high = (ram.saturating_sub(0x100_0000) / 0x10000).min(0xFFFF) as u16;
+                self.set_reg16(GprIndex::Rax, low);
+                self.set_reg16(GprIndex::Rcx, low);
+                self.set_reg16(GprIndex::Rbx, high);
+                self.set_reg16(GprIndex::Rdx, high);
+                self.set_return_flag(flags::CF, false);
+            }
+            ax @ (0x2400 | 0x2401) => {
+                // Disable / enable the A20 gate.
+                self.engine.mmu.a20_enabled = ax == 0x2401;
+                self.set_ah(0);
+                self.set_return_flag(flags::CF, false);
+            }
+            0x2402 => {
+                let status = self.engine.mmu.a20_enabled as u16;
+                self.set_reg16(GprIndex::Rax, status);
+                self.set_return_flag(flags::CF, false);
+            }
+            0x2403 => {
+                self.set_ah(0);
+                self.set_reg16(GprIndex::Rbx, 0x0003);
+                self.set_return_flag(flags::CF, false);
+            }
+            ax => match (ax >> 8) as u8 {
+                0x88 => {
+                    self.set_reg16(GprIndex::Rax, ext_kb.min(0xFFFF) as u16);
+                    self.set_return_flag(flags::CF, false);
+                }
+                0x86 => self.set_return_flag(flags::CF, false),
+                _ => {
+                    self.set_ah(0x86);
+                    self.set_return_flag(flags::CF, true);
+                }
+            },
+        }
+    }
+}
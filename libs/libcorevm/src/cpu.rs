@@ -7,7 +7,7 @@
 
 use crate::decoder::{CpuMode, Decoder};
 use crate::error::{Result, VmError};
-use crate::fpu_state::FpuState;
+use crate::fpu_state::{FpuAccuracy, FpuState};
 use crate::interrupts::InterruptController;
 use crate::io::IoDispatch;
 use crate::memory::{AccessType, GuestMemory, MemoryBus, Mmu};
@@ -41,6 +41,18 @@ pub enum ExitReason {
     Breakpoint,
     /// External stop request via `request_stop()`.
     StopRequested,
+    /// Guest powered itself off (ACPI sleep state S5).
+    Shutdown,
+    /// Guest pulsed the reset line (keyboard controller or reset control register).
+    Reset,
+    /// A built-in BIOS stub is waiting for its service to be performed.
+    BiosTrap,
+    /// A watch expression changed value (see [`crate::debug`]).
+    Watchpoint,
+    /// Guest wrote its exit status to the test-exit port.
+    GuestExit(u16),
+    /// An unhandled device access waits for the host (see [`crate::ioexit`]).
+    IoRequest,
 }
 
 /// Virtual x86 CPU.
@@ -49,6 +61,8 @@ pub struct Cpu {
     pub regs: RegisterFile,
     /// x87 FPU state.
     pub fpu: FpuState,
+    /// Accuracy mode for x87 transcendentals (preserved across reset).
+    pub fpu_accuracy: FpuAccuracy,
     /// SSE register state.
     pub sse: SseState,
     /// Instruction decoder.
@@ -59,8 +73,6 @@ pub struct Cpu {
     pub instruction_count: u64,
     /// If true, stop at the next instruction boundary.
     stop_requested: bool,
-    /// A20 gate enabled (address line 20 masking for real-mode compat).
-    pub a20_enabled: bool,
     /// RIP at the start of the last successfully decoded instruction.
     pub last_exec_rip: u64,
     /// CS selector at the start of the last decoded instruction.
@@ -69,6 +81,13 @@ pub struct Cpu {
     pub last_opcode: u16,
     /// Physical address of the last decoded instruction.
     pub last_fetch_addr: u64,
+    /// Symbols and watches owned by the VM instance (null when unused).
+    pub debug: *mut crate::debug::Debugger,
+    /// I/O exit state owned by the VM instance (null when unused).
+    pub io_exit: *mut crate::ioexit::IoExit,
+    /// Host handler and userdata for MSRs corevm does not implement
+    /// (preserved across reset).
+    pub msr_handler: Option<(crate::msr::MsrCb, u64)>,
 }
 
 impl Cpu {
@@ -77,16 +96,19 @@ impl Cpu {
         Cpu {
             regs: RegisterFile::new(),
             fpu: FpuState::new(),
+            fpu_accuracy: FpuAccuracy::Precise,
             sse: SseState::new(),
             decoder: Decoder::new(CpuMode::Real16),
             mode: Mode::RealMode,
             instruction_count: 0,
             stop_requested: false,
-            a20_enabled: true,
             last_exec_rip: 0,
             last_exec_cs: 0,
             last_opcode: 0,
             last_fetch_addr: 0,
+            debug: core::ptr::null_mut(),
+            io_exit: core::ptr::null_mut(),
+            msr_handler: None,
         }
     }
 
@@ -105,6 +127,15 @@ impl Cpu {
         self.last_fetch_addr = 0;
     }
 
+    /// Symbol annotation for `addr` in diagnostics (empty without a symbol map).
+    pub fn annotate(&self, addr: u64) -> crate::debug::Annotation<'_> {
+        if self.debug.is_null() {
+            crate::debug::Annotation::none(addr)
+        } else {
+            unsafe { (*self.debug).symbols.annotate(addr) }
+        }
+    }
+
     /// Request the CPU to stop at the next instruction boundary.
     pub fn request_stop(&mut self) {
         self.stop_requested = true;
@@ -211,14 +242,15 @@ impl Cpu {
         mmu: &Mmu,
     ) -> Result<()> {
         if (selector & 0xFFFC) == 0 {
-            // Null selector — allowed for data segments, not CS/SS.
+            // Null selector — allowed for data segments, not CS/SS. The
+            // cached limit is left alone, so a later real-mode load of the
+            // register does not inherit a zero limit.
             if matches!(seg, SegReg::Cs | SegReg::Ss) {
                 return Err(VmError::GeneralProtection(0));
             }
             let desc = &mut self.regs.seg[seg as usize];
             desc.selector = selector;
             desc.base = 0;
-            desc.limit = 0;
             desc.present = false;
             desc.is_code = false;
             desc.readable = false;
@@ -284,8 +316,12 @@ impl Cpu {
             // Sync MMU state from control registers (fast-path: skips if unchanged).
             mmu.update_from_regs(self.regs.cr0, self.regs.cr4, self.regs.efer);
 
+            // An instruction restarted after an I/O exit finishes first.
+            let restarting = !self.io_exit.is_null() && unsafe { (*self.io_exit).restarting() };
+
             // Check pending interrupts (only if IF=1 and no interrupt shadow)
-            if let Some(vector) = interrupts.pending_interrupt(self.regs.rflags) {
+            let pending = if restarting { None } else { interrupts.pending_interrupt(self.regs.rflags) };
+            if let Some(vector) = pending {
                 interrupts.acknowledge(vector);
                 if let Err(e) = self.deliver_interrupt(vector, false, None, memory, mmu, interrupts)
                 {
@@ -302,14 +338,7 @@ impl Cpu {
             let cs = &self.regs.seg[SegReg::Cs as usize];
             let fetch_addr = cs.base.wrapping_add(self.regs.rip);
 
-            // Apply A20 gate masking
-            let fetch_addr = if !self.a20_enabled {
-                fetch_addr & !0x10_0000 // Clear bit 20
-            } else {
-                fetch_addr
-            };
-
-            // Translate through paging if enabled
+            // Translate through paging if enabled (and the A20 gate)
             let phys_addr = match mmu.translate_linear(
                 fetch_addr,
                 self.regs.cr3,
@@ -360,9 +389,9 @@ impl Cpu {
                     let b4 = memory.read_u8(phys_addr + 4).unwrap_or(0xFF);
                     let b5 = memory.read_u8(phys_addr + 5).unwrap_or(0xFF);
                     libsyscall::serial_print(format_args!(
-                        "[corevm] #UD at CS:IP={:04X}:{:X} phys={:X} bytes=[{:02X} {:02X} {:02X} {:02X} {:02X} {:02X}]\n",
+                        "[corevm] #UD at CS:IP={:04X}:{:X}{} phys={:X} bytes=[{:02X} {:02X} {:02X} {:02X} {:02X} {:02X}]\n",
                         self.regs.seg[SegReg::Cs as usize].selector,
-                        self.regs.rip, phys_addr,
+                        self.regs.rip, self.annotate(self.regs.rip), phys_addr,
                         b0, b1, b2, b3, b4, b5,
                     ));
                     let ud = VmError::UndefinedOpcode(b0);
@@ -378,9 +407,22 @@ impl Cpu {
             self.last_opcode = inst.opcode;
 
             // Execute the decoded instruction
-            match crate::executor::execute(self, &inst, memory, mmu, io, interrupts) {
+            if !self.io_exit.is_null() {
+                unsafe { (*self.io_exit).begin() };
+            }
+            let result = crate::executor::execute(self, &inst, memory, mmu, io, interrupts);
+            if !self.io_exit.is_null() {
+                unsafe { (*self.io_exit).end(&result) };
+            }
+            match result {
                 Ok(()) => {
                     self.instruction_count += 1;
+                    if !self.debug.is_null() {
+                        let dbg = unsafe { &mut *self.debug };
+                        if dbg.check(self.last_exec_rip, &self.regs, memory, mmu) {
+                            return ExitReason::Watchpoint;
+                        }
+                    }
                 }
                 Err(VmError::Halted) => {
                     self.instruction_count += 1;
@@ -390,6 +432,27 @@ impl Cpu {
                     self.instruction_count += 1;
                     return ExitReason::Breakpoint;
                 }
+                Err(VmError::ShutdownRequested) => {
+                    self.instruction_count += 1;
+                    return ExitReason::Shutdown;
+                }
+                Err(VmError::ResetRequested) => {
+                    self.instruction_count += 1;
+                    return ExitReason::Reset;
+                }
+                Err(VmError::GuestExit(status)) => {
+                    self.instruction_count += 1;
+                    return ExitReason::GuestExit(status);
+                }
+                Err(VmError::BiosTrap) => {
+                    self.instruction_count += 1;
+                    return ExitReason::BiosTrap;
+                }
+                Err(VmError::IoExit) => {
+                    // Not retired: the instruction restarts once the host
+                    // has completed the access.
+                    return ExitReason::IoRequest;
+                }
                 Err(ref e) => {
                     use crate::memory::MemoryBus;
                     let b0 = memory.read_u8(phys_addr).unwrap_or(0xFF);
@@ -397,9 +460,10 @@ impl Cpu {
                     let b2 = memory.read_u8(phys_addr + 2).unwrap_or(0xFF);
                     let b3 = memory.read_u8(phys_addr + 3).unwrap_or(0xFF);
                     libsyscall::serial_print(format_args!(
-                        "[corevm] exec error at CS:IP={:04X}:{:X} phys={:X} opcode=0x{:04X} bytes=[{:02X} {:02X} {:02X} {:02X}] modrm_reg={} CS.base={:X}: {:?}\n",
+                        "[corevm] exec error at CS:IP={:04X}:{:X}{} phys={:X} opcode=0x{:04X} bytes=[{:02X} {:02X} {:02X} {:02X}] modrm_reg={} CS.base={:X}: {:?}\n",
                         self.regs.seg[SegReg::Cs as usize].selector,
                         self.last_exec_rip,
+                        self.annotate(self.last_exec_rip),
                         phys_addr,
                         inst.opcode,
                         b0, b1, b2, b3,
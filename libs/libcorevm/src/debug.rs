@@ -0,0 +1,315 @@
+//! Guest symbol map and watch expressions for the trace/debug layer.
+//!
+//! A [`SymbolMap`] holds address → name pairs loaded from the host (the
+//! text output of `nm`, or plain `address name` lines). Exception dumps,
+//! breakpoint messages and the CPU's diagnostic prints annotate RIP values
+//! with `<symbol+0xoffset>`, and hosts can resolve the RIPs of I/O trace
+//! records through `corevm_symbol_lookup`.
+//!
+//! Watches are evaluated after every executed instruction. Each watch
+//! observes a register or a 1/2/4/8-byte memory location; when its value
+//! differs from the last one seen, the CPU stops with
+//! [`ExitReason::Watchpoint`](crate::cpu::ExitReason::Watchpoint) and the
+//! change is kept as a [`WatchHit`]. Memory watches read guest RAM only, so
+//! watching an MMIO address never triggers device side effects.
+//!
+//! The [`Debugger`] is owned by `VmInstance` and shared with the CPU through
+//! a raw pointer (same ownership pattern as the I/O tracer); the execution
+//! loop costs one null-pointer check per instruction while none exists.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::memory::{AccessType, GuestMemory, MemoryBus, Mmu};
+use crate::registers::RegisterFile;
+
+/// Watch kind: register (target = one of the `REG_*` indices).
+pub const WATCH_REGISTER: u32 = 0;
+/// Watch kind: memory at a linear (virtual) address, translated through
+/// the guest's current page tables on every check.
+pub const WATCH_LINEAR: u32 = 1;
+/// Watch kind: memory at a guest physical address.
+pub const WATCH_PHYSICAL: u32 = 2;
+
+/// Register watch targets 0–15 are RAX..R15; these follow them.
+pub const REG_RFLAGS: u64 = 16;
+pub const REG_CR0: u64 = 17;
+pub const REG_CR2: u64 = 18;
+pub const REG_CR3: u64 = 19;
+pub const REG_CR4: u64 = 20;
+
+/// Maximum number of simultaneous watches.
+pub const MAX_WATCHES: usize = 16;
+
+/// Details of the watch that stopped the CPU (48 bytes, C layout).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WatchHit {
+    /// Id returned when the watch was added.
+    pub id: u32,
+    /// One of the `WATCH_*` constants.
+    pub kind: u32,
+    /// Register index or address.
+    pub target: u64,
+    /// Value before the instruction.
+    pub old: u64,
+    /// Value after the instruction.
+    pub new: u64,
+    /// RIP of the instruction that changed the value.
+    pub rip: u64,
+    /// Access size in bytes (0 for registers).
+    pub size: u32,
+    pub _reserved: u32,
+}
+
+struct Symbol {
+    addr: u64,
+    name: String,
+}
+
+/// Guest symbols sorted by address.
+pub struct SymbolMap {
+    symbols: Vec<Symbol>,
+}
+
+impl SymbolMap {
+    pub fn new() -> Self {
+        SymbolMap { symbols: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.symbols.clear();
+    }
+
+    /// Add one symbol, keeping the map sorted. A symbol at an address that
+    /// already has one replaces it.
+    pub fn insert(&mut self, addr: u64, name: &str) {
+        let sym = Symbol { addr, name: String::from(name) };
+        match self.symbols.binary_search_by_key(&addr, |s| s.addr) {
+            Ok(i) => self.symbols[i] = sym,
+            Err(i) => self.symbols.insert(i, sym),
+        }
+    }
+
+    /// Parse a text symbol map and add its entries. Accepted line forms:
+    ///
+    /// ```text
+    /// ffffffff80001000 kmain
+    /// ffffffff80001000 T kmain
+    /// ffffffff80001000 0000000000000040 T kmain
+    /// ```
+    ///
+    /// Addresses are hex with an optional `0x` prefix. Blank lines, `#`
+    /// comments and lines without an address (undefined `nm` symbols) are
+    /// skipped. Returns the number of symbols added.
+    pub fn load(&mut self, text: &[u8]) -> usize {
+        let mut parsed = Vec::new();
+        for line in text.split(|&b| b == b'\n') {
+            let line = match core::str::from_utf8(line) {
+                Ok(s) => s.trim(),
+                Err(_) => continue,
+            };
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let addr = match fields.next().and_then(parse_hex) {
+                Some(a) => a,
+                None => continue,
+            };
+            let name = match fields.last() {
+                Some(n) => n,
+                None => continue,
+            };
+            parsed.push(Symbol { addr, name: String::from(name) });
+        }
+        let added = parsed.len();
+        self.symbols.append(&mut parsed);
+        // Reverse before the stable sort so the latest entry for an address
+        // comes first and survives `dedup`, as with `insert`.
+        self.symbols.reverse();
+        self.symbols.sort_by_key(|s| s.addr);
+        self.symbols.dedup_by_key(|s| s.addr);
+        added
+    }
+
+    /// Nearest symbol at or below `addr`, with the offset into it.
+    pub fn lookup(&self, addr: u64) -> Option<(&str, u64)> {
+        let i = self.symbols.partition_point(|s| s.addr <= addr);
+        if i == 0 {
+            return None;
+        }
+        let sym = &self.symbols[i - 1];
+        Some((&sym.name, addr - sym.addr))
+    }
+
+    /// Display adapter printing ` <name+0xoff>` for `addr`, or nothing when
+    /// no symbol covers it.
+    pub fn annotate(&self, addr: u64) -> Annotation<'_> {
+        Annotation { map: Some(self), addr }
+    }
+}
+
+/// Symbol annotation for one address; see [`SymbolMap::annotate`].
+pub struct Annotation<'a> {
+    map: Option<&'a SymbolMap>,
+    addr: u64,
+}
+
+impl Annotation<'_> {
+    /// An annotation that prints nothing (no symbol map loaded).
+    pub fn none(addr: u64) -> Self {
+        Annotation { map: None, addr }
+    }
+}
+
+impl fmt::Display for Annotation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.map.and_then(|m| m.lookup(self.addr)) {
+            Some((name, 0)) => write!(f, " <{}>", name),
+            Some((name, off)) => write!(f, " <{}+0x{:X}>", name, off),
+            None => Ok(()),
+        }
+    }
+}
+
+fn parse_hex(s: &str) -> Option<u64> {
+    let s = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+    u64::from_str_radix(s, 16).ok()
+}
+
+struct Watch {
+    id: u32,
+    kind: u32,
+    target: u64,
+    size: u8,
+    /// Last value seen; `None` while a memory watch is unreadable.
+    last: Option<u64>,
+}
+
+/// Symbols and watches of one VM.
+pub struct Debugger {
+    pub symbols: SymbolMap,
+    watches: Vec<Watch>,
+    next_id: u32,
+    hit: Option<WatchHit>,
+}
+
+impl Debugger {
+    pub fn new() -> Self {
+        Debugger { symbols: SymbolMap::new(), watches: Vec::new(), next_id: 1, hit: None }
+    }
+
+    /// Add a watch, capturing its current value as the baseline. Returns the
+    /// watch id, or `None` if the table is full or the watch is invalid.
+    pub fn add_watch(
+        &mut self,
+        kind: u32,
+        target: u64,
+        size: u8,
+        regs: &RegisterFile,
+        memory: &GuestMemory,
+        mmu: &Mmu,
+    ) -> Option<u32> {
+        let valid = match kind {
+            WATCH_REGISTER => target <= REG_CR4,
+            WATCH_LINEAR | WATCH_PHYSICAL => matches!(size, 1 | 2 | 4 | 8),
+            _ => false,
+        };
+        if !valid || self.watches.len() >= MAX_WATCHES {
+            return None;
+        }
+        let size = if kind == WATCH_REGISTER { 0 } else { size };
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        let mut watch = Watch { id, kind, target, size, last: None };
+        watch.last = read_watch(&watch, regs, memory, mmu);
+        self.watches.push(watch);
+        Some(id)
+    }
+
+    /// Remove a watch by id. Returns `false` if no such watch exists.
+    pub fn remove_watch(&mut self, id: u32) -> bool {
+        let before = self.watches.len();
+        self.watches.retain(|w| w.id != id);
+        self.watches.len() != before
+    }
+
+    pub fn clear_watches(&mut self) {
+        self.watches.clear();
+        self.hit = None;
+    }
+
+    /// The change that caused the last watchpoint exit.
+    pub fn last_hit(&self) -> Option<WatchHit> {
+        self.hit
+    }
+
+    /// Re-evaluate all watches after the instruction at `rip`. Returns `true`
+    /// (and records the hit) for the first watch whose value changed; every
+    /// watch takes its new value as the baseline so resuming continues.
+    pub fn check(&mut self, rip: u64, regs: &RegisterFile, memory: &GuestMemory, mmu: &Mmu) -> bool {
+        if self.watches.is_empty() {
+            return false;
+        }
+        let mut hit = None;
+        for w in self.watches.iter_mut() {
+            let now = read_watch(w, regs, memory, mmu);
+            if let (Some(old), Some(new)) = (w.last, now) {
+                if old != new && hit.is_none() {
+                    hit = Some(WatchHit {
+                        id: w.id,
+                        kind: w.kind,
+                        target: w.target,
+                        old,
+                        new,
+                        rip,
+                        size: w.size as u32,
+                        _reserved: 0,
+                    });
+                }
+            }
+            if now.is_some() {
+                w.last = now;
+            }
+        }
+        if hit.is_some() {
+            self.hit = hit;
+            return true;
+        }
+        false
+    }
+}
+
+/// Current value of a watch, or `None` if its memory is not mapped or not RAM.
+fn read_watch(w: &Watch, regs: &RegisterFile, memory: &GuestMemory, mmu: &Mmu) -> Option<u64> {
+    match w.kind {
+        WATCH_REGISTER => Some(match w.target {
+            t if t < 16 => regs.gpr[t as usize],
+            REG_RFLAGS => regs.rflags,
+            REG_CR0 => regs.cr0,
+            REG_CR2 => regs.cr2,
+            REG_CR3 => regs.cr3,
+            _ => regs.cr4,
+        }),
+        _ => {
+            let phys = if w.kind == WATCH_LINEAR {
+                mmu.translate_linear(w.target, regs.cr3, AccessType::Read, 0, memory).ok()?
+            } else {
+                w.target
+            };
+            let ram = memory.ram();
+            match w.size {
+                1 => ram.read_u8(phys).ok().map(|v| v as u64),
+                2 => ram.read_u16(phys).ok().map(|v| v as u64),
+                4 => ram.read_u32(phys).ok().map(|v| v as u64),
+                _ => ram.read_u64(phys).ok(),
+            }
+        }
+    }
+}
@@ -319,6 +319,13 @@ impl<'m> DecodeCursor<'m> {
                 }
             }
         }
+
+        // Byte-form opcodes (r/m8, r8, AL, imm8 destinations) ignore the
+        // mode default entirely. The executor reads and writes registers and
+        // I/O ports at `operand_size`, so it must reflect the 8-bit width.
+        if is_byte_op(self.inst.opcode_map, self.inst.opcode) {
+            self.inst.operand_size = OperandSize::Byte;
+        }
     }
 
     // ===================================================================
@@ -1901,6 +1908,49 @@ fn is_default_64(opcode: u16) -> bool {
     )
 }
 
+/// Returns `true` for opcodes whose destination operand is 8 bits wide.
+///
+/// These are the even "Eb/Gb/AL" forms of the ALU, MOV, TEST, XCHG, shift,
+/// group 3/4, string and port I/O instructions, plus SETcc, CMPXCHG r/m8
+/// and XADD r/m8 in the two-byte map. MOVZX/MOVSX are excluded since only
+/// their source is byte-sized.
+fn is_byte_op(map: OpcodeMap, opcode: u16) -> bool {
+    match map {
+        OpcodeMap::Primary => matches!(
+            opcode as u8,
+            // ALU r/m8,r8 / r8,r/m8 / AL,imm8
+            0x00 | 0x02 | 0x04 | 0x08 | 0x0A | 0x0C |
+            0x10 | 0x12 | 0x14 | 0x18 | 0x1A | 0x1C |
+            0x20 | 0x22 | 0x24 | 0x28 | 0x2A | 0x2C |
+            0x30 | 0x32 | 0x34 | 0x38 | 0x3A | 0x3C |
+            // INSB / OUTSB
+            0x6C | 0x6E |
+            // Group 1 r/m8, imm8
+            0x80 | 0x82 |
+            // TEST / XCHG / MOV r/m8, r8 and MOV r8, r/m8
+            0x84 | 0x86 | 0x88 | 0x8A |
+            // MOV AL, moffs / MOV moffs, AL
+            0xA0 | 0xA2 |
+            // MOVSB / CMPSB / TEST AL, imm8 / STOSB / LODSB / SCASB
+            0xA4 | 0xA6 | 0xA8 | 0xAA | 0xAC | 0xAE |
+            // MOV r8, imm8
+            0xB0..=0xB7 |
+            // Group 2 r/m8 / MOV r/m8, imm8
+            0xC0 | 0xC6 | 0xD0 | 0xD2 |
+            // IN AL / OUT AL
+            0xE4 | 0xE6 | 0xEC | 0xEE |
+            // Group 3 r/m8 / Group 4 r/m8
+            0xF6 | 0xFE
+        ),
+        OpcodeMap::Secondary => matches!(
+            opcode as u8,
+            // SETcc r/m8 / CMPXCHG r/m8, r8 / XADD r/m8, r8
+            0x90..=0x9F | 0xB0 | 0xC0
+        ),
+        _ => false,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -2126,6 +2176,26 @@ mod tests {
         assert_eq!(inst.modrm_reg() & 7, 0);
     }
 
+    #[test]
+    fn test_byte_ops_use_byte_operand_size() {
+        // MOV AH, 0x0E in real mode must not widen to a 16-bit write.
+        let mem = TestMemory::new(&[0xB4, 0x0E]);
+        let dec = Decoder::new(CpuMode::Real16);
+        let inst = dec.decode(&mem, 0).unwrap();
+        assert_eq!(inst.length, 2);
+        assert_eq!(inst.operand_size, OperandSize::Byte);
+
+        // IN AL, DX
+        let mem = TestMemory::new(&[0xEC]);
+        let inst = Decoder::new(CpuMode::Protected32).decode(&mem, 0).unwrap();
+        assert_eq!(inst.operand_size, OperandSize::Byte);
+
+        // MOVZX keeps the destination width.
+        let mem = TestMemory::new(&[0x0F, 0xB6, 0x01]);
+        let inst = Decoder::new(CpuMode::Protected32).decode(&mem, 0).unwrap();
+        assert_eq!(inst.operand_size, OperandSize::Dword);
+    }
+
     #[test]
     fn test_movzx() {
         // MOVZX EAX, BYTE [ECX]: 0F B6 01
@@ -0,0 +1,129 @@
+//! Minimal ACPI power management register block (PIIX4 PM1a).
+//!
+//! Provides just enough of the fixed-hardware PM1a registers for a guest
+//! to power itself off: writing SLP_TYP=S5 together with SLP_EN to the
+//! PM1a control register makes the write fail with
+//! [`VmError::ShutdownRequested`], which the CPU run loop turns into
+//! [`ExitReason::Shutdown`](crate::cpu::ExitReason::Shutdown).
+//!
+//! # I/O Ports (relative to the PM base)
+//!
+//! | Offset | Width | Description |
+//! |--------|-------|-------------|
+//! | +0 | 16-bit | PM1a_STS — status (write 1 to clear) |
+//! | +2 | 16-bit | PM1a_EN — event enable |
+//! | +4 | 16-bit | PM1a_CNT — control (SCI_EN, SLP_TYP, SLP_EN) |
+//!
+//! The block is registered at 0x600 (the PIIX4 PMBA programmed by SeaBIOS
+//! and advertised in QEMU's FADT) and at 0xB000, the legacy Bochs/QEMU
+//! base that some kernels write to directly.
+
+use crate::error::{Result, VmError};
+use crate::io::IoHandler;
+
+/// PM1_CNT: sleep type field (bits 10-12).
+const CNT_SLP_TYP_MASK: u16 = 0x7 << 10;
+/// PM1_CNT: sleep enable — writing 1 enters the state in SLP_TYP.
+const CNT_SLP_EN: u16 = 1 << 13;
+/// SLP_TYP value for soft-off (S5), as declared by the `\_S5` package
+/// in QEMU's PIIX4 DSDT.
+const SLP_TYP_S5: u16 = 0;
+
+/// ACPI PM1a event and control registers.
+#[derive(Debug)]
+pub struct AcpiPm {
+    /// PM1a_STS — event status bits.
+    status: u16,
+    /// PM1a_EN — event enable bits.
+    enable: u16,
+    /// PM1a_CNT — control register (SLP_EN is write-only and never stored).
+    control: u16,
+}
+
+impl AcpiPm {
+    /// Create the PM block in its power-on state (legacy mode, no events).
+    pub fn new() -> Self {
+        AcpiPm {
+            status: 0,
+            enable: 0,
+            control: 0,
+        }
+    }
+
+    /// Return all registers to their power-on values.
+    pub fn reset(&mut self) {
+        *self = AcpiPm::new();
+    }
+
+    /// Read the 16-bit register at `offset` (0, 2 or 4).
+    fn reg16(&self, offset: u16) -> u16 {
+        match offset {
+            0 => self.status,
+            2 => self.enable,
+            4 => self.control,
+            _ => 0,
+        }
+    }
+
+    /// Write the 16-bit register at `offset`.
+    fn write_reg16(&mut self, offset: u16, val: u16) -> Result<()> {
+        match offset {
+            0 => self.status &= !val,
+            2 => self.enable = val,
+            4 => {
+                self.control = val & !CNT_SLP_EN;
+                if val & CNT_SLP_EN != 0 {
+                    let slp_typ = (val & CNT_SLP_TYP_MASK) >> 10;
+                    if slp_typ == SLP_TYP_S5 {
+                        return Err(VmError::ShutdownRequested);
+                    }
+                    // S1-S4 are not supported; the guest simply keeps running.
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl IoHandler for AcpiPm {
+    /// Read PM1a registers. Byte reads of the high half of a register
+    /// are supported; unimplemented offsets read as zero.
+    fn read(&mut self, port: u16, size: u8) -> Result<u32> {
+        let offset = port & 0xF;
+        let reg = self.reg16(offset & !1) as u32;
+        let val = match (size, offset & 1) {
+            (1, 0) => reg & 0xFF,
+            (1, _) => reg >> 8,
+            (2, _) => reg,
+            _ => reg | ((self.reg16((offset & !1) + 2) as u32) << 16),
+        };
+        Ok(val)
+    }
+
+    /// Write PM1a registers.
+    ///
+    /// Byte writes are merged into the containing 16-bit register, so a
+    /// guest writing only the high byte of PM1a_CNT (where SLP_EN lives)
+    /// still triggers the sleep transition.
+    fn write(&mut self, port: u16, size: u8, val: u32) -> Result<()> {
+        let offset = port & 0xF;
+        let base = offset & !1;
+        match size {
+            1 => {
+                let cur = if base == 0 { 0 } else { self.reg16(base) };
+                let merged = if offset & 1 == 0 {
+                    (cur & 0xFF00) | (val as u16 & 0xFF)
+                } else {
+                    (cur & 0x00FF) | ((val as u16 & 0xFF) << 8)
+                };
+                self.write_reg16(base, merged)
+            }
+            2 => self.write_reg16(base, val as u16),
+            _ => {
+                self.write_reg16(base, val as u16)?;
+                self.write_reg16(base + 2, (val >> 16) as u16)
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Memory balloon — lets the host reclaim guest RAM at run time.
+//!
+//! The host sets a target balloon size with
+//! [`corevm_balloon_set_target`](crate::corevm_balloon_set_target). A guest
+//! driver polls the target and inflates the balloon by allocating pages
+//! from its own free memory and handing their frame numbers to the device;
+//! the device discards them from guest RAM, so their backing chunks can be
+//! freed (see [`FlatMemory`]). Deflating returns pages to the guest, which
+//! then read as zero.
+//!
+//! The guest must not touch a page between inflating and deflating it.
+//!
+//! # I/O Ports (relative to the balloon base, 32-bit registers)
+//!
+//! | Offset | Access | Description |
+//! |--------|--------|-------------|
+//! | +0x00 | R | Signature `"BALN"` (0x4E4C4142) |
+//! | +0x04 | R | Target balloon size in 4 KiB pages |
+//! | +0x08 | R | Pages currently held by the balloon |
+//! | +0x0C | W | Inflate: PFN of a page the guest gives up |
+//! | +0x10 | W | Deflate: PFN of a page the guest takes back |
+//! | +0x14 | R | Status: bit 0 = target changed since last read (cleared on read) |
+//!
+//! The standard base is 0x5A0; the block is 0x20 ports long and must be
+//! 0x20-aligned.
+
+use crate::error::Result;
+use crate::io::IoHandler;
+use crate::memory::FlatMemory;
+
+/// Value of the signature register: ASCII `"BALN"`.
+pub const SIGNATURE: u32 = 0x4E4C_4142;
+/// Number of I/O ports decoded by the device.
+pub const PORT_COUNT: u16 = 0x20;
+
+const REG_SIGNATURE: u16 = 0x00;
+const REG_TARGET: u16 = 0x04;
+const REG_ACTUAL: u16 = 0x08;
+const REG_INFLATE: u16 = 0x0C;
+const REG_DEFLATE: u16 = 0x10;
+const REG_STATUS: u16 = 0x14;
+
+/// Status bit: the target changed since the guest last read the status.
+const STATUS_TARGET_CHANGED: u32 = 1 << 0;
+
+/// Balloon device state.
+pub struct Balloon {
+    /// Guest RAM the inflated pages are discarded from. Points into the
+    /// owning `VmInstance`'s engine and is valid for the device's lifetime.
+    ram: *mut FlatMemory,
+    /// Requested balloon size in pages.
+    target: u32,
+    /// Pages currently held by the balloon.
+    actual: u32,
+    /// Pending status bits.
+    status: u32,
+}
+
+impl Balloon {
+    /// Create an empty balloon over `ram`.
+    pub fn new(ram: *mut FlatMemory) -> Self {
+        Balloon {
+            ram,
+            target: 0,
+            actual: 0,
+            status: 0,
+        }
+    }
+
+    /// Set the balloon size the guest should reach, in 4 KiB pages. Clamped
+    /// to the guest RAM size.
+    pub fn set_target(&mut self, pages: u32) {
+        let max = unsafe { (*self.ram).size() / crate::memory::flat::PAGE_SIZE } as u32;
+        let pages = pages.min(max);
+        if pages != self.target {
+            self.target = pages;
+            self.status |= STATUS_TARGET_CHANGED;
+        }
+    }
+
+    /// Return all pages to the guest, keeping the target. Used on VM reset,
+    /// where the guest driver starts over with an empty balloon.
+    pub fn reset(&mut self) {
+        unsafe { (*self.ram).reclaim_all() };
+        self.actual = 0;
+        self.status = if self.target != 0 { STATUS_TARGET_CHANGED } else { 0 };
+    }
+
+    /// Requested balloon size in pages.
+    pub fn target(&self) -> u32 {
+        self.target
+    }
+
+    /// Pages currently held by the balloon.
+    pub fn actual(&self) -> u32 {
+        self.actual
+    }
+}
+
+impl IoHandler for Balloon {
+    fn read(&mut self, port: u16, _size: u8) -> Result<u32> {
+        let val = match port & (PORT_COUNT - 1) {
+            REG_SIGNATURE => SIGNATURE,
+            REG_TARGET => self.target,
+            REG_ACTUAL => self.actual,
+            REG_STATUS => core::mem::take(&mut self.status),
+            _ => 0,
+        };
+        Ok(val)
+    }
+
+    fn write(&mut self, port: u16, _size: u8, val: u32) -> Result<()> {
+        let ram = unsafe { &mut *self.ram };
+        match port & (PORT_COUNT - 1) {
+            REG_INFLATE => {
+                if ram.discard_page(val as u64) {
+                    self.actual += 1;
+                }
+            }
+            REG_DEFLATE => {
+                if ram.reclaim_page(val as u64) {
+                    self.actual -= 1;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
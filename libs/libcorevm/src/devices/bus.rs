@@ -11,6 +11,7 @@
 //! | Port | Description |
 //! |------|-------------|
 //! | 0xCF8 | PCI Configuration Address (32-bit write) |
+//! | 0xCF9 | Reset Control Register (8-bit write, PIIX3 RCR) |
 //! | 0xCFC-0xCFF | PCI Configuration Data (32-bit read/write) |
 //!
 //! # Configuration Address Format (port 0xCF8)
@@ -41,7 +42,7 @@
 //! | 0x3D | 1 | Interrupt Pin |
 
 use alloc::vec::Vec;
-use crate::error::Result;
+use crate::error::{Result, VmError};
 use crate::io::IoHandler;
 
 /// A single PCI device with a 256-byte configuration space (header type 0).
@@ -305,6 +306,7 @@ impl IoHandler for PciBus {
     /// Write to PCI bus I/O ports.
     ///
     /// - 0xCF8: stores the configuration address
+    /// - 0xCF9: byte write with RST_CPU (bit 2) set requests a system reset
     /// - 0xCFC-0xCFF: writes to PCI configuration data, supports
     ///   byte and word sub-accesses
     fn write(&mut self, port: u16, size: u8, val: u32) -> Result<()> {
@@ -325,6 +327,11 @@ impl IoHandler for PciBus {
                 }
                 self.config_address = val;
             }
+            0xCF9 if size == 1 => {
+                if val & 0x04 != 0 {
+                    return Err(VmError::ResetRequested);
+                }
+            }
             0xCFC..=0xCFF => {
                 // Read-modify-write for sub-dword accesses.
                 let current = self.config_read();
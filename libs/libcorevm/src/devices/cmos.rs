@@ -20,7 +20,15 @@
 //! - `0x15-0x16`: Base memory size (KB)
 //! - `0x17-0x18`: Extended memory size above 1 MB (KB)
 //! - `0x30-0x31`: Extended memory above 1 MB (KB, duplicate)
+//! - `0x32`: Century
 //! - `0x34-0x35`: Extended memory above 16 MB (64 KB units)
+//!
+//! # Clock
+//!
+//! The time registers follow the host clock plus a configurable offset
+//! (see [`Cmos::set_offset`]), encoded in BCD or binary and 12/24-hour
+//! format according to status register B. A guest that sets the clock
+//! (SET bit in register B) moves the offset, so its time keeps running.
 
 use crate::error::Result;
 use crate::io::IoHandler;
@@ -34,6 +42,55 @@ pub struct Cmos {
     pub data: [u8; 128],
     /// NMI disable flag (bit 7 of port 0x70).
     pub nmi_disabled: bool,
+    /// Seconds added to the host clock (fixed offset plus timezone).
+    pub offset_secs: i64,
+}
+
+/// Status register B bits.
+const REG_B_SET: u8 = 0x80;
+const REG_B_24H: u8 = 0x02;
+const REG_B_BINARY: u8 = 0x04;
+
+/// Century register (IBM PC/AT convention, also named in the ACPI FADT).
+const REG_CENTURY: usize = 0x32;
+
+/// Whether `idx` is one of the clock (not alarm) registers.
+fn is_clock_reg(idx: usize) -> bool {
+    matches!(idx, 0x00 | 0x02 | 0x04 | 0x06 | 0x07 | 0x08 | 0x09 | REG_CENTURY)
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Inverse of [`days_from_civil`]: (year, month, day).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Host clock in seconds since the Unix epoch.
+fn host_time() -> i64 {
+    let mut buf = [0u8; 8];
+    libsyscall::time(&mut buf);
+    let year = u16::from_le_bytes([buf[0], buf[1]]) as i64;
+    let days = days_from_civil(year, buf[2].max(1) as u32, buf[3].max(1) as u32);
+    days * 86400 + buf[4] as i64 * 3600 + buf[5] as i64 * 60 + buf[6] as i64
 }
 
 impl Cmos {
@@ -91,11 +148,76 @@ impl Cmos {
         data[0x34] = above_16mb as u8;
         data[0x35] = (above_16mb >> 8) as u8;
 
-        Cmos {
+        let mut cmos = Cmos {
             index: 0,
             data,
             nmi_disabled: false,
+            offset_secs: 0,
+        };
+        cmos.update_clock();
+        cmos
+    }
+
+    /// Set the guest clock offset from the host clock in seconds.
+    pub fn set_offset(&mut self, offset_secs: i64) {
+        self.offset_secs = offset_secs;
+        self.update_clock();
+    }
+
+    /// Encode a register value in the guest-selected BCD/binary mode.
+    fn encode(&self, v: u8) -> u8 {
+        if self.data[0x0B] & REG_B_BINARY != 0 { v } else { (v / 10) << 4 | (v % 10) }
+    }
+
+    fn decode(&self, v: u8) -> u8 {
+        if self.data[0x0B] & REG_B_BINARY != 0 { v } else { (v >> 4) * 10 + (v & 0x0F) }
+    }
+
+    /// Refresh the clock registers from the host clock, unless the guest
+    /// is setting the time.
+    fn update_clock(&mut self) {
+        if self.data[0x0B] & REG_B_SET != 0 {
+            return;
         }
+        let t = host_time() + self.offset_secs;
+        let days = t.div_euclid(86400);
+        let secs = t.rem_euclid(86400);
+        let (year, month, day) = civil_from_days(days);
+        let hour = (secs / 3600) as u8;
+        self.data[0x00] = self.encode((secs % 60) as u8);
+        self.data[0x02] = self.encode((secs / 60 % 60) as u8);
+        self.data[0x04] = if self.data[0x0B] & REG_B_24H != 0 {
+            self.encode(hour)
+        } else {
+            // 12-hour mode: 12, 1..11 with bit 7 set for PM.
+            let h12 = if hour % 12 == 0 { 12 } else { hour % 12 };
+            self.encode(h12) | if hour >= 12 { 0x80 } else { 0 }
+        };
+        // Day of week: 1 = Sunday (1970-01-01 was a Thursday).
+        self.data[0x06] = self.encode(((days + 4).rem_euclid(7) + 1) as u8);
+        self.data[0x07] = self.encode(day as u8);
+        self.data[0x08] = self.encode(month as u8);
+        self.data[0x09] = self.encode(year.rem_euclid(100) as u8);
+        self.data[REG_CENTURY] = self.encode(year.div_euclid(100) as u8);
+    }
+
+    /// Take over the time the guest wrote while the SET bit was held.
+    fn commit_guest_time(&mut self) {
+        let hour_reg = self.data[0x04];
+        let hour = if self.data[0x0B] & REG_B_24H != 0 {
+            self.decode(hour_reg)
+        } else {
+            let h = self.decode(hour_reg & 0x7F) % 12;
+            if hour_reg & 0x80 != 0 { h + 12 } else { h }
+        };
+        let year = self.decode(self.data[REG_CENTURY]) as i64 * 100 + self.decode(self.data[0x09]) as i64;
+        let month = self.decode(self.data[0x08]).clamp(1, 12) as u32;
+        let day = self.decode(self.data[0x07]).clamp(1, 31) as u32;
+        let t = days_from_civil(year, month, day) * 86400
+            + hour as i64 * 3600
+            + self.decode(self.data[0x02]) as i64 * 60
+            + self.decode(self.data[0x00]) as i64;
+        self.offset_secs = t - host_time();
     }
 }
 
@@ -109,6 +231,9 @@ impl IoHandler for Cmos {
         let val = match port {
             0x71 => {
                 let idx = (self.index & 0x7F) as usize;
+                if is_clock_reg(idx) {
+                    self.update_clock();
+                }
                 let v = self.data[idx];
                 // Reading status register C clears all interrupt flags.
                 if idx == 0x0C {
@@ -138,7 +263,13 @@ impl IoHandler for Cmos {
             0x71 => {
                 let idx = (self.index & 0x7F) as usize;
                 // Status register C (0x0C) and D (0x0D) are read-only.
-                if idx != 0x0C && idx != 0x0D {
+                if idx == 0x0B {
+                    let was_set = self.data[0x0B] & REG_B_SET != 0;
+                    self.data[0x0B] = byte;
+                    if was_set && byte & REG_B_SET == 0 {
+                        self.commit_guest_time();
+                    }
+                } else if idx != 0x0C && idx != 0x0D {
                     self.data[idx] = byte;
                 }
             }
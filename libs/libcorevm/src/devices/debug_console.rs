@@ -0,0 +1,116 @@
+//! Bochs-style debug console (port 0xE9).
+//!
+//! Hobby kernels and test suites write log text to port 0xE9 one byte at a
+//! time — the "port E9 hack" of Bochs, also understood by QEMU's `debugcon`.
+//! Unlike the SeaBIOS port at 0x402 (see [`debug_port`](super::debug_port)),
+//! output is split into lines, each stamped with the host uptime at which
+//! its first byte arrived, so a host test harness can correlate guest log
+//! messages with its own events.
+//!
+//! # I/O Port
+//!
+//! | Port | Width | Direction | Description |
+//! |------|-------|-----------|-------------|
+//! | 0xE9 | 8-bit | Write | Log character output |
+//! | 0xE9 | 8-bit | Read | Returns 0xE9 (port present) |
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use crate::error::Result;
+use crate::io::IoHandler;
+
+/// Completed lines kept for the host; the oldest are dropped beyond this.
+pub const MAX_LINES: usize = 1024;
+
+/// A line longer than this is split into several lines.
+pub const MAX_LINE_LEN: usize = 1024;
+
+/// One line of guest output.
+#[derive(Debug, Clone)]
+pub struct DebugLine {
+    /// Host uptime (milliseconds) when the first byte of the line was written.
+    pub timestamp_ms: u64,
+    /// Line text without the trailing newline.
+    pub text: Vec<u8>,
+}
+
+/// Bochs debug console emulation.
+#[derive(Debug)]
+pub struct DebugConsole {
+    /// Completed lines waiting to be drained by the host.
+    lines: VecDeque<DebugLine>,
+    /// Line being written, if any byte of it has arrived yet.
+    partial: Option<DebugLine>,
+    /// Lines dropped because the host did not drain the queue in time.
+    pub dropped: u64,
+}
+
+impl DebugConsole {
+    /// Create a console with an empty line queue.
+    pub fn new() -> Self {
+        DebugConsole {
+            lines: VecDeque::new(),
+            partial: None,
+            dropped: 0,
+        }
+    }
+
+    /// Take the oldest completed line.
+    pub fn take_line(&mut self) -> Option<DebugLine> {
+        self.lines.pop_front()
+    }
+
+    /// True if at least one completed line is queued.
+    pub fn has_lines(&self) -> bool {
+        !self.lines.is_empty()
+    }
+
+    /// Complete the line being written even though no newline arrived yet,
+    /// e.g. a panic message printed just before the guest exits.
+    pub fn flush(&mut self) {
+        if let Some(line) = self.partial.take() {
+            self.push_line(line);
+        }
+    }
+
+    fn push_line(&mut self, line: DebugLine) {
+        if self.lines.len() >= MAX_LINES {
+            self.lines.pop_front();
+            self.dropped += 1;
+        }
+        self.lines.push_back(line);
+    }
+}
+
+impl IoHandler for DebugConsole {
+    /// Returns 0xE9 so guests can detect the console before using it.
+    fn read(&mut self, _port: u16, _size: u8) -> Result<u32> {
+        Ok(0xE9)
+    }
+
+    /// Append the low byte of `val` to the current line. `\n` completes the
+    /// line; `\r` is dropped.
+    fn write(&mut self, _port: u16, _size: u8, val: u32) -> Result<()> {
+        match val as u8 {
+            b'\n' => {
+                let line = self.partial.take().unwrap_or_else(|| DebugLine {
+                    timestamp_ms: libsyscall::uptime_ms() as u64,
+                    text: Vec::new(),
+                });
+                self.push_line(line);
+            }
+            b'\r' => {}
+            byte => {
+                let line = self.partial.get_or_insert_with(|| DebugLine {
+                    timestamp_ms: libsyscall::uptime_ms() as u64,
+                    text: Vec::new(),
+                });
+                line.text.push(byte);
+                if line.text.len() >= MAX_LINE_LEN {
+                    self.flush();
+                }
+            }
+        }
+        Ok(())
+    }
+}
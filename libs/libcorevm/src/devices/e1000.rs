@@ -29,8 +29,19 @@
 //! | 0x3808 | TDLEN | TX Descriptor Ring Length |
 //! | 0x3810 | TDH | TX Descriptor Head |
 //! | 0x3818 | TDT | TX Descriptor Tail |
+//! | 0x5200 | MTA | Multicast Table Array (128 dwords) |
 //! | 0x5400 | RAL0 | Receive Address Low (MAC bytes 0-3) |
 //! | 0x5404 | RAH0 | Receive Address High (MAC bytes 4-5 + flags) |
+//!
+//! # Link and Receive Filtering
+//!
+//! The host controls the link ([`E1000::set_link`]); a change updates
+//! STATUS.LU and raises the LSC interrupt cause, and frames are neither
+//! accepted nor left for the host while the link is down. Received frames
+//! pass the guest's filter ([`E1000::accepts`]): unicast frames must match
+//! one of the 16 receive address pairs (RAL/RAH with AV set) unless
+//! RCTL.UPE is set; broadcast frames need RCTL.BAM; other multicast frames
+//! need RCTL.MPE or their hash bit set in the MTA, hashed per RCTL.MO.
 
 use alloc::collections::VecDeque;
 use alloc::vec;
@@ -59,9 +70,22 @@ const REG_TDBAH: usize = 0x3804;
 const REG_TDLEN: usize = 0x3808;
 const REG_TDH: usize = 0x3810;
 const REG_TDT: usize = 0x3818;
+const REG_MTA: usize = 0x5200;
 const REG_RAL0: usize = 0x5400;
 const REG_RAH0: usize = 0x5404;
 
+/// Maximum NICs per VM (see `corevm_add_e1000`).
+pub const MAX_NICS: usize = 4;
+
+/// Default MMIO base of the first NIC; further NICs follow at 128 KB steps.
+pub const DEFAULT_MMIO: u64 = 0xFEB8_0000;
+
+/// Default MAC of the first NIC; further NICs add their index to the last byte.
+pub const DEFAULT_MAC: [u8; 6] = [0x52, 0x54, 0x00, 0x12, 0x34, 0x56];
+
+/// Receive address pairs (RAL/RAH 0-15).
+const RECEIVE_ADDRESSES: usize = 16;
+
 /// Total register space size: 128 KB (0x20000 bytes, 0x8000 dwords).
 const REG_SPACE_DWORDS: usize = 0x8000;
 
@@ -69,6 +93,20 @@ const REG_SPACE_DWORDS: usize = 0x8000;
 const STATUS_LINK_UP: u32 = 0x02;
 const STATUS_SPEED_1000: u32 = 0x80;
 
+/// RAH: address valid (bit 31).
+const RAH_AV: u32 = 1 << 31;
+
+/// RCTL: unicast promiscuous (bit 3), multicast promiscuous (bit 4),
+/// multicast offset (bits 13:12), broadcast accept mode (bit 15).
+const RCTL_UPE: u32 = 1 << 3;
+const RCTL_MPE: u32 = 1 << 4;
+const RCTL_MO_SHIFT: u32 = 12;
+const RCTL_BAM: u32 = 1 << 15;
+
+/// ICR: link status change (bit 2), receiver timer (bit 7).
+const ICR_LSC: u32 = 1 << 2;
+const ICR_RXT0: u32 = 1 << 7;
+
 /// CTRL register: software reset bit (bit 26).
 const CTRL_RST: u32 = 1 << 26;
 
@@ -90,6 +128,10 @@ pub struct E1000 {
     pub rx_buffer: VecDeque<Vec<u8>>,
     /// Packets transmitted by the guest, waiting for the host to send.
     pub tx_buffer: Vec<Vec<u8>>,
+    /// Link state set by the host ("cable plugged in").
+    pub link_up: bool,
+    /// Frames dropped by the receive filter or a down link.
+    pub rx_filtered: u64,
 }
 
 impl E1000 {
@@ -124,32 +166,96 @@ impl E1000 {
             eeprom,
             rx_buffer: VecDeque::new(),
             tx_buffer: Vec::new(),
+            link_up: true,
+            rx_filtered: 0,
         }
     }
 
     /// Enqueue a packet received from the network for guest consumption.
     ///
     /// The packet will be delivered to the guest when it polls the RX
-    /// descriptor ring.
-    pub fn receive_packet(&mut self, data: &[u8]) {
+    /// descriptor ring. Returns false, and drops the packet, if the link is
+    /// down or the guest's receive filter rejects its destination address.
+    pub fn receive_packet(&mut self, data: &[u8]) -> bool {
+        if !self.link_up || !self.accepts(data) {
+            self.rx_filtered += 1;
+            return false;
+        }
         self.rx_buffer.push_back(data.to_vec());
         // Set RX interrupt cause (bit 7 = RXT0, receiver timer interrupt).
-        let icr = self.regs[REG_ICR / 4];
-        self.regs[REG_ICR / 4] = icr | (1 << 7);
+        self.regs[REG_ICR / 4] |= ICR_RXT0;
+        true
+    }
+
+    /// Whether the receive filter passes a frame, judged by its destination
+    /// address (the first 6 bytes).
+    pub fn accepts(&self, frame: &[u8]) -> bool {
+        if frame.len() < 6 {
+            return false;
+        }
+        let dst = &frame[..6];
+        let rctl = self.regs[REG_RCTL / 4];
+
+        // I/G bit: group (multicast or broadcast) address.
+        if dst[0] & 0x01 == 0 {
+            return rctl & RCTL_UPE != 0 || self.matches_receive_address(dst);
+        }
+        if dst.iter().all(|&b| b == 0xFF) && rctl & RCTL_BAM != 0 {
+            return true;
+        }
+        if rctl & RCTL_MPE != 0 || self.matches_receive_address(dst) {
+            return true;
+        }
+        let hash = multicast_hash(dst, (rctl >> RCTL_MO_SHIFT) & 0x3);
+        let mta = self.regs[REG_MTA / 4 + (hash >> 5) as usize];
+        mta & (1 << (hash & 0x1F)) != 0
+    }
+
+    /// Whether `dst` equals one of the valid receive address pairs.
+    fn matches_receive_address(&self, dst: &[u8]) -> bool {
+        let low = u32::from_le_bytes([dst[0], dst[1], dst[2], dst[3]]);
+        let high = (dst[4] as u32) | ((dst[5] as u32) << 8);
+        (0..RECEIVE_ADDRESSES).any(|i| {
+            let ral = self.regs[REG_RAL0 / 4 + i * 2];
+            let rah = self.regs[REG_RAH0 / 4 + i * 2];
+            rah & RAH_AV != 0 && ral == low && rah & 0xFFFF == high
+        })
+    }
+
+    /// Plug in (`up`) or pull the virtual cable. A change updates
+    /// STATUS.LU and raises the link status change interrupt cause.
+    pub fn set_link(&mut self, up: bool) {
+        if up == self.link_up {
+            return;
+        }
+        self.link_up = up;
+        let status = &mut self.regs[REG_STATUS / 4];
+        if up {
+            *status |= STATUS_LINK_UP | STATUS_SPEED_1000;
+        } else {
+            *status &= !(STATUS_LINK_UP | STATUS_SPEED_1000);
+        }
+        self.regs[REG_ICR / 4] |= ICR_LSC;
+        if !up {
+            self.rx_buffer.clear();
+        }
     }
 
     /// Drain and return all packets transmitted by the guest.
     ///
     /// The host should forward these packets to the actual network or
-    /// to another VM.
+    /// to another VM. Nothing leaves the NIC while the link is down.
     pub fn take_tx_packets(&mut self) -> Vec<Vec<u8>> {
         let mut packets = Vec::new();
         core::mem::swap(&mut packets, &mut self.tx_buffer);
+        if !self.link_up {
+            packets.clear();
+        }
         packets
     }
 
     /// Perform a software reset, restoring registers to their power-on
-    /// defaults while preserving the MAC address and EEPROM.
+    /// defaults while preserving the MAC address, EEPROM and link state.
     fn reset(&mut self) {
         let mac = self.mac_address;
         let eeprom = self.eeprom;
@@ -160,7 +266,9 @@ impl E1000 {
         }
 
         // Restore defaults.
-        self.regs[REG_STATUS / 4] = STATUS_LINK_UP | STATUS_SPEED_1000;
+        if self.link_up {
+            self.regs[REG_STATUS / 4] = STATUS_LINK_UP | STATUS_SPEED_1000;
+        }
 
         // Restore MAC in RAL0/RAH0.
         let ral = (mac[0] as u32)
@@ -177,6 +285,19 @@ impl E1000 {
     }
 }
 
+/// 12-bit MTA hash of a multicast address for RCTL.MO `offset`: bits
+/// 47:36, 46:35, 45:34 or 43:32 of the address (last two bytes, LSB first).
+fn multicast_hash(dst: &[u8], offset: u32) -> u32 {
+    let word = (dst[4] as u32) | ((dst[5] as u32) << 8);
+    let shift = match offset {
+        0 => 4,
+        1 => 3,
+        2 => 2,
+        _ => 0,
+    };
+    (word >> shift) & 0xFFF
+}
+
 impl MmioHandler for E1000 {
     /// Read a register from the E1000 MMIO region.
     ///
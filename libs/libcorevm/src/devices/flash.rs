@@ -0,0 +1,268 @@
+//! CFI-compatible NOR flash (Intel/Sharp command set, x8).
+//!
+//! Models a parallel NOR flash chip such as the firmware flash of a PC
+//! (typically mapped just below 4 GiB). In read-array mode the device
+//! behaves like ROM and the guest can execute from it; writes are decoded
+//! as commands. Programming can only clear bits, so a sector must be erased
+//! (set to 0xFF) before new data can be written to it. Program and erase
+//! complete instantly and leave the device in read-status mode.
+//!
+//! When the flash is backed by a host file, the contents are written back
+//! by [`NorFlash::persist`] (called from `corevm_destroy` and
+//! `corevm_flash_sync`) if the guest modified them.
+//!
+//! # Commands
+//!
+//! | Byte | Command |
+//! |------|---------|
+//! | 0xFF / 0xF0 | Read array |
+//! | 0x90 | Read identifier (offset 0 manufacturer, 1 device) |
+//! | 0x98 | CFI query |
+//! | 0x70 | Read status register |
+//! | 0x50 | Clear status register |
+//! | 0x40 / 0x10 | Program: the next write stores its bytes |
+//! | 0x20, 0xD0 | Block erase of the sector addressed by the confirm write |
+//!
+//! # Status register
+//!
+//! | Bit | Meaning |
+//! |-----|---------|
+//! | 7 | Ready |
+//! | 5 | Erase error / bad command sequence |
+//! | 4 | Program error / bad command sequence |
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::error::Result;
+use crate::memory::mmio::MmioHandler;
+
+/// Manufacturer code reported in read-identifier mode (Intel).
+const MANUFACTURER_ID: u8 = 0x89;
+/// Device code reported in read-identifier mode.
+const DEVICE_ID: u8 = 0x18;
+
+/// Smallest supported sector size (the CFI table stores it in 256-byte units).
+pub const MIN_SECTOR_SIZE: usize = 256;
+
+const STATUS_READY: u8 = 0x80;
+const STATUS_ERASE_ERROR: u8 = 0x20;
+const STATUS_PROGRAM_ERROR: u8 = 0x10;
+
+/// What reads currently return, and how the next write is interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    ReadArray,
+    ReadId,
+    CfiQuery,
+    ReadStatus,
+    /// A program command was issued; the next write is the data.
+    ProgramSetup,
+    /// An erase command was issued; the next write must be 0xD0.
+    EraseSetup,
+}
+
+/// NOR flash device backing one MMIO region.
+pub struct NorFlash {
+    data: Vec<u8>,
+    sector_size: usize,
+    mode: Mode,
+    status: u8,
+    /// CFI query table, indexed by byte offset.
+    cfi: [u8; 0x31],
+    /// Host file the contents are persisted to.
+    path: Option<String>,
+    /// Contents changed since the last [`persist`](NorFlash::persist).
+    dirty: bool,
+}
+
+impl NorFlash {
+    /// Create a flash device holding `data`. The size must be a power of
+    /// two and a multiple of `sector_size`, which itself must be a multiple
+    /// of [`MIN_SECTOR_SIZE`]; returns `None` otherwise.
+    pub fn new(data: Vec<u8>, sector_size: usize, path: Option<String>) -> Option<Self> {
+        let size = data.len();
+        if !size.is_power_of_two()
+            || sector_size < MIN_SECTOR_SIZE
+            || sector_size % MIN_SECTOR_SIZE != 0
+            || size % sector_size != 0
+            || size / sector_size > 0x1_0000
+        {
+            return None;
+        }
+        Some(NorFlash {
+            cfi: cfi_table(size, sector_size),
+            data,
+            sector_size,
+            mode: Mode::ReadArray,
+            status: STATUS_READY,
+            path,
+            dirty: false,
+        })
+    }
+
+    /// Load the initial contents: the host file at `path` if it exists,
+    /// otherwise `init`. The rest of the `size` bytes reads as erased (0xFF).
+    /// Returns `None` if the file exists but cannot be read.
+    pub fn load_contents(size: usize, path: Option<&str>, init: &[u8]) -> Option<Vec<u8>> {
+        let mut data = vec![0xFFu8; size];
+        let mut st = [0u32; 7];
+        match path {
+            Some(p) if libsyscall::stat(p, &mut st) == 0 => {
+                let len = (st[1] as usize).min(size);
+                crate::share::read_host_file(p, &mut data[..len]).ok()?;
+            }
+            _ => {
+                let len = init.len().min(size);
+                data[..len].copy_from_slice(&init[..len]);
+            }
+        }
+        Some(data)
+    }
+
+    /// Flash contents.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// True if the guest changed the contents since the last persist.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Return to read-array mode (power-on / reset).
+    pub fn reset(&mut self) {
+        self.mode = Mode::ReadArray;
+        self.status = STATUS_READY;
+    }
+
+    /// Write the contents back to the host file if they changed.
+    ///
+    /// Returns `Some(true)` if the file was written, `Some(false)` if there
+    /// was nothing to write (clean, or no host file), `None` on write error.
+    pub fn persist(&mut self) -> Option<bool> {
+        let path = match self.path.as_deref() {
+            Some(p) if self.dirty => p,
+            _ => return Some(false),
+        };
+        if !crate::share::write_host_file(path, &self.data) {
+            return None;
+        }
+        self.dirty = false;
+        Some(true)
+    }
+
+    /// Execute a command byte written at `offset`.
+    fn command(&mut self, offset: usize, cmd: u8) {
+        self.mode = match cmd {
+            0xFF | 0xF0 => Mode::ReadArray,
+            0x90 => Mode::ReadId,
+            0x98 => Mode::CfiQuery,
+            0x70 => Mode::ReadStatus,
+            0x50 => {
+                self.status = STATUS_READY;
+                self.mode
+            }
+            0x40 | 0x10 => Mode::ProgramSetup,
+            0x20 => Mode::EraseSetup,
+            _ => {
+                libsyscall::serial_print(format_args!(
+                    "[flash] unknown command 0x{:02X} at offset 0x{:X}\n", cmd, offset
+                ));
+                self.mode
+            }
+        };
+    }
+
+    /// Program `bytes` at `offset`. Programming clears bits only.
+    fn program(&mut self, offset: usize, bytes: &[u8]) {
+        for (i, &b) in bytes.iter().enumerate() {
+            if let Some(cell) = self.data.get_mut(offset + i) {
+                if *cell & b != *cell {
+                    self.dirty = true;
+                }
+                *cell &= b;
+            }
+        }
+    }
+
+    /// Erase the sector containing `offset` to 0xFF.
+    fn erase(&mut self, offset: usize) {
+        let start = offset - offset % self.sector_size;
+        let end = (start + self.sector_size).min(self.data.len());
+        let sector = &mut self.data[start..end];
+        if sector.iter().any(|&b| b != 0xFF) {
+            sector.fill(0xFF);
+            self.dirty = true;
+        }
+    }
+}
+
+impl MmioHandler for NorFlash {
+    fn read(&mut self, offset: u64, size: u8) -> Result<u64> {
+        let off = offset as usize;
+        let mut val = 0u64;
+        for i in 0..size as usize {
+            let b = match self.mode {
+                Mode::ReadArray => self.data.get(off + i).copied().unwrap_or(0xFF),
+                Mode::ReadId => match off + i {
+                    0 => MANUFACTURER_ID,
+                    1 => DEVICE_ID,
+                    _ => 0,
+                },
+                Mode::CfiQuery => self.cfi.get(off + i).copied().unwrap_or(0),
+                _ => self.status,
+            };
+            val |= (b as u64) << (i * 8);
+        }
+        Ok(val)
+    }
+
+    fn write(&mut self, offset: u64, size: u8, val: u64) -> Result<()> {
+        let off = offset as usize;
+        match self.mode {
+            Mode::ProgramSetup => {
+                let bytes = val.to_le_bytes();
+                self.program(off, &bytes[..size as usize]);
+                self.mode = Mode::ReadStatus;
+            }
+            Mode::EraseSetup => {
+                if val as u8 == 0xD0 {
+                    self.erase(off);
+                } else {
+                    self.status |= STATUS_ERASE_ERROR | STATUS_PROGRAM_ERROR;
+                }
+                self.mode = Mode::ReadStatus;
+            }
+            _ => self.command(off, val as u8),
+        }
+        Ok(())
+    }
+}
+
+/// Build the CFI query table for a single erase-block region.
+fn cfi_table(size: usize, sector_size: usize) -> [u8; 0x31] {
+    let mut t = [0u8; 0x31];
+    t[0x10..0x13].copy_from_slice(b"QRY");
+    // Primary vendor command set: Intel/Sharp extended (0x0001), no
+    // extended query table, no alternate command set.
+    t[0x13] = 0x01;
+    // Vcc 4.5–5.5 V, no Vpp.
+    t[0x1B] = 0x45;
+    t[0x1C] = 0x55;
+    // Typical timeouts: 2 µs per byte program, 2 ms per block erase.
+    t[0x1F] = 0x01;
+    t[0x21] = 0x01;
+    // Maximum timeouts: 2x typical.
+    t[0x23] = 0x01;
+    t[0x25] = 0x01;
+    t[0x27] = size.trailing_zeros() as u8;
+    // x8-only interface, no multi-byte (buffered) writes.
+    t[0x2C] = 1;
+    let blocks = (size / sector_size - 1) as u16;
+    t[0x2D..0x2F].copy_from_slice(&blocks.to_le_bytes());
+    let units = (sector_size / MIN_SECTOR_SIZE) as u16;
+    t[0x2F..0x31].copy_from_slice(&units.to_le_bytes());
+    t
+}
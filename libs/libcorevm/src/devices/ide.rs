@@ -24,7 +24,10 @@
 //! | FLUSH CACHE | 0xE7 | Flush write cache |
 //! | DEVICE RESET | 0x08 | Software reset |
 
+use alloc::boxed::Box;
 use alloc::vec::Vec;
+use crate::disk::{DiskBackend, DiskError, MemDisk};
+use crate::disk::probe::{self, GuestOs};
 use crate::error::Result;
 use crate::io::IoHandler;
 
@@ -47,6 +50,8 @@ const SR_ERR: u8 = 0x01;
 
 /// ABRT — command aborted.
 const ER_ABRT: u8 = 0x04;
+/// UNC — uncorrectable data error (the host image could not be accessed).
+const ER_UNC: u8 = 0x40;
 
 // ── ATA commands ──
 
@@ -69,14 +74,15 @@ const SECTOR_SIZE: usize = 512;
 
 /// IDE/ATA disk controller with one attached drive.
 ///
-/// The drive image is stored as a flat `Vec<u8>`. Reads/writes beyond
-/// the image size return zeros / are silently ignored.
+/// The medium is a [`DiskBackend`]: a flat image in memory or an image
+/// file on the host. Reads/writes beyond the medium size return zeros /
+/// are silently ignored; host I/O errors fail the command with UNC.
 pub struct Ide {
     // ── Drive image ──
 
-    /// Flat disk image data. Length determines drive capacity.
-    disk: Vec<u8>,
-    /// Total number of sectors (disk.len() / 512).
+    /// Attached medium. Its size determines drive capacity.
+    disk: Option<Box<dyn DiskBackend>>,
+    /// Total number of sectors (disk size / 512).
     total_sectors: u64,
 
     // ── Task file registers ──
@@ -125,13 +131,15 @@ pub struct Ide {
     irq_pending: bool,
     /// Multiple sector count for READ/WRITE MULTIPLE.
     multiple_count: u8,
+    /// Reject write commands (the medium is write-protected).
+    read_only: bool,
 }
 
 impl Ide {
     /// Create a new IDE controller with no disk attached.
     pub fn new() -> Self {
         Ide {
-            disk: Vec::new(),
+            disk: None,
             total_sectors: 0,
             error: 0,
             features: 0,
@@ -153,26 +161,78 @@ impl Ide {
             is_write: false,
             irq_pending: false,
             multiple_count: 1,
+            read_only: false,
         }
     }
 
-    /// Attach a disk image. The image is a flat sector dump.
+    /// Attach a disk image held in memory. The image is a flat sector dump.
     ///
     /// The image length is rounded down to the nearest sector boundary.
     pub fn attach_disk(&mut self, mut image: Vec<u8>) {
         let sectors = image.len() / SECTOR_SIZE;
         image.truncate(sectors * SECTOR_SIZE);
-        self.total_sectors = sectors as u64;
-        self.disk = image;
+        self.attach_backend(Box::new(MemDisk::new(image)));
+    }
+
+    /// Attach a medium (see [`crate::disk`]). A partial last sector is
+    /// not addressable.
+    pub fn attach_backend(&mut self, disk: Box<dyn DiskBackend>) {
+        self.total_sectors = disk.size() / SECTOR_SIZE as u64;
+        self.disk = Some(disk);
         // Update status to indicate drive present and ready.
         self.status = SR_DRDY | SR_DSC;
     }
 
-    /// Detach the current disk image and return it.
-    pub fn detach_disk(&mut self) -> Vec<u8> {
+    /// Detach the current medium, writing back cached changes first.
+    ///
+    /// Returns false if the write-back failed (the medium is detached
+    /// anyway).
+    pub fn detach_disk(&mut self) -> bool {
         self.total_sectors = 0;
         self.status = 0;
-        core::mem::take(&mut self.disk)
+        match self.disk.take() {
+            Some(mut disk) => disk.flush(),
+            None => true,
+        }
+    }
+
+    /// Write cached changes of the medium back to the host.
+    /// Returns false on error.
+    pub fn flush(&mut self) -> bool {
+        self.disk.as_mut().map_or(true, |d| d.flush())
+    }
+
+    /// Copy the changes held in an overlay medium into its base image
+    /// (see [`DiskBackend::commit`]). Returns the number of clusters copied.
+    pub fn commit_overlay(&mut self) -> core::result::Result<u64, DiskError> {
+        self.disk.as_mut().ok_or(DiskError::NotFound)?.commit()
+    }
+
+    /// Drop the changes held in an overlay medium (see
+    /// [`DiskBackend::discard`]).
+    pub fn discard_overlay(&mut self) -> core::result::Result<(), DiskError> {
+        self.disk.as_mut().ok_or(DiskError::NotFound)?.discard()
+    }
+
+    /// Write-protect the drive: write commands abort and
+    /// [`write_image`](Self::write_image) fails.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    /// Guess the operating system on the attached medium
+    /// (see [`crate::disk::probe`]).
+    pub fn probe_guest(&mut self) -> GuestOs {
+        match self.disk.as_mut() {
+            Some(disk) => probe::probe(disk.as_mut()),
+            None => GuestOs::NoDisk,
+        }
+    }
+
+    /// Borrow the raw disk image. Empty unless the medium is held in
+    /// memory.
+    pub fn image(&self) -> &[u8] {
+        self.disk.as_ref().and_then(|d| d.as_bytes()).unwrap_or(&[])
     }
 
     /// Returns true if an IRQ is pending (and nIEN is not set).
@@ -187,7 +247,39 @@ impl Ide {
 
     /// Get total disk size in bytes.
     pub fn disk_size(&self) -> u64 {
-        self.disk.len() as u64
+        self.disk.as_ref().map_or(0, |d| d.size())
+    }
+
+    /// Get the number of whole sectors on the attached disk.
+    pub fn sector_count(&self) -> u64 {
+        self.total_sectors
+    }
+
+    /// Copy sectors starting at `lba` straight out of the disk image.
+    ///
+    /// Bypasses the ATA task file; used by the built-in BIOS to serve
+    /// INT 13h. `buf` must be a whole number of sectors. Returns false
+    /// if the range extends past the disk or the host image cannot be read.
+    pub fn read_image(&mut self, lba: u64, buf: &mut [u8]) -> bool {
+        match self.disk.as_mut() {
+            Some(disk) => disk.read(lba * SECTOR_SIZE as u64, buf),
+            None => false,
+        }
+    }
+
+    /// Copy sectors starting at `lba` straight into the disk image.
+    ///
+    /// Counterpart of [`read_image`](Ide::read_image). Returns false if
+    /// the range extends past the disk, the drive is read-only or the host
+    /// image cannot be written.
+    pub fn write_image(&mut self, lba: u64, data: &[u8]) -> bool {
+        if self.read_only {
+            return false;
+        }
+        match self.disk.as_mut() {
+            Some(disk) => disk.write(lba * SECTOR_SIZE as u64, data),
+            None => false,
+        }
     }
 
     // ── Internal helpers ──
@@ -213,24 +305,38 @@ impl Ide {
     }
 
     /// Read one sector from the disk image into the buffer.
-    fn read_sector(&mut self, lba: u64) {
-        let offset = (lba as usize) * SECTOR_SIZE;
-        if offset + SECTOR_SIZE <= self.disk.len() {
-            self.buffer.copy_from_slice(&self.disk[offset..offset + SECTOR_SIZE]);
-        } else {
-            // Beyond disk — return zeros.
-            self.buffer = [0u8; SECTOR_SIZE];
-        }
+    /// Returns false on a host I/O error.
+    fn read_sector(&mut self, lba: u64) -> bool {
         self.buffer_offset = 0;
+        let disk = match self.disk.as_mut() {
+            Some(d) if lba < self.total_sectors => d,
+            _ => {
+                // Beyond disk — return zeros.
+                self.buffer = [0u8; SECTOR_SIZE];
+                return true;
+            }
+        };
+        disk.read(lba * SECTOR_SIZE as u64, &mut self.buffer)
     }
 
     /// Write the buffer contents to the disk image at the given LBA.
-    fn write_sector(&mut self, lba: u64) {
-        let offset = (lba as usize) * SECTOR_SIZE;
-        if offset + SECTOR_SIZE <= self.disk.len() {
-            self.disk[offset..offset + SECTOR_SIZE].copy_from_slice(&self.buffer);
+    /// Returns false on a host I/O error.
+    fn write_sector(&mut self, lba: u64) -> bool {
+        match self.disk.as_mut() {
+            Some(d) if lba < self.total_sectors => d.write(lba * SECTOR_SIZE as u64, &self.buffer),
+            // Writes beyond the disk boundary are silently ignored.
+            _ => true,
         }
-        // Writes beyond the disk boundary are silently ignored.
+    }
+
+    /// End the current transfer with an uncorrectable data error.
+    fn fail_transfer(&mut self) {
+        self.sectors_remaining = 0;
+        self.is_write = false;
+        self.buffer_offset = 0;
+        self.status = SR_DRDY | SR_ERR;
+        self.error = ER_UNC;
+        self.irq_pending = true;
     }
 
     /// Current LBA for the ongoing transfer, computed from the task file.
@@ -347,6 +453,15 @@ impl Ide {
             return;
         }
 
+        if self.read_only && matches!(cmd,
+            CMD_WRITE_SECTORS | CMD_WRITE_SECTORS_EXT | CMD_WRITE_MULTIPLE)
+        {
+            self.status = SR_DRDY | SR_ERR;
+            self.error = ER_ABRT;
+            self.irq_pending = true;
+            return;
+        }
+
         match cmd {
             CMD_IDENTIFY => {
                 if self.total_sectors == 0 {
@@ -428,9 +543,14 @@ impl Ide {
             }
 
             CMD_FLUSH_CACHE => {
-                // No write-back cache to flush.
-                self.status = SR_DRDY | SR_DSC;
-                self.error = 0;
+                // Write back the host image's block cache.
+                if self.flush() {
+                    self.status = SR_DRDY | SR_DSC;
+                    self.error = 0;
+                } else {
+                    self.status = SR_DRDY | SR_ERR;
+                    self.error = ER_ABRT;
+                }
                 self.irq_pending = true;
             }
 
@@ -464,7 +584,10 @@ impl Ide {
         }
         self.sectors_remaining = count;
         self.is_write = false;
-        self.read_sector(lba);
+        if !self.read_sector(lba) {
+            self.fail_transfer();
+            return;
+        }
         self.sectors_remaining -= 1;
         self.status = SR_DRDY | SR_DRQ | SR_DSC;
         self.error = 0;
@@ -491,7 +614,10 @@ impl Ide {
                 // Load next sector.
                 self.advance_lba();
                 let lba = self.current_lba();
-                self.read_sector(lba);
+                if !self.read_sector(lba) {
+                    self.fail_transfer();
+                    return word;
+                }
                 self.sectors_remaining -= 1;
                 self.irq_pending = true;
             } else {
@@ -522,7 +648,10 @@ impl Ide {
         if self.buffer_offset >= SECTOR_SIZE {
             // Write this sector to disk.
             let lba = self.current_lba();
-            self.write_sector(lba);
+            if !self.write_sector(lba) {
+                self.fail_transfer();
+                return;
+            }
             self.sectors_remaining -= 1;
 
             if self.sectors_remaining > 0 {
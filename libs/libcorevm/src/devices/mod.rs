@@ -9,15 +9,22 @@
 //! - [`pit`] — Intel 8253/8254 PIT (Programmable Interval Timer)
 //! - [`cmos`] — CMOS RTC and NVRAM
 //! - [`ps2`] — PS/2 controller (keyboard + mouse)
-//! - [`serial`] — 16550 UART serial port (COM1)
+//! - [`sys_ctrl`] — System Control Port A (port 0x92: fast A20 and reset)
+//! - [`serial`] — 16550 UART serial ports (COM1-COM4)
 //! - [`svga`] — Simple VGA/SVGA framebuffer
 //! - [`e1000`] — Intel E1000 network card
 //! - [`bus`] — PCI configuration space and system bus
+//! - [`acpi_pm`] — ACPI PM1a event/control registers (guest power-off)
+//! - [`balloon`] — memory balloon for reclaiming guest RAM
+//! - [`flash`] — CFI NOR flash with host-file persistence
+//! - [`debug_console`] — Bochs-style port 0xE9 log console
+//! - [`test_exit`] — magic-value exit port for test harnesses
 
 pub mod pic;
 pub mod pit;
 pub mod cmos;
 pub mod ps2;
+pub mod sys_ctrl;
 pub mod serial;
 pub mod svga;
 pub mod e1000;
@@ -26,3 +33,8 @@ pub mod fw_cfg;
 pub mod ide;
 pub mod debug_port;
 pub mod ioapic;
+pub mod acpi_pm;
+pub mod balloon;
+pub mod flash;
+pub mod debug_console;
+pub mod test_exit;
@@ -19,9 +19,25 @@
 //! | 0   | OBF  | Output buffer full (data available for guest to read) |
 //! | 1   | IBF  | Input buffer full (controller processing a command) |
 //! | 5   | MOBF | Mouse output buffer full (data is from mouse, not keyboard) |
+//!
+//! # Output Port
+//!
+//! Read with command 0xD0, written with 0xD1. Bit 0 drives the CPU reset
+//! line (active low) and bit 1 the A20 gate; commands 0xDD / 0xDF disable /
+//! enable A20 directly. The gate is shared with System Control Port A
+//! (see [`super::sys_ctrl`]) through [`Ps2Controller::set_a20_gate`].
+//!
+//! # Scancodes
+//!
+//! Host key events are given as set 1 (XT) make codes plus an extended (E0)
+//! flag. The keyboard emits them in the scancode set the guest selected
+//! (command 0xF0): set 1 as-is, set 2 (and set 3, approximated by set 2)
+//! with `F0` break prefixes. When the translation bit of the configuration
+//! byte is set, the controller converts the keyboard's set 2 stream back to
+//! set 1 like a real 8042, so guests see correct keys in every combination.
 
 use alloc::collections::VecDeque;
-use crate::error::Result;
+use crate::error::{Result, VmError};
 use crate::io::IoHandler;
 
 /// Intel 8042-compatible PS/2 controller.
@@ -52,6 +68,12 @@ pub struct Ps2Controller {
     /// Whether the keyboard is expecting a parameter byte for a
     /// multi-byte device command (e.g., 0xED set LEDs, 0xF0 scancode set).
     kbd_expecting_param: Option<u8>,
+    /// A raw `0xE0` was passed to `key_press`/`key_release`; the next code
+    /// is an extended key.
+    raw_e0_pending: bool,
+    /// The MMU's A20 gate (null until `set_a20_gate`; A20 then reads as
+    /// enabled and writes are ignored).
+    a20: *mut bool,
 }
 
 /// Status register bit masks.
@@ -59,6 +81,93 @@ const STATUS_OUTPUT_FULL: u8 = 0x01;
 const STATUS_INPUT_FULL: u8 = 0x02;
 const STATUS_MOUSE_DATA: u8 = 0x20;
 
+/// Configuration byte bit 6: translate keyboard set 2 output to set 1.
+const CONFIG_TRANSLATE: u8 = 0x40;
+
+/// 8042 translation table: set 2 code → set 1 code (0x00..=0x83; codes
+/// above 0x83 pass through unchanged).
+const SET2_TO_SET1: [u8; 0x84] = [
+    0xff, 0x43, 0x41, 0x3f, 0x3d, 0x3b, 0x3c, 0x58, 0x64, 0x44, 0x42, 0x40, 0x3e, 0x0f, 0x29, 0x59,
+    0x65, 0x38, 0x2a, 0x70, 0x1d, 0x10, 0x02, 0x5a, 0x66, 0x71, 0x2c, 0x1f, 0x1e, 0x11, 0x03, 0x5b,
+    0x67, 0x2e, 0x2d, 0x20, 0x12, 0x05, 0x04, 0x5c, 0x68, 0x39, 0x2f, 0x21, 0x14, 0x13, 0x06, 0x5d,
+    0x69, 0x31, 0x30, 0x23, 0x22, 0x15, 0x07, 0x5e, 0x6a, 0x72, 0x32, 0x24, 0x16, 0x08, 0x09, 0x5f,
+    0x6b, 0x33, 0x25, 0x17, 0x18, 0x0b, 0x0a, 0x60, 0x6c, 0x34, 0x35, 0x26, 0x27, 0x19, 0x0c, 0x61,
+    0x6d, 0x73, 0x28, 0x74, 0x1a, 0x0d, 0x62, 0x6e, 0x3a, 0x36, 0x1c, 0x1b, 0x75, 0x2b, 0x63, 0x76,
+    0x55, 0x56, 0x77, 0x78, 0x79, 0x7a, 0x0e, 0x7b, 0x7c, 0x4f, 0x7d, 0x4b, 0x47, 0x7e, 0x7f, 0x6f,
+    0x52, 0x53, 0x50, 0x4c, 0x4d, 0x48, 0x01, 0x45, 0x57, 0x4e, 0x51, 0x4a, 0x37, 0x49, 0x46, 0x54,
+    0x80, 0x81, 0x82, 0x41,
+];
+
+/// Translate one set 2 byte to set 1 (as the 8042 does with XLATE on).
+fn set2_to_set1(code: u8) -> u8 {
+    SET2_TO_SET1.get(code as usize).copied().unwrap_or(code)
+}
+
+/// Set 2 make code of a set 1 make code (inverse of the 8042 table).
+fn set1_to_set2(code: u8) -> u8 {
+    match code {
+        // F7 is the only key whose set 2 code lies above 0x7F.
+        0x41 => 0x83,
+        _ => (1..0x80u8).find(|&c| SET2_TO_SET1[c as usize] == code).unwrap_or(code),
+    }
+}
+
+// anyOS key codes (compositor `KEY_*`); other codes below 0x80 are set 1
+// make codes, and `0xE000 | code` is an extended (E0-prefixed) set 1 code.
+const KEY_ENTER: u32 = 0x100;
+const KEY_BACKSPACE: u32 = 0x101;
+const KEY_TAB: u32 = 0x102;
+const KEY_ESCAPE: u32 = 0x103;
+const KEY_SPACE: u32 = 0x104;
+const KEY_UP: u32 = 0x105;
+const KEY_DOWN: u32 = 0x106;
+const KEY_LEFT: u32 = 0x107;
+const KEY_RIGHT: u32 = 0x108;
+const KEY_DELETE: u32 = 0x120;
+const KEY_HOME: u32 = 0x121;
+const KEY_END: u32 = 0x122;
+const KEY_PAGE_UP: u32 = 0x123;
+const KEY_PAGE_DOWN: u32 = 0x124;
+const KEY_F1: u32 = 0x140;
+const KEY_F12: u32 = 0x14B;
+const KEY_VOLUME_UP: u32 = 0x160;
+const KEY_VOLUME_DOWN: u32 = 0x161;
+const KEY_VOLUME_MUTE: u32 = 0x162;
+/// Marker for an explicit extended set 1 code (`0xE000 | code`).
+const KEY_EXTENDED: u32 = 0xE000;
+
+/// Map an anyOS key code to its set 1 make code and extended (E0) flag.
+pub fn keycode_to_set1(keycode: u32) -> Option<(u8, bool)> {
+    let key = match keycode {
+        KEY_ENTER => (0x1C, false),
+        KEY_BACKSPACE => (0x0E, false),
+        KEY_TAB => (0x0F, false),
+        KEY_ESCAPE => (0x01, false),
+        KEY_SPACE => (0x39, false),
+        KEY_UP => (0x48, true),
+        KEY_DOWN => (0x50, true),
+        KEY_LEFT => (0x4B, true),
+        KEY_RIGHT => (0x4D, true),
+        KEY_DELETE => (0x53, true),
+        KEY_HOME => (0x47, true),
+        KEY_END => (0x4F, true),
+        KEY_PAGE_UP => (0x49, true),
+        KEY_PAGE_DOWN => (0x51, true),
+        KEY_F1..=KEY_F12 => {
+            let n = (keycode - KEY_F1) as u8;
+            // F1-F10 are contiguous; F11/F12 live at 0x57/0x58.
+            (if n < 10 { 0x3B + n } else { 0x57 + n - 10 }, false)
+        }
+        KEY_VOLUME_UP => (0x30, true),
+        KEY_VOLUME_DOWN => (0x2E, true),
+        KEY_VOLUME_MUTE => (0x20, true),
+        k if k & 0xFF00 == KEY_EXTENDED && k & 0x80 == 0 => ((k & 0x7F) as u8, true),
+        k if k < 0x80 && k != 0 => (k as u8, false),
+        _ => return None,
+    };
+    Some(key)
+}
+
 impl Ps2Controller {
     /// Create a new PS/2 controller with keyboard enabled and mouse disabled.
     pub fn new() -> Self {
@@ -74,34 +183,111 @@ impl Ps2Controller {
             keyboard_buffer: VecDeque::new(),
             write_to_mouse: false,
             kbd_expecting_param: None,
+            raw_e0_pending: false,
+            a20: core::ptr::null_mut(),
         }
     }
 
-    /// Enqueue a keyboard make (press) scancode.
-    ///
-    /// The scancode is pushed into the keyboard buffer and will be
-    /// delivered to the guest on the next read from port 0x60.
-    pub fn key_press(&mut self, scancode: u8) {
-        if self.keyboard_enabled {
-            self.keyboard_buffer.push_back(scancode);
-            self.update_output_buffer();
+    /// Connect output port bit 1 to the A20 gate at `a20` (the MMU's
+    /// `a20_enabled`). The pointer must stay valid for the controller's
+    /// lifetime.
+    pub fn set_a20_gate(&mut self, a20: *mut bool) {
+        self.a20 = a20;
+    }
+
+    fn a20_enabled(&self) -> bool {
+        self.a20.is_null() || unsafe { *self.a20 }
+    }
+
+    fn set_a20(&mut self, enabled: bool) {
+        if !self.a20.is_null() {
+            unsafe { *self.a20 = enabled };
         }
     }
 
-    /// Enqueue a keyboard break (release) scancode.
+    /// Enqueue a key press given its set 1 make code.
     ///
-    /// For scancode set 2, the break code is the two-byte sequence
-    /// `0xF0, scancode`. For set 1, the break code is `scancode | 0x80`.
+    /// A `0xE0` byte marks the next code as an extended key. The key is
+    /// delivered in the guest's scancode set (see the module docs).
+    pub fn key_press(&mut self, scancode: u8) {
+        self.raw_key(scancode, true);
+    }
+
+    /// Enqueue a key release given its set 1 make code (see `key_press`).
     pub fn key_release(&mut self, scancode: u8) {
-        if self.keyboard_enabled {
-            if self.scancode_set == 1 {
-                self.keyboard_buffer.push_back(scancode | 0x80);
-            } else {
-                // Scancode set 2 (and 3): break prefix + make code.
-                self.keyboard_buffer.push_back(0xF0);
-                self.keyboard_buffer.push_back(scancode);
+        self.raw_key(scancode, false);
+    }
+
+    fn raw_key(&mut self, scancode: u8, pressed: bool) {
+        if scancode == 0xE0 {
+            self.raw_e0_pending = true;
+            return;
+        }
+        let extended = core::mem::replace(&mut self.raw_e0_pending, false);
+        self.key_event(scancode & 0x7F, extended, pressed);
+    }
+
+    /// Inject an anyOS key code press or release. Returns false if the key
+    /// code has no PS/2 equivalent.
+    pub fn inject_key(&mut self, keycode: u32, pressed: bool) -> bool {
+        match keycode_to_set1(keycode) {
+            Some((code, extended)) => {
+                self.key_event(code, extended, pressed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Emit the make/break sequence of a key (set 1 make code) in the
+    /// active scancode set, through the controller's translation.
+    pub fn key_event(&mut self, code: u8, extended: bool, pressed: bool) {
+        if !self.keyboard_enabled {
+            return;
+        }
+        if self.scancode_set == 1 {
+            // Keyboard already speaks set 1; the 8042 passes it through.
+            if extended {
+                self.keyboard_buffer.push_back(0xE0);
+            }
+            self.keyboard_buffer.push_back(if pressed { code } else { code | 0x80 });
+        } else {
+            let mut seq = [0u8; 3];
+            let mut n = 0;
+            if extended {
+                seq[n] = 0xE0;
+                n += 1;
+            }
+            if !pressed {
+                seq[n] = 0xF0;
+                n += 1;
+            }
+            seq[n] = set1_to_set2(code);
+            n += 1;
+            self.push_keyboard_bytes(&seq[..n]);
+        }
+        self.update_output_buffer();
+    }
+
+    /// Queue set 2 bytes from the keyboard, translating them to set 1 when
+    /// the configuration byte asks for it.
+    fn push_keyboard_bytes(&mut self, bytes: &[u8]) {
+        if self.command_byte & CONFIG_TRANSLATE == 0 {
+            self.keyboard_buffer.extend(bytes.iter().copied());
+            return;
+        }
+        let mut release = false;
+        for &b in bytes {
+            match b {
+                // Break prefix becomes bit 7 of the next code.
+                0xF0 => release = true,
+                0xE0 | 0xE1 => self.keyboard_buffer.push_back(b),
+                _ => {
+                    let code = set2_to_set1(b);
+                    self.keyboard_buffer.push_back(if release { code | 0x80 } else { code });
+                    release = false;
+                }
             }
-            self.update_output_buffer();
         }
     }
 
@@ -314,6 +500,14 @@ impl IoHandler for Ps2Controller {
                             // Write to mouse.
                             self.handle_mouse_data(byte);
                         }
+                        0xD1 => {
+                            // Write output port. Bit 0 drives the CPU reset
+                            // line (active low), bit 1 the A20 gate.
+                            self.set_a20(byte & 0x02 != 0);
+                            if byte & 0x01 == 0 {
+                                return Err(VmError::ResetRequested);
+                            }
+                        }
                         _ => {}
                     }
                 } else if self.write_to_mouse {
@@ -365,10 +559,39 @@ impl IoHandler for Ps2Controller {
                         // Enable keyboard.
                         self.keyboard_enabled = true;
                     }
+                    0xD0 => {
+                        // Read output port: reset line high, A20 gate,
+                        // output buffer full flags for IRQ 1 / IRQ 12.
+                        let mut port = 0x01;
+                        if self.a20_enabled() { port |= 0x02; }
+                        if self.status & STATUS_OUTPUT_FULL != 0 {
+                            port |= if self.status & STATUS_MOUSE_DATA != 0 { 0x20 } else { 0x10 };
+                        }
+                        self.output_buffer.push_back(port);
+                        self.status |= STATUS_OUTPUT_FULL;
+                        self.status &= !STATUS_MOUSE_DATA;
+                    }
+                    0xD1 => {
+                        // Write output port (next data byte).
+                        self.expecting_data = Some(0xD1);
+                    }
                     0xD4 => {
                         // Next byte written to port 0x60 goes to the mouse.
                         self.expecting_data = Some(0xD4);
                     }
+                    0xDD => {
+                        // Disable A20.
+                        self.set_a20(false);
+                    }
+                    0xDF => {
+                        // Enable A20.
+                        self.set_a20(true);
+                    }
+                    0xF0..=0xFF if byte & 0x01 == 0 => {
+                        // Pulse output port bits low; bit 0 is the CPU reset
+                        // line, so 0xFE (and any even pulse mask) resets.
+                        return Err(VmError::ResetRequested);
+                    }
                     _ => {
                         // Unknown controller command — ignore.
                     }
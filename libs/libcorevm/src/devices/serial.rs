@@ -1,14 +1,22 @@
-//! 16550 UART serial port (COM1) emulation.
+//! 16550 UART serial port (COM1-COM4) emulation.
 //!
-//! Emulates an NS16550A-compatible UART at the standard COM1 base address.
-//! The device supports DLAB (Divisor Latch Access Bit) for baud rate
-//! configuration, FIFO mode, and modem control/status registers.
+//! Emulates an NS16550A-compatible UART at one of the standard PC base
+//! addresses (see [`COM_PORTS`]). The device supports DLAB (Divisor Latch
+//! Access Bit) for baud rate configuration, FIFO mode, and modem
+//! control/status registers.
 //!
 //! Characters written by the guest to the Transmit Holding Register (THR)
 //! are collected in an output buffer. Characters injected via [`Serial::send_input`]
 //! become available for the guest to read from the Receive Buffer Register (RBR).
 //!
-//! # I/O Ports (COM1: 0x3F8-0x3FF)
+//! # Interrupts
+//!
+//! "Received data available" (IER bit 0) and "THR empty" (IER bit 1) are
+//! reported through the IIR. As on a real PC, the IRQ line only reaches the
+//! PIC while MCR OUT2 is set. The UART itself cannot signal the PIC; the VM
+//! polls [`Serial::take_irq_edge`] and raises the port's IRQ (4 or 3).
+//!
+//! # I/O Ports (relative to the base, e.g. COM1: 0x3F8-0x3FF)
 //!
 //! | Offset | DLAB=0 Read | DLAB=0 Write | DLAB=1 Read | DLAB=1 Write |
 //! |--------|-------------|--------------|-------------|--------------|
@@ -31,9 +39,30 @@ const LSR_DATA_READY: u8 = 0x01;
 const LSR_THR_EMPTY: u8 = 0x20;
 const LSR_XMIT_EMPTY: u8 = 0x40;
 
-/// 16550 UART serial port emulation (COM1).
+/// Interrupt Enable Register bit masks.
+const IER_RX_AVAILABLE: u8 = 0x01;
+const IER_THR_EMPTY: u8 = 0x02;
+
+/// Interrupt Identification Register values (low nibble).
+const IIR_NONE: u8 = 0x01;
+const IIR_THR_EMPTY: u8 = 0x02;
+const IIR_RX_AVAILABLE: u8 = 0x04;
+/// IIR bits 6-7: FIFOs enabled.
+const IIR_FIFO_BITS: u8 = 0xC0;
+
+/// Modem Control Register OUT2 bit — gates the IRQ line on PCs.
+const MCR_OUT2: u8 = 0x08;
+
+/// Standard PC serial ports: `(I/O base, IRQ)` for COM1-COM4.
+pub const COM_PORTS: [(u16, u8); 4] = [(0x3F8, 4), (0x2F8, 3), (0x3E8, 4), (0x2E8, 3)];
+
+/// 16550 UART serial port emulation (one of COM1-COM4).
 #[derive(Debug)]
 pub struct Serial {
+    /// First I/O port of the register block.
+    pub base: u16,
+    /// ISA IRQ line the port is wired to.
+    pub irq: u8,
     /// Receive Buffer Register — last byte received from input.
     pub rbr: u8,
     /// Transmit Holding Register — last byte written by guest.
@@ -64,19 +93,27 @@ pub struct Serial {
     /// Characters available for the guest to read (RBR input), injected
     /// by the host via [`send_input`](Serial::send_input).
     pub input: VecDeque<u8>,
+    /// A "THR empty" interrupt is pending (cleared by reading IIR or
+    /// writing THR).
+    thre_pending: bool,
+    /// Level of the IRQ line at the last [`take_irq_edge`](Serial::take_irq_edge).
+    irq_asserted: bool,
 }
 
 impl Serial {
-    /// Create a new serial port in its power-on default state.
+    /// Create a new serial port at `base` wired to `irq`, in its power-on
+    /// default state.
     ///
     /// The Line Status Register starts with THR empty and transmitter
     /// empty flags set, indicating the port is ready to accept data.
-    pub fn new() -> Self {
+    pub fn new(base: u16, irq: u8) -> Self {
         Serial {
+            base,
+            irq,
             rbr: 0,
             thr: 0,
             ier: 0,
-            iir: 0x01, // no interrupt pending
+            iir: IIR_NONE,
             fcr: 0,
             lcr: 0,
             mcr: 0,
@@ -87,6 +124,8 @@ impl Serial {
             dlm: 0,
             output: VecDeque::new(),
             input: VecDeque::new(),
+            thre_pending: false,
+            irq_asserted: false,
         }
     }
 
@@ -111,6 +150,33 @@ impl Serial {
         self.output.drain(..).collect()
     }
 
+    /// Highest-priority pending interrupt, as reported in IIR bits 0-3.
+    fn interrupt_id(&self) -> u8 {
+        if self.ier & IER_RX_AVAILABLE != 0 && self.lsr & LSR_DATA_READY != 0 {
+            IIR_RX_AVAILABLE
+        } else if self.ier & IER_THR_EMPTY != 0 && self.thre_pending {
+            IIR_THR_EMPTY
+        } else {
+            IIR_NONE
+        }
+    }
+
+    /// Whether the UART currently drives its IRQ line (gated by MCR OUT2).
+    pub fn irq_pending(&self) -> bool {
+        self.interrupt_id() != IIR_NONE && self.mcr & MCR_OUT2 != 0
+    }
+
+    /// Returns `true` once per rising edge of the IRQ line.
+    ///
+    /// The PIC is edge-triggered, so the VM raises [`irq`](Serial::irq)
+    /// only when this reports a new assertion.
+    pub fn take_irq_edge(&mut self) -> bool {
+        let level = self.irq_pending();
+        let edge = level && !self.irq_asserted;
+        self.irq_asserted = level;
+        edge
+    }
+
     /// Returns `true` if DLAB (Divisor Latch Access Bit) is set in the LCR.
     #[inline]
     fn dlab(&self) -> bool {
@@ -123,7 +189,7 @@ impl IoHandler for Serial {
     ///
     /// Register selection depends on the port offset and DLAB state.
     fn read(&mut self, port: u16, _size: u8) -> Result<u32> {
-        let offset = port - 0x3F8;
+        let offset = port - self.base;
         let val = match offset {
             0 => {
                 if self.dlab() {
@@ -146,7 +212,15 @@ impl IoHandler for Serial {
                     self.ier
                 }
             }
-            2 => self.iir,
+            2 => {
+                let id = self.interrupt_id();
+                // Reading IIR acknowledges a THR-empty interrupt.
+                if id == IIR_THR_EMPTY {
+                    self.thre_pending = false;
+                }
+                self.iir = (self.iir & IIR_FIFO_BITS) | id;
+                self.iir
+            }
             3 => self.lcr,
             4 => self.mcr,
             5 => self.lsr,
@@ -161,7 +235,7 @@ impl IoHandler for Serial {
     ///
     /// Register selection depends on the port offset and DLAB state.
     fn write(&mut self, port: u16, _size: u8, val: u32) -> Result<()> {
-        let offset = port - 0x3F8;
+        let offset = port - self.base;
         let byte = val as u8;
         match offset {
             0 => {
@@ -174,12 +248,18 @@ impl IoHandler for Serial {
                     self.output.push_back(byte);
                     // THR is immediately "empty" again (infinite speed UART).
                     self.lsr |= LSR_THR_EMPTY | LSR_XMIT_EMPTY;
+                    self.thre_pending = true;
                 }
             }
             1 => {
                 if self.dlab() {
                     self.dlm = byte;
                 } else {
+                    // Enabling the THR-empty interrupt while THR is empty
+                    // raises it immediately, as on real hardware.
+                    if byte & IER_THR_EMPTY != 0 && self.ier & IER_THR_EMPTY == 0 {
+                        self.thre_pending = true;
+                    }
                     self.ier = byte & 0x0F; // only low 4 bits are writable
                 }
             }
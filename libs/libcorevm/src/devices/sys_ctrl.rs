@@ -0,0 +1,56 @@
+//! System Control Port A (port 0x92, "fast A20").
+//!
+//! The PS/2-era shortcut for the A20 gate and a CPU reset, without going
+//! through the keyboard controller.
+//!
+//! | Bit | Description |
+//! |-----|-------------|
+//! | 0   | Fast reset: writing 1 pulses the CPU reset line (reads as 0) |
+//! | 1   | A20 gate: 1 = address line 20 enabled |
+//! | 2-7 | Stored and read back, no effect |
+//!
+//! The A20 gate is the MMU's `a20_enabled` flag, which the keyboard
+//! controller output port drives as well; both devices hold a raw pointer
+//! to it (see [`Mmu::a20_enabled`](crate::memory::Mmu::a20_enabled)).
+
+use crate::error::{Result, VmError};
+use crate::io::IoHandler;
+
+/// Port number of System Control Port A.
+pub const PORT: u16 = 0x92;
+
+const FAST_RESET: u8 = 0x01;
+const A20: u8 = 0x02;
+
+/// System Control Port A.
+#[derive(Debug)]
+pub struct SystemControlA {
+    /// Bits 2-7 as last written.
+    value: u8,
+    /// The MMU's A20 gate. Valid for the lifetime of the owning `VmInstance`.
+    a20: *mut bool,
+}
+
+impl SystemControlA {
+    /// Create the port driving the A20 gate at `a20`.
+    pub fn new(a20: *mut bool) -> Self {
+        SystemControlA { value: 0, a20 }
+    }
+}
+
+impl IoHandler for SystemControlA {
+    fn read(&mut self, _port: u16, _size: u8) -> Result<u32> {
+        let a20 = if unsafe { *self.a20 } { A20 } else { 0 };
+        Ok((self.value | a20) as u32)
+    }
+
+    fn write(&mut self, _port: u16, _size: u8, val: u32) -> Result<()> {
+        let byte = val as u8;
+        unsafe { *self.a20 = byte & A20 != 0 };
+        self.value = byte & !(FAST_RESET | A20);
+        if byte & FAST_RESET != 0 {
+            return Err(VmError::ResetRequested);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+//! Test-exit port for automated test harnesses.
+//!
+//! A guest test suite reports its result by writing a 32-bit value whose
+//! upper half is [`EXIT_MAGIC`] to this port; the lower half is the status
+//! (by convention 0 = pass, anything else = failure or panic code). The
+//! write fails with [`VmError::GuestExit`], which the CPU run loop turns
+//! into [`ExitReason::GuestExit`](crate::cpu::ExitReason::GuestExit), so
+//! `corevm_run` returns a code of its own instead of the harness having to
+//! scrape log output.
+//!
+//! Writes without the magic (BIOS port probing, stray byte writes) are
+//! ignored, so the port can stay mapped for guests that do not use it.
+//!
+//! # I/O Port
+//!
+//! | Port | Width | Direction | Description |
+//! |------|-------|-----------|-------------|
+//! | 0xF4 | 32-bit | Write | `EXIT_MAGIC << 16 \| status` |
+//! | 0xF4 | 32-bit | Read | Returns `EXIT_MAGIC` (port present) |
+
+use crate::error::{Result, VmError};
+use crate::io::IoHandler;
+
+/// Upper 16 bits of a valid exit write.
+pub const EXIT_MAGIC: u16 = 0x7E57;
+
+/// Number of I/O ports occupied by the device.
+pub const PORT_COUNT: u16 = 4;
+
+/// Test-exit port emulation.
+#[derive(Debug)]
+pub struct TestExit {
+    /// Status of the last exit the guest reported, if any.
+    pub status: Option<u16>,
+}
+
+impl TestExit {
+    pub fn new() -> Self {
+        TestExit { status: None }
+    }
+}
+
+impl IoHandler for TestExit {
+    fn read(&mut self, _port: u16, _size: u8) -> Result<u32> {
+        Ok(EXIT_MAGIC as u32)
+    }
+
+    fn write(&mut self, _port: u16, size: u8, val: u32) -> Result<()> {
+        if size == 4 && (val >> 16) as u16 == EXIT_MAGIC {
+            let status = val as u16;
+            self.status = Some(status);
+            return Err(VmError::GuestExit(status));
+        }
+        Ok(())
+    }
+}
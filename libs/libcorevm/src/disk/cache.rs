@@ -0,0 +1,146 @@
+//! Write-back block cache over a [`HostFile`].
+//!
+//! The file is split into fixed-size blocks; up to `capacity` of them are
+//! kept in memory. A miss evicts the least recently used block, writing it
+//! back first if it was modified.
+
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::file::HostFile;
+
+/// Memory a cache may use (block size × capacity).
+pub const CACHE_BYTES: usize = 4 * 1024 * 1024;
+
+struct Block {
+    /// Block number in the file.
+    index: u64,
+    data: Vec<u8>,
+    /// Bytes of `data` that exist in the file or were written; only these
+    /// are written back, so a partial last block does not grow the file.
+    valid: usize,
+    dirty: bool,
+    /// Access tick of the last use (for LRU eviction).
+    used: u64,
+}
+
+pub struct BlockCache {
+    block_size: usize,
+    capacity: usize,
+    blocks: Vec<Block>,
+    /// Block number → position in `blocks`.
+    map: BTreeMap<u64, usize>,
+    tick: u64,
+}
+
+impl BlockCache {
+    /// A cache of `block_size`-byte blocks using about [`CACHE_BYTES`].
+    pub fn new(block_size: usize) -> Self {
+        BlockCache {
+            block_size,
+            capacity: (CACHE_BYTES / block_size).max(8),
+            blocks: Vec::new(),
+            map: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Position in `blocks` of block `index`, loading it on a miss. With
+    /// `overwrite` the caller replaces the whole block, so it is not read.
+    fn slot(&mut self, file: &mut HostFile, index: u64, overwrite: bool) -> Option<usize> {
+        self.tick += 1;
+        if let Some(&i) = self.map.get(&index) {
+            self.blocks[i].used = self.tick;
+            return Some(i);
+        }
+        let i = if self.blocks.len() < self.capacity {
+            self.blocks.push(Block { index, data: vec![0u8; self.block_size], valid: 0, dirty: false, used: 0 });
+            self.blocks.len() - 1
+        } else {
+            let (i, _) = self.blocks.iter().enumerate().min_by_key(|(_, b)| b.used)?;
+            let victim = &self.blocks[i];
+            if victim.dirty && !file.write_at(victim.index * self.block_size as u64, &victim.data[..victim.valid]) {
+                return None;
+            }
+            self.map.remove(&victim.index);
+            i
+        };
+        let start = index * self.block_size as u64;
+        let block = &mut self.blocks[i];
+        block.valid = 0;
+        if !overwrite && !file.read_at(start, &mut block.data) {
+            // Leave the slot unmapped; it is reused by the next miss.
+            block.used = 0;
+            block.dirty = false;
+            return None;
+        }
+        if !overwrite {
+            block.valid = file.size().saturating_sub(start).min(self.block_size as u64) as usize;
+        }
+        block.index = index;
+        block.dirty = false;
+        block.used = self.tick;
+        self.map.insert(index, i);
+        Some(i)
+    }
+
+    /// Read `buf.len()` bytes at file offset `offset`.
+    pub fn read(&mut self, file: &mut HostFile, offset: u64, buf: &mut [u8]) -> bool {
+        let bs = self.block_size as u64;
+        let mut done = 0;
+        while done < buf.len() {
+            let pos = offset + done as u64;
+            let within = (pos % bs) as usize;
+            let n = (self.block_size - within).min(buf.len() - done);
+            let i = match self.slot(file, pos / bs, false) {
+                Some(i) => i,
+                None => return false,
+            };
+            buf[done..done + n].copy_from_slice(&self.blocks[i].data[within..within + n]);
+            done += n;
+        }
+        true
+    }
+
+    /// Write `data` at file offset `offset`. The file itself is only
+    /// updated on eviction or [`flush`](Self::flush).
+    pub fn write(&mut self, file: &mut HostFile, offset: u64, data: &[u8]) -> bool {
+        if !file.writable() {
+            return false;
+        }
+        let bs = self.block_size as u64;
+        let mut done = 0;
+        while done < data.len() {
+            let pos = offset + done as u64;
+            let within = (pos % bs) as usize;
+            let n = (self.block_size - within).min(data.len() - done);
+            let i = match self.slot(file, pos / bs, n == self.block_size) {
+                Some(i) => i,
+                None => return false,
+            };
+            let block = &mut self.blocks[i];
+            block.data[within..within + n].copy_from_slice(&data[done..done + n]);
+            block.valid = block.valid.max(within + n);
+            block.dirty = true;
+            done += n;
+        }
+        true
+    }
+
+    /// Write every modified block back to the file, in file order.
+    pub fn flush(&mut self, file: &mut HostFile) -> bool {
+        let mut ok = true;
+        for &i in self.map.values() {
+            let block = &mut self.blocks[i];
+            if block.dirty {
+                if file.write_at(block.index * self.block_size as u64, &block.data[..block.valid]) {
+                    block.dirty = false;
+                } else {
+                    ok = false;
+                }
+            }
+        }
+        ok
+    }
+}
@@ -0,0 +1,117 @@
+//! Positioned I/O on a host file.
+
+use super::DiskError;
+
+/// Largest step of a single `lseek` (the offset argument is an `i32`).
+const MAX_SEEK_STEP: u64 = i32::MAX as u64;
+/// `lseek` whence: relative to the current position.
+const SEEK_CUR: u32 = 1;
+
+/// An open host file with read/write at absolute offsets.
+///
+/// The file is closed when this is dropped.
+pub struct HostFile {
+    fd: u32,
+    /// Current file length in bytes.
+    size: u64,
+    writable: bool,
+}
+
+impl HostFile {
+    /// Open an existing file, for reading and (with `writable`) writing.
+    pub fn open(path: &str, writable: bool) -> Result<Self, DiskError> {
+        let fd = libsyscall::open(path, if writable { libsyscall::O_WRITE } else { 0 });
+        if fd == u32::MAX {
+            return Err(DiskError::NotFound);
+        }
+        let mut st = [0u32; 4];
+        if libsyscall::fstat(fd, &mut st) != 0 {
+            libsyscall::close(fd);
+            return Err(DiskError::Io);
+        }
+        Ok(HostFile { fd, size: st[1] as u64, writable })
+    }
+
+    /// Create `path` (emptying an existing file) and open it for writing.
+    pub fn create(path: &str) -> Result<Self, DiskError> {
+        let fd = libsyscall::open(path, libsyscall::O_WRITE | libsyscall::O_CREATE | libsyscall::O_TRUNC);
+        if fd == u32::MAX {
+            return Err(DiskError::NotFound);
+        }
+        Ok(HostFile { fd, size: 0, writable: true })
+    }
+
+    /// File length in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn writable(&self) -> bool {
+        self.writable
+    }
+
+    /// Move the file position to `offset`, in `i32`-sized steps.
+    fn seek(&self, offset: u64) -> bool {
+        if offset > u32::MAX as u64 {
+            return false;
+        }
+        let first = offset.min(MAX_SEEK_STEP);
+        if libsyscall::lseek(self.fd, first as i32, libsyscall::SEEK_SET) != first as u32 {
+            return false;
+        }
+        let mut pos = first;
+        while pos < offset {
+            let step = (offset - pos).min(MAX_SEEK_STEP);
+            pos += step;
+            if libsyscall::lseek(self.fd, step as i32, SEEK_CUR) != pos as u32 {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Fill `buf` from `offset`. Bytes past the end of the file read as zero.
+    pub fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> bool {
+        let avail = self.size.saturating_sub(offset).min(buf.len() as u64) as usize;
+        buf[avail..].fill(0);
+        if avail == 0 {
+            return true;
+        }
+        if !self.seek(offset) {
+            return false;
+        }
+        let mut done = 0;
+        while done < avail {
+            let n = libsyscall::read(self.fd, &mut buf[done..avail]);
+            if n == 0 || n == u32::MAX {
+                return false;
+            }
+            done += n as usize;
+        }
+        true
+    }
+
+    /// Write `data` at `offset`, growing the file if needed.
+    pub fn write_at(&mut self, offset: u64, data: &[u8]) -> bool {
+        let end = offset + data.len() as u64;
+        if !self.writable || end > u32::MAX as u64 || !self.seek(offset) {
+            return false;
+        }
+        let mut done = 0;
+        while done < data.len() {
+            let n = libsyscall::write(self.fd, &data[done..]);
+            if n == 0 || n == u32::MAX {
+                return false;
+            }
+            done += n as usize;
+        }
+        self.size = self.size.max(end);
+        true
+    }
+}
+
+impl Drop for HostFile {
+    fn drop(&mut self) {
+        libsyscall::close(self.fd);
+    }
+}
@@ -0,0 +1,182 @@
+//! Disk image backends for the emulated drives.
+//!
+//! An [`Ide`](crate::devices::ide::Ide) drive reads and writes its medium
+//! through a [`DiskBackend`]. Three backends exist:
+//!
+//! - [`MemDisk`] — a flat image held in memory (images handed over by the
+//!   host as a byte buffer, and generated FAT16 shares).
+//! - [`raw::RawDisk`] — a flat image file on the host, read and written on
+//!   demand through a block cache instead of being loaded whole.
+//! - [`qcow2::Qcow2Disk`] — a QEMU copy-on-write image (versions 2 and 3)
+//!   that only stores the clusters the guest wrote, optionally on top of a
+//!   read-only backing image.
+//!
+//! [`open`] picks the file backend by looking at the image header. File
+//! backends cache writes; they reach the host file on
+//! [`DiskBackend::flush`], which the drive calls on ATA FLUSH CACHE and when
+//! the disk is detached or the VM destroyed.
+//!
+//! Overlays: [`create_overlay`] writes an empty qcow2 image on top of a
+//! base image without copying it. Many VMs can each run on their own
+//! overlay of one shared base: writes stay in the overlay, reads of
+//! unwritten sectors fall through to the base. [`DiskBackend::commit`]
+//! copies an overlay's changes into the base, [`DiskBackend::discard`]
+//! empties the overlay again.
+//!
+//! [`probe::probe`] guesses the guest operating system from a disk's boot
+//! sectors and partition table.
+//!
+//! Host file positions are 32-bit, so image files are limited to 4 GiB
+//! (a qcow2 image can still describe a larger virtual disk).
+
+pub mod file;
+pub mod cache;
+pub mod raw;
+pub mod qcow2;
+pub mod probe;
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+/// A drive medium addressed by byte offset.
+pub trait DiskBackend {
+    /// Capacity in bytes.
+    fn size(&self) -> u64;
+
+    /// Read `buf.len()` bytes at `offset`. Returns false on a host I/O error
+    /// or if the range extends past the end of the disk.
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> bool;
+
+    /// Write `data` at `offset`. Returns false on a host I/O error, if the
+    /// range extends past the end of the disk or the image is read-only.
+    fn write(&mut self, offset: u64, data: &[u8]) -> bool;
+
+    /// Write cached changes back to the host. Returns false on error.
+    fn flush(&mut self) -> bool {
+        true
+    }
+
+    /// The whole medium, if it is held in memory.
+    fn as_bytes(&self) -> Option<&[u8]> {
+        None
+    }
+
+    /// Write the sectors this overlay holds into its base image. Returns
+    /// the number of clusters copied. The overlay keeps its contents.
+    fn commit(&mut self) -> Result<u64, DiskError> {
+        Err(DiskError::Unsupported)
+    }
+
+    /// Drop everything written to this overlay, so the disk reads as its
+    /// base image again.
+    fn discard(&mut self) -> Result<(), DiskError> {
+        Err(DiskError::Unsupported)
+    }
+}
+
+/// Why a disk image could not be opened.
+///
+/// `corevm_ide_open_disk` returns the negated [`code`](DiskError::code).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskError {
+    /// The file does not exist or cannot be opened.
+    NotFound,
+    /// A host read failed.
+    Io,
+    /// The header or metadata tables are malformed.
+    Corrupt,
+    /// The image uses a feature this implementation does not support
+    /// (encryption, external data files, ...), or cannot be opened writable
+    /// (internal snapshots, unclean shutdown).
+    Unsupported,
+    /// The backing image of a qcow2 file could not be opened.
+    Backing,
+}
+
+impl DiskError {
+    /// Positive error code (1-5).
+    pub fn code(&self) -> i32 {
+        match self {
+            DiskError::NotFound => 1,
+            DiskError::Io => 2,
+            DiskError::Corrupt => 3,
+            DiskError::Unsupported => 4,
+            DiskError::Backing => 5,
+        }
+    }
+}
+
+/// Open the image file at `path`, choosing the backend from its header.
+///
+/// With `writable` = false the file is opened read-only and every write
+/// fails.
+pub fn open(path: &str, writable: bool) -> Result<Box<dyn DiskBackend>, DiskError> {
+    open_nested(path, writable, 0)
+}
+
+/// [`open`], tracking how deep in a qcow2 backing chain `path` is.
+fn open_nested(path: &str, writable: bool, depth: u32) -> Result<Box<dyn DiskBackend>, DiskError> {
+    let mut file = file::HostFile::open(path, writable)?;
+    if qcow2::probe(&mut file) {
+        Ok(Box::new(qcow2::Qcow2Disk::open(file, path, depth)?))
+    } else {
+        Ok(Box::new(raw::RawDisk::new(file)))
+    }
+}
+
+/// Create an empty overlay image at `path` on top of the image `base`,
+/// replacing any existing file. `base` is stored in the overlay as given;
+/// a relative name is relative to the overlay's directory. The overlay is
+/// a qcow2 image the size of the base, so [`open`] recognizes it.
+pub fn create_overlay(path: &str, base: &str) -> Result<(), DiskError> {
+    let size = open(&qcow2::resolve_backing(path, base), false).map_err(|_| DiskError::Backing)?.size();
+    qcow2::create(path, size, Some(base))
+}
+
+/// A flat disk image held in memory.
+pub struct MemDisk {
+    data: Vec<u8>,
+}
+
+impl MemDisk {
+    pub fn new(data: Vec<u8>) -> Self {
+        MemDisk { data }
+    }
+
+    /// Take back the image.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+impl DiskBackend for MemDisk {
+    fn size(&self) -> u64 {
+        self.data.len() as u64
+    }
+
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> bool {
+        let start = offset as usize;
+        match self.data.get(start..start + buf.len()) {
+            Some(src) => {
+                buf.copy_from_slice(src);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn write(&mut self, offset: u64, data: &[u8]) -> bool {
+        let start = offset as usize;
+        match self.data.get_mut(start..start + data.len()) {
+            Some(dst) => {
+                dst.copy_from_slice(data);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn as_bytes(&self) -> Option<&[u8]> {
+        Some(&self.data)
+    }
+}
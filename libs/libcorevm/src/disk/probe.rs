@@ -0,0 +1,302 @@
+//! Guest OS detection from the contents of a disk image.
+//!
+//! A front end picks device defaults (RAM, network card) per guest, so it
+//! wants to know what is on a disk before booting it. [`probe`] makes a
+//! best guess from a handful of sector reads, in this order:
+//!
+//! 1. A partitionless file system starting at sector 0 (floppy-style FAT,
+//!    NTFS or ext2/3/4 images), by OEM name, file system type and magic.
+//! 2. Boot code signatures in the MBR and the boot track (the first
+//!    [`SCAN_SECTORS`] sectors): GRUB, SYSLINUX and LILO, BOOTMGR and NTLDR,
+//!    the DOS system file names, the BSD boot blocks and the anyOS stage 2.
+//! 3. The partition table: MBR type bytes, or the type GUIDs of a GPT. The
+//!    first sector of each partition is inspected like sector 0, and ext
+//!    superblocks are recognized by their magic.
+//!
+//! Detection only reads; it never changes the image. The result is a hint —
+//! a disk with several systems reports the one its boot code points to, or
+//! else the first recognized partition (the active one first).
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::DiskBackend;
+
+const SECTOR: usize = 512;
+
+/// Sectors at the start of the disk searched for boot code signatures
+/// (the MBR plus the traditional 63-sector boot track).
+pub const SCAN_SECTORS: usize = 64;
+
+/// Most GPT entries examined.
+const MAX_GPT_ENTRIES: usize = 128;
+
+/// Best guess at the operating system on a disk.
+///
+/// The values are the `u32` codes returned by `corevm_probe_disk`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum GuestOs {
+    /// No disk attached, or sector 0 cannot be read.
+    NoDisk = 0,
+    /// No boot signature and no recognized file system (a new disk).
+    Blank = 1,
+    /// Bootable or formatted, but nothing recognized.
+    Unknown = 2,
+    /// MS-DOS, PC DOS or FreeDOS (FAT12/16 system disk).
+    Dos = 3,
+    /// Windows 95/98/ME (FAT32 or `MSWIN4.x` boot sector).
+    Windows9x = 4,
+    /// Windows NT family: NT, 2000, XP and later (NTFS, NTLDR or BOOTMGR).
+    WindowsNt = 5,
+    /// Linux (GRUB, SYSLINUX, LILO, ext2/3/4 or Linux partition types).
+    Linux = 6,
+    /// FreeBSD, OpenBSD or NetBSD.
+    Bsd = 7,
+    /// anyOS.
+    AnyOs = 8,
+}
+
+/// Guess the operating system on `disk`.
+pub fn probe(disk: &mut dyn DiskBackend) -> GuestOs {
+    let sectors = (disk.size() / SECTOR as u64) as usize;
+    if sectors == 0 {
+        return GuestOs::NoDisk;
+    }
+    let mut track = vec![0u8; SCAN_SECTORS.min(sectors) * SECTOR];
+    if !disk.read(0, &mut track) {
+        // A short image: fall back to the MBR alone.
+        track.truncate(SECTOR);
+        if !disk.read(0, &mut track) {
+            return GuestOs::NoDisk;
+        }
+    }
+
+    let mbr = &track[..SECTOR];
+    if let Some(os) = probe_volume(disk, 0, mbr) {
+        return os;
+    }
+    if let Some(os) = scan_signatures(&track) {
+        return os;
+    }
+    let bootable = mbr[510] == 0x55 && mbr[511] == 0xAA;
+    if bootable {
+        let parts = mbr_partitions(mbr);
+        if parts.iter().any(|p| p.kind == 0xEE) {
+            if let Some(os) = probe_gpt(disk) {
+                return os;
+            }
+        } else if let Some(os) = probe_mbr(disk, &parts) {
+            return os;
+        }
+        return GuestOs::Unknown;
+    }
+    if mbr.iter().all(|&b| b == 0) { GuestOs::Blank } else { GuestOs::Unknown }
+}
+
+// ── Boot code signatures ────────────────────────────────────────────
+
+/// Strings found in boot code, checked in order. Boot loaders come before
+/// file names, which a multi-boot disk may mention for every system.
+const SIGNATURES: &[(&[u8], GuestOs)] = &[
+    (b"Stage 2: Starting", GuestOs::AnyOs),
+    (b"GRUB", GuestOs::Linux),
+    (b"SYSLINUX", GuestOs::Linux),
+    (b"EXTLINUX", GuestOs::Linux),
+    (b"LILO", GuestOs::Linux),
+    (b"BOOTMGR", GuestOs::WindowsNt),
+    (b"NTLDR", GuestOs::WindowsNt),
+    (b"FreeBSD", GuestOs::Bsd),
+    (b"OpenBSD", GuestOs::Bsd),
+    (b"NetBSD", GuestOs::Bsd),
+    (b"WINBOOT SYS", GuestOs::Windows9x),
+    (b"IO      SYS", GuestOs::Dos),
+    (b"IBMBIO  COM", GuestOs::Dos),
+    (b"KERNEL  SYS", GuestOs::Dos),
+];
+
+fn scan_signatures(data: &[u8]) -> Option<GuestOs> {
+    SIGNATURES.iter().find(|(sig, _)| contains(data, sig)).map(|&(_, os)| os)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+// ── File systems ────────────────────────────────────────────────────
+
+/// ext2/3/4 superblock magic, at byte 56 of the superblock (which starts
+/// 1024 bytes into the volume).
+const EXT_MAGIC: u16 = 0xEF53;
+
+/// Recognize the volume starting at `lba` from its first sector `vbr`.
+fn probe_volume(disk: &mut dyn DiskBackend, lba: u64, vbr: &[u8]) -> Option<GuestOs> {
+    if let Some(os) = probe_boot_sector(vbr) {
+        return Some(os);
+    }
+    let mut sb = [0u8; SECTOR];
+    if disk.read((lba + 2) * SECTOR as u64, &mut sb) && le16(&sb, 56) == EXT_MAGIC {
+        return Some(GuestOs::Linux);
+    }
+    None
+}
+
+/// Classify a FAT or NTFS boot sector by its OEM name and file system type.
+fn probe_boot_sector(vbr: &[u8]) -> Option<GuestOs> {
+    // x86 jump over the BPB.
+    if vbr[0] != 0xEB && vbr[0] != 0xE9 {
+        return None;
+    }
+    let oem = &vbr[3..11];
+    if oem == b"NTFS    " {
+        return Some(GuestOs::WindowsNt);
+    }
+    if oem.starts_with(b"MSWIN4") {
+        return Some(GuestOs::Windows9x);
+    }
+    if oem.starts_with(b"FRDOS") || oem.starts_with(b"FreeDOS") {
+        return Some(GuestOs::Dos);
+    }
+    if let Some(os) = scan_signatures(vbr) {
+        return Some(os);
+    }
+    if &vbr[82..87] == b"FAT32" {
+        return Some(GuestOs::Windows9x);
+    }
+    if &vbr[54..59] == b"FAT12" || &vbr[54..59] == b"FAT16" {
+        return Some(if oem.starts_with(b"MSDOS") || oem.starts_with(b"IBM") {
+            GuestOs::Dos
+        } else {
+            GuestOs::Unknown
+        });
+    }
+    None
+}
+
+// ── Partition tables ────────────────────────────────────────────────
+
+struct MbrPartition {
+    kind: u8,
+    active: bool,
+    lba: u64,
+}
+
+fn mbr_partitions(mbr: &[u8]) -> Vec<MbrPartition> {
+    let mut parts: Vec<MbrPartition> = (0..4)
+        .map(|i| &mbr[446 + i * 16..462 + i * 16])
+        .filter(|e| e[4] != 0)
+        .map(|e| MbrPartition { kind: e[4], active: e[0] & 0x80 != 0, lba: le32(e, 8) as u64 })
+        .collect();
+    // Active partition first (stable, so table order otherwise).
+    parts.sort_by_key(|p| !p.active);
+    parts
+}
+
+fn probe_mbr(disk: &mut dyn DiskBackend, parts: &[MbrPartition]) -> Option<GuestOs> {
+    let mut fallback = None;
+    for p in parts {
+        let by_type = match p.kind {
+            0x83 | 0x82 | 0x8E => Some(GuestOs::Linux),
+            0xA5 | 0xA6 | 0xA9 => Some(GuestOs::Bsd),
+            0x0B | 0x0C => Some(GuestOs::Windows9x),
+            0x01 | 0x04 | 0x06 | 0x0E => Some(GuestOs::Dos),
+            _ => None,
+        };
+        let mut vbr = [0u8; SECTOR];
+        let by_volume = if p.lba != 0 && disk.read(p.lba * SECTOR as u64, &mut vbr) {
+            probe_volume(disk, p.lba, &vbr).filter(|&os| os != GuestOs::Unknown)
+        } else {
+            None
+        };
+        // The volume's own boot sector is the stronger hint (type 0x07 is
+        // NTFS, exFAT or HPFS; a FAT partition may hold Windows 9x).
+        match by_volume.or(by_type) {
+            Some(os) if p.active => return Some(os),
+            Some(os) => { fallback.get_or_insert(os); }
+            None => {}
+        }
+    }
+    fallback
+}
+
+// GPT partition type GUIDs, in on-disk (mixed-endian) byte order.
+const GUID_LINUX_FS: [u8; 16] = guid(0x0FC63DAF, 0x8483, 0x4772, [0x8E, 0x79, 0x3D, 0x69, 0xD8, 0x47, 0x7D, 0xE4]);
+const GUID_LINUX_SWAP: [u8; 16] = guid(0x0657FD6D, 0xA4AB, 0x43C4, [0x84, 0xE5, 0x09, 0x33, 0xC8, 0x4B, 0x4F, 0x4F]);
+const GUID_LINUX_LVM: [u8; 16] = guid(0xE6D6D379, 0xF507, 0x44C2, [0xA2, 0x3C, 0x23, 0x8F, 0x2A, 0x3D, 0xF9, 0x28]);
+const GUID_MS_RESERVED: [u8; 16] = guid(0xE3C9E316, 0x0B5C, 0x4DB8, [0x81, 0x7D, 0xF9, 0x2D, 0xF0, 0x02, 0x15, 0xAE]);
+const GUID_MS_BASIC_DATA: [u8; 16] = guid(0xEBD0A0A2, 0xB9E5, 0x4433, [0x87, 0xC0, 0x68, 0xB6, 0xB7, 0x26, 0x99, 0xC7]);
+const GUID_FREEBSD_BOOT: [u8; 16] = guid(0x83BD6B9D, 0x7F41, 0x11DC, [0xBE, 0x0B, 0x00, 0x15, 0x60, 0xB8, 0x4F, 0x0F]);
+const GUID_FREEBSD_UFS: [u8; 16] = guid(0x516E7CB6, 0x6ECF, 0x11D6, [0x8F, 0xF8, 0x00, 0x02, 0x2D, 0x09, 0x71, 0x2B]);
+const GUID_FREEBSD_ZFS: [u8; 16] = guid(0x516E7CBA, 0x6ECF, 0x11D6, [0x8F, 0xF8, 0x00, 0x02, 0x2D, 0x09, 0x71, 0x2B]);
+const GUID_OPENBSD_DATA: [u8; 16] = guid(0x824CC7A0, 0x36A8, 0x11E3, [0x89, 0x0A, 0x95, 0x25, 0x19, 0xAD, 0x3F, 0x61]);
+const GUID_NETBSD_FFS: [u8; 16] = guid(0x49F48D5A, 0xB10E, 0x11DC, [0xB9, 0x9B, 0x00, 0x19, 0xD1, 0x87, 0x96, 0x48]);
+
+/// Encode a GUID as stored on disk: the first three fields little-endian.
+const fn guid(a: u32, b: u16, c: u16, d: [u8; 8]) -> [u8; 16] {
+    let a = a.to_le_bytes();
+    let b = b.to_le_bytes();
+    let c = c.to_le_bytes();
+    [a[0], a[1], a[2], a[3], b[0], b[1], c[0], c[1], d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7]]
+}
+
+fn probe_gpt(disk: &mut dyn DiskBackend) -> Option<GuestOs> {
+    let mut hdr = [0u8; SECTOR];
+    if !disk.read(SECTOR as u64, &mut hdr) || &hdr[..8] != b"EFI PART" {
+        return None;
+    }
+    let entries_lba = le64(&hdr, 72);
+    let count = (le32(&hdr, 80) as usize).min(MAX_GPT_ENTRIES);
+    let entry_size = le32(&hdr, 84) as usize;
+    if entry_size < 128 || entry_size > SECTOR || count == 0 {
+        return None;
+    }
+    let mut table = vec![0u8; (count * entry_size).div_ceil(SECTOR) * SECTOR];
+    if !disk.read(entries_lba * SECTOR as u64, &mut table) {
+        return None;
+    }
+
+    let mut fallback = None;
+    for e in table.chunks_exact(entry_size).take(count) {
+        let kind: [u8; 16] = e[..16].try_into().unwrap_or([0; 16]);
+        if kind == [0; 16] {
+            continue;
+        }
+        let os = match kind {
+            GUID_LINUX_FS | GUID_LINUX_SWAP | GUID_LINUX_LVM => Some(GuestOs::Linux),
+            GUID_MS_RESERVED => Some(GuestOs::WindowsNt),
+            GUID_FREEBSD_BOOT | GUID_FREEBSD_UFS | GUID_FREEBSD_ZFS
+            | GUID_OPENBSD_DATA | GUID_NETBSD_FFS => Some(GuestOs::Bsd),
+            // Basic data is shared by Windows and other systems: look at
+            // the volume itself.
+            GUID_MS_BASIC_DATA => {
+                let lba = le64(e, 32);
+                let mut vbr = [0u8; SECTOR];
+                if disk.read(lba * SECTOR as u64, &mut vbr) {
+                    probe_volume(disk, lba, &vbr).filter(|&os| os != GuestOs::Unknown)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+        match os {
+            // A Linux root outweighs the FAT of an EFI system partition.
+            Some(GuestOs::Linux) | Some(GuestOs::Bsd) | Some(GuestOs::WindowsNt) => return os,
+            Some(os) => { fallback.get_or_insert(os); }
+            None => {}
+        }
+    }
+    fallback.or(Some(GuestOs::Unknown))
+}
+
+fn le16(b: &[u8], off: usize) -> u16 {
+    u16::from_le_bytes([b[off], b[off + 1]])
+}
+
+fn le32(b: &[u8], off: usize) -> u32 {
+    u32::from_le_bytes([b[off], b[off + 1], b[off + 2], b[off + 3]])
+}
+
+fn le64(b: &[u8], off: usize) -> u64 {
+    le32(b, off) as u64 | (le32(b, off + 4) as u64) << 32
+}
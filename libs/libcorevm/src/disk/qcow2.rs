@@ -0,0 +1,568 @@
+//! QEMU copy-on-write (qcow2) disk images, versions 2 and 3.
+//!
+//! The virtual disk is split into clusters (512 B - 2 MiB, usually 64 KiB).
+//! A two-level table maps a virtual cluster to the host cluster holding its
+//! data:
+//!
+//! | Structure | Content |
+//! |-----------|---------|
+//! | Header | Virtual size, cluster size, table offsets, backing file name |
+//! | L1 table | One entry per L2 table (contiguous, fixed size) |
+//! | L2 table | One cluster of entries, one per data cluster |
+//! | Refcount table | Offsets of refcount blocks |
+//! | Refcount block | One cluster of 16-bit use counts, one per host cluster |
+//!
+//! All fields are big-endian. A cluster with no L2 entry reads from the
+//! backing image (opened read-only), or as zeros without one. The guest's
+//! first write to such a cluster appends a new host cluster to the file,
+//! filled with the old contents, and records it in its L2 table and
+//! refcount block; missing L2 tables and refcount blocks are appended the
+//! same way. Clusters are never freed.
+//!
+//! [`create`] writes a new, empty image; with a backing file it is an
+//! overlay (see [`super::create_overlay`]). Committing copies the image's
+//! clusters into the backing image; discarding re-creates the image empty.
+//!
+//! Not supported: compressed clusters (reads and writes of them fail),
+//! encryption, external data files and extended L2 entries (the image is
+//! refused). Images with internal snapshots, refcounts other than 16 bits,
+//! or a dirty/corrupt flag can only be opened read-only.
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::cache::BlockCache;
+use super::file::HostFile;
+use super::{DiskBackend, DiskError};
+
+/// "QFI\xfb".
+const MAGIC: u32 = 0x5146_49FB;
+/// Bytes of the version 3 header read at open (the fields used here).
+const HEADER_LEN: usize = 104;
+const MIN_CLUSTER_BITS: u32 = 9;
+const MAX_CLUSTER_BITS: u32 = 21;
+/// Longest backing file name accepted.
+const MAX_BACKING_NAME: usize = 1023;
+/// Backing chains deeper than this are refused (they are most likely loops).
+const MAX_BACKING_DEPTH: u32 = 8;
+/// Largest L1 table accepted, in entries (32 MiB).
+const MAX_L1_ENTRIES: u64 = 4 * 1024 * 1024;
+
+/// Host offset bits of an L1/L2 entry.
+const OFFSET_MASK: u64 = 0x00FF_FFFF_FFFF_FE00;
+/// Refcount is exactly 1: the cluster may be written in place.
+const FLAG_COPIED: u64 = 1 << 63;
+const FLAG_COMPRESSED: u64 = 1 << 62;
+/// Version 3: the cluster reads as zeros.
+const FLAG_ZERO: u64 = 1;
+
+/// Incompatible feature bits this implementation understands.
+const INCOMPAT_DIRTY: u64 = 1 << 0;
+const INCOMPAT_CORRUPT: u64 = 1 << 1;
+
+/// Refcount width this implementation can update (2^4 = 16 bits).
+const REFCOUNT_ORDER: u32 = 4;
+/// Cluster size of created images (64 KiB, as qemu-img).
+const CREATE_CLUSTER_BITS: u32 = 16;
+/// Where a created image stores its backing file name: after the header
+/// and the end-of-extensions marker.
+const CREATE_BACKING_OFFSET: usize = HEADER_LEN + 8;
+
+/// True if `file` starts with the qcow2 magic.
+pub fn probe(file: &mut HostFile) -> bool {
+    let mut magic = [0u8; 4];
+    file.size() >= HEADER_LEN as u64 && file.read_at(0, &mut magic) && u32::from_be_bytes(magic) == MAGIC
+}
+
+fn be32(b: &[u8], off: usize) -> u32 {
+    u32::from_be_bytes([b[off], b[off + 1], b[off + 2], b[off + 3]])
+}
+
+fn be64(b: &[u8], off: usize) -> u64 {
+    (be32(b, off) as u64) << 32 | be32(b, off + 4) as u64
+}
+
+/// Where the data of a virtual cluster is.
+enum Cluster {
+    /// At this host offset.
+    Data(u64),
+    /// Reads as zeros.
+    Zero,
+    /// Not in this image: read from the backing image.
+    Unallocated,
+    Compressed,
+}
+
+pub struct Qcow2Disk {
+    file: HostFile,
+    /// Cluster-sized cache of data clusters and metadata tables.
+    cache: BlockCache,
+    cluster_bits: u32,
+    /// Virtual disk size in bytes.
+    size: u64,
+    l1: Vec<u64>,
+    l1_offset: u64,
+    /// Empty when the image is read-only.
+    refcount_table: Vec<u64>,
+    refcount_table_offset: u64,
+    backing: Option<Box<dyn DiskBackend>>,
+    /// Backing file name as stored in the header.
+    backing_name: Option<String>,
+    /// Path the image was opened from, and its depth in a backing chain.
+    path: String,
+    depth: u32,
+    /// Next cluster to allocate: the cluster-aligned end of the file.
+    end: u64,
+    writable: bool,
+}
+
+impl Qcow2Disk {
+    /// Parse the image in `file` (opened from `path`) and open its backing
+    /// image. `depth` is the position of this image in a backing chain.
+    pub fn open(mut file: HostFile, path: &str, depth: u32) -> Result<Self, DiskError> {
+        let mut h = [0u8; HEADER_LEN];
+        if !file.read_at(0, &mut h) {
+            return Err(DiskError::Io);
+        }
+        let version = be32(&h, 4);
+        let cluster_bits = be32(&h, 20);
+        if version != 2 && version != 3 {
+            return Err(DiskError::Unsupported);
+        }
+        if !(MIN_CLUSTER_BITS..=MAX_CLUSTER_BITS).contains(&cluster_bits) {
+            return Err(DiskError::Corrupt);
+        }
+        if be32(&h, 32) != 0 {
+            // Encrypted.
+            return Err(DiskError::Unsupported);
+        }
+        let (incompat, refcount_order) = if version == 3 { (be64(&h, 72), be32(&h, 96)) } else { (0, REFCOUNT_ORDER) };
+        if incompat & !(INCOMPAT_DIRTY | INCOMPAT_CORRUPT) != 0 {
+            return Err(DiskError::Unsupported);
+        }
+        let snapshots = be32(&h, 60);
+        let writable = file.writable();
+        if writable && (incompat != 0 || snapshots != 0 || refcount_order != REFCOUNT_ORDER) {
+            return Err(DiskError::Unsupported);
+        }
+
+        let cluster_size = 1u64 << cluster_bits;
+        let size = be64(&h, 24);
+        let l1_size = be32(&h, 36) as u64;
+        let l1_offset = be64(&h, 40);
+        let l1_span = cluster_size * (cluster_size / 8);
+        if l1_size > MAX_L1_ENTRIES || l1_size < size.div_ceil(l1_span) {
+            return Err(DiskError::Corrupt);
+        }
+        let l1 = read_table(&mut file, l1_offset, l1_size as usize)?;
+
+        let refcount_table_offset = be64(&h, 48);
+        let refcount_table = if writable {
+            let entries = be32(&h, 56) as u64 * cluster_size / 8;
+            if entries > MAX_L1_ENTRIES {
+                return Err(DiskError::Corrupt);
+            }
+            read_table(&mut file, refcount_table_offset, entries as usize)?
+        } else {
+            Vec::new()
+        };
+
+        let backing_offset = be64(&h, 8);
+        let backing_len = be32(&h, 16) as usize;
+        let mut backing_name = None;
+        let backing = if backing_offset != 0 && backing_len != 0 {
+            if backing_len > MAX_BACKING_NAME {
+                return Err(DiskError::Corrupt);
+            }
+            let mut name = vec![0u8; backing_len];
+            if !file.read_at(backing_offset, &mut name) {
+                return Err(DiskError::Io);
+            }
+            let name = core::str::from_utf8(&name).map_err(|_| DiskError::Corrupt)?;
+            if depth + 1 >= MAX_BACKING_DEPTH {
+                return Err(DiskError::Backing);
+            }
+            let backing_path = resolve_backing(path, name);
+            backing_name = Some(String::from(name));
+            Some(super::open_nested(&backing_path, false, depth + 1).map_err(|_| DiskError::Backing)?)
+        } else {
+            None
+        };
+
+        let end = file.size().next_multiple_of(cluster_size);
+        Ok(Qcow2Disk {
+            file,
+            cache: BlockCache::new(cluster_size as usize),
+            cluster_bits,
+            size,
+            l1,
+            l1_offset,
+            refcount_table,
+            refcount_table_offset,
+            backing,
+            backing_name,
+            path: String::from(path),
+            depth,
+            end,
+            writable,
+        })
+    }
+
+    fn cluster_size(&self) -> u64 {
+        1 << self.cluster_bits
+    }
+
+    /// L1 index and byte offset of the L2 entry (within its table) for
+    /// virtual offset `vaddr`.
+    fn indices(&self, vaddr: u64) -> (usize, u64) {
+        let l2_bits = self.cluster_bits - 3;
+        let l1_idx = (vaddr >> (self.cluster_bits + l2_bits)) as usize;
+        let l2_idx = (vaddr >> self.cluster_bits) & ((1 << l2_bits) - 1);
+        (l1_idx, l2_idx * 8)
+    }
+
+    fn read_u64(&mut self, offset: u64) -> Option<u64> {
+        let mut b = [0u8; 8];
+        self.cache.read(&mut self.file, offset, &mut b).then(|| u64::from_be_bytes(b))
+    }
+
+    fn write_u64(&mut self, offset: u64, value: u64) -> bool {
+        self.cache.write(&mut self.file, offset, &value.to_be_bytes())
+    }
+
+    /// Find the data of the virtual cluster containing `vaddr`. `None` on a
+    /// host read error.
+    fn lookup(&mut self, vaddr: u64) -> Option<Cluster> {
+        let (l1_idx, l2_entry) = self.indices(vaddr);
+        let l2 = self.l1.get(l1_idx).copied().unwrap_or(0) & OFFSET_MASK;
+        if l2 == 0 {
+            return Some(Cluster::Unallocated);
+        }
+        let entry = self.read_u64(l2 + l2_entry)?;
+        let host = entry & OFFSET_MASK;
+        Some(if entry & FLAG_COMPRESSED != 0 {
+            Cluster::Compressed
+        } else if entry & FLAG_ZERO != 0 {
+            Cluster::Zero
+        } else if host == 0 {
+            Cluster::Unallocated
+        } else {
+            Cluster::Data(host)
+        })
+    }
+
+    /// Read `buf` at virtual offset `vaddr` from the backing image; the
+    /// part past its end (or everything, without one) reads as zeros.
+    fn read_backing(&mut self, vaddr: u64, buf: &mut [u8]) -> bool {
+        let n = match self.backing.as_mut() {
+            Some(b) => {
+                let n = b.size().saturating_sub(vaddr).min(buf.len() as u64) as usize;
+                if n > 0 && !b.read(vaddr, &mut buf[..n]) {
+                    return false;
+                }
+                n
+            }
+            None => 0,
+        };
+        buf[n..].fill(0);
+        true
+    }
+
+    /// Append a cluster to the file and give it refcount 1. Returns its
+    /// host offset. The cluster's contents are not initialized.
+    fn alloc_cluster(&mut self) -> Option<u64> {
+        let offset = self.end;
+        self.end += self.cluster_size();
+        self.set_refcount(offset, 1).then_some(offset)
+    }
+
+    /// Set the refcount of the host cluster at `offset`, appending a
+    /// refcount block if it has none. Fails if the refcount table is full.
+    fn set_refcount(&mut self, offset: u64, value: u16) -> bool {
+        let entries_per_block = self.cluster_size() / 2;
+        let cluster = offset >> self.cluster_bits;
+        let table_idx = (cluster / entries_per_block) as usize;
+        let mut block = match self.refcount_table.get(table_idx) {
+            Some(&e) => e & !0x1FF,
+            None => return false,
+        };
+        if block == 0 {
+            block = self.end;
+            self.end += self.cluster_size();
+            let zeros = vec![0u8; self.cluster_size() as usize];
+            if !self.cache.write(&mut self.file, block, &zeros)
+                || !self.write_u64(self.refcount_table_offset + table_idx as u64 * 8, block)
+            {
+                return false;
+            }
+            self.refcount_table[table_idx] = block;
+            // The new block is a cluster too; it usually counts itself.
+            let own = block >> self.cluster_bits;
+            let counted = if own / entries_per_block == table_idx as u64 {
+                self.cache.write(&mut self.file, block + (own % entries_per_block) * 2, &1u16.to_be_bytes())
+            } else {
+                self.set_refcount(block, 1)
+            };
+            if !counted {
+                return false;
+            }
+        }
+        let entry = block + (cluster % entries_per_block) * 2;
+        self.cache.write(&mut self.file, entry, &value.to_be_bytes())
+    }
+
+    /// Host offset of the virtual cluster containing `vaddr`, ready to be
+    /// written in place. A cluster not yet in the image is allocated and
+    /// filled with its current contents, unless `overwrite` says the caller
+    /// replaces all of it.
+    fn writable_cluster(&mut self, vaddr: u64, overwrite: bool) -> Option<u64> {
+        let cs = self.cluster_size();
+        let (l1_idx, l2_entry) = self.indices(vaddr);
+        let mut l2 = self.l1.get(l1_idx).copied()? & OFFSET_MASK;
+        if l2 == 0 {
+            l2 = self.alloc_cluster()?;
+            let zeros = vec![0u8; cs as usize];
+            if !self.cache.write(&mut self.file, l2, &zeros)
+                || !self.write_u64(self.l1_offset + l1_idx as u64 * 8, l2 | FLAG_COPIED)
+            {
+                return None;
+            }
+            self.l1[l1_idx] = l2 | FLAG_COPIED;
+        }
+
+        let entry = self.read_u64(l2 + l2_entry)?;
+        if entry & FLAG_COMPRESSED != 0 {
+            return None;
+        }
+        let host = entry & OFFSET_MASK;
+        if host != 0 && entry & FLAG_ZERO == 0 {
+            return Some(host);
+        }
+
+        // First write: a zero cluster keeps its preallocated host cluster.
+        let zero = entry & FLAG_ZERO != 0;
+        let host = if host != 0 { host } else { self.alloc_cluster()? };
+        if !overwrite {
+            let base = vaddr & !(cs - 1);
+            let mut data = vec![0u8; cs as usize];
+            if !zero && !self.read_backing(base, &mut data) {
+                return None;
+            }
+            if !self.cache.write(&mut self.file, host, &data) {
+                return None;
+            }
+        }
+        self.write_u64(l2 + l2_entry, host | FLAG_COPIED).then_some(host)
+    }
+
+    /// Write every cluster this image holds (data or zero) to `base` at
+    /// the same virtual offset. Returns the number of clusters written.
+    fn copy_clusters(&mut self, base: &mut dyn DiskBackend) -> Result<u64, DiskError> {
+        let cs = self.cluster_size();
+        let l2_bits = self.cluster_bits - 3;
+        let limit = self.size.min(base.size());
+        let mut data = vec![0u8; cs as usize];
+        let mut copied = 0;
+        for l1_idx in 0..self.l1.len() {
+            if self.l1[l1_idx] & OFFSET_MASK == 0 {
+                continue;
+            }
+            for l2_idx in 0..1u64 << l2_bits {
+                let vaddr = ((l1_idx as u64) << l2_bits | l2_idx) << self.cluster_bits;
+                if vaddr >= limit {
+                    break;
+                }
+                let chunk = &mut data[..(limit - vaddr).min(cs) as usize];
+                match self.lookup(vaddr).ok_or(DiskError::Io)? {
+                    Cluster::Data(host) => {
+                        if !self.cache.read(&mut self.file, host, chunk) {
+                            return Err(DiskError::Io);
+                        }
+                    }
+                    Cluster::Zero => chunk.fill(0),
+                    Cluster::Unallocated => continue,
+                    Cluster::Compressed => return Err(DiskError::Unsupported),
+                }
+                if !base.write(vaddr, chunk) {
+                    return Err(DiskError::Io);
+                }
+                copied += 1;
+            }
+        }
+        Ok(copied)
+    }
+}
+
+impl DiskBackend for Qcow2Disk {
+    fn size(&self) -> u64 {
+        self.size
+    }
+
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> bool {
+        if offset + buf.len() as u64 > self.size {
+            return false;
+        }
+        let cs = self.cluster_size();
+        let mut done = 0;
+        while done < buf.len() {
+            let pos = offset + done as u64;
+            let within = pos & (cs - 1);
+            let n = ((cs - within) as usize).min(buf.len() - done);
+            let chunk = &mut buf[done..done + n];
+            let ok = match self.lookup(pos) {
+                Some(Cluster::Data(host)) => self.cache.read(&mut self.file, host + within, chunk),
+                Some(Cluster::Zero) => {
+                    chunk.fill(0);
+                    true
+                }
+                Some(Cluster::Unallocated) => self.read_backing(pos, chunk),
+                Some(Cluster::Compressed) | None => false,
+            };
+            if !ok {
+                return false;
+            }
+            done += n;
+        }
+        true
+    }
+
+    fn write(&mut self, offset: u64, data: &[u8]) -> bool {
+        if !self.writable || offset + data.len() as u64 > self.size {
+            return false;
+        }
+        let cs = self.cluster_size();
+        let mut done = 0;
+        while done < data.len() {
+            let pos = offset + done as u64;
+            let within = pos & (cs - 1);
+            let n = ((cs - within) as usize).min(data.len() - done);
+            let host = match self.writable_cluster(pos, n as u64 == cs) {
+                Some(h) => h,
+                None => return false,
+            };
+            if !self.cache.write(&mut self.file, host + within, &data[done..done + n]) {
+                return false;
+            }
+            done += n;
+        }
+        true
+    }
+
+    fn flush(&mut self) -> bool {
+        self.cache.flush(&mut self.file)
+    }
+
+    /// Reopens the backing image writable for the copy, then read-only
+    /// again (the read-only handle would keep stale cached sectors). Other
+    /// VMs must not be using the backing image meanwhile.
+    fn commit(&mut self) -> Result<u64, DiskError> {
+        let name = self.backing_name.clone().ok_or(DiskError::Unsupported)?;
+        if !self.flush() {
+            return Err(DiskError::Io);
+        }
+        let backing_path = resolve_backing(&self.path, &name);
+        let mut base = super::open_nested(&backing_path, true, self.depth + 1).map_err(|_| DiskError::Backing)?;
+        self.backing = None;
+        let copied = self.copy_clusters(&mut *base);
+        let flushed = base.flush();
+        drop(base);
+        self.backing = Some(super::open_nested(&backing_path, false, self.depth + 1).map_err(|_| DiskError::Backing)?);
+        let copied = copied?;
+        if !flushed {
+            return Err(DiskError::Io);
+        }
+        Ok(copied)
+    }
+
+    /// Re-creates the file as an empty image with the same size and
+    /// backing file; cached changes are dropped unwritten.
+    fn discard(&mut self) -> Result<(), DiskError> {
+        if !self.writable {
+            return Err(DiskError::Unsupported);
+        }
+        let path = self.path.clone();
+        create(&path, self.size, self.backing_name.as_deref())?;
+        *self = Qcow2Disk::open(HostFile::open(&path, true)?, &path, self.depth)?;
+        Ok(())
+    }
+}
+
+/// Write a new, empty version 3 image of `size` bytes to `path`,
+/// replacing any existing file, optionally on top of `backing` (stored
+/// as given).
+///
+/// Layout: header and backing name in cluster 0, refcount table in
+/// cluster 1, the first refcount block in cluster 2, then the L1 table.
+pub fn create(path: &str, size: u64, backing: Option<&str>) -> Result<(), DiskError> {
+    let cs = 1u64 << CREATE_CLUSTER_BITS;
+    let name = backing.unwrap_or("").as_bytes();
+    if name.len() > MAX_BACKING_NAME {
+        return Err(DiskError::Unsupported);
+    }
+    let l1_span = cs * (cs / 8);
+    let l1_size = size.div_ceil(l1_span).max(1);
+    if l1_size > MAX_L1_ENTRIES {
+        return Err(DiskError::Unsupported);
+    }
+    let l1_clusters = (l1_size * 8).div_ceil(cs);
+    let clusters = 3 + l1_clusters;
+    let mut image = vec![0u8; (clusters * cs) as usize];
+
+    let mut put32 = |off: usize, v: u32| image[off..off + 4].copy_from_slice(&v.to_be_bytes());
+    put32(0, MAGIC);
+    put32(4, 3);
+    put32(16, name.len() as u32);
+    put32(20, CREATE_CLUSTER_BITS);
+    put32(36, l1_size as u32);
+    put32(56, 1); // refcount table clusters
+    put32(96, REFCOUNT_ORDER);
+    put32(100, HEADER_LEN as u32);
+    let mut put64 = |off: usize, v: u64| image[off..off + 8].copy_from_slice(&v.to_be_bytes());
+    if !name.is_empty() {
+        put64(8, CREATE_BACKING_OFFSET as u64);
+    }
+    put64(24, size);
+    put64(40, 3 * cs);
+    put64(48, cs);
+    // Refcount table entry 0 → the block in cluster 2.
+    put64(cs as usize, 2 * cs);
+    image[CREATE_BACKING_OFFSET..CREATE_BACKING_OFFSET + name.len()].copy_from_slice(name);
+    // Every cluster written here is in use.
+    for c in 0..clusters as usize {
+        let off = 2 * cs as usize + c * 2;
+        image[off..off + 2].copy_from_slice(&1u16.to_be_bytes());
+    }
+
+    let mut file = HostFile::create(path)?;
+    if !file.write_at(0, &image) {
+        return Err(DiskError::Io);
+    }
+    Ok(())
+}
+
+/// Read a table of `entries` big-endian u64s at `offset`.
+fn read_table(file: &mut HostFile, offset: u64, entries: usize) -> Result<Vec<u64>, DiskError> {
+    let mut raw = vec![0u8; entries * 8];
+    if !file.read_at(offset, &mut raw) {
+        return Err(DiskError::Io);
+    }
+    Ok(raw.chunks_exact(8).map(|e| be64(e, 0)).collect())
+}
+
+/// Path of a backing file named `name` in the header of the image at
+/// `image`: relative names are relative to the image's directory.
+pub(super) fn resolve_backing(image: &str, name: &str) -> String {
+    if name.starts_with('/') {
+        return String::from(name);
+    }
+    match image.rfind('/') {
+        Some(i) => {
+            let mut p = String::from(&image[..=i]);
+            p.push_str(name);
+            p
+        }
+        None => String::from(name),
+    }
+}
@@ -0,0 +1,51 @@
+//! Flat (raw) disk image file, accessed on demand.
+//!
+//! Sector `n` of the disk is at byte `n * 512` of the file. Nothing is read
+//! until the guest asks for it, so the image can be far larger than the
+//! memory of the VM process, and a sparse host file stays sparse outside
+//! the cache blocks the guest writes to.
+
+use super::cache::BlockCache;
+use super::file::HostFile;
+use super::DiskBackend;
+
+/// Cache block size: large enough for sequential boot-time reads.
+const BLOCK_SIZE: usize = 64 * 1024;
+
+pub struct RawDisk {
+    file: HostFile,
+    cache: BlockCache,
+    /// Disk size: the file length when it was opened.
+    size: u64,
+}
+
+impl RawDisk {
+    pub fn new(file: HostFile) -> Self {
+        let size = file.size();
+        RawDisk { file, cache: BlockCache::new(BLOCK_SIZE), size }
+    }
+}
+
+impl DiskBackend for RawDisk {
+    fn size(&self) -> u64 {
+        self.size
+    }
+
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> bool {
+        if offset + buf.len() as u64 > self.size {
+            return false;
+        }
+        self.cache.read(&mut self.file, offset, buf)
+    }
+
+    fn write(&mut self, offset: u64, data: &[u8]) -> bool {
+        if offset + data.len() as u64 > self.size {
+            return false;
+        }
+        self.cache.write(&mut self.file, offset, data)
+    }
+
+    fn flush(&mut self) -> bool {
+        self.cache.flush(&mut self.file)
+    }
+}
@@ -44,6 +44,16 @@ pub enum VmError {
     UnhandledIo { port: u16, is_write: bool },
     /// Guest executed HLT — normal exit condition.
     Halted,
+    /// Guest requested power-off (ACPI S5 via PM1a_CNT).
+    ShutdownRequested,
+    /// Guest requested a system reset (keyboard controller or 0xCF9).
+    ResetRequested,
+    /// Guest reported a test result through the test-exit port.
+    GuestExit(u16),
+    /// Built-in BIOS stub requested a host service (see [`crate::bios`]).
+    BiosTrap,
+    /// An unhandled device access was handed to the host (see [`crate::ioexit`]).
+    IoExit,
     /// Instruction fetch crossed into unmapped memory.
     FetchFault(u64),
     /// Maximum instruction count exceeded (infinite loop protection).
@@ -114,6 +124,11 @@ impl fmt::Display for VmError {
                 write!(f, "unhandled I/O {} port 0x{:04X}", if *is_write { "write" } else { "read" }, port)
             }
             VmError::Halted => write!(f, "CPU halted"),
+            VmError::ShutdownRequested => write!(f, "guest shutdown requested"),
+            VmError::ResetRequested => write!(f, "guest reset requested"),
+            VmError::GuestExit(status) => write!(f, "guest test exit (status {})", status),
+            VmError::BiosTrap => write!(f, "BIOS service trap"),
+            VmError::IoExit => write!(f, "I/O exit to host"),
             VmError::FetchFault(addr) => write!(f, "fetch fault at 0x{:016X}", addr),
             VmError::InstructionLimitExceeded => write!(f, "instruction limit exceeded"),
             VmError::OutOfMemory => write!(f, "out of guest memory"),
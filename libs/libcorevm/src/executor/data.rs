@@ -10,7 +10,7 @@ use crate::instruction::{DecodedInst, Operand, RegOperand};
 use crate::memory::{GuestMemory, Mmu};
 use crate::registers::{GprIndex, SegReg};
 
-use super::{compute_effective_address, read_operand, write_operand};
+use super::{effective_offset, read_operand, write_operand};
 
 /// MOV: simple data transfer with no flags modification.
 pub fn exec_mov(
@@ -90,22 +90,14 @@ pub fn exec_movsxd(
 
 /// LEA: compute effective address and store in register (no memory access).
 pub fn exec_lea(cpu: &mut Cpu, inst: &DecodedInst) -> Result<()> {
-    let addr = match &inst.operands[1] {
-        Operand::Memory(mem_op) => compute_effective_address(cpu, mem_op, inst)?,
+    // LEA uses neither the segment base nor the segment limit.
+    let offset = match &inst.operands[1] {
+        Operand::Memory(mem_op) => effective_offset(cpu, mem_op, inst),
         _ => return Err(VmError::UndefinedOpcode(inst.opcode as u8)),
     };
 
-    // LEA does not use segment base; subtract it back out
-    let linear = match &inst.operands[1] {
-        Operand::Memory(mem_op) => {
-            let seg_base = cpu.regs.segment(mem_op.segment).base;
-            addr.wrapping_sub(seg_base)
-        }
-        _ => addr,
-    };
-
     // Mask to operand size
-    let result = linear & inst.operand_size.mask();
+    let result = offset & inst.operand_size.mask();
 
     match &inst.operands[0] {
         Operand::Register(RegOperand::Gpr(idx)) => {
@@ -17,6 +17,7 @@ use crate::instruction::DecodedInst;
 use crate::memory::{GuestMemory, Mmu};
 use crate::registers::GprIndex;
 
+use super::fpu_trig;
 use super::{translate_and_read, translate_and_write};
 
 /// Dispatch an x87 FPU instruction based on the escape byte (D8-DF) and ModR/M.
@@ -206,6 +207,38 @@ pub fn exec_fpu(
                     0xEE => {
                         cpu.fpu.push(0.0);
                     }
+                    // D9 F0: F2XM1 — ST(0) = 2^ST(0) - 1
+                    0xF0 => {
+                        let val = cpu.fpu.st(0);
+                        cpu.fpu.set_st(0, fpu_trig::f2xm1(val, cpu.fpu_accuracy));
+                    }
+                    // D9 F2: FPTAN — ST(0) = tan(ST(0)), then push 1.0
+                    0xF2 => {
+                        if let Some(val) = trig_operand(cpu) {
+                            cpu.fpu.set_st(0, fpu_trig::tan(val, cpu.fpu_accuracy));
+                            cpu.fpu.push(1.0);
+                        }
+                    }
+                    // D9 FB: FSINCOS — ST(0) = sin(ST(0)), then push cos
+                    0xFB => {
+                        if let Some(val) = trig_operand(cpu) {
+                            let (s, c) = fpu_trig::sin_cos(val, cpu.fpu_accuracy);
+                            cpu.fpu.set_st(0, s);
+                            cpu.fpu.push(c);
+                        }
+                    }
+                    // D9 FE: FSIN
+                    0xFE => {
+                        if let Some(val) = trig_operand(cpu) {
+                            cpu.fpu.set_st(0, fpu_trig::sin(val, cpu.fpu_accuracy));
+                        }
+                    }
+                    // D9 FF: FCOS
+                    0xFF => {
+                        if let Some(val) = trig_operand(cpu) {
+                            cpu.fpu.set_st(0, fpu_trig::cos(val, cpu.fpu_accuracy));
+                        }
+                    }
                     _ => {} // Skip unrecognized
                 }
             }
@@ -506,6 +539,31 @@ pub fn exec_fpu(
 
 // ── FPU helpers ──
 
+/// Fetch ST(0) as the operand of FSIN/FCOS/FSINCOS/FPTAN.
+///
+/// Returns `None` when the instruction must leave the stack untouched:
+/// for `|ST(0)| >= 2^63` C2 is set (the guest is expected to reduce the
+/// argument itself and retry). NaN and infinity produce the default NaN
+/// with the invalid-operation flag raised for infinity. C2 is cleared
+/// otherwise.
+fn trig_operand(cpu: &mut Cpu) -> Option<f64> {
+    let val = cpu.fpu.st(0);
+    cpu.fpu.fsw &= !0x0400;
+    if val.is_nan() {
+        return None;
+    }
+    if val.is_infinite() {
+        cpu.fpu.fsw |= 0x0001; // IE
+        cpu.fpu.set_st(0, f64::NAN);
+        return None;
+    }
+    if f64::from_bits(val.to_bits() & !(1u64 << 63)) >= fpu_trig::TRIG_LIMIT {
+        cpu.fpu.fsw |= 0x0400; // C2: operand out of range
+        return None;
+    }
+    Some(val)
+}
+
 /// Set the FPU condition codes (C0, C2, C3) in FSW based on a comparison.
 ///
 /// C3:C2:C0 encoding:
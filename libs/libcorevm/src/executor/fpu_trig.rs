@@ -0,0 +1,379 @@
+//! x87 transcendental functions (FSIN, FCOS, FSINCOS, FPTAN, F2XM1).
+//!
+//! `libcorevm` is `no_std` and cannot call into a host math library, so the
+//! transcendentals are implemented here on top of plain `f64` arithmetic.
+//!
+//! The hard part of sine and cosine is range reduction: finding `r` and the
+//! quadrant `n` such that `x = n * π/2 + r` with `|r| <= π/4`. Subtracting a
+//! 53-bit approximation of π/2 loses every correct bit once `x` grows past a
+//! few thousand, which is what guests used to observe. Two strategies are
+//! provided, selected by [`FpuAccuracy`]:
+//!
+//! - **Fast** — single-step Cody–Waite reduction with an ~85-bit π/2. Good
+//!   to full precision for moderate arguments and degrades gracefully for
+//!   huge ones, roughly like the 66-bit π of real x87 hardware.
+//! - **Precise** — three-step Cody–Waite for `|x| < 2^20 · π/2`, and
+//!   Payne–Hanek reduction against a 384-bit table of `2/π` beyond that,
+//!   giving results within a couple of ulps over the whole x87 operand
+//!   range (`|x| < 2^63`).
+//!
+//! The polynomial kernels are the fdlibm `__kernel_sin` / `__kernel_cos`
+//! minimax approximations, which take the reduced argument as a `hi + lo`
+//! pair so the extra precision from reduction is not thrown away.
+
+use crate::fpu_state::FpuAccuracy;
+
+/// Largest magnitude accepted by FSIN/FCOS/FSINCOS/FPTAN (2^63). Larger
+/// operands leave ST(0) unchanged and set C2.
+pub const TRIG_LIMIT: f64 = 9223372036854775808.0;
+
+// ── Constants ──
+
+/// 2/π rounded to double.
+const INV_PIO2: f64 = 6.36619772367581382433e-01;
+/// First 33 bits of π/2.
+const PIO2_1: f64 = 1.57079632673412561417e+00;
+/// π/2 - PIO2_1.
+const PIO2_1T: f64 = 6.07710050650619224932e-11;
+/// Second 33 bits of π/2.
+const PIO2_2: f64 = 6.07710050630396597660e-11;
+/// π/2 - (PIO2_1 + PIO2_2).
+const PIO2_2T: f64 = 2.02226624879595063154e-21;
+/// Third 33 bits of π/2.
+const PIO2_3: f64 = 2.02226624871116645580e-21;
+/// π/2 - (PIO2_1 + PIO2_2 + PIO2_3).
+const PIO2_3T: f64 = 8.47842766036889956997e-32;
+/// π/2 as a double-double (hi + lo).
+const PIO2_HI: f64 = 1.57079632679489655800e+00;
+const PIO2_LO: f64 = 6.12323399573676603587e-17;
+/// Upper bound for the Cody–Waite path in precise mode (2^20 · π/2).
+const MEDIUM_LIMIT: f64 = 1647099.3291652855;
+
+/// ln(2) as a double-double (hi + lo).
+const LN2_HI: f64 = 6.93147180559945286227e-01;
+const LN2_LO: f64 = 2.31904681384629955842e-17;
+
+/// Fractional bits of 2/π, most significant first (bit 1 of word 0 has
+/// weight 2^-1). 384 bits cover every exponent below 2^63 with a 192-bit
+/// window to spare.
+const TWO_OVER_PI: [u64; 6] = [
+    0xA2F9836E4E441529,
+    0xFC2757D1F534DDC0,
+    0xDB6295993C439041,
+    0xFE5163ABDEBBC561,
+    0xB7246E3A424DD2E0,
+    0x06492EEA09D1921C,
+];
+
+// fdlibm __kernel_sin coefficients.
+const S1: f64 = -1.66666666666666324348e-01;
+const S2: f64 = 8.33333333332248946124e-03;
+const S3: f64 = -1.98412698298579493134e-04;
+const S4: f64 = 2.75573137070700676789e-06;
+const S5: f64 = -2.50507602534068634195e-08;
+const S6: f64 = 1.58969099521155010221e-10;
+
+// fdlibm __kernel_cos coefficients.
+const C1: f64 = 4.16666666666666019037e-02;
+const C2: f64 = -1.38888888888741095749e-03;
+const C3: f64 = 2.48015872894767294178e-05;
+const C4: f64 = -2.75573143513906633035e-07;
+const C5: f64 = 2.08757232129817482790e-09;
+const C6: f64 = -1.13596475577881948265e-11;
+
+// ── Public entry points ──
+
+/// Sine and cosine of `x` (`|x| < 2^63`, finite).
+pub fn sin_cos(x: f64, accuracy: FpuAccuracy) -> (f64, f64) {
+    let (n, hi, lo) = reduce(x, accuracy);
+    let s = kernel_sin(hi, lo);
+    let c = kernel_cos(hi, lo);
+    match n & 3 {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    }
+}
+
+/// Sine of `x` (`|x| < 2^63`, finite).
+pub fn sin(x: f64, accuracy: FpuAccuracy) -> f64 {
+    sin_cos(x, accuracy).0
+}
+
+/// Cosine of `x` (`|x| < 2^63`, finite).
+pub fn cos(x: f64, accuracy: FpuAccuracy) -> f64 {
+    sin_cos(x, accuracy).1
+}
+
+/// Tangent of `x` (`|x| < 2^63`, finite).
+pub fn tan(x: f64, accuracy: FpuAccuracy) -> f64 {
+    let (n, hi, lo) = reduce(x, accuracy);
+    let s = kernel_sin(hi, lo);
+    let c = kernel_cos(hi, lo);
+    if n & 1 == 0 { s / c } else { -c / s }
+}
+
+/// `2^x - 1`, the F2XM1 operation (defined by hardware for `-1 <= x <= 1`).
+///
+/// Computed as `expm1(x · ln 2)`: the argument is scaled down by 2^5, a
+/// short Taylor series is evaluated, and the result is squared back up
+/// with `e' = e · (e + 2)`, which keeps full relative precision near zero
+/// where `2^x - 1` is tiny. Precise mode additionally carries the product
+/// `x · ln 2` as a double-double.
+pub fn f2xm1(x: f64, accuracy: FpuAccuracy) -> f64 {
+    if x == 0.0 || x.is_nan() {
+        return x;
+    }
+    const HALVINGS: i32 = 5;
+    let scale = 1.0 / (1u64 << HALVINGS) as f64;
+    let (r_hi, r_lo) = match accuracy {
+        FpuAccuracy::Fast => (x * LN2_HI, 0.0),
+        FpuAccuracy::Precise => {
+            let (p, e) = two_prod(x, LN2_HI);
+            fast_two_sum(p, e + x * LN2_LO)
+        }
+    };
+    let t = r_hi * scale;
+    let t_lo = r_lo * scale;
+
+    // expm1(t) for |t| <= ln2/32: terms beyond t^10/10! are below 2^-60.
+    let mut p = 1.0 / 3628800.0;
+    let mut k = 9;
+    while k >= 2 {
+        p = p * t + 1.0 / factorial(k);
+        k -= 1;
+    }
+    let mut e = t + (t * t) * p + t_lo * (1.0 + t);
+
+    for _ in 0..HALVINGS {
+        e = e * (e + 2.0);
+    }
+    e
+}
+
+// ── Range reduction ──
+
+/// Reduce `x` to `n · π/2 + (hi + lo)` with `|hi + lo| <= ~π/4`.
+fn reduce(x: f64, accuracy: FpuAccuracy) -> (i64, f64, f64) {
+    let ax = abs(x);
+    if ax <= core::f64::consts::FRAC_PI_4 {
+        return (0, x, 0.0);
+    }
+    match accuracy {
+        FpuAccuracy::Fast => reduce_fast(x),
+        FpuAccuracy::Precise if ax < MEDIUM_LIMIT => reduce_medium(x),
+        FpuAccuracy::Precise => {
+            let (n, hi, lo) = reduce_large(ax);
+            if x < 0.0 { (-n, -hi, -lo) } else { (n, hi, lo) }
+        }
+    }
+}
+
+/// Single-step Cody–Waite reduction using PIO2_1 + PIO2_1T.
+fn reduce_fast(x: f64) -> (i64, f64, f64) {
+    let fn_ = round_nearest(x * INV_PIO2);
+    let r = x - fn_ * PIO2_1;
+    let w = fn_ * PIO2_1T;
+    let y0 = r - w;
+    let y1 = (r - y0) - w;
+    (fn_ as i64, y0, y1)
+}
+
+/// fdlibm medium-range reduction: up to three Cody–Waite steps, each
+/// taken only if the previous one cancelled too many bits.
+fn reduce_medium(x: f64) -> (i64, f64, f64) {
+    let fn_ = round_nearest(x * INV_PIO2);
+    let mut r = x - fn_ * PIO2_1;
+    let mut w = fn_ * PIO2_1T;
+    let mut y0 = r - w;
+    let j = exponent(x);
+    if j - exponent(y0) > 16 {
+        let t = r;
+        w = fn_ * PIO2_2;
+        r = t - w;
+        w = fn_ * PIO2_2T - ((t - r) - w);
+        y0 = r - w;
+        if j - exponent(y0) > 49 {
+            let t = r;
+            w = fn_ * PIO2_3;
+            r = t - w;
+            w = fn_ * PIO2_3T - ((t - r) - w);
+            y0 = r - w;
+        }
+    }
+    let y1 = (r - y0) - w;
+    (fn_ as i64, y0, y1)
+}
+
+/// Payne–Hanek reduction of a positive `x < 2^63`.
+///
+/// With `x = m · 2^e` (`m` a 53-bit integer), only the bits of 2/π whose
+/// product with `x` lands in `[2^-190, 2^2)` matter: higher bits add
+/// multiples of 4 (whole turns), lower ones are below double precision
+/// even for the worst-case operands. A 192-bit window of 2/π starting at
+/// weight 2^(1-e) is multiplied by `m`, keeping only the low 192 bits of the
+/// product: 2 quadrant bits and 190 fraction bits.
+fn reduce_large(x: f64) -> (i64, f64, f64) {
+    let bits = x.to_bits();
+    let m = (bits & ((1u64 << 52) - 1)) | (1u64 << 52);
+    let e = ((bits >> 52) & 0x7FF) as i32 - 1075;
+
+    let start = e - 2;
+    let w = [window(start), window(start + 64), window(start + 128)];
+
+    // P = m * W mod 2^192, as little-endian 64-bit limbs.
+    let mut p = [0u64; 3];
+    let mut carry: u128 = 0;
+    for i in 0..3 {
+        let prod = (m as u128) * (w[2 - i] as u128) + carry;
+        p[i] = prod as u64;
+        carry = prod >> 64;
+    }
+
+    let mut n = (p[2] >> 62) as i64;
+    p[2] &= (1u64 << 62) - 1;
+    // Fractions above one half round up to the next quadrant, leaving a
+    // negative remainder so |r| <= π/4.
+    let negative = p[2] >> 61 != 0;
+    if negative {
+        n += 1;
+        // p = 2^190 - p
+        let mut borrow = 0u64;
+        for limb in p.iter_mut() {
+            let (v, b1) = 0u64.overflowing_sub(*limb);
+            let (v, b2) = v.overflowing_sub(borrow);
+            *limb = v;
+            borrow = (b1 | b2) as u64;
+        }
+        p[2] &= (1u64 << 62) - 1;
+    }
+
+    // Normalize the 190-bit fraction so its top set bit is bit 127 of a u128.
+    let (top, shift) = if p[2] != 0 {
+        // p[2] holds at most 62 bits, so lz >= 2.
+        let lz = p[2].leading_zeros();
+        let v = ((p[2] as u128) << 64) | p[1] as u128;
+        ((v << lz) | (p[0] >> (64 - lz)) as u128, lz as i32)
+    } else if p[1] != 0 {
+        let lz = p[1].leading_zeros();
+        let v = ((p[1] as u128) << 64) | p[0] as u128;
+        (v << lz, 64 + lz as i32)
+    } else if p[0] != 0 {
+        let lz = p[0].leading_zeros();
+        (((p[0] as u128) << lz) << 64, 128 + lz as i32)
+    } else {
+        return (n, 0.0, 0.0);
+    };
+
+    // fraction = top * 2^(-126 - shift), as a double-double.
+    let a = (top >> 75) as u64 as f64;
+    let b = (top & ((1u128 << 75) - 1)) as u64 as f64;
+    let (f_hi, f_lo) = fast_two_sum(a * pow2(75), b);
+    let scale = pow2(-126 - shift);
+    // (f_hi + f_lo) * scale * π/2
+    let (p_hi, p_err) = two_prod(f_hi, PIO2_HI);
+    let p_lo = p_err + f_hi * PIO2_LO + f_lo * PIO2_HI;
+    let (hi, lo) = fast_two_sum(p_hi, p_lo);
+    let (hi, lo) = (hi * scale, lo * scale);
+    if negative { (n, -hi, -lo) } else { (n, hi, lo) }
+}
+
+/// 64 bits of 2/π starting at table bit `start`, where table bit `b` has
+/// weight 2^-(b+1). Negative indices (above the binary point) are zero.
+fn window(start: i32) -> u64 {
+    let mut out = 0u64;
+    for i in 0..64 {
+        let bit = start + i;
+        out <<= 1;
+        if bit >= 0 && (bit as usize) < TWO_OVER_PI.len() * 64 {
+            let word = TWO_OVER_PI[bit as usize / 64];
+            out |= (word >> (63 - bit as usize % 64)) & 1;
+        }
+    }
+    out
+}
+
+// ── Polynomial kernels ──
+
+/// sin(x + y) for |x + y| <= π/4, |y| tiny relative to x.
+fn kernel_sin(x: f64, y: f64) -> f64 {
+    let z = x * x;
+    let v = z * x;
+    let r = S2 + z * (S3 + z * (S4 + z * (S5 + z * S6)));
+    x - ((z * (0.5 * y - v * r) - y) - v * S1)
+}
+
+/// cos(x + y) for |x + y| <= π/4, |y| tiny relative to x.
+fn kernel_cos(x: f64, y: f64) -> f64 {
+    let z = x * x;
+    let w = z * z;
+    let r = z * (C1 + z * (C2 + z * C3)) + w * w * (C4 + z * (C5 + z * C6));
+    let hz = 0.5 * z;
+    let w = 1.0 - hz;
+    w + (((1.0 - w) - hz) + (z * r - x * y))
+}
+
+// ── Helpers (no libm in no_std) ──
+
+#[inline]
+fn abs(x: f64) -> f64 {
+    f64::from_bits(x.to_bits() & !(1u64 << 63))
+}
+
+/// Unbiased binary exponent of `x` (garbage for zero/denormals, which the
+/// callers only use as "very small").
+#[inline]
+fn exponent(x: f64) -> i32 {
+    ((x.to_bits() >> 52) & 0x7FF) as i32 - 1023
+}
+
+/// 2^k for `-1022 <= k <= 1023`.
+#[inline]
+fn pow2(k: i32) -> f64 {
+    f64::from_bits(((k + 1023) as u64) << 52)
+}
+
+/// Round to nearest integer, ties to even.
+fn round_nearest(y: f64) -> f64 {
+    const TWO52: f64 = 4503599627370496.0;
+    let a = abs(y);
+    if a >= TWO52 {
+        return y;
+    }
+    let r = (a + TWO52) - TWO52;
+    if y < 0.0 { -r } else { r }
+}
+
+fn factorial(k: i32) -> f64 {
+    let mut f = 1.0;
+    for i in 2..=k {
+        f *= i as f64;
+    }
+    f
+}
+
+/// Exact `a + b = s + e` given `|a| >= |b|`.
+#[inline]
+fn fast_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    (s, b - (s - a))
+}
+
+/// Dekker split of `a` into two 26-bit halves.
+#[inline]
+fn split(a: f64) -> (f64, f64) {
+    let c = 134217729.0 * a; // 2^27 + 1
+    let hi = c - (c - a);
+    (hi, a - hi)
+}
+
+/// Exact `a * b = p + e` (no FMA available).
+#[inline]
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    let (ah, al) = split(a);
+    let (bh, bl) = split(b);
+    let e = ((ah * bh - p) + ah * bl + al * bh) + al * bl;
+    (p, e)
+}
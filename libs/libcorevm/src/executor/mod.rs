@@ -13,6 +13,7 @@ pub mod arith;
 pub mod control;
 pub mod data;
 pub mod fpu;
+pub mod fpu_trig;
 pub mod logic;
 pub mod setcc;
 pub mod sse;
@@ -940,12 +941,37 @@ fn write_reg_operand(
 ///
 /// Assembles the address from the base register, index register (scaled),
 /// displacement, and segment base. RIP-relative addressing is handled for
-/// 64-bit mode.
+/// 64-bit mode. In real mode the offset is checked against the cached
+/// segment limit (see [`check_real_mode_limit`]).
 pub fn compute_effective_address(
     cpu: &Cpu,
     mem_op: &MemOperand,
     inst: &DecodedInst,
 ) -> Result<u64> {
+    let offset = effective_offset(cpu, mem_op, inst);
+    check_real_mode_limit(cpu, mem_op.segment, offset)?;
+    Ok(cpu.regs.segment(mem_op.segment).base.wrapping_add(offset))
+}
+
+/// Real-mode segment limit check. The cached limit is 64 KiB unless the
+/// segment was loaded in protected mode before switching back (big real
+/// mode), so 32-bit offsets past 0xFFFF only work in unreal mode. Faults
+/// with #SS for the stack segment and #GP otherwise.
+#[inline]
+pub fn check_real_mode_limit(cpu: &Cpu, seg: SegReg, offset: u64) -> Result<()> {
+    if cpu.mode == Mode::RealMode && offset > cpu.regs.segment(seg).limit as u64 {
+        return Err(if seg == SegReg::Ss {
+            VmError::StackFault(0)
+        } else {
+            VmError::GeneralProtection(0)
+        });
+    }
+    Ok(())
+}
+
+/// Effective address of a memory operand within its segment (no segment
+/// base, no limit check), masked to the address size.
+pub fn effective_offset(cpu: &Cpu, mem_op: &MemOperand, inst: &DecodedInst) -> u64 {
     let mut addr: u64 = 0;
 
     // Base register
@@ -970,14 +996,7 @@ pub fn compute_effective_address(
     }
 
     // Mask to address size
-    let mask = inst.address_size.mask();
-    addr &= mask;
-
-    // Add segment base
-    let seg_desc = cpu.regs.segment(mem_op.segment);
-    addr = seg_desc.base.wrapping_add(addr);
-
-    Ok(addr)
+    addr & inst.address_size.mask()
 }
 
 /// Translate a linear address via the MMU and read a value of the given size.
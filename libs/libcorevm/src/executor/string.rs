@@ -18,7 +18,7 @@ use crate::io::IoDispatch;
 use crate::memory::{GuestMemory, Mmu};
 use crate::registers::{GprIndex, SegReg};
 
-use super::{translate_and_read, translate_and_write};
+use super::{check_real_mode_limit, translate_and_read, translate_and_write};
 
 /// Determine the element size for string operations from the opcode.
 ///
@@ -50,6 +50,9 @@ fn read_counter(cpu: &Cpu, inst: &DecodedInst) -> u64 {
 }
 
 /// Write the counter register at the instruction's address size.
+///
+/// Called once per finished `REP` iteration, which also ends the replay of
+/// that iteration's device accesses after an I/O exit.
 fn write_counter(cpu: &mut Cpu, inst: &DecodedInst, val: u64) {
     cpu.regs.write_gpr(
         GprIndex::Rcx as u8,
@@ -57,6 +60,9 @@ fn write_counter(cpu: &mut Cpu, inst: &DecodedInst, val: u64) {
         inst.prefix.has_rex(),
         val,
     );
+    if !cpu.io_exit.is_null() {
+        unsafe { (*cpu.io_exit).commit() };
+    }
 }
 
 /// Read RSI/ESI/SI at the instruction's address size.
@@ -90,16 +96,18 @@ fn write_di(cpu: &mut Cpu, inst: &DecodedInst, val: u64) {
 }
 
 /// Compute the linear address for DS:[RSI] with optional segment override.
-fn src_linear(cpu: &Cpu, inst: &DecodedInst) -> u64 {
+fn src_linear(cpu: &Cpu, inst: &DecodedInst) -> Result<u64> {
     let seg = inst.prefix.seg_override.unwrap_or(SegReg::Ds);
-    let base = cpu.regs.segment(seg).base;
-    base.wrapping_add(read_si(cpu, inst))
+    let si = read_si(cpu, inst);
+    check_real_mode_limit(cpu, seg, si)?;
+    Ok(cpu.regs.segment(seg).base.wrapping_add(si))
 }
 
 /// Compute the linear address for ES:[RDI] (always ES, no override allowed).
-fn dst_linear(cpu: &Cpu, inst: &DecodedInst) -> u64 {
-    let base = cpu.regs.segment(SegReg::Es).base;
-    base.wrapping_add(read_di(cpu, inst))
+fn dst_linear(cpu: &Cpu, inst: &DecodedInst) -> Result<u64> {
+    let di = read_di(cpu, inst);
+    check_real_mode_limit(cpu, SegReg::Es, di)?;
+    Ok(cpu.regs.segment(SegReg::Es).base.wrapping_add(di))
 }
 
 /// MOVS: copy from DS:[RSI] to ES:[RDI].
@@ -122,8 +130,8 @@ pub fn exec_movs(
                 break;
             }
 
-            let s = src_linear(cpu, inst);
-            let d = dst_linear(cpu, inst);
+            let s = src_linear(cpu, inst)?;
+            let d = dst_linear(cpu, inst)?;
             let val = translate_and_read(cpu, s, elem, mmu, memory)?;
             translate_and_write(cpu, d, elem, val, mmu, memory)?;
 
@@ -133,8 +141,8 @@ pub fn exec_movs(
         }
     } else {
         // Single MOVS
-        let s = src_linear(cpu, inst);
-        let d = dst_linear(cpu, inst);
+        let s = src_linear(cpu, inst)?;
+        let d = dst_linear(cpu, inst)?;
         let val = translate_and_read(cpu, s, elem, mmu, memory)?;
         translate_and_write(cpu, d, elem, val, mmu, memory)?;
 
@@ -168,8 +176,8 @@ pub fn exec_cmps(
                     break;
                 }
 
-                let s = src_linear(cpu, inst);
-                let d = dst_linear(cpu, inst);
+                let s = src_linear(cpu, inst)?;
+                let d = dst_linear(cpu, inst)?;
                 let src_val = translate_and_read(cpu, s, elem, mmu, memory)?;
                 let dst_val = translate_and_read(cpu, d, elem, mmu, memory)?;
 
@@ -195,8 +203,8 @@ pub fn exec_cmps(
                     break;
                 }
 
-                let s = src_linear(cpu, inst);
-                let d = dst_linear(cpu, inst);
+                let s = src_linear(cpu, inst)?;
+                let d = dst_linear(cpu, inst)?;
                 let src_val = translate_and_read(cpu, s, elem, mmu, memory)?;
                 let dst_val = translate_and_read(cpu, d, elem, mmu, memory)?;
 
@@ -216,8 +224,8 @@ pub fn exec_cmps(
         }
         RepPrefix::None => {
             // Single CMPS
-            let s = src_linear(cpu, inst);
-            let d = dst_linear(cpu, inst);
+            let s = src_linear(cpu, inst)?;
+            let d = dst_linear(cpu, inst)?;
             let src_val = translate_and_read(cpu, s, elem, mmu, memory)?;
             let dst_val = translate_and_read(cpu, d, elem, mmu, memory)?;
 
@@ -254,14 +262,14 @@ pub fn exec_stos(
                 break;
             }
 
-            let d = dst_linear(cpu, inst);
+            let d = dst_linear(cpu, inst)?;
             translate_and_write(cpu, d, elem, acc, mmu, memory)?;
 
             write_di(cpu, inst, read_di(cpu, inst).wrapping_add(delta as u64));
             write_counter(cpu, inst, count - 1);
         }
     } else {
-        let d = dst_linear(cpu, inst);
+        let d = dst_linear(cpu, inst)?;
         translate_and_write(cpu, d, elem, acc, mmu, memory)?;
 
         write_di(cpu, inst, read_di(cpu, inst).wrapping_add(delta as u64));
@@ -290,7 +298,7 @@ pub fn exec_lods(
                 break;
             }
 
-            let s = src_linear(cpu, inst);
+            let s = src_linear(cpu, inst)?;
             let val = translate_and_read(cpu, s, elem, mmu, memory)?;
             cpu.regs.write_gpr(GprIndex::Rax as u8, elem, inst.prefix.has_rex(), val);
 
@@ -298,7 +306,7 @@ pub fn exec_lods(
             write_counter(cpu, inst, count - 1);
         }
     } else {
-        let s = src_linear(cpu, inst);
+        let s = src_linear(cpu, inst)?;
         let val = translate_and_read(cpu, s, elem, mmu, memory)?;
         cpu.regs.write_gpr(GprIndex::Rax as u8, elem, inst.prefix.has_rex(), val);
 
@@ -332,7 +340,7 @@ pub fn exec_scas(
                     break;
                 }
 
-                let d = dst_linear(cpu, inst);
+                let d = dst_linear(cpu, inst)?;
                 let mem_val = translate_and_read(cpu, d, elem, mmu, memory)?;
 
                 let result = acc.wrapping_sub(mem_val) & elem.mask();
@@ -355,7 +363,7 @@ pub fn exec_scas(
                     break;
                 }
 
-                let d = dst_linear(cpu, inst);
+                let d = dst_linear(cpu, inst)?;
                 let mem_val = translate_and_read(cpu, d, elem, mmu, memory)?;
 
                 let result = acc.wrapping_sub(mem_val) & elem.mask();
@@ -372,7 +380,7 @@ pub fn exec_scas(
         }
         RepPrefix::None => {
             // Single SCAS
-            let d = dst_linear(cpu, inst);
+            let d = dst_linear(cpu, inst)?;
             let mem_val = translate_and_read(cpu, d, elem, mmu, memory)?;
 
             let result = acc.wrapping_sub(mem_val) & elem.mask();
@@ -424,7 +432,7 @@ pub fn exec_ins(
             }
 
             let val = io.port_in(port, size_io)? as u64;
-            let d = dst_linear(cpu, inst);
+            let d = dst_linear(cpu, inst)?;
             translate_and_write(cpu, d, elem, val, mmu, memory)?;
 
             write_di(cpu, inst, read_di(cpu, inst).wrapping_add(delta as u64));
@@ -432,7 +440,7 @@ pub fn exec_ins(
         }
     } else {
         let val = io.port_in(port, size_io)? as u64;
-        let d = dst_linear(cpu, inst);
+        let d = dst_linear(cpu, inst)?;
         translate_and_write(cpu, d, elem, val, mmu, memory)?;
 
         write_di(cpu, inst, read_di(cpu, inst).wrapping_add(delta as u64));
@@ -465,7 +473,7 @@ pub fn exec_outs(
                 break;
             }
 
-            let s = src_linear(cpu, inst);
+            let s = src_linear(cpu, inst)?;
             let val = translate_and_read(cpu, s, elem, mmu, memory)?;
             io.port_out(port, size_io, val as u32)?;
 
@@ -473,7 +481,7 @@ pub fn exec_outs(
             write_counter(cpu, inst, count - 1);
         }
     } else {
-        let s = src_linear(cpu, inst);
+        let s = src_linear(cpu, inst)?;
         let val = translate_and_read(cpu, s, elem, mmu, memory)?;
         io.port_out(port, size_io, val as u32)?;
 
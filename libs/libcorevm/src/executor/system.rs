@@ -9,6 +9,7 @@ use crate::error::{Result, VmError};
 use crate::flags::{self, OperandSize};
 use crate::instruction::{DecodedInst, Operand};
 use crate::memory::{GuestMemory, Mmu};
+use crate::msr;
 use crate::registers::*;
 
 use super::{compute_effective_address, translate_and_read, translate_and_write};
@@ -277,10 +278,11 @@ pub fn exec_mov_dr(cpu: &mut Cpu, inst: &DecodedInst) -> Result<()> {
 
 /// RDMSR: read Model-Specific Register.
 ///
-/// ECX selects the MSR; the 64-bit value is returned in EDX:EAX.
+/// ECX selects the MSR; the 64-bit value is returned in EDX:EAX. Faults
+/// with #GP(0) as described in [`crate::msr`].
 pub fn exec_rdmsr(cpu: &mut Cpu, inst: &DecodedInst) -> Result<()> {
     let msr_index = cpu.regs.read_gpr32(GprIndex::Rcx as u8);
-    let val = cpu.regs.read_msr(msr_index);
+    let val = msr::read(cpu, msr_index)?;
 
     cpu.regs.write_gpr32(GprIndex::Rax as u8, val as u32);
     cpu.regs.write_gpr32(GprIndex::Rdx as u8, (val >> 32) as u32);
@@ -291,29 +293,15 @@ pub fn exec_rdmsr(cpu: &mut Cpu, inst: &DecodedInst) -> Result<()> {
 
 /// WRMSR: write Model-Specific Register.
 ///
-/// ECX selects the MSR; the 64-bit value comes from EDX:EAX.
-/// After writing EFER, calls `cpu.update_mode()` to recalculate CPU mode.
+/// ECX selects the MSR; the 64-bit value comes from EDX:EAX. Side effects
+/// (FS/GS base, EFER mode switch) and faults are handled by [`crate::msr`].
 pub fn exec_wrmsr(cpu: &mut Cpu, inst: &DecodedInst) -> Result<()> {
     let msr_index = cpu.regs.read_gpr32(GprIndex::Rcx as u8);
     let lo = cpu.regs.read_gpr32(GprIndex::Rax as u8) as u64;
     let hi = cpu.regs.read_gpr32(GprIndex::Rdx as u8) as u64;
     let val = (hi << 32) | lo;
 
-    cpu.regs.write_msr(msr_index, val);
-
-    // Special handling for FS/GS base MSRs
-    match msr_index {
-        MSR_FS_BASE => {
-            cpu.regs.segment_mut(SegReg::Fs).base = val;
-        }
-        MSR_GS_BASE => {
-            cpu.regs.segment_mut(SegReg::Gs).base = val;
-        }
-        MSR_EFER => {
-            cpu.update_mode();
-        }
-        _ => {}
-    }
+    msr::write(cpu, msr_index, val)?;
 
     cpu.regs.rip += inst.length as u64;
     Ok(())
@@ -15,6 +15,32 @@ pub const TAG_SPECIAL: u8 = 0b10;
 /// Tag indicating the register is empty (available for push).
 pub const TAG_EMPTY: u8 = 0b11;
 
+/// Accuracy/speed trade-off for the x87 transcendental instructions
+/// (FSIN, FCOS, FSINCOS, FPTAN, F2XM1).
+///
+/// This is a host-side emulator setting, not guest-visible state: `FINIT`
+/// and CPU reset leave it unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum FpuAccuracy {
+    /// Cheap single-step argument reduction. Exact enough for everyday
+    /// arguments; large operands lose precision much like real hardware.
+    Fast = 0,
+    /// Payne–Hanek argument reduction for large operands; results are
+    /// within a few ulps of the true value across the whole operand range.
+    Precise = 1,
+}
+
+impl FpuAccuracy {
+    /// Decode the FFI representation (unknown values select `Precise`).
+    pub fn from_u32(v: u32) -> Self {
+        match v {
+            0 => FpuAccuracy::Fast,
+            _ => FpuAccuracy::Precise,
+        }
+    }
+}
+
 /// x87 FPU register file and associated control state.
 ///
 /// The eight data registers are organized as a circular stack addressed
@@ -3,12 +3,16 @@
 //! Provides a dispatch table mapping I/O port ranges to handler objects.
 //! Guest `IN`/`OUT` instructions are routed through [`IoDispatch`] to the
 //! registered [`IoHandler`] for that port, or silently handled with default
-//! bus behavior (all-ones on read, ignore on write) when no handler exists.
+//! bus behavior (all-ones on read, ignore on write) when no handler exists —
+//! unless I/O exits are enabled, in which case such an access is handed to
+//! the host (see [`crate::ioexit`]).
 
 use alloc::boxed::Box;
 use alloc::vec::Vec;
 use core::sync::atomic::{AtomicU32, Ordering};
 use crate::error::Result;
+use crate::ioexit::IoExit;
+use crate::iotrace::{IoTrace, KIND_PORT_READ, KIND_PORT_WRITE};
 
 /// Diagnostic counter for total CF8 writes reaching the dispatcher.
 static CF8_DISPATCH_COUNT: AtomicU32 = AtomicU32::new(0);
@@ -57,6 +61,10 @@ impl IoRegion {
 pub struct IoDispatch {
     /// Registered I/O regions, searched linearly on each access.
     regions: Vec<IoRegion>,
+    /// Access tracer owned by the VM instance (null when tracing is off).
+    pub trace: *mut IoTrace,
+    /// I/O exit state owned by the VM instance (null when unused).
+    pub exit: *mut IoExit,
 }
 
 impl IoDispatch {
@@ -64,6 +72,8 @@ impl IoDispatch {
     pub fn new() -> Self {
         IoDispatch {
             regions: Vec::new(),
+            trace: core::ptr::null_mut(),
+            exit: core::ptr::null_mut(),
         }
     }
 
@@ -85,28 +95,72 @@ impl IoDispatch {
     /// Searches for a handler covering `port`. If found, delegates to
     /// [`IoHandler::read`]. If no handler is registered, returns the
     /// default x86 bus float value: all bits set for the requested size
-    /// (0xFF for byte, 0xFFFF for word, 0xFFFFFFFF for dword).
+    /// (0xFF for byte, 0xFFFF for word, 0xFFFFFFFF for dword), or fails
+    /// with [`VmError::IoExit`](crate::error::VmError::IoExit) while I/O
+    /// exits are armed.
     pub fn port_in(&mut self, port: u16, size: u8) -> Result<u32> {
-        for region in self.regions.iter_mut() {
-            if region.contains(port) {
-                return region.handler.read(port, size);
-            }
-        }
-        // No handler — return bus float (all ones) matching the access size.
-        let val = match size {
-            1 => 0xFF,
-            2 => 0xFFFF,
-            _ => 0xFFFF_FFFF,
+        let val = match self.armed_exit() {
+            Some(exit) => match exit.replay(KIND_PORT_READ, port as u64, size) {
+                Some(val) => val as u32,
+                None => match self.dispatch_in(port, size) {
+                    Some(val) => {
+                        let val = val?;
+                        exit.record(KIND_PORT_READ, port as u64, size, val as u64);
+                        val
+                    }
+                    None => return Err(exit.exit(KIND_PORT_READ, port as u64, size, 0)),
+                },
+            },
+            None => match self.dispatch_in(port, size) {
+                Some(val) => val?,
+                // No handler — return bus float (all ones) matching the access size.
+                None => match size {
+                    1 => 0xFF,
+                    2 => 0xFFFF,
+                    _ => 0xFFFF_FFFF,
+                },
+            },
         };
+        if !self.trace.is_null() {
+            unsafe { (*self.trace).record(KIND_PORT_READ, port as u64, size, val as u64) };
+        }
         Ok(val)
     }
 
+    /// Read from the handler covering `port` (`None` = no handler).
+    fn dispatch_in(&mut self, port: u16, size: u8) -> Option<Result<u32>> {
+        self.regions
+            .iter_mut()
+            .find(|region| region.contains(port))
+            .map(|region| region.handler.read(port, size))
+    }
+
+    /// The I/O exit state, if an instruction is executing with exits on.
+    #[inline]
+    fn armed_exit(&self) -> Option<&'static mut IoExit> {
+        if self.exit.is_null() {
+            return None;
+        }
+        let exit = unsafe { &mut *self.exit };
+        if exit.armed() { Some(exit) } else { None }
+    }
+
     /// Perform a port write (guest `OUT` instruction).
     ///
     /// Searches for a handler covering `port`. If found, delegates to
     /// [`IoHandler::write`]. If no handler is registered, the write is
-    /// silently ignored (standard x86 bus behavior).
+    /// silently ignored (standard x86 bus behavior), or handed to the host
+    /// while I/O exits are armed.
     pub fn port_out(&mut self, port: u16, size: u8, val: u32) -> Result<()> {
+        let mut exit = self.armed_exit();
+        if let Some(exit) = exit.as_deref_mut() {
+            if exit.replay(KIND_PORT_WRITE, port as u64, size).is_some() {
+                return Ok(());
+            }
+        }
+        if !self.trace.is_null() {
+            unsafe { (*self.trace).record(KIND_PORT_WRITE, port as u64, size, val as u64) };
+        }
         // Diagnostic: track ALL writes to PCI config address port.
         if port == 0xCF8 {
             let n = CF8_DISPATCH_COUNT.fetch_add(1, Ordering::Relaxed);
@@ -120,11 +174,18 @@ impl IoDispatch {
         }
         for region in self.regions.iter_mut() {
             if region.contains(port) {
-                return region.handler.write(port, size, val);
+                region.handler.write(port, size, val)?;
+                if let Some(exit) = exit {
+                    exit.record(KIND_PORT_WRITE, port as u64, size, val as u64);
+                }
+                return Ok(());
             }
         }
-        // No handler — silently discard the write.
-        Ok(())
+        match exit {
+            Some(exit) => Err(exit.exit(KIND_PORT_WRITE, port as u64, size, val as u64)),
+            // No handler — silently discard the write.
+            None => Ok(()),
+        }
     }
 
     /// Return the number of registered I/O regions (diagnostic).
@@ -0,0 +1,186 @@
+//! I/O exits: unhandled guest device accesses handed to the host.
+//!
+//! When enabled via `corevm_set_io_exit`, a port access with no registered
+//! handler, or a memory access that hits neither guest RAM nor an MMIO
+//! region, does not get the default bus behavior. Instead the instruction
+//! stops with [`VmError::IoExit`], `corevm_run` returns with exit code 9
+//! and the access is available as an [`IoRequest`]. The host performs it,
+//! passes the value read (if any) to `corevm_complete_io` and runs the VM
+//! again — the same loop as a KVM I/O exit.
+//!
+//! The interpreter does not save the state of a half-executed instruction.
+//! The instruction is restarted from its first byte instead, and every
+//! device access it made before the exit is replayed from a log rather
+//! than sent to the device again: a read returns the logged value, a write
+//! is dropped. The completed request is the last entry of the log, so the
+//! restarted instruction gets past it. The log is cleared when the
+//! instruction retires, and after each iteration of a `REP` string
+//! instruction (whose registers already reflect the finished iterations).
+//!
+//! Accesses only exit while an instruction executes; BIOS services and
+//! the host's own accesses keep the default behavior.
+
+use alloc::vec::Vec;
+use crate::error::{Result, VmError};
+
+/// Most accesses one instruction may make before it is treated as a
+/// runaway and stops replaying (never reached by real instructions).
+const MAX_LOG: usize = 64;
+
+/// An access waiting for the host (32 bytes, C layout).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IoRequest {
+    /// Port number or guest physical address.
+    pub addr: u64,
+    /// Value to write (zero-extended); 0 for reads.
+    pub value: u64,
+    /// RIP of the instruction that made the access.
+    pub rip: u64,
+    /// One of the [`crate::iotrace`] `KIND_*` constants.
+    pub kind: u8,
+    /// Access size in bytes (1, 2, 4 or 8).
+    pub size: u8,
+    pub _reserved: [u8; 6],
+}
+
+impl IoRequest {
+    fn matches(&self, kind: u8, addr: u64, size: u8) -> bool {
+        self.kind == kind && self.addr == addr && self.size == size
+    }
+}
+
+/// I/O exit state, owned by `VmInstance` and shared with the CPU and the
+/// dispatchers through raw pointers (same ownership pattern as the tracer).
+pub struct IoExit {
+    /// Unhandled accesses exit (`corevm_set_io_exit`).
+    pub enabled: bool,
+    /// An instruction is executing, so accesses may exit.
+    armed: bool,
+    /// Device accesses of the current instruction, in order; the `value`
+    /// of a read is the value it returned.
+    log: Vec<IoRequest>,
+    /// Next log entry to replay.
+    cursor: usize,
+    /// The access the host has to perform, until it is completed.
+    pending: Option<IoRequest>,
+    /// Where to read the current guest RIP from (owned by the CPU).
+    rip_src: *const u64,
+}
+
+impl IoExit {
+    pub fn new(rip_src: *const u64) -> Self {
+        IoExit {
+            enabled: false,
+            armed: false,
+            log: Vec::new(),
+            cursor: 0,
+            pending: None,
+            rip_src,
+        }
+    }
+
+    /// Called by the CPU before executing an instruction.
+    #[inline]
+    pub fn begin(&mut self) {
+        self.armed = self.enabled;
+        self.cursor = 0;
+    }
+
+    /// Called by the CPU after executing an instruction. The log is kept
+    /// only if the instruction exited and will be restarted.
+    #[inline]
+    pub fn end(&mut self, result: &Result<()>) {
+        self.armed = false;
+        if !matches!(result, Err(VmError::IoExit)) {
+            self.log.clear();
+        }
+    }
+
+    /// Forget the accesses made so far: the instruction has committed
+    /// progress (a finished `REP` iteration) and will not redo them.
+    #[inline]
+    pub fn commit(&mut self) {
+        if self.armed {
+            self.log.clear();
+            self.cursor = 0;
+        }
+    }
+
+    /// True while an instruction that exited is waiting to be restarted.
+    /// The CPU must not deliver interrupts before restarting it.
+    #[inline]
+    pub fn restarting(&self) -> bool {
+        !self.log.is_empty()
+    }
+
+    /// Whether accesses are being logged (an instruction executes and the
+    /// mode is on).
+    #[inline]
+    pub fn armed(&self) -> bool {
+        self.armed
+    }
+
+    /// The logged result of this access if the instruction made it before
+    /// it was restarted (value read, or 0 for a write).
+    pub fn replay(&mut self, kind: u8, addr: u64, size: u8) -> Option<u64> {
+        let entry = self.log.get(self.cursor)?;
+        if !entry.matches(kind, addr, size) {
+            // The instruction took another path; the rest of the log is stale.
+            self.log.truncate(self.cursor);
+            return None;
+        }
+        self.cursor += 1;
+        Some(entry.value)
+    }
+
+    /// Log an access a device handled, so a restart does not repeat it.
+    pub fn record(&mut self, kind: u8, addr: u64, size: u8, value: u64) {
+        if self.log.len() < MAX_LOG {
+            self.log.push(self.request(kind, addr, size, value));
+            self.cursor = self.log.len();
+        }
+    }
+
+    /// Hand an unhandled access to the host. Returns the error that stops
+    /// the instruction.
+    pub fn exit(&mut self, kind: u8, addr: u64, size: u8, value: u64) -> VmError {
+        self.pending = Some(self.request(kind, addr, size, value));
+        VmError::IoExit
+    }
+
+    /// The access waiting for the host, if any.
+    pub fn pending(&self) -> Option<IoRequest> {
+        self.pending
+    }
+
+    /// Complete the pending access: a read returns `value` (masked to the
+    /// access size) when the instruction is restarted. Returns false if
+    /// nothing is pending.
+    pub fn complete(&mut self, value: u64) -> bool {
+        let Some(mut req) = self.pending.take() else { return false };
+        if req.kind & 1 == 0 {
+            req.value = match req.size {
+                1 => value & 0xFF,
+                2 => value & 0xFFFF,
+                4 => value & 0xFFFF_FFFF,
+                _ => value,
+            };
+        }
+        self.log.push(req);
+        true
+    }
+
+    /// Drop the pending access and the replay log (VM reset).
+    pub fn clear(&mut self) {
+        self.armed = false;
+        self.log.clear();
+        self.cursor = 0;
+        self.pending = None;
+    }
+
+    fn request(&self, kind: u8, addr: u64, size: u8, value: u64) -> IoRequest {
+        let rip = if self.rip_src.is_null() { 0 } else { unsafe { *self.rip_src } };
+        IoRequest { addr, value, rip, kind, size, _reserved: [0; 6] }
+    }
+}
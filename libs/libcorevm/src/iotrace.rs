@@ -0,0 +1,229 @@
+//! Port I/O and MMIO access tracing.
+//!
+//! When enabled via `corevm_iotrace_enable`, every guest port access routed
+//! through [`IoDispatch`](crate::io::IoDispatch) and every access that hits an
+//! MMIO region is recorded as a [`TraceRecord`] (kind, address, size, value,
+//! guest RIP). Records go into a fixed-size ring buffer drained by
+//! `corevm_iotrace_take` (oldest first; the oldest record is overwritten when
+//! full), or — in callback mode — are handed to a host callback as they
+//! happen.
+//!
+//! Per-range filters narrow the trace: if any *include* filter exists for an
+//! address space, only accesses inside an include range are recorded, and an
+//! access inside any *exclude* range is never recorded.
+//!
+//! Tracing costs one null-pointer check per access while disabled.
+
+use alloc::vec::Vec;
+
+/// Address space: port I/O.
+pub const SPACE_PORT: u8 = 0;
+/// Address space: memory-mapped I/O.
+pub const SPACE_MMIO: u8 = 1;
+
+/// Record kind: port read (`IN`).
+pub const KIND_PORT_READ: u8 = 0;
+/// Record kind: port write (`OUT`).
+pub const KIND_PORT_WRITE: u8 = 1;
+/// Record kind: MMIO read.
+pub const KIND_MMIO_READ: u8 = 2;
+/// Record kind: MMIO write.
+pub const KIND_MMIO_WRITE: u8 = 3;
+
+/// Default ring buffer capacity (records).
+pub const DEFAULT_CAPACITY: usize = 4096;
+/// Maximum ring buffer capacity (records, 32 bytes each).
+pub const MAX_CAPACITY: usize = 1 << 20;
+/// Maximum number of filters.
+pub const MAX_FILTERS: usize = 32;
+
+/// One traced access (32 bytes, C layout).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TraceRecord {
+    /// Port number or guest physical address.
+    pub addr: u64,
+    /// Value read or written (zero-extended).
+    pub value: u64,
+    /// RIP of the instruction that made the access.
+    pub rip: u64,
+    /// Sequence number (wraps), for spotting gaps after overflow.
+    pub seq: u32,
+    /// One of the `KIND_*` constants.
+    pub kind: u8,
+    /// Access size in bytes (1, 2, 4 or 8).
+    pub size: u8,
+    pub _reserved: u16,
+}
+
+/// Host callback for live tracing: `(userdata, record)`.
+///
+/// Called synchronously from inside the access, i.e. during
+/// `corevm_run`; it must not call back into the VM.
+pub type TraceCb = extern "C" fn(u64, *const TraceRecord);
+
+/// An include or exclude range in one address space.
+#[derive(Debug, Clone, Copy)]
+struct TraceFilter {
+    space: u8,
+    start: u64,
+    /// Exclusive end address.
+    end: u64,
+    include: bool,
+}
+
+/// Trace state, owned by `VmInstance` and shared with the dispatchers
+/// through raw pointers (same ownership pattern as the device proxies).
+pub struct IoTrace {
+    /// Bit `1 << SPACE_*` set for each traced address space.
+    spaces: u8,
+    ring: Vec<TraceRecord>,
+    capacity: usize,
+    /// Index of the oldest record.
+    head: usize,
+    len: usize,
+    seq: u32,
+    /// Records overwritten before being taken.
+    dropped: u64,
+    filters: Vec<TraceFilter>,
+    callback: Option<(TraceCb, u64)>,
+    /// Points at `Cpu::last_exec_rip` of the owning VM.
+    rip_src: *const u64,
+}
+
+impl IoTrace {
+    /// Create a disabled trace reading the current RIP from `rip_src`.
+    pub fn new(rip_src: *const u64) -> Self {
+        IoTrace {
+            spaces: 0,
+            ring: Vec::new(),
+            capacity: DEFAULT_CAPACITY,
+            head: 0,
+            len: 0,
+            seq: 0,
+            dropped: 0,
+            filters: Vec::new(),
+            callback: None,
+            rip_src,
+        }
+    }
+
+    /// Start tracing the address spaces in `spaces` (bit 0 = ports, bit 1 =
+    /// MMIO). A different `capacity` (0 = default) discards buffered records.
+    pub fn enable(&mut self, spaces: u8, capacity: usize) {
+        let capacity = if capacity == 0 { DEFAULT_CAPACITY } else { capacity.min(MAX_CAPACITY) };
+        if capacity != self.capacity {
+            self.capacity = capacity;
+            self.ring = Vec::new();
+            self.head = 0;
+            self.len = 0;
+        }
+        self.spaces = spaces & 0x3;
+    }
+
+    /// Stop recording. Buffered records remain available to [`take`](Self::take).
+    pub fn disable(&mut self) {
+        self.spaces = 0;
+    }
+
+    /// Add an include or exclude range. Returns `false` if the filter table is full.
+    pub fn add_filter(&mut self, space: u8, start: u64, count: u64, include: bool) -> bool {
+        if self.filters.len() >= MAX_FILTERS {
+            return false;
+        }
+        self.filters.push(TraceFilter {
+            space,
+            start,
+            end: start.saturating_add(count),
+            include,
+        });
+        true
+    }
+
+    /// Remove all filters (trace every access).
+    pub fn clear_filters(&mut self) {
+        self.filters.clear();
+    }
+
+    /// Set or clear the live callback. While set, records bypass the ring buffer.
+    pub fn set_callback(&mut self, cb: Option<(TraceCb, u64)>) {
+        self.callback = cb;
+    }
+
+    /// Number of records overwritten before being taken.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Number of buffered records.
+    pub fn pending(&self) -> usize {
+        self.len
+    }
+
+    fn passes_filters(&self, space: u8, addr: u64) -> bool {
+        let mut has_include = false;
+        let mut included = false;
+        for f in self.filters.iter().filter(|f| f.space == space) {
+            let hit = addr >= f.start && addr < f.end;
+            if f.include {
+                has_include = true;
+                included |= hit;
+            } else if hit {
+                return false;
+            }
+        }
+        !has_include || included
+    }
+
+    /// Record one access if its space is traced and it passes the filters.
+    pub fn record(&mut self, kind: u8, addr: u64, size: u8, value: u64) {
+        let space = if kind >= KIND_MMIO_READ { SPACE_MMIO } else { SPACE_PORT };
+        if self.spaces & (1 << space) == 0 || !self.passes_filters(space, addr) {
+            return;
+        }
+        let rec = TraceRecord {
+            addr,
+            value,
+            rip: unsafe { *self.rip_src },
+            seq: self.seq,
+            kind,
+            size,
+            _reserved: 0,
+        };
+        self.seq = self.seq.wrapping_add(1);
+
+        if let Some((cb, userdata)) = self.callback {
+            cb(userdata, &rec);
+            return;
+        }
+        if self.ring.len() < self.capacity {
+            self.ring.push(rec);
+            self.len += 1;
+        } else if self.len < self.capacity {
+            let tail = (self.head + self.len) % self.capacity;
+            self.ring[tail] = rec;
+            self.len += 1;
+        } else {
+            // Full: overwrite the oldest record.
+            self.ring[self.head] = rec;
+            self.head = (self.head + 1) % self.capacity;
+            self.dropped += 1;
+        }
+    }
+
+    /// Move up to `out.len()` records (oldest first) into `out`.
+    /// Returns the number of records written.
+    pub fn take(&mut self, out: &mut [TraceRecord]) -> usize {
+        let n = self.len.min(out.len());
+        for slot in out.iter_mut().take(n) {
+            *slot = self.ring[self.head];
+            self.head = (self.head + 1) % self.capacity;
+        }
+        self.len -= n;
+        if self.len == 0 {
+            self.head = 0;
+            self.ring.clear();
+        }
+        n
+    }
+}
@@ -15,6 +15,8 @@
 //! - **Executor** (`executor/`) — instruction execution grouped by category
 //! - **Memory** (`memory/`) — guest RAM, segmentation, paging, MMIO
 //! - **Devices** (`devices/`) — emulated hardware (SVGA, PS/2, E1000, etc.)
+//! - **Disk** (`disk/`) — disk image backends (in-memory, raw and qcow2 files)
+//! - **BIOS** (`bios.rs`) — optional built-in firmware serviced by the host
 //! - **CPU** (`cpu.rs`) — ties everything together in the fetch-decode-execute loop
 //!
 //! # C ABI
@@ -32,6 +34,7 @@ extern crate libheap;
 pub mod error;
 pub mod flags;
 pub mod registers;
+pub mod msr;
 pub mod instruction;
 pub mod decoder;
 pub mod memory;
@@ -39,9 +42,17 @@ pub mod cpu;
 pub mod executor;
 pub mod interrupts;
 pub mod io;
+pub mod iotrace;
+pub mod ioexit;
+pub mod debug;
 pub mod fpu_state;
 pub mod sse_state;
 pub mod devices;
+pub mod bios;
+pub mod machine;
+pub mod share;
+pub mod disk;
+pub mod pacing;
 
 /// Syscall wrappers for the allocator, panic handler, and debug output.
 mod syscall {
@@ -78,6 +89,7 @@ pub use registers::{RegisterFile, SegReg};
 pub use flags::OperandSize;
 
 use alloc::boxed::Box;
+use alloc::string::String;
 use alloc::vec::Vec;
 use core::ptr;
 
@@ -211,6 +223,28 @@ impl<T: IoHandler> IoHandler for IoProxy<T> {
     }
 }
 
+/// [`IoProxy`] variant for devices that decode fixed port numbers: accesses
+/// to `[base, base+n)` are forwarded as `[native, native+n)`, so such a
+/// device can be placed at a non-standard I/O base.
+struct IoRemap<T: IoHandler> {
+    /// Raw pointer to the device. Valid for the lifetime of the owning `VmInstance`.
+    ptr: *mut T,
+    /// Port base the range is registered at.
+    base: u16,
+    /// Port base the device decodes.
+    native: u16,
+}
+
+impl<T: IoHandler> IoHandler for IoRemap<T> {
+    fn read(&mut self, port: u16, size: u8) -> Result<u32> {
+        unsafe { (*self.ptr).read(port - self.base + self.native, size) }
+    }
+
+    fn write(&mut self, port: u16, size: u8, val: u32) -> Result<()> {
+        unsafe { (*self.ptr).write(port - self.base + self.native, size, val) }
+    }
+}
+
 /// Thin proxy that forwards [`MmioHandler`] calls through a raw pointer.
 ///
 /// Same ownership pattern as [`IoProxy`] — the device is heap-allocated and
@@ -250,14 +284,43 @@ struct VmInstance {
     // Null when the corresponding device has not been set up.
     pic_ptr: *mut devices::pic::PicPair,
     pit_ptr: *mut devices::pit::Pit,
+    cmos_ptr: *mut devices::cmos::Cmos,
+    /// RTC offset from the host clock in seconds (see [`corevm_set_rtc_offset`]).
+    rtc_offset: i64,
     ps2_ptr: *mut devices::ps2::Ps2Controller,
-    serial_ptr: *mut devices::serial::Serial,
+    /// COM1-COM4, indexed by port number (see [`devices::serial::COM_PORTS`]).
+    serial_ptrs: [*mut devices::serial::Serial; 4],
+    /// Per-port output callbacks set via [`corevm_serial_set_output_cb`].
+    serial_output_cbs: [Option<(SerialOutputCb, u64)>; 4],
     svga_ptr: *mut devices::svga::Svga,
-    e1000_ptr: *mut devices::e1000::E1000,
+    /// E1000 NICs, indexed by NIC number (see [`corevm_add_e1000`]).
+    e1000_ptrs: Vec<*mut devices::e1000::E1000>,
     bus_ptr: *mut devices::bus::PciBus,
     ide_ptr: *mut devices::ide::Ide,
     fw_cfg_ptr: *mut devices::fw_cfg::FwCfg,
     debug_port_ptr: *mut devices::debug_port::DebugPort,
+    debugcon_ptr: *mut devices::debug_console::DebugConsole,
+    /// Line callback set via [`corevm_debugcon_set_cb`].
+    debugcon_cb: Option<(DebugconCb, u64)>,
+    test_exit_ptr: *mut devices::test_exit::TestExit,
+    acpi_pm_ptr: *mut devices::acpi_pm::AcpiPm,
+    balloon_ptr: *mut devices::balloon::Balloon,
+    /// Secondary-channel IDE drive holding the shared host directory.
+    share_ide_ptr: *mut devices::ide::Ide,
+    /// Host directory behind `share_ide_ptr` (see [`corevm_share_directory`]).
+    share: Option<share::HostShare>,
+    /// Port/MMIO access tracer, shared with the dispatchers (null until first enabled).
+    iotrace_ptr: *mut iotrace::IoTrace,
+    /// Symbol map and watches, shared with the CPU (null until first used).
+    debug_ptr: *mut debug::Debugger,
+    /// I/O exit state, shared with the CPU and dispatchers (null until first enabled).
+    io_exit_ptr: *mut ioexit::IoExit,
+    /// NOR flash devices added with [`corevm_flash_attach`].
+    flash_ptrs: Vec<*mut devices::flash::NorFlash>,
+    /// Whether the built-in BIOS trap port has been registered.
+    bios_loaded: bool,
+    /// Execution rate and virtual clock for [`corevm_run_for_micros`].
+    pacer: pacing::Pacer,
 }
 
 impl Drop for VmInstance {
@@ -267,14 +330,30 @@ impl Drop for VmInstance {
         unsafe {
             if !self.pic_ptr.is_null() { let _ = Box::from_raw(self.pic_ptr); }
             if !self.pit_ptr.is_null() { let _ = Box::from_raw(self.pit_ptr); }
+            if !self.cmos_ptr.is_null() { let _ = Box::from_raw(self.cmos_ptr); }
             if !self.ps2_ptr.is_null() { let _ = Box::from_raw(self.ps2_ptr); }
-            if !self.serial_ptr.is_null() { let _ = Box::from_raw(self.serial_ptr); }
+            for &serial in &self.serial_ptrs {
+                if !serial.is_null() { let _ = Box::from_raw(serial); }
+            }
             if !self.svga_ptr.is_null() { let _ = Box::from_raw(self.svga_ptr); }
-            if !self.e1000_ptr.is_null() { let _ = Box::from_raw(self.e1000_ptr); }
             if !self.bus_ptr.is_null() { let _ = Box::from_raw(self.bus_ptr); }
             if !self.ide_ptr.is_null() { let _ = Box::from_raw(self.ide_ptr); }
             if !self.fw_cfg_ptr.is_null() { let _ = Box::from_raw(self.fw_cfg_ptr); }
             if !self.debug_port_ptr.is_null() { let _ = Box::from_raw(self.debug_port_ptr); }
+            if !self.debugcon_ptr.is_null() { let _ = Box::from_raw(self.debugcon_ptr); }
+            if !self.test_exit_ptr.is_null() { let _ = Box::from_raw(self.test_exit_ptr); }
+            if !self.acpi_pm_ptr.is_null() { let _ = Box::from_raw(self.acpi_pm_ptr); }
+            if !self.balloon_ptr.is_null() { let _ = Box::from_raw(self.balloon_ptr); }
+            if !self.share_ide_ptr.is_null() { let _ = Box::from_raw(self.share_ide_ptr); }
+            if !self.iotrace_ptr.is_null() { let _ = Box::from_raw(self.iotrace_ptr); }
+            if !self.debug_ptr.is_null() { let _ = Box::from_raw(self.debug_ptr); }
+            if !self.io_exit_ptr.is_null() { let _ = Box::from_raw(self.io_exit_ptr); }
+            for &flash in &self.flash_ptrs {
+                let _ = Box::from_raw(flash);
+            }
+            for &nic in &self.e1000_ptrs {
+                let _ = Box::from_raw(nic);
+            }
         }
     }
 }
@@ -308,14 +387,30 @@ pub extern "C" fn corevm_create(ram_size_mb: u32) -> u64 {
         last_error_rip: 0,
         pic_ptr: ptr::null_mut(),
         pit_ptr: ptr::null_mut(),
+        cmos_ptr: ptr::null_mut(),
+        rtc_offset: 0,
         ps2_ptr: ptr::null_mut(),
-        serial_ptr: ptr::null_mut(),
+        serial_ptrs: [ptr::null_mut(); 4],
+        serial_output_cbs: [None; 4],
         svga_ptr: ptr::null_mut(),
-        e1000_ptr: ptr::null_mut(),
+        e1000_ptrs: Vec::new(),
         bus_ptr: ptr::null_mut(),
         ide_ptr: ptr::null_mut(),
         fw_cfg_ptr: ptr::null_mut(),
         debug_port_ptr: ptr::null_mut(),
+        debugcon_ptr: ptr::null_mut(),
+        debugcon_cb: None,
+        test_exit_ptr: ptr::null_mut(),
+        acpi_pm_ptr: ptr::null_mut(),
+        balloon_ptr: ptr::null_mut(),
+        share_ide_ptr: ptr::null_mut(),
+        share: None,
+        iotrace_ptr: ptr::null_mut(),
+        debug_ptr: ptr::null_mut(),
+        io_exit_ptr: ptr::null_mut(),
+        flash_ptrs: Vec::new(),
+        bios_loaded: false,
+        pacer: pacing::Pacer::new(),
     });
     let h = Box::into_raw(instance) as u64;
     vm_log!("VM created (handle=0x{:X})", h);
@@ -324,6 +419,10 @@ pub extern "C" fn corevm_create(ram_size_mb: u32) -> u64 {
 
 /// Destroy a VM instance and free all associated resources.
 ///
+/// Flash devices backed by a host file are written back first if the guest
+/// modified them (see [`corevm_flash_attach`]), and so are cached writes to
+/// a disk image file (see [`corevm_ide_open_disk`]).
+///
 /// After this call the handle is invalid and must not be used again.
 #[no_mangle]
 pub extern "C" fn corevm_destroy(handle: u64) {
@@ -331,6 +430,12 @@ pub extern "C" fn corevm_destroy(handle: u64) {
         return;
     }
     vm_log!("destroying VM (handle=0x{:X})", handle);
+    if corevm_flash_sync(handle) < 0 {
+        vm_log!("destroy: could not persist flash contents");
+    }
+    if corevm_ide_flush(handle) < 0 {
+        vm_log!("destroy: could not write back the IDE disk image");
+    }
     unsafe {
         let _ = Box::from_raw(handle as *mut VmInstance);
     }
@@ -345,6 +450,20 @@ pub extern "C" fn corevm_reset(handle: u64) {
     vm_log!("resetting VM");
     let vm = unsafe { vm_from_handle(handle) };
     vm.engine.reset();
+    if !vm.acpi_pm_ptr.is_null() {
+        // Power management registers return to their power-on values so a
+        // guest-initiated reset comes back up in legacy (non-ACPI) mode.
+        unsafe { (*vm.acpi_pm_ptr).reset(); }
+    }
+    if !vm.balloon_ptr.is_null() {
+        unsafe { (*vm.balloon_ptr).reset(); }
+    }
+    for &flash in &vm.flash_ptrs {
+        unsafe { (*flash).reset(); }
+    }
+    if !vm.io_exit_ptr.is_null() {
+        unsafe { (*vm.io_exit_ptr).clear(); }
+    }
     vm.last_error = None;
     vm.last_error_rip = 0;
 }
@@ -500,6 +619,32 @@ pub extern "C" fn corevm_get_cpl(handle: u64) -> u8 {
     vm.engine.cpu.regs.cpl
 }
 
+/// Select the x87 transcendental accuracy mode (FSIN/FCOS/FSINCOS/FPTAN/F2XM1).
+///
+/// `mode`: 0 = fast, 1 = precise (default). Survives `corevm_reset`.
+#[no_mangle]
+pub extern "C" fn corevm_set_fpu_accuracy(handle: u64, mode: u32) {
+    let vm = unsafe { vm_from_handle(handle) };
+    vm.engine.cpu.fpu_accuracy = fpu_state::FpuAccuracy::from_u32(mode);
+}
+
+// ════════════════════════════════════════════════════════════════════════
+// CPU State — Model-Specific Registers
+// ════════════════════════════════════════════════════════════════════════
+
+/// Service MSRs that corevm does not implement (see [`msr`]) on the host.
+///
+/// `cb(userdata, index, is_write, value)` is called for RDMSR/WRMSR of such
+/// an MSR; it fills `*value` on a read and returns nonzero if it handled the
+/// access, or 0 to raise #GP(0) as without a handler. The callback runs
+/// inside [`corevm_run`] and must not call back into the VM. Pass a null
+/// `cb` to remove the handler. Survives `corevm_reset`.
+#[no_mangle]
+pub extern "C" fn corevm_set_msr_handler(handle: u64, cb: Option<msr::MsrCb>, userdata: u64) {
+    let vm = unsafe { vm_from_handle(handle) };
+    vm.engine.cpu.msr_handler = cb.map(|cb| (cb, userdata));
+}
+
 // ════════════════════════════════════════════════════════════════════════
 // Execution
 // ════════════════════════════════════════════════════════════════════════
@@ -512,10 +657,206 @@ pub extern "C" fn corevm_get_cpl(handle: u64) -> u8 {
 /// - 2 = instruction limit reached
 /// - 3 = breakpoint (INT 3)
 /// - 4 = stop requested via [`corevm_request_stop`]
+/// - 5 = guest shutdown (ACPI S5 written to PM1a_CNT)
+/// - 6 = guest reset (keyboard controller pulse or reset control register)
+/// - 7 = a watch changed value (see [`corevm_watch_get_hit`])
+/// - 8 = the guest wrote its status to the test-exit port (see
+///   [`corevm_test_exit_status`])
+/// - 9 = an unhandled device access waits for the host (see
+///   [`corevm_get_pending_io`])
 #[no_mangle]
 pub extern "C" fn corevm_run(handle: u64, max_instructions: u64) -> u32 {
     let vm = unsafe { vm_from_handle(handle) };
-    let exit = vm.engine.run(max_instructions);
+    let exit = run_slice(vm, max_instructions);
+    after_slice(vm, &exit);
+    exit_code(vm, exit)
+}
+
+/// Run the guest for up to `usecs` microseconds of virtual time.
+///
+/// Unlike [`corevm_run`] the caller does not pick an instruction count: the
+/// VM measures how fast this host executes guest code, runs batches sized
+/// to a fraction of a millisecond, and ticks the PIT (raising IRQ 0) from
+/// the resulting virtual clock, so the host must not call
+/// [`corevm_pit_tick`] as well. While the guest idles in HLT the virtual
+/// clock skips to the next timer interrupt and the calling thread sleeps
+/// for that time; a busy guest yields the CPU every few milliseconds.
+///
+/// Returns the virtual time that advanced, in microseconds. It falls short
+/// of `usecs` when the guest stopped early or could not keep up with the
+/// host clock; pace frames and guest-visible timers by this value. The
+/// exit reason ([`corevm_run`] codes, 2 = budget used up) is available from
+/// [`corevm_get_last_exit_reason`]. A halted guest with no timer running
+/// sleeps out the rest of the budget and reports 0 (halted).
+#[no_mangle]
+pub extern "C" fn corevm_run_for_micros(handle: u64, usecs: u64) -> u64 {
+    let vm = unsafe { vm_from_handle(handle) };
+    let start_us = vm.pacer.virtual_us;
+    let start_ms = libsyscall::uptime_ms();
+    // Leave the loop if the guest falls behind the host clock by more than
+    // the budget, so the frontend keeps its frame rate.
+    let wall_limit_ms = (usecs / 1000) as u32 + 1;
+    let mut last_yield_ms = start_ms;
+    let exit = loop {
+        let done = vm.pacer.virtual_us - start_us;
+        if done >= usecs {
+            break ExitReason::InstructionLimit;
+        }
+        let now = libsyscall::uptime_ms();
+        if now.wrapping_sub(start_ms) > wall_limit_ms {
+            break ExitReason::InstructionLimit;
+        }
+        if now.wrapping_sub(last_yield_ms) >= pacing::YIELD_MS {
+            libsyscall::yield_cpu();
+            last_yield_ms = libsyscall::uptime_ms();
+        }
+
+        let before = vm.engine.instruction_count();
+        let exit = run_slice(vm, vm.pacer.batch(usecs - done));
+        let insns = vm.engine.instruction_count() - before;
+        let elapsed = libsyscall::uptime_ms().wrapping_sub(now);
+        let us = vm.pacer.record(insns, elapsed);
+        advance_pit(vm, us);
+        after_slice(vm, &exit);
+
+        match exit {
+            ExitReason::InstructionLimit => {}
+            ExitReason::Halted => {
+                let rflags = vm.engine.cpu.regs.rflags;
+                if vm.engine.interrupts.pending_interrupt(rflags).is_some() {
+                    continue;
+                }
+                let remaining = usecs.saturating_sub(vm.pacer.virtual_us - start_us);
+                let woken = idle_until_timer(vm, remaining);
+                last_yield_ms = libsyscall::uptime_ms();
+                if !woken && !timer_running(vm) {
+                    // Only the host (input, disk, network) can wake it now.
+                    break ExitReason::Halted;
+                }
+            }
+            exit => break exit,
+        }
+    };
+    let code = exit_code(vm, exit);
+    vm.pacer.last_exit = code;
+    vm.pacer.virtual_us - start_us
+}
+
+/// Exit reason code of the last [`corevm_run_for_micros`] call.
+#[no_mangle]
+pub extern "C" fn corevm_get_last_exit_reason(handle: u64) -> u32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    vm.pacer.last_exit
+}
+
+/// Virtual time advanced by [`corevm_run_for_micros`] since the VM was
+/// created, in microseconds.
+#[no_mangle]
+pub extern "C" fn corevm_get_virtual_time_us(handle: u64) -> u64 {
+    let vm = unsafe { vm_from_handle(handle) };
+    vm.pacer.virtual_us
+}
+
+/// Measured execution rate in guest instructions per host millisecond
+/// (an initial estimate until [`corevm_run_for_micros`] has run a while).
+#[no_mangle]
+pub extern "C" fn corevm_get_exec_rate(handle: u64) -> u64 {
+    let vm = unsafe { vm_from_handle(handle) };
+    vm.pacer.rate()
+}
+
+/// Run the guest for up to `max_instructions` (0 = unlimited). BIOS traps
+/// are serviced here and execution resumes within the same slice, so
+/// callers never see them.
+fn run_slice(vm: &mut VmInstance, max_instructions: u64) -> ExitReason {
+    let start = vm.engine.instruction_count();
+    loop {
+        let budget = if max_instructions > 0 {
+            let used = vm.engine.instruction_count() - start;
+            if used >= max_instructions {
+                break ExitReason::InstructionLimit;
+            }
+            max_instructions - used
+        } else {
+            0
+        };
+        match vm.engine.run(budget) {
+            ExitReason::BiosTrap => {
+                if service_bios(vm) == bios::BiosAction::Wait {
+                    break ExitReason::Halted;
+                }
+            }
+            exit => break exit,
+        }
+    }
+}
+
+/// Deliver device output and interrupts that accumulated during a slice.
+fn after_slice(vm: &mut VmInstance, exit: &ExitReason) {
+    if let ExitReason::GuestExit(_) = exit {
+        // A failing test typically prints its panic message without a
+        // trailing newline right before exiting.
+        if !vm.debugcon_ptr.is_null() {
+            unsafe { (*vm.debugcon_ptr).flush(); }
+        }
+    }
+    service_serial_ports(vm);
+    service_debugcon(vm);
+}
+
+/// Whether PIT channel 0 is counting (and can wake a halted guest).
+fn timer_running(vm: &VmInstance) -> bool {
+    if vm.pit_ptr.is_null() {
+        return false;
+    }
+    let ch = unsafe { &(*vm.pit_ptr).channels[0] };
+    ch.enabled && ch.gate
+}
+
+/// Tick the PIT for `us` microseconds of virtual time, raising IRQ 0 for
+/// every channel 0 interrupt.
+fn advance_pit(vm: &mut VmInstance, us: u64) {
+    let ticks = vm.pacer.pit_ticks(us);
+    if vm.pit_ptr.is_null() {
+        return;
+    }
+    for _ in 0..ticks {
+        if unsafe { (*vm.pit_ptr).tick() } {
+            inject_irq(vm, 0);
+        }
+    }
+}
+
+/// Skip the virtual clock of a halted guest to the next timer interrupt,
+/// at most `max_us` ahead, and sleep the host for that time. Returns `true`
+/// if the interrupt was raised; otherwise all of `max_us` was spent idle.
+fn idle_until_timer(vm: &mut VmInstance, max_us: u64) -> bool {
+    let mut idle_us = max_us;
+    let mut fired = false;
+    if timer_running(vm) {
+        let max_ticks = max_us * pacing::PIT_HZ / 1_000_000;
+        let mut ticks = 0;
+        while ticks < max_ticks {
+            ticks += 1;
+            if unsafe { (*vm.pit_ptr).tick() } {
+                fired = true;
+                idle_us = pacing::ticks_to_us(ticks).min(max_us);
+                break;
+            }
+        }
+    }
+    let ms = vm.pacer.idle(idle_us);
+    if ms > 0 {
+        libsyscall::sleep(ms);
+    }
+    if fired {
+        inject_irq(vm, 0);
+    }
+    fired
+}
+
+/// Log a slice's exit and convert it to its C ABI code.
+fn exit_code(vm: &mut VmInstance, exit: ExitReason) -> u32 {
     match exit {
         ExitReason::Halted => {
             vm_log!("VM halted after {} instructions", vm.engine.instruction_count());
@@ -527,11 +868,12 @@ pub extern "C" fn corevm_run(handle: u64, max_instructions: u64) -> u32 {
             let orig_cs = vm.engine.cpu.last_exec_cs;
             let orig_opcode = vm.engine.cpu.last_opcode;
             let orig_phys = vm.engine.cpu.last_fetch_addr;
+            let cpu = &vm.engine.cpu;
             vm_log!("VM exception: {}", err);
-            vm_log!("  current RIP=0x{:X}, mode={:?}", rip, vm.engine.cpu.mode);
+            vm_log!("  current RIP=0x{:X}{}, mode={:?}", rip, cpu.annotate(rip), cpu.mode);
             vm_log!(
-                "  last instruction: CS=0x{:04X} IP=0x{:X} phys=0x{:X} opcode=0x{:04X}",
-                orig_cs, orig_rip, orig_phys, orig_opcode
+                "  last instruction: CS=0x{:04X} IP=0x{:X}{} phys=0x{:X} opcode=0x{:04X}",
+                orig_cs, orig_rip, cpu.annotate(orig_rip), orig_phys, orig_opcode
             );
             vm_log!(
                 "  instructions executed: {}",
@@ -543,16 +885,49 @@ pub extern "C" fn corevm_run(handle: u64, max_instructions: u64) -> u32 {
         }
         ExitReason::InstructionLimit => 2,
         ExitReason::Breakpoint => {
-            vm_log!("VM breakpoint at RIP=0x{:X}", vm.engine.cpu.regs.rip);
+            let rip = vm.engine.cpu.regs.rip;
+            vm_log!("VM breakpoint at RIP=0x{:X}{}", rip, vm.engine.cpu.annotate(rip));
             3
         }
         ExitReason::StopRequested => {
             vm_log!("VM stop requested");
             4
         }
+        ExitReason::Shutdown => {
+            vm_log!("VM guest shutdown after {} instructions", vm.engine.instruction_count());
+            5
+        }
+        ExitReason::Reset => {
+            vm_log!("VM guest reset after {} instructions", vm.engine.instruction_count());
+            6
+        }
+        ExitReason::Watchpoint => {
+            if let Some(hit) = debugger(vm).last_hit() {
+                vm_log!(
+                    "VM watch {} changed 0x{:X} -> 0x{:X} at RIP=0x{:X}{}",
+                    hit.id, hit.old, hit.new, hit.rip, vm.engine.cpu.annotate(hit.rip)
+                );
+            }
+            7
+        }
+        ExitReason::GuestExit(status) => {
+            vm_log!("VM guest test exit with status {} after {} instructions", status, vm.engine.instruction_count());
+            8
+        }
+        // Frequent in this mode and expected by the host; not logged.
+        ExitReason::IoRequest => 9,
+        // Serviced by the loop above.
+        ExitReason::BiosTrap => 2,
     }
 }
 
+/// Perform the built-in BIOS service the CPU just trapped on.
+fn service_bios(vm: &mut VmInstance) -> bios::BiosAction {
+    let ide = if vm.ide_ptr.is_null() { None } else { Some(unsafe { &mut *vm.ide_ptr }) };
+    let svga = if vm.svga_ptr.is_null() { None } else { Some(unsafe { &mut *vm.svga_ptr }) };
+    bios::service(&mut vm.engine, ide, svga)
+}
+
 /// Request the VM to stop at the next instruction boundary.
 ///
 /// Safe to call from any context; the flag is checked at the top of each
@@ -634,6 +1009,70 @@ impl core::fmt::Write for StackWriter {
     }
 }
 
+// ════════════════════════════════════════════════════════════════════════
+// Execution — I/O Exits
+// ════════════════════════════════════════════════════════════════════════
+
+/// Get the VM's I/O exit state, creating it (disabled) and attaching it to
+/// the CPU and the port and MMIO dispatchers on first use.
+fn io_exit(vm: &mut VmInstance) -> &mut ioexit::IoExit {
+    if vm.io_exit_ptr.is_null() {
+        let rip_src = &vm.engine.cpu.last_exec_rip as *const u64;
+        vm.io_exit_ptr = Box::into_raw(Box::new(ioexit::IoExit::new(rip_src)));
+        vm.engine.cpu.io_exit = vm.io_exit_ptr;
+        vm.engine.io.exit = vm.io_exit_ptr;
+        vm.engine.memory.set_io_exit(vm.io_exit_ptr);
+    }
+    unsafe { &mut *vm.io_exit_ptr }
+}
+
+/// Enable (`enable` != 0) or disable I/O exits.
+///
+/// While enabled, a port access with no registered handler, or a memory
+/// access outside guest RAM and all MMIO regions, stops the guest:
+/// [`corevm_run`] returns 9 and the access can be read with
+/// [`corevm_get_pending_io`]. The host performs it, calls
+/// [`corevm_complete_io`] and runs the VM again, which restarts the
+/// interrupted instruction with the completed value. Disabled, such
+/// accesses read all ones and writes are dropped.
+#[no_mangle]
+pub extern "C" fn corevm_set_io_exit(handle: u64, enable: u32) {
+    let vm = unsafe { vm_from_handle(handle) };
+    vm_log!("I/O exits {}", if enable != 0 { "enabled" } else { "disabled" });
+    io_exit(vm).enabled = enable != 0;
+}
+
+/// Copy the access behind the last I/O exit into `out` (a 32-byte
+/// [`ioexit::IoRequest`]: address or port, value for writes, RIP, kind as
+/// in [`iotrace`], size). Returns 0 on success, -1 if no access is pending.
+#[no_mangle]
+pub extern "C" fn corevm_get_pending_io(handle: u64, out: *mut ioexit::IoRequest) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if out.is_null() || vm.io_exit_ptr.is_null() {
+        return -1;
+    }
+    match unsafe { (*vm.io_exit_ptr).pending() } {
+        Some(req) => {
+            unsafe { *out = req };
+            0
+        }
+        None => -1,
+    }
+}
+
+/// Complete the pending access. For a read, `value` is what the guest
+/// reads (truncated to the access size); for a write it is ignored. The
+/// next [`corevm_run`] finishes the interrupted instruction before
+/// anything else, including interrupts. Running without completing makes
+/// the instruction exit again with the same access.
+///
+/// Returns 0 on success, -1 if no access is pending.
+#[no_mangle]
+pub extern "C" fn corevm_complete_io(handle: u64, value: u64) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if !vm.io_exit_ptr.is_null() && unsafe { (*vm.io_exit_ptr).complete(value) } { 0 } else { -1 }
+}
+
 // ════════════════════════════════════════════════════════════════════════
 // Memory
 // ════════════════════════════════════════════════════════════════════════
@@ -744,144 +1183,362 @@ pub extern "C" fn corevm_write_phys_u32(handle: u64, addr: u64, val: u32) {
     let _ = vm.engine.memory.write_u32(addr, val);
 }
 
+/// Load `data` at guest physical `addr` and make the range read-only.
+///
+/// Guest writes to the range are silently discarded, like writes to a ROM
+/// chip. Returns 0 on success, -1 on null/empty data or if the range is
+/// outside guest RAM.
+#[no_mangle]
+pub extern "C" fn corevm_load_rom(handle: u64, addr: u64, data: *const u8, len: u32) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if data.is_null() || len == 0 || addr + len as u64 > vm.engine.memory.ram().size() as u64 {
+        vm_log!("load_rom: invalid range 0x{:X}+0x{:X}", addr, len);
+        return -1;
+    }
+    vm_log!("loading {}-byte ROM at physical 0x{:X}", len, addr);
+    let slice = unsafe { core::slice::from_raw_parts(data, len as usize) };
+    vm.engine.load_binary(addr as usize, slice);
+    vm.engine.memory.set_read_only(addr, len as u64, true);
+    0
+}
+
+/// Mark guest RAM in `[base, base+size)` read-only (`read_only` != 0) or
+/// writable again. Guest writes and the `corevm_write_phys_*` functions
+/// leave read-only RAM unchanged; [`corevm_load_binary`] still fills it.
+#[no_mangle]
+pub extern "C" fn corevm_set_read_only(handle: u64, base: u64, size: u64, read_only: u32) {
+    let vm = unsafe { vm_from_handle(handle) };
+    vm.engine.memory.set_read_only(base, size, read_only != 0);
+}
+
+// ════════════════════════════════════════════════════════════════════════
+// Firmware
+// ════════════════════════════════════════════════════════════════════════
+
+/// Load the built-in BIOS and point the CPU at its reset vector.
+///
+/// An alternative to loading a SeaBIOS image with [`corevm_load_binary`]:
+/// the ROM is placed at 0xF0000 and its services (INT 10h video, INT 13h
+/// disk on the IDE image, INT 16h keyboard, INT 15h E820 memory map, ...)
+/// are performed inside [`corevm_run`]. POST sets up the IVT and BDA and
+/// boots the MBR of the attached disk. Call after
+/// [`corevm_setup_standard_devices`] and [`corevm_setup_ide`]; the disk
+/// image may be attached later, as POST only runs on the first
+/// [`corevm_run`].
+///
+/// Returns 0 on success, -1 if guest RAM is smaller than 1 MiB.
+#[no_mangle]
+pub extern "C" fn corevm_load_default_bios(handle: u64) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if vm.engine.memory.ram().size() < 0x10_0000 {
+        vm_log!("load_default_bios: guest RAM below 1 MiB");
+        return -1;
+    }
+    vm_log!("loading built-in BIOS at 0x{:X}", bios::ROM_BASE);
+    let rom = bios::rom_image();
+    vm.engine.load_binary(bios::ROM_BASE as usize, &rom);
+    if !vm.bios_loaded {
+        vm.engine.io.register(bios::TRAP_PORT, 1, Box::new(bios::TrapPort));
+        vm.bios_loaded = true;
+    }
+    let regs = &mut vm.engine.cpu.regs;
+    regs.load_segment_real(SegReg::Cs, 0xF000);
+    regs.rip = 0xFFF0;
+    0
+}
+
 // ════════════════════════════════════════════════════════════════════════
 // Devices — Setup
 // ════════════════════════════════════════════════════════════════════════
 
+impl VmInstance {
+    /// True once any device has been registered.
+    fn has_devices(&self) -> bool {
+        !self.pic_ptr.is_null() || !self.pit_ptr.is_null() || !self.ps2_ptr.is_null()
+            || self.serial_ptrs.iter().any(|p| !p.is_null())
+            || !self.svga_ptr.is_null() || !self.e1000_ptrs.is_empty() || !self.bus_ptr.is_null()
+            || !self.ide_ptr.is_null() || !self.fw_cfg_ptr.is_null()
+            || !self.debug_port_ptr.is_null() || !self.acpi_pm_ptr.is_null()
+            || !self.debugcon_ptr.is_null() || !self.test_exit_ptr.is_null()
+            || !self.balloon_ptr.is_null() || !self.share_ide_ptr.is_null()
+            || self.engine.memory.mmio_region_count() > 0
+    }
+
+    /// PIC — dual 8259A at standard ports.
+    fn add_pic(&mut self) {
+        let pic = Box::into_raw(Box::new(devices::pic::PicPair::new()));
+        self.pic_ptr = pic;
+        self.engine.io.register(0x20, 2, Box::new(IoProxy { ptr: pic }));
+        self.engine.io.register(0xA0, 2, Box::new(IoProxy { ptr: pic }));
+    }
+
+    /// PIT — Intel 8254 at standard ports.
+    fn add_pit(&mut self) {
+        let pit = Box::into_raw(Box::new(devices::pit::Pit::new()));
+        self.pit_ptr = pit;
+        self.engine.io.register(0x40, 4, Box::new(IoProxy { ptr: pit }));
+    }
+
+    /// CMOS — RTC and NVRAM. Pass actual guest RAM size.
+    fn add_cmos(&mut self) {
+        let ram_bytes = self.engine.memory.ram().size();
+        let cmos = Box::into_raw(Box::new(devices::cmos::Cmos::new(ram_bytes)));
+        unsafe { (*cmos).set_offset(self.rtc_offset) };
+        self.cmos_ptr = cmos;
+        self.engine.io.register(0x70, 2, Box::new(IoProxy { ptr: cmos }));
+    }
+
+    /// PS/2 — keyboard and mouse controller, plus System Control Port A.
+    /// Both drive the A20 gate in the MMU.
+    fn add_ps2(&mut self) {
+        let a20: *mut bool = &mut self.engine.mmu.a20_enabled;
+        let ps2 = Box::into_raw(Box::new(devices::ps2::Ps2Controller::new()));
+        unsafe { (*ps2).set_a20_gate(a20) };
+        self.ps2_ptr = ps2;
+        self.engine.io.register(0x60, 1, Box::new(IoProxy { ptr: ps2 }));
+        self.engine.io.register(0x64, 1, Box::new(IoProxy { ptr: ps2 }));
+        self.engine.io.register(
+            devices::sys_ctrl::PORT, 1, Box::new(devices::sys_ctrl::SystemControlA::new(a20)),
+        );
+    }
+
+    /// Serial — 16550 UART in COM slot `index` (0-3).
+    fn add_serial(&mut self, index: usize, base: u16, irq: u8) {
+        let serial = Box::into_raw(Box::new(devices::serial::Serial::new(base, irq)));
+        self.serial_ptrs[index] = serial;
+        self.engine.io.register(base, 8, Box::new(IoProxy { ptr: serial }));
+    }
+
+    /// VGA/SVGA — standard VGA ports + legacy framebuffer MMIO + Bochs VBE.
+    fn add_svga(&mut self, width: u32, height: u32) {
+        let svga = Box::into_raw(Box::new(devices::svga::Svga::new(width, height)));
+        self.svga_ptr = svga;
+        self.engine.io.register(0x3C0, 0x1B, Box::new(IoProxy { ptr: svga }));
+        // Bochs VBE ports (0x1CE index, 0x1CF data) — used by VGA BIOS to detect hardware.
+        self.engine.io.register(0x1CE, 2, Box::new(IoProxy { ptr: svga }));
+        self.engine.memory.add_mmio(0xA0000, 0x20000, Box::new(MmioProxy { ptr: svga }));
+    }
+
+    /// PCI bus with standard QEMU i440FX machine devices.
+    fn add_pci_platform(&mut self) {
+        let mut bus = devices::bus::PciBus::new();
+
+        // i440FX host bridge at 0:0.0 — required by SeaBIOS for PAM/RAM unlock.
+        let mut host_bridge = devices::bus::PciDevice::new(
+            0x8086,  // Vendor ID: Intel
+            0x1237,  // Device ID: i440FX
+            0x06,    // Class: Bridge
+            0x00,    // Subclass: Host bridge
+            0x00,    // Prog IF
+        );
+        host_bridge.bus = 0;
+        host_bridge.device = 0;
+        host_bridge.function = 0;
+        // Header type 0x00 = single-function device.
+        // PAM registers (0x59-0x5F): default to 0 (ROM read-only).
+        // Make all PAM regions writable so SeaBIOS can shadow ROMs.
+        // PAM0 (0x59): covers 0xF0000-0xFFFFF — BIOS area.
+        host_bridge.config_space[0x59] = 0x30; // Read/write enabled
+        // PAM1-PAM6 (0x5A-0x5F): cover 0xC0000-0xEFFFF — option ROM area.
+        for i in 0x5A..=0x5F {
+            host_bridge.config_space[i] = 0x33; // Read/write for both halves
+        }
+        bus.add_device(host_bridge);
+
+        // PIIX3 ISA bridge at 0:1.0 — SeaBIOS uses this for IRQ routing.
+        let mut isa_bridge = devices::bus::PciDevice::new(
+            0x8086,  // Vendor ID: Intel
+            0x7000,  // Device ID: PIIX3 ISA
+            0x06,    // Class: Bridge
+            0x01,    // Subclass: ISA bridge
+            0x00,    // Prog IF
+        );
+        isa_bridge.bus = 0;
+        isa_bridge.device = 1;
+        isa_bridge.function = 0;
+        // Mark as multi-function (header type bit 7) since real PIIX3 has IDE at fn 1.
+        isa_bridge.config_space[0x0E] = 0x80;
+        bus.add_device(isa_bridge);
+
+        // VGA device at 0:2.0 — SeaBIOS scans PCI to detect display hardware.
+        if !self.svga_ptr.is_null() {
+            let mut vga_pci = devices::bus::PciDevice::new(
+                0x1234,  // Vendor ID: QEMU standard VGA
+                0x1111,  // Device ID: stdvga
+                0x03,    // Class: Display controller
+                0x00,    // Subclass: VGA compatible
+                0x00,    // Prog IF: VGA
+            );
+            vga_pci.bus = 0;
+            vga_pci.device = 2;
+            vga_pci.function = 0;
+            // BAR0: framebuffer at 0xFD000000 (256 MiB, matches typical VGA).
+            vga_pci.set_bar(0, 0xFD000000, 0x01000000, true); // 16 MiB MMIO
+            // BAR2: Bochs VBE MMIO (optional, not strictly needed).
+            vga_pci.set_bar(2, 0xFEBE0000, 0x1000, true); // 4 KiB
+            // Expansion ROM base address (offset 0x30): 0xC0000 with enable bit.
+            vga_pci.config_space[0x30] = 0x01; // enabled
+            vga_pci.config_space[0x31] = 0x00;
+            vga_pci.config_space[0x32] = 0x0C; // 0x000C0001 LE
+            vga_pci.config_space[0x33] = 0x00;
+            bus.add_device(vga_pci);
+        }
+
+        let bus_ptr = Box::into_raw(Box::new(bus));
+        self.bus_ptr = bus_ptr;
+        self.engine.io.register(0xCF8, 8, Box::new(IoProxy { ptr: bus_ptr }));
+    }
+
+    /// IO-APIC at MMIO `base` (standard: 0xFEC00000).
+    fn add_ioapic(&mut self, base: u64) {
+        let ioapic = Box::into_raw(Box::new(devices::ioapic::IoApic::new()));
+        self.engine.memory.add_mmio(base, 0x1000, Box::new(MmioProxy { ptr: ioapic }));
+    }
+
+    /// fw_cfg — QEMU firmware configuration interface.
+    /// SeaBIOS uses this to discover platform config and VGA BIOS files.
+    fn add_fw_cfg(&mut self, port: u16) {
+        let ram_bytes = self.engine.memory.ram().size();
+        let fw_cfg = Box::into_raw(Box::new(
+            devices::fw_cfg::FwCfg::new(ram_bytes as u64),
+        ));
+        self.fw_cfg_ptr = fw_cfg;
+        self.engine.io.register(port, 2, Box::new(IoProxy { ptr: fw_cfg }));
+    }
+
+    /// Debug port — QEMU debug console (standard: port 0x402).
+    /// SeaBIOS writes debug output here; reading 0xE9 signals port is active.
+    fn add_debug_port(&mut self, port: u16) {
+        let debug_port = Box::into_raw(Box::new(devices::debug_port::DebugPort::new()));
+        self.debug_port_ptr = debug_port;
+        self.engine.io.register(port, 1, Box::new(IoProxy { ptr: debug_port }));
+    }
+
+    /// Bochs debug console (standard: port 0xE9) — timestamped guest log lines.
+    fn add_debugcon(&mut self, port: u16) {
+        let debugcon = Box::into_raw(Box::new(devices::debug_console::DebugConsole::new()));
+        self.debugcon_ptr = debugcon;
+        self.engine.io.register(port, 1, Box::new(IoProxy { ptr: debugcon }));
+    }
+
+    /// Test-exit port (conventionally 0xF4) — lets a guest test suite end
+    /// [`corevm_run`] with its result.
+    fn add_test_exit(&mut self, port: u16) {
+        let test_exit = Box::into_raw(Box::new(devices::test_exit::TestExit::new()));
+        self.test_exit_ptr = test_exit;
+        self.engine.io.register(port, devices::test_exit::PORT_COUNT, Box::new(IoProxy { ptr: test_exit }));
+    }
+
+    /// ACPI PM — PM1a event/control blocks for guest power-off. 0x600 is the PIIX4
+    /// PMBA used by QEMU; 0xB000 is the legacy base some guests hard-code.
+    /// `alias` = 0 registers no legacy alias.
+    fn add_acpi_pm(&mut self, port: u16, alias: u16) {
+        let acpi_pm = Box::into_raw(Box::new(devices::acpi_pm::AcpiPm::new()));
+        self.acpi_pm_ptr = acpi_pm;
+        self.engine.io.register(port, 16, Box::new(IoProxy { ptr: acpi_pm }));
+        if alias != 0 {
+            self.engine.io.register(alias, 16, Box::new(IoProxy { ptr: acpi_pm }));
+        }
+    }
+
+    /// Intel E1000 NIC with a 128 KB register space at `mmio_base`.
+    /// Returns its NIC index, or `None` if [`devices::e1000::MAX_NICS`]
+    /// are already present.
+    fn add_e1000(&mut self, mmio_base: u64, mac: [u8; 6]) -> Option<usize> {
+        if self.e1000_ptrs.len() >= devices::e1000::MAX_NICS {
+            return None;
+        }
+        let e1000 = Box::into_raw(Box::new(devices::e1000::E1000::new(mac)));
+        self.e1000_ptrs.push(e1000);
+        self.engine.memory.add_mmio(
+            mmio_base,
+            0x20000, // 128 KB register space
+            Box::new(MmioProxy { ptr: e1000 }),
+        );
+        Some(self.e1000_ptrs.len() - 1)
+    }
+
+    /// NIC `nic`, if it exists.
+    fn e1000(&mut self, nic: u32) -> Option<&mut devices::e1000::E1000> {
+        self.e1000_ptrs.get(nic as usize).map(|&p| unsafe { &mut *p })
+    }
+
+    /// ATA/IDE controller with its command block at `port` and control block
+    /// at `ctrl_port` (standard: 0x1F0 / 0x3F6).
+    fn add_ide(&mut self, port: u16, ctrl_port: u16) {
+        let ide = Box::into_raw(Box::new(devices::ide::Ide::new()));
+        self.ide_ptr = ide;
+        self.engine.io.register(port, 8, Box::new(IoRemap { ptr: ide, base: port, native: 0x1F0 }));
+        self.engine.io.register(ctrl_port, 2, Box::new(IoRemap { ptr: ide, base: ctrl_port, native: 0x3F6 }));
+    }
+
+    /// Memory balloon at `port` (standard: 0x5A0). Must be added after guest
+    /// RAM has its final size: the device keeps a pointer to it.
+    fn add_balloon(&mut self, port: u16) {
+        let ram: *mut memory::FlatMemory = self.engine.memory.ram_mut();
+        let balloon = Box::into_raw(Box::new(devices::balloon::Balloon::new(ram)));
+        self.balloon_ptr = balloon;
+        self.engine.io.register(port, devices::balloon::PORT_COUNT, Box::new(IoProxy { ptr: balloon }));
+    }
+
+    /// Build the machine described by `desc`, in dependency order.
+    fn apply_machine(&mut self, desc: &machine::MachineDesc) {
+        if let Some(mb) = desc.ram_mb {
+            let bytes = (mb as usize) * 1024 * 1024;
+            if bytes != self.engine.memory.ram().size() {
+                vm_log!("configure: resizing guest RAM to {} MiB", mb);
+                self.engine.memory = GuestMemory::new(bytes);
+                self.engine.memory.set_mmio_trace(self.iotrace_ptr);
+                self.engine.memory.set_io_exit(self.io_exit_ptr);
+            }
+        }
+        if desc.pic { self.add_pic(); }
+        if desc.pit { self.add_pit(); }
+        if desc.cmos { self.add_cmos(); }
+        if desc.ps2 { self.add_ps2(); }
+        for (i, s) in desc.serial.iter().enumerate() {
+            self.add_serial(i, s.port, s.irq);
+        }
+        if let Some(v) = desc.vga { self.add_svga(v.width, v.height); }
+        if desc.pci { self.add_pci_platform(); }
+        if let Some(base) = desc.ioapic { self.add_ioapic(base); }
+        if let Some(port) = desc.fw_cfg { self.add_fw_cfg(port); }
+        if let Some(port) = desc.debug_port { self.add_debug_port(port); }
+        if let Some(port) = desc.debugcon { self.add_debugcon(port); }
+        if let Some(port) = desc.test_exit { self.add_test_exit(port); }
+        if let Some(a) = desc.acpi_pm { self.add_acpi_pm(a.port, a.alias); }
+        for e in &desc.e1000 { self.add_e1000(e.mmio, e.mac); }
+        if let Some(ide) = desc.ide {
+            self.add_ide(ide.port, ide.ctrl_port);
+            if let Some((addr, len)) = ide.disk {
+                let slice = unsafe { core::slice::from_raw_parts(addr as *const u8, len as usize) };
+                vm_log!("configure: attaching IDE disk image ({} bytes)", len);
+                unsafe { (*self.ide_ptr).attach_disk(slice.to_vec()) };
+            }
+        }
+        if let Some(port) = desc.balloon { self.add_balloon(port); }
+    }
+}
+
 /// Register standard PC devices: PIC, PIT, CMOS, PS/2, Serial, VGA (800x600).
 ///
 /// This sets up the following I/O and MMIO regions:
 /// - PIC: ports 0x20-0x21 (master), 0xA0-0xA1 (slave)
 /// - PIT: ports 0x40-0x43
 /// - CMOS: ports 0x70-0x71
-/// - PS/2: ports 0x60, 0x64
-/// - Serial (COM1): ports 0x3F8-0x3FF
+/// - PS/2: ports 0x60, 0x64, System Control Port A (fast A20): port 0x92
+/// - Serial (COM1-COM4): ports 0x3F8, 0x2F8, 0x3E8, 0x2E8 (8 each), IRQ 4/3/4/3
 /// - VGA: ports 0x3C0-0x3DA, MMIO at 0xA0000 (128 KB)
+/// - ACPI PM: ports 0x600-0x60F (alias 0xB000-0xB00F)
+/// - Debug console: port 0xE9
 ///
-/// Must only be called once per VM instance.
+/// Must only be called once per VM instance. Equivalent to
+/// [`corevm_configure`] with a single STANDARD node.
 #[no_mangle]
 pub extern "C" fn corevm_setup_standard_devices(handle: u64) {
     vm_log!("setting up standard devices (PIC, PIT, CMOS, PS/2, serial, VGA)");
     let vm = unsafe { vm_from_handle(handle) };
-
-    // PIC — dual 8259A at standard ports.
-    let pic = Box::into_raw(Box::new(devices::pic::PicPair::new()));
-    vm.pic_ptr = pic;
-    vm.engine.io.register(0x20, 2, Box::new(IoProxy { ptr: pic }));
-    vm.engine.io.register(0xA0, 2, Box::new(IoProxy { ptr: pic }));
-
-    // PIT — Intel 8254 at standard ports.
-    let pit = Box::into_raw(Box::new(devices::pit::Pit::new()));
-    vm.pit_ptr = pit;
-    vm.engine.io.register(0x40, 4, Box::new(IoProxy { ptr: pit }));
-
-    // CMOS — RTC and NVRAM. Pass actual guest RAM size.
-    let ram_bytes = vm.engine.memory.ram().size();
-    let cmos = Box::new(devices::cmos::Cmos::new(ram_bytes));
-    vm.engine.io.register(0x70, 2, cmos);
-
-    // PS/2 — keyboard and mouse controller.
-    let ps2 = Box::into_raw(Box::new(devices::ps2::Ps2Controller::new()));
-    vm.ps2_ptr = ps2;
-    vm.engine.io.register(0x60, 1, Box::new(IoProxy { ptr: ps2 }));
-    vm.engine.io.register(0x64, 1, Box::new(IoProxy { ptr: ps2 }));
-
-    // Serial (COM1) — 16550 UART.
-    let serial = Box::into_raw(Box::new(devices::serial::Serial::new()));
-    vm.serial_ptr = serial;
-    vm.engine.io.register(0x3F8, 8, Box::new(IoProxy { ptr: serial }));
-
-    // VGA/SVGA — standard VGA ports + legacy framebuffer MMIO + Bochs VBE.
-    let svga = Box::into_raw(Box::new(devices::svga::Svga::new(800, 600)));
-    vm.svga_ptr = svga;
-    vm.engine.io.register(0x3C0, 0x1B, Box::new(IoProxy { ptr: svga }));
-    // Bochs VBE ports (0x1CE index, 0x1CF data) — used by VGA BIOS to detect hardware.
-    vm.engine.io.register(0x1CE, 2, Box::new(IoProxy { ptr: svga }));
-    vm.engine.memory.add_mmio(0xA0000, 0x20000, Box::new(MmioProxy { ptr: svga }));
-
-    // PCI bus with standard QEMU i440FX machine devices.
-    let mut bus = devices::bus::PciBus::new();
-
-    // i440FX host bridge at 0:0.0 — required by SeaBIOS for PAM/RAM unlock.
-    let mut host_bridge = devices::bus::PciDevice::new(
-        0x8086,  // Vendor ID: Intel
-        0x1237,  // Device ID: i440FX
-        0x06,    // Class: Bridge
-        0x00,    // Subclass: Host bridge
-        0x00,    // Prog IF
-    );
-    host_bridge.bus = 0;
-    host_bridge.device = 0;
-    host_bridge.function = 0;
-    // Header type 0x00 = single-function device.
-    // PAM registers (0x59-0x5F): default to 0 (ROM read-only).
-    // Make all PAM regions writable so SeaBIOS can shadow ROMs.
-    // PAM0 (0x59): covers 0xF0000-0xFFFFF — BIOS area.
-    host_bridge.config_space[0x59] = 0x30; // Read/write enabled
-    // PAM1-PAM6 (0x5A-0x5F): cover 0xC0000-0xEFFFF — option ROM area.
-    for i in 0x5A..=0x5F {
-        host_bridge.config_space[i] = 0x33; // Read/write for both halves
-    }
-    bus.add_device(host_bridge);
-
-    // PIIX3 ISA bridge at 0:1.0 — SeaBIOS uses this for IRQ routing.
-    let mut isa_bridge = devices::bus::PciDevice::new(
-        0x8086,  // Vendor ID: Intel
-        0x7000,  // Device ID: PIIX3 ISA
-        0x06,    // Class: Bridge
-        0x01,    // Subclass: ISA bridge
-        0x00,    // Prog IF
-    );
-    isa_bridge.bus = 0;
-    isa_bridge.device = 1;
-    isa_bridge.function = 0;
-    // Mark as multi-function (header type bit 7) since real PIIX3 has IDE at fn 1.
-    isa_bridge.config_space[0x0E] = 0x80;
-    bus.add_device(isa_bridge);
-
-    // VGA device at 0:2.0 — SeaBIOS scans PCI to detect display hardware.
-    let mut vga_pci = devices::bus::PciDevice::new(
-        0x1234,  // Vendor ID: QEMU standard VGA
-        0x1111,  // Device ID: stdvga
-        0x03,    // Class: Display controller
-        0x00,    // Subclass: VGA compatible
-        0x00,    // Prog IF: VGA
-    );
-    vga_pci.bus = 0;
-    vga_pci.device = 2;
-    vga_pci.function = 0;
-    // BAR0: framebuffer at 0xFD000000 (256 MiB, matches typical VGA).
-    vga_pci.set_bar(0, 0xFD000000, 0x01000000, true); // 16 MiB MMIO
-    // BAR2: Bochs VBE MMIO (optional, not strictly needed).
-    vga_pci.set_bar(2, 0xFEBE0000, 0x1000, true); // 4 KiB
-    // Expansion ROM base address (offset 0x30): 0xC0000 with enable bit.
-    vga_pci.config_space[0x30] = 0x01; // enabled
-    vga_pci.config_space[0x31] = 0x00;
-    vga_pci.config_space[0x32] = 0x0C; // 0x000C0001 LE
-    vga_pci.config_space[0x33] = 0x00;
-    bus.add_device(vga_pci);
-
-    let bus_ptr = Box::into_raw(Box::new(bus));
-    vm.bus_ptr = bus_ptr;
-    vm.engine.io.register(0xCF8, 8, Box::new(IoProxy { ptr: bus_ptr }));
-
-    // IO-APIC at standard MMIO address.
-    let ioapic = Box::into_raw(Box::new(devices::ioapic::IoApic::new()));
-    vm.engine.memory.add_mmio(0xFEC00000, 0x1000, Box::new(MmioProxy { ptr: ioapic }));
-
-    // fw_cfg — QEMU firmware configuration interface.
-    // SeaBIOS uses this to discover platform config and VGA BIOS files.
-    let fw_cfg = Box::into_raw(Box::new(
-        devices::fw_cfg::FwCfg::new(ram_bytes as u64),
-    ));
-    vm.fw_cfg_ptr = fw_cfg;
-    vm.engine.io.register(0x510, 2, Box::new(IoProxy { ptr: fw_cfg }));
-
-    // Debug port — QEMU debug console at port 0x402.
-    // SeaBIOS writes debug output here; reading 0xE9 signals port is active.
-    let debug_port = Box::into_raw(Box::new(devices::debug_port::DebugPort::new()));
-    vm.debug_port_ptr = debug_port;
-    vm.engine.io.register(0x402, 1, Box::new(IoProxy { ptr: debug_port }));
+    vm.apply_machine(&machine::MachineDesc::standard());
 
     let count = vm.engine.memory.mmio_region_count();
     let (lo, hi) = vm.engine.memory.mmio_bounds();
@@ -889,6 +1546,44 @@ pub extern "C" fn corevm_setup_standard_devices(handle: u64) {
     vm_log!("PCI bus: 3 devices (host bridge 0:0.0, ISA bridge 0:1.0, VGA 0:2.0)");
 }
 
+/// Configure the machine from a description blob (see [`machine`] for the
+/// format): RAM size, which devices exist, their port/MMIO bases, MAC
+/// address and disk attachment. The built-in BIOS is loaded last if requested.
+///
+/// Must be called on a freshly created VM, instead of the `corevm_setup_*`
+/// functions. A RAM node resizes guest memory from the size given to
+/// [`corevm_create`].
+///
+/// Returns 0 on success, or a negative [`machine::ConfigError`] code:
+/// -1 malformed, -2 unknown tag, -3 invalid value, -4 duplicate device,
+/// -5 VM already has devices, -6 BIOS load failed. Nothing is set up on error
+/// (except for -6).
+#[no_mangle]
+pub extern "C" fn corevm_configure(handle: u64, desc: *const u8, len: u32) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if desc.is_null() {
+        return -machine::ConfigError::Malformed.code();
+    }
+    if vm.has_devices() {
+        vm_log!("configure: VM already has devices");
+        return -machine::ConfigError::AlreadyConfigured.code();
+    }
+    let blob = unsafe { core::slice::from_raw_parts(desc, len as usize) };
+    let desc = match machine::MachineDesc::parse(blob) {
+        Ok(d) => d,
+        Err(e) => {
+            vm_log!("configure: rejected machine description ({:?})", e);
+            return -e.code();
+        }
+    };
+    vm_log!("configuring machine from {}-byte description", len);
+    vm.apply_machine(&desc);
+    if desc.bios && corevm_load_default_bios(handle) != 0 {
+        return -6;
+    }
+    0
+}
+
 /// Register a PCI bus at the standard configuration ports (0xCF8-0xCFF).
 ///
 /// Must only be called once per VM instance.
@@ -911,33 +1606,50 @@ pub extern "C" fn corevm_setup_pci_bus(handle: u64) {
 /// `mac` must point to exactly 6 bytes (the MAC address). If `mac` is null,
 /// the default MAC 52:54:00:12:34:56 is used.
 ///
-/// The E1000 uses MMIO (128 KB region), not port I/O.
+/// The E1000 uses MMIO (128 KB region), not port I/O. Equivalent to
+/// [`corevm_add_e1000`] with the NIC index discarded.
 #[no_mangle]
 pub extern "C" fn corevm_setup_e1000(handle: u64, mmio_base: u64, mac: *const u8) {
+    corevm_add_e1000(handle, mmio_base, mac);
+}
+
+/// Add an Intel E1000 network card at `mmio_base` and return its NIC index
+/// (0 for the first, used by the `corevm_e1000_nic_*` functions), or -1
+/// if the VM already has 4 NICs.
+///
+/// `mac` must point to exactly 6 bytes. If `mac` is null, the default MAC
+/// 52:54:00:12:34:56 is used with the NIC index added to the last byte.
+#[no_mangle]
+pub extern "C" fn corevm_add_e1000(handle: u64, mmio_base: u64, mac: *const u8) -> i32 {
     vm_log!("setting up E1000 NIC at MMIO 0x{:X}", mmio_base);
     let vm = unsafe { vm_from_handle(handle) };
 
     let mac_bytes = if mac.is_null() {
-        [0x52, 0x54, 0x00, 0x12, 0x34, 0x56]
+        let mut m = devices::e1000::DEFAULT_MAC;
+        m[5] = m[5].wrapping_add(vm.e1000_ptrs.len() as u8);
+        m
     } else {
         let slice = unsafe { core::slice::from_raw_parts(mac, 6) };
         [slice[0], slice[1], slice[2], slice[3], slice[4], slice[5]]
     };
 
-    let e1000 = Box::into_raw(Box::new(devices::e1000::E1000::new(mac_bytes)));
-    vm.e1000_ptr = e1000;
-    vm.engine.memory.add_mmio(
-        mmio_base,
-        0x20000, // 128 KB register space
-        Box::new(MmioProxy { ptr: e1000 }),
-    );
+    match vm.add_e1000(mmio_base, mac_bytes) {
+        Some(nic) => nic as i32,
+        None => {
+            vm_log!("E1000: NIC limit reached");
+            -1
+        }
+    }
 }
 
 // ════════════════════════════════════════════════════════════════════════
 // Device Interaction — PS/2
 // ════════════════════════════════════════════════════════════════════════
 
-/// Inject a keyboard key-press (make) scancode into the PS/2 controller.
+/// Inject a keyboard key press into the PS/2 controller.
+///
+/// `scancode` is a set 1 make code; pass `0xE0` first for extended keys.
+/// The guest receives it in its selected scancode set.
 ///
 /// No-op if standard devices have not been set up.
 #[no_mangle]
@@ -948,7 +1660,9 @@ pub extern "C" fn corevm_ps2_key_press(handle: u64, scancode: u8) {
     }
 }
 
-/// Inject a keyboard key-release (break) scancode into the PS/2 controller.
+/// Inject a keyboard key release into the PS/2 controller.
+///
+/// `scancode` is the set 1 make code of the key (see `corevm_ps2_key_press`).
 ///
 /// No-op if standard devices have not been set up.
 #[no_mangle]
@@ -959,6 +1673,24 @@ pub extern "C" fn corevm_ps2_key_release(handle: u64, scancode: u8) {
     }
 }
 
+/// Inject an anyOS key code press (`pressed` != 0) or release.
+///
+/// Accepts the compositor's `KEY_*` codes (Enter, arrows, F1-F12, ...),
+/// set 1 make codes below 0x80 for ordinary keys, and `0xE000 | code` for
+/// any other extended key. The matching make/break sequence, including the
+/// `E0` prefix, is generated for the guest's scancode set.
+///
+/// Returns 0 on success, -1 if the key code is unknown or there is no
+/// PS/2 controller.
+#[no_mangle]
+pub extern "C" fn corevm_inject_key(handle: u64, keycode: u32, pressed: u32) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if vm.ps2_ptr.is_null() {
+        return -1;
+    }
+    if unsafe { (*vm.ps2_ptr).inject_key(keycode, pressed != 0) } { 0 } else { -1 }
+}
+
 /// Inject a mouse movement/button event into the PS/2 controller.
 ///
 /// `dx` and `dy` are relative displacement; `buttons` is a bitmask
@@ -1093,24 +1825,92 @@ pub extern "C" fn corevm_mmio_diag(
 // Device Interaction — Serial
 // ════════════════════════════════════════════════════════════════════════
 
-/// Push input data into the serial port's receive buffer.
+/// Host callback receiving serial output: `(userdata, port, data, len)`.
+///
+/// `port` is the COM index (0 = COM1 … 3 = COM4). `data` is only valid for
+/// the duration of the call.
+pub type SerialOutputCb = extern "C" fn(u64, u32, *const u8, u32);
+
+/// Look up a serial port by COM index. Returns `None` if out of range or
+/// serial has not been set up.
+fn serial_port(vm: &mut VmInstance, port: u32) -> Option<&mut devices::serial::Serial> {
+    let ptr = *vm.serial_ptrs.get(port as usize)?;
+    if ptr.is_null() {
+        return None;
+    }
+    Some(unsafe { &mut *ptr })
+}
+
+/// Assert `irq` on the PIC and queue the resulting vector for the CPU.
+fn inject_irq(vm: &mut VmInstance, irq: u8) {
+    if vm.pic_ptr.is_null() {
+        return;
+    }
+    let pic = unsafe { &mut *vm.pic_ptr };
+    pic.raise_irq(irq);
+    // Bridge: poll the PIC for the resulting vector and inject into the CPU.
+    // Acknowledge on the PIC (IRR→ISR) so the same IRQ isn't re-injected.
+    if let Some(vector) = pic.get_interrupt_vector() {
+        pic.acknowledge(irq);
+        vm.engine.interrupts.raise_irq(vector);
+    }
+}
+
+/// Deliver pending UART interrupts and push output to registered callbacks.
+///
+/// Called after every [`corevm_run`] slice and whenever the host injects
+/// serial input, so interrupt-driven guests need no host-side IRQ polling.
+fn service_serial_ports(vm: &mut VmInstance) {
+    for port in 0..vm.serial_ptrs.len() {
+        let ptr = vm.serial_ptrs[port];
+        if ptr.is_null() {
+            continue;
+        }
+        let serial = unsafe { &mut *ptr };
+        if let Some((cb, userdata)) = vm.serial_output_cbs[port] {
+            if !serial.output.is_empty() {
+                let data = serial.take_output();
+                cb(userdata, port as u32, data.as_ptr(), data.len() as u32);
+            }
+        }
+        if serial.take_irq_edge() {
+            let irq = serial.irq;
+            inject_irq(vm, irq);
+        }
+    }
+}
+
+/// Push input data into the receive buffer of COM1.
 ///
 /// The guest will see this data when it reads the Receive Buffer Register.
 /// No-op if `data` is null, `len` is 0, or serial has not been set up.
 #[no_mangle]
 pub extern "C" fn corevm_serial_send_input(handle: u64, data: *const u8, len: u32) {
+    corevm_serial_send_input_port(handle, 0, data, len);
+}
+
+/// Push input data into the receive buffer of COM`port + 1`.
+///
+/// If the guest enabled the receive interrupt (IER bit 0, MCR OUT2), the
+/// port's IRQ (4 for COM1/COM3, 3 for COM2/COM4) is raised on the PIC.
+/// No-op if `data` is null, `len` is 0, `port` > 3, or serial has not been
+/// set up.
+#[no_mangle]
+pub extern "C" fn corevm_serial_send_input_port(handle: u64, port: u32, data: *const u8, len: u32) {
     if data.is_null() || len == 0 {
         return;
     }
     let vm = unsafe { vm_from_handle(handle) };
-    if vm.serial_ptr.is_null() {
-        return;
-    }
+    let serial = match serial_port(vm, port) {
+        Some(s) => s,
+        None => return,
+    };
     let slice = unsafe { core::slice::from_raw_parts(data, len as usize) };
-    unsafe { (*vm.serial_ptr).send_input(slice) };
+    serial.send_input(slice);
+    service_serial_ports(vm);
 }
 
-/// Drain serial output written by the guest into the provided buffer.
+/// Drain COM1 output written by the guest into the provided buffer.
 ///
 /// Returns the number of bytes written to `buf`. If the output is larger
 /// than `buf_len`, only `buf_len` bytes are copied (remaining data is lost).
@@ -1120,15 +1920,30 @@ pub extern "C" fn corevm_serial_take_output(
     handle: u64,
     buf: *mut u8,
     buf_len: u32,
+) -> u32 {
+    corevm_serial_take_output_port(handle, 0, buf, buf_len)
+}
+
+/// Drain COM`port + 1` output written by the guest into the provided buffer.
+///
+/// Same semantics as [`corevm_serial_take_output`]. Returns 0 if `port` > 3.
+/// Ports with an output callback are drained by the callback instead.
+#[no_mangle]
+pub extern "C" fn corevm_serial_take_output_port(
+    handle: u64,
+    port: u32,
+    buf: *mut u8,
+    buf_len: u32,
 ) -> u32 {
     if buf.is_null() || buf_len == 0 {
         return 0;
     }
     let vm = unsafe { vm_from_handle(handle) };
-    if vm.serial_ptr.is_null() {
-        return 0;
-    }
-    let output = unsafe { (*vm.serial_ptr).take_output() };
+    let serial = match serial_port(vm, port) {
+        Some(s) => s,
+        None => return 0,
+    };
+    let output = serial.take_output();
     let copy_len = (output.len() as u32).min(buf_len) as usize;
     if copy_len > 0 {
         unsafe {
@@ -1138,13 +1953,32 @@ pub extern "C" fn corevm_serial_take_output(
     copy_len as u32
 }
 
-// ════════════════════════════════════════════════════════════════════════
-// Device Interaction — Debug Port
-// ════════════════════════════════════════════════════════════════════════
-
-/// Drain debug port output written by the guest into the provided buffer.
+/// Register a callback that receives COM`port + 1` output.
 ///
-/// SeaBIOS writes debug messages to port 0x402. This function returns the
+/// Output is pushed in batches at the end of each [`corevm_run`] slice (and
+/// after [`corevm_serial_send_input_port`], so echoed input appears at
+/// once), replacing polling via [`corevm_serial_take_output_port`]. Pass a
+/// null `cb` to return to polling mode. No-op if `port` > 3.
+#[no_mangle]
+pub extern "C" fn corevm_serial_set_output_cb(
+    handle: u64,
+    port: u32,
+    cb: Option<SerialOutputCb>,
+    userdata: u64,
+) {
+    let vm = unsafe { vm_from_handle(handle) };
+    if let Some(slot) = vm.serial_output_cbs.get_mut(port as usize) {
+        *slot = cb.map(|cb| (cb, userdata));
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════
+// Device Interaction — Debug Port
+// ════════════════════════════════════════════════════════════════════════
+
+/// Drain debug port output written by the guest into the provided buffer.
+///
+/// SeaBIOS writes debug messages to port 0x402. This function returns the
 /// accumulated bytes. Returns 0 if `buf` is null or the debug port has not
 /// been set up.
 #[no_mangle]
@@ -1170,35 +2004,162 @@ pub extern "C" fn corevm_debug_take_output(
     copy_len as u32
 }
 
+// ════════════════════════════════════════════════════════════════════════
+// Device Interaction — Debug Console and Test Exit
+// ════════════════════════════════════════════════════════════════════════
+
+/// Host callback receiving debug console lines:
+/// `(userdata, timestamp_ms, data, len)`.
+///
+/// `timestamp_ms` is the host uptime when the line's first byte was
+/// written. `data` excludes the newline and is only valid for the duration
+/// of the call.
+pub type DebugconCb = extern "C" fn(u64, u64, *const u8, u32);
+
+/// Push completed debug console lines to the registered callback.
+fn service_debugcon(vm: &mut VmInstance) {
+    if vm.debugcon_ptr.is_null() {
+        return;
+    }
+    if let Some((cb, userdata)) = vm.debugcon_cb {
+        let console = unsafe { &mut *vm.debugcon_ptr };
+        while let Some(line) = console.take_line() {
+            cb(userdata, line.timestamp_ms, line.text.as_ptr(), line.text.len() as u32);
+        }
+    }
+}
+
+/// Take the oldest completed line written to the debug console (port 0xE9).
+///
+/// Copies the line (without its newline, truncated to `buf_len`) into
+/// `buf` and stores the host uptime in milliseconds at which it was started
+/// in `*timestamp_ms` if non-null. Returns the number of bytes copied, or
+/// -1 if no line is queued or the console has not been set up.
+#[no_mangle]
+pub extern "C" fn corevm_debugcon_take_line(
+    handle: u64,
+    buf: *mut u8,
+    buf_len: u32,
+    timestamp_ms: *mut u64,
+) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if vm.debugcon_ptr.is_null() {
+        return -1;
+    }
+    let line = match unsafe { (*vm.debugcon_ptr).take_line() } {
+        Some(l) => l,
+        None => return -1,
+    };
+    let copy_len = if buf.is_null() { 0 } else { (line.text.len() as u32).min(buf_len) as usize };
+    unsafe {
+        if copy_len > 0 {
+            ptr::copy_nonoverlapping(line.text.as_ptr(), buf, copy_len);
+        }
+        if !timestamp_ms.is_null() {
+            *timestamp_ms = line.timestamp_ms;
+        }
+    }
+    copy_len as i32
+}
+
+/// Register a callback that receives debug console lines.
+///
+/// Lines are pushed at the end of each [`corevm_run`] slice, replacing
+/// polling via [`corevm_debugcon_take_line`]. Pass a null `cb` to return to
+/// polling mode. Lines the host neither polled nor received are dropped
+/// oldest first once 1024 are queued (see [`corevm_debugcon_dropped`]).
+#[no_mangle]
+pub extern "C" fn corevm_debugcon_set_cb(handle: u64, cb: Option<DebugconCb>, userdata: u64) {
+    let vm = unsafe { vm_from_handle(handle) };
+    vm.debugcon_cb = cb.map(|cb| (cb, userdata));
+}
+
+/// Number of debug console lines lost because the queue overflowed.
+#[no_mangle]
+pub extern "C" fn corevm_debugcon_dropped(handle: u64) -> u64 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if vm.debugcon_ptr.is_null() {
+        return 0;
+    }
+    unsafe { (*vm.debugcon_ptr).dropped }
+}
+
+/// Status the guest last wrote to the test-exit port.
+///
+/// Valid after [`corevm_run`] returned 8. Returns -1 if the guest has not
+/// reported a status or the port has not been configured (TEST_EXIT
+/// machine node).
+#[no_mangle]
+pub extern "C" fn corevm_test_exit_status(handle: u64) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if vm.test_exit_ptr.is_null() {
+        return -1;
+    }
+    match unsafe { (*vm.test_exit_ptr).status } {
+        Some(status) => status as i32,
+        None => -1,
+    }
+}
+
 // ════════════════════════════════════════════════════════════════════════
 // Device Interaction — E1000
 // ════════════════════════════════════════════════════════════════════════
 
-/// Inject a received network packet into the E1000 RX buffer.
+/// Number of E1000 NICs in the VM.
+#[no_mangle]
+pub extern "C" fn corevm_e1000_count(handle: u64) -> u32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    vm.e1000_ptrs.len() as u32
+}
+
+/// Inject a received network packet into the RX buffer of NIC 0.
 ///
 /// No-op if `data` is null, `len` is 0, or E1000 has not been set up.
 #[no_mangle]
 pub extern "C" fn corevm_e1000_receive_packet(handle: u64, data: *const u8, len: u32) {
+    corevm_e1000_nic_receive(handle, 0, data, len);
+}
+
+/// Inject a received network packet into the RX buffer of NIC `nic`.
+///
+/// Returns 1 if the NIC accepted the frame, 0 if it was dropped: the link
+/// is down, the destination address does not pass the guest's receive
+/// filter (RAL/RAH, MTA and the RCTL promiscuous/broadcast bits), `data`
+/// is null or `len` is 0, or the NIC does not exist.
+#[no_mangle]
+pub extern "C" fn corevm_e1000_nic_receive(handle: u64, nic: u32, data: *const u8, len: u32) -> u32 {
     if data.is_null() || len == 0 {
-        return;
+        return 0;
     }
     let vm = unsafe { vm_from_handle(handle) };
-    if vm.e1000_ptr.is_null() {
-        return;
-    }
+    let Some(e1000) = vm.e1000(nic) else { return 0 };
     let slice = unsafe { core::slice::from_raw_parts(data, len as usize) };
-    unsafe { (*vm.e1000_ptr).receive_packet(slice) };
+    e1000.receive_packet(slice) as u32
+}
+
+/// Drain transmitted packets from the TX buffer of NIC 0 into a flat buffer.
+///
+/// See [`corevm_e1000_nic_take_tx`] for the buffer format.
+#[no_mangle]
+pub extern "C" fn corevm_e1000_take_tx_packets(
+    handle: u64,
+    buf: *mut u8,
+    buf_len: u32,
+) -> u32 {
+    corevm_e1000_nic_take_tx(handle, 0, buf, buf_len)
 }
 
-/// Drain transmitted packets from the E1000 TX buffer into a flat buffer.
+/// Drain transmitted packets from the TX buffer of NIC `nic` into a flat
+/// buffer.
 ///
 /// Packets are serialized as: `[u32 length][payload bytes]` repeated.
 /// Returns the total number of bytes written to `buf`. If the buffer is
 /// too small to fit all packets, only complete packets that fit are written.
-/// Returns 0 if `buf` is null or E1000 has not been set up.
+/// Returns 0 if `buf` is null or the NIC does not exist.
 #[no_mangle]
-pub extern "C" fn corevm_e1000_take_tx_packets(
+pub extern "C" fn corevm_e1000_nic_take_tx(
     handle: u64,
+    nic: u32,
     buf: *mut u8,
     buf_len: u32,
 ) -> u32 {
@@ -1206,10 +2167,8 @@ pub extern "C" fn corevm_e1000_take_tx_packets(
         return 0;
     }
     let vm = unsafe { vm_from_handle(handle) };
-    if vm.e1000_ptr.is_null() {
-        return 0;
-    }
-    let packets = unsafe { (*vm.e1000_ptr).take_tx_packets() };
+    let Some(e1000) = vm.e1000(nic) else { return 0 };
+    let packets = e1000.take_tx_packets();
     let mut offset: u32 = 0;
     for pkt in &packets {
         let header_size = 4u32; // u32 length prefix
@@ -1233,6 +2192,75 @@ pub extern "C" fn corevm_e1000_take_tx_packets(
     offset
 }
 
+/// Plug in (`up` != 0) or pull the virtual cable of NIC `nic`.
+///
+/// A change updates the link-up bit in STATUS and raises the link status
+/// change interrupt cause, so the guest driver notices. While the link is
+/// down, received frames are dropped and transmitted frames are discarded.
+///
+/// Returns 0 on success, -1 if the NIC does not exist.
+#[no_mangle]
+pub extern "C" fn corevm_e1000_set_link(handle: u64, nic: u32, up: u32) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    match vm.e1000(nic) {
+        Some(e1000) => {
+            e1000.set_link(up != 0);
+            0
+        }
+        None => -1,
+    }
+}
+
+/// Link state of NIC `nic`: 1 = up, 0 = down, -1 = no such NIC.
+#[no_mangle]
+pub extern "C" fn corevm_e1000_get_link(handle: u64, nic: u32) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    match vm.e1000(nic) {
+        Some(e1000) => e1000.link_up as i32,
+        None => -1,
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════
+// Memory — Balloon and Overcommit
+// ════════════════════════════════════════════════════════════════════════
+
+/// Ask the guest to hand `pages` 4 KiB pages to the balloon (0 = give all
+/// back). The guest driver adjusts the balloon asynchronously; poll
+/// [`corevm_balloon_get_actual`] for progress.
+///
+/// No-op if the machine has no balloon device.
+#[no_mangle]
+pub extern "C" fn corevm_balloon_set_target(handle: u64, pages: u32) {
+    let vm = unsafe { vm_from_handle(handle) };
+    if vm.balloon_ptr.is_null() {
+        return;
+    }
+    unsafe { (*vm.balloon_ptr).set_target(pages) };
+}
+
+/// Number of pages the guest has currently handed to the balloon.
+///
+/// Returns 0 if the machine has no balloon device.
+#[no_mangle]
+pub extern "C" fn corevm_balloon_get_actual(handle: u64) -> u32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if vm.balloon_ptr.is_null() {
+        return 0;
+    }
+    unsafe { (*vm.balloon_ptr).actual() }
+}
+
+/// Host memory currently backing guest RAM, in bytes.
+///
+/// Guest RAM is committed lazily on first write, so this is usually well
+/// below the configured RAM size.
+#[no_mangle]
+pub extern "C" fn corevm_ram_committed(handle: u64) -> u64 {
+    let vm = unsafe { vm_from_handle(handle) };
+    vm.engine.memory.ram().committed() as u64
+}
+
 // ════════════════════════════════════════════════════════════════════════
 // Device Interaction — PIT
 // ════════════════════════════════════════════════════════════════════════
@@ -1251,6 +2279,25 @@ pub extern "C" fn corevm_pit_tick(handle: u64) -> u32 {
     if fired { 1 } else { 0 }
 }
 
+// ════════════════════════════════════════════════════════════════════════
+// Device Interaction — CMOS RTC
+// ════════════════════════════════════════════════════════════════════════
+
+/// Set the guest real-time clock relative to the host clock.
+///
+/// The RTC shows host time plus `offset_secs` plus `tz_minutes` (the guest
+/// timezone in minutes east of UTC, for guests that keep the RTC in local
+/// time). May be called before or after the CMOS has been set up; a guest
+/// that sets its clock later moves the offset itself.
+#[no_mangle]
+pub extern "C" fn corevm_set_rtc_offset(handle: u64, offset_secs: i64, tz_minutes: i32) {
+    let vm = unsafe { vm_from_handle(handle) };
+    vm.rtc_offset = offset_secs + tz_minutes as i64 * 60;
+    if !vm.cmos_ptr.is_null() {
+        unsafe { (*vm.cmos_ptr).set_offset(vm.rtc_offset) };
+    }
+}
+
 // ════════════════════════════════════════════════════════════════════════
 // Device Interaction — PIC
 // ════════════════════════════════════════════════════════════════════════
@@ -1265,17 +2312,7 @@ pub extern "C" fn corevm_pit_tick(handle: u64) -> u32 {
 #[no_mangle]
 pub extern "C" fn corevm_pic_raise_irq(handle: u64, irq: u8) {
     let vm = unsafe { vm_from_handle(handle) };
-    if vm.pic_ptr.is_null() {
-        return;
-    }
-    let pic = unsafe { &mut *vm.pic_ptr };
-    pic.raise_irq(irq);
-    // Bridge: poll the PIC for the resulting vector and inject into the CPU.
-    // Acknowledge on the PIC (IRR→ISR) so the same IRQ isn't re-injected.
-    if let Some(vector) = pic.get_interrupt_vector() {
-        pic.acknowledge(irq);
-        vm.engine.interrupts.raise_irq(vector);
-    }
+    inject_irq(vm, irq);
 }
 
 /// Get the vector number of the highest-priority pending interrupt.
@@ -1307,10 +2344,7 @@ pub extern "C" fn corevm_setup_ide(handle: u64) {
     vm_log!("setting up IDE controller (ports 0x1F0-0x1F7, 0x3F6-0x3F7)");
     let vm = unsafe { vm_from_handle(handle) };
 
-    let ide = Box::into_raw(Box::new(devices::ide::Ide::new()));
-    vm.ide_ptr = ide;
-    vm.engine.io.register(0x1F0, 8, Box::new(IoProxy { ptr: ide }));
-    vm.engine.io.register(0x3F6, 2, Box::new(IoProxy { ptr: ide }));
+    vm.add_ide(0x1F0, 0x3F6);
 }
 
 /// Attach a disk image to the IDE controller.
@@ -1331,12 +2365,67 @@ pub extern "C" fn corevm_ide_attach_disk(handle: u64, data: *const u8, len: u32)
     vm_log!("attaching IDE disk image ({} bytes)", len);
     let mut image = alloc::vec::Vec::with_capacity(len as usize);
     image.extend_from_slice(slice);
-    unsafe { (*vm.ide_ptr).attach_disk(image) };
+    let ide = unsafe { &mut *vm.ide_ptr };
+    if !ide.detach_disk() {
+        vm_log!("ide: could not write back the previous disk image");
+    }
+    ide.set_read_only(false);
+    ide.attach_disk(image);
+}
+
+/// Attach the disk image file at `path` (UTF-8, `path_len` bytes) to the
+/// IDE controller, replacing the current disk.
+///
+/// Unlike [`corevm_ide_attach_disk`] the image is not loaded into memory:
+/// sectors are read from and written to the host file on demand through a
+/// block cache (see [`disk`]). qcow2 images (versions 2 and 3, with an
+/// optional read-only backing file) are recognized by their header; any
+/// other file is a raw sector dump. With `writable` = 0 the file is opened
+/// read-only and the drive rejects guest writes. Cached writes reach the
+/// file on ATA FLUSH CACHE, [`corevm_ide_flush`], detach and destroy.
+///
+/// Returns 0 on success, or a negative [`disk::DiskError`] code:
+/// -1 cannot open, -2 host read error, -3 corrupt image, -4 unsupported
+/// image feature, -5 backing file cannot be opened. Returns -1 if IDE has
+/// not been set up. The previous disk stays attached on error.
+#[no_mangle]
+pub extern "C" fn corevm_ide_open_disk(
+    handle: u64,
+    path: *const u8,
+    path_len: u32,
+    writable: u32,
+) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if vm.ide_ptr.is_null() || path.is_null() {
+        return -disk::DiskError::NotFound.code();
+    }
+    let bytes = unsafe { core::slice::from_raw_parts(path, path_len as usize) };
+    let path = match core::str::from_utf8(bytes) {
+        Ok(p) => p,
+        Err(_) => return -disk::DiskError::NotFound.code(),
+    };
+    let backend = match disk::open(path, writable != 0) {
+        Ok(b) => b,
+        Err(e) => {
+            vm_log!("ide: cannot open disk image {} ({:?})", path, e);
+            return -e.code();
+        }
+    };
+    vm_log!("attaching IDE disk image {} ({} bytes, {})",
+        path, backend.size(), if writable != 0 { "read-write" } else { "read-only" });
+    let ide = unsafe { &mut *vm.ide_ptr };
+    if !ide.detach_disk() {
+        vm_log!("ide: could not write back the previous disk image");
+    }
+    ide.set_read_only(writable == 0);
+    ide.attach_backend(backend);
+    0
 }
 
 /// Detach the disk image from the IDE controller.
 ///
-/// The image data is freed. No-op if IDE has not been set up or no disk
+/// Cached writes to an image file are written back and the file is closed;
+/// an in-memory image is freed. No-op if IDE has not been set up or no disk
 /// is attached.
 #[no_mangle]
 pub extern "C" fn corevm_ide_detach_disk(handle: u64) {
@@ -1344,7 +2433,128 @@ pub extern "C" fn corevm_ide_detach_disk(handle: u64) {
     if vm.ide_ptr.is_null() {
         return;
     }
-    unsafe { (*vm.ide_ptr).detach_disk() };
+    if !unsafe { (*vm.ide_ptr).detach_disk() } {
+        vm_log!("ide: could not write back the disk image");
+    }
+}
+
+/// Create an empty overlay image file at `path` on top of the disk image
+/// `base` (UTF-8, `*_len` bytes), replacing any existing file.
+///
+/// The base is not copied: the overlay is a qcow2 image that names `base`
+/// as its backing file (relative names are relative to the overlay's
+/// directory). Attach the overlay with [`corevm_ide_open_disk`]; the guest's
+/// writes then go to the overlay while the base stays unchanged, so many
+/// VMs can share one base image, each on its own overlay.
+///
+/// Returns 0 on success, or a negative [`disk::DiskError`] code (-1 the
+/// overlay cannot be created, -2 write error, -5 base cannot be opened).
+#[no_mangle]
+pub extern "C" fn corevm_disk_create_overlay(
+    path: *const u8,
+    path_len: u32,
+    base: *const u8,
+    base_len: u32,
+) -> i32 {
+    if path.is_null() || base.is_null() {
+        return -disk::DiskError::NotFound.code();
+    }
+    let path = unsafe { core::slice::from_raw_parts(path, path_len as usize) };
+    let base = unsafe { core::slice::from_raw_parts(base, base_len as usize) };
+    let (path, base) = match (core::str::from_utf8(path), core::str::from_utf8(base)) {
+        (Ok(p), Ok(b)) => (p, b),
+        _ => return -disk::DiskError::NotFound.code(),
+    };
+    match disk::create_overlay(path, base) {
+        Ok(()) => {
+            vm_log!("created disk overlay {} on {}", path, base);
+            0
+        }
+        Err(e) => {
+            vm_log!("cannot create disk overlay {} on {} ({:?})", path, base, e);
+            -e.code()
+        }
+    }
+}
+
+/// Copy the changes held in the attached IDE overlay image into its base
+/// image. The overlay keeps its contents; call
+/// [`corevm_ide_discard_overlay`] afterwards to empty it. No other VM may
+/// be using the base image while it is written.
+///
+/// Returns 0 on success, or a negative [`disk::DiskError`] code: -1 no disk
+/// attached or IDE not set up, -2 host I/O error, -4 the disk is not an
+/// overlay, -5 the base image cannot be opened writable.
+#[no_mangle]
+pub extern "C" fn corevm_ide_commit_overlay(handle: u64) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if vm.ide_ptr.is_null() {
+        return -disk::DiskError::NotFound.code();
+    }
+    match unsafe { (*vm.ide_ptr).commit_overlay() } {
+        Ok(clusters) => {
+            vm_log!("ide: committed {} overlay clusters to the base image", clusters);
+            0
+        }
+        Err(e) => {
+            vm_log!("ide: overlay commit failed ({:?})", e);
+            -e.code()
+        }
+    }
+}
+
+/// Drop every change held in the attached IDE overlay image, so the disk
+/// reads as its base image again. Meant for a stopped or freshly reset
+/// guest: a running guest sees its disk change underneath it.
+///
+/// Returns 0 on success, or a negative [`disk::DiskError`] code: -1 no disk
+/// attached or IDE not set up, -2 host I/O error, -4 the disk is not a
+/// writable qcow2 image, -5 the base image cannot be reopened.
+#[no_mangle]
+pub extern "C" fn corevm_ide_discard_overlay(handle: u64) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if vm.ide_ptr.is_null() {
+        return -disk::DiskError::NotFound.code();
+    }
+    match unsafe { (*vm.ide_ptr).discard_overlay() } {
+        Ok(()) => 0,
+        Err(e) => {
+            vm_log!("ide: overlay discard failed ({:?})", e);
+            -e.code()
+        }
+    }
+}
+
+/// Guess the operating system on the attached IDE disk from its boot
+/// sectors, file system signatures and partition table (see
+/// [`disk::probe`]). Only reads the disk; call it before booting to pick
+/// device defaults for the guest.
+///
+/// Returns a [`disk::probe::GuestOs`] code: 0 no disk (or IDE not set up),
+/// 1 blank, 2 unknown, 3 DOS, 4 Windows 9x, 5 Windows NT family, 6 Linux,
+/// 7 BSD, 8 anyOS.
+#[no_mangle]
+pub extern "C" fn corevm_probe_disk(handle: u64) -> u32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if vm.ide_ptr.is_null() {
+        return disk::probe::GuestOs::NoDisk as u32;
+    }
+    let os = unsafe { (*vm.ide_ptr).probe_guest() };
+    vm_log!("ide: disk probe: {:?}", os);
+    os as u32
+}
+
+/// Write cached changes of the IDE disk image file back to the host.
+///
+/// Returns 0 on success (also when there is nothing to write or IDE has not
+/// been set up), -1 on a host write error.
+#[no_mangle]
+pub extern "C" fn corevm_ide_flush(handle: u64) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if vm.ide_ptr.is_null() {
+        return 0;
+    }
+    if unsafe { (*vm.ide_ptr).flush() } { 0 } else { -1 }
 }
 
 /// Check whether the IDE controller has a pending IRQ (IRQ 14).
@@ -1371,3 +2581,433 @@ pub extern "C" fn corevm_ide_clear_irq(handle: u64) {
     }
     unsafe { (*vm.ide_ptr).clear_irq() };
 }
+
+// ════════════════════════════════════════════════════════════════════════
+// Device Setup — Shared Host Directory
+// ════════════════════════════════════════════════════════════════════════
+
+/// Share a host directory with the guest as a FAT16 disk (see [`share`]).
+///
+/// The disk is the master drive of the secondary IDE channel (ports
+/// 0x170-0x177 and 0x376-0x377), registered on first use. `path` is the
+/// UTF-8 host path (`path_len` bytes). With `writable` = 0 the drive rejects
+/// guest writes; otherwise changes can be copied back with
+/// [`corevm_share_sync`]. Sharing again replaces the disk with a fresh
+/// snapshot, and a null `path` removes it; in both cases a previous writable
+/// share is synced first, so the guest should unmount it beforehand.
+///
+/// Returns 0 on success, or a negative [`share::ShareError`] code:
+/// -1 not a directory, -2 too large for FAT16, -3 too many top-level
+/// entries, -4 host read error. The previous share stays attached on error.
+#[no_mangle]
+pub extern "C" fn corevm_share_directory(
+    handle: u64,
+    path: *const u8,
+    path_len: u32,
+    writable: u32,
+) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    let built = if path.is_null() {
+        None
+    } else {
+        let bytes = unsafe { core::slice::from_raw_parts(path, path_len as usize) };
+        let root = match core::str::from_utf8(bytes) {
+            Ok(p) => p,
+            Err(_) => return -share::ShareError::NotFound.code(),
+        };
+        match share::HostShare::build(root, writable != 0) {
+            Ok(b) => Some(b),
+            Err(e) => {
+                vm_log!("share: cannot share {} ({:?})", root, e);
+                return -e.code();
+            }
+        }
+    };
+
+    corevm_share_sync(handle);
+    vm.share = None;
+    if vm.share_ide_ptr.is_null() {
+        if built.is_none() {
+            return 0;
+        }
+        let ide = Box::into_raw(Box::new(devices::ide::Ide::new()));
+        vm.share_ide_ptr = ide;
+        vm.engine.io.register(0x170, 8, Box::new(IoRemap { ptr: ide, base: 0x170, native: 0x1F0 }));
+        vm.engine.io.register(0x376, 2, Box::new(IoRemap { ptr: ide, base: 0x376, native: 0x3F6 }));
+    }
+    let ide = unsafe { &mut *vm.share_ide_ptr };
+    ide.detach_disk();
+    if let Some((share, image)) = built {
+        vm_log!("share: attaching {}-byte FAT16 image ({})",
+            image.len(), if share.writable() { "read-write" } else { "read-only" });
+        ide.set_read_only(!share.writable());
+        ide.attach_disk(image);
+        vm.share = Some(share);
+    }
+    0
+}
+
+/// Copy files and directories the guest created or modified on a writable
+/// share back to the host directory. Host files are only ever created or
+/// overwritten, never deleted.
+///
+/// Call while the guest has the volume unmounted or its caches flushed.
+/// Returns the number of files written, or -1 if no writable directory is
+/// shared or the guest reformatted the disk.
+#[no_mangle]
+pub extern "C" fn corevm_share_sync(handle: u64) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    let share = match vm.share.as_ref() {
+        Some(s) if !vm.share_ide_ptr.is_null() => s,
+        _ => return -1,
+    };
+    match share.sync(unsafe { (*vm.share_ide_ptr).image() }) {
+        Some(n) => {
+            if n > 0 {
+                vm_log!("share: synced {} file(s) back to the host", n);
+            }
+            n as i32
+        }
+        None => -1,
+    }
+}
+
+// ════════════════════════════════════════════════════════════════════════
+// Device Setup — NOR Flash
+// ════════════════════════════════════════════════════════════════════════
+
+/// Map a CFI NOR flash device (see [`devices::flash`]) of `size` bytes at
+/// guest physical `base`, erasable in `sector_size` blocks.
+///
+/// The initial contents come from the host file `path` (`path_len` bytes of
+/// UTF-8) if it exists, otherwise from `init` (`init_len` bytes, may be
+/// null); the remainder reads as erased (0xFF). With a `path`, guest
+/// modifications are written to that file by [`corevm_flash_sync`] and when
+/// the VM is destroyed. A null `path` gives a volatile flash.
+///
+/// `size` must be a power of two and a multiple of `sector_size`, which must
+/// be a multiple of 256. The range must not overlap RAM the guest uses or
+/// another MMIO region.
+///
+/// Returns 0 on success, -1 for invalid geometry or path, -2 if the host
+/// file exists but cannot be read.
+#[no_mangle]
+pub extern "C" fn corevm_flash_attach(
+    handle: u64,
+    base: u64,
+    size: u32,
+    sector_size: u32,
+    path: *const u8,
+    path_len: u32,
+    init: *const u8,
+    init_len: u32,
+) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    let path = if path.is_null() {
+        None
+    } else {
+        let bytes = unsafe { core::slice::from_raw_parts(path, path_len as usize) };
+        match core::str::from_utf8(bytes) {
+            Ok(p) => Some(p),
+            Err(_) => return -1,
+        }
+    };
+    let init = if init.is_null() {
+        &[][..]
+    } else {
+        unsafe { core::slice::from_raw_parts(init, init_len as usize) }
+    };
+    let data = match devices::flash::NorFlash::load_contents(size as usize, path, init) {
+        Some(d) => d,
+        None => {
+            vm_log!("flash: cannot read {}", path.unwrap_or(""));
+            return -2;
+        }
+    };
+    let flash = match devices::flash::NorFlash::new(data, sector_size as usize, path.map(String::from)) {
+        Some(f) => f,
+        None => {
+            vm_log!("flash: invalid geometry (size 0x{:X}, sector 0x{:X})", size, sector_size);
+            return -1;
+        }
+    };
+    vm_log!("flash: {} KiB at 0x{:X} ({})", size / 1024, base, path.unwrap_or("volatile"));
+    let flash = Box::into_raw(Box::new(flash));
+    vm.flash_ptrs.push(flash);
+    vm.engine.memory.add_mmio(base, size as u64, Box::new(MmioProxy { ptr: flash }));
+    0
+}
+
+/// Write the contents of every file-backed flash the guest modified back
+/// to its host file.
+///
+/// Returns the number of files written, or -1 if any write failed.
+#[no_mangle]
+pub extern "C" fn corevm_flash_sync(handle: u64) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    let mut written = 0;
+    let mut failed = false;
+    for &flash in &vm.flash_ptrs {
+        match unsafe { (*flash).persist() } {
+            Some(true) => written += 1,
+            Some(false) => {}
+            None => failed = true,
+        }
+    }
+    if failed { -1 } else { written }
+}
+
+// ════════════════════════════════════════════════════════════════════════
+// Diagnostics — Port I/O and MMIO Trace
+// ════════════════════════════════════════════════════════════════════════
+
+/// Get the VM's tracer, creating it (disabled) and attaching it to the
+/// port and MMIO dispatchers on first use.
+fn iotrace(vm: &mut VmInstance) -> &mut iotrace::IoTrace {
+    if vm.iotrace_ptr.is_null() {
+        let rip_src = &vm.engine.cpu.last_exec_rip as *const u64;
+        vm.iotrace_ptr = Box::into_raw(Box::new(iotrace::IoTrace::new(rip_src)));
+        vm.engine.io.trace = vm.iotrace_ptr;
+        vm.engine.memory.set_mmio_trace(vm.iotrace_ptr);
+    }
+    unsafe { &mut *vm.iotrace_ptr }
+}
+
+/// Start tracing guest device accesses.
+///
+/// `spaces`: bit 0 = port I/O, bit 1 = MMIO. `capacity` is the ring buffer
+/// size in records (0 = 4096, max 1M); changing it discards buffered
+/// records. Filters and the callback are kept across enable/disable.
+#[no_mangle]
+pub extern "C" fn corevm_iotrace_enable(handle: u64, spaces: u32, capacity: u32) {
+    let vm = unsafe { vm_from_handle(handle) };
+    vm_log!("I/O trace enabled (spaces=0x{:X}, capacity={})", spaces, capacity);
+    iotrace(vm).enable(spaces as u8, capacity as usize);
+}
+
+/// Stop tracing. Buffered records can still be taken.
+#[no_mangle]
+pub extern "C" fn corevm_iotrace_disable(handle: u64) {
+    let vm = unsafe { vm_from_handle(handle) };
+    if !vm.iotrace_ptr.is_null() {
+        unsafe { (*vm.iotrace_ptr).disable() };
+    }
+}
+
+/// Add a filter range `[start, start + count)` in `space` (0 = ports,
+/// 1 = MMIO). `exclude` = 0 adds an include range, 1 an exclude range.
+///
+/// With any include range for a space, only accesses inside one are
+/// recorded; accesses in an exclude range are never recorded.
+/// Returns 0 on success, -1 if the filter table (32 entries) is full or
+/// `space` is invalid.
+#[no_mangle]
+pub extern "C" fn corevm_iotrace_add_filter(
+    handle: u64,
+    space: u32,
+    start: u64,
+    count: u64,
+    exclude: u32,
+) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if space > iotrace::SPACE_MMIO as u32 {
+        return -1;
+    }
+    if iotrace(vm).add_filter(space as u8, start, count, exclude == 0) { 0 } else { -1 }
+}
+
+/// Remove all trace filters.
+#[no_mangle]
+pub extern "C" fn corevm_iotrace_clear_filters(handle: u64) {
+    let vm = unsafe { vm_from_handle(handle) };
+    iotrace(vm).clear_filters();
+}
+
+/// Copy up to `max` buffered records (oldest first) into `out` and remove
+/// them from the buffer. Each record is a 32-byte
+/// [`iotrace::TraceRecord`]. Returns the number of records copied.
+#[no_mangle]
+pub extern "C" fn corevm_iotrace_take(
+    handle: u64,
+    out: *mut iotrace::TraceRecord,
+    max: u32,
+) -> u32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if out.is_null() || max == 0 || vm.iotrace_ptr.is_null() {
+        return 0;
+    }
+    let out = unsafe { core::slice::from_raw_parts_mut(out, max as usize) };
+    unsafe { (*vm.iotrace_ptr).take(out) as u32 }
+}
+
+/// Number of records overwritten before they were taken (ring overflow).
+#[no_mangle]
+pub extern "C" fn corevm_iotrace_dropped(handle: u64) -> u64 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if vm.iotrace_ptr.is_null() {
+        return 0;
+    }
+    unsafe { (*vm.iotrace_ptr).dropped() }
+}
+
+/// Switch to live mode: `cb(userdata, record)` is called for each traced
+/// access as it happens, instead of buffering. The callback runs inside
+/// [`corevm_run`] and must not call back into the VM. Pass a null `cb` to
+/// return to ring buffer mode.
+#[no_mangle]
+pub extern "C" fn corevm_iotrace_set_callback(
+    handle: u64,
+    cb: Option<iotrace::TraceCb>,
+    userdata: u64,
+) {
+    let vm = unsafe { vm_from_handle(handle) };
+    iotrace(vm).set_callback(cb.map(|cb| (cb, userdata)));
+}
+
+// ════════════════════════════════════════════════════════════════════════
+// Diagnostics — Guest Symbols and Watches
+// ════════════════════════════════════════════════════════════════════════
+
+/// Get the VM's debugger, creating it and attaching it to the CPU on first use.
+fn debugger(vm: &mut VmInstance) -> &mut debug::Debugger {
+    if vm.debug_ptr.is_null() {
+        vm.debug_ptr = Box::into_raw(Box::new(debug::Debugger::new()));
+        vm.engine.cpu.debug = vm.debug_ptr;
+    }
+    unsafe { &mut *vm.debug_ptr }
+}
+
+/// Load a text symbol map (`nm` output or `address name` lines) used to
+/// annotate RIP values in exception dumps and diagnostics. Entries are
+/// added to any already loaded. Returns the number of symbols parsed, or
+/// -1 if `data` is null.
+#[no_mangle]
+pub extern "C" fn corevm_symbols_load(handle: u64, data: *const u8, len: u32) -> i32 {
+    if data.is_null() {
+        return -1;
+    }
+    let vm = unsafe { vm_from_handle(handle) };
+    let text = unsafe { core::slice::from_raw_parts(data, len as usize) };
+    let dbg = debugger(vm);
+    let n = dbg.symbols.load(text);
+    vm_log!("loaded {} guest symbols ({} total)", n, dbg.symbols.len());
+    n as i32
+}
+
+/// Add a single symbol `name` (UTF-8, `name_len` bytes) at `addr`.
+///
+/// Returns 0 on success, -1 if the name is null or not valid UTF-8.
+#[no_mangle]
+pub extern "C" fn corevm_symbols_add(handle: u64, addr: u64, name: *const u8, name_len: u32) -> i32 {
+    if name.is_null() {
+        return -1;
+    }
+    let vm = unsafe { vm_from_handle(handle) };
+    let bytes = unsafe { core::slice::from_raw_parts(name, name_len as usize) };
+    match core::str::from_utf8(bytes) {
+        Ok(name) => {
+            debugger(vm).symbols.insert(addr, name);
+            0
+        }
+        Err(_) => -1,
+    }
+}
+
+/// Remove all guest symbols.
+#[no_mangle]
+pub extern "C" fn corevm_symbols_clear(handle: u64) {
+    let vm = unsafe { vm_from_handle(handle) };
+    if !vm.debug_ptr.is_null() {
+        unsafe { (*vm.debug_ptr).symbols.clear() };
+    }
+}
+
+/// Resolve `addr` (e.g. the `rip` of a trace record) to the nearest symbol
+/// at or below it. The name is copied into `buf` (NUL-terminated if it
+/// fits) and the offset into the symbol stored in `*offset` if non-null.
+///
+/// Returns the name length in bytes written, or 0 if no symbol covers `addr`.
+#[no_mangle]
+pub extern "C" fn corevm_symbol_lookup(
+    handle: u64,
+    addr: u64,
+    buf: *mut u8,
+    buf_len: u32,
+    offset: *mut u64,
+) -> u32 {
+    if buf.is_null() || buf_len == 0 {
+        return 0;
+    }
+    let vm = unsafe { vm_from_handle(handle) };
+    if vm.debug_ptr.is_null() {
+        return 0;
+    }
+    let (name, off) = match unsafe { (*vm.debug_ptr).symbols.lookup(addr) } {
+        Some(found) => found,
+        None => return 0,
+    };
+    let copy_len = name.len().min((buf_len - 1) as usize);
+    unsafe {
+        ptr::copy_nonoverlapping(name.as_ptr(), buf, copy_len);
+        *buf.add(copy_len) = 0;
+        if !offset.is_null() {
+            *offset = off;
+        }
+    }
+    copy_len as u32
+}
+
+/// Add a watch evaluated after every instruction; when its value changes
+/// [`corevm_run`] returns 7.
+///
+/// `kind`: 0 = register (`target` 0–15 = RAX..R15, 16 = RFLAGS, 17 = CR0,
+/// 18 = CR2, 19 = CR3, 20 = CR4), 1 = memory at a linear address, 2 =
+/// memory at a physical address. `size` is 1, 2, 4 or 8 for memory watches
+/// and ignored for registers.
+///
+/// Returns the watch id (> 0), or -1 if the watch is invalid or the table
+/// (16 entries) is full.
+#[no_mangle]
+pub extern "C" fn corevm_watch_add(handle: u64, kind: u32, target: u64, size: u32) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    debugger(vm);
+    let engine = &vm.engine;
+    let dbg = unsafe { &mut *vm.debug_ptr };
+    match dbg.add_watch(kind, target, size as u8, &engine.cpu.regs, &engine.memory, &engine.mmu) {
+        Some(id) => id as i32,
+        None => -1,
+    }
+}
+
+/// Remove a watch by id. Returns 0 on success, -1 if no such watch exists.
+#[no_mangle]
+pub extern "C" fn corevm_watch_remove(handle: u64, id: u32) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if !vm.debug_ptr.is_null() && unsafe { (*vm.debug_ptr).remove_watch(id) } { 0 } else { -1 }
+}
+
+/// Remove all watches.
+#[no_mangle]
+pub extern "C" fn corevm_watch_clear(handle: u64) {
+    let vm = unsafe { vm_from_handle(handle) };
+    if !vm.debug_ptr.is_null() {
+        unsafe { (*vm.debug_ptr).clear_watches() };
+    }
+}
+
+/// Copy the change behind the last watchpoint exit into `out` (a 48-byte
+/// [`debug::WatchHit`]). Returns 0 on success, -1 if no watch has fired.
+#[no_mangle]
+pub extern "C" fn corevm_watch_get_hit(handle: u64, out: *mut debug::WatchHit) -> i32 {
+    let vm = unsafe { vm_from_handle(handle) };
+    if out.is_null() || vm.debug_ptr.is_null() {
+        return -1;
+    }
+    match unsafe { (*vm.debug_ptr).last_hit() } {
+        Some(hit) => {
+            unsafe { *out = hit };
+            0
+        }
+        None => -1,
+    }
+}
@@ -0,0 +1,464 @@
+//! Machine description — declarative VM configuration blob.
+//!
+//! [`corevm_configure`](crate::corevm_configure) accepts a compact binary
+//! description of the machine instead of a fixed sequence of
+//! `corevm_setup_*` calls. New devices and properties become new tags, not
+//! new exports.
+//!
+//! # Format
+//!
+//! All integers are little-endian. The blob starts with the 4-byte magic
+//! `"CVM1"`, followed by a sequence of nodes:
+//!
+//! ```text
+//! node := tag:u8  len:u16  body[len]
+//! ```
+//!
+//! Top-level nodes describe the machine or one device. Device bodies are
+//! themselves a sequence of property nodes in the same encoding; omitted
+//! properties take the defaults used by `corevm_setup_standard_devices`.
+//!
+//! | Tag | Node | Body / properties |
+//! |-----|------|-------------------|
+//! | 0x01 | RAM | u32 size in MiB |
+//! | 0x02 | BIOS | empty — load the built-in BIOS |
+//! | 0x0F | STANDARD | empty — all devices of `corevm_setup_standard_devices` |
+//! | 0x10 | PIC | — |
+//! | 0x11 | PIT | — |
+//! | 0x12 | CMOS | — |
+//! | 0x13 | PS/2 | — (also System Control Port A at 0x92) |
+//! | 0x14 | SERIAL | PORT, IRQ (one node per UART, max 4) |
+//! | 0x15 | VGA | WIDTH, HEIGHT |
+//! | 0x16 | PCI | — (i440FX host bridge, PIIX3 ISA bridge, VGA function) |
+//! | 0x17 | IOAPIC | MMIO |
+//! | 0x18 | FW_CFG | PORT |
+//! | 0x19 | DEBUG_PORT | PORT |
+//! | 0x1A | ACPI_PM | PORT, ALIAS (0 = no alias) |
+//! | 0x1B | DEBUGCON | PORT (Bochs log console, standard 0xE9) |
+//! | 0x1C | TEST_EXIT | PORT (not in STANDARD; default 0xF4) |
+//! | 0x20 | E1000 | MMIO, MAC (one node per NIC, max 4) |
+//! | 0x21 | IDE | PORT, CTRL_PORT, DISK |
+//! | 0x22 | BALLOON | PORT (0x20-aligned) |
+//!
+//! | Tag | Property | Body |
+//! |-----|----------|------|
+//! | 0x80 | PORT | u16 I/O base |
+//! | 0x81 | IRQ | u8 |
+//! | 0x82 | MMIO | u64 physical base |
+//! | 0x83 | MAC | 6 bytes |
+//! | 0x84 | WIDTH | u32 |
+//! | 0x85 | HEIGHT | u32 |
+//! | 0x86 | CTRL_PORT | u16 I/O base |
+//! | 0x87 | DISK | u64 host address + u32 length (image is copied) |
+//! | 0x88 | ALIAS | u16 I/O base |
+//!
+//! Devices are instantiated in a fixed order (interrupt controllers first,
+//! BIOS last) regardless of node order in the blob.
+
+use alloc::vec::Vec;
+
+/// Blob magic: ASCII `"CVM1"`.
+pub const MAGIC: [u8; 4] = *b"CVM1";
+
+// ── Node tags ──
+
+pub const TAG_RAM: u8 = 0x01;
+pub const TAG_BIOS: u8 = 0x02;
+pub const TAG_STANDARD: u8 = 0x0F;
+pub const TAG_PIC: u8 = 0x10;
+pub const TAG_PIT: u8 = 0x11;
+pub const TAG_CMOS: u8 = 0x12;
+pub const TAG_PS2: u8 = 0x13;
+pub const TAG_SERIAL: u8 = 0x14;
+pub const TAG_VGA: u8 = 0x15;
+pub const TAG_PCI: u8 = 0x16;
+pub const TAG_IOAPIC: u8 = 0x17;
+pub const TAG_FW_CFG: u8 = 0x18;
+pub const TAG_DEBUG_PORT: u8 = 0x19;
+pub const TAG_ACPI_PM: u8 = 0x1A;
+pub const TAG_DEBUGCON: u8 = 0x1B;
+pub const TAG_TEST_EXIT: u8 = 0x1C;
+pub const TAG_E1000: u8 = 0x20;
+pub const TAG_IDE: u8 = 0x21;
+pub const TAG_BALLOON: u8 = 0x22;
+
+// ── Property tags ──
+
+pub const PROP_PORT: u8 = 0x80;
+pub const PROP_IRQ: u8 = 0x81;
+pub const PROP_MMIO: u8 = 0x82;
+pub const PROP_MAC: u8 = 0x83;
+pub const PROP_WIDTH: u8 = 0x84;
+pub const PROP_HEIGHT: u8 = 0x85;
+pub const PROP_CTRL_PORT: u8 = 0x86;
+pub const PROP_DISK: u8 = 0x87;
+pub const PROP_ALIAS: u8 = 0x88;
+
+/// Reasons a machine description is rejected.
+///
+/// `corevm_configure` returns the negated [`code`](ConfigError::code).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Missing magic, or a node extends past the end of its parent.
+    Malformed,
+    /// Unknown node or property tag, or a property on the wrong device.
+    UnknownTag(u8),
+    /// A node or property body has the wrong size or an out-of-range value.
+    InvalidValue(u8),
+    /// A device appears more often than the machine supports.
+    Duplicate(u8),
+    /// The VM already has devices; configure a freshly created VM.
+    AlreadyConfigured,
+}
+
+impl ConfigError {
+    /// Positive error code (1-5).
+    pub fn code(&self) -> i32 {
+        match self {
+            ConfigError::Malformed => 1,
+            ConfigError::UnknownTag(_) => 2,
+            ConfigError::InvalidValue(_) => 3,
+            ConfigError::Duplicate(_) => 4,
+            ConfigError::AlreadyConfigured => 5,
+        }
+    }
+}
+
+/// 16550 UART configuration.
+#[derive(Debug, Clone, Copy)]
+pub struct SerialDesc {
+    pub port: u16,
+    pub irq: u8,
+}
+
+/// VGA adapter configuration.
+#[derive(Debug, Clone, Copy)]
+pub struct VgaDesc {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// ACPI PM block configuration.
+#[derive(Debug, Clone, Copy)]
+pub struct AcpiPmDesc {
+    pub port: u16,
+    /// Legacy alias base (0 = none).
+    pub alias: u16,
+}
+
+/// E1000 NIC configuration.
+#[derive(Debug, Clone, Copy)]
+pub struct E1000Desc {
+    pub mmio: u64,
+    pub mac: [u8; 6],
+}
+
+/// IDE controller configuration.
+#[derive(Debug, Clone, Copy)]
+pub struct IdeDesc {
+    pub port: u16,
+    pub ctrl_port: u16,
+    /// Host buffer to copy as the attached disk image (address, length).
+    pub disk: Option<(u64, u32)>,
+}
+
+/// Parsed machine description. `None` / empty means the device is absent.
+#[derive(Debug, Clone, Default)]
+pub struct MachineDesc {
+    pub ram_mb: Option<u32>,
+    pub bios: bool,
+    pub pic: bool,
+    pub pit: bool,
+    pub cmos: bool,
+    pub ps2: bool,
+    pub serial: Vec<SerialDesc>,
+    pub vga: Option<VgaDesc>,
+    pub pci: bool,
+    pub ioapic: Option<u64>,
+    pub fw_cfg: Option<u16>,
+    pub debug_port: Option<u16>,
+    /// Bochs debug console port.
+    pub debugcon: Option<u16>,
+    /// Test-exit port.
+    pub test_exit: Option<u16>,
+    pub acpi_pm: Option<AcpiPmDesc>,
+    /// NICs in index order (see `corevm_e1000_count`).
+    pub e1000: Vec<E1000Desc>,
+    pub ide: Option<IdeDesc>,
+    /// Memory balloon I/O base.
+    pub balloon: Option<u16>,
+}
+
+/// Iterator over `tag:u8 len:u16 body` nodes.
+struct Nodes<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Iterator for Nodes<'a> {
+    type Item = core::result::Result<(u8, &'a [u8]), ConfigError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.data.len() {
+            return None;
+        }
+        let rest = &self.data[self.pos..];
+        if rest.len() < 3 {
+            self.pos = self.data.len();
+            return Some(Err(ConfigError::Malformed));
+        }
+        let tag = rest[0];
+        let len = u16::from_le_bytes([rest[1], rest[2]]) as usize;
+        if rest.len() < 3 + len {
+            self.pos = self.data.len();
+            return Some(Err(ConfigError::Malformed));
+        }
+        self.pos += 3 + len;
+        Some(Ok((tag, &rest[3..3 + len])))
+    }
+}
+
+fn nodes(data: &[u8]) -> Nodes<'_> {
+    Nodes { data, pos: 0 }
+}
+
+fn read_u16(tag: u8, body: &[u8]) -> core::result::Result<u16, ConfigError> {
+    match body {
+        [a, b] => Ok(u16::from_le_bytes([*a, *b])),
+        _ => Err(ConfigError::InvalidValue(tag)),
+    }
+}
+
+fn read_u32(tag: u8, body: &[u8]) -> core::result::Result<u32, ConfigError> {
+    match body {
+        [a, b, c, d] => Ok(u32::from_le_bytes([*a, *b, *c, *d])),
+        _ => Err(ConfigError::InvalidValue(tag)),
+    }
+}
+
+fn read_u64(tag: u8, body: &[u8]) -> core::result::Result<u64, ConfigError> {
+    if body.len() != 8 {
+        return Err(ConfigError::InvalidValue(tag));
+    }
+    let mut b = [0u8; 8];
+    b.copy_from_slice(body);
+    Ok(u64::from_le_bytes(b))
+}
+
+/// Check that a device node has no properties; returns `true` (present).
+fn expect_empty(body: &[u8]) -> core::result::Result<bool, ConfigError> {
+    if let Some(first) = nodes(body).next() {
+        let (prop, _) = first?;
+        return Err(ConfigError::UnknownTag(prop));
+    }
+    Ok(true)
+}
+
+/// Reject a second occurrence of a single-instance device.
+fn once<T>(tag: u8, slot: &Option<T>) -> core::result::Result<(), ConfigError> {
+    if slot.is_some() { Err(ConfigError::Duplicate(tag)) } else { Ok(()) }
+}
+
+impl MachineDesc {
+    /// Description equivalent to `corevm_setup_standard_devices`.
+    pub fn standard() -> Self {
+        let mut d = MachineDesc::default();
+        d.add_standard();
+        d
+    }
+
+    fn add_standard(&mut self) {
+        self.pic = true;
+        self.pit = true;
+        self.cmos = true;
+        self.ps2 = true;
+        self.serial = crate::devices::serial::COM_PORTS
+            .iter()
+            .map(|&(port, irq)| SerialDesc { port, irq })
+            .collect();
+        self.vga = Some(VgaDesc { width: 800, height: 600 });
+        self.pci = true;
+        self.ioapic = Some(0xFEC0_0000);
+        self.fw_cfg = Some(0x510);
+        self.debug_port = Some(0x402);
+        self.debugcon = Some(0xE9);
+        self.acpi_pm = Some(AcpiPmDesc { port: 0x600, alias: 0xB000 });
+    }
+
+    /// Parse a description blob.
+    pub fn parse(blob: &[u8]) -> core::result::Result<Self, ConfigError> {
+        if blob.len() < 4 || blob[..4] != MAGIC {
+            return Err(ConfigError::Malformed);
+        }
+        let mut d = MachineDesc::default();
+        for node in nodes(&blob[4..]) {
+            let (tag, body) = node?;
+            match tag {
+                TAG_RAM => {
+                    once(tag, &d.ram_mb)?;
+                    let mb = read_u32(tag, body)?;
+                    if mb == 0 {
+                        return Err(ConfigError::InvalidValue(tag));
+                    }
+                    d.ram_mb = Some(mb);
+                }
+                TAG_BIOS => d.bios = expect_empty(body)?,
+                TAG_STANDARD => {
+                    expect_empty(body)?;
+                    d.add_standard();
+                }
+                TAG_PIC => d.pic = expect_empty(body)?,
+                TAG_PIT => d.pit = expect_empty(body)?,
+                TAG_CMOS => d.cmos = expect_empty(body)?,
+                TAG_PS2 => d.ps2 = expect_empty(body)?,
+                TAG_PCI => d.pci = expect_empty(body)?,
+                TAG_SERIAL => {
+                    let index = d.serial.len();
+                    if index >= crate::devices::serial::COM_PORTS.len() {
+                        return Err(ConfigError::Duplicate(tag));
+                    }
+                    let (port, irq) = crate::devices::serial::COM_PORTS[index];
+                    let mut s = SerialDesc { port, irq };
+                    for prop in nodes(body) {
+                        let (p, v) = prop?;
+                        match p {
+                            PROP_PORT => s.port = read_u16(p, v)?,
+                            PROP_IRQ => match v {
+                                [irq] if *irq < 16 => s.irq = *irq,
+                                _ => return Err(ConfigError::InvalidValue(p)),
+                            },
+                            _ => return Err(ConfigError::UnknownTag(p)),
+                        }
+                    }
+                    d.serial.push(s);
+                }
+                TAG_VGA => {
+                    once(tag, &d.vga)?;
+                    let mut v = VgaDesc { width: 800, height: 600 };
+                    for prop in nodes(body) {
+                        let (p, val) = prop?;
+                        match p {
+                            PROP_WIDTH => v.width = read_u32(p, val)?,
+                            PROP_HEIGHT => v.height = read_u32(p, val)?,
+                            _ => return Err(ConfigError::UnknownTag(p)),
+                        }
+                    }
+                    if v.width == 0 || v.height == 0 || v.width > 4096 || v.height > 4096 {
+                        return Err(ConfigError::InvalidValue(tag));
+                    }
+                    d.vga = Some(v);
+                }
+                TAG_IOAPIC => {
+                    once(tag, &d.ioapic)?;
+                    let mut base = 0xFEC0_0000;
+                    for prop in nodes(body) {
+                        let (p, v) = prop?;
+                        match p {
+                            PROP_MMIO => base = read_u64(p, v)?,
+                            _ => return Err(ConfigError::UnknownTag(p)),
+                        }
+                    }
+                    d.ioapic = Some(base);
+                }
+                TAG_FW_CFG | TAG_DEBUG_PORT | TAG_DEBUGCON | TAG_TEST_EXIT => {
+                    let (slot, default) = match tag {
+                        TAG_FW_CFG => (&mut d.fw_cfg, 0x510),
+                        TAG_DEBUG_PORT => (&mut d.debug_port, 0x402),
+                        TAG_DEBUGCON => (&mut d.debugcon, 0xE9),
+                        _ => (&mut d.test_exit, 0xF4),
+                    };
+                    once(tag, slot)?;
+                    let mut port = default;
+                    for prop in nodes(body) {
+                        let (p, v) = prop?;
+                        match p {
+                            PROP_PORT => port = read_u16(p, v)?,
+                            _ => return Err(ConfigError::UnknownTag(p)),
+                        }
+                    }
+                    *slot = Some(port);
+                }
+                TAG_ACPI_PM => {
+                    once(tag, &d.acpi_pm)?;
+                    let mut a = AcpiPmDesc { port: 0x600, alias: 0xB000 };
+                    for prop in nodes(body) {
+                        let (p, v) = prop?;
+                        match p {
+                            PROP_PORT => a.port = read_u16(p, v)?,
+                            PROP_ALIAS => a.alias = read_u16(p, v)?,
+                            _ => return Err(ConfigError::UnknownTag(p)),
+                        }
+                    }
+                    d.acpi_pm = Some(a);
+                }
+                TAG_E1000 => {
+                    use crate::devices::e1000::{DEFAULT_MAC, DEFAULT_MMIO, MAX_NICS};
+                    let index = d.e1000.len();
+                    if index >= MAX_NICS {
+                        return Err(ConfigError::Duplicate(tag));
+                    }
+                    let mut e = E1000Desc {
+                        mmio: DEFAULT_MMIO + index as u64 * 0x20000,
+                        mac: DEFAULT_MAC,
+                    };
+                    e.mac[5] = e.mac[5].wrapping_add(index as u8);
+                    for prop in nodes(body) {
+                        let (p, v) = prop?;
+                        match p {
+                            PROP_MMIO => e.mmio = read_u64(p, v)?,
+                            PROP_MAC => {
+                                if v.len() != 6 {
+                                    return Err(ConfigError::InvalidValue(p));
+                                }
+                                e.mac.copy_from_slice(v);
+                            }
+                            _ => return Err(ConfigError::UnknownTag(p)),
+                        }
+                    }
+                    d.e1000.push(e);
+                }
+                TAG_IDE => {
+                    once(tag, &d.ide)?;
+                    let mut i = IdeDesc { port: 0x1F0, ctrl_port: 0x3F6, disk: None };
+                    for prop in nodes(body) {
+                        let (p, v) = prop?;
+                        match p {
+                            PROP_PORT => i.port = read_u16(p, v)?,
+                            PROP_CTRL_PORT => i.ctrl_port = read_u16(p, v)?,
+                            PROP_DISK => {
+                                if v.len() != 12 {
+                                    return Err(ConfigError::InvalidValue(p));
+                                }
+                                let addr = read_u64(p, &v[..8])?;
+                                let len = read_u32(p, &v[8..])?;
+                                if addr == 0 || len == 0 {
+                                    return Err(ConfigError::InvalidValue(p));
+                                }
+                                i.disk = Some((addr, len));
+                            }
+                            _ => return Err(ConfigError::UnknownTag(p)),
+                        }
+                    }
+                    d.ide = Some(i);
+                }
+                TAG_BALLOON => {
+                    once(tag, &d.balloon)?;
+                    let mut port = 0x5A0;
+                    for prop in nodes(body) {
+                        let (p, v) = prop?;
+                        match p {
+                            PROP_PORT => port = read_u16(p, v)?,
+                            _ => return Err(ConfigError::UnknownTag(p)),
+                        }
+                    }
+                    if port % crate::devices::balloon::PORT_COUNT != 0 {
+                        return Err(ConfigError::InvalidValue(PROP_PORT));
+                    }
+                    d.balloon = Some(port);
+                }
+                _ => return Err(ConfigError::UnknownTag(tag)),
+            }
+        }
+        Ok(d)
+    }
+}
@@ -1,35 +1,67 @@
-//! Flat guest physical memory backed by a contiguous byte vector.
+//! Flat guest physical memory with lazily committed backing storage.
+//!
+//! `FlatMemory` maps guest physical addresses 1:1 to offsets in a set of
+//! fixed-size host chunks. A chunk is only allocated when the guest first
+//! writes a non-zero value into it; until then it reads as zero. A VM
+//! configured with 1 GiB of RAM therefore only costs the host what the
+//! guest actually touches, which lets several VMs overcommit host memory.
+//!
+//! Pages the guest hands back through the balloon device
+//! ([`crate::devices::balloon`]) are discarded: their contents become zero
+//! and a chunk whose pages have all been discarded is freed again.
 //!
-//! `FlatMemory` is the simplest guest RAM implementation: a single zeroed
-//! allocation that maps guest physical addresses 1:1 to host offsets.
 //! Out-of-bounds reads return `0xFF` (floating bus), matching real x86
 //! hardware behavior for accesses to unmapped physical address space.
 //! Out-of-bounds writes are silently ignored.
 
+use alloc::boxed::Box;
 use alloc::vec;
 use alloc::vec::Vec;
 
 use super::MemoryBus;
 use crate::error::Result;
 
-/// Flat, contiguous guest physical memory.
+/// Guest page size used for ballooning.
+pub const PAGE_SIZE: usize = 4096;
+/// log2 of the backing chunk size (64 KiB).
+const CHUNK_SHIFT: usize = 16;
+/// Backing chunk size in bytes.
+const CHUNK_SIZE: usize = 1 << CHUNK_SHIFT;
+/// Guest pages per backing chunk.
+const PAGES_PER_CHUNK: usize = CHUNK_SIZE / PAGE_SIZE;
+
+/// Flat, lazily committed guest physical memory.
 ///
 /// Addresses `0..size` are valid; anything beyond is out-of-bounds.
 /// All multi-byte reads and writes use little-endian byte order,
 /// matching the x86 memory model.
 pub struct FlatMemory {
-    /// Backing storage.
-    data: Vec<u8>,
-    /// Logical size in bytes (always equals `data.len()`).
+    /// Backing chunks; `None` = never written (or released), reads as zero.
+    chunks: Vec<Option<Box<[u8]>>>,
+    /// Logical size in bytes.
     size: usize,
+    /// One bit per guest page: page was discarded by the balloon.
+    discarded: Vec<u64>,
+    /// Number of discarded pages per chunk.
+    discarded_in_chunk: Vec<u8>,
+    /// Number of allocated chunks.
+    committed_chunks: usize,
 }
 
 impl FlatMemory {
-    /// Allocate `size` bytes of zeroed guest RAM.
+    /// Create `size` bytes of zeroed guest RAM. No backing storage is
+    /// allocated until the guest writes to it.
     pub fn new(size: usize) -> Self {
+        let chunk_count = (size + CHUNK_SIZE - 1) >> CHUNK_SHIFT;
+        let page_count = (size + PAGE_SIZE - 1) / PAGE_SIZE;
+        let mut chunks = Vec::with_capacity(chunk_count);
+        chunks.resize_with(chunk_count, || None);
         FlatMemory {
-            data: vec![0u8; size],
+            chunks,
             size,
+            discarded: vec![0u64; (page_count + 63) / 64],
+            discarded_in_chunk: vec![0u8; chunk_count],
+            committed_chunks: 0,
         }
     }
 
@@ -47,22 +79,149 @@ impl FlatMemory {
             src.len(),
             self.size,
         );
-        self.data[offset..end].copy_from_slice(src);
+        self.copy_in(offset, src);
     }
 
-    /// Borrow the entire guest RAM as a byte slice.
-    pub fn as_slice(&self) -> &[u8] {
-        &self.data
+    /// Returns the size of guest RAM in bytes.
+    pub fn size(&self) -> usize {
+        self.size
     }
 
-    /// Borrow the entire guest RAM as a mutable byte slice.
-    pub fn as_mut_slice(&mut self) -> &mut [u8] {
-        &mut self.data
+    /// Host memory currently committed to guest RAM, in bytes.
+    pub fn committed(&self) -> usize {
+        self.committed_chunks * CHUNK_SIZE
     }
 
-    /// Returns the size of guest RAM in bytes.
-    pub fn size(&self) -> usize {
-        self.size
+    /// Discard guest page `pfn`: its contents become zero and its backing
+    /// chunk is released once every page in it has been discarded.
+    ///
+    /// Returns `false` if the page is out of range or already discarded.
+    pub fn discard_page(&mut self, pfn: u64) -> bool {
+        let page = pfn as usize;
+        if page >= self.size / PAGE_SIZE || self.is_discarded(page) {
+            return false;
+        }
+        self.discarded[page / 64] |= 1 << (page % 64);
+        let ci = page / PAGES_PER_CHUNK;
+        self.discarded_in_chunk[ci] += 1;
+        if self.discarded_in_chunk[ci] as usize == PAGES_PER_CHUNK {
+            if self.chunks[ci].take().is_some() {
+                self.committed_chunks -= 1;
+            }
+        } else if let Some(chunk) = self.chunks[ci].as_mut() {
+            let off = (page % PAGES_PER_CHUNK) * PAGE_SIZE;
+            chunk[off..off + PAGE_SIZE].fill(0);
+        }
+        true
+    }
+
+    /// Give a discarded page back to the guest (balloon deflate). It reads
+    /// as zero until written.
+    ///
+    /// Returns `false` if the page was not discarded.
+    pub fn reclaim_page(&mut self, pfn: u64) -> bool {
+        let page = pfn as usize;
+        if page >= self.size / PAGE_SIZE || !self.is_discarded(page) {
+            return false;
+        }
+        self.discarded[page / 64] &= !(1 << (page % 64));
+        self.discarded_in_chunk[page / PAGES_PER_CHUNK] -= 1;
+        true
+    }
+
+    /// Give every discarded page back to the guest (e.g. on VM reset).
+    pub fn reclaim_all(&mut self) {
+        self.discarded.fill(0);
+        self.discarded_in_chunk.fill(0);
+    }
+
+    fn is_discarded(&self, page: usize) -> bool {
+        self.discarded[page / 64] & (1 << (page % 64)) != 0
+    }
+
+    /// Backing chunk for `addr`, if committed.
+    #[inline]
+    fn chunk(&self, addr: usize) -> Option<&[u8]> {
+        self.chunks[addr >> CHUNK_SHIFT].as_deref()
+    }
+
+    /// Backing chunk for `addr`, committing it if necessary.
+    #[inline]
+    fn chunk_mut(&mut self, addr: usize) -> &mut [u8] {
+        let ci = addr >> CHUNK_SHIFT;
+        if self.chunks[ci].is_none() {
+            self.chunks[ci] = Some(vec![0u8; CHUNK_SIZE].into_boxed_slice());
+            self.committed_chunks += 1;
+        }
+        self.chunks[ci].as_deref_mut().unwrap()
+    }
+
+    /// Read `N` bytes at an in-bounds address.
+    #[inline]
+    fn read_n<const N: usize>(&self, a: usize) -> [u8; N] {
+        let mut out = [0u8; N];
+        let off = a & (CHUNK_SIZE - 1);
+        if off + N <= CHUNK_SIZE {
+            if let Some(chunk) = self.chunk(a) {
+                out.copy_from_slice(&chunk[off..off + N]);
+            }
+        } else {
+            self.copy_out(a, &mut out);
+        }
+        out
+    }
+
+    /// Write `N` bytes at an in-bounds address. Zero writes into an
+    /// uncommitted chunk are dropped, so a guest clearing its memory does
+    /// not commit it.
+    #[inline]
+    fn write_n<const N: usize>(&mut self, a: usize, bytes: [u8; N]) {
+        let off = a & (CHUNK_SIZE - 1);
+        if off + N <= CHUNK_SIZE {
+            if self.chunk(a).is_none() && bytes.iter().all(|&b| b == 0) {
+                return;
+            }
+            self.chunk_mut(a)[off..off + N].copy_from_slice(&bytes);
+        } else {
+            self.copy_in(a, &bytes);
+        }
+    }
+
+    /// Copy guest bytes starting at in-bounds `a` into `buf`.
+    fn copy_out(&self, mut a: usize, buf: &mut [u8]) {
+        let mut done = 0;
+        while done < buf.len() {
+            let off = a & (CHUNK_SIZE - 1);
+            let n = (CHUNK_SIZE - off).min(buf.len() - done);
+            match self.chunk(a) {
+                Some(chunk) => buf[done..done + n].copy_from_slice(&chunk[off..off + n]),
+                None => buf[done..done + n].fill(0),
+            }
+            done += n;
+            a += n;
+        }
+    }
+
+    /// Copy `src` into guest memory starting at in-bounds `a`.
+    fn copy_in(&mut self, mut a: usize, src: &[u8]) {
+        let mut done = 0;
+        while done < src.len() {
+            let off = a & (CHUNK_SIZE - 1);
+            let n = (CHUNK_SIZE - off).min(src.len() - done);
+            let part = &src[done..done + n];
+            if self.chunk(a).is_some() || part.iter().any(|&b| b != 0) {
+                self.chunk_mut(a)[off..off + n].copy_from_slice(part);
+            }
+            done += n;
+            a += n;
+        }
+    }
+
+    /// Whether `[a, a+len)` lies inside guest RAM.
+    #[inline]
+    fn in_bounds(&self, a: usize, len: usize) -> bool {
+        let end = a.wrapping_add(len);
+        end <= self.size && end >= a
     }
 }
 
@@ -72,51 +231,31 @@ impl MemoryBus for FlatMemory {
         if a >= self.size {
             return Ok(0xFF); // floating bus
         }
-        Ok(self.data[a])
+        Ok(self.chunk(a).map_or(0, |c| c[a & (CHUNK_SIZE - 1)]))
     }
 
     fn read_u16(&self, addr: u64) -> Result<u16> {
         let a = addr as usize;
-        let end = a.wrapping_add(2);
-        if end > self.size || end < a {
+        if !self.in_bounds(a, 2) {
             return Ok(0xFFFF); // floating bus
         }
-        let bytes: [u8; 2] = [self.data[a], self.data[a + 1]];
-        Ok(u16::from_le_bytes(bytes))
+        Ok(u16::from_le_bytes(self.read_n(a)))
     }
 
     fn read_u32(&self, addr: u64) -> Result<u32> {
         let a = addr as usize;
-        let end = a.wrapping_add(4);
-        if end > self.size || end < a {
+        if !self.in_bounds(a, 4) {
             return Ok(0xFFFF_FFFF); // floating bus
         }
-        let bytes: [u8; 4] = [
-            self.data[a],
-            self.data[a + 1],
-            self.data[a + 2],
-            self.data[a + 3],
-        ];
-        Ok(u32::from_le_bytes(bytes))
+        Ok(u32::from_le_bytes(self.read_n(a)))
     }
 
     fn read_u64(&self, addr: u64) -> Result<u64> {
         let a = addr as usize;
-        let end = a.wrapping_add(8);
-        if end > self.size || end < a {
+        if !self.in_bounds(a, 8) {
             return Ok(0xFFFF_FFFF_FFFF_FFFF); // floating bus
         }
-        let bytes: [u8; 8] = [
-            self.data[a],
-            self.data[a + 1],
-            self.data[a + 2],
-            self.data[a + 3],
-            self.data[a + 4],
-            self.data[a + 5],
-            self.data[a + 6],
-            self.data[a + 7],
-        ];
-        Ok(u64::from_le_bytes(bytes))
+        Ok(u64::from_le_bytes(self.read_n(a)))
     }
 
     fn write_u8(&mut self, addr: u64, val: u8) -> Result<()> {
@@ -124,73 +263,54 @@ impl MemoryBus for FlatMemory {
         if a >= self.size {
             return Ok(()); // ignore write to unmapped physical memory
         }
-        self.data[a] = val;
+        self.write_n(a, [val]);
         Ok(())
     }
 
     fn write_u16(&mut self, addr: u64, val: u16) -> Result<()> {
         let a = addr as usize;
-        let end = a.wrapping_add(2);
-        if end > self.size || end < a {
+        if !self.in_bounds(a, 2) {
             return Ok(()); // ignore write to unmapped physical memory
         }
-        let bytes = val.to_le_bytes();
-        self.data[a] = bytes[0];
-        self.data[a + 1] = bytes[1];
+        self.write_n(a, val.to_le_bytes());
         Ok(())
     }
 
     fn write_u32(&mut self, addr: u64, val: u32) -> Result<()> {
         let a = addr as usize;
-        let end = a.wrapping_add(4);
-        if end > self.size || end < a {
+        if !self.in_bounds(a, 4) {
             return Ok(()); // ignore write to unmapped physical memory
         }
-        let bytes = val.to_le_bytes();
-        self.data[a] = bytes[0];
-        self.data[a + 1] = bytes[1];
-        self.data[a + 2] = bytes[2];
-        self.data[a + 3] = bytes[3];
+        self.write_n(a, val.to_le_bytes());
         Ok(())
     }
 
     fn write_u64(&mut self, addr: u64, val: u64) -> Result<()> {
         let a = addr as usize;
-        let end = a.wrapping_add(8);
-        if end > self.size || end < a {
+        if !self.in_bounds(a, 8) {
             return Ok(()); // ignore write to unmapped physical memory
         }
-        let bytes = val.to_le_bytes();
-        self.data[a] = bytes[0];
-        self.data[a + 1] = bytes[1];
-        self.data[a + 2] = bytes[2];
-        self.data[a + 3] = bytes[3];
-        self.data[a + 4] = bytes[4];
-        self.data[a + 5] = bytes[5];
-        self.data[a + 6] = bytes[6];
-        self.data[a + 7] = bytes[7];
+        self.write_n(a, val.to_le_bytes());
         Ok(())
     }
 
     fn read_bytes(&self, addr: u64, buf: &mut [u8]) -> Result<()> {
         let a = addr as usize;
-        let end = a.wrapping_add(buf.len());
-        if end > self.size || end < a {
+        if !self.in_bounds(a, buf.len()) {
             // Fill with 0xFF for unmapped physical memory
             buf.fill(0xFF);
             return Ok(());
         }
-        buf.copy_from_slice(&self.data[a..end]);
+        self.copy_out(a, buf);
         Ok(())
     }
 
     fn write_bytes(&mut self, addr: u64, buf: &[u8]) -> Result<()> {
         let a = addr as usize;
-        let end = a.wrapping_add(buf.len());
-        if end > self.size || end < a {
+        if !self.in_bounds(a, buf.len()) {
             return Ok(()); // ignore write to unmapped physical memory
         }
-        self.data[a..end].copy_from_slice(buf);
+        self.copy_in(a, buf);
         Ok(())
     }
 }
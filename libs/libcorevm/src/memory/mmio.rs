@@ -9,6 +9,8 @@ use alloc::boxed::Box;
 use alloc::vec::Vec;
 
 use crate::error::Result;
+use crate::ioexit::IoExit;
+use crate::iotrace::IoTrace;
 
 /// Trait implemented by device models that handle MMIO accesses.
 ///
@@ -50,6 +52,10 @@ pub struct MmioDispatch {
     min_base: u64,
     /// Highest end address (base + size) across all regions.
     max_end: u64,
+    /// Access tracer owned by the VM instance (null when tracing is off).
+    pub trace: *mut IoTrace,
+    /// I/O exit state owned by the VM instance (null when unused).
+    pub exit: *mut IoExit,
 }
 
 impl MmioDispatch {
@@ -59,6 +65,8 @@ impl MmioDispatch {
             regions: Vec::new(),
             min_base: u64::MAX,
             max_end: 0,
+            trace: core::ptr::null_mut(),
+            exit: core::ptr::null_mut(),
         }
     }
 
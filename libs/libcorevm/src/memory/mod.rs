@@ -23,9 +23,11 @@ pub mod paging;
 pub mod segment;
 
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 use core::cell::UnsafeCell;
 
 use crate::error::Result;
+use crate::iotrace::{KIND_MMIO_READ, KIND_MMIO_WRITE};
 use crate::registers::{
     SegmentDescriptor, CR0_PG, CR0_WP, CR4_PAE, CR4_PSE, EFER_LMA, EFER_NXE,
 };
@@ -131,6 +133,9 @@ pub trait MemoryBus {
 ///
 /// Reads and writes are first checked against registered MMIO regions;
 /// if no MMIO region matches, the access falls through to flat RAM.
+/// Ranges of RAM can be marked read-only (ROM): guest writes to them are
+/// silently discarded, as on real hardware, while [`load_at`](Self::load_at)
+/// still fills them.
 ///
 /// `UnsafeCell` is used for the MMIO dispatch because device handlers
 /// are stateful (`read`/`write` take `&mut self`), but the `MemoryBus`
@@ -141,6 +146,8 @@ pub struct GuestMemory {
     ram: FlatMemory,
     /// MMIO region dispatcher (interior mutability for `&self` read path).
     mmio: UnsafeCell<MmioDispatch>,
+    /// Read-only RAM ranges as `(base, end)`, sorted by base.
+    rom: Vec<(u64, u64)>,
 }
 
 impl GuestMemory {
@@ -149,6 +156,7 @@ impl GuestMemory {
         GuestMemory {
             ram: FlatMemory::new(ram_size),
             mmio: UnsafeCell::new(MmioDispatch::new()),
+            rom: Vec::new(),
         }
     }
 
@@ -183,6 +191,52 @@ impl GuestMemory {
         self.mmio.get_mut().register(base, size, handler);
     }
 
+    /// Mark RAM in `[base, base+size)` read-only (`read_only` = true) or
+    /// writable again. Overlapping and adjacent read-only ranges are merged;
+    /// clearing may split a range.
+    pub fn set_read_only(&mut self, base: u64, size: u64, read_only: bool) {
+        if size == 0 {
+            return;
+        }
+        let end = base.saturating_add(size);
+        let mut ranges = Vec::with_capacity(self.rom.len() + 2);
+        for &(b, e) in &self.rom {
+            if e <= base || b >= end {
+                ranges.push((b, e));
+                continue;
+            }
+            // Keep the parts outside the new range; the overlap is either
+            // re-added below or dropped.
+            if b < base {
+                ranges.push((b, base));
+            }
+            if e > end {
+                ranges.push((end, e));
+            }
+        }
+        if read_only {
+            ranges.push((base, end));
+        }
+        ranges.sort_unstable();
+        self.rom.clear();
+        for (b, e) in ranges {
+            match self.rom.last_mut() {
+                Some(last) if b <= last.1 => last.1 = last.1.max(e),
+                _ => self.rom.push((b, e)),
+            }
+        }
+    }
+
+    /// True if any byte of `[addr, addr+len)` is read-only RAM.
+    #[inline]
+    pub fn is_read_only(&self, addr: u64, len: u64) -> bool {
+        if self.rom.is_empty() {
+            return false;
+        }
+        let end = addr.saturating_add(len);
+        self.rom.iter().any(|&(b, e)| addr < e && end > b)
+    }
+
     /// Borrow the underlying flat RAM.
     pub fn ram(&self) -> &FlatMemory {
         &self.ram
@@ -193,6 +247,77 @@ impl GuestMemory {
         &mut self.ram
     }
 
+    /// Attach (or detach with null) the MMIO access tracer.
+    pub fn set_mmio_trace(&mut self, trace: *mut crate::iotrace::IoTrace) {
+        self.mmio.get_mut().trace = trace;
+    }
+
+    /// Attach (or detach with null) the I/O exit state, which makes
+    /// accesses outside RAM and MMIO regions exit to the host.
+    pub fn set_io_exit(&mut self, exit: *mut crate::ioexit::IoExit) {
+        self.mmio.get_mut().exit = exit;
+    }
+
+    /// True if `[addr, addr+size)` lies inside guest RAM.
+    #[inline]
+    fn in_ram(&self, addr: u64, size: u8) -> bool {
+        addr.checked_add(size as u64).map_or(false, |end| end <= self.ram.size() as u64)
+    }
+
+    /// The I/O exit state, if it is armed and `addr` is a device address
+    /// (an MMIO region, or outside RAM).
+    #[inline]
+    fn exit_for(&self, addr: u64, size: u8) -> Option<&mut crate::ioexit::IoExit> {
+        let mmio = self.mmio_mut();
+        if mmio.exit.is_null() {
+            return None;
+        }
+        let exit = unsafe { &mut *mmio.exit };
+        if !exit.armed() || (mmio.find(addr).is_none() && self.in_ram(addr, size)) {
+            return None;
+        }
+        Some(exit)
+    }
+
+    /// Route a read to an MMIO handler, replaying or exiting to the host
+    /// while I/O exits are armed. `None` means the access goes to RAM.
+    #[inline]
+    fn device_read(&self, addr: u64, size: u8) -> Option<Result<u64>> {
+        let Some(exit) = self.exit_for(addr, size) else {
+            return try_mmio_read(self.mmio_mut(), addr, size);
+        };
+        if let Some(val) = exit.replay(KIND_MMIO_READ, addr, size) {
+            return Some(Ok(val));
+        }
+        Some(match try_mmio_read(self.mmio_mut(), addr, size) {
+            Some(Ok(val)) => {
+                exit.record(KIND_MMIO_READ, addr, size, val);
+                Ok(val)
+            }
+            Some(Err(e)) => Err(e),
+            None => Err(exit.exit(KIND_MMIO_READ, addr, size, 0)),
+        })
+    }
+
+    /// Write counterpart of [`device_read`](Self::device_read).
+    #[inline]
+    fn device_write(&self, addr: u64, size: u8, val: u64) -> Option<Result<()>> {
+        let Some(exit) = self.exit_for(addr, size) else {
+            return try_mmio_write(self.mmio_mut(), addr, size, val);
+        };
+        if exit.replay(KIND_MMIO_WRITE, addr, size).is_some() {
+            return Some(Ok(()));
+        }
+        Some(match try_mmio_write(self.mmio_mut(), addr, size, val) {
+            Some(Ok(())) => {
+                exit.record(KIND_MMIO_WRITE, addr, size, val);
+                Ok(())
+            }
+            Some(Err(e)) => Err(e),
+            None => Err(exit.exit(KIND_MMIO_WRITE, addr, size, val)),
+        })
+    }
+
     /// Return the number of registered MMIO regions (diagnostic).
     pub fn mmio_region_count(&self) -> usize {
         // Safety: single-threaded, non-re-entrant.
@@ -209,9 +334,16 @@ impl GuestMemory {
 ///
 /// Returns `Some(value)` if the address hit an MMIO region, `None` otherwise.
 fn try_mmio_read(mmio: &mut MmioDispatch, addr: u64, size: u8) -> Option<Result<u64>> {
+    let trace = mmio.trace;
     if let Some(region) = mmio.find(addr) {
         let offset = addr - region.base;
-        Some(region.handler.read(offset, size))
+        let res = region.handler.read(offset, size);
+        if !trace.is_null() {
+            if let Ok(val) = res {
+                unsafe { (*trace).record(crate::iotrace::KIND_MMIO_READ, addr, size, val) };
+            }
+        }
+        Some(res)
     } else {
         None
     }
@@ -226,7 +358,11 @@ fn try_mmio_write(
     size: u8,
     val: u64,
 ) -> Option<Result<()>> {
+    let trace = mmio.trace;
     if let Some(region) = mmio.find(addr) {
+        if !trace.is_null() {
+            unsafe { (*trace).record(crate::iotrace::KIND_MMIO_WRITE, addr, size, val) };
+        }
         let offset = addr - region.base;
         Some(region.handler.write(offset, size, val))
     } else {
@@ -236,58 +372,70 @@ fn try_mmio_write(
 
 impl MemoryBus for GuestMemory {
     fn read_u8(&self, addr: u64) -> Result<u8> {
-        if let Some(res) = try_mmio_read(self.mmio_mut(), addr, 1) {
+        if let Some(res) = self.device_read(addr, 1) {
             return Ok(res? as u8);
         }
         self.ram.read_u8(addr)
     }
 
     fn read_u16(&self, addr: u64) -> Result<u16> {
-        if let Some(res) = try_mmio_read(self.mmio_mut(), addr, 2) {
+        if let Some(res) = self.device_read(addr, 2) {
             return Ok(res? as u16);
         }
         self.ram.read_u16(addr)
     }
 
     fn read_u32(&self, addr: u64) -> Result<u32> {
-        if let Some(res) = try_mmio_read(self.mmio_mut(), addr, 4) {
+        if let Some(res) = self.device_read(addr, 4) {
             return Ok(res? as u32);
         }
         self.ram.read_u32(addr)
     }
 
     fn read_u64(&self, addr: u64) -> Result<u64> {
-        if let Some(res) = try_mmio_read(self.mmio_mut(), addr, 8) {
+        if let Some(res) = self.device_read(addr, 8) {
             return res;
         }
         self.ram.read_u64(addr)
     }
 
     fn write_u8(&mut self, addr: u64, val: u8) -> Result<()> {
-        if let Some(res) = try_mmio_write(self.mmio_mut(), addr, 1, val as u64) {
+        if let Some(res) = self.device_write(addr, 1, val as u64) {
             return res;
         }
+        if self.is_read_only(addr, 1) {
+            return Ok(());
+        }
         self.ram.write_u8(addr, val)
     }
 
     fn write_u16(&mut self, addr: u64, val: u16) -> Result<()> {
-        if let Some(res) = try_mmio_write(self.mmio_mut(), addr, 2, val as u64) {
+        if let Some(res) = self.device_write(addr, 2, val as u64) {
             return res;
         }
+        if self.is_read_only(addr, 2) {
+            return Ok(());
+        }
         self.ram.write_u16(addr, val)
     }
 
     fn write_u32(&mut self, addr: u64, val: u32) -> Result<()> {
-        if let Some(res) = try_mmio_write(self.mmio_mut(), addr, 4, val as u64) {
+        if let Some(res) = self.device_write(addr, 4, val as u64) {
             return res;
         }
+        if self.is_read_only(addr, 4) {
+            return Ok(());
+        }
         self.ram.write_u32(addr, val)
     }
 
     fn write_u64(&mut self, addr: u64, val: u64) -> Result<()> {
-        if let Some(res) = try_mmio_write(self.mmio_mut(), addr, 8, val) {
+        if let Some(res) = self.device_write(addr, 8, val) {
             return res;
         }
+        if self.is_read_only(addr, 8) {
+            return Ok(());
+        }
         self.ram.write_u64(addr, val)
     }
 
@@ -298,12 +446,25 @@ impl MemoryBus for GuestMemory {
     fn write_bytes(&mut self, addr: u64, buf: &[u8]) -> Result<()> {
         // Bulk writes bypass MMIO for performance. Device models that need
         // bulk writes should use their own handler interface.
+        if self.is_read_only(addr, buf.len() as u64) {
+            // Write around the read-only bytes.
+            for (i, &b) in buf.iter().enumerate() {
+                let a = addr + i as u64;
+                if !self.is_read_only(a, 1) {
+                    self.ram.write_u8(a, b)?;
+                }
+            }
+            return Ok(());
+        }
         self.ram.write_bytes(addr, buf)
     }
 }
 
 // ── Mmu ──
 
+/// Physical address line 20, masked while the A20 gate is closed.
+const A20_BIT: u64 = 1 << 20;
+
 /// Memory Management Unit state derived from control registers.
 ///
 /// Tracks the paging mode and protection flags that affect address
@@ -322,6 +483,11 @@ pub struct Mmu {
     pub wp: bool,
     /// EFER.NXE — no-execute enable.
     pub nxe: bool,
+    /// A20 gate. When false, bit 20 of every physical address is forced to
+    /// zero (the 8086 wrap-around at 1 MiB). Driven by System Control Port
+    /// A (0x92) and the keyboard controller output port through a raw
+    /// pointer to this field.
+    pub a20_enabled: bool,
     /// Cached CR0/CR4/EFER for change detection.
     cached_cr0: u64,
     /// Cached CR4 value.
@@ -340,6 +506,7 @@ impl Mmu {
             long_mode: false,
             wp: false,
             nxe: false,
+            a20_enabled: true,
             cached_cr0: 0,
             cached_cr4: 0,
             cached_efer: 0,
@@ -397,8 +564,9 @@ impl Mmu {
 
     /// Translate a linear (virtual) address to a physical address via paging.
     ///
-    /// If paging is disabled, the linear address is returned unchanged.
-    /// Otherwise, the appropriate page-table walker is invoked.
+    /// If paging is disabled, the linear address is the physical address.
+    /// Otherwise, the appropriate page-table walker is invoked. Either way
+    /// the result goes through the A20 gate.
     ///
     /// # Parameters
     ///
@@ -417,8 +585,14 @@ impl Mmu {
         mem: &dyn MemoryBus,
     ) -> Result<u64> {
         if !self.paging_enabled {
-            return Ok(linear);
+            return Ok(self.a20(linear));
         }
-        walk_page_tables(linear, cr3, access, cpl, self, mem)
+        walk_page_tables(linear, cr3, access, cpl, self, mem).map(|phys| self.a20(phys))
+    }
+
+    /// Apply the A20 gate to a physical address.
+    #[inline]
+    fn a20(&self, phys: u64) -> u64 {
+        if self.a20_enabled { phys } else { phys & !A20_BIT }
     }
 }
@@ -0,0 +1,155 @@
+//! Model-specific registers.
+//!
+//! RDMSR and WRMSR go through [`read`] and [`write`], which implement the
+//! MSRs a 32- or 64-bit guest touches during early boot, with the
+//! architectural checks: #GP(0) outside ring 0, on reserved bits, on
+//! non-canonical addresses and on read-only registers.
+//!
+//! | MSR | Index | Notes |
+//! |-----|-------|-------|
+//! | TSC | 0x10 | also advanced by RDTSC |
+//! | APIC_BASE | 0x1B | BSP, enabled at 0xFEE00000; x2APIC mode rejected |
+//! | FEATURE_CONTROL | 0x3A | locked on reset, writes fault |
+//! | BIOS_SIGN_ID | 0x8B | plain storage |
+//! | SYSENTER_CS/ESP/EIP | 0x174-0x176 | ESP/EIP must be canonical |
+//! | MCG_CAP | 0x179 | no banks, read-only |
+//! | MCG_STATUS | 0x17A | only 0 may be written |
+//! | MISC_ENABLE | 0x1A0 | fast strings on reset |
+//! | PAT | 0x277 | each entry must be a valid memory type |
+//! | EFER | 0xC0000080 | SCE, LME, NXE writable; LMA is read-only |
+//! | STAR | 0xC0000081 | plain storage |
+//! | LSTAR/CSTAR | 0xC0000082-83 | must be canonical |
+//! | SFMASK | 0xC0000084 | bits 63:32 reserved |
+//! | FS/GS base | 0xC0000100-01 | alias the segment bases |
+//! | KernelGSBase | 0xC0000102 | swapped by SWAPGS |
+//!
+//! Any other index is offered to the host handler set with
+//! `corevm_set_msr_handler`, and raises #GP(0) if there is none or it
+//! declines the access.
+
+use crate::cpu::Cpu;
+use crate::error::{Result, VmError};
+use crate::registers::*;
+
+/// Host MSR handler: `(userdata, index, is_write, value)`. For a read the
+/// handler stores the result in `*value`; for a write `*value` holds the
+/// guest's value. Returns nonzero if it serviced the access, 0 to raise
+/// #GP(0). Runs inside `corevm_run` and must not call back into the VM.
+pub type MsrCb = extern "C" fn(u64, u32, u32, *mut u64) -> u32;
+
+/// EFER bits a guest may set (LMA is accepted but ignored).
+const EFER_WRITABLE: u64 = EFER_SCE | EFER_LME | EFER_LMA | EFER_NXE;
+
+/// APIC_BASE reserved bits: 7:0, 9, x2APIC enable (10) and bits above
+/// the 36-bit physical address width.
+const APIC_BASE_RESERVED: u64 = 0xFF | (1 << 9) | (1 << 10) | !((1u64 << 36) - 1);
+
+/// Read MSR `index` for RDMSR.
+pub fn read(cpu: &Cpu, index: u32) -> Result<u64> {
+    check_cpl(cpu)?;
+    let regs = &cpu.regs;
+    match index {
+        MSR_FS_BASE => Ok(regs.segment(SegReg::Fs).base),
+        MSR_GS_BASE => Ok(regs.segment(SegReg::Gs).base),
+        MSR_MCG_CAP => Ok(0),
+        MSR_TSC | MSR_APIC_BASE | MSR_FEATURE_CONTROL | MSR_BIOS_SIGN_ID
+        | MSR_SYSENTER_CS | MSR_SYSENTER_ESP | MSR_SYSENTER_EIP | MSR_MCG_STATUS
+        | MSR_MISC_ENABLE | MSR_PAT | MSR_EFER | MSR_STAR | MSR_LSTAR | MSR_CSTAR
+        | MSR_SFMASK | MSR_KERNEL_GS_BASE => Ok(regs.read_msr(index)),
+        _ => host(cpu, index, false, 0),
+    }
+}
+
+/// Write `val` to MSR `index` for WRMSR.
+pub fn write(cpu: &mut Cpu, index: u32, val: u64) -> Result<()> {
+    check_cpl(cpu)?;
+    match index {
+        MSR_EFER => {
+            let old = cpu.regs.read_msr(MSR_EFER);
+            if val & !EFER_WRITABLE != 0 {
+                return Err(VmError::GeneralProtection(0));
+            }
+            // LME cannot change while paging is enabled.
+            if (val ^ old) & EFER_LME != 0 && cpu.regs.cr0 & CR0_PG != 0 {
+                return Err(VmError::GeneralProtection(0));
+            }
+            cpu.regs.write_msr(MSR_EFER, (val & !EFER_LMA) | (old & EFER_LMA));
+            cpu.update_mode();
+        }
+        MSR_FS_BASE | MSR_GS_BASE | MSR_KERNEL_GS_BASE | MSR_LSTAR | MSR_CSTAR
+        | MSR_SYSENTER_ESP | MSR_SYSENTER_EIP => {
+            if !is_canonical(val) {
+                return Err(VmError::GeneralProtection(0));
+            }
+            cpu.regs.write_msr(index, val);
+            match index {
+                MSR_FS_BASE => cpu.regs.segment_mut(SegReg::Fs).base = val,
+                MSR_GS_BASE => cpu.regs.segment_mut(SegReg::Gs).base = val,
+                _ => {}
+            }
+        }
+        MSR_SFMASK => {
+            if val >> 32 != 0 {
+                return Err(VmError::GeneralProtection(0));
+            }
+            cpu.regs.write_msr(index, val);
+        }
+        MSR_APIC_BASE => {
+            if val & APIC_BASE_RESERVED != 0 {
+                return Err(VmError::GeneralProtection(0));
+            }
+            cpu.regs.write_msr(index, val);
+        }
+        MSR_PAT => {
+            // Valid types: UC(0), WC(1), WT(4), WP(5), WB(6), UC-(7).
+            if (0..8).any(|i| matches!((val >> (i * 8)) as u8, 2 | 3 | 8..=0xFF)) {
+                return Err(VmError::GeneralProtection(0));
+            }
+            cpu.regs.write_msr(index, val);
+        }
+        MSR_FEATURE_CONTROL => {
+            if cpu.regs.read_msr(index) & FEATURE_CONTROL_DEFAULT != 0 {
+                return Err(VmError::GeneralProtection(0));
+            }
+            cpu.regs.write_msr(index, val);
+        }
+        MSR_MCG_CAP => return Err(VmError::GeneralProtection(0)),
+        MSR_MCG_STATUS => {
+            if val != 0 {
+                return Err(VmError::GeneralProtection(0));
+            }
+            cpu.regs.write_msr(index, val);
+        }
+        MSR_TSC | MSR_BIOS_SIGN_ID | MSR_SYSENTER_CS | MSR_MISC_ENABLE | MSR_STAR => {
+            cpu.regs.write_msr(index, val);
+        }
+        _ => {
+            host(cpu, index, true, val)?;
+        }
+    }
+    Ok(())
+}
+
+/// RDMSR and WRMSR are ring-0 only.
+fn check_cpl(cpu: &Cpu) -> Result<()> {
+    if cpu.regs.cpl != 0 {
+        return Err(VmError::GeneralProtection(0));
+    }
+    Ok(())
+}
+
+/// Whether `addr` is canonical for 48-bit linear addresses.
+fn is_canonical(addr: u64) -> bool {
+    (((addr as i64) << 16) >> 16) as u64 == addr
+}
+
+/// Offer an unimplemented MSR to the host handler.
+fn host(cpu: &Cpu, index: u32, write: bool, val: u64) -> Result<u64> {
+    if let Some((cb, userdata)) = cpu.msr_handler {
+        let mut value = val;
+        if cb(userdata, index, write as u32, &mut value) != 0 {
+            return Ok(value);
+        }
+    }
+    Err(VmError::GeneralProtection(0))
+}
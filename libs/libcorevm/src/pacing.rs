@@ -0,0 +1,121 @@
+//! Run-loop pacing for time-budgeted execution (`corevm_run_for_micros`).
+//!
+//! Frontends that drive the VM with `corevm_run(max_instructions)` have to
+//! guess how many instructions fit into a frame: too few and the guest runs
+//! slowly, too many and the frontend stutters. The [`Pacer`] instead measures
+//! how many guest instructions this host executes per millisecond and turns
+//! a time budget into instruction batches of a fraction of a millisecond
+//! each, so timer interrupts are delivered with low latency.
+//!
+//! Virtual time is derived from the work done: a batch of N instructions
+//! advances the guest clock by N / rate. While the guest idles in HLT the
+//! clock jumps straight to the next timer interrupt and the host thread
+//! sleeps for that long instead of spinning. Either way the guest clock
+//! follows the host clock, so guest timers and frame pacing stay consistent.
+//! The PIT is ticked from the virtual clock at its real 1.193182 MHz rate.
+
+/// PIT input clock in Hz.
+pub const PIT_HZ: u64 = 1_193_182;
+
+/// Instructions per millisecond assumed before the first measurement.
+const INITIAL_RATE: u64 = 20_000;
+
+/// Lower / upper bound of a batch, in instructions.
+const MIN_BATCH: u64 = 1_000;
+const MAX_BATCH: u64 = 2_000_000;
+
+/// Guest time covered by one batch, in microseconds.
+const BATCH_US: u64 = 250;
+
+/// Busy host time over which one rate sample is taken, in milliseconds.
+const SAMPLE_MS: u32 = 20;
+
+/// Busy host time after which the run loop yields the CPU, in milliseconds.
+pub const YIELD_MS: u32 = 10;
+
+/// Execution rate estimate and virtual clock of one VM.
+#[derive(Debug)]
+pub struct Pacer {
+    /// Measured guest instructions per host millisecond.
+    rate: u64,
+    /// Instructions executed in the current sample.
+    sample_insns: u64,
+    /// Busy host time of the current sample, in milliseconds.
+    sample_ms: u32,
+    /// Virtual time since the VM was created, in microseconds.
+    pub virtual_us: u64,
+    /// PIT time not yet turned into whole ticks (µs × `PIT_HZ`, < 1 tick).
+    pit_frac: u64,
+    /// Idle time the host has not slept off yet, in microseconds.
+    idle_debt_us: u64,
+    /// Exit reason code of the last `corevm_run_for_micros` call.
+    pub last_exit: u32,
+}
+
+impl Pacer {
+    pub fn new() -> Self {
+        Pacer {
+            rate: INITIAL_RATE,
+            sample_insns: 0,
+            sample_ms: 0,
+            virtual_us: 0,
+            pit_frac: 0,
+            idle_debt_us: 0,
+            last_exit: 0,
+        }
+    }
+
+    /// Measured guest instructions per host millisecond.
+    pub fn rate(&self) -> u64 {
+        self.rate
+    }
+
+    /// Size of the next batch when `remaining_us` of the budget are left.
+    pub fn batch(&self, remaining_us: u64) -> u64 {
+        let batch = (self.rate * BATCH_US / 1000).clamp(MIN_BATCH, MAX_BATCH);
+        batch.min(self.rate * remaining_us / 1000).max(MIN_BATCH)
+    }
+
+    /// Account a finished batch of `insns` instructions that took
+    /// `elapsed_ms` of host time. Returns the virtual time it covers, in
+    /// microseconds.
+    ///
+    /// The host clock only has millisecond resolution, so single batches
+    /// mostly measure 0 or 1 ms; summed over a sample the rounding evens out.
+    pub fn record(&mut self, insns: u64, elapsed_ms: u32) -> u64 {
+        self.sample_insns += insns;
+        self.sample_ms += elapsed_ms;
+        if self.sample_ms >= SAMPLE_MS {
+            let sample = (self.sample_insns / self.sample_ms as u64).max(1);
+            self.rate = ((self.rate * 3 + sample) / 4).max(1);
+            self.sample_insns = 0;
+            self.sample_ms = 0;
+        }
+        let us = insns * 1000 / self.rate;
+        self.virtual_us += us;
+        us
+    }
+
+    /// Whole PIT ticks covered by `us` more microseconds of virtual time.
+    pub fn pit_ticks(&mut self, us: u64) -> u64 {
+        self.pit_frac += us * PIT_HZ;
+        let ticks = self.pit_frac / 1_000_000;
+        self.pit_frac %= 1_000_000;
+        ticks
+    }
+
+    /// Account `us` microseconds the guest spent idle. Returns how many
+    /// whole milliseconds the host should sleep now.
+    pub fn idle(&mut self, us: u64) -> u32 {
+        self.virtual_us += us;
+        self.idle_debt_us += us;
+        let ms = self.idle_debt_us / 1000;
+        self.idle_debt_us %= 1000;
+        ms as u32
+    }
+}
+
+/// Virtual time covered by `ticks` PIT ticks, in microseconds (rounded up).
+pub fn ticks_to_us(ticks: u64) -> u64 {
+    (ticks * 1_000_000 + PIT_HZ - 1) / PIT_HZ
+}
@@ -246,6 +246,37 @@ pub const MSR_GS_BASE: u32 = 0xC000_0101;
 pub const MSR_KERNEL_GS_BASE: u32 = 0xC000_0102;
 /// Time Stamp Counter.
 pub const MSR_TSC: u32 = 0x0000_0010;
+/// Local APIC base address and enable bits.
+pub const MSR_APIC_BASE: u32 = 0x0000_001B;
+/// VMX/SMX feature control (lock bit 0).
+pub const MSR_FEATURE_CONTROL: u32 = 0x0000_003A;
+/// Microcode update signature.
+pub const MSR_BIOS_SIGN_ID: u32 = 0x0000_008B;
+/// SYSENTER target CS.
+pub const MSR_SYSENTER_CS: u32 = 0x0000_0174;
+/// SYSENTER target RSP.
+pub const MSR_SYSENTER_ESP: u32 = 0x0000_0175;
+/// SYSENTER target RIP.
+pub const MSR_SYSENTER_EIP: u32 = 0x0000_0176;
+/// Machine-check capabilities (bank count in bits 7:0).
+pub const MSR_MCG_CAP: u32 = 0x0000_0179;
+/// Machine-check status.
+pub const MSR_MCG_STATUS: u32 = 0x0000_017A;
+/// Miscellaneous feature enables.
+pub const MSR_MISC_ENABLE: u32 = 0x0000_01A0;
+/// Page Attribute Table.
+pub const MSR_PAT: u32 = 0x0000_0277;
+
+// ── MSR reset values ──
+
+/// APIC base on reset: 0xFEE00000, BSP (bit 8), global enable (bit 11).
+pub const APIC_BASE_DEFAULT: u64 = 0xFEE0_0000 | (1 << 8) | (1 << 11);
+/// PAT on reset: WB, WT, UC-, UC repeated.
+pub const PAT_DEFAULT: u64 = 0x0007_0406_0007_0406;
+/// MISC_ENABLE on reset: fast string operations (bit 0).
+pub const MISC_ENABLE_DEFAULT: u64 = 1 << 0;
+/// FEATURE_CONTROL on reset: locked with VMX off, as firmware leaves it.
+pub const FEATURE_CONTROL_DEFAULT: u64 = 1 << 0;
 
 // ── EFER bits ──
 
@@ -340,6 +371,10 @@ impl RegisterFile {
             efer: 0,
             cpl: 0,
         };
+        regs.msr.insert(MSR_APIC_BASE, APIC_BASE_DEFAULT);
+        regs.msr.insert(MSR_PAT, PAT_DEFAULT);
+        regs.msr.insert(MSR_MISC_ENABLE, MISC_ENABLE_DEFAULT);
+        regs.msr.insert(MSR_FEATURE_CONTROL, FEATURE_CONTROL_DEFAULT);
         // EDX contains processor identification on reset (we report a generic P6)
         regs.gpr[GprIndex::Rdx as usize] = 0x0000_0600;
         // DR6 initial value: all breakpoint conditions clear
@@ -479,12 +514,22 @@ impl RegisterFile {
     }
 
     /// Load a segment register in real mode (base = selector << 4).
+    ///
+    /// As on hardware, the cached limit (and its granularity / D-B size) is
+    /// kept: a limit loaded in protected mode survives the switch back to
+    /// real mode, which is what big real mode ("unreal mode") relies on.
     pub fn load_segment_real(&mut self, reg: SegReg, selector: u16) {
-        if reg == SegReg::Cs {
-            self.seg[reg as usize] = SegmentDescriptor::real_mode_code(selector);
+        let old = self.seg[reg as usize];
+        let mut desc = if reg == SegReg::Cs {
+            SegmentDescriptor::real_mode_code(selector)
         } else {
-            self.seg[reg as usize] = SegmentDescriptor::real_mode(selector);
-        }
+            SegmentDescriptor::real_mode(selector)
+        };
+        desc.limit = old.limit;
+        desc.flags = old.flags & 0x0C;
+        desc.granularity = old.granularity;
+        desc.big = old.big;
+        self.seg[reg as usize] = desc;
     }
 
     // ── Stack pointer helpers ──
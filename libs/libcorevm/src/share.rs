@@ -0,0 +1,699 @@
+//! Host directory sharing — a FAT16 disk generated from a host folder.
+//!
+//! [`corevm_share_directory`](crate::corevm_share_directory) walks a host
+//! directory and lays it out as a partitioned FAT16 image, which is attached
+//! as the master drive of the secondary IDE channel (0x170/0x376). Any guest
+//! with a FAT driver can mount it; no guest-side agent is needed.
+//!
+//! The image is a snapshot: host changes made after sharing are not visible
+//! until the directory is shared again. A writable share leaves free space
+//! for the guest, and [`HostShare::sync`] copies new and modified files (and
+//! new directories) from the image back to the host. Deletions and renames
+//! inside the guest are not propagated, so host files are never removed.
+//!
+//! # Image layout
+//!
+//! | LBA | Content |
+//! |-----|---------|
+//! | 0 | MBR, one FAT16 (LBA) partition |
+//! | 2048 | Boot sector / BPB |
+//! | +1 | FAT #1, FAT #2 |
+//! | +FATs | Root directory (512 entries) |
+//! | +32 | Data clusters, allocated contiguously in tree order |
+//!
+//! Names that are not valid upper-case 8.3 names get VFAT long-name entries
+//! with a `NAME~N.EXT` short alias.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+const SECTOR_SIZE: usize = 512;
+/// First sector of the FAT partition (1 MiB aligned).
+const PART_START: usize = 2048;
+const RESERVED_SECTORS: usize = 1;
+const NUM_FATS: usize = 2;
+const ROOT_ENTRIES: usize = 512;
+const ROOT_SECTORS: usize = ROOT_ENTRIES * 32 / SECTOR_SIZE;
+/// Cluster count range that identifies a volume as FAT16.
+const MIN_CLUSTERS: usize = 4085;
+const MAX_CLUSTERS: usize = 65524;
+/// Free space added to a writable share, at least this much...
+const MIN_HEADROOM: usize = 16 * 1024 * 1024;
+/// ...or this fraction (1/n) of the shared data.
+const HEADROOM_DIV: usize = 4;
+
+const ATTR_DIRECTORY: u8 = 0x10;
+const ATTR_ARCHIVE: u8 = 0x20;
+const ATTR_VOLUME_ID: u8 = 0x08;
+const ATTR_LFN: u8 = 0x0F;
+/// UTF-16 units per long-name entry.
+const LFN_CHARS: usize = 13;
+/// Byte offsets of the name characters inside a long-name entry.
+const LFN_OFFSETS: [usize; LFN_CHARS] = [1, 3, 5, 7, 9, 14, 16, 18, 20, 22, 24, 28, 30];
+
+/// `readdir` entry type of a directory.
+const DT_DIR: u8 = 1;
+/// Maximum entries read from one host directory.
+const MAX_DIR_ENTRIES: usize = 1024;
+
+/// Why a directory could not be shared.
+///
+/// `corevm_share_directory` returns the negated [`code`](ShareError::code).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareError {
+    /// The path does not exist or is not a directory.
+    NotFound,
+    /// The contents do not fit in a FAT16 volume (about 2 GiB).
+    TooLarge,
+    /// The top-level directory has more entries than the FAT16 root holds.
+    RootFull,
+    /// A host file could not be read.
+    Io,
+}
+
+impl ShareError {
+    /// Positive error code (1-4).
+    pub fn code(&self) -> i32 {
+        match self {
+            ShareError::NotFound => 1,
+            ShareError::TooLarge => 2,
+            ShareError::RootFull => 3,
+            ShareError::Io => 4,
+        }
+    }
+}
+
+/// A host directory currently exposed to the guest.
+pub struct HostShare {
+    root: String,
+    writable: bool,
+}
+
+/// One file or directory of the shared tree. Index 0 is the root.
+struct Node {
+    name: String,
+    /// Host path.
+    path: String,
+    /// 8.3 directory entry name (space padded).
+    short: [u8; 11],
+    /// Long-name entries preceding the short entry (0 = plain 8.3 name).
+    lfn_slots: usize,
+    is_dir: bool,
+    size: usize,
+    mtime: u32,
+    children: Vec<usize>,
+    first_cluster: usize,
+    clusters: usize,
+}
+
+impl HostShare {
+    /// Build the disk image for `root`. Returns the share and the image.
+    pub fn build(root: &str, writable: bool) -> Result<(HostShare, Vec<u8>), ShareError> {
+        let root = root.trim_end_matches('/');
+        let root = if root.is_empty() { "/" } else { root };
+        let mut st = [0u32; 7];
+        if libsyscall::stat(root, &mut st) != 0 || st[0] as u8 != DT_DIR {
+            return Err(ShareError::NotFound);
+        }
+
+        let mut nodes = vec![Node::new(String::new(), String::from(root), true, 0, st[6])];
+        scan(&mut nodes, 0);
+        if dir_entries(&nodes, 0) > ROOT_ENTRIES {
+            return Err(ShareError::RootFull);
+        }
+
+        let image = Layout::choose(&nodes, writable)
+            .ok_or(ShareError::TooLarge)?
+            .write(&mut nodes)?;
+        let share = HostShare { root: String::from(root), writable };
+        Ok((share, image))
+    }
+
+    /// Whether guest writes are accepted (and can be synced back).
+    pub fn writable(&self) -> bool {
+        self.writable
+    }
+
+    /// Copy files and directories the guest created or modified in `image`
+    /// back to the host directory. Returns the number of files written, or
+    /// `None` if the image no longer holds a FAT16 volume (e.g. the guest
+    /// reformatted it) or the share is read-only.
+    pub fn sync(&self, image: &[u8]) -> Option<u32> {
+        if !self.writable {
+            return None;
+        }
+        let vol = Volume::open(image)?;
+        let mut written = 0;
+        vol.sync_dir(None, &self.root, &mut written);
+        Some(written)
+    }
+}
+
+impl Node {
+    fn new(name: String, path: String, is_dir: bool, size: usize, mtime: u32) -> Self {
+        Node {
+            name,
+            path,
+            short: [b' '; 11],
+            lfn_slots: 0,
+            is_dir,
+            size,
+            mtime,
+            children: Vec::new(),
+            first_cluster: 0,
+            clusters: 0,
+        }
+    }
+}
+
+// ── Host tree scan ──
+
+/// Read the children of directory node `idx` (recursively) into `nodes`.
+fn scan(nodes: &mut Vec<Node>, idx: usize) {
+    let dir = nodes[idx].path.clone();
+    let mut path_buf = Vec::with_capacity(dir.len() + 1);
+    path_buf.extend_from_slice(dir.as_bytes());
+    path_buf.push(0);
+    let mut buf = vec![0u8; 64 * MAX_DIR_ENTRIES];
+    let count = libsyscall::readdir(&path_buf, &mut buf);
+    if count == u32::MAX {
+        return;
+    }
+
+    let mut taken: Vec<[u8; 11]> = Vec::new();
+    for raw in buf.chunks_exact(64).take((count as usize).min(MAX_DIR_ENTRIES)) {
+        let name_len = (raw[1] as usize).min(56);
+        let name = match core::str::from_utf8(&raw[8..8 + name_len]) {
+            Ok(n) if !n.is_empty() && n != "." && n != ".." => n,
+            _ => continue,
+        };
+        let path = child_path(&dir, name);
+        let is_dir = raw[0] == DT_DIR;
+        let mut st = [0u32; 7];
+        let mtime = if libsyscall::stat(&path, &mut st) == 0 { st[6] } else { 0 };
+        let size = if is_dir { 0 } else { u32::from_le_bytes([raw[4], raw[5], raw[6], raw[7]]) as usize };
+
+        let mut node = Node::new(String::from(name), path, is_dir, size, mtime);
+        let (short, needs_lfn) = short_name(name, &taken);
+        taken.push(short);
+        node.short = short;
+        node.lfn_slots = if needs_lfn { name.encode_utf16().count().div_ceil(LFN_CHARS) } else { 0 };
+        let child = nodes.len();
+        nodes.push(node);
+        nodes[idx].children.push(child);
+        if is_dir {
+            scan(nodes, child);
+        }
+    }
+}
+
+fn child_path(parent: &str, name: &str) -> String {
+    if parent == "/" { format!("/{}", name) } else { format!("{}/{}", parent, name) }
+}
+
+/// Directory entries (32 bytes each) needed by directory node `idx`.
+fn dir_entries(nodes: &[Node], idx: usize) -> usize {
+    let own = if idx == 0 { 0 } else { 2 }; // "." and ".."
+    own + nodes[idx].children.iter().map(|&c| 1 + nodes[c].lfn_slots).sum::<usize>()
+}
+
+// ── 8.3 names ──
+
+fn is_short_char(c: u8) -> bool {
+    c.is_ascii_uppercase() || c.is_ascii_digit() || b"$%'-_@~`!(){}^#&".contains(&c)
+}
+
+/// Generate a unique 8.3 name for `name` among `taken`. The flag is set if
+/// the name does not round-trip and needs long-name entries.
+fn short_name(name: &str, taken: &[[u8; 11]]) -> ([u8; 11], bool) {
+    let clean = |part: &str| -> Vec<u8> {
+        part.bytes()
+            .filter(|&c| c != b' ' && c != b'.')
+            .map(|c| c.to_ascii_uppercase())
+            .map(|c| if is_short_char(c) { c } else { b'_' })
+            .collect()
+    };
+    let stem = name.trim_start_matches('.');
+    let (base, ext) = match stem.rfind('.') {
+        Some(dot) => (&stem[..dot], &stem[dot + 1..]),
+        None => (stem, ""),
+    };
+    let base = clean(base);
+    let ext = clean(ext);
+
+    let mut short = [b' '; 11];
+    for (d, &c) in short[8..].iter_mut().zip(ext.iter()) {
+        *d = c;
+    }
+    let exact = !base.is_empty() && base.len() <= 8 && ext.len() <= 3
+        && name.bytes().all(|c| c == b'.' || is_short_char(c))
+        && name.bytes().filter(|&c| c == b'.').count() <= 1
+        && !name.starts_with('.');
+    if exact {
+        short[..base.len()].copy_from_slice(&base);
+        if !taken.contains(&short) {
+            return (short, false);
+        }
+    }
+
+    // Numeric tail: BASE~N, shortening the base as N grows.
+    for n in 1u32.. {
+        let tail = format!("~{}", n);
+        let keep = base.len().min(8 - tail.len());
+        short[..8].fill(b' ');
+        short[..keep].copy_from_slice(&base[..keep]);
+        short[keep..keep + tail.len()].copy_from_slice(tail.as_bytes());
+        if keep == 0 {
+            short[0] = b'_';
+        }
+        if !taken.contains(&short) {
+            break;
+        }
+    }
+    (short, true)
+}
+
+fn lfn_checksum(short: &[u8; 11]) -> u8 {
+    short.iter().fold(0u8, |sum, &c| ((sum & 1) << 7).wrapping_add(sum >> 1).wrapping_add(c))
+}
+
+// ── Image layout ──
+
+/// Geometry of the generated volume.
+struct Layout {
+    sectors_per_cluster: usize,
+    clusters: usize,
+    fat_sectors: usize,
+}
+
+impl Layout {
+    /// Pick the smallest cluster size that fits the tree plus headroom.
+    fn choose(nodes: &[Node], writable: bool) -> Option<Layout> {
+        for shift in 0..7 {
+            let spc = 1usize << shift;
+            let cluster = spc * SECTOR_SIZE;
+            let used: usize = (1..nodes.len())
+                .map(|i| Self::node_clusters(nodes, i, cluster))
+                .sum();
+            let headroom = if writable {
+                (used * cluster / HEADROOM_DIV).max(MIN_HEADROOM).div_ceil(cluster)
+            } else {
+                0
+            };
+            let clusters = (used + headroom).max(MIN_CLUSTERS);
+            if clusters <= MAX_CLUSTERS {
+                let fat_sectors = ((clusters + 2) * 2).div_ceil(SECTOR_SIZE);
+                return Some(Layout { sectors_per_cluster: spc, clusters, fat_sectors });
+            }
+        }
+        None
+    }
+
+    fn node_clusters(nodes: &[Node], idx: usize, cluster: usize) -> usize {
+        let bytes = if nodes[idx].is_dir { dir_entries(nodes, idx) * 32 } else { nodes[idx].size };
+        bytes.div_ceil(cluster).max(if nodes[idx].is_dir { 1 } else { 0 })
+    }
+
+    fn cluster_bytes(&self) -> usize {
+        self.sectors_per_cluster * SECTOR_SIZE
+    }
+
+    /// First partition-relative sector of the root directory.
+    fn root_sector(&self) -> usize {
+        RESERVED_SECTORS + NUM_FATS * self.fat_sectors
+    }
+
+    fn data_sector(&self) -> usize {
+        self.root_sector() + ROOT_SECTORS
+    }
+
+    fn total_sectors(&self) -> usize {
+        self.data_sector() + self.clusters * self.sectors_per_cluster
+    }
+
+    /// Image byte offset of cluster `n`.
+    fn cluster_offset(&self, n: usize) -> usize {
+        (PART_START + self.data_sector() + (n - 2) * self.sectors_per_cluster) * SECTOR_SIZE
+    }
+
+    /// Allocate clusters, then emit MBR, BPB, FATs, directories and file data.
+    fn write(&self, nodes: &mut [Node]) -> Result<Vec<u8>, ShareError> {
+        let cluster = self.cluster_bytes();
+        let mut next = 2;
+        for i in 1..nodes.len() {
+            let n = Self::node_clusters(nodes, i, cluster);
+            nodes[i].clusters = n;
+            nodes[i].first_cluster = if n > 0 { next } else { 0 };
+            next += n;
+        }
+
+        let mut image = vec![0u8; (PART_START + self.total_sectors()) * SECTOR_SIZE];
+        self.write_mbr(&mut image);
+        self.write_bpb(&mut image);
+        self.write_fats(&mut image, nodes);
+
+        let root_off = (PART_START + self.root_sector()) * SECTOR_SIZE;
+        let label = volume_label(&nodes[0].path);
+        image[root_off..root_off + 11].copy_from_slice(&label);
+        image[root_off + 11] = ATTR_VOLUME_ID;
+        write_entries(&mut image[root_off + 32..], nodes, 0);
+
+        for i in 1..nodes.len() {
+            if nodes[i].clusters == 0 {
+                continue;
+            }
+            let off = self.cluster_offset(nodes[i].first_cluster);
+            if nodes[i].is_dir {
+                write_entries(&mut image[off..], nodes, i);
+            } else {
+                read_host_file(&nodes[i].path, &mut image[off..off + nodes[i].size])?;
+            }
+        }
+        Ok(image)
+    }
+
+    fn write_mbr(&self, image: &mut [u8]) {
+        let e = &mut image[0x1BE..0x1CE];
+        e[0] = 0x00; // not bootable
+        e[1..4].copy_from_slice(&[0xFE, 0xFF, 0xFF]); // CHS unused (LBA)
+        e[4] = 0x0E; // FAT16, LBA addressed
+        e[5..8].copy_from_slice(&[0xFE, 0xFF, 0xFF]);
+        e[8..12].copy_from_slice(&(PART_START as u32).to_le_bytes());
+        e[12..16].copy_from_slice(&(self.total_sectors() as u32).to_le_bytes());
+        image[510] = 0x55;
+        image[511] = 0xAA;
+    }
+
+    fn write_bpb(&self, image: &mut [u8]) {
+        let total = self.total_sectors();
+        let b = &mut image[PART_START * SECTOR_SIZE..(PART_START + 1) * SECTOR_SIZE];
+        b[0..3].copy_from_slice(&[0xEB, 0x3C, 0x90]);
+        b[3..11].copy_from_slice(b"COREVM  ");
+        b[11..13].copy_from_slice(&(SECTOR_SIZE as u16).to_le_bytes());
+        b[13] = self.sectors_per_cluster as u8;
+        b[14..16].copy_from_slice(&(RESERVED_SECTORS as u16).to_le_bytes());
+        b[16] = NUM_FATS as u8;
+        b[17..19].copy_from_slice(&(ROOT_ENTRIES as u16).to_le_bytes());
+        if total < 0x10000 {
+            b[19..21].copy_from_slice(&(total as u16).to_le_bytes());
+        } else {
+            b[32..36].copy_from_slice(&(total as u32).to_le_bytes());
+        }
+        b[21] = 0xF8; // fixed disk
+        b[22..24].copy_from_slice(&(self.fat_sectors as u16).to_le_bytes());
+        b[24..26].copy_from_slice(&63u16.to_le_bytes());
+        b[26..28].copy_from_slice(&255u16.to_le_bytes());
+        b[28..32].copy_from_slice(&(PART_START as u32).to_le_bytes());
+        b[36] = 0x80;
+        b[38] = 0x29; // extended boot signature
+        b[39..43].copy_from_slice(&libsyscall::uptime_ms().to_le_bytes());
+        b[43..54].copy_from_slice(b"SHARED     ");
+        b[54..62].copy_from_slice(b"FAT16   ");
+        b[510] = 0x55;
+        b[511] = 0xAA;
+    }
+
+    fn write_fats(&self, image: &mut [u8], nodes: &[Node]) {
+        let mut fat = vec![0u8; self.fat_sectors * SECTOR_SIZE];
+        let mut set = |n: usize, v: u16| fat[n * 2..n * 2 + 2].copy_from_slice(&v.to_le_bytes());
+        set(0, 0xFFF8);
+        set(1, 0xFFFF);
+        for node in &nodes[1..] {
+            for k in 0..node.clusters {
+                let n = node.first_cluster + k;
+                set(n, if k + 1 == node.clusters { 0xFFFF } else { (n + 1) as u16 });
+            }
+        }
+        for f in 0..NUM_FATS {
+            let off = (PART_START + RESERVED_SECTORS + f * self.fat_sectors) * SECTOR_SIZE;
+            image[off..off + fat.len()].copy_from_slice(&fat);
+        }
+    }
+}
+
+/// Volume label from the last component of the host path.
+fn volume_label(path: &str) -> [u8; 11] {
+    let mut label = [b' '; 11];
+    let name = path.rsplit('/').find(|s| !s.is_empty()).unwrap_or("SHARED");
+    for (d, c) in label.iter_mut().zip(name.bytes().filter(|&c| is_short_char(c.to_ascii_uppercase()))) {
+        *d = c.to_ascii_uppercase();
+    }
+    if label[0] == b' ' {
+        label.copy_from_slice(b"SHARED     ");
+    }
+    label
+}
+
+/// Write the directory entries of node `idx` into `out`.
+fn write_entries(out: &mut [u8], nodes: &[Node], idx: usize) {
+    let mut pos = 0;
+    let mut put = |entry: [u8; 32]| {
+        out[pos..pos + 32].copy_from_slice(&entry);
+        pos += 32;
+    };
+    let dir = &nodes[idx];
+    if idx != 0 {
+        let parent = nodes.iter().position(|n| n.children.contains(&idx)).unwrap_or(0);
+        put(short_entry(b".          ", ATTR_DIRECTORY, dir.first_cluster, 0, dir.mtime));
+        put(short_entry(b"..         ", ATTR_DIRECTORY, nodes[parent].first_cluster, 0, dir.mtime));
+    }
+    for &c in &dir.children {
+        let node = &nodes[c];
+        if node.lfn_slots > 0 {
+            let units: Vec<u16> = node.name.encode_utf16().collect();
+            let sum = lfn_checksum(&node.short);
+            for seq in (1..=node.lfn_slots).rev() {
+                let mut e = [0u8; 32];
+                e[0] = seq as u8 | if seq == node.lfn_slots { 0x40 } else { 0 };
+                e[11] = ATTR_LFN;
+                e[13] = sum;
+                for (k, &off) in LFN_OFFSETS.iter().enumerate() {
+                    let i = (seq - 1) * LFN_CHARS + k;
+                    let u = match i.cmp(&units.len()) {
+                        core::cmp::Ordering::Less => units[i],
+                        core::cmp::Ordering::Equal => 0x0000,
+                        core::cmp::Ordering::Greater => 0xFFFF,
+                    };
+                    e[off..off + 2].copy_from_slice(&u.to_le_bytes());
+                }
+                put(e);
+            }
+        }
+        let attr = if node.is_dir { ATTR_DIRECTORY } else { ATTR_ARCHIVE };
+        put(short_entry(&node.short, attr, node.first_cluster, node.size as u32, node.mtime));
+    }
+}
+
+fn short_entry(name: &[u8; 11], attr: u8, cluster: usize, size: u32, mtime: u32) -> [u8; 32] {
+    let mut e = [0u8; 32];
+    e[..11].copy_from_slice(name);
+    e[11] = attr;
+    let (date, time) = fat_datetime(mtime);
+    for off in [14, 22] {
+        e[off..off + 2].copy_from_slice(&time.to_le_bytes());
+        e[off + 2..off + 4].copy_from_slice(&date.to_le_bytes());
+    }
+    e[18..20].copy_from_slice(&date.to_le_bytes());
+    e[26..28].copy_from_slice(&(cluster as u16).to_le_bytes());
+    e[28..32].copy_from_slice(&size.to_le_bytes());
+    e
+}
+
+/// Convert a Unix timestamp to FAT `(date, time)`, clamped to 1980.
+fn fat_datetime(unix: u32) -> (u16, u16) {
+    let days = (unix / 86400) as i64;
+    let secs = unix % 86400;
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    if year < 1980 {
+        return ((1 << 5) | 1, 0);
+    }
+    let date = (((year - 1980).min(127) as u16) << 9) | ((month as u16) << 5) | day as u16;
+    let time = (((secs / 3600) as u16) << 11) | ((((secs / 60) % 60) as u16) << 5) | ((secs % 60) / 2) as u16;
+    (date, time)
+}
+
+pub(crate) fn read_host_file(path: &str, dst: &mut [u8]) -> Result<(), ShareError> {
+    let fd = libsyscall::open(path, 0);
+    if fd == u32::MAX {
+        return Err(ShareError::Io);
+    }
+    let mut done = 0;
+    while done < dst.len() {
+        let n = libsyscall::read(fd, &mut dst[done..]);
+        if n == 0 || n == u32::MAX {
+            break;
+        }
+        done += n as usize;
+    }
+    libsyscall::close(fd);
+    if done == dst.len() { Ok(()) } else { Err(ShareError::Io) }
+}
+
+// ── Sync back ──
+
+/// A FAT16 volume inside a shared image, as left by the guest.
+struct Volume<'a> {
+    image: &'a [u8],
+    cluster_bytes: usize,
+    fat: usize,
+    root: usize,
+    data: usize,
+}
+
+impl<'a> Volume<'a> {
+    fn open(image: &'a [u8]) -> Option<Volume<'a>> {
+        let part = PART_START * SECTOR_SIZE;
+        let b = image.get(part..part + SECTOR_SIZE)?;
+        let u16_at = |o: usize| u16::from_le_bytes([b[o], b[o + 1]]) as usize;
+        if b[510] != 0x55 || b[511] != 0xAA || &b[54..59] != b"FAT16" || u16_at(11) != SECTOR_SIZE {
+            return None;
+        }
+        let spc = b[13] as usize;
+        let fat = u16_at(14);
+        let root = fat + b[16] as usize * u16_at(22);
+        let data = root + (u16_at(17) * 32).div_ceil(SECTOR_SIZE);
+        if spc == 0 {
+            return None;
+        }
+        Some(Volume {
+            image,
+            cluster_bytes: spc * SECTOR_SIZE,
+            fat: part + fat * SECTOR_SIZE,
+            root: part + root * SECTOR_SIZE,
+            data: part + data * SECTOR_SIZE,
+        })
+    }
+
+    fn fat_entry(&self, n: usize) -> usize {
+        let o = self.fat + n * 2;
+        self.image.get(o..o + 2).map_or(0xFFFF, |e| u16::from_le_bytes([e[0], e[1]]) as usize)
+    }
+
+    /// Contents of the cluster chain starting at `first`, up to `limit` bytes.
+    fn read_chain(&self, first: usize, limit: usize) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut n = first;
+        // The chain length bound guards against loops in a corrupted FAT.
+        for _ in 0..MAX_CLUSTERS {
+            if !(2..0xFFF0).contains(&n) || out.len() >= limit {
+                break;
+            }
+            let off = self.data + (n - 2) * self.cluster_bytes;
+            match self.image.get(off..off + self.cluster_bytes) {
+                Some(c) => out.extend_from_slice(c),
+                None => break,
+            }
+            n = self.fat_entry(n);
+        }
+        out.truncate(limit);
+        out
+    }
+
+    /// Mirror directory `cluster` (`None` = root) into host directory `path`.
+    fn sync_dir(&self, cluster: Option<usize>, path: &str, written: &mut u32) {
+        let entries = match cluster {
+            Some(c) => self.read_chain(c, usize::MAX),
+            None => self.image[self.root..self.data.min(self.image.len())].to_vec(),
+        };
+        let mut lfn = [0u16; 20 * LFN_CHARS];
+        let mut lfn_sum: Option<u8> = None;
+        for e in entries.chunks_exact(32) {
+            match e[0] {
+                0x00 => break,
+                0xE5 => { lfn_sum = None; continue; }
+                _ => {}
+            }
+            if e[11] == ATTR_LFN {
+                let seq = (e[0] & 0x1F) as usize;
+                if e[0] & 0x40 != 0 {
+                    lfn.fill(0);
+                    lfn_sum = Some(e[13]);
+                }
+                if (1..=20).contains(&seq) {
+                    for (k, &off) in LFN_OFFSETS.iter().enumerate() {
+                        lfn[(seq - 1) * LFN_CHARS + k] = u16::from_le_bytes([e[off], e[off + 1]]);
+                    }
+                }
+                continue;
+            }
+            let short: [u8; 11] = e[..11].try_into().unwrap_or([b' '; 11]);
+            let long = lfn_sum.take().filter(|&s| s == lfn_checksum(&short));
+            if e[11] & ATTR_VOLUME_ID != 0 || e[0] == b'.' {
+                continue;
+            }
+            let name = match long {
+                Some(_) => {
+                    let end = lfn.iter().position(|&u| u == 0 || u == 0xFFFF).unwrap_or(lfn.len());
+                    char::decode_utf16(lfn[..end].iter().copied())
+                        .map(|c| c.unwrap_or('_'))
+                        .collect::<String>()
+                }
+                None => short_to_string(&short),
+            };
+            if name.is_empty() || name.contains('/') {
+                continue;
+            }
+            let host = child_path(path, &name);
+            let first = u16::from_le_bytes([e[26], e[27]]) as usize;
+            if e[11] & ATTR_DIRECTORY != 0 {
+                let mut st = [0u32; 7];
+                if libsyscall::stat(&host, &mut st) != 0 && libsyscall::mkdir(&host) == u32::MAX {
+                    continue;
+                }
+                if first >= 2 {
+                    self.sync_dir(Some(first), &host, written);
+                }
+            } else {
+                let size = u32::from_le_bytes([e[28], e[29], e[30], e[31]]) as usize;
+                let data = if size == 0 { Vec::new() } else { self.read_chain(first, size) };
+                if host_differs(&host, &data) && write_host_file(&host, &data) {
+                    *written += 1;
+                }
+            }
+        }
+    }
+}
+
+fn short_to_string(short: &[u8; 11]) -> String {
+    let base = core::str::from_utf8(&short[..8]).unwrap_or("").trim_end();
+    let ext = core::str::from_utf8(&short[8..]).unwrap_or("").trim_end();
+    if ext.is_empty() { String::from(base) } else { format!("{}.{}", base, ext) }
+}
+
+/// True if the host file at `path` is missing or its content is not `data`.
+fn host_differs(path: &str, data: &[u8]) -> bool {
+    let mut st = [0u32; 7];
+    if libsyscall::stat(path, &mut st) != 0 || st[1] as usize != data.len() {
+        return true;
+    }
+    let mut current = vec![0u8; data.len()];
+    read_host_file(path, &mut current).is_err() || current != data
+}
+
+pub(crate) fn write_host_file(path: &str, data: &[u8]) -> bool {
+    let fd = libsyscall::open(path, libsyscall::O_WRITE | libsyscall::O_CREATE | libsyscall::O_TRUNC);
+    if fd == u32::MAX {
+        return false;
+    }
+    let mut done = 0;
+    while done < data.len() {
+        let n = libsyscall::write(fd, &data[done..]);
+        if n == 0 || n == u32::MAX {
+            break;
+        }
+        done += n as usize;
+    }
+    libsyscall::close(fd);
+    done == data.len()
+}
@@ -53,6 +53,11 @@ use dynlink::{DlHandle, dl_open, dl_sym};
 /// - 2 = InstructionLimit
 /// - 3 = Breakpoint
 /// - 4 = StopRequested
+/// - 5 = Shutdown
+/// - 6 = Reset
+/// - 7 = Watchpoint
+/// - 8 = GuestExit
+/// - 9 = IoRequest
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u32)]
 pub enum ExitReason {
@@ -66,6 +71,18 @@ pub enum ExitReason {
     Breakpoint = 3,
     /// An external stop was requested via [`VmHandle::request_stop`].
     StopRequested = 4,
+    /// The guest powered itself off (ACPI S5).
+    Shutdown = 5,
+    /// The guest requested a system reset (keyboard controller or port 0xCF9).
+    Reset = 6,
+    /// A watch changed value; see [`VmHandle::watch_hit`].
+    Watchpoint = 7,
+    /// The guest reported a result through the test-exit port; see
+    /// [`VmHandle::test_exit_status`].
+    GuestExit = 8,
+    /// An unhandled device access waits for the host; see
+    /// [`VmHandle::pending_io`].
+    IoRequest = 9,
 }
 
 impl ExitReason {
@@ -80,6 +97,11 @@ impl ExitReason {
             2 => ExitReason::InstructionLimit,
             3 => ExitReason::Breakpoint,
             4 => ExitReason::StopRequested,
+            5 => ExitReason::Shutdown,
+            6 => ExitReason::Reset,
+            7 => ExitReason::Watchpoint,
+            8 => ExitReason::GuestExit,
+            9 => ExitReason::IoRequest,
             _ => ExitReason::Exception,
         }
     }
@@ -118,6 +140,228 @@ impl CpuMode {
     }
 }
 
+/// Accuracy mode for the guest's x87 transcendental instructions
+/// (FSIN, FCOS, FSINCOS, FPTAN, F2XM1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum FpuAccuracy {
+    /// Cheaper argument reduction; large operands lose precision much
+    /// like on real hardware.
+    Fast = 0,
+    /// Full-precision argument reduction over the whole operand range
+    /// (the default).
+    Precise = 1,
+}
+
+/// Operating system detected on a disk by [`VmHandle::probe_disk`].
+///
+/// These values match the `u32` codes returned by the `corevm_probe_disk`
+/// C ABI function in libcorevm.so. Detection is a best guess from the boot
+/// sectors, file system signatures and partition table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum GuestOs {
+    /// No disk attached, or it cannot be read.
+    NoDisk = 0,
+    /// No boot signature and no file system (a new disk).
+    Blank = 1,
+    /// Bootable or formatted, but not recognized.
+    Unknown = 2,
+    /// MS-DOS, PC DOS or FreeDOS.
+    Dos = 3,
+    /// Windows 95/98/ME.
+    Windows9x = 4,
+    /// Windows NT, 2000, XP and later.
+    WindowsNt = 5,
+    Linux = 6,
+    /// FreeBSD, OpenBSD or NetBSD.
+    Bsd = 7,
+    AnyOs = 8,
+}
+
+/// Recommended machine defaults for a guest (see [`GuestOs::profile`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceProfile {
+    /// Guest RAM in MiB.
+    pub ram_mb: u32,
+    /// The guest ships a driver for the E1000 network card.
+    pub e1000: bool,
+    /// The guest routes interrupts through the I/O APIC.
+    pub ioapic: bool,
+}
+
+impl GuestOs {
+    /// Convert a raw `u32` code from the C ABI into a `GuestOs`.
+    ///
+    /// Returns `GuestOs::Unknown` for any unrecognized value.
+    pub fn from_u32(val: u32) -> Self {
+        match val {
+            0 => GuestOs::NoDisk,
+            1 => GuestOs::Blank,
+            3 => GuestOs::Dos,
+            4 => GuestOs::Windows9x,
+            5 => GuestOs::WindowsNt,
+            6 => GuestOs::Linux,
+            7 => GuestOs::Bsd,
+            8 => GuestOs::AnyOs,
+            _ => GuestOs::Unknown,
+        }
+    }
+
+    /// Display name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            GuestOs::NoDisk => "No disk",
+            GuestOs::Blank => "Empty disk",
+            GuestOs::Unknown => "Unknown",
+            GuestOs::Dos => "DOS",
+            GuestOs::Windows9x => "Windows 9x",
+            GuestOs::WindowsNt => "Windows NT",
+            GuestOs::Linux => "Linux",
+            GuestOs::Bsd => "BSD",
+            GuestOs::AnyOs => "anyOS",
+        }
+    }
+
+    /// Recommended machine defaults for this guest. Disks that were not
+    /// recognized get a conservative profile any PC operating system boots
+    /// with.
+    pub fn profile(&self) -> DeviceProfile {
+        let (ram_mb, e1000, ioapic) = match self {
+            GuestOs::Dos => (16, false, false),
+            GuestOs::Windows9x => (64, false, false),
+            GuestOs::WindowsNt => (512, true, true),
+            GuestOs::Linux | GuestOs::Bsd => (256, true, true),
+            GuestOs::AnyOs => (512, true, true),
+            GuestOs::NoDisk | GuestOs::Blank | GuestOs::Unknown => (64, true, false),
+        };
+        DeviceProfile { ram_mb, e1000, ioapic }
+    }
+}
+
+/// Serial output callback: `(userdata, port, data, len)`.
+///
+/// `port` is the COM index (0 = COM1 … 3 = COM4); `data` is only valid for
+/// the duration of the call.
+pub type SerialOutputCb = extern "C" fn(u64, u32, *const u8, u32);
+
+/// Debug console line callback: `(userdata, timestamp_ms, data, len)`.
+/// `data` excludes the newline and is only valid during the call.
+pub type DebugconCb = extern "C" fn(u64, u64, *const u8, u32);
+
+/// I/O trace address space: port I/O.
+pub const TRACE_SPACE_PORT: u32 = 0;
+/// I/O trace address space: MMIO.
+pub const TRACE_SPACE_MMIO: u32 = 1;
+
+/// [`TraceRecord::kind`]: port read (`IN`).
+pub const TRACE_PORT_READ: u8 = 0;
+/// [`TraceRecord::kind`]: port write (`OUT`).
+pub const TRACE_PORT_WRITE: u8 = 1;
+/// [`TraceRecord::kind`]: MMIO read.
+pub const TRACE_MMIO_READ: u8 = 2;
+/// [`TraceRecord::kind`]: MMIO write.
+pub const TRACE_MMIO_WRITE: u8 = 3;
+
+/// One traced port or MMIO access (layout shared with libcorevm).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TraceRecord {
+    /// Port number or guest physical address.
+    pub addr: u64,
+    /// Value read or written (zero-extended).
+    pub value: u64,
+    /// RIP of the accessing instruction.
+    pub rip: u64,
+    /// Sequence number (wraps); gaps mean records were dropped.
+    pub seq: u32,
+    /// One of the `TRACE_*_READ` / `TRACE_*_WRITE` constants.
+    pub kind: u8,
+    /// Access size in bytes.
+    pub size: u8,
+    pub _reserved: u16,
+}
+
+/// Live I/O trace callback: `(userdata, record)`. Runs inside
+/// [`VmHandle::run`] and must not call back into the VM.
+pub type TraceCb = extern "C" fn(u64, *const TraceRecord);
+
+/// Host MSR handler: `(userdata, index, is_write, value)`. Fills `*value`
+/// on a read; returns nonzero if it handled the access, 0 to let the guest
+/// take #GP(0). Runs inside [`VmHandle::run`] and must not call back into
+/// the VM.
+pub type MsrCb = extern "C" fn(u64, u32, u32, *mut u64) -> u32;
+
+/// Watch on a register (see the `WATCH_REG_*` targets; 0–15 = RAX..R15).
+pub const WATCH_REGISTER: u32 = 0;
+/// Watch on memory at a linear (virtual) address.
+pub const WATCH_LINEAR: u32 = 1;
+/// Watch on memory at a guest physical address.
+pub const WATCH_PHYSICAL: u32 = 2;
+
+/// Register watch target: RFLAGS.
+pub const WATCH_REG_RFLAGS: u64 = 16;
+/// Register watch target: CR0.
+pub const WATCH_REG_CR0: u64 = 17;
+/// Register watch target: CR2.
+pub const WATCH_REG_CR2: u64 = 18;
+/// Register watch target: CR3.
+pub const WATCH_REG_CR3: u64 = 19;
+/// Register watch target: CR4.
+pub const WATCH_REG_CR4: u64 = 20;
+
+/// The change that stopped the VM with [`ExitReason::Watchpoint`]
+/// (layout shared with libcorevm).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WatchHit {
+    /// Id returned by [`VmHandle::watch_register`] / [`VmHandle::watch_memory`].
+    pub id: u32,
+    /// One of the `WATCH_*` kinds.
+    pub kind: u32,
+    /// Register index or address.
+    pub target: u64,
+    /// Value before the instruction.
+    pub old: u64,
+    /// Value after the instruction.
+    pub new: u64,
+    /// RIP of the instruction that changed the value.
+    pub rip: u64,
+    /// Access size in bytes (0 for registers).
+    pub size: u32,
+    pub _reserved: u32,
+}
+
+/// A device access waiting for the host after [`ExitReason::IoRequest`]
+/// (layout shared with libcorevm).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IoRequest {
+    /// Port number or guest physical address.
+    pub addr: u64,
+    /// Value written (zero-extended); 0 for reads.
+    pub value: u64,
+    /// RIP of the accessing instruction.
+    pub rip: u64,
+    /// One of the `TRACE_*_READ` / `TRACE_*_WRITE` constants.
+    pub kind: u8,
+    /// Access size in bytes.
+    pub size: u8,
+    pub _reserved: [u8; 6],
+}
+
+impl IoRequest {
+    /// True for writes (`OUT` or an MMIO write).
+    pub fn is_write(&self) -> bool {
+        self.kind & 1 != 0
+    }
+
+    /// True for port I/O, false for MMIO.
+    pub fn is_port(&self) -> bool {
+        self.kind <= TRACE_PORT_WRITE
+    }
+}
+
 // ══════════════════════════════════════════════════════════════════════
 //  Internal: cached function pointers from libcorevm.so
 // ══════════════════════════════════════════════════════════════════════
@@ -143,6 +387,15 @@ struct CoreVmLib {
     /// Execute up to `max_instructions` guest instructions.
     /// Returns an `ExitReason` as a `u32`.
     run: extern "C" fn(u64, u64) -> u32,
+    /// Execute for up to `usecs` microseconds of virtual time.
+    /// Returns the virtual time that advanced.
+    run_for_micros: extern "C" fn(u64, u64) -> u64,
+    /// Exit reason of the last `run_for_micros` call, as a `u32`.
+    get_last_exit_reason: extern "C" fn(u64) -> u32,
+    /// Total virtual time advanced by `run_for_micros`, in microseconds.
+    get_virtual_time_us: extern "C" fn(u64) -> u64,
+    /// Measured guest instructions per host millisecond.
+    get_exec_rate: extern "C" fn(u64) -> u64,
     /// Request the VM to stop at the next instruction boundary.
     request_stop: extern "C" fn(u64),
 
@@ -175,6 +428,10 @@ struct CoreVmLib {
     get_mode: extern "C" fn(u64) -> u32,
     /// Get the current privilege level (0-3).
     get_cpl: extern "C" fn(u64) -> u8,
+    /// Select the x87 transcendental accuracy mode (`FpuAccuracy` discriminant).
+    set_fpu_accuracy: extern "C" fn(u64, u32),
+    /// Set or clear the host handler for unimplemented MSRs.
+    set_msr_handler: extern "C" fn(u64, Option<MsrCb>, u64),
     /// Get the total number of instructions executed since last reset.
     get_instruction_count: extern "C" fn(u64) -> u64,
 
@@ -183,6 +440,9 @@ struct CoreVmLib {
     /// `data_ptr` / `data_len` define the source buffer.
     /// Returns 1 on success, 0 on failure.
     load_binary: extern "C" fn(u64, u64, *const u8, u32) -> u32,
+    /// Load the built-in BIOS ROM and jump to its reset vector.
+    /// Returns 0 on success, -1 if guest RAM is below 1 MiB.
+    load_default_bios: extern "C" fn(u64) -> i32,
     /// Read a byte from guest physical memory.
     read_phys_u8: extern "C" fn(u64, u64) -> u8,
     /// Read a 16-bit value from guest physical memory (little-endian).
@@ -195,21 +455,31 @@ struct CoreVmLib {
     write_phys_u16: extern "C" fn(u64, u64, u16),
     /// Write a 32-bit value to guest physical memory (little-endian).
     write_phys_u32: extern "C" fn(u64, u64, u32),
+    /// Load data at a guest physical address and make it read-only. Returns 0 or -1.
+    load_rom: extern "C" fn(u64, u64, *const u8, u32) -> i32,
+    /// Mark a RAM range read-only (non-zero) or writable: (handle, base, size, read_only).
+    set_read_only: extern "C" fn(u64, u64, u64, u32),
 
     // ── Device setup ─────────────────────────────────────────────
-    /// Register all standard devices (PIC, PIT, PS/2, CMOS, serial, VGA).
+    /// Register all standard devices (PIC, PIT, PS/2, CMOS, COM1-COM4, VGA).
     setup_standard_devices: extern "C" fn(u64),
     /// Register a PCI bus device.
     setup_pci_bus: extern "C" fn(u64),
     /// Register an E1000 NIC with the given MMIO base and MAC address.
     /// `mac_ptr` points to a 6-byte MAC address array.
     setup_e1000: extern "C" fn(u64, u64, *const u8),
+    /// Add an E1000 NIC. Returns its NIC index, or -1 at the NIC limit.
+    add_e1000: extern "C" fn(u64, u64, *const u8) -> i32,
+    /// Configure the machine from a description blob. Returns 0 or a negative error.
+    configure: extern "C" fn(u64, *const u8, u32) -> i32,
 
     // ── PS/2 keyboard and mouse input ────────────────────────────
     /// Inject a keyboard key press (scancode).
     ps2_key_press: extern "C" fn(u64, u8),
     /// Inject a keyboard key release (scancode).
     ps2_key_release: extern "C" fn(u64, u8),
+    /// Inject an anyOS key code press or release.
+    inject_key: extern "C" fn(u64, u32, u32) -> i32,
     /// Inject a mouse movement packet.
     ps2_mouse_move: extern "C" fn(u64, i16, i16, u8),
 
@@ -231,6 +501,12 @@ struct CoreVmLib {
     /// Copies up to `buf_len` bytes into `buf_ptr`.
     /// Returns the number of bytes actually written.
     serial_take_output: extern "C" fn(u64, *mut u8, u32) -> u32,
+    /// Send input bytes to COM`port + 1` (raises its IRQ if enabled).
+    serial_send_input_port: extern "C" fn(u64, u32, *const u8, u32),
+    /// Read output bytes from COM`port + 1`.
+    serial_take_output_port: extern "C" fn(u64, u32, *mut u8, u32) -> u32,
+    /// Register (or clear, with `None`) the output callback for a port.
+    serial_set_output_cb: extern "C" fn(u64, u32, Option<SerialOutputCb>, u64),
 
     // ── E1000 network ────────────────────────────────────────────
     /// Deliver a network packet to the guest E1000 NIC.
@@ -239,12 +515,29 @@ struct CoreVmLib {
     /// Copies serialized packet data into `buf_ptr` (up to `buf_len` bytes).
     /// Returns the number of bytes written.
     e1000_take_tx_packets: extern "C" fn(u64, *mut u8, u32) -> u32,
+    /// Number of E1000 NICs.
+    e1000_count: extern "C" fn(u64) -> u32,
+    /// Deliver a packet to NIC `nic`. Returns 1 if the receive filter accepted it.
+    e1000_nic_receive: extern "C" fn(u64, u32, *const u8, u32) -> u32,
+    /// Take transmitted packets from NIC `nic`.
+    e1000_nic_take_tx: extern "C" fn(u64, u32, *mut u8, u32) -> u32,
+    /// Set the link state of NIC `nic`. Returns 0 or -1 (no such NIC).
+    e1000_set_link: extern "C" fn(u64, u32, u32) -> i32,
+    /// Link state of NIC `nic`: 1 = up, 0 = down, -1 = no such NIC.
+    e1000_get_link: extern "C" fn(u64, u32) -> i32,
 
     // ── PIT timer ────────────────────────────────────────────────
     /// Advance the PIT by one tick.
     /// Returns 1 if channel 0 fired (IRQ 0 should be raised), 0 otherwise.
     pit_tick: extern "C" fn(u64) -> u32,
 
+    // ── CMOS RTC ─────────────────────────────────────────────────
+    /// Set the RTC offset from the host clock (seconds, timezone minutes).
+    set_rtc_offset: extern "C" fn(u64, i64, i32),
+    balloon_set_target: extern "C" fn(u64, u32),
+    balloon_get_actual: extern "C" fn(u64) -> u32,
+    ram_committed: extern "C" fn(u64) -> u64,
+
     // ── PIC interrupt controller ─────────────────────────────────
     /// Assert an IRQ line on the PIC (0-15).
     pic_raise_irq: extern "C" fn(u64, u8),
@@ -257,12 +550,35 @@ struct CoreVmLib {
     setup_ide: extern "C" fn(u64),
     /// Attach a disk image (raw bytes) to the IDE controller.
     ide_attach_disk: extern "C" fn(u64, *const u8, u32),
+    /// Attach a disk image file: (handle, path, path_len, writable).
+    /// Returns 0 or a negative error.
+    ide_open_disk: extern "C" fn(u64, *const u8, u32, u32) -> i32,
     /// Detach the disk image from the IDE controller.
     ide_detach_disk: extern "C" fn(u64),
+    /// Guess the OS on the attached disk. Returns a `GuestOs` code.
+    probe_disk: extern "C" fn(u64) -> u32,
+    /// Write back cached disk image changes. Returns 0 or -1.
+    ide_flush: extern "C" fn(u64) -> i32,
+    /// Create an overlay image: (path, path_len, base, base_len).
+    /// Returns 0 or a negative error.
+    disk_create_overlay: extern "C" fn(*const u8, u32, *const u8, u32) -> i32,
+    /// Copy the attached overlay's changes into its base. Returns 0 or a negative error.
+    ide_commit_overlay: extern "C" fn(u64) -> i32,
+    /// Drop the attached overlay's changes. Returns 0 or a negative error.
+    ide_discard_overlay: extern "C" fn(u64) -> i32,
     /// Check if the IDE controller has a pending IRQ (1=yes, 0=no).
     ide_irq_raised: extern "C" fn(u64) -> u32,
     /// Clear the pending IDE IRQ.
     ide_clear_irq: extern "C" fn(u64),
+    share_directory: extern "C" fn(u64, *const u8, u32, u32) -> i32,
+    share_sync: extern "C" fn(u64) -> i32,
+
+    // ── NOR flash ───────────────────────────────────────────────
+    /// Map a CFI flash: (handle, base, size, sector_size, path, path_len,
+    /// init, init_len). Returns 0 or a negative error.
+    flash_attach: extern "C" fn(u64, u64, u32, u32, *const u8, u32, *const u8, u32) -> i32,
+    /// Persist modified file-backed flashes. Returns files written or -1.
+    flash_sync: extern "C" fn(u64) -> i32,
 
     // ── fw_cfg ────────────────────────────────────────────────
     /// Add a named file to the fw_cfg device.
@@ -274,9 +590,53 @@ struct CoreVmLib {
     /// Returns the number of bytes actually written.
     debug_take_output: extern "C" fn(u64, *mut u8, u32) -> u32,
 
+    // ── Debug console / test exit ────────────────────────────────
+    /// Take the oldest port 0xE9 line: (handle, buf, len, timestamp out).
+    /// Returns the bytes copied or -1 if none is queued.
+    debugcon_take_line: extern "C" fn(u64, *mut u8, u32, *mut u64) -> i32,
+    /// Set or clear the line callback.
+    debugcon_set_cb: extern "C" fn(u64, Option<DebugconCb>, u64),
+    /// Lines lost to queue overflow.
+    debugcon_dropped: extern "C" fn(u64) -> u64,
+    /// Status written to the test-exit port, or -1.
+    test_exit_status: extern "C" fn(u64) -> i32,
+
     // ── Diagnostics ─────────────────────────────────────────────
     /// MMIO diagnostic: region count, bounds, RAM content at 0xB8000.
     mmio_diag: extern "C" fn(u64, *mut u32, *mut u64, *mut u64, *mut u32),
+    /// Start I/O tracing: (handle, spaces bitmask, ring capacity).
+    iotrace_enable: extern "C" fn(u64, u32, u32),
+    /// Stop I/O tracing (buffered records are kept).
+    iotrace_disable: extern "C" fn(u64),
+    /// Add a trace filter: (handle, space, start, count, exclude). Returns 0 or -1.
+    iotrace_add_filter: extern "C" fn(u64, u32, u64, u64, u32) -> i32,
+    /// Remove all trace filters.
+    iotrace_clear_filters: extern "C" fn(u64),
+    /// Drain buffered trace records. Returns the number copied.
+    iotrace_take: extern "C" fn(u64, *mut TraceRecord, u32) -> u32,
+    /// Records lost to ring buffer overflow.
+    iotrace_dropped: extern "C" fn(u64) -> u64,
+    /// Set or clear the live trace callback.
+    iotrace_set_callback: extern "C" fn(u64, Option<TraceCb>, u64),
+    /// Load a text symbol map. Returns the number of symbols or -1.
+    symbols_load: extern "C" fn(u64, *const u8, u32) -> i32,
+    /// Add one symbol: (handle, addr, name, name_len). Returns 0 or -1.
+    symbols_add: extern "C" fn(u64, u64, *const u8, u32) -> i32,
+    /// Remove all symbols.
+    symbols_clear: extern "C" fn(u64),
+    /// Resolve an address: (handle, addr, buf, buf_len, offset out). Returns the name length.
+    symbol_lookup: extern "C" fn(u64, u64, *mut u8, u32, *mut u64) -> u32,
+    /// Add a watch: (handle, kind, target, size). Returns the id or -1.
+    watch_add: extern "C" fn(u64, u32, u64, u32) -> i32,
+    /// Remove a watch by id. Returns 0 or -1.
+    watch_remove: extern "C" fn(u64, u32) -> i32,
+    /// Remove all watches.
+    watch_clear: extern "C" fn(u64),
+    /// Get the last watch hit. Returns 0 or -1.
+    watch_get_hit: extern "C" fn(u64, *mut WatchHit) -> i32,
+    set_io_exit: extern "C" fn(u64, u32),
+    get_pending_io: extern "C" fn(u64, *mut IoRequest) -> i32,
+    complete_io: extern "C" fn(u64, u64) -> i32,
 
     // ── Error reporting ────────────────────────────────────────
     /// Write the last error message into a buffer. Returns bytes written.
@@ -307,6 +667,23 @@ unsafe fn resolve<T: Copy>(handle: &DlHandle, name: &str) -> T {
     core::mem::transmute_copy::<*const (), T>(&ptr)
 }
 
+// ══════════════════════════════════════════════════════════════════════
+//  Public API: disk overlays
+// ══════════════════════════════════════════════════════════════════════
+
+/// Create an empty overlay image at `path` on top of the disk image `base`,
+/// replacing any existing file. The base is not copied; attach the overlay
+/// with [`ide_open_disk`](VmHandle::ide_open_disk) to give a VM its own
+/// writable view of a shared base image. A relative `base` is relative to
+/// the overlay's directory. On failure returns the negative
+/// `corevm_disk_create_overlay` error code.
+pub fn create_disk_overlay(path: &str, base: &str) -> Result<(), i32> {
+    match (lib().disk_create_overlay)(path.as_ptr(), path.len() as u32, base.as_ptr(), base.len() as u32) {
+        0 => Ok(()),
+        err => Err(err),
+    }
+}
+
 // ══════════════════════════════════════════════════════════════════════
 //  Public API: init
 // ══════════════════════════════════════════════════════════════════════
@@ -329,6 +706,10 @@ pub fn init() -> bool {
             destroy: resolve(&handle, "corevm_destroy"),
             reset: resolve(&handle, "corevm_reset"),
             run: resolve(&handle, "corevm_run"),
+            run_for_micros: resolve(&handle, "corevm_run_for_micros"),
+            get_last_exit_reason: resolve(&handle, "corevm_get_last_exit_reason"),
+            get_virtual_time_us: resolve(&handle, "corevm_get_virtual_time_us"),
+            get_exec_rate: resolve(&handle, "corevm_get_exec_rate"),
             request_stop: resolve(&handle, "corevm_request_stop"),
             // CPU state: instruction pointer
             get_rip: resolve(&handle, "corevm_get_rip"),
@@ -345,22 +726,30 @@ pub fn init() -> bool {
             // CPU state: mode and privilege
             get_mode: resolve(&handle, "corevm_get_mode"),
             get_cpl: resolve(&handle, "corevm_get_cpl"),
+            set_fpu_accuracy: resolve(&handle, "corevm_set_fpu_accuracy"),
+            set_msr_handler: resolve(&handle, "corevm_set_msr_handler"),
             get_instruction_count: resolve(&handle, "corevm_get_instruction_count"),
             // Memory
             load_binary: resolve(&handle, "corevm_load_binary"),
+            load_default_bios: resolve(&handle, "corevm_load_default_bios"),
             read_phys_u8: resolve(&handle, "corevm_read_phys_u8"),
             read_phys_u16: resolve(&handle, "corevm_read_phys_u16"),
             read_phys_u32: resolve(&handle, "corevm_read_phys_u32"),
             write_phys_u8: resolve(&handle, "corevm_write_phys_u8"),
             write_phys_u16: resolve(&handle, "corevm_write_phys_u16"),
             write_phys_u32: resolve(&handle, "corevm_write_phys_u32"),
+            load_rom: resolve(&handle, "corevm_load_rom"),
+            set_read_only: resolve(&handle, "corevm_set_read_only"),
             // Device setup
             setup_standard_devices: resolve(&handle, "corevm_setup_standard_devices"),
             setup_pci_bus: resolve(&handle, "corevm_setup_pci_bus"),
             setup_e1000: resolve(&handle, "corevm_setup_e1000"),
+            add_e1000: resolve(&handle, "corevm_add_e1000"),
+            configure: resolve(&handle, "corevm_configure"),
             // PS/2
             ps2_key_press: resolve(&handle, "corevm_ps2_key_press"),
             ps2_key_release: resolve(&handle, "corevm_ps2_key_release"),
+            inject_key: resolve(&handle, "corevm_inject_key"),
             ps2_mouse_move: resolve(&handle, "corevm_ps2_mouse_move"),
             // VGA
             vga_get_framebuffer: resolve(&handle, "corevm_vga_get_framebuffer"),
@@ -369,26 +758,73 @@ pub fn init() -> bool {
             // Serial
             serial_send_input: resolve(&handle, "corevm_serial_send_input"),
             serial_take_output: resolve(&handle, "corevm_serial_take_output"),
+            serial_send_input_port: resolve(&handle, "corevm_serial_send_input_port"),
+            serial_take_output_port: resolve(&handle, "corevm_serial_take_output_port"),
+            serial_set_output_cb: resolve(&handle, "corevm_serial_set_output_cb"),
             // E1000
             e1000_receive_packet: resolve(&handle, "corevm_e1000_receive_packet"),
             e1000_take_tx_packets: resolve(&handle, "corevm_e1000_take_tx_packets"),
+            e1000_count: resolve(&handle, "corevm_e1000_count"),
+            e1000_nic_receive: resolve(&handle, "corevm_e1000_nic_receive"),
+            e1000_nic_take_tx: resolve(&handle, "corevm_e1000_nic_take_tx"),
+            e1000_set_link: resolve(&handle, "corevm_e1000_set_link"),
+            e1000_get_link: resolve(&handle, "corevm_e1000_get_link"),
             // PIT
             pit_tick: resolve(&handle, "corevm_pit_tick"),
+            // CMOS
+            set_rtc_offset: resolve(&handle, "corevm_set_rtc_offset"),
+            balloon_set_target: resolve(&handle, "corevm_balloon_set_target"),
+            balloon_get_actual: resolve(&handle, "corevm_balloon_get_actual"),
+            ram_committed: resolve(&handle, "corevm_ram_committed"),
             // PIC
             pic_raise_irq: resolve(&handle, "corevm_pic_raise_irq"),
             pic_get_interrupt: resolve(&handle, "corevm_pic_get_interrupt"),
             // IDE
             setup_ide: resolve(&handle, "corevm_setup_ide"),
             ide_attach_disk: resolve(&handle, "corevm_ide_attach_disk"),
+            ide_open_disk: resolve(&handle, "corevm_ide_open_disk"),
             ide_detach_disk: resolve(&handle, "corevm_ide_detach_disk"),
+            probe_disk: resolve(&handle, "corevm_probe_disk"),
+            ide_flush: resolve(&handle, "corevm_ide_flush"),
+            disk_create_overlay: resolve(&handle, "corevm_disk_create_overlay"),
+            ide_commit_overlay: resolve(&handle, "corevm_ide_commit_overlay"),
+            ide_discard_overlay: resolve(&handle, "corevm_ide_discard_overlay"),
             ide_irq_raised: resolve(&handle, "corevm_ide_irq_raised"),
             ide_clear_irq: resolve(&handle, "corevm_ide_clear_irq"),
+            share_directory: resolve(&handle, "corevm_share_directory"),
+            share_sync: resolve(&handle, "corevm_share_sync"),
+            // NOR flash
+            flash_attach: resolve(&handle, "corevm_flash_attach"),
+            flash_sync: resolve(&handle, "corevm_flash_sync"),
             // fw_cfg
             fw_cfg_add_file: resolve(&handle, "corevm_fw_cfg_add_file"),
             // Debug port
             debug_take_output: resolve(&handle, "corevm_debug_take_output"),
+            // Debug console / test exit
+            debugcon_take_line: resolve(&handle, "corevm_debugcon_take_line"),
+            debugcon_set_cb: resolve(&handle, "corevm_debugcon_set_cb"),
+            debugcon_dropped: resolve(&handle, "corevm_debugcon_dropped"),
+            test_exit_status: resolve(&handle, "corevm_test_exit_status"),
             // Diagnostics
             mmio_diag: resolve(&handle, "corevm_mmio_diag"),
+            iotrace_enable: resolve(&handle, "corevm_iotrace_enable"),
+            iotrace_disable: resolve(&handle, "corevm_iotrace_disable"),
+            iotrace_add_filter: resolve(&handle, "corevm_iotrace_add_filter"),
+            iotrace_clear_filters: resolve(&handle, "corevm_iotrace_clear_filters"),
+            iotrace_take: resolve(&handle, "corevm_iotrace_take"),
+            iotrace_dropped: resolve(&handle, "corevm_iotrace_dropped"),
+            iotrace_set_callback: resolve(&handle, "corevm_iotrace_set_callback"),
+            symbols_load: resolve(&handle, "corevm_symbols_load"),
+            symbols_add: resolve(&handle, "corevm_symbols_add"),
+            symbols_clear: resolve(&handle, "corevm_symbols_clear"),
+            symbol_lookup: resolve(&handle, "corevm_symbol_lookup"),
+            watch_add: resolve(&handle, "corevm_watch_add"),
+            watch_remove: resolve(&handle, "corevm_watch_remove"),
+            watch_clear: resolve(&handle, "corevm_watch_clear"),
+            watch_get_hit: resolve(&handle, "corevm_watch_get_hit"),
+            set_io_exit: resolve(&handle, "corevm_set_io_exit"),
+            get_pending_io: resolve(&handle, "corevm_get_pending_io"),
+            complete_io: resolve(&handle, "corevm_complete_io"),
             // Error reporting
             get_last_error: resolve(&handle, "corevm_get_last_error"),
             get_last_error_rip: resolve(&handle, "corevm_get_last_error_rip"),
@@ -469,6 +905,76 @@ impl VmHandle {
         ExitReason::from_u32(code)
     }
 
+    /// Execute guest code for up to `usecs` microseconds of virtual time.
+    ///
+    /// The VM sizes its instruction batches from the measured execution
+    /// rate, ticks the PIT itself (do not call [`pit_tick`](Self::pit_tick)
+    /// as well), sleeps while the guest idles in HLT and yields the CPU
+    /// while it is busy.
+    ///
+    /// # Returns
+    ///
+    /// The reason the VM stopped ([`ExitReason::InstructionLimit`] when the
+    /// budget was used up) and the virtual time that advanced, in
+    /// microseconds — less than `usecs` if the guest stopped early or could
+    /// not keep up with the host clock.
+    pub fn run_for_micros(&self, usecs: u64) -> (ExitReason, u64) {
+        let advanced = (lib().run_for_micros)(self.handle, usecs);
+        let code = (lib().get_last_exit_reason)(self.handle);
+        (ExitReason::from_u32(code), advanced)
+    }
+
+    /// Total virtual time advanced by [`run_for_micros`](Self::run_for_micros),
+    /// in microseconds.
+    pub fn virtual_time_us(&self) -> u64 {
+        (lib().get_virtual_time_us)(self.handle)
+    }
+
+    /// Measured execution rate in guest instructions per host millisecond.
+    pub fn exec_rate(&self) -> u64 {
+        (lib().get_exec_rate)(self.handle)
+    }
+
+    /// Hand unhandled device accesses to the host instead of emulating an
+    /// empty bus.
+    ///
+    /// While enabled, a port with no registered device, or a memory access
+    /// outside guest RAM and all MMIO regions, stops the guest with
+    /// [`ExitReason::IoRequest`]. Perform the access described by
+    /// [`pending_io`](Self::pending_io), pass the value read to
+    /// [`complete_io`](Self::complete_io) and run again:
+    ///
+    /// ```rust
+    /// vm.set_io_exit(true);
+    /// loop {
+    ///     match vm.run(0) {
+    ///         ExitReason::IoRequest => {
+    ///             let req = vm.pending_io().unwrap();
+    ///             let value = if req.is_write() { my_write(&req); 0 } else { my_read(&req) };
+    ///             vm.complete_io(value);
+    ///         }
+    ///         _ => break,
+    ///     }
+    /// }
+    /// ```
+    pub fn set_io_exit(&self, enable: bool) {
+        (lib().set_io_exit)(self.handle, enable as u32);
+    }
+
+    /// The access behind the last [`ExitReason::IoRequest`], until it is
+    /// completed.
+    pub fn pending_io(&self) -> Option<IoRequest> {
+        let mut req = IoRequest::default();
+        if (lib().get_pending_io)(self.handle, &mut req) == 0 { Some(req) } else { None }
+    }
+
+    /// Complete the pending access with the value the guest reads (ignored
+    /// for writes). The next run finishes the interrupted instruction.
+    /// Returns `false` if no access is pending.
+    pub fn complete_io(&self, value: u64) -> bool {
+        (lib().complete_io)(self.handle, value) == 0
+    }
+
     /// Request the VM to stop at the next instruction boundary.
     ///
     /// This is safe to call from another thread or a signal handler.
@@ -556,6 +1062,20 @@ impl VmHandle {
         (lib().get_cpl)(self.handle)
     }
 
+    /// Select how accurately FSIN/FCOS/FSINCOS/FPTAN/F2XM1 are emulated.
+    /// The setting survives [`reset`](Self::reset).
+    pub fn set_fpu_accuracy(&self, accuracy: FpuAccuracy) {
+        (lib().set_fpu_accuracy)(self.handle, accuracy as u32);
+    }
+
+    /// Service RDMSR/WRMSR of MSRs the emulator does not implement with
+    /// `cb(userdata, index, is_write, value)` instead of faulting. Pass
+    /// `None` to remove the handler. The handler survives
+    /// [`reset`](Self::reset).
+    pub fn set_msr_handler(&self, cb: Option<MsrCb>, userdata: u64) {
+        (lib().set_msr_handler)(self.handle, cb, userdata);
+    }
+
     /// Get the total number of instructions executed since the last
     /// reset.
     pub fn instruction_count(&self) -> u64 {
@@ -578,6 +1098,20 @@ impl VmHandle {
         (lib().load_binary)(self.handle, addr, data.as_ptr(), data.len() as u32) != 0
     }
 
+    /// Load the built-in BIOS instead of an external firmware image.
+    ///
+    /// Provides POST, INT 10h text output, INT 13h disk access on the IDE
+    /// image, INT 16h keyboard input and an E820 memory map, and boots the
+    /// MBR of the attached disk. Call after setting up the standard devices
+    /// and IDE.
+    ///
+    /// # Returns
+    ///
+    /// `true` on success, `false` if guest RAM is smaller than 1 MiB.
+    pub fn load_default_bios(&self) -> bool {
+        (lib().load_default_bios)(self.handle) == 0
+    }
+
     /// Read a byte from guest physical memory.
     pub fn read_phys_u8(&self, addr: u64) -> u8 {
         (lib().read_phys_u8)(self.handle, addr)
@@ -608,6 +1142,21 @@ impl VmHandle {
         (lib().write_phys_u32)(self.handle, addr, val);
     }
 
+    /// Load `data` at guest physical `addr` as ROM: guest writes to the
+    /// range are discarded.
+    ///
+    /// # Returns
+    ///
+    /// `true` on success, `false` if the range is outside guest RAM.
+    pub fn load_rom(&self, addr: u64, data: &[u8]) -> bool {
+        (lib().load_rom)(self.handle, addr, data.as_ptr(), data.len() as u32) == 0
+    }
+
+    /// Make guest RAM in `[base, base+size)` read-only or writable again.
+    pub fn set_read_only(&self, base: u64, size: u64, read_only: bool) {
+        (lib().set_read_only)(self.handle, base, size, read_only as u32);
+    }
+
     // ── Device setup ─────────────────────────────────────────────
 
     /// Register all standard hardware devices.
@@ -617,7 +1166,7 @@ impl VmHandle {
     /// - Intel 8254 PIT (system timer on IRQ 0)
     /// - PS/2 controller (keyboard + mouse)
     /// - CMOS RTC
-    /// - 16550 UART serial port (COM1)
+    /// - 16550 UART serial ports (COM1-COM4, IRQ 4/3)
     /// - VGA/SVGA framebuffer
     pub fn setup_standard_devices(&self) {
         (lib().setup_standard_devices)(self.handle);
@@ -641,24 +1190,54 @@ impl VmHandle {
         (lib().setup_e1000)(self.handle, mmio_base, mac.as_ptr());
     }
 
+    /// Add an Intel E1000 NIC and return its NIC index (0 for the first),
+    /// used by the `e1000_nic_*` and link methods. Returns `None` if the
+    /// VM already has 4 NICs.
+    pub fn add_e1000(&self, mmio_base: u64, mac: &[u8; 6]) -> Option<u32> {
+        let nic = (lib().add_e1000)(self.handle, mmio_base, mac.as_ptr());
+        if nic < 0 { None } else { Some(nic as u32) }
+    }
+
+    /// Build the machine declaratively from a [`Machine`] description.
+    ///
+    /// Replaces the `setup_*` calls; must be called on a freshly created VM.
+    /// Returns `Err(code)` with the negative libcorevm error code if the
+    /// description is rejected (see `corevm_configure`).
+    pub fn configure(&self, machine: &Machine) -> Result<(), i32> {
+        let blob = machine.encode();
+        match (lib().configure)(self.handle, blob.as_ptr(), blob.len() as u32) {
+            0 => Ok(()),
+            err => Err(err),
+        }
+    }
+
     // ── PS/2 keyboard and mouse ──────────────────────────────────
 
     /// Inject a keyboard key press event.
     ///
-    /// The `scancode` is in the format matching the currently active
-    /// scancode set (default: set 2).
+    /// The `scancode` is a set 1 make code (send `0xE0` first for extended
+    /// keys); it is converted to the guest's active scancode set.
     pub fn ps2_key_press(&self, scancode: u8) {
         (lib().ps2_key_press)(self.handle, scancode);
     }
 
     /// Inject a keyboard key release event.
     ///
-    /// For scancode set 2, the controller automatically generates the
-    /// `0xF0` break prefix. For set 1, it generates `scancode | 0x80`.
+    /// `scancode` is the set 1 make code; the controller generates the
+    /// break sequence for the active scancode set.
     pub fn ps2_key_release(&self, scancode: u8) {
         (lib().ps2_key_release)(self.handle, scancode);
     }
 
+    /// Inject an anyOS key code press or release, as delivered in window
+    /// key events (`KEY_*` codes, set 1 codes for ordinary keys, or
+    /// `0xE000 | code` for other extended keys).
+    ///
+    /// Returns `false` if the key has no PS/2 equivalent.
+    pub fn inject_key(&self, keycode: u32, pressed: bool) -> bool {
+        (lib().inject_key)(self.handle, keycode, pressed as u32) == 0
+    }
+
     /// Inject a mouse movement packet.
     ///
     /// # Arguments
@@ -797,6 +1376,32 @@ impl VmHandle {
         v
     }
 
+    // ── Serial ports (COM1-COM4) ─────────────────────────────────
+
+    /// Send input to COM`port + 1` (0 = COM1 … 3 = COM4).
+    ///
+    /// If the guest has enabled the receive interrupt, IRQ 4 (COM1/COM3)
+    /// or IRQ 3 (COM2/COM4) is raised automatically.
+    pub fn serial_send_input_port(&self, port: u32, data: &[u8]) {
+        (lib().serial_send_input_port)(self.handle, port, data.as_ptr(), data.len() as u32);
+    }
+
+    /// Read output bytes produced on COM`port + 1` into `buf`.
+    ///
+    /// Returns the number of bytes read. Always 0 for ports that have an
+    /// output callback registered.
+    pub fn serial_take_output_port(&self, port: u32, buf: &mut [u8]) -> usize {
+        (lib().serial_take_output_port)(self.handle, port, buf.as_mut_ptr(), buf.len() as u32) as usize
+    }
+
+    /// Push COM`port + 1` output to `cb` instead of buffering it for polling.
+    ///
+    /// `cb(userdata, port, data, len)` is invoked after each [`run`](Self::run)
+    /// slice that produced output. Pass `None` to return to polling.
+    pub fn serial_set_output_cb(&self, port: u32, cb: Option<SerialOutputCb>, userdata: u64) {
+        (lib().serial_set_output_cb)(self.handle, port, cb, userdata);
+    }
+
     // ── Debug port ────────────────────────────────────────────────
 
     /// Drain debug port output (port 0x402) into the provided buffer.
@@ -817,9 +1422,150 @@ impl VmHandle {
         v
     }
 
+    // ── Debug console / test exit ────────────────────────────────
+
+    /// Take the oldest line written to the Bochs debug console (port 0xE9)
+    /// together with the host uptime in milliseconds at which it started.
+    /// Lines longer than `buf` are truncated.
+    pub fn debugcon_take_line(&self, buf: &mut [u8]) -> Option<(u64, usize)> {
+        let mut ts = 0u64;
+        let n = (lib().debugcon_take_line)(self.handle, buf.as_mut_ptr(), buf.len() as u32, &mut ts);
+        if n < 0 { None } else { Some((ts, n as usize)) }
+    }
+
+    /// Receive debug console lines through `cb` at the end of every
+    /// [`run`](Self::run) slice instead of polling. `None` returns to polling.
+    pub fn debugcon_set_cb(&self, cb: Option<DebugconCb>, userdata: u64) {
+        (lib().debugcon_set_cb)(self.handle, cb, userdata);
+    }
+
+    /// Debug console lines lost because nobody drained the queue.
+    pub fn debugcon_dropped(&self) -> u64 {
+        (lib().debugcon_dropped)(self.handle)
+    }
+
+    /// Status the guest wrote to the test-exit port, after
+    /// [`ExitReason::GuestExit`].
+    pub fn test_exit_status(&self) -> Option<u16> {
+        let s = (lib().test_exit_status)(self.handle);
+        if s < 0 { None } else { Some(s as u16) }
+    }
+
+    // ── I/O trace ────────────────────────────────────────────────
+
+    /// Start recording guest port and/or MMIO accesses into a ring buffer
+    /// of `capacity` records (0 = 4096).
+    pub fn iotrace_enable(&self, ports: bool, mmio: bool, capacity: u32) {
+        let spaces = (ports as u32) | ((mmio as u32) << 1);
+        (lib().iotrace_enable)(self.handle, spaces, capacity);
+    }
+
+    /// Stop recording. Buffered records can still be taken.
+    pub fn iotrace_disable(&self) {
+        (lib().iotrace_disable)(self.handle);
+    }
+
+    /// Only record accesses in `[start, start + count)` of `space`
+    /// ([`TRACE_SPACE_PORT`] or [`TRACE_SPACE_MMIO`]). Several include
+    /// ranges may be combined. Returns `false` if the filter table is full.
+    pub fn iotrace_include(&self, space: u32, start: u64, count: u64) -> bool {
+        (lib().iotrace_add_filter)(self.handle, space, start, count, 0) == 0
+    }
+
+    /// Never record accesses in `[start, start + count)` of `space`.
+    /// Returns `false` if the filter table is full.
+    pub fn iotrace_exclude(&self, space: u32, start: u64, count: u64) -> bool {
+        (lib().iotrace_add_filter)(self.handle, space, start, count, 1) == 0
+    }
+
+    /// Remove all trace filters.
+    pub fn iotrace_clear_filters(&self) {
+        (lib().iotrace_clear_filters)(self.handle);
+    }
+
+    /// Move buffered records (oldest first) into `out`.
+    /// Returns the number of records written.
+    pub fn iotrace_take(&self, out: &mut [TraceRecord]) -> usize {
+        (lib().iotrace_take)(self.handle, out.as_mut_ptr(), out.len() as u32) as usize
+    }
+
+    /// Number of records lost because the ring buffer was full.
+    pub fn iotrace_dropped(&self) -> u64 {
+        (lib().iotrace_dropped)(self.handle)
+    }
+
+    /// Deliver each record to `cb(userdata, record)` as it happens instead
+    /// of buffering it. Pass `None` to return to ring buffer mode.
+    pub fn iotrace_set_callback(&self, cb: Option<TraceCb>, userdata: u64) {
+        (lib().iotrace_set_callback)(self.handle, cb, userdata);
+    }
+
+    // ── Symbols and watches ──────────────────────────────────────
+
+    /// Load a guest symbol map (`nm` output or `address name` lines) used
+    /// to annotate RIPs in exception dumps. Returns the number of symbols.
+    pub fn load_symbols(&self, text: &[u8]) -> u32 {
+        (lib().symbols_load)(self.handle, text.as_ptr(), text.len() as u32).max(0) as u32
+    }
+
+    /// Add one guest symbol at `addr`.
+    pub fn add_symbol(&self, addr: u64, name: &str) {
+        (lib().symbols_add)(self.handle, addr, name.as_ptr(), name.len() as u32);
+    }
+
+    /// Remove all guest symbols.
+    pub fn clear_symbols(&self) {
+        (lib().symbols_clear)(self.handle);
+    }
+
+    /// Resolve `addr` (e.g. a [`TraceRecord::rip`]) to the nearest symbol
+    /// at or below it and the offset into that symbol.
+    pub fn lookup_symbol(&self, addr: u64) -> Option<(alloc::string::String, u64)> {
+        let mut buf = [0u8; 256];
+        let mut offset = 0u64;
+        let n = (lib().symbol_lookup)(self.handle, addr, buf.as_mut_ptr(), buf.len() as u32, &mut offset);
+        if n == 0 {
+            return None;
+        }
+        let name = core::str::from_utf8(&buf[..n as usize]).unwrap_or("?");
+        Some((alloc::string::String::from(name), offset))
+    }
+
+    /// Stop with [`ExitReason::Watchpoint`] whenever register `reg` changes
+    /// (0–15 = RAX..R15 or a `WATCH_REG_*` target). Returns the watch id.
+    pub fn watch_register(&self, reg: u64) -> Option<u32> {
+        let id = (lib().watch_add)(self.handle, WATCH_REGISTER, reg, 0);
+        if id < 0 { None } else { Some(id as u32) }
+    }
+
+    /// Stop with [`ExitReason::Watchpoint`] whenever the `size`-byte value
+    /// (1, 2, 4 or 8) at `addr` changes. `physical` selects a guest physical
+    /// address instead of a linear one. Returns the watch id.
+    pub fn watch_memory(&self, addr: u64, size: u32, physical: bool) -> Option<u32> {
+        let kind = if physical { WATCH_PHYSICAL } else { WATCH_LINEAR };
+        let id = (lib().watch_add)(self.handle, kind, addr, size);
+        if id < 0 { None } else { Some(id as u32) }
+    }
+
+    /// Remove a watch. Returns `false` if no watch has this id.
+    pub fn unwatch(&self, id: u32) -> bool {
+        (lib().watch_remove)(self.handle, id) == 0
+    }
+
+    /// Remove all watches.
+    pub fn clear_watches(&self) {
+        (lib().watch_clear)(self.handle);
+    }
+
+    /// The change behind the last [`ExitReason::Watchpoint`].
+    pub fn watch_hit(&self) -> Option<WatchHit> {
+        let mut hit = WatchHit::default();
+        if (lib().watch_get_hit)(self.handle, &mut hit) == 0 { Some(hit) } else { None }
+    }
+
     // ── E1000 network ────────────────────────────────────────────
 
-    /// Deliver a network packet to the guest E1000 NIC.
+    /// Deliver a network packet to the first guest E1000 NIC.
     ///
     /// The packet should be a complete Ethernet frame (destination MAC,
     /// source MAC, EtherType, payload). The E1000 will set the RX
@@ -839,6 +1585,46 @@ impl VmHandle {
         n as usize
     }
 
+    /// Number of E1000 NICs in the VM.
+    pub fn e1000_count(&self) -> u32 {
+        (lib().e1000_count)(self.handle)
+    }
+
+    /// Deliver a network packet to NIC `nic`.
+    ///
+    /// Returns `false` if the frame was dropped: the link is down, the
+    /// destination address does not pass the guest's receive filter
+    /// (programmed MAC addresses, multicast table, promiscuous and
+    /// broadcast-accept bits), or the NIC does not exist.
+    pub fn e1000_nic_receive_packet(&self, nic: u32, data: &[u8]) -> bool {
+        (lib().e1000_nic_receive)(self.handle, nic, data.as_ptr(), data.len() as u32) != 0
+    }
+
+    /// Take transmitted packets from NIC `nic`.
+    ///
+    /// Same format as [`e1000_take_tx_packets`](Self::e1000_take_tx_packets):
+    /// each packet is a `u32` little-endian length followed by the frame.
+    pub fn e1000_nic_take_tx_packets(&self, nic: u32, buf: &mut [u8]) -> usize {
+        (lib().e1000_nic_take_tx)(self.handle, nic, buf.as_mut_ptr(), buf.len() as u32) as usize
+    }
+
+    /// Plug in (`true`) or pull the virtual cable of NIC `nic`.
+    ///
+    /// The guest sees the link status change interrupt. While the link is
+    /// down, packets are neither delivered nor transmitted. Returns `false`
+    /// if the NIC does not exist.
+    pub fn e1000_set_link(&self, nic: u32, up: bool) -> bool {
+        (lib().e1000_set_link)(self.handle, nic, up as u32) == 0
+    }
+
+    /// Link state of NIC `nic`, or `None` if it does not exist.
+    pub fn e1000_link_up(&self, nic: u32) -> Option<bool> {
+        match (lib().e1000_get_link)(self.handle, nic) {
+            -1 => None,
+            state => Some(state != 0),
+        }
+    }
+
     // ── PIT timer ────────────────────────────────────────────────
 
     /// Advance the Programmable Interval Timer by one tick.
@@ -850,6 +1636,37 @@ impl VmHandle {
         (lib().pit_tick)(self.handle) != 0
     }
 
+    // ── CMOS RTC ─────────────────────────────────────────────────
+
+    /// Set the guest clock relative to the host clock.
+    ///
+    /// The guest sees host time plus `offset_secs` plus `tz_minutes`
+    /// (timezone in minutes east of UTC, for guests such as Windows that
+    /// keep the RTC in local time). Pass `0, 0` for plain host time.
+    pub fn set_rtc_offset(&self, offset_secs: i64, tz_minutes: i32) {
+        (lib().set_rtc_offset)(self.handle, offset_secs, tz_minutes);
+    }
+
+    // ── Memory ───────────────────────────────────────────────────
+
+    /// Ask the guest to give `pages` 4 KiB pages back to the host through
+    /// the balloon device (0 = return them all). Requires a machine
+    /// configured with [`Machine::balloon`].
+    pub fn balloon_set_target(&self, pages: u32) {
+        (lib().balloon_set_target)(self.handle, pages);
+    }
+
+    /// Pages the guest currently holds in the balloon.
+    pub fn balloon_pages(&self) -> u32 {
+        (lib().balloon_get_actual)(self.handle)
+    }
+
+    /// Host memory currently backing guest RAM, in bytes. Guest RAM is
+    /// committed on first write, so this grows with what the guest touches.
+    pub fn ram_committed(&self) -> u64 {
+        (lib().ram_committed)(self.handle)
+    }
+
     // ── PIC interrupt controller ─────────────────────────────────
 
     /// Assert an IRQ line on the PIC.
@@ -894,13 +1711,61 @@ impl VmHandle {
         (lib().ide_attach_disk)(self.handle, data.as_ptr(), data.len() as u32);
     }
 
+    /// Attach the disk image file at `path` to the IDE controller.
+    ///
+    /// The file is read and written on demand instead of being copied into
+    /// the VM. qcow2 images are recognized by their header, anything else
+    /// is a raw sector dump. Without `writable` the drive rejects guest
+    /// writes. On failure returns the negative `corevm_ide_open_disk`
+    /// error code. Must be called after [`setup_ide`](Self::setup_ide).
+    pub fn ide_open_disk(&self, path: &str, writable: bool) -> Result<(), i32> {
+        match (lib().ide_open_disk)(self.handle, path.as_ptr(), path.len() as u32, writable as u32) {
+            0 => Ok(()),
+            err => Err(err),
+        }
+    }
+
     /// Detach the disk image from the IDE controller.
     ///
-    /// Frees the in-VM copy of the disk image.
+    /// Frees the in-VM copy of the disk image, or writes back cached
+    /// changes and closes an image file.
     pub fn ide_detach_disk(&self) {
         (lib().ide_detach_disk)(self.handle);
     }
 
+    /// Guess the operating system on the attached IDE disk, e.g. to pick
+    /// defaults with [`GuestOs::profile`] before booting. Only reads the
+    /// disk.
+    pub fn probe_disk(&self) -> GuestOs {
+        GuestOs::from_u32((lib().probe_disk)(self.handle))
+    }
+
+    /// Write cached changes of an IDE disk image file back to the host.
+    /// Returns `false` on a host write error.
+    pub fn ide_flush(&self) -> bool {
+        (lib().ide_flush)(self.handle) == 0
+    }
+
+    /// Copy the changes held in the attached overlay image (see
+    /// [`create_disk_overlay`]) into its base image. No other VM may be
+    /// using the base meanwhile. On failure returns the negative
+    /// `corevm_ide_commit_overlay` error code.
+    pub fn ide_commit_overlay(&self) -> Result<(), i32> {
+        match (lib().ide_commit_overlay)(self.handle) {
+            0 => Ok(()),
+            err => Err(err),
+        }
+    }
+
+    /// Drop every change held in the attached overlay image, so the disk
+    /// reads as its base image again. Use on a stopped or reset guest.
+    pub fn ide_discard_overlay(&self) -> Result<(), i32> {
+        match (lib().ide_discard_overlay)(self.handle) {
+            0 => Ok(()),
+            err => Err(err),
+        }
+    }
+
     /// Check whether the IDE controller has a pending IRQ (IRQ 14).
     ///
     /// Returns `true` if an IRQ is pending and should be raised on the
@@ -914,6 +1779,71 @@ impl VmHandle {
         (lib().ide_clear_irq)(self.handle);
     }
 
+    // ── Shared host directory ───────────────────────────────────
+
+    /// Expose host directory `path` to the guest as a FAT16 disk on the
+    /// secondary IDE channel (master). The disk is a snapshot of the
+    /// directory; a `writable` share accepts guest writes, which
+    /// [`share_sync`](Self::share_sync) copies back to the host.
+    ///
+    /// Replaces any previous share (syncing it first). On failure returns
+    /// the negative `corevm_share_directory` error code.
+    pub fn share_directory(&self, path: &str, writable: bool) -> Result<(), i32> {
+        match (lib().share_directory)(self.handle, path.as_ptr(), path.len() as u32, writable as u32) {
+            0 => Ok(()),
+            err => Err(err),
+        }
+    }
+
+    /// Remove the shared directory disk, syncing a writable share first.
+    pub fn unshare_directory(&self) {
+        (lib().share_directory)(self.handle, core::ptr::null(), 0, 0);
+    }
+
+    /// Copy files the guest created or changed on a writable share back
+    /// to the host. Returns the number of files written, or `None` if no
+    /// writable share is attached.
+    pub fn share_sync(&self) -> Option<u32> {
+        let n = (lib().share_sync)(self.handle);
+        if n < 0 { None } else { Some(n as u32) }
+    }
+
+    // ── NOR flash ───────────────────────────────────────────────
+
+    /// Map a CFI NOR flash of `size` bytes at guest physical `base`,
+    /// erasable in `sector_size` blocks (e.g. firmware flash with an
+    /// NVRAM area).
+    ///
+    /// Contents are loaded from the host file `path` if it exists, else
+    /// from `init`; guest changes are written back to `path` by
+    /// [`flash_sync`](Self::flash_sync) and when the VM is dropped. On
+    /// failure returns the negative `corevm_flash_attach` error code.
+    pub fn flash_attach(
+        &self,
+        base: u64,
+        size: u32,
+        sector_size: u32,
+        path: Option<&str>,
+        init: &[u8],
+    ) -> Result<(), i32> {
+        let (p, plen) = match path {
+            Some(p) => (p.as_ptr(), p.len() as u32),
+            None => (core::ptr::null(), 0),
+        };
+        let i = if init.is_empty() { core::ptr::null() } else { init.as_ptr() };
+        match (lib().flash_attach)(self.handle, base, size, sector_size, p, plen, i, init.len() as u32) {
+            0 => Ok(()),
+            err => Err(err),
+        }
+    }
+
+    /// Write modified flash contents back to their host files now.
+    /// Returns the number of files written, or `None` if a write failed.
+    pub fn flash_sync(&self) -> Option<u32> {
+        let n = (lib().flash_sync)(self.handle);
+        if n < 0 { None } else { Some(n as u32) }
+    }
+
     // ── Error reporting ─────────────────────────────────────────
 
     /// Get a human-readable description of the last error.
@@ -946,6 +1876,171 @@ impl Drop for VmHandle {
     }
 }
 
+// ══════════════════════════════════════════════════════════════════════
+//  Machine description builder
+// ══════════════════════════════════════════════════════════════════════
+
+/// Declarative machine description for [`VmHandle::configure`].
+///
+/// Encodes the nested tag/length/value blob understood by
+/// `corevm_configure`. Devices not added are absent; omitted bases use the
+/// standard PC addresses.
+///
+/// ```rust
+/// let m = Machine::new()
+///     .ram_mb(256)
+///     .standard()
+///     .e1000(0xFEB8_0000, &[0x52, 0x54, 0x00, 0x12, 0x34, 0x56])
+///     .ide(Some(&disk));
+/// vm.configure(&m)?;
+/// ```
+#[derive(Default)]
+pub struct Machine<'a> {
+    nodes: Vec<u8>,
+    disk: Option<&'a [u8]>,
+    ide: Option<(u16, u16)>,
+}
+
+const MACHINE_MAGIC: &[u8; 4] = b"CVM1";
+
+const TAG_RAM: u8 = 0x01;
+const TAG_BIOS: u8 = 0x02;
+const TAG_STANDARD: u8 = 0x0F;
+const TAG_PIC: u8 = 0x10;
+const TAG_PIT: u8 = 0x11;
+const TAG_CMOS: u8 = 0x12;
+const TAG_PS2: u8 = 0x13;
+const TAG_SERIAL: u8 = 0x14;
+const TAG_VGA: u8 = 0x15;
+const TAG_PCI: u8 = 0x16;
+const TAG_E1000: u8 = 0x20;
+const TAG_IDE: u8 = 0x21;
+const TAG_BALLOON: u8 = 0x22;
+
+const PROP_PORT: u8 = 0x80;
+const PROP_IRQ: u8 = 0x81;
+const PROP_MMIO: u8 = 0x82;
+const PROP_MAC: u8 = 0x83;
+const PROP_WIDTH: u8 = 0x84;
+const PROP_HEIGHT: u8 = 0x85;
+const PROP_CTRL_PORT: u8 = 0x86;
+const PROP_DISK: u8 = 0x87;
+
+/// Append a `tag:u8 len:u16 body` node.
+fn push_node(out: &mut Vec<u8>, tag: u8, body: &[u8]) {
+    out.push(tag);
+    out.extend_from_slice(&(body.len() as u16).to_le_bytes());
+    out.extend_from_slice(body);
+}
+
+impl<'a> Machine<'a> {
+    /// Empty machine (no devices).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Guest RAM size, overriding the size passed to [`VmHandle::new`].
+    pub fn ram_mb(mut self, mb: u32) -> Self {
+        push_node(&mut self.nodes, TAG_RAM, &mb.to_le_bytes());
+        self
+    }
+
+    /// All devices of [`VmHandle::setup_standard_devices`].
+    pub fn standard(mut self) -> Self {
+        push_node(&mut self.nodes, TAG_STANDARD, &[]);
+        self
+    }
+
+    /// Load the built-in BIOS once the devices exist.
+    pub fn bios(mut self) -> Self {
+        push_node(&mut self.nodes, TAG_BIOS, &[]);
+        self
+    }
+
+    /// Dual 8259A PIC, 8254 PIT, CMOS and PS/2 controller.
+    pub fn legacy_pc(mut self) -> Self {
+        for tag in [TAG_PIC, TAG_PIT, TAG_CMOS, TAG_PS2] {
+            push_node(&mut self.nodes, tag, &[]);
+        }
+        self
+    }
+
+    /// A 16550 UART in the next free COM slot (max 4).
+    pub fn serial(mut self, port: u16, irq: u8) -> Self {
+        let mut body = Vec::new();
+        push_node(&mut body, PROP_PORT, &port.to_le_bytes());
+        push_node(&mut body, PROP_IRQ, &[irq]);
+        push_node(&mut self.nodes, TAG_SERIAL, &body);
+        self
+    }
+
+    /// VGA/SVGA adapter with a linear framebuffer of up to `width` x `height`.
+    pub fn vga(mut self, width: u32, height: u32) -> Self {
+        let mut body = Vec::new();
+        push_node(&mut body, PROP_WIDTH, &width.to_le_bytes());
+        push_node(&mut body, PROP_HEIGHT, &height.to_le_bytes());
+        push_node(&mut self.nodes, TAG_VGA, &body);
+        self
+    }
+
+    /// i440FX PCI platform (host bridge, ISA bridge, VGA function).
+    pub fn pci(mut self) -> Self {
+        push_node(&mut self.nodes, TAG_PCI, &[]);
+        self
+    }
+
+    /// Intel E1000 NIC at `mmio_base` with the given MAC address. May be
+    /// called up to 4 times; NICs are indexed in call order.
+    pub fn e1000(mut self, mmio_base: u64, mac: &[u8; 6]) -> Self {
+        let mut body = Vec::new();
+        push_node(&mut body, PROP_MMIO, &mmio_base.to_le_bytes());
+        push_node(&mut body, PROP_MAC, mac);
+        push_node(&mut self.nodes, TAG_E1000, &body);
+        self
+    }
+
+    /// Memory balloon at the standard port (0x5A0), letting the host
+    /// reclaim guest RAM with [`VmHandle::balloon_set_target`].
+    pub fn balloon(mut self) -> Self {
+        push_node(&mut self.nodes, TAG_BALLOON, &[]);
+        self
+    }
+
+    /// IDE controller at the standard ports, optionally with a disk image
+    /// (copied into the VM by [`VmHandle::configure`]).
+    pub fn ide(self, disk: Option<&'a [u8]>) -> Self {
+        self.ide_at(0x1F0, 0x3F6, disk)
+    }
+
+    /// IDE controller with its command block at `port` and control block
+    /// at `ctrl_port`.
+    pub fn ide_at(mut self, port: u16, ctrl_port: u16, disk: Option<&'a [u8]>) -> Self {
+        self.ide = Some((port, ctrl_port));
+        self.disk = disk;
+        self
+    }
+
+    /// Encode the description blob. The disk pointer stays valid while `self` lives.
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(4 + self.nodes.len() + 32);
+        out.extend_from_slice(MACHINE_MAGIC);
+        out.extend_from_slice(&self.nodes);
+        if let Some((port, ctrl_port)) = self.ide {
+            let mut body = Vec::new();
+            push_node(&mut body, PROP_PORT, &port.to_le_bytes());
+            push_node(&mut body, PROP_CTRL_PORT, &ctrl_port.to_le_bytes());
+            if let Some(disk) = self.disk.filter(|d| !d.is_empty()) {
+                let mut d = [0u8; 12];
+                d[..8].copy_from_slice(&(disk.as_ptr() as u64).to_le_bytes());
+                d[8..].copy_from_slice(&(disk.len() as u32).to_le_bytes());
+                push_node(&mut body, PROP_DISK, &d);
+            }
+            push_node(&mut out, TAG_IDE, &body);
+        }
+        out
+    }
+}
+
 // ══════════════════════════════════════════════════════════════════════
 //  GPR index constants (convenience)
 // ══════════════════════════════════════════════════════════════════════
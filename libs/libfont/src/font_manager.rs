@@ -3,6 +3,11 @@
 //!
 //! Performance-critical: all `/ 255` replaced with `div255()` bit trick,
 //! glyph cache uses hash table for O(1) lookup, FIR filter uses fixed-point.
+//!
+//! Characters missing from the requested font fall back to the color emoji
+//! font. Emoji sequences (ZWJ families, flags, skin tones, keycaps, tag
+//! flags) are looked up as one cluster and drawn as the font's ligature
+//! glyph; joiners and variation selectors left over are zero-width.
 
 use alloc::boxed::Box;
 use alloc::vec::Vec;
@@ -299,6 +304,62 @@ impl FontManager {
         idx
     }
 
+    /// Advance width of `glyph_id` in `font_id` at `size`, in pixels.
+    fn advance_px(&self, font_id: u16, glyph_id: u16, size: u16) -> u32 {
+        match self.get_font(font_id) {
+            Some(ttf) => {
+                let upm = ttf.units_per_em as u32;
+                (ttf.advance_width(glyph_id) as u32 * size as u32 + upm / 2) / upm
+            }
+            None => 0,
+        }
+    }
+
+    /// Resolve the glyph for the character or emoji sequence starting at
+    /// byte `i` of `text`. Returns `((glyph, font), end)`; the glyph is
+    /// `None` for zero-width format characters, which draw nothing.
+    fn resolve_glyph(&mut self, font_id: u16, text: &str, i: usize) -> (Option<(u16, u16)>, usize) {
+        let ch = match text[i..].chars().next() {
+            Some(c) => c,
+            None => return (None, text.len()),
+        };
+        let next = i + ch.len_utf8();
+        if is_ignorable(ch) {
+            return (None, next);
+        }
+        let primary = match self.get_font_mut(font_id) {
+            Some(f) => f.char_to_glyph_cached(ch as u32),
+            None => 0,
+        };
+        let end = emoji_sequence_end(text, i);
+        let text_style = text[next..end].contains('\u{FE0E}');
+        let want_emoji = font_id != SYSTEM_FONT_EMOJI
+            && (primary == 0 || (end > next && !text_style));
+        if want_emoji {
+            if let Some(ef) = self.get_font_mut(SYSTEM_FONT_EMOJI) {
+                // Glyphs of the sequence (selectors dropped) and the byte
+                // offset each one ends at.
+                let mut glyphs = [0u16; MAX_SEQUENCE];
+                let mut ends = [0usize; MAX_SEQUENCE];
+                let mut n = 0;
+                for (off, c) in text[i..end].char_indices() {
+                    if c == '\u{FE0E}' || c == '\u{FE0F}' { continue; }
+                    if n == MAX_SEQUENCE { break; }
+                    glyphs[n] = ef.char_to_glyph_cached(c as u32);
+                    ends[n] = i + off + c.len_utf8();
+                    n += 1;
+                }
+                if n > 0 && glyphs[0] != 0 {
+                    if let Some((lig, used)) = ef.ttf.ligature(&glyphs[..n]) {
+                        return (Some((lig, SYSTEM_FONT_EMOJI)), ends[used - 1]);
+                    }
+                    return (Some((glyphs[0], SYSTEM_FONT_EMOJI)), next);
+                }
+            }
+        }
+        (Some((primary, font_id)), next)
+    }
+
     fn rasterize_and_cache(
         &mut self, font_id: u16, glyph_id: u16, size: u16, subpixel: bool,
     ) -> Option<usize> {
@@ -323,7 +384,7 @@ impl FontManager {
 
         // Try bitmap glyph (CBDT/CBLC) — e.g. NotoColorEmoji
         if ttf.has_bitmaps {
-            if let Some(bmp) = ttf.get_bitmap_glyph(glyph_id) {
+            if let Some(bmp) = ttf.get_bitmap_glyph(glyph_id, size) {
                 if let Some(png) = png_decode::decode_png(bmp.png_data) {
                     let scale_num = size as u32;
                     let scale_den = bmp.strike_ppem as u32;
@@ -353,6 +414,54 @@ impl FontManager {
     }
 }
 
+// ─── Emoji sequences ─────────────────────────────────────────────────────
+
+/// Longest emoji sequence (in characters) looked up as one ligature.
+const MAX_SEQUENCE: usize = 16;
+
+/// Zero-width format characters that never draw a glyph of their own:
+/// joiners, directional marks, variation selectors and tag characters.
+fn is_ignorable(ch: char) -> bool {
+    matches!(ch as u32, 0x200B..=0x200F | 0x2060..=0x2064 | 0xFE00..=0xFE0F | 0xFEFF
+        | 0xE0000..=0xE007F | 0xE0100..=0xE01EF)
+}
+
+fn is_regional_indicator(ch: char) -> bool {
+    matches!(ch as u32, 0x1F1E6..=0x1F1FF)
+}
+
+/// End (byte offset) of the emoji sequence starting at byte `i`: the base
+/// character plus any variation selectors, skin-tone modifiers, keycap and
+/// tag characters, ZWJ-joined characters, or the second regional indicator
+/// of a flag. Equals the end of the first character if nothing follows.
+fn emoji_sequence_end(text: &str, i: usize) -> usize {
+    let mut chars = text[i..].char_indices();
+    let base = match chars.next() {
+        Some((_, c)) => c,
+        None => return i,
+    };
+    let mut end = i + base.len_utf8();
+    let mut prev = base;
+    let mut count = 1;
+    for (off, c) in chars {
+        if count == MAX_SEQUENCE { break; }
+        let joins = match c as u32 {
+            0xFE0E | 0xFE0F | 0x1F3FB..=0x1F3FF | 0x20E3 | 0xE0020..=0xE007F | 0x200D => true,
+            _ if prev == '\u{200D}' => true,
+            _ => count == 1 && is_regional_indicator(base) && is_regional_indicator(c),
+        };
+        if !joins { break; }
+        end = i + off + c.len_utf8();
+        prev = c;
+        count += 1;
+    }
+    // A trailing joiner belongs to nothing.
+    if prev == '\u{200D}' && end > i + base.len_utf8() {
+        end -= '\u{200D}'.len_utf8();
+    }
+    end
+}
+
 fn line_height_internal(ttf: &TtfFont, size: u16) -> u32 {
     let ascent = ttf.ascent.unsigned_abs() as u32;
     let descent = ttf.descent.unsigned_abs() as u32;
@@ -487,6 +596,26 @@ pub fn load_font(path: &[u8]) -> u32 {
     }
 }
 
+/// Load a custom TTF font from memory. Returns font_id or u32::MAX.
+pub fn load_font_memory(data: &[u8]) -> u32 {
+    let mgr = match ensure_init() {
+        Some(m) => m,
+        None => return u32::MAX,
+    };
+    match TtfFont::parse(data.to_vec()) {
+        Some(ttf) => mgr.add_font(ttf) as u32,
+        None => u32::MAX,
+    }
+}
+
+/// Whether `font_id` maps `codepoint` to a real glyph (not .notdef).
+pub fn has_glyph(font_id: u16, codepoint: u32) -> bool {
+    match ensure_init().and_then(|m| m.get_font_mut(font_id)) {
+        Some(f) => f.char_to_glyph_cached(codepoint) != 0,
+        None => false,
+    }
+}
+
 /// Unload a font by ID. Cannot unload system font (ID 0).
 pub fn unload_font(font_id: u16) {
     if font_id == SYSTEM_FONT_ID {
@@ -543,11 +672,17 @@ pub fn measure_string(text: &str, font_id: u16, size: u16) -> (u32, u32) {
     let mut max_width = 0u32;
     let mut lines = 1u32;
 
-    for ch in text.chars() {
+    let mut i = 0;
+    while i < text.len() {
+        let ch = match text[i..].chars().next() {
+            Some(c) => c,
+            None => break,
+        };
         if ch == '\n' {
             max_width = max_width.max(width);
             width = 0;
             lines += 1;
+            i += 1;
             continue;
         }
         if ch == '\t' {
@@ -557,32 +692,15 @@ pub fn measure_string(text: &str, font_id: u16, size: u16) -> (u32, u32) {
                 let space_adv = font.ttf.advance_width(space_gid) as u32;
                 width += space_adv * 4 * size as u32 / upm;
             }
+            i += 1;
             continue;
         }
 
-        // Get glyph from primary font
-        let gid = match mgr.get_font_mut(actual_font_id) {
-            Some(font) => font.char_to_glyph_cached(ch as u32),
-            None => 0,
-        };
-
-        // Fallback to emoji font if glyph missing
-        if gid == 0 && actual_font_id != SYSTEM_FONT_EMOJI {
-            if let Some(emoji_font) = mgr.get_font_mut(SYSTEM_FONT_EMOJI) {
-                let emoji_gid = emoji_font.char_to_glyph_cached(ch as u32);
-                if emoji_gid != 0 {
-                    let adv = emoji_font.ttf.advance_width(emoji_gid) as u32;
-                    let emoji_upm = emoji_font.ttf.units_per_em as u32;
-                    width += (adv * size as u32 + emoji_upm / 2) / emoji_upm;
-                    continue;
-                }
-            }
-        }
-
-        // Use primary font advance
-        if let Some(font) = mgr.get_font_mut(actual_font_id) {
-            let adv = font.ttf.advance_width(gid) as u32;
-            width += (adv * size as u32 + upm / 2) / upm;
+        // Primary font glyph, emoji fallback, or emoji sequence ligature
+        let (glyph, end) = mgr.resolve_glyph(actual_font_id, text, i);
+        i = end;
+        if let Some((gid, render_font_id)) = glyph {
+            width += mgr.advance_px(render_font_id, gid, size);
         }
     }
     max_width = max_width.max(width);
@@ -628,7 +746,7 @@ pub fn draw_string_buf_clipped(
     let smoothing = read_font_smoothing();
     let subpixel = smoothing == 2;
     let actual_font_id = if mgr.get_font(font_id).is_some() { font_id } else { SYSTEM_FONT_ID };
-    let (upm, ascent_px, lh, tab_advance) = {
+    let (ascent_px, lh, tab_advance) = {
         let ttf = match mgr.get_font(actual_font_id) {
             Some(t) => t,
             None => return,
@@ -639,7 +757,7 @@ pub fn draw_string_buf_clipped(
         let space_gid = ttf.char_to_glyph(b' ' as u32);
         let space_adv = ttf.advance_width(space_gid) as u32;
         let tab_advance = (space_adv * 4 * size as u32 / upm) as i32;
-        (upm, ascent_px, lh, tab_advance)
+        (ascent_px, lh, tab_advance)
     };
 
     let mut cx = x;
@@ -650,56 +768,46 @@ pub fn draw_string_buf_clipped(
     let col_g = ((color >> 8) & 0xFF) as u8;
     let col_b = (color & 0xFF) as u8;
 
-    for ch in text.chars() {
+    let mut i = 0;
+    while i < text.len() {
         // Early exit: cursor past right edge of clip rect
         if cx >= clip_r { break; }
 
+        let ch = match text[i..].chars().next() {
+            Some(c) => c,
+            None => break,
+        };
         if ch == '\n' {
             cx = x;
             cy += lh;
+            i += 1;
             continue;
         }
         if ch == '\t' {
             cx += tab_advance;
+            i += 1;
             continue;
         }
 
-        // Step 1: Get glyph ID from primary font
-        let primary_gid = match mgr.get_font_mut(actual_font_id) {
-            Some(f) => f.char_to_glyph_cached(ch as u32),
+        // Step 1-2: Glyph from the primary font, else the emoji font
+        // (sequences resolve to a single ligature glyph)
+        let (glyph, end) = mgr.resolve_glyph(actual_font_id, text, i);
+        i = end;
+        let (gid, render_font_id) = match glyph {
+            Some(g) => g,
             None => continue,
         };
 
-        // Step 2: Fallback to emoji font if glyph missing
-        let (gid, render_font_id) = if primary_gid == 0 && actual_font_id != SYSTEM_FONT_EMOJI {
-            match mgr.get_font_mut(SYSTEM_FONT_EMOJI) {
-                Some(ef) => {
-                    let eid = ef.char_to_glyph_cached(ch as u32);
-                    if eid != 0 { (eid, SYSTEM_FONT_EMOJI) } else { (primary_gid, actual_font_id) }
-                }
-                None => (primary_gid, actual_font_id),
-            }
-        } else {
-            (primary_gid, actual_font_id)
-        };
-
         // Step 3: Compute advance width from the resolved font
-        let advance_px = match mgr.get_font(render_font_id) {
-            Some(ttf) => {
-                let adv_fu = ttf.advance_width(gid);
-                let font_upm = ttf.units_per_em as u32;
-                (adv_fu as u32 * size as u32 + font_upm / 2) / font_upm
-            }
-            None => continue,
-        };
+        let advance_px = mgr.advance_px(render_font_id, gid, size);
 
         // Step 4: Rasterize/cache and draw
         let cache_idx = mgr.find_cached(render_font_id, gid, size, subpixel);
-        let idx = if let Some(i) = cache_idx {
-            i
+        let idx = if let Some(ci) = cache_idx {
+            ci
         } else {
             match mgr.rasterize_and_cache(render_font_id, gid, size, subpixel) {
-                Some(i) => i,
+                Some(ci) => ci,
                 None => {
                     cx += advance_px as i32;
                     continue;
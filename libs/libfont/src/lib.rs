@@ -51,6 +51,21 @@ pub extern "C" fn font_load(path_ptr: *const u8, path_len: u32) -> u32 {
     font_manager::load_font(path)
 }
 
+/// Load a TTF font from memory (e.g. a downloaded web font). The data is
+/// copied. Returns font_id, or u32::MAX if it is not a usable TrueType font.
+#[no_mangle]
+pub extern "C" fn font_load_memory(data_ptr: *const u8, data_len: u32) -> u32 {
+    let data = unsafe { core::slice::from_raw_parts(data_ptr, data_len as usize) };
+    font_manager::load_font_memory(data)
+}
+
+/// Whether a font has its own glyph for a Unicode codepoint (no fallback
+/// to the emoji or system font). Returns 1 or 0.
+#[no_mangle]
+pub extern "C" fn font_has_glyph(font_id: u32, codepoint: u32) -> u32 {
+    font_manager::has_glyph(font_id as u16, codepoint) as u32
+}
+
 /// Unload a previously loaded font by ID.
 #[no_mangle]
 pub extern "C" fn font_unload(font_id: u32) {
@@ -3,7 +3,8 @@
 //!
 //! Zero-copy parser that operates on `&[u8]` references into raw TTF data.
 //! Supports simple and composite glyphs, cmap format 4 and 12 character mapping,
-//! horizontal metrics, and CBDT/CBLC color bitmap lookup.
+//! horizontal metrics, CBDT/CBLC color bitmap lookup, and GSUB ligatures
+//! (used by the emoji font for ZWJ, flag, skin-tone and keycap sequences).
 
 use alloc::vec::Vec;
 
@@ -160,6 +161,8 @@ pub struct TtfFont {
     pub cblc_offset: usize,
     /// Byte offset of the `CBDT` table.
     pub cbdt_offset: usize,
+    /// Byte offset of the `GSUB` table (0 = none).
+    pub gsub_offset: usize,
 }
 
 impl TtfFont {
@@ -201,6 +204,7 @@ impl TtfFont {
         let mut glyf_off: Option<usize> = None;
         let mut cblc_off: Option<usize> = None;
         let mut cbdt_off: Option<usize> = None;
+        let mut gsub_off: usize = 0;
 
         for i in 0..num_tables {
             let rec = 12 + i * 16;
@@ -217,6 +221,7 @@ impl TtfFont {
                 0x676C7966 => glyf_off = Some(offset), // 'glyf'
                 0x43424C43 => cblc_off = Some(offset), // 'CBLC'
                 0x43424454 => cbdt_off = Some(offset), // 'CBDT'
+                0x47535542 => gsub_off = offset,       // 'GSUB'
                 _ => {}
             }
         }
@@ -268,6 +273,7 @@ impl TtfFont {
             has_bitmaps,
             cblc_offset: cblc_off,
             cbdt_offset: cbdt_off,
+            gsub_offset: gsub_off,
         })
     }
 
@@ -725,16 +731,20 @@ impl TtfFont {
 
     /// Look up a bitmap glyph from CBLC/CBDT tables.
     /// Returns the raw PNG data and metrics for imageFormat 17.
-    pub fn get_bitmap_glyph(&self, glyph_id: u16) -> Option<BitmapGlyphData> {
+    ///
+    /// The strike chosen is the smallest one at least `ppem` pixels high
+    /// (downscaling looks better than upscaling), else the largest.
+    pub fn get_bitmap_glyph(&self, glyph_id: u16, ppem: u16) -> Option<BitmapGlyphData> {
         if !self.has_bitmaps { return None; }
         let d = &self.data;
         let cblc = self.cblc_offset;
-        let cbdt = self.cbdt_offset;
 
         if d.len() < cblc + 8 { return None; }
         let num_sizes = read_u32_be(d, cblc + 4) as usize;
 
-        // Iterate BitmapSize records (48 bytes each, starting at offset 8)
+        // BitmapSize records (48 bytes each, starting at offset 8) covering
+        // the glyph, best strike first.
+        let mut strikes: Vec<(usize, u8)> = Vec::new();
         for s in 0..num_sizes {
             let bs = cblc + 8 + s * 48;
             if d.len() < bs + 48 { continue; }
@@ -742,73 +752,199 @@ impl TtfFont {
             let start_glyph = read_u16_be(d, bs + 40);
             let end_glyph = read_u16_be(d, bs + 42);
             if glyph_id < start_glyph || glyph_id > end_glyph { continue; }
+            strikes.push((bs, d[bs + 44]));
+        }
+        strikes.sort_unstable_by_key(|&(_, p)| {
+            if p as u16 >= ppem { (0, p as i32) } else { (1, -(p as i32)) }
+        });
 
-            let ppem_x = d[bs + 44];
-            let index_sub_array_off = read_u32_be(d, bs) as usize;
-            let num_index_sub_tables = read_u32_be(d, bs + 8) as usize;
+        strikes.iter().find_map(|&(bs, ppem_x)| self.strike_glyph(bs, ppem_x, glyph_id))
+    }
+
+    /// Look up `glyph_id` in the strike whose BitmapSize record is at `bs`.
+    fn strike_glyph(&self, bs: usize, ppem_x: u8, glyph_id: u16) -> Option<BitmapGlyphData<'_>> {
+        let d = &self.data;
+        let cblc = self.cblc_offset;
+        let cbdt = self.cbdt_offset;
 
-            let arr_base = cblc + index_sub_array_off;
+        let index_sub_array_off = read_u32_be(d, bs) as usize;
+        let num_index_sub_tables = read_u32_be(d, bs + 8) as usize;
 
-            // Search IndexSubTableArray entries (8 bytes each)
-            for ist in 0..num_index_sub_tables {
-                let entry = arr_base + ist * 8;
-                if d.len() < entry + 8 { continue; }
+        let arr_base = cblc + index_sub_array_off;
 
-                let first = read_u16_be(d, entry);
-                let last = read_u16_be(d, entry + 2);
-                if glyph_id < first || glyph_id > last { continue; }
+        // Search IndexSubTableArray entries (8 bytes each)
+        for ist in 0..num_index_sub_tables {
+            let entry = arr_base + ist * 8;
+            if d.len() < entry + 8 { continue; }
 
-                let additional_off = read_u32_be(d, entry + 4) as usize;
-                let sub_table = arr_base + additional_off;
-                if d.len() < sub_table + 8 { continue; }
+            let first = read_u16_be(d, entry);
+            let last = read_u16_be(d, entry + 2);
+            if glyph_id < first || glyph_id > last { continue; }
 
-                let index_format = read_u16_be(d, sub_table);
-                let image_format = read_u16_be(d, sub_table + 2);
-                let image_data_offset = read_u32_be(d, sub_table + 4) as usize;
+            let additional_off = read_u32_be(d, entry + 4) as usize;
+            let sub_table = arr_base + additional_off;
+            if d.len() < sub_table + 8 { continue; }
 
-                // Only support imageFormat 17 (SmallGlyphMetrics + PNG data)
-                if image_format != 17 { continue; }
+            let index_format = read_u16_be(d, sub_table);
+            let image_format = read_u16_be(d, sub_table + 2);
+            let image_data_offset = read_u32_be(d, sub_table + 4) as usize;
 
-                let glyph_idx = (glyph_id - first) as usize;
+            // Only support imageFormat 17 (SmallGlyphMetrics + PNG data)
+            if image_format != 17 { continue; }
 
-                let data_offset = match index_format {
-                    1 => {
-                        // IndexSubTable1: array of u32 sbitOffsets
-                        let off_entry = sub_table + 8 + glyph_idx * 4;
-                        if d.len() < off_entry + 4 { continue; }
-                        let off = read_u32_be(d, off_entry) as usize;
-                        cbdt + image_data_offset + off
-                    }
-                    3 => {
-                        // IndexSubTable3: array of u16 sbitOffsets
-                        let off_entry = sub_table + 8 + glyph_idx * 2;
-                        if d.len() < off_entry + 2 { continue; }
-                        let off = read_u16_be(d, off_entry) as usize;
-                        cbdt + image_data_offset + off
-                    }
-                    _ => continue,
-                };
-
-                // SmallGlyphMetrics (5 bytes) + dataLen (4 bytes) + PNG data
-                if d.len() < data_offset + 9 { continue; }
-                let height = d[data_offset];
-                let width = d[data_offset + 1];
-                let bearing_x = d[data_offset + 2] as i8;
-                let bearing_y = d[data_offset + 3] as i8;
-                let advance = d[data_offset + 4];
-                let data_len = read_u32_be(d, data_offset + 5) as usize;
-
-                if d.len() < data_offset + 9 + data_len { continue; }
-                let png_data = &d[data_offset + 9..data_offset + 9 + data_len];
-
-                return Some(BitmapGlyphData {
-                    width, height, bearing_x, bearing_y, advance,
-                    strike_ppem: ppem_x,
-                    png_data,
-                });
+            let glyph_idx = (glyph_id - first) as usize;
+
+            let data_offset = match index_format {
+                1 => {
+                    // IndexSubTable1: array of u32 sbitOffsets
+                    let off_entry = sub_table + 8 + glyph_idx * 4;
+                    if d.len() < off_entry + 4 { continue; }
+                    let off = read_u32_be(d, off_entry) as usize;
+                    cbdt + image_data_offset + off
+                }
+                3 => {
+                    // IndexSubTable3: array of u16 sbitOffsets
+                    let off_entry = sub_table + 8 + glyph_idx * 2;
+                    if d.len() < off_entry + 2 { continue; }
+                    let off = read_u16_be(d, off_entry) as usize;
+                    cbdt + image_data_offset + off
+                }
+                _ => continue,
+            };
+
+            // SmallGlyphMetrics (5 bytes) + dataLen (4 bytes) + PNG data
+            if d.len() < data_offset + 9 { continue; }
+            let height = d[data_offset];
+            let width = d[data_offset + 1];
+            let bearing_x = d[data_offset + 2] as i8;
+            let bearing_y = d[data_offset + 3] as i8;
+            let advance = d[data_offset + 4];
+            let data_len = read_u32_be(d, data_offset + 5) as usize;
+
+            if d.len() < data_offset + 9 + data_len { continue; }
+            let png_data = &d[data_offset + 9..data_offset + 9 + data_len];
+
+            return Some(BitmapGlyphData {
+                width, height, bearing_x, bearing_y, advance,
+                strike_ppem: ppem_x,
+                png_data,
+            });
+        }
+
+        None
+    }
+
+    // ------------------------------------------------------------------
+    // GSUB: ligature substitution
+    // ------------------------------------------------------------------
+
+    /// Find a ligature for the glyph sequence starting at `glyphs[0]`.
+    /// Returns the ligature glyph and how many input glyphs it replaces.
+    ///
+    /// Only ligature lookups (type 4, also behind extension lookups) are
+    /// applied, in lookup order; within a lookup the font's ligature order
+    /// decides, as the spec requires. That covers emoji sequences, which is
+    /// all the engine uses it for.
+    pub fn ligature(&self, glyphs: &[u16]) -> Option<(u16, usize)> {
+        let g = self.gsub_offset;
+        let d = &self.data;
+        if g == 0 || glyphs.len() < 2 || d.len() < g + 10 { return None; }
+
+        let lookup_list = g + read_u16_be(d, g + 8) as usize;
+        if d.len() < lookup_list + 2 { return None; }
+        let num_lookups = read_u16_be(d, lookup_list) as usize;
+        for l in 0..num_lookups {
+            let rec = lookup_list + 2 + l * 2;
+            if d.len() < rec + 2 { break; }
+            let lookup = lookup_list + read_u16_be(d, rec) as usize;
+            if d.len() < lookup + 6 { continue; }
+            let lookup_type = read_u16_be(d, lookup);
+            let num_subtables = read_u16_be(d, lookup + 4) as usize;
+            for st in 0..num_subtables {
+                if d.len() < lookup + 8 + st * 2 { break; }
+                let mut sub = lookup + read_u16_be(d, lookup + 6 + st * 2) as usize;
+                let mut sub_type = lookup_type;
+                if sub_type == 7 {
+                    // ExtensionSubstFormat1: real type + 32-bit offset
+                    if d.len() < sub + 8 { continue; }
+                    sub_type = read_u16_be(d, sub + 2);
+                    sub += read_u32_be(d, sub + 4) as usize;
+                }
+                if sub_type != 4 { continue; }
+                if let Some(hit) = self.ligature_subtable(sub, glyphs) {
+                    return Some(hit);
+                }
             }
         }
+        None
+    }
 
+    /// LigatureSubstFormat1 lookup of `glyphs` in the subtable at `sub`.
+    fn ligature_subtable(&self, sub: usize, glyphs: &[u16]) -> Option<(u16, usize)> {
+        let d = &self.data;
+        if d.len() < sub + 6 || read_u16_be(d, sub) != 1 { return None; }
+        let coverage = sub + read_u16_be(d, sub + 2) as usize;
+        let index = self.coverage_index(coverage, glyphs[0])?;
+        if index >= read_u16_be(d, sub + 4) as usize { return None; }
+        let set = sub + read_u16_be(d, sub + 6 + index * 2) as usize;
+        if d.len() < set + 2 { return None; }
+        let count = read_u16_be(d, set) as usize;
+        for i in 0..count {
+            let lig = set + read_u16_be(d, set + 2 + i * 2) as usize;
+            if d.len() < lig + 4 { continue; }
+            let lig_glyph = read_u16_be(d, lig);
+            let components = read_u16_be(d, lig + 2) as usize;
+            if components < 2 || components > glyphs.len() || d.len() < lig + 2 + components * 2 {
+                continue;
+            }
+            let matches = (1..components).all(|c| read_u16_be(d, lig + 2 + c * 2) == glyphs[c]);
+            if matches {
+                return Some((lig_glyph, components));
+            }
+        }
         None
     }
+
+    /// Coverage index of `glyph` in the Coverage table at `cov`.
+    fn coverage_index(&self, cov: usize, glyph: u16) -> Option<usize> {
+        let d = &self.data;
+        if d.len() < cov + 4 { return None; }
+        let format = read_u16_be(d, cov);
+        let count = read_u16_be(d, cov + 2) as usize;
+        match format {
+            1 => {
+                // Sorted glyph array
+                let (mut lo, mut hi) = (0, count);
+                while lo < hi {
+                    let mid = (lo + hi) / 2;
+                    let off = cov + 4 + mid * 2;
+                    if d.len() < off + 2 { return None; }
+                    let g = read_u16_be(d, off);
+                    if g == glyph { return Some(mid); }
+                    if g < glyph { lo = mid + 1; } else { hi = mid; }
+                }
+                None
+            }
+            2 => {
+                // Sorted range records: start, end, startCoverageIndex
+                let (mut lo, mut hi) = (0, count);
+                while lo < hi {
+                    let mid = (lo + hi) / 2;
+                    let rec = cov + 4 + mid * 6;
+                    if d.len() < rec + 6 { return None; }
+                    let start = read_u16_be(d, rec);
+                    let end = read_u16_be(d, rec + 2);
+                    if glyph < start {
+                        hi = mid;
+                    } else if glyph > end {
+                        lo = mid + 1;
+                    } else {
+                        return Some(read_u16_be(d, rec + 4) as usize + (glyph - start) as usize);
+                    }
+                }
+                None
+            }
+            _ => None,
+        }
+    }
 }
@@ -14,11 +14,13 @@ struct FontLib {
     _handle: DlHandle,
     init_fn: extern "C" fn(),
     load_fn: extern "C" fn(*const u8, u32) -> u32,
+    load_memory_fn: extern "C" fn(*const u8, u32) -> u32,
     unload_fn: extern "C" fn(u32),
     measure_fn: extern "C" fn(u32, u16, *const u8, u32, *mut u32, *mut u32),
     draw_fn: extern "C" fn(*mut u32, u32, u32, i32, i32, u32, u32, u16, *const u8, u32),
     line_height_fn: extern "C" fn(u32, u16) -> u32,
     set_subpixel_fn: extern "C" fn(u32),
+    has_glyph_fn: extern "C" fn(u32, u32) -> u32,
 }
 
 static mut LIB: Option<FontLib> = None;
@@ -45,11 +47,13 @@ pub fn init() -> bool {
         let lib = FontLib {
             init_fn: resolve(&handle, "font_init"),
             load_fn: resolve(&handle, "font_load"),
+            load_memory_fn: resolve(&handle, "font_load_memory"),
             unload_fn: resolve(&handle, "font_unload"),
             measure_fn: resolve(&handle, "font_measure_string"),
             draw_fn: resolve(&handle, "font_draw_string_buf"),
             line_height_fn: resolve(&handle, "font_line_height"),
             set_subpixel_fn: resolve(&handle, "font_set_subpixel"),
+            has_glyph_fn: resolve(&handle, "font_has_glyph"),
             _handle: handle,
         };
         (lib.init_fn)();
@@ -71,6 +75,24 @@ pub fn load(path: &str) -> Option<u32> {
     }
 }
 
+/// Load a TTF font from memory (the data is copied).
+///
+/// Returns `Some(font_id)` on success, or `None` if the data is not a
+/// usable TrueType font.
+pub fn load_memory(data: &[u8]) -> Option<u32> {
+    let id = (lib().load_memory_fn)(data.as_ptr(), data.len() as u32);
+    if id != u32::MAX {
+        Some(id)
+    } else {
+        None
+    }
+}
+
+/// Whether a font has its own glyph for `ch` (no emoji/system fallback).
+pub fn has_glyph(font_id: u32, ch: char) -> bool {
+    (lib().has_glyph_fn)(font_id, ch as u32) != 0
+}
+
 /// Unload a previously loaded font.
 pub fn unload(font_id: u32) {
     (lib().unload_fn)(font_id);
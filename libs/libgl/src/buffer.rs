@@ -2,6 +2,12 @@
 //!
 //! Manages named buffer objects: `glGenBuffers`, `glDeleteBuffers`, `glBindBuffer`,
 //! `glBufferData`, `glBufferSubData`. Data is stored as raw byte `Vec<u8>`.
+//!
+//! `glMapBufferOES` hands out a pointer straight into that storage, so a
+//! client streaming geometry writes each vertex once instead of building a
+//! staging array and copying it in.  Re-specifying a buffer with the same
+//! size and a null pointer (orphaning) keeps the allocation: draws are
+//! executed synchronously, so no earlier draw can still be reading it.
 
 use alloc::vec::Vec;
 use crate::types::*;
@@ -10,6 +16,8 @@ use crate::types::*;
 pub struct GlBuffer {
     pub data: Vec<u8>,
     pub usage: GLenum,
+    /// Mapped by `glMapBufferOES` and not yet unmapped.
+    pub mapped: bool,
 }
 
 /// Storage for all buffer objects.
@@ -40,6 +48,7 @@ impl BufferStore {
             self.slots[id as usize] = Some(GlBuffer {
                 data: Vec::new(),
                 usage: GL_STATIC_DRAW,
+                mapped: false,
             });
             ids[i] = id;
         }
@@ -73,9 +82,49 @@ impl BufferStore {
             buf.data.clear();
             buf.data.extend_from_slice(data);
             buf.usage = usage;
+            buf.mapped = false;
+        }
+    }
+
+    /// Re-specify a buffer without initial data (glBufferData with a null
+    /// pointer). The contents are undefined afterwards, so an orphaned
+    /// buffer of unchanged size keeps its storage as is.
+    pub fn orphan(&mut self, id: u32, size: usize, usage: GLenum) {
+        if let Some(buf) = self.get_mut(id) {
+            if buf.data.len() != size {
+                buf.data.clear();
+                buf.data.resize(size, 0);
+            }
+            buf.usage = usage;
+            buf.mapped = false;
         }
     }
 
+    /// Map a buffer for writing (glMapBufferOES). Returns `None` if the
+    /// buffer does not exist or is already mapped.
+    pub fn map(&mut self, id: u32) -> Option<*mut u8> {
+        let buf = self.get_mut(id)?;
+        if buf.mapped { return None; }
+        buf.mapped = true;
+        Some(buf.data.as_mut_ptr())
+    }
+
+    /// Unmap a buffer (glUnmapBufferOES). Returns `false` if it was not mapped.
+    pub fn unmap(&mut self, id: u32) -> bool {
+        match self.get_mut(id) {
+            Some(buf) if buf.mapped => {
+                buf.mapped = false;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether buffer `id` is currently mapped.
+    pub fn is_mapped(&self, id: u32) -> bool {
+        self.get(id).map_or(false, |b| b.mapped)
+    }
+
     /// Update a sub-region of a buffer (glBufferSubData).
     pub fn buffer_sub_data(&mut self, id: u32, offset: usize, data: &[u8]) {
         if let Some(buf) = self.get_mut(id) {
@@ -10,6 +10,11 @@ use super::ast::*;
 use super::ir::*;
 use crate::types::*;
 
+/// Name of the instance-number input. The vertex shader reads it as an extra
+/// attribute (appended after the declared ones) that the draw call fills
+/// with the current instance instead of fetching it from an array.
+pub const INSTANCE_ID: &str = "gl_InstanceID";
+
 /// Lowering context tracking register allocation and variable mappings.
 struct LowerCtx {
     insts: Vec<Inst>,
@@ -315,6 +320,14 @@ fn lower_expr(ctx: &mut LowerCtx, expr: &Expr) -> Result<u32, String> {
         Expr::Ident(name) => {
             if let Some((reg, _)) = ctx.find_var(name) {
                 Ok(reg)
+            } else if name == INSTANCE_ID && ctx.shader_type == GL_VERTEX_SHADER {
+                // First use: declare the implicit attribute and load it here.
+                let r = ctx.alloc_reg();
+                let idx = ctx.attributes.len() as u32;
+                ctx.attributes.push(VarInfo { name: name.clone(), components: 1, reg: r });
+                ctx.vars.push((name.clone(), r, 1));
+                ctx.insts.push(Inst::LoadAttribute(r, idx));
+                Ok(r)
             } else {
                 // Could be gl_Position etc. — return a temp
                 let r = ctx.alloc_reg();
@@ -17,6 +17,19 @@ pub fn draw_arrays(ctx: &mut GlContext, mode: GLenum, first: GLint, count: GLsiz
     }
 }
 
+/// Execute gl_draw_arrays_instanced.
+pub fn draw_arrays_instanced(
+    ctx: &mut GlContext,
+    mode: GLenum,
+    first: GLint,
+    count: GLsizei,
+    instances: GLsizei,
+) {
+    // The SVGA3D path packs one vertex stream per draw with no instance
+    // data, so instanced drawing always runs on the software rasterizer.
+    rasterizer::draw_instanced(ctx, mode, first, count, instances);
+}
+
 /// Execute glDrawElements.
 pub fn draw_elements(
     ctx: &mut GlContext,
@@ -80,6 +93,18 @@ fn gl_blend_to_svga3d(factor: GLenum) -> u32 {
     }
 }
 
+/// Map GL blend equation to SVGA3D blend equation.
+fn gl_blend_eq_to_svga3d(mode: GLenum) -> u32 {
+    use crate::svga3d::*;
+    match mode {
+        GL_FUNC_SUBTRACT => SVGA3D_BLENDEQ_SUBTRACT,
+        GL_FUNC_REVERSE_SUBTRACT => SVGA3D_BLENDEQ_REVSUBTRACT,
+        GL_MIN => SVGA3D_BLENDEQ_MINIMUM,
+        GL_MAX => SVGA3D_BLENDEQ_MAXIMUM,
+        _ => SVGA3D_BLENDEQ_ADD,
+    }
+}
+
 /// Map GL cull face mode to SVGA3D cull mode.
 fn gl_cull_to_svga3d(cull_face: bool, mode: GLenum) -> u32 {
     use crate::svga3d::*;
@@ -174,6 +199,12 @@ fn draw_arrays_hw(ctx: &mut GlContext, mode: GLenum, first: GLint, count: GLsize
 
     // 4. Set render states from GL context
     let cid = svga.context_id;
+    // Depth bias is in depth-buffer units; the HW depth buffer is D24S8.
+    let (slope_bias, depth_bias) = if ctx.polygon_offset_fill {
+        (ctx.polygon_offset_factor, ctx.polygon_offset_units / 16_777_216.0)
+    } else {
+        (0.0f32, 0.0f32)
+    };
     svga.cmd.set_render_states(cid, &[
         (SVGA3D_RS_ZENABLE, ctx.depth_test as u32),
         (SVGA3D_RS_ZWRITEENABLE, ctx.depth_mask as u32),
@@ -181,7 +212,14 @@ fn draw_arrays_hw(ctx: &mut GlContext, mode: GLenum, first: GLint, count: GLsize
         (SVGA3D_RS_BLENDENABLE, ctx.blend as u32),
         (SVGA3D_RS_SRCBLEND, gl_blend_to_svga3d(ctx.blend_src_rgb)),
         (SVGA3D_RS_DSTBLEND, gl_blend_to_svga3d(ctx.blend_dst_rgb)),
+        (SVGA3D_RS_BLENDEQUATION, gl_blend_eq_to_svga3d(ctx.blend_equation_rgb)),
+        (SVGA3D_RS_SEPARATEALPHABLENDENABLE, 1),
+        (SVGA3D_RS_SRCBLENDALPHA, gl_blend_to_svga3d(ctx.blend_src_alpha)),
+        (SVGA3D_RS_DSTBLENDALPHA, gl_blend_to_svga3d(ctx.blend_dst_alpha)),
+        (SVGA3D_RS_BLENDEQUATIONALPHA, gl_blend_eq_to_svga3d(ctx.blend_equation_alpha)),
         (SVGA3D_RS_CULLMODE, gl_cull_to_svga3d(ctx.cull_face, ctx.cull_face_mode)),
+        (SVGA3D_RS_SLOPESCALEDEPTHBIAS, slope_bias.to_bits()),
+        (SVGA3D_RS_DEPTHBIAS, depth_bias.to_bits()),
     ]);
 
     // 5. Create a vertex buffer surface and upload vertex data via DMA
@@ -203,6 +241,8 @@ fn draw_arrays_hw(ctx: &mut GlContext, mode: GLenum, first: GLint, count: GLsize
                     ctx, va.size, va.typ, va.stride, va.offset, va.buffer_id, vi as u32,
                 );
                 vertex_data.extend_from_slice(&fetched);
+            } else if loc < ctx.attribs.len() {
+                vertex_data.extend_from_slice(&ctx.attribs[loc].current);
             } else {
                 vertex_data.extend_from_slice(&[0.0, 0.0, 0.0, 1.0]);
             }
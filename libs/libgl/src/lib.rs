@@ -8,6 +8,8 @@
 //! - GLSL compiler: [`compiler`] (lexer → parser → AST → IR)
 //! - Software rasterizer: [`rasterizer`] (vertex → clip → raster → fragment)
 //! - Framebuffer: [`framebuffer::SwFramebuffer`] (ARGB color + f32 depth)
+//! - Write-out: ordered dithering (`GL_DITHER`) and optional sRGB encoding
+//!   (`GL_FRAMEBUFFER_SRGB`) in [`rasterizer::fragment`]
 //!
 //! # Export Convention
 //! All public functions are `extern "C"` with `#[no_mangle]` for use via `dl_sym()`.
@@ -29,6 +31,7 @@ pub mod compiler;
 pub mod rasterizer;
 pub mod simd;
 pub mod fxaa;
+pub mod srgb;
 pub mod svga3d;
 
 mod syscall;
@@ -231,6 +234,7 @@ pub extern "C" fn glGetString(name: GLenum) -> *const u8 {
         }
         GL_VERSION => b"OpenGL ES 2.0 (anyOS libgl 2.0)\0".as_ptr(),
         GL_SHADING_LANGUAGE_VERSION => b"GLSL ES 1.00\0".as_ptr(),
+        GL_EXTENSIONS => b"GL_OES_compressed_ETC1_RGB8_texture GL_EXT_texture_compression_dxt1 GL_EXT_blend_minmax GL_OES_mapbuffer GL_OES_vertex_half_float GL_EXT_sRGB_write_control\0".as_ptr(),
         _ => core::ptr::null(),
     }
 }
@@ -244,6 +248,9 @@ pub extern "C" fn glEnable(cap: GLenum) {
         GL_BLEND => c.blend = true,
         GL_CULL_FACE_CAP => c.cull_face = true,
         GL_SCISSOR_TEST => c.scissor_test = true,
+        GL_POLYGON_OFFSET_FILL => c.polygon_offset_fill = true,
+        GL_DITHER => c.dither = true,
+        GL_FRAMEBUFFER_SRGB => c.framebuffer_srgb = true,
         _ => c.set_error(GL_INVALID_ENUM),
     }
 }
@@ -257,6 +264,9 @@ pub extern "C" fn glDisable(cap: GLenum) {
         GL_BLEND => c.blend = false,
         GL_CULL_FACE_CAP => c.cull_face = false,
         GL_SCISSOR_TEST => c.scissor_test = false,
+        GL_POLYGON_OFFSET_FILL => c.polygon_offset_fill = false,
+        GL_DITHER => c.dither = false,
+        GL_FRAMEBUFFER_SRGB => c.framebuffer_srgb = false,
         _ => c.set_error(GL_INVALID_ENUM),
     }
 }
@@ -284,6 +294,34 @@ pub extern "C" fn glBlendFuncSeparate(
     c.blend_dst_alpha = dst_alpha;
 }
 
+fn is_blend_equation(mode: GLenum) -> bool {
+    matches!(mode, GL_FUNC_ADD | GL_FUNC_SUBTRACT | GL_FUNC_REVERSE_SUBTRACT | GL_MIN | GL_MAX)
+}
+
+/// Set the blend equation for both RGB and alpha.
+#[no_mangle]
+pub extern "C" fn glBlendEquation(mode: GLenum) {
+    let c = ctx();
+    if !is_blend_equation(mode) {
+        c.set_error(GL_INVALID_ENUM);
+        return;
+    }
+    c.blend_equation_rgb = mode;
+    c.blend_equation_alpha = mode;
+}
+
+/// Set separate blend equations for RGB and alpha.
+#[no_mangle]
+pub extern "C" fn glBlendEquationSeparate(mode_rgb: GLenum, mode_alpha: GLenum) {
+    let c = ctx();
+    if !is_blend_equation(mode_rgb) || !is_blend_equation(mode_alpha) {
+        c.set_error(GL_INVALID_ENUM);
+        return;
+    }
+    c.blend_equation_rgb = mode_rgb;
+    c.blend_equation_alpha = mode_alpha;
+}
+
 /// Set the depth comparison function.
 #[no_mangle]
 pub extern "C" fn glDepthFunc(func: GLenum) {
@@ -383,13 +421,11 @@ pub extern "C" fn glClear(mask: GLbitfield) {
         }
     }
 
-    // Always clear the software framebuffer too (for state consistency)
+    // Always clear the software framebuffer too (for state consistency).
+    // The clear color is sRGB-encoded like fragments, but not dithered.
     if mask & GL_COLOR_BUFFER_BIT != 0 {
-        let r = (c.clear_r.clamp(0.0, 1.0) * 255.0) as u32;
-        let g = (c.clear_g.clamp(0.0, 1.0) * 255.0) as u32;
-        let b = (c.clear_b.clamp(0.0, 1.0) * 255.0) as u32;
-        let a = (c.clear_a.clamp(0.0, 1.0) * 255.0) as u32;
-        let argb = (a << 24) | (r << 16) | (g << 8) | b;
+        let out = rasterizer::fragment::WriteOut { dither: false, srgb: c.framebuffer_srgb };
+        let argb = out.pack([c.clear_r, c.clear_g, c.clear_b, c.clear_a], 0, 0);
         c.default_fb.clear_color(argb);
     }
     if mask & GL_DEPTH_BUFFER_BIT != 0 {
@@ -413,6 +449,14 @@ pub extern "C" fn glLineWidth(width: GLfloat) {
     ctx().line_width = width;
 }
 
+/// Set the depth offset applied to filled polygons (`GL_POLYGON_OFFSET_FILL`).
+#[no_mangle]
+pub extern "C" fn glPolygonOffset(factor: GLfloat, units: GLfloat) {
+    let c = ctx();
+    c.polygon_offset_factor = factor;
+    c.polygon_offset_units = units;
+}
+
 /// Set pixel storage modes.
 #[no_mangle]
 pub extern "C" fn glPixelStorei(pname: GLenum, param: GLint) {
@@ -472,14 +516,14 @@ pub extern "C" fn glBufferData(target: GLenum, size: GLsizeiptr, data: *const GL
         _ => { c.set_error(GL_INVALID_ENUM); return; }
     };
     if id == 0 { c.set_error(GL_INVALID_OPERATION); return; }
+    if size < 0 { c.set_error(GL_INVALID_VALUE); return; }
 
-    let bytes = if data.is_null() {
-        alloc::vec![0u8; size as usize]
+    if data.is_null() {
+        c.buffers.orphan(id, size as usize, usage);
     } else {
         let slice = unsafe { core::slice::from_raw_parts(data as *const u8, size as usize) };
-        slice.to_vec()
-    };
-    c.buffers.buffer_data(id, &bytes, usage);
+        c.buffers.buffer_data(id, slice, usage);
+    }
 }
 
 /// Update a sub-region of a buffer.
@@ -492,10 +536,68 @@ pub extern "C" fn glBufferSubData(target: GLenum, offset: GLintptr, size: GLsize
         _ => { c.set_error(GL_INVALID_ENUM); return; }
     };
     if id == 0 || data.is_null() { return; }
+    if c.buffers.is_mapped(id) { c.set_error(GL_INVALID_OPERATION); return; }
     let slice = unsafe { core::slice::from_raw_parts(data as *const u8, size as usize) };
     c.buffers.buffer_sub_data(id, offset as usize, slice);
 }
 
+/// Map the currently bound buffer for writing (OES_mapbuffer).
+///
+/// Returns a pointer into the buffer's storage, valid until
+/// `glUnmapBufferOES` or the next `glBufferData` on the buffer. Combined
+/// with orphaning (`glBufferData(target, size, NULL, GL_STREAM_DRAW)`) this
+/// lets per-frame geometry be written in place without a staging copy.
+#[no_mangle]
+pub extern "C" fn glMapBufferOES(target: GLenum, access: GLenum) -> *mut GLvoid {
+    let c = ctx();
+    let id = match target {
+        GL_ARRAY_BUFFER => c.bound_array_buffer,
+        GL_ELEMENT_ARRAY_BUFFER => c.bound_element_buffer,
+        _ => { c.set_error(GL_INVALID_ENUM); return core::ptr::null_mut(); }
+    };
+    if access != GL_WRITE_ONLY_OES { c.set_error(GL_INVALID_ENUM); return core::ptr::null_mut(); }
+    match c.buffers.map(id) {
+        Some(ptr) => ptr as *mut GLvoid,
+        None => { c.set_error(GL_INVALID_OPERATION); core::ptr::null_mut() }
+    }
+}
+
+/// Unmap the currently bound buffer. Returns `GL_FALSE` (with
+/// `GL_INVALID_OPERATION`) if it was not mapped.
+#[no_mangle]
+pub extern "C" fn glUnmapBufferOES(target: GLenum) -> GLboolean {
+    let c = ctx();
+    let id = match target {
+        GL_ARRAY_BUFFER => c.bound_array_buffer,
+        GL_ELEMENT_ARRAY_BUFFER => c.bound_element_buffer,
+        _ => { c.set_error(GL_INVALID_ENUM); return GL_FALSE; }
+    };
+    if c.buffers.unmap(id) {
+        GL_TRUE
+    } else {
+        c.set_error(GL_INVALID_OPERATION);
+        GL_FALSE
+    }
+}
+
+/// Query the map pointer of the currently bound buffer (null if unmapped).
+#[no_mangle]
+pub extern "C" fn glGetBufferPointervOES(target: GLenum, pname: GLenum, params: *mut *mut GLvoid) {
+    let c = ctx();
+    let id = match target {
+        GL_ARRAY_BUFFER => c.bound_array_buffer,
+        GL_ELEMENT_ARRAY_BUFFER => c.bound_element_buffer,
+        _ => { c.set_error(GL_INVALID_ENUM); return; }
+    };
+    if pname != GL_BUFFER_MAP_POINTER_OES { c.set_error(GL_INVALID_ENUM); return; }
+    if params.is_null() { return; }
+    let ptr = match c.buffers.get_mut(id) {
+        Some(buf) if buf.mapped => buf.data.as_mut_ptr() as *mut GLvoid,
+        _ => core::ptr::null_mut(),
+    };
+    unsafe { *params = ptr; }
+}
+
 // ══════════════════════════════════════════════════════════════════════════════
 //  Texture Objects
 // ══════════════════════════════════════════════════════════════════════════════
@@ -532,7 +634,7 @@ pub extern "C" fn glBindTexture(target: GLenum, texture: GLuint) {
 pub extern "C" fn glTexImage2D(
     target: GLenum, _level: GLint, internal_format: GLint,
     width: GLsizei, height: GLsizei, _border: GLint,
-    format: GLenum, _type: GLenum, data: *const GLvoid,
+    format: GLenum, type_: GLenum, data: *const GLvoid,
 ) {
     let c = ctx();
     if target != GL_TEXTURE_2D { c.set_error(GL_INVALID_ENUM); return; }
@@ -540,13 +642,9 @@ pub extern "C" fn glTexImage2D(
     if unit >= state::MAX_TEXTURE_UNITS { return; }
     let tex_id = c.bound_textures[unit];
 
-    let pixel_size = match format {
-        GL_RGBA => 4,
-        GL_RGB => 3,
-        GL_LUMINANCE => 1,
-        GL_ALPHA => 1,
-        GL_LUMINANCE_ALPHA => 2,
-        _ => 4,
+    let pixel_size = match texture::pixel_size(format, type_) {
+        Some(n) => n,
+        None => { c.set_error(GL_INVALID_OPERATION); return; }
     };
     let data_slice = if data.is_null() {
         None
@@ -555,7 +653,7 @@ pub extern "C" fn glTexImage2D(
         Some(unsafe { core::slice::from_raw_parts(data as *const u8, len) })
     };
 
-    c.textures.tex_image_2d(tex_id, width as u32, height as u32, format, data_slice);
+    c.textures.tex_image_2d(tex_id, width as u32, height as u32, format, type_, data_slice);
     let _ = internal_format;
 }
 
@@ -573,6 +671,80 @@ pub extern "C" fn glTexSubImage2D(
     // TODO: implement sub-image update
 }
 
+/// Upload compressed texture image data (ETC1, DXT1), decoded to RGBA8.
+///
+/// Only mip level 0 is stored; uploads of other levels are accepted and
+/// ignored, since textures have no mipmap chain.
+#[no_mangle]
+pub extern "C" fn glCompressedTexImage2D(
+    target: GLenum, level: GLint, internal_format: GLenum,
+    width: GLsizei, height: GLsizei, border: GLint,
+    image_size: GLsizei, data: *const GLvoid,
+) {
+    let c = ctx();
+    if target != GL_TEXTURE_2D { c.set_error(GL_INVALID_ENUM); return; }
+    let expected = match texture::compressed_size(internal_format, width.max(0) as u32, height.max(0) as u32) {
+        Some(n) => n,
+        None => { c.set_error(GL_INVALID_ENUM); return; }
+    };
+    if level < 0 || width < 0 || height < 0 || border != 0 || image_size as usize != expected {
+        c.set_error(GL_INVALID_VALUE);
+        return;
+    }
+    if data.is_null() && image_size > 0 { c.set_error(GL_INVALID_VALUE); return; }
+    if level != 0 { return; }
+    let unit = c.active_texture_unit as usize;
+    if unit >= state::MAX_TEXTURE_UNITS { return; }
+    let tex_id = c.bound_textures[unit];
+
+    let data_slice = if data.is_null() {
+        &[][..]
+    } else {
+        unsafe { core::slice::from_raw_parts(data as *const u8, expected) }
+    };
+    c.textures.compressed_tex_image_2d(tex_id, width as u32, height as u32, internal_format, data_slice);
+}
+
+/// Update a sub-region of a compressed texture.
+///
+/// DXT1 regions must start on a 4-texel boundary. ETC1 textures cannot be
+/// partially updated (`GL_INVALID_OPERATION`, per OES_compressed_ETC1_RGB8_texture).
+#[no_mangle]
+pub extern "C" fn glCompressedTexSubImage2D(
+    target: GLenum, level: GLint,
+    xoffset: GLint, yoffset: GLint,
+    width: GLsizei, height: GLsizei,
+    format: GLenum, image_size: GLsizei, data: *const GLvoid,
+) {
+    let c = ctx();
+    if target != GL_TEXTURE_2D { c.set_error(GL_INVALID_ENUM); return; }
+    let expected = match texture::compressed_size(format, width.max(0) as u32, height.max(0) as u32) {
+        Some(n) => n,
+        None => { c.set_error(GL_INVALID_ENUM); return; }
+    };
+    if format == GL_ETC1_RGB8_OES { c.set_error(GL_INVALID_OPERATION); return; }
+    if level < 0 || xoffset < 0 || yoffset < 0 || width < 0 || height < 0
+        || xoffset % 4 != 0 || yoffset % 4 != 0
+        || image_size as usize != expected || data.is_null()
+    {
+        c.set_error(GL_INVALID_VALUE);
+        return;
+    }
+    if level != 0 { return; }
+    let unit = c.active_texture_unit as usize;
+    if unit >= state::MAX_TEXTURE_UNITS { return; }
+    let tex_id = c.bound_textures[unit];
+    match c.textures.get(tex_id) {
+        Some(tex) if tex.internal_format == format => {}
+        _ => { c.set_error(GL_INVALID_OPERATION); return; }
+    }
+
+    let data_slice = unsafe { core::slice::from_raw_parts(data as *const u8, expected) };
+    c.textures.compressed_tex_sub_image_2d(
+        tex_id, xoffset as u32, yoffset as u32, width as u32, height as u32, format, data_slice,
+    );
+}
+
 /// Set texture parameter.
 #[no_mangle]
 pub extern "C" fn glTexParameteri(target: GLenum, pname: GLenum, param: GLint) {
@@ -931,9 +1103,92 @@ pub extern "C" fn glVertexAttribPointer(
         stride,
         offset: pointer as usize,
         buffer_id: c.bound_array_buffer,
+        divisor: c.attribs[index as usize].divisor,
+        current: c.attribs[index as usize].current,
     };
 }
 
+/// Set the constant value of a vertex attribute, used while its array is
+/// disabled. Missing components default to (0, 0, 0, 1).
+fn set_vertex_attrib(index: GLuint, value: [f32; 4]) {
+    let c = ctx();
+    if (index as usize) >= state::MAX_VERTEX_ATTRIBS {
+        c.set_error(GL_INVALID_VALUE);
+        return;
+    }
+    c.attribs[index as usize].current = value;
+}
+
+/// Read `n` floats from `v` into a constant attribute value.
+unsafe fn attrib_from_ptr(v: *const GLfloat, n: usize) -> Option<[f32; 4]> {
+    if v.is_null() { return None; }
+    let mut value = [0.0, 0.0, 0.0, 1.0];
+    for (i, slot) in value.iter_mut().enumerate().take(n) {
+        *slot = *v.add(i);
+    }
+    Some(value)
+}
+
+/// Set a 1-component constant vertex attribute.
+#[no_mangle]
+pub extern "C" fn glVertexAttrib1f(index: GLuint, x: GLfloat) {
+    set_vertex_attrib(index, [x, 0.0, 0.0, 1.0]);
+}
+
+/// Set a 2-component constant vertex attribute.
+#[no_mangle]
+pub extern "C" fn glVertexAttrib2f(index: GLuint, x: GLfloat, y: GLfloat) {
+    set_vertex_attrib(index, [x, y, 0.0, 1.0]);
+}
+
+/// Set a 3-component constant vertex attribute.
+#[no_mangle]
+pub extern "C" fn glVertexAttrib3f(index: GLuint, x: GLfloat, y: GLfloat, z: GLfloat) {
+    set_vertex_attrib(index, [x, y, z, 1.0]);
+}
+
+/// Set a 4-component constant vertex attribute.
+#[no_mangle]
+pub extern "C" fn glVertexAttrib4f(index: GLuint, x: GLfloat, y: GLfloat, z: GLfloat, w: GLfloat) {
+    set_vertex_attrib(index, [x, y, z, w]);
+}
+
+/// Set a 1-component constant vertex attribute from an array.
+#[no_mangle]
+pub extern "C" fn glVertexAttrib1fv(index: GLuint, v: *const GLfloat) {
+    if let Some(value) = unsafe { attrib_from_ptr(v, 1) } { set_vertex_attrib(index, value); }
+}
+
+/// Set a 2-component constant vertex attribute from an array.
+#[no_mangle]
+pub extern "C" fn glVertexAttrib2fv(index: GLuint, v: *const GLfloat) {
+    if let Some(value) = unsafe { attrib_from_ptr(v, 2) } { set_vertex_attrib(index, value); }
+}
+
+/// Set a 3-component constant vertex attribute from an array.
+#[no_mangle]
+pub extern "C" fn glVertexAttrib3fv(index: GLuint, v: *const GLfloat) {
+    if let Some(value) = unsafe { attrib_from_ptr(v, 3) } { set_vertex_attrib(index, value); }
+}
+
+/// Set a 4-component constant vertex attribute from an array.
+#[no_mangle]
+pub extern "C" fn glVertexAttrib4fv(index: GLuint, v: *const GLfloat) {
+    if let Some(value) = unsafe { attrib_from_ptr(v, 4) } { set_vertex_attrib(index, value); }
+}
+
+/// Instancing extension: advance attribute `index` once every `divisor`
+/// instances in `gl_draw_arrays_instanced` (0 = once per vertex).
+#[no_mangle]
+pub extern "C" fn gl_vertex_attrib_divisor(index: GLuint, divisor: GLuint) {
+    let c = ctx();
+    if (index as usize) >= state::MAX_VERTEX_ATTRIBS {
+        c.set_error(GL_INVALID_VALUE);
+        return;
+    }
+    c.attribs[index as usize].divisor = divisor;
+}
+
 // ══════════════════════════════════════════════════════════════════════════════
 //  Draw Calls
 // ══════════════════════════════════════════════════════════════════════════════
@@ -941,7 +1196,23 @@ pub extern "C" fn glVertexAttribPointer(
 /// Draw primitives from array data.
 #[no_mangle]
 pub extern "C" fn glDrawArrays(mode: GLenum, first: GLint, count: GLsizei) {
-    draw::draw_arrays(ctx(), mode, first, count);
+    let c = ctx();
+    if reads_mapped_buffer(c, false) { c.set_error(GL_INVALID_OPERATION); return; }
+    draw::draw_arrays(c, mode, first, count);
+}
+
+/// Instancing extension: draw `instance_count` copies of an array range.
+///
+/// The vertex shader reads the copy number from `gl_InstanceID`; attributes
+/// with a divisor (`gl_vertex_attrib_divisor`) advance per instance.
+#[no_mangle]
+pub extern "C" fn gl_draw_arrays_instanced(
+    mode: GLenum, first: GLint, count: GLsizei, instance_count: GLsizei,
+) {
+    let c = ctx();
+    if count < 0 || instance_count < 0 { c.set_error(GL_INVALID_VALUE); return; }
+    if reads_mapped_buffer(c, false) { c.set_error(GL_INVALID_OPERATION); return; }
+    draw::draw_arrays_instanced(c, mode, first, count, instance_count);
 }
 
 /// Draw indexed primitives.
@@ -949,7 +1220,16 @@ pub extern "C" fn glDrawArrays(mode: GLenum, first: GLint, count: GLsizei) {
 pub extern "C" fn glDrawElements(
     mode: GLenum, count: GLsizei, type_: GLenum, indices: *const GLvoid,
 ) {
-    draw::draw_elements(ctx(), mode, count, type_, indices as usize);
+    let c = ctx();
+    if reads_mapped_buffer(c, true) { c.set_error(GL_INVALID_OPERATION); return; }
+    draw::draw_elements(c, mode, count, type_, indices as usize);
+}
+
+/// Whether a draw would source vertex (or index) data from a mapped buffer,
+/// which OES_mapbuffer forbids.
+fn reads_mapped_buffer(c: &GlContext, indexed: bool) -> bool {
+    (indexed && c.buffers.is_mapped(c.bound_element_buffer))
+        || c.attribs.iter().any(|a| a.enabled && c.buffers.is_mapped(a.buffer_id))
 }
 
 // ══════════════════════════════════════════════════════════════════════════════
@@ -1,6 +1,14 @@
-//! Fragment processing: depth test and blending.
+//! Fragment processing: depth test, blending and write-out.
+//!
+//! Write-out turns the blended [0, 1] color into an ARGB pixel. With
+//! `GL_DITHER` a 4×4 ordered-dither threshold is added before truncating to
+//! 8 bits, so smooth gradients no longer collapse into visible bands; with
+//! `GL_FRAMEBUFFER_SRGB` the color is sRGB-encoded (and the destination
+//! decoded for blending) so blending and interpolation happen in linear light.
 
+use crate::state::GlContext;
 use crate::types::*;
+use crate::srgb;
 
 /// Perform the depth test. Returns true if the fragment passes.
 pub fn depth_test(frag_depth: f32, buffer_depth: f32, func: GLenum) -> bool {
@@ -17,34 +25,62 @@ pub fn depth_test(frag_depth: f32, buffer_depth: f32, func: GLenum) -> bool {
     }
 }
 
+/// Blend state for one draw call, copied out of the context.
+#[derive(Clone, Copy)]
+pub struct BlendState {
+    pub src_rgb: GLenum,
+    pub dst_rgb: GLenum,
+    pub src_alpha: GLenum,
+    pub dst_alpha: GLenum,
+    pub equation_rgb: GLenum,
+    pub equation_alpha: GLenum,
+}
+
+impl BlendState {
+    pub fn from_ctx(ctx: &GlContext) -> Self {
+        Self {
+            src_rgb: ctx.blend_src_rgb,
+            dst_rgb: ctx.blend_dst_rgb,
+            src_alpha: ctx.blend_src_alpha,
+            dst_alpha: ctx.blend_dst_alpha,
+            equation_rgb: ctx.blend_equation_rgb,
+            equation_alpha: ctx.blend_equation_alpha,
+        }
+    }
+}
+
 /// Alpha blending: combine source (new fragment) with destination (framebuffer).
 ///
-/// Both colors are ARGB u32. Returns blended ARGB u32.
-pub fn blend(src: u32, dst: u32, src_factor: GLenum, dst_factor: GLenum) -> u32 {
-    let sa = ((src >> 24) & 0xFF) as f32 / 255.0;
-    let sr = ((src >> 16) & 0xFF) as f32 / 255.0;
-    let sg = ((src >> 8) & 0xFF) as f32 / 255.0;
-    let sb = (src & 0xFF) as f32 / 255.0;
-
-    let da = ((dst >> 24) & 0xFF) as f32 / 255.0;
-    let dr = ((dst >> 16) & 0xFF) as f32 / 255.0;
-    let dg = ((dst >> 8) & 0xFF) as f32 / 255.0;
-    let db = (dst & 0xFF) as f32 / 255.0;
-
-    let sf = blend_factor(src_factor, sa, da);
-    let df = blend_factor(dst_factor, sa, da);
-
-    let out_r = clamp01(sr * sf + dr * df);
-    let out_g = clamp01(sg * sf + dg * df);
-    let out_b = clamp01(sb * sf + db * df);
-    let out_a = clamp01(sa * sf + da * df);
-
-    let ri = (out_r * 255.0) as u32;
-    let gi = (out_g * 255.0) as u32;
-    let bi = (out_b * 255.0) as u32;
-    let ai = (out_a * 255.0) as u32;
-
-    (ai << 24) | (ri << 16) | (gi << 8) | bi
+/// Both colors are `[r, g, b, a]` in [0, 1] (see [`WriteOut::unpack`] for the
+/// destination). RGB and alpha use their own factors and equations; MIN and
+/// MAX ignore the factors.
+pub fn blend(src: [f32; 4], dst: [f32; 4], state: &BlendState) -> [f32; 4] {
+    let [sr, sg, sb, sa] = src;
+    let [dr, dg, db, da] = dst;
+
+    let sf = blend_factor(state.src_rgb, sa, da);
+    let df = blend_factor(state.dst_rgb, sa, da);
+    let saf = blend_factor(state.src_alpha, sa, da);
+    let daf = blend_factor(state.dst_alpha, sa, da);
+
+    let eq = state.equation_rgb;
+    let out_r = clamp01(combine(eq, sr, sf, dr, df));
+    let out_g = clamp01(combine(eq, sg, sf, dg, df));
+    let out_b = clamp01(combine(eq, sb, sf, db, df));
+    let out_a = clamp01(combine(state.equation_alpha, sa, saf, da, daf));
+
+    [out_r, out_g, out_b, out_a]
+}
+
+/// Apply a blend equation to one channel.
+fn combine(equation: GLenum, s: f32, sf: f32, d: f32, df: f32) -> f32 {
+    match equation {
+        GL_FUNC_SUBTRACT => s * sf - d * df,
+        GL_FUNC_REVERSE_SUBTRACT => d * df - s * sf,
+        GL_MIN => if s < d { s } else { d },
+        GL_MAX => if s > d { s } else { d },
+        _ => s * sf + d * df,
+    }
 }
 
 /// Compute blend factor.
@@ -60,6 +96,62 @@ fn blend_factor(factor: GLenum, src_alpha: f32, dst_alpha: f32) -> f32 {
     }
 }
 
+/// 4×4 Bayer matrix as thresholds in (0, 1), averaging 0.5 so dithering
+/// rounds without bias.
+const BAYER4: [[f32; 4]; 4] = [
+    [0.5 / 16.0, 8.5 / 16.0, 2.5 / 16.0, 10.5 / 16.0],
+    [12.5 / 16.0, 4.5 / 16.0, 14.5 / 16.0, 6.5 / 16.0],
+    [3.5 / 16.0, 11.5 / 16.0, 1.5 / 16.0, 9.5 / 16.0],
+    [15.5 / 16.0, 7.5 / 16.0, 13.5 / 16.0, 5.5 / 16.0],
+];
+
+/// Write-out state for one draw call, copied out of the context.
+#[derive(Clone, Copy)]
+pub struct WriteOut {
+    pub dither: bool,
+    pub srgb: bool,
+}
+
+impl WriteOut {
+    pub fn from_ctx(ctx: &GlContext) -> Self {
+        Self { dither: ctx.dither, srgb: ctx.framebuffer_srgb }
+    }
+
+    /// Amount added to a 0..255 channel value at pixel (x, y) before it is
+    /// truncated to 8 bits: the Bayer threshold, or 0 without dithering.
+    #[inline(always)]
+    pub fn threshold(&self, x: i32, y: i32) -> f32 {
+        if self.dither { BAYER4[(y & 3) as usize][(x & 3) as usize] } else { 0.0 }
+    }
+
+    /// Framebuffer pixel as `[r, g, b, a]` for blending (linear with sRGB writes).
+    pub fn unpack(&self, argb: u32) -> [f32; 4] {
+        let a = ((argb >> 24) & 0xFF) as f32 / 255.0;
+        if self.srgb {
+            [srgb::decode(argb >> 16), srgb::decode(argb >> 8), srgb::decode(argb), a]
+        } else {
+            [
+                ((argb >> 16) & 0xFF) as f32 / 255.0,
+                ((argb >> 8) & 0xFF) as f32 / 255.0,
+                (argb & 0xFF) as f32 / 255.0,
+                a,
+            ]
+        }
+    }
+
+    /// Color `[r, g, b, a]` in [0, 1] as the framebuffer pixel at (x, y).
+    /// Alpha is stored linearly and never dithered.
+    pub fn pack(&self, c: [f32; 4], x: i32, y: i32) -> u32 {
+        let t = self.threshold(x, y);
+        let channel = |v: f32| {
+            let v = if self.srgb { srgb::encode(v) } else { clamp01(v) };
+            ((v * 255.0 + t) as u32).min(255)
+        };
+        let a = (clamp01(c[3]) * 255.0) as u32;
+        (a << 24) | (channel(c[0]) << 16) | (channel(c[1]) << 8) | channel(c[2])
+    }
+}
+
 fn clamp01(x: f32) -> f32 {
     if x < 0.0 { 0.0 } else if x > 1.0 { 1.0 } else { x }
 }
@@ -67,9 +67,74 @@ fn trivially_inside(v: &ClipVertex) -> bool {
     v.position[2] >= -w && v.position[2] <= w
 }
 
+/// Where each vertex shader input of a draw call comes from.
+///
+/// Slot `i` is the program's `i`-th attribute. Enabled arrays are fetched
+/// per vertex through `info`; everything else is set in the attribute
+/// buffer beforehand: the `glVertexAttrib*` constant for disabled arrays,
+/// and per instance the arrays with a divisor and `gl_InstanceID`.
+struct AttribSources {
+    /// Per-vertex arrays for `fetch_attributes_into` (size 0 = not per vertex).
+    info: [(i32, i32, GLenum, i32, usize, u32); 16],
+    count: usize,
+    /// Attribute buffer contents before the first instance.
+    base: [[f32; 4]; 16],
+    /// (slot, attrib location) of enabled arrays with a divisor.
+    per_instance: Vec<(usize, usize)>,
+    /// Slot of `gl_InstanceID`, if the vertex shader reads it.
+    instance_slot: Option<usize>,
+}
+
+impl AttribSources {
+    fn new(ctx: &GlContext, program: &crate::shader::GlProgram) -> Self {
+        let mut src = Self {
+            info: [(0i32, 0i32, 0u32, 0i32, 0usize, 0u32); 16],
+            count: program.attributes.len().min(16),
+            base: [[0.0, 0.0, 0.0, 1.0]; 16],
+            per_instance: Vec::new(),
+            instance_slot: None,
+        };
+        for (i, a) in program.attributes.iter().enumerate().take(src.count) {
+            if a.name == crate::compiler::lower::INSTANCE_ID {
+                src.instance_slot = Some(i);
+                continue;
+            }
+            let loc = a.location as usize;
+            if loc >= ctx.attribs.len() { continue; }
+            let va = &ctx.attribs[loc];
+            if !va.enabled {
+                src.base[i] = va.current;
+            } else if va.divisor > 0 {
+                src.per_instance.push((i, loc));
+            } else {
+                src.info[i] = (a.location, va.size, va.typ, va.stride, va.offset, va.buffer_id);
+            }
+        }
+        src
+    }
+
+    /// Load the values that stay fixed for `instance` into `buf`.
+    fn begin_instance(&self, ctx: &GlContext, instance: u32, buf: &mut [[f32; 4]; 16]) {
+        if let Some(slot) = self.instance_slot {
+            buf[slot] = [instance as f32, 0.0, 0.0, 1.0];
+        }
+        for &(slot, loc) in &self.per_instance {
+            let va = &ctx.attribs[loc];
+            buf[slot] = vertex::fetch_single_attribute(
+                ctx, va.size, va.typ, va.stride, va.offset, va.buffer_id, instance / va.divisor,
+            );
+        }
+    }
+}
+
 /// Render primitives using the software rasterizer.
 pub fn draw(ctx: &mut GlContext, mode: GLenum, first: i32, count: i32) {
-    if count <= 0 { return; }
+    draw_instanced(ctx, mode, first, count, 1);
+}
+
+/// Render `instances` copies of an array range using the software rasterizer.
+pub fn draw_instanced(ctx: &mut GlContext, mode: GLenum, first: i32, count: i32, instances: i32) {
+    if count <= 0 || instances <= 0 { return; }
     let prog_id = ctx.current_program;
     let program = match ctx.shaders.get_program(prog_id) {
         Some(p) if p.linked => p,
@@ -97,16 +162,9 @@ pub fn draw(ctx: &mut GlContext, mode: GLenum, first: i32, count: i32) {
     let vs_jit: Option<JitFn> = program.vs_jit.as_ref().map(|j| j.as_fn());
     let fs_jit: Option<JitFn> = program.fs_jit.as_ref().map(|j| j.as_fn());
 
-    // Build attribute info (stack-allocated, max 16 entries)
-    let mut attrib_info = [(0i32, 0i32, 0u32, 0i32, 0usize, 0u32); 16];
-    let num_attribs = program.attributes.len().min(16);
-    for (i, a) in program.attributes.iter().enumerate().take(num_attribs) {
-        let loc = a.location as usize;
-        if loc < ctx.attribs.len() && ctx.attribs[loc].enabled {
-            let va = &ctx.attribs[loc];
-            attrib_info[i] = (a.location, va.size, va.typ, va.stride, va.offset, va.buffer_id);
-        }
-    }
+    // Build attribute sources (stack-allocated, max 16 entries)
+    let attribs = AttribSources::new(ctx, program);
+    let num_attribs = attribs.count;
 
     // Set raw texture pointers before draw — avoids &CTX / &mut CTX aliasing UB.
     unsafe {
@@ -114,45 +172,17 @@ pub fn draw(ctx: &mut GlContext, mode: GLenum, first: i32, count: i32) {
         crate::BOUND_TEXTURES_PTR = &ctx.bound_textures as *const _;
     }
 
-    // ── Vertex Processing (one ShaderExec reused for all vertices) ────────
     let mut vs_exec = ShaderExec::new(vs_ir.num_regs, num_varyings);
-    let mut attrib_buf = [[0.0f32, 0.0, 0.0, 1.0]; 16];
+    let mut attrib_buf = attribs.base;
     let mut clip_verts = Vec::with_capacity(count as usize);
 
     let tex_sample_addr = raster::real_tex_sample as usize;
 
-    for i in first..(first + count) {
-        vertex::fetch_attributes_into(ctx, &attrib_info[..num_attribs], i as u32, &mut attrib_buf);
-        vs_exec.reset_vertex();
-        if let Some(jit) = vs_jit {
-            let mut jit_ctx = JitContext {
-                regs: vs_exec.regs.as_mut_ptr() as *mut f32,
-                uniforms: uniforms.as_ptr() as *const f32,
-                attributes: attrib_buf.as_ptr() as *const f32,
-                varyings_in: core::ptr::null(),
-                varyings_out: vs_exec.varyings.as_mut_ptr() as *mut f32,
-                position: vs_exec.position.as_mut_ptr(),
-                frag_color: vs_exec.frag_color.as_mut_ptr(),
-                point_size: &mut vs_exec.point_size,
-                tex_sample: tex_sample_addr,
-            };
-            unsafe { jit(&mut jit_ctx); }
-        } else {
-            vs_exec.execute(&vs_ir, &attrib_buf[..num_attribs], &uniforms, None, raster::real_tex_sample);
-        }
-        clip_verts.push(ClipVertex {
-            position: vs_exec.position,
-            varyings: vs_exec.varyings,
-            num_varyings,
-        });
-    }
-
-    // ── Primitive Assembly + Rasterization ───────────────────────────────
     let fb_w = ctx.default_fb.width as i32;
     let fb_h = ctx.default_fb.height as i32;
 
     // Try fast path: trivial FS (≤20 instructions) + bound texture + 2 varyings
-    let fast = if fs_ir.instructions.len() <= 20 && num_varyings >= 2 && !ctx.blend {
+    let fast = if fs_ir.instructions.len() <= 20 && num_varyings >= 2 && !ctx.blend && !ctx.framebuffer_srgb {
         raster::ResolvedTexture::resolve_unit0().map(|tex| FastPathInfo {
             tex,
             mat_r: mat_color[0],
@@ -166,38 +196,71 @@ pub fn draw(ctx: &mut GlContext, mode: GLenum, first: i32, count: i32) {
     // Pre-allocate fragment shader exec (reused for all pixels in this draw call)
     let mut fs_exec = ShaderExec::new(fs_ir.num_regs, num_varyings);
 
-    match mode {
-        GL_TRIANGLES => {
-            let mut i = 0;
-            while i + 2 < clip_verts.len() {
-                process_triangle(
-                    ctx, &fs_ir, &uniforms, &mut fs_exec, fs_jit, fast.as_ref(),
-                    &clip_verts[i], &clip_verts[i+1], &clip_verts[i+2],
-                    num_varyings, fb_w, fb_h,
-                );
-                i += 3;
-            }
-        }
-        GL_TRIANGLE_STRIP => {
-            for i in 0..clip_verts.len().saturating_sub(2) {
-                let (a, b, c) = if i % 2 == 0 {
-                    (&clip_verts[i], &clip_verts[i+1], &clip_verts[i+2])
-                } else {
-                    (&clip_verts[i+1], &clip_verts[i], &clip_verts[i+2])
+    for instance in 0..instances as u32 {
+        attribs.begin_instance(ctx, instance, &mut attrib_buf);
+        clip_verts.clear();
+
+        // ── Vertex Processing (one ShaderExec reused for all vertices) ────────
+        for i in first..(first + count) {
+            vertex::fetch_attributes_into(ctx, &attribs.info[..num_attribs], i as u32, &mut attrib_buf);
+            vs_exec.reset_vertex();
+            if let Some(jit) = vs_jit {
+                let mut jit_ctx = JitContext {
+                    regs: vs_exec.regs.as_mut_ptr() as *mut f32,
+                    uniforms: uniforms.as_ptr() as *const f32,
+                    attributes: attrib_buf.as_ptr() as *const f32,
+                    varyings_in: core::ptr::null(),
+                    varyings_out: vs_exec.varyings.as_mut_ptr() as *mut f32,
+                    position: vs_exec.position.as_mut_ptr(),
+                    frag_color: vs_exec.frag_color.as_mut_ptr(),
+                    point_size: &mut vs_exec.point_size,
+                    tex_sample: tex_sample_addr,
                 };
-                process_triangle(ctx, &fs_ir, &uniforms, &mut fs_exec, fs_jit, fast.as_ref(), a, b, c, num_varyings, fb_w, fb_h);
+                unsafe { jit(&mut jit_ctx); }
+            } else {
+                vs_exec.execute(&vs_ir, &attrib_buf[..num_attribs], &uniforms, None, raster::real_tex_sample);
             }
+            clip_verts.push(ClipVertex {
+                position: vs_exec.position,
+                varyings: vs_exec.varyings,
+                num_varyings,
+            });
         }
-        GL_TRIANGLE_FAN => {
-            for i in 1..clip_verts.len().saturating_sub(1) {
-                process_triangle(
-                    ctx, &fs_ir, &uniforms, &mut fs_exec, fs_jit, fast.as_ref(),
-                    &clip_verts[0], &clip_verts[i], &clip_verts[i+1],
-                    num_varyings, fb_w, fb_h,
-                );
+
+        // ── Primitive Assembly + Rasterization ───────────────────────────────
+        match mode {
+            GL_TRIANGLES => {
+                let mut i = 0;
+                while i + 2 < clip_verts.len() {
+                    process_triangle(
+                        ctx, &fs_ir, &uniforms, &mut fs_exec, fs_jit, fast.as_ref(),
+                        &clip_verts[i], &clip_verts[i+1], &clip_verts[i+2],
+                        num_varyings, fb_w, fb_h,
+                    );
+                    i += 3;
+                }
+            }
+            GL_TRIANGLE_STRIP => {
+                for i in 0..clip_verts.len().saturating_sub(2) {
+                    let (a, b, c) = if i % 2 == 0 {
+                        (&clip_verts[i], &clip_verts[i+1], &clip_verts[i+2])
+                    } else {
+                        (&clip_verts[i+1], &clip_verts[i], &clip_verts[i+2])
+                    };
+                    process_triangle(ctx, &fs_ir, &uniforms, &mut fs_exec, fs_jit, fast.as_ref(), a, b, c, num_varyings, fb_w, fb_h);
+                }
             }
+            GL_TRIANGLE_FAN => {
+                for i in 1..clip_verts.len().saturating_sub(1) {
+                    process_triangle(
+                        ctx, &fs_ir, &uniforms, &mut fs_exec, fs_jit, fast.as_ref(),
+                        &clip_verts[0], &clip_verts[i], &clip_verts[i+1],
+                        num_varyings, fb_w, fb_h,
+                    );
+                }
+            }
+            _ => {} // GL_LINES, GL_POINTS — Phase 2
         }
-        _ => {} // GL_LINES, GL_POINTS — Phase 2
     }
 }
 
@@ -237,15 +300,8 @@ pub fn draw_elements(ctx: &mut GlContext, mode: GLenum, count: i32, type_: GLenu
     let vs_jit: Option<JitFn> = program.vs_jit.as_ref().map(|j| j.as_fn());
     let fs_jit: Option<JitFn> = program.fs_jit.as_ref().map(|j| j.as_fn());
 
-    let mut attrib_info = [(0i32, 0i32, 0u32, 0i32, 0usize, 0u32); 16];
-    let num_attribs = program.attributes.len().min(16);
-    for (i, a) in program.attributes.iter().enumerate().take(num_attribs) {
-        let loc = a.location as usize;
-        if loc < ctx.attribs.len() && ctx.attribs[loc].enabled {
-            let va = &ctx.attribs[loc];
-            attrib_info[i] = (a.location, va.size, va.typ, va.stride, va.offset, va.buffer_id);
-        }
-    }
+    let attribs = AttribSources::new(ctx, program);
+    let num_attribs = attribs.count;
 
     // Fetch indices into a compact buffer
     let mut indices = Vec::with_capacity(count as usize);
@@ -283,7 +339,8 @@ pub fn draw_elements(ctx: &mut GlContext, mode: GLenum, count: i32, type_: GLenu
 
     // ── Vertex Processing with post-transform cache ─────────────────────
     let mut vs_exec = ShaderExec::new(vs_ir.num_regs, num_varyings);
-    let mut attrib_buf = [[0.0f32, 0.0, 0.0, 1.0]; 16];
+    let mut attrib_buf = attribs.base;
+    attribs.begin_instance(ctx, 0, &mut attrib_buf);
     let tex_sample_addr = raster::real_tex_sample as usize;
 
     let max_idx = indices.iter().copied().max().unwrap_or(0) as usize;
@@ -301,7 +358,7 @@ pub fn draw_elements(ctx: &mut GlContext, mode: GLenum, count: i32, type_: GLenu
                 continue;
             }
         }
-        vertex::fetch_attributes_into(ctx, &attrib_info[..num_attribs], idx, &mut attrib_buf);
+        vertex::fetch_attributes_into(ctx, &attribs.info[..num_attribs], idx, &mut attrib_buf);
         vs_exec.reset_vertex();
         if let Some(jit) = vs_jit {
             let mut jit_ctx = JitContext {
@@ -335,7 +392,7 @@ pub fn draw_elements(ctx: &mut GlContext, mode: GLenum, count: i32, type_: GLenu
     let fb_h = ctx.default_fb.height as i32;
 
     // Try fast path (same logic as draw_arrays)
-    let fast = if fs_ir.instructions.len() <= 20 && num_varyings >= 2 && !ctx.blend {
+    let fast = if fs_ir.instructions.len() <= 20 && num_varyings >= 2 && !ctx.blend && !ctx.framebuffer_srgb {
         raster::ResolvedTexture::resolve_unit0().map(|tex| FastPathInfo {
             tex,
             mat_r: mat_color[0],
@@ -406,9 +463,9 @@ fn process_triangle(
 ) {
     // Fast path: if all vertices are inside the frustum, skip clipping entirely
     if trivially_inside(v0) && trivially_inside(v1) && trivially_inside(v2) {
-        let s0 = to_screen(&v0.position, ctx.viewport_x, ctx.viewport_y, ctx.viewport_w, ctx.viewport_h);
-        let s1 = to_screen(&v1.position, ctx.viewport_x, ctx.viewport_y, ctx.viewport_w, ctx.viewport_h);
-        let s2 = to_screen(&v2.position, ctx.viewport_x, ctx.viewport_y, ctx.viewport_w, ctx.viewport_h);
+        let mut s0 = to_screen(&v0.position, ctx.viewport_x, ctx.viewport_y, ctx.viewport_w, ctx.viewport_h);
+        let mut s1 = to_screen(&v1.position, ctx.viewport_x, ctx.viewport_y, ctx.viewport_w, ctx.viewport_h);
+        let mut s2 = to_screen(&v2.position, ctx.viewport_x, ctx.viewport_y, ctx.viewport_w, ctx.viewport_h);
 
         if ctx.cull_face {
             let area = edge_function(&s0, &s1, &s2);
@@ -421,6 +478,9 @@ fn process_triangle(
             };
             if cull { return; }
         }
+        if ctx.polygon_offset_fill {
+            polygon_offset(ctx, &mut s0, &mut s1, &mut s2);
+        }
 
         if let Some(fp) = fast {
             raster::rasterize_triangle_fast(ctx, &fp.tex, fp.mat_r, fp.mat_g, fp.mat_b, v0, v1, v2, &s0, &s1, &s2, fb_w, fb_h);
@@ -436,9 +496,9 @@ fn process_triangle(
 
     for t in clipped.chunks(3) {
         if t.len() < 3 { continue; }
-        let s0 = to_screen(&t[0].position, ctx.viewport_x, ctx.viewport_y, ctx.viewport_w, ctx.viewport_h);
-        let s1 = to_screen(&t[1].position, ctx.viewport_x, ctx.viewport_y, ctx.viewport_w, ctx.viewport_h);
-        let s2 = to_screen(&t[2].position, ctx.viewport_x, ctx.viewport_y, ctx.viewport_w, ctx.viewport_h);
+        let mut s0 = to_screen(&t[0].position, ctx.viewport_x, ctx.viewport_y, ctx.viewport_w, ctx.viewport_h);
+        let mut s1 = to_screen(&t[1].position, ctx.viewport_x, ctx.viewport_y, ctx.viewport_w, ctx.viewport_h);
+        let mut s2 = to_screen(&t[2].position, ctx.viewport_x, ctx.viewport_y, ctx.viewport_w, ctx.viewport_h);
 
         if ctx.cull_face {
             let area = edge_function(&s0, &s1, &s2);
@@ -449,6 +509,9 @@ fn process_triangle(
             };
             if cull { continue; }
         }
+        if ctx.polygon_offset_fill {
+            polygon_offset(ctx, &mut s0, &mut s1, &mut s2);
+        }
 
         if let Some(fp) = fast {
             raster::rasterize_triangle_fast(ctx, &fp.tex, fp.mat_r, fp.mat_g, fp.mat_b, &t[0], &t[1], &t[2], &s0, &s1, &s2, fb_w, fb_h);
@@ -458,6 +521,32 @@ fn process_triangle(
     }
 }
 
+/// Apply `glPolygonOffset` to the window-space depth of a triangle.
+///
+/// The offset is `factor * m + units * r`, where `m` is the larger depth
+/// slope of the triangle in x or y and `r` is the smallest difference the
+/// f32 depth buffer can resolve at the triangle's farthest depth (one ulp,
+/// 2^(e - 23) for exponent `e`). The offset is constant across the triangle,
+/// so it is added to each vertex; the rasterizers clamp the result to [0, 1].
+fn polygon_offset(ctx: &GlContext, s0: &mut [f32; 3], s1: &mut [f32; 3], s2: &mut [f32; 3]) {
+    let area = edge_function(s0, s1, s2);
+    if area.abs() < 1e-6 { return; }
+    let (dx1, dy1, dz1) = (s1[0] - s0[0], s1[1] - s0[1], s1[2] - s0[2]);
+    let (dx2, dy2, dz2) = (s2[0] - s0[0], s2[1] - s0[1], s2[2] - s0[2]);
+    let dzdx = (dz1 * dy2 - dy1 * dz2) / area;
+    let dzdy = (dx1 * dz2 - dz1 * dx2) / area;
+    let m = dzdx.abs().max(dzdy.abs());
+
+    let max_z = s0[2].max(s1[2]).max(s2[2]).max(0.0);
+    let exp = (max_z.to_bits() >> 23) & 0xFF;
+    let r = f32::from_bits(exp.saturating_sub(23) << 23);
+
+    let offset = ctx.polygon_offset_factor * m + ctx.polygon_offset_units * r;
+    s0[2] += offset;
+    s1[2] += offset;
+    s2[2] += offset;
+}
+
 /// Perspective divide + viewport transform in one step.
 #[inline(always)]
 fn to_screen(clip: &[f32; 4], vx: i32, vy: i32, vw: i32, vh: i32) -> [f32; 3] {
@@ -140,8 +140,8 @@ pub fn rasterize_triangle(
     let depth_func = ctx.depth_func;
     let depth_mask = ctx.depth_mask;
     let blend_enabled = ctx.blend;
-    let blend_src = ctx.blend_src_rgb;
-    let blend_dst = ctx.blend_dst_rgb;
+    let blend_state = fragment::BlendState::from_ctx(ctx);
+    let write_out = fragment::WriteOut::from_ctx(ctx);
 
     // ── Scanline loop with span clipping ─────────────────────────────────
     // Instead of scanning min_x..max_x and testing every pixel, we compute
@@ -204,8 +204,9 @@ pub fn rasterize_triangle(
                     let bary1 = w1 * inv_area;
                     let bary2 = w2 * inv_area;
 
-                    // Depth interpolation (screen-space linear)
-                    let depth = bary0 * z0 + bary1 * z1 + bary2 * z2;
+                    // Depth interpolation (screen-space linear), clamped
+                    // because polygon offset can push it out of range
+                    let depth = (bary0 * z0 + bary1 * z1 + bary2 * z2).max(0.0).min(1.0);
 
                     // Early depth test — BEFORE varying interpolation and fragment shader
                     let fb_idx = (row_base + px as u32) as usize;
@@ -264,20 +265,22 @@ pub fn rasterize_triangle(
                     }
                     let fc = fs_exec.frag_color;
 
-                    // Convert fragment color [r,g,b,a] to ARGB u32
-                    let r = (fc[0].clamp(0.0, 1.0) * 255.0) as u32;
-                    let g = (fc[1].clamp(0.0, 1.0) * 255.0) as u32;
-                    let b = (fc[2].clamp(0.0, 1.0) * 255.0) as u32;
-                    let a = (fc[3].clamp(0.0, 1.0) * 255.0) as u32;
-                    let color = (a << 24) | (r << 16) | (g << 8) | b;
+                    let color = [
+                        fc[0].clamp(0.0, 1.0),
+                        fc[1].clamp(0.0, 1.0),
+                        fc[2].clamp(0.0, 1.0),
+                        fc[3].clamp(0.0, 1.0),
+                    ];
 
-                    // Blending
-                    let final_color = if blend_enabled {
+                    // Blending (in float, before the 8-bit write-out)
+                    let blended = if blend_enabled {
                         let dst = unsafe { *ctx.default_fb.color.get_unchecked(fb_idx) };
-                        fragment::blend(color, dst, blend_src, blend_dst)
+                        fragment::blend(color, write_out.unpack(dst), &blend_state)
                     } else {
                         color
                     };
+                    // Convert to ARGB u32 (dither, sRGB encode)
+                    let final_color = write_out.pack(blended, px, py);
 
                     // Write to framebuffer
                     unsafe {
@@ -346,7 +349,9 @@ pub struct ResolvedTexture {
 impl ResolvedTexture {
     /// Resolve the currently bound texture on unit 0.
     ///
-    /// Returns `None` if no texture is bound or the texture has no data.
+    /// Returns `None` if no texture is bound, the texture has no data, or it
+    /// does not use `GL_REPEAT` on both axes (the only wrap mode the fast
+    /// path inlines).
     pub fn resolve_unit0() -> Option<Self> {
         unsafe {
             let bound = crate::BOUND_TEXTURES_PTR;
@@ -355,7 +360,8 @@ impl ResolvedTexture {
             let tex_id = (*bound)[0];
             if tex_id == 0 { return None; }
             match (*store).get(tex_id) {
-                Some(tex) if tex.width > 0 && tex.height > 0 => Some(ResolvedTexture {
+                Some(tex) if tex.width > 0 && tex.height > 0
+                    && tex.wrap_s == GL_REPEAT && tex.wrap_t == GL_REPEAT => Some(ResolvedTexture {
                     data: tex.data.as_ptr(),
                     len: tex.data.len(),
                     width: tex.width,
@@ -427,6 +433,7 @@ pub fn rasterize_triangle_fast(
     let depth_test = ctx.depth_test;
     let depth_func = ctx.depth_func;
     let depth_mask = ctx.depth_mask;
+    let write_out = fragment::WriteOut::from_ctx(ctx);
 
     let tex_data = tex.data;
     let tex_w = tex.width;
@@ -501,8 +508,8 @@ pub fn rasterize_triangle_fast(
                     let bary1 = w1 * inv_area;
                     let bary2 = w2 * inv_area;
 
-                    // Depth
-                    let depth = bary0 * z0 + bary1 * z1 + bary2 * z2;
+                    // Depth (clamped for polygon offset)
+                    let depth = (bary0 * z0 + bary1 * z1 + bary2 * z2).max(0.0).min(1.0);
                     let fb_idx = (row_base + px as u32) as usize;
 
                     if depth_test {
@@ -541,10 +548,11 @@ pub fn rasterize_triangle_fast(
                     let tex_g = ((texel >> 8) & 0xFF) as f32;
                     let tex_b = (texel & 0xFF) as f32;
 
-                    // lighting * texColor * matColor, scaled to 0..255
-                    let r = (lit_r * tex_r * mat_r).min(255.0).max(0.0) as u32;
-                    let g = (lit_g * tex_g * mat_g).min(255.0).max(0.0) as u32;
-                    let b = (lit_b * tex_b * mat_b).min(255.0).max(0.0) as u32;
+                    // lighting * texColor * matColor, scaled to 0..255 (+ dither)
+                    let t = write_out.threshold(px, py);
+                    let r = (lit_r * tex_r * mat_r + t).min(255.0).max(0.0) as u32;
+                    let g = (lit_g * tex_g * mat_g + t).min(255.0).max(0.0) as u32;
+                    let b = (lit_b * tex_b * mat_b + t).min(255.0).max(0.0) as u32;
 
                     let color = 0xFF000000 | (r << 16) | (g << 8) | b;
 
@@ -1,7 +1,8 @@
 //! Vertex attribute fetching from VBOs.
 //!
 //! Reads vertex data from bound buffer objects according to the
-//! vertex attribute pointer configuration.
+//! vertex attribute pointer configuration. Components may be float,
+//! half-float (`GL_HALF_FLOAT_OES`) or normalized (unsigned) byte / short.
 
 use crate::state::GlContext;
 use crate::types::*;
@@ -29,7 +30,7 @@ pub fn fetch_single_attribute(
 
     let elem_size = match typ {
         GL_FLOAT => 4,
-        GL_SHORT | GL_UNSIGNED_SHORT => 2,
+        GL_SHORT | GL_UNSIGNED_SHORT | GL_HALF_FLOAT_OES => 2,
         GL_BYTE | GL_UNSIGNED_BYTE => 1,
         GL_INT | GL_UNSIGNED_INT => 4,
         _ => 4,
@@ -64,6 +65,11 @@ pub fn fetch_single_attribute(
                     v as f32 / 32767.0
                 } else { 0.0 }
             }
+            GL_HALF_FLOAT_OES => {
+                if off + 1 < buf.len() {
+                    half_to_f32(u16::from_le_bytes([buf[off], buf[off+1]]))
+                } else { 0.0 }
+            }
             _ => 0.0,
         };
     }
@@ -73,6 +79,8 @@ pub fn fetch_single_attribute(
 /// Fetch all attributes for a single vertex into a caller-provided buffer.
 ///
 /// Writes one `[f32; 4]` per attribute into `out[0..attrib_info.len()]`.
+/// Entries with size 0 (no per-vertex array) are left untouched, so the
+/// caller can pre-fill constant and per-instance values.
 /// **Zero heap allocation** — writes directly into the caller's stack buffer.
 #[inline]
 pub fn fetch_attributes_into(
@@ -84,7 +92,10 @@ pub fn fetch_attributes_into(
     for (i, &(_loc, size, typ, stride, offset, buffer_id)) in attrib_info.iter().enumerate() {
         if i >= out.len() { break; }
 
-        if size == 0 || buffer_id == 0 {
+        if size == 0 {
+            continue;
+        }
+        if buffer_id == 0 {
             out[i] = [0.0, 0.0, 0.0, 1.0];
             continue;
         }
@@ -99,7 +110,7 @@ pub fn fetch_attributes_into(
 
         let elem_size: i32 = match typ {
             GL_FLOAT => 4,
-            GL_SHORT | GL_UNSIGNED_SHORT => 2,
+            GL_SHORT | GL_UNSIGNED_SHORT | GL_HALF_FLOAT_OES => 2,
             GL_BYTE | GL_UNSIGNED_BYTE => 1,
             GL_INT | GL_UNSIGNED_INT => 4,
             _ => 4,
@@ -140,6 +151,11 @@ pub fn fetch_attributes_into(
                             v as f32 / 32767.0
                         } else { 0.0 }
                     }
+                    GL_HALF_FLOAT_OES => {
+                        if off + 1 < buf.len() {
+                            half_to_f32(u16::from_le_bytes([buf[off], buf[off+1]]))
+                        } else { 0.0 }
+                    }
                     _ => 0.0,
                 };
             }
@@ -148,6 +164,25 @@ pub fn fetch_attributes_into(
     }
 }
 
+/// Convert an IEEE 754 half-precision value (`GL_HALF_FLOAT_OES`) to f32.
+#[inline]
+pub fn half_to_f32(h: u16) -> f32 {
+    let sign = ((h as u32) & 0x8000) << 16;
+    let exp = ((h >> 10) & 0x1F) as u32;
+    let mant = (h & 0x3FF) as u32;
+    let bits = match exp {
+        0 if mant == 0 => sign,
+        0 => {
+            // Subnormal: value = mant * 2^-24.
+            let v = mant as f32 * (1.0 / 16_777_216.0);
+            return if sign != 0 { -v } else { v };
+        }
+        31 => sign | 0x7F80_0000 | (mant << 13),
+        _ => sign | ((exp + 112) << 23) | (mant << 13),
+    };
+    f32::from_bits(bits)
+}
+
 /// Fetch all attribute values for a single vertex (returns Vec, legacy API).
 ///
 /// Kept for backward compatibility with the HW draw path.
@@ -42,7 +42,8 @@ pub struct UniformInfo {
 #[derive(Clone)]
 pub struct AttribInfo {
     pub name: String,
-    /// Attribute location (matches vertex attrib array index).
+    /// Attribute location (matches vertex attrib array index; -1 for
+    /// `gl_InstanceID`).
     pub location: i32,
 }
 
@@ -249,15 +250,21 @@ impl ShaderStore {
             loc += 1;
         }
 
-        // Collect attributes from vertex shader
+        // Collect attributes from vertex shader. gl_InstanceID has no array
+        // location; the draw call supplies its value.
         let mut attributes = Vec::new();
         for (i, a) in vs_ir.attributes.iter().enumerate() {
             let bound_loc = bindings.iter()
                 .find(|(n, _)| n == &a.name)
                 .map(|(_, l)| *l);
+            let location = if a.name == compiler::lower::INSTANCE_ID {
+                -1
+            } else {
+                bound_loc.unwrap_or(i as i32)
+            };
             attributes.push(AttribInfo {
                 name: a.name.clone(),
-                location: bound_loc.unwrap_or(i as i32),
+                location,
             });
         }
 
@@ -0,0 +1,108 @@
+//! sRGB transfer functions for `GL_FRAMEBUFFER_SRGB`.
+//!
+//! With sRGB writes enabled the framebuffer holds sRGB-encoded pixels while
+//! fragment colors and blending stay linear: the destination is decoded
+//! before blending and the result is encoded on write-out. Both directions
+//! use tables built on first use — the fast `math::pow` approximation is too
+//! coarse for 8-bit output, so the tables are computed in f64.
+
+use alloc::vec::Vec;
+
+/// Encode table resolution over linear [0, 1] (interpolated between steps).
+const ENCODE_STEPS: usize = 4096;
+
+struct Tables {
+    /// sRGB byte → linear.
+    decode: Vec<f32>,
+    /// Linear (i / ENCODE_STEPS) → sRGB-encoded [0, 1].
+    encode: Vec<f32>,
+}
+
+static mut TABLES: Option<Tables> = None;
+
+fn tables() -> &'static Tables {
+    unsafe { TABLES.get_or_insert_with(build) }
+}
+
+fn build() -> Tables {
+    let decode = (0..256).map(|i| to_linear(i as f64 / 255.0) as f32).collect();
+    let encode = (0..=ENCODE_STEPS)
+        .map(|i| to_srgb(i as f64 / ENCODE_STEPS as f64) as f32)
+        .collect();
+    Tables { decode, encode }
+}
+
+/// Decode an 8-bit sRGB channel to linear [0, 1].
+#[inline]
+pub fn decode(v: u32) -> f32 {
+    tables().decode[(v & 0xFF) as usize]
+}
+
+/// Encode a linear value to sRGB [0, 1]. The input is clamped to [0, 1].
+#[inline]
+pub fn encode(x: f32) -> f32 {
+    let t = &tables().encode;
+    if !(x > 0.0) {
+        return 0.0;
+    }
+    let pos = x * ENCODE_STEPS as f32;
+    let i = pos as usize;
+    if i >= ENCODE_STEPS {
+        return 1.0;
+    }
+    let f = pos - i as f32;
+    t[i] + (t[i + 1] - t[i]) * f
+}
+
+fn to_linear(c: f64) -> f64 {
+    if c <= 0.04045 { c / 12.92 } else { pow((c + 0.055) / 1.055, 2.4) }
+}
+
+fn to_srgb(l: f64) -> f64 {
+    if l <= 0.003_130_8 { l * 12.92 } else { 1.055 * pow(l, 1.0 / 2.4) - 0.055 }
+}
+
+// ── f64 pow for the tables (x > 0) ──────────────────────────────────────────
+
+const LN2: f64 = 0.693_147_180_559_945_3;
+
+fn pow(x: f64, y: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    exp(y * ln(x))
+}
+
+/// Natural log: x = m·2^e with m in [1, 2), ln(m) = 2·atanh((m-1)/(m+1)).
+fn ln(x: f64) -> f64 {
+    let bits = x.to_bits();
+    let e = ((bits >> 52) & 0x7FF) as i64 - 1023;
+    let m = f64::from_bits((bits & 0x000F_FFFF_FFFF_FFFF) | 0x3FF0_0000_0000_0000);
+    let s = (m - 1.0) / (m + 1.0);
+    let s2 = s * s;
+    let mut term = s;
+    let mut sum = 0.0;
+    let mut k = 1.0;
+    while k < 40.0 {
+        sum += term / k;
+        term *= s2;
+        k += 2.0;
+    }
+    2.0 * sum + e as f64 * LN2
+}
+
+/// e^z: z = k·ln2 + r with |r| <= ln2/2, Taylor series for e^r.
+fn exp(z: f64) -> f64 {
+    let k = (z / LN2 + if z < 0.0 { -0.5 } else { 0.5 }) as i64;
+    let r = z - k as f64 * LN2;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for n in 1..20 {
+        term *= r / n as f64;
+        sum += term;
+    }
+    if !(-1022..=1023).contains(&k) {
+        return if k < 0 { 0.0 } else { f64::MAX };
+    }
+    sum * f64::from_bits(((k + 1023) as u64) << 52)
+}
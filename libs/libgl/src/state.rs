@@ -33,6 +33,11 @@ pub struct VertexAttrib {
     pub offset: usize,
     /// VBO that was bound to GL_ARRAY_BUFFER when this was set.
     pub buffer_id: u32,
+    /// Instanced draws: advance once every `divisor` instances instead of
+    /// once per vertex (0 = per vertex).
+    pub divisor: u32,
+    /// Constant value set by `glVertexAttrib*`, used while the array is disabled.
+    pub current: [f32; 4],
 }
 
 impl Default for VertexAttrib {
@@ -45,6 +50,8 @@ impl Default for VertexAttrib {
             stride: 0,
             offset: 0,
             buffer_id: 0,
+            divisor: 0,
+            current: [0.0, 0.0, 0.0, 1.0],
         }
     }
 }
@@ -73,16 +80,25 @@ pub struct GlContext {
     pub blend: bool,
     pub cull_face: bool,
     pub scissor_test: bool,
+    pub polygon_offset_fill: bool,
+    /// Ordered dithering on write-out (GL_DITHER, on by default as in GL).
+    pub dither: bool,
+    /// sRGB-encode framebuffer writes and blend in linear (off by default).
+    pub framebuffer_srgb: bool,
 
     // ── Depth State ─────────────────────────────────────────────────────
     pub depth_func: GLenum,
     pub depth_mask: bool,
+    pub polygon_offset_factor: f32,
+    pub polygon_offset_units: f32,
 
     // ── Blend State ─────────────────────────────────────────────────────
     pub blend_src_rgb: GLenum,
     pub blend_dst_rgb: GLenum,
     pub blend_src_alpha: GLenum,
     pub blend_dst_alpha: GLenum,
+    pub blend_equation_rgb: GLenum,
+    pub blend_equation_alpha: GLenum,
 
     // ── Cull State ──────────────────────────────────────────────────────
     pub cull_face_mode: GLenum,
@@ -149,14 +165,21 @@ impl GlContext {
             blend: false,
             cull_face: false,
             scissor_test: false,
+            polygon_offset_fill: false,
+            dither: true,
+            framebuffer_srgb: false,
 
             depth_func: GL_LESS,
             depth_mask: true,
+            polygon_offset_factor: 0.0,
+            polygon_offset_units: 0.0,
 
             blend_src_rgb: GL_ONE,
             blend_dst_rgb: GL_ZERO,
             blend_src_alpha: GL_ONE,
             blend_dst_alpha: GL_ZERO,
+            blend_equation_rgb: GL_FUNC_ADD,
+            blend_equation_alpha: GL_FUNC_ADD,
 
             cull_face_mode: GL_BACK,
             front_face: GL_CCW,
@@ -67,6 +67,13 @@ pub const SVGA3D_RS_CULLMODE: u32        = 22;
 pub const SVGA3D_RS_ZFUNC: u32           = 23;
 pub const SVGA3D_RS_BLENDENABLE: u32     = 27;
 pub const SVGA3D_RS_COLORWRITEENABLE: u32 = 168;
+pub const SVGA3D_RS_BLENDEQUATION: u32   = 171;
+pub const SVGA3D_RS_SLOPESCALEDEPTHBIAS: u32 = 175;
+pub const SVGA3D_RS_DEPTHBIAS: u32      = 195;
+pub const SVGA3D_RS_SEPARATEALPHABLENDENABLE: u32 = 206;
+pub const SVGA3D_RS_SRCBLENDALPHA: u32   = 207;
+pub const SVGA3D_RS_DSTBLENDALPHA: u32   = 208;
+pub const SVGA3D_RS_BLENDEQUATIONALPHA: u32 = 209;
 
 // ── Render state values ──────────────────────────────────
 
@@ -97,6 +104,13 @@ pub const SVGA3D_BLEND_INVDESTALPHA: u32     = 8;
 pub const SVGA3D_BLEND_DESTCOLOR: u32        = 9;
 pub const SVGA3D_BLEND_INVDESTCOLOR: u32     = 10;
 
+// Blend equations
+pub const SVGA3D_BLENDEQ_ADD: u32         = 1;
+pub const SVGA3D_BLENDEQ_SUBTRACT: u32    = 2;
+pub const SVGA3D_BLENDEQ_REVSUBTRACT: u32 = 3;
+pub const SVGA3D_BLENDEQ_MINIMUM: u32     = 4;
+pub const SVGA3D_BLENDEQ_MAXIMUM: u32     = 5;
+
 // Clear flags
 pub const SVGA3D_CLEAR_COLOR: u32   = 1;
 pub const SVGA3D_CLEAR_DEPTH: u32   = 2;
@@ -2,6 +2,16 @@
 //!
 //! Stores texture data as RGBA8 pixels. Supports `glTexImage2D`, `glTexSubImage2D`,
 //! `glTexParameteri`, and nearest/linear filtering for the software rasterizer.
+//! All wrap modes (`GL_REPEAT`, `GL_MIRRORED_REPEAT`, `GL_CLAMP_TO_EDGE`) work
+//! for any texture size, not only powers of two.
+//!
+//! Compressed uploads (`glCompressedTexImage2D`) are decoded to RGBA8 on upload:
+//! ETC1 (`GL_OES_compressed_ETC1_RGB8_texture`) and DXT1
+//! (`GL_EXT_texture_compression_dxt1`). Both use 8-byte blocks of 4x4 texels.
+//!
+//! The 16-bit packed types (`GL_UNSIGNED_SHORT_5_6_5` for RGB,
+//! `GL_UNSIGNED_SHORT_4_4_4_4` and `GL_UNSIGNED_SHORT_5_5_5_1` for RGBA) are
+//! expanded to RGBA8 the same way.
 
 use alloc::vec;
 use alloc::vec::Vec;
@@ -39,12 +49,11 @@ impl GlTexture {
         if self.width == 0 || self.height == 0 {
             return [0.0, 0.0, 0.0, 1.0];
         }
-        let u = wrap_coord(u, self.wrap_s);
-        let v = wrap_coord(v, self.wrap_t);
-        let x = ((u * self.width as f32) as i32).clamp(0, self.width as i32 - 1) as u32;
-        let y = ((v * self.height as f32) as i32).clamp(0, self.height as i32 - 1) as u32;
-        let px = self.data[(y * self.width + x) as usize];
-        unpack_rgba(px)
+        let w = self.width as i32;
+        let h = self.height as i32;
+        let x = wrap_texel(floor_f32(reduce_coord(u, self.wrap_s) * w as f32) as i32, w, self.wrap_s);
+        let y = wrap_texel(floor_f32(reduce_coord(v, self.wrap_t) * h as f32) as i32, h, self.wrap_t);
+        self.fetch(x as u32, y as u32)
     }
 
     /// Sample a texel at (u, v) with bilinear filtering.
@@ -52,21 +61,26 @@ impl GlTexture {
         if self.width == 0 || self.height == 0 {
             return [0.0, 0.0, 0.0, 1.0];
         }
-        let u = wrap_coord(u, self.wrap_s);
-        let v = wrap_coord(v, self.wrap_t);
-        let fx = u * self.width as f32 - 0.5;
-        let fy = v * self.height as f32 - 0.5;
+        let w = self.width as i32;
+        let h = self.height as i32;
+        let fx = reduce_coord(u, self.wrap_s) * w as f32 - 0.5;
+        let fy = reduce_coord(v, self.wrap_t) * h as f32 - 0.5;
         let x0 = floor_f32(fx) as i32;
         let y0 = floor_f32(fy) as i32;
         let frac_x = fx - x0 as f32;
         let frac_y = fy - y0 as f32;
 
-        let w = self.width as i32;
-        let h = self.height as i32;
-        let s00 = self.fetch(x0.clamp(0, w - 1) as u32, y0.clamp(0, h - 1) as u32);
-        let s10 = self.fetch((x0 + 1).clamp(0, w - 1) as u32, y0.clamp(0, h - 1) as u32);
-        let s01 = self.fetch(x0.clamp(0, w - 1) as u32, (y0 + 1).clamp(0, h - 1) as u32);
-        let s11 = self.fetch((x0 + 1).clamp(0, w - 1) as u32, (y0 + 1).clamp(0, h - 1) as u32);
+        // Each of the four texels is wrapped on its own, so the footprint
+        // straddling an edge blends with the texel across it (the opposite
+        // edge for REPEAT, the edge itself for MIRRORED_REPEAT and clamping).
+        let xa = wrap_texel(x0, w, self.wrap_s) as u32;
+        let xb = wrap_texel(x0 + 1, w, self.wrap_s) as u32;
+        let ya = wrap_texel(y0, h, self.wrap_t) as u32;
+        let yb = wrap_texel(y0 + 1, h, self.wrap_t) as u32;
+        let s00 = self.fetch(xa, ya);
+        let s10 = self.fetch(xb, ya);
+        let s01 = self.fetch(xa, yb);
+        let s11 = self.fetch(xb, yb);
 
         let mut result = [0.0f32; 4];
         for i in 0..4 {
@@ -148,6 +162,7 @@ impl TextureStore {
         width: u32,
         height: u32,
         format: GLenum,
+        type_: GLenum,
         data: Option<&[u8]>,
     ) {
         if let Some(tex) = self.get_mut(id) {
@@ -158,6 +173,13 @@ impl TextureStore {
             tex.data = vec![0u32; npixels];
 
             if let Some(src) = data {
+                if is_packed_16(type_) {
+                    for i in 0..npixels.min(src.len() / 2) {
+                        let v = u16::from_le_bytes([src[i * 2], src[i * 2 + 1]]);
+                        tex.data[i] = unpack_16(type_, v);
+                    }
+                    return;
+                }
                 match format {
                     GL_RGBA => {
                         for i in 0..npixels.min(src.len() / 4) {
@@ -193,6 +215,231 @@ impl TextureStore {
             }
         }
     }
+
+    /// Upload compressed data (glCompressedTexImage2D), decoded to RGBA8.
+    /// The caller has validated `format` and the data size.
+    pub fn compressed_tex_image_2d(
+        &mut self,
+        id: u32,
+        width: u32,
+        height: u32,
+        format: GLenum,
+        data: &[u8],
+    ) {
+        if let Some(tex) = self.get_mut(id) {
+            tex.width = width;
+            tex.height = height;
+            tex.internal_format = format;
+            tex.data = decode_compressed(format, width, height, data);
+        }
+    }
+
+    /// Replace a block-aligned region with compressed data
+    /// (glCompressedTexSubImage2D). Texels outside the texture are dropped.
+    pub fn compressed_tex_sub_image_2d(
+        &mut self,
+        id: u32,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        format: GLenum,
+        data: &[u8],
+    ) {
+        if let Some(tex) = self.get_mut(id) {
+            let pixels = decode_compressed(format, width, height, data);
+            for row in 0..height.min(tex.height.saturating_sub(y)) {
+                for col in 0..width.min(tex.width.saturating_sub(x)) {
+                    tex.data[((y + row) * tex.width + x + col) as usize] =
+                        pixels[(row * width + col) as usize];
+                }
+            }
+        }
+    }
+}
+
+// ── Packed 16-bit formats ───────────────────────────────────────────────────
+
+/// Whether `type_` is one of the 16-bit packed pixel types.
+pub fn is_packed_16(type_: GLenum) -> bool {
+    matches!(type_, GL_UNSIGNED_SHORT_5_6_5 | GL_UNSIGNED_SHORT_4_4_4_4 | GL_UNSIGNED_SHORT_5_5_5_1)
+}
+
+/// Bytes per pixel of client data for `format`/`type_`, or `None` if the
+/// combination is invalid (a packed type with the wrong format).
+pub fn pixel_size(format: GLenum, type_: GLenum) -> Option<usize> {
+    match type_ {
+        GL_UNSIGNED_SHORT_5_6_5 => if format == GL_RGB { Some(2) } else { None },
+        GL_UNSIGNED_SHORT_4_4_4_4 | GL_UNSIGNED_SHORT_5_5_5_1 => {
+            if format == GL_RGBA { Some(2) } else { None }
+        }
+        _ => Some(match format {
+            GL_RGBA => 4,
+            GL_RGB => 3,
+            GL_LUMINANCE => 1,
+            GL_ALPHA => 1,
+            GL_LUMINANCE_ALPHA => 2,
+            _ => 4,
+        }),
+    }
+}
+
+/// Expand one packed 16-bit pixel to ARGB8, replicating the high bits of
+/// each channel into the low bits so full intensity maps to 0xFF.
+fn unpack_16(type_: GLenum, v: u16) -> u32 {
+    let v = v as u32;
+    let (r, g, b, a) = match type_ {
+        GL_UNSIGNED_SHORT_5_6_5 => {
+            let (r, g, b) = ((v >> 11) & 0x1F, (v >> 5) & 0x3F, v & 0x1F);
+            ((r << 3) | (r >> 2), (g << 2) | (g >> 4), (b << 3) | (b >> 2), 0xFF)
+        }
+        GL_UNSIGNED_SHORT_4_4_4_4 => {
+            let (r, g, b, a) = ((v >> 12) & 0xF, (v >> 8) & 0xF, (v >> 4) & 0xF, v & 0xF);
+            (r * 0x11, g * 0x11, b * 0x11, a * 0x11)
+        }
+        _ => {
+            let (r, g, b) = ((v >> 11) & 0x1F, (v >> 6) & 0x1F, (v >> 1) & 0x1F);
+            ((r << 3) | (r >> 2), (g << 3) | (g >> 2), (b << 3) | (b >> 2), (v & 1) * 0xFF)
+        }
+    };
+    (a << 24) | (r << 16) | (g << 8) | b
+}
+
+// ── Compressed formats ──────────────────────────────────────────────────────
+
+/// Byte size of a compressed `width` x `height` image, or `None` if `format`
+/// is not a supported compressed format.
+pub fn compressed_size(format: GLenum, width: u32, height: u32) -> Option<usize> {
+    match format {
+        GL_ETC1_RGB8_OES | GL_COMPRESSED_RGB_S3TC_DXT1_EXT | GL_COMPRESSED_RGBA_S3TC_DXT1_EXT => {
+            Some(((width as usize + 3) / 4) * ((height as usize + 3) / 4) * 8)
+        }
+        _ => None,
+    }
+}
+
+/// Decode a compressed image into `width * height` ARGB pixels.
+/// `data` must hold at least `compressed_size(format, width, height)` bytes.
+pub fn decode_compressed(format: GLenum, width: u32, height: u32, data: &[u8]) -> Vec<u32> {
+    let (w, h) = (width as usize, height as usize);
+    let mut out = vec![0u32; w * h];
+    let blocks_x = (w + 3) / 4;
+    let mut block = [0u32; 16];
+    for by in 0..(h + 3) / 4 {
+        for bx in 0..blocks_x {
+            let off = (by * blocks_x + bx) * 8;
+            let src = match data.get(off..off + 8) {
+                Some(b) => b,
+                None => return out,
+            };
+            match format {
+                GL_ETC1_RGB8_OES => decode_etc1_block(src, &mut block),
+                GL_COMPRESSED_RGBA_S3TC_DXT1_EXT => decode_dxt1_block(src, true, &mut block),
+                _ => decode_dxt1_block(src, false, &mut block),
+            }
+            // Blocks at the right/bottom edge may extend past the image.
+            for y in 0..4 {
+                let py = by * 4 + y;
+                if py >= h { break; }
+                for x in 0..4 {
+                    let px = bx * 4 + x;
+                    if px >= w { break; }
+                    out[py * w + px] = block[y * 4 + x];
+                }
+            }
+        }
+    }
+    out
+}
+
+/// ETC1 intensity modifier tables, indexed by the 3-bit table codeword.
+const ETC1_MODIFIERS: [[i32; 2]; 8] = [
+    [2, 8], [5, 17], [9, 29], [13, 42], [18, 60], [24, 80], [33, 106], [47, 183],
+];
+
+/// Decode one ETC1 block into 16 ARGB texels (row-major).
+fn decode_etc1_block(src: &[u8], out: &mut [u32; 16]) {
+    let hi = u32::from_be_bytes([src[0], src[1], src[2], src[3]]);
+    let lo = u32::from_be_bytes([src[4], src[5], src[6], src[7]]);
+    let diff = hi & 2 != 0;
+    let flip = hi & 1 != 0;
+
+    // Base colors of the two sub-blocks.
+    let mut base = [[0i32; 3]; 2];
+    for c in 0..3 {
+        let shift = 24 - c * 8;
+        if diff {
+            let c1 = ((hi >> (shift + 3)) & 0x1F) as i32;
+            let d = (((hi >> shift) & 0x7) as i32) << 29 >> 29; // sign-extend 3 bits
+            let c2 = (c1 + d) & 0x1F;
+            base[0][c] = (c1 << 3) | (c1 >> 2);
+            base[1][c] = (c2 << 3) | (c2 >> 2);
+        } else {
+            base[0][c] = ((hi >> (shift + 4)) & 0xF) as i32 * 17;
+            base[1][c] = ((hi >> shift) & 0xF) as i32 * 17;
+        }
+    }
+    let tables = [(hi >> 5) & 7, (hi >> 2) & 7];
+
+    for x in 0..4 {
+        for y in 0..4 {
+            let sub = if flip { (y >= 2) as usize } else { (x >= 2) as usize };
+            // Texel indices are stored column-major.
+            let i = x * 4 + y;
+            let msb = (lo >> (16 + i)) & 1;
+            let lsb = (lo >> i) & 1;
+            let m = ETC1_MODIFIERS[tables[sub] as usize][lsb as usize];
+            let m = if msb != 0 { -m } else { m };
+            let r = (base[sub][0] + m).clamp(0, 255) as u32;
+            let g = (base[sub][1] + m).clamp(0, 255) as u32;
+            let b = (base[sub][2] + m).clamp(0, 255) as u32;
+            out[y * 4 + x] = 0xFF000000 | (r << 16) | (g << 8) | b;
+        }
+    }
+}
+
+/// Decode one DXT1 block into 16 ARGB texels (row-major). With `alpha`,
+/// index 3 in three-color mode is transparent black.
+fn decode_dxt1_block(src: &[u8], alpha: bool, out: &mut [u32; 16]) {
+    let c0 = u16::from_le_bytes([src[0], src[1]]);
+    let c1 = u16::from_le_bytes([src[2], src[3]]);
+    let rgb0 = rgb565(c0);
+    let rgb1 = rgb565(c1);
+    let mut palette = [0u32; 4];
+    palette[0] = pack_rgb(rgb0);
+    palette[1] = pack_rgb(rgb1);
+    if c0 > c1 {
+        palette[2] = pack_rgb(mix(rgb0, rgb1, 2, 1, 3));
+        palette[3] = pack_rgb(mix(rgb0, rgb1, 1, 2, 3));
+    } else {
+        palette[2] = pack_rgb(mix(rgb0, rgb1, 1, 1, 2));
+        palette[3] = if alpha { 0 } else { 0xFF000000 };
+    }
+    for y in 0..4 {
+        let row = src[4 + y];
+        for x in 0..4 {
+            out[y * 4 + x] = palette[((row >> (x * 2)) & 3) as usize];
+        }
+    }
+}
+
+fn rgb565(c: u16) -> [u32; 3] {
+    let r = ((c >> 11) & 0x1F) as u32;
+    let g = ((c >> 5) & 0x3F) as u32;
+    let b = (c & 0x1F) as u32;
+    [(r << 3) | (r >> 2), (g << 2) | (g >> 4), (b << 3) | (b >> 2)]
+}
+
+fn mix(a: [u32; 3], b: [u32; 3], wa: u32, wb: u32, div: u32) -> [u32; 3] {
+    [
+        (a[0] * wa + b[0] * wb) / div,
+        (a[1] * wa + b[1] * wb) / div,
+        (a[2] * wa + b[2] * wb) / div,
+    ]
+}
+
+fn pack_rgb(c: [u32; 3]) -> u32 {
+    0xFF000000 | (c[0] << 16) | (c[1] << 8) | c[2]
 }
 
 /// Unpack an ARGB u32 into [r, g, b, a] floats in 0..1.
@@ -210,19 +457,38 @@ fn floor_f32(x: f32) -> f32 {
     if x < 0.0 && x != i as f32 { (i - 1) as f32 } else { i as f32 }
 }
 
-/// Wrap a texture coordinate according to the wrap mode.
-fn wrap_coord(c: f32, mode: GLenum) -> f32 {
+/// Bring a texture coordinate into one period of its wrap mode — [0, 1)
+/// for REPEAT, [0, 2) for MIRRORED_REPEAT, [-1, 2] for CLAMP_TO_EDGE — so
+/// that scaling it to texels cannot overflow. Wrapping itself happens per
+/// texel in [`wrap_texel`], which works for any texture size.
+fn reduce_coord(c: f32, mode: GLenum) -> f32 {
+    if !c.is_finite() {
+        return 0.0;
+    }
     match mode {
-        GL_CLAMP_TO_EDGE => c.clamp(0.0, 1.0),
+        GL_CLAMP_TO_EDGE => c.clamp(-1.0, 2.0),
         GL_MIRRORED_REPEAT => {
-            let t = floor_f32(c) as i32;
-            let frac = c - floor_f32(c);
-            if t & 1 != 0 { 1.0 - frac } else { frac }
+            let r = c - 2.0 * floor_f32(c * 0.5);
+            if r < 0.0 { r + 2.0 } else if r >= 2.0 { r - 2.0 } else { r }
         }
         _ => {
             // GL_REPEAT
-            let f = c - floor_f32(c);
-            if f < 0.0 { f + 1.0 } else { f }
+            let r = c - floor_f32(c);
+            if r < 0.0 { r + 1.0 } else if r >= 1.0 { r - 1.0 } else { r }
+        }
+    }
+}
+
+/// Map a texel index outside `0..size` back into the texture. Modulo based,
+/// so non-power-of-two sizes repeat and mirror like any other.
+fn wrap_texel(i: i32, size: i32, mode: GLenum) -> i32 {
+    match mode {
+        GL_CLAMP_TO_EDGE => i.clamp(0, size - 1),
+        GL_MIRRORED_REPEAT => {
+            let m = i.rem_euclid(2 * size);
+            if m < size { m } else { 2 * size - 1 - m }
         }
+        // GL_REPEAT
+        _ => i.rem_euclid(size),
     }
 }
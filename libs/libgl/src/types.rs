@@ -37,6 +37,10 @@ pub const GL_DEPTH_TEST: GLenum = 0x0B71;
 pub const GL_BLEND: GLenum = 0x0BE2;
 pub const GL_CULL_FACE_CAP: GLenum = 0x0B44;
 pub const GL_SCISSOR_TEST: GLenum = 0x0C11;
+pub const GL_POLYGON_OFFSET_FILL: GLenum = 0x8037;
+pub const GL_DITHER: GLenum = 0x0BD0;
+/// GL_EXT_sRGB_write_control: sRGB-encode framebuffer writes, blend in linear.
+pub const GL_FRAMEBUFFER_SRGB: GLenum = 0x8DB9;
 
 // ── Clear Bits ──────────────────────────────────────────────────────────────
 
@@ -62,6 +66,14 @@ pub const GL_ELEMENT_ARRAY_BUFFER: GLenum = 0x8893;
 
 pub const GL_STATIC_DRAW: GLenum = 0x88E4;
 pub const GL_DYNAMIC_DRAW: GLenum = 0x88E8;
+pub const GL_STREAM_DRAW: GLenum = 0x88E0;
+
+// ── Buffer Mapping (OES_mapbuffer) ──────────────────────────────────────────
+
+pub const GL_WRITE_ONLY_OES: GLenum = 0x88B9;
+pub const GL_BUFFER_ACCESS_OES: GLenum = 0x88BB;
+pub const GL_BUFFER_MAPPED_OES: GLenum = 0x88BC;
+pub const GL_BUFFER_MAP_POINTER_OES: GLenum = 0x88BD;
 
 // ── Data Types ──────────────────────────────────────────────────────────────
 
@@ -72,6 +84,13 @@ pub const GL_UNSIGNED_SHORT: GLenum = 0x1403;
 pub const GL_INT: GLenum = 0x1404;
 pub const GL_UNSIGNED_INT: GLenum = 0x1405;
 pub const GL_FLOAT: GLenum = 0x1406;
+pub const GL_HALF_FLOAT_OES: GLenum = 0x8D61;
+
+// ── Packed Pixel Types ──────────────────────────────────────────────────────
+
+pub const GL_UNSIGNED_SHORT_4_4_4_4: GLenum = 0x8033;
+pub const GL_UNSIGNED_SHORT_5_5_5_1: GLenum = 0x8034;
+pub const GL_UNSIGNED_SHORT_5_6_5: GLenum = 0x8363;
 
 // ── Texture Targets ─────────────────────────────────────────────────────────
 
@@ -107,6 +126,12 @@ pub const GL_RGBA: GLenum = 0x1908;
 pub const GL_LUMINANCE: GLenum = 0x1909;
 pub const GL_LUMINANCE_ALPHA: GLenum = 0x190A;
 
+// ── Compressed Texture Formats ──────────────────────────────────────────────
+
+pub const GL_ETC1_RGB8_OES: GLenum = 0x8D64;
+pub const GL_COMPRESSED_RGB_S3TC_DXT1_EXT: GLenum = 0x83F0;
+pub const GL_COMPRESSED_RGBA_S3TC_DXT1_EXT: GLenum = 0x83F1;
+
 // ── Texture Units ───────────────────────────────────────────────────────────
 
 pub const GL_TEXTURE0: GLenum = 0x84C0;
@@ -136,6 +161,16 @@ pub const GL_ONE_MINUS_SRC_COLOR: GLenum = 0x0301;
 pub const GL_DST_COLOR: GLenum = 0x0306;
 pub const GL_ONE_MINUS_DST_COLOR: GLenum = 0x0307;
 
+// ── Blend Equations ─────────────────────────────────────────────────────────
+
+pub const GL_FUNC_ADD: GLenum = 0x8006;
+pub const GL_MIN: GLenum = 0x8007;
+pub const GL_MAX: GLenum = 0x8008;
+pub const GL_FUNC_SUBTRACT: GLenum = 0x800A;
+pub const GL_FUNC_REVERSE_SUBTRACT: GLenum = 0x800B;
+pub const GL_BLEND_EQUATION_RGB: GLenum = 0x8009;
+pub const GL_BLEND_EQUATION_ALPHA: GLenum = 0x883D;
+
 // ── Depth Functions ─────────────────────────────────────────────────────────
 
 pub const GL_NEVER: GLenum = 0x0200;
@@ -168,6 +203,7 @@ pub const GL_VENDOR: GLenum = 0x1F00;
 pub const GL_RENDERER: GLenum = 0x1F01;
 pub const GL_VERSION: GLenum = 0x1F02;
 pub const GL_SHADING_LANGUAGE_VERSION: GLenum = 0x8B8C;
+pub const GL_EXTENSIONS: GLenum = 0x1F03;
 
 // ── Pixel Store ─────────────────────────────────────────────────────────────
 
@@ -47,6 +47,9 @@ pub const GL_TRIANGLE_FAN: GLenum = 0x0006;
 pub const GL_ARRAY_BUFFER: GLenum = 0x8892;
 pub const GL_ELEMENT_ARRAY_BUFFER: GLenum = 0x8893;
 pub const GL_STATIC_DRAW: GLenum = 0x88E4;
+pub const GL_DYNAMIC_DRAW: GLenum = 0x88E8;
+pub const GL_STREAM_DRAW: GLenum = 0x88E0;
+pub const GL_WRITE_ONLY_OES: GLenum = 0x88B9;
 pub const GL_FLOAT: GLenum = 0x1406;
 pub const GL_UNSIGNED_SHORT: GLenum = 0x1403;
 pub const GL_UNSIGNED_INT: GLenum = 0x1405;
@@ -65,6 +68,11 @@ pub const GL_COMPILE_STATUS: GLenum = 0x8B81;
 pub const GL_LINK_STATUS: GLenum = 0x8B82;
 pub const GL_SRC_ALPHA: GLenum = 0x0302;
 pub const GL_ONE_MINUS_SRC_ALPHA: GLenum = 0x0303;
+pub const GL_FUNC_ADD: GLenum = 0x8006;
+pub const GL_MIN: GLenum = 0x8007;
+pub const GL_MAX: GLenum = 0x8008;
+pub const GL_FUNC_SUBTRACT: GLenum = 0x800A;
+pub const GL_FUNC_REVERSE_SUBTRACT: GLenum = 0x800B;
 pub const GL_LESS: GLenum = 0x0201;
 pub const GL_LEQUAL: GLenum = 0x0203;
 pub const GL_BACK: GLenum = 0x0405;
@@ -77,9 +85,20 @@ pub const GL_TEXTURE_WRAP_T: GLenum = 0x2803;
 pub const GL_REPEAT: GLenum = 0x2901;
 pub const GL_CLAMP_TO_EDGE: GLenum = 0x812F;
 pub const GL_SCISSOR_TEST: GLenum = 0x0C11;
+pub const GL_POLYGON_OFFSET_FILL: GLenum = 0x8037;
+pub const GL_DITHER: GLenum = 0x0BD0;
+pub const GL_FRAMEBUFFER_SRGB: GLenum = 0x8DB9;
 pub const GL_VENDOR: GLenum = 0x1F00;
 pub const GL_RENDERER: GLenum = 0x1F01;
 pub const GL_VERSION: GLenum = 0x1F02;
+pub const GL_EXTENSIONS: GLenum = 0x1F03;
+pub const GL_ETC1_RGB8_OES: GLenum = 0x8D64;
+pub const GL_COMPRESSED_RGB_S3TC_DXT1_EXT: GLenum = 0x83F0;
+pub const GL_COMPRESSED_RGBA_S3TC_DXT1_EXT: GLenum = 0x83F1;
+pub const GL_HALF_FLOAT_OES: GLenum = 0x8D61;
+pub const GL_UNSIGNED_SHORT_4_4_4_4: GLenum = 0x8033;
+pub const GL_UNSIGNED_SHORT_5_5_5_1: GLenum = 0x8034;
+pub const GL_UNSIGNED_SHORT_5_6_5: GLenum = 0x8363;
 
 // ── Function pointer cache ──────────────────────────────────────────────────
 
@@ -97,6 +116,8 @@ struct LibGl {
     disable: extern "C" fn(GLenum),
     blend_func: extern "C" fn(GLenum, GLenum),
     blend_func_separate: extern "C" fn(GLenum, GLenum, GLenum, GLenum),
+    blend_equation: extern "C" fn(GLenum),
+    blend_equation_separate: extern "C" fn(GLenum, GLenum),
     depth_func: extern "C" fn(GLenum),
     depth_mask: extern "C" fn(GLboolean),
     cull_face: extern "C" fn(GLenum),
@@ -106,6 +127,7 @@ struct LibGl {
     clear: extern "C" fn(GLbitfield),
     scissor: extern "C" fn(GLint, GLint, GLsizei, GLsizei),
     line_width: extern "C" fn(GLfloat),
+    polygon_offset: extern "C" fn(GLfloat, GLfloat),
     pixel_storei: extern "C" fn(GLenum, GLint),
     color_mask: extern "C" fn(GLboolean, GLboolean, GLboolean, GLboolean),
     // Buffers
@@ -114,12 +136,16 @@ struct LibGl {
     bind_buffer: extern "C" fn(GLenum, GLuint),
     buffer_data: extern "C" fn(GLenum, GLsizeiptr, *const u8, GLenum),
     buffer_sub_data: extern "C" fn(GLenum, GLintptr, GLsizeiptr, *const u8),
+    map_buffer: extern "C" fn(GLenum, GLenum) -> *mut u8,
+    unmap_buffer: extern "C" fn(GLenum) -> GLboolean,
     // Textures
     gen_textures: extern "C" fn(GLsizei, *mut GLuint),
     delete_textures: extern "C" fn(GLsizei, *const GLuint),
     bind_texture: extern "C" fn(GLenum, GLuint),
     tex_image_2d: extern "C" fn(GLenum, GLint, GLint, GLsizei, GLsizei, GLint, GLenum, GLenum, *const u8),
     tex_sub_image_2d: extern "C" fn(GLenum, GLint, GLint, GLint, GLsizei, GLsizei, GLenum, GLenum, *const u8),
+    compressed_tex_image_2d: extern "C" fn(GLenum, GLint, GLenum, GLsizei, GLsizei, GLint, GLsizei, *const u8),
+    compressed_tex_sub_image_2d: extern "C" fn(GLenum, GLint, GLint, GLint, GLsizei, GLsizei, GLenum, GLsizei, *const u8),
     tex_parameteri: extern "C" fn(GLenum, GLenum, GLint),
     active_texture: extern "C" fn(GLenum),
     generate_mipmap: extern "C" fn(GLenum),
@@ -151,8 +177,14 @@ struct LibGl {
     enable_vertex_attrib_array: extern "C" fn(GLuint),
     disable_vertex_attrib_array: extern "C" fn(GLuint),
     vertex_attrib_pointer: extern "C" fn(GLuint, GLint, GLenum, GLboolean, GLsizei, *const u8),
+    vertex_attrib1f: extern "C" fn(GLuint, GLfloat),
+    vertex_attrib2f: extern "C" fn(GLuint, GLfloat, GLfloat),
+    vertex_attrib3f: extern "C" fn(GLuint, GLfloat, GLfloat, GLfloat),
+    vertex_attrib4f: extern "C" fn(GLuint, GLfloat, GLfloat, GLfloat, GLfloat),
+    vertex_attrib_divisor: extern "C" fn(GLuint, GLuint),
     // Draw
     draw_arrays: extern "C" fn(GLenum, GLint, GLsizei),
+    draw_arrays_instanced: extern "C" fn(GLenum, GLint, GLsizei, GLsizei),
     draw_elements: extern "C" fn(GLenum, GLsizei, GLenum, *const u8),
     // Framebuffer
     gen_framebuffers: extern "C" fn(GLsizei, *mut GLuint),
@@ -218,6 +250,8 @@ pub fn init() -> bool {
             disable: resolve(&handle, "glDisable"),
             blend_func: resolve(&handle, "glBlendFunc"),
             blend_func_separate: resolve(&handle, "glBlendFuncSeparate"),
+            blend_equation: resolve(&handle, "glBlendEquation"),
+            blend_equation_separate: resolve(&handle, "glBlendEquationSeparate"),
             depth_func: resolve(&handle, "glDepthFunc"),
             depth_mask: resolve(&handle, "glDepthMask"),
             cull_face: resolve(&handle, "glCullFace"),
@@ -227,6 +261,7 @@ pub fn init() -> bool {
             clear: resolve(&handle, "glClear"),
             scissor: resolve(&handle, "glScissor"),
             line_width: resolve(&handle, "glLineWidth"),
+            polygon_offset: resolve(&handle, "glPolygonOffset"),
             pixel_storei: resolve(&handle, "glPixelStorei"),
             color_mask: resolve(&handle, "glColorMask"),
             gen_buffers: resolve(&handle, "glGenBuffers"),
@@ -234,11 +269,15 @@ pub fn init() -> bool {
             bind_buffer: resolve(&handle, "glBindBuffer"),
             buffer_data: resolve(&handle, "glBufferData"),
             buffer_sub_data: resolve(&handle, "glBufferSubData"),
+            map_buffer: resolve(&handle, "glMapBufferOES"),
+            unmap_buffer: resolve(&handle, "glUnmapBufferOES"),
             gen_textures: resolve(&handle, "glGenTextures"),
             delete_textures: resolve(&handle, "glDeleteTextures"),
             bind_texture: resolve(&handle, "glBindTexture"),
             tex_image_2d: resolve(&handle, "glTexImage2D"),
             tex_sub_image_2d: resolve(&handle, "glTexSubImage2D"),
+            compressed_tex_image_2d: resolve(&handle, "glCompressedTexImage2D"),
+            compressed_tex_sub_image_2d: resolve(&handle, "glCompressedTexSubImage2D"),
             tex_parameteri: resolve(&handle, "glTexParameteri"),
             active_texture: resolve(&handle, "glActiveTexture"),
             generate_mipmap: resolve(&handle, "glGenerateMipmap"),
@@ -267,7 +306,13 @@ pub fn init() -> bool {
             enable_vertex_attrib_array: resolve(&handle, "glEnableVertexAttribArray"),
             disable_vertex_attrib_array: resolve(&handle, "glDisableVertexAttribArray"),
             vertex_attrib_pointer: resolve(&handle, "glVertexAttribPointer"),
+            vertex_attrib1f: resolve(&handle, "glVertexAttrib1f"),
+            vertex_attrib2f: resolve(&handle, "glVertexAttrib2f"),
+            vertex_attrib3f: resolve(&handle, "glVertexAttrib3f"),
+            vertex_attrib4f: resolve(&handle, "glVertexAttrib4f"),
+            vertex_attrib_divisor: resolve(&handle, "gl_vertex_attrib_divisor"),
             draw_arrays: resolve(&handle, "glDrawArrays"),
+            draw_arrays_instanced: resolve(&handle, "gl_draw_arrays_instanced"),
             draw_elements: resolve(&handle, "glDrawElements"),
             gen_framebuffers: resolve(&handle, "glGenFramebuffers"),
             delete_framebuffers: resolve(&handle, "glDeleteFramebuffers"),
@@ -328,6 +373,14 @@ pub fn disable(cap: GLenum) { (lib().disable)(cap); }
 /// Set the blend function.
 pub fn blend_func(sfactor: GLenum, dfactor: GLenum) { (lib().blend_func)(sfactor, dfactor); }
 
+/// Set the blend equation for RGB and alpha.
+pub fn blend_equation(mode: GLenum) { (lib().blend_equation)(mode); }
+
+/// Set separate blend equations for RGB and alpha.
+pub fn blend_equation_separate(mode_rgb: GLenum, mode_alpha: GLenum) {
+    (lib().blend_equation_separate)(mode_rgb, mode_alpha);
+}
+
 /// Set the depth function.
 pub fn depth_func(func: GLenum) { (lib().depth_func)(func); }
 
@@ -340,6 +393,9 @@ pub fn cull_face(mode: GLenum) { (lib().cull_face)(mode); }
 /// Set front face winding.
 pub fn front_face(mode: GLenum) { (lib().front_face)(mode); }
 
+/// Set the depth offset for filled polygons (enable `GL_POLYGON_OFFSET_FILL`).
+pub fn polygon_offset(factor: f32, units: f32) { (lib().polygon_offset)(factor, units); }
+
 /// Set the viewport.
 pub fn viewport(x: i32, y: i32, w: i32, h: i32) { (lib().viewport)(x, y, w, h); }
 
@@ -379,6 +435,25 @@ pub fn buffer_data_u16(target: GLenum, data: &[u16], usage: GLenum) {
     (lib().buffer_data)(target, bytes.len() as isize, bytes.as_ptr(), usage);
 }
 
+/// Map the bound buffer for writing (OES_mapbuffer). Null on error.
+pub fn map_buffer(target: GLenum) -> *mut u8 { (lib().map_buffer)(target, GL_WRITE_ONLY_OES) }
+
+/// Unmap the bound buffer. Returns false if it was not mapped.
+pub fn unmap_buffer(target: GLenum) -> bool { (lib().unmap_buffer)(target) != 0 }
+
+/// Orphan the bound buffer at `size` bytes and fill it in place through `f`.
+///
+/// This is the streaming path for per-frame geometry: the data is written
+/// directly into the buffer store, with no staging copy.
+pub fn buffer_write_discard(target: GLenum, size: usize, usage: GLenum, f: impl FnOnce(&mut [u8])) {
+    (lib().buffer_data)(target, size as isize, core::ptr::null(), usage);
+    if size == 0 { return; }
+    let ptr = map_buffer(target);
+    if ptr.is_null() { return; }
+    f(unsafe { core::slice::from_raw_parts_mut(ptr, size) });
+    unmap_buffer(target);
+}
+
 /// Generate textures.
 pub fn gen_textures(n: i32, ids: &mut [u32]) { (lib().gen_textures)(n, ids.as_mut_ptr()); }
 
@@ -401,6 +476,20 @@ pub fn tex_image_2d(target: GLenum, level: i32, internal_format: i32,
                          format, type_, data.as_ptr());
 }
 
+/// Upload compressed texture data (ETC1 or DXT1).
+pub fn compressed_tex_image_2d(target: GLenum, level: i32, internal_format: GLenum,
+                               width: i32, height: i32, border: i32, data: &[u8]) {
+    (lib().compressed_tex_image_2d)(target, level, internal_format, width, height, border,
+                                    data.len() as i32, data.as_ptr());
+}
+
+/// Update a block-aligned region of a compressed (DXT1) texture.
+pub fn compressed_tex_sub_image_2d(target: GLenum, level: i32, x: i32, y: i32,
+                                   width: i32, height: i32, format: GLenum, data: &[u8]) {
+    (lib().compressed_tex_sub_image_2d)(target, level, x, y, width, height, format,
+                                        data.len() as i32, data.as_ptr());
+}
+
 /// Set active texture unit.
 pub fn active_texture(texture: GLenum) { (lib().active_texture)(texture); }
 
@@ -510,9 +599,31 @@ pub fn vertex_attrib_pointer(
     );
 }
 
+/// Set a constant 1-component vertex attribute (used while its array is disabled).
+pub fn vertex_attrib1f(index: u32, x: f32) { (lib().vertex_attrib1f)(index, x); }
+
+/// Set a constant 2-component vertex attribute.
+pub fn vertex_attrib2f(index: u32, x: f32, y: f32) { (lib().vertex_attrib2f)(index, x, y); }
+
+/// Set a constant 3-component vertex attribute.
+pub fn vertex_attrib3f(index: u32, x: f32, y: f32, z: f32) { (lib().vertex_attrib3f)(index, x, y, z); }
+
+/// Set a constant 4-component vertex attribute.
+pub fn vertex_attrib4f(index: u32, x: f32, y: f32, z: f32, w: f32) {
+    (lib().vertex_attrib4f)(index, x, y, z, w);
+}
+
+/// Advance an attribute once every `divisor` instances in instanced draws (0 = per vertex).
+pub fn vertex_attrib_divisor(index: u32, divisor: u32) { (lib().vertex_attrib_divisor)(index, divisor); }
+
 /// Draw arrays.
 pub fn draw_arrays(mode: GLenum, first: i32, count: i32) { (lib().draw_arrays)(mode, first, count); }
 
+/// Draw `instances` copies of an array range (`gl_InstanceID` = copy number).
+pub fn draw_arrays_instanced(mode: GLenum, first: i32, count: i32, instances: i32) {
+    (lib().draw_arrays_instanced)(mode, first, count, instances);
+}
+
 /// Draw elements.
 pub fn draw_elements(mode: GLenum, count: i32, type_: GLenum, offset: usize) {
     (lib().draw_elements)(mode, count, type_, offset as *const u8);
@@ -29,8 +29,10 @@ pub const SYS_GETPID: u32 = 6;
 pub const SYS_YIELD: u32 = 7;
 pub const SYS_SLEEP: u32 = 8;
 pub const SYS_SBRK: u32 = 9;
+pub const SYS_WAITPID: u32 = 12;
 pub const SYS_MMAP: u32 = 14;
 pub const SYS_MUNMAP: u32 = 15;
+pub const SYS_THREAD_CREATE: u32 = 170;
 
 // Filesystem
 pub const SYS_READDIR: u32 = 23;
@@ -73,10 +75,13 @@ pub const SYS_EVT_CHAN_CREATE: u32 = 63;
 pub const SYS_EVT_CHAN_SUBSCRIBE: u32 = 64;
 pub const SYS_EVT_CHAN_EMIT: u32 = 65;
 pub const SYS_EVT_CHAN_POLL: u32 = 66;
+pub const SYS_EVT_CHAN_UNSUBSCRIBE: u32 = 67;
 pub const SYS_EVT_CHAN_EMIT_TO: u32 = 69;
 pub const SYS_EVT_CHAN_WAIT: u32 = 70;
+pub const SYS_EVT_CHAN_WAIT_ANY: u32 = 73;
 
 // System info
+pub const SYS_TIME: u32 = 30;
 pub const SYS_UPTIME_MS: u32 = 35;
 
 // Random
@@ -401,6 +406,25 @@ pub fn get_tid() -> u32 {
     syscall0(SYS_GETPID) as u32
 }
 
+/// Start a thread at `entry` on the stack ending at `stack_top` (the stack
+/// base + size - 8). `name` is shown in the task manager (max 31 chars).
+/// The thread inherits the caller's priority. Returns the TID, or 0 on error.
+pub fn thread_create(entry: fn(), stack_top: u64, name: &str) -> u32 {
+    syscall5(SYS_THREAD_CREATE, entry as u64, stack_top, name.as_ptr() as u64, name.len() as u64, 0) as u32
+}
+
+/// Block until thread `tid` exits. Returns its exit code.
+pub fn waitpid(tid: u32) -> u32 {
+    // Pass options = 0 explicitly; a stray WNOHANG bit would not block.
+    syscall3(SYS_WAITPID, tid as u64, 0, 0) as u32
+}
+
+/// Get the current date and time as
+/// `[year_lo, year_hi, month, day, hour, min, sec, 0]`.
+pub fn time(buf: &mut [u8; 8]) -> u32 {
+    syscall1(SYS_TIME, buf.as_mut_ptr() as u64) as u32
+}
+
 /// Get uptime in milliseconds.
 pub fn uptime_ms() -> u32 {
     syscall0(SYS_UPTIME_MS) as u32
@@ -524,6 +548,16 @@ pub fn evt_chan_wait(channel_id: u32, sub_id: u32, timeout_ms: u32) -> u32 {
     syscall3(SYS_EVT_CHAN_WAIT, channel_id as u64, sub_id as u64, timeout_ms as u64) as u32
 }
 
+pub fn evt_chan_unsubscribe(channel_id: u32, sub_id: u32) {
+    syscall2(SYS_EVT_CHAN_UNSUBSCRIBE, channel_id as u64, sub_id as u64);
+}
+
+/// Block until any of the `(channel_id, sub_id)` subscriptions has an event
+/// (max 16), or timeout. Returns 1 if events are available, 0 on timeout.
+pub fn evt_chan_wait_any(subs: &[[u32; 2]], timeout_ms: u32) -> u32 {
+    syscall3(SYS_EVT_CHAN_WAIT_ANY, subs.as_ptr() as u64, subs.len() as u64, timeout_ms as u64) as u32
+}
+
 // ── Networking ───────────────────────────────────────────────────────
 
 /// Resolve a hostname to an IPv4 address. Returns 0 on success.
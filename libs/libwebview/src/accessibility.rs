@@ -0,0 +1,730 @@
+//! Accessibility tree — the rendered page as screen readers and caret
+//! browsing see it.
+//!
+//! [`tree`] reduces the DOM to the nodes that carry meaning for assistive
+//! technology: landmarks, headings, links, lists, tables, images, form
+//! fields and the text between them.  Each [`AccessNode`] has a [`Role`],
+//! an accessible name, the live value of form fields, its bounds in
+//! document coordinates and a set of `STATE_*` flags.  Generic containers
+//! (`<div>`, `<span>`, ...) are dropped and their children attached to the
+//! nearest kept ancestor; hidden content (`display: none`,
+//! `visibility: hidden`, `hidden`, `aria-hidden="true"`) is left out.
+//!
+//! Names are computed as browsers do, in this order: `aria-label`,
+//! `aria-labelledby`, the field's `<label>`, `alt`, the text content
+//! (links, headings, buttons, cells), `title`, then `placeholder`.  Links,
+//! headings and buttons take their text as their name, so their text is
+//! not repeated as separate static text children.
+//!
+//! [`to_json`] serializes the tree in the shape of the inspector's JSON
+//! (flat array, parent indices) for hosts that hand it over IPC.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::dom::{Dom, NodeId, NodeType, Tag};
+use crate::forms::{self, FieldState};
+use crate::inspect::push_json_str;
+use crate::layout::{FormFieldKind, LayoutBox};
+use crate::style::{ComputedStyle, Display, Visibility};
+
+/// Keyboard focus can move to the node (links, fields, `tabindex`).
+pub const STATE_FOCUSABLE: u32 = 1 << 0;
+/// Checkbox or radio button is checked.
+pub const STATE_CHECKED: u32 = 1 << 1;
+/// Field is disabled (itself or through a `<fieldset disabled>`).
+pub const STATE_DISABLED: u32 = 1 << 2;
+/// Field has the `required` attribute.
+pub const STATE_REQUIRED: u32 = 1 << 3;
+/// Field has the `readonly` attribute.
+pub const STATE_READONLY: u32 = 1 << 4;
+/// `<details open>` (or `aria-expanded="true"`).
+pub const STATE_EXPANDED: u32 = 1 << 5;
+/// Text field accepts several lines (`<textarea>`).
+pub const STATE_MULTILINE: u32 = 1 << 6;
+/// Password field; its value is masked.
+pub const STATE_PROTECTED: u32 = 1 << 7;
+/// No part of the node is inside the viewport.
+pub const STATE_OFFSCREEN: u32 = 1 << 8;
+
+/// What a node is to assistive technology (WAI-ARIA role names).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    Document,
+    Banner,
+    Navigation,
+    Main,
+    Complementary,
+    ContentInfo,
+    Article,
+    Region,
+    Form,
+    Group,
+    /// `<h1>`..`<h6>` with their level.
+    Heading(u8),
+    Paragraph,
+    Link,
+    Button,
+    TextBox,
+    CheckBox,
+    Radio,
+    ComboBox,
+    Image,
+    List,
+    ListItem,
+    Table,
+    Row,
+    ColumnHeader,
+    Cell,
+    Separator,
+    /// A run of text outside links, headings and buttons.
+    StaticText,
+}
+
+impl Role {
+    /// ARIA role name (`"link"`, `"heading"`, ...).
+    pub fn name(self) -> &'static str {
+        match self {
+            Role::Document => "document",
+            Role::Banner => "banner",
+            Role::Navigation => "navigation",
+            Role::Main => "main",
+            Role::Complementary => "complementary",
+            Role::ContentInfo => "contentinfo",
+            Role::Article => "article",
+            Role::Region => "region",
+            Role::Form => "form",
+            Role::Group => "group",
+            Role::Heading(_) => "heading",
+            Role::Paragraph => "paragraph",
+            Role::Link => "link",
+            Role::Button => "button",
+            Role::TextBox => "textbox",
+            Role::CheckBox => "checkbox",
+            Role::Radio => "radio",
+            Role::ComboBox => "combobox",
+            Role::Image => "img",
+            Role::List => "list",
+            Role::ListItem => "listitem",
+            Role::Table => "table",
+            Role::Row => "row",
+            Role::ColumnHeader => "columnheader",
+            Role::Cell => "cell",
+            Role::Separator => "separator",
+            Role::StaticText => "statictext",
+        }
+    }
+
+    /// Role for an explicit `role` attribute; `None` for roles not
+    /// modelled here, which keep the element's implicit role.
+    fn from_aria(name: &str) -> Option<Role> {
+        Some(match name {
+            "banner" => Role::Banner,
+            "navigation" => Role::Navigation,
+            "main" => Role::Main,
+            "complementary" => Role::Complementary,
+            "contentinfo" => Role::ContentInfo,
+            "article" => Role::Article,
+            "region" => Role::Region,
+            "form" | "search" => Role::Form,
+            "group" => Role::Group,
+            "heading" => Role::Heading(2),
+            "paragraph" => Role::Paragraph,
+            "link" => Role::Link,
+            "button" => Role::Button,
+            "textbox" | "searchbox" => Role::TextBox,
+            "checkbox" | "switch" => Role::CheckBox,
+            "radio" => Role::Radio,
+            "combobox" | "listbox" => Role::ComboBox,
+            "img" | "image" => Role::Image,
+            "list" => Role::List,
+            "listitem" => Role::ListItem,
+            "table" | "grid" => Role::Table,
+            "row" => Role::Row,
+            "columnheader" => Role::ColumnHeader,
+            "cell" | "gridcell" => Role::Cell,
+            "separator" => Role::Separator,
+            _ => return None,
+        })
+    }
+
+    /// Roles named by their text content; their text is not listed again
+    /// as static text children.
+    fn name_from_content(self) -> bool {
+        matches!(self, Role::Heading(_) | Role::Link | Role::Button)
+    }
+}
+
+/// A node of the accessibility tree.
+pub struct AccessNode {
+    /// DOM node (for static text, the text node).
+    pub node: NodeId,
+    /// Index of the parent in the tree array; `None` for the document.
+    pub parent: Option<usize>,
+    /// Nesting depth in the accessibility tree (the document is 0).
+    pub depth: u32,
+    pub role: Role,
+    /// Accessible name (collapsed whitespace; may be empty).
+    pub name: String,
+    /// Current value of form fields: text, the selected option's label,
+    /// or bullets for passwords.  Empty for other roles.
+    pub value: String,
+    /// Bounds `(x, y, w, h)` in document coordinates.  Text and elements
+    /// without boxes of their own use their nearest laid-out ancestor's.
+    pub bounds: (i32, i32, i32, i32),
+    /// `STATE_*` flags.
+    pub states: u32,
+}
+
+/// Inputs shared by the whole walk.
+struct Ctx<'a> {
+    dom: &'a Dom,
+    root: Option<&'a LayoutBox>,
+    styles: Option<&'a [ComputedStyle]>,
+    fields: &'a [FieldState],
+    /// Visible document rows `[top, bottom)`.
+    viewport: (i32, i32),
+    /// Rendered link spans not yet claimed by an `<a>`, in layout order.
+    link_spans: Vec<(String, (i32, i32, i32, i32))>,
+}
+
+/// Build the accessibility tree of a page in document order.
+///
+/// `fields` is the live state of the rendered form controls, `styles` the
+/// computed styles (hidden content is only detected with them) and
+/// `viewport` the visible document rows `(top, height)`.
+pub fn tree(
+    dom: &Dom,
+    root: Option<&LayoutBox>,
+    styles: Option<&[ComputedStyle]>,
+    fields: &[FieldState],
+    viewport: (i32, i32),
+) -> Vec<AccessNode> {
+    let mut out = Vec::new();
+    let body = match dom.find_body() {
+        Some(b) => b,
+        None => return out,
+    };
+    let mut link_spans = Vec::new();
+    if let Some(r) = root {
+        collect_link_spans(r, 0, 0, &mut false, &mut link_spans);
+    }
+    let mut ctx = Ctx {
+        dom,
+        root,
+        styles,
+        fields,
+        viewport: (viewport.0, viewport.0 + viewport.1),
+        link_spans,
+    };
+    let bounds = root.map(|r| (0, 0, r.width, r.height)).unwrap_or((0, 0, 0, 0));
+    out.push(AccessNode {
+        node: body,
+        parent: None,
+        depth: 0,
+        role: Role::Document,
+        name: dom.find_title().map(|t| collapse(&t)).unwrap_or_default(),
+        value: String::new(),
+        bounds,
+        states: 0,
+    });
+    for &c in &dom.get(body).children {
+        walk(&mut ctx, c, 0, bounds, false, &mut out);
+    }
+    out
+}
+
+fn walk(
+    ctx: &mut Ctx,
+    id: NodeId,
+    parent: usize,
+    parent_bounds: (i32, i32, i32, i32),
+    skip_text: bool,
+    out: &mut Vec<AccessNode>,
+) {
+    let dom = ctx.dom;
+    let tag = match &dom.get(id).node_type {
+        NodeType::Text(t) => {
+            let text = collapse(t);
+            if !skip_text && !text.is_empty() {
+                let depth = out[parent].depth + 1;
+                out.push(AccessNode {
+                    node: id,
+                    parent: Some(parent),
+                    depth,
+                    role: Role::StaticText,
+                    name: text,
+                    value: String::new(),
+                    bounds: parent_bounds,
+                    states: offscreen(ctx, parent_bounds),
+                });
+            }
+            return;
+        }
+        NodeType::Element { tag, .. } => *tag,
+    };
+    if is_hidden(ctx, id, tag) {
+        return;
+    }
+
+    let rects = match ctx.root {
+        Some(r) => crate::inspect::node_rects(r, id),
+        None => Vec::new(),
+    };
+    let mut bounds = union(rects.iter().map(|r| (r.x, r.y, r.width, r.height)));
+    if let (Tag::A, Some(href)) = (tag, dom.attr(id, "href")) {
+        // Claim the span even if the link has boxes, to keep the order.
+        let span = claim_link_span(ctx, href);
+        bounds = bounds.or(span);
+    }
+    let bounds = bounds.unwrap_or(parent_bounds);
+
+    let role = match role_of(dom, id, tag) {
+        Some(r) => r,
+        None => {
+            // Generic container: its children move up to `parent`.
+            for &c in &dom.get(id).children {
+                walk(ctx, c, parent, bounds, skip_text, out);
+            }
+            return;
+        }
+    };
+
+    let index = out.len();
+    let depth = out[parent].depth + 1;
+    let (value, mut states) = field_value_and_states(ctx, id, tag, role);
+    states |= offscreen(ctx, bounds);
+    out.push(AccessNode {
+        node: id,
+        parent: Some(parent),
+        depth,
+        role,
+        name: accessible_name(ctx, id, tag, role),
+        value,
+        bounds,
+        states,
+    });
+
+    // Form fields and images have no accessible children; the options of
+    // a `<select>` are its value.
+    if matches!(role, Role::TextBox | Role::CheckBox | Role::Radio | Role::ComboBox | Role::Image | Role::Separator) {
+        return;
+    }
+    let skip = skip_text || role.name_from_content();
+    for &c in &dom.get(id).children {
+        walk(ctx, c, index, bounds, skip, out);
+    }
+}
+
+/// Implicit role of an element, overridden by a known `role` attribute.
+/// `None` for generic containers.
+fn role_of(dom: &Dom, id: NodeId, tag: Tag) -> Option<Role> {
+    if let Some(r) = dom.attr(id, "role") {
+        if r.trim().eq_ignore_ascii_case("presentation") || r.trim().eq_ignore_ascii_case("none") {
+            return None;
+        }
+        if let Some(role) = r.split_ascii_whitespace().find_map(Role::from_aria) {
+            if let (Role::Heading(_), Some(level)) = (role, dom.attr(id, "aria-level")) {
+                return Some(Role::Heading(level.trim().parse::<u8>().unwrap_or(2).clamp(1, 6)));
+            }
+            return Some(role);
+        }
+    }
+    Some(match tag {
+        Tag::H1 => Role::Heading(1),
+        Tag::H2 => Role::Heading(2),
+        Tag::H3 => Role::Heading(3),
+        Tag::H4 => Role::Heading(4),
+        Tag::H5 => Role::Heading(5),
+        Tag::H6 => Role::Heading(6),
+        Tag::Header => Role::Banner,
+        Tag::Footer => Role::ContentInfo,
+        Tag::Nav => Role::Navigation,
+        Tag::Main => Role::Main,
+        Tag::Aside => Role::Complementary,
+        Tag::Article => Role::Article,
+        Tag::Section if has_label(dom, id) => Role::Region,
+        Tag::Form => Role::Form,
+        Tag::Fieldset | Tag::Details | Tag::Figure => Role::Group,
+        Tag::P => Role::Paragraph,
+        Tag::A if dom.attr(id, "href").is_some() => Role::Link,
+        Tag::Button | Tag::Summary => Role::Button,
+        Tag::Textarea => Role::TextBox,
+        Tag::Select => Role::ComboBox,
+        Tag::Input => match forms::input_type(dom, id).as_str() {
+            "hidden" => return None,
+            "checkbox" => Role::CheckBox,
+            "radio" => Role::Radio,
+            "submit" | "button" | "reset" | "image" | "file" => Role::Button,
+            _ => Role::TextBox,
+        },
+        Tag::Img => Role::Image,
+        Tag::Ul | Tag::Ol | Tag::Dl => Role::List,
+        Tag::Li | Tag::Dt | Tag::Dd => Role::ListItem,
+        Tag::Table => Role::Table,
+        Tag::Tr => Role::Row,
+        Tag::Th => Role::ColumnHeader,
+        Tag::Td => Role::Cell,
+        Tag::Hr => Role::Separator,
+        _ => return None,
+    })
+}
+
+fn has_label(dom: &Dom, id: NodeId) -> bool {
+    dom.attr(id, "aria-label").is_some() || dom.attr(id, "aria-labelledby").is_some()
+}
+
+/// True for elements that are never shown or are hidden from assistive
+/// technology.
+fn is_hidden(ctx: &Ctx, id: NodeId, tag: Tag) -> bool {
+    if matches!(
+        tag,
+        Tag::Head | Tag::Title | Tag::Style | Tag::Script | Tag::Noscript | Tag::Template
+            | Tag::Meta | Tag::Link | Tag::Option | Tag::Optgroup | Tag::Datalist
+    ) {
+        return true;
+    }
+    let dom = ctx.dom;
+    if dom.attr(id, "hidden").is_some() || dom.attr(id, "aria-hidden").map(|v| v.trim()) == Some("true") {
+        return true;
+    }
+    match ctx.styles.and_then(|s| s.get(id)) {
+        Some(s) => s.display == Display::None || s.visibility != Visibility::Visible,
+        None => false,
+    }
+}
+
+/// Live value and state flags of form fields (and focusability of links).
+fn field_value_and_states(ctx: &Ctx, id: NodeId, tag: Tag, role: Role) -> (String, u32) {
+    let dom = ctx.dom;
+    let mut states = 0;
+    if dom.attr(id, "tabindex").map(|t| t.trim() != "-1").unwrap_or(false) {
+        states |= STATE_FOCUSABLE;
+    }
+    let expanded = match dom.attr(id, "aria-expanded") {
+        Some(v) => v.trim() == "true",
+        None => tag == Tag::Details && dom.attr(id, "open").is_some(),
+    };
+    if expanded {
+        states |= STATE_EXPANDED;
+    }
+    if role == Role::Link {
+        states |= STATE_FOCUSABLE;
+    }
+    if !matches!(tag, Tag::Input | Tag::Textarea | Tag::Select | Tag::Button) {
+        return (String::new(), states);
+    }
+
+    states |= STATE_FOCUSABLE;
+    if forms::is_disabled(dom, id) {
+        states |= STATE_DISABLED;
+    }
+    if dom.attr(id, "required").is_some() {
+        states |= STATE_REQUIRED;
+    }
+    if dom.attr(id, "readonly").is_some() {
+        states |= STATE_READONLY;
+    }
+    if tag == Tag::Textarea {
+        states |= STATE_MULTILINE;
+    }
+
+    let field = ctx.fields.iter().find(|f| f.node_id == id);
+    let value = match field {
+        Some(f) => match f.kind {
+            FormFieldKind::Checkbox | FormFieldKind::Radio => {
+                if f.state != 0 {
+                    states |= STATE_CHECKED;
+                }
+                String::new()
+            }
+            FormFieldKind::Password => {
+                states |= STATE_PROTECTED;
+                f.value.chars().map(|_| '\u{2022}').collect()
+            }
+            FormFieldKind::Select => forms::select_options(dom, id)
+                .get(f.state as usize)
+                .map(|&o| forms::option_label(dom, o))
+                .unwrap_or_default(),
+            FormFieldKind::File => match f.state {
+                0 => String::new(),
+                1 => String::from("1 file"),
+                n => format!("{} files", n),
+            },
+            _ => forms::field_value(dom, f).unwrap_or_default(),
+        },
+        // Not rendered as a control (yet): fall back to the markup.
+        None => match role {
+            Role::CheckBox | Role::Radio => {
+                if dom.attr(id, "checked").is_some() {
+                    states |= STATE_CHECKED;
+                }
+                String::new()
+            }
+            Role::TextBox if tag == Tag::Textarea => forms::textarea_default(dom, id),
+            Role::TextBox => {
+                if forms::input_type(dom, id) == "password" {
+                    states |= STATE_PROTECTED;
+                    String::new()
+                } else {
+                    String::from(dom.attr(id, "value").unwrap_or(""))
+                }
+            }
+            Role::ComboBox => {
+                let options = forms::select_options(dom, id);
+                options.get(forms::initial_selection(dom, &options) as usize)
+                    .map(|&o| forms::option_label(dom, o))
+                    .unwrap_or_default()
+            }
+            _ => String::new(),
+        },
+    };
+    (value, states)
+}
+
+/// Accessible name of an element (see the module docs for the order).
+fn accessible_name(ctx: &Ctx, id: NodeId, tag: Tag, role: Role) -> String {
+    let dom = ctx.dom;
+    let attr = |name: &str| dom.attr(id, name).map(collapse).filter(|s| !s.is_empty());
+
+    if let Some(n) = attr("aria-label") {
+        return n;
+    }
+    if let Some(ids) = dom.attr(id, "aria-labelledby") {
+        let parts: Vec<String> = ids.split_ascii_whitespace()
+            .filter_map(|r| element_by_id(dom, r))
+            .map(|n| collapse(&dom.text_content(n)))
+            .filter(|s| !s.is_empty())
+            .collect();
+        if !parts.is_empty() {
+            return parts.join(" ");
+        }
+    }
+    if matches!(tag, Tag::Input | Tag::Textarea | Tag::Select) {
+        if let Some(n) = field_label(dom, id) {
+            return n;
+        }
+    }
+    if tag == Tag::Input {
+        match forms::input_type(dom, id).as_str() {
+            "submit" => return attr("value").unwrap_or_else(|| String::from("Submit")),
+            "reset" => return attr("value").unwrap_or_else(|| String::from("Reset")),
+            "button" => return attr("value").unwrap_or_default(),
+            "image" => {
+                if let Some(n) = attr("alt") {
+                    return n;
+                }
+            }
+            _ => {}
+        }
+    }
+    if tag == Tag::Img {
+        if let Some(n) = attr("alt") {
+            return n;
+        }
+    }
+    if role.name_from_content() || matches!(role, Role::Cell | Role::ColumnHeader) {
+        let text = content_name(dom, id);
+        if !text.is_empty() {
+            return text;
+        }
+    }
+    if let Some(n) = attr("title") {
+        return n;
+    }
+    if role == Role::TextBox {
+        if let Some(n) = attr("placeholder") {
+            return n;
+        }
+    }
+    String::new()
+}
+
+/// Text of `id`'s subtree, with the `alt` text of images standing in for
+/// them (an image-only link is named by its image).
+fn content_name(dom: &Dom, id: NodeId) -> String {
+    let mut raw = String::new();
+    let mut stack = alloc::vec![id];
+    while let Some(n) = stack.pop() {
+        match &dom.get(n).node_type {
+            NodeType::Text(t) => {
+                raw.push_str(t);
+                raw.push(' ');
+            }
+            NodeType::Element { tag, .. } => {
+                if *tag == Tag::Img {
+                    raw.push_str(dom.attr(n, "alt").unwrap_or(""));
+                    raw.push(' ');
+                } else if !matches!(tag, Tag::Script | Tag::Style) && dom.attr(n, "aria-hidden") != Some("true") {
+                    stack.extend(dom.get(n).children.iter().rev());
+                }
+            }
+        }
+    }
+    collapse(&raw)
+}
+
+/// Text of the `<label for=id>` of a field, or of the `<label>` around it.
+fn field_label(dom: &Dom, id: NodeId) -> Option<String> {
+    if let Some(field_id) = dom.attr(id, "id") {
+        let label = (0..dom.nodes.len())
+            .find(|&n| dom.tag(n) == Some(Tag::Label) && dom.attr(n, "for") == Some(field_id));
+        if let Some(l) = label {
+            let text = collapse(&dom.text_content(l));
+            if !text.is_empty() {
+                return Some(text);
+            }
+        }
+    }
+    let mut cur = dom.get(id).parent;
+    while let Some(p) = cur {
+        if dom.tag(p) == Some(Tag::Label) {
+            let text = collapse(&dom.text_content(p));
+            return Some(text).filter(|t| !t.is_empty());
+        }
+        cur = dom.get(p).parent;
+    }
+    None
+}
+
+fn element_by_id(dom: &Dom, id: &str) -> Option<NodeId> {
+    (0..dom.nodes.len()).find(|&n| dom.attr(n, "id") == Some(id))
+}
+
+/// `STATE_OFFSCREEN` if `bounds` lies entirely outside the viewport rows.
+fn offscreen(ctx: &Ctx, bounds: (i32, i32, i32, i32)) -> u32 {
+    let (_, y, _, h) = bounds;
+    if y + h <= ctx.viewport.0 || y >= ctx.viewport.1 { STATE_OFFSCREEN } else { 0 }
+}
+
+/// Collapse whitespace runs to single spaces and trim.
+fn collapse(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for word in s.split_whitespace() {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    out
+}
+
+fn union(mut rects: impl Iterator<Item = (i32, i32, i32, i32)>) -> Option<(i32, i32, i32, i32)> {
+    let first = rects.next()?;
+    let (mut x0, mut y0, mut x1, mut y1) = (first.0, first.1, first.0 + first.2, first.1 + first.3);
+    for (x, y, w, h) in rects {
+        x0 = x0.min(x);
+        y0 = y0.min(y);
+        x1 = x1.max(x + w);
+        y1 = y1.max(y + h);
+    }
+    Some((x0, y0, x1 - x0, y1 - y0))
+}
+
+// ---------------------------------------------------------------------------
+// Link bounds
+// ---------------------------------------------------------------------------
+
+// Inline `<a>` elements generate no boxes of their own: their text runs
+// carry the `href` instead.  Consecutive runs with the same `href` form one
+// rendered link, and the n-th such span is matched with the n-th `<a>` of
+// that `href` in document order.
+
+/// Rendered link spans `(href, bounds)` in layout order.  `open` is set
+/// while the last text run seen belonged to a link, so a link wrapped over
+/// several line boxes stays one span.
+fn collect_link_spans(
+    bx: &LayoutBox,
+    offset_x: i32,
+    offset_y: i32,
+    open: &mut bool,
+    out: &mut Vec<(String, (i32, i32, i32, i32))>,
+) {
+    let abs_x = if bx.is_fixed { bx.x } else { offset_x + bx.x };
+    let abs_y = if bx.is_fixed { bx.y } else { offset_y + bx.y };
+    for child in &bx.children {
+        if child.text.is_none() {
+            // Element boxes (blocks, images, fields) end a span; anonymous
+            // line boxes and spacers do not.
+            if child.node_id.is_some() {
+                *open = false;
+            }
+            collect_link_spans(child, abs_x, abs_y, open, out);
+            continue;
+        }
+        let href = match child.link_url {
+            Some(ref h) => h,
+            None => {
+                *open = false;
+                continue;
+            }
+        };
+        let r = (abs_x + child.x, abs_y + child.y, child.width, child.height);
+        match out.last_mut() {
+            Some((h, b)) if *open && h == href => *b = union([*b, r].into_iter()).unwrap_or(r),
+            _ => out.push((href.clone(), r)),
+        }
+        *open = true;
+    }
+}
+
+/// Take the next unclaimed rendered span of `href`.
+fn claim_link_span(ctx: &mut Ctx, href: &str) -> Option<(i32, i32, i32, i32)> {
+    let i = ctx.link_spans.iter().position(|(h, _)| h == href)?;
+    Some(ctx.link_spans.remove(i).1)
+}
+
+/// Serialize `nodes` as a JSON array: every entry has its `"node"` id,
+/// `"parent"` index, `"depth"`, `"role"` (plus `"level"` for headings),
+/// `"name"`, `"value"`, `"bounds"` `[x, y, w, h]` and `"states"` as an
+/// array of state names.
+pub fn to_json(nodes: &[AccessNode]) -> String {
+    const STATE_NAMES: [(u32, &str); 9] = [
+        (STATE_FOCUSABLE, "focusable"),
+        (STATE_CHECKED, "checked"),
+        (STATE_DISABLED, "disabled"),
+        (STATE_REQUIRED, "required"),
+        (STATE_READONLY, "readonly"),
+        (STATE_EXPANDED, "expanded"),
+        (STATE_MULTILINE, "multiline"),
+        (STATE_PROTECTED, "protected"),
+        (STATE_OFFSCREEN, "offscreen"),
+    ];
+    let mut out = String::from("[");
+    for (i, n) in nodes.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&format!("{{\"node\":{},\"parent\":", n.node));
+        match n.parent {
+            Some(p) => out.push_str(&format!("{}", p)),
+            None => out.push_str("null"),
+        }
+        out.push_str(&format!(",\"depth\":{},\"role\":\"{}\"", n.depth, n.role.name()));
+        if let Role::Heading(level) = n.role {
+            out.push_str(&format!(",\"level\":{}", level));
+        }
+        out.push_str(",\"name\":");
+        push_json_str(&mut out, &n.name);
+        out.push_str(",\"value\":");
+        push_json_str(&mut out, &n.value);
+        let (x, y, w, h) = n.bounds;
+        out.push_str(&format!(",\"bounds\":[{},{},{},{}],\"states\":[", x, y, w, h));
+        let mut first = true;
+        for (bit, name) in STATE_NAMES {
+            if n.states & bit != 0 {
+                if !first {
+                    out.push(',');
+                }
+                first = false;
+                out.push('"');
+                out.push_str(name);
+                out.push('"');
+            }
+        }
+        out.push_str("]}");
+    }
+    out.push(']');
+    out
+}
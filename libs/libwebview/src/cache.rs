@@ -0,0 +1,508 @@
+//! HTTP resource cache for sub-resources (stylesheets, images).
+//!
+//! Entries are keyed by absolute URL and hold the raw response body and
+//! headers, plus — for images — the decoded pixels, so a page that reuses an
+//! image another page already showed skips both the fetch and the decode.
+//!
+//! Freshness follows `Cache-Control` (`max-age`, `no-cache`, `no-store`),
+//! `Pragma: no-cache` and `Expires`.  A response without explicit freshness
+//! information is considered fresh for `HEURISTIC_SECS`.  Stale entries
+//! carrying an `ETag` or `Last-Modified` validator can be revalidated: the
+//! caller sends the header lines carried by `Lookup::Stale` and reports a
+//! `304 Not Modified` via `not_modified()`.
+//!
+//! Memory use (bodies + decoded pixels) is kept under a byte budget by
+//! evicting the least recently used entries.  When a disk directory is set,
+//! cacheable responses are also written to `<dir>/<hash>.meta` and
+//! `<dir>/<hash>.body` and loaded back on a memory miss, so they survive a
+//! browser restart.  Decoded images are never written to disk.
+//!
+//! Metadata file format (one field per line, headers last):
+//! ```text
+//! url
+//! expires (Unix seconds, 0 = already stale)
+//! flags (n = no-cache)
+//! etag
+//! last-modified
+//! raw response headers…
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::cookies::{now_secs, parse_http_date};
+
+/// Default memory budget: 32 MiB.
+pub const DEFAULT_BUDGET: usize = 32 * 1024 * 1024;
+
+/// Freshness lifetime of responses without `max-age` / `Expires`.
+const HEURISTIC_SECS: u64 = 300;
+
+/// Responses larger than this are not cached (neither memory nor disk).
+const MAX_ENTRY_BYTES: usize = 8 * 1024 * 1024;
+
+// ═══════════════════════════════════════════════════════════
+// Cache policy
+// ═══════════════════════════════════════════════════════════
+
+/// Caching directives extracted from a response's headers.
+#[derive(Clone, Default)]
+pub struct CachePolicy {
+    /// `Cache-Control: no-store` — never cache.
+    pub no_store: bool,
+    /// `no-cache`: revalidate on every use.
+    pub no_cache: bool,
+    /// Absolute expiry time (Unix seconds).
+    pub expires: u64,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl CachePolicy {
+    /// Derive the policy for a response received at `now`.
+    pub fn from_headers(headers: &str, now: u64) -> Self {
+        let mut policy = CachePolicy::default();
+        let mut max_age: Option<u64> = None;
+        let mut expires_hdr: Option<u64> = None;
+        let mut date_hdr: Option<u64> = None;
+
+        for line in headers.split('\n') {
+            let line = line.trim_end_matches('\r');
+            let colon = match line.find(':') {
+                Some(c) => c,
+                None => continue,
+            };
+            let name = line[..colon].trim();
+            let value = line[colon + 1..].trim();
+            if name.eq_ignore_ascii_case("cache-control") {
+                for directive in value.split(',') {
+                    let d = directive.trim();
+                    let (key, arg) = match d.find('=') {
+                        Some(eq) => (d[..eq].trim(), Some(d[eq + 1..].trim().trim_matches('"'))),
+                        None => (d, None),
+                    };
+                    if key.eq_ignore_ascii_case("no-store") {
+                        policy.no_store = true;
+                    } else if key.eq_ignore_ascii_case("no-cache") {
+                        policy.no_cache = true;
+                    } else if key.eq_ignore_ascii_case("max-age") {
+                        // `s-maxage` applies to shared caches only.
+                        max_age = arg.and_then(|a| a.parse::<u64>().ok());
+                    }
+                }
+            } else if name.eq_ignore_ascii_case("pragma") {
+                if value.eq_ignore_ascii_case("no-cache") {
+                    policy.no_cache = true;
+                }
+            } else if name.eq_ignore_ascii_case("expires") {
+                // Invalid dates (e.g. "0") mean "already expired".
+                expires_hdr = Some(parse_http_date(value).unwrap_or(0));
+            } else if name.eq_ignore_ascii_case("date") {
+                date_hdr = parse_http_date(value);
+            } else if name.eq_ignore_ascii_case("etag") {
+                policy.etag = Some(String::from(value));
+            } else if name.eq_ignore_ascii_case("last-modified") {
+                policy.last_modified = Some(String::from(value));
+            }
+        }
+
+        policy.expires = if let Some(age) = max_age {
+            now.saturating_add(age)
+        } else if let Some(exp) = expires_hdr {
+            // Interpret `Expires` relative to the server's clock when a
+            // `Date` header is present, so local clock skew does not matter.
+            match date_hdr {
+                Some(date) => now.saturating_add(exp.saturating_sub(date)),
+                None => exp,
+            }
+        } else {
+            now + HEURISTIC_SECS
+        };
+        if policy.no_cache {
+            policy.expires = 0;
+        }
+        policy
+    }
+
+    /// `true` if a validator is available for a conditional request.
+    pub fn can_revalidate(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+// Entries
+// ═══════════════════════════════════════════════════════════
+
+/// Decoded image pixels attached to a cache entry.
+pub struct CachedImage {
+    /// ARGB pixels, `width × height`.
+    pub pixels: Vec<u32>,
+    pub width: u32,
+    pub height: u32,
+}
+
+struct Entry {
+    url: String,
+    body: Vec<u8>,
+    headers: String,
+    policy: CachePolicy,
+    image: Option<CachedImage>,
+    /// Value of the cache's use counter at the last access (LRU order).
+    last_used: u64,
+}
+
+impl Entry {
+    fn bytes(&self) -> usize {
+        let image = self.image.as_ref().map_or(0, |i| i.pixels.len() * 4);
+        self.body.len() + self.headers.len() + self.url.len() + image
+    }
+}
+
+/// Result of `ResourceCache::lookup()`.
+pub enum Lookup {
+    /// Usable without contacting the server.
+    Fresh,
+    /// Cached but stale: fetch again, sending the contained header lines
+    /// (empty if no validator is known) to allow a `304` response.
+    Stale(String),
+    /// Not cached.
+    Miss,
+}
+
+/// Counters reported by `ResourceCache::stats()`.
+#[derive(Clone, Copy, Default)]
+pub struct CacheStats {
+    /// Entries held in memory.
+    pub entries: usize,
+    /// Entries that also carry decoded image pixels.
+    pub images: usize,
+    /// Memory used by bodies, headers and decoded pixels.
+    pub bytes: usize,
+    /// Memory budget in bytes.
+    pub budget: usize,
+    /// Fresh lookups served from memory or disk.
+    pub hits: u32,
+    /// Lookups that found nothing.
+    pub misses: u32,
+    /// Stale lookups answered with a `304 Not Modified`.
+    pub revalidated: u32,
+    /// Entries loaded back from the disk cache.
+    pub disk_loads: u32,
+}
+
+// ═══════════════════════════════════════════════════════════
+// ResourceCache
+// ═══════════════════════════════════════════════════════════
+
+/// In-memory LRU cache of fetched sub-resources with an optional disk tier.
+pub struct ResourceCache {
+    entries: Vec<Entry>,
+    bytes: usize,
+    budget: usize,
+    use_counter: u64,
+    disk_dir: Option<String>,
+    stats: CacheStats,
+}
+
+impl Default for ResourceCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResourceCache {
+    /// Create an empty memory-only cache with the default budget.
+    pub fn new() -> Self {
+        ResourceCache {
+            entries: Vec::new(),
+            bytes: 0,
+            budget: DEFAULT_BUDGET,
+            use_counter: 0,
+            disk_dir: None,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Set the memory budget in bytes, evicting entries if now over it.
+    pub fn set_budget(&mut self, bytes: usize) {
+        self.budget = bytes;
+        self.evict_to(self.budget);
+    }
+
+    /// Enable the disk tier, storing files in `dir` (created on demand).
+    pub fn set_disk_dir(&mut self, dir: &str) {
+        self.disk_dir = Some(String::from(dir.trim_end_matches('/')));
+    }
+
+    /// Check whether `url` can be served from the cache.
+    ///
+    /// Falls back to the disk tier on a memory miss.  Updates the hit/miss
+    /// counters and the entry's LRU position.
+    pub fn lookup(&mut self, url: &str) -> Lookup {
+        let idx = match self.find(url).or_else(|| self.load_from_disk(url)) {
+            Some(i) => i,
+            None => {
+                self.stats.misses += 1;
+                return Lookup::Miss;
+            }
+        };
+        self.touch(idx);
+        let policy = &self.entries[idx].policy;
+        if !policy.no_cache && now_secs() < policy.expires {
+            self.stats.hits += 1;
+            return Lookup::Fresh;
+        }
+        let mut validators = String::new();
+        if let Some(etag) = &policy.etag {
+            validators.push_str("If-None-Match: ");
+            validators.push_str(etag);
+            validators.push_str("\r\n");
+        }
+        if let Some(lm) = &policy.last_modified {
+            validators.push_str("If-Modified-Since: ");
+            validators.push_str(lm);
+            validators.push_str("\r\n");
+        }
+        Lookup::Stale(validators)
+    }
+
+    /// Store a `2xx` response.  Returns `false` if the response may not be
+    /// cached (`no-store`, or too large); any older entry is dropped then.
+    pub fn store(&mut self, url: &str, headers: &str, body: &[u8]) -> bool {
+        let policy = CachePolicy::from_headers(headers, now_secs());
+        self.remove(url);
+        if policy.no_store || body.len() > MAX_ENTRY_BYTES {
+            return false;
+        }
+        let entry = Entry {
+            url: String::from(url),
+            body: body.to_vec(),
+            headers: String::from(headers),
+            policy,
+            image: None,
+            last_used: 0,
+        };
+        self.write_to_disk(&entry);
+        self.insert(entry);
+        true
+    }
+
+    /// Record a `304 Not Modified` answer for a stale entry: refresh its
+    /// freshness from the new headers.  Returns `false` if the entry is gone
+    /// (evicted meanwhile), in which case the resource must be refetched.
+    pub fn not_modified(&mut self, url: &str, headers: &str) -> bool {
+        let idx = match self.find(url) {
+            Some(i) => i,
+            None => return false,
+        };
+        let fresh = CachePolicy::from_headers(headers, now_secs());
+        let entry = &mut self.entries[idx];
+        // A 304 may omit the validators; keep the stored ones then.
+        entry.policy.no_cache = fresh.no_cache;
+        entry.policy.expires = fresh.expires;
+        if fresh.etag.is_some() {
+            entry.policy.etag = fresh.etag;
+        }
+        if fresh.last_modified.is_some() {
+            entry.policy.last_modified = fresh.last_modified;
+        }
+        self.stats.revalidated += 1;
+        self.touch(idx);
+        if let Some(dir) = &self.disk_dir {
+            write_meta(&dir, &self.entries[idx]);
+        }
+        true
+    }
+
+    /// Cached response body and headers for `url`.
+    pub fn get(&mut self, url: &str) -> Option<(&[u8], &str)> {
+        let idx = self.find(url)?;
+        self.touch(idx);
+        let e = &self.entries[idx];
+        Some((&e.body, &e.headers))
+    }
+
+    /// Attach decoded pixels to the entry for `url` (no-op if not cached).
+    pub fn set_image(&mut self, url: &str, pixels: Vec<u32>, width: u32, height: u32) {
+        let idx = match self.find(url) {
+            Some(i) => i,
+            None => return,
+        };
+        let before = self.entries[idx].bytes();
+        self.entries[idx].image = Some(CachedImage { pixels, width, height });
+        self.bytes = self.bytes - before + self.entries[idx].bytes();
+        self.touch(idx);
+        self.evict_to(self.budget);
+    }
+
+    /// Decoded pixels for `url`, if the image has been decoded before.
+    pub fn image(&mut self, url: &str) -> Option<&CachedImage> {
+        let idx = self.find(url)?;
+        self.touch(idx);
+        self.entries[idx].image.as_ref()
+    }
+
+    /// Drop the entry for `url` from memory and disk.
+    pub fn remove(&mut self, url: &str) {
+        if let Some(idx) = self.find(url) {
+            let e = self.entries.swap_remove(idx);
+            self.bytes -= e.bytes();
+        }
+        if let Some(dir) = &self.disk_dir {
+            let base = disk_base(dir, url);
+            anyos_std::fs::unlink(&format!("{}.meta", base));
+            anyos_std::fs::unlink(&format!("{}.body", base));
+        }
+    }
+
+    /// Drop every entry, in memory and on disk, and reset the counters.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.bytes = 0;
+        self.stats = CacheStats::default();
+        let dir = match &self.disk_dir {
+            Some(d) => d.clone(),
+            None => return,
+        };
+        // `read_dir` returns a bounded batch; repeat until nothing is removed.
+        loop {
+            let names: Vec<String> = match anyos_std::fs::read_dir(&dir) {
+                Ok(rd) => rd
+                    .filter(|e| e.file_type == 0)
+                    .filter(|e| e.name.ends_with(".meta") || e.name.ends_with(".body"))
+                    .map(|e| e.name)
+                    .collect(),
+                Err(_) => return,
+            };
+            if names.is_empty() {
+                return;
+            }
+            let mut removed = false;
+            for name in names {
+                removed |= anyos_std::fs::unlink(&format!("{}/{}", dir, name)) == 0;
+            }
+            if !removed {
+                return;
+            }
+        }
+    }
+
+    /// Current counters and memory usage.
+    pub fn stats(&self) -> CacheStats {
+        let mut s = self.stats;
+        s.entries = self.entries.len();
+        s.images = self.entries.iter().filter(|e| e.image.is_some()).count();
+        s.bytes = self.bytes;
+        s.budget = self.budget;
+        s
+    }
+
+    // ── internals ──────────────────────────────────────────────────────
+
+    fn find(&self, url: &str) -> Option<usize> {
+        self.entries.iter().position(|e| e.url == url)
+    }
+
+    fn touch(&mut self, idx: usize) {
+        self.use_counter += 1;
+        self.entries[idx].last_used = self.use_counter;
+    }
+
+    fn insert(&mut self, mut entry: Entry) -> usize {
+        self.use_counter += 1;
+        entry.last_used = self.use_counter;
+        let size = entry.bytes();
+        // Make room first so the new entry itself is not the one evicted.
+        self.evict_to(self.budget.saturating_sub(size));
+        self.bytes += size;
+        self.entries.push(entry);
+        self.entries.len() - 1
+    }
+
+    /// Evict least recently used entries until at most `limit` bytes are used.
+    fn evict_to(&mut self, limit: usize) {
+        while self.bytes > limit && !self.entries.is_empty() {
+            let lru = self.entries.iter()
+                .enumerate()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(i, _)| i)
+                .unwrap_or(0);
+            let e = self.entries.swap_remove(lru);
+            self.bytes -= e.bytes();
+        }
+    }
+
+    fn write_to_disk(&self, entry: &Entry) {
+        let dir = match &self.disk_dir {
+            Some(d) => d,
+            None => return,
+        };
+        // Entries that must be refetched unconditionally are useless on disk.
+        if entry.policy.no_cache && !entry.policy.can_revalidate() {
+            return;
+        }
+        anyos_std::fs::mkdir(dir);
+        let base = disk_base(dir, &entry.url);
+        if anyos_std::fs::write_bytes(&format!("{}.body", base), &entry.body).is_ok() {
+            write_meta(dir, entry);
+        }
+    }
+
+    fn load_from_disk(&mut self, url: &str) -> Option<usize> {
+        let base = disk_base(self.disk_dir.as_ref()?, url);
+        let meta = anyos_std::fs::read_to_string(&format!("{}.meta", base)).ok()?;
+        let mut lines = meta.splitn(6, '\n');
+        if lines.next()? != url {
+            // Hash collision with another URL.
+            return None;
+        }
+        let expires = lines.next()?.parse::<u64>().ok()?;
+        let no_cache = lines.next()?.contains('n');
+        let etag = lines.next()?;
+        let last_modified = lines.next()?;
+        let headers = lines.next().unwrap_or("");
+        let body = anyos_std::fs::read_to_vec(&format!("{}.body", base)).ok()?;
+        let policy = CachePolicy {
+            no_store: false,
+            no_cache,
+            expires,
+            etag: if etag.is_empty() { None } else { Some(String::from(etag)) },
+            last_modified: if last_modified.is_empty() { None } else { Some(String::from(last_modified)) },
+        };
+        self.stats.disk_loads += 1;
+        Some(self.insert(Entry {
+            url: String::from(url),
+            body,
+            headers: String::from(headers),
+            policy,
+            image: None,
+            last_used: 0,
+        }))
+    }
+}
+
+/// Path prefix of the disk files for `url`: `<dir>/<fnv1a-64 hex>`.
+fn disk_base(dir: &str, url: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in url.bytes() {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{}/{:016x}", dir, hash)
+}
+
+fn write_meta(dir: &str, entry: &Entry) {
+    let p = &entry.policy;
+    let meta = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        entry.url,
+        p.expires,
+        if p.no_cache { "n" } else { "-" },
+        p.etag.as_deref().unwrap_or(""),
+        p.last_modified.as_deref().unwrap_or(""),
+        entry.headers,
+    );
+    let _ = anyos_std::fs::write_bytes(&format!("{}.meta", disk_base(dir, &entry.url)), meta.as_bytes());
+}
@@ -0,0 +1,437 @@
+//! HTTP cookie jar (RFC 6265 subset) with on-disk persistence.
+//!
+//! Cookies arrive from `Set-Cookie` response headers and from scripts writing
+//! `document.cookie`.  The `Expires`, `Max-Age`, `Domain`, `Path`, `Secure`
+//! and `HttpOnly` attributes are honored.  Cookies with an expiry date are
+//! written to `<profile_dir>/cookies.txt` by `save()`; session cookies live
+//! only in memory.
+//!
+//! File format (one cookie per line, tab separated):
+//! ```text
+//! domain  host_only  path  secure  http_only  expires  name  value
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Maximum number of cookies kept in a jar.  Oldest cookies are evicted first.
+const MAX_COOKIES: usize = 1000;
+
+/// Name of the cookie file inside the profile directory.
+const COOKIE_FILE: &str = "cookies.txt";
+
+// ═══════════════════════════════════════════════════════════
+// Cookie
+// ═══════════════════════════════════════════════════════════
+
+/// A single stored cookie.
+#[derive(Clone)]
+pub struct Cookie {
+    /// Lower-case domain without a leading dot.
+    pub domain: String,
+    pub path: String,
+    pub name: String,
+    pub value: String,
+    pub secure: bool,
+    pub http_only: bool,
+    /// `true` if the cookie had no `Domain` attribute — it is then only sent
+    /// to the exact host that set it, not to subdomains.
+    pub host_only: bool,
+    /// Expiry as Unix seconds, or `None` for a session cookie.
+    pub expires: Option<u64>,
+}
+
+impl Cookie {
+    /// Returns `true` if the cookie has expired at Unix time `now`.
+    pub fn is_expired(&self, now: u64) -> bool {
+        matches!(self.expires, Some(t) if t <= now)
+    }
+
+    /// Returns `true` if the cookie is kept across browser restarts.
+    pub fn is_persistent(&self) -> bool {
+        self.expires.is_some()
+    }
+
+    /// Returns `true` if this cookie should be sent to `host` + `path`.
+    fn matches(&self, host: &str, path: &str, is_secure: bool) -> bool {
+        let domain_ok = if self.host_only {
+            host == self.domain
+        } else {
+            domain_matches(host, &self.domain)
+        };
+        domain_ok && path_matches(path, &self.path) && (is_secure || !self.secure)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+// Cookie jar
+// ═══════════════════════════════════════════════════════════
+
+/// A collection of cookies shared by all pages of an application.
+#[derive(Clone)]
+pub struct CookieJar {
+    pub cookies: Vec<Cookie>,
+}
+
+impl CookieJar {
+    pub fn new() -> Self {
+        CookieJar { cookies: Vec::new() }
+    }
+
+    /// Load persistent cookies from `<profile_dir>/cookies.txt`.
+    ///
+    /// Returns an empty jar if the file does not exist.  Expired entries are
+    /// dropped while loading.
+    pub fn load(profile_dir: &str) -> Self {
+        let mut jar = CookieJar::new();
+        let path = cookie_path(profile_dir);
+        let contents = match anyos_std::fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(_) => return jar,
+        };
+        let now = now_secs();
+        for line in contents.lines() {
+            let f: Vec<&str> = line.split('\t').collect();
+            if f.len() != 8 { continue; }
+            let expires = match f[5].parse::<u64>() {
+                Ok(t) => t,
+                Err(_) => continue,
+            };
+            let cookie = Cookie {
+                domain: String::from(f[0]),
+                host_only: f[1] == "1",
+                path: String::from(f[2]),
+                secure: f[3] == "1",
+                http_only: f[4] == "1",
+                expires: Some(expires),
+                name: String::from(f[6]),
+                value: String::from(f[7]),
+            };
+            if !cookie.is_expired(now) {
+                jar.cookies.push(cookie);
+            }
+        }
+        jar
+    }
+
+    /// Write all persistent, unexpired cookies to `<profile_dir>/cookies.txt`.
+    /// Creates the profile directory if needed.
+    pub fn save(&self, profile_dir: &str) {
+        let now = now_secs();
+        let mut out = String::new();
+        for c in &self.cookies {
+            let expires = match c.expires {
+                Some(t) if t > now => t,
+                _ => continue,
+            };
+            out.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                c.domain,
+                c.host_only as u8,
+                c.path,
+                c.secure as u8,
+                c.http_only as u8,
+                expires,
+                c.name,
+                c.value,
+            ));
+        }
+        anyos_std::fs::mkdir(profile_dir);
+        let _ = anyos_std::fs::write_bytes(&cookie_path(profile_dir), out.as_bytes());
+    }
+
+    /// Parse and store cookies from the `Set-Cookie` lines of a raw header block.
+    pub fn store_from_headers(&mut self, headers: &str, request_host: &str, request_path: &str) {
+        for line in headers.split('\n') {
+            let line = line.trim_end_matches('\r');
+            if !starts_with_ignore_case(line, "set-cookie") { continue; }
+            let rest = &line["set-cookie".len()..];
+            if !rest.starts_with(':') { continue; }
+            let val = rest[1..].trim_start();
+            self.set_cookie(val, request_host, request_path, false);
+        }
+    }
+
+    /// Store a single cookie from a `Set-Cookie` value or a `document.cookie`
+    /// assignment.
+    ///
+    /// `from_script` must be `true` for `document.cookie` writes: scripts may
+    /// neither create `HttpOnly` cookies nor overwrite existing ones.
+    /// Returns `false` if the cookie was rejected.
+    pub fn set_cookie(&mut self, header: &str, request_host: &str, request_path: &str, from_script: bool) -> bool {
+        let now = now_secs();
+        let host = request_host.to_ascii_lowercase();
+
+        // Format: name=value; Path=/; Domain=.example.com; Max-Age=3600; Secure; HttpOnly
+        let mut parts = header.splitn(2, ';');
+        let name_value = match parts.next() {
+            Some(nv) => nv.trim(),
+            None => return false,
+        };
+        let (name, value) = match name_value.find('=') {
+            Some(eq) => (name_value[..eq].trim(), name_value[eq + 1..].trim()),
+            None => return false,
+        };
+        if name.is_empty() { return false; }
+
+        let mut domain: Option<String> = None;
+        let mut path: Option<String> = None;
+        let mut secure = false;
+        let mut http_only = false;
+        let mut max_age: Option<i64> = None;
+        let mut expires: Option<u64> = None;
+
+        if let Some(attrs) = parts.next() {
+            for attr in attrs.split(';') {
+                let attr = attr.trim();
+                let (key, val) = match attr.find('=') {
+                    Some(eq) => (attr[..eq].trim(), attr[eq + 1..].trim()),
+                    None => (attr, ""),
+                };
+                if eq_ignore_case(key, "domain") {
+                    let d = val.trim_start_matches('.');
+                    if !d.is_empty() {
+                        domain = Some(d.to_ascii_lowercase());
+                    }
+                } else if eq_ignore_case(key, "path") {
+                    if val.starts_with('/') {
+                        path = Some(String::from(val));
+                    }
+                } else if eq_ignore_case(key, "max-age") {
+                    if let Ok(n) = val.parse::<i64>() {
+                        max_age = Some(n);
+                    }
+                } else if eq_ignore_case(key, "expires") {
+                    expires = parse_http_date(val);
+                } else if eq_ignore_case(key, "secure") {
+                    secure = true;
+                } else if eq_ignore_case(key, "httponly") {
+                    http_only = true;
+                }
+            }
+        }
+
+        // A Domain attribute must cover the host that sets the cookie.
+        let host_only = domain.is_none();
+        let domain = match domain {
+            Some(d) => {
+                if !domain_matches(&host, &d) { return false; }
+                d
+            }
+            None => host,
+        };
+        let path = path.unwrap_or_else(|| default_path(request_path));
+
+        // Max-Age takes precedence over Expires.
+        let expires = match max_age {
+            Some(n) if n <= 0 => Some(0),
+            Some(n) => Some(now + n as u64),
+            None => expires,
+        };
+
+        if from_script {
+            if http_only { return false; }
+            let clobbers_http_only = self.cookies.iter().any(|c| {
+                c.http_only && c.name == name && c.domain == domain && c.path == path
+            });
+            if clobbers_http_only { return false; }
+        }
+
+        // Replace any existing cookie with the same name + domain + path.
+        self.cookies.retain(|c| !(c.name == name && c.domain == domain && c.path == path));
+
+        let cookie = Cookie {
+            domain,
+            path,
+            name: String::from(name),
+            value: String::from(value),
+            secure,
+            http_only,
+            host_only,
+            expires,
+        };
+        // An already-expired cookie is a deletion request.
+        if cookie.is_expired(now) {
+            return true;
+        }
+
+        if self.cookies.len() >= MAX_COOKIES {
+            self.cookies.remove(0);
+        }
+        self.cookies.push(cookie);
+        true
+    }
+
+    /// Build the `Cookie` request header value for `host` + `path`.
+    pub fn cookie_header(&self, host: &str, path: &str, is_secure: bool) -> Option<String> {
+        self.join_matching(host, path, is_secure, true)
+    }
+
+    /// Build the `document.cookie` string for a page — like `cookie_header`
+    /// but without `HttpOnly` cookies.
+    pub fn script_cookies(&self, host: &str, path: &str, is_secure: bool) -> String {
+        self.join_matching(host, path, is_secure, false).unwrap_or_default()
+    }
+
+    /// Cookies that would be sent to `host` (any path), for inspection.
+    pub fn cookies_for_host(&self, host: &str) -> Vec<&Cookie> {
+        let host = host.to_ascii_lowercase();
+        let now = now_secs();
+        self.cookies.iter()
+            .filter(|c| !c.is_expired(now))
+            .filter(|c| if c.host_only { host == c.domain } else { domain_matches(&host, &c.domain) })
+            .collect()
+    }
+
+    /// Remove a cookie by name, domain and path.  Returns `true` if one was removed.
+    pub fn remove(&mut self, name: &str, domain: &str, path: &str) -> bool {
+        let before = self.cookies.len();
+        self.cookies.retain(|c| !(c.name == name && c.domain == domain && c.path == path));
+        self.cookies.len() != before
+    }
+
+    /// Drop all expired cookies.
+    pub fn purge_expired(&mut self) {
+        let now = now_secs();
+        self.cookies.retain(|c| !c.is_expired(now));
+    }
+
+    /// Remove every cookie.
+    pub fn clear(&mut self) {
+        self.cookies.clear();
+    }
+
+    fn join_matching(&self, host: &str, path: &str, is_secure: bool, include_http_only: bool) -> Option<String> {
+        let host_lower = host.to_ascii_lowercase();
+        let now = now_secs();
+
+        // Longer paths first (RFC 6265 §5.4).
+        let mut matching: Vec<&Cookie> = self.cookies.iter()
+            .filter(|c| !c.is_expired(now))
+            .filter(|c| include_http_only || !c.http_only)
+            .filter(|c| c.matches(&host_lower, path, is_secure))
+            .collect();
+        if matching.is_empty() { return None; }
+        matching.sort_by(|a, b| b.path.len().cmp(&a.path.len()));
+
+        let mut s = String::new();
+        for (i, c) in matching.iter().enumerate() {
+            if i > 0 { s.push_str("; "); }
+            s.push_str(&c.name);
+            s.push('=');
+            s.push_str(&c.value);
+        }
+        Some(s)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════
+// Matching helpers
+// ═══════════════════════════════════════════════════════════
+
+/// Domain match: `host` equals `domain` or is a subdomain of it.
+pub fn domain_matches(host: &str, domain: &str) -> bool {
+    if host == domain { return true; }
+    if host.ends_with(domain) {
+        let prefix_len = host.len() - domain.len();
+        if prefix_len > 0 && host.as_bytes()[prefix_len - 1] == b'.' {
+            return true;
+        }
+    }
+    false
+}
+
+/// Path match (RFC 6265 §5.1.4).
+fn path_matches(request_path: &str, cookie_path: &str) -> bool {
+    if request_path == cookie_path { return true; }
+    request_path.starts_with(cookie_path)
+        && (cookie_path.ends_with('/') || request_path.as_bytes().get(cookie_path.len()) == Some(&b'/'))
+}
+
+/// Default cookie path: the request path up to (not including) its last `/`.
+fn default_path(request_path: &str) -> String {
+    if !request_path.starts_with('/') {
+        return String::from("/");
+    }
+    match request_path.rfind('/') {
+        Some(0) | None => String::from("/"),
+        Some(i) => String::from(&request_path[..i]),
+    }
+}
+
+fn cookie_path(profile_dir: &str) -> String {
+    format!("{}/{}", profile_dir.trim_end_matches('/'), COOKIE_FILE)
+}
+
+fn starts_with_ignore_case(s: &str, prefix: &str) -> bool {
+    s.len() >= prefix.len() && s.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
+}
+
+fn eq_ignore_case(a: &str, b: &str) -> bool {
+    a.eq_ignore_ascii_case(b)
+}
+
+// ═══════════════════════════════════════════════════════════
+// Time helpers
+// ═══════════════════════════════════════════════════════════
+
+/// Current wall-clock time as Unix seconds.
+pub fn now_secs() -> u64 {
+    let mut buf = [0u8; 8];
+    anyos_std::sys::time(&mut buf);
+    let year = buf[0] as i64 | ((buf[1] as i64) << 8);
+    unix_time(year, buf[2] as i64, buf[3] as i64, buf[4] as i64, buf[5] as i64, buf[6] as i64)
+}
+
+/// Convert a UTC civil date/time to Unix seconds.
+fn unix_time(year: i64, month: i64, day: i64, hour: i64, min: i64, sec: i64) -> u64 {
+    // Days from civil (Howard Hinnant's algorithm).
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = (if y >= 0 { y } else { y - 399 }) / 400;
+    let yoe = y - era * 400;
+    let m = month;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+    let secs = days * 86400 + hour * 3600 + min * 60 + sec;
+    secs.max(0) as u64
+}
+
+/// Parse an HTTP date (`Wed, 21 Oct 2015 07:28:00 GMT` and the common
+/// `21-Oct-2015` / two-digit-year variants) into Unix seconds.
+pub(crate) fn parse_http_date(s: &str) -> Option<u64> {
+    const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun",
+                                "jul", "aug", "sep", "oct", "nov", "dec"];
+    let mut day = None;
+    let mut month = None;
+    let mut year = None;
+    let mut time = None;
+
+    for tok in s.split(|c: char| c == ' ' || c == '-' || c == ',').filter(|t| !t.is_empty()) {
+        if time.is_none() && tok.contains(':') {
+            let mut it = tok.split(':').map(|p| p.parse::<i64>().ok());
+            if let (Some(Some(h)), Some(Some(m)), Some(Some(sec))) = (it.next(), it.next(), it.next()) {
+                time = Some((h, m, sec));
+            }
+        } else if month.is_none() && tok.len() >= 3 && tok.as_bytes()[0].is_ascii_alphabetic() {
+            let prefix = tok[..3].to_ascii_lowercase();
+            if let Some(i) = MONTHS.iter().position(|m| *m == prefix) {
+                month = Some(i as i64 + 1);
+            }
+        } else if let Ok(n) = tok.parse::<i64>() {
+            if day.is_none() && tok.len() <= 2 {
+                day = Some(n);
+            } else if year.is_none() {
+                year = Some(if tok.len() <= 2 { if n < 70 { 2000 + n } else { 1900 + n } } else { n });
+            }
+        }
+    }
+
+    let (h, m, sec) = time.unwrap_or((0, 0, 0));
+    let (day, month, year) = (day?, month?, year?);
+    if !(1..=31).contains(&day) || h > 23 || m > 59 || sec > 60 {
+        return None;
+    }
+    Some(unix_time(year, month, day, h, m, sec))
+}
@@ -18,6 +18,23 @@ pub struct Stylesheet {
     pub media_rules: Vec<MediaRule>,
     /// @keyframes blocks indexed by animation name.
     pub keyframes: Vec<KeyframeSet>,
+    /// @font-face blocks in source order.
+    pub font_faces: Vec<FontFace>,
+}
+
+/// A `@font-face { … }` block.
+#[derive(Clone)]
+pub struct FontFace {
+    /// Family name, lowercase and unquoted.
+    pub family: String,
+    /// `src` URLs as written, in order of preference.  Only sources the
+    /// font engine can read (TrueType / OpenType) are kept; WOFF, WOFF2,
+    /// SVG, EOT and `local()` sources are dropped.
+    pub sources: Vec<String>,
+    /// `font-weight` is 600 or more.
+    pub bold: bool,
+    /// `font-style` is italic or oblique.
+    pub italic: bool,
 }
 
 /// A complete `@keyframes name { … }` block.
@@ -142,6 +159,7 @@ pub enum Property {
     FontSize,
     FontWeight,
     FontStyle,
+    FontFamily,
     TextAlign,
     TextDecoration,
     TextIndent,
@@ -207,6 +225,7 @@ pub enum Property {
     // Visual
     Opacity,
     Visibility,
+    Transform,
     TextTransform,
     Cursor,
     // Table
@@ -244,6 +263,8 @@ pub enum Property {
     GridRowStart,
     GridRowEnd,
     GridArea,
+    /// `color-scheme` (read by `scheme`, not resolved per element).
+    ColorScheme,
     /// CSS custom property (--name). Value stored in Declaration.value as Keyword.
     CustomProperty(String),
 }
@@ -419,6 +440,7 @@ pub fn parse_stylesheet(css: &str) -> Stylesheet {
     let mut rules = Vec::new();
     let mut media_rules = Vec::new();
     let mut keyframes = Vec::new();
+    let mut font_faces = Vec::new();
 
     loop {
         p.skip_whitespace();
@@ -447,6 +469,13 @@ pub fn parse_stylesheet(css: &str) -> Stylesheet {
                 continue;
             }
 
+            if kw_lower == "font-face" {
+                if let Some(ff) = parse_font_face(&mut p) {
+                    font_faces.push(ff);
+                }
+                continue;
+            }
+
             // Skip other at-rules.
             loop {
                 p.skip_whitespace();
@@ -478,9 +507,9 @@ pub fn parse_stylesheet(css: &str) -> Stylesheet {
         }
     }
 
-    crate::debug_surf!("[css] parse_stylesheet done: {} rules, {} @media, {} @keyframes",
-        rules.len(), media_rules.len(), keyframes.len());
-    Stylesheet { rules, media_rules, keyframes }
+    crate::debug_surf!("[css] parse_stylesheet done: {} rules, {} @media, {} @keyframes, {} @font-face",
+        rules.len(), media_rules.len(), keyframes.len(), font_faces.len());
+    Stylesheet { rules, media_rules, keyframes, font_faces }
 }
 
 /// Parse a @media rule: query { rules }.
@@ -529,7 +558,7 @@ fn parse_media_rule(p: &mut Parser) -> Option<MediaRule> {
 }
 
 /// Parse a media query string like `screen and (max-width: 768px)`.
-fn parse_media_query(text: &str) -> MediaQuery {
+pub(crate) fn parse_media_query(text: &str) -> MediaQuery {
     let mut conditions = Vec::new();
     let trimmed = text.trim();
 
@@ -629,8 +658,9 @@ fn parse_px_value(s: &str) -> Option<i32> {
     if val > 0 || s == "0" { Some(val) } else { None }
 }
 
-/// Evaluate a media query against viewport dimensions.
-pub fn evaluate_media_query(query: &MediaQuery, viewport_width: i32, viewport_height: i32) -> bool {
+/// Evaluate a media query against viewport dimensions and the preferred
+/// color scheme.
+pub fn evaluate_media_query(query: &MediaQuery, viewport_width: i32, viewport_height: i32, prefers_dark: bool) -> bool {
     for cond in &query.conditions {
         let ok = match cond {
             MediaCondition::MinWidth(w) => viewport_width >= *w,
@@ -638,8 +668,7 @@ pub fn evaluate_media_query(query: &MediaQuery, viewport_width: i32, viewport_he
             MediaCondition::MinHeight(h) => viewport_height >= *h,
             MediaCondition::MaxHeight(h) => viewport_height <= *h,
             MediaCondition::PrefersColorScheme(scheme) => {
-                // anyOS uses dark theme.
-                scheme == "dark"
+                scheme.eq_ignore_ascii_case(if prefers_dark { "dark" } else { "light" })
             }
         };
         if !ok { return false; }
@@ -731,6 +760,127 @@ fn parse_keyframes(p: &mut Parser) -> Option<KeyframeSet> {
     Some(KeyframeSet { name, stops })
 }
 
+/// Parse a `@font-face { … }` block.  Descriptors are read raw (not through
+/// `parse_value`) so that `src` URLs keep their case.
+fn parse_font_face(p: &mut Parser) -> Option<FontFace> {
+    p.skip_whitespace();
+    if p.eof() || p.peek() != b'{' { return None; }
+    p.pos += 1; // consume '{'
+
+    let mut face = FontFace { family: String::new(), sources: Vec::new(), bold: false, italic: false };
+    loop {
+        p.skip_whitespace();
+        if p.eof() { break; }
+        if p.peek() == b'}' {
+            p.pos += 1;
+            break;
+        }
+        let name = p.read_ident().to_ascii_lowercase();
+        p.skip_whitespace();
+        if name.is_empty() || p.peek() != b':' {
+            while !p.eof() && p.peek() != b';' && p.peek() != b'}' { p.pos += 1; }
+            if p.peek() == b';' { p.pos += 1; }
+            continue;
+        }
+        p.pos += 1; // consume ':'
+        let value = read_value_str(p);
+        if p.peek() == b';' { p.pos += 1; }
+        let value = value.trim();
+        match name.as_str() {
+            "font-family" => {
+                if let Some(family) = parse_family_list(value).into_iter().next() {
+                    face.family = family;
+                }
+            }
+            "src" => face.sources = parse_font_sources(value),
+            "font-weight" => {
+                let lower = value.to_ascii_lowercase();
+                // A range (`100 900`) covers bold too; judge by its upper end.
+                let last = lower.split_whitespace().last().unwrap_or("");
+                face.bold = last == "bold" || last.parse::<u32>().map_or(false, |w| w >= 600);
+            }
+            "font-style" => {
+                let lower = value.to_ascii_lowercase();
+                face.italic = lower.starts_with("italic") || lower.starts_with("oblique");
+            }
+            _ => {}
+        }
+    }
+
+    if face.family.is_empty() || face.sources.is_empty() {
+        return None;
+    }
+    Some(face)
+}
+
+/// Usable URLs of an `@font-face` `src` list, e.g.
+/// `url(a.woff2) format("woff2"), url("a.ttf") format("truetype")`.
+fn parse_font_sources(value: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    for item in split_top_level_commas(value) {
+        let lower = item.to_ascii_lowercase();
+        let start = match lower.find("url(") {
+            Some(i) => i + 4,
+            None => continue, // local() — system fonts are not matched by name
+        };
+        let end = match item[start..].find(')') {
+            Some(i) => start + i,
+            None => continue,
+        };
+        let url = item[start..end].trim().trim_matches(|c| c == '"' || c == '\'');
+        if url.is_empty() || url.starts_with("data:") {
+            continue;
+        }
+        let usable = match lower.find("format(") {
+            Some(i) => {
+                let f = &lower[i + 7..];
+                f.contains("truetype") || f.contains("opentype")
+            }
+            None => {
+                let path = url.split(|c| c == '?' || c == '#').next().unwrap_or("").to_ascii_lowercase();
+                !(path.ends_with(".woff") || path.ends_with(".woff2")
+                    || path.ends_with(".eot") || path.ends_with(".svg"))
+            }
+        };
+        if usable {
+            out.push(String::from(url));
+        }
+    }
+    out
+}
+
+/// Split a `font-family` value into lowercase, unquoted family names.
+pub fn parse_family_list(value: &str) -> Vec<String> {
+    split_top_level_commas(value)
+        .into_iter()
+        .map(|f| f.trim().trim_matches(|c| c == '"' || c == '\'').trim().to_ascii_lowercase())
+        .filter(|f| !f.is_empty())
+        .collect()
+}
+
+/// Split on commas that are not inside quotes or parentheses.
+fn split_top_level_commas(value: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0u32;
+    let mut quote = 0u8;
+    let mut start = 0;
+    for (i, b) in value.bytes().enumerate() {
+        match b {
+            b'"' | b'\'' if quote == 0 => quote = b,
+            _ if b == quote => quote = 0,
+            b'(' if quote == 0 => depth += 1,
+            b')' if quote == 0 => depth = depth.saturating_sub(1),
+            b',' if quote == 0 && depth == 0 => {
+                parts.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
 /// Parse a `{ declaration; ... }` block and return the declarations.
 /// Expects the opening `{` to be the next character; consumes through the matching `}`.
 fn parse_declarations_block(p: &mut Parser) -> Vec<Declaration> {
@@ -1209,6 +1359,7 @@ pub fn parse_property(name: &str) -> Option<Property> {
         "font-size" => Some(Property::FontSize),
         "font-weight" => Some(Property::FontWeight),
         "font-style" => Some(Property::FontStyle),
+        "font-family" => Some(Property::FontFamily),
         "text-align" => Some(Property::TextAlign),
         "text-decoration" => Some(Property::TextDecoration),
         "text-indent" => Some(Property::TextIndent),
@@ -1277,6 +1428,7 @@ pub fn parse_property(name: &str) -> Option<Property> {
         // Visual
         "opacity" => Some(Property::Opacity),
         "visibility" => Some(Property::Visibility),
+        "transform" => Some(Property::Transform),
         "text-transform" => Some(Property::TextTransform),
         "cursor" => Some(Property::Cursor),
         "table-layout" => Some(Property::TableLayout),
@@ -1310,6 +1462,7 @@ pub fn parse_property(name: &str) -> Option<Property> {
         "grid-row-start"        => Some(Property::GridRowStart),
         "grid-row-end"          => Some(Property::GridRowEnd),
         "grid-area"             => Some(Property::GridArea),
+        "color-scheme"          => Some(Property::ColorScheme),
         _ => Option::None,
     }
 }
@@ -330,6 +330,19 @@ impl Dom {
         None
     }
 
+    /// Find the target of a URL fragment: the first element whose `id`
+    /// equals `fragment`, or failing that the first `<a name="fragment">`.
+    pub fn find_fragment_target(&self, fragment: &str) -> Option<NodeId> {
+        if fragment.is_empty() {
+            return None;
+        }
+        (0..self.nodes.len())
+            .find(|&i| self.attr(i, "id") == Some(fragment))
+            .or_else(|| (0..self.nodes.len()).find(|&i| {
+                self.tag(i) == Some(Tag::A) && self.attr(i, "name") == Some(fragment)
+            }))
+    }
+
     // -- mutation methods ---------------------------------------------------
 
     /// Set or add an attribute on an element node.
@@ -0,0 +1,225 @@
+//! Print / export — paginated page bitmaps and minimal PDF output.
+//!
+//! The document is laid out at the page width (see `WebView::paginate()`)
+//! and cut into page-height slices.  A cut never passes through a line of
+//! text or an image that fits on one page: the break is moved up to the top
+//! of the first such box it would split, unless that would leave the page
+//! less than half full (a box taller than that is split as-is).
+//!
+//! Each slice is rasterized with the same pixel walk as the on-screen tiles.
+//! `encode_pdf()` wraps the page bitmaps in a PDF with one full-page image
+//! per page, RunLength-compressed, at 96 px per inch (CSS pixels).
+
+use alloc::format;
+use alloc::vec::Vec;
+
+use crate::layout::LayoutBox;
+use crate::renderer::{self, ImageCache};
+
+/// One rendered page.
+pub struct PageBitmap {
+    /// ARGB pixels, `width × height`, row-major.
+    pub pixels: Vec<u32>,
+    pub width: u32,
+    pub height: u32,
+    /// Document Y coordinate the page starts at.
+    pub doc_y: i32,
+}
+
+/// Collect the absolute vertical extent of every box a page break must not
+/// cut through: text runs and images no taller than `max_h`.
+fn collect_unbreakable(bx: &LayoutBox, offset_y: i32, max_h: i32, out: &mut Vec<(i32, i32)>) {
+    if bx.visibility_hidden || bx.is_fixed {
+        return;
+    }
+    let abs_y = offset_y + bx.y;
+    let text = bx.text.as_ref().map_or(false, |t| !t.is_empty());
+    if (text || bx.image_src.is_some() || bx.media.is_some()) && bx.height > 0 && bx.height <= max_h {
+        out.push((abs_y, abs_y + bx.height));
+    }
+    for child in &bx.children {
+        collect_unbreakable(child, abs_y, max_h, out);
+    }
+}
+
+/// Split a document of height `doc_h` into pages of at most `page_h` rows.
+/// Returns `(doc_y, height)` per page.
+pub fn page_slices(root: &LayoutBox, doc_h: i32, page_h: i32) -> Vec<(i32, i32)> {
+    let page_h = page_h.max(1);
+    let mut boxes = Vec::new();
+    collect_unbreakable(root, 0, page_h, &mut boxes);
+
+    let mut slices = Vec::new();
+    let mut start = 0;
+    while start < doc_h {
+        let mut end = start + page_h;
+        if end >= doc_h {
+            slices.push((start, doc_h - start));
+            break;
+        }
+        // Pull the break up above any box it cuts; repeat, since the new
+        // position may cut a box beside it (columns, floats).
+        let min_end = start + page_h / 2;
+        loop {
+            let cut = boxes.iter()
+                .filter(|&&(top, bottom)| top < end && bottom > end && top >= min_end)
+                .map(|&(top, _)| top)
+                .min();
+            match cut {
+                Some(top) => end = top,
+                None => break,
+            }
+        }
+        slices.push((start, end - start));
+        start = end;
+    }
+    if slices.is_empty() {
+        slices.push((0, doc_h.max(1).min(page_h)));
+    }
+    slices
+}
+
+/// Rasterize `root` (laid out at `page_w`) into `page_w × page_h` pages.
+/// Rows below a page's slice are left in `bg_color`.
+pub fn render_pages(
+    root: &LayoutBox,
+    images: &ImageCache,
+    doc_h: i32,
+    page_w: u32,
+    page_h: u32,
+    bg_color: u32,
+) -> Vec<PageBitmap> {
+    let page_w = page_w.max(1);
+    let page_h = page_h.max(1);
+    page_slices(root, doc_h, page_h as i32)
+        .into_iter()
+        .map(|(doc_y, slice_h)| {
+            let mut pixels = renderer::rasterize_region(root, images, page_w, doc_y, page_h, bg_color);
+            // Boxes straddling the break belong to the next page.
+            let keep = (slice_h.max(0) as usize).min(page_h as usize) * page_w as usize;
+            for px in &mut pixels[keep..] {
+                *px = bg_color;
+            }
+            PageBitmap { pixels, width: page_w, height: page_h, doc_y }
+        })
+        .collect()
+}
+
+// ── PDF ─────────────────────────────────────────────────────────────────
+
+/// Format a pixel length as PDF points (72 / 96 of a pixel) with two decimals.
+fn pt(px: u32) -> alloc::string::String {
+    let q = px as u64 * 300;
+    format!("{}.{:02}", q / 400, q % 400 / 4)
+}
+
+/// PDF RunLength encoding: runs of 3–128 equal bytes become
+/// `(257 - n, byte)`, everything else literal chunks of up to 128 bytes.
+fn run_length(data: &[u8], out: &mut Vec<u8>) {
+    let mut i = 0;
+    let mut lit_start = 0;
+    while i < data.len() {
+        let b = data[i];
+        let mut run = 1;
+        while run < 128 && i + run < data.len() && data[i + run] == b {
+            run += 1;
+        }
+        if run >= 3 {
+            flush_literal(&data[lit_start..i], out);
+            out.push((257 - run) as u8);
+            out.push(b);
+            i += run;
+            lit_start = i;
+        } else {
+            i += run;
+        }
+    }
+    flush_literal(&data[lit_start..], out);
+    out.push(128); // EOD
+}
+
+fn flush_literal(mut lit: &[u8], out: &mut Vec<u8>) {
+    while !lit.is_empty() {
+        let n = lit.len().min(128);
+        out.push((n - 1) as u8);
+        out.extend_from_slice(&lit[..n]);
+        lit = &lit[n..];
+    }
+}
+
+/// Encode page bitmaps as a PDF document, one page per bitmap.
+///
+/// Pixels are composited onto white (PDF images have no alpha here).
+pub fn encode_pdf(pages: &[PageBitmap]) -> Vec<u8> {
+    let mut out: Vec<u8> = Vec::new();
+    // Object offsets, indexed by object number - 1.
+    let mut offsets: Vec<usize> = Vec::new();
+    // Object numbers: 1 catalog, 2 page tree, then (page, content, image)
+    // triples starting at 3.
+    let page_obj = |i: usize| 3 + i * 3;
+
+    out.extend_from_slice(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n");
+
+    offsets.push(out.len());
+    out.extend_from_slice(b"1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n");
+
+    offsets.push(out.len());
+    let mut kids = alloc::string::String::new();
+    for i in 0..pages.len() {
+        kids.push_str(&format!("{} 0 R ", page_obj(i)));
+    }
+    out.extend_from_slice(format!(
+        "2 0 obj\n<< /Type /Pages /Kids [{}] /Count {} >>\nendobj\n",
+        kids.trim_end(), pages.len(),
+    ).as_bytes());
+
+    let mut rgb = Vec::new();
+    let mut data = Vec::new();
+    for (i, page) in pages.iter().enumerate() {
+        let (w, h) = (pt(page.width), pt(page.height));
+        let n = page_obj(i);
+
+        offsets.push(out.len());
+        out.extend_from_slice(format!(
+            "{} 0 obj\n<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /XObject << /Im0 {} 0 R >> >> /Contents {} 0 R >>\nendobj\n",
+            n, w, h, n + 2, n + 1,
+        ).as_bytes());
+
+        let content = format!("q {} 0 0 {} 0 0 cm /Im0 Do Q\n", w, h);
+        offsets.push(out.len());
+        out.extend_from_slice(format!("{} 0 obj\n<< /Length {} >>\nstream\n", n + 1, content.len()).as_bytes());
+        out.extend_from_slice(content.as_bytes());
+        out.extend_from_slice(b"endstream\nendobj\n");
+
+        rgb.clear();
+        for &px in &page.pixels {
+            let a = px >> 24;
+            for shift in [16u32, 8, 0] {
+                let c = (px >> shift) & 0xFF;
+                rgb.push(((c * a + 255 * (255 - a)) / 255) as u8);
+            }
+        }
+        data.clear();
+        run_length(&rgb, &mut data);
+        offsets.push(out.len());
+        out.extend_from_slice(format!(
+            "{} 0 obj\n<< /Type /XObject /Subtype /Image /Width {} /Height {} \
+             /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /RunLengthDecode /Length {} >>\nstream\n",
+            n + 2, page.width, page.height, data.len(),
+        ).as_bytes());
+        out.extend_from_slice(&data);
+        out.extend_from_slice(b"\nendstream\nendobj\n");
+    }
+
+    let xref = out.len();
+    out.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1).as_bytes());
+    for off in &offsets {
+        out.extend_from_slice(format!("{:010} 00000 n \n", off).as_bytes());
+    }
+    out.extend_from_slice(format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        offsets.len() + 1, xref,
+    ).as_bytes());
+    out
+}
@@ -0,0 +1,324 @@
+//! Web fonts (`@font-face`) and `font-family` fallback chains.
+//!
+//! [`FontSet`] holds the `@font-face` faces of the current document.  The
+//! embedder fetches their files (`WebView::take_font_requests()`) and hands
+//! them back (`WebView::add_font()` / `WebView::font_failed()`); each file
+//! is registered with libfont once.  A face whose source fails moves on to
+//! its next `src` URL.
+//!
+//! After styles are resolved, [`FontSet::assign`] turns every element's
+//! `font-family` list into a chain of concrete fonts ([`TextFont`]) in
+//! `ComputedStyle::font_chain`.  Families that are not loaded (yet) are
+//! skipped, generic families map to the system fonts, and a family without
+//! the requested bold / italic face uses its closest face with the missing
+//! style synthesized.  Layout then picks, per text run, the first font of
+//! the chain that has glyphs for the whole run ([`pick`]).
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::css::{self, FontFace};
+use crate::style::{ComputedStyle, FontStyleVal, FontWeight};
+
+/// System font IDs (see libfont).
+const SYSTEM_REGULAR: u16 = 0;
+const SYSTEM_BOLD: u16 = 1;
+const SYSTEM_ITALIC: u16 = 3;
+const SYSTEM_MONO: u16 = 4;
+
+/// A concrete font a text run is drawn with.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct TextFont {
+    /// libfont font ID.
+    pub font_id: u16,
+    /// Draw twice, one pixel apart (the face has no bold variant).
+    pub synth_bold: bool,
+    /// Shear the glyphs (the face has no italic variant).
+    pub synth_italic: bool,
+}
+
+impl TextFont {
+    /// The system font for a style.  There is no bold-italic system face,
+    /// so bold italic is the bold face, slanted.
+    pub fn system(bold: bool, italic: bool) -> Self {
+        let font_id = if bold {
+            SYSTEM_BOLD
+        } else if italic {
+            SYSTEM_ITALIC
+        } else {
+            SYSTEM_REGULAR
+        };
+        TextFont { font_id, synth_bold: false, synth_italic: bold && italic }
+    }
+}
+
+/// First font of `chain` with glyphs for every non-space character of
+/// `text`, or `None` if the chain is empty (use the system font).  The
+/// last entry is a system font and always accepted.
+pub fn pick(chain: &[TextFont], text: &str) -> Option<TextFont> {
+    let (last, web) = chain.split_last()?;
+    for font in web {
+        let covered = text
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .all(|c| libfont_client::has_glyph(font.font_id as u32, c));
+        if covered {
+            return Some(*font);
+        }
+    }
+    Some(*last)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FaceState {
+    /// The current source has not been handed to the embedder yet.
+    Pending,
+    /// The current source is being fetched.
+    Requested,
+    Loaded(u16),
+    /// Every source failed.
+    Failed,
+}
+
+struct Face {
+    family: String,
+    bold: bool,
+    italic: bool,
+    /// Absolute source URLs, in order of preference.
+    sources: Vec<String>,
+    /// Index of the source being tried.
+    source: usize,
+    state: FaceState,
+    /// Declared in an inline `<style>` block (re-collected on every re-parse).
+    inline: bool,
+}
+
+impl Face {
+    fn url(&self) -> &str {
+        &self.sources[self.source]
+    }
+}
+
+/// The `@font-face` faces of one document.
+pub struct FontSet {
+    faces: Vec<Face>,
+    /// Resolved chains by (family list, bold, italic); cleared whenever a
+    /// face is added, loaded or removed.
+    chains: Vec<(String, bool, bool, Vec<TextFont>)>,
+}
+
+impl FontSet {
+    pub fn new() -> Self {
+        FontSet { faces: Vec::new(), chains: Vec::new() }
+    }
+
+    /// Register the faces of a stylesheet whose relative URLs resolve
+    /// against `base_url`.  Faces already known are ignored.
+    pub fn add_faces(&mut self, faces: &[FontFace], base_url: &str, inline: bool) {
+        for ff in faces {
+            let sources: Vec<String> = ff.sources.iter()
+                .map(|s| crate::js::resolve_url(base_url, s))
+                .collect();
+            let known = self.faces.iter().any(|f| {
+                f.family == ff.family && f.bold == ff.bold && f.italic == ff.italic && f.sources == sources
+            });
+            if known {
+                continue;
+            }
+            self.faces.push(Face {
+                family: ff.family.clone(),
+                bold: ff.bold,
+                italic: ff.italic,
+                sources,
+                source: 0,
+                state: FaceState::Pending,
+                inline,
+            });
+            self.chains.clear();
+        }
+    }
+
+    /// Drop the faces of inline `<style>` blocks (before re-parsing them).
+    pub fn clear_inline(&mut self) {
+        self.remove_where(|f| f.inline);
+    }
+
+    /// Drop every face and unload their fonts (new page).
+    pub fn clear(&mut self) {
+        self.remove_where(|_| true);
+    }
+
+    fn remove_where(&mut self, remove: impl Fn(&Face) -> bool) {
+        let mut unloaded: Vec<u16> = Vec::new();
+        for f in &self.faces {
+            if let FaceState::Loaded(id) = f.state {
+                if remove(f) && !unloaded.contains(&id) {
+                    unloaded.push(id);
+                }
+            }
+        }
+        let before = self.faces.len();
+        self.faces.retain(|f| !remove(f));
+        // A file shared with a surviving face stays loaded.
+        for id in unloaded {
+            if !self.faces.iter().any(|f| f.state == FaceState::Loaded(id)) {
+                libfont_client::unload(id as u32);
+            }
+        }
+        if self.faces.len() != before {
+            self.chains.clear();
+        }
+    }
+
+    /// URLs to fetch: the current source of every face not yet requested.
+    /// A source another face already loaded is reused without a fetch.
+    pub fn take_requests(&mut self) -> Vec<String> {
+        let mut urls: Vec<String> = Vec::new();
+        for i in 0..self.faces.len() {
+            if self.faces[i].state != FaceState::Pending {
+                continue;
+            }
+            let url = self.faces[i].url();
+            let loaded = self.faces.iter().find_map(|f| match f.state {
+                FaceState::Loaded(id) if f.url() == url => Some(id),
+                _ => None,
+            });
+            if let Some(id) = loaded {
+                self.faces[i].state = FaceState::Loaded(id);
+                self.chains.clear();
+                continue;
+            }
+            if !urls.iter().any(|u| u == url) {
+                urls.push(String::from(url));
+            }
+            self.faces[i].state = FaceState::Requested;
+        }
+        urls
+    }
+
+    /// Register the fetched file of `url`.  Returns `true` if a face became
+    /// usable (relayout) — if libfont cannot read the file it counts as a
+    /// failed source instead.
+    pub fn font_loaded(&mut self, url: &str, data: &[u8]) -> bool {
+        if !self.faces.iter().any(|f| f.state == FaceState::Requested && f.url() == url) {
+            return false;
+        }
+        let id = match libfont_client::load_memory(data) {
+            Some(id) => id as u16,
+            None => {
+                self.font_failed(url);
+                return false;
+            }
+        };
+        for f in &mut self.faces {
+            if f.state == FaceState::Requested && f.url() == url {
+                f.state = FaceState::Loaded(id);
+            }
+        }
+        self.chains.clear();
+        true
+    }
+
+    /// The fetch of `url` failed: move its faces to their next source.
+    /// Returns `true` if there are new sources to fetch.
+    pub fn font_failed(&mut self, url: &str) -> bool {
+        let mut retry = false;
+        for f in &mut self.faces {
+            if f.state == FaceState::Requested && f.url() == url {
+                if f.source + 1 < f.sources.len() {
+                    f.source += 1;
+                    f.state = FaceState::Pending;
+                    retry = true;
+                } else {
+                    f.state = FaceState::Failed;
+                }
+            }
+        }
+        retry
+    }
+
+    /// Fill `font_chain` of every style from its `font_family`.
+    pub fn assign(&mut self, styles: &mut [ComputedStyle]) {
+        for style in styles.iter_mut() {
+            if style.font_family.is_empty() {
+                style.font_chain.clear();
+                continue;
+            }
+            let bold = matches!(style.font_weight, FontWeight::Bold);
+            let italic = matches!(style.font_style, FontStyleVal::Italic);
+            let idx = match self.chains.iter().position(|(fam, b, i, _)| {
+                *b == bold && *i == italic && *fam == style.font_family
+            }) {
+                Some(idx) => idx,
+                None => {
+                    let chain = self.build_chain(&style.font_family, bold, italic);
+                    self.chains.push((style.font_family.clone(), bold, italic, chain));
+                    self.chains.len() - 1
+                }
+            };
+            let chain = &self.chains[idx].3;
+            if style.font_chain != *chain {
+                style.font_chain = chain.clone();
+            }
+        }
+    }
+
+    fn build_chain(&self, family_list: &str, bold: bool, italic: bool) -> Vec<TextFont> {
+        let mut chain = Vec::new();
+        let mut end = TextFont::system(bold, italic);
+        for family in css::parse_family_list(family_list) {
+            if let Some(generic) = generic_font(&family, bold, italic) {
+                end = generic;
+                break;
+            }
+            if let Some(font) = self.face_font(&family, bold, italic) {
+                chain.push(font);
+            }
+        }
+        // Only the system font: leave the chain empty (layout's default).
+        if chain.is_empty() && end == TextFont::system(bold, italic) {
+            return chain;
+        }
+        chain.push(end);
+        chain
+    }
+
+    /// Closest loaded face of `family`: matching style first, then
+    /// matching weight; whatever is missing is synthesized.
+    fn face_font(&self, family: &str, bold: bool, italic: bool) -> Option<TextFont> {
+        let mut best: Option<(&Face, u16, u32)> = None;
+        for f in &self.faces {
+            let id = match f.state {
+                FaceState::Loaded(id) if f.family == family => id,
+                _ => continue,
+            };
+            // Never use a bold/italic face for regular text when there is a
+            // better match; prefer the right style over the right weight.
+            let score = ((f.italic == italic) as u32) * 2 + (f.bold == bold) as u32;
+            if best.map_or(true, |(_, _, s)| score > s) {
+                best = Some((f, id, score));
+            }
+        }
+        best.map(|(f, id, _)| TextFont {
+            font_id: id,
+            synth_bold: bold && !f.bold,
+            synth_italic: italic && !f.italic,
+        })
+    }
+}
+
+/// System font for a generic family name (`None` for other names).
+fn generic_font(family: &str, bold: bool, italic: bool) -> Option<TextFont> {
+    match family {
+        "monospace" | "ui-monospace" => Some(TextFont {
+            font_id: SYSTEM_MONO,
+            synth_bold: bold,
+            synth_italic: italic,
+        }),
+        "sans-serif" | "serif" | "system-ui" | "ui-sans-serif" | "ui-serif" | "ui-rounded"
+        | "cursive" | "fantasy" | "math" | "emoji" | "-apple-system" | "blinkmacsystemfont" => {
+            Some(TextFont::system(bold, italic))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,704 @@
+//! HTML form semantics: form ownership, `<select>` options, constraint
+//! validation, form-data serialization and request body encoding.
+//!
+//! The renderer owns the native controls; the `WebView` reads their live
+//! state into `FieldState` records and hands them here together with the
+//! DOM, so everything in this module is independent of libanyui.  Files
+//! for `<input type="file">` come from the host's [`FilePicker`] and are
+//! kept by the `WebView` per input.
+
+pub mod pattern;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::dom::{Dom, NodeId, Tag};
+use crate::layout::FormFieldKind;
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+/// Live state of one rendered form control.
+pub struct FieldState {
+    pub node_id: NodeId,
+    pub kind: FormFieldKind,
+    /// Current text (text inputs, passwords, textareas); empty otherwise.
+    pub value: String,
+    /// Checked flag (checkbox / radio), selected option index (select) or
+    /// number of chosen files (file input).
+    pub state: u32,
+}
+
+/// A file chosen for an `<input type="file">`.
+#[derive(Clone)]
+pub struct PickedFile {
+    /// File name without directory (the multipart `filename`).
+    pub name: String,
+    /// MIME type; empty is sent as `application/octet-stream`.
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Host file chooser for `<input type="file">`, installed with
+/// `WebView::set_file_picker()`.  Called on the UI thread when the user
+/// activates a file input.
+pub trait FilePicker {
+    /// Let the user choose files.  `accept` is the input's `accept`
+    /// attribute (may be empty); `multiple` allows more than one file.
+    /// Returns `None` if the user cancelled.
+    fn pick(&mut self, accept: &str, multiple: bool) -> Option<Vec<PickedFile>>;
+}
+
+/// One value of a form data set.
+pub enum FormValue<'a> {
+    Text(String),
+    /// A chosen file, or `None` for a file input without a selection.
+    File(Option<&'a PickedFile>),
+}
+
+/// Request body encoding of a form (`enctype` / `formenctype`).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Enctype {
+    /// `application/x-www-form-urlencoded` (the default).
+    UrlEncoded,
+    /// `multipart/form-data` — required to upload file contents.
+    Multipart,
+    /// `text/plain`.
+    TextPlain,
+}
+
+impl Enctype {
+    /// MIME type, without the multipart boundary parameter.
+    pub fn mime(&self) -> &'static str {
+        match self {
+            Enctype::UrlEncoded => "application/x-www-form-urlencoded",
+            Enctype::Multipart => "multipart/form-data",
+            Enctype::TextPlain => "text/plain",
+        }
+    }
+}
+
+/// Why a field fails constraint validation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Validity {
+    /// `required` field is empty / unchecked / has no selection.
+    ValueMissing,
+    /// Value does not match the `pattern` attribute.
+    PatternMismatch,
+    /// Numeric value is below `min`.
+    RangeUnderflow,
+    /// Numeric value is above `max`.
+    RangeOverflow,
+}
+
+impl Validity {
+    /// User-facing message, as shown in the browser's validation bubble.
+    pub fn message(&self) -> &'static str {
+        match self {
+            Validity::ValueMissing => "Please fill out this field.",
+            Validity::PatternMismatch => "Please match the requested format.",
+            Validity::RangeUnderflow => "Value is below the allowed minimum.",
+            Validity::RangeOverflow => "Value is above the allowed maximum.",
+        }
+    }
+}
+
+/// A field that failed validation.
+pub struct InvalidField {
+    pub node_id: NodeId,
+    pub name: String,
+    pub reason: Validity,
+}
+
+/// A validated, encoded form ready to be sent by the host.
+pub struct FormSubmission {
+    /// `action` URL resolved against the page URL (the page itself when
+    /// the form has no action).
+    pub action: String,
+    /// `"GET"` or `"POST"`.
+    pub method: String,
+    /// Name/value pairs in tree order; file inputs contribute file names.
+    pub fields: Vec<(String, String)>,
+    /// URL to load: `action`, with the form data as its query for GET.
+    pub url: String,
+    /// Body encoding; always `UrlEncoded` for GET.
+    pub enctype: Enctype,
+    /// `Content-Type` header of `body`, including the multipart boundary.
+    /// Empty for GET.
+    pub content_type: String,
+    /// Request body; empty for GET.
+    pub body: Vec<u8>,
+}
+
+/// Why a form could not be submitted.
+pub enum FormError {
+    /// The submitter is not associated with any `<form>`.
+    NoForm,
+    /// Constraint validation failed (fields in tree order).
+    Invalid(Vec<InvalidField>),
+}
+
+// ---------------------------------------------------------------------------
+// Form ownership
+// ---------------------------------------------------------------------------
+
+/// The `<form>` a control belongs to: the element named by its `form`
+/// attribute, otherwise the nearest ancestor `<form>`.
+pub fn form_owner(dom: &Dom, node: NodeId) -> Option<NodeId> {
+    if let Some(form_id) = dom.attr(node, "form") {
+        return (0..dom.nodes.len()).find(|&id| {
+            dom.tag(id) == Some(Tag::Form) && dom.attr(id, "id") == Some(form_id)
+        });
+    }
+    let mut cur = dom.get(node).parent;
+    while let Some(id) = cur {
+        if dom.tag(id) == Some(Tag::Form) {
+            return Some(id);
+        }
+        cur = dom.get(id).parent;
+    }
+    None
+}
+
+/// True if the control or an enclosing `<fieldset>` is `disabled`.
+pub fn is_disabled(dom: &Dom, node: NodeId) -> bool {
+    if dom.attr(node, "disabled").is_some() {
+        return true;
+    }
+    let mut cur = dom.get(node).parent;
+    while let Some(id) = cur {
+        if dom.tag(id) == Some(Tag::Fieldset) && dom.attr(id, "disabled").is_some() {
+            return true;
+        }
+        cur = dom.get(id).parent;
+    }
+    false
+}
+
+/// Lower-cased `type` attribute of an `<input>` (default `"text"`).
+pub fn input_type(dom: &Dom, node: NodeId) -> String {
+    dom.attr(node, "type").unwrap_or("text").to_ascii_lowercase()
+}
+
+/// `(action, method)` for submitting `form` via `submitter`, honouring the
+/// submitter's `formaction` / `formmethod` overrides.  The method is
+/// normalised to `"GET"` or `"POST"`.
+pub fn submission_target(dom: &Dom, form: NodeId, submitter: Option<NodeId>) -> (String, String) {
+    let over = |name: &str| submitter.and_then(|s| dom.attr(s, name));
+    let action = over("formaction").or_else(|| dom.attr(form, "action")).unwrap_or("");
+    let method = over("formmethod").or_else(|| dom.attr(form, "method")).unwrap_or("get");
+    let method = if method.eq_ignore_ascii_case("post") { "POST" } else { "GET" };
+    (String::from(action), String::from(method))
+}
+
+/// Body encoding for submitting `form` via `submitter`, honouring the
+/// submitter's `formenctype` override.  Unknown values mean `UrlEncoded`.
+pub fn enctype(dom: &Dom, form: NodeId, submitter: Option<NodeId>) -> Enctype {
+    let value = submitter.and_then(|s| dom.attr(s, "formenctype"))
+        .or_else(|| dom.attr(form, "enctype"))
+        .unwrap_or("");
+    match value.trim() {
+        v if v.eq_ignore_ascii_case("multipart/form-data") => Enctype::Multipart,
+        v if v.eq_ignore_ascii_case("text/plain") => Enctype::TextPlain,
+        _ => Enctype::UrlEncoded,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// <select> options
+// ---------------------------------------------------------------------------
+
+/// All `<option>` elements of a `<select>` (including those inside
+/// `<optgroup>`), in tree order.
+pub fn select_options(dom: &Dom, select: NodeId) -> Vec<NodeId> {
+    let mut out = Vec::new();
+    for &child in &dom.get(select).children {
+        match dom.tag(child) {
+            Some(Tag::Option) => out.push(child),
+            Some(Tag::Optgroup) => {
+                for &opt in &dom.get(child).children {
+                    if dom.tag(opt) == Some(Tag::Option) {
+                        out.push(opt);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Display label of an `<option>`: its `label` attribute, otherwise its
+/// text with whitespace collapsed.
+pub fn option_label(dom: &Dom, option: NodeId) -> String {
+    if let Some(label) = dom.attr(option, "label") {
+        return String::from(label);
+    }
+    let text = dom.text_content(option);
+    let mut out = String::new();
+    for word in text.split_whitespace() {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    out
+}
+
+/// Submitted value of an `<option>`: its `value` attribute, otherwise its label.
+pub fn option_value(dom: &Dom, option: NodeId) -> String {
+    match dom.attr(option, "value") {
+        Some(v) => String::from(v),
+        None => option_label(dom, option),
+    }
+}
+
+/// Index of the initially selected option: the last one marked
+/// `selected`, otherwise the first.
+pub fn initial_selection(dom: &Dom, options: &[NodeId]) -> u32 {
+    options.iter().rposition(|&o| dom.attr(o, "selected").is_some()).unwrap_or(0) as u32
+}
+
+/// Initial value of a `<textarea>`: its text, minus one leading newline.
+pub fn textarea_default(dom: &Dom, node: NodeId) -> String {
+    let text = dom.text_content(node);
+    match text.strip_prefix("\r\n").or_else(|| text.strip_prefix('\n')) {
+        Some(rest) => String::from(rest),
+        None => text,
+    }
+}
+
+/// Number of lines in a textarea value (a trailing newline starts a new line).
+pub fn line_count(text: &str) -> usize {
+    text.split('\n').count()
+}
+
+// ---------------------------------------------------------------------------
+// Values
+// ---------------------------------------------------------------------------
+
+/// The value a field contributes to the form data set, or `None` if it
+/// contributes nothing (unchecked box, select without options).
+pub fn field_value(dom: &Dom, field: &FieldState) -> Option<String> {
+    match field.kind {
+        FormFieldKind::Checkbox | FormFieldKind::Radio => {
+            if field.state == 0 {
+                return None;
+            }
+            Some(String::from(dom.attr(field.node_id, "value").unwrap_or("on")))
+        }
+        FormFieldKind::Select => {
+            let options = select_options(dom, field.node_id);
+            options.get(field.state as usize).map(|&o| option_value(dom, o))
+        }
+        FormFieldKind::Hidden => Some(String::from(dom.attr(field.node_id, "value").unwrap_or(""))),
+        FormFieldKind::TextInput | FormFieldKind::Password | FormFieldKind::Textarea => {
+            Some(field.value.clone())
+        }
+        // File inputs contribute their `PickedFile`s; see `form_data_set()`.
+        FormFieldKind::Submit | FormFieldKind::ButtonEl | FormFieldKind::File => None,
+    }
+}
+
+/// Fields of `form` that take part in submission, in tree order.
+fn owned_fields<'a>(dom: &'a Dom, form: NodeId, fields: &'a [FieldState]) -> impl Iterator<Item = &'a FieldState> {
+    fields.iter().filter(move |f| form_owner(dom, f.node_id) == Some(form) && !is_disabled(dom, f.node_id))
+}
+
+// ---------------------------------------------------------------------------
+// Constraint validation
+// ---------------------------------------------------------------------------
+
+/// True if submitting `form` via `submitter` skips validation
+/// (`novalidate` on the form or `formnovalidate` on the submitter).
+pub fn skips_validation(dom: &Dom, form: NodeId, submitter: Option<NodeId>) -> bool {
+    dom.attr(form, "novalidate").is_some()
+        || submitter.map_or(false, |s| dom.attr(s, "formnovalidate").is_some())
+}
+
+/// Run constraint validation over the fields of `form`.  Returns the
+/// failing fields in tree order (empty if the form is valid).
+pub fn validate(dom: &Dom, form: NodeId, fields: &[FieldState]) -> Vec<InvalidField> {
+    let mut invalid = Vec::new();
+    let mut radio_groups: Vec<&str> = Vec::new();
+
+    for field in owned_fields(dom, form, fields) {
+        let node = field.node_id;
+        if dom.attr(node, "readonly").is_some() && field.kind != FormFieldKind::Select {
+            continue;
+        }
+        let name = dom.attr(node, "name").unwrap_or("");
+        let required = dom.attr(node, "required").is_some();
+
+        let reason = match field.kind {
+            FormFieldKind::Radio => {
+                // A radio group is missing a value if any member is required
+                // and none is checked; report it once, at its first member.
+                if name.is_empty() || radio_groups.contains(&name) {
+                    None
+                } else {
+                    radio_groups.push(name);
+                    let group = owned_fields(dom, form, fields).filter(|f| {
+                        f.kind == FormFieldKind::Radio && dom.attr(f.node_id, "name") == Some(name)
+                    });
+                    let mut any_required = false;
+                    let mut any_checked = false;
+                    for f in group {
+                        any_required |= dom.attr(f.node_id, "required").is_some();
+                        any_checked |= f.state != 0;
+                    }
+                    if any_required && !any_checked { Some(Validity::ValueMissing) } else { None }
+                }
+            }
+            FormFieldKind::Checkbox | FormFieldKind::File => {
+                if required && field.state == 0 { Some(Validity::ValueMissing) } else { None }
+            }
+            FormFieldKind::Select => {
+                let empty = field_value(dom, field).map_or(true, |v| v.is_empty());
+                if required && empty { Some(Validity::ValueMissing) } else { None }
+            }
+            FormFieldKind::TextInput | FormFieldKind::Password | FormFieldKind::Textarea => {
+                validate_text(dom, node, field.kind, &field.value, required)
+            }
+            _ => None,
+        };
+
+        if let Some(reason) = reason {
+            invalid.push(InvalidField { node_id: node, name: String::from(name), reason });
+        }
+    }
+    invalid
+}
+
+fn validate_text(dom: &Dom, node: NodeId, kind: FormFieldKind, value: &str, required: bool) -> Option<Validity> {
+    if value.is_empty() {
+        return if required { Some(Validity::ValueMissing) } else { None };
+    }
+    if kind == FormFieldKind::Textarea {
+        return None;
+    }
+    if let Some(re) = dom.attr(node, "pattern").and_then(pattern::Regex::new) {
+        if !re.full_match(value) {
+            return Some(Validity::PatternMismatch);
+        }
+    }
+    let ty = input_type(dom, node);
+    if ty == "number" || ty == "range" {
+        if let Ok(v) = value.trim().parse::<f64>() {
+            let bound = |name: &str| dom.attr(node, name).and_then(|s| s.trim().parse::<f64>().ok());
+            if bound("min").map_or(false, |min| v < min) {
+                return Some(Validity::RangeUnderflow);
+            }
+            if bound("max").map_or(false, |max| v > max) {
+                return Some(Validity::RangeOverflow);
+            }
+        }
+    }
+    None
+}
+
+// ---------------------------------------------------------------------------
+// Serialization
+// ---------------------------------------------------------------------------
+
+/// Build the form data set of `form`: name/value pairs of its enabled,
+/// named fields in tree order, followed by the submitter's own name/value.
+/// A file input contributes one entry per chosen file in `files`, or a
+/// single empty file entry when nothing was chosen.
+pub fn form_data_set<'a>(
+    dom: &Dom,
+    form: NodeId,
+    fields: &[FieldState],
+    files: &'a [(NodeId, Vec<PickedFile>)],
+    submitter: Option<NodeId>,
+) -> Vec<(String, FormValue<'a>)> {
+    let mut data = Vec::new();
+    let mut submitter_done = submitter.is_none();
+
+    for field in owned_fields(dom, form, fields) {
+        // The submitter goes in its tree position.
+        if let Some(s) = submitter {
+            if !submitter_done && s < field.node_id {
+                push_submitter(dom, s, &mut data);
+                submitter_done = true;
+            }
+        }
+        let name = match dom.attr(field.node_id, "name") {
+            Some(n) if !n.is_empty() => n,
+            _ => continue,
+        };
+        if field.kind == FormFieldKind::File {
+            let chosen = files.iter().find(|(id, _)| *id == field.node_id).map(|(_, f)| &f[..]);
+            match chosen {
+                Some(list) if !list.is_empty() => {
+                    for f in list {
+                        data.push((String::from(name), FormValue::File(Some(f))));
+                    }
+                }
+                _ => data.push((String::from(name), FormValue::File(None))),
+            }
+        } else if let Some(value) = field_value(dom, field) {
+            data.push((String::from(name), FormValue::Text(value)));
+        }
+    }
+    if let (false, Some(s)) = (submitter_done, submitter) {
+        push_submitter(dom, s, &mut data);
+    }
+    data
+}
+
+/// `form_data_set()` as name/value text pairs; files become their names.
+pub fn serialize(
+    dom: &Dom,
+    form: NodeId,
+    fields: &[FieldState],
+    files: &[(NodeId, Vec<PickedFile>)],
+    submitter: Option<NodeId>,
+) -> Vec<(String, String)> {
+    form_data_set(dom, form, fields, files, submitter)
+        .into_iter()
+        .map(|(name, value)| (name, text_value(value)))
+        .collect()
+}
+
+fn text_value(value: FormValue) -> String {
+    match value {
+        FormValue::Text(t) => t,
+        FormValue::File(f) => f.map(|f| f.name.clone()).unwrap_or_default(),
+    }
+}
+
+fn push_submitter(dom: &Dom, submitter: NodeId, data: &mut Vec<(String, FormValue)>) {
+    if is_disabled(dom, submitter) {
+        return;
+    }
+    let name = match dom.attr(submitter, "name") {
+        Some(n) if !n.is_empty() => n,
+        _ => return,
+    };
+    let default = if dom.tag(submitter) == Some(Tag::Input) { "Submit" } else { "" };
+    let value = dom.attr(submitter, "value").unwrap_or(default);
+    data.push((String::from(name), FormValue::Text(String::from(value))));
+}
+
+// ---------------------------------------------------------------------------
+// Submission
+// ---------------------------------------------------------------------------
+
+/// Serialize and encode `form` for submission via `submitter`, resolving
+/// the action against `page_url`.  Does not validate.
+pub fn build_submission(
+    dom: &Dom,
+    form: NodeId,
+    fields: &[FieldState],
+    files: &[(NodeId, Vec<PickedFile>)],
+    submitter: Option<NodeId>,
+    page_url: &str,
+) -> FormSubmission {
+    let (action, method) = submission_target(dom, form, submitter);
+    let action = if action.trim().is_empty() {
+        String::from(page_url)
+    } else {
+        crate::js::resolve_url(page_url, action.trim())
+    };
+    let data = form_data_set(dom, form, fields, files, submitter);
+
+    let (url, enctype, content_type, body) = if method == "GET" {
+        (with_query(&action, &urlencode(&data)), Enctype::UrlEncoded, String::new(), Vec::new())
+    } else {
+        let enctype = enctype(dom, form, submitter);
+        match enctype {
+            Enctype::UrlEncoded => {
+                let body = urlencode(&data).into_bytes();
+                (action.clone(), enctype, String::from(enctype.mime()), body)
+            }
+            Enctype::TextPlain => {
+                let body = text_plain(&data).into_bytes();
+                (action.clone(), enctype, String::from("text/plain; charset=UTF-8"), body)
+            }
+            Enctype::Multipart => {
+                let boundary = multipart_boundary(&data);
+                let body = multipart(&data, &boundary);
+                let content_type = format!("{}; boundary={}", enctype.mime(), boundary);
+                (action.clone(), enctype, content_type, body)
+            }
+        }
+    };
+
+    let fields = data.into_iter().map(|(name, value)| (name, text_value(value))).collect();
+    FormSubmission { action, method, fields, url, enctype, content_type, body }
+}
+
+/// `url` with its query replaced by `query` (the fragment is kept).
+fn with_query(url: &str, query: &str) -> String {
+    let (base, fragment) = match url.find('#') {
+        Some(i) => (&url[..i], &url[i..]),
+        None => (url, ""),
+    };
+    let base = base.split('?').next().unwrap_or(base);
+    let mut out = String::from(base);
+    out.push('?');
+    out.push_str(query);
+    out.push_str(fragment);
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Encoding
+// ---------------------------------------------------------------------------
+
+/// `application/x-www-form-urlencoded` serialization.  Line breaks are
+/// normalised to CRLF, spaces become `+`.
+pub fn urlencode(data: &[(String, FormValue)]) -> String {
+    let mut out = String::new();
+    for (name, value) in data {
+        if !out.is_empty() {
+            out.push('&');
+        }
+        percent_encode_into(&mut out, &normalize_newlines(name));
+        out.push('=');
+        let value = match value {
+            FormValue::Text(t) => normalize_newlines(t),
+            FormValue::File(f) => f.map(|f| f.name.clone()).unwrap_or_default(),
+        };
+        percent_encode_into(&mut out, &value);
+    }
+    out
+}
+
+/// `text/plain` serialization: one `name=value` line per entry.
+pub fn text_plain(data: &[(String, FormValue)]) -> String {
+    let mut out = String::new();
+    for (name, value) in data {
+        out.push_str(name);
+        out.push('=');
+        match value {
+            FormValue::Text(t) => out.push_str(t),
+            FormValue::File(f) => out.push_str(f.map_or("", |f| f.name.as_str())),
+        }
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// `multipart/form-data` serialization with the given boundary (see
+/// [`multipart_boundary`]).
+pub fn multipart(data: &[(String, FormValue)], boundary: &str) -> Vec<u8> {
+    let mut out: Vec<u8> = Vec::new();
+    for (name, value) in data {
+        out.extend_from_slice(b"--");
+        out.extend_from_slice(boundary.as_bytes());
+        out.extend_from_slice(b"\r\nContent-Disposition: form-data; name=\"");
+        out.extend_from_slice(escape_part_name(name).as_bytes());
+        out.push(b'"');
+        match value {
+            FormValue::Text(t) => {
+                out.extend_from_slice(b"\r\n\r\n");
+                out.extend_from_slice(normalize_newlines(t).as_bytes());
+            }
+            FormValue::File(f) => {
+                let (file_name, content_type, bytes) = match f {
+                    Some(f) => (f.name.as_str(), f.content_type.as_str(), &f.data[..]),
+                    None => ("", "", &[][..]),
+                };
+                let content_type = if content_type.is_empty() { "application/octet-stream" } else { content_type };
+                out.extend_from_slice(b"; filename=\"");
+                out.extend_from_slice(escape_part_name(file_name).as_bytes());
+                out.extend_from_slice(b"\"\r\nContent-Type: ");
+                out.extend_from_slice(content_type.as_bytes());
+                out.extend_from_slice(b"\r\n\r\n");
+                out.extend_from_slice(bytes);
+            }
+        }
+        out.extend_from_slice(b"\r\n");
+    }
+    out.extend_from_slice(b"--");
+    out.extend_from_slice(boundary.as_bytes());
+    out.extend_from_slice(b"--\r\n");
+    out
+}
+
+/// A multipart boundary that occurs in none of the entries of `data`.
+pub fn multipart_boundary(data: &[(String, FormValue)]) -> String {
+    // Seed from the content so the boundary needs no entropy source.
+    let mut seed: u32 = 0x811C_9DC5;
+    for (name, value) in data {
+        let bytes: &[u8] = match value {
+            FormValue::Text(t) => t.as_bytes(),
+            FormValue::File(f) => f.map_or(&[][..], |f| &f.data[..]),
+        };
+        for &b in name.as_bytes().iter().chain(bytes.iter().take(4096)) {
+            seed = (seed ^ b as u32).wrapping_mul(0x0100_0193);
+        }
+    }
+    loop {
+        let boundary = format!("----anyOSFormBoundary{:08x}", seed);
+        let clash = data.iter().any(|(name, value)| {
+            contains(name.as_bytes(), boundary.as_bytes()) || match value {
+                FormValue::Text(t) => contains(t.as_bytes(), boundary.as_bytes()),
+                FormValue::File(f) => f.map_or(false, |f| contains(&f.data, boundary.as_bytes())),
+            }
+        });
+        if !clash {
+            return boundary;
+        }
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+/// Quote-safe multipart name / filename: `"` and line breaks are
+/// percent-encoded.
+fn escape_part_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '"' => out.push_str("%22"),
+            '\r' => out.push_str("%0D"),
+            '\n' => out.push_str("%0A"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Convert lone CR and LF to CRLF.
+fn normalize_newlines(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' => {
+                if chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                out.push_str("\r\n");
+            }
+            '\n' => out.push_str("\r\n"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Percent-encode `s` for `application/x-www-form-urlencoded`: ASCII
+/// alphanumerics and `*-._` stay, spaces become `+`.
+fn percent_encode_into(out: &mut String, s: &str) {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => out.push(b as char),
+            b' ' => out.push('+'),
+            _ => {
+                out.push('%');
+                out.push(HEX[(b >> 4) as usize] as char);
+                out.push(HEX[(b & 0xF) as usize] as char);
+            }
+        }
+    }
+}
@@ -197,7 +197,8 @@ fn collect_text(bytes: &[u8], pos: &mut usize) -> String {
 }
 
 /// Read raw text content for `<script>` or `<style>` — stops at `</tag_name>`.
-fn collect_raw_text(bytes: &[u8], pos: &mut usize, tag_name: &str) -> String {
+/// Returns the text and whether the closing tag was found.
+fn collect_raw_text(bytes: &[u8], pos: &mut usize, tag_name: &str) -> (String, bool) {
     let mut out = String::new();
     let end_tag = {
         let mut e = String::from("</");
@@ -228,7 +229,7 @@ fn collect_raw_text(bytes: &[u8], pos: &mut usize, tag_name: &str) -> String {
                 if *pos < bytes.len() {
                     *pos += 1; // skip '>'
                 }
-                return out;
+                return (out, true);
             }
         }
         // Advance past the full UTF-8 character (1-4 bytes) so multi-byte
@@ -241,7 +242,25 @@ fn collect_raw_text(bytes: &[u8], pos: &mut usize, tag_name: &str) -> String {
         }
         *pos = end;
     }
-    out
+    (out, false)
+}
+
+/// Check whether the tag starting at `pos` (just after `<`) is closed by a
+/// `>` within `bytes`, skipping quoted attribute values.
+fn has_tag_end(bytes: &[u8], pos: usize) -> bool {
+    let mut quote = 0u8;
+    for &b in &bytes[pos..] {
+        if quote != 0 {
+            if b == quote {
+                quote = 0;
+            }
+        } else if b == b'"' || b == b'\'' {
+            quote = b;
+        } else if b == b'>' {
+            return true;
+        }
+    }
+    false
 }
 
 fn skip_whitespace(bytes: &[u8], pos: &mut usize) {
@@ -394,15 +413,30 @@ fn read_attr_value(bytes: &[u8], pos: &mut usize) -> String {
 }
 
 pub fn tokenize(html: &str) -> Vec<Token> {
-    crate::debug_surf!("[html] tokenize: {} bytes input", html.len());
-    let bytes = html.as_bytes();
+    tokenize_bytes(html.as_bytes(), false).0
+}
+
+/// Tokenize `bytes`.  Returns the tokens and the number of bytes consumed.
+///
+/// With `partial` set, the input is a prefix of a document still being
+/// received: a construct that runs into the end of the buffer (unclosed tag,
+/// comment or raw-text element, or trailing text that may continue) is left
+/// unconsumed so it can be re-tokenized once more data has arrived.
+fn tokenize_bytes(bytes: &[u8], partial: bool) -> (Vec<Token>, usize) {
+    crate::debug_surf!("[html] tokenize: {} bytes input", bytes.len());
     let mut pos: usize = 0;
     let mut tokens = Vec::new();
 
     while pos < bytes.len() {
+        let start = pos;
+        let token_count = tokens.len();
+
         if bytes[pos] == b'<' {
             pos += 1; // skip '<'
             if pos >= bytes.len() {
+                if partial {
+                    pos = start;
+                }
                 break;
             }
 
@@ -414,6 +448,7 @@ pub fn tokenize(html: &str) -> Vec<Token> {
             {
                 pos += 3;
                 // Scan for -->
+                let mut closed = false;
                 loop {
                     if pos + 2 >= bytes.len() {
                         pos = bytes.len(); // unterminated comment — consume rest
@@ -421,14 +456,25 @@ pub fn tokenize(html: &str) -> Vec<Token> {
                     }
                     if bytes[pos] == b'-' && bytes[pos + 1] == b'-' && bytes[pos + 2] == b'>' {
                         pos += 3;
+                        closed = true;
                         break;
                     }
                     pos += 1;
                 }
+                if partial && !closed {
+                    pos = start;
+                    break;
+                }
                 tokens.push(Token::Comment);
                 continue;
             }
 
+            // Every other construct ends at '>'; wait for it when streaming.
+            if partial && !has_tag_end(bytes, pos) {
+                pos = start;
+                break;
+            }
+
             // Doctype or other <!...> declaration
             if bytes[pos] == b'!' {
                 pos += 1; // skip '!'
@@ -490,7 +536,12 @@ pub fn tokenize(html: &str) -> Vec<Token> {
             });
 
             if is_raw && !self_closing {
-                let raw = collect_raw_text(bytes, &mut pos, &name);
+                let (raw, closed) = collect_raw_text(bytes, &mut pos, &name);
+                if partial && !closed {
+                    tokens.truncate(token_count);
+                    pos = start;
+                    break;
+                }
                 if !raw.is_empty() {
                     tokens.push(Token::Text(raw));
                 }
@@ -499,6 +550,10 @@ pub fn tokenize(html: &str) -> Vec<Token> {
         } else {
             // Text content
             let text = collect_text(bytes, &mut pos);
+            if partial && pos >= bytes.len() {
+                pos = start;
+                break;
+            }
             if !text.is_empty() {
                 tokens.push(Token::Text(text));
             }
@@ -506,7 +561,7 @@ pub fn tokenize(html: &str) -> Vec<Token> {
     }
 
     crate::debug_surf!("[html] tokenize done: {} tokens", tokens.len());
-    tokens
+    (tokens, pos)
 }
 
 // ---------------------------------------------------------------------------
@@ -592,67 +647,69 @@ fn in_pre(dom: &Dom, stack: &[NodeId]) -> bool {
     stack_has(dom, stack, Tag::Pre)
 }
 
-pub fn parse(html: &str) -> Dom {
-    let tokens = tokenize(html);
-    crate::debug_surf!("[html] tree build start: {} tokens", tokens.len());
-    #[cfg(feature = "debug_surf")]
-    crate::debug_surf!("[html]   RSP=0x{:X} heap=0x{:X}", crate::debug_rsp(), crate::debug_heap_pos());
-    let mut dom = Dom::new();
-
-    // Create implicit root (html-like)
-    let root = dom.add_node(
-        NodeType::Element {
-            tag: Tag::Html,
-            attrs: Vec::new(),
-        },
-        None,
-    );
-
-    let mut stack: Vec<NodeId> = Vec::new();
-    stack.push(root);
-
-    // Track whether we've seen explicit structural tags
-    let mut saw_head = false;
-    let mut saw_body = false;
-    let mut head_id: Option<NodeId> = None;
-    let mut body_id: Option<NodeId> = None;
+/// Incremental tree builder: turns a token stream into a `Dom`.
+///
+/// Shared by `parse()` (whole document) and `StreamParser` (document
+/// delivered in chunks).
+struct TreeBuilder {
+    dom: Dom,
+    root: NodeId,
+    /// Open element stack; the last entry is the insertion point.
+    stack: Vec<NodeId>,
+    head_id: Option<NodeId>,
+    body_id: Option<NodeId>,
+}
 
-    // First pass: check for explicit html/head/body
-    for tok in &tokens {
-        if let Token::StartTag { name, .. } = tok {
-            match name.as_str() {
-                "head" => saw_head = true,
-                "body" => saw_body = true,
-                _ => {}
-            }
-        }
-    }
+impl TreeBuilder {
+    /// Create the implicit root plus `<head>` / `<body>` for whichever of
+    /// them the document does not contain explicitly.
+    fn new(saw_head: bool, saw_body: bool) -> Self {
+        let mut dom = Dom::new();
 
-    // If no explicit structure, create implicit <head> and <body>
-    if !saw_head {
-        head_id = Some(dom.add_node(
-            NodeType::Element {
-                tag: Tag::Head,
-                attrs: Vec::new(),
-            },
-            Some(root),
-        ));
-    }
-    if !saw_body {
-        body_id = Some(dom.add_node(
+        // Create implicit root (html-like)
+        let root = dom.add_node(
             NodeType::Element {
-                tag: Tag::Body,
+                tag: Tag::Html,
                 attrs: Vec::new(),
             },
-            Some(root),
-        ));
-        // Push body as default insertion point when no explicit body
-        if let Some(bid) = body_id {
-            stack.push(bid);
+            None,
+        );
+
+        let mut stack: Vec<NodeId> = Vec::new();
+        stack.push(root);
+
+        let mut head_id: Option<NodeId> = None;
+        let mut body_id: Option<NodeId> = None;
+
+        // If no explicit structure, create implicit <head> and <body>
+        if !saw_head {
+            head_id = Some(dom.add_node(
+                NodeType::Element {
+                    tag: Tag::Head,
+                    attrs: Vec::new(),
+                },
+                Some(root),
+            ));
         }
+        if !saw_body {
+            body_id = Some(dom.add_node(
+                NodeType::Element {
+                    tag: Tag::Body,
+                    attrs: Vec::new(),
+                },
+                Some(root),
+            ));
+            // Push body as default insertion point when no explicit body
+            if let Some(bid) = body_id {
+                stack.push(bid);
+            }
+        }
+
+        TreeBuilder { dom, root, stack, head_id, body_id }
     }
 
-    for tok in tokens {
+    /// Insert one token into the tree.
+    fn process(&mut self, tok: Token) {
         match tok {
             Token::Doctype | Token::Comment => {
                 // Skip
@@ -674,59 +731,69 @@ pub fn parse(html: &str) -> Dom {
                     Tag::Html => {
                         // Merge attrs onto root if desired; otherwise skip creating duplicate
                         if dom_attrs.is_empty() {
-                            continue;
+                            return;
                         }
                         // Apply attrs to root node
                         if let NodeType::Element { ref mut attrs, .. } =
-                            dom.nodes[root].node_type
+                            self.dom.nodes[self.root].node_type
                         {
                             *attrs = dom_attrs;
                         }
-                        continue;
+                        return;
                     }
                     Tag::Head => {
-                        if head_id.is_none() {
-                            let hid = dom.add_node(
+                        if self.head_id.is_none() {
+                            let hid = self.dom.add_node(
                                 NodeType::Element {
                                     tag: Tag::Head,
                                     attrs: dom_attrs,
                                 },
-                                Some(root),
+                                Some(self.root),
                             );
-                            head_id = Some(hid);
-                            stack.push(hid);
+                            self.head_id = Some(hid);
+                            self.stack.push(hid);
                         } else {
-                            stack.push(head_id.unwrap());
+                            self.stack.push(self.head_id.unwrap());
                         }
-                        continue;
+                        return;
                     }
                     Tag::Body => {
-                        if body_id.is_none() {
-                            let bid = dom.add_node(
+                        if self.body_id.is_none() {
+                            let bid = self.dom.add_node(
                                 NodeType::Element {
                                     tag: Tag::Body,
                                     attrs: dom_attrs,
                                 },
-                                Some(root),
+                                Some(self.root),
                             );
-                            body_id = Some(bid);
+                            self.body_id = Some(bid);
                             // Replace implicit body in stack
-                            stack.retain(|&id| node_tag(&dom, id) != Some(Tag::Head));
-                            stack.push(bid);
+                            self.stack.retain(|&id| node_tag(&self.dom, id) != Some(Tag::Head));
+                            self.stack.push(bid);
                         } else {
+                            // A streamed document has an implicit <body> before the
+                            // real one arrives — give it the tag's attributes.
+                            let bid = self.body_id.unwrap();
+                            if let NodeType::Element { ref mut attrs, .. } = self.dom.nodes[bid].node_type {
+                                for a in dom_attrs {
+                                    if !attrs.iter().any(|e| e.name == a.name) {
+                                        attrs.push(a);
+                                    }
+                                }
+                            }
                             // Pop back to body level
-                            while stack.len() > 1 {
-                                if node_tag(&dom, *stack.last().unwrap()) == Some(Tag::Body) {
+                            while self.stack.len() > 1 {
+                                if node_tag(&self.dom, *self.stack.last().unwrap()) == Some(Tag::Body) {
                                     break;
                                 }
-                                stack.pop();
+                                self.stack.pop();
                             }
-                            if stack.last().map(|&id| node_tag(&dom, id)) != Some(Some(Tag::Body))
+                            if self.stack.last().map(|&id| node_tag(&self.dom, id)) != Some(Some(Tag::Body))
                             {
-                                stack.push(body_id.unwrap());
+                                self.stack.push(self.body_id.unwrap());
                             }
                         }
-                        continue;
+                        return;
                     }
                     _ => {}
                 }
@@ -734,61 +801,61 @@ pub fn parse(html: &str) -> Dom {
                 // Head-only elements go into <head>
                 let is_head_element =
                     matches!(tag, Tag::Title | Tag::Meta | Tag::Link | Tag::Style)
-                        && !stack_has(&dom, &stack, Tag::Body);
+                        && !stack_has(&self.dom, &self.stack, Tag::Body);
                 if is_head_element {
-                    if let Some(hid) = head_id {
+                    if let Some(hid) = self.head_id {
                         let parent = hid;
-                        let id = dom.add_node(
+                        let id = self.dom.add_node(
                             NodeType::Element {
                                 tag,
                                 attrs: dom_attrs,
                             },
                             Some(parent),
                         );
-                        if !tag.is_void() && !self_closing && stack.len() < 256 {
-                            stack.push(id);
+                        if !tag.is_void() && !self_closing && self.stack.len() < 256 {
+                            self.stack.push(id);
                         }
-                        continue;
+                        return;
                     }
                 }
 
                 // Auto-close <p> when block element opens inside it
-                if closes_p(tag) && stack_has(&dom, &stack, Tag::P) {
-                    pop_to(&dom, &mut stack, Tag::P);
+                if closes_p(tag) && stack_has(&self.dom, &self.stack, Tag::P) {
+                    pop_to(&self.dom, &mut self.stack, Tag::P);
                 }
 
                 // Auto-close <li> when another <li> opens
                 if tag == Tag::Li {
-                    if let Some(&top) = stack.last() {
-                        if node_tag(&dom, top) == Some(Tag::Li) {
-                            stack.pop();
+                    if let Some(&top) = self.stack.last() {
+                        if node_tag(&self.dom, top) == Some(Tag::Li) {
+                            self.stack.pop();
                         }
                     }
                 }
 
                 // Auto-close <td>/<th> when another opens
                 if tag == Tag::Td || tag == Tag::Th {
-                    if let Some(&top) = stack.last() {
-                        let top_tag = node_tag(&dom, top);
+                    if let Some(&top) = self.stack.last() {
+                        let top_tag = node_tag(&self.dom, top);
                         if top_tag == Some(Tag::Td) || top_tag == Some(Tag::Th) {
-                            stack.pop();
+                            self.stack.pop();
                         }
                     }
                 }
 
                 // Auto-close <tr> when another <tr> opens
                 if tag == Tag::Tr {
-                    if let Some(&top) = stack.last() {
-                        if node_tag(&dom, top) == Some(Tag::Tr) {
-                            stack.pop();
+                    if let Some(&top) = self.stack.last() {
+                        if node_tag(&self.dom, top) == Some(Tag::Tr) {
+                            self.stack.pop();
                         }
                     }
                 }
 
                 // Determine parent
-                let parent = stack.last().copied().unwrap_or(root);
+                let parent = self.stack.last().copied().unwrap_or(self.root);
 
-                let id = dom.add_node(
+                let id = self.dom.add_node(
                     NodeType::Element {
                         tag,
                         attrs: dom_attrs,
@@ -799,8 +866,8 @@ pub fn parse(html: &str) -> Dom {
                 // Push to stack unless void or self-closing.
                 // Cap nesting depth at 256 to prevent stack overflow in
                 // downstream recursive layout/rendering passes.
-                if !tag.is_void() && !self_closing && stack.len() < 256 {
-                    stack.push(id);
+                if !tag.is_void() && !self_closing && self.stack.len() < 256 {
+                    self.stack.push(id);
                 }
             }
 
@@ -810,69 +877,158 @@ pub fn parse(html: &str) -> Dom {
                 match tag {
                     Tag::Html | Tag::Body => {
                         // Don't actually pop these — they stay until the end
-                        continue;
+                        return;
                     }
                     Tag::Head => {
                         // Pop head and ensure body is on stack
-                        if stack_has(&dom, &stack, Tag::Head) {
-                            pop_to(&dom, &mut stack, Tag::Head);
+                        if stack_has(&self.dom, &self.stack, Tag::Head) {
+                            pop_to(&self.dom, &mut self.stack, Tag::Head);
                         }
                         // Ensure body is insertion point
-                        if let Some(bid) = body_id {
-                            if stack.last().copied() != Some(bid) {
-                                stack.push(bid);
+                        if let Some(bid) = self.body_id {
+                            if self.stack.last().copied() != Some(bid) {
+                                self.stack.push(bid);
                             }
                         }
-                        continue;
+                        return;
                     }
                     _ => {}
                 }
 
                 // Pop stack to matching open tag
-                if stack_has(&dom, &stack, tag) {
-                    pop_to(&dom, &mut stack, tag);
+                if stack_has(&self.dom, &self.stack, tag) {
+                    pop_to(&self.dom, &mut self.stack, tag);
                 }
                 // If not found, just ignore the end tag (error recovery)
             }
 
             Token::Text(text) => {
                 if text.is_empty() {
-                    continue;
+                    return;
                 }
 
-                let processed = if in_pre(&dom, &stack) {
+                let processed = if in_pre(&self.dom, &self.stack) {
                     text
                 } else {
                     collapse_whitespace(&text)
                 };
 
                 if processed.is_empty() {
-                    continue;
+                    return;
                 }
 
-                let parent = stack.last().copied().unwrap_or(root);
-                dom.add_node(NodeType::Text(processed), Some(parent));
+                let parent = self.stack.last().copied().unwrap_or(self.root);
+                self.dom.add_node(NodeType::Text(processed), Some(parent));
             }
         }
     }
 
+    /// Finish building and return the DOM.
+    fn finish(self) -> Dom {
+        #[cfg(feature = "debug_surf")]
+        {
+            let max_depth = self.dom.nodes.iter().map(|n| {
+                let mut depth = 0u32;
+                let mut cur = n.parent;
+                while let Some(pid) = cur {
+                    depth += 1;
+                    cur = self.dom.nodes.get(pid).and_then(|p| p.parent);
+                    if depth > 500 { break; } // safety
+                }
+                depth
+            }).max().unwrap_or(0);
+            crate::debug_surf!("[html] tree build done: {} nodes, max_depth={}", self.dom.nodes.len(), max_depth);
+            crate::debug_surf!("[html]   RSP=0x{:X} heap=0x{:X}", crate::debug_rsp(), crate::debug_heap_pos());
+        }
+
+        self.dom
+    }
+}
+
+pub fn parse(html: &str) -> Dom {
+    let tokens = tokenize(html);
+    crate::debug_surf!("[html] tree build start: {} tokens", tokens.len());
     #[cfg(feature = "debug_surf")]
-    {
-        let max_depth = dom.nodes.iter().map(|n| {
-            let mut depth = 0u32;
-            let mut cur = n.parent;
-            while let Some(pid) = cur {
-                depth += 1;
-                cur = dom.nodes.get(pid).and_then(|p| p.parent);
-                if depth > 500 { break; } // safety
+    crate::debug_surf!("[html]   RSP=0x{:X} heap=0x{:X}", crate::debug_rsp(), crate::debug_heap_pos());
+
+    // Track whether we've seen explicit structural tags
+    let mut saw_head = false;
+    let mut saw_body = false;
+
+    // First pass: check for explicit html/head/body
+    for tok in &tokens {
+        if let Token::StartTag { name, .. } = tok {
+            match name.as_str() {
+                "head" => saw_head = true,
+                "body" => saw_body = true,
+                _ => {}
             }
-            depth
-        }).max().unwrap_or(0);
-        crate::debug_surf!("[html] tree build done: {} nodes, max_depth={}", dom.nodes.len(), max_depth);
-        crate::debug_surf!("[html]   RSP=0x{:X} heap=0x{:X}", crate::debug_rsp(), crate::debug_heap_pos());
+        }
     }
 
-    dom
+    let mut builder = TreeBuilder::new(saw_head, saw_body);
+    for tok in tokens {
+        builder.process(tok);
+    }
+    builder.finish()
+}
+
+/// Streaming HTML parser: builds the DOM as the document arrives in chunks.
+///
+/// Bytes must be UTF-8 (transcode other charsets before feeding).  Only
+/// complete tokens are consumed; an unfinished tag, comment, raw-text
+/// element or trailing text run stays buffered until the next chunk or
+/// `finish()`.  `<head>` and `<body>` are created up front since the parser
+/// cannot look ahead for explicit ones; explicit tags reuse them.
+pub struct StreamParser {
+    /// Received bytes not yet turned into tokens.
+    buf: Vec<u8>,
+    builder: TreeBuilder,
+}
+
+impl StreamParser {
+    pub fn new() -> Self {
+        StreamParser {
+            buf: Vec::new(),
+            builder: TreeBuilder::new(false, false),
+        }
+    }
+
+    /// Append a chunk of the document and parse all complete tokens.
+    /// Returns the number of DOM nodes added.
+    pub fn feed_bytes(&mut self, data: &[u8]) -> usize {
+        let before = self.builder.dom.nodes.len();
+        self.buf.extend_from_slice(data);
+
+        let (tokens, consumed) = tokenize_bytes(&self.buf, true);
+        for tok in tokens {
+            self.builder.process(tok);
+        }
+        self.buf.drain(..consumed);
+
+        self.builder.dom.nodes.len() - before
+    }
+
+    /// The DOM built so far.
+    pub fn dom(&self) -> &Dom {
+        &self.builder.dom
+    }
+
+    /// Mutable access to the DOM built so far.  Nodes may be edited in
+    /// place; open elements must stay in the tree (detached ones keep
+    /// receiving their children, invisibly).
+    pub fn dom_mut(&mut self) -> &mut Dom {
+        &mut self.builder.dom
+    }
+
+    /// End of input: parse whatever is still buffered and return the DOM.
+    pub fn finish(mut self) -> Dom {
+        let (tokens, _) = tokenize_bytes(&self.buf, false);
+        for tok in tokens {
+            self.builder.process(tok);
+        }
+        self.builder.finish()
+    }
 }
 
 /// Parse an HTML fragment (for innerHTML). No implicit html/head/body wrapping.
@@ -0,0 +1,464 @@
+//! Inspector support — the structured view of a page that devtools-style
+//! element inspectors are built on.
+//!
+//! - [`InspectNode`]s flatten the DOM in document order with node ids,
+//!   attributes and the layout rectangles of every box the node generated.
+//! - [`computed_style`] lists a node's resolved style as CSS property/value
+//!   text.
+//! - [`node_at`] maps a document point to the deepest node painted there.
+//! - [`highlight_rects`] turns a node's boxes into the margin / border /
+//!   padding / content overlay drawn by `WebView::highlight_node()`.
+//! - [`edit_style_attr`] rewrites one property of a `style="..."` attribute,
+//!   which is how `WebView::set_node_style()` edits styles live.
+//!
+//! All rectangles are in absolute document coordinates; transforms are not
+//! applied.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::dom::{Dom, NodeId, NodeType};
+use crate::layout::{Edges, LayoutBox};
+use crate::renderer::Highlight;
+use crate::style::{
+    AlignItems, BoxSizing, ClearVal, ComputedStyle, Display, FlexDirection, FlexWrap, FloatVal,
+    FontStyleVal, FontWeight, JustifyContent, ListStyle, OverflowVal, Position, TextAlignVal,
+    TextDeco, TextTransform, Visibility, WhiteSpace,
+};
+
+/// Text nodes longer than this are cut in [`InspectNode::text`].
+const MAX_TEXT_PREVIEW: usize = 200;
+
+/// Overlay colours (translucent, like common browser devtools).
+const MARGIN_COLOR: u32 = 0x66F6B26B;
+const BORDER_COLOR: u32 = 0x66FFDD88;
+const PADDING_COLOR: u32 = 0x6693C47D;
+const CONTENT_COLOR: u32 = 0x666FA8DC;
+
+/// One layout box generated by a node.
+#[derive(Clone, Copy)]
+pub struct NodeRect {
+    /// Border box in document coordinates.
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub margin: Edges,
+    pub padding: Edges,
+    pub border: i32,
+}
+
+impl NodeRect {
+    /// Content box `(x, y, w, h)` inside border and padding.
+    pub fn content(&self) -> (i32, i32, i32, i32) {
+        let b = self.border;
+        (
+            self.x + b + self.padding.left,
+            self.y + b + self.padding.top,
+            (self.width - self.padding.left - self.padding.right - b * 2).max(0),
+            (self.height - self.padding.top - self.padding.bottom - b * 2).max(0),
+        )
+    }
+}
+
+/// A DOM node as seen by the inspector.
+pub struct InspectNode {
+    pub id: NodeId,
+    pub parent: Option<NodeId>,
+    /// Nesting depth (the root is 0).
+    pub depth: u32,
+    /// Lowercase tag name, or `#text`.
+    pub name: String,
+    pub attrs: Vec<(String, String)>,
+    /// Text content of a text node (cut after `MAX_TEXT_PREVIEW` bytes).
+    pub text: Option<String>,
+    /// Boxes the node generated; empty for `display: none` and nodes not
+    /// laid out (e.g. `<head>`).
+    pub rects: Vec<NodeRect>,
+}
+
+/// Flatten the DOM in document order.
+pub fn tree(dom: &Dom, root: Option<&LayoutBox>) -> Vec<InspectNode> {
+    let mut out = Vec::with_capacity(dom.nodes.len());
+    let mut stack: Vec<(NodeId, u32)> = dom.nodes.iter().enumerate()
+        .filter(|(_, n)| n.parent.is_none())
+        .map(|(id, _)| (id, 0))
+        .rev()
+        .collect();
+    while let Some((id, depth)) = stack.pop() {
+        let node = &dom.nodes[id];
+        let (name, attrs, text) = match &node.node_type {
+            NodeType::Element { tag, attrs } => (
+                tag.tag_name().to_ascii_lowercase(),
+                attrs.iter().map(|a| (a.name.clone(), a.value.clone())).collect(),
+                None,
+            ),
+            NodeType::Text(t) => {
+                let mut end = t.len().min(MAX_TEXT_PREVIEW);
+                while !t.is_char_boundary(end) {
+                    end -= 1;
+                }
+                (String::from("#text"), Vec::new(), Some(String::from(&t[..end])))
+            }
+        };
+        let rects = match (root, &node.node_type) {
+            (Some(r), NodeType::Element { .. }) => node_rects(r, id),
+            _ => Vec::new(),
+        };
+        out.push(InspectNode { id, parent: node.parent, depth, name, attrs, text, rects });
+        for &c in node.children.iter().rev() {
+            stack.push((c, depth + 1));
+        }
+    }
+    out
+}
+
+/// Boxes generated by `node`, in document order.
+pub fn node_rects(root: &LayoutBox, node: NodeId) -> Vec<NodeRect> {
+    let mut out = Vec::new();
+    collect_rects(root, node, 0, 0, &mut out);
+    out
+}
+
+fn collect_rects(bx: &LayoutBox, node: NodeId, offset_x: i32, offset_y: i32, out: &mut Vec<NodeRect>) {
+    let abs_x = if bx.is_fixed { bx.x } else { offset_x + bx.x };
+    let abs_y = if bx.is_fixed { bx.y } else { offset_y + bx.y };
+    if bx.node_id == Some(node) {
+        out.push(NodeRect {
+            x: abs_x,
+            y: abs_y,
+            width: bx.width,
+            height: bx.height,
+            margin: bx.margin,
+            padding: bx.padding,
+            border: bx.border_width,
+        });
+    }
+    for child in &bx.children {
+        collect_rects(child, node, abs_x, abs_y, out);
+    }
+}
+
+/// The deepest node whose box contains the document point `(x, y)`.
+/// Later siblings win over earlier ones, as they paint on top.
+pub fn node_at(root: &LayoutBox, x: i32, y: i32) -> Option<NodeId> {
+    find_at(root, x, y, 0, 0)
+}
+
+fn find_at(bx: &LayoutBox, x: i32, y: i32, offset_x: i32, offset_y: i32) -> Option<NodeId> {
+    let abs_x = if bx.is_fixed { bx.x } else { offset_x + bx.x };
+    let abs_y = if bx.is_fixed { bx.y } else { offset_y + bx.y };
+    // Children may overflow their parent, so search them first.
+    for child in bx.children.iter().rev() {
+        if child.visibility_hidden {
+            continue;
+        }
+        if let Some(id) = find_at(child, x, y, abs_x, abs_y) {
+            return Some(id);
+        }
+    }
+    let inside = x >= abs_x && x < abs_x + bx.width && y >= abs_y && y < abs_y + bx.height;
+    if inside { bx.node_id } else { None }
+}
+
+/// Overlay rectangles for one box: margin, border and padding rings around
+/// the content box.
+pub fn highlight_rects(r: &NodeRect, out: &mut Vec<Highlight>) {
+    let m = r.margin;
+    let margin_box = (r.x - m.left, r.y - m.top, r.width + m.left + m.right, r.height + m.top + m.bottom);
+    let border_box = (r.x, r.y, r.width, r.height);
+    let b = r.border;
+    let padding_box = (r.x + b, r.y + b, (r.width - b * 2).max(0), (r.height - b * 2).max(0));
+    let content_box = r.content();
+    ring(margin_box, border_box, MARGIN_COLOR, out);
+    ring(border_box, padding_box, BORDER_COLOR, out);
+    ring(padding_box, content_box, PADDING_COLOR, out);
+    let (x, y, w, h) = content_box;
+    if w > 0 && h > 0 {
+        out.push(Highlight { x, y, w, h, color: CONTENT_COLOR });
+    }
+}
+
+/// The area of `outer` not covered by `inner`, as up to four rectangles.
+fn ring(outer: (i32, i32, i32, i32), inner: (i32, i32, i32, i32), color: u32, out: &mut Vec<Highlight>) {
+    let (ox, oy, ow, oh) = outer;
+    let (ix, iy, iw, ih) = inner;
+    let mut push = |x: i32, y: i32, w: i32, h: i32| {
+        if w > 0 && h > 0 {
+            out.push(Highlight { x, y, w, h, color });
+        }
+    };
+    push(ox, oy, ow, iy - oy);
+    push(ox, iy + ih, ow, oy + oh - (iy + ih));
+    push(ox, iy, ix - ox, ih);
+    push(ix + iw, iy, ox + ow - (ix + iw), ih);
+}
+
+/// A node's resolved style as `(property, value)` pairs in CSS syntax.
+pub fn computed_style(s: &ComputedStyle) -> Vec<(&'static str, String)> {
+    let px = |v: i32| format!("{}px", v);
+    let opt_px = |v: Option<i32>| v.map_or(String::from("auto"), |v| format!("{}px", v));
+    let mut out: Vec<(&'static str, String)> = Vec::new();
+    out.push(("display", String::from(display_name(s.display))));
+    out.push(("position", String::from(position_name(s.position))));
+    out.push(("top", opt_px(s.top)));
+    out.push(("right", opt_px(s.right_offset)));
+    out.push(("bottom", opt_px(s.bottom_offset)));
+    out.push(("left", opt_px(s.left_offset)));
+    out.push(("z-index", format!("{}", s.z_index)));
+    out.push(("float", String::from(match s.float {
+        FloatVal::None => "none", FloatVal::Left => "left", FloatVal::Right => "right",
+    })));
+    out.push(("clear", String::from(match s.clear {
+        ClearVal::None => "none", ClearVal::Left => "left", ClearVal::Right => "right", ClearVal::Both => "both",
+    })));
+    out.push(("box-sizing", String::from(match s.box_sizing {
+        BoxSizing::ContentBox => "content-box", BoxSizing::BorderBox => "border-box",
+    })));
+    let width = match (s.width, s.width_pct) {
+        (_, Some(pct)) => format!("{}%", pct as f32 / 100.0),
+        (w, None) => opt_px(w),
+    };
+    let height = match (s.height, s.height_pct) {
+        (_, Some(pct)) => format!("{}%", pct as f32 / 100.0),
+        (h, None) => opt_px(h),
+    };
+    out.push(("width", width));
+    out.push(("height", height));
+    out.push(("min-width", px(s.min_width)));
+    out.push(("max-width", s.max_width.map_or(String::from("none"), px)));
+    out.push(("min-height", px(s.min_height)));
+    out.push(("max-height", s.max_height.map_or(String::from("none"), px)));
+    let auto_or = |auto: bool, v: i32| if auto { String::from("auto") } else { px(v) };
+    out.push(("margin-top", px(s.margin_top)));
+    out.push(("margin-right", auto_or(s.margin_right_auto, s.margin_right)));
+    out.push(("margin-bottom", px(s.margin_bottom)));
+    out.push(("margin-left", auto_or(s.margin_left_auto, s.margin_left)));
+    out.push(("padding-top", px(s.padding_top)));
+    out.push(("padding-right", px(s.padding_right)));
+    out.push(("padding-bottom", px(s.padding_bottom)));
+    out.push(("padding-left", px(s.padding_left)));
+    out.push(("border-width", px(s.border_width)));
+    out.push(("border-color", css_color(s.border_color)));
+    out.push(("border-radius", px(s.border_radius)));
+    out.push(("color", css_color(s.color)));
+    out.push(("background-color", css_color(s.background_color)));
+    out.push(("opacity", format!("{}", s.opacity as f32 / 255.0)));
+    out.push(("visibility", String::from(match s.visibility {
+        Visibility::Visible => "visible", Visibility::Hidden => "hidden", Visibility::Collapse => "collapse",
+    })));
+    out.push(("overflow-x", String::from(overflow_name(s.overflow_x))));
+    out.push(("overflow-y", String::from(overflow_name(s.overflow_y))));
+    out.push(("font-size", px(s.font_size)));
+    out.push(("font-weight", String::from(match s.font_weight {
+        FontWeight::Normal => "normal", FontWeight::Bold => "bold",
+    })));
+    out.push(("font-style", String::from(match s.font_style {
+        FontStyleVal::Normal => "normal", FontStyleVal::Italic => "italic",
+    })));
+    out.push(("line-height", if s.line_height == 0 { String::from("normal") } else { px(s.line_height) }));
+    out.push(("text-align", String::from(match s.text_align {
+        TextAlignVal::Left => "left", TextAlignVal::Center => "center",
+        TextAlignVal::Right => "right", TextAlignVal::Justify => "justify",
+    })));
+    out.push(("text-decoration", String::from(match s.text_decoration {
+        TextDeco::None => "none", TextDeco::Underline => "underline", TextDeco::LineThrough => "line-through",
+    })));
+    out.push(("text-transform", String::from(match s.text_transform {
+        TextTransform::None => "none", TextTransform::Uppercase => "uppercase",
+        TextTransform::Lowercase => "lowercase", TextTransform::Capitalize => "capitalize",
+    })));
+    out.push(("white-space", String::from(match s.white_space {
+        WhiteSpace::Normal => "normal", WhiteSpace::Pre => "pre",
+        WhiteSpace::Nowrap => "nowrap", WhiteSpace::PreWrap => "pre-wrap",
+    })));
+    out.push(("list-style-type", String::from(match s.list_style {
+        ListStyle::None => "none", ListStyle::Disc => "disc", ListStyle::Circle => "circle",
+        ListStyle::Square => "square", ListStyle::Decimal => "decimal",
+    })));
+    if matches!(s.display, Display::Flex | Display::InlineFlex) {
+        out.push(("flex-direction", String::from(match s.flex_direction {
+            FlexDirection::Row => "row", FlexDirection::RowReverse => "row-reverse",
+            FlexDirection::Column => "column", FlexDirection::ColumnReverse => "column-reverse",
+        })));
+        out.push(("flex-wrap", String::from(match s.flex_wrap {
+            FlexWrap::Nowrap => "nowrap", FlexWrap::Wrap => "wrap", FlexWrap::WrapReverse => "wrap-reverse",
+        })));
+        out.push(("justify-content", String::from(match s.justify_content {
+            JustifyContent::FlexStart => "flex-start", JustifyContent::FlexEnd => "flex-end",
+            JustifyContent::Center => "center", JustifyContent::SpaceBetween => "space-between",
+            JustifyContent::SpaceAround => "space-around", JustifyContent::SpaceEvenly => "space-evenly",
+        })));
+        out.push(("align-items", String::from(align_name(s.align_items))));
+    }
+    if matches!(s.display, Display::Flex | Display::InlineFlex | Display::Grid | Display::InlineGrid) {
+        out.push(("row-gap", px(s.row_gap)));
+        out.push(("column-gap", px(s.column_gap)));
+    }
+    out.push(("flex-grow", format!("{}", s.flex_grow as f32 / 100.0)));
+    out.push(("flex-shrink", format!("{}", s.flex_shrink as f32 / 100.0)));
+    out.push(("flex-basis", opt_px(s.flex_basis)));
+    out.push(("align-self", String::from(s.align_self.map_or("auto", align_name))));
+    out.push(("order", format!("{}", s.order)));
+    out
+}
+
+fn display_name(d: Display) -> &'static str {
+    match d {
+        Display::Block => "block",
+        Display::Inline => "inline",
+        Display::InlineBlock => "inline-block",
+        Display::ListItem => "list-item",
+        Display::TableRow => "table-row",
+        Display::TableCell => "table-cell",
+        Display::Flex => "flex",
+        Display::InlineFlex => "inline-flex",
+        Display::Grid => "grid",
+        Display::InlineGrid => "inline-grid",
+        Display::None => "none",
+    }
+}
+
+fn position_name(p: Position) -> &'static str {
+    match p {
+        Position::Static => "static",
+        Position::Relative => "relative",
+        Position::Absolute => "absolute",
+        Position::Fixed => "fixed",
+        Position::Sticky => "sticky",
+    }
+}
+
+fn overflow_name(o: OverflowVal) -> &'static str {
+    match o {
+        OverflowVal::Visible => "visible",
+        OverflowVal::Hidden => "hidden",
+        OverflowVal::Scroll => "scroll",
+        OverflowVal::Auto => "auto",
+    }
+}
+
+fn align_name(a: AlignItems) -> &'static str {
+    match a {
+        AlignItems::FlexStart => "flex-start",
+        AlignItems::FlexEnd => "flex-end",
+        AlignItems::Center => "center",
+        AlignItems::Stretch => "stretch",
+        AlignItems::Baseline => "baseline",
+    }
+}
+
+/// `#rrggbb`, `rgba(...)` for translucent colours, or `transparent`.
+fn css_color(argb: u32) -> String {
+    let a = argb >> 24;
+    let (r, g, b) = ((argb >> 16) & 0xFF, (argb >> 8) & 0xFF, argb & 0xFF);
+    match a {
+        0 => String::from("transparent"),
+        255 => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        _ => format!("rgba({}, {}, {}, {:.2})", r, g, b, a as f32 / 255.0),
+    }
+}
+
+/// Serialize `nodes` as a JSON array. With `styles`, every element carries
+/// its computed style as a `"style"` object.
+pub fn to_json(nodes: &[InspectNode], styles: Option<&[ComputedStyle]>) -> String {
+    let mut out = String::from("[");
+    for (i, n) in nodes.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&format!("{{\"id\":{},\"parent\":", n.id));
+        match n.parent {
+            Some(p) => out.push_str(&format!("{}", p)),
+            None => out.push_str("null"),
+        }
+        out.push_str(&format!(",\"depth\":{},\"name\":", n.depth));
+        push_json_str(&mut out, &n.name);
+        if let Some(ref t) = n.text {
+            out.push_str(",\"text\":");
+            push_json_str(&mut out, t);
+        } else {
+            out.push_str(",\"attrs\":{");
+            for (j, (k, v)) in n.attrs.iter().enumerate() {
+                if j > 0 {
+                    out.push(',');
+                }
+                push_json_str(&mut out, k);
+                out.push(':');
+                push_json_str(&mut out, v);
+            }
+            out.push('}');
+        }
+        out.push_str(",\"rects\":[");
+        for (j, r) in n.rects.iter().enumerate() {
+            if j > 0 {
+                out.push(',');
+            }
+            out.push_str(&format!(
+                "{{\"x\":{},\"y\":{},\"w\":{},\"h\":{},\"margin\":[{},{},{},{}],\"padding\":[{},{},{},{}],\"border\":{}}}",
+                r.x, r.y, r.width, r.height,
+                r.margin.top, r.margin.right, r.margin.bottom, r.margin.left,
+                r.padding.top, r.padding.right, r.padding.bottom, r.padding.left,
+                r.border,
+            ));
+        }
+        out.push(']');
+        if let Some(s) = styles.filter(|_| n.text.is_none()).and_then(|s| s.get(n.id)) {
+            out.push_str(",\"style\":{");
+            for (j, (k, v)) in computed_style(s).iter().enumerate() {
+                if j > 0 {
+                    out.push(',');
+                }
+                push_json_str(&mut out, k);
+                out.push(':');
+                push_json_str(&mut out, v);
+            }
+            out.push('}');
+        }
+        out.push('}');
+    }
+    out.push(']');
+    out
+}
+
+pub(crate) fn push_json_str(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Set (`Some`) or remove (`None`) `property` in the declaration list of a
+/// `style` attribute, keeping the other declarations and their order.
+pub fn edit_style_attr(style: &str, property: &str, value: Option<&str>) -> String {
+    let property = property.trim();
+    let mut decls: Vec<(String, String)> = style.split(';')
+        .filter_map(|d| {
+            let (k, v) = d.split_once(':')?;
+            let k = k.trim();
+            if k.is_empty() { None } else { Some((String::from(k), String::from(v.trim()))) }
+        })
+        .filter(|(k, _)| !k.eq_ignore_ascii_case(property))
+        .collect();
+    if let Some(v) = value {
+        decls.push((String::from(property), String::from(v.trim())));
+    }
+    let mut out = String::new();
+    for (k, v) in &decls {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(&format!("{}: {};", k, v));
+    }
+    out
+}
@@ -0,0 +1,255 @@
+//! Invalidation of script DOM changes.
+//!
+//! `JsRuntime::apply_mutations()` records what each mutation touched in a
+//! [`DomChanges`].  The WebView then re-resolves the styles of the affected
+//! subtrees only ([`DomChanges::restyle_roots`]) and lays out again only the
+//! blocks that contain them ([`DomChanges::relayout_roots`], see
+//! `layout::relayout_nodes`), repainting just the tile rows those blocks
+//! cover.  A change to a `<style>` element still restyles the whole page.
+
+use alloc::vec::Vec;
+
+use crate::css::{PseudoClass, Selector, SimpleSelector, Stylesheet};
+use crate::dom::{Dom, NodeId, Tag};
+
+/// What a batch of script mutations changed in the DOM.
+pub struct DomChanges {
+    /// Elements whose attributes changed.
+    pub attrs: Vec<NodeId>,
+    /// Elements whose `style` attribute changed (cached inline declarations
+    /// are stale).
+    pub inline_styles: Vec<NodeId>,
+    /// Nodes whose children were added, removed or replaced.
+    pub parents: Vec<NodeId>,
+    /// Nodes inserted or moved into the tree (with their subtrees).
+    pub inserted: Vec<NodeId>,
+    /// A `<style>` element or its text changed: every node may be affected.
+    pub sheets: bool,
+}
+
+impl DomChanges {
+    pub fn new() -> Self {
+        DomChanges {
+            attrs: Vec::new(),
+            inline_styles: Vec::new(),
+            parents: Vec::new(),
+            inserted: Vec::new(),
+            sheets: false,
+        }
+    }
+
+    /// Whether nothing in the tree changed.
+    pub fn is_empty(&self) -> bool {
+        self.attrs.is_empty() && self.parents.is_empty() && self.inserted.is_empty() && !self.sheets
+    }
+
+    /// Record a change of attribute `name` on `id`.
+    pub(crate) fn attr_changed(&mut self, dom: &Dom, id: NodeId, name: &str) {
+        if id >= dom.nodes.len() {
+            return;
+        }
+        push_unique(&mut self.attrs, id);
+        if name.eq_ignore_ascii_case("style") {
+            push_unique(&mut self.inline_styles, id);
+        }
+        if in_style_element(dom, id) {
+            self.sheets = true;
+        }
+    }
+
+    /// Record that `child` is about to be removed from or moved out of its
+    /// current parent.
+    pub(crate) fn child_removed(&mut self, dom: &Dom, child: NodeId) {
+        let parent = match dom.nodes.get(child).and_then(|n| n.parent) {
+            Some(p) => p,
+            None => return,
+        };
+        push_unique(&mut self.parents, parent);
+        if in_style_element(dom, parent) || contains_style(dom, child) {
+            self.sheets = true;
+        }
+    }
+
+    /// Record that `child` was inserted under `parent`.
+    pub(crate) fn child_inserted(&mut self, dom: &Dom, parent: NodeId, child: NodeId) {
+        if parent >= dom.nodes.len() || child >= dom.nodes.len() {
+            return;
+        }
+        push_unique(&mut self.parents, parent);
+        push_unique(&mut self.inserted, child);
+        if in_style_element(dom, parent) || contains_style(dom, child) {
+            self.sheets = true;
+        }
+    }
+
+    /// Record that all children of `parent` were replaced.  Call after the
+    /// new children are in place; `old` are the children it had before.
+    pub(crate) fn children_replaced(&mut self, dom: &Dom, parent: NodeId, old: &[NodeId]) {
+        if parent >= dom.nodes.len() {
+            return;
+        }
+        push_unique(&mut self.parents, parent);
+        for &c in &dom.nodes[parent].children {
+            push_unique(&mut self.inserted, c);
+        }
+        if in_style_element(dom, parent)
+            || old.iter().chain(dom.nodes[parent].children.iter()).any(|&c| contains_style(dom, c))
+        {
+            self.sheets = true;
+        }
+    }
+
+    /// Roots of the subtrees whose styles must be resolved again: changed
+    /// elements and inserted nodes, plus the siblings or parents whose
+    /// selectors can match differently (`+`, `~`, `:nth-child()`, `:empty`
+    /// and the like) if any of `sheets` use such selectors.
+    pub fn restyle_roots(&self, dom: &Dom, sheets: &[&Stylesheet]) -> Vec<NodeId> {
+        let (siblings, empty) = structural_selectors(sheets);
+        let mut roots: Vec<NodeId> = Vec::new();
+        for &id in &self.attrs {
+            push_unique(&mut roots, id);
+            if siblings {
+                for s in following_siblings(dom, id) {
+                    push_unique(&mut roots, s);
+                }
+            }
+        }
+        for &id in &self.inserted {
+            push_unique(&mut roots, id);
+        }
+        for &p in &self.parents {
+            if empty {
+                push_unique(&mut roots, p);
+            } else if siblings {
+                for &c in &dom.nodes[p].children {
+                    push_unique(&mut roots, c);
+                }
+            }
+        }
+        roots
+    }
+
+    /// Nodes whose boxes must be laid out again: the restyle roots and the
+    /// nodes whose children changed.
+    pub fn relayout_roots(&self, restyle_roots: &[NodeId]) -> Vec<NodeId> {
+        let mut nodes: Vec<NodeId> = restyle_roots.to_vec();
+        for &p in &self.parents {
+            push_unique(&mut nodes, p);
+        }
+        nodes
+    }
+}
+
+/// Flag `nodes` and all their descendants in `dirty`, growing it to the
+/// size of `dom` as needed.
+pub fn mark_subtrees(dom: &Dom, dirty: &mut Vec<bool>, nodes: &[NodeId]) {
+    let count = dom.nodes.len();
+    if dirty.len() < count {
+        dirty.resize(count, false);
+    }
+    // Walk the child lists: nodes moved by scripts may come before their
+    // new parent in the arena.
+    let mut stack: Vec<NodeId> = Vec::new();
+    for &id in nodes {
+        if id < count {
+            dirty[id] = true;
+            stack.push(id);
+        }
+    }
+    while let Some(id) = stack.pop() {
+        for &c in &dom.nodes[id].children {
+            if c < count && !dirty[c] {
+                dirty[c] = true;
+                stack.push(c);
+            }
+        }
+    }
+}
+
+/// Whether `sheets` contain selectors that depend on an element's siblings
+/// (first) or on whether it has children (second).
+fn structural_selectors(sheets: &[&Stylesheet]) -> (bool, bool) {
+    let mut siblings = false;
+    let mut empty = false;
+    for sheet in sheets {
+        let rules = sheet.rules.iter().chain(sheet.media_rules.iter().flat_map(|mr| mr.rules.iter()));
+        for rule in rules {
+            for sel in &rule.selectors {
+                scan_selector(sel, &mut siblings, &mut empty);
+            }
+        }
+        if siblings && empty {
+            break;
+        }
+    }
+    (siblings, empty)
+}
+
+fn scan_selector(sel: &Selector, siblings: &mut bool, empty: &mut bool) {
+    match sel {
+        Selector::Simple(s) => scan_simple(s, siblings, empty),
+        Selector::Descendant(a, s) | Selector::Child(a, s) => {
+            scan_selector(a, siblings, empty);
+            scan_simple(s, siblings, empty);
+        }
+        Selector::AdjacentSibling(a, s) | Selector::GeneralSibling(a, s) => {
+            *siblings = true;
+            scan_selector(a, siblings, empty);
+            scan_simple(s, siblings, empty);
+        }
+        Selector::Universal => {}
+    }
+}
+
+fn scan_simple(s: &SimpleSelector, siblings: &mut bool, empty: &mut bool) {
+    for pc in &s.pseudo_classes {
+        match pc {
+            PseudoClass::FirstChild | PseudoClass::LastChild
+            | PseudoClass::NthChild(_) | PseudoClass::NthLastChild(_)
+            | PseudoClass::FirstOfType | PseudoClass::LastOfType => *siblings = true,
+            PseudoClass::Empty => *empty = true,
+            PseudoClass::Not(inner) => scan_simple(inner, siblings, empty),
+            _ => {}
+        }
+    }
+}
+
+/// Siblings after `id` in its parent's child list.
+fn following_siblings(dom: &Dom, id: NodeId) -> Vec<NodeId> {
+    let parent = match dom.nodes.get(id).and_then(|n| n.parent) {
+        Some(p) => p,
+        None => return Vec::new(),
+    };
+    let children = &dom.nodes[parent].children;
+    match children.iter().position(|&c| c == id) {
+        Some(pos) => children[pos + 1..].to_vec(),
+        None => Vec::new(),
+    }
+}
+
+/// Whether `id` is a `<style>` element or inside one.
+fn in_style_element(dom: &Dom, id: NodeId) -> bool {
+    let mut cur = Some(id);
+    while let Some(n) = cur {
+        if dom.tag(n) == Some(Tag::Style) {
+            return true;
+        }
+        cur = dom.nodes.get(n).and_then(|node| node.parent);
+    }
+    false
+}
+
+/// Whether the subtree of `id` contains a `<style>` element.
+fn contains_style(dom: &Dom, id: NodeId) -> bool {
+    if id >= dom.nodes.len() {
+        return false;
+    }
+    dom.tag(id) == Some(Tag::Style)
+        || dom.nodes[id].children.iter().any(|&c| contains_style(dom, c))
+}
+
+fn push_unique(list: &mut Vec<NodeId>, id: NodeId) {
+    if !list.contains(&id) {
+        list.push(id);
+    }
+}
@@ -19,6 +19,7 @@ use super::{
     read_child_ids, read_node_type, read_inner_html,
     dom_property_hook, DomMutation,
 };
+use crate::media::{MediaCommand, MediaState};
 use super::classlist;
 use super::selector;
 
@@ -91,6 +92,7 @@ pub fn make_element(vm: &mut Vm, node_id: i64) -> JsValue {
 fn make_element_impl(vm: &mut Vm, node_id: i64, include_siblings: bool) -> JsValue {
     // Read properties from DOM or virtual node store.
     let tag_name = read_tag_name(vm, node_id);
+    let is_media = tag_name == "AUDIO" || tag_name == "VIDEO";
     let text = read_text_content(vm, node_id);
     let node_type = read_node_type(vm, node_id);
     let inner_html = read_inner_html(vm, node_id);
@@ -184,6 +186,11 @@ fn make_element_impl(vm: &mut Vm, node_id: i64, include_siblings: bool) -> JsVal
     let cl = classlist::make_class_list(node_id, &class_name);
     obj.set(String::from("classList"), cl);
 
+    // HTMLMediaElement.
+    if is_media {
+        set_media_props(vm, node_id, &mut obj);
+    }
+
     // ── Native methods ──
     obj.set(String::from("getAttribute"), native_fn("getAttribute", el_get_attribute));
     obj.set(String::from("setAttribute"), native_fn("setAttribute", el_set_attribute));
@@ -638,6 +645,68 @@ fn el_to_string(_vm: &mut Vm, _args: &[JsValue]) -> JsValue {
     JsValue::String(String::from("[object HTMLElement]"))
 }
 
+// ═══════════════════════════════════════════════════════════
+// HTMLMediaElement
+// ═══════════════════════════════════════════════════════════
+
+/// Add the `<audio>` / `<video>` properties and methods to `obj`.
+/// Playback state is a snapshot of what the WebView last reported.
+fn set_media_props(vm: &mut Vm, node_id: i64, obj: &mut JsObject) {
+    let state = get_bridge(vm)
+        .and_then(|b| b.media_states.iter().find(|(id, _)| *id as i64 == node_id).map(|(_, s)| *s))
+        .unwrap_or(MediaState::IDLE);
+    let flag = |vm: &mut Vm, name: &str| !matches!(read_attribute(vm, node_id, name), JsValue::Null);
+
+    obj.set(String::from("paused"), JsValue::Bool(state.paused));
+    obj.set(String::from("ended"), JsValue::Bool(state.ended));
+    obj.set(String::from("currentTime"), JsValue::Number(state.position_ms as f64 / 1000.0));
+    let duration = if state.duration_ms > 0 { state.duration_ms as f64 / 1000.0 } else { f64::NAN };
+    obj.set(String::from("duration"), JsValue::Number(duration));
+    obj.set(String::from("autoplay"), JsValue::Bool(flag(vm, "autoplay")));
+    obj.set(String::from("controls"), JsValue::Bool(flag(vm, "controls")));
+    obj.set(String::from("loop"), JsValue::Bool(flag(vm, "loop")));
+    obj.set(String::from("error"), if state.error { JsValue::new_object() } else { JsValue::Null });
+    let poster = match read_attribute(vm, node_id, "poster") {
+        JsValue::String(s) => s,
+        _ => String::new(),
+    };
+    obj.set(String::from("poster"), JsValue::String(poster));
+
+    obj.set(String::from("play"), native_fn("play", el_media_play));
+    obj.set(String::from("pause"), native_fn("pause", el_media_pause));
+    obj.set(String::from("load"), native_fn("load", el_media_load));
+}
+
+/// Record a playback request for the element `this`.
+fn push_media_command(vm: &mut Vm, command: MediaCommand) {
+    let nid = this_node_id(vm);
+    if nid < 0 { return; }
+    if let Some(bridge) = get_bridge(vm) {
+        bridge.mutations.push(DomMutation::Media { node_id: nid as usize, command });
+    }
+}
+
+fn el_media_play(vm: &mut Vm, _args: &[JsValue]) -> JsValue {
+    push_media_command(vm, MediaCommand::Play);
+    if let JsValue::Object(obj) = &vm.current_this {
+        obj.borrow_mut().set(String::from("paused"), JsValue::Bool(false));
+    }
+    JsValue::Undefined
+}
+
+fn el_media_pause(vm: &mut Vm, _args: &[JsValue]) -> JsValue {
+    push_media_command(vm, MediaCommand::Pause);
+    if let JsValue::Object(obj) = &vm.current_this {
+        obj.borrow_mut().set(String::from("paused"), JsValue::Bool(true));
+    }
+    JsValue::Undefined
+}
+
+fn el_media_load(vm: &mut Vm, _args: &[JsValue]) -> JsValue {
+    push_media_command(vm, MediaCommand::Load);
+    JsValue::Undefined
+}
+
 fn el_noop(_vm: &mut Vm, _args: &[JsValue]) -> JsValue { JsValue::Undefined }
 fn el_noop_false(_vm: &mut Vm, _args: &[JsValue]) -> JsValue { JsValue::Bool(false) }
 fn el_noop_null(_vm: &mut Vm, _args: &[JsValue]) -> JsValue { JsValue::Null }
@@ -1,7 +1,16 @@
 //! Native fetch() API + Headers constructor.
+//!
+//! libjs Promises settle synchronously, so an asynchronous `fetch()` cannot
+//! hand back a real pending Promise.  Instead it returns a lightweight
+//! *thenable*: `.then()` / `.catch()` / `.finally()` append handlers to a
+//! pipeline that runs once the host delivers the response.  Handlers that
+//! return a settled libjs Promise (e.g. `response.json()`) are unwrapped, so
+//! the common `fetch(u).then(r => r.json()).then(data => …)` chain works.
 
 use alloc::rc::Rc;
 use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
 use core::cell::RefCell;
 
 use libjs::JsValue;
@@ -9,20 +18,22 @@ use libjs::Vm;
 use libjs::value::JsObject;
 use libjs::vm::native_fn;
 
-use super::arg_string;
-use super::http;
+use super::{arg_string, make_array};
+use super::{http, HttpResponse};
 
 /// `fetch(url, options)` — performs HTTP request, returns a Promise-like result.
 ///
-/// Since our Promise implementation is synchronous, the request is made
-/// immediately via `__http_request` and wrapped in Promise.resolve/reject.
+/// When the host handles requests asynchronously the request is queued and
+/// a thenable is returned (see module docs).  With a synchronous
+/// `__http_handler` the request is made immediately and wrapped in
+/// Promise.resolve/reject.
 pub fn native_fetch(vm: &mut Vm, args: &[JsValue]) -> JsValue {
-    let url = arg_string(args, 0);
+    let url = http::resolve_request_url(vm, &arg_string(args, 0));
     let options = args.get(1).cloned().unwrap_or(JsValue::Undefined);
 
     let method = if let JsValue::Object(_) = &options {
         let m = options.get_property("method").to_js_string();
-        if m.is_empty() || m == "undefined" { String::from("GET") } else { m }
+        if m.is_empty() || m == "undefined" { String::from("GET") } else { m.to_ascii_uppercase() }
     } else {
         String::from("GET")
     };
@@ -34,6 +45,18 @@ pub fn native_fetch(vm: &mut Vm, args: &[JsValue]) -> JsValue {
         String::new()
     };
 
+    if !http::has_sync_handler(vm) {
+        let headers = if let JsValue::Object(_) = &options {
+            http::collect_headers(&options.get_property("headers"))
+        } else {
+            Vec::new()
+        };
+        let pending = make_thenable(&url);
+        if http::queue_request(vm, method.clone(), url.clone(), headers, body.clone(), pending.clone()).is_some() {
+            return pending;
+        }
+    }
+
     let headers_str = String::from("{}");
 
     // Perform the request.
@@ -50,7 +73,7 @@ pub fn native_fetch(vm: &mut Vm, args: &[JsValue]) -> JsValue {
 
     if status > 0.0 {
         // Build Response object.
-        let response = make_response(status, &status_text, &url, &resp_body);
+        let response = make_response(status, &status_text, &url, &resp_body, &[]);
 
         // Wrap in Promise.resolve(response).
         // Call the global Promise.resolve if available.
@@ -83,7 +106,7 @@ pub fn native_fetch(vm: &mut Vm, args: &[JsValue]) -> JsValue {
     }
 }
 
-fn make_response(status: f64, status_text: &str, url: &str, body: &str) -> JsValue {
+fn make_response(status: f64, status_text: &str, url: &str, body: &str, headers: &[(String, String)]) -> JsValue {
     let mut obj = JsObject::new();
     obj.set(String::from("ok"), JsValue::Bool(status >= 200.0 && status < 300.0));
     obj.set(String::from("status"), JsValue::Number(status));
@@ -94,12 +117,17 @@ fn make_response(status: f64, status_text: &str, url: &str, body: &str) -> JsVal
     obj.set(String::from("bodyUsed"), JsValue::Bool(false));
     obj.set(String::from("__body"), JsValue::String(String::from(body)));
 
-    // Headers sub-object.
-    let headers = JsValue::new_object();
-    headers.set_property(String::from("get"), native_fn("get", |_,_| JsValue::Null));
-    headers.set_property(String::from("has"), native_fn("has", |_,_| JsValue::Bool(false)));
-    headers.set_property(String::from("forEach"), native_fn("forEach", |_,_| JsValue::Undefined));
-    obj.set(String::from("headers"), headers);
+    // Headers sub-object (same shape as `new Headers(…)`).
+    let data = JsValue::new_object();
+    for (name, value) in headers {
+        data.set_property(name.to_ascii_lowercase(), JsValue::String(value.clone()));
+    }
+    let hdrs = JsValue::new_object();
+    hdrs.set_property(String::from("_headers"), data);
+    hdrs.set_property(String::from("get"), native_fn("get", headers_get));
+    hdrs.set_property(String::from("has"), native_fn("has", headers_has));
+    hdrs.set_property(String::from("forEach"), native_fn("forEach", |_,_| JsValue::Undefined));
+    obj.set(String::from("headers"), hdrs);
 
     // Body methods — return the stored __body wrapped in Promise.resolve.
     obj.set(String::from("text"), native_fn("text", resp_text));
@@ -161,6 +189,85 @@ fn wrap_promise_resolve(vm: &mut Vm, val: JsValue) -> JsValue {
     val
 }
 
+// ═══════════════════════════════════════════════════════════
+// Async fetch thenable
+// ═══════════════════════════════════════════════════════════
+
+/// Create the pending result object returned by an asynchronous `fetch()`.
+fn make_thenable(url: &str) -> JsValue {
+    let mut obj = JsObject::new();
+    obj.set(String::from("_fetch"), JsValue::Bool(true));
+    obj.set(String::from("_url"), JsValue::String(String::from(url)));
+    // Pipeline of [onFulfilled, onRejected] pairs, run in order on settle.
+    obj.set(String::from("_handlers"), make_array(Vec::new()));
+    obj.set(String::from("then"), native_fn("then", thenable_then));
+    obj.set(String::from("catch"), native_fn("catch", thenable_catch));
+    obj.set(String::from("finally"), native_fn("finally", thenable_finally));
+    JsValue::Object(Rc::new(RefCell::new(obj)))
+}
+
+fn push_handler(vm: &Vm, on_ok: JsValue, on_err: JsValue) -> JsValue {
+    let this = vm.current_this.clone();
+    if let JsValue::Array(arr) = this.get_property("_handlers") {
+        arr.borrow_mut().push(make_array(vec![on_ok, on_err]));
+    }
+    this
+}
+
+fn thenable_then(vm: &mut Vm, args: &[JsValue]) -> JsValue {
+    let on_ok = args.first().cloned().unwrap_or(JsValue::Undefined);
+    let on_err = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+    push_handler(vm, on_ok, on_err)
+}
+
+fn thenable_catch(vm: &mut Vm, args: &[JsValue]) -> JsValue {
+    let on_err = args.first().cloned().unwrap_or(JsValue::Undefined);
+    push_handler(vm, JsValue::Undefined, on_err)
+}
+
+fn thenable_finally(vm: &mut Vm, args: &[JsValue]) -> JsValue {
+    let f = args.first().cloned().unwrap_or(JsValue::Undefined);
+    push_handler(vm, f.clone(), f)
+}
+
+/// Settle a fetch thenable with a host-delivered response.
+pub fn deliver(vm: &mut Vm, pending: &JsValue, resp: &HttpResponse) {
+    let url = pending.get_property("_url").to_js_string();
+    let response = make_response(resp.status as f64, &resp.status_text, &url, &resp.body, &resp.headers);
+    settle(vm, pending, true, response);
+}
+
+/// Reject a fetch thenable after a network failure (or blocked cross-origin read).
+pub fn fail(vm: &mut Vm, pending: &JsValue) {
+    let err = vm.make_type_error("Failed to fetch");
+    settle(vm, pending, false, err);
+}
+
+/// Run the handler pipeline of `pending`, threading the value through each
+/// stage like a Promise chain would.
+fn settle(vm: &mut Vm, pending: &JsValue, fulfilled: bool, value: JsValue) {
+    let handlers = match pending.get_property("_handlers") {
+        JsValue::Array(arr) => core::mem::take(&mut arr.borrow_mut().elements),
+        _ => return,
+    };
+    let mut ok = fulfilled;
+    let mut value = value;
+    for pair in &handlers {
+        let cb = pair.get_property(if ok { "0" } else { "1" });
+        if !cb.is_function() { continue; }
+        let result = vm.call_value(&cb, &[value.clone()], JsValue::Undefined);
+        // Unwrap settled libjs Promises returned by handlers (`r.json()` etc.).
+        let state = result.get_property("__state");
+        if let JsValue::String(st) = &state {
+            ok = st != "rejected";
+            value = result.get_property("__value");
+        } else {
+            ok = true;
+            value = result;
+        }
+    }
+}
+
 /// `new Headers(init)` constructor.
 pub fn native_headers_ctor(_vm: &mut Vm, args: &[JsValue]) -> JsValue {
     let mut obj = JsObject::new();
@@ -4,6 +4,11 @@
 //!
 //! If the host registered `__http_handler` as a native global, it is
 //! called synchronously.  Otherwise the request is queued as pending.
+//!
+//! Asynchronous XHR / fetch go through `queue_request()` instead: the
+//! request is recorded as a `PendingHttpRequest` and the issuing JS object
+//! is parked in the bridge's HTTP registry until the host delivers the
+//! result via `JsRuntime::http_response` / `JsRuntime::http_failed`.
 
 use alloc::rc::Rc;
 use alloc::string::String;
@@ -14,10 +19,18 @@ use libjs::JsValue;
 use libjs::Vm;
 use libjs::value::{JsObject, FnKind};
 
-use super::{get_bridge, arg_string, PendingHttpRequest};
+use super::{get_bridge, arg_string, resolve_url, PendingHttpRequest};
 
 static mut NEXT_HTTP_REQ_ID: u64 = 1;
 
+fn alloc_req_id() -> u64 {
+    unsafe {
+        let id = NEXT_HTTP_REQ_ID;
+        NEXT_HTTP_REQ_ID += 1;
+        id
+    }
+}
+
 pub fn http_request(vm: &mut Vm, args: &[JsValue]) -> JsValue {
     let method = arg_string(args, 0);
     let url = arg_string(args, 1);
@@ -35,11 +48,7 @@ pub fn http_request(vm: &mut Vm, args: &[JsValue]) -> JsValue {
 
     // Record as pending request.
     if let Some(bridge) = get_bridge(vm) {
-        let id = unsafe {
-            let id = NEXT_HTTP_REQ_ID;
-            NEXT_HTTP_REQ_ID += 1;
-            id
-        };
+        let id = alloc_req_id();
         bridge.pending_http_requests.push(PendingHttpRequest {
             id,
             method,
@@ -56,3 +65,64 @@ pub fn http_request(vm: &mut Vm, args: &[JsValue]) -> JsValue {
     obj.set(String::from("body"), JsValue::String(String::new()));
     JsValue::Object(Rc::new(RefCell::new(obj)))
 }
+
+/// Returns `true` if the host installed a synchronous `__http_handler`.
+pub fn has_sync_handler(vm: &mut Vm) -> bool {
+    matches!(vm.get_global("__http_handler"), JsValue::Function(_))
+}
+
+/// Resolve `url` against the URL of the page running the script.
+pub fn resolve_request_url(vm: &mut Vm, url: &str) -> String {
+    match get_bridge(vm) {
+        Some(bridge) => resolve_url(&bridge.page_url, url),
+        None => String::from(url),
+    }
+}
+
+/// Queue an asynchronous request for the host and park `target` (the XHR
+/// object or fetch thenable) until the response arrives.
+///
+/// Returns the request ID, or `None` when no DOM bridge is active (the
+/// caller then falls back to the synchronous path).
+pub fn queue_request(
+    vm: &mut Vm,
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: String,
+    target: JsValue,
+) -> Option<u64> {
+    let bridge = get_bridge(vm)?;
+    let id = alloc_req_id();
+    bridge.pending_http_requests.push(PendingHttpRequest {
+        id,
+        method,
+        url,
+        headers,
+        body: if body.is_empty() { None } else { Some(body) },
+    });
+    bridge.http_registry.push((id, target));
+    Some(id)
+}
+
+/// Collect the string properties of a JS headers object into (name, value) pairs.
+///
+/// Accepts both plain objects (`{ "Content-Type": "…" }`) and `Headers`
+/// instances created by `new Headers(…)` (which keep their data in `_headers`).
+pub fn collect_headers(val: &JsValue) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    let data = match val {
+        JsValue::Object(obj) => {
+            let inner = obj.borrow().get("_headers");
+            if let JsValue::Object(_) = inner { inner } else { val.clone() }
+        }
+        _ => return out,
+    };
+    if let JsValue::Object(obj) = &data {
+        for (k, prop) in &obj.borrow().properties {
+            if matches!(prop.value, JsValue::Function(_)) { continue; }
+            out.push((k.clone(), prop.value.to_js_string()));
+        }
+    }
+    out
+}
@@ -29,7 +29,13 @@ use libjs::vm::native_fn;
 
 use crate::dom::{Dom, NodeId, NodeType, Tag};
 use crate::css::{Declaration, KeyframeSet};
-use crate::style::{apply_timing, TimingFunction, TransitionDef};
+use crate::invalidate::DomChanges;
+use crate::style::{
+    apply_timing, transition_def, transition_values, ComputedStyle, TimingFunction,
+    TransitionDef, TRANSITIONABLE,
+};
+use crate::transform::{interpolate_transform, parse_transform, transform_to_css};
+use crate::media::{MediaCommand, MediaState};
 
 // ═══════════════════════════════════════════════════════════
 // Property write interception — static target for set_hook
@@ -83,6 +89,16 @@ fn dom_property_hook(data: *mut u8, key: &str, value: &JsValue) {
                 });
             }
         }
+        "currentTime" => {
+            if node_id >= 0 {
+                let secs = value.to_number();
+                let ms = if secs.is_finite() && secs > 0.0 { (secs * 1000.0) as u32 } else { 0 };
+                mutations.push(DomMutation::Media {
+                    node_id: node_id as usize,
+                    command: MediaCommand::Seek(ms),
+                });
+            }
+        }
         "checked" | "disabled" => {
             if node_id >= 0 {
                 if value.to_boolean() {
@@ -132,6 +148,12 @@ struct DomBridge {
     pending_ws_closes: Vec<PendingWsClose>,
     /// Live WebSocket objects: (ws_id → JsValue clone) for callback delivery.
     ws_registry: Vec<(u64, JsValue)>,
+    /// In-flight async XHR / fetch objects: (request id → JsValue) awaiting a response.
+    http_registry: Vec<(u64, JsValue)>,
+    /// URL of the page the scripts belong to (base for relative request URLs).
+    page_url: String,
+    /// Playback state of the page's media elements, for `paused` / `currentTime`.
+    media_states: Vec<(NodeId, MediaState)>,
 }
 
 impl DomBridge {
@@ -194,18 +216,40 @@ pub enum DomMutation {
     /// The host application should parse this Set-Cookie string and update its
     /// cookie jar accordingly.
     SetCookie { value: String },
+    /// A playback request on an `<audio>` / `<video>` element
+    /// (`play()`, `pause()`, `load()`, `currentTime = …`).
+    /// The WebView forwards it to its `MediaHost`.
+    Media { node_id: usize, command: MediaCommand },
 }
 
 /// A pending HTTP request from XMLHttpRequest / fetch.
+///
+/// The host performs the request (typically on a network worker thread) and
+/// hands the result back on the UI thread via `JsRuntime::http_response` or
+/// `JsRuntime::http_failed`, quoting the same `id`.
 #[derive(Clone)]
 pub struct PendingHttpRequest {
     pub id: u64,
     pub method: String,
+    /// Absolute URL (relative URLs are resolved against the page URL).
     pub url: String,
     pub headers: Vec<(String, String)>,
     pub body: Option<String>,
 }
 
+/// A completed HTTP response for a `PendingHttpRequest`, supplied by the host.
+#[derive(Clone)]
+pub struct HttpResponse {
+    /// ID of the `PendingHttpRequest` this response answers.
+    pub id: u64,
+    pub status: u16,
+    pub status_text: String,
+    /// Response headers as (name, value) pairs, in wire order.
+    pub headers: Vec<(String, String)>,
+    /// Decoded response body.
+    pub body: String,
+}
+
 /// An event listener registered from JavaScript.
 #[derive(Clone)]
 pub struct EventListener {
@@ -308,6 +352,10 @@ pub struct JsRuntime {
     /// Cookie string for the current page (e.g. `"name=value; n2=v2"`).
     /// Set by the host before calling `execute_scripts`.
     pub cookies: String,
+    /// `document.cookie` assignments not yet collected by the host.
+    cookie_writes: Vec<String>,
+    /// Browser profile directory (localStorage files live below it).
+    profile_dir: String,
     /// Pending WebSocket connection requests (from `new WebSocket(url)`).
     pub pending_ws_connects: Vec<PendingWsConnect>,
     /// Pending WebSocket send requests (from `ws.send(data)`).
@@ -316,10 +364,24 @@ pub struct JsRuntime {
     pub pending_ws_closes: Vec<PendingWsClose>,
     /// Registry of live WebSocket JS objects: (id, JsValue) for callback delivery.
     ws_registry: Vec<(u64, JsValue)>,
+    /// In-flight async XHR / fetch objects awaiting `http_response` / `http_failed`.
+    http_registry: Vec<(u64, JsValue)>,
+    /// URL of the current page (set by `execute_scripts`).
+    page_url: String,
+    /// When `true`, cross-origin responses are only delivered to scripts if
+    /// the server opted in via `Access-Control-Allow-Origin`.
+    same_origin_only: bool,
     /// Currently running `@keyframes` animations.
     pub active_animations: Vec<ActiveAnimation>,
     /// Currently running CSS transitions.
     pub active_transitions: Vec<ActiveTransition>,
+    /// `(node, @keyframes name)` pairs the current styles request; each is
+    /// started once, so finished animations do not restart on relayout.
+    started_animations: Vec<(NodeId, String)>,
+    /// Media playback requests not yet collected by the WebView.
+    media_commands: Vec<(NodeId, MediaCommand)>,
+    /// Playback state of media elements, as last reported by the WebView.
+    media_states: Vec<(NodeId, MediaState)>,
 }
 
 impl JsRuntime {
@@ -334,12 +396,20 @@ impl JsRuntime {
             timers: Vec::new(),
             next_timer_id: 1,
             cookies: String::new(),
+            cookie_writes: Vec::new(),
+            profile_dir: String::new(),
             pending_ws_connects: Vec::new(),
             pending_ws_sends: Vec::new(),
             pending_ws_closes: Vec::new(),
             ws_registry: Vec::new(),
+            http_registry: Vec::new(),
+            page_url: String::new(),
+            same_origin_only: true,
             active_animations: Vec::new(),
             active_transitions: Vec::new(),
+            started_animations: Vec::new(),
+            media_commands: Vec::new(),
+            media_states: Vec::new(),
         }
     }
 
@@ -350,11 +420,79 @@ impl JsRuntime {
         self.cookies = String::from(cookies);
     }
 
+    /// Take the `document.cookie` assignments made by scripts since the last
+    /// call.  Each entry is a `Set-Cookie`-style string (`"name=value; Path=/"`)
+    /// for the host to store in its cookie jar.
+    pub fn take_cookie_writes(&mut self) -> Vec<String> {
+        for m in &self.mutations {
+            if let DomMutation::SetCookie { value } = m {
+                self.cookie_writes.push(value.clone());
+            }
+        }
+        self.mutations.retain(|m| !matches!(m, DomMutation::SetCookie { .. }));
+        core::mem::take(&mut self.cookie_writes)
+    }
+
+    /// Take the media playback requests made by scripts since the last call.
+    pub fn take_media_commands(&mut self) -> Vec<(NodeId, MediaCommand)> {
+        for m in &self.mutations {
+            if let DomMutation::Media { node_id, command } = m {
+                self.media_commands.push((*node_id, *command));
+            }
+        }
+        self.mutations.retain(|m| !matches!(m, DomMutation::Media { .. }));
+        core::mem::take(&mut self.media_commands)
+    }
+
+    /// Record the playback state of a media element, exposed to scripts as
+    /// `paused`, `ended`, `currentTime` and `duration`.
+    pub fn set_media_state(&mut self, node_id: NodeId, state: MediaState) {
+        match self.media_states.iter_mut().find(|(id, _)| *id == node_id) {
+            Some(entry) => entry.1 = state,
+            None => self.media_states.push((node_id, state)),
+        }
+    }
+
+    /// Forget the playback state of all media elements (new document).
+    pub fn clear_media_states(&mut self) {
+        self.media_states.clear();
+        self.media_commands.clear();
+    }
+
+    /// Set the browser profile directory.  `localStorage` is persisted to
+    /// `<dir>/localstorage/` for pages loaded after this call.
+    pub fn set_profile_dir(&mut self, dir: &str) {
+        self.profile_dir = String::from(dir);
+    }
+
+    /// Read the persisted `localStorage` entries of `origin`
+    /// (e.g. `"https://example.com"`), sorted by key.
+    pub fn local_storage_entries(&self, origin: &str) -> Vec<(String, String)> {
+        storage::read_entries(origin, &self.profile_dir)
+    }
+
+    /// Delete the persisted `localStorage` of `origin`.
+    pub fn clear_local_storage(&self, origin: &str) {
+        storage::clear_entries(origin, &self.profile_dir);
+    }
+
+    /// Enable or disable the same-origin policy for XHR / fetch responses.
+    ///
+    /// Enabled by default.  When disabled, every response is handed to the
+    /// page regardless of its origin (useful for trusted local content).
+    pub fn set_same_origin_policy(&mut self, enabled: bool) {
+        self.same_origin_only = enabled;
+    }
+
     /// Execute all `<script>` tags in the DOM.
     ///
     /// * `url` — the current page URL, used to populate `window.location` /
     ///   `document.location` inside the JS environment.
     pub fn execute_scripts(&mut self, dom: &Dom, url: &str) {
+        self.page_url = String::from(url);
+        // Responses for requests issued by the previous page are dropped.
+        self.http_registry.clear();
+
         let mut scripts: Vec<String> = Vec::new();
         for i in 0..dom.nodes.len() {
             if let NodeType::Element { tag: Tag::Script, attrs } = &dom.nodes[i].node_type {
@@ -391,21 +529,7 @@ impl JsRuntime {
         self.engine.set_step_limit(2_000_000);
 
         // Set up DOM bridge via userdata.
-        let mut bridge = DomBridge {
-            dom: dom as *const Dom,
-            mutations: Vec::new(),
-            event_listeners: Vec::new(),
-            next_virtual_id: -1,
-            virtual_nodes: Vec::new(),
-            pending_http_requests: Vec::new(),
-            timers: Vec::new(),
-            next_timer_id: 1,
-            propagation_stopped: false,
-            pending_ws_connects: Vec::new(),
-            pending_ws_sends: Vec::new(),
-            pending_ws_closes: Vec::new(),
-            ws_registry: Vec::new(),
-        };
+        let mut bridge = self.new_bridge(dom, 1);
         self.engine.vm().userdata = &mut bridge as *mut DomBridge as *mut u8;
 
         // Set up native host objects (document, window, etc.).
@@ -446,6 +570,7 @@ impl JsRuntime {
         self.pending_ws_sends.extend(bridge.pending_ws_sends);
         self.pending_ws_closes.extend(bridge.pending_ws_closes);
         self.ws_registry.extend(bridge.ws_registry);
+        self.http_registry.extend(bridge.http_registry);
         self.engine.vm().userdata = core::ptr::null_mut();
         crate::debug_surf!("[js] execute_scripts complete: {} mutations, {} listeners",
             self.mutations.len(), self.event_listeners.len());
@@ -469,7 +594,7 @@ impl JsRuntime {
         let origin = extract_origin(url);
 
         // Create window object natively.
-        let win = window::make_window(vm, doc, &origin);
+        let win = window::make_window(vm, doc, &origin, &self.profile_dir);
         vm.set_global("window", win.clone());
         vm.set_global("self", win.clone());
         vm.set_global("globalThis", win);
@@ -501,21 +626,7 @@ impl JsRuntime {
     }
 
     pub fn eval_with_dom(&mut self, source: &str, dom: &Dom) -> JsValue {
-        let mut bridge = DomBridge {
-            dom: dom as *const Dom,
-            mutations: Vec::new(),
-            event_listeners: Vec::new(),
-            next_virtual_id: -1,
-            virtual_nodes: Vec::new(),
-            pending_http_requests: Vec::new(),
-            timers: Vec::new(),
-            next_timer_id: self.next_timer_id,
-            propagation_stopped: false,
-            pending_ws_connects: Vec::new(),
-            pending_ws_sends: Vec::new(),
-            pending_ws_closes: Vec::new(),
-            ws_registry: Vec::new(),
-        };
+        let mut bridge = self.new_bridge(dom, self.next_timer_id);
         self.engine.vm().userdata = &mut bridge as *mut DomBridge as *mut u8;
 
         unsafe { MUTATION_TARGET = &mut bridge.mutations as *mut Vec<DomMutation>; }
@@ -529,6 +640,7 @@ impl JsRuntime {
         self.mutations.extend(bridge.mutations);
         self.event_listeners.extend(bridge.event_listeners);
         self.pending_http_requests.extend(bridge.pending_http_requests);
+        self.http_registry.extend(bridge.http_registry);
         self.next_timer_id = bridge.next_timer_id;
         self.timers.extend(bridge.timers);
         self.engine.vm().userdata = core::ptr::null_mut();
@@ -550,6 +662,11 @@ impl JsRuntime {
         core::mem::take(&mut self.pending_http_requests)
     }
 
+    /// Returns `true` while async XHR / fetch requests are awaiting a response.
+    pub fn has_http_in_flight(&self) -> bool {
+        !self.http_registry.is_empty()
+    }
+
     pub fn take_timers(&mut self) -> Vec<PendingTimer> {
         core::mem::take(&mut self.timers)
     }
@@ -656,11 +773,113 @@ impl JsRuntime {
         self.engine.clear_console();
     }
 
+    // ── HTTP (XHR / fetch) response delivery ─────────────────────────────────
+
+    /// Called by the host (on the UI thread) when an async request completes.
+    ///
+    /// Fires the XHR `readystatechange` / `load` callbacks or settles the
+    /// fetch result.  Cross-origin responses without a matching
+    /// `Access-Control-Allow-Origin` header are reported as network errors
+    /// while the same-origin policy is enabled.  DOM mutations made by the
+    /// callbacks are queued in `mutations` like any other script activity.
+    pub fn http_response(&mut self, dom: &Dom, resp: &HttpResponse) {
+        let target = match self.take_http_target(resp.id) {
+            Some(t) => t,
+            None => return,
+        };
+        let url = target.get_property("_url").to_js_string();
+        if self.cross_origin_blocked(&url, &resp.headers) {
+            anyos_std::println!("[js] blocked cross-origin response from {}", url);
+            self.deliver_http(dom, &target, None);
+            return;
+        }
+        self.deliver_http(dom, &target, Some(resp));
+    }
+
+    /// Called by the host when an async request failed at the network level
+    /// (DNS, connect, TLS, …).  Fires `onerror` / rejects the fetch.
+    pub fn http_failed(&mut self, dom: &Dom, id: u64) {
+        if let Some(target) = self.take_http_target(id) {
+            self.deliver_http(dom, &target, None);
+        }
+    }
+
+    /// Remove and return the JS object waiting for request `id`.
+    fn take_http_target(&mut self, id: u64) -> Option<JsValue> {
+        let pos = self.http_registry.iter().position(|(rid, _)| *rid == id)?;
+        Some(self.http_registry.remove(pos).1)
+    }
+
+    /// Same-origin policy check for a response to `url`.
+    fn cross_origin_blocked(&self, url: &str, headers: &[(String, String)]) -> bool {
+        if !self.same_origin_only { return false; }
+        let page_origin = extract_origin(&self.page_url);
+        if page_origin.is_empty() || extract_origin(url) == page_origin { return false; }
+        match headers.iter().find(|(n, _)| n.eq_ignore_ascii_case("access-control-allow-origin")) {
+            Some((_, v)) => { let v = v.trim(); v != "*" && v != page_origin }
+            None => true,
+        }
+    }
+
+    /// Run the XHR / fetch completion callbacks for `target` with DOM access.
+    /// `resp = None` signals a network error.
+    fn deliver_http(&mut self, dom: &Dom, target: &JsValue, resp: Option<&HttpResponse>) {
+        let mut bridge = self.new_bridge(dom, self.next_timer_id);
+        self.engine.vm().userdata = &mut bridge as *mut DomBridge as *mut u8;
+        unsafe { MUTATION_TARGET = &mut bridge.mutations as *mut Vec<DomMutation>; }
+
+        self.engine.set_step_limit(500_000);
+        let vm = self.engine.vm();
+        let is_fetch = target.get_property("_fetch").to_boolean();
+        match (resp, is_fetch) {
+            (Some(r), false) => xhr::deliver(vm, target, r),
+            (Some(r), true) => fetch::deliver(vm, target, r),
+            (None, false) => xhr::fail(vm, target),
+            (None, true) => fetch::fail(vm, target),
+        }
+
+        unsafe { MUTATION_TARGET = core::ptr::null_mut(); }
+        for msg in self.engine.console_output() {
+            self.console.push(msg.clone());
+        }
+        self.engine.clear_console();
+        self.mutations.extend(bridge.mutations);
+        self.event_listeners.extend(bridge.event_listeners);
+        self.pending_http_requests.extend(bridge.pending_http_requests);
+        self.http_registry.extend(bridge.http_registry);
+        self.next_timer_id = bridge.next_timer_id;
+        self.timers.extend(bridge.timers);
+        self.engine.vm().userdata = core::ptr::null_mut();
+    }
+
+    /// Create a fresh DomBridge for one round of script execution.
+    fn new_bridge(&self, dom: &Dom, next_timer_id: u32) -> DomBridge {
+        DomBridge {
+            dom: dom as *const Dom,
+            mutations: Vec::new(),
+            event_listeners: Vec::new(),
+            next_virtual_id: -1,
+            virtual_nodes: Vec::new(),
+            pending_http_requests: Vec::new(),
+            timers: Vec::new(),
+            next_timer_id,
+            propagation_stopped: false,
+            pending_ws_connects: Vec::new(),
+            pending_ws_sends: Vec::new(),
+            pending_ws_closes: Vec::new(),
+            ws_registry: Vec::new(),
+            http_registry: Vec::new(),
+            page_url: self.page_url.clone(),
+            media_states: self.media_states.clone(),
+        }
+    }
+
     /// Apply recorded mutations to the real DOM.
-    /// Returns a map from virtual_id → real NodeId for newly created elements.
-    pub fn apply_mutations(&mut self, dom: &mut Dom) -> BTreeMap<i64, usize> {
+    /// Returns what they changed, for incremental restyle and relayout.
+    pub fn apply_mutations(&mut self, dom: &mut Dom) -> DomChanges {
         let mutations = core::mem::take(&mut self.mutations);
         let mut id_map: BTreeMap<i64, usize> = BTreeMap::new();
+        let mut changes = DomChanges::new();
 
         for m in &mutations {
             match m {
@@ -671,24 +890,33 @@ impl JsRuntime {
                 }
                 DomMutation::SetAttribute { node_id, name, value } => {
                     dom.set_attr(*node_id, name, value);
+                    changes.attr_changed(dom, *node_id, name);
                 }
                 DomMutation::RemoveAttribute { node_id, name } => {
                     dom.remove_attr(*node_id, name);
+                    changes.attr_changed(dom, *node_id, name);
                 }
                 DomMutation::SetTextContent { node_id, text } => {
+                    let old: Vec<usize> = dom.nodes.get(*node_id)
+                        .map(|n| n.children.clone())
+                        .unwrap_or_default();
                     dom.set_text(*node_id, text);
+                    changes.children_replaced(dom, *node_id, &old);
                 }
                 DomMutation::AppendChild { parent_id, child_id } => {
                     let real_parent = resolve_id(*parent_id, &id_map);
                     let real_child = resolve_id(*child_id, &id_map);
                     if let (Some(p), Some(c)) = (real_parent, real_child) {
+                        changes.child_removed(dom, c);
                         dom.append_child(p, c);
+                        changes.child_inserted(dom, p, c);
                     }
                 }
                 DomMutation::RemoveChild { parent_id, child_id } => {
                     let real_parent = resolve_id(*parent_id, &id_map);
                     let real_child = resolve_id(*child_id, &id_map);
                     if let (Some(p), Some(c)) = (real_parent, real_child) {
+                        changes.child_removed(dom, c);
                         dom.remove_child(p, c);
                     }
                 }
@@ -697,7 +925,9 @@ impl JsRuntime {
                     let real_new = resolve_id(*new_child_id, &id_map);
                     let real_ref = resolve_id(*ref_child_id, &id_map);
                     if let (Some(p), Some(n), Some(r)) = (real_parent, real_new, real_ref) {
+                        changes.child_removed(dom, n);
                         dom.insert_before(p, n, r);
+                        changes.child_inserted(dom, p, n);
                     }
                 }
                 DomMutation::ReplaceChild { parent_id, new_child_id, old_child_id } => {
@@ -705,14 +935,18 @@ impl JsRuntime {
                     let real_new = resolve_id(*new_child_id, &id_map);
                     let real_old = resolve_id(*old_child_id, &id_map);
                     if let (Some(p), Some(n), Some(o)) = (real_parent, real_new, real_old) {
+                        changes.child_removed(dom, o);
+                        changes.child_removed(dom, n);
                         dom.remove_child(p, o);
                         dom.append_child(p, n);
+                        changes.child_inserted(dom, p, n);
                     }
                 }
                 DomMutation::RemoveNode { node_id } => {
                     if let Some(real_id) = resolve_id(*node_id, &id_map) {
                         // Remove from parent.
                         if let Some(pid) = dom.nodes.get(real_id).and_then(|n| n.parent) {
+                            changes.child_removed(dom, real_id);
                             dom.remove_child(pid, real_id);
                         }
                     }
@@ -723,7 +957,7 @@ impl JsRuntime {
                         let children: Vec<usize> = dom.nodes.get(real_id)
                             .map(|n| n.children.clone())
                             .unwrap_or_default();
-                        for cid in children {
+                        for &cid in &children {
                             dom.remove_child(real_id, cid);
                         }
                         // Parse HTML fragment and adopt new children.
@@ -731,6 +965,7 @@ impl JsRuntime {
                             let fragment = crate::html::parse_fragment(html);
                             dom.adopt_children_from(real_id, &fragment);
                         }
+                        changes.children_replaced(dom, real_id, &children);
                     }
                 }
                 DomMutation::SetStyleProperty { node_id, property, value } => {
@@ -744,16 +979,22 @@ impl JsRuntime {
                             alloc::format!("{}; {}: {}", existing, property, value)
                         };
                         dom.set_attr(real_id, "style", &new_style);
+                        changes.attr_changed(dom, real_id, "style");
                     }
                 }
-                DomMutation::SetCookie { .. } => {
-                    // Cookie mutations do not modify the DOM tree.
-                    // The host application (e.g. surf) reads these via
-                    // `take_mutations()` and updates its cookie jar.
+                DomMutation::SetCookie { value } => {
+                    // Cookie writes do not modify the DOM tree; keep them for
+                    // the host, which collects them via `take_cookie_writes()`.
+                    self.cookie_writes.push(value.clone());
+                }
+                DomMutation::Media { node_id, command } => {
+                    // Playback requests go to the WebView's media host via
+                    // `take_media_commands()`.
+                    self.media_commands.push((*node_id, *command));
                 }
             }
         }
-        id_map
+        changes
     }
 
     /// Dispatch an event to matching listeners, bubbling up the DOM ancestor chain.
@@ -793,21 +1034,7 @@ impl JsRuntime {
         evt.set_property(String::from("cancelable"), JsValue::Bool(true));
 
         // Set up bridge for DOM access during callbacks.
-        let mut bridge = DomBridge {
-            dom: dom as *const Dom,
-            mutations: Vec::new(),
-            event_listeners: Vec::new(),
-            next_virtual_id: -1,
-            virtual_nodes: Vec::new(),
-            pending_http_requests: Vec::new(),
-            timers: Vec::new(),
-            next_timer_id: self.next_timer_id,
-            propagation_stopped: false,
-            pending_ws_connects: Vec::new(),
-            pending_ws_sends: Vec::new(),
-            pending_ws_closes: Vec::new(),
-            ws_registry: Vec::new(),
-        };
+        let mut bridge = self.new_bridge(dom, self.next_timer_id);
         self.engine.vm().userdata = &mut bridge as *mut DomBridge as *mut u8;
         unsafe { MUTATION_TARGET = &mut bridge.mutations as *mut Vec<DomMutation>; }
 
@@ -838,6 +1065,7 @@ impl JsRuntime {
         self.mutations.extend(bridge.mutations);
         self.event_listeners.extend(bridge.event_listeners);
         self.pending_http_requests.extend(bridge.pending_http_requests);
+        self.http_registry.extend(bridge.http_registry);
         self.next_timer_id = bridge.next_timer_id;
         self.timers.extend(bridge.timers);
         self.engine.vm().userdata = core::ptr::null_mut();
@@ -857,21 +1085,7 @@ impl JsRuntime {
             t.elapsed_ms += delta_ms;
             if t.elapsed_ms >= t.delay_ms {
                 // Timer is due — execute callback.
-                let mut bridge = DomBridge {
-                    dom: dom as *const Dom,
-                    mutations: Vec::new(),
-                    event_listeners: Vec::new(),
-                    next_virtual_id: -1,
-                    virtual_nodes: Vec::new(),
-                    pending_http_requests: Vec::new(),
-                    timers: Vec::new(),
-                    next_timer_id: self.next_timer_id,
-                    propagation_stopped: false,
-            pending_ws_connects: Vec::new(),
-            pending_ws_sends: Vec::new(),
-            pending_ws_closes: Vec::new(),
-            ws_registry: Vec::new(),
-                };
+                let mut bridge = self.new_bridge(dom, self.next_timer_id);
                 self.engine.vm().userdata = &mut bridge as *mut DomBridge as *mut u8;
                 unsafe { MUTATION_TARGET = &mut bridge.mutations as *mut Vec<DomMutation>; }
 
@@ -887,6 +1101,7 @@ impl JsRuntime {
                 self.mutations.extend(bridge.mutations);
                 self.event_listeners.extend(bridge.event_listeners);
                 self.pending_http_requests.extend(bridge.pending_http_requests);
+                self.http_registry.extend(bridge.http_registry);
                 self.next_timer_id = bridge.next_timer_id;
                 // New timers created during callback.
                 keep.extend(bridge.timers);
@@ -908,23 +1123,24 @@ impl JsRuntime {
 
     pub fn engine(&mut self) -> &mut JsEngine { &mut self.engine }
 
-    /// Register `@keyframes` animation starts for every node whose computed
-    /// style requests an animation that is not already running.
+    /// Start the `@keyframes` animations the computed styles request and
+    /// stop the ones they no longer list.
     ///
-    /// Call this after `execute_scripts()` / relayout when styles change.
+    /// Each `(node, name)` pair starts once: a finished animation does not
+    /// restart on later relayouts until the node stops requesting it.
+    /// Call this after every style resolve.
     pub fn start_animations(
         &mut self,
-        styles: &[crate::style::ComputedStyle],
+        styles: &[ComputedStyle],
     ) {
+        let mut requested: Vec<(NodeId, String)> = Vec::new();
         for (node_id, style) in styles.iter().enumerate() {
-            'anim: for adef in &style.animations {
+            for adef in &style.animations {
                 if adef.name.is_empty() || adef.duration_ms == 0 { continue; }
-                // Check if this animation is already running for this node.
-                for active in &self.active_animations {
-                    if active.node_id == node_id && active.keyframe_name == adef.name {
-                        continue 'anim;
-                    }
-                }
+                requested.push((node_id, adef.name.clone()));
+                let known = self.started_animations.iter()
+                    .any(|(id, name)| *id == node_id && *name == adef.name);
+                if known { continue; }
                 self.active_animations.push(ActiveAnimation {
                     node_id,
                     keyframe_name: adef.name.clone(),
@@ -938,6 +1154,64 @@ impl JsRuntime {
                 });
             }
         }
+        self.active_animations.retain(|a| {
+            requested.iter().any(|(id, name)| *id == a.node_id && *name == a.keyframe_name)
+        });
+        self.started_animations = requested;
+    }
+
+    /// Start CSS transitions for properties whose computed value changed.
+    ///
+    /// `before` holds per-node snapshots taken with `transition_values()`
+    /// before the restyle; `styles` are the new styles.  A changed property
+    /// starts a transition when the node's new style has a `transition`
+    /// entry for it.  A running transition that already heads to the new
+    /// value keeps running; any other one for that property is replaced,
+    /// starting from the snapshot (its current, possibly mid-way, value).
+    pub fn start_transitions(
+        &mut self,
+        before: &[(NodeId, [Declaration; 4])],
+        styles: &[ComputedStyle],
+    ) {
+        for (node_id, old) in before {
+            let style = match styles.get(*node_id) {
+                Some(s) => s,
+                None => continue,
+            };
+            let new = transition_values(style);
+            for (i, name) in TRANSITIONABLE.iter().enumerate() {
+                if same_value(&old[i].value, &new[i].value) { continue; }
+                let running = self.active_transitions.iter()
+                    .position(|t| t.node_id == *node_id && t.property == *name);
+                if let Some(pos) = running {
+                    if same_value(&self.active_transitions[pos].to_decl.value, &new[i].value) {
+                        continue;
+                    }
+                    self.active_transitions.swap_remove(pos);
+                }
+                let def = match transition_def(style, name) {
+                    Some(d) => d,
+                    None => continue,
+                };
+                self.active_transitions.push(ActiveTransition {
+                    node_id: *node_id,
+                    property: String::from(*name),
+                    duration_ms: def.duration_ms,
+                    timing: def.timing,
+                    delay_ms: def.delay_ms,
+                    elapsed_ms: 0,
+                    from_decl: Some(old[i].clone()),
+                    to_decl: new[i].clone(),
+                });
+            }
+        }
+    }
+
+    /// Drop all animation and transition state (new document).
+    pub fn reset_animations(&mut self) {
+        self.active_animations.clear();
+        self.active_transitions.clear();
+        self.started_animations.clear();
     }
 
     /// Advance all active animations and transitions by `delta_ms`.
@@ -980,24 +1254,24 @@ impl JsRuntime {
             };
             let t = apply_timing(anim.timing, t_raw).clamp(0, 1000);
 
-            if let Some(kf) = keyframe_sets.iter().find(|k| k.name == anim.keyframe_name) {
-                let decls = interpolate_keyframe(kf, t);
-                if !decls.is_empty() {
-                    overrides.push((anim.node_id, decls));
-                }
-            }
-
             let finished = if anim_elapsed >= dur {
                 anim.current_iteration += 1;
                 anim.iteration_count != 0 && anim.current_iteration >= anim.iteration_count
             } else {
                 false
             };
+            // A finished animation stops affecting the style
+            // (`animation-fill-mode: none`).
+            if finished { continue; }
 
-            if !finished {
-                any_active = true;
-                keep_anims.push(anim);
+            if let Some(kf) = keyframe_sets.iter().find(|k| k.name == anim.keyframe_name) {
+                let decls = interpolate_keyframe(kf, t);
+                if !decls.is_empty() {
+                    overrides.push((anim.node_id, decls));
+                }
             }
+            any_active = true;
+            keep_anims.push(anim);
         }
         self.active_animations = keep_anims;
 
@@ -1287,6 +1561,38 @@ fn extract_origin(url: &str) -> String {
     origin
 }
 
+/// Resolve a (possibly relative) request URL against the page URL.
+pub(crate) fn resolve_url(base: &str, url: &str) -> String {
+    if url.contains("://") || base.is_empty() {
+        return String::from(url);
+    }
+    if url.starts_with("//") {
+        let scheme_end = base.find("://").unwrap_or(0);
+        let mut out = String::from(&base[..scheme_end + 1]);
+        out.push_str(url);
+        return out;
+    }
+    let origin = extract_origin(base);
+    if url.starts_with('/') {
+        let mut out = origin;
+        out.push_str(url);
+        return out;
+    }
+    // Relative path: replace everything after the last '/' of the base path.
+    let path_start = origin.len();
+    let path_end = base[path_start..]
+        .find(|c| c == '?' || c == '#')
+        .map(|p| path_start + p)
+        .unwrap_or(base.len());
+    let dir_end = base[path_start..path_end].rfind('/').map(|p| path_start + p + 1);
+    let mut out = match dir_end {
+        Some(end) => String::from(&base[..end]),
+        None => { let mut o = origin; o.push('/'); o }
+    };
+    out.push_str(url);
+    out
+}
+
 // ═══════════════════════════════════════════════════════════
 // Native timer functions
 // ═══════════════════════════════════════════════════════════
@@ -1412,6 +1718,13 @@ fn interpolate_decl(
     use crate::css::CssValue;
 
     let from_val = from.map(|d| &d.value);
+    if to.property == crate::css::Property::Transform {
+        return Some(crate::css::Declaration {
+            property: to.property.clone(),
+            value: interpolate_transform_value(from_val, &to.value, t),
+            important: to.important,
+        });
+    }
     let blended = match (&from_val, &to.value) {
         (Some(CssValue::Number(a)), CssValue::Number(b)) => {
             CssValue::Number(lerp_i32(*a, *b, t))
@@ -1443,6 +1756,50 @@ fn interpolate_decl(
     })
 }
 
+/// Interpolate two `transform` values (`Keyword` lists or `None`).
+/// Values that do not parse switch to `to` at the end.
+fn interpolate_transform_value(
+    from: Option<&crate::css::CssValue>,
+    to: &crate::css::CssValue,
+    t: i32,
+) -> crate::css::CssValue {
+    use crate::css::CssValue;
+
+    let ops = |v: &CssValue| match v {
+        CssValue::Keyword(kw) => parse_transform(kw),
+        CssValue::None => Some(Vec::new()),
+        _ => None,
+    };
+    let from = match from {
+        Some(f) => f,
+        None => return to.clone(),
+    };
+    match (ops(from), ops(to)) {
+        (Some(a), Some(b)) => {
+            let mixed = interpolate_transform(&a, &b, t);
+            if mixed.is_empty() {
+                CssValue::None
+            } else {
+                CssValue::Keyword(transform_to_css(&mixed))
+            }
+        }
+        _ => if t >= 1000 { to.clone() } else { from.clone() },
+    }
+}
+
+/// Whether two computed values are the same (for transition triggering).
+fn same_value(a: &crate::css::CssValue, b: &crate::css::CssValue) -> bool {
+    use crate::css::CssValue;
+
+    match (a, b) {
+        (CssValue::Number(x), CssValue::Number(y)) => x == y,
+        (CssValue::Color(x), CssValue::Color(y)) => x == y,
+        (CssValue::Keyword(x), CssValue::Keyword(y)) => x == y,
+        (CssValue::None, CssValue::None) => true,
+        _ => false,
+    }
+}
+
 /// Linear interpolation for i32 fixed-point values.
 #[inline]
 fn lerp_i32(a: i32, b: i32, t: i32) -> i32 {
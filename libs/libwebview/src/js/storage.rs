@@ -1,9 +1,11 @@
 //! Native localStorage / sessionStorage host objects.
 //!
-//! `make_storage(origin, persistent)` creates a storage object backed by an
-//! in-memory JS object.  When `persistent = true` the data is additionally
-//! written to `/tmp/surf_ls_<sanitized-origin>.dat` on every mutation and
-//! loaded from that file at construction time.
+//! `make_storage(origin, persistent, profile_dir)` creates a storage object
+//! backed by an in-memory JS object.  When `persistent = true` the data is
+//! additionally written to `<profile_dir>/localstorage/<sanitized-origin>.dat`
+//! (or `/tmp/surf_ls_<sanitized-origin>.dat` when no profile directory is
+//! configured) on every mutation and loaded from that file at construction
+//! time.
 //!
 //! File format (one entry per line):
 //! ```text
@@ -34,13 +36,14 @@ use super::arg_string;
 ///   Used to derive the persistence file path when `persistent = true`.
 /// * `persistent` — when `true` the storage is loaded from and saved to disk;
 ///   when `false` (sessionStorage) data lives only in memory.
-pub fn make_storage(origin: &str, persistent: bool) -> JsValue {
+/// * `profile_dir` — directory holding the browser profile (may be empty).
+pub fn make_storage(origin: &str, persistent: bool, profile_dir: &str) -> JsValue {
     let mut obj = JsObject::new();
     obj.set(String::from("__data"), JsValue::Object(Rc::new(RefCell::new(JsObject::new()))));
 
     // For persistent (localStorage), derive the file path and pre-load data.
     if persistent && !origin.is_empty() {
-        let path = storage_path(origin);
+        let path = storage_path(origin, profile_dir);
         if let Ok(contents) = anyos_std::fs::read_to_string(&path) {
             load_entries_into(&obj, &contents);
         }
@@ -63,8 +66,18 @@ pub fn make_storage(origin: &str, persistent: bool) -> JsValue {
 /// Derive a safe filesystem path from `origin`.
 ///
 /// Characters outside `[A-Za-z0-9\-.]` are replaced with `_`.
-fn storage_path(origin: &str) -> String {
-    let mut path = String::from("/tmp/surf_ls_");
+fn storage_path(origin: &str, profile_dir: &str) -> String {
+    let mut path = if profile_dir.is_empty() {
+        String::from("/tmp/surf_ls_")
+    } else {
+        let mut dir = String::from(profile_dir.trim_end_matches('/'));
+        dir.push_str("/localstorage");
+        // Best effort: both levels may already exist.
+        anyos_std::fs::mkdir(profile_dir);
+        anyos_std::fs::mkdir(&dir);
+        dir.push('/');
+        dir
+    };
     for c in origin.chars() {
         match c {
             'A'..='Z' | 'a'..='z' | '0'..='9' | '-' | '.' => path.push(c),
@@ -75,6 +88,29 @@ fn storage_path(origin: &str) -> String {
     path
 }
 
+// ═══════════════════════════════════════════════════════════
+// Host inspection
+// ═══════════════════════════════════════════════════════════
+
+/// Read the persisted localStorage entries of `origin` (sorted by key).
+pub fn read_entries(origin: &str, profile_dir: &str) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    if let Ok(contents) = anyos_std::fs::read_to_string(&storage_path(origin, profile_dir)) {
+        for line in contents.lines() {
+            if let Some(tab_pos) = line.find('\t') {
+                entries.push((unescape(&line[..tab_pos]), unescape(&line[tab_pos + 1..])));
+            }
+        }
+    }
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    entries
+}
+
+/// Delete the persisted localStorage of `origin`.
+pub fn clear_entries(origin: &str, profile_dir: &str) {
+    anyos_std::fs::unlink(&storage_path(origin, profile_dir));
+}
+
 // ═══════════════════════════════════════════════════════════
 // Serialization helpers
 // ═══════════════════════════════════════════════════════════
@@ -20,7 +20,8 @@ use super::fetch;
 ///
 /// * `origin` — the page origin (e.g. `"https://example.com"`) used to key
 ///   the persistent localStorage file.
-pub fn make_window(_vm: &mut Vm, document: JsValue, origin: &str) -> JsValue {
+/// * `profile_dir` — browser profile directory holding the localStorage files.
+pub fn make_window(_vm: &mut Vm, document: JsValue, origin: &str, profile_dir: &str) -> JsValue {
     let mut obj = JsObject::new();
 
     obj.set(String::from("document"), document.clone());
@@ -102,8 +103,8 @@ pub fn make_window(_vm: &mut Vm, document: JsValue, origin: &str) -> JsValue {
     obj.set(String::from("performance"), perf);
 
     // Storage.
-    obj.set(String::from("localStorage"), storage::make_storage(origin, true));
-    obj.set(String::from("sessionStorage"), storage::make_storage(origin, false));
+    obj.set(String::from("localStorage"), storage::make_storage(origin, true, profile_dir));
+    obj.set(String::from("sessionStorage"), storage::make_storage(origin, false, profile_dir));
 
     // History.
     let history = JsValue::new_object();
@@ -1,19 +1,26 @@
 //! Native XMLHttpRequest host object.
 //!
 //! Implements the full XHR lifecycle as native methods.
-//! send() calls `__http_request` synchronously, then fires callbacks.
+//!
+//! Asynchronous requests (the default) are queued for the host via
+//! `http::queue_request`; `send()` returns immediately and the response
+//! is applied later by `deliver()` / `fail()` when the host calls
+//! `JsRuntime::http_response` / `http_failed`.  Synchronous requests
+//! (`open(m, url, false)`) and hosts that install `__http_handler` go
+//! through `__http_request` and fire all callbacks before `send()` returns.
 
 use alloc::rc::Rc;
 use alloc::string::String;
+use alloc::vec::Vec;
 use core::cell::RefCell;
 
 use libjs::JsValue;
 use libjs::Vm;
-use libjs::value::{JsObject, FnKind};
+use libjs::value::JsObject;
 use libjs::vm::native_fn;
 
-use super::arg_string;
-use super::http;
+use super::{arg_string, make_array};
+use super::{http, HttpResponse};
 
 /// Create the XMLHttpRequest constructor function.
 pub fn make_xhr_constructor() -> JsValue {
@@ -42,6 +49,8 @@ fn xhr_ctor(_vm: &mut Vm, _args: &[JsValue]) -> JsValue {
     obj.set(String::from("_async"), JsValue::Bool(true));
     obj.set(String::from("_headers"), JsValue::new_object());
     obj.set(String::from("_sent"), JsValue::Bool(false));
+    obj.set(String::from("_resp_headers"), JsValue::String(String::new()));
+    obj.set(String::from("_listeners"), JsValue::new_object());
 
     // Callbacks.
     obj.set(String::from("onreadystatechange"), JsValue::Null);
@@ -68,6 +77,8 @@ fn xhr_ctor(_vm: &mut Vm, _args: &[JsValue]) -> JsValue {
     obj.set(String::from("getResponseHeader"), native_fn("getResponseHeader", xhr_get_response_header));
     obj.set(String::from("getAllResponseHeaders"), native_fn("getAllResponseHeaders", xhr_get_all_response_headers));
     obj.set(String::from("overrideMimeType"), native_fn("overrideMimeType", xhr_noop));
+    obj.set(String::from("addEventListener"), native_fn("addEventListener", xhr_add_event_listener));
+    obj.set(String::from("removeEventListener"), native_fn("removeEventListener", xhr_remove_event_listener));
 
     JsValue::Object(Rc::new(RefCell::new(obj)))
 }
@@ -77,8 +88,8 @@ fn xhr_ctor(_vm: &mut Vm, _args: &[JsValue]) -> JsValue {
 // ═══════════════════════════════════════════════════════════
 
 fn xhr_open(vm: &mut Vm, args: &[JsValue]) -> JsValue {
-    let method = arg_string(args, 0);
-    let url = arg_string(args, 1);
+    let method = arg_string(args, 0).to_ascii_uppercase();
+    let url = http::resolve_request_url(vm, &arg_string(args, 1));
     let is_async = args.get(2).map(|v| v.to_boolean()).unwrap_or(true);
 
     set_this_prop(vm, "_method", JsValue::String(if method.is_empty() { String::from("GET") } else { method }));
@@ -91,8 +102,10 @@ fn xhr_open(vm: &mut Vm, args: &[JsValue]) -> JsValue {
     set_this_prop(vm, "statusText", JsValue::String(String::new()));
     set_this_prop(vm, "responseText", JsValue::String(String::new()));
     set_this_prop(vm, "response", JsValue::String(String::new()));
+    set_this_prop(vm, "_resp_headers", JsValue::String(String::new()));
 
-    fire_callback(vm, "onreadystatechange");
+    let this = vm.current_this.clone();
+    fire_event(vm, &this, "readystatechange");
     JsValue::Undefined
 }
 
@@ -105,22 +118,34 @@ fn xhr_set_request_header(vm: &mut Vm, args: &[JsValue]) -> JsValue {
 }
 
 fn xhr_send(vm: &mut Vm, args: &[JsValue]) -> JsValue {
-    // Check if already sent.
-    let sent = get_this_prop(vm, "_sent");
-    if sent.to_boolean() { return JsValue::Undefined; }
-    set_this_prop(vm, "_sent", JsValue::Bool(true));
+    // Callbacks may run script that clobbers vm.current_this — keep our own handle.
+    let this = vm.current_this.clone();
 
-    let method = get_this_prop(vm, "_method").to_js_string();
-    let url = get_this_prop(vm, "_url").to_js_string();
-    let body = arg_string(args, 0);
+    // Check if already sent.
+    if this.get_property("_sent").to_boolean() { return JsValue::Undefined; }
+    this.set_property(String::from("_sent"), JsValue::Bool(true));
+
+    let method = this.get_property("_method").to_js_string();
+    let url = this.get_property("_url").to_js_string();
+    let body = match args.first() {
+        Some(v) if !v.is_nullish() => v.to_js_string(),
+        _ => String::new(),
+    };
+
+    // Asynchronous path: hand the request to the host and return immediately.
+    if this.get_property("_async").to_boolean() && !http::has_sync_handler(vm) {
+        let headers = http::collect_headers(&this.get_property("_headers"));
+        if let Some(id) = http::queue_request(vm, method.clone(), url.clone(), headers, body.clone(), this.clone()) {
+            this.set_property(String::from("_req_id"), JsValue::Number(id as f64));
+            fire_event(vm, &this, "loadstart");
+            return JsValue::Undefined;
+        }
+    }
 
     // Serialize headers (simplified).
     let headers_str = String::from("{}");
 
-    // readyState = 2 (HEADERS_RECEIVED).
-    set_this_prop(vm, "readyState", JsValue::Number(2.0));
-    fire_callback(vm, "onreadystatechange");
-    fire_callback(vm, "onloadstart");
+    fire_event(vm, &this, "loadstart");
 
     // Perform the HTTP request via the bridge.
     let result = http::http_request(vm, &[
@@ -130,48 +155,144 @@ fn xhr_send(vm: &mut Vm, args: &[JsValue]) -> JsValue {
         JsValue::String(body),
     ]);
 
-    // readyState = 3 (LOADING).
-    set_this_prop(vm, "readyState", JsValue::Number(3.0));
-    fire_callback(vm, "onreadystatechange");
-    fire_callback(vm, "onprogress");
-
-    // Process result.
     let status = result.get_property("status").to_number();
     let status_text = result.get_property("statusText").to_js_string();
     let resp_body = result.get_property("body").to_js_string();
+    complete(vm, &this, status, status_text, resp_body, String::new());
 
-    set_this_prop(vm, "status", JsValue::Number(status));
-    set_this_prop(vm, "statusText", JsValue::String(status_text));
-    set_this_prop(vm, "responseText", JsValue::String(resp_body.clone()));
-    set_this_prop(vm, "response", JsValue::String(resp_body));
+    JsValue::Undefined
+}
+
+/// Apply an async response delivered by the host to the XHR object `this`.
+pub fn deliver(vm: &mut Vm, this: &JsValue, resp: &HttpResponse) {
+    // Ignore responses for requests that were aborted or re-opened meanwhile.
+    if !this.get_property("_sent").to_boolean() { return; }
+    let mut raw_headers = String::new();
+    for (name, value) in &resp.headers {
+        raw_headers.push_str(&name.to_ascii_lowercase());
+        raw_headers.push_str(": ");
+        raw_headers.push_str(value);
+        raw_headers.push_str("\r\n");
+    }
+    complete(
+        vm,
+        this,
+        resp.status as f64,
+        resp.status_text.clone(),
+        resp.body.clone(),
+        raw_headers,
+    );
+}
+
+/// Report a network-level failure for the XHR object `this`.
+pub fn fail(vm: &mut Vm, this: &JsValue) {
+    if !this.get_property("_sent").to_boolean() { return; }
+    this.set_property(String::from("status"), JsValue::Number(0.0));
+    this.set_property(String::from("readyState"), JsValue::Number(4.0));
+    fire_event(vm, this, "readystatechange");
+    fire_event(vm, this, "error");
+    fire_event(vm, this, "loadend");
+}
+
+/// Walk `this` through HEADERS_RECEIVED → LOADING → DONE and fire the
+/// matching callbacks for a completed response.
+fn complete(vm: &mut Vm, this: &JsValue, status: f64, status_text: String, body: String, raw_headers: String) {
+    this.set_property(String::from("_resp_headers"), JsValue::String(raw_headers));
+    this.set_property(String::from("responseURL"), this.get_property("_url"));
+
+    // readyState = 2 (HEADERS_RECEIVED).
+    this.set_property(String::from("status"), JsValue::Number(status));
+    this.set_property(String::from("statusText"), JsValue::String(status_text));
+    this.set_property(String::from("readyState"), JsValue::Number(2.0));
+    fire_event(vm, this, "readystatechange");
+
+    // readyState = 3 (LOADING).
+    this.set_property(String::from("readyState"), JsValue::Number(3.0));
+    fire_event(vm, this, "readystatechange");
+    fire_event(vm, this, "progress");
+
+    this.set_property(String::from("responseText"), JsValue::String(body.clone()));
+    let response = if this.get_property("responseType").to_js_string() == "json" {
+        parse_json(vm, body)
+    } else {
+        JsValue::String(body)
+    };
+    this.set_property(String::from("response"), response);
 
     // readyState = 4 (DONE).
-    set_this_prop(vm, "readyState", JsValue::Number(4.0));
-    fire_callback(vm, "onreadystatechange");
+    this.set_property(String::from("readyState"), JsValue::Number(4.0));
+    fire_event(vm, this, "readystatechange");
 
-    if status >= 200.0 && status < 400.0 {
-        fire_callback(vm, "onload");
+    // HTTP error statuses still complete with `load`; only network failures
+    // (status 0) are reported as `error`.
+    if status > 0.0 {
+        fire_event(vm, this, "load");
     } else {
-        fire_callback(vm, "onerror");
+        fire_event(vm, this, "error");
     }
-    fire_callback(vm, "onloadend");
+    fire_event(vm, this, "loadend");
+}
 
-    JsValue::Undefined
+/// Parse a JSON body via the global `JSON.parse` (for `responseType = "json"`).
+fn parse_json(vm: &mut Vm, body: String) -> JsValue {
+    let parse_fn = vm.get_global("JSON").get_property("parse");
+    if let JsValue::Function(_) = parse_fn {
+        return vm.call_value(&parse_fn, &[JsValue::String(body)], JsValue::Undefined);
+    }
+    JsValue::Null
 }
 
 fn xhr_abort(vm: &mut Vm, _args: &[JsValue]) -> JsValue {
+    let this = vm.current_this.clone();
+    // A late response for an aborted request is dropped by `deliver()`.
     set_this_prop(vm, "readyState", JsValue::Number(0.0));
     set_this_prop(vm, "_sent", JsValue::Bool(false));
-    fire_callback(vm, "onabort");
+    fire_event(vm, &this, "abort");
     JsValue::Undefined
 }
 
-fn xhr_get_response_header(_vm: &mut Vm, _args: &[JsValue]) -> JsValue {
-    JsValue::Null
+fn xhr_get_response_header(vm: &mut Vm, args: &[JsValue]) -> JsValue {
+    let name = arg_string(args, 0).to_ascii_lowercase();
+    let raw = get_this_prop(vm, "_resp_headers").to_js_string();
+    let values: Vec<&str> = raw.split("\r\n")
+        .filter_map(|line| line.split_once(": "))
+        .filter(|(n, _)| *n == name)
+        .map(|(_, v)| v)
+        .collect();
+    if values.is_empty() {
+        JsValue::Null
+    } else {
+        JsValue::String(values.join(", "))
+    }
+}
+
+fn xhr_get_all_response_headers(vm: &mut Vm, _args: &[JsValue]) -> JsValue {
+    get_this_prop(vm, "_resp_headers")
 }
 
-fn xhr_get_all_response_headers(_vm: &mut Vm, _args: &[JsValue]) -> JsValue {
-    JsValue::String(String::new())
+/// `xhr.addEventListener(type, fn)` — listeners run after the `on<type>` handler.
+fn xhr_add_event_listener(vm: &mut Vm, args: &[JsValue]) -> JsValue {
+    let event = arg_string(args, 0);
+    let cb = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+    if !cb.is_function() { return JsValue::Undefined; }
+    let listeners = get_this_prop(vm, "_listeners");
+    let list = listeners.get_property(&event);
+    if let JsValue::Array(arr) = &list {
+        arr.borrow_mut().push(cb);
+    } else {
+        listeners.set_property(event, make_array(alloc::vec![cb]));
+    }
+    JsValue::Undefined
+}
+
+fn xhr_remove_event_listener(vm: &mut Vm, args: &[JsValue]) -> JsValue {
+    let event = arg_string(args, 0);
+    let cb = args.get(1).cloned().unwrap_or(JsValue::Undefined);
+    let list = get_this_prop(vm, "_listeners").get_property(&event);
+    if let JsValue::Array(arr) = &list {
+        arr.borrow_mut().elements.retain(|l| !l.strict_eq(&cb));
+    }
+    JsValue::Undefined
 }
 
 fn xhr_noop(_vm: &mut Vm, _args: &[JsValue]) -> JsValue { JsValue::Undefined }
@@ -193,15 +314,25 @@ fn set_this_prop(vm: &Vm, name: &str, val: JsValue) {
     }
 }
 
-/// Fire a callback stored as a property on `this`.
-fn fire_callback(vm: &mut Vm, name: &str) {
-    let cb = get_this_prop(vm, name);
-    if let JsValue::Function(f) = cb {
-        let kind = f.borrow().kind.clone();
-        if let FnKind::Native(native) = kind {
-            native(vm, &[]);
-        }
-        // For bytecode functions we'd need to call through the VM.
-        // Simplified: only native callbacks work for now.
+/// Fire the `on<event>` handler and any `addEventListener` listeners of `xhr`.
+fn fire_event(vm: &mut Vm, xhr: &JsValue, event: &str) {
+    let mut handler_name = String::from("on");
+    handler_name.push_str(event);
+    let mut callbacks = Vec::new();
+    let handler = xhr.get_property(&handler_name);
+    if handler.is_function() {
+        callbacks.push(handler);
+    }
+    if let JsValue::Array(arr) = xhr.get_property("_listeners").get_property(event) {
+        callbacks.extend(arr.borrow().elements.iter().cloned());
+    }
+    if callbacks.is_empty() { return; }
+
+    let evt = JsValue::new_object();
+    evt.set_property(String::from("type"), JsValue::String(String::from(event)));
+    evt.set_property(String::from("target"), xhr.clone());
+    evt.set_property(String::from("currentTarget"), xhr.clone());
+    for cb in &callbacks {
+        vm.call_value(cb, &[evt.clone()], xhr.clone());
     }
 }
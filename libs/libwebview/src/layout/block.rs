@@ -7,13 +7,14 @@ use crate::dom::{Dom, NodeId, Tag};
 use crate::style::{
     ComputedStyle, Display, BoxSizing, OverflowVal, Visibility, Position,
 };
+use crate::media::MediaKind;
 use crate::ImageCache;
 
 use super::{
     LayoutBox, BoxType,
     font_size_px, is_bold, is_italic, edges_from,
     link_href, list_marker_for, image_dimensions,
-    layout_children,
+    media_dimensions, media_box, layout_children,
 };
 use super::flex::layout_flex;
 use super::grid::layout_grid;
@@ -43,6 +44,7 @@ pub fn build_block(dom: &Dom, styles: &[ComputedStyle], node_id: NodeId, availab
         || matches!(style.overflow_y, OverflowVal::Hidden);
     bx.visibility_hidden = matches!(style.visibility, Visibility::Hidden | Visibility::Collapse);
     bx.opacity = style.opacity;
+    bx.transform = style.transform.clone();
     bx.margin = edges_from(
         style.margin_top, style.margin_right,
         style.margin_bottom, style.margin_left,
@@ -147,6 +149,21 @@ pub fn build_block(dom: &Dom, styles: &[ComputedStyle], node_id: NodeId, availab
         return bx;
     }
 
+    // Handle <video> / <audio> as replaced elements; their <source> and
+    // fallback children are not rendered.
+    let media_kind = match tag {
+        Some(Tag::Video) => Some(MediaKind::Video),
+        Some(Tag::Audio) => Some(MediaKind::Audio),
+        _ => None,
+    };
+    if let Some(kind) = media_kind {
+        let (mw, mh) = media_dimensions(dom, node_id, kind, bx.width, images);
+        bx.media = Some(media_box(dom, node_id, kind));
+        bx.height = mh + bx.padding.top + bx.padding.bottom + border2;
+        bx.width = mw + bx.padding.left + bx.padding.right + border2;
+        return bx;
+    }
+
     // Inner (content) width for child layout.
     let inner_w = bx.width - bx.padding.left - bx.padding.right - border2;
     let inner_w = inner_w.max(0);
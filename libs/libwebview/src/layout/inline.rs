@@ -5,12 +5,13 @@ use alloc::vec::Vec;
 
 use crate::dom::{Dom, NodeId, NodeType, Tag};
 use crate::style::{ComputedStyle, Display, Position, WhiteSpace, TextDeco, TextTransform, TextAlignVal};
+use crate::fonts::TextFont;
 use crate::ImageCache;
 
 use super::{
     LayoutBox, BoxType, FormFieldKind,
     font_size_px, is_bold, is_italic, inherited_link,
-    image_dimensions, measure_text, parse_attr_int,
+    image_dimensions, measure_text, text_run, parse_attr_int,
     is_ascii_ws, ascii_lower_str, size_attr_width,
     apply_text_transform,
 };
@@ -171,11 +172,11 @@ fn collect_inline_fragments(
 
             let start_idx = out.len();
             if style.white_space == WhiteSpace::Pre || style.white_space == WhiteSpace::PreWrap {
-                emit_preformatted_fragments(&transformed, fs, bold, italic, color, link, deco, out);
+                emit_preformatted_fragments(&transformed, &style.font_chain, fs, bold, italic, color, link, deco, out);
             } else if style.white_space == WhiteSpace::Nowrap {
-                emit_nowrap_fragments(&transformed, fs, bold, italic, color, link, deco, out);
+                emit_nowrap_fragments(&transformed, &style.font_chain, fs, bold, italic, color, link, deco, out);
             } else {
-                emit_word_fragments(&transformed, fs, bold, italic, color, link, deco, out);
+                emit_word_fragments(&transformed, &style.font_chain, fs, bold, italic, color, link, deco, out);
             }
             // Propagate inherited background color to newly emitted text fragments.
             if inherited_bg != 0 {
@@ -230,23 +231,35 @@ fn collect_inline_fragments(
                 return;
             }
 
-            // Handle <textarea>
+            // Handle <textarea> — grows with its initial content, from
+            // `rows` up to the height cap.
             if *tag == Tag::Textarea {
                 let cols = dom.attr(node_id, "cols").and_then(parse_attr_int).unwrap_or(20);
                 let rows = dom.attr(node_id, "rows").and_then(parse_attr_int).unwrap_or(2);
+                let value = crate::forms::textarea_default(dom, node_id);
+                let lines = crate::forms::line_count(&value) as i32;
                 let w = (cols * 8).max(80).min(600);
-                let h = (rows * 18).max(28).min(400);
+                let h = (rows.max(lines) * 18).max(28).min(400);
                 let mut ta = LayoutBox::new(Some(node_id), BoxType::Inline);
                 ta.form_field = Some(FormFieldKind::Textarea);
+                ta.form_value = Some(value);
                 out.push(InlineFragment { width: w, height: h, layout_box: ta, breaks_after: false });
                 return;
             }
 
-            // Handle <select>
+            // Handle <select> — sized to its widest option plus the arrow.
             if *tag == Tag::Select {
-                let w = 150;
+                let options = crate::forms::select_options(dom, node_id);
                 let mut sel = LayoutBox::new(Some(node_id), BoxType::Inline);
-                sel.form_field = Some(FormFieldKind::TextInput);
+                sel.form_field = Some(FormFieldKind::Select);
+                sel.form_state = crate::forms::initial_selection(dom, &options);
+                let mut text_w = 0;
+                for &opt in &options {
+                    let label = crate::forms::option_label(dom, opt);
+                    text_w = text_w.max(measure_text(&label, 14, false).0);
+                    sel.form_options.push(label);
+                }
+                let w = (text_w + 40).max(60).min(400);
                 out.push(InlineFragment { width: w, height: 28, layout_box: sel, breaks_after: false });
                 return;
             }
@@ -298,6 +311,7 @@ fn collect_inline_fragments(
 /// Emit fragments for nowrap text (no line breaking within words or between them).
 fn emit_nowrap_fragments(
     text: &str,
+    chain: &[TextFont],
     font_size: i32,
     bold: bool,
     italic: bool,
@@ -308,8 +322,7 @@ fn emit_nowrap_fragments(
 ) {
     let collapsed = collapse_whitespace(text);
     if collapsed.is_empty() { return; }
-    let (w, h) = measure_text(&collapsed, font_size, bold);
-    let mut wbox = LayoutBox::new_text(collapsed, font_size, bold, italic, color);
+    let (w, h, mut wbox) = text_run(collapsed, chain, font_size, bold, italic, color);
     wbox.link_url = link;
     wbox.text_decoration = deco;
     out.push(InlineFragment { width: w, height: h, layout_box: wbox, breaks_after: false });
@@ -365,11 +378,13 @@ fn emit_input_fragment(
         "checkbox" => {
             let mut cb = LayoutBox::new(Some(node_id), BoxType::Inline);
             cb.form_field = Some(FormFieldKind::Checkbox);
+            cb.form_state = dom.attr(node_id, "checked").is_some() as u32;
             out.push(InlineFragment { width: 20, height: 20, layout_box: cb, breaks_after: false });
         }
         "radio" => {
             let mut rb = LayoutBox::new(Some(node_id), BoxType::Inline);
             rb.form_field = Some(FormFieldKind::Radio);
+            rb.form_state = dom.attr(node_id, "checked").is_some() as u32;
             out.push(InlineFragment { width: 20, height: 20, layout_box: rb, breaks_after: false });
         }
         "submit" | "button" | "reset" => {
@@ -383,6 +398,14 @@ fn emit_input_fragment(
             btn.color = css_fg;
             out.push(InlineFragment { width: w, height: 28, layout_box: btn, breaks_after: false });
         }
+        "file" => {
+            let label = if dom.attr(node_id, "multiple").is_some() { "Choose Files…" } else { "Choose File…" };
+            let (bw, _) = measure_text(label, 14, false);
+            let mut fb = LayoutBox::new(Some(node_id), BoxType::Inline);
+            fb.form_field = Some(FormFieldKind::File);
+            fb.text = Some(String::from(label));
+            out.push(InlineFragment { width: (bw + 24).max(120), height: 28, layout_box: fb, breaks_after: false });
+        }
         "password" => {
             let w = size_attr_width(dom, node_id, 200);
             let mut tf = LayoutBox::new(Some(node_id), BoxType::Inline);
@@ -434,6 +457,7 @@ fn emit_button_fragment(
 /// Emit word fragments for normal text (collapse whitespace, break on words).
 fn emit_word_fragments(
     text: &str,
+    chain: &[TextFont],
     font_size: i32,
     bold: bool,
     italic: bool,
@@ -474,8 +498,7 @@ fn emit_word_fragments(
 
     if words.is_empty() {
         if has_leading_space {
-            let (sw, sh) = measure_text(" ", font_size, bold);
-            let mut space_box = LayoutBox::new_text(String::from(" "), font_size, bold, italic, color);
+            let (sw, sh, mut space_box) = text_run(String::from(" "), chain, font_size, bold, italic, color);
             space_box.link_url = link.clone();
             space_box.text_decoration = deco;
             out.push(InlineFragment {
@@ -489,8 +512,7 @@ fn emit_word_fragments(
     }
 
     if has_leading_space {
-        let (sw, sh) = measure_text(" ", font_size, bold);
-        let mut space_box = LayoutBox::new_text(String::from(" "), font_size, bold, italic, color);
+        let (sw, sh, mut space_box) = text_run(String::from(" "), chain, font_size, bold, italic, color);
         space_box.link_url = link.clone();
         space_box.text_decoration = deco;
         out.push(InlineFragment {
@@ -502,8 +524,7 @@ fn emit_word_fragments(
     }
 
     for (wi, word) in words.iter().enumerate() {
-        let (ww, wh) = measure_text(word, font_size, bold);
-        let mut wbox = LayoutBox::new_text(String::from(*word), font_size, bold, italic, color);
+        let (ww, wh, mut wbox) = text_run(String::from(*word), chain, font_size, bold, italic, color);
         wbox.link_url = link.clone();
         wbox.text_decoration = deco;
         out.push(InlineFragment {
@@ -515,8 +536,7 @@ fn emit_word_fragments(
 
         let need_space = wi + 1 < words.len() || has_trailing_space;
         if need_space {
-            let (sw, sh) = measure_text(" ", font_size, bold);
-            let mut sbox = LayoutBox::new_text(String::from(" "), font_size, bold, italic, color);
+            let (sw, sh, mut sbox) = text_run(String::from(" "), chain, font_size, bold, italic, color);
             sbox.link_url = link.clone();
             sbox.text_decoration = deco;
             out.push(InlineFragment {
@@ -532,6 +552,7 @@ fn emit_word_fragments(
 /// Emit fragments for preformatted text (preserve whitespace, break on \n).
 fn emit_preformatted_fragments(
     text: &str,
+    chain: &[TextFont],
     font_size: i32,
     bold: bool,
     italic: bool,
@@ -552,8 +573,7 @@ fn emit_preformatted_fragments(
 
         if start < i {
             if let Ok(seg) = core::str::from_utf8(&bytes[start..i]) {
-                let (sw, sh) = measure_text(seg, font_size, bold);
-                let mut sbox = LayoutBox::new_text(String::from(seg), font_size, bold, italic, color);
+                let (sw, sh, mut sbox) = text_run(String::from(seg), chain, font_size, bold, italic, color);
                 sbox.link_url = link.clone();
                 sbox.text_decoration = deco;
                 out.push(InlineFragment {
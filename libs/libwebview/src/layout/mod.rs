@@ -24,6 +24,9 @@ use crate::style::{
     ComputedStyle, Display, FontWeight, FontStyleVal, TextAlignVal,
     ListStyle, TextDeco, TextTransform, FloatVal, Position, ClearVal,
 };
+use crate::transform::TransformOp;
+use crate::fonts::{self, TextFont};
+use crate::media::{MediaKind, MediaState, CONTROLS_HEIGHT};
 use crate::ImageCache;
 
 // Re-export sub-module public items.
@@ -51,6 +54,9 @@ pub struct LayoutBox {
     pub font_size: i32,
     pub bold: bool,
     pub italic: bool,
+    /// Font of a text run picked from the `font-family` chain
+    /// (`None` = system font for `bold` / `italic`).
+    pub font: Option<TextFont>,
     pub color: u32,
     pub bg_color: u32,
     pub border_color: u32,
@@ -64,21 +70,32 @@ pub struct LayoutBox {
     pub image_src: Option<String>,
     pub image_width: Option<i32>,
     pub image_height: Option<i32>,
+    /// Media element (`<audio>` / `<video>`) painted by this box.
+    pub media: Option<MediaBox>,
     /// Form field kind (for `<input>`, `<button>`, `<textarea>`, `<select>`).
     pub form_field: Option<FormFieldKind>,
     /// Placeholder text for form text inputs.
     pub form_placeholder: Option<String>,
-    /// Default value for form text inputs.
+    /// Default value for form text inputs and textareas.
     pub form_value: Option<String>,
+    /// Option labels of a `<select>`.
+    pub form_options: Vec<String>,
+    /// Initial checked flag (checkbox / radio) or selected option index (select).
+    pub form_state: u32,
     /// If true, children that extend outside this box should be clipped.
     pub overflow_hidden: bool,
     /// If true, this box is invisible but still takes up space.
     pub visibility_hidden: bool,
     /// Opacity: 0..255 (255 = fully opaque).
     pub opacity: i32,
+    /// 2D `transform` functions (empty = none), origin at the box centre.
+    pub transform: Vec<TransformOp>,
     /// If true, this box is `position:fixed` and its x/y are viewport-relative.
     /// The renderer will ignore accumulated parent offsets and use x/y directly.
     pub is_fixed: bool,
+    /// Available width this box was laid out at if it is a normal-flow
+    /// block, so it can be rebuilt in place (see `relayout_nodes`).
+    pub flow_width: Option<i32>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -90,6 +107,21 @@ pub enum BoxType {
     LineBox,
 }
 
+/// Replaced-element data of an `<audio>` / `<video>` box.
+#[derive(Clone)]
+pub struct MediaBox {
+    pub kind: MediaKind,
+    /// Whether the `controls` attribute is present.
+    pub controls: bool,
+    /// `poster` image URL of a video.
+    pub poster: Option<String>,
+    /// Playback state, patched in place by the WebView (no relayout).
+    pub state: MediaState,
+    /// Whether the host supplied a video frame (stored in the image cache
+    /// under `media::frame_key()`).
+    pub has_frame: bool,
+}
+
 /// Kind of HTML form field for interactive rendering.
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum FormFieldKind {
@@ -101,6 +133,9 @@ pub enum FormFieldKind {
     Hidden,
     ButtonEl,
     Textarea,
+    Select,
+    /// `<input type="file">`: a native button that opens the host's file picker.
+    File,
 }
 
 #[derive(Clone, Copy, Default)]
@@ -132,6 +167,7 @@ impl LayoutBox {
             font_size: 16,
             bold: false,
             italic: false,
+            font: None,
             color: 0xFF000000,
             bg_color: 0,
             border_color: 0,
@@ -144,13 +180,18 @@ impl LayoutBox {
             image_src: None,
             image_width: None,
             image_height: None,
+            media: None,
             form_field: None,
             form_placeholder: None,
             form_value: None,
+            form_options: Vec::new(),
+            form_state: 0,
             overflow_hidden: false,
             visibility_hidden: false,
             opacity: 255,
+            transform: Vec::new(),
             is_fixed: false,
+            flow_width: None,
         }
     }
 
@@ -169,12 +210,41 @@ impl LayoutBox {
 // Shared helpers (pub(super) for sub-modules)
 // ---------------------------------------------------------------------------
 
-pub(super) fn measure_text(text: &str, font_size: i32, bold: bool) -> (i32, i32) {
+pub(crate) fn measure_text(text: &str, font_size: i32, bold: bool) -> (i32, i32) {
     let font_id: u16 = if bold { 1 } else { 0 };
     let (w, h) = libanyui_client::measure_text(text, font_id, font_size as u16);
     (w as i32, h as i32)
 }
 
+/// Width/height of `text` in `font`, or in the system font if `None`.
+pub(crate) fn measure_in(text: &str, font_size: i32, bold: bool, font: Option<TextFont>) -> (i32, i32) {
+    match font {
+        Some(f) => {
+            let (w, h) = libfont_client::measure(f.font_id as u32, font_size as u16, text);
+            // Synthetic bold is drawn twice, one pixel apart.
+            (w as i32 + f.synth_bold as i32, h as i32)
+        }
+        None => measure_text(text, font_size, bold),
+    }
+}
+
+/// Build a measured text box for `text` in the first font of `chain` that
+/// covers it.  Returns (width, height, box).
+pub(super) fn text_run(
+    text: String,
+    chain: &[TextFont],
+    font_size: i32,
+    bold: bool,
+    italic: bool,
+    color: u32,
+) -> (i32, i32, LayoutBox) {
+    let font = fonts::pick(chain, &text);
+    let (w, h) = measure_in(&text, font_size, bold, font);
+    let mut b = LayoutBox::new_text(text, font_size, bold, italic, color);
+    b.font = font;
+    (w, h, b)
+}
+
 pub(super) fn font_size_px(style: &ComputedStyle) -> i32 {
     let s = style.font_size;
     if s <= 0 { 16 } else { s }
@@ -260,11 +330,51 @@ fn format_decimal(out: &mut String, mut n: u32) {
 
 pub(super) fn image_dimensions(dom: &Dom, node_id: NodeId, max_width: i32, images: &ImageCache) -> (i32, i32) {
     // Get natural dimensions from image cache (actual decoded image size).
-    let src = dom.attr(node_id, "src");
-    let natural = src.and_then(|s| images.get_ref(s)).map(|e| {
+    let natural = natural_size(dom.attr(node_id, "src"), images);
+    replaced_dimensions(dom, node_id, natural, max_width)
+}
+
+/// Content size of an `<audio>` / `<video>` element.  A video sizes like an
+/// image whose natural size is that of its poster; an audio element is just
+/// its controls bar, and takes no space without one.
+pub(super) fn media_dimensions(dom: &Dom, node_id: NodeId, kind: MediaKind, max_width: i32, images: &ImageCache) -> (i32, i32) {
+    match kind {
+        MediaKind::Audio if dom.attr(node_id, "controls").is_some() => {
+            let w = dom.attr(node_id, "width").and_then(parse_attr_int).unwrap_or(300);
+            (if max_width > 0 { w.min(max_width) } else { w }, CONTROLS_HEIGHT)
+        }
+        MediaKind::Audio => (0, 0),
+        MediaKind::Video => {
+            let natural = natural_size(dom.attr(node_id, "poster"), images);
+            replaced_dimensions(dom, node_id, natural, max_width)
+        }
+    }
+}
+
+/// Build the `MediaBox` of an `<audio>` / `<video>` element.
+pub(super) fn media_box(dom: &Dom, node_id: NodeId, kind: MediaKind) -> MediaBox {
+    MediaBox {
+        kind,
+        controls: dom.attr(node_id, "controls").is_some(),
+        poster: match kind {
+            MediaKind::Video => dom.attr(node_id, "poster").map(String::from),
+            MediaKind::Audio => None,
+        },
+        state: MediaState::IDLE,
+        has_frame: false,
+    }
+}
+
+/// Decoded size of the cached image `src`, if any.
+fn natural_size(src: Option<&str>, images: &ImageCache) -> Option<(i32, i32)> {
+    src.and_then(|s| images.get_ref(s)).map(|e| {
         (e.width.min(65535) as i32, e.height.min(65535) as i32)
-    });
+    })
+}
 
+/// Size of a replaced element from its `width` / `height` attributes and
+/// natural size, scaled down to `max_width`.
+fn replaced_dimensions(dom: &Dom, node_id: NodeId, natural: Option<(i32, i32)>, max_width: i32) -> (i32, i32) {
     // HTML attributes override natural size; fall back to natural; then 300x150.
     let w = dom.attr(node_id, "width").and_then(parse_attr_int)
         .or(natural.map(|(w, _)| w))
@@ -371,6 +481,176 @@ pub fn layout(dom: &Dom, styles: &[ComputedStyle], viewport_width: i32, images:
     root
 }
 
+// ---------------------------------------------------------------------------
+// Incremental relayout
+// ---------------------------------------------------------------------------
+
+/// Most changed nodes `relayout_nodes` handles before a full layout is cheaper.
+const MAX_RELAYOUT_NODES: usize = 32;
+
+/// Lay out the boxes of `nodes` (DOM nodes whose style, attributes or
+/// children changed) again inside the existing tree `root`, which was built
+/// by `layout()` with the same `viewport_width`.
+///
+/// The nearest normal-flow block around each node is rebuilt at the width it
+/// was laid out with.  If the new box has the old size and margins it takes
+/// the old one's place and nothing around it moves; otherwise the next
+/// enclosing block is tried.  Returns the document Y ranges `[y0, y1)` whose
+/// pixels changed, or `None` if a change reaches `root` and the whole
+/// document must be laid out again (`root` may be partly updated then).
+pub fn relayout_nodes(
+    root: &mut LayoutBox,
+    dom: &Dom,
+    styles: &[ComputedStyle],
+    nodes: &[NodeId],
+    images: &ImageCache,
+    viewport_width: i32,
+) -> Option<Vec<(i32, i32)>> {
+    if nodes.len() > MAX_RELAYOUT_NODES {
+        return None;
+    }
+    let body_id = root.node_id?;
+    let mut damage: Vec<(i32, i32)> = Vec::new();
+    for &node in nodes {
+        if node >= dom.nodes.len() || node >= styles.len() {
+            return None;
+        }
+        // The node and its ancestors, nearest first.
+        let mut chain: Vec<NodeId> = Vec::new();
+        let mut cur = Some(node);
+        while let Some(id) = cur {
+            chain.push(id);
+            cur = dom.get(id).parent;
+        }
+        match chain.iter().position(|&id| id == body_id) {
+            // <body> itself or an ancestor of it: everything may move.
+            Some(0) => return None,
+            Some(_) => {}
+            None if node_contains(dom, node, body_id) => return None,
+            // <head> content or a detached node: nothing is painted.
+            None => continue,
+        }
+
+        // Child indices from `root` down to the deepest normal-flow block
+        // in the chain.
+        let mut path: Vec<usize> = Vec::new();
+        let mut bx: &LayoutBox = root;
+        'descend: loop {
+            for (i, child) in bx.children.iter().enumerate() {
+                if child.flow_width.is_some() && child.node_id.map_or(false, |id| chain.contains(&id)) {
+                    path.push(i);
+                    bx = child;
+                    continue 'descend;
+                }
+            }
+            break;
+        }
+
+        let mut depth = path.len();
+        loop {
+            if depth == 0 {
+                return None;
+            }
+            // Document Y of the box's parent; transformed ancestors paint
+            // elsewhere.
+            let mut parent_y = root.y;
+            let mut parent: &LayoutBox = root;
+            for &i in &path[..depth - 1] {
+                parent = &parent.children[i];
+                if !parent.transform.is_empty() {
+                    return None;
+                }
+                parent_y += parent.y;
+            }
+            if !parent.transform.is_empty() {
+                return None;
+            }
+            let old = &parent.children[path[depth - 1]];
+            if let Some(mut new) = rebuild_flow_box(old, dom, styles, images, viewport_width) {
+                new.x = old.x;
+                new.y = old.y;
+                new.flow_width = old.flow_width;
+                let before = subtree_y_extent(old, parent_y + old.y)?;
+                let after = subtree_y_extent(&new, parent_y + new.y)?;
+                let mut target: &mut LayoutBox = root;
+                for &i in &path[..depth] {
+                    target = &mut target.children[i];
+                }
+                *target = new;
+                damage.push((before.0.min(after.0), before.1.max(after.1)));
+                break;
+            }
+            depth -= 1;
+        }
+    }
+    Some(damage)
+}
+
+/// Lay out the normal-flow block `old` again from the current DOM and
+/// styles.  Returns `None` if it is no longer one, if floats beside it may
+/// move it, or if its size or margins changed.
+fn rebuild_flow_box(
+    old: &LayoutBox,
+    dom: &Dom,
+    styles: &[ComputedStyle],
+    images: &ImageCache,
+    viewport_w: i32,
+) -> Option<LayoutBox> {
+    let id = old.node_id?;
+    let width = old.flow_width?;
+    let style = &styles[id];
+    if style.display == Display::None
+        || style.float != FloatVal::None
+        || matches!(style.position, Position::Absolute | Position::Fixed)
+        || !is_block_level(dom, id, style)
+    {
+        return None;
+    }
+    // Floats and `clear` place blocks by what precedes them.
+    let parent = dom.get(id).parent?;
+    if dom.get(parent).children.iter().any(|&c| styles[c].float != FloatVal::None) {
+        return None;
+    }
+    let new = if is_table_element(dom, id) {
+        table::layout_table(dom, styles, id, width, images, viewport_w)
+    } else {
+        build_block(dom, styles, id, width, images, viewport_w)
+    };
+    let same_margin = new.margin.top == old.margin.top && new.margin.right == old.margin.right
+        && new.margin.bottom == old.margin.bottom && new.margin.left == old.margin.left;
+    if new.width == old.width && new.height == old.height && same_margin {
+        Some(new)
+    } else {
+        None
+    }
+}
+
+/// Document Y range covered by `bx` (at document Y `abs_y`) and its subtree,
+/// or `None` if part of it paints elsewhere (fixed or transformed boxes).
+fn subtree_y_extent(bx: &LayoutBox, abs_y: i32) -> Option<(i32, i32)> {
+    if bx.is_fixed || !bx.transform.is_empty() {
+        return None;
+    }
+    let mut ext = (abs_y, abs_y + bx.height);
+    for child in &bx.children {
+        let (y0, y1) = subtree_y_extent(child, abs_y + child.y)?;
+        ext = (ext.0.min(y0), ext.1.max(y1));
+    }
+    Some(ext)
+}
+
+/// Whether `ancestor` is `node` or one of its ancestors.
+fn node_contains(dom: &Dom, ancestor: NodeId, node: NodeId) -> bool {
+    let mut cur = Some(node);
+    while let Some(id) = cur {
+        if id == ancestor {
+            return true;
+        }
+        cur = dom.get(id).parent;
+    }
+    false
+}
+
 // ---------------------------------------------------------------------------
 // Block flow orchestration
 // ---------------------------------------------------------------------------
@@ -507,6 +787,7 @@ pub(super) fn layout_children(
             }
 
             placed.y = cursor_y;
+            placed.flow_width = Some(effective_avail);
             cursor_y += placed.height + placed.margin.bottom;
             prev_margin_bottom = placed.margin.bottom;
 
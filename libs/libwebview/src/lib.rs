@@ -58,17 +58,42 @@ pub mod dom;
 pub mod html;
 pub mod css;
 pub mod style;
+pub mod invalidate;
+pub mod transform;
+pub mod fonts;
 pub mod layout;
+pub mod forms;
 pub mod js;
+pub mod cookies;
+pub mod navigation;
+pub mod export;
+pub mod cache;
+pub mod media;
+pub mod policy;
+pub mod inspect;
+pub mod accessibility;
+pub mod session;
+pub mod scheme;
+pub mod metadata;
 mod renderer;
+mod scroll;
+mod selection;
 
+use alloc::boxed::Box;
 use alloc::string::String;
 use alloc::vec::Vec;
 
 use libanyui_client::{self as ui};
 
-pub use renderer::{ImageCache, ImageEntry, FormControl, HitKind};
-pub use layout::{LayoutBox, FormFieldKind};
+pub use renderer::{ImageCache, ImageEntry, FormControl, HitKind, Highlight};
+pub use layout::{LayoutBox, FormFieldKind, MediaBox};
+pub use media::{MediaHost, MediaKind, MediaState, MediaFrame, MediaCommand};
+pub use policy::{ContentPolicy, BlockedItem};
+pub use forms::{FormSubmission, FormError, InvalidField, Validity, Enctype, FilePicker, PickedFile};
+pub use export::PageBitmap;
+pub use cache::{ResourceCache, CacheStats};
+pub use scheme::{ColorScheme, PageScheme};
+pub use metadata::{Favicon, OpenGraph};
 
 /// A WebView renders HTML content inside a ScrollView using libanyui controls.
 ///
@@ -82,6 +107,8 @@ pub struct WebView {
     dom_val: Option<dom::Dom>,
     /// Browser default stylesheet — parsed once in `new()`, reused on every relayout.
     default_sheet: css::Stylesheet,
+    /// Dark user-agent stylesheet (`scheme::DARK_CSS`) — parsed once in `new()`.
+    dark_sheet: css::Stylesheet,
     /// Pre-parsed external stylesheets — parsed once in `add_stylesheet()` and cached.
     /// Eliminates the need to re-parse up to several hundred KB of CSS on every image load.
     external_sheets: Vec<css::Stylesheet>,
@@ -94,6 +121,8 @@ pub struct WebView {
     /// Avoids re-parsing the same style attribute on every relayout.
     inline_style_cache: Vec<(usize, Vec<css::Declaration>)>,
     pub images: ImageCache,
+    /// `@font-face` faces of the current document and their loaded fonts.
+    fonts: fonts::FontSet,
     viewport_width: i32,
     /// Viewport height in pixels (visible ScrollView area).
     viewport_height: u32,
@@ -115,8 +144,96 @@ pub struct WebView {
     last_render_scroll_y: i32,
     /// Cached body background color for scroll re-renders.
     bg_color_cached: u32,
+    /// Flattened text of the current layout (built lazily for selection / find).
+    text_index: Option<selection::TextIndex>,
+    /// Current text selection as (anchor, focus) offsets into the text index.
+    selection: Option<(usize, usize)>,
+    /// True while the mouse button is held after pressing on the page.
+    selecting: bool,
+    /// Find-in-page matches (byte ranges into the text index).
+    find_matches: Vec<(usize, usize)>,
+    /// Index of the current match in `find_matches`.
+    find_current: usize,
+    /// Session history and the in-flight navigation.
+    navigation: navigation::Navigation,
+    /// Navigation event callback: `cb(nav_id, NAV_*, userdata)`.
+    nav_cb: Option<ui::Callback>,
+    nav_cb_ud: u64,
+    /// ID of the last committed navigation (reported by `NAV_FINISHED`).
+    committed_nav_id: u32,
+    /// Scroll position still to be restored once the page is tall enough.
+    scroll_restore: Option<i32>,
+    /// Smooth scrolling / momentum state and scroll anchoring switch.
+    scroller: scroll::Scroller,
+    /// Parser of a document being streamed in via `feed_bytes()`.
+    stream: Option<html::StreamParser>,
+    /// DOM node count at the last progressive render of the stream.
+    stream_rendered_nodes: usize,
+    /// Uptime (ms) of the last progressive render of the stream.
+    stream_render_ms: u32,
+    /// Styles of the last layout, reused for incremental restyles.
+    /// `None` whenever a full resolve is required.
+    style_cache: Option<style::StyleCache>,
+    /// Nodes to re-resolve on the next layout (set by late stylesheets).
+    restyle_nodes: Option<Vec<bool>>,
+    /// Nodes whose boxes the next layout may rebuild in place instead of
+    /// laying out the whole document (see `layout::relayout_nodes`).
+    relayout_nodes: Option<Vec<dom::NodeId>>,
+    /// Something besides the DOM that layout depends on (images, fonts,
+    /// stylesheets) changed: the next layout must be a full one.
+    full_layout: bool,
+    /// Current values of running animations and transitions, applied on
+    /// top of the resolved styles.
+    anim_overrides: Vec<(dom::NodeId, Vec<css::Declaration>)>,
+    /// Embedder-supplied media backend for `<audio>` / `<video>`.
+    media_host: Option<Box<dyn MediaHost>>,
+    /// Media elements of the current document.
+    media: Vec<media::MediaElement>,
+    /// What pages may run and load.
+    policy: ContentPolicy,
+    /// Items the policy blocked on the current document.
+    blocked: Vec<BlockedItem>,
+    /// Blocked-content callback.
+    blocked_cb: Option<ui::Callback>,
+    blocked_cb_ud: u64,
+    /// Node outlined by the inspector overlay (`highlight_node()`).
+    inspected: Option<dom::NodeId>,
+    /// Embedder-supplied file chooser for `<input type="file">`.
+    file_picker: Option<Box<dyn FilePicker>>,
+    /// Files chosen for each file input of the current document.
+    picked_files: Vec<(dom::NodeId, Vec<PickedFile>)>,
+    /// Form drafts of a restored session; `Some` while its page has not
+    /// been loaded (see [`session`]).
+    session_drafts: Option<Vec<session::FormDraft>>,
+    /// Navigation started by `resume_session()` and the drafts to fill in
+    /// when it commits.
+    resume_nav: Option<(u32, Vec<session::FormDraft>)>,
+    /// Scheme pages are asked for (`prefers-color-scheme`).
+    color_scheme: ColorScheme,
+    /// Darken pages without dark styles when dark is preferred.
+    force_dark: bool,
+    /// `color_scheme` resolved at the last layout (the anyui theme for
+    /// `ColorScheme::System`).
+    prefers_dark: bool,
+    /// How the current page renders.
+    page_scheme: PageScheme,
+    /// Title, theme colour and favicon of the current document.
+    meta: metadata::PageMeta,
+    /// Metadata callback.
+    meta_cb: Option<ui::Callback>,
+    meta_cb_ud: u64,
 }
 
+/// Minimum interval between progressive renders of a streamed document.
+const STREAM_RENDER_INTERVAL_MS: u32 = 150;
+
+/// Selection highlight colour (translucent blue).
+const SELECTION_COLOR: u32 = 0x663399FF;
+/// Find-in-page match highlight colour (translucent yellow).
+const FIND_MATCH_COLOR: u32 = 0x80FFFF00;
+/// Highlight colour of the current find-in-page match (translucent orange).
+const FIND_CURRENT_COLOR: u32 = 0xA0FF9632;
+
 impl WebView {
     /// Create a new WebView with the given initial dimensions.
     pub fn new(w: u32, h: u32) -> Self {
@@ -137,11 +254,13 @@ impl WebView {
             renderer: renderer::Renderer::new(),
             dom_val: None,
             default_sheet: css::parse_stylesheet(DEFAULT_CSS),
+            dark_sheet: css::parse_stylesheet(scheme::DARK_CSS),
             external_sheets: Vec::new(),
             inline_sheets: Vec::new(),
             inline_sheets_dirty: true,
             inline_style_cache: Vec::new(),
             images: ImageCache::new(),
+            fonts: fonts::FontSet::new(),
             viewport_width: w as i32,
             viewport_height: h,
             total_height_val: 0,
@@ -155,6 +274,43 @@ impl WebView {
             layout_root: None,
             last_render_scroll_y: 0,
             bg_color_cached: 0xFFFFFFFF,
+            text_index: None,
+            selection: None,
+            selecting: false,
+            find_matches: Vec::new(),
+            find_current: 0,
+            navigation: navigation::Navigation::new(),
+            nav_cb: None,
+            nav_cb_ud: 0,
+            committed_nav_id: 0,
+            scroll_restore: None,
+            scroller: scroll::Scroller::new(),
+            stream: None,
+            stream_rendered_nodes: 0,
+            stream_render_ms: 0,
+            style_cache: None,
+            restyle_nodes: None,
+            relayout_nodes: None,
+            full_layout: false,
+            anim_overrides: Vec::new(),
+            media_host: None,
+            media: Vec::new(),
+            policy: ContentPolicy::default(),
+            blocked: Vec::new(),
+            blocked_cb: None,
+            blocked_cb_ud: 0,
+            inspected: None,
+            file_picker: None,
+            picked_files: Vec::new(),
+            session_drafts: None,
+            resume_nav: None,
+            color_scheme: ColorScheme::System,
+            force_dark: false,
+            prefers_dark: ColorScheme::System.prefers_dark(),
+            page_scheme: PageScheme::Light,
+            meta: metadata::PageMeta::new(),
+            meta_cb: None,
+            meta_cb_ud: 0,
         }
     }
 
@@ -182,6 +338,119 @@ impl WebView {
         self.submit_cb_ud = userdata;
     }
 
+    /// Set the form-control change callback (extern "C" function pointer).
+    ///
+    /// Registered on checkboxes, radio buttons, selects, textareas and file
+    /// inputs; the host forwards each call to `handle_form_change()`.
+    pub fn set_form_change_callback(&mut self, cb: ui::Callback, userdata: u64) {
+        self.renderer.set_change_callback(cb, userdata);
+    }
+
+    /// Set the text-selection mouse callback (extern "C" function pointer).
+    ///
+    /// It is registered for mouse down/move/up and double-click on the page
+    /// canvas; the host forwards each event to `handle_selection_event()`.
+    pub fn set_selection_callback(&mut self, cb: ui::Callback, userdata: u64) {
+        self.renderer.set_select_callback(cb, userdata);
+    }
+
+    /// Set the navigation event callback (extern "C" function pointer).
+    ///
+    /// Called as `cb(nav_id, event, userdata)` with `event` one of
+    /// `navigation::NAV_STARTED`, `NAV_COMMITTED`, `NAV_FINISHED` or `NAV_FAILED`.
+    pub fn set_navigation_callback(&mut self, cb: ui::Callback, userdata: u64) {
+        self.nav_cb = Some(cb);
+        self.nav_cb_ud = userdata;
+    }
+
+    /// Set the blocked-content callback (extern "C" function pointer).
+    ///
+    /// Called as `cb(kind, count, userdata)` whenever the content policy
+    /// blocks something, with `kind` one of the `policy::BLOCKED_*`
+    /// constants and `count` the number of items of that kind blocked on
+    /// the current document.  See `blocked_items()` for details.
+    pub fn set_blocked_callback(&mut self, cb: ui::Callback, userdata: u64) {
+        self.blocked_cb = Some(cb);
+        self.blocked_cb_ud = userdata;
+    }
+
+    /// Set the page metadata callback (extern "C" function pointer).
+    ///
+    /// Called as `cb(kind, 0, userdata)` with `kind` one of
+    /// `metadata::META_TITLE`, `META_ICON` or `META_THEME_COLOR` whenever
+    /// that piece of the current document changes, including while it is
+    /// still streaming in and when a new page clears it.
+    pub fn set_metadata_callback(&mut self, cb: ui::Callback, userdata: u64) {
+        self.meta_cb = Some(cb);
+        self.meta_cb_ud = userdata;
+    }
+
+    /// Set the content policy.  Takes effect for documents loaded after this
+    /// call (reload the page to apply it to the current one).
+    pub fn set_policy(&mut self, policy: ContentPolicy) {
+        self.policy = policy;
+    }
+
+    /// The current content policy.
+    pub fn policy(&self) -> &ContentPolicy {
+        &self.policy
+    }
+
+    /// Scripts, resources, links and forms the policy blocked on the
+    /// current document, in document order of discovery.
+    pub fn blocked_items(&self) -> &[BlockedItem] {
+        &self.blocked
+    }
+
+    /// Set the scheme pages are asked for (`prefers-color-scheme`) and
+    /// re-render.  `ColorScheme::System` follows the anyui theme.
+    pub fn set_color_scheme(&mut self, scheme: ColorScheme) {
+        self.color_scheme = scheme;
+        self.refresh_color_scheme();
+    }
+
+    /// The scheme pages are asked for.
+    pub fn color_scheme(&self) -> ColorScheme {
+        self.color_scheme
+    }
+
+    /// Darken pages that have no dark styles of their own while dark is
+    /// preferred.
+    pub fn set_force_dark(&mut self, on: bool) {
+        if self.force_dark == on {
+            return;
+        }
+        self.force_dark = on;
+        self.style_cache = None;
+        if self.dom_val.is_some() {
+            self.relayout();
+        }
+    }
+
+    /// Whether force-dark is on.
+    pub fn force_dark(&self) -> bool {
+        self.force_dark
+    }
+
+    /// Re-read the preferred scheme and re-render if it changed.  Call from
+    /// the app's `on_theme_changed` handler when using `ColorScheme::System`.
+    pub fn refresh_color_scheme(&mut self) {
+        let dark = self.color_scheme.prefers_dark();
+        if dark == self.prefers_dark {
+            return;
+        }
+        self.prefers_dark = dark;
+        self.style_cache = None;
+        if self.dom_val.is_some() {
+            self.relayout();
+        }
+    }
+
+    /// Whether the current page renders dark (dark styles or force-dark).
+    pub fn page_is_dark(&self) -> bool {
+        self.page_scheme.is_dark()
+    }
+
     /// Set the current page URL.  Must be called before `set_html()` so that
     /// the JS environment has the correct `window.location` / `document.location`
     /// values when scripts run.
@@ -194,8 +463,44 @@ impl WebView {
     /// Parsing happens exactly once here.  Subsequent calls to `relayout()` reuse
     /// the pre-parsed form, which is orders of magnitude faster than re-parsing
     /// hundreds of kilobytes of CSS text on every image or resource load.
+    ///
+    /// If the page has already been laid out, only the nodes the sheet's
+    /// selectors match (and their subtrees) are re-resolved by the next
+    /// `relayout()`.
+    ///
+    /// Relative `@font-face` URLs resolve against the page URL; use
+    /// `add_stylesheet_from()` for a sheet fetched from its own URL.
     pub fn add_stylesheet(&mut self, css_text: &str) {
-        self.external_sheets.push(css::parse_stylesheet(css_text));
+        let base = self.current_url.clone();
+        self.add_stylesheet_from(css_text, &base);
+    }
+
+    /// Like `add_stylesheet()`, for a sheet loaded from `sheet_url`
+    /// (the base URL of its `@font-face` sources).
+    pub fn add_stylesheet_from(&mut self, css_text: &str, sheet_url: &str) {
+        let sheet = css::parse_stylesheet(css_text);
+        if self.style_cache.is_some() {
+            let vh = self.total_height_val.max(self.viewport_width);
+            let dark = self.prefers_dark;
+            let affected = self.dom().map(|d| style::affected_nodes(d, &sheet, self.viewport_width, vh, dark));
+            if let Some(affected) = affected {
+                match self.restyle_nodes {
+                    Some(ref mut dirty) => {
+                        if dirty.len() < affected.len() {
+                            dirty.resize(affected.len(), true);
+                        }
+                        for (d, a) in dirty.iter_mut().zip(affected.iter()) {
+                            *d |= *a;
+                        }
+                    }
+                    None => self.restyle_nodes = Some(affected),
+                }
+            }
+        }
+        push_keyframes(&mut self.keyframes, &sheet);
+        self.fonts.add_faces(&sheet.font_faces, sheet_url, false);
+        self.external_sheets.push(sheet);
+        self.full_layout = true;
     }
 
     /// Clear all cached external and inline stylesheets.
@@ -203,11 +508,57 @@ impl WebView {
         self.external_sheets.clear();
         self.inline_sheets.clear();
         self.inline_sheets_dirty = true;
+        self.fonts.clear();
+    }
+
+    /// Web font files the page's `@font-face` rules need, as absolute URLs.
+    ///
+    /// Each URL is returned once; fetch it and pass the result to
+    /// `add_font()` or `font_failed()`.  Call again after adding
+    /// stylesheets, after a relayout and after `font_failed()`, which may
+    /// queue a face's next source.  URLs the content policy blocks are
+    /// skipped.
+    pub fn take_font_requests(&mut self) -> Vec<String> {
+        let mut out = Vec::new();
+        loop {
+            let mut retry = false;
+            for url in self.fonts.take_requests() {
+                if self.policy.allows_load(&url, &self.current_url) {
+                    out.push(url);
+                } else {
+                    retry |= self.fonts.font_failed(&url);
+                }
+            }
+            if !retry {
+                return out;
+            }
+        }
+    }
+
+    /// Register a fetched web font file (TrueType / OpenType).
+    ///
+    /// Returns `true` if text may now render differently and a
+    /// `relayout()` is needed.  A file libfont cannot read counts as a
+    /// failed source.
+    pub fn add_font(&mut self, url: &str, data: &[u8]) -> bool {
+        let changed = self.fonts.font_loaded(url, data);
+        self.full_layout |= changed;
+        changed
+    }
+
+    /// Report that fetching a web font file failed.  Returns `true` if
+    /// other sources were queued (see `take_font_requests()`).
+    pub fn font_failed(&mut self, url: &str) -> bool {
+        self.fonts.font_failed(url)
     }
 
     /// Add a decoded image to the cache. Will be displayed on next render.
     pub fn add_image(&mut self, src: &str, pixels: Vec<u32>, w: u32, h: u32) {
+        if !self.policy.allows_load(src, &self.current_url) {
+            return;
+        }
         self.images.add(String::from(src), pixels, w, h);
+        self.full_layout = true;
     }
 
     /// Set HTML content and render it.
@@ -228,12 +579,124 @@ impl WebView {
         anyos_std::println!("[webview]   RSP=0x{:X} heap=0x{:X}", debug_rsp(), debug_heap_pos());
 
         // New page — inline <style> blocks and style attribute cache need re-parsing.
+        self.stream = None;
         self.inline_sheets.clear();
         self.inline_sheets_dirty = true;
         self.inline_style_cache.clear();
+        self.style_cache = None;
+        self.reset_animation_state();
+        self.reset_media();
+        self.blocked.clear();
+        self.scroller.reset();
+        self.reset_metadata();
+        self.enforce_policy(&mut parsed_dom);
 
         // Collect stylesheets and resolve + layout + render.
         self.do_layout_and_render(&parsed_dom);
+        self.run_scripts(parsed_dom);
+    }
+
+    /// Start a document that arrives in chunks (e.g. over a slow connection).
+    ///
+    /// Replaces the current page like `set_html()`.  Feed the document with
+    /// `feed_bytes()` and end it with `finish()`; the content received so far
+    /// is laid out and rendered progressively in between.  Call `set_url()`
+    /// first, as for `set_html()`.
+    pub fn begin_html(&mut self) {
+        debug_surf!("[webview] begin_html");
+        self.stream = Some(html::StreamParser::new());
+        self.dom_val = None;
+        self.inline_sheets.clear();
+        self.inline_sheets_dirty = true;
+        self.inline_style_cache.clear();
+        self.style_cache = None;
+        self.restyle_nodes = None;
+        self.relayout_nodes = None;
+        self.reset_animation_state();
+        self.reset_media();
+        self.blocked.clear();
+        self.scroller.reset();
+        self.reset_metadata();
+        self.stream_rendered_nodes = 0;
+        self.stream_render_ms = anyos_std::sys::uptime_ms();
+    }
+
+    /// Feed the next chunk of a document started with `begin_html()`.
+    ///
+    /// `data` must be UTF-8.  Complete tokens are added to the DOM at once;
+    /// the page is re-rendered when new nodes arrived and at least
+    /// `STREAM_RENDER_INTERVAL_MS` passed since the previous render (the
+    /// first content renders immediately).  Only new nodes are styled.
+    /// Returns `true` if the page was re-rendered.
+    pub fn feed_bytes(&mut self, data: &[u8]) -> bool {
+        let mut parser = match self.stream.take() {
+            Some(p) => p,
+            None => return false,
+        };
+        parser.feed_bytes(data);
+        // Strip blocked URLs before the host can see them in `dom()`.
+        self.enforce_policy(parser.dom_mut());
+        self.update_metadata(parser.dom(), false);
+
+        let count = parser.dom().nodes.len();
+        let now = anyos_std::sys::uptime_ms();
+        let due = count > self.stream_rendered_nodes
+            && (self.stream_rendered_nodes == 0
+                || now.wrapping_sub(self.stream_render_ms) >= STREAM_RENDER_INTERVAL_MS);
+        if due {
+            // A new <style> block must be parsed before styling its subtree.
+            let d = parser.dom();
+            let new_style = d.nodes[self.stream_rendered_nodes..].iter().any(|n| {
+                matches!(n.node_type, dom::NodeType::Element { tag: dom::Tag::Style, .. })
+            });
+            if new_style {
+                self.inline_sheets_dirty = true;
+            }
+            debug_surf!("[webview] feed_bytes: progressive render at {} nodes", count);
+            self.do_layout_and_render(d);
+            self.stream_rendered_nodes = count;
+            self.stream_render_ms = now;
+        }
+        self.stream = Some(parser);
+        due
+    }
+
+    /// End a document started with `begin_html()`: parse the remaining
+    /// input, do a final full style resolve + layout, and run its scripts.
+    pub fn finish(&mut self) {
+        let parser = match self.stream.take() {
+            Some(p) => p,
+            None => return,
+        };
+        let mut parsed_dom = parser.finish();
+        self.enforce_policy(&mut parsed_dom);
+        debug_surf!("[webview] finish: {} nodes", parsed_dom.nodes.len());
+
+        // Structural selectors (:last-child, +, ~) may have changed for nodes
+        // styled while the document was incomplete.
+        self.inline_sheets_dirty = true;
+        self.do_layout_and_render(&parsed_dom);
+        self.run_scripts(parsed_dom);
+    }
+
+    /// Whether a streamed document is still being received.
+    pub fn is_streaming(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// Run the document's `<script>` tags, apply their DOM mutations and
+    /// store the DOM as the current page.
+    fn run_scripts(&mut self, mut parsed_dom: dom::Dom) {
+        // Scripts disabled by the content policy: report them and skip JS.
+        if !self.policy.scripts {
+            let before = self.blocked.len();
+            self.blocked.extend(policy::blocked_scripts(&parsed_dom));
+            self.report_blocked(before);
+            self.update_metadata(&parsed_dom, true);
+            self.dom_val = Some(parsed_dom);
+            self.sync_media();
+            return;
+        }
 
         // Execute JavaScript <script> tags after initial render so that DOM
         // elements already exist for querySelector / getElementById calls.
@@ -248,14 +711,41 @@ impl WebView {
         if !self.js_runtime.mutations.is_empty() {
             debug_surf!("[webview] applying {} JS mutations + relayout", self.js_runtime.mutations.len());
             self.js_runtime.apply_mutations(&mut parsed_dom);
+            self.enforce_policy(&mut parsed_dom);
             self.inline_sheets_dirty = true; // JS may have altered <style> tags
             self.inline_style_cache.clear(); // JS may have altered style="..." attrs
             self.do_layout_and_render(&parsed_dom);
         }
 
         // Store DOM for title queries etc.
+        self.update_metadata(&parsed_dom, true);
         self.dom_val = Some(parsed_dom);
-        debug_surf!("[webview] set_html complete");
+        self.sync_media();
+        self.flush_media_commands();
+        debug_surf!("[webview] document complete");
+    }
+
+    /// Apply the content policy to `d` and report newly blocked items.
+    fn enforce_policy(&mut self, d: &mut dom::Dom) {
+        let before = self.blocked.len();
+        policy::enforce(d, &self.policy, &self.current_url, &mut self.blocked);
+        self.report_blocked(before);
+    }
+
+    /// Fire the blocked-content callback once per kind among the items
+    /// blocked since index `from`.
+    fn report_blocked(&self, from: usize) {
+        let cb = match self.blocked_cb {
+            Some(cb) => cb,
+            None => return,
+        };
+        let mut kinds: Vec<u32> = self.blocked[from..].iter().map(|b| b.kind).collect();
+        kinds.sort_unstable();
+        kinds.dedup();
+        for kind in kinds {
+            let count = self.blocked.iter().filter(|b| b.kind == kind).count();
+            cb(kind, count as u32, self.blocked_cb_ud);
+        }
     }
 
     /// Get the page title from the current DOM (if any).
@@ -263,6 +753,72 @@ impl WebView {
         self.dom_val.as_ref().and_then(|d| d.find_title())
     }
 
+    /// Re-read the metadata of `d` and report what changed.
+    fn update_metadata(&mut self, d: &dom::Dom, complete: bool) {
+        let changed = self.meta.scan(d, &self.current_url, self.prefers_dark, complete);
+        self.fire_meta(&changed);
+    }
+
+    /// Forget the metadata of the previous document and report what it clears.
+    fn reset_metadata(&mut self) {
+        let cleared = self.meta.reset();
+        self.fire_meta(&cleared);
+    }
+
+    fn fire_meta(&self, kinds: &[u32]) {
+        if let Some(cb) = self.meta_cb {
+            for &kind in kinds {
+                cb(kind, 0, self.meta_cb_ud);
+            }
+        }
+    }
+
+    /// The page's `<meta name="theme-color">` for the current colour
+    /// scheme (opaque ARGB), if any.
+    pub fn theme_color(&self) -> Option<u32> {
+        self.meta.theme_color()
+    }
+
+    /// Open Graph properties of the current page (empty without a DOM).
+    pub fn open_graph(&self) -> OpenGraph {
+        self.dom()
+            .map(|d| metadata::open_graph(d, &self.current_url))
+            .unwrap_or_default()
+    }
+
+    /// The next favicon URL to fetch, or `None` if there is nothing (more)
+    /// to try, an icon is loaded or a fetch is still outstanding.
+    ///
+    /// Call again after the document grows (streaming), after `finish()` /
+    /// `commit_navigation()` (which allows the `/favicon.ico` fallback) and
+    /// after `favicon_failed()`.  URLs the content policy blocks are skipped.
+    pub fn take_favicon_request(&mut self) -> Option<String> {
+        let policy = &self.policy;
+        let page = &self.current_url;
+        self.meta.take_request(page, |url| policy.allows_load(url, page))
+    }
+
+    /// Register the fetched favicon file for `url`.  Returns `true` (and
+    /// fires `META_ICON`) if it decoded and is now `favicon()`; otherwise
+    /// it counts as failed.
+    pub fn add_favicon(&mut self, url: &str, data: &[u8]) -> bool {
+        let ok = self.meta.loaded(url, data);
+        if ok {
+            self.fire_meta(&[metadata::META_ICON]);
+        }
+        ok
+    }
+
+    /// Report that fetching the favicon `url` failed.
+    pub fn favicon_failed(&mut self, url: &str) {
+        self.meta.failed(url);
+    }
+
+    /// The current page's favicon, `metadata::FAVICON_SIZE` square.
+    pub fn favicon(&self) -> Option<&Favicon> {
+        self.meta.favicon()
+    }
+
     /// Get the total document height in pixels.
     pub fn total_height(&self) -> i32 {
         self.total_height_val
@@ -286,14 +842,108 @@ impl WebView {
         if let Some(mut d) = self.dom_val.take() {
             // Apply any pending JS mutations before re-rendering.
             if !self.js_runtime.mutations.is_empty() {
-                self.js_runtime.apply_mutations(&mut d);
-                // JS may have modified <style> tags or style="..." attributes.
-                self.inline_sheets_dirty = true;
-                self.inline_style_cache.clear();
+                let changes = self.js_runtime.apply_mutations(&mut d);
+                let blocked = self.blocked.len();
+                self.enforce_policy(&mut d);
+                if changes.sheets || self.blocked.len() != blocked {
+                    // JS modified a <style> block, or the policy stripped
+                    // what it inserted: restyle everything.
+                    self.inline_sheets_dirty = true;
+                    self.inline_style_cache.clear();
+                } else if !changes.is_empty() {
+                    // Only the changed subtrees are restyled and laid out.
+                    self.inline_style_cache.retain(|(id, _)| !changes.inline_styles.contains(id));
+                    let roots = {
+                        let mut sheets: Vec<&css::Stylesheet> = Vec::with_capacity(
+                            2 + self.external_sheets.len() + self.inline_sheets.len()
+                        );
+                        sheets.push(&self.default_sheet);
+                        sheets.push(&self.dark_sheet);
+                        for sheet in &self.external_sheets { sheets.push(sheet); }
+                        for sheet in &self.inline_sheets { sheets.push(sheet); }
+                        changes.restyle_roots(&d, &sheets)
+                    };
+                    if self.style_cache.is_some() {
+                        let dirty = self.restyle_nodes.get_or_insert_with(Vec::new);
+                        invalidate::mark_subtrees(&d, dirty, &roots);
+                    }
+                    self.relayout_nodes.get_or_insert_with(Vec::new)
+                        .extend(changes.relayout_roots(&roots));
+                }
             }
             self.do_layout_and_render(&d);
+            // Scripts may have changed the title or icon links, and a new
+            // colour scheme may select another theme colour.
+            self.update_metadata(&d, true);
             self.dom_val = Some(d);
+            // Scripts may have added media elements or changed their sources.
+            self.sync_media();
+            self.flush_media_commands();
+        } else if let Some(p) = self.stream.take() {
+            // Still streaming: re-render what has arrived so far.
+            self.relayout_nodes = None;
+            self.do_layout_and_render(p.dom());
+            self.stream = Some(p);
+        }
+    }
+
+    // ─────────────────────────────────────────────────────────────────────
+    // Print / export
+    // ─────────────────────────────────────────────────────────────────────
+
+    /// Render the current page for printing: lay it out at `page_width` and
+    /// split it into `page_width × page_height` ARGB page bitmaps, moving
+    /// page breaks so they do not cut through lines of text.
+    ///
+    /// The on-screen layout is left untouched; when `page_width` equals the
+    /// viewport width its cached layout tree is reused.
+    pub fn paginate(&mut self, page_width: u32, page_height: u32) -> Vec<PageBitmap> {
+        let page_width = page_width.max(1);
+        if page_width as i32 == self.viewport_width {
+            if let Some(ref root) = self.layout_root {
+                return export::render_pages(
+                    root, &self.images, self.total_height_val,
+                    page_width, page_height, self.bg_color_cached,
+                );
+            }
         }
+        match self.print_layout(page_width as i32) {
+            Some((root, bg_color)) => {
+                let doc_h = calc_total_height(&root);
+                export::render_pages(&root, &self.images, doc_h, page_width, page_height, bg_color)
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Render the current page as a PDF document ("Save as PDF"), with
+    /// pages paginated as by `paginate()`.
+    pub fn export_pdf(&mut self, page_width: u32, page_height: u32) -> Vec<u8> {
+        let pages = self.paginate(page_width, page_height);
+        export::encode_pdf(&pages)
+    }
+
+    /// Resolve styles and lay out the current DOM at `width` without
+    /// touching the cached on-screen layout.  Returns the layout tree and the
+    /// body background colour.
+    fn print_layout(&mut self, width: i32) -> Option<(LayoutBox, u32)> {
+        let d = self.dom_val.as_ref()?;
+        let mut all_sheets: Vec<&css::Stylesheet> = Vec::with_capacity(
+            1 + self.external_sheets.len() + self.inline_sheets.len()
+        );
+        all_sheets.push(&self.default_sheet);
+        for sheet in &self.external_sheets { all_sheets.push(sheet); }
+        for sheet in &self.inline_sheets { all_sheets.push(sheet); }
+        let vh = self.total_height_val.max(width);
+        // Printed pages are always light.
+        let mut cache = style::restyle(d, &all_sheets, width, vh, false, &mut self.inline_style_cache, None);
+        apply_anim_overrides(d, &mut cache.styles, &self.anim_overrides);
+        self.fonts.assign(&mut cache.styles);
+
+        let root = layout::layout(d, &cache.styles, width, &self.images);
+        let body_id = d.find_body().unwrap_or(0);
+        let body_bg = cache.styles.get(body_id).map(|s| s.background_color).unwrap_or(0);
+        Some((root, if body_bg != 0 { body_bg } else { 0xFFFFFFFF }))
     }
 
     /// Advance CSS animations/transitions, JS timers, and scroll-based tile
@@ -313,24 +963,42 @@ impl WebView {
             self.dom_val = dom_opt;
         }
 
-        // ── 2. CSS animations — DISABLED for performance investigation. ──────────
-        // TODO: re-enable once the idle-loop root cause is confirmed fixed.
-        // if !self.js_runtime.active_animations.is_empty()
-        //     || !self.js_runtime.active_transitions.is_empty()
-        // {
-        //     let (any_active, _overrides) =
-        //         self.js_runtime.advance_animations(delta_ms, &self.keyframes);
-        //     if any_active {
-        //         self.relayout();
-        //         changed = true;
-        //     }
-        // }
-
-        // ── 3. Scroll-based tile management (compositor-driven). ─────────────────
-        // Per-tile canvases are positioned in the content_view.  The compositor
-        // handles smooth scrolling natively.  We only need to create tile
-        // canvases for rows entering the pre-render zone (incrementally, max
-        // 2 per tick to avoid blocking the event loop).
+        // ── 2. Media playback: forward script requests, poll state and frames. ──
+        self.flush_media_commands();
+        if self.poll_media() {
+            changed = true;
+        }
+
+        // ── 3. CSS animations and transitions. ───────────────────────────────────
+        // Paint-only changes (opacity, transform, background) patch the cached
+        // layout and repaint the affected tiles; anything else relayouts.
+        if self.css_animating() {
+            let animated: Vec<dom::NodeId> = self.js_runtime.active_animations.iter()
+                .map(|a| a.node_id)
+                .collect();
+            let (_, overrides) = self.js_runtime.advance_animations(delta_ms, &self.keyframes);
+            self.anim_overrides = overrides;
+            // Nodes whose keyframe animation just ended get their own style back.
+            let ended: Vec<dom::NodeId> = animated.into_iter()
+                .filter(|id| !self.js_runtime.active_animations.iter().any(|a| a.node_id == *id))
+                .collect();
+            if !ended.is_empty() || !self.repaint_animated() {
+                self.restyle_subtrees(&ended);
+                self.relayout();
+            }
+            changed = true;
+        }
+
+        // ── 4. Smooth scroll / fling animation. ─────────────────────────────────
+        if let Some(y) = self.scroller.advance(delta_ms as u32, self.max_scroll()) {
+            self.scroll_view.set_state(y as u32);
+            changed = true;
+        }
+
+        // ── 5. Scroll-based tile management (compositor-driven). ─────────────────
+        // Per-tile canvases are positioned in the content_view.  We only need
+        // to create tile canvases for rows entering the pre-render zone
+        // (incrementally, max 2 per tick to avoid blocking the event loop).
         if self.layout_root.is_some() {
             let scroll_y = self.scroll_view.get_state() as i32;
             let delta = (scroll_y - self.last_render_scroll_y).abs();
@@ -347,6 +1015,118 @@ impl WebView {
         changed
     }
 
+    /// Whether CSS animations or transitions are running, media is
+    /// playing, or a smooth scroll is in progress (the host should keep
+    /// calling `tick()`).
+    pub fn is_animating(&self) -> bool {
+        self.css_animating()
+            || self.scroller.is_active()
+            || self.media.iter().any(|m| m.opened && !m.state.paused)
+    }
+
+    /// Whether CSS animations or transitions are running.
+    fn css_animating(&self) -> bool {
+        !self.js_runtime.active_animations.is_empty()
+            || !self.js_runtime.active_transitions.is_empty()
+    }
+
+    /// Apply `anim_overrides` without a relayout when they only touch
+    /// paint properties: patch the cached styles and layout boxes, then
+    /// repaint the tile rows the animated boxes cover before and after.
+    /// Returns `false` if a relayout is needed instead.
+    fn repaint_animated(&mut self) -> bool {
+        let paint_only = self.anim_overrides.iter().all(|(_, decls)| {
+            decls.iter().all(|d| matches!(
+                d.property,
+                css::Property::Opacity | css::Property::Transform | css::Property::BackgroundColor
+            ))
+        });
+        let (root, cache) = match (self.layout_root.as_mut(), self.style_cache.as_mut()) {
+            (Some(root), Some(cache)) if paint_only => (root, cache),
+            _ => return false,
+        };
+        let nodes: Vec<dom::NodeId> = self.anim_overrides.iter().map(|(id, _)| *id).collect();
+        let before = renderer::paint_extent(root, &nodes);
+        for (id, decls) in &self.anim_overrides {
+            let style = match cache.styles.get_mut(*id) {
+                Some(s) => s,
+                None => return false,
+            };
+            for decl in decls {
+                style::apply_declaration(style, decl, style.font_size, 16);
+            }
+            if !patch_paint_props(root, *id, style) {
+                return false;
+            }
+        }
+        let after = renderer::paint_extent(root, &nodes);
+        let (y0, y1) = match (before, after) {
+            (Some(a), Some(b)) => (a.0.min(b.0), a.1.max(b.1)),
+            (Some(r), None) | (None, Some(r)) => r,
+            (None, None) => return true,
+        };
+        self.renderer.repaint_range(root, &self.images, y0, y1, self.bg_color_cached);
+        true
+    }
+
+    /// Flag `nodes` and their descendants for re-resolution on the next
+    /// layout, and their boxes for rebuilding.
+    fn restyle_subtrees(&mut self, nodes: &[dom::NodeId]) {
+        if nodes.is_empty() || self.style_cache.is_none() {
+            return;
+        }
+        let d = match self.dom_val.as_ref().or_else(|| self.stream.as_ref().map(|p| p.dom())) {
+            Some(d) => d,
+            None => return,
+        };
+        let dirty = self.restyle_nodes.get_or_insert_with(Vec::new);
+        invalidate::mark_subtrees(d, dirty, nodes);
+        self.relayout_nodes.get_or_insert_with(Vec::new).extend_from_slice(nodes);
+    }
+
+    /// Rebuild the boxes of `nodes` in the cached layout tree (see
+    /// `layout::relayout_nodes`) and repaint the tile rows they cover.
+    /// Returns `false` if a full layout is needed instead.
+    fn relayout_in_place(&mut self, d: &dom::Dom, styles: &[style::ComputedStyle], nodes: &[dom::NodeId]) -> bool {
+        // Running animations change their nodes' boxes on every frame.
+        let mut nodes = nodes.to_vec();
+        for (id, _) in &self.anim_overrides {
+            if !nodes.contains(id) {
+                nodes.push(*id);
+            }
+        }
+        self.reset_selection_state();
+        let root = match self.layout_root.as_mut() {
+            Some(root) => root,
+            None => return false,
+        };
+        let damage = match layout::relayout_nodes(root, d, styles, &nodes, &self.images, self.viewport_width) {
+            Some(damage) => damage,
+            None => return false,
+        };
+        patch_media_boxes(root, &self.media);
+        if calc_total_height(root) != self.total_height_val {
+            return false;
+        }
+        debug_surf!("[webview] relayout in place: {} nodes, {} damage ranges", nodes.len(), damage.len());
+        self.renderer.update(
+            root,
+            &self.content_view,
+            &self.images,
+            &damage,
+            self.bg_color_cached,
+            self.submit_cb,
+            self.submit_cb_ud,
+        );
+        true
+    }
+
+    /// Drop running animations and transitions (new document).
+    fn reset_animation_state(&mut self) {
+        self.js_runtime.reset_animations();
+        self.anim_overrides.clear();
+    }
+
     /// Ensure tile canvases exist for the visible viewport range.
     ///
     /// Uses the fast scroll path: only creates canvases for rows not yet
@@ -385,15 +1165,29 @@ impl WebView {
         self.renderer.clear_all();
         self.images.clear();
         self.dom_val = None;
+        self.stream = None;
+        self.style_cache = None;
+        self.restyle_nodes = None;
+        self.reset_animation_state();
+        self.reset_media();
+        self.blocked.clear();
+        self.reset_metadata();
+        self.inspected = None;
+        self.picked_files.clear();
         self.layout_root = None;
         self.total_height_val = 0;
         self.last_render_scroll_y = 0;
+        self.scroller.reset();
+        self.reset_selection_state();
         self.content_view.set_size(self.viewport_width as u32, 1);
     }
 
     /// Access the current DOM (if set).
+    ///
+    /// While a document is streaming this is the partial DOM parsed so far,
+    /// so hosts can start fetching `<link>` stylesheets and images early.
     pub fn dom(&self) -> Option<&dom::Dom> {
-        self.dom_val.as_ref()
+        self.dom_val.as_ref().or_else(|| self.stream.as_ref().map(|p| p.dom()))
     }
 
     /// Look up the link URL for a control ID (used in click callbacks).
@@ -403,6 +1197,10 @@ impl WebView {
     pub fn link_url_for(&self, control_id: u32) -> Option<&str> {
         // Tile canvas click: translate mouse to document coords and hit-test.
         if let Some((mx, doc_y)) = self.renderer.tile_hit_coords(control_id) {
+            // A click that ended a drag selection is not a link click.
+            if self.has_selection() {
+                return None;
+            }
             return self.renderer.hit_test_link_at(mx, doc_y);
         }
         // Legacy: real control link_map lookup.
@@ -424,96 +1222,192 @@ impl WebView {
     /// Used for canvas-based submit hit regions.
     pub fn form_action_for_node(&self, node_id: usize) -> Option<(String, String)> {
         let dom = self.dom_val.as_ref()?;
-        let mut cur = Some(node_id);
-        while let Some(id) = cur {
-            if dom.tag(id) == Some(dom::Tag::Form) {
-                let action = dom.attr(id, "action").unwrap_or("");
-                let method = dom.attr(id, "method").unwrap_or("GET");
-                return Some((String::from(action), method.to_ascii_uppercase()));
-            }
-            cur = dom.get(id).parent;
-        }
-        None
+        let form = forms::form_owner(dom, node_id)?;
+        Some(forms::submission_target(dom, form, Some(node_id)))
     }
 
     /// Collect form data for a form containing the given DOM node_id.
     /// Used for canvas-based submit hit regions.
     pub fn collect_form_data_for_node(&self, node_id: usize) -> Vec<(String, String)> {
         let dom = match self.dom_val.as_ref() { Some(d) => d, None => return Vec::new() };
+        match forms::form_owner(dom, node_id) {
+            Some(form) => forms::serialize(dom, form, &self.field_states(), &self.picked_files, None),
+            None => Vec::new(),
+        }
+    }
 
-        // Find the parent <form> node.
-        let mut form_node = None;
-        let mut cur = Some(node_id);
-        while let Some(id) = cur {
-            if dom.tag(id) == Some(dom::Tag::Form) {
-                form_node = Some(id);
-                break;
+    /// Validate and encode the form submitted by the DOM node `submitter`
+    /// (a submit `<input>` or `<button>`).
+    ///
+    /// The returned submission carries the action resolved against the page
+    /// URL, the URL to load and, for POST, the encoded request body and its
+    /// `Content-Type` (url-encoded, multipart with chosen files, or plain
+    /// text, per `enctype` / `formenctype`).
+    ///
+    /// Validation is skipped for `novalidate` forms and `formnovalidate`
+    /// submitters.  On failure, the first invalid field receives focus.
+    pub fn submit_form(&self, submitter: usize) -> Result<FormSubmission, FormError> {
+        let dom = self.dom_val.as_ref().ok_or(FormError::NoForm)?;
+        let form = forms::form_owner(dom, submitter).ok_or(FormError::NoForm)?;
+        let fields = self.field_states();
+
+        if !forms::skips_validation(dom, form, Some(submitter)) {
+            let invalid = forms::validate(dom, form, &fields);
+            if let Some(first) = invalid.first() {
+                if let Some(fc) = self.renderer.form_controls.iter().find(|fc| fc.node_id == first.node_id) {
+                    if fc.control_id != 0 {
+                        ui::Control::from_id(fc.control_id).focus();
+                    }
+                }
+                return Err(FormError::Invalid(invalid));
             }
-            cur = dom.get(id).parent;
         }
-        let form_id = match form_node { Some(id) => id, None => return Vec::new() };
 
-        // Collect all form controls that are descendants of this form.
-        let mut data = Vec::new();
-        for fc in &self.renderer.form_controls {
-            let mut is_child = false;
-            let mut up = Some(fc.node_id);
-            while let Some(id) = up {
-                if id == form_id { is_child = true; break; }
-                up = dom.get(id).parent;
-            }
-            if !is_child { continue; }
+        Ok(forms::build_submission(dom, form, &fields, &self.picked_files, Some(submitter), &self.current_url))
+    }
 
-            let name = dom.attr(fc.node_id, "name").unwrap_or("");
-            if name.is_empty() { continue; }
+    /// `submit_form()` for a click on `control_id` (a tile canvas hit on a
+    /// submit region, or a native submit control).
+    pub fn submit_form_for(&self, control_id: u32) -> Result<FormSubmission, FormError> {
+        let node = self.canvas_submit_hit(control_id).or_else(|| {
+            self.renderer.form_controls.iter()
+                .find(|fc| fc.control_id == control_id)
+                .map(|fc| fc.node_id)
+        });
+        match node {
+            Some(n) => self.submit_form(n),
+            None => Err(FormError::NoForm),
+        }
+    }
 
-            match fc.kind {
-                FormFieldKind::TextInput | FormFieldKind::Password => {
-                    if fc.control_id == 0 { continue; }
-                    let ctrl = ui::Control::from_id(fc.control_id);
-                    let mut buf = [0u8; 2048];
-                    let len = ctrl.get_text(&mut buf);
-                    let val = core::str::from_utf8(&buf[..len as usize]).unwrap_or("");
-                    data.push((String::from(name), String::from(val)));
-                }
-                FormFieldKind::Checkbox => {
-                    if fc.control_id == 0 { continue; }
-                    let ctrl = ui::Control::from_id(fc.control_id);
-                    if ctrl.get_state() != 0 {
-                        let val = dom.attr(fc.node_id, "value").unwrap_or("on");
-                        data.push((String::from(name), String::from(val)));
+    /// Install the host's file chooser for `<input type="file">`.  Without
+    /// one, file inputs cannot be given a file and submit empty.
+    pub fn set_file_picker(&mut self, picker: Box<dyn FilePicker>) {
+        self.file_picker = Some(picker);
+    }
+
+    /// Files chosen for the file input `node_id`.
+    pub fn picked_files(&self, node_id: usize) -> &[PickedFile] {
+        self.picked_files.iter()
+            .find(|(id, _)| *id == node_id)
+            .map_or(&[], |(_, files)| &files[..])
+    }
+
+    /// Handle a change notification from a native form control.
+    ///
+    /// Enforces radio-group exclusivity (same `name` and form owner), grows
+    /// textareas with their content, runs the file picker for file inputs,
+    /// and fires the DOM `change` event.
+    pub fn handle_form_change(&mut self, control_id: u32) {
+        let (node_id, kind) = match self.renderer.form_controls.iter().find(|fc| fc.control_id == control_id) {
+            Some(fc) => (fc.node_id, fc.kind),
+            None => return,
+        };
+        if kind == FormFieldKind::File && !self.pick_files(node_id, control_id) {
+            return;
+        }
+        let mut dom = match self.dom_val.take() { Some(d) => d, None => return };
+        let mut relayout = false;
+
+        match kind {
+            FormFieldKind::Radio => {
+                if ui::Control::from_id(control_id).get_state() != 0 {
+                    let name = dom.attr(node_id, "name").unwrap_or("");
+                    let owner = forms::form_owner(&dom, node_id);
+                    if !name.is_empty() {
+                        for fc in &self.renderer.form_controls {
+                            if fc.kind == FormFieldKind::Radio
+                                && fc.control_id != control_id
+                                && dom.attr(fc.node_id, "name") == Some(name)
+                                && forms::form_owner(&dom, fc.node_id) == owner
+                            {
+                                ui::Control::from_id(fc.control_id).set_state(0);
+                            }
+                        }
                     }
                 }
-                FormFieldKind::Radio => {
-                    if fc.control_id == 0 { continue; }
-                    let ctrl = ui::Control::from_id(fc.control_id);
-                    if ctrl.get_state() != 0 {
-                        let val = dom.attr(fc.node_id, "value").unwrap_or("");
-                        data.push((String::from(name), String::from(val)));
-                    }
+            }
+            FormFieldKind::Textarea => {
+                // Mirror the text into the DOM when the line count changes so
+                // the next layout sizes the box to the content.
+                let value = read_editor_text(&ui::TextEditor::from_id(control_id));
+                let old = forms::textarea_default(&dom, node_id);
+                if forms::line_count(&value) != forms::line_count(&old) {
+                    dom.set_text(node_id, &value);
+                    relayout = true;
                 }
-                FormFieldKind::Hidden => {
-                    let val = dom.attr(fc.node_id, "value").unwrap_or("");
-                    data.push((String::from(name), String::from(val)));
+            }
+            _ => {}
+        }
+
+        self.js_runtime.dispatch_event(&dom, node_id, "change");
+        self.dom_val = Some(dom);
+        if relayout || !self.js_runtime.mutations.is_empty() {
+            self.relayout();
+        }
+    }
+
+    /// Let the user choose files for the file input `node_id` and show them
+    /// on its button.  Returns `false` if nothing changed (cancelled, no
+    /// picker installed, or the input is disabled).
+    fn pick_files(&mut self, node_id: usize, control_id: u32) -> bool {
+        let (accept, multiple) = match self.dom_val.as_ref() {
+            Some(d) if !forms::is_disabled(d, node_id) => {
+                (String::from(d.attr(node_id, "accept").unwrap_or("")), d.attr(node_id, "multiple").is_some())
+            }
+            _ => return false,
+        };
+        let mut files = match self.file_picker.as_mut().and_then(|p| p.pick(&accept, multiple)) {
+            Some(f) => f,
+            None => return false,
+        };
+        if !multiple {
+            files.truncate(1);
+        }
+        let label = match files.len() {
+            0 => String::from(if multiple { "Choose Files…" } else { "Choose File…" }),
+            1 => files[0].name.clone(),
+            n => alloc::format!("{} files", n),
+        };
+        ui::Control::from_id(control_id).set_text(&label);
+        self.picked_files.retain(|(id, _)| *id != node_id);
+        self.picked_files.push((node_id, files));
+        true
+    }
+
+    /// Read the live state of every rendered form control, in tree order.
+    fn field_states(&self) -> Vec<forms::FieldState> {
+        let mut fields = Vec::new();
+        for fc in &self.renderer.form_controls {
+            let ctrl = ui::Control::from_id(fc.control_id);
+            let (value, state) = match fc.kind {
+                FormFieldKind::TextInput | FormFieldKind::Password => {
+                    let mut buf = [0u8; 2048];
+                    let len = ctrl.get_text(&mut buf) as usize;
+                    (String::from(core::str::from_utf8(&buf[..len]).unwrap_or("")), 0)
                 }
-                FormFieldKind::Textarea => {
-                    if fc.control_id == 0 { continue; }
-                    let ctrl = ui::Control::from_id(fc.control_id);
-                    let mut buf = [0u8; 8192];
-                    let len = ctrl.get_text(&mut buf);
-                    let val = core::str::from_utf8(&buf[..len as usize]).unwrap_or("");
-                    data.push((String::from(name), String::from(val)));
+                FormFieldKind::Textarea => (read_editor_text(&ui::TextEditor::from_id(fc.control_id)), 0),
+                FormFieldKind::Checkbox | FormFieldKind::Radio | FormFieldKind::Select => {
+                    (String::new(), ctrl.get_state())
                 }
-                _ => {}
-            }
+                FormFieldKind::Hidden => (String::new(), 0),
+                FormFieldKind::File => (String::new(), self.picked_files(fc.node_id).len() as u32),
+                FormFieldKind::Submit | FormFieldKind::ButtonEl => continue,
+            };
+            fields.push(forms::FieldState { node_id: fc.node_id, kind: fc.kind, value, state });
         }
-        data
+        fields.sort_by_key(|f| f.node_id);
+        fields
     }
 
     /// Internal: collect stylesheets, resolve styles, layout, and render controls.
     fn do_layout_and_render(&mut self, d: &dom::Dom) {
         debug_surf!("[webview] do_layout_and_render: {} DOM nodes", d.nodes.len());
 
+        // Boxes to rebuild in place, unless something else changed too.
+        let full_layout = core::mem::replace(&mut self.full_layout, false);
+        let relayout_nodes = self.relayout_nodes.take().filter(|_| !full_layout);
+
         // ── Stylesheet pipeline — parse once, reuse on every relayout ────────────
         //
         // `self.default_sheet` is parsed once in `WebView::new()`.
@@ -524,22 +1418,48 @@ impl WebView {
         // This eliminates the catastrophic O(images × CSS-bytes) re-parse cost
         // visible in logs as repeated 150 KB parses per image load.
 
+        // Transition start values: the current (possibly animated) values of
+        // nodes that declare transitions, taken before styles are re-resolved.
+        // Nodes driven by a keyframe animation are left to it.
+        let transition_before: Vec<(dom::NodeId, [css::Declaration; 4])> = match self.style_cache {
+            Some(ref cache) if cache.viewport_width == self.viewport_width => {
+                let animations = &self.js_runtime.active_animations;
+                cache.styles.iter().enumerate()
+                    .filter(|(id, s)| {
+                        !s.transitions.is_empty() && !animations.iter().any(|a| a.node_id == *id)
+                    })
+                    .map(|(id, s)| (id, style::transition_values(s)))
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+
         // Phase A: Parse inline <style> blocks — cached across relayouts.
         // Only re-parsed when dirty (new page via set_html, or JS mutations).
         if self.inline_sheets_dirty {
             self.inline_sheets.clear();
+            self.fonts.clear_inline();
             let mut inline_count = 0u32;
             for (i, node) in d.nodes.iter().enumerate() {
                 if let dom::NodeType::Element { tag: dom::Tag::Style, .. } = &node.node_type {
                     let css_text = d.text_content(i);
                     if !css_text.is_empty() {
                         debug_surf!("[webview] parse inline <style> #{}: {} bytes", inline_count, css_text.len());
-                        self.inline_sheets.push(css::parse_stylesheet(&css_text));
+                        let sheet = css::parse_stylesheet(&css_text);
+                        self.fonts.add_faces(&sheet.font_faces, &self.current_url, true);
+                        self.inline_sheets.push(sheet);
                         inline_count += 1;
                     }
                 }
             }
             self.inline_sheets_dirty = false;
+            // Every node may be affected — no incremental restyle.
+            self.style_cache = None;
+            // @keyframes of all sheets, in cascade order.
+            self.keyframes.clear();
+            push_keyframes(&mut self.keyframes, &self.default_sheet);
+            for sheet in &self.external_sheets { push_keyframes(&mut self.keyframes, sheet); }
+            for sheet in &self.inline_sheets { push_keyframes(&mut self.keyframes, sheet); }
             debug_surf!("[webview] parsed {} inline <style> blocks", inline_count);
         }
 
@@ -547,34 +1467,94 @@ impl WebView {
             1 + self.external_sheets.len() + self.inline_sheets.len(),
             self.external_sheets.len(), self.inline_sheets.len());
 
+        // Color scheme: a page whose dark support appeared or went away
+        // (new stylesheet, theme switch) needs a full restyle.
+        let page_scheme = {
+            let author: Vec<&css::Stylesheet> = self.external_sheets.iter().chain(self.inline_sheets.iter()).collect();
+            scheme::page_scheme(self.prefers_dark, self.force_dark, d, &author)
+        };
+        if page_scheme != self.page_scheme {
+            self.page_scheme = page_scheme;
+            self.style_cache = None;
+        }
+
         // Phase B: Resolve styles using zero-copy references to pre-parsed sheets.
         let vw = self.viewport_width;
         let vh = self.total_height_val.max(self.viewport_width);
         debug_surf!("[webview] resolve_styles start ({} nodes)", d.nodes.len());
-        let styles = {
-            let mut all_sheets: Vec<&css::Stylesheet> = Vec::with_capacity(
-                1 + self.external_sheets.len() + self.inline_sheets.len()
-            );
-            all_sheets.push(&self.default_sheet);
-            for sheet in &self.external_sheets { all_sheets.push(sheet); }
-            for sheet in &self.inline_sheets { all_sheets.push(sheet); }
-            style::resolve_styles(d, &all_sheets, vw, vh, &mut self.inline_style_cache)
-        };
-        debug_surf!("[webview] resolve_styles done: {} styles", styles.len());
-
-        // Register new @keyframe animations for nodes that request them.
-        // DISABLED: CSS animations are disabled for performance investigation.
-        // self.js_runtime.start_animations(&styles);
+
+        // Styles of the previous layout are reused for nodes that neither a
+        // late stylesheet (`restyle_nodes`) nor streaming (new nodes) touched.
+        let restyle_nodes = self.restyle_nodes.take();
+        let prev = match self.style_cache.take() {
+            Some(cache) if cache.viewport_width == vw && cache.prefers_dark == self.prefers_dark
+                && cache.styles.len() <= d.nodes.len() => {
+                Some(cache)
+            }
+            _ => None,
+        };
+        let incremental = prev.is_some();
+        let mut style_cache = {
+            let author: Vec<&css::Stylesheet> = self.external_sheets.iter().chain(self.inline_sheets.iter()).collect();
+            let all_sheets = scheme::cascade(&self.default_sheet, &self.dark_sheet, &author, page_scheme);
+            let dirty = restyle_nodes.as_deref().unwrap_or(&[]);
+            let prev = prev.map(|cache| (cache, dirty));
+            style::restyle(d, &all_sheets, vw, vh, self.prefers_dark, &mut self.inline_style_cache, prev)
+        };
+        debug_surf!("[webview] resolve_styles done: {} styles", style_cache.styles.len());
+
+        // Start @keyframes animations and transitions the new styles trigger,
+        // then apply the current animated values on top of them.
+        self.js_runtime.start_animations(&style_cache.styles);
+        if !transition_before.is_empty() {
+            self.js_runtime.start_transitions(&transition_before, &style_cache.styles);
+        }
+        if self.css_animating() {
+            let (_, overrides) = self.js_runtime.advance_animations(0, &self.keyframes);
+            self.anim_overrides = overrides;
+        } else {
+            self.anim_overrides.clear();
+        }
+        apply_anim_overrides(d, &mut style_cache.styles, &self.anim_overrides);
+        if page_scheme == PageScheme::ForcedDark {
+            scheme::darken_styles(&mut style_cache.styles);
+        }
+        self.fonts.assign(&mut style_cache.styles);
+        let styles = &style_cache.styles;
         #[cfg(feature = "debug_surf")]
         debug_surf!("[webview]   RSP=0x{:X} heap=0x{:X}", debug_rsp(), debug_heap_pos());
 
+        // A script changed a few subtrees: rebuild just their boxes and
+        // repaint the tiles they cover.
+        if let Some(ref nodes) = relayout_nodes {
+            if incremental && self.relayout_in_place(d, styles, nodes) {
+                self.style_cache = Some(style_cache);
+                if self.inspected.is_some() {
+                    self.update_highlights();
+                }
+                return;
+            }
+        }
+
+        // Scroll anchor: the element at the top of the viewport, so content
+        // inserted or grown above it (late images, streamed nodes) does not
+        // move the reading position.
+        let scroll_y = self.scroll_view.get_state() as i32;
+        let anchor = match self.layout_root {
+            Some(ref root) if self.scroller.anchoring && !self.scroller.is_dragging() => {
+                scroll::select_anchor(root, scroll_y, self.viewport_height as i32)
+            }
+            _ => None,
+        };
+
         // Drop old layout tree before allocating the new one — avoids holding
         // two full trees in memory simultaneously (can save several MB on complex pages).
         self.layout_root = None;
 
         // Layout.
         debug_surf!("[webview] layout start (viewport_width={})", self.viewport_width);
-        let root = layout::layout(d, &styles, self.viewport_width, &self.images);
+        let mut root = layout::layout(d, styles, self.viewport_width, &self.images);
+        patch_media_boxes(&mut root, &self.media);
         self.total_height_val = calc_total_height(&root);
         #[cfg(feature = "debug_surf")]
         {
@@ -587,11 +1567,16 @@ impl WebView {
         // Canvas and form controls persist across relayouts.
         self.renderer.clear();
 
+        // Text offsets refer to the old layout — drop selection and find state.
+        self.reset_selection_state();
+
         // Sync content view background to the body element's CSS background-color.
         let body_id = d.find_body().unwrap_or(0);
         let body_bg = styles.get(body_id).map(|s| s.background_color).unwrap_or(0);
-        let bg_color = if body_bg != 0 { body_bg } else { 0xFFFFFFFF };
+        let palette = page_scheme.palette();
+        let bg_color = if body_bg != 0 { body_bg } else { palette.canvas };
         self.content_view.set_color(bg_color);
+        self.renderer.set_palette(*palette);
 
         // Set content view height to document height.
         let doc_w = self.viewport_width as u32;
@@ -623,15 +1608,1025 @@ impl WebView {
         #[cfg(feature = "debug_surf")]
         debug_surf!("[webview]   RSP=0x{:X} heap=0x{:X}", debug_rsp(), debug_heap_pos());
 
+        // Re-apply the scroll position, shifted to keep the anchor in place.
+        let anchored = anchor.and_then(|a| {
+            scroll::node_top(&root, a.node).map(|top| top + a.offset)
+        });
+
         // Cache layout tree for scroll re-renders (no relayout needed on scroll).
         self.layout_root = Some(root);
-    }
+        self.style_cache = Some(style_cache);
+        self.scroller.anchoring = true;
+
+        if scroll_y > 0 || anchored.is_some() {
+            let target = anchored.unwrap_or(scroll_y).max(0).min(self.max_scroll());
+            // A running smooth scroll or fling moves along with the content.
+            if self.scroller.is_active() || self.scroller.is_dragging() {
+                self.scroller.shift(target - scroll_y);
+            } else {
+                self.scroller.jump(target);
+            }
+            self.show_scroll(target);
+        }
+
+        // `renderer.clear()` dropped the inspector overlay; redraw it on
+        // the new boxes.
+        if self.inspected.is_some() {
+            self.update_highlights();
+        }
+    }
+
+    // ─────────────────────────────────────────────────────────────────────
+    // Media (<audio> / <video>)
+    // ─────────────────────────────────────────────────────────────────────
+
+    /// Install the backend that decodes and plays `<audio>` / `<video>`
+    /// sources.  Players of the previous host are closed and the current
+    /// document's media elements are reopened on the new one.
+    pub fn set_media_host(&mut self, host: Box<dyn MediaHost>) {
+        self.reset_media();
+        self.media_host = Some(host);
+        self.sync_media();
+    }
+
+    /// Handle a click on a media controls bar: the play / pause button
+    /// toggles playback and the progress track seeks.  Returns `true` if
+    /// the click hit a controls bar.
+    pub fn media_click(&mut self, control_id: u32) -> bool {
+        let hit = self.renderer.tile_hit_coords(control_id)
+            .and_then(|(mx, doc_y)| self.renderer.hit_test_media_at(mx, doc_y));
+        let (node_id, x, w) = match hit {
+            Some(h) => h,
+            None => return false,
+        };
+        let state = self.media.iter()
+            .find(|m| m.node_id == node_id)
+            .map_or(MediaState::IDLE, |m| m.state);
+        let track_w = w - media::PLAY_BUTTON_WIDTH - media::TIME_WIDTH;
+        if x < media::PLAY_BUTTON_WIDTH {
+            let cmd = if state.paused { MediaCommand::Play } else { MediaCommand::Pause };
+            self.run_media_command(node_id, cmd);
+        } else if x < media::PLAY_BUTTON_WIDTH + track_w && state.duration_ms > 0 {
+            let pos = (x - media::PLAY_BUTTON_WIDTH) as u64 * state.duration_ms as u64 / track_w as u64;
+            self.run_media_command(node_id, MediaCommand::Seek(pos as u32));
+        }
+        true
+    }
+
+    /// Playback state of the media element `node_id`, if it is one.
+    pub fn media_state(&self, node_id: dom::NodeId) -> Option<MediaState> {
+        self.media.iter().find(|m| m.node_id == node_id).map(|m| m.state)
+    }
+
+    /// Close all players and forget the media elements (new document).
+    fn reset_media(&mut self) {
+        if let Some(host) = self.media_host.as_mut() {
+            for m in self.media.iter().filter(|m| m.opened) {
+                host.close(m.node_id);
+            }
+        }
+        self.media.clear();
+        self.js_runtime.clear_media_states();
+    }
+
+    /// Match the tracked media elements against the current DOM: open
+    /// players for new elements and changed sources, close the players of
+    /// removed elements.
+    fn sync_media(&mut self) {
+        let d = match self.dom_val.as_ref() {
+            Some(d) => d,
+            None => return,
+        };
+        let found = media::collect_media(d);
+        let host = &mut self.media_host;
+        self.media.retain(|m| {
+            let keep = found.iter().any(|(id, _)| *id == m.node_id);
+            if !keep && m.opened {
+                if let Some(h) = host.as_mut() {
+                    h.close(m.node_id);
+                }
+            }
+            keep
+        });
+
+        for (node_id, kind) in found {
+            let url = media::media_source(d, node_id)
+                .map(|src| js::resolve_url(&self.current_url, src))
+                .unwrap_or_default();
+            let idx = match self.media.iter().position(|m| m.node_id == node_id) {
+                Some(i) if self.media[i].url == url => continue,
+                Some(i) => {
+                    if self.media[i].opened {
+                        if let Some(h) = host.as_mut() {
+                            h.close(node_id);
+                        }
+                    }
+                    self.media[i].url = url;
+                    i
+                }
+                None => {
+                    self.media.push(media::MediaElement {
+                        node_id,
+                        kind,
+                        url,
+                        opened: false,
+                        state: MediaState::IDLE,
+                        has_frame: false,
+                    });
+                    self.media.len() - 1
+                }
+            };
+
+            let m = &mut self.media[idx];
+            m.state = MediaState::IDLE;
+            m.has_frame = false;
+            m.opened = false;
+            if let Some(h) = host.as_mut() {
+                if !m.url.is_empty() {
+                    m.opened = h.open(node_id, &m.url, kind);
+                    m.state.error = !m.opened;
+                    if m.opened && d.attr(node_id, "autoplay").is_some() {
+                        h.play(node_id);
+                    }
+                }
+            }
+            self.js_runtime.set_media_state(node_id, m.state);
+        }
+    }
+
+    /// Forward the playback requests made by scripts to the media host.
+    fn flush_media_commands(&mut self) {
+        for (node_id, cmd) in self.js_runtime.take_media_commands() {
+            self.run_media_command(node_id, cmd);
+        }
+    }
+
+    /// Forward one playback request to the media host.
+    fn run_media_command(&mut self, node_id: dom::NodeId, cmd: MediaCommand) {
+        let (m, host) = match (self.media.iter_mut().find(|m| m.node_id == node_id), self.media_host.as_mut()) {
+            (Some(m), Some(h)) => (m, h),
+            _ => return,
+        };
+        if cmd == MediaCommand::Load {
+            if m.opened {
+                host.close(node_id);
+            }
+            m.opened = !m.url.is_empty() && host.open(node_id, &m.url, m.kind);
+            m.has_frame = false;
+            return;
+        }
+        if !m.opened {
+            return;
+        }
+        match cmd {
+            MediaCommand::Play => {
+                if m.state.ended {
+                    host.seek(node_id, 0);
+                }
+                host.play(node_id);
+            }
+            MediaCommand::Pause => host.pause(node_id),
+            MediaCommand::Seek(ms) => host.seek(node_id, ms),
+            MediaCommand::Load => {}
+        }
+    }
+
+    /// Poll the host for playback state and new video frames, fire the
+    /// matching media events, and repaint elements whose appearance
+    /// changed.  Returns `true` if anything was repainted.
+    fn poll_media(&mut self) -> bool {
+        let host = match self.media_host.as_mut() {
+            Some(h) => h,
+            None => return false,
+        };
+        let mut changed: Vec<dom::NodeId> = Vec::new();
+        let mut events: Vec<(dom::NodeId, &'static str)> = Vec::new();
+        for m in self.media.iter_mut().filter(|m| m.opened) {
+            let id = m.node_id;
+            let mut dirty = false;
+            if let Some(frame) = host.take_frame(id) {
+                self.images.add(media::frame_key(id), frame.pixels, frame.width, frame.height);
+                m.has_frame = true;
+                dirty = true;
+            }
+            let st = host.state(id);
+            if st != m.state {
+                if st.duration_ms != m.state.duration_ms {
+                    events.push((id, "durationchange"));
+                }
+                if st.paused != m.state.paused {
+                    events.push((id, if st.paused { "pause" } else { "play" }));
+                }
+                if st.position_ms != m.state.position_ms {
+                    events.push((id, "timeupdate"));
+                }
+                if st.ended && !m.state.ended {
+                    events.push((id, "ended"));
+                }
+                if st.error && !m.state.error {
+                    events.push((id, "error"));
+                }
+                m.state = st;
+                self.js_runtime.set_media_state(id, st);
+                dirty = true;
+            }
+            if dirty {
+                changed.push(id);
+            }
+        }
+
+        if !changed.is_empty() {
+            if let Some(root) = self.layout_root.as_mut() {
+                patch_media_boxes(root, &self.media);
+                if let Some((y0, y1)) = renderer::paint_extent(root, &changed) {
+                    self.renderer.repaint_range(root, &self.images, y0, y1, self.bg_color_cached);
+                }
+            }
+        }
+        if !events.is_empty() {
+            if let Some(d) = self.dom_val.take() {
+                for (id, name) in events {
+                    self.js_runtime.dispatch_event(&d, id, name);
+                }
+                self.dom_val = Some(d);
+            }
+        }
+        !changed.is_empty()
+    }
+
+    // ─────────────────────────────────────────────────────────────────────
+    // Text selection
+    // ─────────────────────────────────────────────────────────────────────
+
+    /// Handle a mouse event from the selection callback.
+    ///
+    /// Mouse down starts a selection, moves extend it while the button is
+    /// held, and a double-click selects the word under the cursor.  Returns
+    /// `true` if the event hit a page canvas and was handled.
+    pub fn handle_selection_event(&mut self, control_id: u32, event_type: u32) -> bool {
+        // Plain hover moves are not part of a selection.
+        if matches!(event_type, ui::EVENT_MOUSE_MOVE | ui::EVENT_MOUSE_UP) && !self.selecting {
+            return false;
+        }
+        let (mx, doc_y) = match self.renderer.tile_hit_coords(control_id) {
+            Some(p) => p,
+            None => return false,
+        };
+        self.ensure_text_index();
+        let index = match self.text_index.as_ref() {
+            Some(i) => i,
+            None => return false,
+        };
+        let offset = match index.hit_test(mx, doc_y) {
+            Some(o) => o,
+            None => {
+                // No text on the page — a press still clears any selection.
+                if event_type == ui::EVENT_MOUSE_DOWN && self.selection.is_some() {
+                    self.selection = None;
+                    self.update_highlights();
+                }
+                return event_type == ui::EVENT_MOUSE_DOWN;
+            }
+        };
+
+        let new_sel = match event_type {
+            ui::EVENT_MOUSE_DOWN => {
+                self.selecting = true;
+                Some((offset, offset))
+            }
+            ui::EVENT_MOUSE_MOVE | ui::EVENT_MOUSE_UP => {
+                if event_type == ui::EVENT_MOUSE_UP {
+                    self.selecting = false;
+                }
+                self.selection.map(|(anchor, _)| (anchor, offset))
+            }
+            ui::EVENT_DOUBLE_CLICK => {
+                self.selecting = false;
+                Some(index.word_bounds(offset))
+            }
+            _ => return false,
+        };
+
+        if new_sel != self.selection {
+            self.selection = new_sel;
+            self.update_highlights();
+        }
+        true
+    }
+
+    /// Returns `true` if a non-empty text selection exists.
+    pub fn has_selection(&self) -> bool {
+        matches!(self.selection, Some((a, b)) if a != b)
+    }
+
+    /// The currently selected text, if any.
+    pub fn selected_text(&self) -> Option<String> {
+        let (a, b) = self.selection?;
+        if a == b {
+            return None;
+        }
+        self.text_index.as_ref().map(|i| String::from(i.slice(a, b)))
+    }
+
+    /// Copy the selected text to the system clipboard.
+    /// Returns `false` if nothing is selected.
+    pub fn copy_selection(&self) -> bool {
+        match self.selected_text() {
+            Some(text) => {
+                ui::clipboard_set(&text);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Select all text on the page.
+    pub fn select_all(&mut self) {
+        self.ensure_text_index();
+        if let Some(len) = self.text_index.as_ref().map(|i| i.len()) {
+            self.selection = Some((0, len));
+            self.selecting = false;
+            self.update_highlights();
+        }
+    }
+
+    /// Clear the text selection.
+    pub fn clear_selection(&mut self) {
+        if self.selection.take().is_some() {
+            self.selecting = false;
+            self.update_highlights();
+        }
+    }
+
+    // ─────────────────────────────────────────────────────────────────────
+    // Find in page
+    // ─────────────────────────────────────────────────────────────────────
+
+    /// Search the page for `query`, highlight all matches, and scroll to the
+    /// first one.  Returns the number of matches.
+    pub fn find(&mut self, query: &str, match_case: bool) -> usize {
+        self.ensure_text_index();
+        self.find_matches = match self.text_index.as_ref() {
+            Some(i) => i.find_all(query, match_case),
+            None => Vec::new(),
+        };
+        self.find_current = 0;
+        if !self.find_matches.is_empty() {
+            self.scroll_to_match();
+        }
+        self.update_highlights();
+        self.find_matches.len()
+    }
+
+    /// Move to the next match (wrapping).  Returns the index of the new
+    /// current match, or `None` if there are no matches.
+    pub fn find_next(&mut self) -> Option<usize> {
+        if self.find_matches.is_empty() {
+            return None;
+        }
+        self.find_current = (self.find_current + 1) % self.find_matches.len();
+        self.scroll_to_match();
+        self.update_highlights();
+        Some(self.find_current)
+    }
+
+    /// Move to the previous match (wrapping).  Returns the index of the new
+    /// current match, or `None` if there are no matches.
+    pub fn find_prev(&mut self) -> Option<usize> {
+        if self.find_matches.is_empty() {
+            return None;
+        }
+        let n = self.find_matches.len();
+        self.find_current = (self.find_current + n - 1) % n;
+        self.scroll_to_match();
+        self.update_highlights();
+        Some(self.find_current)
+    }
+
+    /// Number of matches from the last `find()`.
+    pub fn find_match_count(&self) -> usize {
+        self.find_matches.len()
+    }
+
+    /// Remove find-in-page highlights.
+    pub fn clear_find(&mut self) {
+        if !self.find_matches.is_empty() {
+            self.find_matches.clear();
+            self.find_current = 0;
+            self.update_highlights();
+        }
+    }
+
+    /// Build the text index from the cached layout tree if needed.
+    fn ensure_text_index(&mut self) {
+        if self.text_index.is_none() {
+            if let Some(ref root) = self.layout_root {
+                self.text_index = Some(selection::TextIndex::build(root));
+            }
+        }
+    }
+
+    /// Drop the text index, selection, and find matches (layout changed).
+    fn reset_selection_state(&mut self) {
+        self.text_index = None;
+        self.selection = None;
+        self.selecting = false;
+        self.find_matches.clear();
+        self.find_current = 0;
+    }
+
+    // ─────────────────────────────────────────────────────────────────────
+    // Inspector
+    // ─────────────────────────────────────────────────────────────────────
+
+    /// The DOM in document order with node ids, attributes and layout
+    /// rectangles (see [`inspect::InspectNode`]).
+    pub fn inspect_tree(&self) -> Vec<inspect::InspectNode> {
+        match self.dom() {
+            Some(d) => inspect::tree(d, self.layout_root.as_ref()),
+            None => Vec::new(),
+        }
+    }
+
+    /// [`inspect_tree()`](Self::inspect_tree) as JSON; with `with_styles`
+    /// every element also carries its computed style.
+    pub fn inspect_json(&self, with_styles: bool) -> String {
+        let styles = self.style_cache.as_ref().map(|c| &c.styles[..]).filter(|_| with_styles);
+        inspect::to_json(&self.inspect_tree(), styles)
+    }
+
+    /// Computed style of `node` as `(property, value)` pairs, or `None` if
+    /// the node has not been styled yet.
+    pub fn computed_style(&self, node: dom::NodeId) -> Option<Vec<(&'static str, String)>> {
+        let style = self.style_cache.as_ref()?.styles.get(node)?;
+        Some(inspect::computed_style(style))
+    }
+
+    /// Layout boxes `node` generated, in document coordinates.
+    pub fn node_rects(&self, node: dom::NodeId) -> Vec<inspect::NodeRect> {
+        match self.layout_root {
+            Some(ref root) => inspect::node_rects(root, node),
+            None => Vec::new(),
+        }
+    }
+
+    /// The deepest node under the viewport point `(x, y)`.
+    pub fn node_at(&self, x: i32, y: i32) -> Option<dom::NodeId> {
+        let root = self.layout_root.as_ref()?;
+        inspect::node_at(root, x, y + self.scroll_position())
+    }
+
+    /// Draw the margin / border / padding / content overlay over `node`,
+    /// or remove it with `None`.  The overlay follows relayouts until
+    /// removed or the page is cleared.
+    pub fn highlight_node(&mut self, node: Option<dom::NodeId>) {
+        if self.inspected != node {
+            self.inspected = node;
+            self.update_highlights();
+        }
+    }
+
+    /// Set (`Some`) or remove (`None`) one property in the `style`
+    /// attribute of `node` and update the page.  Opacity, transform and
+    /// background colour changes only repaint the affected rows; anything
+    /// else restyles the node's subtree and relays out.  Returns `false`
+    /// if `node` is not an element.
+    pub fn set_node_style(&mut self, node: dom::NodeId, property: &str, value: Option<&str>) -> bool {
+        let d = match self.dom_val.as_mut() {
+            Some(d) => d,
+            None => return false,
+        };
+        if !matches!(d.nodes.get(node).map(|n| &n.node_type), Some(dom::NodeType::Element { .. })) {
+            return false;
+        }
+        let style = inspect::edit_style_attr(d.attr(node, "style").unwrap_or(""), property, value);
+        if style.is_empty() {
+            d.remove_attr(node, "style");
+        } else {
+            d.set_attr(node, "style", &style);
+        }
+        self.inline_style_cache.retain(|(id, _)| *id != node);
+
+        if let Some(v) = value {
+            let decls = css::parse_inline_style(&alloc::format!("{}: {}", property, v));
+            if self.repaint_node_style(node, &decls) {
+                return true;
+            }
+        }
+        self.restyle_subtrees(&[node]);
+        self.relayout();
+        true
+    }
+
+    /// Paint-only fast path of `set_node_style()`, as `repaint_animated()`
+    /// does for animations.  Returns `false` if a relayout is needed.
+    fn repaint_node_style(&mut self, node: dom::NodeId, decls: &[css::Declaration]) -> bool {
+        let paint_only = !decls.is_empty() && decls.iter().all(|d| matches!(
+            d.property,
+            css::Property::Opacity | css::Property::Transform | css::Property::BackgroundColor
+        ));
+        let (root, style) = match (self.layout_root.as_mut(), self.style_cache.as_mut()) {
+            (Some(root), Some(cache)) if paint_only => match cache.styles.get_mut(node) {
+                Some(style) => (root, style),
+                None => return false,
+            },
+            _ => return false,
+        };
+        let before = renderer::paint_extent(root, &[node]);
+        for decl in decls {
+            style::apply_declaration(style, decl, style.font_size, 16);
+        }
+        if !patch_paint_props(root, node, style) {
+            return false;
+        }
+        let after = renderer::paint_extent(root, &[node]);
+        let (y0, y1) = match (before, after) {
+            (Some(a), Some(b)) => (a.0.min(b.0), a.1.max(b.1)),
+            (Some(r), None) | (None, Some(r)) => r,
+            (None, None) => return true,
+        };
+        self.renderer.repaint_range(root, &self.images, y0, y1, self.bg_color_cached);
+        true
+    }
+
+    // ─────────────────────────────────────────────────────────────────────
+    // Accessibility
+    // ─────────────────────────────────────────────────────────────────────
+
+    /// The page as an accessibility tree: roles, names, live field values,
+    /// bounds and states (see [`accessibility::AccessNode`]).  Screen
+    /// readers walk it in order; caret browsing uses the bounds.
+    pub fn accessibility_tree(&self) -> Vec<accessibility::AccessNode> {
+        let d = match self.dom() {
+            Some(d) => d,
+            None => return Vec::new(),
+        };
+        let styles = self.style_cache.as_ref().map(|c| &c.styles[..]);
+        let viewport = (self.scroll_position(), self.viewport_height as i32);
+        accessibility::tree(d, self.layout_root.as_ref(), styles, &self.field_states(), viewport)
+    }
+
+    /// [`accessibility_tree()`](Self::accessibility_tree) as JSON.
+    pub fn accessibility_json(&self) -> String {
+        accessibility::to_json(&self.accessibility_tree())
+    }
+
+    /// Move to an accessibility node: scroll its bounds into view (if not
+    /// already visible) and give keyboard focus to its form control, if it
+    /// has one.  Returns `false` if `node` is not in the tree.
+    pub fn focus_accessible(&mut self, node: dom::NodeId) -> bool {
+        let bounds = match self.accessibility_tree().iter().find(|n| n.node == node) {
+            Some(n) => n.bounds,
+            None => return false,
+        };
+        let (_, y, _, h) = bounds;
+        let top = self.scroll_position();
+        let bottom = top + self.viewport_height as i32;
+        if y < top || y + h > bottom {
+            self.scroll_to(y - self.viewport_height as i32 / 3);
+        }
+        if let Some(fc) = self.renderer.form_controls.iter().find(|fc| fc.node_id == node) {
+            ui::Control::from_id(fc.control_id).focus();
+        }
+        true
+    }
+
+    // ─────────────────────────────────────────────────────────────────────
+    // Scrolling
+    // ─────────────────────────────────────────────────────────────────────
+
+    /// Current scroll offset (document Y at the top of the viewport).
+    pub fn scroll_position(&self) -> i32 {
+        self.scroll_view.get_state() as i32
+    }
+
+    /// Enable or disable animated scrolling (on by default).  When off,
+    /// wheel input and `scroll_by()` jump immediately.
+    pub fn set_smooth_scrolling(&mut self, enabled: bool) {
+        self.scroller.smooth = enabled;
+    }
+
+    /// Report a scroll offset change of the ScrollView.  Call this from its
+    /// `on_scroll` handler: the ScrollView applies wheel input as an instant
+    /// jump, which is taken back here and replayed as a smooth scroll by
+    /// `tick()`.  Returns `true` if an animation started (keep ticking).
+    pub fn scroll_changed(&mut self, offset: u32) -> bool {
+        let offset = offset as i32;
+        let shown = self.scroller.position();
+        if offset == shown || !self.scroller.smooth || self.scroller.is_dragging() {
+            if !self.scroller.is_dragging() {
+                self.scroller.jump(offset);
+            }
+            return false;
+        }
+        // Wheel notches during an animation add to its target.
+        let target = self.scroller.target() + (offset - shown);
+        self.scroll_view.set_state(shown as u32);
+        self.scroller.animate_to(target.max(0).min(self.max_scroll()));
+        true
+    }
+
+    /// Scroll by `dy` pixels, animated when smooth scrolling is enabled.
+    /// Successive calls accumulate while the animation runs.
+    pub fn scroll_by(&mut self, dy: i32) {
+        self.sync_scroller();
+        let target = (self.scroller.target() + dy).max(0).min(self.max_scroll());
+        self.scroller.animate_to(target);
+        if !self.scroller.is_active() {
+            self.show_scroll(target);
+        }
+    }
+
+    /// Start dragging the page with the pointer at viewport Y `y`
+    /// (touch-style panning).  Stops any running animation.
+    pub fn drag_scroll_begin(&mut self, y: i32) {
+        self.sync_scroller();
+        self.scroller.drag_begin(y, anyos_std::sys::uptime_ms());
+    }
+
+    /// Move the pointer of a page drag to viewport Y `y`.
+    pub fn drag_scroll_move(&mut self, y: i32) {
+        if !self.scroller.is_dragging() {
+            return;
+        }
+        let max = self.max_scroll();
+        let pos = self.scroller.drag_move(y, anyos_std::sys::uptime_ms(), max);
+        self.scroll_view.set_state(pos as u32);
+    }
+
+    /// Release a page drag.  A quick release keeps the page moving with
+    /// decaying momentum; returns `true` if it does (keep ticking).
+    pub fn drag_scroll_end(&mut self) -> bool {
+        self.scroller.drag_end(anyos_std::sys::uptime_ms());
+        self.scroller.is_active()
+    }
+
+    /// Scroll so the element `node_id` is at the top of the viewport.
+    /// Returns `false` if the element generated no layout box.
+    pub fn scroll_to_element(&mut self, node_id: dom::NodeId, smooth: bool) -> bool {
+        let top = match self.layout_root.as_ref().and_then(|r| scroll::node_top(r, node_id)) {
+            Some(y) => y,
+            None => return false,
+        };
+        if smooth && self.scroller.smooth {
+            self.sync_scroller();
+            self.scroller.animate_to(top.max(0).min(self.max_scroll()));
+        } else {
+            self.scroll_to(top);
+        }
+        true
+    }
+
+    /// Scroll to the target of the URL fragment `fragment` (without the
+    /// leading `#`): the element with that `id`, or an `<a name>` of it.
+    /// An empty fragment or `top` scrolls to the top of the page.
+    /// Returns `false` if there is no such element.
+    pub fn scroll_to_fragment(&mut self, fragment: &str, smooth: bool) -> bool {
+        let fragment = fragment.trim_start_matches('#');
+        if fragment.is_empty() || fragment.eq_ignore_ascii_case("top") {
+            if smooth && self.scroller.smooth {
+                self.sync_scroller();
+                self.scroller.animate_to(0);
+            } else {
+                self.scroll_to(0);
+            }
+            return true;
+        }
+        let target = match self.dom_val.as_ref().or_else(|| self.stream.as_ref().map(|p| p.dom())) {
+            Some(d) => d.find_fragment_target(fragment),
+            None => None,
+        };
+        match target {
+            Some(id) => self.scroll_to_element(id, smooth),
+            None => false,
+        }
+    }
+
+    /// Start animations from the ScrollView's offset when idle (it may
+    /// have been moved by the scrollbar without `scroll_changed()`).
+    fn sync_scroller(&mut self) {
+        if !self.scroller.is_active() && !self.scroller.is_dragging() {
+            self.scroller.jump(self.scroll_view.get_state() as i32);
+        }
+    }
+
+    // ─────────────────────────────────────────────────────────────────────
+    // Navigation
+    // ─────────────────────────────────────────────────────────────────────
+
+    /// Session history and the in-flight navigation.
+    pub fn navigation(&self) -> &navigation::Navigation {
+        &self.navigation
+    }
+
+    /// Start navigating to `url`.  The host fetches
+    /// `navigation().pending().url` and hands the document to
+    /// `commit_navigation()`.  Returns the navigation ID.
+    pub fn load_url(&mut self, url: &str, referrer: Option<&str>) -> u32 {
+        let id = self.navigation.start_load(url, referrer);
+        self.fire_nav(id, navigation::NAV_STARTED);
+        id
+    }
+
+    /// Start navigating to the previous history entry.
+    /// Returns `None` if there is nothing to go back to.
+    pub fn go_back(&mut self) -> Option<u32> {
+        let id = self.navigation.start_traverse(-1)?;
+        self.fire_nav(id, navigation::NAV_STARTED);
+        Some(id)
+    }
+
+    /// Start navigating to the next history entry.
+    /// Returns `None` if there is nothing to go forward to.
+    pub fn go_forward(&mut self) -> Option<u32> {
+        let id = self.navigation.start_traverse(1)?;
+        self.fire_nav(id, navigation::NAV_STARTED);
+        Some(id)
+    }
+
+    /// Start reloading the current page.  With `bypass_cache` the decoded
+    /// image cache is dropped on commit and the pending navigation tells the
+    /// host not to use cached responses.
+    pub fn reload(&mut self, bypass_cache: bool) -> Option<u32> {
+        let id = self.navigation.start_reload(bypass_cache)?;
+        self.fire_nav(id, navigation::NAV_STARTED);
+        Some(id)
+    }
+
+    /// Record that the pending navigation was redirected to `url`.
+    pub fn navigation_redirected(&mut self, url: &str) {
+        self.navigation.redirect(url);
+    }
+
+    /// Render the document of the pending navigation and commit it to the
+    /// session history.
+    ///
+    /// The page URL is the last redirect target (or the requested URL).
+    /// Back/forward and reload restore the entry's saved scroll position;
+    /// new loads start at the top.
+    pub fn commit_navigation(&mut self, html: &str) {
+        let (url, bypass_cache) = match self.navigation.pending() {
+            Some(p) => (String::from(p.final_url()), p.bypass_cache),
+            None => (self.current_url.clone(), false),
+        };
+
+        // Remember where the user was on the page being left.  A restored
+        // page that was never loaded keeps its saved position.
+        if self.session_drafts.take().is_none() && self.resume_nav.is_none() {
+            self.navigation.save_scroll(self.scroll_view.get_state() as i32);
+        }
+
+        if bypass_cache {
+            self.images.clear();
+        }
+        self.set_url(&url);
+        self.set_html(html);
+
+        let (id, restore) = self.navigation.commit(&url);
+        let title = self.get_title().unwrap_or_default();
+        self.navigation.set_title(&title);
+        self.committed_nav_id = id;
+
+        // New loads of a URL with a fragment start at its target element.
+        let target = restore.unwrap_or_else(|| {
+            let frag = url.split_once('#').map_or("", |(_, f)| f);
+            self.dom_val.as_ref()
+                .and_then(|d| d.find_fragment_target(frag))
+                .and_then(|id| self.layout_root.as_ref().and_then(|r| scroll::node_top(r, id)))
+                .unwrap_or(0)
+        });
+        let applied = self.scroll_to(target);
+        self.scroll_restore = if applied != target { Some(target) } else { None };
+
+        if let Some((resume_id, drafts)) = self.resume_nav.take() {
+            if resume_id == id {
+                self.apply_drafts(&drafts);
+            }
+        }
+
+        self.fire_nav(id, navigation::NAV_COMMITTED);
+    }
+
+    /// Report that the committed page finished loading (sub-resources
+    /// included).  Retries a scroll restore the page was too short for.
+    pub fn finish_navigation(&mut self) {
+        if let Some(y) = self.scroll_restore.take() {
+            self.scroll_to(y);
+        }
+        self.fire_nav(self.committed_nav_id, navigation::NAV_FINISHED);
+    }
+
+    /// Report that the pending navigation failed.  The current page stays.
+    pub fn fail_navigation(&mut self) {
+        if let Some(id) = self.navigation.fail() {
+            // A failed resume leaves the session waiting for the next try.
+            if let Some((_, drafts)) = self.resume_nav.take() {
+                self.session_drafts = Some(drafts);
+            }
+            self.fire_nav(id, navigation::NAV_FAILED);
+        }
+    }
+
+    // ─────────────────────────────────────────────────────────────────────
+    // Session persistence
+    // ─────────────────────────────────────────────────────────────────────
+
+    /// Serialize the session history, the scroll position and the form
+    /// fields the user changed on the current page into a blob for
+    /// `restore_session()` (see [`session`]).  Passwords are not saved.
+    pub fn save_session(&self) -> Vec<u8> {
+        let mut entries = self.navigation.entries().to_vec();
+        let current = self.navigation.current_index();
+        let drafts = match self.session_drafts {
+            // Restored but not loaded yet: save what was restored.
+            Some(ref drafts) => drafts.clone(),
+            None => {
+                if let Some(i) = current {
+                    entries[i].scroll_y = self.scroll_position();
+                }
+                match self.dom_val.as_ref() {
+                    Some(d) => session::collect_drafts(d, &self.field_states()),
+                    None => Vec::new(),
+                }
+            }
+        };
+        session::encode(&session::Session { entries, current, drafts })
+    }
+
+    /// Replace the page and history with a session saved by
+    /// `save_session()`.  Nothing is fetched: history and URL are restored
+    /// and the page stays empty until `resume_session()`.  Returns `false`
+    /// if `data` is not a valid session blob.
+    pub fn restore_session(&mut self, data: &[u8]) -> bool {
+        let saved = match session::decode(data) {
+            Some(s) => s,
+            None => return false,
+        };
+        self.clear();
+        self.resume_nav = None;
+        self.navigation.restore(saved.entries, saved.current);
+        let url = String::from(self.navigation.current_url().unwrap_or(""));
+        self.set_url(&url);
+        self.session_drafts = self.navigation.current().map(|_| saved.drafts);
+        true
+    }
+
+    /// Whether a restored session is waiting for `resume_session()`.
+    pub fn needs_resume(&self) -> bool {
+        self.session_drafts.is_some()
+    }
+
+    /// Load the current page of a restored session.  Call it when the user
+    /// first interacts with the view.  Starts a reload navigation; on
+    /// commit the saved scroll position and form drafts are applied.
+    /// Returns `None` if there is nothing to resume.
+    pub fn resume_session(&mut self) -> Option<u32> {
+        if self.session_drafts.is_none() {
+            return None;
+        }
+        let id = self.reload(false)?;
+        self.resume_nav = self.session_drafts.take().map(|drafts| (id, drafts));
+        Some(id)
+    }
+
+    /// Fill restored form drafts into the fields of the current page.
+    fn apply_drafts(&mut self, drafts: &[session::FormDraft]) {
+        let fields = self.field_states();
+        let dom = match self.dom_val.as_ref() {
+            Some(d) => d,
+            None => return,
+        };
+        for (i, draft) in session::match_drafts(dom, &fields, drafts) {
+            let node_id = fields[i].node_id;
+            let control_id = match self.renderer.form_controls.iter().find(|fc| fc.node_id == node_id) {
+                Some(fc) => fc.control_id,
+                None => continue,
+            };
+            match draft.kind {
+                FormFieldKind::TextInput => ui::Control::from_id(control_id).set_text(&draft.value),
+                FormFieldKind::Textarea => ui::TextEditor::from_id(control_id).set_text(&draft.value),
+                _ => ui::Control::from_id(control_id).set_state(draft.state),
+            }
+        }
+    }
+
+    fn fire_nav(&self, id: u32, event: u32) {
+        if let Some(cb) = self.nav_cb {
+            cb(id, event, self.nav_cb_ud);
+        }
+    }
+
+    /// Scroll the view to document Y `y` (clamped) and create its tiles,
+    /// stopping any smooth scroll or fling.
+    /// Returns the scroll position actually applied.
+    fn scroll_to(&mut self, y: i32) -> i32 {
+        let target = y.max(0).min(self.max_scroll());
+        self.scroller.jump(target);
+        self.show_scroll(target);
+        target
+    }
+
+    /// Put the ScrollView at `y` and create the tiles it shows.
+    fn show_scroll(&mut self, y: i32) {
+        self.scroll_view.set_state(y as u32);
+        self.render_viewport(y);
+        self.last_render_scroll_y = y;
+    }
+
+    /// Largest valid scroll offset for the current document.
+    fn max_scroll(&self) -> i32 {
+        (self.total_height_val - self.viewport_height as i32).max(0)
+    }
+
+    /// Scroll so the current find match is in view (one third from the top).
+    fn scroll_to_match(&mut self) {
+        let (start, _) = match self.find_matches.get(self.find_current) {
+            Some(m) => *m,
+            None => return,
+        };
+        let y = match self.text_index.as_ref() {
+            Some(i) => i.y_for_offset(start),
+            None => return,
+        };
+        self.scroll_to(y - self.viewport_height as i32 / 3);
+    }
+
+    /// Recompute selection and find highlights and repaint affected tiles.
+    fn update_highlights(&mut self) {
+        let mut highlights = Vec::new();
+        if let Some(ref index) = self.text_index {
+            for (i, &(s, e)) in self.find_matches.iter().enumerate() {
+                let color = if i == self.find_current { FIND_CURRENT_COLOR } else { FIND_MATCH_COLOR };
+                for (x, y, w, h) in index.rects_for_range(s, e) {
+                    highlights.push(Highlight { x, y, w, h, color });
+                }
+            }
+            if let Some((a, b)) = self.selection {
+                for (x, y, w, h) in index.rects_for_range(a, b) {
+                    highlights.push(Highlight { x, y, w, h, color: SELECTION_COLOR });
+                }
+            }
+        }
+        if let (Some(node), Some(root)) = (self.inspected, self.layout_root.as_ref()) {
+            for r in inspect::node_rects(root, node) {
+                inspect::highlight_rects(&r, &mut highlights);
+            }
+        }
+        if let Some(ref root) = self.layout_root {
+            self.renderer.set_highlights(root, &self.images, highlights, self.bg_color_cached);
+        }
+    }
 
     /// Access the JS runtime (e.g. for evaluating additional scripts or reading console).
     pub fn js_runtime(&mut self) -> &mut js::JsRuntime {
         &mut self.js_runtime
     }
 
+    /// Deliver the response to an async XHR / fetch request issued by the page.
+    ///
+    /// Must be called on the UI thread (the host typically performs the
+    /// request on a network worker and forwards the result here from its
+    /// poll timer).  Re-lays out the page if the callbacks mutated the DOM.
+    pub fn http_response(&mut self, resp: &js::HttpResponse) {
+        if let Some(d) = self.dom_val.take() {
+            self.js_runtime.http_response(&d, resp);
+            self.dom_val = Some(d);
+            if !self.js_runtime.mutations.is_empty() {
+                self.relayout();
+            }
+        }
+    }
+
+    /// Report a network failure for an async XHR / fetch request.
+    pub fn http_failed(&mut self, request_id: u64) {
+        if let Some(d) = self.dom_val.take() {
+            self.js_runtime.http_failed(&d, request_id);
+            self.dom_val = Some(d);
+            if !self.js_runtime.mutations.is_empty() {
+                self.relayout();
+            }
+        }
+    }
+
+    /// Set the browser profile directory used to persist `localStorage`.
+    ///
+    /// Takes effect for pages loaded after this call.  The embedding browser
+    /// typically stores its `cookies::CookieJar` in the same directory.
+    pub fn set_profile_dir(&mut self, dir: &str) {
+        self.js_runtime.set_profile_dir(dir);
+    }
+
+    /// Take the `document.cookie` assignments made by page scripts since the
+    /// last call, for the host to store via `CookieJar::set_cookie(…, true)`.
+    pub fn take_cookie_writes(&mut self) -> Vec<String> {
+        self.js_runtime.take_cookie_writes()
+    }
+
+    /// Persisted `localStorage` entries of `origin`, for inspection.
+    pub fn local_storage_entries(&self, origin: &str) -> Vec<(String, String)> {
+        self.js_runtime.local_storage_entries(origin)
+    }
+
     /// Get console output from JavaScript execution.
     pub fn js_console(&self) -> &[String] {
         self.js_runtime.get_console()
@@ -694,6 +2689,14 @@ impl WebView {
     }
 }
 
+/// Read the full text of a textarea editor.
+fn read_editor_text(editor: &ui::TextEditor) -> String {
+    let mut buf = alloc::vec![0u8; 16384];
+    let len = editor.get_text(&mut buf) as usize;
+    buf.truncate(len);
+    String::from_utf8(buf).unwrap_or_default()
+}
+
 /// Count total layout boxes in the tree (debug only).
 #[cfg(feature = "debug_surf")]
 fn count_layout_boxes(root: &LayoutBox) -> usize {
@@ -704,6 +2707,75 @@ fn count_layout_boxes(root: &LayoutBox) -> usize {
     count
 }
 
+/// Append the `@keyframes` blocks of `sheet`, with stops sorted by offset.
+fn push_keyframes(out: &mut Vec<css::KeyframeSet>, sheet: &css::Stylesheet) {
+    for kf in &sheet.keyframes {
+        let mut kf = kf.clone();
+        kf.stops.sort_by_key(|s| s.offset);
+        out.push(kf);
+    }
+}
+
+/// Apply animation / transition values on top of resolved styles.
+///
+/// An animated `color` also reaches the descendants that inherited it
+/// (those whose colour equals the node's un-animated colour).
+fn apply_anim_overrides(
+    d: &dom::Dom,
+    styles: &mut [style::ComputedStyle],
+    overrides: &[(dom::NodeId, Vec<css::Declaration>)],
+) {
+    for (id, decls) in overrides {
+        let id = *id;
+        if id >= styles.len() { continue; }
+        let parent_fs = d.nodes[id].parent
+            .and_then(|pid| styles.get(pid))
+            .map_or(16, |s| s.font_size);
+        let old_color = styles[id].color;
+        for decl in decls {
+            style::apply_declaration(&mut styles[id], decl, parent_fs, 16);
+        }
+        let new_color = styles[id].color;
+        if new_color == old_color { continue; }
+        let mut stack: Vec<dom::NodeId> = d.nodes[id].children.clone();
+        while let Some(c) = stack.pop() {
+            if c < styles.len() && styles[c].color == old_color {
+                styles[c].color = new_color;
+                stack.extend_from_slice(&d.nodes[c].children);
+            }
+        }
+    }
+}
+
+/// Copy the paint-only properties of `style` to every layout box of
+/// `node_id`.  Returns `false` if the node has no box.
+fn patch_paint_props(bx: &mut LayoutBox, node_id: dom::NodeId, style: &style::ComputedStyle) -> bool {
+    let mut found = false;
+    if bx.node_id == Some(node_id) {
+        bx.opacity = style.opacity;
+        bx.transform = style.transform.clone();
+        bx.bg_color = style.background_color;
+        found = true;
+    }
+    for child in &mut bx.children {
+        found |= patch_paint_props(child, node_id, style);
+    }
+    found
+}
+
+/// Copy the playback state of `media` into the matching media boxes.
+fn patch_media_boxes(bx: &mut LayoutBox, media: &[media::MediaElement]) {
+    if let (Some(mb), Some(id)) = (bx.media.as_mut(), bx.node_id) {
+        if let Some(m) = media.iter().find(|m| m.node_id == id) {
+            mb.state = m.state;
+            mb.has_frame = m.has_frame;
+        }
+    }
+    for child in &mut bx.children {
+        patch_media_boxes(child, media);
+    }
+}
+
 /// Calculate total document height from the root layout box.
 /// Fixed-position boxes are excluded — they are viewport-anchored and do not
 /// contribute to the scrollable document height.
@@ -754,6 +2826,8 @@ hr { margin: 16px 0; border: none; border-top: 1px solid #ccc; }
 table { border-collapse: collapse; }
 td, th { padding: 4px 8px; }
 img { max-width: 100%; }
+video { max-width: 100%; }
+audio:not([controls]) { display: none; }
 strong, b { font-weight: bold; }
 em, i { font-style: italic; }
 ";
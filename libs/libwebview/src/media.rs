@@ -0,0 +1,165 @@
+//! Media elements — `<audio>` and `<video>`.
+//!
+//! libwebview does not decode media itself.  The embedder implements
+//! `MediaHost` (decoding in-process or routing to the system media library)
+//! and installs it with `WebView::set_media_host()`.  The WebView opens one
+//! player per media element, keyed by the element's DOM node id, forwards
+//! play / pause / seek requests from the built-in controls and from page
+//! scripts, and polls the host from `tick()` for the playback state and the
+//! current video frame.
+//!
+//! Without a host, media elements still lay out and paint (poster image and
+//! controls bar) but never leave the paused state.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::dom::{Dom, NodeId, Tag};
+
+/// Height of the controls bar painted at the bottom of a media element.
+pub const CONTROLS_HEIGHT: i32 = 32;
+/// Width of the play / pause button at the left end of the controls bar.
+pub const PLAY_BUTTON_WIDTH: i32 = 32;
+/// Width reserved for the time readout at the right end of the controls bar.
+pub const TIME_WIDTH: i32 = 88;
+
+/// Kind of media element.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MediaKind {
+    Audio,
+    Video,
+}
+
+/// Playback state of one player, as reported by the host.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MediaState {
+    pub paused: bool,
+    /// Playback reached the end of the media.
+    pub ended: bool,
+    /// Current playback position in milliseconds.
+    pub position_ms: u32,
+    /// Total duration in milliseconds (0 = unknown, e.g. not loaded yet or
+    /// a live stream).
+    pub duration_ms: u32,
+    /// The media could not be loaded or decoded.
+    pub error: bool,
+}
+
+impl MediaState {
+    /// State of an element that has not started playing.
+    pub const IDLE: MediaState = MediaState {
+        paused: true,
+        ended: false,
+        position_ms: 0,
+        duration_ms: 0,
+        error: false,
+    };
+}
+
+/// A decoded video frame handed over by the host.
+pub struct MediaFrame {
+    /// ARGB pixels, `width × height`, row-major.
+    pub pixels: Vec<u32>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A playback request from a page script or the built-in controls.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MediaCommand {
+    Play,
+    Pause,
+    /// Seek to the given position in milliseconds.
+    Seek(u32),
+    /// Reload the media source (`el.load()`).
+    Load,
+}
+
+/// Media backend supplied by the embedder.
+///
+/// `id` is the DOM node id of the `<audio>` / `<video>` element; it is
+/// stable until `close()` is called for it.  All methods are called on the
+/// UI thread.
+pub trait MediaHost {
+    /// Open a player for `url` (absolute).  Returns `false` if the media
+    /// cannot be played; the element then reports an error state.
+    fn open(&mut self, id: NodeId, url: &str, kind: MediaKind) -> bool;
+    /// Release the player of element `id`.
+    fn close(&mut self, id: NodeId);
+    fn play(&mut self, id: NodeId);
+    fn pause(&mut self, id: NodeId);
+    fn seek(&mut self, id: NodeId, position_ms: u32);
+    /// Current playback state of element `id`.
+    fn state(&mut self, id: NodeId) -> MediaState;
+    /// Take the newest decoded video frame of element `id`, if it changed
+    /// since the previous call.  Audio players return `None`.
+    fn take_frame(&mut self, _id: NodeId) -> Option<MediaFrame> {
+        None
+    }
+}
+
+/// Per-element playback bookkeeping kept by the WebView.
+pub(crate) struct MediaElement {
+    pub node_id: NodeId,
+    pub kind: MediaKind,
+    /// Resolved source URL (empty = no source).
+    pub url: String,
+    /// Whether the host accepted the source.
+    pub opened: bool,
+    pub state: MediaState,
+    /// Whether a host frame is stored in the image cache under `frame_key()`.
+    pub has_frame: bool,
+}
+
+/// Image cache key under which the current video frame of `node_id` is stored.
+pub fn frame_key(node_id: NodeId) -> String {
+    format!("media-frame:{}", node_id)
+}
+
+/// Source URL of a media element: its `src` attribute, else the first
+/// `<source src>` child.
+pub fn media_source(dom: &Dom, node_id: NodeId) -> Option<&str> {
+    if let Some(src) = dom.attr(node_id, "src").filter(|s| !s.is_empty()) {
+        return Some(src);
+    }
+    dom.get(node_id).children.iter()
+        .filter(|&&c| dom.tag(c) == Some(Tag::Source))
+        .find_map(|&c| dom.attr(c, "src").filter(|s| !s.is_empty()))
+}
+
+/// All `<audio>` / `<video>` elements attached to the document.
+pub(crate) fn collect_media(dom: &Dom) -> Vec<(NodeId, MediaKind)> {
+    let mut out = Vec::new();
+    for id in 0..dom.nodes.len() {
+        let kind = match dom.tag(id) {
+            Some(Tag::Audio) => MediaKind::Audio,
+            Some(Tag::Video) => MediaKind::Video,
+            _ => continue,
+        };
+        if is_connected(dom, id) {
+            out.push((id, kind));
+        }
+    }
+    out
+}
+
+/// Whether `id` is still reachable from the document root (nodes removed by
+/// scripts stay in the arena without a parent).
+fn is_connected(dom: &Dom, mut id: NodeId) -> bool {
+    while let Some(parent) = dom.nodes[id].parent {
+        id = parent;
+    }
+    id == 0
+}
+
+/// Format a position as `m:ss` (or `h:mm:ss` from one hour on).
+pub fn format_time(ms: u32) -> String {
+    let secs = ms / 1000;
+    let (h, m, s) = (secs / 3600, (secs / 60) % 60, secs % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
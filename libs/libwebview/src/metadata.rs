@@ -0,0 +1,356 @@
+//! Page metadata for the browser chrome — title, favicon, theme colour and
+//! Open Graph properties.
+//!
+//! [`PageMeta`] follows the document as it is parsed (streamed chunks,
+//! script mutations) and reports what changed through the metadata
+//! callback (`cb(kind, 0, userdata)` with `kind` one of the `META_*`
+//! constants), so a URL bar or tab strip can update before the page has
+//! finished loading.
+//!
+//! Favicons are fetched by the embedder, like web fonts: it asks for the
+//! next candidate with `WebView::take_favicon_request()` and hands the file
+//! back with `WebView::add_favicon()` / `WebView::favicon_failed()`.
+//! Candidates are the page's `<link rel="icon">` (and
+//! `apple-touch-icon`) URLs, best size first, then `/favicon.ico` of the
+//! page's origin once the document is complete.  The file is decoded (ICO,
+//! PNG, BMP, GIF, JPEG) and scaled to [`FAVICON_SIZE`] square.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use libanyui_client as ui;
+
+use crate::css;
+use crate::dom::{Dom, NodeType, Tag};
+
+/// Metadata kind: the `<title>` changed (`WebView::get_title()`).
+pub const META_TITLE: u32 = 1;
+/// Metadata kind: a favicon was loaded or cleared (`WebView::favicon()`).
+pub const META_ICON: u32 = 2;
+/// Metadata kind: `<meta name="theme-color">` changed
+/// (`WebView::theme_color()`).
+pub const META_THEME_COLOR: u32 = 3;
+
+/// Width and height of a decoded favicon in pixels (tab / URL bar size).
+pub const FAVICON_SIZE: u32 = 16;
+
+/// A decoded favicon, `FAVICON_SIZE` square.
+pub struct Favicon {
+    /// URL the icon was loaded from.
+    pub url: String,
+    /// ARGB pixels, `width * height`.
+    pub pixels: Vec<u32>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Open Graph properties (`<meta property="og:…">`) of a page, for share
+/// sheets and bookmarks.  `image` and `url` are absolute.
+#[derive(Clone, Default, Debug)]
+pub struct OpenGraph {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub url: Option<String>,
+    pub site_name: Option<String>,
+    /// `og:type`, e.g. `"website"` or `"article"`.
+    pub kind: Option<String>,
+}
+
+/// Open Graph properties of `dom`, resolving URLs against `page_url`.
+/// The first occurrence of a property wins.
+pub fn open_graph(dom: &Dom, page_url: &str) -> OpenGraph {
+    let mut og = OpenGraph::default();
+    for i in 0..dom.nodes.len() {
+        if dom.tag(i) != Some(Tag::Meta) {
+            continue;
+        }
+        let prop = match dom.attr(i, "property").or_else(|| dom.attr(i, "name")) {
+            Some(p) => p.trim(),
+            None => continue,
+        };
+        let content = match dom.attr(i, "content") {
+            Some(c) if !c.trim().is_empty() => c.trim(),
+            _ => continue,
+        };
+        let slot = match prop.to_ascii_lowercase().as_str() {
+            "og:title" => &mut og.title,
+            "og:description" => &mut og.description,
+            "og:image" | "og:image:url" => &mut og.image,
+            "og:url" => &mut og.url,
+            "og:site_name" => &mut og.site_name,
+            "og:type" => &mut og.kind,
+            _ => continue,
+        };
+        if slot.is_none() {
+            *slot = Some(String::from(content));
+        }
+    }
+    for url in [&mut og.image, &mut og.url] {
+        if let Some(u) = url.as_mut() {
+            *u = crate::js::resolve_url(page_url, u);
+        }
+    }
+    og
+}
+
+/// Metadata of the current document and the state of its favicon.
+pub(crate) struct PageMeta {
+    title: Option<String>,
+    theme_color: Option<u32>,
+    /// Favicon candidates, best first.
+    icons: Vec<String>,
+    /// Whether the document is complete (enables the `/favicon.ico` fallback).
+    complete: bool,
+    /// Favicon URLs handed to the embedder, in order.
+    tried: Vec<String>,
+    /// Favicon URL being fetched.
+    pending: Option<String>,
+    favicon: Option<Favicon>,
+}
+
+impl PageMeta {
+    pub fn new() -> Self {
+        PageMeta {
+            title: None,
+            theme_color: None,
+            icons: Vec::new(),
+            complete: false,
+            tried: Vec::new(),
+            pending: None,
+            favicon: None,
+        }
+    }
+
+    /// Forget the previous document.  Returns the `META_*` kinds that were
+    /// set and are now cleared.
+    pub fn reset(&mut self) -> Vec<u32> {
+        let mut cleared = Vec::new();
+        if self.title.is_some() {
+            cleared.push(META_TITLE);
+        }
+        if self.favicon.is_some() {
+            cleared.push(META_ICON);
+        }
+        if self.theme_color.is_some() {
+            cleared.push(META_THEME_COLOR);
+        }
+        *self = PageMeta::new();
+        cleared
+    }
+
+    /// Re-read the title, theme colour and icon links of `dom`.  Returns
+    /// the `META_TITLE` / `META_THEME_COLOR` kinds that changed.
+    pub fn scan(&mut self, dom: &Dom, page_url: &str, prefers_dark: bool, complete: bool) -> Vec<u32> {
+        let mut changed = Vec::new();
+        let title = dom.find_title().map(|t| collapse_whitespace(&t));
+        if title != self.title {
+            self.title = title;
+            changed.push(META_TITLE);
+        }
+        let theme = theme_color(dom, prefers_dark);
+        if theme != self.theme_color {
+            self.theme_color = theme;
+            changed.push(META_THEME_COLOR);
+        }
+        self.icons = icon_links(dom, page_url);
+        self.complete = complete;
+        changed
+    }
+
+    pub fn theme_color(&self) -> Option<u32> {
+        self.theme_color
+    }
+
+    pub fn favicon(&self) -> Option<&Favicon> {
+        self.favicon.as_ref()
+    }
+
+    /// Next favicon URL to fetch: the best candidate not tried yet that
+    /// `allowed` accepts.  `None` while a fetch is pending or once an icon
+    /// is loaded.
+    pub fn take_request(&mut self, page_url: &str, allowed: impl Fn(&str) -> bool) -> Option<String> {
+        if self.pending.is_some() || self.favicon.is_some() {
+            return None;
+        }
+        let mut candidates = self.icons.clone();
+        if self.complete && (page_url.starts_with("http://") || page_url.starts_with("https://")) {
+            candidates.push(crate::js::resolve_url(page_url, "/favicon.ico"));
+        }
+        let url = candidates
+            .into_iter()
+            .find(|u| !self.tried.contains(u) && allowed(u))?;
+        self.tried.push(url.clone());
+        self.pending = Some(url.clone());
+        Some(url)
+    }
+
+    /// Decode the fetched favicon `url`.  Returns `true` if it became the
+    /// page's icon; a file that cannot be decoded counts as failed.
+    pub fn loaded(&mut self, url: &str, data: &[u8]) -> bool {
+        if self.pending.as_deref() != Some(url) {
+            return false;
+        }
+        self.pending = None;
+        let icon = if data.starts_with(&[0, 0, 1, 0]) {
+            ui::Icon::from_ico_bytes(data, FAVICON_SIZE)
+        } else {
+            ui::Icon::from_bytes(data)
+        };
+        let icon = match icon {
+            Some(i) if i.width > 0 && i.height > 0 => i,
+            _ => return false,
+        };
+        self.favicon = Some(Favicon {
+            url: String::from(url),
+            pixels: scale_to_fit(&icon.pixels, icon.width, icon.height, FAVICON_SIZE),
+            width: FAVICON_SIZE,
+            height: FAVICON_SIZE,
+        });
+        true
+    }
+
+    /// The fetch of favicon `url` failed; the next candidate may be taken.
+    pub fn failed(&mut self, url: &str) {
+        if self.pending.as_deref() == Some(url) {
+            self.pending = None;
+        }
+    }
+}
+
+/// Collapse runs of whitespace to single spaces and trim, as browsers do
+/// for `document.title`.
+fn collapse_whitespace(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for word in s.split_whitespace() {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    out
+}
+
+/// The first `<meta name="theme-color">` whose `media` matches the colour
+/// scheme.
+fn theme_color(dom: &Dom, prefers_dark: bool) -> Option<u32> {
+    for i in 0..dom.nodes.len() {
+        if dom.tag(i) != Some(Tag::Meta) {
+            continue;
+        }
+        if !dom.attr(i, "name").map_or(false, |n| n.trim().eq_ignore_ascii_case("theme-color")) {
+            continue;
+        }
+        if let Some(media) = dom.attr(i, "media") {
+            let query = css::parse_media_query(media);
+            if !css::evaluate_media_query(&query, i32::MAX, i32::MAX, prefers_dark) {
+                continue;
+            }
+        }
+        let content = dom.attr(i, "content").unwrap_or("").trim();
+        if let css::CssValue::Color(c) = css::parse_value(&css::Property::Color, content) {
+            return Some(c | 0xFF000000);
+        }
+    }
+    None
+}
+
+/// Absolute URLs of the page's icon links, best first: `rel="icon"` before
+/// `apple-touch-icon`, then the smallest declared size of at least
+/// `FAVICON_SIZE`, then undeclared sizes, then smaller ones.  SVG and mask
+/// icons are skipped (they need a renderer).
+fn icon_links(dom: &Dom, page_url: &str) -> Vec<String> {
+    let mut found: Vec<(u32, u32, String)> = Vec::new();
+    for (i, node) in dom.nodes.iter().enumerate() {
+        if !matches!(node.node_type, NodeType::Element { tag: Tag::Link, .. }) {
+            continue;
+        }
+        let rel = dom.attr(i, "rel").unwrap_or("");
+        let mut icon = false;
+        let mut touch = false;
+        for token in rel.split_ascii_whitespace() {
+            if token.eq_ignore_ascii_case("icon") {
+                icon = true;
+            } else if token.eq_ignore_ascii_case("apple-touch-icon")
+                || token.eq_ignore_ascii_case("apple-touch-icon-precomposed")
+            {
+                touch = true;
+            }
+        }
+        if !icon && !touch {
+            continue;
+        }
+        let href = match dom.attr(i, "href") {
+            Some(h) if !h.trim().is_empty() => h.trim(),
+            _ => continue,
+        };
+        let svg = dom.attr(i, "type").map_or(false, |t| t.trim().eq_ignore_ascii_case("image/svg+xml"))
+            || href.split(['?', '#']).next().unwrap_or("").to_ascii_lowercase().ends_with(".svg");
+        if svg {
+            continue;
+        }
+        let size_rank = match dom.attr(i, "sizes").and_then(declared_size) {
+            Some(s) if s >= FAVICON_SIZE => s - FAVICON_SIZE,
+            Some(s) => 0x2000 - s,
+            None => 0x1000,
+        };
+        let url = crate::js::resolve_url(page_url, href);
+        if !found.iter().any(|(_, _, u)| *u == url) {
+            found.push((if icon { 0 } else { 1 }, size_rank, url));
+        }
+    }
+    found.sort_by_key(|(kind, size, _)| (*kind, *size));
+    found.into_iter().map(|(_, _, url)| url).collect()
+}
+
+/// Largest square size in a `sizes` attribute (`"16x16 32x32"`); `None`
+/// for `"any"` or nothing parseable.
+fn declared_size(sizes: &str) -> Option<u32> {
+    sizes
+        .split_ascii_whitespace()
+        .filter_map(|s| {
+            let (w, h) = s.split_once(|c| c == 'x' || c == 'X')?;
+            Some(w.parse::<u32>().ok()?.max(h.parse::<u32>().ok()?))
+        })
+        .max()
+}
+
+/// Scale `w`×`h` ARGB pixels to fit a `size` square, centred on a
+/// transparent background.  Downscaling averages each source box
+/// (premultiplied, so transparent pixels do not darken the edges).
+fn scale_to_fit(src: &[u32], w: u32, h: u32, size: u32) -> Vec<u32> {
+    let mut out = alloc::vec![0u32; (size * size) as usize];
+    let longest = w.max(h);
+    let dw = (w * size / longest).max(1);
+    let dh = (h * size / longest).max(1);
+    let ox = (size - dw) / 2;
+    let oy = (size - dh) / 2;
+    for dy in 0..dh {
+        let sy0 = dy * h / dh;
+        let sy1 = ((dy + 1) * h / dh).max(sy0 + 1);
+        for dx in 0..dw {
+            let sx0 = dx * w / dw;
+            let sx1 = ((dx + 1) * w / dw).max(sx0 + 1);
+            let (mut a, mut r, mut g, mut b, mut n) = (0u32, 0u32, 0u32, 0u32, 0u32);
+            for sy in sy0..sy1 {
+                for sx in sx0..sx1 {
+                    let p = src[(sy * w + sx) as usize];
+                    let pa = p >> 24;
+                    a += pa;
+                    r += ((p >> 16) & 0xFF) * pa / 255;
+                    g += ((p >> 8) & 0xFF) * pa / 255;
+                    b += (p & 0xFF) * pa / 255;
+                    n += 1;
+                }
+            }
+            let pixel = if a == 0 {
+                0
+            } else {
+                let un = |c: u32| (c as u64 * 255 / a as u64).min(255) as u32;
+                ((a / n) << 24) | (un(r) << 16) | (un(g) << 8) | un(b)
+            };
+            out[((oy + dy) * size + ox + dx) as usize] = pixel;
+        }
+    }
+    out
+}
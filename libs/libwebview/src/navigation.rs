@@ -0,0 +1,275 @@
+//! Session history and navigation state.
+//!
+//! libwebview does no networking itself, so navigation is a handshake with
+//! the host:
+//!
+//! 1. `WebView::load_url` / `go_back` / `go_forward` / `reload` start a
+//!    *pending* navigation and fire `NAV_STARTED`.  The host fetches the
+//!    URL from `pending()`.
+//! 2. `WebView::navigation_redirected` records each redirect hop.
+//! 3. `WebView::commit_navigation` renders the response, updates the
+//!    history, restores the scroll position for back/forward/reload and
+//!    fires `NAV_COMMITTED`.
+//! 4. `WebView::finish_navigation` (after sub-resources) fires
+//!    `NAV_FINISHED`; `WebView::fail_navigation` fires `NAV_FAILED`.
+//!
+//! Callbacks use the usual libanyui signature: `cb(nav_id, event, userdata)`.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Navigation event: a new navigation was started.
+pub const NAV_STARTED: u32 = 1;
+/// Navigation event: the response was rendered and the history updated.
+pub const NAV_COMMITTED: u32 = 2;
+/// Navigation event: the page and its sub-resources finished loading.
+pub const NAV_FINISHED: u32 = 3;
+/// Navigation event: the navigation failed or was abandoned.
+pub const NAV_FAILED: u32 = 4;
+
+/// Maximum number of session history entries kept per WebView.
+const MAX_HISTORY: usize = 100;
+
+/// How a navigation was initiated.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NavKind {
+    /// A new URL (link click, address bar, form submit, script).
+    Load,
+    /// Reload of the current entry.
+    Reload,
+    /// Traversal to an existing history entry.
+    BackForward,
+}
+
+/// One entry of the session history.
+#[derive(Clone)]
+pub struct HistoryEntry {
+    /// Final URL of the page (after redirects).
+    pub url: String,
+    /// Page `<title>` at commit time.
+    pub title: String,
+    /// Scroll position when the page was left (restored on back/forward).
+    pub scroll_y: i32,
+}
+
+/// A navigation waiting for the host to deliver the document.
+#[derive(Clone)]
+pub struct PendingNavigation {
+    pub id: u32,
+    /// URL the host should fetch (the original request URL).
+    pub url: String,
+    /// Referring page URL, if any (sent as the `Referer` header).
+    pub referrer: Option<String>,
+    pub kind: NavKind,
+    /// `true` if cached data must not be used (hard reload).
+    pub bypass_cache: bool,
+    /// Redirect hops recorded so far, in order.
+    pub redirects: Vec<String>,
+    /// History index to commit into for `BackForward` / `Reload`.
+    target_index: Option<usize>,
+}
+
+impl PendingNavigation {
+    /// The URL the navigation currently resolves to (last redirect or the original URL).
+    pub fn final_url(&self) -> &str {
+        self.redirects.last().map(|s| s.as_str()).unwrap_or(&self.url)
+    }
+}
+
+/// Back/forward stacks plus the in-flight navigation.
+pub struct Navigation {
+    entries: Vec<HistoryEntry>,
+    /// Index of the current entry in `entries` (None before the first commit).
+    current: Option<usize>,
+    pending: Option<PendingNavigation>,
+    /// Redirect chain of the last committed navigation.
+    last_redirects: Vec<String>,
+    next_id: u32,
+}
+
+impl Navigation {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            current: None,
+            pending: None,
+            last_redirects: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    /// The in-flight navigation, if any.
+    pub fn pending(&self) -> Option<&PendingNavigation> {
+        self.pending.as_ref()
+    }
+
+    /// All session history entries (oldest first).
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Index of the current entry in `entries()`.
+    pub fn current_index(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// The current (committed) history entry.
+    pub fn current(&self) -> Option<&HistoryEntry> {
+        self.current.and_then(|i| self.entries.get(i))
+    }
+
+    /// URL of the current (committed) page.
+    pub fn current_url(&self) -> Option<&str> {
+        self.current().map(|e| e.url.as_str())
+    }
+
+    /// Redirect hops of the last committed navigation.
+    pub fn last_redirects(&self) -> &[String] {
+        &self.last_redirects
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        matches!(self.current, Some(i) if i > 0)
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        matches!(self.current, Some(i) if i + 1 < self.entries.len())
+    }
+
+    // ── Starting navigations ─────────────────────────────────────────
+
+    /// Start loading a new URL.  Replaces any pending navigation.
+    pub(crate) fn start_load(&mut self, url: &str, referrer: Option<&str>) -> u32 {
+        self.start(String::from(url), referrer.map(String::from), NavKind::Load, false, None)
+    }
+
+    /// Start a traversal `delta` entries away from the current one.
+    pub(crate) fn start_traverse(&mut self, delta: isize) -> Option<u32> {
+        let cur = self.current? as isize;
+        let target = cur + delta;
+        if target < 0 || target as usize >= self.entries.len() {
+            return None;
+        }
+        let target = target as usize;
+        let url = self.entries[target].url.clone();
+        Some(self.start(url, None, NavKind::BackForward, false, Some(target)))
+    }
+
+    /// Start reloading the current entry.
+    pub(crate) fn start_reload(&mut self, bypass_cache: bool) -> Option<u32> {
+        let cur = self.current?;
+        let url = self.entries[cur].url.clone();
+        Some(self.start(url, None, NavKind::Reload, bypass_cache, Some(cur)))
+    }
+
+    fn start(
+        &mut self,
+        url: String,
+        referrer: Option<String>,
+        kind: NavKind,
+        bypass_cache: bool,
+        target_index: Option<usize>,
+    ) -> u32 {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        self.pending = Some(PendingNavigation {
+            id,
+            url,
+            referrer,
+            kind,
+            bypass_cache,
+            redirects: Vec::new(),
+            target_index,
+        });
+        id
+    }
+
+    /// Record a redirect hop of the pending navigation.
+    pub(crate) fn redirect(&mut self, url: &str) {
+        if let Some(ref mut p) = self.pending {
+            p.redirects.push(String::from(url));
+        }
+    }
+
+    /// Abandon the pending navigation.  Returns its ID.
+    pub(crate) fn fail(&mut self) -> Option<u32> {
+        self.pending.take().map(|p| p.id)
+    }
+
+    // ── Committing ───────────────────────────────────────────────────
+
+    /// Save the scroll position of the page being left.
+    pub(crate) fn save_scroll(&mut self, scroll_y: i32) {
+        if let Some(i) = self.current {
+            self.entries[i].scroll_y = scroll_y;
+        }
+    }
+
+    /// Commit the pending navigation (or an untracked load of `url` when none
+    /// is pending) into the history.
+    ///
+    /// Returns `(nav_id, scroll_y_to_restore)`.
+    pub(crate) fn commit(&mut self, url: &str) -> (u32, Option<i32>) {
+        if self.pending.is_none() {
+            self.start_load(url, None);
+        }
+        let pending = match self.pending.take() {
+            Some(p) => p,
+            None => return (0, None),
+        };
+        self.last_redirects = pending.redirects.clone();
+
+        let entry = HistoryEntry { url: String::from(url), title: String::new(), scroll_y: 0 };
+        let restore = match (pending.kind, pending.target_index) {
+            (NavKind::BackForward, Some(t)) | (NavKind::Reload, Some(t)) if t < self.entries.len() => {
+                let scroll = self.entries[t].scroll_y;
+                self.entries[t].url = entry.url;
+                self.current = Some(t);
+                Some(scroll)
+            }
+            _ => {
+                // Reloading into the same URL replaces the entry; a new URL
+                // drops the forward stack.
+                let same = self.current().map(|e| e.url == url).unwrap_or(false);
+                if same {
+                    let i = self.current.unwrap_or(0);
+                    self.entries[i] = entry;
+                } else {
+                    let keep = self.current.map(|i| i + 1).unwrap_or(0);
+                    self.entries.truncate(keep);
+                    self.entries.push(entry);
+                    if self.entries.len() > MAX_HISTORY {
+                        self.entries.remove(0);
+                    }
+                    self.current = Some(self.entries.len() - 1);
+                }
+                None
+            }
+        };
+        (pending.id, restore)
+    }
+
+    /// Set the title of the current entry.
+    pub(crate) fn set_title(&mut self, title: &str) {
+        if let Some(i) = self.current {
+            self.entries[i].title = String::from(title);
+        }
+    }
+
+    /// Replace the history with restored entries (see [`crate::session`]).
+    pub(crate) fn restore(&mut self, mut entries: Vec<HistoryEntry>, current: Option<usize>) {
+        entries.truncate(MAX_HISTORY);
+        self.current = current.filter(|&i| i < entries.len()).or(entries.len().checked_sub(1));
+        self.entries = entries;
+        self.pending = None;
+        self.last_redirects.clear();
+    }
+
+    /// Forget all history (keeps the ID counter).
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.current = None;
+        self.pending = None;
+        self.last_redirects.clear();
+    }
+}
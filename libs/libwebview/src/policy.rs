@@ -0,0 +1,215 @@
+//! Content policy — what a page may run and load.
+//!
+//! Embedders that render untrusted HTML (e.g. a mail client showing HTML
+//! email) install a `ContentPolicy` with `WebView::set_policy()`.  It is
+//! enforced on the DOM as soon as it is parsed, before the host looks for
+//! sub-resources:
+//!
+//! - Blocked URLs are removed from `src` / `href` / `poster` attributes, so
+//!   a host that scans the DOM for images and stylesheets never fetches them.
+//! - With forms disabled, `<form>` becomes a plain container (content kept,
+//!   `action` dropped) and form controls are detached from the tree.
+//! - With scripts disabled, `<script>` tags are not executed.
+//!
+//! Each blocked item is recorded as a `BlockedItem` and reported through the
+//! blocked-content callback (`cb(kind, count, userdata)` with `kind` one of
+//! the `BLOCKED_*` constants and `count` the number of items of that kind
+//! blocked on the current document), e.g. for a "remote content blocked"
+//! badge.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::dom::{Dom, NodeId, NodeType, Tag};
+
+/// Blocked item: a `<script>` that was not executed.
+pub const BLOCKED_SCRIPT: u32 = 1;
+/// Blocked item: a remote image, stylesheet or media source.
+pub const BLOCKED_REMOTE: u32 = 2;
+/// Blocked item: a link or resource URL whose scheme is not allowed.
+pub const BLOCKED_SCHEME: u32 = 3;
+/// Blocked item: a form or form control.
+pub const BLOCKED_FORM: u32 = 4;
+
+/// URL-bearing attributes checked by the policy, per tag.
+const URL_ATTRS: &[(Tag, &str)] = &[
+    (Tag::A, "href"),
+    (Tag::Area, "href"),
+    (Tag::Link, "href"),
+    (Tag::Img, "src"),
+    (Tag::Video, "src"),
+    (Tag::Video, "poster"),
+    (Tag::Audio, "src"),
+    (Tag::Source, "src"),
+    (Tag::Track, "src"),
+    (Tag::Iframe, "src"),
+    (Tag::Embed, "src"),
+    (Tag::Object, "data"),
+    (Tag::Script, "src"),
+];
+
+/// What the page may run and load.  The default allows everything.
+#[derive(Clone)]
+pub struct ContentPolicy {
+    /// Execute `<script>` tags (and with them event listeners and timers).
+    pub scripts: bool,
+    /// Load images, stylesheets and media over the network.  `data:`,
+    /// `cid:`, `file:` and `about:` URLs are local and always allowed.
+    pub remote_loads: bool,
+    /// Keep forms interactive.
+    pub forms: bool,
+    /// URL schemes allowed in links and resource URLs (lowercase, without
+    /// the colon).  Empty = any scheme.  Relative URLs are always allowed.
+    pub schemes: Vec<String>,
+}
+
+impl Default for ContentPolicy {
+    fn default() -> Self {
+        ContentPolicy::permissive()
+    }
+}
+
+impl ContentPolicy {
+    /// Allow everything (the browser default).
+    pub fn permissive() -> Self {
+        ContentPolicy {
+            scripts: true,
+            remote_loads: true,
+            forms: true,
+            schemes: Vec::new(),
+        }
+    }
+
+    /// Policy for untrusted documents such as HTML email: no scripts, no
+    /// remote loads, no forms; links limited to web and mail URLs.
+    pub fn restricted() -> Self {
+        ContentPolicy {
+            scripts: false,
+            remote_loads: false,
+            forms: false,
+            schemes: ["http", "https", "mailto", "cid", "data"]
+                .iter().map(|s| String::from(*s)).collect(),
+        }
+    }
+
+    /// Whether `url`'s scheme is on the allowlist.
+    pub fn allows_scheme(&self, url: &str) -> bool {
+        if self.schemes.is_empty() {
+            return true;
+        }
+        match url_scheme(url) {
+            Some(scheme) => self.schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme)),
+            None => true,
+        }
+    }
+
+    /// Whether the resource `url`, referenced from a page at `base`, may be
+    /// loaded.
+    pub fn allows_load(&self, url: &str, base: &str) -> bool {
+        self.allows_scheme(url) && (self.remote_loads || !is_remote(url, base))
+    }
+}
+
+/// A link, resource, script or form the policy blocked.
+#[derive(Clone)]
+pub struct BlockedItem {
+    /// One of the `BLOCKED_*` constants.
+    pub kind: u32,
+    /// DOM node of the blocked element.
+    pub node_id: NodeId,
+    /// The blocked URL (empty for inline scripts and forms).
+    pub url: String,
+}
+
+/// Scheme of an absolute URL (`"https"` for `https://…`), `None` for
+/// relative URLs.
+fn url_scheme(url: &str) -> Option<&str> {
+    let url = url.trim_start();
+    let end = url.find(':')?;
+    let scheme = &url[..end];
+    let mut chars = scheme.chars();
+    let valid = chars.next().map_or(false, |c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.');
+    if valid { Some(scheme) } else { None }
+}
+
+/// Whether `url` is fetched over the network.  Relative URLs inherit the
+/// page's location.
+fn is_remote(url: &str, base: &str) -> bool {
+    let url = url.trim_start();
+    if url.starts_with("//") {
+        return true;
+    }
+    match url_scheme(url) {
+        Some(scheme) => ["http", "https", "ftp", "ws", "wss"]
+            .iter().any(|s| s.eq_ignore_ascii_case(scheme)),
+        None => !base.is_empty() && is_remote(base, ""),
+    }
+}
+
+/// Apply `policy` to `dom`: strip blocked URLs and defuse forms, appending
+/// what was blocked to `blocked`.  Idempotent — items blocked by an earlier
+/// pass are not reported again — so it can run after every parse step and
+/// script mutation.
+pub(crate) fn enforce(dom: &mut Dom, policy: &ContentPolicy, base: &str, blocked: &mut Vec<BlockedItem>) {
+    for id in 0..dom.nodes.len() {
+        let tag = match dom.tag(id) {
+            Some(t) => t,
+            None => continue,
+        };
+
+        for &(t, attr) in URL_ATTRS {
+            if t != tag {
+                continue;
+            }
+            let url = match dom.attr(id, attr) {
+                Some(u) if !u.is_empty() => String::from(u),
+                _ => continue,
+            };
+            // Links are navigated, not loaded: only the scheme matters.
+            let is_link = matches!(tag, Tag::A | Tag::Area);
+            let kind = if !policy.allows_scheme(&url) {
+                BLOCKED_SCHEME
+            } else if !is_link && tag != Tag::Script && !policy.remote_loads && is_remote(&url, base) {
+                BLOCKED_REMOTE
+            } else {
+                continue;
+            };
+            dom.remove_attr(id, attr);
+            blocked.push(BlockedItem { kind, node_id: id, url });
+        }
+
+        if policy.forms {
+            continue;
+        }
+        match tag {
+            Tag::Form => {
+                if let NodeType::Element { tag, attrs } = &mut dom.nodes[id].node_type {
+                    *tag = Tag::Div;
+                    attrs.retain(|a| a.name != "action" && a.name != "method");
+                }
+                blocked.push(BlockedItem { kind: BLOCKED_FORM, node_id: id, url: String::new() });
+            }
+            Tag::Input | Tag::Button | Tag::Select | Tag::Textarea => {
+                if let Some(parent) = dom.nodes[id].parent {
+                    dom.remove_child(parent, id);
+                    blocked.push(BlockedItem { kind: BLOCKED_FORM, node_id: id, url: String::new() });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The `<script>` elements of `dom`, as items blocked by a policy that
+/// disables scripts.
+pub(crate) fn blocked_scripts(dom: &Dom) -> Vec<BlockedItem> {
+    (0..dom.nodes.len())
+        .filter(|&id| dom.tag(id) == Some(Tag::Script))
+        .map(|id| BlockedItem {
+            kind: BLOCKED_SCRIPT,
+            node_id: id,
+            url: dom.attr(id, "src").map(String::from).unwrap_or_default(),
+        })
+        .collect()
+}
@@ -12,8 +12,12 @@ use alloc::vec::Vec;
 
 use libanyui_client::{self as ui, Widget};
 
-use crate::layout::{LayoutBox, FormFieldKind};
+use crate::dom::NodeId;
+use crate::fonts::TextFont;
+use crate::layout::{LayoutBox, FormFieldKind, MediaBox};
+use crate::media::{self, MediaKind};
 use crate::style::TextDeco;
+use crate::transform::{transform_matrix, Affine};
 
 // ═══════════════════════════════════════════════════════════════════════════
 // Image cache
@@ -138,6 +142,25 @@ pub enum HitKind {
     Link(String),
     /// A form submit button with DOM node_id.
     Submit(usize),
+    /// The controls bar of an `<audio>` / `<video>` element with DOM node_id.
+    Media(usize),
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Highlights (text selection, find matches)
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// A translucent rectangle painted over the rasterized content.
+///
+/// Coordinates are in **absolute document space**.
+#[derive(Clone, Copy)]
+pub struct Highlight {
+    pub x: i32,
+    pub y: i32,
+    pub w: i32,
+    pub h: i32,
+    /// ARGB colour — alpha blends the highlight over the text beneath.
+    pub color: u32,
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -177,6 +200,10 @@ const MAX_TILE_CANVASES: usize = 30;
 /// Maximum number of tiles to rasterize per tick (avoids blocking the event loop).
 const MAX_TILES_PER_TICK: usize = 2;
 
+/// Maximum pixel count of one offscreen layer (opacity / transform).
+/// Taller layers are cut off at the bottom.
+const MAX_LAYER_PIXELS: i64 = 512 * 1024;
+
 /// A cached rasterized tile strip: doc_width × TILE_HEIGHT pixels.
 struct CachedTile {
     /// Tile row index (y_start = row * TILE_HEIGHT).
@@ -229,6 +256,11 @@ impl TileCache {
         self.tiles.push(CachedTile { row, pixels, generation: gen });
     }
 
+    /// Drop the cached pixels of a single tile row.
+    fn invalidate(&mut self, row: u32) {
+        self.tiles.retain(|t| t.row != row);
+    }
+
     /// Invalidate all cached tiles (called on relayout, resize, navigation).
     fn invalidate_all(&mut self) {
         self.tiles.clear();
@@ -278,8 +310,18 @@ pub(crate) struct Renderer {
     /// Link callback (set on each tile canvas for click handling).
     link_cb: Option<ui::Callback>,
     link_cb_ud: u64,
+    /// Mouse callback for text selection (down/move/up/double-click on tiles).
+    select_cb: Option<ui::Callback>,
+    select_cb_ud: u64,
+    /// Change callback registered on checkboxes, radios, selects and textareas.
+    change_cb: Option<ui::Callback>,
+    change_cb_ud: u64,
+    /// Selection and find-match highlights — absolute document coordinates.
+    highlights: Vec<Highlight>,
     /// Last scroll Y that triggered tile management.
     last_scroll_y: i32,
+    /// User-agent colours of the page's color scheme.
+    palette: crate::scheme::Palette,
 }
 
 impl Renderer {
@@ -294,10 +336,36 @@ impl Renderer {
             link_map: Vec::new(),
             link_cb: None,
             link_cb_ud: 0,
+            select_cb: None,
+            select_cb_ud: 0,
+            change_cb: None,
+            change_cb_ud: 0,
+            highlights: Vec::new(),
             last_scroll_y: 0,
+            palette: crate::scheme::LIGHT_PALETTE,
         }
     }
 
+    /// Set the user-agent colours for the next render (canvas and form
+    /// controls the page does not style).
+    pub fn set_palette(&mut self, palette: crate::scheme::Palette) {
+        self.palette = palette;
+    }
+
+    /// Set the mouse callback registered on every tile canvas for text selection.
+    /// Only affects tile canvases created after this call.
+    pub fn set_select_callback(&mut self, cb: ui::Callback, userdata: u64) {
+        self.select_cb = Some(cb);
+        self.select_cb_ud = userdata;
+    }
+
+    /// Set the change callback registered on checkboxes, radio buttons,
+    /// selects and textareas.  Only affects controls created after this call.
+    pub fn set_change_callback(&mut self, cb: ui::Callback, userdata: u64) {
+        self.change_cb = Some(cb);
+        self.change_cb_ud = userdata;
+    }
+
     /// Check if a control ID belongs to any tile canvas, and if so return
     /// the mouse position translated to absolute document coordinates.
     pub fn tile_hit_coords(&self, ctrl_id: u32) -> Option<(i32, i32)> {
@@ -322,6 +390,7 @@ impl Renderer {
     pub fn clear(&mut self) {
         self.hit_regions.clear();
         self.link_map.clear();
+        self.highlights.clear();
         self.tile_cache.invalidate_all();
         // Destroy all tile canvases (content is stale after relayout).
         for tc in self.tile_canvases.drain(..) {
@@ -348,6 +417,7 @@ impl Renderer {
         self.doc_h = 0;
         self.hit_regions.clear();
         self.link_map.clear();
+        self.highlights.clear();
         self.tile_cache.invalidate_all();
         self.link_cb = None;
         self.link_cb_ud = 0;
@@ -382,6 +452,22 @@ impl Renderer {
         None
     }
 
+    /// Hit-test at absolute document coordinates for a media controls bar.
+    /// Returns the element's DOM node_id, the X offset of the point within
+    /// the bar, and the bar width.
+    pub fn hit_test_media_at(&self, x: i32, doc_y: i32) -> Option<(usize, i32, i32)> {
+        for region in &self.hit_regions {
+            if x >= region.x && x < region.x + region.w
+                && doc_y >= region.y && doc_y < region.y + region.h
+            {
+                if let HitKind::Media(node_id) = region.kind {
+                    return Some((node_id, x - region.x, region.w));
+                }
+            }
+        }
+        None
+    }
+
     // ─────────────────────────────────────────────────────────────────────
     // Full render (relayout path)
     // ─────────────────────────────────────────────────────────────────────
@@ -410,7 +496,7 @@ impl Renderer {
             doc_w, doc_h, viewport_h, scroll_y);
 
         let w = doc_w.max(1);
-        let clear_color = if bg_color != 0 { bg_color } else { 0xFFFFFFFF };
+        let clear_color = if bg_color != 0 { bg_color } else { self.palette.canvas };
 
         self.doc_w = w;
         self.doc_h = doc_h;
@@ -436,25 +522,68 @@ impl Renderer {
 
         // 4. Rasterize visible tile rows, cache them, and create canvases.
         for row in first_row..=last_row {
-            let tile_buf = rasterize_tile(root, images, w, row, doc_h, clear_color);
+            let tile_buf = rasterize_tile(root, images, w, row, doc_h, clear_color, &self.highlights);
             self.tile_cache.insert(row, tile_buf);
             self.create_tile_canvas(row, w, doc_h, parent);
         }
 
         // 5. GC unseen form controls.
-        self.form_controls.retain(|fc| {
-            if !fc.seen && fc.control_id != 0 {
-                ui::Control::from_id(fc.control_id).remove();
-                false
-            } else {
-                fc.seen || fc.control_id == 0
-            }
-        });
+        self.gc_form_controls();
 
         crate::debug_surf!("[render] full render done: {} tile canvases, {} hit_regions, {} form_controls",
             self.tile_canvases.len(), self.hit_regions.len(), self.form_controls.len());
     }
 
+    // ─────────────────────────────────────────────────────────────────────
+    // Damage render (incremental relayout path)
+    // ─────────────────────────────────────────────────────────────────────
+
+    /// Re-render after `layout::relayout_nodes()` rebuilt parts of the
+    /// layout tree in place, keeping the document size.
+    ///
+    /// Form controls and hit regions are refreshed from the full tree, but
+    /// only the tile rows overlapping `damage` (document Y ranges) or the
+    /// dropped highlights are repainted; every other tile keeps its cached
+    /// pixels and its canvas.
+    pub fn update(
+        &mut self,
+        root: &LayoutBox,
+        parent: &ui::View,
+        images: &ImageCache,
+        damage: &[(i32, i32)],
+        bg_color: u32,
+        submit_cb: Option<ui::Callback>,
+        submit_cb_ud: u64,
+    ) {
+        self.hit_regions.clear();
+        self.link_map.clear();
+        for fc in &mut self.form_controls {
+            fc.seen = false;
+        }
+        self.walk_controls(root, 0, 0, parent, submit_cb, submit_cb_ud);
+        self.gc_form_controls();
+
+        let doc_h = self.doc_h as i32;
+        let mut rows: Vec<u32> = Vec::new();
+        let highlighted = self.highlights.iter().map(|h| (h.y, h.y + h.h));
+        for (y0, y1) in damage.iter().copied().chain(highlighted) {
+            let y1 = y1.min(doc_h);
+            if y1 <= 0 || y0 >= y1 { continue; }
+            let first = y0.max(0) as u32 / TILE_HEIGHT;
+            let last = (y1 - 1) as u32 / TILE_HEIGHT;
+            for row in first..=last {
+                if !rows.contains(&row) {
+                    rows.push(row);
+                }
+            }
+        }
+        self.highlights.clear();
+        self.repaint_rows(root, images, &rows, bg_color);
+
+        crate::debug_surf!("[render] damage render: {} rows, {} hit_regions, {} form_controls",
+            rows.len(), self.hit_regions.len(), self.form_controls.len());
+    }
+
     // ─────────────────────────────────────────────────────────────────────
     // Scroll render (fast path — compositor-driven)
     // ─────────────────────────────────────────────────────────────────────
@@ -483,7 +612,7 @@ impl Renderer {
         _link_cb_ud: u64,
     ) -> bool {
         let w = doc_w.max(1);
-        let clear_color = if bg_color != 0 { bg_color } else { 0xFFFFFFFF };
+        let clear_color = if bg_color != 0 { bg_color } else { self.palette.canvas };
 
         self.doc_w = w;
         self.doc_h = doc_h;
@@ -514,7 +643,7 @@ impl Renderer {
                     pending = true;
                     continue;
                 }
-                let tile_buf = rasterize_tile(root, images, w, row, doc_h, clear_color);
+                let tile_buf = rasterize_tile(root, images, w, row, doc_h, clear_color, &self.highlights);
                 self.tile_cache.insert(row, tile_buf);
                 rasterized += 1;
             }
@@ -555,10 +684,86 @@ impl Renderer {
         pending
     }
 
+    // ─────────────────────────────────────────────────────────────────────
+    // Highlights
+    // ─────────────────────────────────────────────────────────────────────
+
+    /// Replace the highlight set and repaint the tile rows it touches.
+    ///
+    /// Only rows touched by the old or new highlights are affected: their
+    /// cached pixels are dropped, and rows with a live canvas are
+    /// re-rasterized immediately.
+    pub fn set_highlights(
+        &mut self,
+        root: &LayoutBox,
+        images: &ImageCache,
+        highlights: Vec<Highlight>,
+        bg_color: u32,
+    ) {
+        let mut rows: Vec<u32> = Vec::new();
+        for h in self.highlights.iter().chain(highlights.iter()) {
+            if h.h <= 0 { continue; }
+            let first = h.y.max(0) as u32 / TILE_HEIGHT;
+            let last = (h.y + h.h - 1).max(0) as u32 / TILE_HEIGHT;
+            for row in first..=last {
+                if !rows.contains(&row) {
+                    rows.push(row);
+                }
+            }
+        }
+        self.highlights = highlights;
+        self.repaint_rows(root, images, &rows, bg_color);
+    }
+
+    /// Repaint the tile rows overlapping document rows `[y0, y1)` after a
+    /// paint-only change of the layout tree (e.g. an animated opacity or
+    /// transform).
+    pub fn repaint_range(
+        &mut self,
+        root: &LayoutBox,
+        images: &ImageCache,
+        y0: i32,
+        y1: i32,
+        bg_color: u32,
+    ) {
+        let y1 = y1.min(self.doc_h as i32);
+        if y1 <= 0 || y0 >= y1 { return; }
+        let first = y0.max(0) as u32 / TILE_HEIGHT;
+        let last = (y1 - 1) as u32 / TILE_HEIGHT;
+        let rows: Vec<u32> = (first..=last).collect();
+        self.repaint_rows(root, images, &rows, bg_color);
+    }
+
+    /// Drop the cached pixels of `rows` and re-rasterize the ones that have
+    /// a live canvas.
+    fn repaint_rows(&mut self, root: &LayoutBox, images: &ImageCache, rows: &[u32], bg_color: u32) {
+        let clear_color = if bg_color != 0 { bg_color } else { self.palette.canvas };
+        for &row in rows {
+            self.tile_cache.invalidate(row);
+            if let Some(tc) = self.tile_canvases.iter().find(|tc| tc.row == row) {
+                let tile_buf = rasterize_tile(root, images, self.doc_w, row, self.doc_h, clear_color, &self.highlights);
+                tc.canvas.copy_pixels_from(&tile_buf);
+                self.tile_cache.insert(row, tile_buf);
+            }
+        }
+    }
+
     // ─────────────────────────────────────────────────────────────────────
     // Internal helpers
     // ─────────────────────────────────────────────────────────────────────
 
+    /// Remove the form controls `walk_controls()` did not visit.
+    fn gc_form_controls(&mut self) {
+        self.form_controls.retain(|fc| {
+            if !fc.seen && fc.control_id != 0 {
+                ui::Control::from_id(fc.control_id).remove();
+                false
+            } else {
+                fc.seen || fc.control_id == 0
+            }
+        });
+    }
+
     /// Create a Canvas control for a tile row from cached pixel data.
     fn create_tile_canvas(&mut self, row: u32, doc_w: u32, doc_h: u32, parent: &ui::View) {
         let pixels = match self.tile_cache.get(row) {
@@ -575,6 +780,12 @@ impl Renderer {
         if let Some(cb) = self.link_cb {
             c.on_click_raw(cb, self.link_cb_ud);
         }
+        if let Some(cb) = self.select_cb {
+            c.on_event_raw(ui::EVENT_MOUSE_DOWN, cb, self.select_cb_ud);
+            c.on_event_raw(ui::EVENT_MOUSE_MOVE, cb, self.select_cb_ud);
+            c.on_event_raw(ui::EVENT_MOUSE_UP, cb, self.select_cb_ud);
+            c.on_event_raw(ui::EVENT_DOUBLE_CLICK, cb, self.select_cb_ud);
+        }
         parent.add(&c);
         c.copy_pixels_from(pixels);
 
@@ -627,6 +838,18 @@ impl Renderer {
             self.emit_form_control(kind, bx, abs_x, abs_y, parent, submit_cb, submit_cb_ud);
         }
 
+        // Media controls bar.
+        if let (Some(m), Some(node_id)) = (bx.media.as_ref(), bx.node_id) {
+            if m.controls {
+                let (cx, cy, cw, ch) = content_rect(bx, abs_x, abs_y);
+                self.hit_regions.push(HitRegion {
+                    x: cx, y: cy + ch - media::CONTROLS_HEIGHT.min(ch),
+                    w: cw, h: media::CONTROLS_HEIGHT.min(ch),
+                    kind: HitKind::Media(node_id),
+                });
+            }
+        }
+
         // Recurse into children.
         for child in &bx.children {
             self.walk_controls(child, abs_x, abs_y, parent, submit_cb, submit_cb_ud);
@@ -654,8 +877,8 @@ impl Renderer {
                     let ctrl = ui::Control::from_id(fc.control_id);
                     ctrl.set_position(x, y);
                     ctrl.set_size(bx.width as u32, bx.height as u32);
-                    let bg = if bx.bg_color != 0 { bx.bg_color } else { 0xFFFFFFFF };
-                    let fg = if bx.color != 0 { bx.color } else { 0xFF000000 };
+                    let bg = if bx.bg_color != 0 { bx.bg_color } else { self.palette.control_bg };
+                    let fg = if bx.color != 0 { bx.color } else { self.palette.control_fg };
                     ctrl.set_color(bg);
                     ctrl.set_text_color(fg);
                     fc.seen = true;
@@ -666,8 +889,8 @@ impl Renderer {
                     }
                     tf.set_position(x, y);
                     tf.set_size(bx.width as u32, bx.height as u32);
-                    let bg = if bx.bg_color != 0 { bx.bg_color } else { 0xFFFFFFFF };
-                    let fg = if bx.color != 0 { bx.color } else { 0xFF000000 };
+                    let bg = if bx.bg_color != 0 { bx.bg_color } else { self.palette.control_bg };
+                    let fg = if bx.color != 0 { bx.color } else { self.palette.control_fg };
                     tf.set_color(bg);
                     tf.set_text_color(fg);
                     if let Some(ref ph) = bx.form_placeholder {
@@ -703,6 +926,10 @@ impl Renderer {
                     let cb = ui::Checkbox::new("");
                     cb.set_position(x, y);
                     cb.set_size(bx.width as u32, bx.height as u32);
+                    cb.set_state(bx.form_state);
+                    if let Some(f) = self.change_cb {
+                        cb.on_change_raw(f, self.change_cb_ud);
+                    }
                     parent.add(&cb);
                     let id = cb.id();
                     self.form_controls.push(FormControl {
@@ -719,9 +946,15 @@ impl Renderer {
                     ctrl.set_size(bx.width as u32, bx.height as u32);
                     fc.seen = true;
                 } else {
+                    // Not placed in a RadioGroup: exclusivity follows the
+                    // `name` attribute and is enforced by the WebView.
                     let rb = ui::RadioButton::new("");
                     rb.set_position(x, y);
                     rb.set_size(bx.width as u32, bx.height as u32);
+                    rb.set_state(bx.form_state);
+                    if let Some(f) = self.change_cb {
+                        rb.on_change_raw(f, self.change_cb_ud);
+                    }
                     parent.add(&rb);
                     let id = rb.id();
                     self.form_controls.push(FormControl {
@@ -732,17 +965,27 @@ impl Renderer {
             }
 
             FormFieldKind::Textarea => {
+                let bg = if bx.bg_color != 0 { bx.bg_color } else { self.palette.control_bg };
+                let fg = if bx.color != 0 { bx.color } else { self.palette.control_fg };
                 if let Some(fc) = self.form_controls.iter_mut().find(|fc| fc.node_id == node_id && fc.kind == kind) {
                     let ctrl = ui::Control::from_id(fc.control_id);
                     ctrl.set_position(x, y);
                     ctrl.set_size(bx.width as u32, bx.height as u32);
+                    ctrl.set_color(bg);
+                    ctrl.set_text_color(fg);
                     fc.seen = true;
                 } else {
-                    let ta = ui::TextArea::new();
+                    let ta = ui::TextEditor::new(bx.width as u32, bx.height as u32);
                     ta.set_position(x, y);
-                    ta.set_size(bx.width as u32, bx.height as u32);
-                    ta.set_color(0xFFFFFFFF);
-                    ta.set_text_color(0xFF000000);
+                    ta.set_line_height(18);
+                    ta.set_color(bg);
+                    ta.set_text_color(fg);
+                    if let Some(ref val) = bx.form_value {
+                        ta.set_text(val);
+                    }
+                    if let Some(f) = self.change_cb {
+                        ta.on_change_raw(f, self.change_cb_ud);
+                    }
                     parent.add(&ta);
                     let id = ta.id();
                     self.form_controls.push(FormControl {
@@ -752,6 +995,62 @@ impl Renderer {
                 }
             }
 
+            FormFieldKind::Select => {
+                if let Some(fc) = self.form_controls.iter_mut().find(|fc| fc.node_id == node_id && fc.kind == kind) {
+                    let ctrl = ui::Control::from_id(fc.control_id);
+                    ctrl.set_position(x, y);
+                    ctrl.set_size(bx.width as u32, bx.height as u32);
+                    fc.seen = true;
+                } else {
+                    // Labels are '|'-separated for the drop-down; strip any
+                    // separators inside option text.
+                    let mut items = String::new();
+                    for (i, label) in bx.form_options.iter().enumerate() {
+                        if i > 0 {
+                            items.push('|');
+                        }
+                        items.extend(label.chars().filter(|&c| c != '|'));
+                    }
+                    let dd = ui::DropDown::new(&items);
+                    dd.set_position(x, y);
+                    dd.set_size(bx.width as u32, bx.height as u32);
+                    dd.set_selected_index(bx.form_state);
+                    if let Some(f) = self.change_cb {
+                        dd.on_change_raw(f, self.change_cb_ud);
+                    }
+                    parent.add(&dd);
+                    let id = dd.id();
+                    self.form_controls.push(FormControl {
+                        control_id: id, node_id, kind,
+                        name: String::new(), seen: true,
+                    });
+                }
+            }
+
+            FormFieldKind::File => {
+                if let Some(fc) = self.form_controls.iter_mut().find(|fc| fc.node_id == node_id && fc.kind == kind) {
+                    let ctrl = ui::Control::from_id(fc.control_id);
+                    ctrl.set_position(x, y);
+                    ctrl.set_size(bx.width as u32, bx.height as u32);
+                    fc.seen = true;
+                } else {
+                    // The label shows the chosen file names once the WebView
+                    // has run the host's picker (see `handle_form_change`).
+                    let btn = ui::Button::new(bx.text.as_deref().unwrap_or("Choose File…"));
+                    btn.set_position(x, y);
+                    btn.set_size(bx.width as u32, bx.height as u32);
+                    if let Some(f) = self.change_cb {
+                        btn.on_click_raw(f, self.change_cb_ud);
+                    }
+                    parent.add(&btn);
+                    let id = btn.id();
+                    self.form_controls.push(FormControl {
+                        control_id: id, node_id, kind,
+                        name: String::new(), seen: true,
+                    });
+                }
+            }
+
             FormFieldKind::Hidden => {
                 if !self.form_controls.iter().any(|fc| fc.node_id == node_id && fc.kind == kind) {
                     self.form_controls.push(FormControl {
@@ -775,7 +1074,8 @@ impl Renderer {
 /// Rasterize a single tile row (pixel-only, no form controls or hit regions).
 ///
 /// Allocates a `doc_w × TILE_HEIGHT` buffer, walks the layout tree with
-/// culling to the tile's Y range, and returns the pixel buffer for caching.
+/// culling to the tile's Y range, blends any highlights on top, and returns
+/// the pixel buffer for caching.
 fn rasterize_tile(
     root: &LayoutBox,
     images: &ImageCache,
@@ -783,6 +1083,7 @@ fn rasterize_tile(
     row: u32,
     doc_h: u32,
     clear_color: u32,
+    highlights: &[Highlight],
 ) -> Vec<u32> {
     let tile_y_start = (row * TILE_HEIGHT) as i32;
     let tile_y_end = (tile_y_start + TILE_HEIGHT as i32).min(doc_h as i32);
@@ -797,6 +1098,33 @@ fn rasterize_tile(
         images, 0, 0, tile_y_start, tile_y_start + tile_h as i32,
     );
 
+    for h in highlights {
+        if h.y + h.h > tile_y_start && h.y < tile_y_start + tile_h as i32 {
+            fill_rect_buf(buf.as_mut_ptr(), doc_w, TILE_HEIGHT, h.x, h.y - tile_y_start, h.w, h.h, h.color);
+        }
+    }
+
+    buf
+}
+
+/// Rasterize the document rows `y_start .. y_start + h` into a fresh
+/// `doc_w × h` buffer (pixel-only, like `rasterize_tile()`).  Used by the
+/// print / export path, which slices pages of arbitrary height.
+pub(crate) fn rasterize_region(
+    root: &LayoutBox,
+    images: &ImageCache,
+    doc_w: u32,
+    y_start: i32,
+    h: u32,
+    clear_color: u32,
+) -> Vec<u32> {
+    let h = h.max(1);
+    let mut buf = Vec::with_capacity((doc_w as usize) * (h as usize));
+    buf.resize((doc_w as usize) * (h as usize), clear_color);
+    walk_pixels(
+        root, buf.as_mut_ptr(), doc_w, h,
+        images, 0, 0, y_start, y_start + h as i32,
+    );
     buf
 }
 
@@ -804,6 +1132,7 @@ fn rasterize_tile(
 /// submit button appearances into the tile buffer.
 ///
 /// Skips form controls and hit regions (handled by `walk_controls()`).
+/// Boxes with `opacity < 1` or a `transform` are painted as layers.
 fn walk_pixels(
     bx: &LayoutBox,
     buf: *mut u32,
@@ -818,7 +1147,26 @@ fn walk_pixels(
     if bx.visibility_hidden {
         return;
     }
+    if bx.opacity < 255 || !bx.transform.is_empty() {
+        paint_layer(bx, buf, stride, buf_h, images, offset_x, offset_y, tile_y_start, tile_y_end);
+    } else {
+        paint_box(bx, buf, stride, buf_h, images, offset_x, offset_y, tile_y_start, tile_y_end);
+    }
+}
 
+/// Paint one box and (through `walk_pixels()`) its subtree directly into
+/// the buffer.
+fn paint_box(
+    bx: &LayoutBox,
+    buf: *mut u32,
+    stride: u32,
+    buf_h: u32,
+    images: &ImageCache,
+    offset_x: i32,
+    offset_y: i32,
+    tile_y_start: i32,
+    tile_y_end: i32,
+) {
     let abs_x = if bx.is_fixed { bx.x } else { offset_x + bx.x };
     let abs_y = if bx.is_fixed { bx.y } else { offset_y + bx.y };
 
@@ -867,24 +1215,16 @@ fn walk_pixels(
         // Text fragment.
         if let Some(ref text) = bx.text {
             if !text.is_empty() && bx.form_field.is_none() {
-                let font_id = if bx.bold && bx.italic {
-                    1u32
-                } else if bx.bold {
-                    1u32
-                } else if bx.italic {
-                    3u32
-                } else {
-                    0u32
-                };
+                let font = bx.font.unwrap_or(TextFont::system(bx.bold, bx.italic));
                 let font_size = bx.font_size.max(1) as u16;
                 let color = if bx.color != 0 { bx.color } else { 0xFF000000 };
 
-                libfont_client::draw_string_buf(
-                    buf, stride, buf_h,
-                    abs_x, draw_y,
-                    color, font_id, font_size,
-                    text,
-                );
+                if font.synth_italic {
+                    draw_slanted_text(buf, stride, buf_h, abs_x, draw_y, bx.width, bx.height,
+                        color, font, font_size, text);
+                } else {
+                    draw_text(buf, stride, buf_h, abs_x, draw_y, color, font, font_size, text);
+                }
 
                 // Underline for links or text-decoration.
                 if bx.text_decoration == TextDeco::Underline || bx.link_url.is_some() {
@@ -915,6 +1255,12 @@ fn walk_pixels(
             }
         }
 
+        // Audio / video (controls hit region is in walk_controls).
+        if let Some(ref m) = bx.media {
+            let (cx, cy, cw, ch) = content_rect(bx, abs_x, draw_y);
+            draw_media_pixels(buf, stride, buf_h, images, cx, cy, cw, ch, bx, m);
+        }
+
         // Submit/button pixel drawing (hit region is in walk_controls).
         if let Some(kind) = bx.form_field {
             if matches!(kind, FormFieldKind::Submit | FormFieldKind::ButtonEl) {
@@ -930,6 +1276,54 @@ fn walk_pixels(
     }
 }
 
+/// Draw a text run; synthetic bold draws it a second time one pixel right.
+fn draw_text(
+    buf: *mut u32, stride: u32, buf_h: u32,
+    x: i32, y: i32, color: u32, font: TextFont, size: u16, text: &str,
+) {
+    libfont_client::draw_string_buf(buf, stride, buf_h, x, y, color, font.font_id as u32, size, text);
+    if font.synth_bold {
+        libfont_client::draw_string_buf(buf, stride, buf_h, x + 1, y, color, font.font_id as u32, size, text);
+    }
+}
+
+/// Draw a text run slanted to the right (synthetic italic, ~14°).
+///
+/// The pixels under the run are copied into a scratch buffer with every row
+/// shifted left by its slant offset, the text is drawn there upright, and
+/// the rows are copied back shifted right again: the glyphs come out
+/// sheared while the background round-trips unchanged.
+fn draw_slanted_text(
+    buf: *mut u32, stride: u32, buf_h: u32,
+    x: i32, y: i32, w: i32, h: i32,
+    color: u32, font: TextFont, size: u16, text: &str,
+) {
+    if w <= 0 || h <= 0 || buf.is_null() { return; }
+    let shift = |row: i32| (h - 1 - row) / 4;
+    let tw = w + 2;
+    let mut tmp = alloc::vec![0u32; (tw * h) as usize];
+    let (s, bh) = (stride as i32, buf_h as i32);
+    let copy = |to_tmp: bool, tmp: &mut [u32]| {
+        for row in 0..h {
+            let by = y + row;
+            if by < 0 || by >= bh { continue; }
+            let off = shift(row);
+            for col in 0..tw {
+                let bx = x + col + off;
+                if bx < 0 || bx >= s { continue; }
+                let t = (row * tw + col) as usize;
+                unsafe {
+                    let p = buf.add(by as usize * stride as usize + bx as usize);
+                    if to_tmp { tmp[t] = *p; } else { *p = tmp[t]; }
+                }
+            }
+        }
+    };
+    copy(true, &mut tmp);
+    draw_text(tmp.as_mut_ptr(), tw as u32, h as u32, 0, 0, color, font, size, text);
+    copy(false, &mut tmp);
+}
+
 /// Draw submit button appearance into the pixel buffer.
 fn draw_submit_pixels(buf: *mut u32, stride: u32, buf_h: u32, x: i32, y: i32, bx: &LayoutBox) {
     let label_text = if let Some(ref t) = bx.text { t.as_str() } else { "Submit" };
@@ -952,6 +1346,273 @@ fn draw_submit_pixels(buf: *mut u32, stride: u32, buf_h: u32, x: i32, y: i32, bx
     libfont_client::draw_string_buf(buf, stride, buf_h, tx, ty, text_color, 0, font_size, label_text);
 }
 
+/// Content box (inside border and padding) of `bx` placed at `(x, y)`.
+fn content_rect(bx: &LayoutBox, x: i32, y: i32) -> (i32, i32, i32, i32) {
+    let bw = bx.border_width;
+    (
+        x + bw + bx.padding.left,
+        y + bw + bx.padding.top,
+        (bx.width - bx.padding.left - bx.padding.right - bw * 2).max(0),
+        (bx.height - bx.padding.top - bx.padding.bottom - bw * 2).max(0),
+    )
+}
+
+/// Draw an `<audio>` / `<video>` element into its content box: the current
+/// video frame (or the poster before playback starts) letterboxed on black,
+/// and the controls bar along the bottom edge.
+fn draw_media_pixels(
+    buf: *mut u32, stride: u32, buf_h: u32, images: &ImageCache,
+    x: i32, y: i32, w: i32, h: i32, bx: &LayoutBox, m: &MediaBox,
+) {
+    if w <= 0 || h <= 0 {
+        return;
+    }
+    if m.kind == MediaKind::Video {
+        fill_rect_buf(buf, stride, buf_h, x, y, w, h, 0xFF000000);
+        let started = !m.state.paused || m.state.position_ms > 0;
+        let frame_key = media::frame_key(bx.node_id.unwrap_or(0));
+        let picture = if m.has_frame && started {
+            images.get_ref(&frame_key)
+        } else {
+            None
+        }.or_else(|| m.poster.as_deref().and_then(|p| images.get_ref(p)));
+        if let Some(entry) = picture {
+            let (fw, fh) = fit_size(entry.width, entry.height, w, h);
+            blit_image_buf(
+                buf, stride, buf_h,
+                x + (w - fw) / 2, y + (h - fh) / 2, fw, fh,
+                &entry.pixels, entry.width, entry.height,
+            );
+        }
+    }
+    if !m.controls {
+        return;
+    }
+
+    // Controls bar: play/pause button, progress track, time readout.
+    let bar_h = media::CONTROLS_HEIGHT.min(h);
+    let bar_y = y + h - bar_h;
+    let bar_bg = if m.kind == MediaKind::Video { 0xB0202020 } else { 0xFF303030 };
+    fill_rect_buf(buf, stride, buf_h, x, bar_y, w, bar_h, bar_bg);
+
+    let glyph_x = x + media::PLAY_BUTTON_WIDTH / 2 - 5;
+    let glyph_y = bar_y + bar_h / 2 - 6;
+    if m.state.paused {
+        // Right-pointing triangle, 12 px tall.
+        for row in 0..12 {
+            let len = if row < 6 { row + 1 } else { 12 - row };
+            fill_rect_buf(buf, stride, buf_h, glyph_x, glyph_y + row, len, 1, 0xFFFFFFFF);
+        }
+    } else {
+        fill_rect_buf(buf, stride, buf_h, glyph_x, glyph_y, 3, 12, 0xFFFFFFFF);
+        fill_rect_buf(buf, stride, buf_h, glyph_x + 6, glyph_y, 3, 12, 0xFFFFFFFF);
+    }
+
+    let track_x = x + media::PLAY_BUTTON_WIDTH;
+    let track_w = w - media::PLAY_BUTTON_WIDTH - media::TIME_WIDTH;
+    if track_w > 0 {
+        let track_y = bar_y + bar_h / 2 - 2;
+        fill_rect_buf(buf, stride, buf_h, track_x, track_y, track_w, 4, 0xFF6A6A6A);
+        if m.state.duration_ms > 0 {
+            let done = (track_w as u64 * m.state.position_ms.min(m.state.duration_ms) as u64
+                / m.state.duration_ms as u64) as i32;
+            fill_rect_buf(buf, stride, buf_h, track_x, track_y, done, 4, 0xFF3D8EF0);
+        }
+    }
+
+    let label = if m.state.error {
+        String::from("Error")
+    } else if m.state.duration_ms > 0 {
+        alloc::format!("{} / {}", media::format_time(m.state.position_ms), media::format_time(m.state.duration_ms))
+    } else {
+        media::format_time(m.state.position_ms)
+    };
+    let font_size = 12u16;
+    let (tw, _) = libfont_client::measure(0, font_size, &label);
+    let tx = x + w - 8 - (tw as i32).min(media::TIME_WIDTH - 8);
+    let ty = bar_y + (bar_h - font_size as i32) / 2;
+    libfont_client::draw_string_buf(buf, stride, buf_h, tx, ty, 0xFFFFFFFF, 0, font_size, &label);
+}
+
+/// Largest `src_w × src_h`-proportioned size that fits in `w × h`.
+fn fit_size(src_w: u32, src_h: u32, w: i32, h: i32) -> (i32, i32) {
+    if src_w == 0 || src_h == 0 {
+        return (w, h);
+    }
+    let scaled_h = (w as i64 * src_h as i64 / src_w as i64) as i32;
+    if scaled_h <= h {
+        (w, scaled_h.max(1))
+    } else {
+        ((h as i64 * src_w as i64 / src_h as i64) as i32, h)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Layers: opacity and 2D transforms
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Paint a box with `opacity < 1` or a `transform` as a layer.
+///
+/// The subtree is painted into two offscreen buffers, one cleared to black
+/// and one to white; the difference between them recovers each pixel's
+/// coverage.  The layer is then composited into `buf` through the box's
+/// transform (nearest-neighbour sampling) and scaled by its opacity.  Only
+/// the part of the subtree that lands inside `[tile_y_start, tile_y_end)`
+/// is painted.
+fn paint_layer(
+    bx: &LayoutBox,
+    buf: *mut u32,
+    stride: u32,
+    buf_h: u32,
+    images: &ImageCache,
+    offset_x: i32,
+    offset_y: i32,
+    tile_y_start: i32,
+    tile_y_end: i32,
+) {
+    if bx.opacity <= 0 || buf.is_null() {
+        return;
+    }
+    let abs_x = if bx.is_fixed { bx.x } else { offset_x + bx.x };
+    let abs_y = if bx.is_fixed { bx.y } else { offset_y + bx.y };
+
+    let m = if bx.transform.is_empty() {
+        Affine::IDENTITY
+    } else {
+        layer_matrix(bx, abs_x, abs_y)
+    };
+    let inv = match m.invert() {
+        Some(inv) => inv,
+        None => return, // collapsed to a line or point
+    };
+
+    // Destination area: the transformed subtree clipped to the tile.
+    let (bx0, by0, bx1, by1) = paint_bounds(bx, abs_x, abs_y);
+    let (dx0, dy0, dx1, dy1) = m.map_rect(bx0, by0, bx1, by1);
+    let dx0 = dx0.max(0);
+    let dy0 = dy0.max(tile_y_start);
+    let dx1 = dx1.min(stride as i32);
+    let dy1 = dy1.min(tile_y_end).min(tile_y_start + buf_h as i32);
+    if dx0 >= dx1 || dy0 >= dy1 {
+        return;
+    }
+
+    // Source area that maps into it.  The layer always starts at x = 0 so
+    // that `position: fixed` descendants land where the tile walk puts them.
+    let (_, sy0, sx1, sy1) = inv.map_rect(dx0, dy0, dx1, dy1);
+    let sy0 = sy0.max(by0);
+    let lw = (sx1 + 1).min(bx1);
+    let lh = (sy1 + 1).min(by1) - sy0;
+    if lw <= 0 || lh <= 0 {
+        return;
+    }
+    let lh = lh.min((MAX_LAYER_PIXELS / lw as i64) as i32).max(1);
+
+    let count = lw as usize * lh as usize;
+    let mut black = alloc::vec![0xFF000000u32; count];
+    let mut white = alloc::vec![0xFFFFFFFFu32; count];
+    paint_box(bx, black.as_mut_ptr(), lw as u32, lh as u32, images, offset_x, offset_y, sy0, sy0 + lh);
+    paint_box(bx, white.as_mut_ptr(), lw as u32, lh as u32, images, offset_x, offset_y, sy0, sy0 + lh);
+
+    let opacity = bx.opacity.min(255) as u32;
+    let half = 1i64 << 15;
+    unsafe {
+        for dy in dy0..dy1 {
+            let dst_row = (dy - tile_y_start) as usize * stride as usize;
+            for dx in dx0..dx1 {
+                let (sx, sy) = inv.apply(((dx as i64) << 16) + half, ((dy as i64) << 16) + half);
+                let lx = (sx >> 16) as i32;
+                let ly = (sy >> 16) as i32 - sy0;
+                if lx < 0 || ly < 0 || lx >= lw || ly >= lh {
+                    continue;
+                }
+                let idx = ly as usize * lw as usize + lx as usize;
+                let b = black[idx];
+                let w = white[idx];
+                // Coverage: how much of the backdrop difference survived.
+                let cov = 255 - (((w >> 8) & 0xFF).saturating_sub((b >> 8) & 0xFF));
+                if cov == 0 {
+                    continue;
+                }
+                let a = cov * opacity / 255;
+                let inv_a = 255 - a;
+                let dst_ptr = buf.add(dst_row + dx as usize);
+                let dst = *dst_ptr;
+                // `b` is the layer colour premultiplied by its coverage.
+                let r = (((b >> 16) & 0xFF) * opacity + ((dst >> 16) & 0xFF) * inv_a) / 255;
+                let g = (((b >> 8) & 0xFF) * opacity + ((dst >> 8) & 0xFF) * inv_a) / 255;
+                let bl = ((b & 0xFF) * opacity + (dst & 0xFF) * inv_a) / 255;
+                *dst_ptr = 0xFF000000 | (r.min(255) << 16) | (g.min(255) << 8) | bl.min(255);
+            }
+        }
+    }
+}
+
+/// Document-space matrix of a transformed box whose border box starts at
+/// `(abs_x, abs_y)`.
+fn layer_matrix(bx: &LayoutBox, abs_x: i32, abs_y: i32) -> Affine {
+    Affine::translate(abs_x, abs_y)
+        .mul(&transform_matrix(&bx.transform, bx.width, bx.height))
+        .mul(&Affine::translate(-abs_x, -abs_y))
+}
+
+/// Document-space bounding box `(x0, y0, x1, y1)` of everything `bx` and
+/// its subtree paint, including transformed descendants but not `bx`'s own
+/// transform.
+fn paint_bounds(bx: &LayoutBox, abs_x: i32, abs_y: i32) -> (i32, i32, i32, i32) {
+    let mut b = (abs_x, abs_y, abs_x + bx.width, abs_y + bx.height);
+    if bx.list_marker.is_some() {
+        b.0 = b.0.min(abs_x - 20);
+    }
+    for child in &bx.children {
+        if child.visibility_hidden {
+            continue;
+        }
+        let cx = if child.is_fixed { child.x } else { abs_x + child.x };
+        let cy = if child.is_fixed { child.y } else { abs_y + child.y };
+        let mut cb = paint_bounds(child, cx, cy);
+        if !child.transform.is_empty() {
+            cb = layer_matrix(child, cx, cy).map_rect(cb.0, cb.1, cb.2, cb.3);
+        }
+        b = (b.0.min(cb.0), b.1.min(cb.1), b.2.max(cb.2), b.3.max(cb.3));
+    }
+    b
+}
+
+/// Document Y range `[y0, y1)` painted by the boxes of `nodes` and their
+/// subtrees, after their own and their ancestors' transforms.
+pub(crate) fn paint_extent(root: &LayoutBox, nodes: &[NodeId]) -> Option<(i32, i32)> {
+    let mut out = None;
+    collect_extent(root, nodes, 0, 0, &Affine::IDENTITY, &mut out);
+    out
+}
+
+fn collect_extent(
+    bx: &LayoutBox,
+    nodes: &[NodeId],
+    offset_x: i32,
+    offset_y: i32,
+    m: &Affine,
+    out: &mut Option<(i32, i32)>,
+) {
+    let abs_x = if bx.is_fixed { bx.x } else { offset_x + bx.x };
+    let abs_y = if bx.is_fixed { bx.y } else { offset_y + bx.y };
+    let m = if bx.transform.is_empty() { *m } else { m.mul(&layer_matrix(bx, abs_x, abs_y)) };
+
+    if bx.node_id.map_or(false, |id| nodes.contains(&id)) {
+        let (x0, y0, x1, y1) = paint_bounds(bx, abs_x, abs_y);
+        let (_, y0, _, y1) = m.map_rect(x0, y0, x1, y1);
+        *out = Some(match *out {
+            Some((a, b)) => (a.min(y0), b.max(y1)),
+            None => (y0, y1),
+        });
+        return;
+    }
+    for child in &bx.children {
+        collect_extent(child, nodes, abs_x, abs_y, &m, out);
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // Buffer drawing helpers
 // ═══════════════════════════════════════════════════════════════════════════
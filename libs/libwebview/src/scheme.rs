@@ -0,0 +1,204 @@
+//! Color schemes — `prefers-color-scheme`, dark page defaults and force-dark.
+//!
+//! The scheme pages are asked for follows the anyui theme unless the
+//! embedder pins it with [`ColorScheme`]; it decides which
+//! `@media (prefers-color-scheme: …)` blocks apply.
+//!
+//! A page that declares dark support (`color-scheme: dark` in a stylesheet
+//! or `<meta name="color-scheme" content="light dark">`) is rendered with the
+//! dark user-agent defaults when dark is preferred: [`DARK_CSS`] for text,
+//! links and form controls, and the dark [`Palette`] for the canvas.
+//!
+//! With force-dark on, a page that has no dark styles of its own gets the
+//! same defaults and is darkened after style resolution
+//! ([`darken_styles`]): light backgrounds and borders get a dark lightness
+//! and dark text a light one, keeping hue and alpha. Images are pixels, not
+//! styles, and keep their colours.
+
+use alloc::vec::Vec;
+
+use libanyui_client as ui;
+
+use crate::css::{CssValue, MediaCondition, Property, Stylesheet};
+use crate::dom::{Dom, NodeType, Tag};
+use crate::style::ComputedStyle;
+
+/// The scheme pages are asked for (`prefers-color-scheme`).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorScheme {
+    /// Follow the anyui theme (default).
+    System,
+    Light,
+    Dark,
+}
+
+impl ColorScheme {
+    /// Whether pages are asked for dark.
+    pub fn prefers_dark(self) -> bool {
+        match self {
+            ColorScheme::System => ui::get_theme() == 0,
+            ColorScheme::Light => false,
+            ColorScheme::Dark => true,
+        }
+    }
+}
+
+/// How the current page is rendered.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PageScheme {
+    Light,
+    /// The page supports dark and gets the dark defaults.
+    Dark,
+    /// A light-only page darkened by force-dark.
+    ForcedDark,
+}
+
+impl PageScheme {
+    pub fn is_dark(self) -> bool {
+        self != PageScheme::Light
+    }
+}
+
+/// User-agent colours for what the page's styles leave unset.
+#[derive(Clone, Copy)]
+pub struct Palette {
+    /// Canvas behind a `<body>` without a background.
+    pub canvas: u32,
+    /// Text fields and text areas without a background / text colour.
+    pub control_bg: u32,
+    pub control_fg: u32,
+}
+
+pub const LIGHT_PALETTE: Palette = Palette {
+    canvas: 0xFFFFFFFF,
+    control_bg: 0xFFFFFFFF,
+    control_fg: 0xFF000000,
+};
+
+pub const DARK_PALETTE: Palette = Palette {
+    canvas: 0xFF1E1E1E,
+    control_bg: 0xFF2B2B2B,
+    control_fg: 0xFFE0E0E0,
+};
+
+impl PageScheme {
+    pub fn palette(self) -> &'static Palette {
+        if self.is_dark() { &DARK_PALETTE } else { &LIGHT_PALETTE }
+    }
+}
+
+/// Dark user-agent stylesheet, cascaded right after the default one when a
+/// page renders dark. Its colours are already dark, so force-dark keeps
+/// them as they are.
+pub const DARK_CSS: &str = "
+body { color: #e0e0e0; }
+a { color: #6cb4ff; }
+pre { background: #2a2a2a; }
+blockquote { border-left: 4px solid #444; }
+hr { border-top: 1px solid #5a5a5a; }
+input, textarea, select, button { background: #2b2b2b; color: #e0e0e0; border: 1px solid #5a5a5a; }
+";
+
+/// Decide how a page is rendered from the preferred scheme, the force-dark
+/// switch and what the page declares.
+pub fn page_scheme(prefers_dark: bool, force_dark: bool, dom: &Dom, sheets: &[&Stylesheet]) -> PageScheme {
+    if !prefers_dark {
+        return PageScheme::Light;
+    }
+    if declares_dark(dom, sheets) {
+        return PageScheme::Dark;
+    }
+    // A page with its own dark rules already looks right.
+    if force_dark && !has_dark_media(sheets) {
+        return PageScheme::ForcedDark;
+    }
+    PageScheme::Light
+}
+
+/// `color-scheme` mentioning `dark` in a stylesheet or `<meta>` tag.
+fn declares_dark(dom: &Dom, sheets: &[&Stylesheet]) -> bool {
+    let meta = dom.nodes.iter().enumerate().any(|(id, node)| {
+        matches!(node.node_type, NodeType::Element { tag: Tag::Meta, .. })
+            && dom.attr(id, "name").map_or(false, |n| n.eq_ignore_ascii_case("color-scheme"))
+            && dom.attr(id, "content").map_or(false, mentions_dark)
+    });
+    meta || sheets.iter().any(|sheet| {
+        sheet.rules.iter().flat_map(|r| r.declarations.iter()).any(|d| {
+            d.property == Property::ColorScheme
+                && matches!(d.value, CssValue::Keyword(ref k) if mentions_dark(k))
+        })
+    })
+}
+
+fn mentions_dark(value: &str) -> bool {
+    value.split_ascii_whitespace().any(|w| w.eq_ignore_ascii_case("dark"))
+}
+
+/// Any `@media (prefers-color-scheme: dark)` block.
+fn has_dark_media(sheets: &[&Stylesheet]) -> bool {
+    sheets.iter().flat_map(|s| s.media_rules.iter()).any(|mr| {
+        mr.query.conditions.iter().any(|c| {
+            matches!(c, MediaCondition::PrefersColorScheme(s) if s.eq_ignore_ascii_case("dark"))
+        })
+    })
+}
+
+// ── Force-dark ──────────────────────────────────────────────────────────────
+
+/// Darken resolved styles for [`PageScheme::ForcedDark`].
+pub fn darken_styles(styles: &mut [ComputedStyle]) {
+    for s in styles.iter_mut() {
+        s.color = lighten_text(s.color);
+        s.background_color = darken_surface(s.background_color);
+        s.border_color = darken_surface(s.border_color);
+    }
+}
+
+/// Darken a light background or border colour; dark ones stay.
+pub fn darken_surface(argb: u32) -> u32 {
+    if argb >> 24 == 0 || luma(argb) < 128 {
+        return argb;
+    }
+    shift_lightness(argb, 0x1E, 0xFF)
+}
+
+/// Lighten dark text; light text stays.
+pub fn lighten_text(argb: u32) -> u32 {
+    if argb >> 24 == 0 || luma(argb) >= 128 {
+        return argb;
+    }
+    shift_lightness(argb, 0x00, 0xE8)
+}
+
+fn luma(argb: u32) -> u32 {
+    let (r, g, b) = ((argb >> 16) & 0xFF, (argb >> 8) & 0xFF, argb & 0xFF);
+    (r * 299 + g * 587 + b * 114) / 1000
+}
+
+/// Mirror the lightness of `argb` around the middle (white ↔ black),
+/// shifting all channels alike so the hue stays, and clamp the result to
+/// `min..=max` lightness.
+fn shift_lightness(argb: u32, min: i32, max: i32) -> u32 {
+    let y = luma(argb) as i32;
+    let target = (255 - y).clamp(min, max);
+    let delta = target - y;
+    let ch = |shift: u32| (((argb >> shift) & 0xFF) as i32 + delta).clamp(0, 255) as u32;
+    (argb & 0xFF00_0000) | (ch(16) << 16) | (ch(8) << 8) | ch(0)
+}
+
+/// The stylesheets of a page in cascade order, with the dark user-agent
+/// sheet after the default one when the page renders dark.
+pub fn cascade<'a>(
+    default_sheet: &'a Stylesheet,
+    dark_sheet: &'a Stylesheet,
+    author: &[&'a Stylesheet],
+    scheme: PageScheme,
+) -> Vec<&'a Stylesheet> {
+    let mut all = Vec::with_capacity(2 + author.len());
+    all.push(default_sheet);
+    if scheme.is_dark() {
+        all.push(dark_sheet);
+    }
+    all.extend_from_slice(author);
+    all
+}
@@ -0,0 +1,291 @@
+//! Smooth scrolling, momentum, and scroll anchoring.
+//!
+//! The ScrollView applies wheel input as instant jumps.  [`Scroller`] turns
+//! those jumps (and programmatic smooth scrolls) into an eased animation
+//! toward a target offset, and continues drag gestures with a decaying
+//! fling velocity after release.  Positions are kept in 1/256 px fixed point
+//! and the animation advances in fixed `STEP_MS` sub-steps, so the motion is
+//! the same regardless of how often the host calls `tick()`.
+//!
+//! Scroll anchoring keeps the reading position stable across relayouts: before
+//! a relayout the first element visible at the top of the viewport and its
+//! distance from the viewport top are recorded ([`Anchor`]); afterwards the
+//! scroll offset is adjusted so that element sits at the same distance again,
+//! even if images above it finished loading and pushed it down.
+
+use crate::dom::NodeId;
+use crate::layout::LayoutBox;
+
+/// Fixed-point shift for sub-pixel positions (1/256 px).
+const FRAC: u32 = 8;
+/// Animation sub-step in milliseconds.
+const STEP_MS: u32 = 8;
+/// Fraction (of 256) of the remaining distance covered per sub-step of a
+/// smooth scroll: a wheel notch settles in roughly 150 ms.
+const EASE_PER_STEP: i64 = 64;
+/// Fling velocity retained per sub-step (of 256): ~0.997 per millisecond.
+const FRICTION_PER_STEP: i64 = 250;
+/// Flings slower than this (px/s) stop.
+const MIN_FLING_VELOCITY: i64 = 20;
+/// Upper bound of a fling's start velocity (px/s).
+const MAX_FLING_VELOCITY: i64 = 8000;
+/// A drag released after resting this long (ms) does not fling.
+const FLING_REST_MS: u32 = 100;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Motion {
+    Idle,
+    /// Easing toward `target`.
+    Smooth,
+    /// Coasting with `velocity`.
+    Fling,
+    /// Following a pointer drag.
+    Drag,
+}
+
+/// Scroll position animator for one WebView.
+pub(crate) struct Scroller {
+    /// Current (displayed) offset, 1/256 px.
+    pos: i64,
+    /// Target offset of a smooth scroll, 1/256 px.
+    target: i64,
+    /// Fling velocity in 1/256 px per millisecond.
+    velocity: i64,
+    motion: Motion,
+    /// Milliseconds not yet consumed by a whole sub-step.
+    carry_ms: u32,
+    /// Pointer Y and document offset when the drag started.
+    drag_origin: (i32, i64),
+    /// Last drag sample: pointer Y and uptime (ms).
+    drag_last: (i32, u32),
+    /// Whether wheel jumps and `scroll_by()` animate.
+    pub smooth: bool,
+    /// Whether the current layout belongs to the same document as the next
+    /// one (false until the first layout of a new document).
+    pub anchoring: bool,
+}
+
+impl Scroller {
+    pub fn new() -> Self {
+        Scroller {
+            pos: 0,
+            target: 0,
+            velocity: 0,
+            motion: Motion::Idle,
+            carry_ms: 0,
+            drag_origin: (0, 0),
+            drag_last: (0, 0),
+            smooth: true,
+            anchoring: false,
+        }
+    }
+
+    /// Forget all motion and jump to offset 0 (new document).
+    pub fn reset(&mut self) {
+        self.jump(0);
+        self.anchoring = false;
+    }
+
+    /// Current offset in whole pixels.
+    pub fn position(&self) -> i32 {
+        (self.pos >> FRAC) as i32
+    }
+
+    /// Whether an animation or fling is in progress.
+    pub fn is_active(&self) -> bool {
+        matches!(self.motion, Motion::Smooth | Motion::Fling)
+    }
+
+    /// Whether a pointer drag is in progress.
+    pub fn is_dragging(&self) -> bool {
+        self.motion == Motion::Drag
+    }
+
+    /// Stop any motion and place the offset at `y`.
+    pub fn jump(&mut self, y: i32) {
+        self.pos = (y as i64) << FRAC;
+        self.target = self.pos;
+        self.velocity = 0;
+        self.motion = Motion::Idle;
+        self.carry_ms = 0;
+    }
+
+    /// Animate toward `y`.  Retargets a running animation without restarting
+    /// it, so repeated wheel notches accumulate.
+    pub fn animate_to(&mut self, y: i32) {
+        if !self.smooth {
+            self.jump(y);
+            return;
+        }
+        self.target = (y as i64) << FRAC;
+        self.velocity = 0;
+        if self.motion != Motion::Smooth {
+            self.motion = Motion::Smooth;
+            self.carry_ms = 0;
+        }
+    }
+
+    /// Target of the running smooth scroll, or the current offset.
+    pub fn target(&self) -> i32 {
+        match self.motion {
+            Motion::Smooth => (self.target >> FRAC) as i32,
+            _ => self.position(),
+        }
+    }
+
+    /// Move position and target by `dy` pixels (the content under the
+    /// viewport moved, e.g. by scroll anchoring).
+    pub fn shift(&mut self, dy: i32) {
+        let d = (dy as i64) << FRAC;
+        self.pos += d;
+        self.target += d;
+        if self.motion == Motion::Drag {
+            self.drag_origin.1 += d;
+        }
+    }
+
+    /// Start following a pointer drag at pointer Y `y`.
+    pub fn drag_begin(&mut self, y: i32, now_ms: u32) {
+        self.motion = Motion::Drag;
+        self.velocity = 0;
+        self.target = self.pos;
+        self.drag_origin = (y, self.pos);
+        self.drag_last = (y, now_ms);
+    }
+
+    /// Follow the pointer to `y`.  Returns the new offset in pixels.
+    pub fn drag_move(&mut self, y: i32, now_ms: u32, max: i32) -> i32 {
+        if self.motion != Motion::Drag {
+            return self.position();
+        }
+        let (last_y, last_ms) = self.drag_last;
+        let dt = now_ms.wrapping_sub(last_ms).max(1) as i64;
+        // Content follows the pointer, so scrolling runs opposite to it.
+        let instant = (((last_y - y) as i64) << FRAC) / dt;
+        // Weight the newest sample most, smoothing out jittery input.
+        self.velocity = (instant * 3 + self.velocity) / 4;
+        self.drag_last = (y, now_ms);
+
+        let (oy, opos) = self.drag_origin;
+        self.pos = (opos + (((oy - y) as i64) << FRAC)).max(0).min((max as i64) << FRAC);
+        self.target = self.pos;
+        self.position()
+    }
+
+    /// End the drag, flinging with the release velocity unless the pointer
+    /// rested before it was lifted.
+    pub fn drag_end(&mut self, now_ms: u32) {
+        if self.motion != Motion::Drag {
+            return;
+        }
+        let rested = now_ms.wrapping_sub(self.drag_last.1) > FLING_REST_MS;
+        let limit = (MAX_FLING_VELOCITY << FRAC) / 1000;
+        self.velocity = self.velocity.max(-limit).min(limit);
+        if rested || self.velocity.abs() * 1000 < MIN_FLING_VELOCITY << FRAC {
+            self.velocity = 0;
+            self.motion = Motion::Idle;
+        } else {
+            self.motion = Motion::Fling;
+            self.carry_ms = 0;
+        }
+    }
+
+    /// Advance the animation by `delta_ms`, clamped to `[0, max]`.
+    /// Returns the new offset if it changed.
+    pub fn advance(&mut self, delta_ms: u32, max: i32) -> Option<i32> {
+        if !self.is_active() {
+            return None;
+        }
+        let before = self.position();
+        let max_fp = (max.max(0) as i64) << FRAC;
+        self.target = self.target.max(0).min(max_fp);
+        self.carry_ms += delta_ms;
+        while self.carry_ms >= STEP_MS && self.is_active() {
+            self.carry_ms -= STEP_MS;
+            match self.motion {
+                Motion::Smooth => {
+                    let diff = self.target - self.pos;
+                    if diff.abs() <= 1 << (FRAC - 1) {
+                        self.pos = self.target;
+                        self.motion = Motion::Idle;
+                    } else {
+                        let step = diff * EASE_PER_STEP / 256;
+                        self.pos += if step == 0 { diff.signum() } else { step };
+                    }
+                }
+                Motion::Fling => {
+                    self.pos += self.velocity * STEP_MS as i64;
+                    self.velocity = self.velocity * FRICTION_PER_STEP / 256;
+                    if self.pos <= 0 || self.pos >= max_fp {
+                        self.pos = self.pos.max(0).min(max_fp);
+                        self.motion = Motion::Idle;
+                    } else if self.velocity.abs() * 1000 < MIN_FLING_VELOCITY << FRAC {
+                        self.motion = Motion::Idle;
+                    }
+                    self.target = self.pos;
+                }
+                _ => {}
+            }
+        }
+        if !self.is_active() {
+            self.carry_ms = 0;
+        }
+        let after = self.position();
+        if after != before { Some(after) } else { None }
+    }
+}
+
+// ── Scroll anchoring ────────────────────────────────────────────────────────
+
+/// The element the reading position is pinned to across a relayout.
+#[derive(Clone, Copy)]
+pub(crate) struct Anchor {
+    pub node: NodeId,
+    /// Viewport top minus the element's top edge.
+    pub offset: i32,
+}
+
+/// Pick the anchor for a viewport starting at document Y `scroll_y`: the
+/// deepest element on the path of first boxes (in document order) that
+/// reach below the viewport top.  `None` at the very top of the page, where
+/// the page should simply stay scrolled to the top.
+pub(crate) fn select_anchor(root: &LayoutBox, scroll_y: i32, viewport_h: i32) -> Option<Anchor> {
+    if scroll_y <= 0 {
+        return None;
+    }
+    let mut node = None;
+    let mut bx = root;
+    let mut abs_y = root.y;
+    loop {
+        if bx.node_id.is_some() {
+            node = bx.node_id;
+        }
+        let next = bx.children.iter().find(|c| {
+            let y = abs_y + c.y;
+            !c.is_fixed && c.height > 0 && y + c.height > scroll_y && y < scroll_y + viewport_h
+        });
+        match next {
+            Some(c) => {
+                abs_y += c.y;
+                bx = c;
+            }
+            None => break,
+        }
+    }
+    let node = node?;
+    let top = node_top(root, node)?;
+    Some(Anchor { node, offset: scroll_y - top })
+}
+
+/// Document Y of the first layout box generated by `node`.
+pub(crate) fn node_top(root: &LayoutBox, node: NodeId) -> Option<i32> {
+    find_top(root, node, 0)
+}
+
+fn find_top(bx: &LayoutBox, node: NodeId, parent_y: i32) -> Option<i32> {
+    let abs_y = if bx.is_fixed { bx.y } else { parent_y + bx.y };
+    if bx.node_id == Some(node) {
+        return Some(abs_y);
+    }
+    bx.children.iter().find_map(|c| find_top(c, node, abs_y))
+}
@@ -0,0 +1,231 @@
+//! Text selection and find-in-page.
+//!
+//! The layout tree stores text as one box per word (plus separate boxes for
+//! inter-word spaces), so selection works on a flattened view of it: a
+//! [`TextIndex`] lists every text run in document order with its absolute
+//! document rectangle, and concatenates their contents into one string.
+//! Selections and find matches are plain byte ranges into that string, which
+//! maps back to pixel rectangles for highlighting via `rects_for_range()`.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::fonts::TextFont;
+use crate::layout::{self, LayoutBox};
+
+/// A single text fragment of the layout tree, in absolute document coordinates.
+struct TextRun {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    font_size: i32,
+    bold: bool,
+    font: Option<TextFont>,
+    /// Byte offset of this run's text within `TextIndex::text`.
+    start: usize,
+    /// Byte length of this run's text.
+    len: usize,
+}
+
+/// Flattened, position-aware view of all text on the page.
+pub(crate) struct TextIndex {
+    runs: Vec<TextRun>,
+    /// Concatenated run text.  Runs on different lines are separated by `'\n'`.
+    text: String,
+}
+
+impl TextIndex {
+    /// Build the index from the root layout box.
+    pub fn build(root: &LayoutBox) -> Self {
+        let mut idx = TextIndex { runs: Vec::new(), text: String::new() };
+        idx.walk(root, 0, 0);
+        idx
+    }
+
+    fn walk(&mut self, bx: &LayoutBox, offset_x: i32, offset_y: i32) {
+        if bx.visibility_hidden {
+            return;
+        }
+
+        // Same coordinate rules as the tile rasterizer.
+        let abs_x = if bx.is_fixed { bx.x } else { offset_x + bx.x };
+        let abs_y = if bx.is_fixed { bx.y } else { offset_y + bx.y };
+
+        if let Some(ref text) = bx.text {
+            if !text.is_empty() && bx.form_field.is_none() {
+                if let Some(prev) = self.runs.last() {
+                    if prev.y != abs_y && !self.text.ends_with('\n') {
+                        self.text.push('\n');
+                    }
+                }
+                let start = self.text.len();
+                self.text.push_str(text);
+                self.runs.push(TextRun {
+                    x: abs_x,
+                    y: abs_y,
+                    width: layout::measure_in(text, bx.font_size.max(1), bx.bold, bx.font).0,
+                    height: bx.height,
+                    font_size: bx.font_size.max(1),
+                    bold: bx.bold,
+                    font: bx.font,
+                    start,
+                    len: text.len(),
+                });
+            }
+        }
+
+        for child in &bx.children {
+            let (cx, cy) = if bx.is_fixed { (bx.x, bx.y) } else { (abs_x, abs_y) };
+            self.walk(child, cx, cy);
+        }
+    }
+
+    /// Total length of the indexed text in bytes.
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    /// Text between two byte offsets (in either order).
+    pub fn slice(&self, a: usize, b: usize) -> &str {
+        let (s, e) = if a <= b { (a, b) } else { (b, a) };
+        let e = e.min(self.text.len());
+        self.text.get(s.min(e)..e).unwrap_or("")
+    }
+
+    /// Map a document-space point to the nearest text offset.
+    ///
+    /// Points between lines snap to the closest line; points left or right of
+    /// a line snap to its start or end.
+    pub fn hit_test(&self, x: i32, y: i32) -> Option<usize> {
+        let mut best: Option<(&TextRun, i32, i32)> = None;
+        for run in &self.runs {
+            let w = run.width;
+            let dy = if y < run.y { run.y - y } else if y >= run.y + run.height { y - (run.y + run.height) + 1 } else { 0 };
+            let dx = if x < run.x { run.x - x } else if x >= run.x + w { x - (run.x + w) + 1 } else { 0 };
+            let better = match best {
+                None => true,
+                Some((_, bdy, bdx)) => dy < bdy || (dy == bdy && dx < bdx),
+            };
+            if better {
+                best = Some((run, dy, dx));
+                if dy == 0 && dx == 0 {
+                    break;
+                }
+            }
+        }
+        let (run, _, _) = best?;
+
+        // Find the character boundary closest to `x` within the run.
+        let text = &self.text[run.start..run.start + run.len];
+        let rel_x = x - run.x;
+        let mut prev_w = 0;
+        for (i, ch) in text.char_indices() {
+            let end = i + ch.len_utf8();
+            let w = self.run_width(run, end);
+            if rel_x < (prev_w + w) / 2 {
+                return Some(run.start + i);
+            }
+            prev_w = w;
+        }
+        Some(run.start + run.len)
+    }
+
+    /// Bounds of the word (run of alphanumeric characters) around `offset`.
+    ///
+    /// If `offset` is on a non-word character, selects just that character.
+    pub fn word_bounds(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.text.len());
+        let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '\'';
+
+        let mut start = offset;
+        for (i, ch) in self.text[..offset].char_indices().rev() {
+            if !is_word(ch) { break; }
+            start = i;
+        }
+        let mut end = offset;
+        for (i, ch) in self.text[offset..].char_indices() {
+            if !is_word(ch) { break; }
+            end = offset + i + ch.len_utf8();
+        }
+
+        if start == end {
+            if let Some(ch) = self.text[offset..].chars().next() {
+                end = offset + ch.len_utf8();
+            }
+        }
+        (start, end)
+    }
+
+    /// Document-space rectangles `(x, y, w, h)` covering the byte range.
+    pub fn rects_for_range(&self, a: usize, b: usize) -> Vec<(i32, i32, i32, i32)> {
+        let (s, e) = if a <= b { (a, b) } else { (b, a) };
+        let mut rects = Vec::new();
+        if s == e {
+            return rects;
+        }
+        for run in &self.runs {
+            let run_end = run.start + run.len;
+            if run_end <= s || run.start >= e {
+                continue;
+            }
+            let local_s = s.max(run.start) - run.start;
+            let local_e = e.min(run_end) - run.start;
+            let x0 = if local_s == 0 { 0 } else { self.run_width(run, local_s) };
+            let x1 = self.run_width(run, local_e);
+            if x1 > x0 {
+                rects.push((run.x + x0, run.y, x1 - x0, run.height));
+            }
+        }
+        rects
+    }
+
+    /// Document Y coordinate of the line containing `offset`.
+    pub fn y_for_offset(&self, offset: usize) -> i32 {
+        self.runs.iter()
+            .find(|r| offset < r.start + r.len)
+            .or(self.runs.last())
+            .map(|r| r.y)
+            .unwrap_or(0)
+    }
+
+    /// Find all non-overlapping occurrences of `query`.
+    ///
+    /// Matching ignores line breaks (a line break matches a space) and, unless
+    /// `match_case` is set, ASCII case.  Returns `(start, end)` byte ranges.
+    pub fn find_all(&self, query: &str, match_case: bool) -> Vec<(usize, usize)> {
+        let mut out = Vec::new();
+        if query.is_empty() {
+            return out;
+        }
+        // Both transforms preserve byte lengths, so offsets stay valid.
+        let fold = |s: &str| -> Vec<u8> {
+            s.bytes()
+                .map(|b| if b == b'\n' { b' ' } else { b })
+                .map(|b| if match_case { b } else { b.to_ascii_lowercase() })
+                .collect()
+        };
+        let hay = fold(&self.text);
+        let needle = fold(query);
+        if needle.len() > hay.len() {
+            return out;
+        }
+
+        let mut i = 0;
+        while i + needle.len() <= hay.len() {
+            if hay[i..i + needle.len()] == needle[..] && self.text.is_char_boundary(i) {
+                out.push((i, i + needle.len()));
+                i += needle.len();
+            } else {
+                i += 1;
+            }
+        }
+        out
+    }
+
+    /// Pixel width of the first `len` bytes of a run.
+    fn run_width(&self, run: &TextRun, len: usize) -> i32 {
+        let text = &self.text[run.start..run.start + len];
+        layout::measure_in(text, run.font_size, run.bold, run.font).0
+    }
+}
@@ -0,0 +1,259 @@
+//! Session persistence: a WebView's navigation state as a compact byte blob.
+//!
+//! `WebView::save_session()` captures the session history (URL, title and
+//! scroll position of every entry, plus which one is current) and drafts
+//! of the form fields the user changed on the current page.  The browser
+//! writes the blob out periodically; after a crash it hands it to
+//! `WebView::restore_session()` on a fresh WebView.
+//!
+//! Restoring fetches nothing: history and URL are back, but the page stays
+//! empty until `WebView::resume_session()` (the browser calls it once the
+//! user interacts with the tab, e.g. selects it).  That reloads the current
+//! entry, scrolls to where the user was and fills the drafts back in.
+//!
+//! Drafts are keyed by field name and the field's position among fields of
+//! that name, so they survive a page whose node IDs changed.  Password and
+//! file inputs are never saved.
+//!
+//! # Format
+//!
+//! Integers are LEB128 varints (scroll offsets zigzag-encoded), strings a
+//! varint length followed by UTF-8 bytes:
+//!
+//! ```text
+//! "WVS" version:u8
+//! entry_count { url title scroll_y }
+//! current+1  (0 = no current entry)
+//! draft_count { name nth kind:u8 value state }
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::dom::Dom;
+use crate::forms::{self, FieldState};
+use crate::layout::FormFieldKind;
+use crate::navigation::HistoryEntry;
+
+const MAGIC: &[u8; 3] = b"WVS";
+const VERSION: u8 = 1;
+
+/// Saved value of one form field the user changed.
+#[derive(Clone)]
+pub struct FormDraft {
+    /// The field's `name` attribute (may be empty).
+    pub name: String,
+    /// Index among the page's fields with the same name, in tree order.
+    pub nth: u32,
+    pub kind: FormFieldKind,
+    /// Text of text inputs and textareas.
+    pub value: String,
+    /// Checked flag (checkbox / radio) or selected option index (select).
+    pub state: u32,
+}
+
+/// Everything a session blob holds.
+pub struct Session {
+    pub entries: Vec<HistoryEntry>,
+    pub current: Option<usize>,
+    pub drafts: Vec<FormDraft>,
+}
+
+// ── Form drafts ─────────────────────────────────────────────────────────
+
+/// (name, nth) key of every field, parallel to `fields` (tree order).
+fn field_keys(dom: &Dom, fields: &[FieldState]) -> Vec<(String, u32)> {
+    let mut keys: Vec<(String, u32)> = Vec::with_capacity(fields.len());
+    for f in fields {
+        let name = String::from(dom.attr(f.node_id, "name").unwrap_or(""));
+        let nth = keys.iter().filter(|(n, _)| *n == name).count() as u32;
+        keys.push((name, nth));
+    }
+    keys
+}
+
+/// Drafts of the fields whose live state differs from the page's default.
+pub fn collect_drafts(dom: &Dom, fields: &[FieldState]) -> Vec<FormDraft> {
+    let keys = field_keys(dom, fields);
+    let mut drafts = Vec::new();
+    for (f, (name, nth)) in fields.iter().zip(keys) {
+        let changed = match f.kind {
+            FormFieldKind::TextInput => f.value != dom.attr(f.node_id, "value").unwrap_or(""),
+            FormFieldKind::Textarea => f.value != forms::textarea_default(dom, f.node_id),
+            FormFieldKind::Checkbox | FormFieldKind::Radio => {
+                f.state != dom.attr(f.node_id, "checked").is_some() as u32
+            }
+            FormFieldKind::Select => {
+                f.state != forms::initial_selection(dom, &forms::select_options(dom, f.node_id))
+            }
+            _ => false,
+        };
+        if changed {
+            drafts.push(FormDraft { name, nth, kind: f.kind, value: f.value.clone(), state: f.state });
+        }
+    }
+    drafts
+}
+
+/// Match drafts to the fields of a freshly loaded page: for each field
+/// with a draft of the same key and kind, yields (index into `fields`, draft).
+pub fn match_drafts<'a>(
+    dom: &Dom,
+    fields: &[FieldState],
+    drafts: &'a [FormDraft],
+) -> Vec<(usize, &'a FormDraft)> {
+    let keys = field_keys(dom, fields);
+    let mut out = Vec::new();
+    for (i, (name, nth)) in keys.iter().enumerate() {
+        if let Some(d) = drafts.iter().find(|d| d.name == *name && d.nth == *nth && d.kind == fields[i].kind) {
+            out.push((i, d));
+        }
+    }
+    out
+}
+
+// ── Encoding ────────────────────────────────────────────────────────────
+
+fn kind_code(kind: FormFieldKind) -> u8 {
+    match kind {
+        FormFieldKind::TextInput => 0,
+        FormFieldKind::Textarea => 1,
+        FormFieldKind::Checkbox => 2,
+        FormFieldKind::Radio => 3,
+        FormFieldKind::Select => 4,
+        _ => 0xFF,
+    }
+}
+
+fn kind_from_code(code: u8) -> Option<FormFieldKind> {
+    Some(match code {
+        0 => FormFieldKind::TextInput,
+        1 => FormFieldKind::Textarea,
+        2 => FormFieldKind::Checkbox,
+        3 => FormFieldKind::Radio,
+        4 => FormFieldKind::Select,
+        _ => return None,
+    })
+}
+
+fn put_varint(out: &mut Vec<u8>, mut v: u64) {
+    loop {
+        let byte = (v & 0x7F) as u8;
+        v >>= 7;
+        if v == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn put_str(out: &mut Vec<u8>, s: &str) {
+    put_varint(out, s.len() as u64);
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn zigzag(v: i32) -> u64 {
+    ((v << 1) ^ (v >> 31)) as u32 as u64
+}
+
+fn unzigzag(v: u64) -> i32 {
+    let v = v as u32;
+    ((v >> 1) as i32) ^ -((v & 1) as i32)
+}
+
+/// Serialize a session.
+pub fn encode(session: &Session) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    put_varint(&mut out, session.entries.len() as u64);
+    for e in &session.entries {
+        put_str(&mut out, &e.url);
+        put_str(&mut out, &e.title);
+        put_varint(&mut out, zigzag(e.scroll_y));
+    }
+    put_varint(&mut out, session.current.map_or(0, |i| i as u64 + 1));
+    put_varint(&mut out, session.drafts.len() as u64);
+    for d in &session.drafts {
+        put_str(&mut out, &d.name);
+        put_varint(&mut out, d.nth as u64);
+        out.push(kind_code(d.kind));
+        put_str(&mut out, &d.value);
+        put_varint(&mut out, d.state as u64);
+    }
+    out
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Option<u8> {
+        let b = *self.data.get(self.pos)?;
+        self.pos += 1;
+        Some(b)
+    }
+
+    fn varint(&mut self) -> Option<u64> {
+        let mut v = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.byte()?;
+            v |= ((b & 0x7F) as u64) << shift;
+            if b & 0x80 == 0 {
+                return Some(v);
+            }
+        }
+        None
+    }
+
+    fn str(&mut self) -> Option<String> {
+        let len = self.varint()? as usize;
+        let end = self.pos.checked_add(len)?;
+        let bytes = self.data.get(self.pos..end)?;
+        self.pos = end;
+        core::str::from_utf8(bytes).ok().map(String::from)
+    }
+
+    /// A count of items that need at least one byte each (guards against
+    /// huge allocations from a corrupt blob).
+    fn count(&mut self) -> Option<usize> {
+        let n = self.varint()? as usize;
+        if n > self.data.len() - self.pos { None } else { Some(n) }
+    }
+}
+
+/// Parse a blob written by [`encode`]. `None` if it is corrupt or from an
+/// unknown version.
+pub fn decode(data: &[u8]) -> Option<Session> {
+    if data.len() < 4 || &data[..3] != MAGIC || data[3] != VERSION {
+        return None;
+    }
+    let mut r = Reader { data, pos: 4 };
+    let n = r.count()?;
+    let mut entries = Vec::with_capacity(n);
+    for _ in 0..n {
+        let url = r.str()?;
+        let title = r.str()?;
+        let scroll_y = unzigzag(r.varint()?);
+        entries.push(HistoryEntry { url, title, scroll_y });
+    }
+    let current = match r.varint()? {
+        0 => None,
+        i if (i as usize) <= entries.len() => Some(i as usize - 1),
+        _ => return None,
+    };
+    let n = r.count()?;
+    let mut drafts = Vec::with_capacity(n);
+    for _ in 0..n {
+        let name = r.str()?;
+        let nth = r.varint()? as u32;
+        let kind = kind_from_code(r.byte()?)?;
+        let value = r.str()?;
+        let state = r.varint()? as u32;
+        drafts.push(FormDraft { name, nth, kind, value, state });
+    }
+    Some(Session { entries, current, drafts })
+}
@@ -15,6 +15,8 @@ use crate::css::{
     Stylesheet, Unit,
 };
 use crate::dom::{Dom, NodeId, NodeType, Tag};
+use crate::fonts::TextFont;
+use crate::transform::{parse_transform, transform_to_css, TransformOp};
 
 // ---------------------------------------------------------------------------
 // Enums
@@ -218,6 +220,11 @@ pub struct ComputedStyle {
     pub font_size: i32,          // pixels
     pub font_weight: FontWeight,
     pub font_style: FontStyleVal,
+    /// `font-family` list as written, lowercase (empty = system font).
+    pub font_family: String,
+    /// Fonts `font_family` resolves to, filled in by `fonts::FontSet::assign`
+    /// after styles are resolved (empty = system font).
+    pub font_chain: Vec<TextFont>,
     pub text_align: TextAlignVal,
     pub text_decoration: TextDeco,
     pub line_height: i32,        // pixels (0 = auto -> 1.2 * font_size)
@@ -290,6 +297,8 @@ pub struct ComputedStyle {
     pub opacity: i32,            // 0..255 (255 = fully opaque)
     pub visibility: Visibility,
     pub text_transform: TextTransform,
+    /// 2D `transform` functions (empty = `none`).
+    pub transform: Vec<TransformOp>,
     // Overflow
     pub overflow_x: OverflowVal,
     pub overflow_y: OverflowVal,
@@ -317,6 +326,7 @@ const SET_LIST_STYLE: u16 = 1 << 7;
 const SET_TEXT_DECO: u16  = 1 << 8;
 const SET_VISIBILITY: u16 = 1 << 9;
 const SET_TEXT_TRANSFORM: u16 = 1 << 10;
+const SET_FONT_FAMILY: u16 = 1 << 11;
 
 // ---------------------------------------------------------------------------
 // Defaults
@@ -331,6 +341,8 @@ pub fn default_style() -> ComputedStyle {
         font_size: 16,
         font_weight: FontWeight::Normal,
         font_style: FontStyleVal::Normal,
+        font_family: String::new(),
+        font_chain: Vec::new(),
         text_align: TextAlignVal::Left,
         text_decoration: TextDeco::None,
         line_height: 0,
@@ -388,6 +400,7 @@ pub fn default_style() -> ComputedStyle {
         opacity: 255,
         visibility: Visibility::Visible,
         text_transform: TextTransform::None,
+        transform: Vec::new(),
         // Overflow
         overflow_x: OverflowVal::Visible,
         overflow_y: OverflowVal::Visible,
@@ -470,10 +483,13 @@ fn ua_style_and_flags(tag: Tag) -> (ComputedStyle, u16) {
         }
         Tag::Code => {
             s.display = Display::Inline;
+            s.font_family = String::from("monospace");
+            flags |= SET_FONT_FAMILY;
         }
         Tag::Pre => {
             s.white_space = WhiteSpace::Pre;
-            flags |= SET_WHITE_SPACE;
+            s.font_family = String::from("monospace");
+            flags |= SET_WHITE_SPACE | SET_FONT_FAMILY;
         }
         Tag::Blockquote => { s.margin_left = 40; }
         Tag::Ul => {
@@ -1086,6 +1102,20 @@ fn has_class(class_str: &str, needle: &str) -> bool {
 // Resolve styles for entire DOM
 // ---------------------------------------------------------------------------
 
+/// Resolved styles of a document, kept so that a later stylesheet or newly
+/// streamed-in nodes can be applied without re-resolving the whole tree.
+pub struct StyleCache {
+    /// Computed style per node, indexed by `NodeId`.
+    pub styles: Vec<ComputedStyle>,
+    /// Custom properties defined by each node (needed to resolve `var()`
+    /// in re-resolved descendants of unchanged nodes).
+    custom_props: Vec<Vec<(String, String)>>,
+    /// Viewport width the styles were resolved for (media queries).
+    pub viewport_width: i32,
+    /// Color scheme the styles were resolved for (`prefers-color-scheme`).
+    pub prefers_dark: bool,
+}
+
 /// Compute the final resolved style for every node in the DOM.
 /// Returns a `Vec<ComputedStyle>` indexed by `NodeId`.
 pub fn resolve_styles(
@@ -1093,8 +1123,29 @@ pub fn resolve_styles(
     stylesheets: &[&Stylesheet],
     viewport_width: i32,
     viewport_height: i32,
+    prefers_dark: bool,
     inline_style_cache: &mut Vec<(usize, Vec<Declaration>)>,
 ) -> Vec<ComputedStyle> {
+    restyle(dom, stylesheets, viewport_width, viewport_height, prefers_dark, inline_style_cache, None).styles
+}
+
+/// Resolve styles, reusing a previous result where possible.
+///
+/// With `prev = Some((cache, dirty))`, nodes covered by `cache` keep their
+/// cached style unless flagged in `dirty` (nodes past its end count as
+/// clean); dirty nodes and nodes added since the cache was built are
+/// resolved again.  `dirty` must already include the descendants of every
+/// dirty node (see [`affected_nodes`]).  With `prev = None` every node is
+/// resolved.
+pub fn restyle(
+    dom: &Dom,
+    stylesheets: &[&Stylesheet],
+    viewport_width: i32,
+    viewport_height: i32,
+    prefers_dark: bool,
+    inline_style_cache: &mut Vec<(usize, Vec<Declaration>)>,
+    prev: Option<(StyleCache, &[bool])>,
+) -> StyleCache {
     let count = dom.nodes.len();
     crate::debug_surf!("[style] resolve_styles: {} nodes, {} stylesheets", count, stylesheets.len());
     #[cfg(feature = "debug_surf")]
@@ -1103,23 +1154,7 @@ pub fn resolve_styles(
     let mut styles: Vec<ComputedStyle> = Vec::with_capacity(count);
     let root_font_size: i32 = 16;
 
-    // ── Pre-collect all applicable CSS rules ONCE (node-independent). ──
-    let mut all_rules: Vec<(&Rule, usize)> = Vec::new();
-    let mut order = 0usize;
-    for sheet in stylesheets {
-        for rule in &sheet.rules {
-            all_rules.push((rule, order));
-            order += 1;
-        }
-        for mr in &sheet.media_rules {
-            if crate::css::evaluate_media_query(&mr.query, viewport_width, viewport_height) {
-                for rule in &mr.rules {
-                    all_rules.push((rule, order));
-                    order += 1;
-                }
-            }
-        }
-    }
+    let all_rules = collect_rules(stylesheets, viewport_width, viewport_height, prefers_dark);
     crate::debug_surf!("[style] collected {} applicable rules (once)", all_rules.len());
 
     // Build rule index for O(1) tag/id/class lookup (avoids O(nodes × rules) brute force).
@@ -1137,9 +1172,24 @@ pub fn resolve_styles(
     // var() references are resolved on-demand by walking the DOM parent chain,
     // eliminating the per-node clone that caused heap-stack collision on large
     // pages (~54 MiB for chip.de's 6228 nodes).
-    let mut custom_props: Vec<Vec<(String, String)>> = vec![Vec::new(); count];
+    let (mut custom_props, mut old_styles, dirty) = match prev {
+        Some((cache, dirty)) => (cache.custom_props, Some(cache.styles.into_iter()), dirty),
+        None => (Vec::new(), None, &[][..]),
+    };
+    custom_props.resize(count, Vec::new());
 
     for id in 0..count {
+        // Incremental restyle: keep the cached style of clean nodes.
+        if let Some(ref mut old) = old_styles {
+            if let Some(old_style) = old.next() {
+                if !dirty.get(id).copied().unwrap_or(false) {
+                    styles.push(old_style);
+                    continue;
+                }
+            }
+        }
+        custom_props[id].clear();
+
         #[cfg(feature = "debug_surf")]
         {
             if id < 5 || id % 1000 == 0 {
@@ -1250,7 +1300,84 @@ pub fn resolve_styles(
     crate::debug_surf!("[style] resolve_styles done: {} styles", styles.len());
     #[cfg(feature = "debug_surf")]
     crate::debug_surf!("[style]   RSP=0x{:X} heap=0x{:X}", crate::debug_rsp(), crate::debug_heap_pos());
-    styles
+    StyleCache { styles, custom_props, viewport_width, prefers_dark }
+}
+
+/// Collect all rules of `stylesheets` that apply at the given viewport and
+/// color scheme (plain rules plus matching `@media` blocks), tagged with
+/// source order.
+fn collect_rules<'a>(
+    stylesheets: &[&'a Stylesheet],
+    viewport_width: i32,
+    viewport_height: i32,
+    prefers_dark: bool,
+) -> Vec<(&'a Rule, usize)> {
+    let mut all_rules: Vec<(&Rule, usize)> = Vec::new();
+    let mut order = 0usize;
+    for sheet in stylesheets {
+        for rule in &sheet.rules {
+            all_rules.push((rule, order));
+            order += 1;
+        }
+        for mr in &sheet.media_rules {
+            if crate::css::evaluate_media_query(&mr.query, viewport_width, viewport_height, prefers_dark) {
+                for rule in &mr.rules {
+                    all_rules.push((rule, order));
+                    order += 1;
+                }
+            }
+        }
+    }
+    all_rules
+}
+
+/// Nodes whose style may change when `sheet` is added to the document.
+///
+/// Marks every element matched by one of the sheet's selectors, plus all of
+/// its descendants (inherited properties, `em` units and `var()` flow down
+/// the tree).  The result is indexed by `NodeId`.
+pub fn affected_nodes(
+    dom: &Dom,
+    sheet: &Stylesheet,
+    viewport_width: i32,
+    viewport_height: i32,
+    prefers_dark: bool,
+) -> Vec<bool> {
+    let count = dom.nodes.len();
+    let mut dirty = vec![false; count];
+    let all_rules = collect_rules(&[sheet], viewport_width, viewport_height, prefers_dark);
+    if all_rules.is_empty() {
+        return dirty;
+    }
+    let rule_index = RuleIndex::build(&all_rules);
+    let mut candidates: Vec<usize> = Vec::with_capacity(64);
+    let mut seen_bitset: Vec<u64> = Vec::new();
+
+    for id in 0..count {
+        let node = &dom.nodes[id];
+        // Parents precede children in the arena, so a dirty parent is
+        // already known when its children are visited.
+        if node.parent.map_or(false, |pid| pid < id && dirty[pid]) {
+            dirty[id] = true;
+            continue;
+        }
+        let (tag, attrs) = match &node.node_type {
+            NodeType::Element { tag, attrs } => (*tag, attrs),
+            _ => continue,
+        };
+        let id_attr = attrs.iter()
+            .find(|a| eq_ignore_ascii_case(&a.name, "id"))
+            .map(|a| a.value.as_str());
+        let class_attr = attrs.iter()
+            .find(|a| eq_ignore_ascii_case(&a.name, "class"))
+            .map(|a| a.value.as_str());
+
+        rule_index.candidates(tag, id_attr, class_attr, &mut candidates, &mut seen_bitset);
+        dirty[id] = candidates.iter().any(|&idx| {
+            all_rules[idx].0.selectors.iter().any(|sel| selector_matches(sel, dom, id))
+        });
+    }
+    dirty
 }
 
 fn apply_author_rules(
@@ -1427,6 +1554,9 @@ fn inherit_unset(child: &mut ComputedStyle, parent: &ComputedStyle, set: u16) {
     if set & SET_FONT_SIZE == 0  { child.font_size = parent.font_size; }
     if set & SET_FONT_WEIGHT == 0 { child.font_weight = parent.font_weight; }
     if set & SET_FONT_STYLE == 0 { child.font_style = parent.font_style; }
+    if set & SET_FONT_FAMILY == 0 && child.font_family != parent.font_family {
+        child.font_family = parent.font_family.clone();
+    }
     if set & SET_TEXT_ALIGN == 0 { child.text_align = parent.text_align; }
     if set & SET_LINE_HEIGHT == 0 { child.line_height = parent.line_height; }
     if set & SET_WHITE_SPACE == 0 { child.white_space = parent.white_space; }
@@ -1443,6 +1573,7 @@ fn decl_set_flag(prop: &Property) -> u16 {
         Property::FontSize => SET_FONT_SIZE,
         Property::FontWeight => SET_FONT_WEIGHT,
         Property::FontStyle => SET_FONT_STYLE,
+        Property::FontFamily => SET_FONT_FAMILY,
         Property::TextAlign => SET_TEXT_ALIGN,
         Property::LineHeight => SET_LINE_HEIGHT,
         Property::WhiteSpace => SET_WHITE_SPACE,
@@ -1570,6 +1701,11 @@ pub fn apply_declaration(
                 };
             }
         }
+        Property::FontFamily => {
+            if let CssValue::Keyword(ref kw) = decl.value {
+                style.font_family = kw.clone();
+            }
+        }
         Property::TextAlign => {
             if let CssValue::Keyword(ref kw) = decl.value {
                 style.text_align = match kw.as_str() {
@@ -1974,6 +2110,15 @@ pub fn apply_declaration(
                 };
             }
         }
+        Property::Transform => {
+            match decl.value {
+                CssValue::Keyword(ref kw) => {
+                    if let Some(ops) = parse_transform(kw) { style.transform = ops; }
+                }
+                CssValue::None => style.transform.clear(),
+                _ => {}
+            }
+        }
         Property::TextTransform => {
             if let CssValue::Keyword(ref kw) = decl.value {
                 style.text_transform = match kw.as_str() {
@@ -2108,7 +2253,8 @@ pub fn apply_declaration(
         | Property::BorderStyle | Property::Overflow
         | Property::AlignContent | Property::Flex
         | Property::Gap | Property::Cursor
-        | Property::BorderCollapse | Property::BorderSpacing | Property::TableLayout => {}
+        | Property::BorderCollapse | Property::BorderSpacing | Property::TableLayout
+        | Property::ColorScheme => {}
         // Grid container properties
         Property::GridTemplateColumns => {
             style.grid_template_columns = decode_track_list(&decl.value);
@@ -2377,6 +2523,36 @@ pub(crate) fn apply_timing(timing: TimingFunction, t: i32) -> i32 {
     }
 }
 
+/// Properties CSS transitions animate, by CSS name (the order of
+/// [`transition_values`]).
+pub const TRANSITIONABLE: [&str; 4] = ["opacity", "color", "background-color", "transform"];
+
+/// The computed value of each [`TRANSITIONABLE`] property of `style`, as
+/// the declaration that sets it.
+pub fn transition_values(style: &ComputedStyle) -> [Declaration; 4] {
+    let decl = |property, value| Declaration { property, value, important: false };
+    let transform = if style.transform.is_empty() {
+        CssValue::None
+    } else {
+        CssValue::Keyword(transform_to_css(&style.transform))
+    };
+    [
+        // opacity 0..255 → CSS number × 100, rounded.
+        decl(Property::Opacity, CssValue::Number((style.opacity * 100 + 127) / 255)),
+        decl(Property::Color, CssValue::Color(style.color)),
+        decl(Property::BackgroundColor, CssValue::Color(style.background_color)),
+        decl(Property::Transform, transform),
+    ]
+}
+
+/// The `transition` entry of `style` that covers `property` (by name or
+/// `all`), if it has a non-zero duration.  Later entries win.
+pub fn transition_def<'a>(style: &'a ComputedStyle, property: &str) -> Option<&'a TransitionDef> {
+    style.transitions.iter().rev()
+        .find(|t| t.property == property || t.property == "all")
+        .filter(|t| t.duration_ms > 0)
+}
+
 /// Parse a CSS time value (`"0.3s"`, `"300ms"`) to milliseconds.
 fn parse_time_ms(s: &str) -> u32 {
     let s = s.trim();
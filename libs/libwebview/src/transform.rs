@@ -0,0 +1,489 @@
+//! CSS 2D transforms: parsing, serialization, interpolation, and the
+//! fixed-point affine matrices the renderer paints transformed boxes with.
+//!
+//! Supported functions: `translate`, `translateX`, `translateY`, `scale`,
+//! `scaleX`, `scaleY` and `rotate`.  All arithmetic is integer — rotation
+//! goes through a quarter-wave sine table, so no libm is needed.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+// ---------------------------------------------------------------------------
+// Transform functions
+// ---------------------------------------------------------------------------
+
+/// One function of a `transform` list.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TransformOp {
+    /// `translate()`.  Each axis is a pixel offset plus a percentage of the
+    /// box's own size (percent × 100, so `50%` → 5000).
+    Translate { x_px: i32, x_pct: i32, y_px: i32, y_pct: i32 },
+    /// `scale()`: factors × 1000 (`1.5` → 1500).
+    Scale(i32, i32),
+    /// `rotate()`: clockwise angle in hundredths of a degree.
+    Rotate(i32),
+}
+
+impl TransformOp {
+    /// The no-op function of the same kind (stands in for `none` when
+    /// interpolating).
+    fn identity(&self) -> TransformOp {
+        match self {
+            TransformOp::Translate { .. } => TransformOp::Translate { x_px: 0, x_pct: 0, y_px: 0, y_pct: 0 },
+            TransformOp::Scale(..) => TransformOp::Scale(1000, 1000),
+            TransformOp::Rotate(_) => TransformOp::Rotate(0),
+        }
+    }
+
+    fn same_kind(&self, other: &TransformOp) -> bool {
+        core::mem::discriminant(self) == core::mem::discriminant(other)
+    }
+}
+
+/// Parse a `transform` value such as `"translate(-50%, 10px) rotate(45deg)"`.
+///
+/// Function names are matched case-insensitively.  Returns `None` if any
+/// function is unknown or malformed (the declaration is then ignored);
+/// `"none"` yields an empty list.
+pub fn parse_transform(s: &str) -> Option<Vec<TransformOp>> {
+    let s = s.trim();
+    let mut ops = Vec::new();
+    if s.eq_ignore_ascii_case("none") {
+        return Some(ops);
+    }
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i].is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        let name_start = i;
+        while i < bytes.len() && bytes[i] != b'(' {
+            i += 1;
+        }
+        if i >= bytes.len() { return None; }
+        let name = s[name_start..i].trim();
+
+        // Arguments run to the matching ')' (calc() may nest).
+        i += 1;
+        let args_start = i;
+        let mut depth = 1;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'(' => depth += 1,
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 { break; }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        if depth != 0 { return None; }
+        let args: Vec<&str> = s[args_start..i].split(',').map(|a| a.trim()).collect();
+        i += 1;
+
+        let arg = |n: usize| args.get(n).copied().filter(|a| !a.is_empty());
+        let op = match to_lower(name).as_str() {
+            "translate" => {
+                let (x_px, x_pct) = parse_length_pct(arg(0)?)?;
+                let (y_px, y_pct) = match arg(1) {
+                    Some(a) => parse_length_pct(a)?,
+                    None => (0, 0),
+                };
+                TransformOp::Translate { x_px, x_pct, y_px, y_pct }
+            }
+            "translatex" => {
+                let (x_px, x_pct) = parse_length_pct(arg(0)?)?;
+                TransformOp::Translate { x_px, x_pct, y_px: 0, y_pct: 0 }
+            }
+            "translatey" => {
+                let (y_px, y_pct) = parse_length_pct(arg(0)?)?;
+                TransformOp::Translate { x_px: 0, x_pct: 0, y_px, y_pct }
+            }
+            "scale" => {
+                let sx = parse_factor(arg(0)?)?;
+                let sy = match arg(1) {
+                    Some(a) => parse_factor(a)?,
+                    None => sx,
+                };
+                TransformOp::Scale(sx, sy)
+            }
+            "scalex" => TransformOp::Scale(parse_factor(arg(0)?)?, 1000),
+            "scaley" => TransformOp::Scale(1000, parse_factor(arg(0)?)?),
+            "rotate" => TransformOp::Rotate(parse_angle(arg(0)?)?),
+            _ => return None,
+        };
+        ops.push(op);
+    }
+    Some(ops)
+}
+
+/// Serialize a transform list back to CSS (`parse_transform` round-trips it).
+pub fn transform_to_css(ops: &[TransformOp]) -> String {
+    if ops.is_empty() {
+        return String::from("none");
+    }
+    let mut out = String::new();
+    for op in ops {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        match *op {
+            TransformOp::Translate { x_px, x_pct, y_px, y_pct } => {
+                out.push_str("translate(");
+                push_length_pct(&mut out, x_px, x_pct);
+                out.push_str(", ");
+                push_length_pct(&mut out, y_px, y_pct);
+                out.push(')');
+            }
+            TransformOp::Scale(sx, sy) => {
+                out.push_str("scale(");
+                push_fixed(&mut out, sx, 1000);
+                out.push_str(", ");
+                push_fixed(&mut out, sy, 1000);
+                out.push(')');
+            }
+            TransformOp::Rotate(a) => {
+                out.push_str("rotate(");
+                push_fixed(&mut out, a, 100);
+                out.push_str("deg)");
+            }
+        }
+    }
+    out
+}
+
+/// Interpolate between two transform lists at `t` (0–1000).
+///
+/// Lists of the same shape (function kinds in the same order) are blended
+/// function by function, with an empty list (`none`) standing in for the
+/// identity of the other side.  Any other pair switches to `to` at the end.
+pub fn interpolate_transform(from: &[TransformOp], to: &[TransformOp], t: i32) -> Vec<TransformOp> {
+    if t >= 1000 {
+        return to.to_vec();
+    }
+    let same_shape = from.is_empty() || to.is_empty()
+        || (from.len() == to.len() && from.iter().zip(to.iter()).all(|(a, b)| a.same_kind(b)));
+    if !same_shape {
+        return from.to_vec();
+    }
+    let len = from.len().max(to.len());
+    let mut out = Vec::with_capacity(len);
+    for i in 0..len {
+        let a = from.get(i).copied().unwrap_or_else(|| to[i].identity());
+        let b = to.get(i).copied().unwrap_or_else(|| from[i].identity());
+        out.push(match (a, b) {
+            (
+                TransformOp::Translate { x_px: ax, x_pct: axp, y_px: ay, y_pct: ayp },
+                TransformOp::Translate { x_px: bx, x_pct: bxp, y_px: by, y_pct: byp },
+            ) => TransformOp::Translate {
+                x_px: lerp(ax, bx, t),
+                x_pct: lerp(axp, bxp, t),
+                y_px: lerp(ay, by, t),
+                y_pct: lerp(ayp, byp, t),
+            },
+            (TransformOp::Scale(ax, ay), TransformOp::Scale(bx, by)) => {
+                TransformOp::Scale(lerp(ax, bx, t), lerp(ay, by, t))
+            }
+            (TransformOp::Rotate(a), TransformOp::Rotate(b)) => TransformOp::Rotate(lerp(a, b, t)),
+            _ => b,
+        });
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Affine matrices
+// ---------------------------------------------------------------------------
+
+/// Fixed-point one (16.16).
+const ONE: i64 = 1 << 16;
+
+/// 2D affine matrix in 16.16 fixed point:
+/// `x' = a·x + c·y + e`, `y' = b·x + d·y + f`.
+#[derive(Clone, Copy)]
+pub struct Affine {
+    pub a: i64,
+    pub b: i64,
+    pub c: i64,
+    pub d: i64,
+    pub e: i64,
+    pub f: i64,
+}
+
+impl Affine {
+    pub const IDENTITY: Affine = Affine { a: ONE, b: 0, c: 0, d: ONE, e: 0, f: 0 };
+
+    /// Translation by whole pixels.
+    pub fn translate(x: i32, y: i32) -> Affine {
+        Affine { e: (x as i64) << 16, f: (y as i64) << 16, ..Affine::IDENTITY }
+    }
+
+    /// `self × other`: applies `other` first, then `self`.
+    pub fn mul(&self, o: &Affine) -> Affine {
+        Affine {
+            a: (self.a * o.a + self.c * o.b) >> 16,
+            b: (self.b * o.a + self.d * o.b) >> 16,
+            c: (self.a * o.c + self.c * o.d) >> 16,
+            d: (self.b * o.c + self.d * o.d) >> 16,
+            e: ((self.a * o.e + self.c * o.f) >> 16) + self.e,
+            f: ((self.b * o.e + self.d * o.f) >> 16) + self.f,
+        }
+    }
+
+    /// Inverse matrix, or `None` if the transform collapses to a line or
+    /// point (e.g. `scale(0)`).
+    pub fn invert(&self) -> Option<Affine> {
+        let det = (self.a * self.d - self.b * self.c) >> 16;
+        if det == 0 {
+            return None;
+        }
+        let a = (self.d << 16) / det;
+        let b = (-self.b << 16) / det;
+        let c = (-self.c << 16) / det;
+        let d = (self.a << 16) / det;
+        Some(Affine {
+            a, b, c, d,
+            e: -((a * self.e + c * self.f) >> 16),
+            f: -((b * self.e + d * self.f) >> 16),
+        })
+    }
+
+    /// Map a 16.16 point.
+    #[inline]
+    pub fn apply(&self, x: i64, y: i64) -> (i64, i64) {
+        (
+            ((self.a * x + self.c * y) >> 16) + self.e,
+            ((self.b * x + self.d * y) >> 16) + self.f,
+        )
+    }
+
+    /// Integer bounding box `(x0, y0, x1, y1)` of the pixel rectangle
+    /// `[x0, x1) × [y0, y1)` after mapping.
+    pub fn map_rect(&self, x0: i32, y0: i32, x1: i32, y1: i32) -> (i32, i32, i32, i32) {
+        let corners = [(x0, y0), (x1, y0), (x0, y1), (x1, y1)];
+        let mut min_x = i64::MAX;
+        let mut min_y = i64::MAX;
+        let mut max_x = i64::MIN;
+        let mut max_y = i64::MIN;
+        for &(x, y) in &corners {
+            let (mx, my) = self.apply((x as i64) << 16, (y as i64) << 16);
+            min_x = min_x.min(mx);
+            min_y = min_y.min(my);
+            max_x = max_x.max(mx);
+            max_y = max_y.max(my);
+        }
+        (
+            (min_x >> 16) as i32,
+            (min_y >> 16) as i32,
+            ((max_x + ONE - 1) >> 16) as i32,
+            ((max_y + ONE - 1) >> 16) as i32,
+        )
+    }
+}
+
+/// Matrix of a transform list applied to a `w × h` box, relative to the
+/// box's top-left corner.  The transform origin is the box centre (the
+/// CSS default `50% 50%`).
+pub fn transform_matrix(ops: &[TransformOp], w: i32, h: i32) -> Affine {
+    let cx = (w as i64) << 15;
+    let cy = (h as i64) << 15;
+    let mut m = Affine { e: cx, f: cy, ..Affine::IDENTITY };
+    for op in ops {
+        let step = match *op {
+            TransformOp::Translate { x_px, x_pct, y_px, y_pct } => Affine {
+                e: ((x_px as i64) << 16) + ((x_pct as i64 * w as i64) << 16) / 10000,
+                f: ((y_px as i64) << 16) + ((y_pct as i64 * h as i64) << 16) / 10000,
+                ..Affine::IDENTITY
+            },
+            TransformOp::Scale(sx, sy) => Affine {
+                a: ((sx as i64) << 16) / 1000,
+                d: ((sy as i64) << 16) / 1000,
+                ..Affine::IDENTITY
+            },
+            TransformOp::Rotate(angle) => {
+                let s = sin_fixed(angle);
+                let c = sin_fixed(angle + 9000);
+                Affine { a: c, b: s, c: -s, d: c, e: 0, f: 0 }
+            }
+        };
+        m = m.mul(&step);
+    }
+    m.mul(&Affine { e: -cx, f: -cy, ..Affine::IDENTITY })
+}
+
+/// sin(0°..=90°) in 16.16 fixed point, one entry per degree.
+const SIN_TABLE: [i64; 91] = [
+    0, 1144, 2287, 3430, 4572, 5712, 6850, 7987,
+    9121, 10252, 11380, 12505, 13626, 14742, 15855, 16962,
+    18064, 19161, 20252, 21336, 22415, 23486, 24550, 25607,
+    26656, 27697, 28729, 29753, 30767, 31772, 32768, 33754,
+    34729, 35693, 36647, 37590, 38521, 39441, 40348, 41243,
+    42126, 42995, 43852, 44695, 45525, 46341, 47143, 47930,
+    48703, 49461, 50203, 50931, 51643, 52339, 53020, 53684,
+    54332, 54963, 55578, 56175, 56756, 57319, 57865, 58393,
+    58903, 59396, 59870, 60326, 60764, 61183, 61584, 61966,
+    62328, 62672, 62997, 63303, 63589, 63856, 64104, 64332,
+    64540, 64729, 64898, 65048, 65177, 65287, 65376, 65446,
+    65496, 65526, 65536,
+];
+
+/// Sine of an angle in hundredths of a degree, 16.16 fixed point.
+fn sin_fixed(angle: i32) -> i64 {
+    let a = angle.rem_euclid(36000);
+    match a {
+        0..=9000 => quarter_sin(a),
+        9001..=18000 => quarter_sin(18000 - a),
+        18001..=27000 => -quarter_sin(a - 18000),
+        _ => -quarter_sin(36000 - a),
+    }
+}
+
+/// Table lookup for 0..=9000, linearly interpolated between whole degrees.
+fn quarter_sin(a: i32) -> i64 {
+    let deg = (a / 100) as usize;
+    let frac = (a % 100) as i64;
+    let lo = SIN_TABLE[deg];
+    let hi = SIN_TABLE[(deg + 1).min(90)];
+    lo + (hi - lo) * frac / 100
+}
+
+// ---------------------------------------------------------------------------
+// Value helpers
+// ---------------------------------------------------------------------------
+
+/// Parse a translate component: `10px`, `50%`, `2em`, `0`, or
+/// `calc(10px + 50%)`.  Returns `(px, percent × 100)`.
+fn parse_length_pct(s: &str) -> Option<(i32, i32)> {
+    let lower = to_lower(s);
+    if let Some(inner) = lower.strip_prefix("calc(").and_then(|r| r.strip_suffix(')')) {
+        let mut px = 0;
+        let mut pct = 0;
+        let mut negate = false;
+        for tok in inner.split_whitespace() {
+            match tok {
+                "+" => negate = false,
+                "-" => negate = true,
+                _ => {
+                    let (p, q) = parse_length_term(tok)?;
+                    let sign = if negate { -1 } else { 1 };
+                    px += sign * p;
+                    pct += sign * q;
+                    negate = false;
+                }
+            }
+        }
+        return Some((px, pct));
+    }
+    parse_length_term(&lower)
+}
+
+fn parse_length_term(s: &str) -> Option<(i32, i32)> {
+    if let Some(v) = s.strip_suffix('%') {
+        return Some((0, fixed(parse_f32(v)?, 100.0)));
+    }
+    if let Some(v) = s.strip_suffix("px") {
+        return Some((fixed(parse_f32(v)?, 1.0), 0));
+    }
+    if let Some(v) = s.strip_suffix("rem").or_else(|| s.strip_suffix("em")) {
+        return Some((fixed(parse_f32(v)?, 16.0), 0));
+    }
+    // Unitless lengths are only valid as zero.
+    match parse_f32(s)? {
+        v if v == 0.0 => Some((0, 0)),
+        _ => None,
+    }
+}
+
+/// Parse a scale factor (`1.5` or `150%`) to × 1000.
+fn parse_factor(s: &str) -> Option<i32> {
+    match s.strip_suffix('%') {
+        Some(v) => Some(fixed(parse_f32(v)?, 10.0)),
+        None => Some(fixed(parse_f32(s)?, 1000.0)),
+    }
+}
+
+/// Parse an angle (`45deg`, `0.5turn`, `1rad`, `100grad`, `0`) to
+/// hundredths of a degree.
+fn parse_angle(s: &str) -> Option<i32> {
+    let lower = to_lower(s);
+    let (num, scale) = if let Some(v) = lower.strip_suffix("deg") {
+        (v, 100.0)
+    } else if let Some(v) = lower.strip_suffix("grad") {
+        (v, 90.0)
+    } else if let Some(v) = lower.strip_suffix("rad") {
+        (v, 5729.578)
+    } else if let Some(v) = lower.strip_suffix("turn") {
+        (v, 36000.0)
+    } else {
+        (lower.as_str(), 0.0)
+    };
+    let v = parse_f32(num)?;
+    if scale == 0.0 {
+        // Unitless angles are only valid as zero.
+        return if v == 0.0 { Some(0) } else { None };
+    }
+    Some(fixed(v, scale))
+}
+
+fn parse_f32(s: &str) -> Option<f32> {
+    s.trim().parse::<f32>().ok()
+}
+
+/// Round `v × scale` to the nearest integer.
+fn fixed(v: f32, scale: f32) -> i32 {
+    let f = v * scale;
+    (if f < 0.0 { f - 0.5 } else { f + 0.5 }) as i32
+}
+
+/// Append a translate component in the form `parse_length_pct` reads.
+fn push_length_pct(out: &mut String, px: i32, pct: i32) {
+    if pct == 0 {
+        push_fixed(out, px, 1);
+        out.push_str("px");
+    } else if px == 0 {
+        push_fixed(out, pct, 100);
+        out.push('%');
+    } else {
+        out.push_str("calc(");
+        push_fixed(out, px, 1);
+        out.push_str("px + ");
+        push_fixed(out, pct, 100);
+        out.push_str("%)");
+    }
+}
+
+/// Append `v / div` as a decimal number without trailing zeros.
+fn push_fixed(out: &mut String, v: i32, div: i32) {
+    if v < 0 {
+        out.push('-');
+    }
+    let abs = v.unsigned_abs();
+    let div = div as u32;
+    out.push_str(&alloc::format!("{}", abs / div));
+    let mut frac = abs % div;
+    if frac != 0 {
+        out.push('.');
+        let mut place = div / 10;
+        while frac != 0 && place != 0 {
+            out.push((b'0' + (frac / place) as u8) as char);
+            frac %= place;
+            place /= 10;
+        }
+    }
+}
+
+fn to_lower(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+#[inline]
+fn lerp(a: i32, b: i32, t: i32) -> i32 {
+    a + (((b - a) as i64 * t as i64) / 1000) as i32
+}
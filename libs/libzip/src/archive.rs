@@ -0,0 +1,236 @@
+//! Format-independent archive reading.
+//!
+//! `open` sniffs the container format from its magic bytes and returns a
+//! reader behind the common `ArchiveReader` trait, so callers iterate and
+//! extract entries the same way for ZIP, 7z, tar, tar.gz and plain gzip files.
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::gzip;
+use crate::sevenz::{self, SevenZReader};
+use crate::tar::{self, TarReader};
+use crate::zip::ZipReader;
+
+// ── Format detection ────────────────────────────────────────────────────────
+
+/// Supported container formats (values are the C ABI format codes).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Zip = 1,
+    Tar = 2,
+    TarGz = 3,
+    /// A single gzip-compressed file (not a tarball).
+    Gzip = 4,
+    SevenZ = 5,
+}
+
+/// Identify the container format of `data` from its leading bytes.
+///
+/// Gzip data is only reported as `TarGz` if its payload is a tar archive,
+/// which requires decompressing it; `open` avoids doing that twice.
+pub fn detect(data: &[u8]) -> Option<Format> {
+    if is_zip(data) {
+        Some(Format::Zip)
+    } else if sevenz::is_7z(data) {
+        Some(Format::SevenZ)
+    } else if gzip::is_gzip(data) {
+        let inner = gzip::gzip_decompress(data)?;
+        Some(if tar::is_tar(&inner) { Format::TarGz } else { Format::Gzip })
+    } else if tar::is_tar(data) {
+        Some(Format::Tar)
+    } else {
+        None
+    }
+}
+
+/// Local file header or (for an empty archive) end-of-central-directory magic.
+fn is_zip(data: &[u8]) -> bool {
+    data.len() >= 4 && data[0] == b'P' && data[1] == b'K'
+        && ((data[2] == 3 && data[3] == 4) || (data[2] == 5 && data[3] == 6))
+}
+
+// ── Reader trait ────────────────────────────────────────────────────────────
+
+/// Uniform read access to the entries of an archive.
+pub trait ArchiveReader {
+    /// Number of entries.
+    fn entry_count(&self) -> usize;
+    /// Entry path inside the archive (directories may end with '/').
+    fn entry_name(&self, index: usize) -> Option<&str>;
+    /// Uncompressed size in bytes.
+    fn entry_size(&self, index: usize) -> u64;
+    /// True if the entry is a directory.
+    fn entry_is_dir(&self, index: usize) -> bool;
+    /// Uncompressed entry data (empty for directories).
+    fn extract(&self, index: usize) -> Option<Vec<u8>>;
+}
+
+impl ArchiveReader for ZipReader {
+    fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn entry_name(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(|e| e.name.as_str())
+    }
+
+    fn entry_size(&self, index: usize) -> u64 {
+        self.entries.get(index).map(|e| e.uncompressed_size as u64).unwrap_or(0)
+    }
+
+    fn entry_is_dir(&self, index: usize) -> bool {
+        self.entries.get(index).map(|e| e.name.ends_with('/')).unwrap_or(false)
+    }
+
+    fn extract(&self, index: usize) -> Option<Vec<u8>> {
+        ZipReader::extract(self, index)
+    }
+}
+
+impl ArchiveReader for TarReader {
+    fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn entry_name(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(|e| e.name.as_str())
+    }
+
+    fn entry_size(&self, index: usize) -> u64 {
+        self.entries.get(index).map(|e| e.size).unwrap_or(0)
+    }
+
+    fn entry_is_dir(&self, index: usize) -> bool {
+        self.entries.get(index).map(|e| e.is_dir).unwrap_or(false)
+    }
+
+    fn extract(&self, index: usize) -> Option<Vec<u8>> {
+        TarReader::extract(self, index)
+    }
+}
+
+impl ArchiveReader for SevenZReader {
+    fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn entry_name(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(|e| e.name.as_str())
+    }
+
+    fn entry_size(&self, index: usize) -> u64 {
+        self.entries.get(index).map(|e| e.size).unwrap_or(0)
+    }
+
+    fn entry_is_dir(&self, index: usize) -> bool {
+        self.entries.get(index).map(|e| e.is_dir).unwrap_or(false)
+    }
+
+    fn extract(&self, index: usize) -> Option<Vec<u8>> {
+        SevenZReader::extract(self, index)
+    }
+}
+
+/// A plain gzip file, presented as an archive with one entry.
+pub struct GzipFile {
+    name: String,
+    data: Vec<u8>,
+}
+
+impl ArchiveReader for GzipFile {
+    fn entry_count(&self) -> usize {
+        1
+    }
+
+    fn entry_name(&self, index: usize) -> Option<&str> {
+        if index == 0 { Some(&self.name) } else { None }
+    }
+
+    fn entry_size(&self, index: usize) -> u64 {
+        if index == 0 { self.data.len() as u64 } else { 0 }
+    }
+
+    fn entry_is_dir(&self, _index: usize) -> bool {
+        false
+    }
+
+    fn extract(&self, index: usize) -> Option<Vec<u8>> {
+        if index == 0 { Some(self.data.clone()) } else { None }
+    }
+}
+
+// ── Open ────────────────────────────────────────────────────────────────────
+
+/// Open an archive of any supported format.
+///
+/// `default_name` names the single entry of a plain gzip file whose header
+/// carries no original file name (typically the archive name minus `.gz`).
+pub fn open(data: Vec<u8>, default_name: &str) -> Option<(Format, Box<dyn ArchiveReader>)> {
+    if is_zip(&data) {
+        let reader = ZipReader::parse(data)?;
+        return Some((Format::Zip, Box::new(reader)));
+    }
+    if sevenz::is_7z(&data) {
+        let reader = SevenZReader::parse(data)?;
+        return Some((Format::SevenZ, Box::new(reader)));
+    }
+    if gzip::is_gzip(&data) {
+        let inner = gzip::gzip_decompress(&data)?;
+        if tar::is_tar(&inner) {
+            let reader = TarReader::parse(inner)?;
+            return Some((Format::TarGz, Box::new(reader)));
+        }
+        let name = gzip::gzip_original_name(&data).unwrap_or_else(|| String::from(default_name));
+        return Some((Format::Gzip, Box::new(GzipFile { name, data: inner })));
+    }
+    if tar::is_tar(&data) {
+        let reader = TarReader::parse(data)?;
+        return Some((Format::Tar, Box::new(reader)));
+    }
+    None
+}
+
+/// Default entry name for a plain gzip file at `path`: its file name
+/// without the `.gz` / `.gzip` suffix.
+pub fn gzip_default_name(path: &str) -> String {
+    let file = path.rsplit('/').next().unwrap_or(path);
+    let stem = file.strip_suffix(".gz")
+        .or_else(|| file.strip_suffix(".gzip"))
+        .unwrap_or(file);
+    if stem.is_empty() || stem == file {
+        return alloc::format!("{}.out", file);
+    }
+    String::from(stem)
+}
+
+/// Cursor over an open archive, as held by a `libarchive_*` handle.
+pub struct ArchiveCursor {
+    pub format: Format,
+    pub reader: Box<dyn ArchiveReader>,
+    /// Index of the current entry (`None` before the first `next_entry`).
+    pub current: Option<usize>,
+}
+
+impl ArchiveCursor {
+    pub fn new(format: Format, reader: Box<dyn ArchiveReader>) -> Self {
+        ArchiveCursor { format, reader, current: None }
+    }
+
+    /// Advance to the next entry.  Returns its index, or `None` at the end.
+    pub fn advance(&mut self) -> Option<usize> {
+        let next = self.current.map_or(0, |i| i + 1);
+        if next >= self.reader.entry_count() {
+            self.current = Some(self.reader.entry_count());
+            return None;
+        }
+        self.current = Some(next);
+        Some(next)
+    }
+
+    /// Index of the current entry, if positioned on one.
+    pub fn entry(&self) -> Option<usize> {
+        self.current.filter(|&i| i < self.reader.entry_count())
+    }
+}
@@ -2,20 +2,50 @@
 //!
 //! Implements stored blocks (no compression) and fixed Huffman encoding with
 //! LZ77 matching for reasonable compression ratios.
+//!
+//! [`deflate_into`] is the heap-free form used by the parallel ZIP writer's
+//! worker threads: the caller supplies the hash-chain table and a fixed
+//! output buffer.
 
 use alloc::vec::Vec;
 
 // ─── Bit Writer ─────────────────────────────────────────────────────────────
 
-struct BitWriter {
-    output: Vec<u8>,
+/// Destination of the compressed bytes.
+trait Output {
+    fn push(&mut self, byte: u8);
+}
+
+impl Output for Vec<u8> {
+    fn push(&mut self, byte: u8) {
+        Vec::push(self, byte);
+    }
+}
+
+/// A fixed buffer; bytes past its end are counted but dropped.
+struct SliceOutput<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl Output for SliceOutput<'_> {
+    fn push(&mut self, byte: u8) {
+        if let Some(b) = self.buf.get_mut(self.len) {
+            *b = byte;
+        }
+        self.len += 1;
+    }
+}
+
+struct BitWriter<O: Output> {
+    output: O,
     bit_buf: u32,
     bit_count: u8,
 }
 
-impl BitWriter {
-    fn new() -> Self {
-        BitWriter { output: Vec::new(), bit_buf: 0, bit_count: 0 }
+impl<O: Output> BitWriter<O> {
+    fn new(output: O) -> Self {
+        BitWriter { output, bit_buf: 0, bit_count: 0 }
     }
 
     fn write_bits(&mut self, value: u32, count: u8) {
@@ -36,7 +66,7 @@ impl BitWriter {
         }
     }
 
-    fn finish(mut self) -> Vec<u8> {
+    fn finish(mut self) -> O {
         self.flush();
         self.output
     }
@@ -45,7 +75,7 @@ impl BitWriter {
 // ─── Fixed Huffman Codes ────────────────────────────────────────────────────
 
 /// Encode a literal/length symbol using fixed Huffman codes.
-fn encode_fixed_literal(writer: &mut BitWriter, sym: u16) {
+fn encode_fixed_literal<O: Output>(writer: &mut BitWriter<O>, sym: u16) {
     // Fixed Huffman code table (reversed bit order for DEFLATE):
     // 0-143:   8-bit codes 00110000..10111111 (0x30..0xBF)
     // 144-255: 9-bit codes 110010000..111111111 (0x190..0x1FF)
@@ -67,7 +97,7 @@ fn encode_fixed_literal(writer: &mut BitWriter, sym: u16) {
 }
 
 /// Encode a distance symbol using fixed Huffman codes (all 5-bit).
-fn encode_fixed_distance(writer: &mut BitWriter, sym: u8) {
+fn encode_fixed_distance<O: Output>(writer: &mut BitWriter<O>, sym: u8) {
     writer.write_bits(reverse_bits(sym as u32, 5), 5);
 }
 
@@ -137,6 +167,9 @@ const MAX_MATCH: usize = 258;
 const MIN_MATCH: usize = 3;
 const WINDOW_SIZE: usize = 32768;
 
+/// Entries of the hash-chain table [`deflate_into`] needs.
+pub const CHAIN_WORDS: usize = WINDOW_SIZE;
+
 fn hash3(data: &[u8], pos: usize) -> usize {
     if pos + 2 >= data.len() {
         return 0;
@@ -190,26 +223,31 @@ fn find_match(data: &[u8], pos: usize, head: &[u32; HASH_SIZE], prev: &[u32]) ->
 
 /// Compress data using DEFLATE with fixed Huffman codes and LZ77.
 pub fn deflate(data: &[u8]) -> Vec<u8> {
-    if data.is_empty() {
-        // Empty stored block
-        let mut writer = BitWriter::new();
-        writer.write_bits(1, 1); // bfinal
-        writer.write_bits(1, 2); // btype = fixed
-        encode_fixed_literal(&mut writer, 256); // end of block
-        return writer.finish();
-    }
+    let mut prev = alloc::vec![u32::MAX; WINDOW_SIZE];
+    compress(data, &mut prev, BitWriter::new(Vec::new()))
+}
 
-    let mut writer = BitWriter::new();
+/// Compress `data` into `out` without allocating.  `prev` is scratch space
+/// of at least [`CHAIN_WORDS`] entries.  Returns the compressed length, or
+/// `None` if it does not fit in `out`.
+pub fn deflate_into(data: &[u8], prev: &mut [u32], out: &mut [u8]) -> Option<usize> {
+    prev[..WINDOW_SIZE].fill(u32::MAX);
+    let result = compress(data, &mut prev[..WINDOW_SIZE], BitWriter::new(SliceOutput { buf: out, len: 0 }));
+    if result.len <= result.buf.len() { Some(result.len) } else { None }
+}
+
+/// LZ77 + fixed Huffman encoder behind [`deflate`] and [`deflate_into`].
+/// `prev` must hold `WINDOW_SIZE` entries set to `u32::MAX`.
+fn compress<O: Output>(data: &[u8], prev: &mut [u32], mut writer: BitWriter<O>) -> O {
     writer.write_bits(1, 1); // bfinal
     writer.write_bits(1, 2); // btype = fixed Huffman
 
     // Initialize hash chains
     let mut head = [u32::MAX; HASH_SIZE];
-    let mut prev = alloc::vec![u32::MAX; WINDOW_SIZE];
     let mut pos = 0;
 
     while pos < data.len() {
-        let (match_len, match_dist) = find_match(data, pos, &head, &prev);
+        let (match_len, match_dist) = find_match(data, pos, &head, prev);
 
         if match_len >= MIN_MATCH {
             // Emit length/distance pair
@@ -3,6 +3,7 @@
 //! Gzip is a thin wrapper around DEFLATE with a 10-byte header and 8-byte trailer.
 //! Reuses the existing `deflate` and `inflate` modules for the actual compression.
 
+use alloc::string::String;
 use alloc::vec::Vec;
 use crate::crc32;
 use crate::deflate;
@@ -130,6 +131,22 @@ pub fn gzip_decompress(data: &[u8]) -> Option<Vec<u8>> {
     Some(decompressed)
 }
 
+/// Original file name stored in the gzip header (FNAME), if any.
+pub fn gzip_original_name(data: &[u8]) -> Option<String> {
+    if !is_gzip(data) || data.len() < 10 || data[3] & FNAME == 0 {
+        return None;
+    }
+    let mut pos = 10usize;
+    if data[3] & FEXTRA != 0 {
+        if pos + 2 > data.len() { return None; }
+        pos += 2 + u16::from_le_bytes([data[pos], data[pos + 1]]) as usize;
+    }
+    let start = pos.min(data.len());
+    let len = data[start..].iter().position(|&b| b == 0)?;
+    let name = core::str::from_utf8(&data[start..start + len]).ok()?;
+    if name.is_empty() { None } else { Some(String::from(name)) }
+}
+
 /// Check if data starts with gzip magic bytes.
 pub fn is_gzip(data: &[u8]) -> bool {
     data.len() >= 2 && data[0] == GZIP_MAGIC[0] && data[1] == GZIP_MAGIC[1]
@@ -12,11 +12,13 @@ struct BitReader<'a> {
     pos: usize,
     bit_buf: u32,
     bit_count: u8,
+    /// Set once a read had to be padded past the end of `data`.
+    overrun: bool,
 }
 
 impl<'a> BitReader<'a> {
     fn new(data: &'a [u8]) -> Self {
-        BitReader { data, pos: 0, bit_buf: 0, bit_count: 0 }
+        BitReader { data, pos: 0, bit_buf: 0, bit_count: 0, overrun: false }
     }
 
     fn ensure_bits(&mut self, count: u8) {
@@ -26,6 +28,7 @@ impl<'a> BitReader<'a> {
                 self.pos += 1;
                 b
             } else {
+                self.overrun = true;
                 0
             };
             self.bit_buf |= (byte as u32) << self.bit_count;
@@ -50,6 +53,7 @@ impl<'a> BitReader<'a> {
             self.pos += 1;
             b
         } else {
+            self.overrun = true;
             0
         }
     }
@@ -180,13 +184,118 @@ const CL_ORDER: [usize; 19] = [
     16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
 ];
 
+// ─── Output ─────────────────────────────────────────────────────────────────
+
+/// Destination for decoded bytes; owns the back-reference window.
+trait Output {
+    fn push(&mut self, b: u8);
+    /// Copy `length` bytes starting `distance` bytes back. None if out of range.
+    fn copy_back(&mut self, distance: usize, length: usize) -> Option<()>;
+}
+
+impl Output for Vec<u8> {
+    fn push(&mut self, b: u8) {
+        Vec::push(self, b);
+    }
+
+    fn copy_back(&mut self, distance: usize, length: usize) -> Option<()> {
+        if distance > self.len() {
+            return None;
+        }
+        let start = self.len() - distance;
+        for i in 0..length {
+            let b = self[start + (i % distance)];
+            Vec::push(self, b);
+        }
+        Some(())
+    }
+}
+
+/// DEFLATE's maximum back-reference distance.
+const WINDOW_SIZE: usize = 32768;
+
+/// Output that keeps only the last window and hands older bytes to a sink.
+struct StreamOutput<F: FnMut(&[u8])> {
+    window: Vec<u8>,
+    total: u64,
+    sink: F,
+}
+
+impl<F: FnMut(&[u8])> StreamOutput<F> {
+    fn flush_old(&mut self) {
+        if self.window.len() >= 2 * WINDOW_SIZE {
+            let cut = self.window.len() - WINDOW_SIZE;
+            (self.sink)(&self.window[..cut]);
+            self.window.drain(..cut);
+        }
+    }
+
+    fn finish(mut self) -> u64 {
+        (self.sink)(&self.window);
+        self.total
+    }
+}
+
+impl<F: FnMut(&[u8])> Output for StreamOutput<F> {
+    fn push(&mut self, b: u8) {
+        self.window.push(b);
+        self.total += 1;
+        self.flush_old();
+    }
+
+    fn copy_back(&mut self, distance: usize, length: usize) -> Option<()> {
+        if distance > self.window.len() {
+            return None;
+        }
+        for _ in 0..length {
+            let b = self.window[self.window.len() - distance];
+            self.push(b);
+        }
+        Some(())
+    }
+}
+
 // ─── Inflate ────────────────────────────────────────────────────────────────
 
 /// Decompress DEFLATE data. Returns decompressed bytes or None on error.
 pub fn inflate(compressed: &[u8]) -> Option<Vec<u8>> {
     let mut reader = BitReader::new(compressed);
     let mut output = Vec::new();
+    inflate_into(&mut reader, &mut output)?;
+    Some(output)
+}
+
+/// Why a streaming inflate stopped early.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StreamError {
+    /// The input ended before the final block was complete.
+    Truncated,
+    /// The bit stream is not valid DEFLATE.
+    Corrupt,
+}
 
+/// Decompress DEFLATE data without materialising the output.
+///
+/// Decoded bytes are passed to `sink` in chunks; only the 32 KiB window
+/// needed for back-references is retained. Returns the decompressed length.
+pub fn inflate_stream<F: FnMut(&[u8])>(compressed: &[u8], sink: F) -> Result<u64, StreamError> {
+    let mut reader = BitReader::new(compressed);
+    let mut output = StreamOutput {
+        window: Vec::with_capacity(2 * WINDOW_SIZE),
+        total: 0,
+        sink,
+    };
+    let ok = inflate_into(&mut reader, &mut output).is_some();
+    if reader.overrun {
+        return Err(StreamError::Truncated);
+    }
+    if !ok {
+        return Err(StreamError::Corrupt);
+    }
+    Ok(output.finish())
+}
+
+fn inflate_into<O: Output>(reader: &mut BitReader, output: &mut O) -> Option<()> {
     loop {
         let bfinal = reader.read_bits(1);
         let btype = reader.read_bits(2);
@@ -203,13 +312,16 @@ pub fn inflate(compressed: &[u8]) -> Option<Vec<u8>> {
                 // nlen is one's complement of len — skip validation
                 for _ in 0..len {
                     output.push(reader.read_byte_aligned());
+                    if reader.overrun {
+                        return None;
+                    }
                 }
             }
             1 => {
                 // Fixed Huffman
                 let lit_table = build_fixed_literal_table();
                 let dist_table = build_fixed_distance_table();
-                decode_block(&mut reader, &lit_table, &dist_table, &mut output)?;
+                decode_block(reader, &lit_table, &dist_table, output)?;
             }
             2 => {
                 // Dynamic Huffman
@@ -230,7 +342,7 @@ pub fn inflate(compressed: &[u8]) -> Option<Vec<u8>> {
                 let mut lengths = vec![0u8; total];
                 let mut i = 0;
                 while i < total {
-                    let sym = cl_table.decode(&mut reader);
+                    let sym = cl_table.decode(reader);
                     match sym {
                         0..=15 => {
                             lengths[i] = sym as u8;
@@ -264,7 +376,7 @@ pub fn inflate(compressed: &[u8]) -> Option<Vec<u8>> {
 
                 let lit_table = HuffmanTable::build(&lengths[..hlit], hlit);
                 let dist_table = HuffmanTable::build(&lengths[hlit..], hdist);
-                decode_block(&mut reader, &lit_table, &dist_table, &mut output)?;
+                decode_block(reader, &lit_table, &dist_table, output)?;
             }
             _ => return None, // Reserved/invalid
         }
@@ -274,14 +386,14 @@ pub fn inflate(compressed: &[u8]) -> Option<Vec<u8>> {
         }
     }
 
-    Some(output)
+    Some(())
 }
 
-fn decode_block(
+fn decode_block<O: Output>(
     reader: &mut BitReader,
     lit_table: &HuffmanTable,
     dist_table: &HuffmanTable,
-    output: &mut Vec<u8>,
+    output: &mut O,
 ) -> Option<()> {
     loop {
         let sym = lit_table.decode(reader) as usize;
@@ -311,14 +423,7 @@ fn decode_block(
                 + reader.read_bits(DIST_EXTRA[dist_sym]) as usize;
 
             // Copy from sliding window
-            if distance > output.len() {
-                return None;
-            }
-            let start = output.len() - distance;
-            for i in 0..length {
-                let b = output[start + (i % distance)];
-                output.push(b);
-            }
+            output.copy_back(distance, length)?;
         }
     }
 }
@@ -7,7 +7,15 @@
 //! - Supports Stored (no compression) and DEFLATE methods
 //! - Full inflate (decompression) with fixed and dynamic Huffman
 //! - DEFLATE compression with LZ77 and fixed Huffman encoding
-//! - CRC-32 verification on extraction
+//! - CRC-32 verification on extraction, plus whole-archive integrity
+//!   checks (`libzip_verify`) that stream-decode without buffering entries
+//! - Format-independent reading (`libarchive_*`) with magic-byte detection
+//!   of ZIP, 7z, tar, tar.gz and gzip
+//! - 7z read support: LZMA / LZMA2 / Deflate coders plus the x86 BCJ filter
+//! - Optional multi-threaded ZIP writing (`libzip_set_threads`): entries
+//!   are deflated on worker threads and written in the order they were added
+//! - In-memory ZIP archives: `libzip_open_mem` reads from a buffer and
+//!   `libzip_writer_get_data` returns a writer's archive without a file
 //!
 //! # Export Convention
 //! All public functions are `extern "C"` with `#[no_mangle]` for use via `dl_sym()`.
@@ -21,13 +29,18 @@ pub mod syscall;
 pub mod crc32;
 pub mod inflate;
 pub mod deflate;
+pub mod parallel;
 pub mod zip;
 pub mod gzip;
 pub mod tar;
+pub mod lzma;
+pub mod sevenz;
+pub mod archive;
 
 use alloc::vec::Vec;
 use zip::{ZipReader, ZipWriter};
 use tar::{TarReader, TarWriter};
+use archive::ArchiveCursor;
 
 // ── Allocator ───────────────────────────────────────────────────────────────
 
@@ -50,6 +63,7 @@ enum ZipHandle {
     Writer(ZipWriter),
     TarReader(TarReader),
     TarWriter(TarWriter),
+    Archive(ArchiveCursor),
 }
 
 static mut HANDLES: [Option<ZipHandle>; MAX_HANDLES] = [
@@ -112,6 +126,17 @@ fn get_tar_writer(handle: u32) -> Option<&'static mut TarWriter> {
     }
 }
 
+fn get_archive(handle: u32) -> Option<&'static mut ArchiveCursor> {
+    let idx = handle as usize;
+    if idx == 0 || idx > MAX_HANDLES { return None; }
+    unsafe {
+        match &mut HANDLES[idx - 1] {
+            Some(ZipHandle::Archive(a)) => Some(a),
+            _ => None,
+        }
+    }
+}
+
 fn free_handle(handle: u32) {
     let idx = handle as usize;
     if idx > 0 && idx <= MAX_HANDLES {
@@ -153,6 +178,19 @@ pub extern "C" fn libzip_open(path_ptr: *const u8, path_len: u32) -> u32 {
     }
 }
 
+/// Open a ZIP archive held in memory. The buffer is copied, so the caller
+/// may free it once this returns.
+/// Returns handle (>0) on success, 0 on error.
+#[no_mangle]
+pub extern "C" fn libzip_open_mem(data_ptr: *const u8, data_len: u32) -> u32 {
+    if data_ptr.is_null() { return 0; }
+    let data = unsafe { core::slice::from_raw_parts(data_ptr, data_len as usize) }.to_vec();
+    match ZipReader::parse(data) {
+        Some(reader) => alloc_handle(ZipHandle::Reader(reader)),
+        None => 0,
+    }
+}
+
 /// Create a new ZIP archive for writing.
 /// Returns handle (>0) on success, 0 on error.
 #[no_mangle]
@@ -288,8 +326,59 @@ pub extern "C" fn libzip_extract_to_file(
     if written == data.len() { 0 } else { u32::MAX }
 }
 
+/// Verify every entry of a ZIP archive (reader only).
+///
+/// Writes a report to `buf`: a 16-byte header of four little-endian u32s
+/// `[entries_checked, damaged_count, first_damaged_index, flags]`, followed by
+/// one 16-byte record `[index, status, expected_crc, actual_crc]` per entry
+/// for as many entries as fit. `status` is one of the `zip::VERIFY_*` codes;
+/// `first_damaged_index` is u32::MAX when no entry is damaged. Flag bit 0 is
+/// set when the central directory holds fewer entries than the archive
+/// declares (the tail of the directory is missing).
+///
+/// Returns the number of damaged entries, or u32::MAX on error.
+#[no_mangle]
+pub extern "C" fn libzip_verify(handle: u32, buf: *mut u8, buf_len: u32) -> u32 {
+    let reader = match get_reader(handle) {
+        Some(r) => r,
+        None => return u32::MAX,
+    };
+
+    let mut report = Vec::with_capacity(16 + reader.entries.len() * 16);
+    report.resize(16, 0);
+    let mut damaged = 0u32;
+    let mut first_damaged = u32::MAX;
+    for i in 0..reader.entries.len() {
+        let check = reader.verify_entry(i);
+        if check.status != zip::VERIFY_OK && check.status != zip::VERIFY_UNSUPPORTED {
+            damaged += 1;
+            if first_damaged == u32::MAX {
+                first_damaged = i as u32;
+            }
+        }
+        report.extend_from_slice(&(i as u32).to_le_bytes());
+        report.extend_from_slice(&check.status.to_le_bytes());
+        report.extend_from_slice(&check.expected_crc.to_le_bytes());
+        report.extend_from_slice(&check.actual_crc.to_le_bytes());
+    }
+
+    let flags: u32 = if reader.central_dir_complete() { 0 } else { 1 };
+    report[0..4].copy_from_slice(&(reader.entries.len() as u32).to_le_bytes());
+    report[4..8].copy_from_slice(&damaged.to_le_bytes());
+    report[8..12].copy_from_slice(&first_damaged.to_le_bytes());
+    report[12..16].copy_from_slice(&flags.to_le_bytes());
+
+    // Only whole records are copied
+    let fit = (buf_len as usize).min(report.len());
+    let copy_len = if fit < 16 { 0 } else { fit - (fit - 16) % 16 };
+    unsafe {
+        core::ptr::copy_nonoverlapping(report.as_ptr(), buf, copy_len);
+    }
+    damaged
+}
+
 /// Add a file to a ZIP writer. `compress`: 0=stored, 1=deflate.
-/// Returns 0 on success, u32::MAX on error.
+/// Returns 0 on success, u32::MAX on error (including a sealed writer).
 #[no_mangle]
 pub extern "C" fn libzip_add_file(
     handle: u32,
@@ -309,8 +398,19 @@ pub extern "C" fn libzip_add_file(
         core::slice::from_raw_parts(data_ptr, data_len as usize)
     };
 
-    writer.add(name, data, compress != 0);
-    0
+    if writer.add(name, data, compress != 0) { 0 } else { u32::MAX }
+}
+
+/// Compress a ZIP writer's entries on up to `threads` threads (1..=8;
+/// 0 or 1 = on the calling thread, as entries are added).
+/// Returns 0 on success, u32::MAX on error (including a sealed writer).
+#[no_mangle]
+pub extern "C" fn libzip_set_threads(handle: u32, threads: u32) -> u32 {
+    let writer = match get_writer(handle) {
+        Some(w) => w,
+        None => return u32::MAX,
+    };
+    if writer.set_threads(threads as usize) { 0 } else { u32::MAX }
 }
 
 /// Add a directory entry to a ZIP writer.
@@ -329,8 +429,7 @@ pub extern "C" fn libzip_add_dir(
         core::str::from_utf8_unchecked(core::slice::from_raw_parts(name_ptr, name_len as usize))
     };
 
-    writer.add_directory(name);
-    0
+    if writer.add_directory(name) { 0 } else { u32::MAX }
 }
 
 /// Finalize the ZIP writer and write to a file.
@@ -372,6 +471,116 @@ pub extern "C" fn libzip_write_to_file(handle: u32, path_ptr: *const u8, path_le
     if written == data.len() { 0 } else { u32::MAX }
 }
 
+/// Finalize a ZIP writer in memory and copy the archive bytes to `buf`.
+///
+/// The first call seals the writer (further `libzip_add_*` calls fail); the
+/// handle stays valid and must still be closed. Call with `buf_len` = 0 to
+/// query the size, then again with a buffer of that size.
+/// Returns the total archive size, or u32::MAX on error.
+#[no_mangle]
+pub extern "C" fn libzip_writer_get_data(handle: u32, buf: *mut u8, buf_len: u32) -> u32 {
+    let writer = match get_writer(handle) {
+        Some(w) => w,
+        None => return u32::MAX,
+    };
+    let data = writer.seal();
+    let copy_len = data.len().min(buf_len as usize);
+    if copy_len > 0 && !buf.is_null() {
+        unsafe {
+            core::ptr::copy_nonoverlapping(data.as_ptr(), buf, copy_len);
+        }
+    }
+    data.len() as u32
+}
+
+/// Copy `src` to `buf` (up to `buf_len` bytes) and return its full length.
+fn copy_field(src: &[u8], buf: *mut u8, buf_len: u32) -> u32 {
+    let copy_len = src.len().min(buf_len as usize);
+    if copy_len > 0 && !buf.is_null() {
+        unsafe {
+            core::ptr::copy_nonoverlapping(src.as_ptr(), buf, copy_len);
+        }
+    }
+    src.len() as u32
+}
+
+/// Copy the archive comment to `buf` (reader only). Call with `buf_len` = 0
+/// to query the size.
+/// Returns the comment length, or u32::MAX on error.
+#[no_mangle]
+pub extern "C" fn libzip_comment(handle: u32, buf: *mut u8, buf_len: u32) -> u32 {
+    match get_reader(handle) {
+        Some(r) => copy_field(&r.comment, buf, buf_len),
+        None => u32::MAX,
+    }
+}
+
+/// Copy an entry's raw central-directory extra field to `buf` (reader only).
+/// Call with `buf_len` = 0 to query the size.
+/// Returns the extra field length, or u32::MAX on error.
+#[no_mangle]
+pub extern "C" fn libzip_entry_extra(handle: u32, index: u32, buf: *mut u8, buf_len: u32) -> u32 {
+    match get_reader(handle).and_then(|r| r.entries.get(index as usize)) {
+        Some(e) => copy_field(&e.extra, buf, buf_len),
+        None => u32::MAX,
+    }
+}
+
+/// Copy the data of the extra-field record with `header_id` from an entry
+/// (reader only). Call with `buf_len` = 0 to query the size.
+/// Returns the record's data length, or u32::MAX if the entry has no such
+/// record or on error.
+#[no_mangle]
+pub extern "C" fn libzip_entry_find_extra(
+    handle: u32, index: u32, header_id: u32, buf: *mut u8, buf_len: u32,
+) -> u32 {
+    let entry = match get_reader(handle).and_then(|r| r.entries.get(index as usize)) {
+        Some(e) => e,
+        None => return u32::MAX,
+    };
+    match zip::find_extra(&entry.extra, header_id as u16) {
+        Some(data) => copy_field(data, buf, buf_len),
+        None => u32::MAX,
+    }
+}
+
+/// Set the archive comment of a ZIP writer (at most 65535 bytes).
+/// Returns 0 on success, u32::MAX on error (including a sealed writer).
+#[no_mangle]
+pub extern "C" fn libzip_set_comment(handle: u32, ptr: *const u8, len: u32) -> u32 {
+    let writer = match get_writer(handle) {
+        Some(w) => w,
+        None => return u32::MAX,
+    };
+    let comment = if len == 0 {
+        &[][..]
+    } else {
+        unsafe { core::slice::from_raw_parts(ptr, len as usize) }
+    };
+    if writer.set_comment(comment) { 0 } else { u32::MAX }
+}
+
+/// Stage an extra-field record for the next entry added to a ZIP writer.
+/// The record is written to both the local header and the central
+/// directory; an entry's extra field holds at most 65535 bytes.
+/// Returns 0 on success, u32::MAX on error (including a sealed writer).
+#[no_mangle]
+pub extern "C" fn libzip_add_extra(
+    handle: u32, header_id: u32, data_ptr: *const u8, data_len: u32,
+) -> u32 {
+    let writer = match get_writer(handle) {
+        Some(w) => w,
+        None => return u32::MAX,
+    };
+    if header_id > 0xFFFF { return u32::MAX; }
+    let data = if data_len == 0 {
+        &[][..]
+    } else {
+        unsafe { core::slice::from_raw_parts(data_ptr, data_len as usize) }
+    };
+    if writer.add_extra(header_id as u16, data) { 0 } else { u32::MAX }
+}
+
 // ── Helper: file I/O ────────────────────────────────────────────────────────
 
 fn read_file_to_vec(path: &str) -> Option<Vec<u8>> {
@@ -640,3 +849,129 @@ pub extern "C" fn libzip_tar_write_to_file(
 
     if write_vec_to_file(path, &output) { 0 } else { u32::MAX }
 }
+
+// ── Archive C ABI Exports (format-independent) ─────────────────────────────
+
+/// Open an archive of any supported format (detected from its magic bytes).
+/// Returns handle (>0) on success, 0 if the file is unreadable or not a
+/// supported archive.
+#[no_mangle]
+pub extern "C" fn libarchive_open(path_ptr: *const u8, path_len: u32) -> u32 {
+    let path = unsafe {
+        core::str::from_utf8_unchecked(core::slice::from_raw_parts(path_ptr, path_len as usize))
+    };
+
+    let data = match read_file_to_vec(path) {
+        Some(d) => d,
+        None => return 0,
+    };
+
+    match archive::open(data, &archive::gzip_default_name(path)) {
+        Some((format, reader)) => alloc_handle(ZipHandle::Archive(ArchiveCursor::new(format, reader))),
+        None => 0,
+    }
+}
+
+/// Get the detected format (1=zip, 2=tar, 3=tar.gz, 4=gzip, 5=7z), 0 on error.
+#[no_mangle]
+pub extern "C" fn libarchive_format(handle: u32) -> u32 {
+    match get_archive(handle) {
+        Some(a) => a.format as u32,
+        None => 0,
+    }
+}
+
+/// Get the number of entries in an archive.
+#[no_mangle]
+pub extern "C" fn libarchive_entry_count(handle: u32) -> u32 {
+    match get_archive(handle) {
+        Some(a) => a.reader.entry_count() as u32,
+        None => 0,
+    }
+}
+
+/// Advance to the next entry. Writes its name to `buf` and its size and
+/// directory flag to `size_out` / `is_dir_out` (either may be null).
+/// Returns the name length, or u32::MAX when there are no more entries.
+#[no_mangle]
+pub extern "C" fn libarchive_next_entry(
+    handle: u32, buf: *mut u8, buf_len: u32, size_out: *mut u64, is_dir_out: *mut u32,
+) -> u32 {
+    let a = match get_archive(handle) {
+        Some(a) => a,
+        None => return u32::MAX,
+    };
+    let index = match a.advance() {
+        Some(i) => i,
+        None => return u32::MAX,
+    };
+
+    let name = a.reader.entry_name(index).unwrap_or("").as_bytes();
+    let copy_len = name.len().min(buf_len as usize);
+    unsafe {
+        core::ptr::copy_nonoverlapping(name.as_ptr(), buf, copy_len);
+        if !size_out.is_null() {
+            *size_out = a.reader.entry_size(index);
+        }
+        if !is_dir_out.is_null() {
+            *is_dir_out = a.reader.entry_is_dir(index) as u32;
+        }
+    }
+    copy_len as u32
+}
+
+/// Extract the current entry (see `libarchive_next_entry`) to `path`.
+/// Directories are created; missing parent directories of files are
+/// created as well. Returns 0 on success, u32::MAX on error.
+#[no_mangle]
+pub extern "C" fn libarchive_extract_entry(handle: u32, path_ptr: *const u8, path_len: u32) -> u32 {
+    let a = match get_archive(handle) {
+        Some(a) => a,
+        None => return u32::MAX,
+    };
+    let index = match a.entry() {
+        Some(i) => i,
+        None => return u32::MAX,
+    };
+    let path = unsafe {
+        core::str::from_utf8_unchecked(core::slice::from_raw_parts(path_ptr, path_len as usize))
+    };
+    let path = path.trim_end_matches('/');
+
+    if a.reader.entry_is_dir(index) {
+        return if make_dirs(path) { 0 } else { u32::MAX };
+    }
+
+    let data = match a.reader.extract(index) {
+        Some(d) => d,
+        None => return u32::MAX,
+    };
+    if let Some(slash) = path.rfind('/') {
+        if slash > 0 && !make_dirs(&path[..slash]) {
+            return u32::MAX;
+        }
+    }
+    if write_vec_to_file(path, &data) { 0 } else { u32::MAX }
+}
+
+/// Close an archive handle.
+#[no_mangle]
+pub extern "C" fn libarchive_close(handle: u32) {
+    if get_archive(handle).is_some() {
+        free_handle(handle);
+    }
+}
+
+/// Create `path` and any missing ancestors. Returns true if it exists afterwards.
+fn make_dirs(path: &str) -> bool {
+    let mut stat_buf = [0u32; 7];
+    if path.is_empty() || syscall::stat(path, &mut stat_buf) != u32::MAX {
+        return true;
+    }
+    if let Some(slash) = path.rfind('/') {
+        if slash > 0 && !make_dirs(&path[..slash]) {
+            return false;
+        }
+    }
+    syscall::mkdir(path) != u32::MAX || syscall::stat(path, &mut stat_buf) != u32::MAX
+}
@@ -0,0 +1,466 @@
+//! LZMA and LZMA2 decompression.
+//!
+//! Follows the reference decoder in the LZMA SDK (`LzmaSpec.cpp`). Output is
+//! accumulated in a `Vec<u8>` that doubles as the dictionary, so match
+//! distances are resolved directly against already-decoded bytes.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+// ─── Constants ──────────────────────────────────────────────────────────────
+
+const NUM_BIT_MODEL_TOTAL_BITS: u32 = 11;
+const BIT_MODEL_TOTAL: u32 = 1 << NUM_BIT_MODEL_TOTAL_BITS;
+const NUM_MOVE_BITS: u32 = 5;
+const PROB_INIT: u16 = (BIT_MODEL_TOTAL / 2) as u16;
+
+const NUM_STATES: usize = 12;
+const NUM_POS_BITS_MAX: usize = 4;
+const NUM_LEN_TO_POS_STATES: usize = 4;
+const NUM_ALIGN_BITS: u32 = 4;
+const START_POS_MODEL_INDEX: u32 = 4;
+const END_POS_MODEL_INDEX: u32 = 14;
+const NUM_FULL_DISTANCES: usize = 1 << (END_POS_MODEL_INDEX >> 1);
+const MATCH_MIN_LEN: usize = 2;
+
+/// Upper bound for speculative output preallocation.
+const MAX_PREALLOC: usize = 64 * 1024 * 1024;
+
+// ─── Range Decoder ──────────────────────────────────────────────────────────
+
+struct RangeDecoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+    range: u32,
+    code: u32,
+    /// Set once a read ran past the end of `data`.
+    overrun: bool,
+}
+
+impl<'a> RangeDecoder<'a> {
+    /// Start decoding at `data[0]`. Returns None if the header is invalid.
+    fn new(data: &'a [u8]) -> Option<Self> {
+        let mut rc = RangeDecoder { data, pos: 0, range: 0xFFFF_FFFF, code: 0, overrun: false };
+        let first = rc.next_byte();
+        for _ in 0..4 {
+            rc.code = (rc.code << 8) | rc.next_byte() as u32;
+        }
+        if first != 0 || rc.code == rc.range || rc.overrun {
+            return None;
+        }
+        Some(rc)
+    }
+
+    fn next_byte(&mut self) -> u8 {
+        if self.pos < self.data.len() {
+            let b = self.data[self.pos];
+            self.pos += 1;
+            b
+        } else {
+            self.overrun = true;
+            0
+        }
+    }
+
+    fn normalize(&mut self) {
+        if self.range < (1 << 24) {
+            self.range <<= 8;
+            self.code = (self.code << 8) | self.next_byte() as u32;
+        }
+    }
+
+    fn is_finished_ok(&self) -> bool {
+        self.code == 0
+    }
+
+    fn decode_bit(&mut self, prob: &mut u16) -> u32 {
+        let v = *prob as u32;
+        let bound = (self.range >> NUM_BIT_MODEL_TOTAL_BITS) * v;
+        let bit = if self.code < bound {
+            *prob = (v + ((BIT_MODEL_TOTAL - v) >> NUM_MOVE_BITS)) as u16;
+            self.range = bound;
+            0
+        } else {
+            *prob = (v - (v >> NUM_MOVE_BITS)) as u16;
+            self.code -= bound;
+            self.range -= bound;
+            1
+        };
+        self.normalize();
+        bit
+    }
+
+    fn decode_direct_bits(&mut self, num_bits: u32) -> u32 {
+        let mut res = 0u32;
+        for _ in 0..num_bits {
+            self.range >>= 1;
+            self.code = self.code.wrapping_sub(self.range);
+            let t = 0u32.wrapping_sub(self.code >> 31);
+            self.code = self.code.wrapping_add(self.range & t);
+            self.normalize();
+            res = (res << 1).wrapping_add(t.wrapping_add(1));
+        }
+        res
+    }
+}
+
+// ─── Bit Trees ──────────────────────────────────────────────────────────────
+
+fn bit_tree_decode(probs: &mut [u16], num_bits: u32, rc: &mut RangeDecoder) -> u32 {
+    let mut m = 1usize;
+    for _ in 0..num_bits {
+        m = (m << 1) + rc.decode_bit(&mut probs[m]) as usize;
+    }
+    (m - (1 << num_bits)) as u32
+}
+
+fn bit_tree_reverse_decode(probs: &mut [u16], num_bits: u32, rc: &mut RangeDecoder) -> u32 {
+    let mut m = 1usize;
+    let mut symbol = 0u32;
+    for i in 0..num_bits {
+        let bit = rc.decode_bit(&mut probs[m]);
+        m = (m << 1) + bit as usize;
+        symbol |= bit << i;
+    }
+    symbol
+}
+
+// ─── Length Decoder ─────────────────────────────────────────────────────────
+
+struct LenDecoder {
+    choice: u16,
+    choice2: u16,
+    low: [[u16; 1 << 3]; 1 << NUM_POS_BITS_MAX],
+    mid: [[u16; 1 << 3]; 1 << NUM_POS_BITS_MAX],
+    high: [u16; 1 << 8],
+}
+
+impl LenDecoder {
+    fn new() -> Self {
+        LenDecoder {
+            choice: PROB_INIT,
+            choice2: PROB_INIT,
+            low: [[PROB_INIT; 1 << 3]; 1 << NUM_POS_BITS_MAX],
+            mid: [[PROB_INIT; 1 << 3]; 1 << NUM_POS_BITS_MAX],
+            high: [PROB_INIT; 1 << 8],
+        }
+    }
+
+    fn decode(&mut self, rc: &mut RangeDecoder, pos_state: usize) -> usize {
+        if rc.decode_bit(&mut self.choice) == 0 {
+            return bit_tree_decode(&mut self.low[pos_state], 3, rc) as usize;
+        }
+        if rc.decode_bit(&mut self.choice2) == 0 {
+            return 8 + bit_tree_decode(&mut self.mid[pos_state], 3, rc) as usize;
+        }
+        16 + bit_tree_decode(&mut self.high, 8, rc) as usize
+    }
+}
+
+// ─── LZMA Properties ────────────────────────────────────────────────────────
+
+/// Literal context / literal position / position bits.
+#[derive(Clone, Copy)]
+pub struct Props {
+    pub lc: u32,
+    pub lp: u32,
+    pub pb: u32,
+}
+
+impl Props {
+    /// Decode the packed `(pb * 5 + lp) * 9 + lc` properties byte.
+    pub fn from_byte(d: u8) -> Option<Props> {
+        if d >= 9 * 5 * 5 {
+            return None;
+        }
+        let d = d as u32;
+        Some(Props { lc: d % 9, lp: (d / 9) % 5, pb: d / 45 })
+    }
+}
+
+// ─── LZMA Decoder ───────────────────────────────────────────────────────────
+
+/// Outcome of decoding one LZMA stream or LZMA2 chunk.
+#[derive(PartialEq, Eq)]
+enum Finish {
+    /// The requested number of bytes was produced.
+    Size,
+    /// An end-of-stream marker was found.
+    Marker,
+}
+
+/// Decoder state: probability models plus the match history.
+///
+/// Kept across LZMA2 chunks that do not request a state reset.
+struct LzmaDecoder {
+    props: Props,
+    literal_probs: Vec<u16>,
+    pos_slot: [[u16; 1 << 6]; NUM_LEN_TO_POS_STATES],
+    pos_decoders: [u16; 1 + NUM_FULL_DISTANCES - END_POS_MODEL_INDEX as usize],
+    align: [u16; 1 << NUM_ALIGN_BITS],
+    len_decoder: LenDecoder,
+    rep_len_decoder: LenDecoder,
+    is_match: [u16; NUM_STATES << NUM_POS_BITS_MAX],
+    is_rep: [u16; NUM_STATES],
+    is_rep_g0: [u16; NUM_STATES],
+    is_rep_g1: [u16; NUM_STATES],
+    is_rep_g2: [u16; NUM_STATES],
+    is_rep0_long: [u16; NUM_STATES << NUM_POS_BITS_MAX],
+    state: usize,
+    reps: [u32; 4],
+}
+
+impl LzmaDecoder {
+    fn new(props: Props) -> Self {
+        LzmaDecoder {
+            props,
+            literal_probs: vec![PROB_INIT; 0x300 << (props.lc + props.lp)],
+            pos_slot: [[PROB_INIT; 1 << 6]; NUM_LEN_TO_POS_STATES],
+            pos_decoders: [PROB_INIT; 1 + NUM_FULL_DISTANCES - END_POS_MODEL_INDEX as usize],
+            align: [PROB_INIT; 1 << NUM_ALIGN_BITS],
+            len_decoder: LenDecoder::new(),
+            rep_len_decoder: LenDecoder::new(),
+            is_match: [PROB_INIT; NUM_STATES << NUM_POS_BITS_MAX],
+            is_rep: [PROB_INIT; NUM_STATES],
+            is_rep_g0: [PROB_INIT; NUM_STATES],
+            is_rep_g1: [PROB_INIT; NUM_STATES],
+            is_rep_g2: [PROB_INIT; NUM_STATES],
+            is_rep0_long: [PROB_INIT; NUM_STATES << NUM_POS_BITS_MAX],
+            state: 0,
+            reps: [0; 4],
+        }
+    }
+
+    fn decode_literal(&mut self, rc: &mut RangeDecoder, out: &mut Vec<u8>, dict_start: usize) {
+        let total_pos = out.len();
+        let prev_byte = if total_pos > dict_start { out[total_pos - 1] as u32 } else { 0 };
+        let lc = self.props.lc;
+        let lp = self.props.lp;
+        let lit_state = ((((total_pos - dict_start) as u32) & ((1 << lp) - 1)) << lc) + (prev_byte >> (8 - lc));
+        let base = 0x300 * lit_state as usize;
+        let probs = &mut self.literal_probs[base..base + 0x300];
+
+        let mut symbol = 1usize;
+        if self.state >= 7 {
+            let mut match_byte = out[total_pos - self.reps[0] as usize - 1] as u32;
+            while symbol < 0x100 {
+                let match_bit = ((match_byte >> 7) & 1) as usize;
+                match_byte <<= 1;
+                let bit = rc.decode_bit(&mut probs[((1 + match_bit) << 8) + symbol]) as usize;
+                symbol = (symbol << 1) | bit;
+                if match_bit != bit {
+                    break;
+                }
+            }
+        }
+        while symbol < 0x100 {
+            symbol = (symbol << 1) | rc.decode_bit(&mut probs[symbol]) as usize;
+        }
+        out.push((symbol - 0x100) as u8);
+    }
+
+    fn decode_distance(&mut self, rc: &mut RangeDecoder, len: usize) -> u32 {
+        let len_state = len.min(NUM_LEN_TO_POS_STATES - 1);
+        let pos_slot = bit_tree_decode(&mut self.pos_slot[len_state], 6, rc);
+        if pos_slot < START_POS_MODEL_INDEX {
+            return pos_slot;
+        }
+        let num_direct_bits = (pos_slot >> 1) - 1;
+        let mut dist = (2 | (pos_slot & 1)) << num_direct_bits;
+        if pos_slot < END_POS_MODEL_INDEX {
+            let base = (dist - pos_slot) as usize;
+            dist += bit_tree_reverse_decode(&mut self.pos_decoders[base..], num_direct_bits, rc);
+        } else {
+            dist += rc.decode_direct_bits(num_direct_bits - NUM_ALIGN_BITS) << NUM_ALIGN_BITS;
+            dist += bit_tree_reverse_decode(&mut self.align, NUM_ALIGN_BITS, rc);
+        }
+        dist
+    }
+
+    /// Decode until `unpack_size` more bytes are in `out` (or, if `None`,
+    /// until the end marker). `dict_start` is where the current dictionary
+    /// begins in `out`; matches may not reach before it. An end marker before
+    /// the size is reached is only accepted if `allow_marker` is set.
+    fn decode(
+        &mut self,
+        rc: &mut RangeDecoder,
+        out: &mut Vec<u8>,
+        dict_start: usize,
+        unpack_size: Option<usize>,
+        allow_marker: bool,
+    ) -> Option<Finish> {
+        let pb_mask = (1usize << self.props.pb) - 1;
+        let mut remaining = unpack_size.unwrap_or(usize::MAX);
+
+        loop {
+            if rc.overrun {
+                return None;
+            }
+            if unpack_size.is_some() && remaining == 0 {
+                return Some(Finish::Size);
+            }
+
+            let pos_state = (out.len() - dict_start) & pb_mask;
+            let state = self.state;
+
+            if rc.decode_bit(&mut self.is_match[(state << NUM_POS_BITS_MAX) + pos_state]) == 0 {
+                self.decode_literal(rc, out, dict_start);
+                self.state = if state < 4 { 0 } else if state < 10 { state - 3 } else { state - 6 };
+                remaining -= 1;
+                continue;
+            }
+
+            let mut len;
+            if rc.decode_bit(&mut self.is_rep[state]) != 0 {
+                if out.len() == dict_start {
+                    return None;
+                }
+                if rc.decode_bit(&mut self.is_rep_g0[state]) == 0 {
+                    if rc.decode_bit(&mut self.is_rep0_long[(state << NUM_POS_BITS_MAX) + pos_state]) == 0 {
+                        self.state = if state < 7 { 9 } else { 11 };
+                        let b = out[out.len() - self.reps[0] as usize - 1];
+                        out.push(b);
+                        remaining -= 1;
+                        continue;
+                    }
+                } else {
+                    let dist;
+                    if rc.decode_bit(&mut self.is_rep_g1[state]) == 0 {
+                        dist = self.reps[1];
+                    } else {
+                        if rc.decode_bit(&mut self.is_rep_g2[state]) == 0 {
+                            dist = self.reps[2];
+                        } else {
+                            dist = self.reps[3];
+                            self.reps[3] = self.reps[2];
+                        }
+                        self.reps[2] = self.reps[1];
+                    }
+                    self.reps[1] = self.reps[0];
+                    self.reps[0] = dist;
+                }
+                len = self.rep_len_decoder.decode(rc, pos_state);
+                self.state = if state < 7 { 8 } else { 11 };
+            } else {
+                self.reps[3] = self.reps[2];
+                self.reps[2] = self.reps[1];
+                self.reps[1] = self.reps[0];
+                len = self.len_decoder.decode(rc, pos_state);
+                self.state = if state < 7 { 7 } else { 10 };
+                self.reps[0] = self.decode_distance(rc, len);
+                if self.reps[0] == 0xFFFF_FFFF {
+                    return if allow_marker && rc.is_finished_ok() { Some(Finish::Marker) } else { None };
+                }
+                if self.reps[0] as usize >= out.len() - dict_start {
+                    return None;
+                }
+            }
+
+            len += MATCH_MIN_LEN;
+            if len > remaining {
+                return None;
+            }
+            let dist = self.reps[0] as usize + 1;
+            let start = out.len() - dist;
+            for i in 0..len {
+                let b = out[start + i];
+                out.push(b);
+            }
+            remaining -= len;
+        }
+    }
+}
+
+// ─── Public API ─────────────────────────────────────────────────────────────
+
+/// Decompress a raw LZMA stream as stored in 7z: `props` is the 5-byte
+/// coder property block (properties byte + little-endian dictionary size).
+pub fn decompress(props: &[u8], data: &[u8], unpack_size: usize) -> Option<Vec<u8>> {
+    if props.len() < 5 {
+        return None;
+    }
+    let props = Props::from_byte(props[0])?;
+    let mut rc = RangeDecoder::new(data)?;
+    let mut decoder = LzmaDecoder::new(props);
+    let mut out = Vec::with_capacity(unpack_size.min(MAX_PREALLOC));
+    decoder.decode(&mut rc, &mut out, 0, Some(unpack_size), true)?;
+    if out.len() != unpack_size {
+        return None;
+    }
+    Some(out)
+}
+
+/// Decompress an LZMA2 stream (sequence of chunks ending in a 0x00 byte).
+pub fn decompress_lzma2(data: &[u8], unpack_size: usize) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(unpack_size.min(MAX_PREALLOC));
+    let mut decoder: Option<LzmaDecoder> = None;
+    let mut dict_start = 0usize;
+    let mut pos = 0usize;
+
+    loop {
+        let control = *data.get(pos)?;
+        pos += 1;
+
+        if control == 0x00 {
+            break;
+        }
+
+        if control < 0x80 {
+            // Uncompressed chunk; 1 resets the dictionary, 2 keeps it
+            if control > 2 || pos + 2 > data.len() {
+                return None;
+            }
+            let size = ((data[pos] as usize) << 8 | data[pos + 1] as usize) + 1;
+            pos += 2;
+            if control == 1 {
+                dict_start = out.len();
+            }
+            let chunk = data.get(pos..pos + size)?;
+            out.extend_from_slice(chunk);
+            pos += size;
+            continue;
+        }
+
+        // LZMA chunk
+        if pos + 4 > data.len() {
+            return None;
+        }
+        let unpacked = (((control & 0x1F) as usize) << 16
+            | (data[pos] as usize) << 8
+            | data[pos + 1] as usize) + 1;
+        let packed = ((data[pos + 2] as usize) << 8 | data[pos + 3] as usize) + 1;
+        pos += 4;
+
+        let reset = (control >> 5) & 0x03;
+        if reset == 3 {
+            dict_start = out.len();
+        }
+        if reset >= 2 {
+            let props = Props::from_byte(*data.get(pos)?)?;
+            pos += 1;
+            if props.lc + props.lp > 4 {
+                return None;
+            }
+            decoder = Some(LzmaDecoder::new(props));
+        } else if reset == 1 {
+            let props = decoder.as_ref()?.props;
+            decoder = Some(LzmaDecoder::new(props));
+        }
+        let dec = decoder.as_mut()?;
+
+        let chunk = data.get(pos..pos + packed)?;
+        let mut rc = RangeDecoder::new(chunk)?;
+        let before = out.len();
+        if dec.decode(&mut rc, &mut out, dict_start, Some(unpacked), false)? != Finish::Size
+            || out.len() - before != unpacked
+        {
+            return None;
+        }
+        pos += packed;
+    }
+
+    if out.len() != unpack_size {
+        return None;
+    }
+    Some(out)
+}
@@ -0,0 +1,160 @@
+//! Parallel compression of independent ZIP entries.
+//!
+//! [`compress_all`] runs a batch of [`Job`]s on up to `threads` threads: the
+//! calling thread plus `threads - 1` workers. Each job computes the CRC-32
+//! of one entry and, if asked to, compresses it into a complete DEFLATE
+//! stream of its own. The caller then writes the entries in their original
+//! order, so the archive is the same as one written on a single thread.
+//!
+//! The DLL heap is not thread-safe, so nothing allocates from it while the
+//! workers run: the job array is built beforehand, and the output buffers,
+//! hash-chain scratch and worker stacks are mapped with `mmap`.
+
+use core::sync::atomic::{fence, AtomicUsize, Ordering};
+use crate::crc32;
+use crate::deflate;
+use crate::syscall;
+
+/// Most threads a batch runs on (including the caller).
+pub const MAX_THREADS: usize = 8;
+
+/// Worker thread stack size.
+const WORKER_STACK: u32 = 64 * 1024;
+
+/// Bytes of hash-chain scratch each thread maps for `deflate_into`.
+const SCRATCH_BYTES: u32 = (deflate::CHAIN_WORDS * 4) as u32;
+
+/// One entry of a batch.
+pub struct Job {
+    /// Uncompressed entry data; must stay alive and unchanged until the
+    /// job is dropped.
+    input: *const u8,
+    input_len: usize,
+    /// Compress (otherwise only the CRC is computed).
+    deflate: bool,
+    /// Mapped output buffer of `input_len` bytes, or 0.
+    out: u64,
+    pub crc32: u32,
+    /// Length of the DEFLATE stream in `out`; `None` if the entry is
+    /// stored (not compressed, or compression did not make it smaller).
+    pub compressed_len: Option<usize>,
+}
+
+impl Job {
+    pub fn new(input: &[u8], deflate: bool) -> Job {
+        Job {
+            input: input.as_ptr(),
+            input_len: input.len(),
+            deflate: deflate && !input.is_empty(),
+            out: 0,
+            crc32: 0,
+            compressed_len: None,
+        }
+    }
+
+    /// The compressed stream, if the entry is to be stored deflated.
+    pub fn output(&self) -> Option<&[u8]> {
+        let len = self.compressed_len?;
+        Some(unsafe { core::slice::from_raw_parts(self.out as *const u8, len) })
+    }
+
+    fn input(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self.input, self.input_len) }
+    }
+
+    fn run(&mut self, scratch: u64) {
+        self.crc32 = crc32::crc32(self.input());
+        if self.out == 0 || scratch == 0 {
+            return;
+        }
+        let prev = unsafe { core::slice::from_raw_parts_mut(scratch as *mut u32, deflate::CHAIN_WORDS) };
+        let out = unsafe { core::slice::from_raw_parts_mut(self.out as *mut u8, self.input_len) };
+        // A stream that fills the whole buffer is no smaller than the input.
+        self.compressed_len = deflate::deflate_into(self.input(), prev, out).filter(|&n| n < self.input_len);
+    }
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        if self.out != 0 {
+            syscall::munmap(self.out, self.input_len as u32);
+        }
+    }
+}
+
+// ── Shared batch state (one batch at a time) ───────────────────────────────
+
+static JOBS: AtomicUsize = AtomicUsize::new(0);
+static JOB_COUNT: AtomicUsize = AtomicUsize::new(0);
+/// Index of the next unclaimed job.
+static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+/// Run every job of `jobs`, on up to `threads` threads. Returns once all
+/// jobs are done. Jobs whose output buffer cannot be mapped are stored.
+pub fn compress_all(jobs: &mut [Job], threads: usize) {
+    for job in jobs.iter_mut().filter(|j| j.deflate) {
+        let addr = syscall::mmap(job.input_len as u32);
+        if addr != u64::MAX {
+            job.out = addr;
+        }
+    }
+
+    JOBS.store(jobs.as_mut_ptr() as usize, Ordering::Relaxed);
+    JOB_COUNT.store(jobs.len(), Ordering::Relaxed);
+    NEXT.store(0, Ordering::Release);
+
+    let mut workers = [(0u32, 0u64); MAX_THREADS];
+    let mut started = 0;
+    let wanted = threads.min(MAX_THREADS).min(jobs.len()).saturating_sub(1);
+    while started < wanted {
+        let stack = syscall::mmap(WORKER_STACK);
+        if stack == u64::MAX {
+            break;
+        }
+        let tid = syscall::thread_create(worker_main, stack + WORKER_STACK as u64 - 8, "libzip-deflate");
+        if tid == 0 {
+            syscall::munmap(stack, WORKER_STACK);
+            break;
+        }
+        workers[started] = (tid, stack);
+        started += 1;
+    }
+
+    // The caller takes jobs too, and finishes the batch alone if no
+    // worker could be started.
+    run_jobs();
+    for &(tid, stack) in &workers[..started] {
+        syscall::waitpid(tid);
+        syscall::munmap(stack, WORKER_STACK);
+    }
+    fence(Ordering::Acquire);
+    JOBS.store(0, Ordering::Relaxed);
+    JOB_COUNT.store(0, Ordering::Relaxed);
+}
+
+fn worker_main() {
+    run_jobs();
+    // The stack has no return address; leave through the exit syscall.
+    syscall::exit(0);
+}
+
+/// Claim and run jobs until none are left.
+fn run_jobs() {
+    let scratch = match syscall::mmap(SCRATCH_BYTES) {
+        u64::MAX => 0,
+        addr => addr,
+    };
+    let jobs = JOBS.load(Ordering::Relaxed) as *mut Job;
+    let count = JOB_COUNT.load(Ordering::Relaxed);
+    loop {
+        let i = NEXT.fetch_add(1, Ordering::AcqRel);
+        if i >= count {
+            break;
+        }
+        unsafe { (*jobs.add(i)).run(scratch) };
+    }
+    fence(Ordering::Release);
+    if scratch != 0 {
+        syscall::munmap(scratch, SCRATCH_BYTES);
+    }
+}
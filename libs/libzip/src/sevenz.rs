@@ -0,0 +1,783 @@
+//! 7z archive format (read-only).
+//!
+//! Parses the signature header, the (optionally LZMA-compressed) archive
+//! header and the folder/coder graph, and unpacks folders on demand.
+//! Supported coders: Copy, LZMA, LZMA2, Deflate and the x86 BCJ filter.
+//! Encrypted archives and multi-input coders (BCJ2) are rejected.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use crate::crc32;
+use crate::inflate;
+use crate::lzma;
+
+// ─── Constants ──────────────────────────────────────────────────────────────
+
+/// `'7' 'z' BC AF 27 1C`
+pub const SIGNATURE: [u8; 6] = [0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C];
+const SIGNATURE_HEADER_SIZE: usize = 32;
+
+// Property IDs
+const K_END: u64 = 0x00;
+const K_HEADER: u64 = 0x01;
+const K_ARCHIVE_PROPERTIES: u64 = 0x02;
+const K_ADDITIONAL_STREAMS_INFO: u64 = 0x03;
+const K_MAIN_STREAMS_INFO: u64 = 0x04;
+const K_FILES_INFO: u64 = 0x05;
+const K_PACK_INFO: u64 = 0x06;
+const K_UNPACK_INFO: u64 = 0x07;
+const K_SUBSTREAMS_INFO: u64 = 0x08;
+const K_SIZE: u64 = 0x09;
+const K_CRC: u64 = 0x0A;
+const K_FOLDER: u64 = 0x0B;
+const K_CODERS_UNPACK_SIZE: u64 = 0x0C;
+const K_NUM_UNPACK_STREAM: u64 = 0x0D;
+const K_EMPTY_STREAM: u64 = 0x0E;
+const K_EMPTY_FILE: u64 = 0x0F;
+const K_NAME: u64 = 0x11;
+const K_WIN_ATTRIBUTES: u64 = 0x15;
+const K_ENCODED_HEADER: u64 = 0x17;
+
+// Coder method IDs
+const METHOD_COPY: &[u8] = &[0x00];
+const METHOD_LZMA: &[u8] = &[0x03, 0x01, 0x01];
+const METHOD_LZMA2: &[u8] = &[0x21];
+const METHOD_DEFLATE: &[u8] = &[0x04, 0x01, 0x08];
+const METHOD_BCJ_X86: &[u8] = &[0x03, 0x03, 0x01, 0x03];
+
+const ATTR_DIRECTORY: u32 = 0x10;
+
+/// Check if data starts with the 7z signature.
+pub fn is_7z(data: &[u8]) -> bool {
+    data.len() >= SIGNATURE_HEADER_SIZE && data[..6] == SIGNATURE
+}
+
+// ─── Byte Reader ────────────────────────────────────────────────────────────
+
+struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        ByteReader { data, pos: 0 }
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        let b = *self.data.get(self.pos)?;
+        self.pos += 1;
+        Some(b)
+    }
+
+    fn bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(n)?;
+        let s = self.data.get(self.pos..end)?;
+        self.pos = end;
+        Some(s)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let b = self.bytes(4)?;
+        Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// 7z variable-length number: the leading one bits of the first byte
+    /// give the count of extra little-endian bytes that follow.
+    fn number(&mut self) -> Option<u64> {
+        let first = self.byte()?;
+        let mut mask = 0x80u8;
+        let mut value = 0u64;
+        for i in 0..8 {
+            if first & mask == 0 {
+                let high = (first & mask.wrapping_sub(1)) as u64;
+                return Some(value | (high << (8 * i)));
+            }
+            value |= (self.byte()? as u64) << (8 * i);
+            mask >>= 1;
+        }
+        Some(value)
+    }
+
+    /// A number used as a count or index; bounded by the input size so a
+    /// corrupt header cannot trigger huge allocations.
+    fn count(&mut self) -> Option<usize> {
+        let n = self.number()?;
+        if n > self.data.len() as u64 * 8 + 64 {
+            return None;
+        }
+        Some(n as usize)
+    }
+
+    fn bit_vector(&mut self, n: usize) -> Option<Vec<bool>> {
+        let mut v = Vec::with_capacity(n);
+        let mut cur = 0u8;
+        for i in 0..n {
+            if i % 8 == 0 {
+                cur = self.byte()?;
+            }
+            v.push(cur & (0x80 >> (i % 8)) != 0);
+        }
+        Some(v)
+    }
+
+    /// "AllAreDefined" byte followed by an optional bit vector.
+    fn defined_vector(&mut self, n: usize) -> Option<Vec<bool>> {
+        if self.byte()? != 0 {
+            Some(alloc::vec![true; n])
+        } else {
+            self.bit_vector(n)
+        }
+    }
+
+    fn digests(&mut self, n: usize) -> Option<Vec<Option<u32>>> {
+        let defined = self.defined_vector(n)?;
+        let mut out = Vec::with_capacity(n);
+        for d in defined {
+            out.push(if d { Some(self.u32()?) } else { None });
+        }
+        Some(out)
+    }
+}
+
+// ─── Folder / Coder Graph ───────────────────────────────────────────────────
+
+struct Coder {
+    method: Vec<u8>,
+    props: Vec<u8>,
+}
+
+/// A folder: a chain of coders that turns one or more packed streams into
+/// one unpacked stream (which may hold several files).
+struct Folder {
+    coders: Vec<Coder>,
+    /// `(in_index, out_index)`: coder input `in_index` reads coder output `out_index`.
+    bind_pairs: Vec<(usize, usize)>,
+    /// Coder input index of each packed stream, in pack-stream order.
+    packed_streams: Vec<usize>,
+    /// Unpacked size of each coder output.
+    unpack_sizes: Vec<u64>,
+    crc: Option<u32>,
+    /// Byte ranges of this folder's packed streams within the archive.
+    pack_ranges: Vec<(usize, usize)>,
+}
+
+impl Folder {
+    /// The coder whose output is not consumed by another coder.
+    fn main_coder(&self) -> Option<usize> {
+        (0..self.coders.len()).find(|&c| !self.bind_pairs.iter().any(|&(_, o)| o == c))
+    }
+
+    fn unpack_size(&self) -> u64 {
+        self.main_coder().and_then(|c| self.unpack_sizes.get(c).copied()).unwrap_or(0)
+    }
+
+    /// Decode the output of coder `index`, recursively decoding its input.
+    fn decode_coder(&self, archive: &[u8], index: usize, depth: usize) -> Option<Vec<u8>> {
+        if depth > self.coders.len() {
+            return None;
+        }
+        let input_owned;
+        let input: &[u8] = match self.bind_pairs.iter().find(|&&(i, _)| i == index) {
+            Some(&(_, out)) => {
+                input_owned = self.decode_coder(archive, out, depth + 1)?;
+                &input_owned
+            }
+            None => {
+                let k = self.packed_streams.iter().position(|&s| s == index)?;
+                let (start, end) = *self.pack_ranges.get(k)?;
+                archive.get(start..end)?
+            }
+        };
+
+        let coder = &self.coders[index];
+        let size = usize::try_from(*self.unpack_sizes.get(index)?).ok()?;
+        let out = match coder.method.as_slice() {
+            METHOD_COPY => input.get(..size)?.to_vec(),
+            METHOD_LZMA => lzma::decompress(&coder.props, input, size)?,
+            METHOD_LZMA2 => lzma::decompress_lzma2(input, size)?,
+            METHOD_DEFLATE => inflate::inflate(input)?,
+            METHOD_BCJ_X86 => {
+                let mut data = input.get(..size)?.to_vec();
+                bcj_x86_decode(&mut data);
+                data
+            }
+            _ => return None, // Unsupported method (e.g. AES, PPMd)
+        };
+        if out.len() != size {
+            return None;
+        }
+        Some(out)
+    }
+
+    /// Unpack the whole folder, verifying its CRC if one is stored.
+    fn decode(&self, archive: &[u8]) -> Option<Vec<u8>> {
+        let out = self.decode_coder(archive, self.main_coder()?, 0)?;
+        if let Some(crc) = self.crc {
+            if crc32::crc32(&out) != crc {
+                return None;
+            }
+        }
+        Some(out)
+    }
+}
+
+fn read_folder(r: &mut ByteReader) -> Option<Folder> {
+    let num_coders = r.count()?;
+    if num_coders == 0 || num_coders > 64 {
+        return None;
+    }
+    let mut coders = Vec::with_capacity(num_coders);
+    for _ in 0..num_coders {
+        let flags = r.byte()?;
+        if flags & 0x80 != 0 {
+            return None; // Alternative methods are not used by real archives
+        }
+        let method = r.bytes((flags & 0x0F) as usize)?.to_vec();
+        if flags & 0x10 != 0 {
+            // Multi-stream coders (BCJ2) are not supported
+            if r.number()? != 1 || r.number()? != 1 {
+                return None;
+            }
+        }
+        let props = if flags & 0x20 != 0 {
+            let n = r.count()?;
+            r.bytes(n)?.to_vec()
+        } else {
+            Vec::new()
+        };
+        coders.push(Coder { method, props });
+    }
+
+    // Every coder has exactly one input and one output here
+    let mut bind_pairs = Vec::with_capacity(num_coders - 1);
+    for _ in 0..num_coders - 1 {
+        let in_index = r.count()?;
+        let out_index = r.count()?;
+        if in_index >= num_coders || out_index >= num_coders {
+            return None;
+        }
+        bind_pairs.push((in_index, out_index));
+    }
+
+    let num_packed = num_coders - bind_pairs.len();
+    let packed_streams = if num_packed == 1 {
+        let free = (0..num_coders).find(|&i| !bind_pairs.iter().any(|&(b, _)| b == i))?;
+        alloc::vec![free]
+    } else {
+        let mut v = Vec::with_capacity(num_packed);
+        for _ in 0..num_packed {
+            v.push(r.count()?);
+        }
+        v
+    };
+
+    Some(Folder {
+        coders,
+        bind_pairs,
+        packed_streams,
+        unpack_sizes: Vec::new(),
+        crc: None,
+        pack_ranges: Vec::new(),
+    })
+}
+
+// ─── Streams Info ───────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct StreamsInfo {
+    pack_pos: u64,
+    pack_sizes: Vec<u64>,
+    folders: Vec<Folder>,
+    /// Number of files stored in each folder.
+    num_unpack_streams: Vec<usize>,
+    /// Size of every substream, folder by folder.
+    substream_sizes: Vec<u64>,
+    substream_crcs: Vec<Option<u32>>,
+}
+
+fn read_streams_info(r: &mut ByteReader) -> Option<StreamsInfo> {
+    let mut info = StreamsInfo::default();
+    let mut id = r.number()?;
+
+    if id == K_PACK_INFO {
+        info.pack_pos = r.number()?;
+        let num_pack = r.count()?;
+        id = r.number()?;
+        if id == K_SIZE {
+            for _ in 0..num_pack {
+                info.pack_sizes.push(r.number()?);
+            }
+            id = r.number()?;
+        }
+        if id == K_CRC {
+            r.digests(num_pack)?;
+            id = r.number()?;
+        }
+        if id != K_END {
+            return None;
+        }
+        id = r.number()?;
+    }
+
+    if id == K_UNPACK_INFO {
+        if r.number()? != K_FOLDER {
+            return None;
+        }
+        let num_folders = r.count()?;
+        if r.byte()? != 0 {
+            return None; // External folder data is not supported
+        }
+        for _ in 0..num_folders {
+            info.folders.push(read_folder(r)?);
+        }
+        if r.number()? != K_CODERS_UNPACK_SIZE {
+            return None;
+        }
+        for f in info.folders.iter_mut() {
+            for _ in 0..f.coders.len() {
+                f.unpack_sizes.push(r.number()?);
+            }
+        }
+        id = r.number()?;
+        if id == K_CRC {
+            let crcs = r.digests(num_folders)?;
+            for (f, crc) in info.folders.iter_mut().zip(crcs) {
+                f.crc = crc;
+            }
+            id = r.number()?;
+        }
+        if id != K_END {
+            return None;
+        }
+        id = r.number()?;
+    }
+
+    info.num_unpack_streams = alloc::vec![1; info.folders.len()];
+    if id == K_SUBSTREAMS_INFO {
+        id = r.number()?;
+        if id == K_NUM_UNPACK_STREAM {
+            for n in info.num_unpack_streams.iter_mut() {
+                *n = r.count()?;
+            }
+            id = r.number()?;
+        }
+        let has_sizes = id == K_SIZE;
+        for (f, &n) in info.folders.iter().zip(info.num_unpack_streams.iter()) {
+            if n == 0 {
+                continue;
+            }
+            let total = f.unpack_size();
+            let mut sum = 0u64;
+            if has_sizes {
+                for _ in 1..n {
+                    let s = r.number()?;
+                    sum = sum.checked_add(s)?;
+                    info.substream_sizes.push(s);
+                }
+            }
+            info.substream_sizes.push(total.checked_sub(sum)?);
+        }
+        if has_sizes {
+            id = r.number()?;
+        }
+
+        // Digests are listed only for streams whose CRC is not already
+        // known from a single-stream folder
+        let mut crcs = Vec::new();
+        let mut missing = 0usize;
+        for (f, &n) in info.folders.iter().zip(info.num_unpack_streams.iter()) {
+            if !(n == 1 && f.crc.is_some()) {
+                missing += n;
+            }
+        }
+        if id == K_CRC {
+            let mut digests = r.digests(missing)?.into_iter();
+            for (f, &n) in info.folders.iter().zip(info.num_unpack_streams.iter()) {
+                if n == 1 && f.crc.is_some() {
+                    crcs.push(f.crc);
+                } else {
+                    for _ in 0..n {
+                        crcs.push(digests.next()?);
+                    }
+                }
+            }
+            id = r.number()?;
+        }
+        info.substream_crcs = crcs;
+        if id != K_END {
+            return None;
+        }
+        id = r.number()?;
+    } else {
+        for f in &info.folders {
+            info.substream_sizes.push(f.unpack_size());
+            info.substream_crcs.push(f.crc);
+        }
+    }
+
+    if id != K_END {
+        return None;
+    }
+
+    // Resolve packed stream byte ranges
+    let mut pos = (SIGNATURE_HEADER_SIZE as u64).checked_add(info.pack_pos)?;
+    let mut pack_index = 0usize;
+    for f in info.folders.iter_mut() {
+        for _ in 0..f.packed_streams.len() {
+            let size = *info.pack_sizes.get(pack_index)?;
+            let end = pos.checked_add(size)?;
+            f.pack_ranges.push((usize::try_from(pos).ok()?, usize::try_from(end).ok()?));
+            pos = end;
+            pack_index += 1;
+        }
+    }
+
+    Some(info)
+}
+
+// ─── Files Info ─────────────────────────────────────────────────────────────
+
+struct FileInfo {
+    name: String,
+    has_stream: bool,
+    is_dir: bool,
+}
+
+fn read_files_info(r: &mut ByteReader) -> Option<Vec<FileInfo>> {
+    let num_files = r.count()?;
+    let mut empty_stream = alloc::vec![false; num_files];
+    let mut empty_file: Vec<bool> = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+    let mut attributes: Vec<Option<u32>> = Vec::new();
+
+    loop {
+        let prop = r.number()?;
+        if prop == K_END {
+            break;
+        }
+        let size = r.count()?;
+        let mut p = ByteReader::new(r.bytes(size)?);
+        match prop {
+            K_EMPTY_STREAM => empty_stream = p.bit_vector(num_files)?,
+            K_EMPTY_FILE => {
+                let n = empty_stream.iter().filter(|&&e| e).count();
+                empty_file = p.bit_vector(n)?;
+            }
+            K_NAME => {
+                if p.byte()? != 0 {
+                    return None;
+                }
+                let mut units: Vec<u16> = Vec::new();
+                while names.len() < num_files {
+                    let b = p.bytes(2)?;
+                    let u = u16::from_le_bytes([b[0], b[1]]);
+                    if u == 0 {
+                        let name: String = core::char::decode_utf16(units.iter().copied())
+                            .map(|c| c.unwrap_or('\u{FFFD}'))
+                            .map(|c| if c == '\\' { '/' } else { c })
+                            .collect();
+                        names.push(name);
+                        units.clear();
+                    } else {
+                        units.push(u);
+                    }
+                }
+            }
+            K_WIN_ATTRIBUTES => {
+                let defined = p.defined_vector(num_files)?;
+                if p.byte()? != 0 {
+                    return None;
+                }
+                for d in defined {
+                    attributes.push(if d { Some(p.u32()?) } else { None });
+                }
+            }
+            _ => {} // Times, anti-items, padding: not needed for extraction
+        }
+    }
+
+    let mut files = Vec::with_capacity(num_files);
+    let mut empty_index = 0usize;
+    for i in 0..num_files {
+        let has_stream = !empty_stream[i];
+        let mut is_dir = false;
+        if !has_stream {
+            is_dir = !empty_file.get(empty_index).copied().unwrap_or(false);
+            empty_index += 1;
+        }
+        if let Some(Some(attr)) = attributes.get(i) {
+            is_dir |= attr & ATTR_DIRECTORY != 0;
+        }
+        let mut name = names.get(i).cloned().unwrap_or_default();
+        if is_dir && !name.ends_with('/') {
+            name.push('/');
+        }
+        files.push(FileInfo { name, has_stream, is_dir });
+    }
+    Some(files)
+}
+
+// ─── 7z Entry ───────────────────────────────────────────────────────────────
+
+/// A single file or directory in a 7z archive.
+pub struct SevenZEntry {
+    pub name: String,
+    pub size: u64,
+    pub is_dir: bool,
+    /// Folder holding the data (None for empty files and directories).
+    folder: Option<usize>,
+    /// Offset of the data within the unpacked folder.
+    offset: u64,
+    crc: Option<u32>,
+}
+
+// ─── 7z Reader ──────────────────────────────────────────────────────────────
+
+/// A parsed 7z archive (read-only).
+pub struct SevenZReader {
+    data: Vec<u8>,
+    folders: Vec<Folder>,
+    pub entries: Vec<SevenZEntry>,
+    /// Most recently unpacked folder; solid archives store many files in
+    /// one folder, so extracting them in order decodes it only once.
+    cache: RefCell<Option<(usize, Vec<u8>)>>,
+}
+
+impl SevenZReader {
+    /// Parse a 7z archive from raw bytes.
+    pub fn parse(data: Vec<u8>) -> Option<SevenZReader> {
+        if !is_7z(&data) {
+            return None;
+        }
+        let mut sig = ByteReader::new(&data[12..SIGNATURE_HEADER_SIZE]);
+        let next_offset = usize::try_from(sig.bytes(8).map(le_u64)?).ok()?;
+        let next_size = usize::try_from(sig.bytes(8).map(le_u64)?).ok()?;
+        let next_crc = sig.u32()?;
+        if crc32::crc32(&data[12..SIGNATURE_HEADER_SIZE]) != le_u32(&data[8..12]) {
+            return None;
+        }
+        if next_size == 0 {
+            // Empty archive
+            return Some(SevenZReader {
+                data,
+                folders: Vec::new(),
+                entries: Vec::new(),
+                cache: RefCell::new(None),
+            });
+        }
+
+        let start = SIGNATURE_HEADER_SIZE.checked_add(next_offset)?;
+        let header = data.get(start..start.checked_add(next_size)?)?;
+        if crc32::crc32(header) != next_crc {
+            return None;
+        }
+
+        // Unwrap (possibly nested) encoded headers
+        let mut header = header.to_vec();
+        loop {
+            let mut r = ByteReader::new(&header);
+            match r.number()? {
+                K_HEADER => break,
+                K_ENCODED_HEADER => {
+                    let info = read_streams_info(&mut r)?;
+                    header = info.folders.first()?.decode(&data)?;
+                }
+                _ => return None,
+            }
+        }
+
+        let mut r = ByteReader::new(&header);
+        r.number()?; // K_HEADER
+        let mut id = r.number()?;
+        if id == K_ARCHIVE_PROPERTIES {
+            loop {
+                let prop = r.number()?;
+                if prop == K_END {
+                    break;
+                }
+                let size = r.count()?;
+                r.bytes(size)?;
+            }
+            id = r.number()?;
+        }
+        if id == K_ADDITIONAL_STREAMS_INFO {
+            read_streams_info(&mut r)?;
+            id = r.number()?;
+        }
+        let mut streams = StreamsInfo::default();
+        if id == K_MAIN_STREAMS_INFO {
+            streams = read_streams_info(&mut r)?;
+            id = r.number()?;
+        }
+        let mut files = Vec::new();
+        if id == K_FILES_INFO {
+            files = read_files_info(&mut r)?;
+            id = r.number()?;
+        }
+        if id != K_END {
+            return None;
+        }
+
+        // Assign each file with data to the next substream
+        let mut entries = Vec::with_capacity(files.len());
+        let mut folder = 0usize;
+        let mut in_folder = 0usize;
+        let mut offset = 0u64;
+        let mut stream = 0usize;
+        for f in files {
+            if !f.has_stream {
+                entries.push(SevenZEntry {
+                    name: f.name,
+                    size: 0,
+                    is_dir: f.is_dir,
+                    folder: None,
+                    offset: 0,
+                    crc: None,
+                });
+                continue;
+            }
+            while folder < streams.folders.len() && in_folder >= streams.num_unpack_streams[folder] {
+                folder += 1;
+                in_folder = 0;
+                offset = 0;
+            }
+            if folder >= streams.folders.len() {
+                return None;
+            }
+            let size = *streams.substream_sizes.get(stream)?;
+            entries.push(SevenZEntry {
+                name: f.name,
+                size,
+                is_dir: f.is_dir,
+                folder: Some(folder),
+                offset,
+                crc: streams.substream_crcs.get(stream).copied().flatten(),
+            });
+            offset += size;
+            in_folder += 1;
+            stream += 1;
+        }
+
+        Some(SevenZReader {
+            data,
+            folders: streams.folders,
+            entries,
+            cache: RefCell::new(None),
+        })
+    }
+
+    /// Extract an entry by index. Returns its data or None.
+    pub fn extract(&self, index: usize) -> Option<Vec<u8>> {
+        let entry = self.entries.get(index)?;
+        let folder = match entry.folder {
+            Some(f) => f,
+            None => return Some(Vec::new()),
+        };
+
+        let mut cache = self.cache.borrow_mut();
+        if cache.as_ref().map(|(f, _)| *f) != Some(folder) {
+            *cache = None;
+            let unpacked = self.folders.get(folder)?.decode(&self.data)?;
+            *cache = Some((folder, unpacked));
+        }
+        let (_, unpacked) = cache.as_ref()?;
+
+        let start = usize::try_from(entry.offset).ok()?;
+        let end = start.checked_add(usize::try_from(entry.size).ok()?)?;
+        let out = unpacked.get(start..end)?.to_vec();
+        if let Some(crc) = entry.crc {
+            if crc32::crc32(&out) != crc {
+                return None;
+            }
+        }
+        Some(out)
+    }
+
+    /// Get entry count.
+    pub fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+fn le_u32(b: &[u8]) -> u32 {
+    u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+}
+
+fn le_u64(b: &[u8]) -> u64 {
+    (le_u32(&b[..4]) as u64) | ((le_u32(&b[4..8]) as u64) << 32)
+}
+
+// ─── x86 BCJ Filter ─────────────────────────────────────────────────────────
+
+/// Undo the x86 branch converter: CALL/JMP targets were stored as absolute
+/// addresses to improve compression.
+fn bcj_x86_decode(data: &mut [u8]) {
+    const MASK_TO_ALLOWED: [bool; 8] = [true, true, true, false, true, false, false, false];
+    const MASK_TO_BIT_NUMBER: [u32; 8] = [0, 1, 2, 2, 3, 3, 3, 3];
+    fn test_ms_byte(b: u8) -> bool {
+        b == 0 || b == 0xFF
+    }
+
+    if data.len() < 5 {
+        return;
+    }
+    let ip: u32 = 5;
+    let limit = data.len() - 4;
+    let mut pos = 0usize;
+    let mut prev_pos = usize::MAX;
+    let mut prev_mask = 0u32;
+
+    loop {
+        while pos < limit && data[pos] & 0xFE != 0xE8 {
+            pos += 1;
+        }
+        if pos >= limit {
+            break;
+        }
+
+        let gap = pos.wrapping_sub(prev_pos);
+        if gap > 3 {
+            prev_mask = 0;
+        } else {
+            prev_mask = (prev_mask << (gap - 1)) & 0x7;
+            if prev_mask != 0 {
+                let b = data[pos + 4 - MASK_TO_BIT_NUMBER[prev_mask as usize] as usize];
+                if !MASK_TO_ALLOWED[prev_mask as usize] || test_ms_byte(b) {
+                    prev_pos = pos;
+                    prev_mask = ((prev_mask << 1) & 0x7) | 1;
+                    pos += 1;
+                    continue;
+                }
+            }
+        }
+        prev_pos = pos;
+
+        if test_ms_byte(data[pos + 4]) {
+            let mut src = u32::from_le_bytes([data[pos + 1], data[pos + 2], data[pos + 3], data[pos + 4]]);
+            let mut dest;
+            loop {
+                dest = src.wrapping_sub(ip.wrapping_add(pos as u32));
+                if prev_mask == 0 {
+                    break;
+                }
+                let index = MASK_TO_BIT_NUMBER[prev_mask as usize] * 8;
+                let b = (dest >> (24 - index)) as u8;
+                if !test_ms_byte(b) {
+                    break;
+                }
+                src = dest ^ ((1u32 << (32 - index)) - 1);
+            }
+            data[pos + 4] = !(((dest >> 24) & 1).wrapping_sub(1)) as u8;
+            data[pos + 3] = (dest >> 16) as u8;
+            data[pos + 2] = (dest >> 8) as u8;
+            data[pos + 1] = dest as u8;
+            pos += 5;
+        } else {
+            prev_mask = ((prev_mask << 1) & 0x7) | 1;
+            pos += 1;
+        }
+    }
+}
@@ -1,7 +1,7 @@
 //! Syscall wrappers for libzip — delegates to libsyscall.
 
 pub use libsyscall::{
-    sbrk, mmap, munmap, exit, close, lseek, file_size, mkdir, stat,
+    sbrk, mmap, munmap, exit, close, lseek, file_size, mkdir, stat, thread_create, waitpid,
     O_WRITE, O_CREATE, O_TRUNC, SEEK_SET,
 };
 
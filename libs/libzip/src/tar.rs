@@ -269,6 +269,19 @@ fn write_checksum(header: &mut [u8; BLOCK_SIZE]) {
 }
 
 /// Verify the checksum of a tar header block.
+/// Check if data starts with a tar header (ustar magic or a valid
+/// pre-POSIX header checksum).
+pub fn is_tar(data: &[u8]) -> bool {
+    if data.len() < BLOCK_SIZE {
+        return false;
+    }
+    let header = &data[..BLOCK_SIZE];
+    if &header[OFF_MAGIC..OFF_MAGIC + 5] == &USTAR_MAGIC[..5] {
+        return true;
+    }
+    header[OFF_NAME] != 0 && verify_checksum(header)
+}
+
 fn verify_checksum(header: &[u8]) -> bool {
     let stored = parse_octal(&header[OFF_CHKSUM..OFF_CHKSUM + 8]) as u32;
 
@@ -7,6 +7,7 @@ use alloc::vec::Vec;
 use crate::crc32;
 use crate::inflate;
 use crate::deflate;
+use crate::parallel;
 
 // ─── Constants ──────────────────────────────────────────────────────────────
 
@@ -17,6 +18,12 @@ const END_CENTRAL_DIR_SIG: u32 = 0x06054B50;
 const METHOD_STORED: u16 = 0;
 const METHOD_DEFLATE: u16 = 8;
 
+/// General-purpose flag bit 3: sizes and CRC follow the data in a descriptor.
+const FLAG_DATA_DESCRIPTOR: u16 = 1 << 3;
+
+/// Largest extra field or archive comment (16-bit length in the headers).
+pub const MAX_FIELD_LEN: usize = 0xFFFF;
+
 // ─── Utility ────────────────────────────────────────────────────────────────
 
 fn read_u16(data: &[u8], offset: usize) -> u16 {
@@ -37,6 +44,25 @@ fn write_u32(buf: &mut Vec<u8>, val: u32) {
     buf.extend_from_slice(&val.to_le_bytes());
 }
 
+/// Find the data of the record with header ID `id` in a raw extra field
+/// (a sequence of `id: u16, size: u16, data[size]` records).
+pub fn find_extra(extra: &[u8], id: u16) -> Option<&[u8]> {
+    let mut pos = 0;
+    while pos + 4 <= extra.len() {
+        let rec_id = read_u16(extra, pos);
+        let size = read_u16(extra, pos + 2) as usize;
+        let end = pos + 4 + size;
+        if end > extra.len() {
+            return None;
+        }
+        if rec_id == id {
+            return Some(&extra[pos + 4..end]);
+        }
+        pos = end;
+    }
+    None
+}
+
 // ─── ZIP Entry ──────────────────────────────────────────────────────────────
 
 /// A single file entry in a ZIP archive.
@@ -46,9 +72,12 @@ pub struct ZipEntry {
     pub uncompressed_size: u32,
     pub crc32: u32,
     pub method: u16,
+    pub flags: u16,
     pub local_header_offset: u32,
     // Offset to actual compressed data within archive
     pub data_offset: u32,
+    /// Raw extra field from the central directory.
+    pub extra: Vec<u8>,
 }
 
 // ─── ZIP Reader ─────────────────────────────────────────────────────────────
@@ -57,6 +86,10 @@ pub struct ZipEntry {
 pub struct ZipReader {
     pub data: Vec<u8>,
     pub entries: Vec<ZipEntry>,
+    /// Entry count declared in the end-of-central-directory record.
+    pub declared_count: usize,
+    /// Archive comment from the end-of-central-directory record.
+    pub comment: Vec<u8>,
 }
 
 impl ZipReader {
@@ -85,6 +118,8 @@ impl ZipReader {
         let eocd = eocd_offset?;
         let entry_count = read_u16(&data, eocd + 10) as usize;
         let central_dir_offset = read_u32(&data, eocd + 16) as usize;
+        let comment_len = read_u16(&data, eocd + 20) as usize;
+        let comment = data[eocd + 22..(eocd + 22 + comment_len).min(len)].to_vec();
 
         // Parse central directory entries
         let mut entries = Vec::with_capacity(entry_count);
@@ -95,6 +130,7 @@ impl ZipReader {
                 break;
             }
 
+            let flags = read_u16(&data, pos + 8);
             let method = read_u16(&data, pos + 10);
             let crc = read_u32(&data, pos + 16);
             let compressed_size = read_u32(&data, pos + 20);
@@ -109,6 +145,8 @@ impl ZipReader {
             let name = core::str::from_utf8(&data[name_start..name_end])
                 .unwrap_or("")
                 .into();
+            let extra_end = (name_end + extra_len).min(len);
+            let extra = data[name_end..extra_end].to_vec();
 
             // Calculate actual data offset from local header
             let lh = local_header_offset as usize;
@@ -126,14 +164,16 @@ impl ZipReader {
                 uncompressed_size,
                 crc32: crc,
                 method,
+                flags,
                 local_header_offset,
                 data_offset,
+                extra,
             });
 
             pos += 46 + name_len + extra_len + comment_len;
         }
 
-        Some(ZipReader { data, entries })
+        Some(ZipReader { data, entries, declared_count: entry_count, comment })
     }
 
     /// Extract an entry by index. Returns decompressed data or None.
@@ -169,6 +209,133 @@ impl ZipReader {
     pub fn entry_count(&self) -> usize {
         self.entries.len()
     }
+
+    /// Whether every entry declared by the end-of-central-directory record
+    /// was found in the central directory.
+    pub fn central_dir_complete(&self) -> bool {
+        self.entries.len() == self.declared_count
+    }
+
+    /// Check one entry's local header and data without extracting it.
+    ///
+    /// Deflated data is CRC'd as it is decoded, so memory use stays bounded
+    /// by the inflate window regardless of the entry's size.
+    pub fn verify_entry(&self, index: usize) -> EntryCheck {
+        let entry = match self.entries.get(index) {
+            Some(e) => e,
+            None => return EntryCheck::status(VERIFY_LOCAL_HEADER),
+        };
+        let mut check = EntryCheck {
+            status: VERIFY_OK,
+            expected_crc: entry.crc32,
+            actual_crc: 0,
+        };
+
+        // Local header must exist and agree with the central directory
+        let data = &self.data;
+        let lh = entry.local_header_offset as usize;
+        if lh + 30 > data.len() {
+            check.status = VERIFY_TRUNCATED;
+            return check;
+        }
+        if read_u32(data, lh) != LOCAL_FILE_HEADER_SIG {
+            check.status = VERIFY_LOCAL_HEADER;
+            return check;
+        }
+        let lh_name_len = read_u16(data, lh + 26) as usize;
+        let lh_name_end = lh + 30 + lh_name_len;
+        if lh_name_end > data.len() {
+            check.status = VERIFY_TRUNCATED;
+            return check;
+        }
+        let sizes_match = entry.flags & FLAG_DATA_DESCRIPTOR != 0
+            || (read_u32(data, lh + 14) == entry.crc32
+                && read_u32(data, lh + 18) == entry.compressed_size
+                && read_u32(data, lh + 22) == entry.uncompressed_size);
+        if read_u16(data, lh + 8) != entry.method
+            || !sizes_match
+            || &data[lh + 30..lh_name_end] != entry.name.as_bytes()
+        {
+            check.status = VERIFY_HEADER_MISMATCH;
+            return check;
+        }
+
+        // Compressed data must be fully present
+        let start = entry.data_offset as usize;
+        let end = start + entry.compressed_size as usize;
+        if end > data.len() {
+            check.status = VERIFY_TRUNCATED;
+            return check;
+        }
+        let compressed = &data[start..end];
+
+        let mut crc = 0u32;
+        let size = match entry.method {
+            METHOD_STORED => {
+                crc = crc32::crc32(compressed);
+                compressed.len() as u64
+            }
+            METHOD_DEFLATE => {
+                match inflate::inflate_stream(compressed, |chunk| {
+                    crc = crc32::crc32_update(crc, chunk);
+                }) {
+                    Ok(n) => n,
+                    Err(inflate::StreamError::Truncated) => {
+                        check.status = VERIFY_TRUNCATED;
+                        return check;
+                    }
+                    Err(inflate::StreamError::Corrupt) => {
+                        check.status = VERIFY_BAD_DATA;
+                        return check;
+                    }
+                }
+            }
+            _ => {
+                check.status = VERIFY_UNSUPPORTED;
+                return check;
+            }
+        };
+        check.actual_crc = crc;
+
+        if size != entry.uncompressed_size as u64 {
+            check.status = VERIFY_SIZE_MISMATCH;
+        } else if crc != entry.crc32 {
+            check.status = VERIFY_CRC_MISMATCH;
+        }
+        check
+    }
+}
+
+// ─── Integrity Check ────────────────────────────────────────────────────────
+
+/// Entry is intact.
+pub const VERIFY_OK: u32 = 0;
+/// Local file header is missing or has a bad signature.
+pub const VERIFY_LOCAL_HEADER: u32 = 1;
+/// Local header disagrees with the central directory (method, sizes, CRC, name).
+pub const VERIFY_HEADER_MISMATCH: u32 = 2;
+/// Archive ends before the entry's header or data is complete.
+pub const VERIFY_TRUNCATED: u32 = 3;
+/// Decoded data does not match the stored CRC-32.
+pub const VERIFY_CRC_MISMATCH: u32 = 4;
+/// Decoded length differs from the stored uncompressed size.
+pub const VERIFY_SIZE_MISMATCH: u32 = 5;
+/// Compressed stream is not valid DEFLATE.
+pub const VERIFY_BAD_DATA: u32 = 6;
+/// Compression method is not supported, so the data could not be checked.
+pub const VERIFY_UNSUPPORTED: u32 = 7;
+
+/// Result of verifying a single entry.
+pub struct EntryCheck {
+    pub status: u32,
+    pub expected_crc: u32,
+    pub actual_crc: u32,
+}
+
+impl EntryCheck {
+    fn status(status: u32) -> Self {
+        EntryCheck { status, expected_crc: 0, actual_crc: 0 }
+    }
 }
 
 // ─── ZIP Writer ─────────────────────────────────────────────────────────────
@@ -180,92 +347,218 @@ struct WriterEntry {
     uncompressed_size: u32,
     method: u16,
     local_header_offset: u32,
-    compressed_data: Vec<u8>,
+    extra: Vec<u8>,
+}
+
+/// An entry queued for the next parallel batch.
+struct PendingEntry {
+    name: String,
+    /// File data; `None` for a directory.
+    data: Option<Vec<u8>>,
+    compress: bool,
+    extra: Vec<u8>,
 }
 
+/// Queued entry data that starts a parallel batch.
+const BATCH_BYTES: usize = 16 * 1024 * 1024;
+
 /// Builds a new ZIP archive in memory.
+///
+/// Local headers and entry data are appended to a growable buffer as
+/// entries are added; `seal` appends the central directory, after which
+/// the buffer holds the complete archive and no more entries are accepted.
+///
+/// With more than one thread (`set_threads`), added entries are queued and
+/// compressed in batches on worker threads, each into a DEFLATE stream of
+/// its own; they are still written in the order they were added.
+///
+/// Extra-field records staged with `add_extra` are attached to the next
+/// entry added, in both its local header and its central directory entry.
 pub struct ZipWriter {
     entries: Vec<WriterEntry>,
+    out: Vec<u8>,
+    sealed: bool,
+    threads: usize,
+    pending: Vec<PendingEntry>,
+    pending_bytes: usize,
+    /// Extra field for the next entry added.
+    next_extra: Vec<u8>,
+    comment: Vec<u8>,
 }
 
 impl ZipWriter {
     pub fn new() -> Self {
-        ZipWriter { entries: Vec::new() }
+        ZipWriter {
+            entries: Vec::new(),
+            out: Vec::new(),
+            sealed: false,
+            threads: 1,
+            pending: Vec::new(),
+            pending_bytes: 0,
+            next_extra: Vec::new(),
+            comment: Vec::new(),
+        }
+    }
+
+    /// Set the archive comment written in the end-of-central-directory
+    /// record. Returns false if the writer is sealed or the comment is
+    /// longer than `MAX_FIELD_LEN`.
+    pub fn set_comment(&mut self, comment: &[u8]) -> bool {
+        if self.sealed || comment.len() > MAX_FIELD_LEN { return false; }
+        self.comment = comment.to_vec();
+        true
+    }
+
+    /// Stage an extra-field record (`id`, `data`) for the next entry added.
+    /// May be called several times to attach several records. Returns false
+    /// if the writer is sealed or the entry's extra field would exceed
+    /// `MAX_FIELD_LEN`.
+    pub fn add_extra(&mut self, id: u16, data: &[u8]) -> bool {
+        if self.sealed || self.next_extra.len() + 4 + data.len() > MAX_FIELD_LEN { return false; }
+        write_u16(&mut self.next_extra, id);
+        write_u16(&mut self.next_extra, data.len() as u16);
+        self.next_extra.extend_from_slice(data);
+        true
+    }
+
+    /// Compress on up to `threads` threads (clamped to 1..=`MAX_THREADS`;
+    /// 1 compresses each entry as it is added). Entries already queued are
+    /// written first. Returns false if the writer is already sealed.
+    pub fn set_threads(&mut self, threads: usize) -> bool {
+        if self.sealed { return false; }
+        self.flush_pending();
+        self.threads = threads.clamp(1, parallel::MAX_THREADS);
+        true
     }
 
     /// Add a file entry with optional DEFLATE compression.
     /// `compress` = true uses DEFLATE, false uses Stored.
-    pub fn add(&mut self, name: &str, data: &[u8], compress: bool) {
+    /// Returns false if the writer is already sealed.
+    pub fn add(&mut self, name: &str, data: &[u8], compress: bool) -> bool {
+        if self.sealed { return false; }
+        let extra = core::mem::take(&mut self.next_extra);
+        if self.threads > 1 {
+            self.queue(name, Some(data.to_vec()), compress, extra);
+            return true;
+        }
         let crc = crc32::crc32(data);
-        let uncompressed_size = data.len() as u32;
-
-        let (method, compressed_data) = if compress && !data.is_empty() {
-            let compressed = deflate::deflate(data);
-            // Only use compressed if it's actually smaller
-            if compressed.len() < data.len() {
-                (METHOD_DEFLATE, compressed)
-            } else {
-                (METHOD_STORED, data.to_vec())
-            }
+        let compressed = if compress && !data.is_empty() {
+            Some(deflate::deflate(data))
         } else {
-            (METHOD_STORED, data.to_vec())
+            None
         };
+        // Only use compressed if it's actually smaller
+        match &compressed {
+            Some(c) if c.len() < data.len() => self.write_entry(name, crc, data.len(), METHOD_DEFLATE, c, extra),
+            _ => self.write_entry(name, crc, data.len(), METHOD_STORED, data, extra),
+        }
+        true
+    }
 
-        let compressed_size = compressed_data.len() as u32;
+    /// Add a directory entry (name should end with '/').
+    /// Returns false if the writer is already sealed.
+    pub fn add_directory(&mut self, name: &str) -> bool {
+        if self.sealed { return false; }
+        let extra = core::mem::take(&mut self.next_extra);
+        if self.threads > 1 {
+            self.queue(name, None, false, extra);
+        } else {
+            self.write_entry(name, 0, 0, METHOD_STORED, &[], extra);
+        }
+        true
+    }
 
-        self.entries.push(WriterEntry {
+    fn queue(&mut self, name: &str, data: Option<Vec<u8>>, compress: bool, extra: Vec<u8>) {
+        self.pending_bytes += data.as_ref().map_or(0, |d| d.len());
+        self.pending.push(PendingEntry { name: String::from(name), data, compress, extra });
+        if self.pending_bytes >= BATCH_BYTES {
+            self.flush_pending();
+        }
+    }
+
+    /// Compress the queued entries in parallel, then write them in order.
+    fn flush_pending(&mut self) {
+        if self.pending.is_empty() { return; }
+        let pending = core::mem::take(&mut self.pending);
+        self.pending_bytes = 0;
+
+        let mut jobs: Vec<parallel::Job> = pending.iter()
+            .filter_map(|p| p.data.as_ref().map(|d| parallel::Job::new(d, p.compress)))
+            .collect();
+        parallel::compress_all(&mut jobs, self.threads);
+
+        // Jobs are in the order of the queued files.
+        let mut jobs = jobs.iter();
+        for p in pending {
+            let data = match &p.data {
+                Some(d) => d,
+                None => {
+                    self.write_entry(&p.name, 0, 0, METHOD_STORED, &[], p.extra);
+                    continue;
+                }
+            };
+            let job = match jobs.next() {
+                Some(j) => j,
+                None => break,
+            };
+            match job.output() {
+                Some(c) => self.write_entry(&p.name, job.crc32, data.len(), METHOD_DEFLATE, c, p.extra),
+                None => self.write_entry(&p.name, job.crc32, data.len(), METHOD_STORED, data, p.extra),
+            }
+        }
+    }
+
+    /// Append one entry's local header and data.
+    fn write_entry(
+        &mut self, name: &str, crc: u32, uncompressed_size: usize, method: u16, payload: &[u8],
+        extra: Vec<u8>,
+    ) {
+        let entry = WriterEntry {
             name: String::from(name),
             crc32: crc,
-            compressed_size,
-            uncompressed_size,
+            compressed_size: payload.len() as u32,
+            uncompressed_size: uncompressed_size as u32,
             method,
-            local_header_offset: 0, // filled in during finalize
-            compressed_data,
-        });
+            local_header_offset: self.out.len() as u32,
+            extra,
+        };
+        write_local_header(&mut self.out, &entry);
+        self.out.extend_from_slice(payload);
+        self.entries.push(entry);
     }
 
-    /// Add a directory entry (name should end with '/').
-    pub fn add_directory(&mut self, name: &str) {
-        self.entries.push(WriterEntry {
-            name: String::from(name),
-            crc32: 0,
-            compressed_size: 0,
-            uncompressed_size: 0,
-            method: METHOD_STORED,
-            local_header_offset: 0,
-            compressed_data: Vec::new(),
-        });
+    /// Append the central directory (once) and return the complete archive.
+    pub fn seal(&mut self) -> &[u8] {
+        if !self.sealed {
+            self.flush_pending();
+            self.sealed = true;
+            let output = &mut self.out;
+
+            // Write central directory
+            let central_dir_offset = output.len() as u32;
+            for entry in &self.entries {
+                write_central_dir_entry(output, entry);
+            }
+            let central_dir_size = output.len() as u32 - central_dir_offset;
+
+            // Write end of central directory
+            write_u32(output, END_CENTRAL_DIR_SIG);
+            write_u16(output, 0); // disk number
+            write_u16(output, 0); // disk with central dir
+            write_u16(output, self.entries.len() as u16); // entries on this disk
+            write_u16(output, self.entries.len() as u16); // total entries
+            write_u32(output, central_dir_size);
+            write_u32(output, central_dir_offset);
+            write_u16(output, self.comment.len() as u16);
+            output.extend_from_slice(&self.comment);
+        }
+        &self.out
     }
 
     /// Finalize and produce the ZIP file bytes.
     pub fn finish(mut self) -> Vec<u8> {
-        let mut output = Vec::new();
-
-        // Write local file headers + data
-        for entry in &mut self.entries {
-            entry.local_header_offset = output.len() as u32;
-            write_local_header(&mut output, entry);
-            output.extend_from_slice(&entry.compressed_data);
-        }
-
-        // Write central directory
-        let central_dir_offset = output.len() as u32;
-        for entry in &self.entries {
-            write_central_dir_entry(&mut output, entry);
-        }
-        let central_dir_size = output.len() as u32 - central_dir_offset;
-
-        // Write end of central directory
-        write_u32(&mut output, END_CENTRAL_DIR_SIG);
-        write_u16(&mut output, 0); // disk number
-        write_u16(&mut output, 0); // disk with central dir
-        write_u16(&mut output, self.entries.len() as u16); // entries on this disk
-        write_u16(&mut output, self.entries.len() as u16); // total entries
-        write_u32(&mut output, central_dir_size);
-        write_u32(&mut output, central_dir_offset);
-        write_u16(&mut output, 0); // comment length
-
-        output
+        self.seal();
+        self.out
     }
 }
 
@@ -280,8 +573,9 @@ fn write_local_header(buf: &mut Vec<u8>, entry: &WriterEntry) {
     write_u32(buf, entry.compressed_size);
     write_u32(buf, entry.uncompressed_size);
     write_u16(buf, entry.name.len() as u16);
-    write_u16(buf, 0);  // extra field length
+    write_u16(buf, entry.extra.len() as u16);
     buf.extend_from_slice(entry.name.as_bytes());
+    buf.extend_from_slice(&entry.extra);
 }
 
 fn write_central_dir_entry(buf: &mut Vec<u8>, entry: &WriterEntry) {
@@ -296,11 +590,12 @@ fn write_central_dir_entry(buf: &mut Vec<u8>, entry: &WriterEntry) {
     write_u32(buf, entry.compressed_size);
     write_u32(buf, entry.uncompressed_size);
     write_u16(buf, entry.name.len() as u16);
-    write_u16(buf, 0);  // extra field length
+    write_u16(buf, entry.extra.len() as u16);
     write_u16(buf, 0);  // comment length
     write_u16(buf, 0);  // disk number start
     write_u16(buf, 0);  // internal file attributes
     write_u32(buf, 0);  // external file attributes
     write_u32(buf, entry.local_header_offset);
     buf.extend_from_slice(entry.name.as_bytes());
+    buf.extend_from_slice(&entry.extra);
 }
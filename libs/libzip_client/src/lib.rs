@@ -1,12 +1,14 @@
 //! libzip_client — Safe Rust wrapper for the libzip shared library.
 //!
 //! Loads `libzip.so` via `dl_open`/`dl_sym` and provides ergonomic Rust types
-//! (`ZipReader`, `ZipWriter`) for archive operations.
+//! (`ZipReader`, `ZipWriter`) for archive operations.  `Archive` reads any
+//! supported container (ZIP, 7z, tar, tar.gz, gzip) with format auto-detection.
 //!
 //! # Usage
 //! ```rust
 //! libzip_client::init();
 //! let reader = libzip_client::ZipReader::open("/path/to/file.zip").unwrap();
+//! // or, for a downloaded buffer: libzip_client::ZipReader::open_bytes(&bytes)
 //! for i in 0..reader.entry_count() {
 //!     let name = reader.entry_name(i);
 //!     reader.extract_to_file(i, &name);
@@ -27,6 +29,7 @@ struct LibZip {
     _handle: DlHandle,
     // Zip functions
     open: extern "C" fn(*const u8, u32) -> u32,
+    open_mem: extern "C" fn(*const u8, u32) -> u32,
     create: extern "C" fn() -> u32,
     close: extern "C" fn(u32),
     entry_count: extern "C" fn(u32) -> u32,
@@ -37,9 +40,17 @@ struct LibZip {
     entry_is_dir: extern "C" fn(u32, u32) -> u32,
     extract: extern "C" fn(u32, u32, *mut u8, u32) -> u32,
     extract_to_file: extern "C" fn(u32, u32, *const u8, u32) -> u32,
+    verify: extern "C" fn(u32, *mut u8, u32) -> u32,
     add_file: extern "C" fn(u32, *const u8, u32, *const u8, u32, u32) -> u32,
     add_dir: extern "C" fn(u32, *const u8, u32) -> u32,
+    set_threads: extern "C" fn(u32, u32) -> u32,
     write_to_file: extern "C" fn(u32, *const u8, u32) -> u32,
+    writer_get_data: extern "C" fn(u32, *mut u8, u32) -> u32,
+    comment: extern "C" fn(u32, *mut u8, u32) -> u32,
+    entry_extra: extern "C" fn(u32, u32, *mut u8, u32) -> u32,
+    entry_find_extra: extern "C" fn(u32, u32, u32, *mut u8, u32) -> u32,
+    set_comment: extern "C" fn(u32, *const u8, u32) -> u32,
+    add_extra: extern "C" fn(u32, u32, *const u8, u32) -> u32,
     // Gzip functions
     gzip_compress_file: extern "C" fn(*const u8, u32, *const u8, u32) -> u32,
     gzip_decompress_file: extern "C" fn(*const u8, u32, *const u8, u32) -> u32,
@@ -56,6 +67,13 @@ struct LibZip {
     tar_add_file: extern "C" fn(u32, *const u8, u32, *const u8, u32) -> u32,
     tar_add_dir: extern "C" fn(u32, *const u8, u32) -> u32,
     tar_write_to_file: extern "C" fn(u32, *const u8, u32, u32) -> u32,
+    // Format-independent archive functions
+    archive_open: extern "C" fn(*const u8, u32) -> u32,
+    archive_format: extern "C" fn(u32) -> u32,
+    archive_entry_count: extern "C" fn(u32) -> u32,
+    archive_next_entry: extern "C" fn(u32, *mut u8, u32, *mut u64, *mut u32) -> u32,
+    archive_extract_entry: extern "C" fn(u32, *const u8, u32) -> u32,
+    archive_close: extern "C" fn(u32),
 }
 
 static mut LIB: Option<LibZip> = None;
@@ -82,6 +100,7 @@ pub fn init() -> bool {
     unsafe {
         let lib = LibZip {
             open: resolve(&handle, "libzip_open"),
+            open_mem: resolve(&handle, "libzip_open_mem"),
             create: resolve(&handle, "libzip_create"),
             close: resolve(&handle, "libzip_close"),
             entry_count: resolve(&handle, "libzip_entry_count"),
@@ -92,9 +111,17 @@ pub fn init() -> bool {
             entry_is_dir: resolve(&handle, "libzip_entry_is_dir"),
             extract: resolve(&handle, "libzip_extract"),
             extract_to_file: resolve(&handle, "libzip_extract_to_file"),
+            verify: resolve(&handle, "libzip_verify"),
             add_file: resolve(&handle, "libzip_add_file"),
             add_dir: resolve(&handle, "libzip_add_dir"),
+            set_threads: resolve(&handle, "libzip_set_threads"),
             write_to_file: resolve(&handle, "libzip_write_to_file"),
+            writer_get_data: resolve(&handle, "libzip_writer_get_data"),
+            comment: resolve(&handle, "libzip_comment"),
+            entry_extra: resolve(&handle, "libzip_entry_extra"),
+            entry_find_extra: resolve(&handle, "libzip_entry_find_extra"),
+            set_comment: resolve(&handle, "libzip_set_comment"),
+            add_extra: resolve(&handle, "libzip_add_extra"),
             // Gzip
             gzip_compress_file: resolve(&handle, "libzip_gzip_compress_file"),
             gzip_decompress_file: resolve(&handle, "libzip_gzip_decompress_file"),
@@ -111,6 +138,13 @@ pub fn init() -> bool {
             tar_add_file: resolve(&handle, "libzip_tar_add_file"),
             tar_add_dir: resolve(&handle, "libzip_tar_add_dir"),
             tar_write_to_file: resolve(&handle, "libzip_tar_write_to_file"),
+            // Archive
+            archive_open: resolve(&handle, "libarchive_open"),
+            archive_format: resolve(&handle, "libarchive_format"),
+            archive_entry_count: resolve(&handle, "libarchive_entry_count"),
+            archive_next_entry: resolve(&handle, "libarchive_next_entry"),
+            archive_extract_entry: resolve(&handle, "libarchive_extract_entry"),
+            archive_close: resolve(&handle, "libarchive_close"),
             _handle: handle,
         };
         LIB = Some(lib);
@@ -132,6 +166,12 @@ impl ZipReader {
         if h == 0 { None } else { Some(ZipReader { handle: h }) }
     }
 
+    /// Open a ZIP archive from a byte buffer (copied by the library).
+    pub fn open_bytes(data: &[u8]) -> Option<ZipReader> {
+        let h = (lib().open_mem)(data.as_ptr(), data.len() as u32);
+        if h == 0 { None } else { Some(ZipReader { handle: h }) }
+    }
+
     /// Number of entries in the archive.
     pub fn entry_count(&self) -> u32 {
         (lib().entry_count)(self.handle)
@@ -180,6 +220,122 @@ impl ZipReader {
     pub fn extract_to_file(&self, index: u32, path: &str) -> bool {
         (lib().extract_to_file)(self.handle, index, path.as_ptr(), path.len() as u32) == 0
     }
+
+    /// Archive comment (empty if the archive has none).
+    pub fn comment(&self) -> alloc::vec::Vec<u8> {
+        read_field(|buf, len| (lib().comment)(self.handle, buf, len)).unwrap_or_default()
+    }
+
+    /// Raw extra field of an entry: `id: u16, size: u16, data[size]` records.
+    pub fn entry_extra(&self, index: u32) -> alloc::vec::Vec<u8> {
+        read_field(|buf, len| (lib().entry_extra)(self.handle, index, buf, len)).unwrap_or_default()
+    }
+
+    /// Data of the extra-field record with `header_id`, if the entry has one.
+    pub fn find_extra(&self, index: u32, header_id: u16) -> Option<alloc::vec::Vec<u8>> {
+        read_field(|buf, len| (lib().entry_find_extra)(self.handle, index, header_id as u32, buf, len))
+    }
+
+    /// Check every entry's headers and CRC-32 without extracting anything.
+    pub fn verify(&self) -> Option<VerifyReport> {
+        let count = self.entry_count() as usize;
+        let mut buf = vec![0u8; 16 + count * 16];
+        let damaged = (lib().verify)(self.handle, buf.as_mut_ptr(), buf.len() as u32);
+        if damaged == u32::MAX {
+            return None;
+        }
+        let word = |off: usize| u32::from_le_bytes([buf[off], buf[off + 1], buf[off + 2], buf[off + 3]]);
+        let checked = word(0) as usize;
+        let first = word(8);
+        let mut entries = alloc::vec::Vec::with_capacity(checked);
+        for i in 0..checked.min(count) {
+            let rec = 16 + i * 16;
+            entries.push(EntryVerify {
+                index: word(rec),
+                status: EntryStatus::from_code(word(rec + 4)),
+                expected_crc: word(rec + 8),
+                actual_crc: word(rec + 12),
+            });
+        }
+        Some(VerifyReport {
+            damaged,
+            first_damaged: if first == u32::MAX { None } else { Some(first) },
+            directory_incomplete: word(12) & 1 != 0,
+            entries,
+        })
+    }
+}
+
+/// Query a variable-length field's size, then copy it.
+fn read_field(get: impl Fn(*mut u8, u32) -> u32) -> Option<alloc::vec::Vec<u8>> {
+    let size = get(core::ptr::null_mut(), 0);
+    if size == u32::MAX {
+        return None;
+    }
+    let mut buf = vec![0u8; size as usize];
+    if size > 0 {
+        get(buf.as_mut_ptr(), size);
+    }
+    Some(buf)
+}
+
+/// Outcome of verifying one ZIP entry.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EntryStatus {
+    Ok,
+    /// Local file header missing or has a bad signature.
+    MissingLocalHeader,
+    /// Local header disagrees with the central directory.
+    HeaderMismatch,
+    /// Archive ends before the entry is complete.
+    Truncated,
+    CrcMismatch,
+    SizeMismatch,
+    /// Compressed stream is not valid DEFLATE.
+    BadData,
+    /// Compression method not supported; data was not checked.
+    Unsupported,
+}
+
+impl EntryStatus {
+    fn from_code(code: u32) -> EntryStatus {
+        match code {
+            0 => EntryStatus::Ok,
+            1 => EntryStatus::MissingLocalHeader,
+            2 => EntryStatus::HeaderMismatch,
+            3 => EntryStatus::Truncated,
+            4 => EntryStatus::CrcMismatch,
+            5 => EntryStatus::SizeMismatch,
+            6 => EntryStatus::BadData,
+            _ => EntryStatus::Unsupported,
+        }
+    }
+}
+
+/// Per-entry result from `ZipReader::verify`.
+pub struct EntryVerify {
+    pub index: u32,
+    pub status: EntryStatus,
+    pub expected_crc: u32,
+    pub actual_crc: u32,
+}
+
+/// Integrity report returned by `ZipReader::verify`.
+pub struct VerifyReport {
+    /// Number of damaged entries (unsupported methods are not counted).
+    pub damaged: u32,
+    /// Index of the first damaged entry.
+    pub first_damaged: Option<u32>,
+    /// The central directory lists fewer entries than the archive declares.
+    pub directory_incomplete: bool,
+    pub entries: alloc::vec::Vec<EntryVerify>,
+}
+
+impl VerifyReport {
+    /// True if no entry is damaged and the central directory is complete.
+    pub fn is_intact(&self) -> bool {
+        self.damaged == 0 && !self.directory_incomplete
+    }
 }
 
 impl Drop for ZipReader {
@@ -204,6 +360,13 @@ impl ZipWriter {
         if h == 0 { None } else { Some(ZipWriter { handle: h }) }
     }
 
+    /// Compress entries on up to `threads` threads (1..=8). With more than
+    /// one, added files are compressed in batches on worker threads; the
+    /// archive keeps the order they were added in.
+    pub fn set_threads(&self, threads: u32) -> bool {
+        (lib().set_threads)(self.handle, threads) == 0
+    }
+
     /// Add a file with data. `compress` = true uses DEFLATE.
     pub fn add_file(&self, name: &str, data: &[u8], compress: bool) -> bool {
         (lib().add_file)(
@@ -219,6 +382,17 @@ impl ZipWriter {
         (lib().add_dir)(self.handle, name.as_ptr(), name.len() as u32) == 0
     }
 
+    /// Set the archive comment (at most 65535 bytes).
+    pub fn set_comment(&self, comment: &[u8]) -> bool {
+        (lib().set_comment)(self.handle, comment.as_ptr(), comment.len() as u32) == 0
+    }
+
+    /// Attach an extra-field record to the next file or directory added.
+    /// Call repeatedly to attach several records (65535 bytes in total).
+    pub fn add_extra(&self, header_id: u16, data: &[u8]) -> bool {
+        (lib().add_extra)(self.handle, header_id as u32, data.as_ptr(), data.len() as u32) == 0
+    }
+
     /// Finalize and write the archive to a file.
     /// Consumes the writer handle.
     pub fn write_to_file(self, path: &str) -> bool {
@@ -226,6 +400,17 @@ impl ZipWriter {
         core::mem::forget(self); // Handle already freed by write_to_file
         result
     }
+
+    /// Finalize the archive in memory and return its bytes.
+    pub fn into_bytes(self) -> Option<alloc::vec::Vec<u8>> {
+        let size = (lib().writer_get_data)(self.handle, core::ptr::null_mut(), 0);
+        if size == u32::MAX {
+            return None;
+        }
+        let mut buf = vec![0u8; size as usize];
+        (lib().writer_get_data)(self.handle, buf.as_mut_ptr(), size);
+        Some(buf)
+    }
 }
 
 impl Drop for ZipWriter {
@@ -364,3 +549,121 @@ impl Drop for TarWriter {
         }
     }
 }
+
+// ── Archive (any format) ────────────────────────────────────────────────────
+
+/// Container format detected by `Archive::open`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+    /// A single gzip-compressed file.
+    Gzip,
+    /// 7z (read-only).
+    SevenZ,
+}
+
+/// An entry returned by `Archive::next_entry`.
+pub struct ArchiveEntry {
+    pub name: String,
+    pub size: u64,
+    pub is_dir: bool,
+}
+
+/// An open archive of any supported format, read entry by entry.
+pub struct Archive {
+    handle: u32,
+}
+
+impl Archive {
+    /// Open an archive, detecting its format from the file's magic bytes.
+    pub fn open(path: &str) -> Option<Archive> {
+        let h = (lib().archive_open)(path.as_ptr(), path.len() as u32);
+        if h == 0 { None } else { Some(Archive { handle: h }) }
+    }
+
+    /// The detected container format.
+    pub fn format(&self) -> ArchiveFormat {
+        match (lib().archive_format)(self.handle) {
+            1 => ArchiveFormat::Zip,
+            2 => ArchiveFormat::Tar,
+            3 => ArchiveFormat::TarGz,
+            5 => ArchiveFormat::SevenZ,
+            _ => ArchiveFormat::Gzip,
+        }
+    }
+
+    /// Number of entries in the archive.
+    pub fn entry_count(&self) -> u32 {
+        (lib().archive_entry_count)(self.handle)
+    }
+
+    /// Advance to the next entry. Returns `None` after the last one.
+    pub fn next_entry(&mut self) -> Option<ArchiveEntry> {
+        let mut buf = [0u8; 512];
+        let mut size = 0u64;
+        let mut is_dir = 0u32;
+        let n = (lib().archive_next_entry)(self.handle, buf.as_mut_ptr(), 512, &mut size, &mut is_dir);
+        if n == u32::MAX {
+            return None;
+        }
+        let name = core::str::from_utf8(&buf[..n as usize]).unwrap_or("");
+        Some(ArchiveEntry { name: String::from(name), size, is_dir: is_dir != 0 })
+    }
+
+    /// Extract the current entry to `path` (directories are created).
+    pub fn extract_entry(&self, path: &str) -> bool {
+        (lib().archive_extract_entry)(self.handle, path.as_ptr(), path.len() as u32) == 0
+    }
+
+    /// Extract all remaining entries below `dest_dir`.
+    ///
+    /// Entries whose names would escape `dest_dir` are skipped.  Returns the
+    /// number of entries extracted, or `None` if any extraction failed.
+    pub fn extract_all(&mut self, dest_dir: &str) -> Option<u32> {
+        let mut count = 0;
+        let mut ok = true;
+        while let Some(entry) = self.next_entry() {
+            let path = match entry_path(dest_dir, &entry.name) {
+                Some(p) => p,
+                None => continue,
+            };
+            if self.extract_entry(&path) {
+                count += 1;
+            } else {
+                ok = false;
+            }
+        }
+        if ok { Some(count) } else { None }
+    }
+}
+
+impl Drop for Archive {
+    fn drop(&mut self) {
+        if self.handle != 0 {
+            (lib().archive_close)(self.handle);
+        }
+    }
+}
+
+/// Join an archive entry name onto `dest_dir`.
+///
+/// Leading slashes and `.` components are dropped; returns `None` for names
+/// that contain `..` or are empty, so extraction cannot escape `dest_dir`.
+pub fn entry_path(dest_dir: &str, name: &str) -> Option<String> {
+    let mut path = String::from(dest_dir.trim_end_matches('/'));
+    let mut any = false;
+    for part in name.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => return None,
+            _ => {
+                path.push('/');
+                path.push_str(part);
+                any = true;
+            }
+        }
+    }
+    if any { Some(path) } else { None }
+}
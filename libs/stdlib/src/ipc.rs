@@ -114,6 +114,14 @@ pub fn evt_chan_wait(channel_id: u32, sub_id: u32, timeout_ms: u32) -> u32 {
     syscall3(SYS_EVT_CHAN_WAIT, channel_id as u64, sub_id as u64, timeout_ms as u64)
 }
 
+/// Block until any of the `(channel_id, sub_id)` subscriptions has an event,
+/// or timeout. At most 16 subscriptions are considered.
+///
+/// Returns 1 if events are available, 0 on timeout/spurious wake.
+pub fn evt_chan_wait_any(subs: &[[u32; 2]], timeout_ms: u32) -> u32 {
+    syscall3(SYS_EVT_CHAN_WAIT_ANY, subs.as_ptr() as u64, subs.len() as u64, timeout_ms as u64)
+}
+
 // ─── Shared Memory ──────────────────────────────────────────────────
 
 /// Create a shared memory region. Returns shm_id (>0) or 0 on failure.
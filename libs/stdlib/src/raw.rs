@@ -257,6 +257,7 @@ pub(crate) const SYS_EVT_CHAN_UNSUBSCRIBE: u32 = 67;
 pub(crate) const SYS_EVT_CHAN_DESTROY: u32 = 68;
 pub(crate) const SYS_EVT_CHAN_EMIT_TO: u32 = 69;
 pub(crate) const SYS_EVT_CHAN_WAIT: u32 = 70;
+pub(crate) const SYS_EVT_CHAN_WAIT_ANY: u32 = 73;
 
 // =========================================================================
 // Raw syscall helpers (x86-64 SYSCALL instruction)
@@ -19,7 +19,14 @@ pub struct UisysExports {
     /// Font smoothing mode: 0 = none, 1 = greyscale AA (default), 2 = subpixel LCD.
     /// Written by compositor, read by libfont via volatile pointer.
     pub font_smoothing: u32,
-    pub _reserved: [u32; 3],
+    /// DPI scale factor in percent (100–300). Written by compositor.
+    pub scale: u32,
+    /// Accessibility flags: bit 0 = high contrast, bits [31:16] = default
+    /// UI zoom in percent (0 = 100%). Written by compositor.
+    pub accessibility: u32,
+    /// Clipboard sequence number, incremented by the compositor each time
+    /// the clipboard is set (0 = never set this session).
+    pub clipboard_seq: u32,
 
     // --- Label (4) ---
     pub label_render: extern "C" fn(u32, i32, i32, *const u8, u32, u32, u16, u8),
@@ -185,7 +192,9 @@ pub static UISYS_EXPORTS: UisysExports = UisysExports {
     num_exports: NUM_EXPORTS,
     theme: 0,
     font_smoothing: 1, // greyscale AA by default
-    _reserved: [0; 3],
+    scale: 100,
+    accessibility: 0,
+    clipboard_seq: 0,
 
     // Label
     label_render: label::label_render,
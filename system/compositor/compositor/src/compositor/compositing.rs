@@ -78,7 +78,7 @@ impl Compositor {
         // Disabled in GMR mode: RECT_COPY operates on the back buffer (registered as GPU
         // framebuffer), which corrupts freshly composited content. Since flush_region is
         // already a no-op in GMR mode, there's no VRAM memcpy cost to optimize away.
-        if self.gpu_accel && !self.hw_double_buffer && !self.gmr_active {
+        if self.gpu_accel && !self.hw_double_buffer && !self.gmr_active && self.snap_preview.is_none() {
             if let Some(ref h) = hint {
                 if let Some(moved_idx) = self.layer_index(h.layer_id) {
                     let layer = &self.layers[moved_idx];
@@ -95,11 +95,19 @@ impl Compositor {
         for i in 0..damage_len {
             let rect = self.compositing_damage[i];
             self.composite_rect(&rect);
+            // Blend right after compositing so overlapping damage rects
+            // never blend the same pixel twice.
+            if let Some(preview) = self.snap_preview {
+                self.blend_snap_preview(&rect, &preview);
+            }
         }
 
         if let Some(outline) = self.resize_outline {
             self.draw_outline_to_bb(&outline);
         }
+        if let Some(preview) = self.snap_preview {
+            self.draw_outline_to_bb(&preview);
+        }
 
         if self.hw_double_buffer {
             let back_offset = if self.current_page == 0 {
@@ -198,6 +206,8 @@ impl Compositor {
             if !need_full_flush {
                 for li in (moved_idx + 1)..self.layers.len() {
                     if !self.layers[li].visible { continue; }
+                    // Sub-surfaces moved with the window and were copied along.
+                    if self.layers[li].parent == hint.layer_id { continue; }
                     let above_bounds = self.layers[li].damage_bounds();
                     if old_b.intersect(&above_bounds).is_some()
                         || new_b.intersect(&above_bounds).is_some()
@@ -544,6 +554,23 @@ impl Compositor {
     }
 
     /// Draw resize outline rectangle into back buffer.
+    /// Tint the part of `rect` covered by the snap preview.
+    fn blend_snap_preview(&mut self, rect: &Rect, preview: &Rect) {
+        let Some(r) = rect.intersect(preview) else { return };
+        let r = r.clip_to_screen(self.fb_width, self.fb_height);
+        let bb_stride = self.fb_width as usize;
+        let tint = 0x404A9EFF; // Translucent blue
+        for y in r.y..r.bottom() {
+            let row = y as usize * bb_stride;
+            for x in r.x..r.right() {
+                let di = row + x as usize;
+                if di < self.back_buffer.len() {
+                    self.back_buffer[di] = super::blend::alpha_blend(tint, self.back_buffer[di]);
+                }
+            }
+        }
+    }
+
     fn draw_outline_to_bb(&mut self, outline: &Rect) {
         let bb_stride = self.fb_width as usize;
         let color = 0xFF4A9EFF; // Blue outline
@@ -66,6 +66,11 @@ pub struct Layer {
     /// DPI-aware flag: true if the app renders at physical resolution (libanyui windows).
     /// false if the app renders at logical resolution and compositor must upscale.
     pub dpi_aware: bool,
+    /// Layer this one is attached to (0 = top-level). Child layers sit
+    /// directly above their parent and move and restack with it.
+    pub parent: u32,
+    /// Visible part of the layer in layer-local coordinates (None = all).
+    pub clip: Option<Rect>,
 }
 
 impl Layer {
@@ -79,8 +84,15 @@ impl Layer {
         }
     }
 
+    /// Screen rect the layer covers, reduced to its clip rect if it has one.
     pub fn bounds(&self) -> Rect {
-        Rect::new(self.x, self.y, self.width, self.height)
+        let full = Rect::new(self.x, self.y, self.width, self.height);
+        match self.clip {
+            Some(c) => full
+                .intersect(&Rect::new(self.x + c.x, self.y + c.y, c.width, c.height))
+                .unwrap_or(Rect::new(self.x, self.y, 0, 0)),
+            None => full,
+        }
     }
 
     /// Bounds including shadow (spread on all sides + vertical offset).
@@ -56,6 +56,9 @@ pub struct Compositor {
     /// Resize outline (drawn as overlay during resize operations)
     pub resize_outline: Option<Rect>,
 
+    /// Snap preview (translucent overlay while dragging a window to an edge)
+    pub snap_preview: Option<Rect>,
+
     /// The currently focused layer (gets stronger shadow)
     pub focused_layer_id: Option<u32>,
 
@@ -99,6 +102,7 @@ impl Compositor {
             gpu_cmds: Vec::with_capacity(32),
             hw_cursor: false,
             resize_outline: None,
+            snap_preview: None,
             focused_layer_id: None,
             accel_move_hint: None,
             vram_allocator: None,
@@ -142,6 +146,8 @@ impl Compositor {
             is_vram: false,
             vram_y: 0,
             dpi_aware: false,
+            parent: 0,
+            clip: None,
         });
         id
     }
@@ -177,6 +183,8 @@ impl Compositor {
             is_vram: false,
             vram_y: 0,
             dpi_aware: false,
+            parent: 0,
+            clip: None,
         });
         id
     }
@@ -214,6 +222,8 @@ impl Compositor {
             is_vram: false,
             vram_y: 0,
             dpi_aware: false,
+            parent: 0,
+            clip: None,
         });
         id
     }
@@ -230,6 +240,51 @@ impl Compositor {
                 }
             }
             self.layers.remove(idx);
+            // Child layers go with their parent.
+            while let Some(ci) = self.layers.iter().position(|l| l.parent == id) {
+                self.damage.push(self.layers[ci].damage_bounds());
+                self.layers.remove(ci);
+            }
+        }
+    }
+
+    /// Add an opaque SHM layer attached to `parent` (see [`Layer::parent`]).
+    /// It starts hidden, stacked above the parent's existing children;
+    /// position it with [`place_child_layer`](Self::place_child_layer).
+    pub fn add_child_shm_layer(
+        &mut self,
+        parent: u32,
+        w: u32,
+        h: u32,
+        shm_id: u32,
+        shm_ptr: *mut u32,
+    ) -> Option<u32> {
+        let pidx = self.layer_index(parent)?;
+        let (x, y) = (self.layers[pidx].x, self.layers[pidx].y);
+        let id = self.add_shm_layer(x, y, w, h, true, shm_id, shm_ptr);
+        let mut layer = self.layers.pop()?;
+        layer.parent = parent;
+        layer.visible = false;
+        let pos = pidx + 1 + self.child_count(parent);
+        self.layers.insert(pos, layer);
+        Some(id)
+    }
+
+    /// Number of layers attached to `parent`. They always directly follow it.
+    fn child_count(&self, parent: u32) -> usize {
+        self.layers.iter().filter(|l| l.parent == parent).count()
+    }
+
+    /// Set a child layer's screen position and clip rect (layer-local).
+    pub fn place_child_layer(&mut self, id: u32, x: i32, y: i32, clip: Option<Rect>) {
+        if let Some(idx) = self.layer_index(id) {
+            let old_bounds = self.layers[idx].damage_bounds();
+            self.layers[idx].x = x;
+            self.layers[idx].y = y;
+            self.layers[idx].clip = clip;
+            let new_bounds = self.layers[idx].damage_bounds();
+            self.damage.push(old_bounds);
+            self.damage.push(new_bounds);
         }
     }
 
@@ -266,6 +321,8 @@ impl Compositor {
             is_vram: true,
             vram_y: alloc.vram_y,
             dpi_aware: false,
+            parent: 0,
+            clip: None,
         });
         Some(id)
     }
@@ -315,9 +372,17 @@ impl Compositor {
     pub fn move_layer(&mut self, id: u32, new_x: i32, new_y: i32) {
         if let Some(idx) = self.layer_index(id) {
             let old_bounds = self.layers[idx].damage_bounds();
+            let dx = new_x - self.layers[idx].x;
+            let dy = new_y - self.layers[idx].y;
             self.layers[idx].x = new_x;
             self.layers[idx].y = new_y;
             let new_bounds = self.layers[idx].damage_bounds();
+            // Child layers ride along. They are clipped to the parent, so
+            // the parent's damage covers them.
+            for child in self.layers.iter_mut().filter(|l| l.parent == id) {
+                child.x += dx;
+                child.y += dy;
+            }
 
             if self.gpu_accel {
                 // Coalesce: keep first old_bounds, update last new_bounds
@@ -340,13 +405,14 @@ impl Compositor {
         }
     }
 
-    /// Bring a layer to the top of the z-order.
+    /// Bring a layer (and its child layers) to the top of the z-order.
     pub fn raise_layer(&mut self, id: u32) {
         if let Some(idx) = self.layer_index(id) {
-            if idx < self.layers.len() - 1 {
-                let layer = self.layers.remove(idx);
-                let bounds = layer.damage_bounds();
-                self.layers.push(layer);
+            let end = idx + 1 + self.child_count(id);
+            if end < self.layers.len() {
+                let bounds = self.layers[idx].damage_bounds();
+                let group: Vec<Layer> = self.layers.drain(idx..end).collect();
+                self.layers.extend(group);
                 self.damage.push(bounds);
             }
         }
@@ -338,6 +338,74 @@ pub fn save_scale_factor(percent: u32) {
     }
 }
 
+// ── Accessibility ───────────────────────────────────────────────────────────
+
+/// Read the `[accessibility]` section (`high_contrast=`, `zoom=`).
+///
+/// Returns `(high_contrast, zoom_percent)`, or `None` if the section is absent.
+pub fn read_accessibility() -> Option<(bool, u32)> {
+    let text = read_conf()?;
+    let mut in_section = false;
+    let mut found = false;
+    let mut high_contrast = false;
+    let mut zoom = 100;
+
+    for line in text.split('\n') {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            in_section = line == "[accessibility]";
+            found |= in_section;
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        if let Some(val) = line.strip_prefix("high_contrast=") {
+            high_contrast = val.trim() == "1";
+        } else if let Some(val) = line.strip_prefix("zoom=") {
+            if let Ok(v) = val.trim().parse::<u32>() {
+                zoom = v;
+            }
+        }
+    }
+
+    if found { Some((high_contrast, zoom)) } else { None }
+}
+
+/// Save accessibility preferences to the `[accessibility]` section of
+/// compositor.conf, replacing the section if it exists.
+pub fn save_accessibility(high_contrast: bool, zoom: u32) {
+    use anyos_std::fs;
+
+    let old_text = read_conf().unwrap_or_default();
+    let mut result = alloc::string::String::with_capacity(old_text.len() + 64);
+    let mut in_section = false;
+
+    for line in old_text.split('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_section = trimmed == "[accessibility]";
+        }
+        if in_section {
+            continue;
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+
+    let mut text = alloc::string::String::from(result.trim_end());
+    text.push_str("\n\n[accessibility]\n");
+    text.push_str(&alloc::format!("high_contrast={}\n", if high_contrast { 1 } else { 0 }));
+    text.push_str(&alloc::format!("zoom={}\n", zoom));
+
+    if fs::write_bytes(CONF_PATH, text.as_bytes()).is_err() {
+        println!("compositor: FAILED to save compositor.conf (accessibility)");
+    }
+}
+
 /// Saved theme preference from `[theme]` section.
 pub struct SavedTheme {
     /// `"dark"` or `"light"`.
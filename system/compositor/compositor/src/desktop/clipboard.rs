@@ -0,0 +1,122 @@
+//! Clipboard — current contents, change notifications and clip history.
+//!
+//! Every CMD_SET_CLIPBOARD increments the clipboard sequence number, which
+//! is published in the shared uisys page (apps compare it to detect changes
+//! without IPC) and broadcast with EVT_CLIPBOARD_CHANGED. The most recent
+//! clips are kept, newest first, for the clipboard manager to list with
+//! CMD_GET_CLIPBOARD_HISTORY.
+//!
+//! The history holds whatever the user copied, passwords included, so only
+//! root and the threads named in `HISTORY_READERS` may read it. Everything
+//! is dropped on logout.
+
+use alloc::vec::Vec;
+
+use crate::ipc_protocol as proto;
+
+use super::theme;
+use super::Desktop;
+
+/// Most clips kept in the history.
+const HISTORY_LEN: usize = 32;
+
+/// Most bytes kept in the history (oldest clips are dropped first).
+const HISTORY_BYTES: usize = 512 * 1024;
+
+/// Thread names allowed to read the history besides root.
+const HISTORY_READERS: &[&[u8]] = &[b"Clipboard Manager"];
+
+/// One clip of the history.
+pub(crate) struct ClipEntry {
+    pub data: Vec<u8>,
+    pub format: u32,
+    /// Clipboard sequence number the clip was set with.
+    pub seq: u32,
+}
+
+impl Desktop {
+    /// Replace the clipboard contents, record them in the history and
+    /// publish the new sequence number. Returns the EVT_CLIPBOARD_CHANGED
+    /// event to broadcast.
+    pub(crate) fn set_clipboard(&mut self, data: Vec<u8>, format: u32) -> [u32; 5] {
+        self.clipboard_seq = self.clipboard_seq.wrapping_add(1).max(1);
+        theme::set_clipboard_seq(self.clipboard_seq);
+
+        // Copying the same clip again moves it to the front.
+        self.clipboard_history.retain(|e| !(e.format == format && e.data == data));
+        self.clipboard_history.insert(0, ClipEntry { data: data.clone(), format, seq: self.clipboard_seq });
+        self.clipboard_history.truncate(HISTORY_LEN);
+        let mut total = 0;
+        let keep = self.clipboard_history.iter()
+            .take_while(|e| { total += e.data.len(); total <= HISTORY_BYTES })
+            .count()
+            .max(1);
+        self.clipboard_history.truncate(keep);
+
+        let len = data.len() as u32;
+        self.clipboard_data = data;
+        self.clipboard_format = format;
+        [proto::EVT_CLIPBOARD_CHANGED, self.clipboard_seq, len, format, 0]
+    }
+
+    /// Clear the clipboard and its history (logout). The sequence number
+    /// keeps counting so apps still see the change.
+    pub(crate) fn clear_clipboard(&mut self) {
+        self.clipboard_data.clear();
+        self.clipboard_format = 0;
+        self.clipboard_history.clear();
+        self.clipboard_seq = self.clipboard_seq.wrapping_add(1).max(1);
+        theme::set_clipboard_seq(self.clipboard_seq);
+    }
+
+    /// Handle CMD_GET_CLIPBOARD_HISTORY: copy history entry `index` into the
+    /// requester's SHM. Answers with len = 0 if there is no such entry or the
+    /// requester may not read the history.
+    pub(crate) fn handle_get_clipboard_history(&mut self, cmd: &[u32; 5]) -> Option<(Option<u32>, [u32; 5])> {
+        let shm_id = cmd[1];
+        let capacity = cmd[2] as usize;
+        let requester_tid = cmd[3];
+        let index = cmd[4] as usize;
+        let target = self.get_sub_id_for_tid(requester_tid);
+        let empty = [proto::RESP_CLIPBOARD_HISTORY, shm_id, 0, 0, 0];
+
+        if !may_read_clipboard_history(requester_tid) {
+            anyos_std::println!("[clipboard] HISTORY refused for tid={}", requester_tid);
+            return Some((target, empty));
+        }
+        let entry = match self.clipboard_history.get(index) {
+            Some(e) if shm_id != 0 && capacity != 0 => e,
+            _ => return Some((target, empty)),
+        };
+        let shm_addr = anyos_std::ipc::shm_map(shm_id);
+        if shm_addr == 0 {
+            return Some((target, empty));
+        }
+        let copy_len = entry.data.len().min(capacity);
+        let dst = unsafe { core::slice::from_raw_parts_mut(shm_addr as *mut u8, copy_len) };
+        dst.copy_from_slice(&entry.data[..copy_len]);
+        anyos_std::ipc::shm_unmap(shm_id);
+        Some((target, [proto::RESP_CLIPBOARD_HISTORY, shm_id, copy_len as u32, entry.format, entry.seq]))
+    }
+}
+
+/// Whether thread `tid` may read the clipboard history: it runs as root or
+/// is one of `HISTORY_READERS` (looked up in the kernel's thread list).
+fn may_read_clipboard_history(tid: u32) -> bool {
+    const ENTRY: usize = 60;
+    let mut buf = [0u8; ENTRY * 128];
+    let count = anyos_std::sys::sysinfo(1, &mut buf);
+    if count == u32::MAX {
+        return false;
+    }
+    buf.chunks_exact(ENTRY).take(count as usize).any(|e| {
+        let entry_tid = u32::from_le_bytes([e[0], e[1], e[2], e[3]]);
+        if entry_tid != tid {
+            return false;
+        }
+        let uid = u16::from_le_bytes([e[56], e[57]]);
+        let name = &e[8..32];
+        let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+        uid == 0 || HISTORY_READERS.contains(&name)
+    })
+}
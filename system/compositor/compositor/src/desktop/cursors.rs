@@ -46,6 +46,23 @@ pub enum CursorShape {
     ResizeNWSE,
     ResizeNESW,
     Move,
+    IBeam,
+    Hand,
+    Busy,
+}
+
+impl CursorShape {
+    /// Map a CMD_SET_CURSOR shape value (unknown values → Arrow).
+    pub fn from_ipc(v: u32) -> Self {
+        match v {
+            2 => CursorShape::IBeam,
+            3 => CursorShape::Hand,
+            4 => CursorShape::ResizeEW,
+            5 => CursorShape::ResizeNS,
+            6 => CursorShape::Busy,
+            _ => CursorShape::Arrow,
+        }
+    }
 }
 
 // ── Arrow Cursor ───────────────────────────────────────────────────────────
@@ -202,6 +219,88 @@ static HW_MOVE: [u32; (15 * 15) as usize] = [
     T,T,T,T,T,T,B,B,B,T,T,T,T,T,T,
 ];
 
+// ── I-Beam (Text) Cursor ───────────────────────────────────────────────────
+
+const HW_IBEAM_W: u32 = 7;
+const HW_IBEAM_H: u32 = 16;
+const HW_IBEAM_HOT_X: u32 = 3;
+const HW_IBEAM_HOT_Y: u32 = 8;
+
+#[rustfmt::skip]
+static HW_IBEAM: [u32; (7 * 16) as usize] = [
+    B,B,B,T,B,B,B,
+    B,W,W,B,W,W,B,
+    B,B,B,W,B,B,B,
+    T,T,B,W,B,T,T,
+    T,T,B,W,B,T,T,
+    T,T,B,W,B,T,T,
+    T,T,B,W,B,T,T,
+    T,T,B,W,B,T,T,
+    T,T,B,W,B,T,T,
+    T,T,B,W,B,T,T,
+    T,T,B,W,B,T,T,
+    T,T,B,W,B,T,T,
+    T,T,B,W,B,T,T,
+    B,B,B,W,B,B,B,
+    B,W,W,B,W,W,B,
+    B,B,B,T,B,B,B,
+];
+
+// ── Hand (Link) Cursor ─────────────────────────────────────────────────────
+
+const HW_HAND_W: u32 = 14;
+const HW_HAND_H: u32 = 17;
+const HW_HAND_HOT_X: u32 = 4;
+const HW_HAND_HOT_Y: u32 = 0;
+
+#[rustfmt::skip]
+static HW_HAND: [u32; (14 * 17) as usize] = [
+    T,T,T,T,B,B,T,T,T,T,T,T,T,T,
+    T,T,T,B,W,W,B,T,T,T,T,T,T,T,
+    T,T,T,B,W,W,B,T,T,T,T,T,T,T,
+    T,T,T,B,W,W,B,T,T,T,T,T,T,T,
+    T,T,T,B,W,W,B,B,B,T,T,T,T,T,
+    T,T,T,B,W,W,B,W,W,B,B,T,T,T,
+    T,T,T,B,W,W,B,W,W,B,W,B,B,T,
+    B,B,T,B,W,W,B,W,W,B,W,B,W,B,
+    B,W,B,B,W,W,W,W,W,W,W,B,W,B,
+    B,W,W,B,W,W,W,W,W,W,W,W,W,B,
+    T,B,W,W,W,W,W,W,W,W,W,W,W,B,
+    T,T,B,W,W,W,W,W,W,W,W,W,W,B,
+    T,T,B,W,W,W,W,W,W,W,W,W,B,T,
+    T,T,T,B,W,W,W,W,W,W,W,W,B,T,
+    T,T,T,T,B,W,W,W,W,W,W,B,T,T,
+    T,T,T,T,B,W,W,W,W,W,W,B,T,T,
+    T,T,T,T,B,B,B,B,B,B,B,B,T,T,
+];
+
+// ── Busy (Hourglass) Cursor ────────────────────────────────────────────────
+
+const HW_BUSY_W: u32 = 11;
+const HW_BUSY_H: u32 = 16;
+const HW_BUSY_HOT_X: u32 = 5;
+const HW_BUSY_HOT_Y: u32 = 8;
+
+#[rustfmt::skip]
+static HW_BUSY: [u32; (11 * 16) as usize] = [
+    B,B,B,B,B,B,B,B,B,B,B,
+    B,W,W,W,W,W,W,W,W,W,B,
+    T,B,B,B,B,B,B,B,B,B,T,
+    T,B,W,W,W,W,W,W,W,B,T,
+    T,B,W,B,B,B,B,B,W,B,T,
+    T,T,B,W,B,B,B,W,B,T,T,
+    T,T,T,B,W,B,W,B,T,T,T,
+    T,T,T,T,B,W,B,T,T,T,T,
+    T,T,T,T,B,W,B,T,T,T,T,
+    T,T,T,B,W,W,W,B,T,T,T,
+    T,T,B,W,W,B,W,W,B,T,T,
+    T,B,W,W,B,B,B,W,W,B,T,
+    T,B,W,B,B,B,B,B,W,B,T,
+    T,B,B,B,B,B,B,B,B,B,T,
+    B,W,W,W,W,W,W,W,W,W,B,
+    B,B,B,B,B,B,B,B,B,B,B,
+];
+
 // ── Desktop Cursor Methods ─────────────────────────────────────────────────
 
 impl Desktop {
@@ -363,6 +462,33 @@ impl Desktop {
                     &HW_MOVE,
                 );
             }
+            CursorShape::IBeam => {
+                self.compositor.define_hw_cursor(
+                    HW_IBEAM_W,
+                    HW_IBEAM_H,
+                    HW_IBEAM_HOT_X,
+                    HW_IBEAM_HOT_Y,
+                    &HW_IBEAM,
+                );
+            }
+            CursorShape::Hand => {
+                self.compositor.define_hw_cursor(
+                    HW_HAND_W,
+                    HW_HAND_H,
+                    HW_HAND_HOT_X,
+                    HW_HAND_HOT_Y,
+                    &HW_HAND,
+                );
+            }
+            CursorShape::Busy => {
+                self.compositor.define_hw_cursor(
+                    HW_BUSY_W,
+                    HW_BUSY_H,
+                    HW_BUSY_HOT_X,
+                    HW_BUSY_HOT_Y,
+                    &HW_BUSY,
+                );
+            }
         }
         // Re-assert cursor position after shape change to ensure visibility.
         // Without this, some GPU backends (VirtIO, VMware SVGA) may briefly
@@ -372,6 +498,21 @@ impl Desktop {
         self.compositor.flush_gpu();
     }
 
+    /// Cursor shape for the mouse position: resize edges of the topmost
+    /// window under the cursor, or the shape its app requested over content.
+    pub(crate) fn cursor_at(&self, mx: i32, my: i32) -> CursorShape {
+        for w in self.windows.iter().rev() {
+            let hit = w.hit_test(mx, my);
+            if hit == super::window::HitTest::Content {
+                return w.cursor;
+            }
+            if hit != super::window::HitTest::None {
+                return self.cursor_for_hit(hit);
+            }
+        }
+        CursorShape::Arrow
+    }
+
     /// Determine the correct cursor shape from a HitTest result.
     pub(crate) fn cursor_for_hit(&self, hit: super::window::HitTest) -> CursorShape {
         match hit {
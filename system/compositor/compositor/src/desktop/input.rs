@@ -128,15 +128,42 @@ impl Desktop {
         // Handle window drag — clamp Y so windows can never go under the menubar.
         if let Some(ref drag) = self.dragging {
             let win_id = drag.window_id;
-            let new_x = self.mouse_x - drag.offset_x;
+            let mut offset_x = drag.offset_x;
+            let old_region = drag.snap_region;
+            let mut resizable = false;
+            if let Some(idx) = self.windows.iter().position(|w| w.id == win_id) {
+                resizable = self.windows[idx].is_resizable();
+                // Dragging a snapped window out restores its floating size,
+                // keeping the cursor at the same relative spot on the title bar.
+                if self.windows[idx].snapped != SNAP_NONE {
+                    let old_w = self.windows[idx].content_width.max(1);
+                    self.snap_window(win_id, SNAP_NONE);
+                    let new_w = self.windows[idx].content_width;
+                    offset_x = (offset_x as i64 * new_w as i64 / old_w as i64) as i32;
+                    if let Some(d) = self.dragging.as_mut() {
+                        d.offset_x = offset_x;
+                    }
+                }
+            }
+            let offset_y = self.dragging.as_ref().map_or(0, |d| d.offset_y);
+            let new_x = self.mouse_x - offset_x;
             let min_y = menubar_height() as i32 + 1;
-            let new_y = (self.mouse_y - drag.offset_y).max(min_y);
+            let new_y = (self.mouse_y - offset_y).max(min_y);
             if let Some(idx) = self.windows.iter().position(|w| w.id == win_id) {
                 let layer_id = self.windows[idx].layer_id;
                 self.windows[idx].x = new_x;
                 self.windows[idx].y = new_y;
                 self.compositor.move_layer(layer_id, new_x, new_y);
             }
+
+            // Snap preview when the cursor reaches a screen edge
+            let region = if resizable { self.snap_region_at(self.mouse_x, self.mouse_y) } else { SNAP_NONE };
+            if region != old_region {
+                if let Some(d) = self.dragging.as_mut() {
+                    d.snap_region = region;
+                }
+                self.set_snap_preview(region);
+            }
         }
 
         // Handle resize (show outline)
@@ -240,16 +267,7 @@ impl Desktop {
         } else if self.resizing.is_some() {
             // Keep current resize cursor
         } else {
-            let mx = self.mouse_x;
-            let my = self.mouse_y;
-            let mut shape = CursorShape::Arrow;
-            for w in self.windows.iter().rev() {
-                let hit = w.hit_test(mx, my);
-                if hit != HitTest::None {
-                    shape = self.cursor_for_hit(hit);
-                    break;
-                }
-            }
+            let shape = self.cursor_at(self.mouse_x, self.mouse_y);
             self.set_cursor_shape(shape);
         }
 
@@ -503,6 +521,7 @@ impl Desktop {
                                     window_id: win_id,
                                     offset_x: mx - self.windows[idx].x,
                                     offset_y: my - self.windows[idx].y,
+                                    snap_region: SNAP_NONE,
                                 });
                                 let layer_id = self.windows[idx].layer_id;
                                 let old_shadow = {
@@ -684,7 +703,13 @@ impl Desktop {
                 }
                 self.set_cursor_shape(CursorShape::Arrow);
             }
-            self.dragging = None;
+            // Released over a screen edge — tile the window
+            if let Some(drag) = self.dragging.take() {
+                if drag.snap_region != SNAP_NONE {
+                    self.set_snap_preview(SNAP_NONE);
+                    self.snap_window(drag.window_id, drag.snap_region);
+                }
+            }
 
             // End resize — apply final size
             if let Some(resize) = self.resizing.take() {
@@ -732,6 +757,15 @@ impl Desktop {
                         win_id,
                         [EVENT_RESIZE, nw, content_h, 0, 0],
                     );
+                    // A manual resize leaves the tiled layout.
+                    if self.windows[idx].snapped != SNAP_NONE {
+                        self.windows[idx].snapped = SNAP_NONE;
+                        self.windows[idx].snap_restore = None;
+                        self.push_event(
+                            win_id,
+                            [EVENT_WINDOW_SNAPPED, SNAP_NONE, nw, content_h, 0],
+                        );
+                    }
                 }
             }
 
@@ -7,6 +7,7 @@ use crate::compositor::Rect;
 use crate::ipc_protocol as proto;
 use crate::menu::MenuBarDef;
 
+use super::cursors::CursorShape;
 use super::window::*;
 use super::Desktop;
 
@@ -38,6 +39,7 @@ impl Desktop {
                     EVENT_STATUS_ICON_CLICK => proto::EVT_STATUS_ICON_CLICK,
                     EVENT_MOUSE_MOVE => proto::EVT_MOUSE_MOVE,
                     EVENT_FOCUS_LOST => proto::EVT_FOCUS_LOST,
+                    EVENT_WINDOW_SNAPPED => proto::EVT_WINDOW_SNAPPED,
                     _ => continue,
                 };
                 out.push((target_sub, [ipc_type, win.id, evt[1], evt[2], evt[3]]));
@@ -153,6 +155,10 @@ impl Desktop {
                 }
                 None
             }
+            proto::CMD_SNAP_WINDOW => {
+                self.snap_window(cmd[1], cmd[2]);
+                None
+            }
             proto::CMD_SET_MENU => {
                 let window_id = cmd[1];
                 let shm_id = cmd[2];
@@ -303,6 +309,7 @@ impl Desktop {
                             self.compositor.move_layer(layer_id, sx, sy);
                         }
                     }
+                    self.restore_owned_group(win_id);
                     self.focus_window(win_id);
                 }
                 None
@@ -338,6 +345,43 @@ impl Desktop {
                 self.minimize_window(window_id);
                 None
             }
+            proto::CMD_SET_WINDOW_OWNER => {
+                self.set_window_owner(cmd[1], cmd[2]);
+                None
+            }
+            proto::CMD_SET_ALWAYS_ON_TOP => {
+                self.set_always_on_top(cmd[1], cmd[2] != 0);
+                None
+            }
+            proto::CMD_PLACE_SURFACE => {
+                self.place_surface(cmd);
+                None
+            }
+            proto::CMD_PRESENT_SURFACE => {
+                self.present_surface(cmd[1]);
+                None
+            }
+            proto::CMD_DETACH_SURFACE => {
+                self.detach_surface(cmd[1]);
+                None
+            }
+            proto::CMD_SET_DOCK_BADGE => {
+                // Relay to the dock as a broadcast event.
+                let app_tid = cmd[1];
+                let count = cmd[2];
+                Some((None, [proto::EVT_DOCK_BADGE, app_tid, count, 0, 0]))
+            }
+            proto::CMD_SET_CURSOR => {
+                let window_id = cmd[1];
+                if let Some(w) = self.windows.iter_mut().find(|w| w.id == window_id) {
+                    w.cursor = CursorShape::from_ipc(cmd[2]);
+                }
+                if self.dragging.is_none() && self.resizing.is_none() {
+                    let shape = self.cursor_at(self.mouse_x, self.mouse_y);
+                    self.set_cursor_shape(shape);
+                }
+                None
+            }
             proto::CMD_SET_BLUR_BEHIND => {
                 let window_id = cmd[1];
                 let radius = cmd[2];
@@ -390,13 +434,13 @@ impl Desktop {
                 let data = unsafe {
                     core::slice::from_raw_parts(shm_addr as *const u8, len)
                 };
-                self.clipboard_data = data.to_vec();
-                self.clipboard_format = format;
+                let evt = self.set_clipboard(data.to_vec(), format);
                 anyos_std::ipc::shm_unmap(shm_id);
                 let preview_len = len.min(40);
                 let preview = core::str::from_utf8(&self.clipboard_data[..preview_len]).unwrap_or("(binary)");
-                anyos_std::println!("[clipboard] SET ok: {} bytes, preview='{}'", len, preview);
-                None
+                anyos_std::println!("[clipboard] SET ok: {} bytes, seq={}, preview='{}'", len, evt[1], preview);
+                // Broadcast so every app (and the clipboard manager) sees the change.
+                Some((None, evt))
             }
             proto::CMD_GET_CLIPBOARD => {
                 let shm_id = cmd[1];
@@ -425,6 +469,7 @@ impl Desktop {
                 anyos_std::println!("[clipboard] GET: stored={} bytes, returning {} to tid={}", self.clipboard_data.len(), copy_len, requester_tid);
                 Some((target, [proto::RESP_CLIPBOARD_DATA, shm_id, copy_len as u32, self.clipboard_format, requester_tid]))
             }
+            proto::CMD_GET_CLIPBOARD_HISTORY => self.handle_get_clipboard_history(cmd),
             proto::CMD_SET_WALLPAPER => {
                 let shm_id = cmd[1];
                 if shm_id == 0 {
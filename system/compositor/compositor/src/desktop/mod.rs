@@ -1,11 +1,13 @@
 //! Desktop manager — coordinates window management, menubar, wallpaper, cursor, and input.
 
+pub mod clipboard;
 pub mod crash_dialog;
 pub mod cursors;
 pub mod desktop_icons;
 pub mod drawing;
 pub mod input;
 pub mod ipc;
+pub mod subsurface;
 pub mod theme;
 pub mod volume_hud;
 pub mod window;
@@ -136,6 +138,10 @@ pub struct Desktop {
     pub(crate) clipboard_data: Vec<u8>,
     /// Clipboard format: 0 = text/plain, 1 = text/uri-list.
     pub(crate) clipboard_format: u32,
+    /// Clipboard sequence number, incremented on every change.
+    pub(crate) clipboard_seq: u32,
+    /// Recent clips, newest first.
+    pub(crate) clipboard_history: Vec<clipboard::ClipEntry>,
     /// Active crash dialogs (internal windows showing crash info).
     pub(crate) crash_dialogs: Vec<crash_dialog::CrashDialog>,
     /// Volume HUD overlay (centered-bottom).
@@ -146,6 +152,8 @@ pub struct Desktop {
     /// Frame ACK queue: (sub_id, window_id) pairs to emit after compose.
     /// Populated during compose(), drained by render thread via evt_chan_emit_to.
     pub(crate) frame_ack_queue: Vec<(u32, u32)>,
+    /// App buffers composited inside windows (CMD_ATTACH_SURFACE).
+    pub(crate) subsurfaces: Vec<subsurface::SubSurface>,
 
     /// Set to true when the user selects "Log Out" from the system menu.
     /// The management loop checks this flag and initiates the logout sequence.
@@ -227,11 +235,14 @@ impl Desktop {
             wallpaper_path_len: 0,
             clipboard_data: Vec::new(),
             clipboard_format: 0,
+            clipboard_seq: 0,
+            clipboard_history: Vec::new(),
             crash_dialogs: Vec::new(),
             volume_hud: volume_hud::VolumeHud::new(),
             cascade_x: 120,
             cascade_y: menubar_height() as i32 + 50,
             frame_ack_queue: Vec::new(),
+            subsurfaces: Vec::new(),
             logout_requested: false,
             shutdown_mode: 0,
             logo_white: Vec::new(),
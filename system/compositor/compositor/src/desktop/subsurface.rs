@@ -0,0 +1,108 @@
+//! Sub-surfaces — app-owned SHM buffers composited inside a window.
+//!
+//! A sub-surface is a child layer of a window's layer whose pixels are read
+//! straight from an SHM buffer of the app, so heavy content (GL output, VM
+//! displays) reaches the screen without being copied into the window's own
+//! buffer first. The app attaches it (CMD_ATTACH_SURFACE), positions it in
+//! content coordinates (CMD_PLACE_SURFACE) and flags new frames
+//! (CMD_PRESENT_SURFACE). The compositor keeps it stacked directly above
+//! the window, moves it with the window and clips it to the content area.
+
+use crate::compositor::Rect;
+
+use super::window::title_bar_height;
+use super::Desktop;
+
+/// One attached sub-surface.
+pub(crate) struct SubSurface {
+    /// SHM region of the app's buffer; also identifies the sub-surface.
+    pub shm_id: u32,
+    pub window_id: u32,
+    pub layer_id: u32,
+}
+
+impl Desktop {
+    /// Handle CMD_ATTACH_SURFACE with a pre-mapped SHM address.
+    pub fn handle_attach_surface_pre_mapped(&mut self, cmd: &[u32; 5], shm_addr: usize) {
+        let window_id = cmd[1];
+        let shm_id = cmd[2];
+        let width = cmd[3] >> 16;
+        let height = cmd[3] & 0xFFFF;
+
+        if shm_addr == 0 {
+            return;
+        }
+        let win_layer = self.windows.iter().find(|w| w.id == window_id).map(|w| w.layer_id);
+        let attached = self.subsurfaces.iter().any(|s| s.shm_id == shm_id);
+        let layer_id = match win_layer {
+            Some(parent) if !attached && width != 0 && height != 0 => self
+                .compositor
+                .add_child_shm_layer(parent, width, height, shm_id, shm_addr as *mut u32),
+            _ => None,
+        };
+        match layer_id {
+            Some(layer_id) => self.subsurfaces.push(SubSurface { shm_id, window_id, layer_id }),
+            None => {
+                anyos_std::ipc::shm_unmap(shm_id);
+            }
+        }
+    }
+
+    /// Handle CMD_PLACE_SURFACE.
+    pub fn place_surface(&mut self, cmd: &[u32; 5]) {
+        let Some(sub) = self.subsurfaces.iter().find(|s| s.shm_id == cmd[1]) else { return };
+        let layer_id = sub.layer_id;
+        let Some(win) = self.windows.iter().find(|w| w.id == sub.window_id) else { return };
+        let Some(win_layer) = self.compositor.get_layer(win.layer_id) else { return };
+
+        let x = (cmd[2] >> 16) as i16 as i32;
+        let y = cmd[2] as i16 as i32;
+        let clip = Rect::new(
+            (cmd[3] >> 16) as i16 as i32,
+            cmd[3] as i16 as i32,
+            cmd[4] >> 16,
+            cmd[4] & 0xFFFF,
+        );
+        let content = Rect::new(0, 0, win.content_width, win.content_height);
+        let visible = clip.intersect(&content);
+
+        // Content origin on screen: the window layer follows hide/restore
+        // moves, so derive it from the layer rather than the window.
+        let title_h = if win.is_borderless() { 0 } else { title_bar_height() as i32 };
+        let origin_x = win_layer.x;
+        let origin_y = win_layer.y + title_h;
+
+        match visible {
+            Some(v) => {
+                let local = Rect::new(v.x - x, v.y - y, v.width, v.height);
+                self.compositor.place_child_layer(layer_id, origin_x + x, origin_y + y, Some(local));
+                self.compositor.set_layer_visible(layer_id, true);
+            }
+            None => self.compositor.set_layer_visible(layer_id, false),
+        }
+    }
+
+    /// Handle CMD_PRESENT_SURFACE.
+    pub fn present_surface(&mut self, shm_id: u32) {
+        if let Some(sub) = self.subsurfaces.iter().find(|s| s.shm_id == shm_id) {
+            self.compositor.mark_layer_dirty(sub.layer_id);
+        }
+    }
+
+    /// Handle CMD_DETACH_SURFACE.
+    pub fn detach_surface(&mut self, shm_id: u32) {
+        if let Some(idx) = self.subsurfaces.iter().position(|s| s.shm_id == shm_id) {
+            let sub = self.subsurfaces.remove(idx);
+            self.compositor.remove_layer(sub.layer_id);
+            anyos_std::ipc::shm_unmap(sub.shm_id);
+        }
+    }
+
+    /// Detach every sub-surface of a window (before it is destroyed).
+    pub(crate) fn detach_window_surfaces(&mut self, window_id: u32) {
+        while let Some(sub) = self.subsurfaces.iter().find(|s| s.window_id == window_id) {
+            let shm_id = sub.shm_id;
+            self.detach_surface(shm_id);
+        }
+    }
+}
@@ -117,6 +117,47 @@ pub fn read_font_smoothing() -> u32 {
     }
 }
 
+// ── Accessibility ─────────────────────────────────────────────────────────
+
+/// Offset of the `accessibility` field in the uisys export struct.
+const UISYS_ACCESSIBILITY_OFFSET: u32 = 0x18;
+
+/// Accessibility flag: apps render with the high-contrast palette.
+pub const A11Y_HIGH_CONTRAST: u32 = 0x01;
+
+/// Pack accessibility preferences into the shared-page word.
+///
+/// Bit 0 = high contrast; bits [31:16] = default UI zoom in percent
+/// (0 = 100%). Zoom is clamped to 100–200 in steps of 25.
+pub fn pack_accessibility(high_contrast: bool, zoom: u32) -> u32 {
+    let zoom = ((zoom.max(100).min(200) + 12) / 25) * 25;
+    let zoom_bits = if zoom == 100 { 0 } else { zoom << 16 };
+    zoom_bits | if high_contrast { A11Y_HIGH_CONTRAST } else { 0 }
+}
+
+/// Set the accessibility word via kernel-mediated write to the shared RO DLIB page.
+pub fn set_accessibility(flags: u32) {
+    anyos_std::dll::set_dll_u32(UISYS_BASE, UISYS_ACCESSIBILITY_OFFSET, flags);
+}
+
+/// Read the accessibility word from the shared DLIB page.
+pub fn read_accessibility() -> u32 {
+    unsafe {
+        core::ptr::read_volatile(
+            (UISYS_BASE as usize + UISYS_ACCESSIBILITY_OFFSET as usize) as *const u32,
+        )
+    }
+}
+
+// ── Clipboard ─────────────────────────────────────────────────────────────
+
+const UISYS_CLIPBOARD_SEQ_OFFSET: u32 = 0x1C;
+
+/// Publish the clipboard sequence number in the shared DLIB page.
+pub fn set_clipboard_seq(seq: u32) {
+    anyos_std::dll::set_dll_u32(UISYS_BASE, UISYS_CLIPBOARD_SEQ_OFFSET, seq);
+}
+
 // ── Desktop Background ─────────────────────────────────────────────────────
 
 pub(crate) const COLOR_DESKTOP_BG: u32 = 0xFF1E1E1E;
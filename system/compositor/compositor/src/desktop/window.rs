@@ -6,6 +6,7 @@ use alloc::vec::Vec;
 
 use crate::compositor::Rect;
 
+use super::cursors::CursorShape;
 use super::drawing::*;
 use super::theme::*;
 use super::Desktop;
@@ -86,6 +87,20 @@ pub const EVENT_WINDOW_CLOSE: u32 = 8;
 pub const EVENT_MENU_ITEM: u32 = 9;
 pub const EVENT_STATUS_ICON_CLICK: u32 = 10;
 pub const EVENT_FOCUS_LOST: u32 = 11;
+pub const EVENT_WINDOW_SNAPPED: u32 = 12;
+
+// ── Snap Regions ───────────────────────────────────────────────────────────
+
+/// Not snapped (free-floating).
+pub const SNAP_NONE: u32 = 0;
+pub const SNAP_LEFT: u32 = 1;
+pub const SNAP_RIGHT: u32 = 2;
+pub const SNAP_TOP_LEFT: u32 = 3;
+pub const SNAP_TOP_RIGHT: u32 = 4;
+pub const SNAP_BOTTOM_LEFT: u32 = 5;
+pub const SNAP_BOTTOM_RIGHT: u32 = 6;
+/// Fill the whole work area below the menubar.
+pub const SNAP_MAXIMIZED: u32 = 7;
 
 // ── Hit Test ───────────────────────────────────────────────────────────────
 
@@ -127,6 +142,8 @@ pub(crate) struct DragState {
     pub window_id: u32,
     pub offset_x: i32,
     pub offset_y: i32,
+    /// Snap region under the cursor (SNAP_NONE = no preview).
+    pub snap_region: u32,
 }
 
 pub(crate) struct ResizeState {
@@ -162,6 +179,10 @@ pub struct WindowInfo {
     pub saved_bounds: Option<(i32, i32, u32, u32)>,
     /// Whether the window is maximized.
     pub maximized: bool,
+    /// Snap region the window is tiled to (SNAP_NONE = free-floating).
+    pub snapped: u32,
+    /// Content bounds before snapping, restored on unsnap.
+    pub snap_restore: Option<(i32, i32, u32, u32)>,
     /// SHM region ID (0 = local/compositor-owned window).
     pub shm_id: u32,
     /// SHM pixel pointer (null = local window).
@@ -171,6 +192,10 @@ pub struct WindowInfo {
     pub shm_height: u32,
     /// Set true on CMD_PRESENT, cleared after compose emits EVT_FRAME_ACK.
     pub needs_frame_ack: bool,
+    /// Cursor shown over the content area (CMD_SET_CURSOR).
+    pub cursor: CursorShape,
+    /// Window this one is a child dialog of (CMD_SET_WINDOW_OWNER, 0 = none).
+    pub owner_window: u32,
 }
 
 impl WindowInfo {
@@ -395,11 +420,15 @@ impl Desktop {
             focused: false,
             saved_bounds: None,
             maximized: false,
+            snapped: SNAP_NONE,
+            snap_restore: None,
             shm_id: 0,
             shm_ptr: core::ptr::null_mut(),
             shm_width: 0,
             shm_height: 0,
             needs_frame_ack: false,
+            cursor: CursorShape::Arrow,
+            owner_window: 0,
         };
 
         self.windows.push(win);
@@ -412,8 +441,15 @@ impl Desktop {
     pub fn destroy_window(&mut self, id: u32) {
         if let Some(idx) = self.windows.iter().position(|w| w.id == id) {
             let layer_id = self.windows[idx].layer_id;
+            self.detach_window_surfaces(id);
             self.compositor.remove_layer(layer_id);
             self.windows.remove(idx);
+            // Child dialogs of a closed window become free-standing.
+            for win in &mut self.windows {
+                if win.owner_window == id {
+                    win.owner_window = 0;
+                }
+            }
 
             self.menu_bar.remove_menu(id);
 
@@ -485,6 +521,7 @@ impl Desktop {
             let win = self.windows.remove(idx);
             self.windows.push(win);
 
+            self.raise_owned(id);
             self.ensure_top_layers();
             self.render_window(id);
 
@@ -520,6 +557,7 @@ impl Desktop {
             let win = self.windows.remove(idx);
             self.windows.push(win);
 
+            self.raise_owned(id);
             self.ensure_top_layers();
             self.compositor.mark_layer_dirty(layer_id);
 
@@ -532,16 +570,120 @@ impl Desktop {
         }
     }
 
-    /// Re-raise always-on-top windows and the menubar.
+    /// Re-raise always-on-top windows (with their child dialogs) and the menubar.
     pub(crate) fn ensure_top_layers(&mut self) {
-        for win in &self.windows {
-            if win.is_always_on_top() {
-                self.compositor.raise_layer(win.layer_id);
+        for idx in 0..self.windows.len() {
+            if self.stays_on_top(idx) {
+                self.compositor.raise_layer(self.windows[idx].layer_id);
             }
         }
         self.compositor.raise_layer(self.menubar_layer_id);
     }
 
+    /// Whether `windows[idx]` or one of its owners is always-on-top.
+    fn stays_on_top(&self, idx: usize) -> bool {
+        let mut win = &self.windows[idx];
+        // Bounded walk: set_window_owner rejects cycles, this is a backstop.
+        for _ in 0..self.windows.len() {
+            if win.is_always_on_top() {
+                return true;
+            }
+            match self.windows.iter().find(|w| w.id == win.owner_window && w.id != 0) {
+                Some(owner) => win = owner,
+                None => break,
+            }
+        }
+        false
+    }
+
+    /// IDs of all windows owned by `id`, directly or through other child
+    /// dialogs, in stacking order (bottom to top).
+    pub(crate) fn owned_windows(&self, id: u32) -> Vec<u32> {
+        let mut ids: Vec<u32> = Vec::new();
+        let mut owners = alloc::vec![id];
+        while let Some(owner) = owners.pop() {
+            for win in &self.windows {
+                if win.owner_window == owner && win.id != id && !ids.contains(&win.id) {
+                    ids.push(win.id);
+                    owners.push(win.id);
+                }
+            }
+        }
+        ids.sort_by_key(|wid| self.windows.iter().position(|w| w.id == *wid));
+        ids
+    }
+
+    /// Topmost owner of `id` (the window itself if it has no owner).
+    pub(crate) fn root_owner(&self, id: u32) -> u32 {
+        let mut root = id;
+        for _ in 0..self.windows.len() {
+            match self.windows.iter().find(|w| w.id == root).map(|w| w.owner_window) {
+                Some(owner) if owner != 0 => root = owner,
+                _ => break,
+            }
+        }
+        root
+    }
+
+    /// Stack the child dialogs of `id` directly above it, keeping their
+    /// relative order.
+    fn raise_owned(&mut self, id: u32) {
+        for child in self.owned_windows(id) {
+            if let Some(idx) = self.windows.iter().position(|w| w.id == child) {
+                self.compositor.raise_layer(self.windows[idx].layer_id);
+                let win = self.windows.remove(idx);
+                self.windows.push(win);
+            }
+        }
+    }
+
+    /// Make `id` a child dialog of `owner` (0 = detach). The child is centered
+    /// over its owner and raised above it. Unknown windows and ownership
+    /// cycles are ignored.
+    pub(crate) fn set_window_owner(&mut self, id: u32, owner: u32) {
+        let Some(idx) = self.windows.iter().position(|w| w.id == id) else { return };
+        if owner == 0 {
+            self.windows[idx].owner_window = 0;
+            return;
+        }
+        let Some(oidx) = self.windows.iter().position(|w| w.id == owner) else { return };
+        if owner == id || self.root_owner(owner) == id {
+            return;
+        }
+        self.windows[idx].owner_window = owner;
+
+        // Center over the owner, kept on screen and below the menubar.
+        let (ox, oy) = (self.windows[oidx].x, self.windows[oidx].y);
+        let (ow, oh) = (self.windows[oidx].full_width() as i32, self.windows[oidx].full_height() as i32);
+        let (cw, ch) = (self.windows[idx].full_width() as i32, self.windows[idx].full_height() as i32);
+        let max_x = (self.screen_width as i32 - cw).max(0);
+        let max_y = (self.screen_height as i32 - ch).max(menubar_height() as i32 + 1);
+        let x = (ox + (ow - cw) / 2).min(max_x).max(0);
+        let y = (oy + (oh - ch) / 2).min(max_y).max(menubar_height() as i32 + 1);
+        if !self.windows[idx].maximized && self.windows[idx].snapped == SNAP_NONE {
+            let layer_id = self.windows[idx].layer_id;
+            self.windows[idx].x = x;
+            self.windows[idx].y = y;
+            self.compositor.move_layer(layer_id, x, y);
+        }
+
+        self.raise_owned(owner);
+        self.ensure_top_layers();
+    }
+
+    /// Set or clear the always-on-top flag of a window. A window losing the
+    /// flag keeps its place until another window is raised above it.
+    pub(crate) fn set_always_on_top(&mut self, id: u32, on: bool) {
+        if let Some(win) = self.windows.iter_mut().find(|w| w.id == id) {
+            if on {
+                win.flags |= WIN_FLAG_ALWAYS_ON_TOP;
+            } else {
+                win.flags &= !WIN_FLAG_ALWAYS_ON_TOP;
+            }
+            self.ensure_top_layers();
+        }
+    }
+
     /// Get a window's event queue.
     pub fn poll_event(&mut self, window_id: u32) -> Option<[u32; 5]> {
         self.windows
@@ -857,25 +999,166 @@ impl Desktop {
         }
     }
 
+    /// Outer bounds (including title bar) of a snap region within the work
+    /// area below the menubar. None for SNAP_NONE or unknown regions.
+    pub(crate) fn snap_rect(&self, region: u32) -> Option<Rect> {
+        let top = menubar_height() as i32 + 1;
+        let w = self.screen_width;
+        let h = self.screen_height.saturating_sub(top as u32);
+        let (hw, hh) = (w / 2, h / 2);
+        let (x, y, rw, rh) = match region {
+            SNAP_LEFT => (0, top, hw, h),
+            SNAP_RIGHT => (hw as i32, top, w - hw, h),
+            SNAP_TOP_LEFT => (0, top, hw, hh),
+            SNAP_TOP_RIGHT => (hw as i32, top, w - hw, hh),
+            SNAP_BOTTOM_LEFT => (0, top + hh as i32, hw, h - hh),
+            SNAP_BOTTOM_RIGHT => (hw as i32, top + hh as i32, w - hw, h - hh),
+            SNAP_MAXIMIZED => (0, top, w, h),
+            _ => return None,
+        };
+        Some(Rect::new(x, y, rw, rh))
+    }
+
+    /// Snap region for a window dragged with the cursor at (mx, my):
+    /// side edges give halves, their ends give quarters, the top edge
+    /// gives maximized.
+    pub(crate) fn snap_region_at(&self, mx: i32, my: i32) -> u32 {
+        let edge = crate::desktop::theme::scale_i32(4);
+        let corner = crate::desktop::theme::scale_i32(48);
+        let top = menubar_height() as i32 + 1;
+        let bottom = self.screen_height as i32 - 1;
+        let at_left = mx <= edge;
+        let at_right = mx >= self.screen_width as i32 - 1 - edge;
+        if at_left || at_right {
+            if my <= top + corner {
+                return if at_left { SNAP_TOP_LEFT } else { SNAP_TOP_RIGHT };
+            }
+            if my >= bottom - corner {
+                return if at_left { SNAP_BOTTOM_LEFT } else { SNAP_BOTTOM_RIGHT };
+            }
+            return if at_left { SNAP_LEFT } else { SNAP_RIGHT };
+        }
+        if my < top + edge {
+            return SNAP_MAXIMIZED;
+        }
+        SNAP_NONE
+    }
+
+    /// Tile a window to a snap region, or restore its pre-snap bounds for
+    /// SNAP_NONE. Non-resizable windows cannot be snapped.
+    pub(crate) fn snap_window(&mut self, win_id: u32, region: u32) {
+        let Some(idx) = self.windows.iter().position(|w| w.id == win_id) else { return };
+        if region == SNAP_NONE {
+            if self.windows[idx].snapped == SNAP_NONE {
+                return;
+            }
+            self.windows[idx].snapped = SNAP_NONE;
+            if let Some((x, y, w, h)) = self.windows[idx].snap_restore.take() {
+                self.apply_snap_bounds(idx, x, y, w, h);
+            }
+            return;
+        }
+        if !self.windows[idx].is_resizable() {
+            return;
+        }
+        let Some(r) = self.snap_rect(region) else { return };
+        if self.windows[idx].snapped == SNAP_NONE {
+            // A maximized window restores to its pre-maximize bounds.
+            let win = &mut self.windows[idx];
+            win.snap_restore = if win.maximized {
+                win.saved_bounds.take()
+            } else {
+                Some((win.x, win.y, win.content_width, win.content_height))
+            };
+        }
+        self.windows[idx].maximized = false;
+        self.windows[idx].snapped = region;
+        let content_h = if self.windows[idx].is_borderless() {
+            r.height
+        } else {
+            r.height.saturating_sub(title_bar_height())
+        };
+        self.apply_snap_bounds(idx, r.x, r.y, r.width, content_h);
+    }
+
+    /// Move/resize a window for a snap change and tell the app its new size.
+    fn apply_snap_bounds(&mut self, idx: usize, x: i32, y: i32, w: u32, h: u32) {
+        let win_id = self.windows[idx].id;
+        let layer_id = self.windows[idx].layer_id;
+        let region = self.windows[idx].snapped;
+        self.windows[idx].x = x;
+        self.windows[idx].y = y;
+        self.windows[idx].content_width = w;
+        self.windows[idx].content_height = h;
+        let full_h = self.windows[idx].full_height();
+        self.compositor.move_layer(layer_id, x, y);
+        self.compositor.resize_layer(layer_id, w, full_h);
+        self.render_window(win_id);
+        self.push_event(win_id, [EVENT_RESIZE, w, h, 0, 0]);
+        self.push_event(win_id, [EVENT_WINDOW_SNAPPED, region, w, h, 0]);
+    }
+
+    /// Show or move the snap preview overlay.
+    pub(crate) fn set_snap_preview(&mut self, region: u32) {
+        let new = self.snap_rect(region);
+        if let Some(old) = self.compositor.snap_preview {
+            self.compositor.add_damage(old.expand(2));
+        }
+        if let Some(r) = new {
+            self.compositor.add_damage(r.expand(2));
+        }
+        self.compositor.snap_preview = new;
+    }
+
     /// Minimize a window (move off-screen and save bounds for restore).
+    /// A window and its child dialogs are minimized together.
     pub(crate) fn minimize_window(&mut self, win_id: u32) {
-        if let Some(idx) = self.windows.iter().position(|w| w.id == win_id) {
-            if self.windows[idx].x >= 0 && self.windows[idx].saved_bounds.is_none() {
-                self.windows[idx].saved_bounds = Some((
-                    self.windows[idx].x,
-                    self.windows[idx].y,
-                    self.windows[idx].content_width,
-                    self.windows[idx].full_height(),
-                ));
+        if !self.windows.iter().any(|w| w.id == win_id) {
+            return;
+        }
+        let root = self.root_owner(win_id);
+        let mut group = self.owned_windows(root);
+        group.push(root);
+        for &id in &group {
+            if let Some(idx) = self.windows.iter().position(|w| w.id == id) {
+                if self.windows[idx].x >= 0 && self.windows[idx].saved_bounds.is_none() {
+                    self.windows[idx].saved_bounds = Some((
+                        self.windows[idx].x,
+                        self.windows[idx].y,
+                        self.windows[idx].content_width,
+                        self.windows[idx].full_height(),
+                    ));
+                }
+                let layer_id = self.windows[idx].layer_id;
+                self.compositor.move_layer(layer_id, -10000, -10000);
             }
+        }
+        // Focus next visible window
+        if let Some(next_id) = self.windows.iter().rev()
+            .find(|w| !group.contains(&w.id) && w.x >= 0)
+            .map(|w| w.id)
+        {
+            self.focus_window(next_id);
+        }
+    }
+
+    /// Bring back the minimized child dialogs (and owners) of `win_id` after
+    /// it was restored.
+    pub(crate) fn restore_owned_group(&mut self, win_id: u32) {
+        let root = self.root_owner(win_id);
+        let mut group = self.owned_windows(root);
+        group.push(root);
+        for id in group {
+            let Some(idx) = self.windows.iter().position(|w| w.id == id) else { continue };
             let layer_id = self.windows[idx].layer_id;
-            self.compositor.move_layer(layer_id, -10000, -10000);
-            // Focus next visible window
-            if let Some(next_id) = self.windows.iter().rev()
-                .find(|w| w.id != win_id && w.x >= 0)
-                .map(|w| w.id)
-            {
-                self.focus_window(next_id);
+            let parked = self.compositor.get_layer(layer_id).map_or(false, |l| l.x == -10000);
+            if !parked {
+                continue;
+            }
+            if let Some((sx, sy, _sw, _sh)) = self.windows[idx].saved_bounds.take() {
+                self.windows[idx].x = sx;
+                self.windows[idx].y = sy;
+                self.compositor.move_layer(layer_id, sx, sy);
             }
         }
     }
@@ -975,11 +1258,15 @@ impl Desktop {
             focused: false,
             saved_bounds: None,
             maximized: false,
+            snapped: SNAP_NONE,
+            snap_restore: None,
             shm_id,
             shm_ptr,
             shm_width: content_w,
             shm_height: content_h,
             needs_frame_ack: false,
+            cursor: CursorShape::Arrow,
+            owner_window: 0,
         };
 
         self.windows.push(win);
@@ -1056,11 +1343,15 @@ impl Desktop {
             focused: false,
             saved_bounds: None,
             maximized: false,
+            snapped: SNAP_NONE,
+            snap_restore: None,
             shm_id: 0,
             shm_ptr: core::ptr::null_mut(),
             shm_width: content_w,
             shm_height: content_h,
             needs_frame_ack: false,
+            cursor: CursorShape::Arrow,
+            owner_window: 0,
         };
 
         self.windows.push(win);
@@ -1146,11 +1437,15 @@ impl Desktop {
             focused: false,
             saved_bounds: None,
             maximized: false,
+            snapped: SNAP_NONE,
+            snap_restore: None,
             shm_id,
             shm_ptr,
             shm_width: content_w,
             shm_height: content_h,
             needs_frame_ack: false,
+            cursor: CursorShape::Arrow,
+            owner_window: 0,
         };
 
         self.windows.push(win);
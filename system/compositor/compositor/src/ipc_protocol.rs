@@ -60,6 +60,11 @@ pub const RESP_CLIPBOARD_DATA: u32 = 0x2010;
 /// content_x/content_y are the screen coordinates of the window's content area top-left.
 pub const RESP_WINDOW_POS: u32 = 0x2006;
 
+/// Clipboard history entry: [RESP, shm_id, len, format, seq]
+/// seq is the clipboard sequence number the entry was set with.
+/// Sent to the requester only (unicast).
+pub const RESP_CLIPBOARD_HISTORY: u32 = 0x2011;
+
 // ── Compositor → App Input Events ────────────────────────────────────────────
 
 /// Key down: [EVT, window_id, scancode, char_code, modifiers]
@@ -158,6 +163,14 @@ pub const CMD_SET_CLIPBOARD: u32 = 0x1011;
 /// and responds with RESP_CLIPBOARD_DATA.
 pub const CMD_GET_CLIPBOARD: u32 = 0x1012;
 
+/// Get an entry of the clipboard history (newest first).
+/// [CMD, shm_id, capacity, requester_tid, index]
+/// Only the clipboard manager and root may read the history (see
+/// `Desktop::may_read_clipboard_history`). The compositor writes the clip at
+/// `index` into the SHM and responds with RESP_CLIPBOARD_HISTORY; len = 0
+/// means there is no such entry or the request was refused.
+pub const CMD_GET_CLIPBOARD_HISTORY: u32 = 0x1026;
+
 // ── App → Compositor: Notification Commands ──────────────────────────────
 
 /// Show a notification banner.
@@ -199,6 +212,66 @@ pub const CMD_SET_FONT_SMOOTHING: u32 = 0x1016;
 /// and broadcasts EVT_SCALE_CHANGED.
 pub const CMD_SET_SCALE: u32 = 0x1017;
 
+/// Snap (tile) a window to a screen region.
+/// [CMD, window_id, region, 0, 0]
+/// region: 0 = restore, 1 = left half, 2 = right half, 3 = top-left,
+/// 4 = top-right, 5 = bottom-left, 6 = bottom-right quarter, 7 = maximized.
+/// The compositor resizes the window (EVT_RESIZE) and sends EVT_WINDOW_SNAPPED.
+pub const CMD_SNAP_WINDOW: u32 = 0x1018;
+
+/// Set the badge count on an app's dock icon.
+/// [CMD, app_tid, count, 0, 0]   count = 0 removes the badge.
+/// The compositor broadcasts EVT_DOCK_BADGE for the dock to display.
+pub const CMD_SET_DOCK_BADGE: u32 = 0x1019;
+
+/// Set the cursor shape shown over a window's content area.
+/// [CMD, window_id, shape, 0, 0]
+/// shape: 1 = arrow, 2 = I-beam, 3 = hand, 4 = resize E-W, 5 = resize N-S,
+/// 6 = busy (0 or unknown = arrow). Applied immediately if the pointer is
+/// over the window.
+pub const CMD_SET_CURSOR: u32 = 0x101A;
+
+/// Set system accessibility preferences.
+/// [CMD, high_contrast (0/1), zoom_percent, 0, 0]
+/// zoom_percent: default UI zoom for app windows, 100–200 in steps of 25.
+/// Compositor writes to shared DLL page, persists to compositor.conf,
+/// and broadcasts EVT_ACCESSIBILITY_CHANGED.
+pub const CMD_SET_ACCESSIBILITY: u32 = 0x101B;
+
+/// Make a window a child (dialog) of another window.
+/// [CMD, window_id, owner_window_id, 0, 0]   owner_window_id = 0 detaches it.
+/// The child is centered over its owner, always stacked above it, and
+/// minimized / restored together with it.
+pub const CMD_SET_WINDOW_OWNER: u32 = 0x101C;
+
+/// Set or clear a window's always-on-top flag after creation.
+/// [CMD, window_id, on (0/1), 0, 0]
+pub const CMD_SET_ALWAYS_ON_TOP: u32 = 0x101D;
+
+/// Attach an app-owned SHM buffer to a window as a sub-surface.
+/// [CMD, window_id, shm_id, (width << 16) | height, 0]
+/// The compositor maps the buffer and composites it zero-copy in a layer
+/// stacked directly above the window. It stays hidden until placed; the
+/// shm_id identifies the sub-surface in the commands below.
+pub const CMD_ATTACH_SURFACE: u32 = 0x101E;
+
+/// Position a sub-surface inside its window's content area.
+/// [CMD, shm_id, (x << 16) | (y & 0xFFFF), (clip_x << 16) | (clip_y & 0xFFFF),
+///  (clip_w << 16) | clip_h]
+/// x, y: buffer origin; clip: visible part. Both are signed 16-bit content
+/// coordinates. The visible part is further clipped to the content area;
+/// clip_w or clip_h = 0 hides the sub-surface.
+pub const CMD_PLACE_SURFACE: u32 = 0x101F;
+
+/// A new frame is in a sub-surface's buffer: recomposite it.
+/// [CMD, shm_id, 0, 0, 0]
+pub const CMD_PRESENT_SURFACE: u32 = 0x1024;
+
+/// Remove a sub-surface and unmap its buffer.
+/// [CMD, shm_id, 0, 0, 0]
+/// Sub-surfaces are also removed with their window.
+pub const CMD_DETACH_SURFACE: u32 = 0x1025;
+
 /// Inject a synthetic key event into the focused window.
 /// [CMD, scancode, char_val, is_down (1=down/0=up), modifiers]
 /// vncd maps RFB KeySyms → (scancode, char_val) before emitting this command.
@@ -234,6 +307,31 @@ pub const EVT_FONT_SMOOTHING_CHANGED: u32 = 0x0051;
 /// scale: 100–300 in steps of 25.
 pub const EVT_SCALE_CHANGED: u32 = 0x0052;
 
+/// Monitor configuration changed (compositor → apps via channel).
+/// [EVT, monitor_count, primary_width, primary_height, 0]
+/// Sent after a resolution change. The compositor drives a single output,
+/// so monitor_count is currently always 1.
+pub const EVT_MONITORS_CHANGED: u32 = 0x0053;
+
+/// Accessibility preferences changed (compositor → apps via channel).
+/// [EVT, new_flags, old_flags, 0, 0]
+/// flags: bit 0 = high contrast, bits [31:16] = UI zoom percent (0 = 100).
+pub const EVT_ACCESSIBILITY_CHANGED: u32 = 0x0054;
+
+/// Clipboard contents changed (compositor → apps via channel).
+/// [EVT, seq, len, format, 0]
+/// seq: new clipboard sequence number, also published in the shared DLL page.
+pub const EVT_CLIPBOARD_CHANGED: u32 = 0x0055;
+
+/// Memory pressure level changed (compositor → apps via channel).
+/// [EVT, new_level, old_level, free_kib, 0]
+/// level: 0 = none, 1 = low (<15% free), 2 = critical (<5% free).
+pub const EVT_MEMORY_PRESSURE: u32 = 0x0056;
+
+pub const MEMORY_PRESSURE_NONE: u32 = 0;
+pub const MEMORY_PRESSURE_LOW: u32 = 1;
+pub const MEMORY_PRESSURE_CRITICAL: u32 = 2;
+
 // ── Compositor → App: Menu & Status Icon Events ─────────────────────────────
 
 /// Menu item selected: [EVT, window_id, menu_index, item_id, 0]
@@ -255,6 +353,21 @@ pub const EVT_FRAME_ACK: u32 = 0x300B;
 /// Sent when a window loses focus (another window was clicked or desktop background).
 pub const EVT_FOCUS_LOST: u32 = 0x300C;
 
+/// Window snapped or unsnapped: [EVT, window_id, region, content_w, content_h]
+/// Sent after EVT_RESIZE when a window is tiled by CMD_SNAP_WINDOW or by
+/// dragging it to a screen edge, and with region = 0 when it leaves the tile.
+pub const EVT_WINDOW_SNAPPED: u32 = 0x300D;
+
+/// Touch contact: [EVT, window_id, local_x, local_y, phase | contact_id << 8]
+/// phase: 0 = down, 1 = move, 2 = up. Contact ids are stable from down to up.
+pub const EVT_TOUCH: u32 = 0x300E;
+
+/// Pen/tablet sample: [EVT, window_id, local_x, local_y, packed]
+/// packed: bits 0-1 phase (0 = down, 1 = move, 2 = up, 3 = hover in range),
+/// bit 2 barrel button, bit 3 eraser, bits 4-15 pressure (0-4095),
+/// bits 16-23 tilt_x, bits 24-31 tilt_y (signed degrees).
+pub const EVT_PEN: u32 = 0x300F;
+
 /// Window opened (broadcast): [EVT, app_tid, win_id, 0, 0]
 /// Emitted when any app creates a window. Used by dock for filtering.
 pub const EVT_WINDOW_OPENED: u32 = 0x0060;
@@ -263,6 +376,10 @@ pub const EVT_WINDOW_OPENED: u32 = 0x0060;
 /// Emitted when a process with windows exits.
 pub const EVT_WINDOW_CLOSED: u32 = 0x0061;
 
+/// Dock badge changed (broadcast): [EVT, app_tid, count, 0, 0]
+/// Relayed from CMD_SET_DOCK_BADGE. count = 0 removes the badge.
+pub const EVT_DOCK_BADGE: u32 = 0x0062;
+
 // ── Helpers ──────────────────────────────────────────────────────────────────
 
 /// Pack up to 12 ASCII characters into 3 u32 words.
@@ -24,6 +24,7 @@ mod config;
 mod desktop;
 mod ipc_protocol;
 mod keys;
+mod memwatch;
 mod menu;
 mod render;
 
@@ -128,6 +129,12 @@ fn main() {
         desktop::theme::set_scale_factor(100);
     }
 
+    // Step 4f: Restore accessibility preferences from compositor.conf.
+    if let Some((high_contrast, zoom)) = config::read_accessibility() {
+        desktop::theme::set_accessibility(desktop::theme::pack_accessibility(high_contrast, zoom));
+        println!("compositor: restored accessibility: high_contrast={} zoom={}%", high_contrast, zoom);
+    }
+
     // Step 3b: Take over cursor from kernel splash mode
     let (splash_x, splash_y) = ipc::cursor_takeover();
     desktop.set_cursor_pos(splash_x, splash_y);
@@ -260,6 +267,7 @@ fn management_loop(
     let mut mgmt_sys: u32 = 0;
     let mut mgmt_idle: u32 = 0;
     let mut mgmt_last_report: u32 = sys::uptime_ms();
+    let mut mem_watch = memwatch::MemWatch::new();
 
     loop {
         // ── Periodic stats dump ──
@@ -401,6 +409,11 @@ fn management_loop(
             false
         };
 
+        // Broadcast memory pressure changes (sampled every ~2s)
+        if !*login_pending {
+            mem_watch.poll(compositor_channel, sys::uptime_ms());
+        }
+
         // Signal render thread ONLY when actual work was processed.
         // Previously this was unconditional, causing the render thread to wake
         // ~62.5 times/sec even when idle (both threads at 1-2% for zero work).
@@ -475,7 +488,7 @@ fn management_loop(
 /// 2. Process fast commands (CMD_PRESENT, etc.) under a SINGLE lock hold
 ///    so the render thread can't fire between consecutive presents.
 /// Commands that need work outside the lock (CREATE_WINDOW, RESIZE_SHM,
-/// SET_THEME) are handled with their own lock cycles.
+/// ATTACH_SURFACE, SET_THEME) are handled with their own lock cycles.
 ///
 /// Returns `true` if any commands were processed.
 fn handle_ipc_commands(
@@ -591,6 +604,16 @@ fn handle_ipc_commands(
                 release_lock();
                 i += 1;
             }
+            // CMD_ATTACH_SURFACE: shm_map OUTSIDE lock (potentially slow)
+            ipc_protocol::CMD_ATTACH_SURFACE => {
+                let shm_id = cmd[2];
+                let shm_addr = if shm_id > 0 { ipc::shm_map(shm_id) } else { 0 };
+                acquire_lock();
+                let desktop = unsafe { desktop_ref() };
+                desktop.handle_attach_surface_pre_mapped(&cmd, shm_addr as usize);
+                release_lock();
+                i += 1;
+            }
             // CMD_SET_FONT_SMOOTHING: write to shared DLL page + repaint
             ipc_protocol::CMD_SET_FONT_SMOOTHING => {
                 let new_mode = cmd[1].min(2);
@@ -654,6 +677,23 @@ fn handle_ipc_commands(
                 }
                 i += 1;
             }
+            // CMD_SET_ACCESSIBILITY: write to shared DLL page + notify apps
+            ipc_protocol::CMD_SET_ACCESSIBILITY => {
+                let high_contrast = cmd[1] != 0;
+                let new_flags = desktop::theme::pack_accessibility(high_contrast, cmd[2]);
+                let old_flags = desktop::theme::read_accessibility();
+                if new_flags != old_flags {
+                    desktop::theme::set_accessibility(new_flags);
+                    let zoom = match new_flags >> 16 { 0 => 100, z => z };
+                    config::save_accessibility(high_contrast, zoom);
+                    ipc::evt_chan_emit(compositor_channel, &[
+                        ipc_protocol::EVT_ACCESSIBILITY_CHANGED,
+                        new_flags, old_flags, 0, 0,
+                    ]);
+                    signal_render();
+                }
+                i += 1;
+            }
             // All other fast commands: batch under a single lock hold.
             // This prevents the render thread from firing between consecutive
             // CMD_PRESENTs during rapid scrolling (eliminates partial-update flicker).
@@ -671,9 +711,11 @@ fn handle_ipc_commands(
                     match c[0] {
                         ipc_protocol::CMD_CREATE_WINDOW
                         | ipc_protocol::CMD_RESIZE_SHM
+                        | ipc_protocol::CMD_ATTACH_SURFACE
                         | ipc_protocol::CMD_SET_THEME
                         | ipc_protocol::CMD_SET_FONT_SMOOTHING
-                        | ipc_protocol::CMD_SET_SCALE => break,
+                        | ipc_protocol::CMD_SET_SCALE
+                        | ipc_protocol::CMD_SET_ACCESSIBILITY => break,
                         _ => {}
                     }
                     if let Some(resp) = desktop.handle_ipc_command(&c) {
@@ -754,6 +796,10 @@ fn handle_system_events(compositor_channel: u32, sys_sub: u32) -> bool {
             desktop.handle_resolution_change(new_w, new_h);
             release_lock();
             config::save_resolution(new_w, new_h);
+            ipc::evt_chan_emit(compositor_channel, &[
+                ipc_protocol::EVT_MONITORS_CHANGED,
+                1, new_w, new_h, 0,
+            ]);
         } else {
             release_lock();
         }
@@ -824,7 +870,7 @@ fn perform_logout(
         desktop.focused_window = None;
         desktop.crash_dialogs.clear();
         desktop.tray_ipc_events.clear();
-        desktop.clipboard_data.clear();
+        desktop.clear_clipboard();
         desktop.desktop_icons.icons.clear();
         desktop.desktop_icons.selected_icon = None;
 
@@ -0,0 +1,81 @@
+//! Memory pressure watcher — samples free physical memory from the
+//! management loop and broadcasts `EVT_MEMORY_PRESSURE` when the level
+//! changes, so apps can drop caches before the heap runs out.
+//!
+//! Levels use a little hysteresis: a level is entered below its threshold
+//! and left only once free memory climbs 3 points above it.
+
+use anyos_std::{ipc, println, sys};
+
+use crate::ipc_protocol as proto;
+
+/// Minimum interval between samples.
+const SAMPLE_INTERVAL_MS: u32 = 2000;
+
+/// Enter LOW below this percentage of free frames.
+const LOW_PERCENT: u32 = 15;
+/// Enter CRITICAL below this percentage of free frames.
+const CRITICAL_PERCENT: u32 = 5;
+/// Extra headroom (percentage points) required to leave a level.
+const HYSTERESIS_PERCENT: u32 = 3;
+
+pub struct MemWatch {
+    level: u32,
+    last_sample_ms: u32,
+}
+
+impl MemWatch {
+    pub const fn new() -> Self {
+        Self { level: proto::MEMORY_PRESSURE_NONE, last_sample_ms: 0 }
+    }
+
+    /// Sample free memory if the interval has elapsed and broadcast a level
+    /// change on `channel`. Returns true if an event was emitted.
+    pub fn poll(&mut self, channel: u32, now_ms: u32) -> bool {
+        if now_ms.wrapping_sub(self.last_sample_ms) < SAMPLE_INTERVAL_MS {
+            return false;
+        }
+        self.last_sample_ms = now_ms;
+
+        let mut buf = [0u8; 16];
+        if sys::sysinfo(0, &mut buf) != 0 {
+            return false;
+        }
+        let total = u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]);
+        let free = u32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]);
+        if total == 0 {
+            return false;
+        }
+        let percent = (free as u64 * 100 / total as u64) as u32;
+
+        let level = next_level(self.level, percent);
+        if level == self.level {
+            return false;
+        }
+        let old = self.level;
+        self.level = level;
+        println!("compositor: memory pressure {} -> {} ({}% free)", old, level, percent);
+        // 4 KiB frames.
+        let free_kib = free.saturating_mul(4);
+        ipc::evt_chan_emit(channel, &[proto::EVT_MEMORY_PRESSURE, level, old, free_kib, 0]);
+        true
+    }
+}
+
+fn next_level(current: u32, percent: u32) -> u32 {
+    if percent < CRITICAL_PERCENT {
+        proto::MEMORY_PRESSURE_CRITICAL
+    } else if percent < LOW_PERCENT {
+        if current == proto::MEMORY_PRESSURE_CRITICAL
+            && percent < CRITICAL_PERCENT + HYSTERESIS_PERCENT
+        {
+            current
+        } else {
+            proto::MEMORY_PRESSURE_LOW
+        }
+    } else if current != proto::MEMORY_PRESSURE_NONE && percent < LOW_PERCENT + HYSTERESIS_PERCENT {
+        proto::MEMORY_PRESSURE_LOW
+    } else {
+        proto::MEMORY_PRESSURE_NONE
+    }
+}
@@ -71,6 +71,7 @@ fn parse_config(text: &str) -> Vec<DockItem> {
             running: false,
             tid: 0,
             pinned: true,
+            badge: 0,
         });
     }
     items
@@ -159,6 +160,7 @@ pub fn ensure_finder(items: &mut Vec<DockItem>) {
             running: false,
             tid: 0,
             pinned: true,
+            badge: 0,
         });
     }
 }
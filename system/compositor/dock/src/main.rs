@@ -347,6 +347,11 @@ fn main() {
         handle_window_closed(app_tid);
     });
 
+    // Badge counts set by apps via anyui::set_dock_badge
+    anyui::on_dock_badge(|app_tid, count| {
+        handle_dock_badge(app_tid, count);
+    });
+
     // Start with idle timer — switches to fast (16ms) when animations/drag are active
     app().timer_id = anyui::set_timer(TIMER_IDLE_MS, || {
         tick();
@@ -557,6 +562,7 @@ fn handle_dock_click(lx: i32, ly: i32) {
         // App exited — reset and re-launch below
         item.running = false;
         item.tid = 0;
+        item.badge = 0;
     }
 
     let tid = process::spawn(&item.bin_path, "");
@@ -765,6 +771,7 @@ fn handle_window_opened(app_tid: u32) {
         running: true,
         tid: app_tid,
         pinned: false,
+        badge: 0,
     });
     if a.has_gpu {
         let idx = a.items.len() - 1;
@@ -782,6 +789,7 @@ fn handle_window_closed(app_tid: u32) {
         if item.tid == app_tid && item.pinned {
             item.running = false;
             item.tid = 0;
+            item.badge = 0;
             a.needs_redraw = true;
         }
     }
@@ -798,6 +806,18 @@ fn handle_window_closed(app_tid: u32) {
     }
 }
 
+/// Handle a dock badge change — show `count` on the app's item (0 = clear).
+/// Apps without a dock item are ignored.
+fn handle_dock_badge(app_tid: u32, count: u32) {
+    let a = app();
+    for item in a.items.iter_mut() {
+        if item.tid == app_tid && item.badge != count {
+            item.badge = count;
+            a.needs_redraw = true;
+        }
+    }
+}
+
 /// Poll the dock IPC channel for reload notifications.
 fn poll_dock_channel() {
     let a = app();
@@ -848,6 +868,7 @@ fn reload_dock_items() {
             running: false,
             tid: 0,
             pinned: true,
+            badge: 0,
         });
         a.needs_redraw = true;
     }
@@ -952,6 +973,7 @@ fn process_system_events() {
                     if item.tid == exited_tid && item.pinned {
                         item.running = false;
                         item.tid = 0;
+                        item.badge = 0;
                         a.needs_redraw = true;
                     }
                 }
@@ -1,5 +1,6 @@
 //! Dock rendering, hit testing, magnification, and position-aware layout.
 
+use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
 
@@ -141,6 +142,21 @@ fn blit_icon_ghost(fb: &mut Framebuffer, icon: &crate::types::Icon, x: i32, y: i
     }
 }
 
+/// Draw an app's badge count as a red pill over the icon's top-right corner.
+fn draw_badge(fb: &mut Framebuffer, icon_x: i32, icon_y: i32, size: u32, count: u32) {
+    if count == 0 {
+        return;
+    }
+    let text = if count > 99 { String::from("99+") } else { alloc::format!("{}", count) };
+    let (tw, th) = anyos_std::ui::window::font_measure(FONT_ID, FONT_SIZE, &text);
+    let pill_h = th + 6;
+    let pill_w = (tw + 10).max(pill_h);
+    let x = icon_x + size as i32 - pill_w as i32 + 4;
+    let y = icon_y - 4;
+    fb.fill_rounded_rect(x, y, pill_w, pill_h, pill_h as i32 / 2, COLOR_BADGE);
+    fb.draw_text(x + (pill_w as i32 - tw as i32) / 2, y + 3, &text, COLOR_WHITE);
+}
+
 /// Choose the best icon source for rendering at a given draw size.
 /// Uses icon_hires when available and draw_size > base icon_size.
 fn pick_icon<'a>(item: &'a DockItem, draw_size: u32, base_size: u32) -> Option<&'a crate::types::Icon> {
@@ -262,6 +278,8 @@ fn render_horizontal(fb: &mut Framebuffer, items: &[DockItem], screen_width: u32
                 fb.fill_rounded_rect(ix, icon_y, icon_size, icon_size, 10, 0xFF3C3C41);
             }
 
+            draw_badge(fb, ix, icon_y, icon_size, item.badge);
+
             if item.running {
                 let dot_x = ix + icon_size as i32 / 2;
                 let dot_y = icon_baseline_y + 5;
@@ -305,6 +323,8 @@ fn render_horizontal(fb: &mut Framebuffer, items: &[DockItem], screen_width: u32
                 fb.fill_rounded_rect(icon_x, icon_y, draw_size, draw_size, 10, 0xFF3C3C41);
             }
 
+            draw_badge(fb, icon_x, icon_y, draw_size, item.badge);
+
             // Running indicator dot (at fixed baseline)
             if item.running {
                 let dot_x = ix + draw_size as i32 / 2;
@@ -430,6 +450,8 @@ fn render_vertical(fb: &mut Framebuffer, items: &[DockItem], _screen_w: u32, scr
             fb.fill_rounded_rect(icon_x_final, icon_y, draw_size, draw_size, 10, 0xFF3C3C41);
         }
 
+        draw_badge(fb, icon_x_final, icon_y, draw_size, item.badge);
+
         // Running indicator dot
         if item.running {
             let dot_y = iy + draw_size as i32 / 2;
@@ -78,6 +78,7 @@ pub fn dock_bg() -> u32 {
 pub const COLOR_WHITE: u32 = 0xFFFFFFFF;
 pub const COLOR_TRANSPARENT: u32 = 0x00000000;
 pub const COLOR_HIGHLIGHT: u32 = 0x19FFFFFF;
+pub const COLOR_BADGE: u32 = 0xFFFF3B30;
 
 #[inline(always)]
 pub fn tooltip_bg() -> u32 {
@@ -25,4 +25,6 @@ pub struct DockItem {
     pub tid: u32,
     /// true = from config file, false = transient (running only).
     pub pinned: bool,
+    /// Badge count set by the app via `set_dock_badge` (0 = none).
+    pub badge: u32,
 }
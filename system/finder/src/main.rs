@@ -227,7 +227,7 @@ struct AppState {
     sb_sel_label: ui::Label,
     // Context menu
     ctx_menu: ui::ContextMenu,
-    ctx_menu_variant: u32, // 0=app, 1=dir, 2=file — set at right-click time
+    ctx_menu_variant: u32, // 0=app, 1=dir, 2=file, 4=archive — set at right-click time
     // Rename panel
     rename_panel: ui::View,
     rename_field: ui::TextField,
@@ -238,6 +238,8 @@ struct AppState {
     clip_is_cut: bool,
     // Copy/move operation state
     copy_op: Option<CopyOperation>,
+    // libzip.so loaded (lazily, on first "Extract Here")
+    zip_loaded: bool,
 }
 
 /// State for an ongoing copy/move operation (timer-driven).
@@ -764,6 +766,90 @@ fn show_package_contents(idx: usize) {
     navigate(&new_path);
 }
 
+// ============================================================================
+// Archives
+// ============================================================================
+
+/// Archive suffixes offered "Extract Here" (longest first).
+const ARCHIVE_EXTS: [&str; 6] = [".tar.gz", ".tgz", ".tar", ".zip", ".7z", ".gz"];
+
+/// Name of `name` without its archive suffix, or None if it is not an archive.
+fn archive_stem(name: &str) -> Option<&str> {
+    let lower = name.to_ascii_lowercase();
+    for ext in ARCHIVE_EXTS {
+        if lower.len() > ext.len() && lower.ends_with(ext) {
+            return Some(&name[..name.len() - ext.len()]);
+        }
+    }
+    None
+}
+
+/// Extract the archive at `idx` into the current directory.
+///
+/// Multi-entry containers go into a new folder named after the archive;
+/// a plain gzip file is decompressed next to it.
+fn extract_here(idx: usize) {
+    let s = app();
+    if idx >= s.entries.len() { return; }
+    if !s.zip_loaded {
+        s.zip_loaded = libzip_client::init();
+        if !s.zip_loaded {
+            s.sb_sel_label.set_text("Archive support not available");
+            return;
+        }
+    }
+
+    let name = String::from(s.entries[idx].name_str());
+    let path = build_full_path(&s.cwd, &name);
+    let mut archive = match libzip_client::Archive::open(&path) {
+        Some(a) => a,
+        None => {
+            s.sb_sel_label.set_text("Not a supported archive");
+            return;
+        }
+    };
+
+    let result = if archive.format() == libzip_client::ArchiveFormat::Gzip {
+        match archive.next_entry() {
+            Some(entry) => {
+                let dest = unique_dest_path(&s.cwd, &entry.name);
+                if archive.extract_entry(&dest) { Some(1) } else { None }
+            }
+            None => None,
+        }
+    } else {
+        let stem = archive_stem(&name).unwrap_or(&name);
+        let dest = unique_dest_path(&s.cwd, stem);
+        if fs::mkdir(&dest) == u32::MAX {
+            None
+        } else {
+            archive.extract_all(&dest)
+        }
+    };
+
+    match result {
+        Some(n) => s.sb_sel_label.set_text(&anyos_std::format!("Extracted {} items", n)),
+        None => s.sb_sel_label.set_text(&extract_failure_message(&path, &archive)),
+    }
+    refresh_current();
+}
+
+/// Status text for a failed extraction; ZIPs are verified to locate the damage.
+fn extract_failure_message(path: &str, archive: &libzip_client::Archive) -> String {
+    if archive.format() != libzip_client::ArchiveFormat::Zip {
+        return String::from("Extraction failed");
+    }
+    let report = match libzip_client::ZipReader::open(path).and_then(|z| z.verify()) {
+        Some(r) => r,
+        None => return String::from("Extraction failed"),
+    };
+    match report.first_damaged {
+        Some(n) => anyos_std::format!("Archive is damaged at entry {}", n + 1),
+        None if report.directory_incomplete => String::from("Archive is damaged (directory truncated)"),
+        None => String::from("Extraction failed"),
+    }
+}
+
 // ============================================================================
 // Context menu handler
 // ============================================================================
@@ -1281,7 +1367,8 @@ fn handle_context_action(index: u32) {
     // variant 0: Open|Add to Dock|Show Package Contents|-|Cut|Copy|Paste|-|Rename|Delete|-|Copy Path|Properties
     // variant 1: Open|-|Cut|Copy|Paste|-|Rename|Delete|-|Copy Path|Properties
     // variant 2: Open|Open With...|-|Cut|Copy|Paste|-|Rename|Delete|-|Copy Path|Properties
-    let paste_idx = match variant { 0 => 6u32, 1 => 4, _ => 5 };
+    // variant 4: Open|Open With...|Extract Here|-|Cut|Copy|Paste|-|Rename|Delete|-|Copy Path|Properties
+    let paste_idx = match variant { 0 => 6u32, 1 => 4, 4 => 6, _ => 5 };
     if index == paste_idx {
         paste_entry();
         return;
@@ -1320,6 +1407,20 @@ fn handle_context_action(index: u32) {
             10 => show_properties(idx),
             _ => {}
         }
+    } else if variant == 4 {
+        // Open|Open With...|Extract Here|-|Cut|Copy|Paste|-|Rename|Delete|-|Copy Path|Properties
+        match index {
+            0 => open_entry(idx),
+            1 => show_open_with(idx),
+            2 => extract_here(idx),
+            4 => cut_selected(),
+            5 => copy_selected(),
+            8 => start_rename(idx),
+            9 => confirm_delete(),
+            11 => copy_path(idx),
+            12 => show_properties(idx),
+            _ => {}
+        }
     } else {
         // Open|Open With...|-|Cut|Copy|Paste|-|Rename|Delete|-|Copy Path|Properties
         match index {
@@ -1355,6 +1456,8 @@ fn update_context_menu() {
         ("Open|Add to Dock|Show Package Contents|-|Cut|Copy|Paste|-|Rename|Delete|-|Copy Path|Properties", 0u32)
     } else if is_dir {
         ("Open|-|Cut|Copy|Paste|-|Rename|Delete|-|Copy Path|Properties", 1u32)
+    } else if archive_stem(s.entries[idx].name_str()).is_some() {
+        ("Open|Open With...|Extract Here|-|Cut|Copy|Paste|-|Rename|Delete|-|Copy Path|Properties", 4u32)
     } else {
         ("Open|Open With...|-|Cut|Copy|Paste|-|Rename|Delete|-|Copy Path|Properties", 2u32)
     };
@@ -2443,6 +2546,7 @@ fn main() {
             clip_files: Vec::new(),
             clip_is_cut: false,
             copy_op: None,
+            zip_loaded: false,
         });
     }
 
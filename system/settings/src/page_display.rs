@@ -101,6 +101,9 @@ pub fn build(parent: &ui::ScrollView) -> u32 {
     // ── DPI Scale card ───────────────────────────────────────────────────
     build_dpi_scale_card(&panel);
 
+    // ── Accessibility card ───────────────────────────────────────────────
+    build_accessibility_card(&panel);
+
     // ── Resolution picker card ──────────────────────────────────────────
     let resolutions = window::list_resolutions();
     if !resolutions.is_empty() {
@@ -435,6 +438,36 @@ fn build_dpi_scale_card(panel: &ui::View) {
     row.add(&dd);
 }
 
+// ── Accessibility card ──────────────────────────────────────────────────────
+
+/// Build the accessibility card with a high-contrast toggle and a zoom
+/// DropDown (100% – 200% in 25% steps). Both settings are system-wide and
+/// applied by the compositor, which broadcasts them to every app.
+fn build_accessibility_card(panel: &ui::View) {
+    let card = layout::build_auto_card(panel);
+
+    let hc_row = layout::build_setting_row(&card, "High Contrast", true);
+    let toggle = layout::add_toggle_to_row(&hc_row, ui::theme::system_high_contrast());
+    toggle.on_checked_changed(move |e| {
+        ui::theme::set_accessibility(e.checked, ui::theme::system_zoom());
+    });
+
+    layout::build_separator(&card);
+
+    let zoom_row = layout::build_setting_row(&card, "Zoom", false);
+
+    let dd = ui::DropDown::new("100%|125%|150%|175%|200%");
+    dd.set_position(200, 8);
+    dd.set_size(280, 28);
+    let idx = (ui::theme::system_zoom().saturating_sub(100) / 25).min(4);
+    dd.set_selected_index(idx);
+
+    dd.on_selection_changed(move |e| {
+        ui::theme::set_accessibility(ui::theme::system_high_contrast(), 100 + e.index * 25);
+    });
+    zoom_row.add(&dd);
+}
+
 // ── Accent style scanning ────────────────────────────────────────────────────
 
 /// Scan the style directory and parse all `.conf` files.